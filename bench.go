@@ -0,0 +1,119 @@
+package canopy
+
+import (
+	"context"
+	"database/sql"
+	"errors"
+	"fmt"
+	"path/filepath"
+	"time"
+
+	"github.com/jward/canopy/internal/workload"
+)
+
+// Workload describes a synthetic workspace for Benchmark: its language, the
+// number of files and of functions per file, and the seed of its call
+// structure. The same Workload always generates the same files.
+type Workload = workload.Spec
+
+// WorkloadLanguages are the languages a Workload can be generated in.
+var WorkloadLanguages = workload.Languages
+
+// BenchmarkTiming is the wall time of one benchmark phase.
+type BenchmarkTiming struct {
+	Phase    string // "extract", "resolve", or a query name such as "transitive-callers"
+	Duration time.Duration
+}
+
+// BenchmarkReport is the outcome of one Benchmark run.
+type BenchmarkReport struct {
+	Workload   Workload
+	Files      int // files generated
+	Symbols    int // symbols extracted from them
+	References int
+	CallEdges  int
+	Timings    []BenchmarkTiming // in run order: extract, resolve, then each query
+}
+
+// Benchmark generates w under dir/workspace, indexes it into a new database
+// at dir/index.db with an engine configured as by New(dbPath, scriptsDir,
+// opts...), and times extraction, resolution, and a fixed set of queries
+// against the function every generated file calls. dir must not hold an
+// earlier run.
+func Benchmark(ctx context.Context, dir, scriptsDir string, w Workload, opts ...Option) (*BenchmarkReport, error) {
+	root := filepath.Join(dir, "workspace")
+	stats, err := workload.Generate(root, w)
+	if err != nil {
+		return nil, fmt.Errorf("benchmark: %w", err)
+	}
+
+	opts = append([]Option{WithLanguages(w.Language)}, opts...)
+	e, err := New(filepath.Join(dir, "index.db"), scriptsDir, opts...)
+	if err != nil {
+		return nil, fmt.Errorf("benchmark: %w", err)
+	}
+	defer e.Close()
+
+	report := &BenchmarkReport{Workload: w, Files: stats.Files}
+	timed := func(phase string, fn func() error) error {
+		start := time.Now()
+		if err := fn(); err != nil {
+			return fmt.Errorf("benchmark: %s: %w", phase, err)
+		}
+		report.Timings = append(report.Timings, BenchmarkTiming{Phase: phase, Duration: time.Since(start)})
+		return nil
+	}
+
+	if err := timed("extract", func() error { return e.IndexDirectory(ctx, root) }); err != nil {
+		return nil, err
+	}
+	if err := timed("resolve", func() error { return e.Resolve(ctx) }); err != nil {
+		return nil, err
+	}
+
+	db := e.store.DB()
+	counts := []struct {
+		dst   *int
+		query string
+	}{
+		{&report.Symbols, `SELECT COUNT(*) FROM symbols s JOIN files f ON f.id = s.file_id WHERE f.language = ?`},
+		{&report.References, `SELECT COUNT(*) FROM references_ r JOIN files f ON f.id = r.file_id WHERE f.language = ?`},
+		{&report.CallEdges, `SELECT COUNT(*) FROM call_graph c JOIN files f ON f.id = c.file_id WHERE f.language = ?`},
+	}
+	for _, c := range counts {
+		if err := db.QueryRow(c.query, w.Language).Scan(c.dst); err != nil {
+			return nil, fmt.Errorf("benchmark: count: %w", err)
+		}
+	}
+	var hot int64
+	err = db.QueryRow(
+		`SELECT s.id FROM symbols s JOIN files f ON f.id = s.file_id
+		 WHERE s.name = ? AND f.language = ? ORDER BY s.id LIMIT 1`, stats.Hot, w.Language).Scan(&hot)
+	if errors.Is(err, sql.ErrNoRows) {
+		return nil, fmt.Errorf("benchmark: hot function %s was not extracted", stats.Hot)
+	}
+	if err != nil {
+		return nil, fmt.Errorf("benchmark: %w", err)
+	}
+
+	q := e.Query()
+	queries := []struct {
+		name string
+		run  func() error
+	}{
+		{"symbols", func() error { _, err := q.Symbols(SymbolFilter{}, Sort{}, Pagination{}); return err }},
+		{"search", func() error { _, err := q.SearchSymbols("*_0*", SymbolFilter{}, Sort{}, Pagination{}); return err }},
+		{"references", func() error { _, err := q.ReferencesTo(hot); return err }},
+		{"callers", func() error { _, err := q.Callers(hot); return err }},
+		{"transitive-callers", func() error { _, err := q.TransitiveCallers(hot, 5); return err }},
+		{"transitive-callees", func() error { _, err := q.TransitiveCallees(hot, 5); return err }},
+		{"hotspots", func() error { _, err := q.Hotspots(10); return err }},
+		{"unused", func() error { _, err := q.UnusedSymbols(SymbolFilter{}, Sort{}, Pagination{}); return err }},
+	}
+	for _, qr := range queries {
+		if err := timed(qr.name, qr.run); err != nil {
+			return nil, err
+		}
+	}
+	return report, nil
+}
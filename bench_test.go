@@ -4,6 +4,7 @@ import (
 	"context"
 	"os"
 	"path/filepath"
+	"slices"
 	"testing"
 )
 
@@ -240,3 +241,29 @@ func BenchmarkQueryDefinitionAt(b *testing.B) {
 		}
 	}
 }
+
+func TestBenchmark(t *testing.T) {
+	scriptsDir := filepath.Join(findModuleRoot(t), "scripts")
+
+	for _, lang := range WorkloadLanguages {
+		t.Run(lang, func(t *testing.T) {
+			w := Workload{Language: lang, Files: 4, Functions: 5, Seed: 1}
+			report, err := Benchmark(context.Background(), t.TempDir(), scriptsDir, w)
+			if err != nil {
+				t.Fatal(err)
+			}
+			if report.Symbols == 0 || report.References == 0 || report.CallEdges == 0 {
+				t.Fatalf("expected symbols, references, and call edges, got %+v", report)
+			}
+			var phases []string
+			for _, timing := range report.Timings {
+				phases = append(phases, timing.Phase)
+			}
+			want := []string{"extract", "resolve", "symbols", "search", "references", "callers",
+				"transitive-callers", "transitive-callees", "hotspots", "unused"}
+			if !slices.Equal(phases, want) {
+				t.Fatalf("phases = %v, want %v", phases, want)
+			}
+		})
+	}
+}
@@ -0,0 +1,188 @@
+package canopy
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// WithBlame enables git blame enrichment. After IndexDirectory extracts a
+// root inside a git work tree, each file is blamed and every symbol records
+// the commit, author, and date that last touched its lines, plus per-file
+// author line counts. Files are re-blamed when they change or when HEAD
+// moves. Off by default because blame is slow on large histories.
+func WithBlame(enabled bool) Option {
+	return func(e *Engine) {
+		e.blame = enabled
+	}
+}
+
+// blameLine is the blame of a single line.
+type blameLine struct {
+	commit string
+	author string
+	email  string
+	time   int64
+}
+
+// parseBlamePorcelain parses `git blame --line-porcelain` output into one
+// entry per line of the file, in order.
+func parseBlamePorcelain(out []byte) ([]blameLine, error) {
+	var lines []blameLine
+	var cur blameLine
+	scanner := bufio.NewScanner(bytes.NewReader(out))
+	scanner.Buffer(make([]byte, 0, 64*1024), 16*1024*1024)
+	header := true
+	for scanner.Scan() {
+		text := scanner.Text()
+		if strings.HasPrefix(text, "\t") {
+			// Content line ends the entry.
+			lines = append(lines, cur)
+			cur = blameLine{}
+			header = true
+			continue
+		}
+		if header {
+			fields := strings.Fields(text)
+			if len(fields) < 3 {
+				return nil, fmt.Errorf("parse blame: unexpected header %q", text)
+			}
+			cur.commit = fields[0]
+			header = false
+			continue
+		}
+		key, value, _ := strings.Cut(text, " ")
+		switch key {
+		case "author":
+			cur.author = value
+		case "author-mail":
+			cur.email = strings.Trim(value, "<>")
+		case "author-time":
+			t, err := strconv.ParseInt(value, 10, 64)
+			if err != nil {
+				return nil, fmt.Errorf("parse blame: author-time %q: %w", value, err)
+			}
+			cur.time = t
+		}
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("parse blame: %w", err)
+	}
+	return lines, nil
+}
+
+// gitBlame runs git blame on one file.
+func gitBlame(path string) ([]blameLine, error) {
+	cmd := exec.Command("git", "blame", "--line-porcelain", "--", filepath.Base(path))
+	cmd.Dir = filepath.Dir(path)
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, fmt.Errorf("git blame %s: %w: %s", path, err, strings.TrimSpace(stderr.String()))
+	}
+	return parseBlamePorcelain(stdout.Bytes())
+}
+
+// summarizeBlame computes per-author line counts for a file and the history
+// of each symbol from the blame of the file's lines.
+func summarizeBlame(lines []blameLine, symbols []*store.Symbol) ([]store.AuthorLines, []*store.SymbolHistory) {
+	counts := map[string]int{}
+	for _, l := range lines {
+		counts[l.author]++
+	}
+	authors := make([]store.AuthorLines, 0, len(counts))
+	for a, n := range counts {
+		authors = append(authors, store.AuthorLines{Author: a, Lines: n})
+	}
+	sort.Slice(authors, func(i, j int) bool {
+		if authors[i].Lines != authors[j].Lines {
+			return authors[i].Lines > authors[j].Lines
+		}
+		return authors[i].Author < authors[j].Author
+	})
+
+	var history []*store.SymbolHistory
+	for _, sym := range symbols {
+		if sym.StartLine < 0 || sym.StartLine >= len(lines) {
+			continue
+		}
+		end := min(sym.EndLine, len(lines)-1)
+		latest := lines[sym.StartLine]
+		distinct := map[string]bool{}
+		for _, l := range lines[sym.StartLine : end+1] {
+			distinct[l.author] = true
+			if l.time > latest.time {
+				latest = l
+			}
+		}
+		history = append(history, &store.SymbolHistory{
+			SymbolID:    sym.ID,
+			Commit:      latest.commit,
+			Author:      latest.author,
+			AuthorEmail: latest.email,
+			Date:        time.Unix(latest.time, 0).UTC(),
+			Authors:     len(distinct),
+		})
+	}
+	return authors, history
+}
+
+// refreshBlame blames the files indexed under root that have no blame data
+// yet, or all of them when HEAD moved since the last run. Roots outside a
+// git work tree are skipped; files git cannot blame (e.g. untracked) are
+// left without history.
+func (e *Engine) refreshBlame(root string) error {
+	root = filepath.Clean(root)
+	cmd := exec.Command("git", "rev-parse", "HEAD")
+	cmd.Dir = root
+	out, err := cmd.Output()
+	if err != nil {
+		return nil // not a git work tree, or no commits yet
+	}
+	head := strings.TrimSpace(string(out))
+
+	headKey := "blame_head:" + root
+	prevHead, err := e.store.GetMetadata(headKey)
+	if err != nil {
+		return err
+	}
+	blamed := map[int64]bool{}
+	if prevHead == head {
+		if blamed, err = e.store.BlamedFileIDs(); err != nil {
+			return err
+		}
+	}
+
+	files, err := e.store.AllFiles()
+	if err != nil {
+		return fmt.Errorf("refresh blame: list files: %w", err)
+	}
+	prefix := root + string(filepath.Separator)
+	for id, path := range files {
+		if blamed[id] || !strings.HasPrefix(path, prefix) || e.rootFor(path) != root {
+			continue
+		}
+		lines, err := gitBlame(path)
+		if err != nil {
+			continue
+		}
+		symbols, err := e.store.SymbolsByFile(id)
+		if err != nil {
+			return fmt.Errorf("refresh blame: %w", err)
+		}
+		authors, history := summarizeBlame(lines, symbols)
+		if err := e.store.ReplaceFileBlame(id, authors, history); err != nil {
+			return fmt.Errorf("refresh blame: %w", err)
+		}
+	}
+	return e.store.SetMetadata(headKey, head)
+}
@@ -0,0 +1,129 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+const testBlamePorcelain = `1111111111111111111111111111111111111111 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1000
+author-tz +0000
+committer Alice
+committer-mail <alice@example.com>
+committer-time 1000
+committer-tz +0000
+summary first
+filename lib.go
+	package lib
+1111111111111111111111111111111111111111 2 2
+author Alice
+author-mail <alice@example.com>
+author-time 1000
+author-tz +0000
+committer Alice
+committer-mail <alice@example.com>
+committer-time 1000
+committer-tz +0000
+summary first
+filename lib.go
+	func A() {
+2222222222222222222222222222222222222222 3 3 1
+author Bob
+author-mail <bob@example.com>
+author-time 2000
+author-tz +0000
+committer Bob
+committer-mail <bob@example.com>
+committer-time 2000
+committer-tz +0000
+summary second
+previous 1111111111111111111111111111111111111111 lib.go
+filename lib.go
+	}
+`
+
+func TestParseBlamePorcelain(t *testing.T) {
+	t.Parallel()
+	lines, err := parseBlamePorcelain([]byte(testBlamePorcelain))
+	require.NoError(t, err)
+	require.Len(t, lines, 3)
+	assert.Equal(t, blameLine{
+		commit: "1111111111111111111111111111111111111111",
+		author: "Alice",
+		email:  "alice@example.com",
+		time:   1000,
+	}, lines[0])
+	assert.Equal(t, "Bob", lines[2].author)
+	assert.Equal(t, int64(2000), lines[2].time)
+}
+
+func TestSummarizeBlame(t *testing.T) {
+	t.Parallel()
+	lines, err := parseBlamePorcelain([]byte(testBlamePorcelain))
+	require.NoError(t, err)
+
+	authors, history := summarizeBlame(lines, []*store.Symbol{
+		{ID: 1, StartLine: 0, EndLine: 0},
+		{ID: 2, StartLine: 1, EndLine: 2},
+		{ID: 3, StartLine: 7, EndLine: 9}, // outside the blamed lines
+	})
+	assert.Equal(t, []store.AuthorLines{{Author: "Alice", Lines: 2}, {Author: "Bob", Lines: 1}}, authors)
+	require.Len(t, history, 2)
+	assert.Equal(t, "Alice", history[0].Author)
+	assert.Equal(t, 1, history[0].Authors)
+	assert.Equal(t, "2222222222222222222222222222222222222222", history[1].Commit)
+	assert.Equal(t, "Bob", history[1].Author)
+	assert.Equal(t, time.Unix(2000, 0).UTC(), history[1].Date)
+	assert.Equal(t, 2, history[1].Authors)
+}
+
+func TestIndexDirectory_BlameRecordsSymbolHistory(t *testing.T) {
+	if _, err := exec.LookPath("git"); err != nil {
+		t.Skip("git not available")
+	}
+	dir := t.TempDir()
+	git := func(args ...string) {
+		t.Helper()
+		cmd := exec.Command("git", append([]string{"-c", "user.name=Alice", "-c", "user.email=alice@example.com"}, args...)...)
+		cmd.Dir = dir
+		cmd.Env = append(os.Environ(), "GIT_AUTHOR_DATE=2020-01-01T00:00:00Z", "GIT_COMMITTER_DATE=2020-01-01T00:00:00Z")
+		out, err := cmd.CombinedOutput()
+		require.NoError(t, err, string(out))
+	}
+	git("init", "-q")
+	writeGoFile(t, dir, "lib.go", `package lib
+
+func Old() {}
+`)
+	git("add", ".")
+	git("commit", "-q", "-m", "initial")
+
+	e := newIntegrationEngine(t, WithLanguages("go"), WithBlame(true))
+	require.NoError(t, e.IndexDirectory(context.Background(), dir))
+
+	syms, err := e.store.SymbolsByName("Old")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	h, err := e.Query().SymbolHistory(syms[0].ID)
+	require.NoError(t, err)
+	require.NotNil(t, h)
+	assert.Equal(t, "Alice", h.Author)
+	assert.Equal(t, "alice@example.com", h.AuthorEmail)
+	assert.Equal(t, time.Date(2020, 1, 1, 0, 0, 0, 0, time.UTC), h.Date)
+
+	factors, err := e.Query().BusFactor("")
+	require.NoError(t, err)
+	require.Len(t, factors, 1)
+	assert.Equal(t, filepath.Join(dir, "lib.go"), factors[0].File)
+	assert.Equal(t, 1, factors[0].BusFactor)
+}
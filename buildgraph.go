@@ -0,0 +1,524 @@
+package canopy
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// buildFileNames are the names of Bazel and Buck build files, in the order
+// one is chosen when a directory has several.
+var buildFileNames = []string{"BUILD.bazel", "BUILD", "BUCK"}
+
+// buildWorkspaceMarkers are the files at a root that mark it as a Bazel
+// workspace or Buck project. Roots without one are not walked for build
+// files.
+var buildWorkspaceMarkers = []string{"MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE", ".buckconfig"}
+
+// buildSrcAttrs are the rule attributes that list a target's source files.
+var buildSrcAttrs = map[string]bool{
+	"srcs": true, "hdrs": true, "textual_hdrs": true, "headers": true, "exported_headers": true,
+}
+
+// buildDepAttrs are the rule attributes that list the targets a target
+// depends on.
+var buildDepAttrs = map[string]bool{
+	"deps": true, "exported_deps": true, "runtime_deps": true, "implementation_deps": true,
+	"proc_macro_deps": true, "exports": true, "embed": true, "crate": true,
+}
+
+// buildGlob is one glob() call of a target's sources.
+type buildGlob struct {
+	include [][]string // patterns split on "/"
+	exclude [][]string
+}
+
+// BuildTarget is a rule declared in a Bazel BUILD or Buck BUCK file.
+type BuildTarget struct {
+	Label string   // normalized, e.g. "//pkg/path:name"
+	Kind  string   // rule name, e.g. "go_library"
+	Deps  []string // normalized labels of the targets it depends on
+	srcs  []string // source files named literally, relative to the package
+	globs []buildGlob
+}
+
+// Matches reports whether rel, a path relative to the target's package, is
+// one of the target's sources.
+func (t *BuildTarget) Matches(rel string) bool {
+	rel = filepath.ToSlash(rel)
+	for _, s := range t.srcs {
+		if s == rel {
+			return true
+		}
+	}
+	segs := strings.Split(rel, "/")
+	for _, g := range t.globs {
+		if matchAnySegments(g.include, segs) && !matchAnySegments(g.exclude, segs) {
+			return true
+		}
+	}
+	return false
+}
+
+// matchAnySegments reports whether segs matches any of patterns.
+func matchAnySegments(patterns [][]string, segs []string) bool {
+	for _, p := range patterns {
+		if matchSegments(p, segs) {
+			return true
+		}
+	}
+	return false
+}
+
+// ParseBuildFile parses the rule calls of a BUILD or BUCK file of package
+// pkg, the slash-separated directory of the file relative to the workspace
+// root. Every top-level call with a string name= argument is a target. Its
+// sources come from string lists, glob(), select() and "+" concatenation;
+// other expressions, such as variables and macros' computed values, are
+// skipped. Labels in sources are treated as deps.
+func ParseBuildFile(r io.Reader, pkg string) ([]BuildTarget, error) {
+	src, err := io.ReadAll(r)
+	if err != nil {
+		return nil, fmt.Errorf("parse build file: %w", err)
+	}
+	p := &buildParser{toks: tokenizeStarlark(string(src))}
+	var targets []BuildTarget
+	for p.pos < len(p.toks) {
+		tok := p.toks[p.pos]
+		if tok.kind == 'i' && p.at(1, "(") {
+			p.pos++
+			if t, ok := p.parseRule(tok.text, pkg); ok {
+				targets = append(targets, t)
+			}
+			continue
+		}
+		if tok.kind == 'p' && strings.Contains("([{", tok.text) {
+			p.skipBalanced()
+			continue
+		}
+		p.pos++
+	}
+	return targets, nil
+}
+
+// normalizeBuildLabel expands label, as written in package pkg, to its full
+// form: ":x" and "x" become "//pkg:x", and "//a/b" becomes "//a/b:b".
+// External repository and Buck cell prefixes are kept.
+func normalizeBuildLabel(label, pkg string) string {
+	label = strings.TrimPrefix(label, "@//")
+	if strings.HasPrefix(label, "@") && !strings.Contains(label, "//") {
+		return label
+	}
+	if strings.HasPrefix(label, ":") {
+		return "//" + pkg + label
+	}
+	i := strings.Index(label, "//")
+	if i < 0 {
+		return "//" + pkg + ":" + label
+	}
+	rest := label[i+2:]
+	if rest != "" && !strings.Contains(rest, ":") {
+		return label + ":" + path.Base(rest)
+	}
+	return label
+}
+
+// isBuildLabel reports whether a source entry names a target rather than a
+// file.
+func isBuildLabel(s string) bool {
+	return strings.HasPrefix(s, ":") || strings.HasPrefix(s, "@") || strings.Contains(s, "//")
+}
+
+// starlarkToken is a token of a BUILD file. kind is 'i' for identifiers, 's'
+// for strings (text is the unquoted value), 'p' for punctuation, and 'o' for
+// anything else.
+type starlarkToken struct {
+	kind byte
+	text string
+}
+
+// tokenizeStarlark splits src into tokens, dropping comments and whitespace.
+func tokenizeStarlark(src string) []starlarkToken {
+	var toks []starlarkToken
+	i := 0
+	for i < len(src) {
+		c := src[i]
+		switch {
+		case c == '#':
+			for i < len(src) && src[i] != '\n' {
+				i++
+			}
+		case c == ' ' || c == '\t' || c == '\r' || c == '\n' || c == '\\':
+			i++
+		case c == '"' || c == '\'':
+			text, end := scanStarlarkString(src, i, false)
+			toks = append(toks, starlarkToken{kind: 's', text: text})
+			i = end
+		case c == '_' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z':
+			j := i
+			for j < len(src) && (src[j] == '_' || src[j] >= 'a' && src[j] <= 'z' || src[j] >= 'A' && src[j] <= 'Z' || src[j] >= '0' && src[j] <= '9') {
+				j++
+			}
+			word := src[i:j]
+			if j < len(src) && (src[j] == '"' || src[j] == '\'') && (word == "r" || word == "b" || word == "rb" || word == "br") {
+				text, end := scanStarlarkString(src, j, strings.Contains(word, "r"))
+				toks = append(toks, starlarkToken{kind: 's', text: text})
+				i = end
+				continue
+			}
+			toks = append(toks, starlarkToken{kind: 'i', text: word})
+			i = j
+		case strings.IndexByte("()[]{},=+:", c) >= 0:
+			toks = append(toks, starlarkToken{kind: 'p', text: string(c)})
+			i++
+		default:
+			toks = append(toks, starlarkToken{kind: 'o', text: string(c)})
+			i++
+		}
+	}
+	return toks
+}
+
+// scanStarlarkString reads the string literal whose opening quote is at i.
+// Returns its value and the index just past the closing quote.
+func scanStarlarkString(src string, i int, raw bool) (string, int) {
+	quote := src[i : i+1]
+	if strings.HasPrefix(src[i:], strings.Repeat(quote, 3)) {
+		quote = strings.Repeat(quote, 3)
+	}
+	i += len(quote)
+	var b strings.Builder
+	for i < len(src) {
+		if strings.HasPrefix(src[i:], quote) {
+			return b.String(), i + len(quote)
+		}
+		if src[i] == '\\' && i+1 < len(src) {
+			if raw {
+				b.WriteByte(src[i])
+			}
+			i++
+		}
+		b.WriteByte(src[i])
+		i++
+	}
+	return b.String(), i
+}
+
+// starlarkValue is what the parser keeps of an expression: the strings it
+// can evaluate to and its glob() calls.
+type starlarkValue struct {
+	strs  []string
+	globs []buildGlob
+}
+
+func (v *starlarkValue) add(o starlarkValue) {
+	v.strs = append(v.strs, o.strs...)
+	v.globs = append(v.globs, o.globs...)
+}
+
+// buildParser evaluates the parts of a BUILD file's expressions that name
+// sources and deps.
+type buildParser struct {
+	toks []starlarkToken
+	pos  int
+}
+
+// at reports whether the token n positions ahead is the punctuation text.
+func (p *buildParser) at(n int, text string) bool {
+	i := p.pos + n
+	return i < len(p.toks) && p.toks[i].kind == 'p' && p.toks[i].text == text
+}
+
+// skipBalanced skips the bracketed group opening at the current token.
+func (p *buildParser) skipBalanced() {
+	depth := 0
+	for p.pos < len(p.toks) {
+		tok := p.toks[p.pos]
+		p.pos++
+		if tok.kind != 'p' {
+			continue
+		}
+		switch tok.text {
+		case "(", "[", "{":
+			depth++
+		case ")", "]", "}":
+			depth--
+		}
+		if depth <= 0 {
+			return
+		}
+	}
+}
+
+// skipToSeparator skips an expression the parser does not understand, up to
+// the next "," or closing bracket at the same depth.
+func (p *buildParser) skipToSeparator() {
+	for p.pos < len(p.toks) {
+		tok := p.toks[p.pos]
+		if tok.kind == 'p' {
+			switch tok.text {
+			case ",", ")", "]", "}", ":":
+				return
+			case "(", "[", "{":
+				p.skipBalanced()
+				continue
+			}
+		}
+		p.pos++
+	}
+}
+
+// parseArgs parses the arguments of the call whose "(" is the current token,
+// calling fn with each keyword (or "" for a positional argument) and value.
+func (p *buildParser) parseArgs(fn func(key string, v starlarkValue)) {
+	p.pos++ // "("
+	for p.pos < len(p.toks) && !p.at(0, ")") {
+		key := ""
+		if p.toks[p.pos].kind == 'i' && p.at(1, "=") {
+			key = p.toks[p.pos].text
+			p.pos += 2
+		}
+		fn(key, p.parseExpr())
+		p.skipToSeparator()
+		if p.at(0, ",") || p.at(0, ":") {
+			p.pos++
+		}
+	}
+	p.pos++ // ")"
+}
+
+// parseRule parses a rule call of kind whose "(" is the current token.
+// Returns false if it has no name.
+func (p *buildParser) parseRule(kind, pkg string) (BuildTarget, bool) {
+	t := BuildTarget{Kind: kind}
+	name := ""
+	seen := map[string]bool{}
+	addDep := func(label string) {
+		label = normalizeBuildLabel(label, pkg)
+		if !seen[label] {
+			seen[label] = true
+			t.Deps = append(t.Deps, label)
+		}
+	}
+	p.parseArgs(func(key string, v starlarkValue) {
+		switch {
+		case key == "name" && len(v.strs) == 1:
+			name = v.strs[0]
+		case buildSrcAttrs[key]:
+			for _, s := range v.strs {
+				if isBuildLabel(s) {
+					addDep(s)
+				} else {
+					t.srcs = append(t.srcs, s)
+				}
+			}
+			t.globs = append(t.globs, v.globs...)
+		case buildDepAttrs[key]:
+			for _, s := range v.strs {
+				addDep(s)
+			}
+		}
+	})
+	if name == "" {
+		return t, false
+	}
+	t.Label = "//" + pkg + ":" + name
+	return t, true
+}
+
+// parseExpr parses a "+"-joined sequence of terms.
+func (p *buildParser) parseExpr() starlarkValue {
+	v := p.parseTerm()
+	for p.at(0, "+") {
+		p.pos++
+		v.add(p.parseTerm())
+	}
+	return v
+}
+
+// parseTerm parses a string, list, dict, glob(), select(), or parenthesized
+// expression. Anything else is skipped and yields nothing.
+func (p *buildParser) parseTerm() starlarkValue {
+	var v starlarkValue
+	if p.pos >= len(p.toks) {
+		return v
+	}
+	tok := p.toks[p.pos]
+	switch {
+	case tok.kind == 's':
+		p.pos++
+		v.strs = append(v.strs, tok.text)
+	case tok.kind == 'p' && (tok.text == "[" || tok.text == "{" || tok.text == "("):
+		closing := map[string]string{"[": "]", "{": "}", "(": ")"}[tok.text]
+		p.pos++
+		for p.pos < len(p.toks) && !p.at(0, closing) {
+			elem := p.parseExpr()
+			// A dict's values, such as select()'s branches, are kept along
+			// with its keys; keys are conditions that name no sources.
+			if !p.at(0, ":") {
+				v.add(elem)
+			}
+			p.skipToSeparator()
+			if p.at(0, ",") || p.at(0, ":") {
+				p.pos++
+			}
+		}
+		p.pos++
+	case tok.kind == 'i' && tok.text == "glob" && p.at(1, "("):
+		p.pos++
+		var g buildGlob
+		p.parseArgs(func(key string, arg starlarkValue) {
+			for _, s := range arg.strs {
+				switch key {
+				case "", "include":
+					g.include = append(g.include, strings.Split(s, "/"))
+				case "exclude":
+					g.exclude = append(g.exclude, strings.Split(s, "/"))
+				}
+			}
+		})
+		v.globs = append(v.globs, g)
+	case tok.kind == 'i' && tok.text == "select" && p.at(1, "("):
+		p.pos++
+		p.parseArgs(func(key string, arg starlarkValue) {
+			if key == "" {
+				v.add(arg)
+			}
+		})
+	case tok.kind == 'i' && p.at(1, "("):
+		p.pos++
+		p.skipBalanced()
+	default:
+		p.pos++
+	}
+	return v
+}
+
+// loadBuildGraph reads the BUILD and BUCK files under root, keyed by package:
+// the slash-separated directory relative to root, "" for root itself. Returns
+// nil with no error if root is not a Bazel workspace or Buck project.
+func loadBuildGraph(root string) (map[string][]BuildTarget, error) {
+	isWorkspace := false
+	for _, m := range buildWorkspaceMarkers {
+		if _, err := os.Stat(filepath.Join(root, m)); err == nil {
+			isWorkspace = true
+			break
+		}
+	}
+	if !isWorkspace {
+		return nil, nil
+	}
+
+	pkgs := map[string][]BuildTarget{}
+	err := filepath.WalkDir(root, func(p string, d fs.DirEntry, err error) error {
+		if err != nil {
+			return nil
+		}
+		if !d.IsDir() {
+			return nil
+		}
+		name := d.Name()
+		if p != root && (strings.HasPrefix(name, ".") || strings.HasPrefix(name, "bazel-") || name == "buck-out" || name == "node_modules") {
+			return filepath.SkipDir
+		}
+		for _, bf := range buildFileNames {
+			f, err := os.Open(filepath.Join(p, bf))
+			if errors.Is(err, fs.ErrNotExist) {
+				continue
+			}
+			if err != nil {
+				return fmt.Errorf("open build file: %w", err)
+			}
+			rel, _ := filepath.Rel(root, p)
+			pkg := filepath.ToSlash(rel)
+			if pkg == "." {
+				pkg = ""
+			}
+			targets, err := ParseBuildFile(f, pkg)
+			f.Close()
+			if err != nil {
+				return err
+			}
+			pkgs[pkg] = targets
+			break
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return pkgs, nil
+}
+
+// buildTargetsOf returns the labels of the targets of pkgs whose sources
+// include rel, a slash-separated path relative to the workspace root. Only
+// the targets of the file's nearest enclosing package are considered, as in
+// Bazel and Buck a glob does not reach into subpackages.
+func buildTargetsOf(pkgs map[string][]BuildTarget, rel string) []string {
+	dir := path.Dir(rel)
+	for {
+		pkg := dir
+		if pkg == "." {
+			pkg = ""
+		}
+		if targets, ok := pkgs[pkg]; ok {
+			inPkg := rel
+			if pkg != "" {
+				inPkg = strings.TrimPrefix(rel, pkg+"/")
+			}
+			var labels []string
+			for i := range targets {
+				if targets[i].Matches(inPkg) {
+					labels = append(labels, targets[i].Label)
+				}
+			}
+			return labels
+		}
+		if pkg == "" {
+			return nil
+		}
+		dir = path.Dir(dir)
+	}
+}
+
+// refreshBuildGraph re-reads the Bazel or Buck build graph of root and
+// recomputes the targets of every file indexed under it. Files of a nested
+// root are left to that root.
+func (e *Engine) refreshBuildGraph(root string) error {
+	root = filepath.Clean(root)
+	pkgs, err := loadBuildGraph(root)
+	if err != nil {
+		return fmt.Errorf("refresh build graph: %w", err)
+	}
+
+	var targets []*store.BuildTarget
+	for _, pkgTargets := range pkgs {
+		for _, t := range pkgTargets {
+			targets = append(targets, &store.BuildTarget{Root: root, Label: t.Label, Kind: t.Kind, Deps: t.Deps})
+		}
+	}
+
+	files, err := e.store.AllFiles()
+	if err != nil {
+		return fmt.Errorf("refresh build graph: list files: %w", err)
+	}
+	prefix := root + string(filepath.Separator)
+	fileTargets := map[int64][]string{}
+	for id, p := range files {
+		if !strings.HasPrefix(p, prefix) || e.rootFor(p) != root {
+			continue
+		}
+		fileTargets[id] = buildTargetsOf(pkgs, filepath.ToSlash(strings.TrimPrefix(p, prefix)))
+	}
+	if err := e.store.ReplaceBuildGraph(root, targets, fileTargets); err != nil {
+		return fmt.Errorf("refresh build graph: %w", err)
+	}
+	return nil
+}
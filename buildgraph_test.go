@@ -0,0 +1,138 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseBuildFile_RulesSourcesAndDeps(t *testing.T) {
+	t.Parallel()
+	targets, err := ParseBuildFile(strings.NewReader(`load("@io_bazel_rules_go//go:def.bzl", "go_library", "go_test")
+
+# The billing library.
+go_library(
+    name = "billing",
+    srcs = glob(["*.go"], exclude = ["*_test.go"]) + [":gen_rates"],
+    deps = [
+        "//pkg/money",
+        ":ledger",
+        "@com_github_pkg_errors//:errors",
+    ] + select({
+        "//conditions:linux": ["//pkg/linux:sys"],
+        "//conditions:default": [],
+    }),
+)
+
+go_test(
+    name = 'billing_test',
+    srcs = ["billing_test.go"],
+    embed = [":billing"],
+)
+
+cc_library(
+    name = "ledger",
+    hdrs = ["ledger.h"],
+    srcs = glob(["impl/**/*.cc"]),
+)
+
+package(default_visibility = ["//visibility:public"])
+`), "services/billing")
+	require.NoError(t, err)
+	require.Len(t, targets, 3)
+
+	lib := targets[0]
+	assert.Equal(t, "//services/billing:billing", lib.Label)
+	assert.Equal(t, "go_library", lib.Kind)
+	assert.Equal(t, []string{
+		"//services/billing:gen_rates",
+		"//pkg/money:money",
+		"//services/billing:ledger",
+		"@com_github_pkg_errors//:errors",
+		"//pkg/linux:sys",
+	}, lib.Deps)
+	assert.True(t, lib.Matches("charge.go"))
+	assert.False(t, lib.Matches("charge_test.go"), "excluded by the glob")
+	assert.False(t, lib.Matches("sub/charge.go"), "a single-star glob does not cross directories")
+
+	test := targets[1]
+	assert.Equal(t, "//services/billing:billing_test", test.Label)
+	assert.Equal(t, []string{"//services/billing:billing"}, test.Deps)
+	assert.True(t, test.Matches("billing_test.go"))
+	assert.False(t, test.Matches("charge.go"))
+
+	ledger := targets[2]
+	assert.True(t, ledger.Matches("ledger.h"))
+	assert.True(t, ledger.Matches("impl/a/b/ledger.cc"))
+}
+
+func TestNormalizeBuildLabel(t *testing.T) {
+	t.Parallel()
+	for _, tc := range []struct{ label, want string }{
+		{":lib", "//a/b:lib"},
+		{"lib", "//a/b:lib"},
+		{"//x/y", "//x/y:y"},
+		{"//x/y:z", "//x/y:z"},
+		{"@repo//x", "@repo//x:x"},
+		{"@//x:z", "//x:z"},
+		{"@repo", "@repo"},
+		{"cell//x/y", "cell//x/y:y"},
+	} {
+		assert.Equal(t, tc.want, normalizeBuildLabel(tc.label, "a/b"), tc.label)
+	}
+}
+
+func TestIntegration_IndexDirectoryAppliesBuildGraph(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+
+	root := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(root, "MODULE.bazel"), nil, 0644))
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "lib"), 0755))
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "app"), 0755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "lib", "BUILD.bazel"), []byte(
+		"go_library(name = \"lib\", srcs = glob([\"*.go\"]))\n"), 0644))
+	appBuild := filepath.Join(root, "app", "BUILD.bazel")
+	require.NoError(t, os.WriteFile(appBuild, []byte(
+		"go_binary(name = \"app\", srcs = [\"main.go\"], deps = [\"//lib\"])\n"), 0644))
+
+	libPath := writeGoFile(t, filepath.Join(root, "lib"), "lib.go", "package lib\n\nfunc Charge() {}\n")
+	appPath := writeGoFile(t, filepath.Join(root, "app"), "main.go", "package main\n\nfunc main() {}\n")
+
+	require.NoError(t, e.IndexDirectory(ctx, root))
+
+	targets := func(path string) []string {
+		f, err := e.store.FileByPath(path)
+		require.NoError(t, err)
+		require.NotNil(t, f)
+		labels, err := e.Query().FileBuildTargets(f.ID)
+		require.NoError(t, err)
+		return labels
+	}
+	assert.Equal(t, []string{"//lib:lib"}, targets(libPath))
+	assert.Equal(t, []string{"//app:app"}, targets(appPath))
+
+	syms, err := e.store.SymbolsByName("Charge")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	impacts, err := e.Query().ImpactedTargets(syms[0].ID, 5)
+	require.NoError(t, err)
+	assert.Equal(t, []TargetImpact{
+		{Label: "//lib:lib", Kind: "go_library", Reason: TargetReasonDefines},
+		{Label: "//app:app", Kind: "go_binary", Reason: TargetReasonDepends, Via: "//lib:lib", Depth: 1},
+	}, impacts)
+
+	// Changing a BUILD file alone updates the graph on the next index.
+	require.NoError(t, os.WriteFile(appBuild, []byte(
+		"go_binary(name = \"server\", srcs = [\"main.go\"])\n"), 0644))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	assert.Equal(t, []string{"//app:server"}, targets(appPath))
+	impacts, err = e.Query().ImpactedTargets(syms[0].ID, 5)
+	require.NoError(t, err)
+	require.Len(t, impacts, 1)
+}
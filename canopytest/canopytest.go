@@ -0,0 +1,206 @@
+// Package canopytest checks name resolution against expectations written
+// inline in the source, so a resolution test is a few lines of code instead
+// of a testdata directory with a golden.json.
+//
+// An expectation is a comment line, // or #, whose caret points at a column
+// of the nearest line above that is not itself an expectation:
+//
+//	fn area(c: &Circle) -> f64 {
+//	// ^ expect-def area
+//	    c.radius * c.radius
+//	}
+//	fn main() { area(&c); }
+//	//          ^ expect-ref area
+//
+// "expect-def NAME" asserts that the narrowest symbol at the caret is
+// named NAME, and labels it. "expect-ref NAME" asserts that the reference
+// at the caret resolves to the symbol labelled NAME by an expect-def in any
+// of the files, or, when no expect-def has that label, to some symbol named
+// NAME. A bare "expect-ref" asserts that the reference resolves at all, and
+// "expect-unresolved" that it does not.
+//
+// The package is meant for tests only; it indexes with the embedded
+// extraction and resolution scripts.
+package canopytest
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strings"
+	"testing"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+)
+
+// Expectation kinds.
+const (
+	ExpectDef        = "expect-def"
+	ExpectRef        = "expect-ref"
+	ExpectUnresolved = "expect-unresolved"
+)
+
+// markerRe matches an expectation line. The caret's byte offset is the
+// column it points at.
+var markerRe = regexp.MustCompile(`^\s*(?://|#)\s*\^\s*(expect-def|expect-ref|expect-unresolved)\b\s*(\S*)\s*$`)
+
+// Marker is one expectation of a source file.
+type Marker struct {
+	File  string // the file's name, as passed to Check
+	Kind  string // one of the Expect* constants
+	Name  string // the label, empty for a bare expect-ref or expect-unresolved
+	Line  int    // 0-based line the caret points into
+	Col   int    // 0-based byte column of the caret
+	Where int    // 0-based line of the expectation itself
+}
+
+func (m Marker) String() string {
+	s := fmt.Sprintf("%s:%d: %s", m.File, m.Where, m.Kind)
+	if m.Name != "" {
+		s += " " + m.Name
+	}
+	return s
+}
+
+// ParseMarkers returns the expectations in src, a file called file, in
+// order. An expectation with no line above it to point into is an error.
+func ParseMarkers(file, src string) ([]Marker, error) {
+	var markers []Marker
+	target := -1
+	for i, line := range strings.Split(src, "\n") {
+		m := markerRe.FindStringSubmatch(line)
+		if m == nil {
+			target = i
+			continue
+		}
+		if target < 0 {
+			return nil, fmt.Errorf("%s:%d: %s has no line above it", file, i, m[1])
+		}
+		markers = append(markers, Marker{
+			File: file, Kind: m[1], Name: m[2],
+			Line: target, Col: strings.Index(line, "^"), Where: i,
+		})
+	}
+	return markers, nil
+}
+
+// CheckSource is Check for a single file called name, whose extension
+// selects the language.
+func CheckSource(t testing.TB, name, src string) {
+	t.Helper()
+	Check(t, map[string]string{name: src})
+}
+
+// Check writes files, keyed by path relative to a temporary directory, indexes
+// and resolves them, and reports each expectation that does not hold as a
+// test error. A test without expectations fails, since it checks nothing.
+func Check(t testing.TB, files map[string]string) {
+	t.Helper()
+	dir := t.TempDir()
+	names := make([]string, 0, len(files))
+	for name := range files {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+
+	var markers []Marker
+	var paths []string
+	for _, name := range names {
+		path := filepath.Join(dir, filepath.FromSlash(name))
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatalf("canopytest: %v", err)
+		}
+		if err := os.WriteFile(path, []byte(files[name]), 0o644); err != nil {
+			t.Fatalf("canopytest: %v", err)
+		}
+		paths = append(paths, path)
+		ms, err := ParseMarkers(name, files[name])
+		if err != nil {
+			t.Fatalf("canopytest: %v", err)
+		}
+		markers = append(markers, ms...)
+	}
+	if len(markers) == 0 {
+		t.Fatalf("canopytest: no expect-def, expect-ref, or expect-unresolved markers")
+	}
+
+	e, err := canopy.New(filepath.Join(t.TempDir(), "canopytest.db"), "", canopy.WithScriptsFS(scripts.FS))
+	if err != nil {
+		t.Fatalf("canopytest: %v", err)
+	}
+	defer e.Close()
+	ctx := context.Background()
+	if err := e.IndexFiles(ctx, paths); err != nil {
+		t.Fatalf("canopytest: index: %v", err)
+	}
+	if err := e.Resolve(ctx); err != nil {
+		t.Fatalf("canopytest: resolve: %v", err)
+	}
+
+	for _, err := range verify(e.Query(), dir, markers) {
+		t.Error(err)
+	}
+}
+
+// verify checks markers against the index of files under dir, definitions
+// first so references can name their labels, and returns the failures.
+func verify(q *canopy.QueryBuilder, dir string, markers []Marker) []error {
+	var errs []error
+	path := func(m Marker) string { return filepath.Join(dir, filepath.FromSlash(m.File)) }
+
+	labels := map[string]int64{}
+	for _, m := range markers {
+		if m.Kind != ExpectDef {
+			continue
+		}
+		sym, err := q.SymbolAt(path(m), m.Line, m.Col)
+		switch {
+		case err != nil:
+			errs = append(errs, fmt.Errorf("%s: %w", m, err))
+		case sym == nil:
+			errs = append(errs, fmt.Errorf("%s: no symbol at %d:%d", m, m.Line, m.Col))
+		case sym.Name != m.Name:
+			errs = append(errs, fmt.Errorf("%s: symbol at %d:%d is %s %s", m, m.Line, m.Col, sym.Kind, sym.Name))
+		default:
+			labels[m.Name] = sym.ID
+		}
+	}
+
+	for _, m := range markers {
+		if m.Kind == ExpectDef {
+			continue
+		}
+		explanations, err := q.ExplainDefinitionAt(path(m), m.Line, m.Col)
+		if err != nil {
+			errs = append(errs, fmt.Errorf("%s: %w", m, err))
+			continue
+		}
+		var got []string
+		matched := false
+		for _, ex := range explanations {
+			got = append(got, fmt.Sprintf("%s %s (#%d)", ex.TargetKind, ex.TargetName, ex.TargetSymbolID))
+			id, labelled := labels[m.Name]
+			switch {
+			case m.Name == "":
+				matched = true
+			case labelled:
+				matched = matched || ex.TargetSymbolID == id
+			default:
+				matched = matched || ex.TargetName == m.Name
+			}
+		}
+		switch {
+		case m.Kind == ExpectUnresolved && len(explanations) > 0:
+			errs = append(errs, fmt.Errorf("%s: reference at %d:%d resolves to %s", m, m.Line, m.Col, strings.Join(got, ", ")))
+		case m.Kind == ExpectRef && len(explanations) == 0:
+			errs = append(errs, fmt.Errorf("%s: no resolved reference at %d:%d", m, m.Line, m.Col))
+		case m.Kind == ExpectRef && !matched:
+			errs = append(errs, fmt.Errorf("%s: reference at %d:%d resolves to %s", m, m.Line, m.Col, strings.Join(got, ", ")))
+		}
+	}
+	return errs
+}
@@ -0,0 +1,75 @@
+package canopytest
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+const goSource = `package main
+
+func helper() int { return 1 }
+//   ^ expect-def helper
+
+func main() {
+	_ = helper()
+	//  ^ expect-ref helper
+	_ = missing()
+	//  ^ expect-unresolved
+}
+`
+
+func TestParseMarkers(t *testing.T) {
+	t.Parallel()
+	markers, err := ParseMarkers("main.go", goSource)
+	require.NoError(t, err)
+	assert.Equal(t, []Marker{
+		{File: "main.go", Kind: ExpectDef, Name: "helper", Line: 2, Col: 5, Where: 3},
+		{File: "main.go", Kind: ExpectRef, Name: "helper", Line: 6, Col: 5, Where: 7},
+		{File: "main.go", Kind: ExpectUnresolved, Line: 8, Col: 5, Where: 9},
+	}, markers)
+
+	// Stacked expectations point into the same line.
+	markers, err = ParseMarkers("a.py", "x = f(y)\n#   ^ expect-ref f\n#     ^ expect-ref\n")
+	require.NoError(t, err)
+	require.Len(t, markers, 2)
+	assert.Equal(t, 0, markers[1].Line)
+	assert.Equal(t, 6, markers[1].Col)
+
+	_, err = ParseMarkers("a.py", "# ^ expect-def x\n")
+	assert.Error(t, err)
+}
+
+func TestCheckSource_Go(t *testing.T) {
+	CheckSource(t, "main.go", goSource)
+}
+
+func TestVerify_ReportsFailedExpectations(t *testing.T) {
+	dir := t.TempDir()
+	path := filepath.Join(dir, "main.go")
+	require.NoError(t, os.WriteFile(path, []byte(goSource), 0o644))
+	e, err := canopy.New(filepath.Join(t.TempDir(), "test.db"), "", canopy.WithScriptsFS(scripts.FS))
+	require.NoError(t, err)
+	defer e.Close()
+	require.NoError(t, e.IndexFiles(context.Background(), []string{path}))
+	require.NoError(t, e.Resolve(context.Background()))
+
+	// Each expectation is the opposite of what the source does.
+	errs := verify(e.Query(), dir, []Marker{
+		{File: "main.go", Kind: ExpectDef, Name: "main", Line: 2, Col: 5, Where: 3},
+		{File: "main.go", Kind: ExpectUnresolved, Line: 6, Col: 5, Where: 7},
+		{File: "main.go", Kind: ExpectRef, Line: 8, Col: 5, Where: 9},
+		{File: "main.go", Kind: ExpectRef, Name: "other", Line: 6, Col: 5, Where: 7},
+	})
+	require.Len(t, errs, 4)
+	assert.Contains(t, errs[0].Error(), "main.go:3: expect-def main: symbol at 2:5 is function helper")
+	assert.Contains(t, errs[1].Error(), "resolves to function helper")
+	assert.Contains(t, errs[2].Error(), "no resolved reference")
+	assert.Contains(t, errs[3].Error(), "expect-ref other")
+}
@@ -0,0 +1,50 @@
+package canopy
+
+import (
+	"fmt"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// fingerprintKinds are the symbol kinds whose bodies are fingerprinted for
+// clone detection.
+var fingerprintKinds = map[string]bool{
+	"function":    true,
+	"method":      true,
+	"constructor": true,
+}
+
+// recordFingerprints stores the body fingerprints of a file's functions. Each
+// function symbol takes the fingerprint of the outermost function node within
+// its span, which is its own node rather than a nested closure. Must run
+// after recordSpanOffsets.
+func (e *Engine) recordFingerprints(fileID int64, fps []runtime.BodyFingerprint) error {
+	if len(fps) == 0 {
+		return nil
+	}
+	syms, err := e.store.SymbolsByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load symbols: %w", err)
+	}
+	var rows []store.FunctionFingerprint
+	for _, sym := range syms {
+		if !fingerprintKinds[sym.Kind] {
+			continue
+		}
+		var best *runtime.BodyFingerprint
+		for i := range fps {
+			fp := &fps[i]
+			if fp.StartByte < sym.StartByte || fp.EndByte > sym.EndByte {
+				continue
+			}
+			if best == nil || fp.EndByte-fp.StartByte > best.EndByte-best.StartByte {
+				best = fp
+			}
+		}
+		if best != nil {
+			rows = append(rows, store.FunctionFingerprint{SymbolID: sym.ID, Statements: best.Statements})
+		}
+	}
+	return e.store.InsertFunctionFingerprints(rows)
+}
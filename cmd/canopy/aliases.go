@@ -0,0 +1,55 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var aliasesCmd = &cobra.Command{
+	Use:   "aliases [<file> <line> <col>]",
+	Short: "List the names a symbol is imported under",
+	Long: "Lists the aliases a symbol is imported under across the index (use foo::Bar as Baz, import { Bar as Baz }),\n" +
+		"with the importing file and how many of its references go through the alias. References to an alias\n" +
+		"resolve to the aliased symbol, so they are also listed by the references command.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runAliases,
+}
+
+func init() {
+	aliasesCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+}
+
+func runAliases(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("aliases", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("aliases", err)
+	}
+	aliases, err := qb.Aliases(symID)
+	if err != nil {
+		return outputError("aliases", err)
+	}
+	if aliases == nil {
+		return outputResult(CLIResult{Command: "aliases", Results: nil})
+	}
+
+	result := CLISymbolAliases{
+		Symbol:  symbolResultToCLI(aliases.Symbol),
+		Aliases: make([]CLISymbolAlias, len(aliases.Aliases)),
+	}
+	for i, a := range aliases.Aliases {
+		result.Aliases[i] = CLISymbolAlias{Alias: a.Alias, Source: a.Source, File: a.FilePath, References: a.References}
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "aliases",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
@@ -0,0 +1,148 @@
+package main
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+)
+
+// outputResultGitHub writes the findings of a rule command or of lint as
+// GitHub Actions workflow commands, which the Actions runner turns into
+// annotations on the lines of a pull request.
+func outputResultGitHub(result CLIResult) error {
+	return outputFindings(result, "github", func(findings []CLILintFinding, repoRoot string) error {
+		writeGitHubAnnotations(os.Stdout, findings, repoRoot)
+		return nil
+	})
+}
+
+// outputResultGitLab writes the findings of a rule command or of lint as a
+// GitLab Code Quality report, for a job's artifacts:reports:codequality.
+func outputResultGitLab(result CLIResult) error {
+	return outputFindings(result, "gitlab", func(findings []CLILintFinding, repoRoot string) error {
+		enc := json.NewEncoder(os.Stdout)
+		enc.SetIndent("", "  ")
+		return enc.Encode(buildGitLabCodeQuality(findings, repoRoot))
+	})
+}
+
+// outputFindings collects the findings of result and hands them, with the
+// repo root, to write.
+func outputFindings(result CLIResult, format string, write func([]CLILintFinding, string) error) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return fmt.Errorf("getting cwd: %w", err)
+	}
+	locatePackage, closeStore := storePackageLocator()
+	defer closeStore()
+
+	findings, _, err := resultFindings(result, format, locatePackage)
+	if err != nil {
+		return err
+	}
+	return write(findings, findRepoRoot(cwd))
+}
+
+// githubLevel returns the workflow command for a rule severity. Findings of
+// rules turned off are still reported, as notices.
+func githubLevel(severity string) string {
+	switch severity {
+	case severityError:
+		return "error"
+	case severityWarning:
+		return "warning"
+	}
+	return "notice"
+}
+
+// writeGitHubAnnotations writes one workflow command per finding, e.g.
+//
+//	::warning file=src/lib.rs,line=3,endLine=3,col=5,endColumn=9,title=canopy unused::function helper is never referenced
+//
+// with paths relative to repoRoot and 1-based lines and columns.
+func writeGitHubAnnotations(w io.Writer, findings []CLILintFinding, repoRoot string) {
+	for _, f := range findings {
+		loc := f.Location
+		props := []string{
+			"file=" + githubEscapeProperty(relativeToRoot(loc.File, repoRoot)),
+			fmt.Sprintf("line=%d", loc.StartLine+1),
+			fmt.Sprintf("endLine=%d", loc.EndLine+1),
+		}
+		// Columns only apply to annotations on a single line.
+		if loc.StartLine == loc.EndLine {
+			props = append(props, fmt.Sprintf("col=%d", loc.UTF16StartCol+1), fmt.Sprintf("endColumn=%d", loc.UTF16EndCol+1))
+		}
+		props = append(props, "title="+githubEscapeProperty("canopy "+f.Rule))
+		fmt.Fprintf(w, "::%s %s::%s\n", githubLevel(f.Severity), strings.Join(props, ","), githubEscapeData(f.Message))
+	}
+}
+
+// githubEscapeData escapes the message of a workflow command.
+func githubEscapeData(s string) string {
+	return strings.NewReplacer("%", "%25", "\r", "%0D", "\n", "%0A").Replace(s)
+}
+
+// githubEscapeProperty escapes a property value of a workflow command.
+func githubEscapeProperty(s string) string {
+	return strings.NewReplacer("%", "%25", "\r", "%0D", "\n", "%0A", ":", "%3A", ",", "%2C").Replace(s)
+}
+
+// gitlabIssue is one entry of a GitLab Code Quality report.
+type gitlabIssue struct {
+	Description string         `json:"description"`
+	CheckName   string         `json:"check_name"`
+	Fingerprint string         `json:"fingerprint"`
+	Severity    string         `json:"severity"`
+	Location    gitlabLocation `json:"location"`
+}
+
+type gitlabLocation struct {
+	Path  string      `json:"path"`
+	Lines gitlabLines `json:"lines"`
+}
+
+// gitlabLines is 1-based.
+type gitlabLines struct {
+	Begin int `json:"begin"`
+	End   int `json:"end"`
+}
+
+// gitlabSeverity returns the Code Quality severity for a rule severity.
+func gitlabSeverity(severity string) string {
+	switch severity {
+	case severityError:
+		return "major"
+	case severityWarning:
+		return "minor"
+	}
+	return "info"
+}
+
+// buildGitLabCodeQuality converts findings to a Code Quality report with
+// paths relative to repoRoot. Fingerprints hash the rule, file, and message
+// like lint baseline entries, so GitLab tracks a finding across commits that
+// move it; identical findings in one file are told apart by occurrence.
+func buildGitLabCodeQuality(findings []CLILintFinding, repoRoot string) []gitlabIssue {
+	issues := make([]gitlabIssue, 0, len(findings))
+	seen := map[lintBaselineEntry]int{}
+	for _, f := range findings {
+		key := lintBaselineKey(f, repoRoot)
+		sum := sha256.Sum256(fmt.Appendf(nil, "%s\x00%s\x00%s\x00%d", key.Rule, key.File, key.Message, seen[key]))
+		seen[key]++
+		issues = append(issues, gitlabIssue{
+			Description: f.Message,
+			CheckName:   f.Rule,
+			Fingerprint: hex.EncodeToString(sum[:16]),
+			Severity:    gitlabSeverity(f.Severity),
+			Location: gitlabLocation{
+				Path:  key.File,
+				Lines: gitlabLines{Begin: f.Location.StartLine + 1, End: f.Location.EndLine + 1},
+			},
+		})
+	}
+	return issues
+}
@@ -0,0 +1,51 @@
+package main
+
+import (
+	"bytes"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWriteGitHubAnnotations(t *testing.T) {
+	findings := []CLILintFinding{
+		{Rule: "unused", Severity: severityOff, Message: "function helper is never referenced",
+			Location: CLILocation{File: "/repo/src/a,b.rs", StartLine: 4, EndLine: 4, UTF16StartCol: 3, UTF16EndCol: 9}},
+		{Rule: "package_metrics", Severity: severityError, Message: "instability 0.9 > 0.8\n100% sure",
+			Location: CLILocation{File: "/repo/pkg/mod.rs", StartLine: 0, EndLine: 2}},
+	}
+	var buf bytes.Buffer
+	writeGitHubAnnotations(&buf, findings, "/repo")
+	assert.Equal(t,
+		"::notice file=src/a%2Cb.rs,line=5,endLine=5,col=4,endColumn=10,title=canopy unused::function helper is never referenced\n"+
+			"::error file=pkg/mod.rs,line=1,endLine=3,title=canopy package_metrics::instability 0.9 > 0.8%0A100%25 sure\n",
+		buf.String())
+}
+
+func TestBuildGitLabCodeQuality(t *testing.T) {
+	finding := CLILintFinding{Rule: "deprecated", Severity: severityWarning, Message: "old is deprecated",
+		Location: CLILocation{File: "/repo/src/lib.rs", StartLine: 9, EndLine: 10}}
+	moved := finding
+	moved.Location.StartLine, moved.Location.EndLine = 20, 20
+	other := finding
+	other.Message = "older is deprecated"
+
+	issues := buildGitLabCodeQuality([]CLILintFinding{finding, moved, other}, "/repo")
+	require.Len(t, issues, 3)
+	assert.Equal(t, gitlabIssue{
+		Description: "old is deprecated",
+		CheckName:   "deprecated",
+		Fingerprint: issues[0].Fingerprint,
+		Severity:    "minor",
+		Location:    gitlabLocation{Path: "src/lib.rs", Lines: gitlabLines{Begin: 10, End: 11}},
+	}, issues[0])
+	assert.Len(t, issues[0].Fingerprint, 32)
+	assert.NotEqual(t, issues[0].Fingerprint, issues[1].Fingerprint, "repeated findings get distinct fingerprints")
+	assert.NotEqual(t, issues[0].Fingerprint, issues[2].Fingerprint)
+
+	again := buildGitLabCodeQuality([]CLILintFinding{moved}, "/repo")
+	assert.Equal(t, issues[0].Fingerprint, again[0].Fingerprint, "fingerprints do not depend on the line")
+
+	assert.NotNil(t, buildGitLabCodeQuality(nil, "/repo"), "no findings encode as an empty array")
+}
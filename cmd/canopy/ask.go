@@ -0,0 +1,78 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var askCmd = &cobra.Command{
+	Use:   "ask <question>",
+	Short: "Answer a plain-English question about a symbol with a bundle of graph queries",
+	Long: "Answers a question such as \"what happens when add_product is called?\" by running the queries it calls for\n" +
+		"and rendering their results together. Questions about what a symbol does get its definition, callees, the\n" +
+		"types it touches, and the tests covering it; \"who calls\" and \"where is ... used\" get its callers; questions\n" +
+		"about tests get the tests covering it; \"what breaks if I change\" gets callers further out and tests; \"where\n" +
+		"is ... defined\" gets the definition and types. The symbol is the first name in backquotes, else the first\n" +
+		"identifier-like word naming an indexed symbol; --symbol <id> selects it instead. With --format json, each\n" +
+		"query's results are fields and the Markdown is in text.",
+	Args: cobra.MinimumNArgs(1),
+	RunE: runAsk,
+}
+
+func init() {
+	askCmd.Flags().Int64("symbol", 0, "symbol ID the question is about")
+}
+
+func runAsk(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("ask", err)
+	}
+	defer s.Close()
+
+	symID, _ := cmd.Flags().GetInt64("symbol")
+	a, err := queryBuilder(s).Ask(strings.Join(args, " "), symID)
+	if err != nil {
+		return outputError("ask", err)
+	}
+	if a.Symbol == nil {
+		return outputError("ask", fmt.Errorf("no symbol named in %q is indexed; put the name in backquotes or pass --symbol", a.Question))
+	}
+
+	nodes := func(ns []canopy.CallGraphNode) []CLICallGraphNode {
+		var out []CLICallGraphNode
+		for _, n := range ns {
+			out = append(out, CLICallGraphNode{Symbol: symbolResultToCLI(n.Symbol), Depth: n.Depth})
+		}
+		return out
+	}
+	r := CLIAnswer{
+		Question: a.Question,
+		Intent:   a.Intent,
+		Subject:  a.Subject,
+		Queries:  a.Queries,
+		Callees:  nodes(a.Callees),
+		Callers:  nodes(a.Callers),
+		Tests:    nodes(a.Tests),
+		Text:     a.Markdown(),
+	}
+	if a.Symbol != nil {
+		d := symbolDetailToCLI(a.Symbol)
+		r.Symbol = &d
+	}
+	for _, o := range a.Others {
+		r.Others = append(r.Others, symbolResultToCLI(o))
+	}
+	for _, t := range a.Types {
+		r.Types = append(r.Types, symbolResultToCLI(t))
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "ask",
+		Results:    r,
+		TotalCount: &one,
+	})
+}
@@ -0,0 +1,134 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/spf13/cobra"
+	"github.com/spf13/pflag"
+)
+
+var batchCmd = &cobra.Command{
+	Use:   "batch <file>",
+	Short: "Run many commands in one process over one open index",
+	Long: "Runs the commands listed in a JSON file in one process, sharing one open database, and prints their\n" +
+		"results keyed by ID. The file holds an array of {\"id\": ..., \"args\": [...]} entries, where args are the\n" +
+		"command line after 'canopy', e.g.\n" +
+		"  [{\"id\": \"callers\", \"args\": [\"query\", \"callers\", \"--symbol\", \"42\"]},\n" +
+		"   {\"id\": \"unused\", \"args\": [\"query\", \"unused\", \"--kind\", \"function\"]}]\n" +
+		"Each entry starts from default flags. A failing entry records its error and the rest still run; the batch\n" +
+		"then exits non-zero. Use - to read stdin. Only the json, ndjson, and text formats are supported.",
+	Args: cobra.ExactArgs(1),
+	RunE: runBatch,
+}
+
+// batchQuery is one entry of a batch file.
+type batchQuery struct {
+	ID   string   `json:"id"`
+	Args []string `json:"args"`
+}
+
+var (
+	// batchStore is the store shared by the entries of a running batch;
+	// openStore hands out views of it.
+	batchStore *store.Store
+	// resultSink, when set, receives results and errors in place of
+	// outputResult and outputError writing them.
+	resultSink func(CLIResult)
+)
+
+func runBatch(cmd *cobra.Command, args []string) error {
+	var in io.Reader = os.Stdin
+	if args[0] != "-" {
+		f, err := os.Open(args[0])
+		if err != nil {
+			return outputError("batch", err)
+		}
+		defer f.Close()
+		in = f
+	}
+	var queries []batchQuery
+	if err := json.NewDecoder(in).Decode(&queries); err != nil {
+		return outputError("batch", fmt.Errorf("parsing batch file: %w", err))
+	}
+	seen := map[string]bool{}
+	for i, q := range queries {
+		switch {
+		case q.ID == "":
+			return outputError("batch", fmt.Errorf("entry %d has no id", i))
+		case seen[q.ID]:
+			return outputError("batch", fmt.Errorf("duplicate id %q", q.ID))
+		case len(q.Args) == 0:
+			return outputError("batch", fmt.Errorf("entry %q has no args", q.ID))
+		case q.Args[0] == cmd.Name():
+			return outputError("batch", fmt.Errorf("entry %q: batches cannot be nested", q.ID))
+		}
+		seen[q.ID] = true
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("batch", err)
+	}
+	defer s.Close()
+
+	format := flagFormat
+	results := make(CLIBatchResults, len(queries))
+	failed := 0
+	batchStore = s
+	start := commandStart
+	for _, q := range queries {
+		var result *CLIResult
+		resultSink = func(r CLIResult) { result = &r }
+		resetFlags(cmd.Root())
+		cmd.Root().SetArgs(q.Args)
+		err := cmd.Root().Execute()
+		if result == nil {
+			result = &CLIResult{}
+			if err != nil {
+				result.Error = err.Error()
+			}
+		}
+		if result.Error != "" {
+			failed++
+		}
+		results[q.ID] = *result
+	}
+	batchStore, resultSink = nil, nil
+	commandStart = start
+	flagFormat = format
+	errorHandled = false
+
+	total := len(queries)
+	if err := outputResult(CLIResult{Command: "batch", Results: results, TotalCount: &total}); err != nil {
+		return err
+	}
+	if failed > 0 {
+		return fmt.Errorf("%d of %d batch entries failed", failed, total)
+	}
+	return nil
+}
+
+// resetFlags restores every flag of cmd and its subcommands to its default,
+// so each batch entry parses its command line from a clean slate.
+func resetFlags(cmd *cobra.Command) {
+	reset := func(f *pflag.Flag) {
+		if !f.Changed {
+			return
+		}
+		if sv, ok := f.Value.(pflag.SliceValue); ok {
+			sv.Replace(nil)
+		} else {
+			f.Value.Set(f.DefValue)
+		}
+		f.Changed = false
+	}
+	cmd.Flags().VisitAll(reset)
+	cmd.PersistentFlags().VisitAll(reset)
+	for _, sub := range cmd.Commands() {
+		resetFlags(sub)
+	}
+}
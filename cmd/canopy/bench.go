@@ -0,0 +1,181 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var (
+	flagBenchLanguage      string
+	flagBenchFiles         int
+	flagBenchFunctions     int
+	flagBenchSeed          int64
+	flagBenchRuns          int
+	flagBenchDir           string
+	flagBenchBaseline      string
+	flagBenchMaxRegression float64
+)
+
+var benchCmd = &cobra.Command{
+	Use:   "bench",
+	Short: "Benchmark indexing and queries on a synthetic workspace",
+	Long: "Generates a synthetic workspace of --files files with --functions functions each, wired together with\n" +
+		"cross-file calls, imports, and method calls, then indexes it and times extraction, resolution, and a set of queries.\n" +
+		"Each phase reports the median of --runs runs. With --baseline <file> (the JSON output of an earlier run), each phase\n" +
+		"also reports its change, and --max-regression <percent> fails the command when a phase slowed down by more.",
+	Args: cobra.NoArgs,
+	RunE: runBench,
+}
+
+func init() {
+	benchCmd.Flags().StringVar(&flagBenchLanguage, "language", "rust", "language of the workspace ("+strings.Join(canopy.WorkloadLanguages, ", ")+")")
+	benchCmd.Flags().IntVar(&flagBenchFiles, "files", 200, "number of files to generate")
+	benchCmd.Flags().IntVar(&flagBenchFunctions, "functions", 10, "number of functions per file")
+	benchCmd.Flags().Int64Var(&flagBenchSeed, "seed", 1, "seed of the generated call structure")
+	benchCmd.Flags().IntVar(&flagBenchRuns, "runs", 3, "number of runs; each phase reports the median")
+	benchCmd.Flags().StringVar(&flagBenchDir, "dir", "", "write the workspaces and databases here and keep them (default: a temporary directory)")
+	benchCmd.Flags().StringVar(&flagBenchBaseline, "baseline", "", "JSON output of an earlier bench run to compare against")
+	benchCmd.Flags().Float64Var(&flagBenchMaxRegression, "max-regression", 0, "fail if a phase is more than this many percent slower than --baseline (0: report only)")
+	benchCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+}
+
+func runBench(cmd *cobra.Command, args []string) error {
+	if flagBenchRuns < 1 {
+		return outputError("bench", fmt.Errorf("--runs must be at least 1, got %d", flagBenchRuns))
+	}
+	var baseline *CLIBenchReport
+	if flagBenchBaseline != "" {
+		var err error
+		if baseline, err = readBenchBaseline(flagBenchBaseline); err != nil {
+			return outputError("bench", err)
+		}
+	}
+
+	dir := flagBenchDir
+	if dir == "" {
+		tmp, err := os.MkdirTemp("", "canopy-bench-")
+		if err != nil {
+			return outputError("bench", err)
+		}
+		defer os.RemoveAll(tmp)
+		dir = tmp
+	}
+	var opts []canopy.Option
+	if flagScriptsDir == "" {
+		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+	}
+	w := canopy.Workload{
+		Language:  flagBenchLanguage,
+		Files:     flagBenchFiles,
+		Functions: flagBenchFunctions,
+		Seed:      flagBenchSeed,
+	}
+
+	var reports []*canopy.BenchmarkReport
+	for run := 0; run < flagBenchRuns; run++ {
+		runDir := filepath.Join(dir, fmt.Sprintf("run%d", run+1))
+		if err := os.RemoveAll(runDir); err != nil {
+			return outputError("bench", err)
+		}
+		report, err := canopy.Benchmark(context.Background(), runDir, flagScriptsDir, w, opts...)
+		if err != nil {
+			return outputError("bench", err)
+		}
+		reports = append(reports, report)
+	}
+
+	result := benchReportToCLI(reports, baseline)
+	if err := outputResult(CLIResult{Command: "bench", Results: result}); err != nil {
+		return err
+	}
+	if baseline != nil && flagBenchMaxRegression > 0 {
+		var slower []string
+		for _, t := range result.Timings {
+			if t.ChangePct != nil && *t.ChangePct > flagBenchMaxRegression {
+				slower = append(slower, fmt.Sprintf("%s %+.1f%%", t.Phase, *t.ChangePct))
+			}
+		}
+		if len(slower) > 0 {
+			return fmt.Errorf("bench: slower than baseline by more than %g%%: %s", flagBenchMaxRegression, strings.Join(slower, ", "))
+		}
+	}
+	return nil
+}
+
+// benchReportToCLI summarizes benchmark runs of one workload, taking the
+// median duration of each phase, and compares them with baseline if given.
+func benchReportToCLI(reports []*canopy.BenchmarkReport, baseline *CLIBenchReport) CLIBenchReport {
+	first := reports[0]
+	result := CLIBenchReport{
+		Language:   first.Workload.Language,
+		Files:      first.Files,
+		Functions:  first.Workload.Functions,
+		Seed:       first.Workload.Seed,
+		Runs:       len(reports),
+		Symbols:    first.Symbols,
+		References: first.References,
+		CallEdges:  first.CallEdges,
+	}
+	baselineMillis := map[string]float64{}
+	if baseline != nil {
+		for _, t := range baseline.Timings {
+			baselineMillis[t.Phase] = t.Millis
+		}
+	}
+	for i, t := range first.Timings {
+		runs := make([]time.Duration, len(reports))
+		for j, r := range reports {
+			runs[j] = r.Timings[i].Duration
+		}
+		slices.Sort(runs)
+		timing := CLIBenchTiming{Phase: t.Phase, Millis: durationMillis(runs[len(runs)/2])}
+		if base, ok := baselineMillis[t.Phase]; ok && base > 0 {
+			change := (timing.Millis - base) / base * 100
+			timing.BaselineMillis = base
+			timing.ChangePct = &change
+		}
+		result.Timings = append(result.Timings, timing)
+	}
+	return result
+}
+
+// durationMillis returns d in milliseconds, rounded to microseconds.
+func durationMillis(d time.Duration) float64 {
+	return float64(d.Round(time.Microsecond)) / float64(time.Millisecond)
+}
+
+// readBenchBaseline reads the JSON output of an earlier bench run, either the
+// full result envelope or the bare report (as written by --format ndjson).
+func readBenchBaseline(path string) (*CLIBenchReport, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, fmt.Errorf("reading baseline: %w", err)
+	}
+	var envelope struct {
+		Results *CLIBenchReport `json:"results"`
+	}
+	if err := json.Unmarshal(data, &envelope); err != nil {
+		return nil, fmt.Errorf("parsing baseline %s: %w", path, err)
+	}
+	if envelope.Results != nil {
+		return envelope.Results, nil
+	}
+	var report CLIBenchReport
+	if err := json.Unmarshal(data, &report); err != nil {
+		return nil, fmt.Errorf("parsing baseline %s: %w", path, err)
+	}
+	if len(report.Timings) == 0 {
+		return nil, fmt.Errorf("parsing baseline %s: no timings", path)
+	}
+	return &report, nil
+}
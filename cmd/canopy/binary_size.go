@@ -0,0 +1,97 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var binarySizeCmd = &cobra.Command{
+	Use:   "binary-size",
+	Short: "Attribute Rust binary size to modules and functions",
+	Long: "Answers which module contributes most to a binary. With --bloat (the output of cargo bloat --release -n 0\n" +
+		"--message-format json) or --nm (the output of nm --print-size), each measured function is matched to its source\n" +
+		"function; monomorphized copies and closures add to the function they come from, reachable functions with no code\n" +
+		"of their own are marked inlined, and code no indexed function accounts for is listed by crate. Without either,\n" +
+		"each function the call graph reaches from the entry points (see entry-points; --target narrows them) is\n" +
+		"estimated at the size of its source, which ranks modules but does not measure bytes of machine code.",
+	Args: cobra.NoArgs,
+	RunE: runBinarySize,
+}
+
+func init() {
+	binarySizeCmd.Flags().String("bloat", "", "cargo bloat JSON output to attribute")
+	binarySizeCmd.Flags().String("nm", "", "nm --print-size output to attribute")
+	binarySizeCmd.Flags().StringVar(&flagTarget, "target", "", "only walk from targets with this name, kind, or kind:name")
+}
+
+func runBinarySize(cmd *cobra.Command, args []string) error {
+	bloat, _ := cmd.Flags().GetString("bloat")
+	nm, _ := cmd.Flags().GetString("nm")
+	if bloat != "" && nm != "" {
+		return outputError("binary-size", fmt.Errorf("--bloat and --nm are mutually exclusive"))
+	}
+	var measured []canopy.MeasuredSize
+	for _, in := range []struct {
+		path  string
+		parse func(io.Reader) ([]canopy.MeasuredSize, error)
+	}{
+		{bloat, canopy.ParseBloatJSON},
+		{nm, canopy.ParseNmSizes},
+	} {
+		if in.path == "" {
+			continue
+		}
+		f, err := os.Open(in.path)
+		if err != nil {
+			return outputError("binary-size", err)
+		}
+		measured, err = in.parse(f)
+		f.Close()
+		if err != nil {
+			return outputError("binary-size", err)
+		}
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("binary-size", err)
+	}
+	defer s.Close()
+
+	report, err := queryBuilder(s).BinarySize(measured, flagTarget)
+	if err != nil {
+		return outputError("binary-size", err)
+	}
+	out := CLIBinarySizeReport{
+		Source:       report.Source,
+		Total:        report.Total,
+		Modules:      make([]CLIModuleSize, len(report.Modules)),
+		Unattributed: make([]CLICrateSize, len(report.Unattributed)),
+	}
+	for i, m := range report.Modules {
+		out.Modules[i] = CLIModuleSize{Module: m.Module, Bytes: m.Bytes, Functions: m.Functions, Share: m.Share}
+	}
+	for i, c := range report.Unattributed {
+		out.Unattributed[i] = CLICrateSize{Crate: c.Crate, Bytes: c.Bytes, Share: c.Share}
+	}
+	functions := make([]CLIFunctionSize, len(report.Functions))
+	for i, f := range report.Functions {
+		functions[i] = CLIFunctionSize{
+			Symbol:  symbolResultToCLI(f.Symbol),
+			Module:  f.Module,
+			Bytes:   f.Bytes,
+			Inlined: f.Inlined,
+		}
+	}
+	paged, totalCount := paginateSlice(functions)
+	out.Functions = paged
+	return outputResult(CLIResult{
+		Command:    "binary-size",
+		Results:    out,
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,121 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var checkCmd = &cobra.Command{
+	Use:   "check",
+	Short: "Index, then fail if the quality gates in .canopy.toml are violated",
+	Long: "Indexes the repository like a bare 'canopy index', then evaluates the gates under [gates] in .canopy.toml:\n" +
+		"  max_dead_code        unused symbols, excluding [analysis] entry_points\n" +
+		"  max_cycles           package dependency cycles\n" +
+		"  max_unresolved       references the resolver could not bind\n" +
+		"  min_resolution_rate  resolved share of all references, between 0 and 1\n" +
+		"Reports each gate with its measured value and exits non-zero, naming the violated gates, when any fails.\n" +
+		"--no-index evaluates the existing index instead.",
+	Args: cobra.NoArgs,
+	RunE: runCheck,
+}
+
+func init() {
+	checkCmd.Flags().Bool("no-index", false, "evaluate the existing index without indexing first")
+}
+
+// gateMeasures are the index measurements the quality gates bound.
+type gateMeasures struct {
+	DeadCode   int
+	Cycles     int
+	Unresolved int
+	References int
+}
+
+func runCheck(cmd *cobra.Command, args []string) error {
+	gates := projectConfig.Gates
+	if gates == (GatesConfig{}) {
+		return outputError("check", fmt.Errorf("no gates configured; add a [gates] section to %s", configFileName))
+	}
+	if noIndex, _ := cmd.Flags().GetBool("no-index"); !noIndex {
+		if err := runIndex(indexCmd, nil); err != nil {
+			return outputError("check", err)
+		}
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("check", err)
+	}
+	defer s.Close()
+
+	m, err := measureGates(queryBuilder(s))
+	if err != nil {
+		return outputError("check", err)
+	}
+	report := evaluateGates(gates, m)
+	if err := outputResult(CLIResult{Command: "check", Results: report}); err != nil {
+		return err
+	}
+
+	var errs []error
+	for _, g := range report.Gates {
+		if !g.Passed {
+			errs = append(errs, fmt.Errorf("gate %s: %s, limit %s", g.Gate, formatGateValue(g.Value), formatGateValue(g.Limit)))
+		}
+	}
+	return errors.Join(errs...)
+}
+
+// measureGates takes the measurements of the index that the gates bound.
+func measureGates(qb *canopy.QueryBuilder) (gateMeasures, error) {
+	var m gateMeasures
+	none := 0
+	unused, err := qb.UnusedSymbols(canopy.SymbolFilter{ExcludeNames: projectConfig.Analysis.EntryPoints}, canopy.Sort{}, canopy.Pagination{Limit: &none})
+	if err != nil {
+		return m, err
+	}
+	m.DeadCode = unused.TotalCount
+
+	cycles, err := qb.CircularDependencies()
+	if err != nil {
+		return m, err
+	}
+	m.Cycles = len(cycles)
+
+	unresolved, err := qb.UnresolvedReferences(canopy.UnresolvedFilter{}, canopy.Pagination{Limit: &none})
+	if err != nil {
+		return m, err
+	}
+	m.Unresolved = unresolved.TotalCount
+	m.References = unresolved.TotalReferences
+	return m, nil
+}
+
+// evaluateGates checks m against the configured gates, in config order.
+// An index without references has a resolution rate of 1.
+func evaluateGates(gates GatesConfig, m gateMeasures) CLIGateReport {
+	report := CLIGateReport{Passed: true, Gates: []CLIGate{}}
+	add := func(name string, value, limit float64, passed bool) {
+		report.Gates = append(report.Gates, CLIGate{Gate: name, Value: value, Limit: limit, Passed: passed})
+		report.Passed = report.Passed && passed
+	}
+	atMost := func(name string, value int, limit *int) {
+		if limit != nil {
+			add(name, float64(value), float64(*limit), value <= *limit)
+		}
+	}
+	atMost("max_dead_code", m.DeadCode, gates.MaxDeadCode)
+	atMost("max_cycles", m.Cycles, gates.MaxCycles)
+	atMost("max_unresolved", m.Unresolved, gates.MaxUnresolved)
+	if gates.MinResolutionRate != nil {
+		rate := 1.0
+		if m.References > 0 {
+			rate = float64(m.References-m.Unresolved) / float64(m.References)
+		}
+		add("min_resolution_rate", rate, *gates.MinResolutionRate, rate >= *gates.MinResolutionRate)
+	}
+	return report
+}
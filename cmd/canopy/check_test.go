@@ -0,0 +1,33 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestEvaluateGates(t *testing.T) {
+	t.Parallel()
+	maxDead, maxCycles := 5, 0
+	minRate := 0.9
+	gates := GatesConfig{MaxDeadCode: &maxDead, MaxCycles: &maxCycles, MinResolutionRate: &minRate}
+
+	report := evaluateGates(gates, gateMeasures{DeadCode: 5, Cycles: 1, Unresolved: 5, References: 100})
+	assert.False(t, report.Passed)
+	assert.Equal(t, []CLIGate{
+		{Gate: "max_dead_code", Value: 5, Limit: 5, Passed: true},
+		{Gate: "max_cycles", Value: 1, Limit: 0, Passed: false},
+		{Gate: "min_resolution_rate", Value: 0.95, Limit: 0.9, Passed: true},
+	}, report.Gates, "unset gates are skipped")
+
+	report = evaluateGates(GatesConfig{MinResolutionRate: &minRate}, gateMeasures{})
+	assert.True(t, report.Passed)
+	assert.Equal(t, 1.0, report.Gates[0].Value, "no references resolve fully")
+}
+
+func TestFormatGateValue(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, "12", formatGateValue(12))
+	assert.Equal(t, "0.9512", formatGateValue(0.95123))
+	assert.Equal(t, "1", formatGateValue(1))
+}
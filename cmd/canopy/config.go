@@ -0,0 +1,696 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy"
+)
+
+// configFileName is the per-repository config file, looked up at the repo root.
+const configFileName = ".canopy.toml"
+
+// Config is the parsed contents of .canopy.toml. A missing file yields the
+// zero Config. Explicit CLI flags always take precedence over config values.
+type Config struct {
+	Workspace WorkspaceConfig
+	Index     IndexConfig
+	Analysis  AnalysisConfig
+	Rules     map[string]string // rule name -> severity ("error", "warning", "off")
+	Output    OutputConfig
+	Metrics   canopy.MetricThresholds
+	Embedding EmbeddingConfig
+	Gates     GatesConfig
+
+	// RuleSets are the [rulesets.<name>] sections: named sets of rule
+	// severities that override Rules when selected with --ruleset.
+	RuleSets map[string]map[string]string
+	RuleSet  string // the selected rule set, or ""
+}
+
+// WorkspaceConfig is the [workspace] section: roots indexed together by a
+// bare `canopy index`, relative to the repo root, and the
+// [workspace.dependencies] section: the roots each root may link to.
+type WorkspaceConfig struct {
+	Roots        []string
+	Dependencies map[string][]string // root -> roots it depends on; nil if not declared
+}
+
+// IndexConfig is the [index] section: which files and languages to index.
+type IndexConfig struct {
+	Include   []string // globs relative to the indexed root
+	Exclude   []string // globs relative to the indexed root
+	Languages []string // restrict to these languages (like --languages)
+	Parallel  *bool    // like --parallel
+	Blame     *bool    // like --blame
+	SQLRefs   *bool    // like --sql-refs
+	Expand    *bool    // like --expand-macros
+	Shards    *bool    // like --shards
+	MaxMemory string   // like --max-memory
+	Degrade   *bool    // like --degrade
+
+	DegradeErrorShare *float64 // like --degrade-error-share
+
+	RustdocJSON []string // rustdoc JSON files to load as dependency stubs (like --rustdoc-json)
+	ParseCache  string   // like --parse-cache, relative to the repo root
+	TargetDir   string   // like --target-dir, relative to the repo root
+}
+
+// AnalysisConfig is the [analysis] section.
+type AnalysisConfig struct {
+	EntryPoints []string // symbol names never reported as unused (e.g. main)
+}
+
+// OutputConfig is the [output] section: defaults for --format and --limit.
+type OutputConfig struct {
+	Format string
+	Limit  *int
+}
+
+// EmbeddingConfig is the [embedding] section: the backend 'canopy embed'
+// and 'canopy search' use.
+type EmbeddingConfig struct {
+	Backend    string // like --backend
+	URL        string // like --url
+	Model      string // like --model
+	APIKeyEnv  string // like --api-key-env
+	Dimensions *int   // like --dimensions
+}
+
+// GatesConfig is the [gates] section: the thresholds 'canopy check' fails
+// on. Gates left unset are not checked.
+type GatesConfig struct {
+	MaxDeadCode       *int     // unused symbols, entry points excluded
+	MaxCycles         *int     // package dependency cycles
+	MaxUnresolved     *int     // references the resolver could not bind
+	MinResolutionRate *float64 // resolved share of all references, 0 to 1
+}
+
+// Rule severities.
+const (
+	severityError   = "error"
+	severityWarning = "warning"
+	severityOff     = "off"
+)
+
+// defaultRuleSeverities lists every configurable rule with its default.
+// Rules at "error" make the command exit non-zero when they have findings;
+// "warning" reports the finding count on stderr.
+var defaultRuleSeverities = map[string]string{
+	"unused":          severityOff,
+	"deprecated":      severityOff,
+	"circular_deps":   severityOff,
+	"package_metrics": severityError,
+	"glob_collisions": severityWarning,
+}
+
+// ruleSeverity returns the configured severity for rule: from the selected
+// rule set, else from [rules], else its default.
+func (c *Config) ruleSeverity(rule string) string {
+	if sev, ok := c.RuleSets[c.RuleSet][rule]; ok {
+		return sev
+	}
+	if sev, ok := c.Rules[rule]; ok {
+		return sev
+	}
+	return defaultRuleSeverities[rule]
+}
+
+// loadConfig reads .canopy.toml from repoRoot. Returns an empty Config if the
+// file does not exist.
+func loadConfig(repoRoot string) (*Config, error) {
+	path := filepath.Join(repoRoot, configFileName)
+	data, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		return &Config{}, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("reading %s: %w", path, err)
+	}
+	cfg, err := parseConfig(data)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", configFileName, err)
+	}
+	return cfg, nil
+}
+
+// parseConfig decodes .canopy.toml contents into a Config. Unknown sections
+// and keys are rejected so typos don't silently disable a setting.
+func parseConfig(data []byte) (*Config, error) {
+	tables, err := parseTOML(data)
+	if err != nil {
+		return nil, err
+	}
+
+	cfg := &Config{}
+	for section, kv := range tables {
+		var err error
+		switch section {
+		case "workspace":
+			err = decodeWorkspaceConfig(kv, &cfg.Workspace)
+		case "workspace.dependencies":
+			cfg.Workspace.Dependencies, err = decodeWorkspaceDependencies(kv)
+		case "index":
+			err = decodeIndexConfig(kv, &cfg.Index)
+		case "analysis":
+			err = decodeAnalysisConfig(kv, &cfg.Analysis)
+		case "rules":
+			cfg.Rules, err = decodeRules("rules", kv)
+		case "output":
+			err = decodeOutputConfig(kv, &cfg.Output)
+		case "metrics":
+			err = decodeMetricThresholds(kv, &cfg.Metrics)
+		case "embedding":
+			err = decodeEmbeddingConfig(kv, &cfg.Embedding)
+		case "gates":
+			err = decodeGatesConfig(kv, &cfg.Gates)
+		case "":
+			for key := range kv {
+				return nil, fmt.Errorf("unknown top-level key %q", key)
+			}
+		default:
+			name, ok := strings.CutPrefix(section, "rulesets.")
+			if !ok || name == "" {
+				return nil, fmt.Errorf("unknown section [%s]", section)
+			}
+			if cfg.RuleSets == nil {
+				cfg.RuleSets = map[string]map[string]string{}
+			}
+			cfg.RuleSets[name], err = decodeRules(section, kv)
+		}
+		if err != nil {
+			return nil, err
+		}
+	}
+	return cfg, nil
+}
+
+// selectRuleSet selects the [rulesets.<name>] section for ruleSeverity.
+func (c *Config) selectRuleSet(name string) error {
+	if _, ok := c.RuleSets[name]; !ok {
+		return fmt.Errorf("unknown rule set %q (define it under [rulesets.%s] in %s)", name, name, configFileName)
+	}
+	c.RuleSet = name
+	return nil
+}
+
+func decodeWorkspaceConfig(kv map[string]any, wc *WorkspaceConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "roots":
+			wc.Roots, err = tomlStrings(val)
+		default:
+			return fmt.Errorf("unknown key workspace.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("workspace.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func decodeWorkspaceDependencies(kv map[string]any) (map[string][]string, error) {
+	deps := make(map[string][]string, len(kv))
+	for root, val := range kv {
+		on, err := tomlStrings(val)
+		if err != nil {
+			return nil, fmt.Errorf("workspace.dependencies.%s: %w", root, err)
+		}
+		deps[root] = on
+	}
+	return deps, nil
+}
+
+func decodeIndexConfig(kv map[string]any, ic *IndexConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "include":
+			ic.Include, err = tomlStrings(val)
+		case "exclude":
+			ic.Exclude, err = tomlStrings(val)
+		case "languages":
+			ic.Languages, err = tomlStrings(val)
+		case "parallel":
+			ic.Parallel, err = tomlBool(val)
+		case "blame":
+			ic.Blame, err = tomlBool(val)
+		case "sql_refs":
+			ic.SQLRefs, err = tomlBool(val)
+		case "expand_macros":
+			ic.Expand, err = tomlBool(val)
+		case "shards":
+			ic.Shards, err = tomlBool(val)
+		case "rustdoc_json":
+			ic.RustdocJSON, err = tomlStrings(val)
+		case "parse_cache":
+			ic.ParseCache, err = tomlString(val)
+		case "target_dir":
+			ic.TargetDir, err = tomlString(val)
+		case "degrade":
+			ic.Degrade, err = tomlBool(val)
+		case "degrade_error_share":
+			if ic.DegradeErrorShare, err = tomlFloat(val); err == nil && (*ic.DegradeErrorShare <= 0 || *ic.DegradeErrorShare > 1) {
+				err = fmt.Errorf("must be above 0 and at most 1, got %v", *ic.DegradeErrorShare)
+			}
+		case "max_memory":
+			if ic.MaxMemory, err = tomlString(val); err == nil {
+				_, err = parseByteSize(ic.MaxMemory)
+			}
+		default:
+			return fmt.Errorf("unknown key index.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("index.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func decodeAnalysisConfig(kv map[string]any, ac *AnalysisConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "entry_points":
+			ac.EntryPoints, err = tomlStrings(val)
+		default:
+			return fmt.Errorf("unknown key analysis.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("analysis.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+// decodeRules decodes a table of rule severities, [rules] or a
+// [rulesets.<name>] section. "warn" is accepted for "warning".
+func decodeRules(section string, kv map[string]any) (map[string]string, error) {
+	rules := make(map[string]string, len(kv))
+	for key, val := range kv {
+		if _, ok := defaultRuleSeverities[key]; !ok {
+			return nil, fmt.Errorf("unknown rule %s.%s", section, key)
+		}
+		sev, ok := val.(string)
+		if !ok {
+			return nil, fmt.Errorf("%s.%s: expected string, got %T", section, key, val)
+		}
+		switch sev {
+		case severityError, severityWarning, severityOff:
+		case "warn":
+			sev = severityWarning
+		default:
+			return nil, fmt.Errorf("%s.%s: invalid severity %q (must be error, warning, or off)", section, key, sev)
+		}
+		rules[key] = sev
+	}
+	return rules, nil
+}
+
+func decodeOutputConfig(kv map[string]any, oc *OutputConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "format":
+			f, ok := val.(string)
+			if !ok {
+				err = fmt.Errorf("expected string, got %T", val)
+				break
+			}
+			err = validateFormat(f)
+			oc.Format = f
+		case "limit":
+			oc.Limit, err = tomlInt(val)
+		default:
+			return fmt.Errorf("unknown key output.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("output.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func decodeEmbeddingConfig(kv map[string]any, ec *EmbeddingConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "backend":
+			if ec.Backend, err = tomlString(val); err == nil {
+				err = validateEmbeddingBackend(ec.Backend)
+			}
+		case "url":
+			ec.URL, err = tomlString(val)
+		case "model":
+			ec.Model, err = tomlString(val)
+		case "api_key_env":
+			ec.APIKeyEnv, err = tomlString(val)
+		case "dimensions":
+			ec.Dimensions, err = tomlInt(val)
+		default:
+			return fmt.Errorf("unknown key embedding.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("embedding.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func decodeGatesConfig(kv map[string]any, gc *GatesConfig) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "max_dead_code":
+			gc.MaxDeadCode, err = tomlCount(val)
+		case "max_cycles":
+			gc.MaxCycles, err = tomlCount(val)
+		case "max_unresolved":
+			gc.MaxUnresolved, err = tomlCount(val)
+		case "min_resolution_rate":
+			if gc.MinResolutionRate, err = tomlFloat(val); err == nil && (*gc.MinResolutionRate < 0 || *gc.MinResolutionRate > 1) {
+				err = fmt.Errorf("must be between 0 and 1, got %g", *gc.MinResolutionRate)
+			}
+		default:
+			return fmt.Errorf("unknown key gates.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("gates.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func decodeMetricThresholds(kv map[string]any, t *canopy.MetricThresholds) error {
+	for key, val := range kv {
+		var err error
+		switch key {
+		case "max_afferent":
+			t.MaxAfferent, err = tomlInt(val)
+		case "max_efferent":
+			t.MaxEfferent, err = tomlInt(val)
+		case "max_instability":
+			t.MaxInstability, err = tomlFloat(val)
+		case "max_fan_in":
+			t.MaxFanIn, err = tomlInt(val)
+		case "max_fan_out":
+			t.MaxFanOut, err = tomlInt(val)
+		default:
+			return fmt.Errorf("unknown key metrics.%s", key)
+		}
+		if err != nil {
+			return fmt.Errorf("metrics.%s: %w", key, err)
+		}
+	}
+	return nil
+}
+
+func tomlStrings(v any) ([]string, error) {
+	arr, ok := v.([]any)
+	if !ok {
+		return nil, fmt.Errorf("expected array of strings, got %T", v)
+	}
+	out := make([]string, len(arr))
+	for i, e := range arr {
+		s, ok := e.(string)
+		if !ok {
+			return nil, fmt.Errorf("element %d: expected string, got %T", i, e)
+		}
+		out[i] = s
+	}
+	return out, nil
+}
+
+func tomlString(v any) (string, error) {
+	s, ok := v.(string)
+	if !ok {
+		return "", fmt.Errorf("expected string, got %T", v)
+	}
+	return s, nil
+}
+
+func tomlBool(v any) (*bool, error) {
+	b, ok := v.(bool)
+	if !ok {
+		return nil, fmt.Errorf("expected boolean, got %T", v)
+	}
+	return &b, nil
+}
+
+func tomlInt(v any) (*int, error) {
+	n, ok := v.(int64)
+	if !ok {
+		return nil, fmt.Errorf("expected integer, got %T", v)
+	}
+	i := int(n)
+	return &i, nil
+}
+
+// tomlCount is tomlInt for values that must not be negative.
+func tomlCount(v any) (*int, error) {
+	n, err := tomlInt(v)
+	if err == nil && *n < 0 {
+		err = fmt.Errorf("must not be negative, got %d", *n)
+	}
+	return n, err
+}
+
+func tomlFloat(v any) (*float64, error) {
+	switch n := v.(type) {
+	case float64:
+		return &n, nil
+	case int64:
+		f := float64(n)
+		return &f, nil
+	default:
+		return nil, fmt.Errorf("expected number, got %T", v)
+	}
+}
+
+// byteUnits are the size suffixes parseByteSize accepts, longest first so
+// "KiB" is not read as "B".
+var byteUnits = []struct {
+	suffix string
+	bytes  int64
+}{
+	{"KiB", 1 << 10}, {"MiB", 1 << 20}, {"GiB", 1 << 30}, {"TiB", 1 << 40},
+	{"KB", 1e3}, {"MB", 1e6}, {"GB", 1e9}, {"TB", 1e12},
+	{"K", 1 << 10}, {"M", 1 << 20}, {"G", 1 << 30}, {"T", 1 << 40},
+	{"B", 1},
+}
+
+// parseByteSize parses a size such as "512MiB", "2GB", "4G", or "1048576"
+// (bytes). K, M, G, and T alone are binary units.
+func parseByteSize(size string) (int64, error) {
+	s := strings.TrimSpace(size)
+	mult := int64(1)
+	for _, u := range byteUnits {
+		if strings.HasSuffix(s, u.suffix) {
+			s, mult = strings.TrimSpace(strings.TrimSuffix(s, u.suffix)), u.bytes
+			break
+		}
+	}
+	n, err := strconv.ParseFloat(s, 64)
+	if err != nil || n <= 0 {
+		return 0, fmt.Errorf("invalid size %q (want e.g. 512MiB or 4GB)", size)
+	}
+	return int64(n * float64(mult)), nil
+}
+
+// parseTOML parses the subset of TOML that .canopy.toml uses: [table] and
+// [dotted.table] headers, key = value pairs, # comments, and values that are
+// strings, integers, floats, booleans, or (possibly multi-line) arrays of
+// those. Returns a map of table name ("" for top level) to key/value pairs.
+func parseTOML(data []byte) (map[string]map[string]any, error) {
+	tables := map[string]map[string]any{"": {}}
+	current := ""
+
+	scanner := bufio.NewScanner(bytes.NewReader(data))
+	lineNo := 0
+	for scanner.Scan() {
+		lineNo++
+		line := strings.TrimSpace(stripTOMLComment(scanner.Text()))
+		if line == "" {
+			continue
+		}
+
+		if strings.HasPrefix(line, "[") {
+			if !strings.HasSuffix(line, "]") || strings.HasPrefix(line, "[[") {
+				return nil, fmt.Errorf("line %d: invalid table header %q", lineNo, line)
+			}
+			current = strings.TrimSpace(line[1 : len(line)-1])
+			if current == "" {
+				return nil, fmt.Errorf("line %d: empty table name", lineNo)
+			}
+			if _, ok := tables[current]; !ok {
+				tables[current] = map[string]any{}
+			}
+			continue
+		}
+
+		eq := strings.Index(line, "=")
+		if eq < 0 {
+			return nil, fmt.Errorf("line %d: expected key = value", lineNo)
+		}
+		key := strings.Trim(strings.TrimSpace(line[:eq]), `"`)
+		raw := strings.TrimSpace(line[eq+1:])
+
+		// Arrays may span lines; keep reading until brackets balance.
+		for strings.HasPrefix(raw, "[") && bracketDepth(raw) > 0 {
+			if !scanner.Scan() {
+				return nil, fmt.Errorf("line %d: unterminated array for %q", lineNo, key)
+			}
+			lineNo++
+			raw += " " + strings.TrimSpace(stripTOMLComment(scanner.Text()))
+		}
+
+		val, err := parseTOMLValue(raw)
+		if err != nil {
+			return nil, fmt.Errorf("line %d: %s: %w", lineNo, key, err)
+		}
+		if _, dup := tables[current][key]; dup {
+			return nil, fmt.Errorf("line %d: duplicate key %q", lineNo, key)
+		}
+		tables[current][key] = val
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	return tables, nil
+}
+
+// stripTOMLComment removes a trailing # comment that is not inside a string.
+func stripTOMLComment(line string) string {
+	var quote byte
+	for i := 0; i < len(line); i++ {
+		c := line[i]
+		switch {
+		case quote != 0:
+			if c == '\\' && quote == '"' {
+				i++
+			} else if c == quote {
+				quote = 0
+			}
+		case c == '"' || c == '\'':
+			quote = c
+		case c == '#':
+			return line[:i]
+		}
+	}
+	return line
+}
+
+// bracketDepth returns the number of unclosed '[' in s, ignoring brackets
+// inside strings.
+func bracketDepth(s string) int {
+	depth := 0
+	var quote byte
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case quote != 0:
+			if c == '\\' && quote == '"' {
+				i++
+			} else if c == quote {
+				quote = 0
+			}
+		case c == '"' || c == '\'':
+			quote = c
+		case c == '[':
+			depth++
+		case c == ']':
+			depth--
+		}
+	}
+	return depth
+}
+
+// parseTOMLValue parses a single scalar or array value.
+func parseTOMLValue(raw string) (any, error) {
+	switch {
+	case raw == "":
+		return nil, fmt.Errorf("missing value")
+	case raw == "true":
+		return true, nil
+	case raw == "false":
+		return false, nil
+	case raw[0] == '"':
+		s, err := strconv.Unquote(raw)
+		if err != nil {
+			return nil, fmt.Errorf("invalid string %s", raw)
+		}
+		return s, nil
+	case raw[0] == '\'':
+		if len(raw) < 2 || raw[len(raw)-1] != '\'' {
+			return nil, fmt.Errorf("invalid literal string %s", raw)
+		}
+		return raw[1 : len(raw)-1], nil
+	case raw[0] == '[':
+		return parseTOMLArray(raw)
+	}
+
+	num := strings.ReplaceAll(raw, "_", "")
+	if n, err := strconv.ParseInt(num, 10, 64); err == nil {
+		return n, nil
+	}
+	if f, err := strconv.ParseFloat(num, 64); err == nil {
+		return f, nil
+	}
+	return nil, fmt.Errorf("unsupported value %s", raw)
+}
+
+// parseTOMLArray parses "[a, b, ...]" into a []any. Nested arrays are not
+// supported. A trailing comma is allowed.
+func parseTOMLArray(raw string) ([]any, error) {
+	if !strings.HasSuffix(raw, "]") {
+		return nil, fmt.Errorf("invalid array %s", raw)
+	}
+	inner := strings.TrimSpace(raw[1 : len(raw)-1])
+
+	items := []any{}
+	var quote byte
+	start := 0
+	for i := 0; i <= len(inner); i++ {
+		if i < len(inner) {
+			c := inner[i]
+			if quote != 0 {
+				if c == '\\' && quote == '"' {
+					i++
+				} else if c == quote {
+					quote = 0
+				}
+				continue
+			}
+			if c == '"' || c == '\'' {
+				quote = c
+				continue
+			}
+			if c == '[' {
+				return nil, fmt.Errorf("nested arrays are not supported")
+			}
+			if c != ',' {
+				continue
+			}
+		}
+		elem := strings.TrimSpace(inner[start:i])
+		start = i + 1
+		if elem == "" {
+			if i == len(inner) {
+				break // trailing comma or empty array
+			}
+			return nil, fmt.Errorf("empty array element")
+		}
+		v, err := parseTOMLValue(elem)
+		if err != nil {
+			return nil, err
+		}
+		items = append(items, v)
+	}
+	return items, nil
+}
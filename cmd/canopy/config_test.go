@@ -0,0 +1,294 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseTOML_ValuesAndTables(t *testing.T) {
+	t.Parallel()
+	tables, err := parseTOML([]byte(`
+# top-level comment
+name = "canopy" # trailing comment
+[metrics]
+max_fan_out = 1_000
+max_instability = 0.8
+enabled = true
+globs = [
+  "src/**",   # first
+  'vendor/#keep',
+]
+`))
+	require.NoError(t, err)
+	assert.Equal(t, "canopy", tables[""]["name"])
+	assert.Equal(t, int64(1000), tables["metrics"]["max_fan_out"])
+	assert.Equal(t, 0.8, tables["metrics"]["max_instability"])
+	assert.Equal(t, true, tables["metrics"]["enabled"])
+	assert.Equal(t, []any{"src/**", "vendor/#keep"}, tables["metrics"]["globs"])
+}
+
+func TestParseTOML_Errors(t *testing.T) {
+	t.Parallel()
+	for _, src := range []string{
+		"key",
+		"[metrics\nx = 1",
+		"x = [1, 2",
+		"x = 1\nx = 2",
+		"x = nope",
+	} {
+		_, err := parseTOML([]byte(src))
+		assert.Error(t, err, "src: %q", src)
+	}
+}
+
+func TestParseConfig_MetricThresholds(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte(`
+[metrics]
+max_efferent = 8
+max_instability = 1
+`))
+	require.NoError(t, err)
+	require.NotNil(t, cfg.Metrics.MaxEfferent)
+	assert.Equal(t, 8, *cfg.Metrics.MaxEfferent)
+	require.NotNil(t, cfg.Metrics.MaxInstability)
+	assert.Equal(t, 1.0, *cfg.Metrics.MaxInstability)
+	assert.Nil(t, cfg.Metrics.MaxFanIn)
+
+	_, err = parseConfig([]byte("[metrics]\nmax_efferrent = 8\n"))
+	assert.ErrorContains(t, err, "unknown key metrics.max_efferrent")
+
+	_, err = parseConfig([]byte("[metrics]\nmax_efferent = \"8\"\n"))
+	assert.Error(t, err)
+}
+
+func TestParseConfig_AllSections(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte(`
+[workspace]
+roots = ["services/api", "libs/core"]
+
+[workspace.dependencies]
+"services/api" = ["libs/core"]
+
+[index]
+include = ["src/**", "crates/**"]
+exclude = ["target", "**/generated/**"]
+languages = ["rust"]
+parallel = false
+blame = true
+sql_refs = true
+expand_macros = true
+shards = true
+rustdoc_json = ["target/doc/serde.json"]
+parse_cache = ".cache/canopy"
+target_dir = "target"
+
+[analysis]
+entry_points = ["main", "run"]
+
+[rules]
+unused = "warning"
+deprecated = "error"
+
+[output]
+format = "text"
+limit = 200
+`))
+	require.NoError(t, err)
+	assert.Equal(t, []string{"services/api", "libs/core"}, cfg.Workspace.Roots)
+	assert.Equal(t, map[string][]string{"services/api": {"libs/core"}}, cfg.Workspace.Dependencies)
+	assert.Equal(t, []string{"src/**", "crates/**"}, cfg.Index.Include)
+	assert.Equal(t, []string{"target", "**/generated/**"}, cfg.Index.Exclude)
+	assert.Equal(t, []string{"rust"}, cfg.Index.Languages)
+	require.NotNil(t, cfg.Index.Parallel)
+	assert.False(t, *cfg.Index.Parallel)
+	require.NotNil(t, cfg.Index.Blame)
+	assert.True(t, *cfg.Index.Blame)
+	require.NotNil(t, cfg.Index.SQLRefs)
+	assert.True(t, *cfg.Index.SQLRefs)
+	require.NotNil(t, cfg.Index.Expand)
+	assert.True(t, *cfg.Index.Expand)
+	require.NotNil(t, cfg.Index.Shards)
+	assert.True(t, *cfg.Index.Shards)
+	assert.Equal(t, []string{"target/doc/serde.json"}, cfg.Index.RustdocJSON)
+	assert.Equal(t, ".cache/canopy", cfg.Index.ParseCache)
+	assert.Equal(t, "target", cfg.Index.TargetDir)
+	assert.Equal(t, []string{"main", "run"}, cfg.Analysis.EntryPoints)
+	assert.Equal(t, "text", cfg.Output.Format)
+	require.NotNil(t, cfg.Output.Limit)
+	assert.Equal(t, 200, *cfg.Output.Limit)
+
+	assert.Equal(t, severityWarning, cfg.ruleSeverity("unused"))
+	assert.Equal(t, severityError, cfg.ruleSeverity("deprecated"))
+	assert.Equal(t, severityOff, cfg.ruleSeverity("circular_deps"), "unset rules use their default")
+	assert.Equal(t, severityError, cfg.ruleSeverity("package_metrics"))
+}
+
+func TestParseConfig_RejectsInvalidValues(t *testing.T) {
+	t.Parallel()
+	for _, src := range []string{
+		"[rules]\nunused = \"fatal\"\n",
+		"[rules]\nno_such_rule = \"error\"\n",
+		"[rulesets.strict]\nunused = \"fatal\"\n",
+		"[rulesets]\nunused = \"error\"\n",
+		"[output]\nformat = \"xml\"\n",
+		"[index]\ninclude = \"src/**\"\n",
+		"[index]\nlanguages = [1, 2]\n",
+		"[workspace.dependencies]\napi = \"core\"\n",
+		"[unknown]\nx = 1\n",
+		"stray = 1\n",
+	} {
+		_, err := parseConfig([]byte(src))
+		assert.Error(t, err, "src: %q", src)
+	}
+}
+
+func TestParseConfig_RuleSets(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte(`
+[rules]
+unused = "warning"
+deprecated = "error"
+
+[rulesets.strict]
+unused = "error"
+circular_deps = "error"
+
+[rulesets.migration]
+deprecated = "warn"
+`))
+	require.NoError(t, err)
+	assert.Equal(t, severityWarning, cfg.ruleSeverity("unused"), "no rule set selected")
+
+	require.NoError(t, cfg.selectRuleSet("strict"))
+	assert.Equal(t, severityError, cfg.ruleSeverity("unused"))
+	assert.Equal(t, severityError, cfg.ruleSeverity("circular_deps"))
+	assert.Equal(t, severityError, cfg.ruleSeverity("deprecated"), "rules the set leaves out fall back to [rules]")
+
+	require.NoError(t, cfg.selectRuleSet("migration"))
+	assert.Equal(t, severityWarning, cfg.ruleSeverity("deprecated"))
+	assert.Equal(t, severityError, cfg.ruleSeverity("package_metrics"), "and then to the default")
+
+	assert.Error(t, cfg.selectRuleSet("lenient"))
+}
+
+func TestLoadConfig_MissingFileIsEmpty(t *testing.T) {
+	t.Parallel()
+	cfg, err := loadConfig(t.TempDir())
+	require.NoError(t, err)
+	assert.Nil(t, cfg.Metrics.MaxEfferent)
+}
+
+func TestLoadConfig_ReadsRepoRootFile(t *testing.T) {
+	t.Parallel()
+	root := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(root, configFileName), []byte("[metrics]\nmax_fan_in = 3\n"), 0o644))
+
+	cfg, err := loadConfig(root)
+	require.NoError(t, err)
+	require.NotNil(t, cfg.Metrics.MaxFanIn)
+	assert.Equal(t, 3, *cfg.Metrics.MaxFanIn)
+}
+
+func TestParseByteSize(t *testing.T) {
+	t.Parallel()
+	for in, want := range map[string]int64{
+		"1048576": 1 << 20,
+		"512MiB":  512 << 20,
+		"2GiB":    2 << 30,
+		"4G":      4 << 30,
+		"1.5GB":   1_500_000_000,
+		"64 KB":   64_000,
+		"100B":    100,
+	} {
+		got, err := parseByteSize(in)
+		require.NoError(t, err, in)
+		assert.Equal(t, want, got, in)
+	}
+	for _, bad := range []string{"", "lots", "-1G", "0", "GiB"} {
+		_, err := parseByteSize(bad)
+		assert.ErrorContains(t, err, "invalid size", bad)
+	}
+}
+
+func TestParseConfig_MaxMemory(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte("[index]\nmax_memory = \"2GiB\"\n"))
+	require.NoError(t, err)
+	assert.Equal(t, "2GiB", cfg.Index.MaxMemory)
+
+	_, err = parseConfig([]byte("[index]\nmax_memory = \"huge\"\n"))
+	assert.ErrorContains(t, err, `index.max_memory: invalid size "huge"`)
+	_, err = parseConfig([]byte("[index]\nmax_memory = 2048\n"))
+	assert.ErrorContains(t, err, "expected string")
+}
+
+func TestParseConfig_Degrade(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte("[index]\ndegrade = true\ndegrade_error_share = 0.5\n"))
+	require.NoError(t, err)
+	require.NotNil(t, cfg.Index.Degrade)
+	assert.True(t, *cfg.Index.Degrade)
+	require.NotNil(t, cfg.Index.DegradeErrorShare)
+	assert.Equal(t, 0.5, *cfg.Index.DegradeErrorShare)
+
+	for _, bad := range []string{"0", "1.5", "-0.1"} {
+		_, err = parseConfig([]byte("[index]\ndegrade_error_share = " + bad + "\n"))
+		assert.ErrorContains(t, err, "index.degrade_error_share", bad)
+	}
+}
+
+func TestParseConfig_Embedding(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte(`
+[embedding]
+backend = "http"
+url = "http://localhost:11434/v1/embeddings"
+model = "nomic-embed-text"
+api_key_env = "EMBED_KEY"
+`))
+	require.NoError(t, err)
+	assert.Equal(t, EmbeddingConfig{
+		Backend:   "http",
+		URL:       "http://localhost:11434/v1/embeddings",
+		Model:     "nomic-embed-text",
+		APIKeyEnv: "EMBED_KEY",
+	}, cfg.Embedding)
+
+	_, err = parseConfig([]byte("[embedding]\nbackend = \"gpu\"\n"))
+	assert.ErrorContains(t, err, "embedding.backend")
+	_, err = parseConfig([]byte("[embedding]\ndimensions = \"256\"\n"))
+	assert.ErrorContains(t, err, "expected integer")
+}
+
+func TestParseConfig_Gates(t *testing.T) {
+	t.Parallel()
+	cfg, err := parseConfig([]byte(`
+[gates]
+max_dead_code = 20
+max_cycles = 0
+min_resolution_rate = 0.95
+`))
+	require.NoError(t, err)
+	require.NotNil(t, cfg.Gates.MaxDeadCode)
+	assert.Equal(t, 20, *cfg.Gates.MaxDeadCode)
+	require.NotNil(t, cfg.Gates.MaxCycles)
+	assert.Equal(t, 0, *cfg.Gates.MaxCycles)
+	assert.Nil(t, cfg.Gates.MaxUnresolved)
+	require.NotNil(t, cfg.Gates.MinResolutionRate)
+	assert.Equal(t, 0.95, *cfg.Gates.MinResolutionRate)
+
+	for src, msg := range map[string]string{
+		"[gates]\nmax_cycles = -1\n":          "gates.max_cycles",
+		"[gates]\nmin_resolution_rate = 95\n": "between 0 and 1",
+		"[gates]\nmax_warnings = 3\n":         "unknown key gates.max_warnings",
+	} {
+		_, err := parseConfig([]byte(src))
+		assert.ErrorContains(t, err, msg, "src: %q", src)
+	}
+}
@@ -0,0 +1,145 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var (
+	flagMaxPercent float64
+	flagMinCallers int
+)
+
+var coverageCmd = &cobra.Command{
+	Use:   "coverage <file>",
+	Short: "Load an LCOV coverage report onto functions and methods",
+	Long: "Loads a coverage report in LCOV format, as written by grcov or cargo llvm-cov --lcov, and records how many\n" +
+		"instrumented lines of each indexed function and method ran. Relative source paths are taken relative to the repo\n" +
+		"root. The report replaces any loaded before; 'canopy query coverage' lists the result. Use - to read stdin.",
+	Args: cobra.ExactArgs(1),
+	RunE: runCoverage,
+}
+
+var queryCoverageCmd = &cobra.Command{
+	Use:   "coverage",
+	Short: "List functions and methods by test coverage",
+	Long: "Lists the functions and methods covered by the report loaded by 'canopy coverage', least covered first, then\n" +
+		"by most callers. For example, public functions at most 20% covered with at least 10 callers:\n" +
+		"  canopy query coverage --kind function --visibility public --max-percent 20 --min-callers 10",
+	Args: cobra.NoArgs,
+	RunE: runQueryCoverage,
+}
+
+func init() {
+	coverageCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+
+	queryCoverageCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	queryCoverageCmd.Flags().StringVar(&flagVisibility, "visibility", "", "filter by visibility (public, private)")
+	queryCoverageCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	queryCoverageCmd.Flags().Float64Var(&flagMaxPercent, "max-percent", 100, "only symbols with at most this percentage of lines covered")
+	queryCoverageCmd.Flags().IntVar(&flagMinCallers, "min-callers", 0, "only symbols with at least this many distinct callers")
+}
+
+func runCoverage(cmd *cobra.Command, args []string) error {
+	var in io.Reader = os.Stdin
+	if args[0] != "-" {
+		f, err := os.Open(args[0])
+		if err != nil {
+			return outputError("coverage", err)
+		}
+		defer f.Close()
+		in = f
+	}
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("coverage", fmt.Errorf("getting cwd: %w", err))
+	}
+	repoRoot := findRepoRoot(cwd)
+	dbPath, err := resolveDBPath(repoRoot)
+	if err != nil {
+		return outputError("coverage", err)
+	}
+	if _, err := os.Stat(dbPath); os.IsNotExist(err) {
+		return outputError("coverage", fmt.Errorf("database not found: %s (run 'canopy index' first)", dbPath))
+	}
+
+	var opts []canopy.Option
+	if flagScriptsDir == "" {
+		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+	}
+	engine, err := canopy.New(dbPath, flagScriptsDir, opts...)
+	if err != nil {
+		return outputError("coverage", fmt.Errorf("creating engine: %w", err))
+	}
+	defer engine.Close()
+
+	summary, err := engine.IngestCoverage(in, repoRoot)
+	if err != nil {
+		return outputError("coverage", err)
+	}
+	return outputResult(CLIResult{
+		Command: "coverage",
+		Results: CLICoverageSummary{
+			Files:        summary.Files,
+			MappedFiles:  summary.MappedFiles,
+			Lines:        summary.Lines,
+			CoveredLines: summary.CoveredLines,
+			Symbols:      summary.Symbols,
+		},
+	})
+}
+
+func runQueryCoverage(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("coverage", err)
+	}
+	defer s.Close()
+
+	filter := canopy.SymbolFilter{}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
+	if flagVisibility != "" {
+		filter.Visibility = &flagVisibility
+	}
+	if flagPathPrefix != "" {
+		filter.PathPrefix = &flagPathPrefix
+	}
+	cov := canopy.CoverageFilter{}
+	if cmd.Flags().Changed("max-percent") {
+		cov.MaxPercent = &flagMaxPercent
+	}
+	if cmd.Flags().Changed("min-callers") {
+		cov.MinCallers = &flagMinCallers
+	}
+
+	qb := queryBuilder(s)
+	result, err := qb.Coverage(filter, cov, buildPagination())
+	if err != nil {
+		return outputError("coverage", err)
+	}
+
+	cliCovered := make([]CLICoveredSymbol, len(result.Items))
+	for i, c := range result.Items {
+		cliCovered[i] = CLICoveredSymbol{
+			Symbol:       symbolResultToCLI(c.Symbol),
+			Lines:        c.Coverage.Lines,
+			CoveredLines: c.Coverage.CoveredLines,
+			Percent:      c.Percent,
+			Callers:      c.Callers,
+		}
+	}
+
+	return outputResult(CLIResult{
+		Command:    "coverage",
+		Results:    cliCovered,
+		TotalCount: &result.TotalCount,
+	})
+}
@@ -0,0 +1,56 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var crateDuplicatesCmd = &cobra.Command{
+	Use:   "crate-duplicates",
+	Short: "Find same-named Rust items with near-identical signatures in different crates",
+	Long: "Reports pairs of Rust functions, structs, enums, traits, and type aliases that share a name, live in different\n" +
+		"Cargo packages of the workspace, and have nearly identical signatures, most similar first. These are usually\n" +
+		"copies that drifted and are candidates for a shared crate. Functions compare parameter and return types in\n" +
+		"order; types compare their fields, variants, or trait items.",
+	Args: cobra.NoArgs,
+	RunE: runCrateDuplicates,
+}
+
+func init() {
+	crateDuplicatesCmd.Flags().Float64("min-similarity", 0.8, "minimum similarity between signatures (0-1)")
+}
+
+func runCrateDuplicates(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("crate-duplicates", err)
+	}
+	defer s.Close()
+
+	minSimilarity, _ := cmd.Flags().GetFloat64("min-similarity")
+
+	qb := queryBuilder(s)
+	dups, err := qb.CrateDuplicates(minSimilarity)
+	if err != nil {
+		return outputError("crate-duplicates", err)
+	}
+
+	cliDups := make([]CLICrateDuplicate, len(dups))
+	for i, d := range dups {
+		cliDups[i] = CLICrateDuplicate{
+			A:          symbolResultToCLI(d.A),
+			B:          symbolResultToCLI(d.B),
+			CrateA:     d.CrateA,
+			CrateB:     d.CrateB,
+			SignatureA: d.SignatureA,
+			SignatureB: d.SignatureB,
+			Similarity: d.Similarity,
+		}
+	}
+
+	page, total := paginateSlice(cliDups)
+	return outputResult(CLIResult{
+		Command:    "crate-duplicates",
+		Results:    page,
+		TotalCount: &total,
+	})
+}
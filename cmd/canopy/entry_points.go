@@ -0,0 +1,92 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var entryPointsCmd = &cobra.Command{
+	Use:   "entry-points",
+	Short: "List the binary, example, test, and bench targets of the Cargo packages",
+	Long: "Lists the entry points of each Cargo package: the [[bin]], [[example]], [[test]] and [[bench]] targets its\n" +
+		"Cargo.toml declares, and those Cargo discovers (src/main.rs, and <name>.rs or <name>/main.rs under src/bin,\n" +
+		"examples, tests and benches) unless autobins and the like are false. Each target is shown with its roots:\n" +
+		"main, and in a test or bench target its #[test] functions. A bench without either, such as a Criterion\n" +
+		"bench, is rooted at its functions. --target selects targets by name, kind, or kind:name (bin:server).",
+	Args: cobra.NoArgs,
+	RunE: runEntryPoints,
+}
+
+var unreachableCmd = &cobra.Command{
+	Use:   "unreachable",
+	Short: "List Rust functions no entry point's call graph reaches",
+	Long: "Walks the call graph from the roots of the entry points (see entry-points) and lists the Rust functions\n" +
+		"and methods it never reaches. With no --target every target is a root, and so are the packages' unit\n" +
+		"tests; --target bin:server asks what the server binary alone never runs. Tests and the methods of trait\n" +
+		"impls, which are called through their trait (Display::fmt by format!), are not listed.",
+	Args: cobra.NoArgs,
+	RunE: runUnreachable,
+}
+
+var flagTarget string
+
+func init() {
+	entryPointsCmd.Flags().StringVar(&flagTarget, "target", "", "only targets with this name, kind, or kind:name")
+	unreachableCmd.Flags().StringVar(&flagTarget, "target", "", "only walk from targets with this name, kind, or kind:name")
+	unreachableCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runEntryPoints(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("entry-points", err)
+	}
+	defer s.Close()
+
+	entries, err := queryBuilder(s).EntryPoints(flagTarget)
+	if err != nil {
+		return outputError("entry-points", err)
+	}
+	cliEntries := make([]CLIEntryPoint, len(entries))
+	for i, ep := range entries {
+		cliEntries[i] = entryPointToCLI(ep)
+	}
+	paged, totalCount := paginateSlice(cliEntries)
+	return outputResult(CLIResult{
+		Command:    "entry-points",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runUnreachable(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("unreachable", err)
+	}
+	defer s.Close()
+
+	reach, err := queryBuilder(s).Reachability(flagTarget, flagPathPrefix)
+	if err != nil {
+		return outputError("unreachable", err)
+	}
+	cliSyms := make([]CLISymbol, len(reach.Unreachable))
+	for i, sr := range reach.Unreachable {
+		cliSyms[i] = symbolResultToCLI(sr)
+	}
+	paged, totalCount := paginateSlice(cliSyms)
+	return outputResult(CLIResult{
+		Command:    "unreachable",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// entryPointToCLI converts an entry point.
+func entryPointToCLI(ep canopy.EntryPoint) CLIEntryPoint {
+	out := CLIEntryPoint{Package: ep.Package, Kind: ep.Kind, Name: ep.Name, Path: ep.Path, Roots: make([]CLISymbol, len(ep.Roots))}
+	for i, r := range ep.Roots {
+		out.Roots[i] = symbolResultToCLI(r)
+	}
+	return out
+}
@@ -0,0 +1,98 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+)
+
+var examplesCmd = &cobra.Command{
+	Use:   "examples [<symbol>]",
+	Short: "Show real uses of a symbol from the index, simplest first, for documentation",
+	Long: "Collects the statements that use a symbol across the index as usage examples for its documentation;\n" +
+		"--include-tests adds those in test code. Uses that differ only in literals and whitespace are shown once with\n" +
+		"a count of similar sites; calls come first, then shorter statements. Every indexed symbol named <symbol> is\n" +
+		"shown; --symbol <id> selects one. --max sets the number of examples per symbol. With --format json, the\n" +
+		"Markdown is in each result's text.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runExamples,
+}
+
+func init() {
+	examplesCmd.ValidArgsFunction = completeSymbolNames
+	examplesCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	examplesCmd.Flags().Int("max", 5, "maximum examples per symbol (0 for all)")
+	examplesCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	examplesCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+func runExamples(cmd *cobra.Command, args []string) error {
+	maxExamples, _ := cmd.Flags().GetInt("max")
+	if maxExamples < 0 {
+		return outputError("examples", fmt.Errorf("--max must be non-negative, got %d", maxExamples))
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("examples", err)
+	}
+	defer s.Close()
+
+	var ids []int64
+	if symID, _ := cmd.Flags().GetInt64("symbol"); symID != 0 {
+		ids = append(ids, symID)
+	} else {
+		if len(args) == 0 {
+			return outputError("examples", fmt.Errorf("requires either a <symbol> argument or --symbol flag"))
+		}
+		syms, err := s.SymbolsByName(args[0])
+		if err != nil {
+			return outputError("examples", err)
+		}
+		for _, sym := range syms {
+			if sym.FileID != nil {
+				ids = append(ids, sym.ID)
+			}
+		}
+		if len(ids) == 0 {
+			return outputError("examples", fmt.Errorf("no symbol named %q", args[0]))
+		}
+	}
+
+	qb := queryBuilder(s)
+	var results []CLIUsageExamples
+	for _, id := range ids {
+		ex, err := qb.UsageExamples(id)
+		if err != nil {
+			return outputError("examples", err)
+		}
+		if ex == nil {
+			return outputError("examples", fmt.Errorf("symbol not found: %d", id))
+		}
+		if maxExamples > 0 && len(ex.Examples) > maxExamples {
+			ex.Examples = ex.Examples[:maxExamples]
+		}
+		r := CLIUsageExamples{
+			Symbol:   symbolResultToCLI(ex.Symbol),
+			Examples: make([]CLIUsageExample, len(ex.Examples)),
+			Text:     ex.Markdown(),
+		}
+		for i, e := range ex.Examples {
+			r.Examples[i] = CLIUsageExample{
+				File:        e.File,
+				Language:    e.Language,
+				Line:        e.Line,
+				Code:        e.Code,
+				Call:        e.Call,
+				Occurrences: e.Occurrences,
+			}
+		}
+		results = append(results, r)
+	}
+	paged, totalCount := paginateSlice(results)
+	return outputResult(CLIResult{
+		Command:    "examples",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,349 @@
+package main
+
+import (
+	"bufio"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/internal/store"
+	"github.com/spf13/cobra"
+)
+
+// Export formats: a store snapshot, code chunks for embedding pipelines,
+// Parquet tables for data warehouses, CSV files for Neo4j, or an access
+// manifest for code-search portals.
+const (
+	snapshotFormat = "canopy-idx"
+	chunksFormat   = "chunks"
+	parquetFormat  = "parquet"
+	neo4jFormat    = "neo4j"
+	accessFormat   = "access"
+)
+
+var (
+	flagExportFormat string
+	flagExportOutput string
+	flagImportForce  bool
+	flagChunkLimits  canopy.ChunkLimits
+	flagAccessRules  string
+)
+
+var exportCmd = &cobra.Command{
+	Use:   "export",
+	Short: "Write the index to a compact snapshot file, or the code as chunks",
+	Long: "Writes the whole index to --output as a compressed, versioned binary snapshot, a fraction of the size of the\n" +
+		"database, for shipping an index to another machine or keeping it as a CI artifact. Load it with 'canopy import'.\n" +
+		"With --format chunks, writes the code instead as JSONL records sliced along symbol boundaries (functions and\n" +
+		"methods with their doc comments and signatures, types, impl blocks), each with a stable ID and the IDs of its\n" +
+		"enclosing chunk, the chunks inside it, and its callers and callees, as input to code-search embedding pipelines.\n" +
+		"--max-chunk-tokens splits larger functions at statement boundaries into parts numbered part/parts, each with its\n" +
+		"symbol's path, signature and ID; --chunk-overlap repeats that many lines between consecutive parts.\n" +
+		"With --format parquet, --output is a directory that receives files.parquet, symbols.parquet, and edges.parquet\n" +
+		"(calls, resolved references, and implementations) for loading into a data warehouse. With --format neo4j, it\n" +
+		"receives node and relationship CSV files for 'neo4j-admin database import'. With --format access, it writes a\n" +
+		"JSONL manifest with each symbol's visibility, Rust visibility scope, CODEOWNERS owners, and the teams that may\n" +
+		"read it under --access-rules, a file of path rules in CODEOWNERS syntax naming teams instead of owners.\n" +
+		"The --format flag here names the export format; the result is always printed as JSON.",
+	Args: cobra.NoArgs,
+	RunE: runExport,
+}
+
+var importCmd = &cobra.Command{
+	Use:   "import <file>",
+	Short: "Load an index from a snapshot file",
+	Long: "Loads a snapshot written by 'canopy export' into the database, which must not exist yet or be empty unless\n" +
+		"--force is given. Snapshots written by newer canopy versions are rejected.",
+	Args: cobra.ExactArgs(1),
+	RunE: runImport,
+}
+
+func init() {
+	exportCmd.Flags().StringVar(&flagExportFormat, "format", snapshotFormat, "export format: "+snapshotFormat+"|"+chunksFormat+"|"+parquetFormat+"|"+neo4jFormat+"|"+accessFormat)
+	exportCmd.Flags().StringVarP(&flagExportOutput, "output", "o", "", "file to write")
+	exportCmd.Flags().IntVar(&flagChunkLimits.MaxTokens, "max-chunk-tokens", 0, "with --format chunks, split functions estimated above this many tokens (0 for no limit)")
+	exportCmd.Flags().IntVar(&flagChunkLimits.Overlap, "chunk-overlap", 0, "with --max-chunk-tokens, lines each part repeats from the part before it")
+	exportCmd.Flags().StringVar(&flagAccessRules, "access-rules", "", "with --format access, path rules in CODEOWNERS syntax mapping paths to the teams that may read them")
+	_ = exportCmd.MarkFlagRequired("output")
+	importCmd.Flags().BoolVar(&flagImportForce, "force", false, "replace an existing database")
+}
+
+func runExport(cmd *cobra.Command, args []string) error {
+	switch flagExportFormat {
+	case snapshotFormat, chunksFormat, parquetFormat, neo4jFormat, accessFormat:
+	default:
+		return outputError("export", fmt.Errorf("unsupported export format %q (supported: %s, %s, %s, %s, %s)",
+			flagExportFormat, snapshotFormat, chunksFormat, parquetFormat, neo4jFormat, accessFormat))
+	}
+	s, err := openStore()
+	if err != nil {
+		return outputError("export", err)
+	}
+	defer s.Close()
+
+	out, err := filepath.Abs(flagExportOutput)
+	if err != nil {
+		return outputError("export", err)
+	}
+	switch flagExportFormat {
+	case chunksFormat:
+		return exportChunks(s, out)
+	case parquetFormat:
+		names := store.AnalyticsTables()
+		for i, name := range names {
+			names[i] = name + ".parquet"
+		}
+		return exportFiles(out, parquetFormat, names, func(name string, w io.Writer) (int64, error) {
+			return s.WriteParquet(strings.TrimSuffix(name, ".parquet"), w)
+		})
+	case neo4jFormat:
+		return exportFiles(out, neo4jFormat, store.Neo4jFiles(), s.WriteNeo4j)
+	case accessFormat:
+		return exportAccess(s, out)
+	}
+	f, err := os.Create(out)
+	if err != nil {
+		return outputError("export", err)
+	}
+	stats, err := s.WriteSnapshot(f)
+	if cerr := f.Close(); err == nil {
+		err = cerr
+	}
+	if err != nil {
+		os.Remove(out)
+		return outputError("export", err)
+	}
+	return outputResult(CLIResult{Command: "export", Results: snapshotResult(out, stats)})
+}
+
+func runImport(cmd *cobra.Command, args []string) error {
+	in, err := filepath.Abs(args[0])
+	if err != nil {
+		return outputError("import", err)
+	}
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("import", fmt.Errorf("getting cwd: %w", err))
+	}
+	dbPath, err := resolveDBPath(findRepoRoot(cwd))
+	if err != nil {
+		return outputError("import", err)
+	}
+	if flagImportForce {
+		if err := os.Remove(dbPath); err != nil && !errors.Is(err, os.ErrNotExist) {
+			return outputError("import", err)
+		}
+	}
+	if err := os.MkdirAll(filepath.Dir(dbPath), 0o755); err != nil {
+		return outputError("import", err)
+	}
+
+	f, err := os.Open(in)
+	if err != nil {
+		return outputError("import", err)
+	}
+	defer f.Close()
+	s, err := store.NewStore(dbPath)
+	if err != nil {
+		return outputError("import", err)
+	}
+	defer s.Close()
+	if err := s.Migrate(); err != nil {
+		return outputError("import", err)
+	}
+	stats, err := s.LoadSnapshot(f)
+	if err != nil {
+		return outputError("import", err)
+	}
+	return outputResult(CLIResult{Command: "import", Results: snapshotResult(in, stats)})
+}
+
+// snapshotResult describes the snapshot file at path.
+func snapshotResult(path string, stats *store.SnapshotStats) CLISnapshot {
+	r := CLISnapshot{Path: path, Format: snapshotFormat, Version: store.SnapshotVersion, Tables: stats.Tables, Rows: stats.Rows}
+	if info, err := os.Stat(path); err == nil {
+		r.Bytes = info.Size()
+	}
+	return r
+}
+
+// exportChunks writes the code chunks of the index to path as JSONL, one
+// chunk per line.
+func exportChunks(s *store.Store, path string) error {
+	chunks, err := queryBuilder(s).CodeChunks()
+	if err != nil {
+		return outputError("export", err)
+	}
+	chunks = canopy.SplitChunks(chunks, flagChunkLimits)
+	f, err := os.Create(path)
+	if err != nil {
+		return outputError("export", err)
+	}
+	w := bufio.NewWriter(f)
+	enc := json.NewEncoder(w)
+	files := map[string]bool{}
+	for _, c := range chunks {
+		files[c.File] = true
+		if err = enc.Encode(codeChunkToCLI(c)); err != nil {
+			break
+		}
+	}
+	if err == nil {
+		err = w.Flush()
+	}
+	if cerr := f.Close(); err == nil {
+		err = cerr
+	}
+	if err != nil {
+		os.Remove(path)
+		return outputError("export", err)
+	}
+
+	r := CLIChunkExport{Path: path, Format: chunksFormat, Chunks: len(chunks), Files: len(files)}
+	if info, err := os.Stat(path); err == nil {
+		r.Bytes = info.Size()
+	}
+	return outputResult(CLIResult{Command: "export", Results: r})
+}
+
+// exportAccess writes the access manifest of the index to path as JSONL, one
+// symbol per line, under the rules in --access-rules.
+func exportAccess(s *store.Store, path string) error {
+	var rules *canopy.CodeOwners
+	if flagAccessRules != "" {
+		f, err := os.Open(flagAccessRules)
+		if err != nil {
+			return outputError("export", err)
+		}
+		rules, err = canopy.ParseCodeOwners(f)
+		f.Close()
+		if err != nil {
+			return outputError("export", err)
+		}
+	}
+	manifest, err := queryBuilder(s).AccessManifest(rules)
+	if err != nil {
+		return outputError("export", err)
+	}
+	f, err := os.Create(path)
+	if err != nil {
+		return outputError("export", err)
+	}
+	w := bufio.NewWriter(f)
+	enc := json.NewEncoder(w)
+	r := CLIAccessExport{Path: path, Format: accessFormat, Symbols: len(manifest)}
+	for _, a := range manifest {
+		if a.Restricted {
+			r.Restricted++
+		}
+		if err = enc.Encode(symbolAccessToCLI(a)); err != nil {
+			break
+		}
+	}
+	if err == nil {
+		err = w.Flush()
+	}
+	if cerr := f.Close(); err == nil {
+		err = cerr
+	}
+	if err != nil {
+		os.Remove(path)
+		return outputError("export", err)
+	}
+	if info, err := os.Stat(path); err == nil {
+		r.Bytes = info.Size()
+	}
+	return outputResult(CLIResult{Command: "export", Results: r})
+}
+
+// symbolAccessToCLI converts an access manifest entry.
+func symbolAccessToCLI(a canopy.SymbolAccess) CLISymbolAccess {
+	return CLISymbolAccess{
+		SymbolID:   a.SymbolID,
+		Name:       a.Name,
+		Kind:       a.Kind,
+		File:       a.File,
+		Line:       a.Line,
+		Language:   a.Language,
+		Visibility: a.Visibility,
+		Scope:      a.Scope,
+		Exported:   a.Exported,
+		Owners:     a.Owners,
+		Teams:      a.Teams,
+		Restricted: a.Restricted,
+		Readers:    a.Readers,
+	}
+}
+
+// exportFiles writes each of the named files to dir with write. With no
+// files to write, the exporter was left out of the build.
+func exportFiles(dir, format string, names []string, write func(name string, w io.Writer) (int64, error)) error {
+	if len(names) == 0 {
+		return outputError("export", fmt.Errorf("the %s exporter is not built in (built with -tags canopy_noexport)", format))
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return outputError("export", err)
+	}
+	r := CLIFileExport{Path: dir, Format: format}
+	for _, name := range names {
+		path := filepath.Join(dir, name)
+		f, err := os.Create(path)
+		if err != nil {
+			return outputError("export", err)
+		}
+		w := bufio.NewWriter(f)
+		rows, err := write(name, w)
+		if err == nil {
+			err = w.Flush()
+		}
+		if cerr := f.Close(); err == nil {
+			err = cerr
+		}
+		if err != nil {
+			os.Remove(path)
+			return outputError("export", err)
+		}
+		ef := CLIExportFile{Path: path, Rows: rows}
+		if info, err := os.Stat(path); err == nil {
+			ef.Bytes = info.Size()
+		}
+		r.Files = append(r.Files, ef)
+	}
+	return outputResult(CLIResult{Command: "export", Results: r})
+}
+
+// codeChunkToCLI converts a code chunk, with empty neighbor lists as [].
+func codeChunkToCLI(c canopy.CodeChunk) CLICodeChunk {
+	orEmpty := func(ids []string) []string {
+		if ids == nil {
+			return []string{}
+		}
+		return ids
+	}
+	return CLICodeChunk{
+		ID:        c.ID,
+		File:      c.File,
+		Language:  c.Language,
+		Kind:      c.Kind,
+		Name:      c.Name,
+		Path:      c.Path,
+		Signature: c.Signature,
+		Doc:       c.Doc,
+		StartLine: c.StartLine,
+		EndLine:   c.EndLine,
+		Text:      c.Text,
+		Tokens:    c.Tokens,
+		SymbolID:  c.SymbolID,
+		Part:      c.Part,
+		Parts:     c.Parts,
+		Parent:    c.Parent,
+		Children:  orEmpty(c.Children),
+		Calls:     orEmpty(c.Calls),
+		CalledBy:  orEmpty(c.CalledBy),
+	}
+}
@@ -0,0 +1,31 @@
+package main
+
+import (
+	"context"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+// extractWorkerCmd is the worker process 'canopy index --processes' starts.
+// It reads extraction requests on stdin and writes results on stdout.
+var extractWorkerCmd = &cobra.Command{
+	Use:    "extract-worker",
+	Short:  "Extract files for a parent 'canopy index --processes' (internal)",
+	Hidden: true,
+	Args:   cobra.NoArgs,
+	RunE: func(cmd *cobra.Command, args []string) error {
+		var opts []canopy.Option
+		if flagScriptsDir == "" {
+			opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+		}
+		return canopy.RunExtractWorker(context.Background(), flagDB, flagScriptsDir, os.Stdin, os.Stdout, opts...)
+	},
+}
+
+func init() {
+	extractWorkerCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+	rootCmd.AddCommand(extractWorkerCmd)
+}
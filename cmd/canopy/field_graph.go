@@ -0,0 +1,80 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var fieldGraphCmd = &cobra.Command{
+	Use:   "field-graph [<file> <line> <col>]",
+	Short: "Show the types a type transitively contains through its fields",
+	Long: "Follows the declared types of a type's fields (Product.price : f64, Order.items : Vec<Item>) up to\n" +
+		"--max-depth, listing each field edge and every type reached. With --reverse, lists the types that\n" +
+		"transitively contain the type instead, for serialization and migration impact.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runFieldGraph,
+}
+
+func init() {
+	fieldGraphCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	fieldGraphCmd.Flags().Int("max-depth", 5, "maximum traversal depth (0-100)")
+	fieldGraphCmd.Flags().Bool("reverse", false, "list the types containing the type instead")
+}
+
+func runFieldGraph(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("field-graph", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("field-graph", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	var graph *canopy.FieldGraph
+	if reverse, _ := cmd.Flags().GetBool("reverse"); reverse {
+		graph, err = qb.FieldDependents(symID, maxDepth)
+	} else {
+		graph, err = qb.FieldDependencies(symID, maxDepth)
+	}
+	if err != nil {
+		return outputError("field-graph", err)
+	}
+	if graph == nil {
+		return outputResult(CLIResult{
+			Command: "field-graph",
+			Results: nil,
+		})
+	}
+
+	cliGraph := CLIFieldGraph{
+		Root:  symbolResultToCLI(graph.Root),
+		Edges: make([]CLIFieldEdge, len(graph.Edges)),
+		Types: make([]CLIFieldGraphType, len(graph.Types)),
+		Depth: graph.Depth,
+	}
+	for i, e := range graph.Edges {
+		cliGraph.Edges[i] = CLIFieldEdge{
+			From:     symbolResultToCLI(e.From),
+			Field:    e.Field,
+			TypeExpr: e.TypeExpr,
+			Type:     e.Type,
+			To:       optionalSymbolToCLI(e.To),
+			Depth:    e.Depth,
+		}
+	}
+	for i, t := range graph.Types {
+		cliGraph.Types[i] = CLIFieldGraphType{Name: t.Name, Symbol: optionalSymbolToCLI(t.Symbol), Depth: t.Depth}
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "field-graph",
+		Results:    cliGraph,
+		TotalCount: &one,
+	})
+}
@@ -0,0 +1,62 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var flagSignature string
+
+var findCmd = &cobra.Command{
+	Use:   "find",
+	Short: "Find functions by signature shape",
+	Long: "Finds functions and methods whose parameter and return types fit --signature, e.g. \"(&str) -> String\".\n" +
+		"Receivers are ignored. Use _ for any type (also nested, as in Vec<_>), a trailing .. for any further\n" +
+		"parameters, and a tuple for multiple results: \"(context.Context, ..) -> (_, error)\". Omit the\n" +
+		"parameter list or the \"-> R\" part to leave it unconstrained.",
+	Args: cobra.NoArgs,
+	RunE: runFind,
+}
+
+func init() {
+	findCmd.Flags().StringVar(&flagSignature, "signature", "", "signature shape to match, e.g. \"(&str) -> String\"")
+	findCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	findCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	findCmd.Flags().StringSliceVar(&flagBounds, "bound", nil, "filter by generic bound trait/interface name (repeatable, e.g. Processor)")
+	findCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	findCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+	findCmd.Flags().StringVar(&flagSort, "sort", "", "sort field: name|kind|file|ref_count|pagerank|betweenness")
+	findCmd.Flags().StringVar(&flagOrder, "order", "asc", "sort order: asc|desc")
+	_ = findCmd.MarkFlagRequired("signature")
+}
+
+func runFind(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("find", err)
+	}
+	defer s.Close()
+
+	filter := canopy.SymbolFilter{Bounds: flagBounds}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
+	if flagPathPrefix != "" {
+		filter.PathPrefix = &flagPathPrefix
+	}
+
+	result, err := queryBuilder(s).SymbolsBySignature(flagSignature, filter, buildSort(), buildPagination())
+	if err != nil {
+		return outputError("find", err)
+	}
+
+	cliSyms := make([]CLISymbol, len(result.Items))
+	for i, sr := range result.Items {
+		cliSyms[i] = symbolResultToCLI(sr)
+	}
+	return outputResult(CLIResult{
+		Command:    "find",
+		Results:    cliSyms,
+		TotalCount: &result.TotalCount,
+	})
+}
@@ -1,18 +1,42 @@
 package main
 
 import (
+	"encoding/csv"
+	"encoding/json"
 	"fmt"
+	"html"
 	"io"
 	"os"
+	"reflect"
 	"sort"
+	"strconv"
 	"strings"
 	"text/tabwriter"
 )
 
-// formatLocationsText formats CLILocation results as "file:line:col" lines.
+// formatLocationsText formats CLILocation results as "file:line:col" lines,
+// followed by the reference kind and confidence level when known, and by
+// the steps of the location's explanation, indented, with --explain.
 func formatLocationsText(w io.Writer, locs []CLILocation) {
 	for _, loc := range locs {
-		fmt.Fprintf(w, "%s:%d:%d\n", loc.File, loc.StartLine, loc.StartCol)
+		switch {
+		case loc.Kind != "" && loc.Confidence != "":
+			fmt.Fprintf(w, "%s:%d:%d\t%s\t%s\n", loc.File, loc.StartLine, loc.StartCol, loc.Kind, loc.Confidence)
+		case loc.Kind != "":
+			fmt.Fprintf(w, "%s:%d:%d\t%s\n", loc.File, loc.StartLine, loc.StartCol, loc.Kind)
+		default:
+			fmt.Fprintf(w, "%s:%d:%d\n", loc.File, loc.StartLine, loc.StartCol)
+		}
+		if loc.Explanation != nil {
+			formatExplanationText(w, *loc.Explanation)
+		}
+	}
+}
+
+// formatExplanationText writes an explanation's steps as indented lines.
+func formatExplanationText(w io.Writer, ex CLIExplanation) {
+	for _, step := range ex.Steps {
+		fmt.Fprintf(w, "    %s\n", step)
 	}
 }
 
@@ -31,29 +55,49 @@ func formatSymbolsText(w io.Writer, syms []CLISymbol) {
 		fmt.Fprintln(tw, "ID\tNAME\tKIND\tVISIBILITY\tREFS (EXT/TOTAL)\tFILE\tLINE")
 		for _, s := range syms {
 			fmt.Fprintf(tw, "%d\t%s\t%s\t%s\t%d/%d\t%s\t%d\n",
-				s.ID, s.Name, s.Kind, s.Visibility, s.ExternalRefCount, s.RefCount, s.File, s.StartLine)
+				s.ID, s.Name, symbolKindText(s), s.Visibility, s.ExternalRefCount, s.RefCount, s.File, s.StartLine)
 		}
 	} else {
 		fmt.Fprintln(tw, "ID\tNAME\tKIND\tVISIBILITY\tFILE\tLINE")
 		for _, s := range syms {
 			fmt.Fprintf(tw, "%d\t%s\t%s\t%s\t%s\t%d\n",
-				s.ID, s.Name, s.Kind, s.Visibility, s.File, s.StartLine)
+				s.ID, s.Name, symbolKindText(s), s.Visibility, s.File, s.StartLine)
 		}
 	}
 	tw.Flush()
 }
 
+// symbolKindText shows a symbol's subkind, which names its kind too, when it
+// has one.
+func symbolKindText(s CLISymbol) string {
+	if s.Subkind != "" {
+		return s.Subkind
+	}
+	return s.Kind
+}
+
 // formatCallEdgesText formats CLICallEdge results as aligned columns.
 func formatCallEdgesText(w io.Writer, edges []CLICallEdge) {
 	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
-	fmt.Fprintln(tw, "CALLER\tCALLEE\tFILE\tLINE\tCOL")
+	fmt.Fprintln(tw, "CALLER\tCALLEE\tFILE\tLINE\tCOL\tCONFIDENCE\tKIND")
 	for _, e := range edges {
 		caller := fmt.Sprintf("%s (#%d)", e.CallerName, e.CallerID)
 		callee := fmt.Sprintf("%s (#%d)", e.CalleeName, e.CalleeID)
-		fmt.Fprintf(tw, "%s\t%s\t%s\t%d\t%d\n",
-			caller, callee, e.File, e.Line, e.Col)
+		fmt.Fprintf(tw, "%s\t%s\t%s\t%d\t%d\t%s\t%s\n",
+			caller, callee, e.File, e.Line, e.Col, e.Confidence, callKindText(e.Kind))
 	}
 	tw.Flush()
+
+	// Explanations follow the table, so they don't break its alignment.
+	for _, e := range edges {
+		if len(e.Explanation) == 0 {
+			continue
+		}
+		fmt.Fprintf(w, "\n%s (#%d) -> %s (#%d) at %s:%d:%d\n", e.CallerName, e.CallerID, e.CalleeName, e.CalleeID, e.File, e.Line, e.Col)
+		for _, ex := range e.Explanation {
+			formatExplanationText(w, ex)
+		}
+	}
 }
 
 // formatImportsText formats CLIImport results as aligned columns.
@@ -76,6 +120,20 @@ func formatFilesText(w io.Writer, files []CLIFile) {
 	tw.Flush()
 }
 
+// formatRootsText formats []CLIRoot as aligned columns.
+func formatRootsText(w io.Writer, roots []CLIRoot) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "ROOT\tFILES\tLINES")
+	for _, r := range roots {
+		root := r.Root
+		if root == "" {
+			root = "(none)"
+		}
+		fmt.Fprintf(tw, "%s\t%d\t%d\n", root, r.FileCount, r.LineCount)
+	}
+	tw.Flush()
+}
+
 // formatSummaryText formats CLIProjectSummary as readable text.
 func formatSummaryText(w io.Writer, summary CLIProjectSummary) {
 	fmt.Fprintln(w, "Project Summary")
@@ -190,10 +248,21 @@ func formatSymbolDetailText(w io.Writer, detail CLISymbolDetail) {
 		fmt.Fprintln(w)
 		fmt.Fprintln(w, "Type Parameters:")
 		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
-		fmt.Fprintln(tw, "  ORD\tNAME\tCONSTRAINTS")
+		fmt.Fprintln(tw, "  ORD\tNAME\tKIND\tCONSTRAINTS")
 		for _, tp := range detail.TypeParams {
-			fmt.Fprintf(tw, "  %d\t%s\t%s\n",
-				tp.Ordinal, tp.Name, tp.Constraints)
+			fmt.Fprintf(tw, "  %d\t%s\t%s\t%s\n",
+				tp.Ordinal, tp.Name, tp.Kind, tp.Constraints)
+		}
+		tw.Flush()
+	}
+
+	if len(detail.TypeBounds) > 0 {
+		fmt.Fprintln(w)
+		fmt.Fprintln(w, "Type Bounds:")
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		fmt.Fprintln(tw, "  SUBJECT\tBOUND\tWHERE")
+		for _, tb := range detail.TypeBounds {
+			fmt.Fprintf(tw, "  %s\t%s\t%t\n", tb.Subject, tb.Bound, tb.InWhereClause)
 		}
 		tw.Flush()
 	}
@@ -321,15 +390,63 @@ func formatCallGraphText(w io.Writer, g CLICallGraph) {
 		fmt.Fprintln(w)
 		fmt.Fprintln(w, "Edges:")
 		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
-		fmt.Fprintln(tw, "  CALLER\tCALLEE\tFILE\tLINE")
+		fmt.Fprintln(tw, "  CALLER\tCALLEE\tFILE\tLINE\tKIND")
 		for _, e := range g.Edges {
-			fmt.Fprintf(tw, "  %d\t%d\t%s\t%d\n",
-				e.CallerID, e.CalleeID, e.File, e.Line)
+			fmt.Fprintf(tw, "  %d\t%d\t%s\t%d\t%s\n",
+				e.CallerID, e.CalleeID, e.File, e.Line, callKindText(e.Kind))
 		}
 		tw.Flush()
 	}
 }
 
+// callKindText names a call edge kind for text output: "call" for a direct
+// call, otherwise the kind itself.
+func callKindText(kind string) string {
+	if kind == "" {
+		return "call"
+	}
+	return kind
+}
+
+// formatCallGraphNodesText formats []CLICallGraphNode as aligned columns.
+func formatCallGraphNodesText(w io.Writer, nodes []CLICallGraphNode) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "ID\tNAME\tKIND\tDEPTH\tFILE\tLINE")
+	for _, n := range nodes {
+		fmt.Fprintf(tw, "%d\t%s\t%s\t%d\t%s\t%d\n",
+			n.Symbol.ID, n.Symbol.Name, n.Symbol.Kind, n.Depth, n.Symbol.File, n.Symbol.StartLine)
+	}
+	tw.Flush()
+}
+
+// formatBlockingCallsText formats CLIBlockingCall results as aligned columns,
+// with the blocking call's location and the number of calls leading to it.
+func formatBlockingCallsText(w io.Writer, calls []CLIBlockingCall) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "ASYNC FN\tAPI\tDEPTH\tCALL SITE")
+	for _, c := range calls {
+		site := ""
+		if n := len(c.Path); n > 0 {
+			site = fmt.Sprintf("%s:%d:%d", c.Path[n-1].File, c.Path[n-1].Line, c.Path[n-1].Col)
+		}
+		fmt.Fprintf(tw, "%s (#%d)\t%s\t%d\t%s\n", c.AsyncFn.Name, c.AsyncFn.ID, c.API, len(c.Path), site)
+	}
+	tw.Flush()
+}
+
+// formatPanicSitesText formats CLIPanicSite results as aligned columns, with
+// the function containing each site and the number of calls leading to it.
+func formatPanicSitesText(w io.Writer, sites []CLIPanicSite) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "KIND\tSITE\tFUNCTION\tDEPTH\tLOCATION")
+	for _, s := range sites {
+		fmt.Fprintf(tw, "%s\t%s\t%s (#%d)\t%d\t%s:%d:%d\n",
+			s.Kind, s.Name, s.Function.Name, s.Function.ID, len(s.Path),
+			s.Location.File, s.Location.StartLine, s.Location.StartCol)
+	}
+	tw.Flush()
+}
+
 // formatDependencyGraphText formats a CLIDependencyGraph as readable text.
 func formatDependencyGraphText(w io.Writer, g CLIDependencyGraph) {
 	if len(g.Packages) > 0 {
@@ -378,111 +495,1720 @@ func formatHotspotsText(w io.Writer, hotspots []CLIHotspot) {
 	tw.Flush()
 }
 
-// outputResultText dispatches to the appropriate text formatter based on the
-// result type. It writes to os.Stdout.
-func outputResultText(result CLIResult) error {
-	w := io.Writer(os.Stdout)
+// formatClonePairsText formats []CLIClonePair as aligned columns.
+func formatClonePairsText(w io.Writer, pairs []CLIClonePair) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "SIMILARITY\tSTATEMENTS\tA\tB")
+	for _, p := range pairs {
+		fmt.Fprintf(tw, "%.2f\t%d/%d\t%s %s:%d\t%s %s:%d\n",
+			p.Similarity, p.Common, max(p.Statements[0], p.Statements[1]),
+			p.A.Name, p.A.File, p.A.StartLine, p.B.Name, p.B.File, p.B.StartLine)
+	}
+	tw.Flush()
+}
 
-	switch v := result.Results.(type) {
-	case []CLILocation:
-		formatLocationsText(w, v)
-	case []CLISymbol:
-		formatSymbolsText(w, v)
-	case CLISymbol:
-		formatSymbolsText(w, []CLISymbol{v})
-	case []CLICallEdge:
-		formatCallEdgesText(w, v)
-	case []CLIImport:
-		formatImportsText(w, v)
-	case []CLIFile:
-		formatFilesText(w, v)
-	case CLIProjectSummary:
-		formatSummaryText(w, v)
-	case CLIPackageSummary:
-		formatPackageSummaryText(w, v)
-	case CLISymbolDetail:
-		formatSymbolDetailText(w, v)
-	case []CLIScope:
-		formatScopesText(w, v)
-	case CLITypeHierarchy:
-		formatTypeHierarchyText(w, v)
-	case []CLIExtensionBinding:
-		formatExtensionBindingsText(w, v)
-	case []CLIReexport:
-		formatReexportsText(w, v)
-	case CLICallGraph:
-		formatCallGraphText(w, v)
-	case CLIDependencyGraph:
-		formatDependencyGraphText(w, v)
-	case []CLICycle:
-		formatCyclesText(w, v)
-	case []CLIHotspot:
-		formatHotspotsText(w, v)
-	case nil:
-		// No output for nil results (e.g., symbol-at with no match).
-	default:
-		return fmt.Errorf("unsupported result type for text format: %T", v)
+// formatCrateDuplicatesText formats []CLICrateDuplicate as one line per
+// pair followed by the two signatures.
+func formatCrateDuplicatesText(w io.Writer, dups []CLICrateDuplicate) {
+	for _, d := range dups {
+		fmt.Fprintf(w, "%.2f  %s  %s %s:%d  %s %s:%d\n", d.Similarity, d.A.Name,
+			d.CrateA, d.A.File, d.A.StartLine, d.CrateB, d.B.File, d.B.StartLine)
+		fmt.Fprintf(w, "  %s\n  %s\n", d.SignatureA, d.SignatureB)
 	}
+}
 
-	// Pagination footer.
-	if result.TotalCount != nil {
-		count := *result.TotalCount
-		shown := resultLen(result.Results)
-		if shown < count {
-			fmt.Fprintf(w, "\nShowing %d of %d results\n", shown, count)
+// formatDeprecatedUsagesText formats []CLIDeprecatedUsage as aligned columns.
+func formatDeprecatedUsagesText(w io.Writer, usages []CLIDeprecatedUsage) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tSYMBOL\tKIND\tNOTE")
+	for _, u := range usages {
+		fmt.Fprintf(tw, "%s:%d:%d\t%s (#%d)\t%s\t%s\n",
+			u.Location.File, u.Location.StartLine, u.Location.StartCol,
+			u.Symbol.Name, u.Symbol.ID, u.Symbol.Kind, u.Note)
+	}
+	tw.Flush()
+}
+
+// formatFFIBoundariesText formats CLIFFIBoundary results as aligned columns.
+func formatFFIBoundariesText(w io.Writer, boundaries []CLIFFIBoundary) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "DIRECTION\tABI\tLINK NAME\tSYMBOL\tKIND\tFILE\tLINE")
+	for _, b := range boundaries {
+		fmt.Fprintf(tw, "%s\t%s\t%s\t%s (#%d)\t%s\t%s\t%d\n",
+			b.Direction, b.ABI, b.LinkName, b.Symbol.Name, b.Symbol.ID, b.Symbol.Kind, b.Symbol.File, b.Symbol.StartLine)
+	}
+	tw.Flush()
+}
+
+// formatErrorFlowsText formats CLIErrorFlow results as aligned columns.
+func formatErrorFlowsText(w io.Writer, flows []CLIErrorFlow) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "FUNCTION\tVISIBILITY\tDECLARED\tERROR TYPES\tFILE\tLINE")
+	for _, f := range flows {
+		fmt.Fprintf(tw, "%s (#%d)\t%s\t%s\t%s\t%s\t%d\n",
+			f.Function.Name, f.Function.ID, f.Function.Visibility, f.Declared,
+			strings.Join(f.ErrorTypes, ", "), f.Function.File, f.Function.StartLine)
+	}
+	tw.Flush()
+}
+
+// formatFeatureAPIsText formats CLIFeatureAPI results as aligned columns, one
+// row per gated item.
+func formatFeatureAPIsText(w io.Writer, apis []CLIFeatureAPI) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "FEATURE\tSYMBOL\tKIND\tCONDITION\tFILE\tLINE")
+	for _, a := range apis {
+		for _, it := range a.Items {
+			fmt.Fprintf(tw, "%s\t%s (#%d)\t%s\t%s\t%s\t%d\n",
+				a.Feature, it.Symbol.Name, it.Symbol.ID, it.Symbol.Kind, it.Condition, it.Symbol.File, it.Symbol.StartLine)
 		}
 	}
+	tw.Flush()
+}
 
-	return nil
+// formatPackageMetricsText formats []CLIPackageMetrics as aligned columns.
+func formatPackageMetricsText(w io.Writer, metrics []CLIPackageMetrics) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "PACKAGE\tCA\tCE\tINSTABILITY\tFAN-IN\tFAN-OUT\tVIOLATIONS")
+	for _, m := range metrics {
+		fmt.Fprintf(tw, "%s\t%d\t%d\t%.2f\t%d\t%d\t%s\n",
+			m.Package, m.Afferent, m.Efferent, m.Instability,
+			m.FanIn, m.FanOut, strings.Join(m.Violations, "; "))
+	}
+	tw.Flush()
 }
 
-// resultLen returns the length of a result slice, or 1 for a single value.
-func resultLen(v any) int {
-	switch r := v.(type) {
-	case []CLILocation:
-		return len(r)
-	case []CLISymbol:
-		return len(r)
-	case []CLICallEdge:
-		return len(r)
-	case []CLIImport:
-		return len(r)
-	case []CLIFile:
-		return len(r)
-	case CLISymbolDetail:
-		return 1
-	case []CLIScope:
-		return len(r)
-	case CLITypeHierarchy:
-		return 1
-	case []CLIExtensionBinding:
-		return len(r)
-	case []CLIReexport:
-		return len(r)
-	case CLICallGraph:
-		return 1
-	case CLIDependencyGraph:
-		return 1
-	case []CLICycle:
-		return len(r)
-	case []CLIHotspot:
-		return len(r)
-	case nil:
-		return 0
-	default:
-		return 1
+// formatComplexityText formats []CLIComplexity as aligned columns.
+func formatComplexityText(w io.Writer, metrics []CLIComplexity) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "ID\tNAME\tKIND\tCYCLOMATIC\tCOGNITIVE\tLOCATION")
+	for _, m := range metrics {
+		fmt.Fprintf(tw, "%d\t%s\t%s\t%d\t%d\t%s:%d:%d\n",
+			m.Symbol.ID, m.Symbol.Name, m.Symbol.Kind,
+			m.Cyclomatic, m.Cognitive,
+			m.Symbol.File, m.Symbol.StartLine, m.Symbol.StartCol)
+	}
+	tw.Flush()
+}
+
+// formatOwnersText formats CLIOwners as the file followed by its owners.
+func formatOwnersText(w io.Writer, o CLIOwners) {
+	owners := "(unowned)"
+	if len(o.Owners) > 0 {
+		owners = strings.Join(o.Owners, " ")
 	}
+	fmt.Fprintf(w, "%s\t%s\n", o.File, owners)
 }
 
-// validFormats lists accepted values for --format.
-var validFormats = []string{"json", "text"}
+// formatOwnerImpactText formats []CLIOwnerImpact as aligned columns.
+func formatOwnerImpactText(w io.Writer, impacts []CLIOwnerImpact) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "OWNER\tREFERENCES\tCALLERS\tFILES")
+	for _, imp := range impacts {
+		owner := imp.Owner
+		if owner == "" {
+			owner = "(unowned)"
+		}
+		fmt.Fprintf(tw, "%s\t%d\t%d\t%d\n", owner, imp.References, imp.Callers, imp.Files)
+	}
+	tw.Flush()
+}
 
-// validateFormat checks that the --format flag value is recognized.
-func validateFormat(format string) error {
-	for _, f := range validFormats {
-		if format == f {
-			return nil
+// formatBuildTargetsText formats CLIBuildTargets as the file followed by its
+// build targets.
+func formatBuildTargetsText(w io.Writer, t CLIBuildTargets) {
+	targets := "(none)"
+	if len(t.Targets) > 0 {
+		targets = strings.Join(t.Targets, " ")
+	}
+	fmt.Fprintf(w, "%s\t%s\n", t.File, targets)
+}
+
+// formatTargetImpactText formats []CLITargetImpact as aligned columns.
+func formatTargetImpactText(w io.Writer, impacts []CLITargetImpact) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "TARGET\tKIND\tREASON\tDEPTH\tVIA")
+	for _, imp := range impacts {
+		via := imp.Via
+		if via == "" {
+			via = "-"
+		}
+		fmt.Fprintf(tw, "%s\t%s\t%s\t%d\t%s\n", imp.Label, imp.Kind, imp.Reason, imp.Depth, via)
+	}
+	tw.Flush()
+}
+
+// formatSnapshotsText formats []CLISnapshotInfo as aligned columns.
+func formatSnapshotsText(w io.Writer, snapshots []CLISnapshotInfo) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "NAME\tCREATED\tBYTES\tPATH")
+	for _, s := range snapshots {
+		fmt.Fprintf(tw, "%s\t%s\t%d\t%s\n", s.Name, s.Created, s.Bytes, s.Path)
+	}
+	tw.Flush()
+}
+
+// formatGraphDiffText formats CLIGraphDiff as the summaries of both sides
+// followed by the added (+) and removed (-) dependencies, cycles, and
+// public symbols.
+func formatGraphDiffText(w io.Writer, d CLIGraphDiff) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintf(tw, "\t%s\t%s\n", d.Base, d.Head)
+	b, h := d.BaseSummary, d.HeadSummary
+	fmt.Fprintf(tw, "files\t%d\t%d\n", b.Files, h.Files)
+	fmt.Fprintf(tw, "symbols\t%d\t%d\n", b.Symbols, h.Symbols)
+	fmt.Fprintf(tw, "packages\t%d\t%d\n", b.Packages, h.Packages)
+	fmt.Fprintf(tw, "dependencies\t%d\t%d\n", b.Dependencies, h.Dependencies)
+	fmt.Fprintf(tw, "cycles\t%d\t%d\n", b.Cycles, h.Cycles)
+	fmt.Fprintf(tw, "public api\t%d\t%d\n", b.PublicAPI, h.PublicAPI)
+	tw.Flush()
+
+	if len(d.AddedDependencies)+len(d.RemovedDependencies) > 0 {
+		fmt.Fprintln(w, "\nDependencies:")
+		for _, e := range d.AddedDependencies {
+			fmt.Fprintf(w, "  + %s -> %s\n", e.FromPackage, e.ToPackage)
+		}
+		for _, e := range d.RemovedDependencies {
+			fmt.Fprintf(w, "  - %s -> %s\n", e.FromPackage, e.ToPackage)
 		}
 	}
-	return fmt.Errorf("invalid format %q: must be %s", format, strings.Join(validFormats, " or "))
+	if len(d.NewCycles)+len(d.BrokenCycles) > 0 {
+		fmt.Fprintln(w, "\nCycles:")
+		for _, c := range d.NewCycles {
+			fmt.Fprintf(w, "  + %s\n", strings.Join(c, " -> "))
+		}
+		for _, c := range d.BrokenCycles {
+			fmt.Fprintf(w, "  - %s\n", strings.Join(c, " -> "))
+		}
+	}
+	if len(d.AddedAPI)+len(d.RemovedAPI) > 0 {
+		fmt.Fprintln(w, "\nPublic API:")
+		for _, s := range d.AddedAPI {
+			fmt.Fprintf(w, "  + %s %s (%s)\n", s.Kind, s.Name, s.File)
+		}
+		for _, s := range d.RemovedAPI {
+			fmt.Fprintf(w, "  - %s %s (%s)\n", s.Kind, s.Name, s.File)
+		}
+	}
+}
+
+// formatStaleText formats []CLIStaleSymbol as aligned columns.
+func formatStaleText(w io.Writer, stale []CLIStaleSymbol) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "ID\tNAME\tKIND\tLAST CHANGED\tAUTHOR\tCOMMIT\tLOCATION")
+	for _, st := range stale {
+		date, _, _ := strings.Cut(st.History.Date, "T")
+		fmt.Fprintf(tw, "%d\t%s\t%s\t%s\t%s\t%.8s\t%s:%d:%d\n",
+			st.Symbol.ID, st.Symbol.Name, st.Symbol.Kind,
+			date, st.History.Author, st.History.Commit,
+			st.Symbol.File, st.Symbol.StartLine, st.Symbol.StartCol)
+	}
+	tw.Flush()
+}
+
+// formatBusFactorText formats []CLIBusFactor as aligned columns listing the
+// authors who make up each file's bus factor.
+func formatBusFactorText(w io.Writer, factors []CLIBusFactor) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "BUS FACTOR\tLINES\tTOP AUTHORS\tFILE")
+	for _, f := range factors {
+		top := make([]string, 0, f.BusFactor)
+		for _, a := range f.Authors[:min(f.BusFactor, len(f.Authors))] {
+			top = append(top, fmt.Sprintf("%s (%d)", a.Author, a.Lines))
+		}
+		fmt.Fprintf(tw, "%d\t%d\t%s\t%s\n", f.BusFactor, f.TotalLines, strings.Join(top, ", "), f.File)
+	}
+	tw.Flush()
+}
+
+// formatHotText formats []CLIHotSymbol as aligned columns.
+func formatHotText(w io.Writer, hot []CLIHotSymbol) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "SELF\tSELF %\tTOTAL\tTOTAL %\tNAME\tKIND\tLOCATION")
+	for _, h := range hot {
+		fmt.Fprintf(tw, "%d\t%.1f\t%d\t%.1f\t%s\t%s\t%s:%d:%d\n",
+			h.SelfSamples, h.SelfPercent, h.TotalSamples, h.TotalPercent,
+			h.Symbol.Name, h.Symbol.Kind,
+			h.Symbol.File, h.Symbol.StartLine, h.Symbol.StartCol)
+	}
+	tw.Flush()
+}
+
+// formatCoverageText formats []CLICoveredSymbol as aligned columns.
+func formatCoverageText(w io.Writer, covered []CLICoveredSymbol) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "COVERED %\tLINES\tCALLERS\tNAME\tKIND\tLOCATION")
+	for _, c := range covered {
+		fmt.Fprintf(tw, "%.1f\t%d/%d\t%d\t%s\t%s\t%s:%d:%d\n",
+			c.Percent, c.CoveredLines, c.Lines, c.Callers,
+			c.Symbol.Name, c.Symbol.Kind,
+			c.Symbol.File, c.Symbol.StartLine, c.Symbol.StartCol)
+	}
+	tw.Flush()
+}
+
+// formatSemanticHitsText formats []CLISemanticHit as aligned columns,
+// with each hit's matching callers and callees.
+func formatSemanticHitsText(w io.Writer, hits []CLISemanticHit) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "SCORE\tSIMILARITY\tNAME\tKIND\tLOCATION\tRELATED")
+	for _, h := range hits {
+		related := make([]string, len(h.Related))
+		for i, r := range h.Related {
+			related[i] = r.Name
+		}
+		fmt.Fprintf(tw, "%.3f\t%.3f\t%s\t%s\t%s:%d:%d\t%s\n",
+			h.Score, h.Similarity, h.Symbol.Name, h.Symbol.Kind,
+			h.Symbol.File, h.Symbol.StartLine, h.Symbol.StartCol,
+			strings.Join(related, ", "))
+	}
+	tw.Flush()
+}
+
+// formatSnippetsText formats []CLISnippet as their Markdown renderings,
+// separated by blank lines.
+func formatSnippetsText(w io.Writer, snippets []CLISnippet) {
+	for i, s := range snippets {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprint(w, s.Text)
+	}
+}
+
+// formatUsageExamplesText formats []CLIUsageExamples as their Markdown
+// renderings, separated by blank lines.
+func formatUsageExamplesText(w io.Writer, examples []CLIUsageExamples) {
+	for i, e := range examples {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprint(w, e.Text)
+	}
+}
+
+// formatStructuralMatchesText formats []CLIStructuralMatch as one location
+// line per match with its first line of code, then its replacement if any.
+func formatStructuralMatchesText(w io.Writer, matches []CLIStructuralMatch) {
+	for _, m := range matches {
+		first, _, more := strings.Cut(m.Text, "\n")
+		if more {
+			first += " ..."
+		}
+		fmt.Fprintf(w, "%s:%d:%d\t%s\n", m.Location.File, m.Location.StartLine, m.Location.StartCol, first)
+		if m.Replacement != "" {
+			fmt.Fprintf(w, "  => %s\n", m.Replacement)
+		}
+	}
+}
+
+// formatAnswerText formats a CLIAnswer as its Markdown rendering.
+func formatAnswerText(w io.Writer, a CLIAnswer) {
+	fmt.Fprint(w, a.Text)
+}
+
+// formatVisibilityText formats a CLISymbolVisibility as the answer and
+// reason, then one line per step.
+func formatVisibilityText(w io.Writer, v CLISymbolVisibility) {
+	answer := "not visible"
+	if v.Visible {
+		answer = "visible"
+	}
+	fmt.Fprintf(w, "%s %s from %s: %s\n", v.Symbol.Name, answer, v.From, v.Reason)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, st := range v.Steps {
+		mark := "ok"
+		if !st.Visible {
+			mark = "blocked"
+		}
+		vis := st.Visibility
+		if vis == "" {
+			vis = "(not indexed)"
+		}
+		fmt.Fprintf(tw, "  %s\t%s\t%s\t%s\n", mark, st.Name, vis, st.Scope)
+	}
+	tw.Flush()
+}
+
+// formatShadowsText formats []CLIShadow as aligned columns: where the
+// shadowing definition is, then where the one it hides is.
+func formatShadowsText(w io.Writer, shadows []CLIShadow) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tKIND\tNAME\tSHADOWS")
+	for _, s := range shadows {
+		shadowed := s.Shadowed.Import
+		if s.Shadowed.Location.File != "" {
+			shadowed = fmt.Sprintf("%s:%d:%d", s.Shadowed.Location.File, s.Shadowed.Location.StartLine, s.Shadowed.Location.StartCol)
+		}
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\t%s\t%s\n",
+			s.Shadowing.Location.File, s.Shadowing.Location.StartLine, s.Shadowing.Location.StartCol,
+			s.Kind, s.Name, shadowed)
+	}
+	tw.Flush()
+}
+
+// formatGlobCollisionsText formats []CLIGlobCollision as one line per name
+// at its first use, followed by the globs it comes through and the import
+// to add.
+func formatGlobCollisionsText(w io.Writer, collisions []CLIGlobCollision) {
+	for _, c := range collisions {
+		if len(c.Uses) > 0 {
+			fmt.Fprintf(w, "%s:%d:%d", c.File, c.Uses[0].StartLine, c.Uses[0].StartCol)
+		} else {
+			fmt.Fprint(w, c.File)
+		}
+		fmt.Fprintf(w, "  %s (%d use(s))
+", c.Name, len(c.Uses))
+		for _, src := range c.Sources {
+			fmt.Fprintf(w, "  from %s", src.Import)
+			if src.Location.File != "" {
+				fmt.Fprintf(w, "  %s:%d:%d", src.Location.File, src.Location.StartLine, src.Location.StartCol)
+			}
+			fmt.Fprintln(w)
+		}
+		fmt.Fprintf(w, "  fix: %s\n", c.Import)
+	}
+}
+
+// formatTraitImplsText formats []CLITraitImpls as one block per trait: a
+// header, one line per impl with the crates owning the trait and type, and
+// one line per overlapping pair.
+func formatTraitImplsText(w io.Writer, traits []CLITraitImpls) {
+	for i, t := range traits {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprintf(w, "%s (%d impl(s))\n", t.Trait, len(t.Impls))
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for j, im := range t.Impls {
+			neg := ""
+			if im.Negative {
+				neg = "!"
+			}
+			owned := "owned by " + im.OwnedBy
+			switch {
+			case im.Orphan:
+				owned = "orphan"
+			case im.OwnedBy == "":
+				owned = "owner unknown"
+			}
+			fmt.Fprintf(tw, "  #%d\timpl %s%s for %s\t%s:%d:%d\tcrate %s\ttrait %s\ttype %s\t%s\n",
+				j, neg, im.Trait, im.Type, im.Location.File, im.Location.StartLine, im.Location.StartCol,
+				orUnknown(im.Crate), orUnknown(im.TraitCrate), orUnknown(im.TypeCrate), owned)
+		}
+		tw.Flush()
+		for _, o := range t.Overlaps {
+			fmt.Fprintf(w, "  overlap (%s): #%d and #%d\n", o.Kind, o.First, o.Second)
+		}
+	}
+}
+
+// formatObjectSafetyText formats []CLITraitObjectSafety as one line per
+// trait with its verdict and dyn use count, followed by its violations.
+func formatObjectSafetyText(w io.Writer, traits []CLITraitObjectSafety) {
+	for _, t := range traits {
+		verdict := "object safe"
+		if !t.DynCompatible {
+			verdict = "not object safe"
+		}
+		fmt.Fprintf(w, "%s  %s:%d  %s, %d dyn use(s)\n", t.Trait.Name, t.Trait.File, t.Trait.StartLine, verdict, len(t.DynUses))
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for _, v := range t.Violations {
+			member := v.Member
+			if member == "" {
+				member = "-"
+			}
+			fmt.Fprintf(tw, "  %s\t%s\t%s\n", v.Kind, member, v.Detail)
+		}
+		tw.Flush()
+	}
+}
+
+// formatEntryPointsText formats []CLIEntryPoint as one line per target with
+// its package, crate root, and root functions.
+func formatEntryPointsText(w io.Writer, entries []CLIEntryPoint) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, ep := range entries {
+		roots := make([]string, len(ep.Roots))
+		for i, r := range ep.Roots {
+			roots[i] = r.Name
+		}
+		fmt.Fprintf(tw, "%s:%s\t%s\t%s\t%s\n", ep.Kind, ep.Name, ep.Package, ep.Path, strings.Join(roots, ", "))
+	}
+	tw.Flush()
+}
+
+// orUnknown returns s, or "?" if it is empty.
+func orUnknown(s string) string {
+	if s == "" {
+		return "?"
+	}
+	return s
+}
+
+// formatInstantiationsText formats a CLIInstantiations as the generic's
+// signature, its distinct type argument lists by use, and then each site.
+func formatInstantiationsText(w io.Writer, inst CLIInstantiations) {
+	fmt.Fprintf(w, "%s[%s]  %s:%d:%d\n", inst.Symbol.Name, strings.Join(inst.TypeParams, ", "),
+		inst.Symbol.File, inst.Symbol.StartLine, inst.Symbol.StartCol)
+	typeArgs := func(args []string) string {
+		known := make([]string, len(args))
+		for i, a := range args {
+			known[i] = orUnknown(a)
+		}
+		return "[" + strings.Join(known, ", ") + "]"
+	}
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, u := range inst.Usages {
+		fmt.Fprintf(tw, "  %s\t%d use(s)\n", typeArgs(u.TypeArgs), u.Count)
+	}
+	tw.Flush()
+	fmt.Fprintln(w)
+	tw = tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, site := range inst.Sites {
+		how := "explicit"
+		switch {
+		case site.Inferred:
+			how = "inferred"
+		case strings.Join(site.TypeArgs, "") == "":
+			how = "unknown"
+		}
+		fmt.Fprintf(tw, "  %s:%d:%d\t%s\t%s\t%s\n", site.Location.File, site.Location.StartLine, site.Location.StartCol,
+			site.Location.Kind, typeArgs(site.TypeArgs), how)
+	}
+	tw.Flush()
+}
+
+// formatImplBlocksText formats []CLIImplBlock as one block per impl: the
+// header and its location, then one line per method.
+func formatImplBlocksText(w io.Writer, blocks []CLIImplBlock) {
+	for i, b := range blocks {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		kind := "inherent"
+		if b.Trait != "" {
+			kind = "trait " + b.Trait
+		}
+		fmt.Fprintf(w, "%s  (%s)  %s:%d:%d\n", b.Header, kind, b.Location.File, b.Location.StartLine, b.Location.StartCol)
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for _, m := range b.Methods {
+			fmt.Fprintf(tw, "  %s\t%s\t%d:%d\n", m.Name, symbolKindText(m), m.StartLine, m.StartCol)
+		}
+		tw.Flush()
+	}
+}
+
+// formatTestRegionsText formats []CLITestRegion as aligned columns: the
+// region, why it is test code, and the item for items.
+func formatTestRegionsText(w io.Writer, regions []CLITestRegion) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tREASON\tITEM")
+	for _, r := range regions {
+		item := "-"
+		if r.Symbol != nil {
+			item = r.Symbol.Name
+		}
+		fmt.Fprintf(tw, "%s:%d-%d\t%s\t%s\n", r.Location.File, r.Location.StartLine, r.Location.EndLine, r.Reason, item)
+	}
+	tw.Flush()
+}
+
+// formatHistoryText formats []CLIHistoryPoint as aligned columns, one
+// sampled revision per row, oldest first.
+func formatHistoryText(w io.Writer, points []CLIHistoryPoint) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "COMMIT\tDATE\tFILES\tSYMBOLS\tUNUSED\tCYCLES\tMEAN CC\tMAX CC\tSUBJECT")
+	for _, p := range points {
+		date, _, _ := strings.Cut(p.Date, "T")
+		fmt.Fprintf(tw, "%.12s\t%s\t%d\t%d\t%d\t%d\t%.2f\t%d\t%s\n",
+			p.Commit, date, p.Files, p.Symbols, p.Unused, p.Cycles, p.MeanCyclomatic, p.MaxCyclomatic, p.Subject)
+	}
+	tw.Flush()
+}
+
+// formatNewtypesText formats []CLINewtype as aligned columns: the newtype,
+// the type it wraps, and where that type is declared when indexed.
+func formatNewtypesText(w io.Writer, newtypes []CLINewtype) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tNAME\tWRAPS\tDECLARED")
+	for _, n := range newtypes {
+		declared := "-"
+		if n.InnerSymbol != nil && n.InnerSymbol.File != "" {
+			declared = fmt.Sprintf("%s:%d", n.InnerSymbol.File, n.InnerSymbol.StartLine)
+		}
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\t%s\t%s\n",
+			n.Symbol.File, n.Symbol.StartLine, n.Symbol.StartCol, n.Symbol.Name, n.Inner, declared)
+	}
+	tw.Flush()
+}
+
+// formatFieldGraphText formats a CLIFieldGraph as a header plus one line
+// per field edge, indented by depth.
+func formatFieldGraphText(w io.Writer, g CLIFieldGraph) {
+	fmt.Fprintf(w, "%s (%s): %d type(s) reached, depth %d\n", g.Root.Name, g.Root.Kind, len(g.Types), g.Depth)
+	for _, e := range g.Edges {
+		to := "-"
+		if e.To != nil && e.To.File != "" {
+			to = fmt.Sprintf("%s:%d", e.To.File, e.To.StartLine)
+		}
+		fmt.Fprintf(w, "%s%s.%s : %s -> %s  %s\n", strings.Repeat("  ", e.Depth), e.From.Name, e.Field, e.TypeExpr, e.Type, to)
+	}
+}
+
+// formatTraitTreesText formats []CLITraitTree as one block per trait: its
+// supertraits and subtraits as indented trees, then one line per impl with
+// the supertraits its type lacks.
+func formatTraitTreesText(w io.Writer, trees []CLITraitTree) {
+	for i, t := range trees {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprintf(w, "%s  %s:%d\n", t.Trait.Name, t.Trait.File, t.Trait.StartLine)
+		fmt.Fprintln(w, "supertraits:")
+		formatTraitTreeNodesText(w, t.Supertraits, 1)
+		fmt.Fprintln(w, "subtraits:")
+		formatTraitTreeNodesText(w, t.Subtraits, 1)
+		fmt.Fprintln(w, "impls:")
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for _, ci := range t.Impls {
+			status := "satisfies chain"
+			if !ci.Satisfied {
+				status = "missing " + strings.Join(ci.Missing, ", ")
+			}
+			fmt.Fprintf(tw, "  impl %s for %s\t%s:%d:%d\t%s\n",
+				ci.Impl.Trait, ci.Impl.Type, ci.Impl.Location.File, ci.Impl.Location.StartLine, ci.Impl.Location.StartCol, status)
+		}
+		tw.Flush()
+	}
+}
+
+// formatTraitTreeNodesText writes one line per trait, indented by depth,
+// with where the trait is declared when indexed.
+func formatTraitTreeNodesText(w io.Writer, nodes []CLITraitTreeNode, depth int) {
+	for _, n := range nodes {
+		declared := "-"
+		if n.Symbol != nil && n.Symbol.File != "" {
+			declared = fmt.Sprintf("%s:%d", n.Symbol.File, n.Symbol.StartLine)
+		}
+		fmt.Fprintf(w, "%s%s  %s\n", strings.Repeat("  ", depth), n.Name, declared)
+		formatTraitTreeNodesText(w, n.Children, depth+1)
+	}
+}
+
+// formatModuleMatrixText formats a CLIModuleMatrix as a numbered grid:
+// one row per module, with its number and name, and one column per module
+// number. Empty cells and the diagonal are shown as ".".
+func formatModuleMatrixText(w io.Writer, m CLIModuleMatrix) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprint(tw, "\t\t")
+	for i := range m.Modules {
+		fmt.Fprintf(tw, "%d\t", i+1)
+	}
+	fmt.Fprintln(tw)
+	for i, name := range m.Modules {
+		fmt.Fprintf(tw, "%d\t%s\t", i+1, name)
+		for _, n := range m.Counts[i] {
+			cell := "."
+			if n > 0 {
+				cell = fmt.Sprint(n)
+			}
+			fmt.Fprintf(tw, "%s\t", cell)
+		}
+		fmt.Fprintln(tw)
+	}
+	tw.Flush()
+	fmt.Fprintf(w, "%d module(s), %d dependency edge(s)\n", len(m.Modules), m.Edges)
+}
+
+// formatLintText formats a CLILintReport as aligned columns, one finding
+// per line, followed by the suppressed and baselined counts.
+func formatLintText(w io.Writer, r CLILintReport) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, f := range r.Findings {
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\t%s\t%s\n",
+			f.Location.File, f.Location.StartLine, f.Location.StartCol, f.Severity, f.Rule, f.Message)
+	}
+	tw.Flush()
+	fmt.Fprintf(w, "%d finding(s) in rules %s; %d suppressed, %d in baseline\n",
+		len(r.Findings), strings.Join(r.Rules, ", "), r.Suppressed, r.Baselined)
+}
+
+// formatRenamePlanText formats a CLIRenamePlan as a header plus one line per edit.
+func formatRenamePlanText(w io.Writer, plan CLIRenamePlan) {
+	fmt.Fprintf(w, "Rename %s (%s) -> %s: %d edit(s) in %d file(s)\n",
+		plan.Symbol.Name, plan.Symbol.Kind, plan.NewName, len(plan.Edits), plan.Files)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, e := range plan.Edits {
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\n", e.File, e.StartLine, e.StartCol, e.Kind)
+	}
+	tw.Flush()
+}
+
+// formatApplyResultText formats a CLIApplyResult as a summary plus the changed files.
+func formatApplyResultText(w io.Writer, res CLIApplyResult) {
+	fmt.Fprintf(w, "Applied %d edit(s) to %d file(s)\n", res.Edits, len(res.Files))
+	for _, f := range res.Files {
+		fmt.Fprintf(w, "  %s\n", f)
+	}
+}
+
+// formatResolutionTraceText formats a CLIResolutionTrace as the steps of
+// resolution: the reference, the scopes searched, the imports considered,
+// and the candidates with their verdicts.
+func formatResolutionTraceText(w io.Writer, tr CLIResolutionTrace) {
+	name := tr.Name
+	if tr.Qualifier != "" {
+		name = tr.Qualifier + "." + tr.Name
+	}
+	kind := tr.Kind
+	if kind == "" {
+		kind = "unclassified"
+	}
+	fmt.Fprintf(w, "Reference #%d %q at %s:%d:%d (%s, %s)\n",
+		tr.ReferenceID, name, tr.Location.File, tr.Location.StartLine, tr.Location.StartCol, kind, tr.Language)
+
+	fmt.Fprintln(w, "\nScopes searched, innermost first:")
+	if len(tr.Scopes) == 0 {
+		fmt.Fprintln(w, "  (none recorded)")
+	}
+	for i, sc := range tr.Scopes {
+		scope := sc.Kind
+		if sc.SymbolName != "" {
+			scope += " " + sc.SymbolName
+		}
+		declares := "no declaration of the name"
+		if len(sc.Declares) > 0 {
+			ids := make([]string, len(sc.Declares))
+			for j, id := range sc.Declares {
+				ids[j] = fmt.Sprintf("#%d", id)
+			}
+			declares = "declares " + strings.Join(ids, ", ")
+		}
+		fmt.Fprintf(w, "  %d. %s (lines %d-%d): %s\n", i+1, scope, sc.StartLine, sc.EndLine, declares)
+	}
+
+	fmt.Fprintln(w, "\nImports considered:")
+	if len(tr.Imports) == 0 {
+		fmt.Fprintln(w, "  (none)")
+	}
+	for _, imp := range tr.Imports {
+		mark := " "
+		if imp.Matches {
+			mark = "*"
+		}
+		binds := imp.Binds
+		if binds == "" {
+			binds = "(glob)"
+		}
+		fmt.Fprintf(w, "  %s %s binds %s\n", mark, imp.Source, binds)
+	}
+
+	fmt.Fprintln(w, "\nCandidates:")
+	if len(tr.Candidates) == 0 {
+		fmt.Fprintln(w, "  (no declaration of the name is indexed)")
+	}
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, c := range tr.Candidates {
+		verdict := "rejected: " + c.Rejected
+		if c.Bound {
+			verdict = fmt.Sprintf("BOUND (%s, %s)", c.ResolutionKind, c.Confidence)
+		}
+		where := "(stub)"
+		if c.Location != nil {
+			where = fmt.Sprintf("%s:%d:%d", c.Location.File, c.Location.StartLine, c.Location.StartCol)
+		}
+		fmt.Fprintf(tw, "  #%d\t%s %s\t%s\t%s\n", c.SymbolID, c.Kind, c.Name, where, verdict)
+	}
+	tw.Flush()
+
+	if tr.UnresolvedReason != "" {
+		fmt.Fprintf(w, "\nUnresolved: %s\n", tr.UnresolvedReason)
+	}
+}
+
+// formatUnresolvedText formats a CLIUnresolvedReport as per-reason counts
+// followed by aligned columns.
+func formatUnresolvedText(w io.Writer, r CLIUnresolvedReport) {
+	unresolved := 0
+	for _, n := range r.ByReason {
+		unresolved += n
+	}
+	fmt.Fprintf(w, "Unresolved: %d of %d references\n", unresolved, r.TotalReferences)
+	reasons := make([]string, 0, len(r.ByReason))
+	for reason := range r.ByReason {
+		reasons = append(reasons, reason)
+	}
+	sort.Strings(reasons)
+	for _, reason := range reasons {
+		fmt.Fprintf(w, "  %-20s %d\n", reason, r.ByReason[reason])
+	}
+	if len(r.References) == 0 {
+		return
+	}
+	fmt.Fprintln(w)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tNAME\tCONTEXT\tREASON\tIMPORT")
+	for _, ref := range r.References {
+		name := ref.Name
+		if ref.Qualifier != "" {
+			name = ref.Qualifier + "." + ref.Name
+		}
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\t%s\t%s\t%s\n",
+			ref.Location.File, ref.Location.StartLine, ref.Location.StartCol,
+			name, ref.Context, ref.Reason, ref.Import)
+	}
+	tw.Flush()
+}
+
+// formatUnsafeText formats a CLIUnsafeReport as a summary followed by aligned
+// columns, one row per usage.
+func formatUnsafeText(w io.Writer, r CLIUnsafeReport) {
+	fmt.Fprintf(w, "Unsafe: %d unsafe fns, %d unsafe blocks in %d files\n", r.Functions, r.Blocks, len(r.ByFile))
+	if len(r.Usages) == 0 {
+		return
+	}
+	fmt.Fprintln(w)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "LOCATION\tKIND\tFUNCTION\tDEPTH")
+	for _, u := range r.Usages {
+		fmt.Fprintf(tw, "%s:%d:%d\t%s\t%s (#%d)\t%d\n",
+			u.Location.File, u.Location.StartLine, u.Location.StartCol,
+			u.Kind, u.Function.Name, u.Function.ID, len(u.Path))
+	}
+	tw.Flush()
+}
+
+// formatNoStdText formats a CLINoStdReport as a table of crates followed by
+// aligned columns, one row per symbol needing alloc or std.
+func formatNoStdText(w io.Writer, r CLINoStdReport) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "CRATE\tROOT\tNO_STD\tCORE\tALLOC\tSTD")
+	for _, c := range r.Crates {
+		noStd := c.NoStd
+		if noStd == "" {
+			noStd = "-"
+		}
+		fmt.Fprintf(tw, "%s\t%s\t%s\t%d\t%d\t%d\n", c.Crate, c.Root, noStd, c.Core, c.Alloc, c.Std)
+	}
+	tw.Flush()
+	if len(r.Dependencies) == 0 {
+		return
+	}
+	fmt.Fprintln(w)
+	tw = tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "TIER\tSYMBOL\tLOCATION\tVIA")
+	for _, d := range r.Dependencies {
+		via := make([]string, len(d.Via))
+		for i, v := range d.Via {
+			via[i] = v.Name
+		}
+		fmt.Fprintf(tw, "%s\t%s %s (#%d)\t%s:%d\t%s\n",
+			d.Tier, d.Symbol.Kind, d.Symbol.Name, d.Symbol.ID,
+			d.Symbol.File, d.Symbol.StartLine, strings.Join(via, " -> "))
+	}
+	tw.Flush()
+}
+
+// formatBinarySizeText formats a CLIBinarySizeReport as a summary, the
+// modules and unattributed crates by size, then one row per function.
+func formatBinarySizeText(w io.Writer, r CLIBinarySizeReport) {
+	fmt.Fprintf(w, "Binary size (%s): %d bytes\n\n", r.Source, r.Total)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "MODULE\tBYTES\tSHARE\tFUNCTIONS")
+	for _, m := range r.Modules {
+		fmt.Fprintf(tw, "%s\t%d\t%.1f%%\t%d\n", m.Module, m.Bytes, m.Share*100, m.Functions)
+	}
+	for _, c := range r.Unattributed {
+		fmt.Fprintf(tw, "(%s)\t%d\t%.1f%%\t\n", c.Crate, c.Bytes, c.Share*100)
+	}
+	tw.Flush()
+	if len(r.Functions) == 0 {
+		return
+	}
+	fmt.Fprintln(w)
+	tw = tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "FUNCTION\tMODULE\tBYTES\tLOCATION")
+	for _, f := range r.Functions {
+		size := fmt.Sprint(f.Bytes)
+		if f.Inlined {
+			size = "inlined"
+		}
+		fmt.Fprintf(tw, "%s (#%d)\t%s\t%s\t%s:%d\n", f.Symbol.Name, f.Symbol.ID, f.Module, size, f.Symbol.File, f.Symbol.StartLine)
+	}
+	tw.Flush()
+}
+
+// formatInstantiationHotspotsText formats instantiation hotspots as
+// aligned columns, one row per generic, with its most used argument lists.
+func formatInstantiationHotspotsText(w io.Writer, hotspots []CLIInstantiationHotspot) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "GENERIC\tDISTINCT\tSITES\tUNKNOWN\tLOCATION\tMOST USED")
+	for _, h := range hotspots {
+		var top []string
+		for _, u := range h.Usages[:min(3, len(h.Usages))] {
+			args := make([]string, len(u.TypeArgs))
+			for i, a := range u.TypeArgs {
+				args[i] = orUnknown(a)
+			}
+			top = append(top, fmt.Sprintf("[%s] x%d", strings.Join(args, ", "), u.Count))
+		}
+		fmt.Fprintf(tw, "%s[%s] (#%d)\t%d\t%d\t%d\t%s:%d\t%s\n", h.Symbol.Name, strings.Join(h.TypeParams, ", "), h.Symbol.ID,
+			h.Distinct, h.Sites, h.Unknown, h.Symbol.File, h.Symbol.StartLine, strings.Join(top, "; "))
+	}
+	tw.Flush()
+}
+
+// formatSymbolAliasesText formats a CLISymbolAliases as the symbol and
+// then one aligned line per alias.
+func formatSymbolAliasesText(w io.Writer, a CLISymbolAliases) {
+	fmt.Fprintf(w, "%s  %s:%d:%d\n", a.Symbol.Name, a.Symbol.File, a.Symbol.StartLine, a.Symbol.StartCol)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	for _, alias := range a.Aliases {
+		fmt.Fprintf(tw, "  %s\t%s\t%s\t%d ref(s)\n", alias.Alias, alias.File, alias.Source, alias.References)
+	}
+	tw.Flush()
+}
+
+// formatImportPathsText formats a CLIImportPaths as the canonical path and
+// then each import path with its chain of re-exports.
+func formatImportPathsText(w io.Writer, p CLIImportPaths) {
+	fmt.Fprintf(w, "%s  %s:%d:%d\n", p.CanonicalPath, p.Symbol.File, p.Symbol.StartLine, p.Symbol.StartCol)
+	for _, path := range p.Paths {
+		visibility := "pub"
+		if !path.Public {
+			visibility = "restricted"
+		}
+		fmt.Fprintf(w, "  %s  (%s)\n", path.Path, visibility)
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for _, hop := range path.Hops {
+			fmt.Fprintf(tw, "    <- %s\t%s\t%s:%d:%d\n", hop.Path, hop.Source, hop.File, hop.Line, hop.Col)
+		}
+		tw.Flush()
+	}
+}
+
+// formatUpgradeImpactText formats a CLIUpgradeImpact as one heading per
+// changed item with its reference sites, then the totals.
+func formatUpgradeImpactText(w io.Writer, u CLIUpgradeImpact) {
+	for _, c := range u.Changes {
+		heading := c.Path
+		if c.Note != "" {
+			heading += " (" + c.Note + ")"
+		}
+		if len(c.Symbols) == 0 {
+			fmt.Fprintf(w, "%s: not indexed\n", heading)
+			continue
+		}
+		fmt.Fprintf(w, "%s: %d site(s)\n", heading, len(c.Sites))
+		tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+		for _, site := range c.Sites {
+			fmt.Fprintf(tw, "  %s:%d:%d\t%s\n", site.File, site.StartLine, site.StartCol, site.Kind)
+		}
+		tw.Flush()
+	}
+	fmt.Fprintf(w, "\n%d site(s) in %d file(s)\n", u.Sites, len(u.Files))
+}
+
+// formatBenchText formats a CLIBenchReport as a summary followed by aligned
+// columns, one row per phase.
+func formatBenchText(w io.Writer, r CLIBenchReport) {
+	fmt.Fprintf(w, "Bench: %s, %d files x %d functions (seed %d), median of %d runs\n",
+		r.Language, r.Files, r.Functions, r.Seed, r.Runs)
+	fmt.Fprintf(w, "Indexed %d symbols, %d references, %d call edges\n\n", r.Symbols, r.References, r.CallEdges)
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "PHASE\tMS\tBASELINE MS\tCHANGE")
+	for _, t := range r.Timings {
+		baseline, change := "", ""
+		if t.ChangePct != nil {
+			baseline = fmt.Sprintf("%.3f", t.BaselineMillis)
+			change = fmt.Sprintf("%+.1f%%", *t.ChangePct)
+		}
+		fmt.Fprintf(tw, "%s\t%.3f\t%s\t%s\n", t.Phase, t.Millis, baseline, change)
+	}
+	tw.Flush()
+}
+
+// formatIndexStatsText formats a CLIIndexStats as totals followed by the
+// per-language, per-kind, and per-phase breakdowns.
+func formatIndexStatsText(w io.Writer, st CLIIndexStats) {
+	fmt.Fprintf(w, "Files: %d (%s)\n", st.Files, formatCounts(st.FilesByLanguage))
+	fmt.Fprintf(w, "Symbols: %d (%s)\n", st.Symbols, formatCounts(st.SymbolsByKind))
+	fmt.Fprintf(w, "References: %d, %d resolved (%.1f%%)\n", st.References, st.Resolved, st.ResolutionRate*100)
+	fmt.Fprintf(w, "Edges: %s\n", formatCounts(st.EdgesByKind))
+	fmt.Fprintf(w, "Parse errors: %d in %d file(s)\n", st.ParseErrors, st.FilesWithErrors)
+	degraded := 0
+	for _, n := range st.DegradedFiles {
+		degraded += n
+	}
+	if degraded > 0 {
+		fmt.Fprintf(w, "Fallback: %d symbol(s) in %d file(s) (%s)\n", st.FallbackSymbols, degraded, formatCounts(st.DegradedFiles))
+	} else {
+		fmt.Fprintln(w, "Fallback: not used")
+	}
+	fmt.Fprintf(w, "Database: %d bytes\n", st.DatabaseBytes)
+	if st.IndexedAt == "" {
+		fmt.Fprintln(w, "Last index: not recorded")
+		return
+	}
+	phases := make([]string, len(st.Timings))
+	for i, t := range st.Timings {
+		phases[i] = fmt.Sprintf("%s %.0fms", t.Phase, t.Millis)
+	}
+	fmt.Fprintf(w, "Last index: %s (%s)\n", st.IndexedAt, strings.Join(phases, ", "))
+}
+
+// formatCounts formats counts by key as "key n" pairs, largest first.
+func formatCounts(counts map[string]int) string {
+	keys := make([]string, 0, len(counts))
+	for k := range counts {
+		keys = append(keys, k)
+	}
+	sort.Slice(keys, func(i, j int) bool {
+		if counts[keys[i]] != counts[keys[j]] {
+			return counts[keys[i]] > counts[keys[j]]
+		}
+		return keys[i] < keys[j]
+	})
+	parts := make([]string, len(keys))
+	for i, k := range keys {
+		parts[i] = fmt.Sprintf("%s %d", k, counts[k])
+	}
+	return strings.Join(parts, ", ")
+}
+
+// formatFixtureText formats a CLIFixture as a short summary.
+func formatFixtureText(w io.Writer, f CLIFixture) {
+	fmt.Fprintf(w, "Fixture: %s (level %d: %s, seed %d)\n", f.Dir, f.Level, strings.Join(f.Features, ", "), f.Seed)
+	fmt.Fprintf(w, "Wrote %d files; expecting %d definitions, %d calls, %d implementations\n",
+		f.Files, f.Definitions, f.Calls, f.Implementations)
+}
+
+// formatSnapshotText formats a CLISnapshot as one summary line.
+func formatSnapshotText(w io.Writer, s CLISnapshot) {
+	fmt.Fprintf(w, "Snapshot: %s (%s v%d, %d bytes): %d tables, %d rows\n", s.Path, s.Format, s.Version, s.Bytes, s.Tables, s.Rows)
+}
+
+// formatChunkExportText formats a CLIChunkExport as one summary line.
+func formatChunkExportText(w io.Writer, e CLIChunkExport) {
+	fmt.Fprintf(w, "Chunks: %s (%d bytes): %d chunks from %d files\n", e.Path, e.Bytes, e.Chunks, e.Files)
+}
+
+// formatAccessExportText formats a CLIAccessExport as one summary line.
+func formatAccessExportText(w io.Writer, e CLIAccessExport) {
+	fmt.Fprintf(w, "Access manifest: %s (%d bytes): %d symbols, %d restricted\n", e.Path, e.Bytes, e.Symbols, e.Restricted)
+}
+
+// formatFileExportText formats a CLIFileExport as one line per file.
+func formatFileExportText(w io.Writer, e CLIFileExport) {
+	for _, f := range e.Files {
+		fmt.Fprintf(w, "Export: %s (%s, %d bytes): %d rows\n", f.Path, e.Format, f.Bytes, f.Rows)
+	}
+}
+
+// formatTraceSummaryText formats a CLITraceSummary as one summary line.
+func formatTraceSummaryText(w io.Writer, s CLITraceSummary) {
+	fmt.Fprintf(w, "Trace: %d samples in %d stacks; mapped %d of %d frames to %d symbols\n",
+		s.Samples, s.Stacks, s.MappedFrames, s.Frames, s.Symbols)
+}
+
+// formatCoverageSummaryText formats a CLICoverageSummary as one summary line.
+func formatCoverageSummaryText(w io.Writer, s CLICoverageSummary) {
+	fmt.Fprintf(w, "Coverage: %d of %d files indexed; %d of %d lines covered in %d functions\n",
+		s.MappedFiles, s.Files, s.CoveredLines, s.Lines, s.Symbols)
+}
+
+// formatEmbeddingSummaryText formats a CLIEmbeddingSummary as one summary line.
+func formatEmbeddingSummaryText(w io.Writer, s CLIEmbeddingSummary) {
+	fmt.Fprintf(w, "Embedded %d symbols with %s (%d dimensions): %d new or changed, %d unchanged\n",
+		s.Symbols, s.Model, s.Dimensions, s.Embedded, s.Reused)
+}
+
+// formatIndexValidationText formats a CLIIndexValidation as counts per
+// check, then one line per violation.
+func formatIndexValidationText(w io.Writer, r CLIIndexValidation) {
+	if r.Valid {
+		fmt.Fprintln(w, "Index is valid")
+		return
+	}
+	checks := make([]string, 0, len(r.ByCheck))
+	for c := range r.ByCheck {
+		checks = append(checks, c)
+	}
+	sort.Strings(checks)
+	parts := make([]string, len(checks))
+	for i, c := range checks {
+		parts[i] = fmt.Sprintf("%s %d", c, r.ByCheck[c])
+	}
+	fmt.Fprintf(w, "%d violations: %s\n\n", len(r.Violations), strings.Join(parts, ", "))
+	for _, v := range r.Violations {
+		fmt.Fprintf(w, "%s  %s row %d: %s\n", v.Check, v.Table, v.RowID, v.Detail)
+	}
+}
+
+// formatGateReportText formats a CLIGateReport as one row per gate plus a
+// verdict.
+func formatGateReportText(w io.Writer, r CLIGateReport) {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', 0)
+	fmt.Fprintln(tw, "GATE\tVALUE\tLIMIT\tRESULT")
+	failed := 0
+	for _, g := range r.Gates {
+		result := "pass"
+		if !g.Passed {
+			result = "FAIL"
+			failed++
+		}
+		fmt.Fprintf(tw, "%s\t%s\t%s\t%s\n", g.Gate, formatGateValue(g.Value), formatGateValue(g.Limit), result)
+	}
+	tw.Flush()
+	if r.Passed {
+		fmt.Fprintf(w, "All %d gate(s) passed\n", len(r.Gates))
+		return
+	}
+	fmt.Fprintf(w, "%d of %d gate(s) failed\n", failed, len(r.Gates))
+}
+
+// formatGateValue formats a gate value: counts as integers, rates with four
+// decimals.
+func formatGateValue(v float64) string {
+	if v == float64(int64(v)) {
+		return strconv.FormatInt(int64(v), 10)
+	}
+	return strconv.FormatFloat(v, 'f', 4, 64)
+}
+
+// formatOutlineText formats CLIOutlineFile results as an indented tree per file.
+func formatOutlineText(w io.Writer, files []CLIOutlineFile) {
+	for i, f := range files {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprintln(w, f.File)
+		writeOutlineText(w, f.Items, 1)
+	}
+}
+
+// writeOutlineText writes outline nodes indented two spaces per depth.
+func writeOutlineText(w io.Writer, nodes []CLIOutlineNode, depth int) {
+	indent := strings.Repeat("  ", depth)
+	for _, n := range nodes {
+		if n.SymbolID == nil {
+			fmt.Fprintf(w, "%s%s  :%d\n", indent, n.Name, n.StartLine)
+		} else {
+			fmt.Fprintf(w, "%s%s %s  :%d\n", indent, n.Kind, n.Name, n.StartLine)
+		}
+		writeOutlineText(w, n.Children, depth+1)
+	}
+}
+
+// formatRepoMapText formats a CLIRepoMap as each file's path followed by the
+// signatures of its entries, indented.
+func formatRepoMapText(w io.Writer, m CLIRepoMap) {
+	for _, f := range m.Files {
+		fmt.Fprintf(w, "%s:\n", f.File)
+		for _, e := range f.Entries {
+			fmt.Fprintf(w, "  %s\n", e.Signature)
+		}
+	}
+}
+
+// formatBatchText formats CLIBatchResults as one section per entry, by ID,
+// each headed "== <id> ==" and holding the entry's text output or error.
+func formatBatchText(w io.Writer, results CLIBatchResults) error {
+	ids := make([]string, 0, len(results))
+	for id := range results {
+		ids = append(ids, id)
+	}
+	sort.Strings(ids)
+	for i, id := range ids {
+		if i > 0 {
+			fmt.Fprintln(w)
+		}
+		fmt.Fprintf(w, "== %s ==\n", id)
+		r := results[id]
+		if r.Error != "" {
+			fmt.Fprintf(w, "Error: %s\n", r.Error)
+			continue
+		}
+		if err := outputResultText(r); err != nil {
+			return fmt.Errorf("%s: %w", id, err)
+		}
+	}
+	return nil
+}
+
+// outputResultText dispatches to the appropriate text formatter based on the
+// result type. It writes to os.Stdout.
+func outputResultText(result CLIResult) error {
+	w := io.Writer(os.Stdout)
+
+	switch v := result.Results.(type) {
+	case CLIBatchResults:
+		return formatBatchText(w, v)
+	case []CLILocation:
+		formatLocationsText(w, v)
+	case []CLISymbol:
+		formatSymbolsText(w, v)
+	case CLISymbol:
+		formatSymbolsText(w, []CLISymbol{v})
+	case []CLICallEdge:
+		formatCallEdgesText(w, v)
+	case []CLIImport:
+		formatImportsText(w, v)
+	case []CLIFile:
+		formatFilesText(w, v)
+	case []CLIRoot:
+		formatRootsText(w, v)
+	case CLIProjectSummary:
+		formatSummaryText(w, v)
+	case CLIPackageSummary:
+		formatPackageSummaryText(w, v)
+	case CLISymbolDetail:
+		formatSymbolDetailText(w, v)
+	case []CLIScope:
+		formatScopesText(w, v)
+	case CLITypeHierarchy:
+		formatTypeHierarchyText(w, v)
+	case []CLIExtensionBinding:
+		formatExtensionBindingsText(w, v)
+	case []CLIReexport:
+		formatReexportsText(w, v)
+	case CLICallGraph:
+		formatCallGraphText(w, v)
+	case []CLICallGraphNode:
+		formatCallGraphNodesText(w, v)
+	case []CLIBlockingCall:
+		formatBlockingCallsText(w, v)
+	case []CLIPanicSite:
+		formatPanicSitesText(w, v)
+	case CLIDependencyGraph:
+		formatDependencyGraphText(w, v)
+	case []CLICycle:
+		formatCyclesText(w, v)
+	case []CLIHotspot:
+		formatHotspotsText(w, v)
+	case []CLIClonePair:
+		formatClonePairsText(w, v)
+	case []CLICrateDuplicate:
+		formatCrateDuplicatesText(w, v)
+	case []CLIDeprecatedUsage:
+		formatDeprecatedUsagesText(w, v)
+	case []CLIFFIBoundary:
+		formatFFIBoundariesText(w, v)
+	case []CLIErrorFlow:
+		formatErrorFlowsText(w, v)
+	case []CLIFeatureAPI:
+		formatFeatureAPIsText(w, v)
+	case []CLIComplexity:
+		formatComplexityText(w, v)
+	case []CLIPackageMetrics:
+		formatPackageMetricsText(w, v)
+	case CLIOwners:
+		formatOwnersText(w, v)
+	case []CLIOwnerImpact:
+		formatOwnerImpactText(w, v)
+	case CLIBuildTargets:
+		formatBuildTargetsText(w, v)
+	case []CLITargetImpact:
+		formatTargetImpactText(w, v)
+	case []CLISnapshotInfo:
+		formatSnapshotsText(w, v)
+	case CLIGraphDiff:
+		formatGraphDiffText(w, v)
+	case []CLIStaleSymbol:
+		formatStaleText(w, v)
+	case []CLIBusFactor:
+		formatBusFactorText(w, v)
+	case []CLIHotSymbol:
+		formatHotText(w, v)
+	case []CLICoveredSymbol:
+		formatCoverageText(w, v)
+	case []CLISemanticHit:
+		formatSemanticHitsText(w, v)
+	case []CLISnippet:
+		formatSnippetsText(w, v)
+	case []CLIUsageExamples:
+		formatUsageExamplesText(w, v)
+	case CLIAnswer:
+		formatAnswerText(w, v)
+	case []CLIStructuralMatch:
+		formatStructuralMatchesText(w, v)
+	case CLISymbolVisibility:
+		formatVisibilityText(w, v)
+	case []CLIShadow:
+		formatShadowsText(w, v)
+	case []CLIGlobCollision:
+		formatGlobCollisionsText(w, v)
+	case []CLITraitImpls:
+		formatTraitImplsText(w, v)
+	case []CLIImplBlock:
+		formatImplBlocksText(w, v)
+	case CLIInstantiations:
+		formatInstantiationsText(w, v)
+	case CLISymbolAliases:
+		formatSymbolAliasesText(w, v)
+	case CLIImportPaths:
+		formatImportPathsText(w, v)
+	case CLIUpgradeImpact:
+		formatUpgradeImpactText(w, v)
+	case []CLIInstantiationHotspot:
+		formatInstantiationHotspotsText(w, v)
+	case []CLITraitObjectSafety:
+		formatObjectSafetyText(w, v)
+	case []CLIEntryPoint:
+		formatEntryPointsText(w, v)
+	case []CLITestRegion:
+		formatTestRegionsText(w, v)
+	case []CLIHistoryPoint:
+		formatHistoryText(w, v)
+	case []CLINewtype:
+		formatNewtypesText(w, v)
+	case CLIFieldGraph:
+		formatFieldGraphText(w, v)
+	case []CLITraitTree:
+		formatTraitTreesText(w, v)
+	case CLIModuleMatrix:
+		formatModuleMatrixText(w, v)
+	case CLINoStdReport:
+		formatNoStdText(w, v)
+	case CLIBinarySizeReport:
+		formatBinarySizeText(w, v)
+	case CLILintReport:
+		formatLintText(w, v)
+	case CLIReport:
+		writeReportMarkdown(w, v)
+	case CLIRenamePlan:
+		formatRenamePlanText(w, v)
+	case CLIApplyResult:
+		formatApplyResultText(w, v)
+	case []CLIOutlineFile:
+		formatOutlineText(w, v)
+	case CLIRepoMap:
+		formatRepoMapText(w, v)
+	case CLIUnresolvedReport:
+		formatUnresolvedText(w, v)
+	case CLIResolutionTrace:
+		formatResolutionTraceText(w, v)
+	case CLIUnsafeReport:
+		formatUnsafeText(w, v)
+	case CLIBenchReport:
+		formatBenchText(w, v)
+	case CLIFixture:
+		formatFixtureText(w, v)
+	case CLIIndexValidation:
+		formatIndexValidationText(w, v)
+	case CLIGateReport:
+		formatGateReportText(w, v)
+	case CLIIndexStats:
+		formatIndexStatsText(w, v)
+	case CLISnapshot:
+		formatSnapshotText(w, v)
+	case CLIChunkExport:
+		formatChunkExportText(w, v)
+	case CLIAccessExport:
+		formatAccessExportText(w, v)
+	case CLIFileExport:
+		formatFileExportText(w, v)
+	case CLITraceSummary:
+		formatTraceSummaryText(w, v)
+	case CLICoverageSummary:
+		formatCoverageSummaryText(w, v)
+	case CLIEmbeddingSummary:
+		formatEmbeddingSummaryText(w, v)
+	case nil:
+		// No output for nil results (e.g., symbol-at with no match).
+	default:
+		return fmt.Errorf("unsupported result type for text format: %T", v)
+	}
+
+	// Pagination footer.
+	if result.TotalCount != nil {
+		count := *result.TotalCount
+		shown := resultLen(result.Results)
+		if shown < count {
+			fmt.Fprintf(w, "\nShowing %d of %d results\n", shown, count)
+		}
+	}
+
+	return nil
+}
+
+// resultLen returns the length of a result slice, or 1 for a single value.
+func resultLen(v any) int {
+	switch r := v.(type) {
+	case []CLILocation:
+		return len(r)
+	case []CLISymbol:
+		return len(r)
+	case []CLICallEdge:
+		return len(r)
+	case []CLIImport:
+		return len(r)
+	case []CLIFile:
+		return len(r)
+	case []CLIRoot:
+		return len(r)
+	case CLISymbolDetail:
+		return 1
+	case CLIInstantiations:
+		return 1
+	case CLISymbolAliases:
+		return 1
+	case CLIImportPaths:
+		return 1
+	case CLIUpgradeImpact:
+		return 1
+	case []CLIScope:
+		return len(r)
+	case CLITypeHierarchy:
+		return 1
+	case []CLIExtensionBinding:
+		return len(r)
+	case []CLIReexport:
+		return len(r)
+	case CLICallGraph:
+		return 1
+	case []CLICallGraphNode:
+		return len(r)
+	case []CLIBlockingCall:
+		return len(r)
+	case []CLIPanicSite:
+		return len(r)
+	case CLIDependencyGraph:
+		return 1
+	case []CLICycle:
+		return len(r)
+	case []CLIHotspot:
+		return len(r)
+	case []CLIClonePair:
+		return len(r)
+	case []CLICrateDuplicate:
+		return len(r)
+	case []CLIDeprecatedUsage:
+		return len(r)
+	case []CLIFFIBoundary:
+		return len(r)
+	case []CLIErrorFlow:
+		return len(r)
+	case []CLIFeatureAPI:
+		return len(r)
+	case []CLIComplexity:
+		return len(r)
+	case []CLIPackageMetrics:
+		return len(r)
+	case []CLIOwnerImpact:
+		return len(r)
+	case []CLITargetImpact:
+		return len(r)
+	case []CLISnapshotInfo:
+		return len(r)
+	case []CLIStaleSymbol:
+		return len(r)
+	case []CLIBusFactor:
+		return len(r)
+	case []CLIHotSymbol:
+		return len(r)
+	case []CLICoveredSymbol:
+		return len(r)
+	case CLISymbolVisibility:
+		return 1
+	case []CLIShadow:
+		return len(r)
+	case []CLIGlobCollision:
+		return len(r)
+	case []CLITraitImpls:
+		return len(r)
+	case []CLIImplBlock:
+		return len(r)
+	case []CLITraitObjectSafety:
+		return len(r)
+	case []CLIEntryPoint:
+		return len(r)
+	case []CLITestRegion:
+		return len(r)
+	case []CLIHistoryPoint:
+		return len(r)
+	case []CLINewtype:
+		return len(r)
+	case CLIFieldGraph:
+		return 1
+	case []CLITraitTree:
+		return len(r)
+	case []CLISemanticHit:
+		return len(r)
+	case []CLISnippet:
+		return len(r)
+	case []CLIUsageExamples:
+		return len(r)
+	case CLIAnswer:
+		return 1
+	case []CLIStructuralMatch:
+		return len(r)
+	case CLIModuleMatrix:
+		return 1
+	case CLINoStdReport:
+		return len(r.Dependencies)
+	case CLIBinarySizeReport:
+		return len(r.Functions)
+	case []CLIInstantiationHotspot:
+		return len(r)
+	case CLILintReport:
+		return len(r.Findings)
+	case CLIRenamePlan:
+		return len(r.Edits)
+	case CLIApplyResult:
+		return len(r.Files)
+	case []CLIOutlineFile:
+		return len(r)
+	case CLIRepoMap:
+		return r.Symbols
+	case CLIUnresolvedReport:
+		return len(r.References)
+	case CLIUnsafeReport:
+		return len(r.Usages)
+	case CLIGateReport:
+		return len(r.Gates)
+	case nil:
+		return 0
+	default:
+		return 1
+	}
+}
+
+// validFormats lists accepted values for --format.
+var validFormats = []string{"json", "text", "ndjson", "markdown", "sarif", "github", "gitlab", "csv", "html"}
+
+// validateFormat checks that the --format flag value is recognized.
+func validateFormat(format string) error {
+	for _, f := range validFormats {
+		if format == f {
+			return nil
+		}
+	}
+	return fmt.Errorf("invalid format %q: must be one of %s", format, strings.Join(validFormats, ", "))
+}
+
+// outputResultNDJSON writes each element of a slice result as one compact
+// JSON line, or a non-slice result as a single line. There is no envelope:
+// total_count is omitted so output can be piped straight into jq.
+func outputResultNDJSON(result CLIResult) error {
+	enc := json.NewEncoder(os.Stdout)
+	v := reflect.ValueOf(result.Results)
+	if !v.IsValid() {
+		return nil
+	}
+	if v.Kind() != reflect.Slice {
+		return enc.Encode(result.Results)
+	}
+	for i := 0; i < v.Len(); i++ {
+		if err := enc.Encode(v.Index(i).Interface()); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// outputResultMarkdown writes results as Markdown. Outlines, snippets, and
+// usage examples support it: an outline is one "## file" heading per file
+// with the items as a nested bullet list; a snippet or a symbol's examples
+// are their own Markdown rendering.
+func outputResultMarkdown(result CLIResult) error {
+	w := os.Stdout
+	switch v := result.Results.(type) {
+	case []CLIOutlineFile:
+		for i, f := range v {
+			if i > 0 {
+				fmt.Fprintln(w)
+			}
+			fmt.Fprintf(w, "## %s\n\n", f.File)
+			writeOutlineMarkdown(w, f.Items, 0)
+		}
+	case []CLISnippet:
+		formatSnippetsText(w, v)
+	case []CLIUsageExamples:
+		formatUsageExamplesText(w, v)
+	case CLIAnswer:
+		formatAnswerText(w, v)
+	case CLIReport:
+		writeReportMarkdown(w, v)
+	default:
+		return fmt.Errorf("unsupported result type for markdown format: %T", result.Results)
+	}
+	return nil
+}
+
+// outputResultCSV writes a module matrix as CSV: a header row of module
+// names, then one row per module with its name and counts. Other results
+// are not supported.
+func outputResultCSV(result CLIResult) error {
+	m, ok := result.Results.(CLIModuleMatrix)
+	if !ok {
+		return fmt.Errorf("unsupported result type for csv format: %T", result.Results)
+	}
+	cw := csv.NewWriter(os.Stdout)
+	if err := cw.Write(append([]string{"module"}, m.Modules...)); err != nil {
+		return err
+	}
+	for i, name := range m.Modules {
+		row := []string{name}
+		for _, n := range m.Counts[i] {
+			row = append(row, strconv.Itoa(n))
+		}
+		if err := cw.Write(row); err != nil {
+			return err
+		}
+	}
+	cw.Flush()
+	return cw.Error()
+}
+
+// outputResultHTML writes a module matrix as a standalone HTML page with
+// one table, its cells shaded by count. Other results are not supported.
+func outputResultHTML(result CLIResult) error {
+	m, ok := result.Results.(CLIModuleMatrix)
+	if !ok {
+		return fmt.Errorf("unsupported result type for html format: %T", result.Results)
+	}
+	most := 0
+	for _, row := range m.Counts {
+		for _, n := range row {
+			most = max(most, n)
+		}
+	}
+
+	w := os.Stdout
+	fmt.Fprintln(w, "<!DOCTYPE html>")
+	fmt.Fprintln(w, "<html><head><meta charset=\"utf-8\"><title>Module dependency matrix</title>")
+	fmt.Fprintln(w, "<style>table{border-collapse:collapse;font:12px monospace}td,th{border:1px solid #ccc;padding:2px 6px;text-align:right}th.module{text-align:left}td.self{background:#eee}</style>")
+	fmt.Fprintln(w, "</head><body>")
+	fmt.Fprintf(w, "<p>%d module(s), %d dependency edge(s). The cell in row A, column B counts references from A to B.</p>\n", len(m.Modules), m.Edges)
+	fmt.Fprint(w, "<table>\n<tr><th></th><th></th>")
+	for i, name := range m.Modules {
+		fmt.Fprintf(w, "<th title=\"%s\">%d</th>", html.EscapeString(name), i+1)
+	}
+	fmt.Fprintln(w, "</tr>")
+	for i, name := range m.Modules {
+		fmt.Fprintf(w, "<tr><th>%d</th><th class=\"module\">%s</th>", i+1, html.EscapeString(name))
+		for j, n := range m.Counts[i] {
+			switch {
+			case i == j:
+				fmt.Fprint(w, "<td class=\"self\"></td>")
+			case n == 0:
+				fmt.Fprint(w, "<td></td>")
+			default:
+				// Shade from light to dark blue by the count relative to the largest.
+				alpha := 0.15 + 0.85*float64(n)/float64(most)
+				fmt.Fprintf(w, "<td style=\"background:rgba(33,102,172,%.2f)\">%d</td>", alpha, n)
+			}
+		}
+		fmt.Fprintln(w, "</tr>")
+	}
+	fmt.Fprintln(w, "</table>\n</body></html>")
+	return nil
+}
+
+// writeOutlineMarkdown writes outline nodes as bullets indented two spaces
+// per depth.
+func writeOutlineMarkdown(w io.Writer, nodes []CLIOutlineNode, depth int) {
+	indent := strings.Repeat("  ", depth)
+	for _, n := range nodes {
+		if n.SymbolID == nil {
+			fmt.Fprintf(w, "%s- `%s` (line %d)\n", indent, n.Name, n.StartLine)
+		} else {
+			fmt.Fprintf(w, "%s- %s `%s` (line %d)\n", indent, n.Kind, n.Name, n.StartLine)
+		}
+		writeOutlineMarkdown(w, n.Children, depth+1)
+	}
+}
+
+// writeReportMarkdown writes an architecture report as a Markdown document
+// with a section per part of the report. Lines are 1-based.
+func writeReportMarkdown(w io.Writer, r CLIReport) {
+	fmt.Fprintf(w, "# %s\n\n## Overview\n\n", r.Title)
+	fmt.Fprintln(w, "| Language | Files | Lines | Symbols |\n|---|---:|---:|---:|")
+	var files, lines, symbols int
+	for _, ls := range r.Languages {
+		fmt.Fprintf(w, "| %s | %d | %d | %d |\n", markdownCell(ls.Language), ls.FileCount, ls.LineCount, ls.SymbolCount)
+		files, lines, symbols = files+ls.FileCount, lines+ls.LineCount, symbols+ls.SymbolCount
+	}
+	fmt.Fprintf(w, "| **Total** | %d | %d | %d |\n\n", files, lines, symbols)
+	fmt.Fprintf(w, "%d packages or modules.\n", r.PackageCount)
+
+	fmt.Fprint(w, "\n## Modules\n\n")
+	if len(r.Modules) == 0 {
+		fmt.Fprintln(w, "No module dependencies were indexed.")
+	} else {
+		fmt.Fprintln(w, "| Module | Used by | Depends on | Instability | Notes |\n|---|---:|---:|---:|---|")
+		for _, m := range r.Modules {
+			fmt.Fprintf(w, "| `%s` | %d | %d | %.2f | %s |\n",
+				m.Package, m.Afferent, m.Efferent, m.Instability, markdownCell(strings.Join(m.Violations, "; ")))
+		}
+	}
+
+	symbolTable := func(title, empty string, syms []CLISymbol) {
+		fmt.Fprintf(w, "\n## %s\n\n", title)
+		if len(syms) == 0 {
+			fmt.Fprintln(w, empty)
+			return
+		}
+		fmt.Fprintln(w, "| Symbol | Kind | Location | References (external) |\n|---|---|---|---:|")
+		for _, s := range syms {
+			fmt.Fprintf(w, "| `%s` | %s | %s | %d (%d) |\n",
+				s.Name, s.Kind, markdownLocation(s.File, s.StartLine), s.RefCount, s.ExternalRefCount)
+		}
+	}
+	symbolTable("Top Types", "No types were indexed.", r.TopTypes)
+	apiTitle := "Public API"
+	if r.PublicAPICount > len(r.PublicAPI) {
+		apiTitle = fmt.Sprintf("Public API (top %d of %d)", len(r.PublicAPI), r.PublicAPICount)
+	}
+	symbolTable(apiTitle, "No public symbols were indexed.", r.PublicAPI)
+
+	fmt.Fprint(w, "\n## Hotspots\n\n")
+	if len(r.Hotspots) == 0 {
+		fmt.Fprintln(w, "No hotspots.")
+	} else {
+		fmt.Fprintln(w, "| Symbol | Kind | Location | References (external) | Callers | Callees |\n|---|---|---|---:|---:|---:|")
+		for _, h := range r.Hotspots {
+			s := h.Symbol
+			fmt.Fprintf(w, "| `%s` | %s | %s | %d (%d) | %d | %d |\n",
+				s.Name, s.Kind, markdownLocation(s.File, s.StartLine), s.RefCount, s.ExternalRefCount, h.CallerCount, h.CalleeCount)
+		}
+	}
+
+	fmt.Fprint(w, "\n## Findings\n\n")
+	if len(r.Findings) == 0 {
+		fmt.Fprintln(w, "All rules are off.")
+		return
+	}
+	fmt.Fprintln(w, "| Rule | Severity | Findings |\n|---|---|---:|")
+	for _, rule := range r.Findings {
+		fmt.Fprintf(w, "| %s | %s | %d |\n", rule.Rule, rule.Severity, rule.Count)
+	}
+	for _, rule := range r.Findings {
+		if rule.Count == 0 {
+			continue
+		}
+		fmt.Fprintf(w, "\n### %s\n\n", rule.Rule)
+		for _, f := range rule.Findings {
+			fmt.Fprintf(w, "- %s: %s\n", markdownLocation(f.Location.File, f.Location.StartLine), f.Message)
+		}
+		if more := rule.Count - len(rule.Findings); more > 0 {
+			fmt.Fprintf(w, "- ... and %d more\n", more)
+		}
+	}
+}
+
+// markdownLocation formats a 0-based location as `file:line` with a 1-based
+// line.
+func markdownLocation(file string, line int) string {
+	if file == "" {
+		return ""
+	}
+	return fmt.Sprintf("`%s:%d`", file, line+1)
+}
+
+// markdownCell escapes the pipes in s for a Markdown table cell.
+func markdownCell(s string) string {
+	return strings.ReplaceAll(s, "|", "\\|")
 }
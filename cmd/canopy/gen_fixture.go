@@ -0,0 +1,73 @@
+package main
+
+import (
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/jward/canopy/internal/workload"
+	"github.com/spf13/cobra"
+)
+
+var (
+	flagFixtureLevel     int
+	flagFixtureFiles     int
+	flagFixtureFunctions int
+	flagFixtureSeed      int64
+)
+
+var genFixtureCmd = &cobra.Command{
+	Use:   "gen-fixture <dir>",
+	Short: "Generate a Rust resolver fixture with its expected results",
+	Long: "Generates a Rust crate of --files modules into <dir>/src and the definitions, trait implementations, and calls\n" +
+		"the resolver should find into <dir>/golden.json, in the format of the golden tests under testdata. Each level adds\n" +
+		"a resolution feature to those below it: " + fixtureLevelsHelp() + ".\n" +
+		"The same flags always generate the same fixture; vary --seed to fuzz the resolver.",
+	Args: cobra.ExactArgs(1),
+	RunE: runGenFixture,
+}
+
+func init() {
+	genFixtureCmd.Flags().IntVar(&flagFixtureLevel, "level", workload.MaxFixtureLevel, fmt.Sprintf("resolution features to exercise, 1 to %d", workload.MaxFixtureLevel))
+	genFixtureCmd.Flags().IntVar(&flagFixtureFiles, "files", 4, "number of modules to generate")
+	genFixtureCmd.Flags().IntVar(&flagFixtureFunctions, "functions", 4, "number of functions per module")
+	genFixtureCmd.Flags().Int64Var(&flagFixtureSeed, "seed", 1, "seed of the generated call structure")
+}
+
+// fixtureLevelsHelp lists the fixture levels as "1 cross-file imports, 2 ...".
+func fixtureLevelsHelp() string {
+	var levels []string
+	for level := 1; level <= workload.MaxFixtureLevel; level++ {
+		levels = append(levels, fmt.Sprintf("%d %s", level, workload.FixtureLevels[level]))
+	}
+	return strings.Join(levels, ", ")
+}
+
+func runGenFixture(cmd *cobra.Command, args []string) error {
+	dir, err := filepath.Abs(args[0])
+	if err != nil {
+		return outputError("gen-fixture", err)
+	}
+	stats, err := workload.GenerateFixture(dir, workload.FixtureSpec{
+		Level:     flagFixtureLevel,
+		Files:     flagFixtureFiles,
+		Functions: flagFixtureFunctions,
+		Seed:      flagFixtureSeed,
+	})
+	if err != nil {
+		return outputError("gen-fixture", err)
+	}
+	return outputResult(CLIResult{
+		Command: "gen-fixture",
+		Results: CLIFixture{
+			Dir:             dir,
+			Level:           flagFixtureLevel,
+			Features:        workload.FixtureLevels[1 : flagFixtureLevel+1],
+			Seed:            flagFixtureSeed,
+			Files:           stats.Files,
+			Definitions:     stats.Definitions,
+			Calls:           stats.Calls,
+			Implementations: stats.Implementations,
+		},
+	})
+}
@@ -0,0 +1,86 @@
+package main
+
+import (
+	"fmt"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var globCollisionsCmd = &cobra.Command{
+	Use:   "glob-collisions",
+	Short: "Find Rust names two glob imports bring in, with the explicit import that settles each",
+	Long: "Lists names that two or more glob imports of a Rust file bring in as different items of the same\n" +
+		"namespace and that the file uses, which rustc rejects as ambiguous. Each comes with the globs it comes\n" +
+		"through, its uses, and an explicit import to add: of the item the uses resolve to, else of the first\n" +
+		"glob's. --fix adds the imports to the files, atomically: if any file changed since indexing, nothing is\n" +
+		"written.",
+	Args: cobra.NoArgs,
+	RunE: runGlobCollisions,
+}
+
+func init() {
+	globCollisionsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	globCollisionsCmd.Flags().BoolVar(&flagFix, "fix", false, "add the suggested imports to the files")
+}
+
+func runGlobCollisions(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("glob-collisions", err)
+	}
+	defer s.Close()
+
+	collisions, err := queryBuilder(s).GlobCollisions(flagPathPrefix)
+	if err != nil {
+		return outputError("glob-collisions", err)
+	}
+
+	if flagFix {
+		res := &canopy.ApplyResult{Files: []string{}}
+		if len(collisions) > 0 {
+			if res, err = canopy.ApplyEdits(canopy.GlobCollisionEdits(collisions)); err != nil {
+				return outputError("glob-collisions", err)
+			}
+			fmt.Fprintf(os.Stderr, "Applied %d edit(s) to %d file(s); re-run 'canopy index' to refresh the database\n",
+				res.Edits, len(res.Files))
+		}
+		count := len(res.Files)
+		return outputResult(CLIResult{
+			Command:    "glob-collisions",
+			Results:    CLIApplyResult{Files: res.Files, Edits: res.Edits},
+			TotalCount: &count,
+		})
+	}
+
+	cliCollisions := make([]CLIGlobCollision, len(collisions))
+	for i, c := range collisions {
+		cliCollisions[i] = globCollisionToCLI(c)
+	}
+	paged, totalCount := paginateSlice(cliCollisions)
+	return outputResult(CLIResult{
+		Command:    "glob-collisions",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// globCollisionToCLI converts a glob collision.
+func globCollisionToCLI(c canopy.GlobCollision) CLIGlobCollision {
+	out := CLIGlobCollision{
+		Name:    c.Name,
+		File:    c.File,
+		Sources: make([]CLIShadowDefinition, len(c.Sources)),
+		Uses:    make([]CLILocation, len(c.Uses)),
+		Import:  c.Import,
+		Fix:     CLITextEdit{CLILocation: locationToCLI(c.Fix.Location, nil), NewText: c.Fix.NewText},
+	}
+	for i, def := range c.Sources {
+		out.Sources[i] = shadowDefinitionToCLI(def)
+	}
+	for i, loc := range c.Uses {
+		out.Uses[i] = locationToCLI(loc, nil)
+	}
+	return out
+}
@@ -0,0 +1,208 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"os"
+	"os/exec"
+	"os/signal"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var historyCmd = &cobra.Command{
+	Use:   "history [repo]",
+	Short: "Index a sample of past revisions and report how the codebase's metrics changed",
+	Long: "Indexes every --every'th first-parent commit from --since to --until (always including both ends)\n" +
+		"straight from git's object database, with no checkout, and reports for each the number of files and\n" +
+		"symbols, the unused symbols (dead code), the package dependency cycles, and the mean and maximum\n" +
+		"cyclomatic complexity of its functions. Use --format json to chart architectural drift over time.\n" +
+		"Each revision is indexed into a temporary database; the repository's own index is left alone.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runHistory,
+}
+
+func init() {
+	historyCmd.Flags().String("since", "", "oldest revision to sample (default: the first commit)")
+	historyCmd.Flags().String("until", "HEAD", "newest revision to sample")
+	historyCmd.Flags().String("every", "10-commits", "sample one revision per this many commits, e.g. 20-commits")
+	historyCmd.Flags().String("languages", "", "comma-separated language filter (e.g. go,rust)")
+}
+
+// parseEvery parses an --every value: a commit count, optionally suffixed
+// with "commits" or "-commits".
+func parseEvery(value string) (int, error) {
+	num := strings.TrimSuffix(strings.TrimSuffix(value, "commits"), "-")
+	n, err := strconv.Atoi(num)
+	if err != nil || n <= 0 {
+		return 0, fmt.Errorf("invalid --every %q: must be a positive commit count such as 20-commits", value)
+	}
+	return n, nil
+}
+
+// historyRevisions returns every'th first-parent commit from since to until,
+// oldest first, always including both ends. An empty since starts at the
+// first commit. A revision starting with "-" is rejected rather than passed
+// to git as an option.
+func historyRevisions(repo, since, until string, every int) ([]string, error) {
+	for _, rev := range []string{since, until} {
+		if strings.HasPrefix(rev, "-") {
+			return nil, fmt.Errorf("invalid revision %q: must not start with -", rev)
+		}
+	}
+	rangeArg := until
+	if since != "" {
+		rangeArg = since + ".." + until
+	}
+	out, err := gitOutput(repo, "rev-list", "--first-parent", "--reverse", "--end-of-options", rangeArg)
+	if err != nil {
+		return nil, err
+	}
+	commits := strings.Fields(out)
+	if since != "" {
+		first, err := gitOutput(repo, "rev-parse", "--verify", "--end-of-options", since+"^{commit}")
+		if err != nil {
+			return nil, err
+		}
+		commits = append([]string{strings.TrimSpace(first)}, commits...)
+	}
+	var sampled []string
+	for i := 0; i < len(commits); i += every {
+		sampled = append(sampled, commits[i])
+	}
+	if len(commits) > 0 && (len(commits)-1)%every != 0 {
+		sampled = append(sampled, commits[len(commits)-1])
+	}
+	return sampled, nil
+}
+
+// gitOutput runs git in repo and returns its standard output.
+func gitOutput(repo string, args ...string) (string, error) {
+	cmd := exec.Command("git", args...)
+	cmd.Dir = repo
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return "", fmt.Errorf("git %s: %w: %s", args[0], err, strings.TrimSpace(stderr.String()))
+	}
+	return stdout.String(), nil
+}
+
+func runHistory(cmd *cobra.Command, args []string) error {
+	since, _ := cmd.Flags().GetString("since")
+	until, _ := cmd.Flags().GetString("until")
+	everyFlag, _ := cmd.Flags().GetString("every")
+	every, err := parseEvery(everyFlag)
+	if err != nil {
+		return outputError("history", err)
+	}
+	repo, err := resolveTargetDir(args)
+	if err != nil {
+		return outputError("history", err)
+	}
+
+	languages := projectConfig.Index.Languages
+	if flag, _ := cmd.Flags().GetString("languages"); flag != "" {
+		languages = strings.Split(flag, ",")
+		for i := range languages {
+			languages[i] = strings.TrimSpace(languages[i])
+		}
+	}
+
+	revisions, err := historyRevisions(repo, since, until, every)
+	if err != nil {
+		return outputError("history", err)
+	}
+	tmp, err := os.MkdirTemp("", "canopy-history-")
+	if err != nil {
+		return outputError("history", err)
+	}
+	defer os.RemoveAll(tmp)
+
+	ctx, stop := signal.NotifyContext(context.Background(), os.Interrupt)
+	defer stop()
+
+	points := make([]CLIHistoryPoint, 0, len(revisions))
+	for i, rev := range revisions {
+		fmt.Fprintf(os.Stderr, "[%d/%d] indexing %s\n", i+1, len(revisions), rev[:min(12, len(rev))])
+		point, err := historyPoint(ctx, repo, rev, filepath.Join(tmp, rev+".db"), languages)
+		if err != nil {
+			return outputError("history", fmt.Errorf("%s: %w", rev, err))
+		}
+		points = append(points, *point)
+	}
+	return outputResult(CLIResult{Command: "history", Results: points})
+}
+
+// historyPoint indexes revision rev of repo into a database at dbPath and
+// returns its metrics.
+func historyPoint(ctx context.Context, repo, rev, dbPath string, languages []string) (*CLIHistoryPoint, error) {
+	meta, err := gitOutput(repo, "show", "-s", "--format=%H%x00%cI%x00%s", "--end-of-options", rev)
+	if err != nil {
+		return nil, err
+	}
+	fields := strings.SplitN(strings.TrimSuffix(meta, "\n"), "\x00", 3)
+	if len(fields) != 3 {
+		return nil, fmt.Errorf("git show: unexpected output %q", meta)
+	}
+	point := &CLIHistoryPoint{Commit: fields[0], Date: fields[1], Subject: fields[2]}
+
+	fsys, err := canopy.GitFileSystem(ctx, repo, point.Commit)
+	if err != nil {
+		return nil, err
+	}
+	opts := []canopy.Option{canopy.WithScriptsFS(scripts.FS), canopy.WithFileSystem(fsys)}
+	if len(languages) > 0 {
+		opts = append(opts, canopy.WithLanguages(languages...))
+	}
+	if len(projectConfig.Index.Exclude) > 0 {
+		opts = append(opts, canopy.WithExclude(projectConfig.Index.Exclude...))
+	}
+	engine, err := canopy.New(dbPath, "", opts...)
+	if err != nil {
+		return nil, fmt.Errorf("creating engine: %w", err)
+	}
+	defer engine.Close()
+	if err := engine.IndexDirectory(ctx, "/"); err != nil {
+		return nil, fmt.Errorf("indexing: %w", err)
+	}
+	if err := engine.Resolve(ctx); err != nil {
+		return nil, fmt.Errorf("resolving: %w", err)
+	}
+
+	qb := engine.Query()
+	stats, err := qb.IndexStats()
+	if err != nil {
+		return nil, err
+	}
+	point.Files, point.Symbols = stats.Files, stats.Symbols
+
+	limit := 1
+	unused, err := qb.UnusedSymbols(canopy.SymbolFilter{ExcludeNames: projectConfig.Analysis.EntryPoints}, canopy.Sort{}, canopy.Pagination{Limit: &limit})
+	if err != nil {
+		return nil, err
+	}
+	point.Unused = unused.TotalCount
+
+	cycles, err := qb.CircularDependencies()
+	if err != nil {
+		return nil, err
+	}
+	point.Cycles = len(cycles)
+
+	complexity, err := qb.ComplexitySummary(canopy.SymbolFilter{})
+	if err != nil {
+		return nil, err
+	}
+	point.Functions = complexity.Functions
+	point.MeanCyclomatic = complexity.MeanCyclomatic
+	point.MaxCyclomatic = complexity.MaxCyclomatic
+	return point, nil
+}
@@ -0,0 +1,66 @@
+package main
+
+import (
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseEvery(t *testing.T) {
+	t.Parallel()
+	for _, v := range []string{"20", "20commits", "20-commits"} {
+		n, err := parseEvery(v)
+		assert.NoError(t, err, v)
+		assert.Equal(t, 20, n, v)
+	}
+	for _, v := range []string{"", "0-commits", "-3", "weekly"} {
+		_, err := parseEvery(v)
+		assert.Error(t, err, v)
+	}
+}
+
+func TestHistoryRevisions_SamplesBothEnds(t *testing.T) {
+	if _, err := exec.LookPath("git"); err != nil {
+		t.Skip("git not available")
+	}
+	dir := t.TempDir()
+	git := func(args ...string) string {
+		t.Helper()
+		cmd := exec.Command("git", append([]string{"-c", "user.name=Alice", "-c", "user.email=alice@example.com"}, args...)...)
+		cmd.Dir = dir
+		out, err := cmd.CombinedOutput()
+		require.NoError(t, err, string(out))
+		return strings.TrimSpace(string(out))
+	}
+	git("init", "-q")
+	var commits []string
+	for i := 0; i < 6; i++ {
+		require.NoError(t, os.WriteFile(filepath.Join(dir, "n.txt"), []byte{byte('0' + i)}, 0o644))
+		git("add", ".")
+		git("commit", "-q", "-m", "commit")
+		commits = append(commits, git("rev-parse", "HEAD"))
+	}
+	git("tag", "start", commits[1])
+
+	revs, err := historyRevisions(dir, "", "HEAD", 2)
+	require.NoError(t, err)
+	assert.Equal(t, []string{commits[0], commits[2], commits[4], commits[5]}, revs)
+
+	revs, err = historyRevisions(dir, "start", "HEAD", 2)
+	require.NoError(t, err)
+	assert.Equal(t, []string{commits[1], commits[3], commits[5]}, revs)
+
+	revs, err = historyRevisions(dir, "start", commits[3], 10)
+	require.NoError(t, err)
+	assert.Equal(t, []string{commits[1], commits[3]}, revs)
+
+	_, err = historyRevisions(dir, "--all", "HEAD", 2)
+	assert.ErrorContains(t, err, "must not start with -")
+	_, err = historyRevisions(dir, "", "--output=x", 2)
+	assert.ErrorContains(t, err, "must not start with -")
+}
@@ -0,0 +1,136 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var implsCmd = &cobra.Command{
+	Use:   "impls",
+	Short: "List Rust trait impls by trait, with their owning crates and overlaps",
+	Long: "Lists the Rust trait impls (impl Trait for Type) grouped by trait. For each impl it shows the crates\n" +
+		"declaring the impl, the trait and the self type, and why the orphan rule lets the impl's crate define it:\n" +
+		"  trait           the crate declares the trait\n" +
+		"  type            the crate declares the self type, or the type under &, &mut, Box or Pin\n" +
+		"  trait_argument  the crate declares a type among the trait's arguments\n" +
+		"An impl whose crate owns neither is reported as an orphan. Pairs of impls of a trait whose types\n" +
+		"unify are reported as overlaps:\n" +
+		"  identical  the same type with the same trait arguments\n" +
+		"  blanket    one impl is for any type (impl<T> Trait for T)\n" +
+		"  generic    the types unify once generic parameters are substituted\n" +
+		"Bounds, where clauses and cfg attributes are not checked, so an overlap is a candidate to review.",
+	Args: cobra.NoArgs,
+	RunE: runImpls,
+}
+
+var implBlocksCmd = &cobra.Command{
+	Use:   "impl-blocks [<file> <line> <col>]",
+	Short: "List the impl blocks of a Rust type with the methods each defines",
+	Long: "Lists the impl blocks defining methods of a Rust type, in source order: inherent impls (impl Circle)\n" +
+		"and trait impls (impl Drawable for Circle) with the trait they implement, each with its methods and\n" +
+		"associated functions. Together they are all the methods of the type and the trait each comes from.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runImplBlocks,
+}
+
+func init() {
+	implsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	implBlocksCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+}
+
+func runImpls(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("impls", err)
+	}
+	defer s.Close()
+
+	traits, err := queryBuilder(s).ImplAudit(flagPathPrefix)
+	if err != nil {
+		return outputError("impls", err)
+	}
+
+	cliTraits := make([]CLITraitImpls, len(traits))
+	for i, t := range traits {
+		cliTraits[i] = CLITraitImpls{Trait: t.Trait, TraitSymbol: optionalSymbolToCLI(t.TraitSymbol)}
+		cliTraits[i].Impls = make([]CLITraitImpl, len(t.Impls))
+		for j, im := range t.Impls {
+			cliTraits[i].Impls[j] = traitImplToCLI(im)
+		}
+		for _, o := range t.Overlaps {
+			cliTraits[i].Overlaps = append(cliTraits[i].Overlaps, CLIImplOverlap{Kind: o.Kind, First: o.First, Second: o.Second})
+		}
+	}
+	paged, totalCount := paginateSlice(cliTraits)
+	return outputResult(CLIResult{
+		Command:    "impls",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runImplBlocks(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("impl-blocks", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("impl-blocks", err)
+	}
+	blocks, err := qb.ImplBlocks(symID)
+	if err != nil {
+		return outputError("impl-blocks", err)
+	}
+
+	cliBlocks := make([]CLIImplBlock, len(blocks))
+	for i, b := range blocks {
+		cliBlocks[i] = CLIImplBlock{
+			Location:    locationToCLI(b.Location, nil),
+			Header:      b.Header,
+			Trait:       b.Trait,
+			TraitSymbol: optionalSymbolToCLI(b.TraitSymbol),
+			Methods:     make([]CLISymbol, len(b.Methods)),
+		}
+		for j, m := range b.Methods {
+			cliBlocks[i].Methods[j] = symbolResultToCLI(m)
+		}
+	}
+	paged, totalCount := paginateSlice(cliBlocks)
+	return outputResult(CLIResult{
+		Command:    "impl-blocks",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// traitImplToCLI converts a trait impl.
+func traitImplToCLI(im canopy.TraitImpl) CLITraitImpl {
+	return CLITraitImpl{
+		Location:    locationToCLI(im.Location, nil),
+		Crate:       im.Crate,
+		Trait:       im.Trait,
+		Type:        im.Type,
+		Generics:    im.Generics,
+		Negative:    im.Negative,
+		TraitSymbol: optionalSymbolToCLI(im.TraitSymbol),
+		TypeSymbol:  optionalSymbolToCLI(im.TypeSymbol),
+		TraitCrate:  im.TraitCrate,
+		TypeCrate:   im.TypeCrate,
+		OwnedBy:     im.OwnedBy,
+		Orphan:      im.Orphan,
+	}
+}
+
+// optionalSymbolToCLI converts a symbol that may be absent.
+func optionalSymbolToCLI(sr *canopy.SymbolResult) *CLISymbol {
+	if sr == nil {
+		return nil
+	}
+	sym := symbolResultToCLI(*sr)
+	return &sym
+}
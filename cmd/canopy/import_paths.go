@@ -0,0 +1,67 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var importPathsCmd = &cobra.Command{
+	Use:   "import-paths [<file> <line> <col>]",
+	Short: "List the paths a Rust item can be imported by, with their re-export chains",
+	Long: "Lists the canonical path of a Rust item and every path a pub use re-exports it under. Each path shows\n" +
+		"the chain of pub use hops from the definition, with the use path and position of each, and whether\n" +
+		"every hop is pub, so the path can be imported from other crates.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runImportPaths,
+}
+
+func init() {
+	importPathsCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+}
+
+func runImportPaths(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("import-paths", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("import-paths", err)
+	}
+	paths, err := qb.ImportPaths(symID)
+	if err != nil {
+		return outputError("import-paths", err)
+	}
+	if paths == nil {
+		return outputResult(CLIResult{Command: "import-paths", Results: nil})
+	}
+
+	result := CLIImportPaths{
+		Symbol:        symbolResultToCLI(paths.Symbol),
+		CanonicalPath: paths.CanonicalPath,
+		Paths:         make([]CLIImportPath, len(paths.Paths)),
+	}
+	for i, p := range paths.Paths {
+		hops := make([]CLIReexportHop, len(p.Hops))
+		for j, h := range p.Hops {
+			hops[j] = CLIReexportHop{
+				Path:       h.Path,
+				Source:     h.Source,
+				Visibility: h.Visibility,
+				File:       h.FilePath,
+				Line:       h.Line,
+				Col:        h.Col,
+			}
+		}
+		result.Paths[i] = CLIImportPath{Path: p.Path, Public: p.Public, Hops: hops}
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "import-paths",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
@@ -2,6 +2,7 @@ package main_test
 
 import (
 	"database/sql"
+	"encoding/json"
 	"os"
 	"os/exec"
 	"path/filepath"
@@ -244,6 +245,21 @@ func TestIndex_NonExistentDirectory(t *testing.T) {
 	assert.Contains(t, string(out), "not found", "error should mention 'not found'")
 }
 
+func TestIndex_GitRevRejectsOption(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin := buildBinary(t)
+	fixture := createGoFixture(t)
+
+	cmd := exec.Command(bin, "index", "--git-rev=--all", fixture)
+	cmd.Dir = fixture
+	out, err := cmd.CombinedOutput()
+	require.Error(t, err, "a revision starting with - must be rejected")
+	assert.Contains(t, string(out), "must not start with -")
+	assert.NoFileExists(t, filepath.Join(fixture, ".canopy", "index.db"), "nothing is indexed")
+}
+
 func TestIndex_StderrTiming(t *testing.T) {
 	if testing.Short() {
 		t.Skip("skipping integration test")
@@ -264,6 +280,40 @@ func TestIndex_StderrTiming(t *testing.T) {
 	assert.Contains(t, output, "Database:")
 }
 
+func TestIndex_TraceOutput(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin := buildBinary(t)
+	fixture := createGoFixture(t)
+	tracePath := filepath.Join(t.TempDir(), "trace.json")
+
+	cmd := exec.Command(bin, "index", "--trace-output", tracePath, fixture)
+	cmd.Dir = fixture
+	out, err := cmd.CombinedOutput()
+	require.NoError(t, err, "index failed: %s", string(out))
+	assert.Contains(t, string(out), "Trace: "+tracePath)
+
+	data, err := os.ReadFile(tracePath)
+	require.NoError(t, err)
+	var trace struct {
+		TraceEvents []struct {
+			Name  string         `json:"name"`
+			Phase string         `json:"ph"`
+			Args  map[string]any `json:"args"`
+		} `json:"traceEvents"`
+	}
+	require.NoError(t, json.Unmarshal(data, &trace))
+	names := map[string]bool{}
+	for _, ev := range trace.TraceEvents {
+		assert.Equal(t, "X", ev.Phase)
+		names[ev.Name] = true
+	}
+	for _, name := range []string{"index_directory", "extract_file", "resolve"} {
+		assert.True(t, names[name], name)
+	}
+}
+
 func TestIndex_ScriptsDir(t *testing.T) {
 	if testing.Short() {
 		t.Skip("skipping integration test")
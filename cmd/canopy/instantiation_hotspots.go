@@ -0,0 +1,58 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var instantiationHotspotsCmd = &cobra.Command{
+	Use:   "instantiation-hotspots",
+	Short: "List generic functions instantiated with unusually many distinct type arguments",
+	Long: "Lists the generic functions and methods used with many distinct type argument lists, each of which the\n" +
+		"compiler monomorphizes into its own copy: the likely compile-time and binary-size hotspots. Type arguments\n" +
+		"are found as by the instantiations command, so the counts are a lower bound. By default a generic is listed\n" +
+		"when its distinct count is two standard deviations above the mean (and at least 4); --min-distinct sets\n" +
+		"the threshold instead. Sorted by distinct argument lists, or with --sort sites by number of uses.",
+	Args: cobra.NoArgs,
+	RunE: runInstantiationHotspots,
+}
+
+func init() {
+	instantiationHotspotsCmd.Flags().Int("min-distinct", 0, "list generics with at least this many distinct type argument lists (0 for automatic)")
+	instantiationHotspotsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runInstantiationHotspots(cmd *cobra.Command, args []string) error {
+	minDistinct, _ := cmd.Flags().GetInt("min-distinct")
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("instantiation-hotspots", err)
+	}
+	defer s.Close()
+
+	hotspots, err := queryBuilder(s).InstantiationHotspots(minDistinct, flagSort, flagPathPrefix)
+	if err != nil {
+		return outputError("instantiation-hotspots", err)
+	}
+
+	results := make([]CLIInstantiationHotspot, len(hotspots))
+	for i, h := range hotspots {
+		results[i] = CLIInstantiationHotspot{
+			Symbol:     symbolResultToCLI(h.Symbol),
+			TypeParams: h.TypeParams,
+			Distinct:   h.Distinct,
+			Sites:      h.Sites,
+			Unknown:    h.Unknown,
+			Usages:     make([]CLIInstantiationUsage, len(h.Usages)),
+		}
+		for j, u := range h.Usages {
+			results[i].Usages[j] = CLIInstantiationUsage{TypeArgs: u.TypeArgs, Count: len(u.Sites), Sites: u.Sites}
+		}
+	}
+	paged, totalCount := paginateSlice(results)
+	return outputResult(CLIResult{
+		Command:    "instantiation-hotspots",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
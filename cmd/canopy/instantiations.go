@@ -0,0 +1,66 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var instantiationsCmd = &cobra.Command{
+	Use:   "instantiations [<file> <line> <col>]",
+	Short: "Show where a generic function or type is instantiated, and with which types",
+	Long: "Lists the calls and type references of a generic function or type with the type arguments each one\n" +
+		"supplies, and the distinct argument lists by how often they are used. Arguments written out at the\n" +
+		"site (convert::<u16>(x), Stack[int], Box<String>) are read as written; otherwise they are inferred from\n" +
+		"the call's literal and constructor arguments where possible, and shown as ? where not.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runInstantiations,
+}
+
+func init() {
+	instantiationsCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+}
+
+func runInstantiations(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("instantiations", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("instantiations", err)
+	}
+	inst, err := qb.Instantiations(symID)
+	if err != nil {
+		return outputError("instantiations", err)
+	}
+	if inst == nil {
+		return outputResult(CLIResult{Command: "instantiations", Results: nil})
+	}
+
+	result := CLIInstantiations{
+		Symbol:     symbolResultToCLI(inst.Symbol),
+		TypeParams: inst.TypeParams,
+		Sites:      make([]CLIInstantiationSite, len(inst.Sites)),
+		Usages:     make([]CLIInstantiationUsage, len(inst.Usages)),
+	}
+	for i, site := range inst.Sites {
+		result.Sites[i] = CLIInstantiationSite{
+			Location: locationToCLI(site.Location, nil),
+			TypeArgs: site.TypeArgs,
+			Inferred: site.Inferred,
+		}
+		result.Sites[i].Location.Kind = site.Kind
+	}
+	for i, u := range inst.Usages {
+		result.Usages[i] = CLIInstantiationUsage{TypeArgs: u.TypeArgs, Count: len(u.Sites), Sites: u.Sites}
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "instantiations",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
@@ -0,0 +1,213 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"slices"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// Control keys the line editor handles.
+const (
+	keyCtrlA     = 1
+	keyCtrlC     = 3
+	keyCtrlD     = 4
+	keyCtrlE     = 5
+	keyBackspace = 8
+	keyTab       = 9
+	keyCtrlK     = 11
+	keyCtrlL     = 12
+	keyCtrlU     = 21
+	keyCtrlW     = 23
+	keyEscape    = 27
+	keyDelete    = 127
+)
+
+// lineEditor reads lines from a terminal in raw mode, with cursor movement,
+// history, and tab completion. It assumes every rune is one column wide.
+type lineEditor struct {
+	in      *bufio.Reader
+	out     io.Writer
+	prompt  string
+	history []string
+	// complete returns the partial last word of the text before the cursor
+	// and the words it could complete to, each starting with it.
+	complete func(line string) (string, []string)
+}
+
+// addHistory appends line to the history unless it repeats the last entry,
+// and reports whether it did.
+func (e *lineEditor) addHistory(line string) bool {
+	if n := len(e.history); n > 0 && e.history[n-1] == line {
+		return false
+	}
+	e.history = append(e.history, line)
+	if len(e.history) > maxReplHistory {
+		e.history = e.history[len(e.history)-maxReplHistory:]
+	}
+	return true
+}
+
+// readLine prints the prompt and edits a line until Enter, returning it
+// without the newline. Returns io.EOF on Ctrl-D at an empty line.
+func (e *lineEditor) readLine() (string, error) {
+	var buf []rune
+	pos := 0
+	hist := len(e.history)
+	var editing []rune // the new line, while browsing history
+	recall := func(i int) {
+		if hist == len(e.history) {
+			editing = buf
+		}
+		hist = i
+		if hist == len(e.history) {
+			buf = editing
+		} else {
+			buf = []rune(e.history[hist])
+		}
+		pos = len(buf)
+	}
+
+	fmt.Fprint(e.out, e.prompt)
+	for {
+		r, _, err := e.in.ReadRune()
+		if err != nil {
+			return "", err
+		}
+		switch r {
+		case '\r', '\n':
+			fmt.Fprint(e.out, "\n")
+			return string(buf), nil
+		case keyCtrlD:
+			if len(buf) == 0 {
+				fmt.Fprint(e.out, "\n")
+				return "", io.EOF
+			}
+			if pos < len(buf) {
+				buf = slices.Delete(buf, pos, pos+1)
+			}
+		case keyCtrlC:
+			fmt.Fprint(e.out, "^C\n")
+			buf, pos, hist = nil, 0, len(e.history)
+		case keyBackspace, keyDelete:
+			if pos > 0 {
+				buf = slices.Delete(buf, pos-1, pos)
+				pos--
+			}
+		case keyCtrlA:
+			pos = 0
+		case keyCtrlE:
+			pos = len(buf)
+		case keyCtrlK:
+			buf = buf[:pos]
+		case keyCtrlU:
+			buf = slices.Clone(buf[pos:])
+			pos = 0
+		case keyCtrlW:
+			start := pos
+			for start > 0 && unicode.IsSpace(buf[start-1]) {
+				start--
+			}
+			for start > 0 && !unicode.IsSpace(buf[start-1]) {
+				start--
+			}
+			buf = slices.Delete(buf, start, pos)
+			pos = start
+		case keyCtrlL:
+			fmt.Fprint(e.out, "\x1b[H\x1b[2J")
+		case keyTab:
+			buf, pos = e.completeAt(buf, pos)
+		case keyEscape:
+			switch escapeSequence(e.in) {
+			case "[A", "OA":
+				if hist > 0 {
+					recall(hist - 1)
+				}
+			case "[B", "OB":
+				if hist < len(e.history) {
+					recall(hist + 1)
+				}
+			case "[C", "OC":
+				pos = min(pos+1, len(buf))
+			case "[D", "OD":
+				pos = max(pos-1, 0)
+			case "[H", "OH", "[1~":
+				pos = 0
+			case "[F", "OF", "[4~":
+				pos = len(buf)
+			case "[3~":
+				if pos < len(buf) {
+					buf = slices.Delete(buf, pos, pos+1)
+				}
+			}
+		default:
+			if unicode.IsPrint(r) {
+				buf = slices.Insert(buf, pos, r)
+				pos++
+			}
+		}
+		e.refresh(buf, pos)
+	}
+}
+
+// escapeSequence reads the rest of an escape sequence after ESC, e.g. "[A"
+// for the up arrow.
+func escapeSequence(in *bufio.Reader) string {
+	var seq strings.Builder
+	r, _, err := in.ReadRune()
+	if err != nil || (r != '[' && r != 'O') {
+		return ""
+	}
+	seq.WriteRune(r)
+	for {
+		r, _, err := in.ReadRune()
+		if err != nil {
+			return ""
+		}
+		seq.WriteRune(r)
+		// Parameters are digits and semicolons; anything else ends it.
+		if (r < '0' || r > '9') && r != ';' {
+			return seq.String()
+		}
+	}
+}
+
+// completeAt completes the word before the cursor: with one candidate it
+// inserts the rest of it and a space, with several their common prefix, and
+// when that adds nothing it lists them.
+func (e *lineEditor) completeAt(buf []rune, pos int) ([]rune, int) {
+	if e.complete == nil {
+		return buf, pos
+	}
+	word, cands := e.complete(string(buf[:pos]))
+	if len(cands) == 0 {
+		return buf, pos
+	}
+	common := cands[0]
+	for _, c := range cands[1:] {
+		for !strings.HasPrefix(c, common) {
+			_, size := utf8.DecodeLastRuneInString(common)
+			common = common[:len(common)-size]
+		}
+	}
+	insert := []rune(strings.TrimPrefix(common, word))
+	if len(cands) == 1 {
+		insert = append(insert, ' ')
+	}
+	if len(insert) == 0 {
+		fmt.Fprintf(e.out, "\n%s\n", strings.Join(cands, "  "))
+		return buf, pos
+	}
+	return slices.Insert(buf, pos, insert...), pos + len(insert)
+}
+
+// refresh redraws the prompt and line and puts the cursor at pos.
+func (e *lineEditor) refresh(buf []rune, pos int) {
+	fmt.Fprintf(e.out, "\r\x1b[K%s%s", e.prompt, string(buf))
+	if n := len(buf) - pos; n > 0 {
+		fmt.Fprintf(e.out, "\x1b[%dD", n)
+	}
+}
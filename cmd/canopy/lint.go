@@ -0,0 +1,295 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+// defaultBaselineFile is the baseline lint reads and --write-baseline
+// writes, relative to the repo root.
+const defaultBaselineFile = ".canopy-baseline.json"
+
+// lintRules lists the rules lint runs, in report order.
+var lintRules = []string{"unused", "deprecated", "circular_deps", "package_metrics", "glob_collisions"}
+
+// lintPageSize is the page size lint collects paginated findings with.
+const lintPageSize = 500
+
+var (
+	flagBaseline      string
+	flagWriteBaseline bool
+)
+
+var lintCmd = &cobra.Command{
+	Use:   "lint",
+	Short: "Report rule findings that are new since the baseline",
+	Long: "Runs every rule not turned off under [rules] in .canopy.toml (unused, deprecated, circular_deps,\n" +
+		"package_metrics, glob_collisions) and reports its findings, except those suppressed inline and those recorded in the baseline.\n" +
+		"Exits non-zero when a rule at severity error has a new finding.\n\n" +
+		"A finding is suppressed by a comment containing canopy:ignore(<rule>) on its line or the line above,\n" +
+		"for example // canopy:ignore(unused) or # canopy:ignore(unused, deprecated).\n\n" +
+		"--write-baseline records the current findings instead, so that later runs report only new ones. Findings\n" +
+		"are matched by rule, file, and message, not line, so they survive edits that move them.",
+	Args: cobra.NoArgs,
+	RunE: runLint,
+}
+
+func init() {
+	lintCmd.Flags().StringVar(&flagBaseline, "baseline", defaultBaselineFile, "baseline file, relative to the repo root")
+	lintCmd.Flags().BoolVar(&flagWriteBaseline, "write-baseline", false, "record the current findings in the baseline and exit")
+}
+
+// lintBaseline is the baseline file: the findings recorded by
+// --write-baseline.
+type lintBaseline struct {
+	Version  int                 `json:"version"`
+	Findings []lintBaselineEntry `json:"findings"`
+}
+
+// lintBaselineEntry identifies a finding independent of its line.
+type lintBaselineEntry struct {
+	Rule    string `json:"rule"`
+	File    string `json:"file"` // slash-separated, relative to the repo root
+	Message string `json:"message"`
+}
+
+// lintBaselineVersion is the version of the baseline file format.
+const lintBaselineVersion = 1
+
+func runLint(cmd *cobra.Command, args []string) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("lint", fmt.Errorf("getting cwd: %w", err))
+	}
+	repoRoot := findRepoRoot(cwd)
+	baselinePath := flagBaseline
+	if !filepath.IsAbs(baselinePath) {
+		baselinePath = filepath.Join(repoRoot, baselinePath)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("lint", err)
+	}
+	defer s.Close()
+	qb := queryBuilder(s)
+
+	var rules []string
+	var findings []CLILintFinding
+	for _, rule := range lintRules {
+		if projectConfig.ruleSeverity(rule) == severityOff {
+			continue
+		}
+		rules = append(rules, rule)
+		found, err := lintRuleFindings(qb, rule)
+		if err != nil {
+			return outputError("lint", fmt.Errorf("rule %s: %w", rule, err))
+		}
+		findings = append(findings, found...)
+	}
+
+	report := CLILintReport{Rules: rules, Findings: []CLILintFinding{}}
+	sources := map[string][]string{}
+	var kept []CLILintFinding
+	for _, f := range findings {
+		if lintSuppressed(f, sources) {
+			report.Suppressed++
+			continue
+		}
+		kept = append(kept, f)
+	}
+
+	if flagWriteBaseline {
+		baseline := lintBaseline{Version: lintBaselineVersion, Findings: []lintBaselineEntry{}}
+		for _, f := range kept {
+			baseline.Findings = append(baseline.Findings, lintBaselineKey(f, repoRoot))
+		}
+		sort.Slice(baseline.Findings, func(i, j int) bool {
+			a, b := baseline.Findings[i], baseline.Findings[j]
+			if a.Rule != b.Rule {
+				return a.Rule < b.Rule
+			}
+			if a.File != b.File {
+				return a.File < b.File
+			}
+			return a.Message < b.Message
+		})
+		data, err := json.MarshalIndent(baseline, "", "  ")
+		if err != nil {
+			return outputError("lint", err)
+		}
+		if err := os.WriteFile(baselinePath, append(data, '\n'), 0o644); err != nil {
+			return outputError("lint", err)
+		}
+		report.Baselined = len(kept)
+		return outputResult(CLIResult{Command: "lint", Results: report})
+	}
+
+	known, err := readLintBaseline(baselinePath)
+	if err != nil {
+		return outputError("lint", err)
+	}
+	newCounts := map[string]int{}
+	for _, f := range kept {
+		key := lintBaselineKey(f, repoRoot)
+		if known[key] > 0 {
+			known[key]--
+			report.Baselined++
+			continue
+		}
+		report.Findings = append(report.Findings, f)
+		newCounts[f.Rule]++
+	}
+
+	total := len(report.Findings)
+	if err := outputResult(CLIResult{Command: "lint", Results: report, TotalCount: &total}); err != nil {
+		return err
+	}
+	var errs []error
+	for _, rule := range rules {
+		errs = append(errs, checkRule(rule, newCounts[rule]))
+	}
+	return errors.Join(errs...)
+}
+
+// lintRuleFindings returns all findings of rule, located in files.
+func lintRuleFindings(qb *canopy.QueryBuilder, rule string) ([]CLILintFinding, error) {
+	var results any
+	switch rule {
+	case "unused":
+		filter := canopy.SymbolFilter{ExcludeNames: projectConfig.Analysis.EntryPoints}
+		var syms []CLISymbol
+		for offset := 0; ; offset += lintPageSize {
+			limit := lintPageSize
+			page, err := qb.UnusedSymbols(filter, canopy.Sort{}, canopy.Pagination{Limit: &limit, Offset: offset})
+			if err != nil {
+				return nil, err
+			}
+			for _, sr := range page.Items {
+				syms = append(syms, symbolResultToCLI(sr))
+			}
+			if len(page.Items) < lintPageSize {
+				break
+			}
+		}
+		results = syms
+	case "deprecated":
+		var usages []CLIDeprecatedUsage
+		for offset := 0; ; offset += lintPageSize {
+			limit := lintPageSize
+			page, err := qb.DeprecatedUsages(canopy.Pagination{Limit: &limit, Offset: offset})
+			if err != nil {
+				return nil, err
+			}
+			for _, u := range page.Items {
+				usages = append(usages, deprecatedUsageToCLI(u))
+			}
+			if len(page.Items) < lintPageSize {
+				break
+			}
+		}
+		results = usages
+	case "circular_deps":
+		cycles, err := qb.CircularDependencies()
+		if err != nil {
+			return nil, err
+		}
+		cliCycles := make([]CLICycle, len(cycles))
+		for i, cycle := range cycles {
+			cliCycles[i] = CLICycle{Packages: cycle}
+		}
+		results = cliCycles
+	case "package_metrics":
+		metrics, err := qb.PackageCouplingMetrics()
+		if err != nil {
+			return nil, err
+		}
+		results, _ = packageMetricsToCLI(metrics, true)
+	case "glob_collisions":
+		collisions, err := qb.GlobCollisions("")
+		if err != nil {
+			return nil, err
+		}
+		cliCollisions := make([]CLIGlobCollision, len(collisions))
+		for i, c := range collisions {
+			cliCollisions[i] = globCollisionToCLI(c)
+		}
+		results = cliCollisions
+	default:
+		return nil, fmt.Errorf("unknown rule %q", rule)
+	}
+	return ruleFindings(rule, results, func(name string) (*CLILocation, error) {
+		return packageLocation(qb, name)
+	})
+}
+
+// lintIgnoreRe matches an inline suppression comment, capturing its rules.
+var lintIgnoreRe = regexp.MustCompile(`canopy:ignore\(([^)]*)\)`)
+
+// lintSuppressed reports whether a canopy:ignore(rule) comment on the
+// finding's line or the line above suppresses it. Source lines are read
+// once per file and cached in sources.
+func lintSuppressed(f CLILintFinding, sources map[string][]string) bool {
+	lines, ok := sources[f.Location.File]
+	if !ok {
+		if data, err := os.ReadFile(f.Location.File); err == nil {
+			lines = strings.Split(string(data), "\n")
+		}
+		sources[f.Location.File] = lines
+	}
+	for _, n := range []int{f.Location.StartLine, f.Location.StartLine - 1} {
+		if n < 0 || n >= len(lines) {
+			continue
+		}
+		for _, m := range lintIgnoreRe.FindAllStringSubmatch(lines[n], -1) {
+			for _, rule := range strings.Split(m[1], ",") {
+				if strings.TrimSpace(rule) == f.Rule {
+					return true
+				}
+			}
+		}
+	}
+	return false
+}
+
+// lintBaselineKey identifies f in a baseline.
+func lintBaselineKey(f CLILintFinding, repoRoot string) lintBaselineEntry {
+	file := f.Location.File
+	if rel, err := filepath.Rel(repoRoot, file); err == nil && !strings.HasPrefix(rel, "..") {
+		file = rel
+	}
+	return lintBaselineEntry{Rule: f.Rule, File: filepath.ToSlash(file), Message: f.Message}
+}
+
+// readLintBaseline reads the baseline at path as a count per finding. A
+// missing file is an empty baseline.
+func readLintBaseline(path string) (map[lintBaselineEntry]int, error) {
+	known := map[lintBaselineEntry]int{}
+	data, err := os.ReadFile(path)
+	if errors.Is(err, os.ErrNotExist) {
+		return known, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("reading baseline: %w", err)
+	}
+	var baseline lintBaseline
+	if err := json.Unmarshal(data, &baseline); err != nil {
+		return nil, fmt.Errorf("reading baseline %s: %w", path, err)
+	}
+	if baseline.Version > lintBaselineVersion {
+		return nil, fmt.Errorf("reading baseline %s: version %d is newer than this canopy supports (%d)", path, baseline.Version, lintBaselineVersion)
+	}
+	for _, e := range baseline.Findings {
+		known[e]++
+	}
+	return known, nil
+}
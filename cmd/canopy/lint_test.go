@@ -0,0 +1,59 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestLintSuppressed(t *testing.T) {
+	t.Parallel()
+	path := filepath.Join(t.TempDir(), "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`// canopy:ignore(unused)
+fn a() {}
+fn b() {} // canopy:ignore(deprecated, unused)
+fn c() {} // canopy:ignore(deprecated)
+
+fn d() {}
+`), 0o644))
+
+	sources := map[string][]string{}
+	finding := func(rule string, line int) CLILintFinding {
+		return CLILintFinding{Rule: rule, Location: CLILocation{File: path, StartLine: line}}
+	}
+	assert.True(t, lintSuppressed(finding("unused", 1), sources), "comment on the line above")
+	assert.True(t, lintSuppressed(finding("unused", 2), sources), "one of several rules on the same line")
+	assert.False(t, lintSuppressed(finding("unused", 3), sources), "other rule")
+	assert.False(t, lintSuppressed(finding("unused", 5), sources))
+	assert.False(t, lintSuppressed(CLILintFinding{Rule: "unused", Location: CLILocation{File: path + ".missing"}}, sources))
+}
+
+func TestLintBaseline(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+	path := filepath.Join(dir, defaultBaselineFile)
+
+	known, err := readLintBaseline(path)
+	require.NoError(t, err)
+	assert.Empty(t, known, "missing baseline")
+
+	f := CLILintFinding{Rule: "unused", Message: "function a is never referenced",
+		Location: CLILocation{File: filepath.Join(dir, "src", "lib.rs"), StartLine: 7}}
+	key := lintBaselineKey(f, dir)
+	assert.Equal(t, lintBaselineEntry{Rule: "unused", File: "src/lib.rs", Message: "function a is never referenced"}, key)
+
+	require.NoError(t, os.WriteFile(path, []byte(`{"version": 1, "findings": [
+  {"rule": "unused", "file": "src/lib.rs", "message": "function a is never referenced"},
+  {"rule": "unused", "file": "src/lib.rs", "message": "function a is never referenced"}
+]}`), 0o644))
+	known, err = readLintBaseline(path)
+	require.NoError(t, err)
+	assert.Equal(t, 2, known[key])
+
+	require.NoError(t, os.WriteFile(path, []byte(`{"version": 99, "findings": []}`), 0o644))
+	_, err = readLintBaseline(path)
+	assert.Error(t, err)
+}
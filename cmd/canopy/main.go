@@ -4,7 +4,10 @@ import (
 	"context"
 	"fmt"
 	"os"
+	"os/exec"
+	"os/signal"
 	"path/filepath"
+	"runtime/debug"
 	"strings"
 	"time"
 
@@ -14,10 +17,15 @@ import (
 )
 
 var (
-	flagDB     string
-	flagFormat string
+	flagDB      string
+	flagFormat  string
+	flagRuleSet string
 )
 
+// projectConfig is the .canopy.toml found at the repo root of the working
+// directory, loaded before any command runs. Never nil after startup.
+var projectConfig = &Config{}
+
 // errorHandled is set by outputError so main() doesn't double-print.
 var errorHandled bool
 
@@ -37,6 +45,13 @@ var rootCmd = &cobra.Command{
 	SilenceErrors: true,
 	SilenceUsage:  true,
 	PersistentPreRunE: func(cmd *cobra.Command, args []string) error {
+		commandStart = time.Now()
+		if err := loadProjectConfig(cmd); err != nil {
+			return err
+		}
+		if err := checkSnapshotFlag(cmd, flagSnapshot); err != nil {
+			return err
+		}
 		return validateFormat(flagFormat)
 	},
 	// No Run — prints help by default.
@@ -44,10 +59,45 @@ var rootCmd = &cobra.Command{
 
 func init() {
 	rootCmd.PersistentFlags().StringVar(&flagDB, "db", "", "database path (default: .canopy/index.db relative to repo root)")
-	rootCmd.PersistentFlags().StringVar(&flagFormat, "format", "json", "output format: json|text")
+	rootCmd.PersistentFlags().StringVar(&flagFormat, "format", "json", "output format: json|text|ndjson|markdown|sarif|github|gitlab|csv|html")
+	rootCmd.PersistentFlags().StringVar(&flagRuleSet, "ruleset", "", "rule severities from [rulesets.<name>] in .canopy.toml")
+	rootCmd.PersistentFlags().BoolVar(&flagIncludeTests, "include-tests", false, "include test code (tests directories, _test.go files, #[cfg(test)] and #[test] items) in results")
+	rootCmd.PersistentFlags().StringVar(&flagSnapshot, "snapshot", "", "query the named snapshot (see 'canopy snapshot save') instead of the index")
 
 	rootCmd.AddCommand(indexCmd)
 	rootCmd.AddCommand(queryCmd)
+	rootCmd.AddCommand(renameCmd)
+	rootCmd.AddCommand(outlineCmd)
+	rootCmd.AddCommand(mapCmd)
+	rootCmd.AddCommand(findCmd)
+	rootCmd.AddCommand(unresolvedCmd)
+	rootCmd.AddCommand(resolveDebugCmd)
+	rootCmd.AddCommand(unsafeCmd)
+	rootCmd.AddCommand(traitTreeCmd)
+	rootCmd.AddCommand(snippetCmd)
+	rootCmd.AddCommand(examplesCmd)
+	rootCmd.AddCommand(askCmd)
+	rootCmd.AddCommand(sgrepCmd)
+	rootCmd.AddCommand(benchCmd)
+	rootCmd.AddCommand(genFixtureCmd)
+	rootCmd.AddCommand(validateIndexCmd)
+	rootCmd.AddCommand(exportCmd)
+	rootCmd.AddCommand(importCmd)
+	rootCmd.AddCommand(mergeCmd)
+	rootCmd.AddCommand(traceCmd)
+	rootCmd.AddCommand(coverageCmd)
+	rootCmd.AddCommand(embedCmd)
+	rootCmd.AddCommand(semanticSearchCmd)
+	rootCmd.AddCommand(lintCmd)
+	rootCmd.AddCommand(checkCmd)
+	rootCmd.AddCommand(statsCmd)
+	rootCmd.AddCommand(batchCmd)
+	rootCmd.AddCommand(replCmd)
+	rootCmd.AddCommand(tuiCmd)
+	rootCmd.AddCommand(reportCmd)
+	rootCmd.AddCommand(historyCmd)
+	rootCmd.AddCommand(serveCmd)
+	rootCmd.AddCommand(snapshotCmd)
 }
 
 var (
@@ -55,13 +105,35 @@ var (
 	flagLanguages  string
 	flagScriptsDir string
 	flagParallel   bool
+	flagBlame      bool
+	flagSQLRefs    bool
+	flagExclude    []string
+	flagRustdoc    []string
+	flagProgress   bool
+	flagMaxMemory  string
+	flagShards     bool
+	flagParseCache string
+	flagTargetDir  string
+	flagExpand     bool
+	flagDegrade    bool
+	flagErrorShare float64
+	flagTraceOut   string
+	flagArchive    string
+	flagGitRev     string
+	flagDeadline   time.Duration
+	flagPriority   []string
+	flagProcesses  int
 )
 
+// indexCompleteness is the completeness report of the last index run with
+// --deadline, read by serve between the runs of its warm-up.
+var indexCompleteness canopy.IndexCompleteness
+
 var indexCmd = &cobra.Command{
-	Use:   "index [path]",
+	Use:   "index [path...]",
 	Short: "Index a repository for semantic analysis",
-	Long:  "Parses source files with tree-sitter, runs extraction and resolution scripts, and writes results to the SQLite database.",
-	Args:  cobra.MaximumNArgs(1),
+	Long:  "Parses source files with tree-sitter, runs extraction and resolution scripts, and writes results to the SQLite database.\nMultiple paths (or [workspace] roots in .canopy.toml) are indexed into one database, recording each file's root.",
+	Args:  cobra.ArbitraryArgs,
 	RunE:  runIndex,
 }
 
@@ -70,20 +142,47 @@ func init() {
 	indexCmd.Flags().StringVar(&flagLanguages, "languages", "", "comma-separated language filter (e.g. go,typescript)")
 	indexCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
 	indexCmd.Flags().BoolVar(&flagParallel, "parallel", false, "enable parallel extraction (worker pool with batched writes)")
+	indexCmd.Flags().BoolVar(&flagBlame, "blame", false, "record git blame history (last commit, author, date) for each symbol")
+	indexCmd.Flags().BoolVar(&flagSQLRefs, "sql-refs", false, "record the tables and columns named by sqlx query macros and diesel sql_query calls")
+	indexCmd.Flags().StringSliceVar(&flagExclude, "exclude", nil, "glob of paths to skip, relative to the indexed root (repeatable, e.g. 'target', '**/generated/**')")
+	indexCmd.Flags().StringSliceVar(&flagRustdoc, "rustdoc-json", nil, "rustdoc JSON file of a dependency crate to load as stub symbols (repeatable)")
+	indexCmd.Flags().IntVar(&flagProcesses, "processes", 0, "extract in this many worker processes instead of threads, for very large repositories (implies --parallel)")
+	indexCmd.Flags().StringVar(&flagMaxMemory, "max-memory", "", "memory budget for indexing, e.g. 2GiB; extraction commits files to the database in chunks that fit it")
+	indexCmd.Flags().BoolVar(&flagDegrade, "degrade", false, "index the declarations of files extraction fails on, or that are mostly syntax errors or Rust macro invocations, with a line-based fallback scanner, marked as fallback")
+	indexCmd.Flags().Float64Var(&flagErrorShare, "degrade-error-share", canopy.DefaultDegradeErrorShare, "with --degrade, the share of a file its syntax errors may cover before it is scanned")
+	indexCmd.Flags().BoolVar(&flagProgress, "progress", false, "report files extracted and languages resolved, with an ETA, on stderr")
+	indexCmd.Flags().StringVar(&flagTraceOut, "trace-output", "", "write a Chrome trace (chrome://tracing, Perfetto) of the indexing phases and of each file to this path")
+	indexCmd.Flags().StringVar(&flagParseCache, "parse-cache", "", "directory caching each file's extraction by content hash, reused when identical files return (e.g. after switching branches)")
+	indexCmd.Flags().StringVar(&flagTargetDir, "target-dir", "", "Cargo target directory; build script output pulled in with include!(concat!(env!(\"OUT_DIR\"), ...)) is indexed from it")
+	indexCmd.Flags().BoolVar(&flagExpand, "expand-macros", false, "run cargo expand on each Rust crate and index the items its macros generate (requires cargo-expand)")
+	indexCmd.Flags().StringVar(&flagArchive, "archive", "", "index the files of a zip, tar, or tar.gz archive instead of a directory")
+	indexCmd.Flags().StringVar(&flagGitRev, "git-rev", "", "index this revision of the git repository at path, reading blobs from its object store without a checkout (works on bare repositories)")
+	indexCmd.Flags().DurationVar(&flagDeadline, "deadline", 0, "index best-effort for this long (e.g. 30s): entry points and the most-used files first, stopping with a partial graph and a completeness report when time is up")
+	indexCmd.Flags().StringSliceVar(&flagPriority, "priority", nil, "file or directory to index first, followed by the files it imports (repeatable)")
+	indexCmd.Flags().BoolVar(&flagShards, "shards", false, "index each crate, Go module, and npm package into its own shard database, re-resolving only changed shards")
 }
 
 func runIndex(cmd *cobra.Command, args []string) error {
 	start := time.Now()
 
-	// Determine the target directory.
-	targetDir, err := resolveTargetDir(args)
+	if flagArchive != "" && (flagGitRev != "" || len(args) > 0) {
+		return fmt.Errorf("--archive takes no path arguments and cannot be combined with --git-rev")
+	}
+	if flagGitRev != "" && len(args) > 1 {
+		return fmt.Errorf("--git-rev indexes one repository at a time")
+	}
+	if strings.HasPrefix(flagGitRev, "-") {
+		return fmt.Errorf("invalid --git-rev %q: a revision must not start with -", flagGitRev)
+	}
+	// Determine the target directories and the repo root that owns the DB.
+	targetDirs, repoRoot, err := resolveIndexTargets(args)
+	if err != nil {
+		return err
+	}
+	dbPath, err := resolveDBPath(repoRoot)
 	if err != nil {
 		return err
 	}
-
-	// Resolve repo root and DB path.
-	repoRoot := findRepoRoot(targetDir)
-	dbPath := resolveDBPath(repoRoot)
 
 	// Ensure .canopy/ directory exists.
 	canopyDir := filepath.Dir(dbPath)
@@ -92,14 +191,25 @@ func runIndex(cmd *cobra.Command, args []string) error {
 	}
 
 	// Handle --force: delete the DB file entirely.
+	shardDir := filepath.Join(canopyDir, "shards")
 	if flagForce {
 		if err := os.Remove(dbPath); err != nil && !os.IsNotExist(err) {
 			return fmt.Errorf("removing database for --force: %w", err)
 		}
+		if err := os.RemoveAll(shardDir); err != nil {
+			return fmt.Errorf("removing shards for --force: %w", err)
+		}
 		fmt.Fprintf(os.Stderr, "Cleared database: %s\n", dbPath)
 	}
 
-	// Build engine options.
+	// The indexed tree may live outside the working directory's repo, so
+	// read its own .canopy.toml for index settings.
+	cfg, err := loadConfig(repoRoot)
+	if err != nil {
+		return err
+	}
+
+	// Build engine options. Flags override the [index] config section.
 	var opts []canopy.Option
 	if flagLanguages != "" {
 		langs := strings.Split(flagLanguages, ",")
@@ -107,18 +217,191 @@ func runIndex(cmd *cobra.Command, args []string) error {
 			langs[i] = strings.TrimSpace(langs[i])
 		}
 		opts = append(opts, canopy.WithLanguages(langs...))
+	} else if len(cfg.Index.Languages) > 0 {
+		opts = append(opts, canopy.WithLanguages(cfg.Index.Languages...))
 	}
 
-	if flagParallel {
+	parallel := flagParallel
+	if !cmd.Flags().Changed("parallel") && cfg.Index.Parallel != nil {
+		parallel = *cfg.Index.Parallel
+	}
+	if parallel {
 		opts = append(opts, canopy.WithParallel(true))
 	}
 
+	blame := flagBlame
+	if !cmd.Flags().Changed("blame") && cfg.Index.Blame != nil {
+		blame = *cfg.Index.Blame
+	}
+	if blame {
+		opts = append(opts, canopy.WithBlame(true))
+	}
+
+	sqlRefs := flagSQLRefs
+	if !cmd.Flags().Changed("sql-refs") && cfg.Index.SQLRefs != nil {
+		sqlRefs = *cfg.Index.SQLRefs
+	}
+	if sqlRefs {
+		opts = append(opts, canopy.WithSQLReferences(true))
+	}
+
+	if len(cfg.Index.Include) > 0 {
+		opts = append(opts, canopy.WithInclude(cfg.Index.Include...))
+	}
+	// --exclude adds to the configured excludes rather than replacing them.
+	if excludes := append(append([]string{}, cfg.Index.Exclude...), flagExclude...); len(excludes) > 0 {
+		opts = append(opts, canopy.WithExclude(excludes...))
+	}
+
+	maxMemory := flagMaxMemory
+	if maxMemory == "" {
+		maxMemory = cfg.Index.MaxMemory
+	}
+	if maxMemory != "" {
+		limit, err := parseByteSize(maxMemory)
+		if err != nil {
+			return fmt.Errorf("--max-memory: %w", err)
+		}
+		// Extraction works in chunks of the budget; the Go runtime collects
+		// garbage harder as the process nears it.
+		opts = append(opts, canopy.WithMaxMemory(limit))
+		debug.SetMemoryLimit(limit)
+	}
+
+	if flagProgress {
+		opts = append(opts, canopy.WithProgress(printProgress))
+	}
+	if os.Getenv(usageLogEnv) != "" {
+		opts = append(opts, canopy.WithUsage(recordUsage))
+	}
+	if flagDeadline > 0 {
+		opts = append(opts, canopy.WithDeadline(start.Add(flagDeadline)))
+	}
+	if len(flagPriority) > 0 {
+		priority := make([]string, len(flagPriority))
+		for i, p := range flagPriority {
+			if priority[i], err = filepath.Abs(p); err != nil {
+				return fmt.Errorf("--priority: %w", err)
+			}
+		}
+		opts = append(opts, canopy.WithPriority(priority...))
+	}
+	if flagTraceOut != "" {
+		trace := &canopy.ChromeTrace{}
+		opts = append(opts, canopy.WithTracer(trace.Record))
+		// Written even when indexing fails, which is when it is most wanted.
+		defer writeChromeTrace(trace, flagTraceOut)
+	}
+
+	parseCache := flagParseCache
+	if parseCache == "" && cfg.Index.ParseCache != "" {
+		parseCache = cfg.Index.ParseCache
+		if !filepath.IsAbs(parseCache) {
+			parseCache = filepath.Join(repoRoot, parseCache)
+		}
+	}
+	if parseCache != "" {
+		opts = append(opts, canopy.WithParseCache(parseCache))
+	}
+
+	targetDir := flagTargetDir
+	if targetDir != "" {
+		if targetDir, err = filepath.Abs(targetDir); err != nil {
+			return fmt.Errorf("--target-dir: %w", err)
+		}
+	} else if cfg.Index.TargetDir != "" {
+		targetDir = cfg.Index.TargetDir
+		if !filepath.IsAbs(targetDir) {
+			targetDir = filepath.Join(repoRoot, targetDir)
+		}
+	}
+	if targetDir != "" {
+		opts = append(opts, canopy.WithTargetDir(targetDir))
+	}
+
+	degrade := flagDegrade
+	if !cmd.Flags().Changed("degrade") && cfg.Index.Degrade != nil {
+		degrade = *cfg.Index.Degrade
+	}
+	if degrade {
+		errorShare := flagErrorShare
+		if !cmd.Flags().Changed("degrade-error-share") && cfg.Index.DegradeErrorShare != nil {
+			errorShare = *cfg.Index.DegradeErrorShare
+		}
+		if errorShare <= 0 || errorShare > 1 {
+			return fmt.Errorf("--degrade-error-share must be above 0 and at most 1")
+		}
+		opts = append(opts, canopy.WithDegradation(errorShare))
+	}
+
+	expand := flagExpand
+	if !cmd.Flags().Changed("expand-macros") && cfg.Index.Expand != nil {
+		expand = *cfg.Index.Expand
+	}
+	if expand {
+		opts = append(opts, canopy.WithMacroExpansion(filepath.Join(canopyDir, "expanded")))
+	}
+
+	// Workspace dependencies name roots relative to the repo root.
+	if deps := cfg.Workspace.Dependencies; deps != nil {
+		abs := func(r string) string {
+			if filepath.IsAbs(r) {
+				return r
+			}
+			return filepath.Join(repoRoot, r)
+		}
+		rootDeps := make(map[string][]string, len(deps))
+		for root, on := range deps {
+			for _, r := range on {
+				rootDeps[abs(root)] = append(rootDeps[abs(root)], abs(r))
+			}
+		}
+		opts = append(opts, canopy.WithRootDependencies(rootDeps))
+	}
+
+	// --archive and --git-rev read the tree from the archive or from git's
+	// object store instead of the directory on disk; its files sit under "/".
+	indexed := strings.Join(targetDirs, ", ")
+	if flagArchive != "" {
+		fsys, err := canopy.OpenArchive(flagArchive)
+		if err != nil {
+			return fmt.Errorf("--archive: %w", err)
+		}
+		opts = append(opts, canopy.WithFileSystem(fsys))
+		targetDirs = []string{"/"}
+		indexed = flagArchive
+	} else if flagGitRev != "" {
+		fsys, err := canopy.GitFileSystem(context.Background(), targetDirs[0], flagGitRev)
+		if err != nil {
+			return fmt.Errorf("--git-rev: %w", err)
+		}
+		opts = append(opts, canopy.WithFileSystem(fsys))
+		indexed = targetDirs[0] + "@" + flagGitRev
+		targetDirs = []string{"/"}
+	}
+
 	// Script source: --scripts-dir overrides embedded FS.
 	scriptsDir := flagScriptsDir
 	if scriptsDir == "" {
 		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
 	}
 
+	if flagProcesses > 0 {
+		exe, err := os.Executable()
+		if err != nil {
+			return fmt.Errorf("--processes: %w", err)
+		}
+		opts = append(opts, canopy.WithWorkerProcesses(flagProcesses, func() *exec.Cmd {
+			args := []string{"extract-worker", "--db", dbPath}
+			if scriptsDir != "" {
+				args = append(args, "--scripts-dir", scriptsDir)
+			}
+			cmd := exec.Command(exe, args...)
+			cmd.Stderr = os.Stderr
+			return cmd
+		}))
+	}
+
 	engine, err := canopy.New(dbPath, scriptsDir, opts...)
 	if err != nil {
 		return fmt.Errorf("creating engine: %w", err)
@@ -139,15 +422,49 @@ func runIndex(cmd *cobra.Command, args []string) error {
 	}
 	defer engine.Close()
 
-	ctx := context.Background()
+	// Ctrl-C stops indexing between files; files already extracted stay
+	// indexed for the next run.
+	ctx, stop := signal.NotifyContext(context.Background(), os.Interrupt)
+	defer stop()
+
+	shards := flagShards
+	if !cmd.Flags().Changed("shards") && cfg.Index.Shards != nil {
+		shards = *cfg.Index.Shards
+	}
+	if shards && (flagArchive != "" || flagGitRev != "") {
+		return fmt.Errorf("--shards indexes directories on disk and cannot be used with --archive or --git-rev")
+	}
+	if shards && (flagDeadline > 0 || len(flagPriority) > 0) {
+		return fmt.Errorf("--deadline and --priority cannot be used with --shards")
+	}
 
 	// Run extraction.
 	extractStart := time.Now()
-	if err := engine.IndexDirectory(ctx, targetDir); err != nil {
+	if shards {
+		err = indexShards(ctx, engine, shardDir, targetDirs, repoRoot)
+	} else {
+		err = engine.IndexDirectories(ctx, targetDirs)
+	}
+	if err != nil {
 		return fmt.Errorf("indexing: %w", err)
 	}
 	extractDuration := time.Since(extractStart)
 
+	// Load dependency stubs before resolution so references can bind to them.
+	// Configured paths are relative to the repo root.
+	rustdocFiles := append([]string{}, flagRustdoc...)
+	for _, p := range cfg.Index.RustdocJSON {
+		if !filepath.IsAbs(p) {
+			p = filepath.Join(repoRoot, p)
+		}
+		rustdocFiles = append(rustdocFiles, p)
+	}
+	for _, p := range rustdocFiles {
+		if err := ingestRustdocFile(engine, p); err != nil {
+			return err
+		}
+	}
+
 	// Run resolution.
 	resolveStart := time.Now()
 	if err := engine.Resolve(ctx); err != nil {
@@ -156,19 +473,203 @@ func runIndex(cmd *cobra.Command, args []string) error {
 	resolveDuration := time.Since(resolveStart)
 
 	totalDuration := time.Since(start)
+	if err := engine.RecordIndexTimings([]canopy.IndexTiming{
+		{Phase: "extract", Duration: extractDuration},
+		{Phase: "resolve", Duration: resolveDuration},
+	}); err != nil {
+		return err
+	}
 
 	// Print timing summary to stderr.
 	fmt.Fprintf(os.Stderr, "Indexed %s in %s (extract: %s, resolve: %s)\n",
-		targetDir,
+		indexed,
 		totalDuration.Round(time.Millisecond),
 		extractDuration.Round(time.Millisecond),
 		resolveDuration.Round(time.Millisecond),
 	)
 	fmt.Fprintf(os.Stderr, "Database: %s\n", dbPath)
+	if flagDeadline > 0 {
+		indexCompleteness = engine.Completeness()
+		printCompleteness(indexCompleteness)
+	}
+
+	return nil
+}
+
+// maxPendingShown caps the pending files the completeness report lists.
+const maxPendingShown = 10
+
+// printCompleteness writes the completeness report of an index run under
+// --deadline to stderr.
+func printCompleteness(c canopy.IndexCompleteness) {
+	if c.Complete() {
+		fmt.Fprintf(os.Stderr, "Complete: all %d files indexed within the deadline\n", c.Files)
+		return
+	}
+	fmt.Fprintf(os.Stderr, "Partial: indexed %d of %d files (%.0f%%) before the deadline", c.Indexed, c.Files, 100*c.Coverage())
+	if c.Stale > 0 {
+		fmt.Fprintf(os.Stderr, "; %d pending files keep symbols from an earlier index", c.Stale)
+	}
+	fmt.Fprintln(os.Stderr)
+	for i, path := range c.Pending {
+		if i == maxPendingShown {
+			fmt.Fprintf(os.Stderr, "  ... and %d more pending\n", len(c.Pending)-i)
+			break
+		}
+		fmt.Fprintf(os.Stderr, "  pending: %s\n", path)
+	}
+	for _, step := range c.Skipped {
+		fmt.Fprintf(os.Stderr, "  skipped: %s\n", step)
+	}
+	fmt.Fprintf(os.Stderr, "Run canopy index again to index the rest.\n")
+}
+
+// indexShards indexes the crates and packages under each target directory
+// as shards. Shards are named by their path relative to the repo root, so
+// names stay unique across targets.
+func indexShards(ctx context.Context, engine *canopy.Engine, shardDir string, targetDirs []string, repoRoot string) error {
+	var shards []canopy.Shard
+	seen := make(map[string]bool)
+	for _, dir := range targetDirs {
+		found, err := canopy.DiscoverShards(dir)
+		if err != nil {
+			return err
+		}
+		for _, sh := range found {
+			if seen[sh.Root] {
+				continue
+			}
+			seen[sh.Root] = true
+			if rel, err := filepath.Rel(repoRoot, sh.Root); err == nil && rel != "." {
+				sh.Name = strings.ReplaceAll(filepath.ToSlash(rel), "/", "-")
+			}
+			shards = append(shards, sh)
+		}
+	}
+	fmt.Fprintf(os.Stderr, "Indexing %d shards into %s\n", len(shards), shardDir)
+	return engine.IndexShards(ctx, shardDir, shards)
+}
+
+// writeChromeTrace writes the spans recorded during indexing to path. A
+// trace that cannot be written is reported on stderr without failing the
+// index.
+func writeChromeTrace(trace *canopy.ChromeTrace, path string) {
+	f, err := os.Create(path)
+	if err == nil {
+		_, err = trace.WriteTo(f)
+		if cerr := f.Close(); err == nil {
+			err = cerr
+		}
+	}
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "warning: writing trace: %v\n", err)
+		return
+	}
+	fmt.Fprintf(os.Stderr, "Trace: %s\n", path)
+}
+
+// printProgress writes an index progress update to stderr.
+func printProgress(p canopy.Progress) {
+	eta := ""
+	if p.ETA > 0 {
+		eta = fmt.Sprintf(", %s left", p.ETA.Round(time.Second))
+	}
+	fmt.Fprintf(os.Stderr, "%s %d/%d%s: %s\n", p.Phase, p.Done, p.Total, eta, p.Path)
+}
+
+// ingestRustdocFile loads one rustdoc JSON file into the engine as stubs.
+func ingestRustdocFile(engine *canopy.Engine, path string) error {
+	f, err := os.Open(path)
+	if err != nil {
+		return fmt.Errorf("opening rustdoc json: %w", err)
+	}
+	defer f.Close()
+	crateName, n, err := engine.IngestRustdocJSON(f)
+	if err != nil {
+		return fmt.Errorf("%s: %w", path, err)
+	}
+	fmt.Fprintf(os.Stderr, "Loaded %d stubs for crate %s\n", n, crateName)
+	return nil
+}
 
+// loadProjectConfig loads .canopy.toml from the working directory's repo root
+// into projectConfig and applies [output] defaults to flags the user did not
+// set explicitly.
+func loadProjectConfig(cmd *cobra.Command) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return fmt.Errorf("getting cwd: %w", err)
+	}
+	cfg, err := loadConfig(findRepoRoot(cwd))
+	if err != nil {
+		return err
+	}
+	projectConfig = cfg
+	if flagRuleSet != "" {
+		if err := cfg.selectRuleSet(flagRuleSet); err != nil {
+			return err
+		}
+	}
+
+	if cfg.Output.Format != "" && !cmd.Flags().Changed("format") {
+		flagFormat = cfg.Output.Format
+	}
+	if cfg.Output.Limit != nil {
+		if f := cmd.Flags().Lookup("limit"); f != nil && !f.Changed {
+			flagLimit = *cfg.Output.Limit
+		}
+	}
 	return nil
 }
 
+// resolveIndexTargets returns the directories to index and the repo root
+// whose .canopy/ holds the database. A single path behaves as before. With
+// several paths, or none and [workspace] roots configured, all roots share
+// the database of the working directory's repo.
+func resolveIndexTargets(args []string) ([]string, string, error) {
+	if len(args) == 1 {
+		dir, err := resolveTargetDir(args)
+		if err != nil {
+			return nil, "", err
+		}
+		return []string{dir}, findRepoRoot(dir), nil
+	}
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return nil, "", fmt.Errorf("getting cwd: %w", err)
+	}
+	repoRoot := findRepoRoot(cwd)
+
+	paths := args
+	if len(paths) == 0 {
+		// Workspace roots are relative to the repo root holding .canopy.toml.
+		for _, r := range projectConfig.Workspace.Roots {
+			if !filepath.IsAbs(r) {
+				r = filepath.Join(repoRoot, r)
+			}
+			paths = append(paths, r)
+		}
+	}
+	if len(paths) == 0 {
+		dir, err := resolveTargetDir(nil)
+		if err != nil {
+			return nil, "", err
+		}
+		return []string{dir}, findRepoRoot(dir), nil
+	}
+
+	dirs := make([]string, 0, len(paths))
+	for _, p := range paths {
+		dir, err := resolveTargetDir([]string{p})
+		if err != nil {
+			return nil, "", err
+		}
+		dirs = append(dirs, dir)
+	}
+	return dirs, repoRoot, nil
+}
+
 // resolveTargetDir returns the absolute path of the directory to index.
 func resolveTargetDir(args []string) (string, error) {
 	dir := "."
@@ -206,8 +707,18 @@ func findRepoRoot(startDir string) string {
 	}
 }
 
-// resolveDBPath returns the database path from the --db flag or the default.
-func resolveDBPath(repoRoot string) string {
+// resolveDBPath returns the database path of the --snapshot snapshot, or
+// else of the index. The snapshot name is validated before commands run.
+func resolveDBPath(repoRoot string) (string, error) {
+	if flagSnapshot != "" {
+		return snapshotPath(repoRoot, flagSnapshot)
+	}
+	return indexDBPath(repoRoot), nil
+}
+
+// indexDBPath returns the index database path from the --db flag or the
+// default.
+func indexDBPath(repoRoot string) string {
 	if flagDB != "" {
 		if filepath.IsAbs(flagDB) {
 			return flagDB
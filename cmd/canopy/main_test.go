@@ -1,11 +1,16 @@
 package main
 
 import (
+	"encoding/json"
 	"os"
 	"path/filepath"
+	"strings"
 	"testing"
+	"time"
 
+	"github.com/jward/canopy"
 	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
 )
 
 func TestFindRepoRoot_DirectGitDir(t *testing.T) {
@@ -43,3 +48,102 @@ func TestFindRepoRoot_NoGitAncestor(t *testing.T) {
 	got := findRepoRoot(dir)
 	assert.Equal(t, dir, got)
 }
+
+func TestParseBudget(t *testing.T) {
+	t.Parallel()
+	for _, v := range []string{"4000", "4000tokens", "4000-tokens"} {
+		n, err := parseBudget(v)
+		assert.NoError(t, err, v)
+		assert.Equal(t, 4000, n, v)
+	}
+	for _, v := range []string{"", "0-tokens", "-5", "lots"} {
+		_, err := parseBudget(v)
+		assert.Error(t, err, v)
+	}
+}
+
+func TestSnapshotPath(t *testing.T) {
+	t.Parallel()
+	path, err := snapshotPath("/repo", "pre-refactor_1.2")
+	require.NoError(t, err)
+	assert.Equal(t, filepath.Join("/repo", ".canopy", "snapshots", "pre-refactor_1.2.db"), path)
+
+	for _, v := range []string{"", ".", "..", "a/b", "../index", "with space"} {
+		_, err := snapshotPath("/repo", v)
+		assert.Error(t, err, v)
+	}
+}
+
+func TestParsePosition(t *testing.T) {
+	t.Parallel()
+	file, line, col, err := parsePosition("src/main.rs:12:4")
+	require.NoError(t, err)
+	assert.Equal(t, "src/main.rs", file)
+	assert.Equal(t, 12, line)
+	assert.Equal(t, 4, col)
+
+	file, _, _, err = parsePosition(`C:\src\main.go:1:0`)
+	require.NoError(t, err)
+	assert.Equal(t, `C:\src\main.go`, file)
+
+	for _, v := range []string{"main.go", "main.go:3", ":1:2", "main.go:x:1", "main.go:1:-2"} {
+		_, _, _, err := parsePosition(v)
+		assert.Error(t, err, v)
+	}
+}
+
+func TestRecordCommandUsage(t *testing.T) {
+	logPath := filepath.Join(t.TempDir(), "usage.ndjson")
+	t.Setenv(usageLogEnv, logPath)
+	commandStart = time.Now()
+	t.Cleanup(func() { commandStart = time.Time{} })
+
+	total := 3
+	recordCommandUsage(CLIResult{Command: "references", Results: []CLILocation{{}, {}}, TotalCount: &total})
+	recordCommandUsage(CLIResult{Command: "definition", Error: "symbol not found"})
+
+	data, err := os.ReadFile(logPath)
+	require.NoError(t, err)
+	lines := strings.Split(strings.TrimSpace(string(data)), "\n")
+	require.Len(t, lines, 2)
+
+	var rec usageRecord
+	require.NoError(t, json.Unmarshal([]byte(lines[0]), &rec))
+	assert.Equal(t, canopy.UsageQuery, rec.Kind)
+	assert.Equal(t, "references", rec.Name)
+	assert.Equal(t, 3, rec.Results, "the total count, not the page shown")
+	assert.False(t, rec.Failed)
+
+	require.NoError(t, json.Unmarshal([]byte(lines[1]), &rec))
+	assert.Equal(t, "definition", rec.Name)
+	assert.True(t, rec.Failed)
+	assert.NotContains(t, lines[1], "symbol not found", "errors may name code and are not logged")
+}
+
+func TestCheckSnapshotFlag(t *testing.T) {
+	t.Parallel()
+	for _, args := range [][]string{{"query", "summary"}, {"snapshot", "diff"}, {"export"}} {
+		cmd, _, err := rootCmd.Find(args)
+		require.NoError(t, err)
+		assert.NoError(t, checkSnapshotFlag(cmd, "pre-refactor"), args)
+		assert.NoError(t, checkSnapshotFlag(cmd, ""), args)
+	}
+	for _, args := range [][]string{{"index"}, {"import"}, {"coverage"}, {"trace"}, {"embed"}, {"merge"}, {"serve"}, {"snapshot", "save"}} {
+		cmd, _, err := rootCmd.Find(args)
+		require.NoError(t, err)
+		assert.ErrorContains(t, checkSnapshotFlag(cmd, "pre-refactor"), "read-only", args)
+		assert.NoError(t, checkSnapshotFlag(cmd, ""), args)
+	}
+
+	// check indexes into the database unless told not to.
+	cmd, _, err := rootCmd.Find([]string{"check"})
+	require.NoError(t, err)
+	assert.ErrorContains(t, checkSnapshotFlag(cmd, "pre-refactor"), "--no-index")
+	require.NoError(t, cmd.Flags().Set("no-index", "true"))
+	t.Cleanup(func() { cmd.Flags().Set("no-index", "false") })
+	assert.NoError(t, checkSnapshotFlag(cmd, "pre-refactor"))
+
+	cmd, _, err = rootCmd.Find([]string{"query", "summary"})
+	require.NoError(t, err)
+	assert.ErrorContains(t, checkSnapshotFlag(cmd, "../index"), "invalid snapshot name")
+}
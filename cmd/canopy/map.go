@@ -0,0 +1,88 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var flagBudget string
+
+var mapCmd = &cobra.Command{
+	Use:   "map",
+	Short: "Summarize the most central symbols within a token budget",
+	Long: "Ranks symbols by PageRank over the reference graph and prints the most central ones with their signatures,\n" +
+		"grouped by file, until the map reaches --budget tokens (estimated at four bytes per token).\n" +
+		"Use --format text for a plain-text map to paste into an AI coding tool's context.",
+	Args: cobra.NoArgs,
+	RunE: runMap,
+}
+
+func init() {
+	mapCmd.Flags().StringVar(&flagBudget, "budget", "4000-tokens", "token budget, e.g. 4000 or 4000-tokens")
+}
+
+func runMap(cmd *cobra.Command, args []string) error {
+	budget, err := parseBudget(flagBudget)
+	if err != nil {
+		return outputError("map", err)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("map", err)
+	}
+	defer s.Close()
+
+	m, err := queryBuilder(s).RepoMap(budget)
+	if err != nil {
+		return outputError("map", err)
+	}
+	return outputResult(CLIResult{
+		Command:    "map",
+		Results:    repoMapToCLI(m),
+		TotalCount: &m.TotalSymbols,
+	})
+}
+
+// parseBudget parses a --budget value: a positive token count, optionally
+// suffixed with "tokens" or "-tokens".
+func parseBudget(value string) (int, error) {
+	num := strings.TrimSuffix(strings.TrimSuffix(value, "tokens"), "-")
+	n, err := strconv.Atoi(num)
+	if err != nil || n <= 0 {
+		return 0, fmt.Errorf("invalid budget %q: must be a positive token count such as 4000-tokens", value)
+	}
+	return n, nil
+}
+
+// repoMapToCLI converts a canopy.RepoMap to a CLIRepoMap.
+func repoMapToCLI(m *canopy.RepoMap) CLIRepoMap {
+	files := make([]CLIRepoMapFile, len(m.Files))
+	for i, f := range m.Files {
+		entries := make([]CLIRepoMapEntry, len(f.Entries))
+		for j, e := range f.Entries {
+			entries[j] = CLIRepoMapEntry{
+				ID:               e.Symbol.ID,
+				Name:             e.Symbol.Name,
+				Kind:             e.Symbol.Kind,
+				StartLine:        e.Symbol.StartLine,
+				EndLine:          e.Symbol.EndLine,
+				RefCount:         e.Symbol.RefCount,
+				ExternalRefCount: e.Symbol.ExternalRefCount,
+				Rank:             e.Symbol.PageRank,
+				Signature:        e.Signature,
+			}
+		}
+		files[i] = CLIRepoMapFile{File: f.File, Entries: entries}
+	}
+	return CLIRepoMap{
+		Budget:          m.Budget,
+		EstimatedTokens: m.EstimatedTokens,
+		Symbols:         m.Symbols,
+		Files:           files,
+	}
+}
@@ -0,0 +1,116 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"os/signal"
+	"path/filepath"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/internal/store"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var flagMergeOutput string
+
+var mergeCmd = &cobra.Command{
+	Use:   "merge <index>...",
+	Short: "Merge indexes of disjoint roots into one snapshot",
+	Long: "Merges indexes of disjoint source roots, such as those built by CI shards, into one queryable graph and writes\n" +
+		"it to --output as a " + snapshotFormat + " snapshot, to load with 'canopy import'. Each index may be a snapshot\n" +
+		"written by 'canopy export' or an index database. References one index could not resolve are resolved again\n" +
+		"against the merged graph, linking calls and uses across the indexes. The indexes must have been built by this\n" +
+		"version of canopy and must not share files, apart from dependency stubs.",
+	Args: cobra.MinimumNArgs(1),
+	RunE: runMerge,
+}
+
+func init() {
+	mergeCmd.Flags().StringVarP(&flagMergeOutput, "output", "o", "", "snapshot file to write")
+	mergeCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+	_ = mergeCmd.MarkFlagRequired("output")
+}
+
+func runMerge(cmd *cobra.Command, args []string) error {
+	out, err := filepath.Abs(flagMergeOutput)
+	if err != nil {
+		return outputError("merge", err)
+	}
+	tmp, err := os.MkdirTemp("", "canopy-merge-")
+	if err != nil {
+		return outputError("merge", err)
+	}
+	defer os.RemoveAll(tmp)
+
+	// Snapshots are loaded into databases of their own first.
+	dbPaths := make([]string, len(args))
+	for i, arg := range args {
+		if dbPaths[i], err = mergeInput(arg, filepath.Join(tmp, fmt.Sprintf("input-%d.db", i))); err != nil {
+			return outputError("merge", fmt.Errorf("%s: %w", arg, err))
+		}
+	}
+
+	var opts []canopy.Option
+	if flagScriptsDir == "" {
+		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+	}
+	mergedPath := filepath.Join(tmp, "merged.db")
+	engine, err := canopy.New(mergedPath, flagScriptsDir, opts...)
+	if err != nil {
+		return outputError("merge", fmt.Errorf("creating engine: %w", err))
+	}
+	ctx, stop := signal.NotifyContext(context.Background(), os.Interrupt)
+	defer stop()
+	err = engine.Merge(ctx, dbPaths...)
+	engine.Close()
+	if err != nil {
+		return outputError("merge", err)
+	}
+
+	merged, err := store.NewStore(mergedPath)
+	if err != nil {
+		return outputError("merge", err)
+	}
+	defer merged.Close()
+	f, err := os.Create(out)
+	if err != nil {
+		return outputError("merge", err)
+	}
+	stats, err := merged.WriteSnapshot(f)
+	if cerr := f.Close(); err == nil {
+		err = cerr
+	}
+	if err != nil {
+		os.Remove(out)
+		return outputError("merge", err)
+	}
+	return outputResult(CLIResult{Command: "merge", Results: snapshotResult(out, stats)})
+}
+
+// mergeInput returns the path of the index database for input, loading
+// input into a new database at tmpPath if it is a snapshot.
+func mergeInput(input, tmpPath string) (string, error) {
+	isSnapshot, err := store.IsSnapshotFile(input)
+	if err != nil || !isSnapshot {
+		return input, err
+	}
+	f, err := os.Open(input)
+	if err != nil {
+		return "", err
+	}
+	defer f.Close()
+	s, err := store.NewStore(tmpPath)
+	if err != nil {
+		return "", err
+	}
+	defer s.Close()
+	if err := s.Migrate(); err != nil {
+		return "", err
+	}
+	if _, err := s.LoadSnapshot(f); err != nil {
+		return "", err
+	}
+	return tmpPath, nil
+}
@@ -0,0 +1,62 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var newtypesCmd = &cobra.Command{
+	Use:   "newtypes [<type>]",
+	Short: "List Rust newtypes and the types they wrap",
+	Long: "Lists Rust structs with a single field, not counting PhantomData markers (struct Meters(f64)),\n" +
+		"with the type each one wraps. <type> keeps the newtypes wrapping that type, matched against the\n" +
+		"wrapped type as written (Vec<u8>), its head (Vec), or the name of the type it resolves to.\n" +
+		"--symbol <id> lists the newtypes wrapping that type symbol instead.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runNewtypes,
+}
+
+func init() {
+	newtypesCmd.Flags().Int64("symbol", 0, "list the newtypes wrapping this type symbol ID")
+	newtypesCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runNewtypes(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("newtypes", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	var newtypes []canopy.Newtype
+	if symID, _ := cmd.Flags().GetInt64("symbol"); symID != 0 {
+		newtypes, err = qb.WrappersOf(symID)
+	} else {
+		inner := ""
+		if len(args) == 1 {
+			inner = args[0]
+		}
+		newtypes, err = qb.Newtypes(inner, flagPathPrefix)
+	}
+	if err != nil {
+		return outputError("newtypes", err)
+	}
+
+	cliNewtypes := make([]CLINewtype, len(newtypes))
+	for i, n := range newtypes {
+		cliNewtypes[i] = CLINewtype{
+			Symbol:      symbolResultToCLI(n.Symbol),
+			Inner:       n.Inner,
+			InnerHead:   n.InnerHead,
+			InnerSymbol: optionalSymbolToCLI(n.InnerSymbol),
+			Visibility:  n.Visibility,
+		}
+	}
+	paged, totalCount := paginateSlice(cliNewtypes)
+	return outputResult(CLIResult{
+		Command:    "newtypes",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,77 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var noStdCmd = &cobra.Command{
+	Use:   "no-std",
+	Short: "Classify Rust symbols by whether they need alloc or std",
+	Long: "Lists each Rust crate, whether its root is #![no_std] (always, or through cfg_attr), and how many of its\n" +
+		"symbols need only core, alloc, or std, directly or through the symbols they reference. Then lists the symbols\n" +
+		"needing alloc or std, each with the chain of references that leads to the standard library item. Use it to\n" +
+		"find the embedded-compatible subset of a shared library. --tier std lists only the symbols needing std.",
+	Args: cobra.NoArgs,
+	RunE: runNoStd,
+}
+
+func init() {
+	noStdCmd.Flags().String("tier", "", "list only symbols needing this tier: alloc or std")
+	noStdCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runNoStd(cmd *cobra.Command, args []string) error {
+	tier, _ := cmd.Flags().GetString("tier")
+	if tier != "" && tier != canopy.StdTierAlloc && tier != canopy.StdTierStd {
+		return outputError("no-std", fmt.Errorf("invalid --tier %q: want alloc or std", tier))
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("no-std", err)
+	}
+	defer s.Close()
+
+	report, err := queryBuilder(s).StdDependencies(flagPathPrefix)
+	if err != nil {
+		return outputError("no-std", err)
+	}
+
+	out := CLINoStdReport{Crates: make([]CLINoStdCrate, len(report.Crates))}
+	for i, c := range report.Crates {
+		out.Crates[i] = CLINoStdCrate{
+			Crate: c.Crate,
+			Root:  c.Root,
+			NoStd: c.NoStd,
+			Core:  c.Core,
+			Alloc: c.Alloc,
+			Std:   c.Std,
+		}
+	}
+	deps := []CLIStdDependency{}
+	for _, d := range report.Dependencies {
+		if tier != "" && d.Tier != tier {
+			continue
+		}
+		dep := CLIStdDependency{
+			Symbol: symbolResultToCLI(d.Symbol),
+			Crate:  d.Crate,
+			Tier:   d.Tier,
+			Via:    make([]CLISymbol, len(d.Via)),
+		}
+		for i, v := range d.Via {
+			dep.Via[i] = symbolResultToCLI(v)
+		}
+		deps = append(deps, dep)
+	}
+	paged, totalCount := paginateSlice(deps)
+	out.Dependencies = paged
+	return outputResult(CLIResult{
+		Command:    "no-std",
+		Results:    out,
+		TotalCount: &totalCount,
+	})
+}
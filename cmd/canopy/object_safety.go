@@ -0,0 +1,70 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var objectSafetyCmd = &cobra.Command{
+	Use:   "object-safety",
+	Short: "Report which Rust traits are object safe (usable as dyn Trait)",
+	Long: "Checks each Rust trait for object safety and lists why a trait cannot be used as dyn Trait:\n" +
+		"  sized_self               the trait requires Self: Sized\n" +
+		"  supertrait               an indexed supertrait is not object safe\n" +
+		"  associated_const         the trait declares a const\n" +
+		"  generic_associated_type  an associated type has generic parameters\n" +
+		"  no_receiver              an associated function takes no self\n" +
+		"  receiver                 self has a type other than Self behind &, Box, Rc, Arc or Pin\n" +
+		"  generic_method           a method has type parameters or impl Trait arguments\n" +
+		"  self_type                a method names Self outside its receiver (Self::Item is fine)\n" +
+		"  async_method             a method is an async fn\n" +
+		"  impl_trait_return        a method returns impl Trait\n" +
+		"Methods bounded by where Self: Sized are exempt. Each trait's dyn Trait uses are listed too, so a\n" +
+		"trait with violations and uses needs fixing, and one without uses is free to change.",
+	Args: cobra.NoArgs,
+	RunE: runObjectSafety,
+}
+
+func init() {
+	objectSafetyCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	objectSafetyCmd.Flags().Bool("incompatible", false, "only list traits that are not object safe")
+}
+
+func runObjectSafety(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("object-safety", err)
+	}
+	defer s.Close()
+
+	traits, err := queryBuilder(s).ObjectSafety(flagPathPrefix)
+	if err != nil {
+		return outputError("object-safety", err)
+	}
+	incompatible, _ := cmd.Flags().GetBool("incompatible")
+
+	cliTraits := []CLITraitObjectSafety{}
+	for _, t := range traits {
+		if incompatible && t.DynCompatible {
+			continue
+		}
+		ct := CLITraitObjectSafety{
+			Trait:         symbolResultToCLI(t.Trait),
+			DynCompatible: t.DynCompatible,
+			Violations:    make([]CLIObjectSafetyViolation, len(t.Violations)),
+			DynUses:       make([]CLILocation, len(t.DynUses)),
+		}
+		for i, v := range t.Violations {
+			ct.Violations[i] = CLIObjectSafetyViolation{Kind: v.Kind, Member: v.Member, Detail: v.Detail}
+		}
+		for i, loc := range t.DynUses {
+			ct.DynUses[i] = locationToCLI(loc, nil)
+		}
+		cliTraits = append(cliTraits, ct)
+	}
+	paged, totalCount := paginateSlice(cliTraits)
+	return outputResult(CLIResult{
+		Command:    "object-safety",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,75 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var outlineCmd = &cobra.Command{
+	Use:   "outline [path]",
+	Short: "Show the module tree of indexed files",
+	Long: "Prints a hierarchical outline of the indexed files under path (all files if omitted): each file's items,\n" +
+		"impl blocks, and methods, nested by containment. Use --format markdown for a Markdown outline.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runOutline,
+}
+
+func runOutline(cmd *cobra.Command, args []string) error {
+	var path string
+	if len(args) == 1 {
+		var err error
+		path, err = resolveFilePath(args[0])
+		if err != nil {
+			return outputError("outline", err)
+		}
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("outline", err)
+	}
+	defer s.Close()
+
+	files, err := queryBuilder(s).Outline(path)
+	if err != nil {
+		return outputError("outline", err)
+	}
+
+	cliFiles := make([]CLIOutlineFile, len(files))
+	for i, f := range files {
+		cliFiles[i] = CLIOutlineFile{
+			File:     f.File,
+			Language: f.Language,
+			Items:    outlineNodesToCLI(f.Items),
+		}
+		if cliFiles[i].Items == nil {
+			cliFiles[i].Items = []CLIOutlineNode{}
+		}
+	}
+	count := len(cliFiles)
+	return outputResult(CLIResult{
+		Command:    "outline",
+		Results:    cliFiles,
+		TotalCount: &count,
+	})
+}
+
+// outlineNodesToCLI converts outline nodes and their children to CLIOutlineNodes.
+func outlineNodesToCLI(nodes []*canopy.OutlineNode) []CLIOutlineNode {
+	if len(nodes) == 0 {
+		return nil
+	}
+	out := make([]CLIOutlineNode, len(nodes))
+	for i, n := range nodes {
+		out[i] = CLIOutlineNode{
+			SymbolID:   n.SymbolID,
+			Name:       n.Name,
+			Kind:       n.Kind,
+			Visibility: n.Visibility,
+			StartLine:  n.StartLine,
+			EndLine:    n.EndLine,
+			Children:   outlineNodesToCLI(n.Children),
+		}
+	}
+	return out
+}
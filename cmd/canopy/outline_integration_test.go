@@ -0,0 +1,47 @@
+package main_test
+
+import (
+	"encoding/json"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestOutline_JSON(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	stdout, _ := runCanopy(t, bin, fixtureDir, "outline", "main.go")
+	var result map[string]any
+	require.NoError(t, json.Unmarshal([]byte(stdout), &result))
+	assert.Equal(t, "outline", result["command"])
+	assert.Empty(t, result["error"])
+
+	files := result["results"].([]any)
+	require.Len(t, files, 1)
+	file := files[0].(map[string]any)
+	assert.Equal(t, filepath.Join(fixtureDir, "main.go"), file["file"])
+	assert.Equal(t, "go", file["language"])
+
+	var names []string
+	for _, item := range file["items"].([]any) {
+		names = append(names, item.(map[string]any)["name"].(string))
+	}
+	assert.Contains(t, names, "main")
+	assert.Contains(t, names, "helper")
+}
+
+func TestOutline_Markdown(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	out, _ := runCanopy(t, bin, fixtureDir, "outline", "--format", "markdown")
+	assert.Contains(t, out, "## "+filepath.Join(fixtureDir, "main.go")+"\n")
+	assert.Contains(t, out, "- function `helper` (line 8)\n")
+}
@@ -1,8 +1,10 @@
 package main
 
 import (
+	"bufio"
 	"database/sql"
 	"encoding/json"
+	"errors"
 	"fmt"
 	"log"
 	"os"
@@ -19,6 +21,10 @@ var (
 	flagOffset int
 	flagSort   string
 	flagOrder  string
+
+	flagMinConfidence string
+	flagIncludeTests  bool
+	flagExplain       bool
 )
 
 var queryCmd = &cobra.Command{
@@ -30,8 +36,10 @@ var queryCmd = &cobra.Command{
 func init() {
 	queryCmd.PersistentFlags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
 	queryCmd.PersistentFlags().IntVar(&flagOffset, "offset", 0, "pagination offset")
-	queryCmd.PersistentFlags().StringVar(&flagSort, "sort", "", "sort field: name|kind|file|ref_count")
+	queryCmd.PersistentFlags().StringVar(&flagSort, "sort", "", "sort field: name|kind|file|ref_count|complexity|cognitive|pagerank|betweenness")
 	queryCmd.PersistentFlags().StringVar(&flagOrder, "order", "asc", "sort order: asc|desc")
+	queryCmd.PersistentFlags().StringVar(&flagMinConfidence, "min-confidence", "", "skip resolutions below this level in definition, references, and call graph queries: exact|probable|heuristic")
+	queryCmd.PersistentFlags().BoolVar(&flagExplain, "explain", false, "show why each definition, references, callers, and callees result matched: the reference, the scopes searched, and how its name was resolved")
 
 	queryCmd.AddCommand(symbolAtCmd)
 	queryCmd.AddCommand(definitionCmd)
@@ -42,6 +50,7 @@ func init() {
 	queryCmd.AddCommand(symbolsCmd)
 	queryCmd.AddCommand(searchCmd)
 	queryCmd.AddCommand(filesCmd)
+	queryCmd.AddCommand(rootsCmd)
 	queryCmd.AddCommand(packagesCmd)
 	queryCmd.AddCommand(summaryCmd)
 	queryCmd.AddCommand(packageSummaryCmd)
@@ -53,12 +62,51 @@ func init() {
 	queryCmd.AddCommand(implementsCmd)
 	queryCmd.AddCommand(extensionsCmd)
 	queryCmd.AddCommand(reexportsCmd)
+	queryCmd.AddCommand(aliasesCmd)
+	queryCmd.AddCommand(importPathsCmd)
+	queryCmd.AddCommand(upgradeImpactCmd)
 	queryCmd.AddCommand(transitiveCallersCmd)
 	queryCmd.AddCommand(transitiveCalleesCmd)
+	queryCmd.AddCommand(testsCoveringCmd)
+	queryCmd.AddCommand(blockingInAsyncCmd)
+	queryCmd.AddCommand(panicsCmd)
 	queryCmd.AddCommand(packageGraphCmd)
 	queryCmd.AddCommand(circularDepsCmd)
+	queryCmd.AddCommand(packageMetricsCmd)
 	queryCmd.AddCommand(unusedCmd)
 	queryCmd.AddCommand(hotspotsCmd)
+	queryCmd.AddCommand(deprecatedCmd)
+	queryCmd.AddCommand(ffiCmd)
+	queryCmd.AddCommand(errorFlowsCmd)
+	queryCmd.AddCommand(featuresCmd)
+	queryCmd.AddCommand(metricsCmd)
+	queryCmd.AddCommand(duplicatesCmd)
+	queryCmd.AddCommand(crateDuplicatesCmd)
+	queryCmd.AddCommand(ownersCmd)
+	queryCmd.AddCommand(ownerImpactCmd)
+	queryCmd.AddCommand(targetsCmd)
+	queryCmd.AddCommand(impactedTargetsCmd)
+	queryCmd.AddCommand(staleCmd)
+	queryCmd.AddCommand(busFactorCmd)
+	queryCmd.AddCommand(hotCmd)
+	queryCmd.AddCommand(queryCoverageCmd)
+	queryCmd.AddCommand(visibleFromCmd)
+	queryCmd.AddCommand(shadowsCmd)
+	queryCmd.AddCommand(globCollisionsCmd)
+	queryCmd.AddCommand(implsCmd)
+	queryCmd.AddCommand(implBlocksCmd)
+	queryCmd.AddCommand(instantiationsCmd)
+	queryCmd.AddCommand(instantiationHotspotsCmd)
+	queryCmd.AddCommand(objectSafetyCmd)
+	queryCmd.AddCommand(entryPointsCmd)
+	queryCmd.AddCommand(unreachableCmd)
+	queryCmd.AddCommand(testRegionsCmd)
+	queryCmd.AddCommand(newtypesCmd)
+	queryCmd.AddCommand(fieldGraphCmd)
+	queryCmd.AddCommand(moduleMatrixCmd)
+	queryCmd.AddCommand(noStdCmd)
+	queryCmd.AddCommand(binarySizeCmd)
+	queryCmd.AddCommand(sqlTableCmd)
 }
 
 // --- Helpers ---
@@ -70,12 +118,21 @@ func openStore() (*store.Store, error) {
 		return nil, fmt.Errorf("getting cwd: %w", err)
 	}
 	repoRoot := findRepoRoot(cwd)
-	dbPath := resolveDBPath(repoRoot)
+	dbPath, err := resolveDBPath(repoRoot)
+	if err != nil {
+		return nil, err
+	}
 
 	if _, err := os.Stat(dbPath); os.IsNotExist(err) {
+		if flagSnapshot != "" {
+			return nil, fmt.Errorf("snapshot not found: %s (run 'canopy snapshot save %s' first)", flagSnapshot, flagSnapshot)
+		}
 		return nil, fmt.Errorf("database not found: %s (run 'canopy index' first)", dbPath)
 	}
 
+	if batchStore != nil && flagSnapshot == "" {
+		return batchStore.Shared(), nil
+	}
 	return store.NewStore(dbPath)
 }
 
@@ -140,10 +197,31 @@ func resolveSymbolID(cmd *cobra.Command, args []string, qb *canopy.QueryBuilder)
 	return sym.ID, nil
 }
 
-// outputResult marshals a CLIResult to stdout in the selected format.
+// outputResult marshals a CLIResult to stdout in the selected format, or
+// hands it to resultSink during a batch.
 func outputResult(result CLIResult) error {
-	if flagFormat == "text" {
+	recordCommandUsage(result)
+	if resultSink != nil {
+		resultSink(result)
+		return nil
+	}
+	switch flagFormat {
+	case "text":
 		return outputResultText(result)
+	case "ndjson":
+		return outputResultNDJSON(result)
+	case "markdown":
+		return outputResultMarkdown(result)
+	case "sarif":
+		return outputResultSARIF(result)
+	case "github":
+		return outputResultGitHub(result)
+	case "gitlab":
+		return outputResultGitLab(result)
+	case "csv":
+		return outputResultCSV(result)
+	case "html":
+		return outputResultHTML(result)
 	}
 	enc := json.NewEncoder(os.Stdout)
 	enc.SetIndent("", "  ")
@@ -152,10 +230,18 @@ func outputResult(result CLIResult) error {
 
 // outputError writes an error in the selected format and returns it so RunE
 // can propagate it to Cobra. In JSON mode the error is written to stdout as a
-// CLIResult envelope. In text mode it goes to stderr.
+// CLIResult envelope (a single compact line in NDJSON mode). In text,
+// Markdown, SARIF, GitHub, GitLab, CSV, and HTML mode it goes to stderr.
+// During a batch it goes to resultSink.
 func outputError(command string, err error) error {
 	errorHandled = true
-	if flagFormat == "text" {
+	recordCommandUsage(CLIResult{Command: command, Error: err.Error()})
+	if resultSink != nil {
+		resultSink(CLIResult{Command: command, Error: err.Error()})
+		return err
+	}
+	switch flagFormat {
+	case "text", "markdown", "sarif", "github", "gitlab", "csv", "html":
 		fmt.Fprintf(os.Stderr, "Error: %s\n", err)
 		return err
 	}
@@ -164,11 +250,29 @@ func outputError(command string, err error) error {
 		Error:   err.Error(),
 	}
 	enc := json.NewEncoder(os.Stdout)
-	enc.SetIndent("", "  ")
+	if flagFormat != "ndjson" {
+		enc.SetIndent("", "  ")
+	}
 	_ = enc.Encode(result)
 	return err
 }
 
+// checkRule applies the configured severity of rule to a finding count after
+// results have been written. At "error" it returns an error so the command
+// exits non-zero; at "warning" it prints the count to stderr.
+func checkRule(rule string, findings int) error {
+	if findings == 0 {
+		return nil
+	}
+	switch projectConfig.ruleSeverity(rule) {
+	case severityError:
+		return fmt.Errorf("rule %s: %d finding(s)", rule, findings)
+	case severityWarning:
+		fmt.Fprintf(os.Stderr, "warning: rule %s: %d finding(s)\n", rule, findings)
+	}
+	return nil
+}
+
 // buildPagination creates a Pagination from CLI flags.
 func buildPagination() canopy.Pagination {
 	return canopy.Pagination{
@@ -205,6 +309,44 @@ func paginateSlice[T any](items []T) ([]T, int) {
 	return items, total
 }
 
+// errStopStream is returned by a streamNDJSON emit function once --limit
+// results have been written.
+var errStopStream = errors.New("stop stream")
+
+// streamNDJSON writes the results produced by each as NDJSON lines as they
+// arrive, so memory stays flat regardless of result count. --offset skips
+// leading results; --limit applies only when passed explicitly and is not
+// capped, since streaming exists for result sets larger than one page.
+func streamNDJSON[T any](cmd *cobra.Command, each func(emit func(T) error) error) error {
+	limit := -1
+	if cmd.Flags().Changed("limit") {
+		limit = flagLimit
+	}
+	skip := max(flagOffset, 0)
+
+	w := bufio.NewWriter(os.Stdout)
+	enc := json.NewEncoder(w)
+	written := 0
+	err := each(func(item T) error {
+		if skip > 0 {
+			skip--
+			return nil
+		}
+		if limit >= 0 && written >= limit {
+			return errStopStream
+		}
+		written++
+		return enc.Encode(item)
+	})
+	if errors.Is(err, errStopStream) {
+		err = nil
+	}
+	if flushErr := w.Flush(); err == nil {
+		err = flushErr
+	}
+	return err
+}
+
 // buildSort creates a Sort from CLI flags.
 func buildSort() canopy.Sort {
 	var field canopy.SortField
@@ -219,6 +361,14 @@ func buildSort() canopy.Sort {
 		field = canopy.SortByRefCount
 	case "external_ref_count":
 		field = canopy.SortByExternalRefCount
+	case "cyclomatic", "complexity":
+		field = canopy.SortByCyclomatic
+	case "cognitive":
+		field = canopy.SortByCognitive
+	case "pagerank":
+		field = canopy.SortByPageRank
+	case "betweenness":
+		field = canopy.SortByBetweenness
 	default:
 		field = canopy.SortByName
 	}
@@ -237,17 +387,24 @@ func buildSort() canopy.Sort {
 // symbolToCLI converts a store.Symbol to a CLISymbol.
 func symbolToCLI(sym *store.Symbol, filePath string, refCount int) CLISymbol {
 	return CLISymbol{
-		ID:         sym.ID,
-		Name:       sym.Name,
-		Kind:       sym.Kind,
-		Visibility: sym.Visibility,
-		Modifiers:  sym.Modifiers,
-		File:       filePath,
-		StartLine:  sym.StartLine,
-		StartCol:   sym.StartCol,
-		EndLine:    sym.EndLine,
-		EndCol:     sym.EndCol,
-		RefCount:   refCount,
+		ID:            sym.ID,
+		Name:          sym.Name,
+		Kind:          sym.Kind,
+		Subkind:       sym.Subkind,
+		Visibility:    sym.Visibility,
+		Modifiers:     sym.Modifiers,
+		File:          filePath,
+		StartLine:     sym.StartLine,
+		StartCol:      sym.StartCol,
+		EndLine:       sym.EndLine,
+		EndCol:        sym.EndCol,
+		StartByte:     sym.StartByte,
+		EndByte:       sym.EndByte,
+		UTF16StartCol: sym.UTF16StartCol,
+		UTF16EndCol:   sym.UTF16EndCol,
+		PageRank:      sym.PageRank,
+		Betweenness:   sym.Betweenness,
+		RefCount:      refCount,
 	}
 }
 
@@ -257,6 +414,7 @@ func symbolResultToCLI(sr canopy.SymbolResult) CLISymbol {
 		ID:               sr.ID,
 		Name:             sr.Name,
 		Kind:             sr.Kind,
+		Subkind:          sr.Subkind,
 		Visibility:       sr.Visibility,
 		Modifiers:        sr.Modifiers,
 		File:             sr.FilePath,
@@ -264,6 +422,12 @@ func symbolResultToCLI(sr canopy.SymbolResult) CLISymbol {
 		StartCol:         sr.StartCol,
 		EndLine:          sr.EndLine,
 		EndCol:           sr.EndCol,
+		StartByte:        sr.StartByte,
+		EndByte:          sr.EndByte,
+		UTF16StartCol:    sr.UTF16StartCol,
+		UTF16EndCol:      sr.UTF16EndCol,
+		PageRank:         sr.PageRank,
+		Betweenness:      sr.Betweenness,
 		RefCount:         sr.RefCount,
 		ExternalRefCount: sr.ExternalRefCount,
 		InternalRefCount: sr.InternalRefCount,
@@ -273,13 +437,80 @@ func symbolResultToCLI(sr canopy.SymbolResult) CLISymbol {
 // locationToCLI converts a canopy.Location to a CLILocation.
 func locationToCLI(loc canopy.Location, symbolID *int64) CLILocation {
 	return CLILocation{
-		File:      loc.File,
-		StartLine: loc.StartLine,
-		StartCol:  loc.StartCol,
-		EndLine:   loc.EndLine,
-		EndCol:    loc.EndCol,
-		SymbolID:  symbolID,
+		File:          loc.File,
+		StartLine:     loc.StartLine,
+		StartCol:      loc.StartCol,
+		EndLine:       loc.EndLine,
+		EndCol:        loc.EndCol,
+		StartByte:     loc.StartByte,
+		EndByte:       loc.EndByte,
+		UTF16StartCol: loc.UTF16StartCol,
+		UTF16EndCol:   loc.UTF16EndCol,
+		SymbolID:      symbolID,
+	}
+}
+
+// referenceSiteToCLI converts a ReferenceSite to a CLILocation carrying the
+// referenced symbol's ID and the reference kind.
+func referenceSiteToCLI(site canopy.ReferenceSite, symbolID int64) CLILocation {
+	loc := locationToCLI(site.Location, &symbolID)
+	loc.Kind = site.Kind
+	loc.Confidence = site.Confidence
+	return loc
+}
+
+// explainReferenceSite attaches the explanation of why site resolved to
+// symbolID to loc.
+func explainReferenceSite(qb *canopy.QueryBuilder, loc *CLILocation, site canopy.ReferenceSite, symbolID int64) error {
+	ex, err := qb.ExplainReference(site.ReferenceID, symbolID)
+	if err != nil {
+		return err
+	}
+	if ex != nil {
+		loc.Explanation = explanationToCLI(*ex)
+	}
+	return nil
+}
+
+// explanationToCLI converts an Explanation to its CLI form.
+func explanationToCLI(ex canopy.Explanation) *CLIExplanation {
+	out := &CLIExplanation{
+		ReferenceID:    ex.ReferenceID,
+		Name:           ex.Name,
+		Kind:           ex.Kind,
+		TargetID:       ex.TargetSymbolID,
+		ResolutionKind: ex.ResolutionKind,
+		Confidence:     ex.Confidence,
+		Steps:          ex.Steps(),
+	}
+	if ex.Import != nil {
+		out.Import = &CLIExplainedImport{
+			Source:       ex.Import.Source,
+			ImportedName: ex.Import.ImportedName,
+			LocalAlias:   ex.Import.LocalAlias,
+		}
+	}
+	for _, sc := range ex.Scopes {
+		out.Scopes = append(out.Scopes, CLIExplainedScope{
+			Kind:       sc.Kind,
+			SymbolID:   sc.SymbolID,
+			SymbolName: sc.SymbolName,
+			StartLine:  sc.StartLine,
+			EndLine:    sc.EndLine,
+		})
 	}
+	return out
+}
+
+// queryBuilder returns a QueryBuilder that honors --include-tests.
+func queryBuilder(s *store.Store) *canopy.QueryBuilder {
+	return canopy.NewQueryBuilder(s).WithTestCode(flagIncludeTests)
+}
+
+// edgeQueryBuilder returns a QueryBuilder that honors --min-confidence and
+// --include-tests.
+func edgeQueryBuilder(s *store.Store) (*canopy.QueryBuilder, error) {
+	return queryBuilder(s).WithMinConfidence(flagMinConfidence)
 }
 
 // lookupSymbolName fetches just the name of a symbol by ID.
@@ -336,7 +567,7 @@ func runSymbolAt(cmd *cobra.Command, args []string) error {
 		return outputError("symbol-at", err)
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	sym, err := qb.SymbolAt(file, line, col)
 	if err != nil {
 		return outputError("symbol-at", err)
@@ -387,7 +618,10 @@ func runDefinition(cmd *cobra.Command, args []string) error {
 		return outputError("definition", err)
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("definition", err)
+	}
 	locs, err := qb.DefinitionAt(file, line, col)
 	if err != nil {
 		return outputError("definition", err)
@@ -401,6 +635,22 @@ func runDefinition(cmd *cobra.Command, args []string) error {
 		}
 		cliLocs[i] = locationToCLI(loc, symID)
 	}
+	if flagExplain {
+		explanations, err := qb.ExplainDefinitionAt(file, line, col)
+		if err != nil {
+			return outputError("definition", err)
+		}
+		// Each explanation belongs to the first location of its target not
+		// yet explained, as DefinitionAt lists a location per resolution.
+		for _, ex := range explanations {
+			for i, loc := range locs {
+				if ex.Target != nil && *ex.Target == loc && cliLocs[i].Explanation == nil {
+					cliLocs[i].Explanation = explanationToCLI(ex)
+					break
+				}
+			}
+		}
+	}
 
 	defCount := len(cliLocs)
 	return outputResult(CLIResult{
@@ -422,6 +672,7 @@ var referencesCmd = &cobra.Command{
 
 func init() {
 	referencesCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	referencesCmd.Flags().StringSlice("kind", nil, "only references of these kinds: call, type_annotation, trait_bound, pattern, field_access, import, read, macro (repeatable or comma-separated)")
 }
 
 func runReferences(cmd *cobra.Command, args []string) error {
@@ -431,27 +682,55 @@ func runReferences(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("references", err)
+	}
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("references", err)
 	}
 
-	locs, err := qb.ReferencesTo(symID)
+	kinds, _ := cmd.Flags().GetStringSlice("kind")
+	filter := canopy.ReferenceFilter{Kinds: kinds}
+
+	if flagFormat == "ndjson" && resultSink == nil {
+		err := streamNDJSON(cmd, func(emit func(CLILocation) error) error {
+			return qb.EachReferenceTo(symID, filter, func(site canopy.ReferenceSite) error {
+				loc := referenceSiteToCLI(site, symID)
+				if flagExplain {
+					if err := explainReferenceSite(qb, &loc, site, symID); err != nil {
+						return err
+					}
+				}
+				return emit(loc)
+			})
+		})
+		if err != nil {
+			return outputError("references", err)
+		}
+		return nil
+	}
+
+	result, err := qb.ReferencesToPaged(symID, filter, buildPagination())
 	if err != nil {
 		return outputError("references", err)
 	}
 
-	cliLocs := make([]CLILocation, len(locs))
-	for i, loc := range locs {
-		cliLocs[i] = locationToCLI(loc, &symID)
+	cliLocs := make([]CLILocation, len(result.Items))
+	for i, site := range result.Items {
+		cliLocs[i] = referenceSiteToCLI(site, symID)
+		if flagExplain {
+			if err := explainReferenceSite(qb, &cliLocs[i], site, symID); err != nil {
+				return outputError("references", err)
+			}
+		}
 	}
 
-	paged, totalCount := paginateSlice(cliLocs)
 	return outputResult(CLIResult{
 		Command:    "references",
-		Results:    paged,
-		TotalCount: &totalCount,
+		Results:    cliLocs,
+		TotalCount: &result.TotalCount,
 	})
 }
 
@@ -474,7 +753,10 @@ func runCallers(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("callers", err)
+	}
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("callers", err)
@@ -495,6 +777,19 @@ func runCallers(cmd *cobra.Command, args []string) error {
 			File:       lookupFilePath(s, e.FileID),
 			Line:       e.Line,
 			Col:        e.Col,
+			Confidence: canopy.ConfidenceLevel(e.Confidence),
+			Awaited:    e.Awaited,
+			Kind:       e.Kind,
+			Propagated: e.Propagated,
+		}
+		if flagExplain {
+			explanations, err := qb.ExplainCall(e)
+			if err != nil {
+				return outputError("callers", err)
+			}
+			for _, ex := range explanations {
+				cliEdges[i].Explanation = append(cliEdges[i].Explanation, *explanationToCLI(ex))
+			}
 		}
 	}
 
@@ -525,7 +820,10 @@ func runCallees(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("callees", err)
+	}
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("callees", err)
@@ -546,6 +844,19 @@ func runCallees(cmd *cobra.Command, args []string) error {
 			File:       lookupFilePath(s, e.FileID),
 			Line:       e.Line,
 			Col:        e.Col,
+			Confidence: canopy.ConfidenceLevel(e.Confidence),
+			Awaited:    e.Awaited,
+			Kind:       e.Kind,
+			Propagated: e.Propagated,
+		}
+		if flagExplain {
+			explanations, err := qb.ExplainCall(e)
+			if err != nil {
+				return outputError("callees", err)
+			}
+			for _, ex := range explanations {
+				cliEdges[i].Explanation = append(cliEdges[i].Explanation, *explanationToCLI(ex))
+			}
 		}
 	}
 
@@ -576,7 +887,7 @@ func runImplementations(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("implementations", err)
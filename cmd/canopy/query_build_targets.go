@@ -0,0 +1,124 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+)
+
+// --- Build Target Commands ---
+
+var targetsCmd = &cobra.Command{
+	Use:   "targets [<file>]",
+	Short: "Show the Bazel or Buck targets of a file or symbol",
+	Long:  "Returns the build targets that list the file as a source, from the BUILD and BUCK files of its indexed root.\nAccepts either a <file> argument or --symbol <id>.",
+	Args:  cobra.MaximumNArgs(1),
+	RunE:  runTargets,
+}
+
+var impactedTargetsCmd = &cobra.Command{
+	Use:   "impacted-targets [<file> <line> <col>]",
+	Short: "List the build targets affected by a change to a symbol",
+	Long:  "Returns the targets whose sources define, reference, or transitively call the symbol (up to --max-depth), and every target that depends on them.\nAccepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args:  cobra.MaximumNArgs(3),
+	RunE:  runImpactedTargets,
+}
+
+func init() {
+	targetsCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+
+	impactedTargetsCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	impactedTargetsCmd.Flags().Int("max-depth", 5, "maximum caller traversal depth (0-100)")
+}
+
+func runTargets(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("targets", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	var result CLIBuildTargets
+	if cmd.Flags().Changed("symbol") {
+		symID, _ := cmd.Flags().GetInt64("symbol")
+		sym, err := s.SymbolByID(symID)
+		if err != nil {
+			return outputError("targets", err)
+		}
+		if sym == nil {
+			return outputError("targets", fmt.Errorf("symbol not found: %d", symID))
+		}
+		result.File = lookupFilePath(s, sym.FileID)
+		result.SymbolID = &symID
+		result.Targets, err = qb.SymbolBuildTargets(symID)
+		if err != nil {
+			return outputError("targets", err)
+		}
+	} else {
+		if len(args) < 1 {
+			return outputError("targets", fmt.Errorf("requires either a <file> argument or --symbol flag"))
+		}
+		filePath, err := resolveFilePath(args[0])
+		if err != nil {
+			return outputError("targets", err)
+		}
+		f, err := s.FileByPath(filePath)
+		if err != nil {
+			return outputError("targets", fmt.Errorf("looking up file %q: %w", args[0], err))
+		}
+		if f == nil {
+			return outputError("targets", fmt.Errorf("file not found: %s", args[0]))
+		}
+		result.File = f.Path
+		result.Targets, err = qb.FileBuildTargets(f.ID)
+		if err != nil {
+			return outputError("targets", err)
+		}
+	}
+
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "targets",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
+
+func runImpactedTargets(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("impacted-targets", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("impacted-targets", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	impacts, err := qb.ImpactedTargets(symID, maxDepth)
+	if err != nil {
+		return outputError("impacted-targets", err)
+	}
+
+	cliImpacts := make([]CLITargetImpact, len(impacts))
+	for i, imp := range impacts {
+		cliImpacts[i] = CLITargetImpact{
+			Label:  imp.Label,
+			Kind:   imp.Kind,
+			Reason: imp.Reason,
+			Via:    imp.Via,
+			Depth:  imp.Depth,
+		}
+	}
+
+	paged, total := paginateSlice(cliImpacts)
+	return outputResult(CLIResult{
+		Command:    "impacted-targets",
+		Results:    paged,
+		TotalCount: &total,
+	})
+}
@@ -28,7 +28,7 @@ func runSymbolDetail(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 
 	var detail *canopy.SymbolDetail
 
@@ -102,7 +102,7 @@ func runScopeAt(cmd *cobra.Command, args []string) error {
 		return outputError("scope-at", err)
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	scopes, err := qb.ScopeAt(file, line, col)
 	if err != nil {
 		return outputError("scope-at", err)
@@ -162,10 +162,20 @@ func symbolDetailToCLI(d *canopy.SymbolDetail) CLISymbolDetail {
 		cli.TypeParams[i] = CLITypeParam{
 			Name:        tp.Name,
 			Ordinal:     tp.Ordinal,
+			Kind:        tp.ParamKind,
 			Constraints: tp.Constraints,
 		}
 	}
 
+	cli.TypeBounds = make([]CLITypeBound, len(d.TypeBounds))
+	for i, tb := range d.TypeBounds {
+		cli.TypeBounds[i] = CLITypeBound{
+			Subject:       tb.Subject,
+			Bound:         tb.Bound,
+			InWhereClause: tb.InWhereClause,
+		}
+	}
+
 	cli.Annotations = make([]CLIAnnotation, len(d.Annotations))
 	for i, a := range d.Annotations {
 		cli.Annotations[i] = CLIAnnotation{
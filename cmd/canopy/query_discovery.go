@@ -17,8 +17,15 @@ var (
 	flagPathPrefix string
 	flagLanguage   string
 	flagPrefix     string
+	flagAttrs      []string
+	flagBounds     []string
 )
 
+// kindFlagUsage describes the --kind flag of the commands that list symbols.
+// Subkinds narrow a kind: associated_function, tuple_struct, unit_struct,
+// and record_struct.
+const kindFlagUsage = "filter by symbol kind or subkind (e.g. function, method, struct, associated_function, tuple_struct)"
+
 var symbolsCmd = &cobra.Command{
 	Use:   "symbols",
 	Short: "List symbols with optional filters",
@@ -26,13 +33,18 @@ var symbolsCmd = &cobra.Command{
 }
 
 func init() {
-	symbolsCmd.Flags().StringVar(&flagKind, "kind", "", "filter by symbol kind (e.g. function, type)")
+	symbolsCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
 	symbolsCmd.Flags().StringVar(&flagFile, "file", "", "filter by file path")
 	symbolsCmd.Flags().StringVar(&flagVisibility, "visibility", "", "filter by visibility (public, private)")
 	symbolsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	symbolsCmd.Flags().StringSliceVar(&flagAttrs, "attr", nil, "filter by attribute/annotation name (repeatable, e.g. test, deprecated)")
+	symbolsCmd.Flags().StringSliceVar(&flagBounds, "bound", nil, "filter by generic bound trait/interface name (repeatable, e.g. Processor)")
 	symbolsCmd.Flags().Int("ref-count-min", 0, "minimum reference count")
 	symbolsCmd.Flags().Int("ref-count-max", 0, "maximum reference count")
 
+	searchCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	searchCmd.Flags().StringSliceVar(&flagAttrs, "attr", nil, "filter by attribute/annotation name (repeatable, e.g. test, deprecated)")
+	searchCmd.Flags().StringSliceVar(&flagBounds, "bound", nil, "filter by generic bound trait/interface name (repeatable, e.g. Processor)")
 	searchCmd.Flags().Int("ref-count-min", 0, "minimum reference count")
 	searchCmd.Flags().Int("ref-count-max", 0, "maximum reference count")
 }
@@ -54,6 +66,8 @@ func runSymbols(cmd *cobra.Command, args []string) error {
 	if flagPathPrefix != "" {
 		filter.PathPrefix = &flagPathPrefix
 	}
+	filter.Annotations = flagAttrs
+	filter.Bounds = flagBounds
 	if cmd.Flags().Changed("ref-count-min") {
 		v, _ := cmd.Flags().GetInt("ref-count-min")
 		filter.RefCountMin = intPtr(v)
@@ -77,7 +91,7 @@ func runSymbols(cmd *cobra.Command, args []string) error {
 		filter.FileID = &f.ID
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	result, err := qb.Symbols(filter, buildSort(), buildPagination())
 	if err != nil {
 		return outputError("symbols", err)
@@ -111,7 +125,10 @@ func runSearch(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	filter := canopy.SymbolFilter{}
+	filter := canopy.SymbolFilter{Annotations: flagAttrs, Bounds: flagBounds}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
 	if cmd.Flags().Changed("ref-count-min") {
 		v, _ := cmd.Flags().GetInt("ref-count-min")
 		filter.RefCountMin = intPtr(v)
@@ -121,7 +138,7 @@ func runSearch(cmd *cobra.Command, args []string) error {
 		filter.RefCountMax = intPtr(v)
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	result, err := qb.SearchSymbols(args[0], filter, buildSort(), buildPagination())
 	if err != nil {
 		return outputError("search", err)
@@ -158,7 +175,7 @@ func runFiles(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	result, err := qb.Files(flagPrefix, flagLanguage, buildSort(), buildPagination())
 	if err != nil {
 		return outputError("files", err)
@@ -171,6 +188,7 @@ func runFiles(cmd *cobra.Command, args []string) error {
 			Path:      f.Path,
 			Language:  f.Language,
 			LineCount: f.LineCount,
+			Root:      f.Root,
 		}
 	}
 
@@ -182,6 +200,39 @@ func runFiles(cmd *cobra.Command, args []string) error {
 	})
 }
 
+var rootsCmd = &cobra.Command{
+	Use:   "roots",
+	Short: "List indexed root directories with file counts",
+	Args:  cobra.NoArgs,
+	RunE:  runRoots,
+}
+
+func runRoots(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("roots", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	roots, err := qb.Roots()
+	if err != nil {
+		return outputError("roots", err)
+	}
+
+	cliRoots := make([]CLIRoot, len(roots))
+	for i, r := range roots {
+		cliRoots[i] = CLIRoot{Root: r.Root, FileCount: r.FileCount, LineCount: r.LineCount}
+	}
+
+	count := len(cliRoots)
+	return outputResult(CLIResult{
+		Command:    "roots",
+		Results:    cliRoots,
+		TotalCount: &count,
+	})
+}
+
 var packagesCmd = &cobra.Command{
 	Use:   "packages",
 	Short: "List packages/modules/namespaces",
@@ -202,7 +253,7 @@ func runPackages(cmd *cobra.Command, args []string) error {
 
 	prefix, _ := cmd.Flags().GetString("prefix")
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	result, err := qb.Packages(prefix, buildSort(), buildPagination())
 	if err != nil {
 		return outputError("packages", err)
@@ -234,7 +285,7 @@ func runSummary(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	summary, err := qb.ProjectSummary(10)
 	if err != nil {
 		return outputError("summary", err)
@@ -281,7 +332,7 @@ func runPackageSummary(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 
 	var pkgPath string
 	var pkgID *int64
@@ -345,7 +396,7 @@ func runDeps(cmd *cobra.Command, args []string) error {
 		return outputError("deps", fmt.Errorf("file not found: %s", args[0]))
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	imports, err := qb.Dependencies(f.ID)
 	if err != nil {
 		return outputError("deps", err)
@@ -385,7 +436,7 @@ func runDependents(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	imports, err := qb.Dependents(args[0])
 	if err != nil {
 		return outputError("dependents", err)
@@ -1,6 +1,8 @@
 package main
 
 import (
+	"fmt"
+
 	"github.com/jward/canopy"
 	"github.com/spf13/cobra"
 )
@@ -23,6 +25,51 @@ var transitiveCalleesCmd = &cobra.Command{
 	RunE:  runTransitiveCallees,
 }
 
+var testsCoveringCmd = &cobra.Command{
+	Use:   "tests-covering [<file> <line> <col>]",
+	Short: "Find tests whose call graph reaches a symbol",
+	Long:  "Returns #[test] / #[cfg(test)] functions that transitively call the symbol, up to --max-depth.\nAccepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args:  cobra.MaximumNArgs(3),
+	RunE:  runTestsCovering,
+}
+
+var blockingInAsyncCmd = &cobra.Command{
+	Use:   "blocking-in-async",
+	Short: "Find blocking calls reachable from async functions",
+	Long:  "Reports calls to blocking std APIs (thread::sleep, fs, Mutex::lock, ...) made by async functions directly or through\nsynchronous functions they call, up to --max-depth. Awaited calls, spawned work, and calls into other async functions are not followed.",
+	Args:  cobra.NoArgs,
+	RunE:  runBlockingInAsync,
+}
+
+var panicsCmd = &cobra.Command{
+	Use:   "panics [<file> <line> <col>]",
+	Short: "Find panics reachable from a function",
+	Long: "Reports panic!/unreachable!/todo!/assert! macros, unwrap()/expect() calls, and indexing in the function or in\n" +
+		"functions it calls up to --max-depth, each with the shortest call path to it. Spawned work is not followed.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.MaximumNArgs(3),
+	RunE: runPanics,
+}
+
+var featuresCmd = &cobra.Command{
+	Use:   "features",
+	Short: "Map Cargo features to the public API they gate",
+	Long: "Lists, for each Cargo feature, the public Rust items available only when it is enabled, with the full cfg condition\n" +
+		"gating each (its own #[cfg] plus those of its impl block, parent type, and enclosing modules and module files).",
+	Args: cobra.NoArgs,
+	RunE: runFeatures,
+}
+
+var errorFlowsCmd = &cobra.Command{
+	Use:   "error-flows",
+	Short: "Map which error types flow through which functions",
+	Long: "Lists Rust functions with the error types that can surface from them: the one their Result return type declares,\n" +
+		"plus those of every call whose error they propagate with ?, transitively. --error io::Error answers which functions\n" +
+		"can surface io::Error; a path suffix matches, so io::Error also matches std::io::Error.",
+	Args: cobra.NoArgs,
+	RunE: runErrorFlows,
+}
+
 var packageGraphCmd = &cobra.Command{
 	Use:   "package-graph",
 	Short: "Show the package dependency graph",
@@ -43,6 +90,23 @@ var unusedCmd = &cobra.Command{
 	RunE:  runUnused,
 }
 
+var deprecatedCmd = &cobra.Command{
+	Use:   "deprecated",
+	Short: "List references to deprecated symbols",
+	Long:  "Flags every resolved reference to a symbol marked deprecated, with the deprecation note.",
+	Args:  cobra.NoArgs,
+	RunE:  runDeprecated,
+}
+
+var ffiCmd = &cobra.Command{
+	Use:   "ffi",
+	Short: "List FFI entry and exit points",
+	Long: "Lists the symbols that cross the FFI boundary: functions and statics of extern blocks (imports, implemented\n" +
+		"outside Rust) and extern fn definitions and #[no_mangle] / #[export_name] items (exports), with their ABI and link name.",
+	Args: cobra.NoArgs,
+	RunE: runFFI,
+}
+
 var hotspotsCmd = &cobra.Command{
 	Use:   "hotspots",
 	Short: "Show most-referenced symbols with call metrics",
@@ -50,6 +114,31 @@ var hotspotsCmd = &cobra.Command{
 	RunE:  runHotspots,
 }
 
+var packageMetricsCmd = &cobra.Command{
+	Use:   "package-metrics",
+	Short: "Show coupling and fan-in/fan-out metrics per package",
+	Long:  "Reports afferent/efferent coupling, instability, and fan-in/fan-out for each package.\nWith --check, compares against the [metrics] thresholds in .canopy.toml; violations fall under the package_metrics rule (error by default, so the command exits non-zero).",
+	Args:  cobra.NoArgs,
+	RunE:  runPackageMetrics,
+}
+
+var metricsCmd = &cobra.Command{
+	Use:   "metrics",
+	Short: "List per-function complexity metrics",
+	Long:  "Lists cyclomatic and cognitive complexity for every function and method. Use --sort complexity --order desc to surface the most complex code first.",
+	Args:  cobra.NoArgs,
+	RunE:  runMetrics,
+}
+
+var duplicatesCmd = &cobra.Command{
+	Use:   "duplicates",
+	Short: "Find duplicated function bodies",
+	Long: "Reports pairs of functions and methods with structurally similar bodies, most similar first. Bodies are compared\n" +
+		"statement by statement over normalized syntax trees, so renamed variables and changed literals still match.",
+	Args: cobra.NoArgs,
+	RunE: runDuplicates,
+}
+
 func init() {
 	transitiveCallersCmd.Flags().Int64("symbol", 0, "symbol ID to query")
 	transitiveCallersCmd.Flags().Int("max-depth", 5, "maximum traversal depth (1-100)")
@@ -57,11 +146,37 @@ func init() {
 	transitiveCalleesCmd.Flags().Int64("symbol", 0, "symbol ID to query")
 	transitiveCalleesCmd.Flags().Int("max-depth", 5, "maximum traversal depth (1-100)")
 
-	unusedCmd.Flags().StringVar(&flagKind, "kind", "", "filter by symbol kind (e.g. function, type)")
+	testsCoveringCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	testsCoveringCmd.Flags().Int("max-depth", 10, "maximum traversal depth (1-100)")
+
+	blockingInAsyncCmd.Flags().Int("max-depth", 5, "maximum traversal depth (1-100)")
+
+	panicsCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	panicsCmd.Flags().Int("max-depth", 10, "maximum traversal depth (1-100)")
+	panicsCmd.Flags().StringSlice("site-kind", nil, "filter by site kind (panic, unwrap, index; repeatable)")
+
+	featuresCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+
+	errorFlowsCmd.Flags().String("error", "", "keep functions that can surface this error type (e.g. io::Error)")
+	errorFlowsCmd.Flags().Bool("public", false, "keep public functions only")
+	errorFlowsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+
+	unusedCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
 	unusedCmd.Flags().StringVar(&flagVisibility, "visibility", "", "filter by visibility (public, private)")
 	unusedCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	unusedCmd.Flags().StringSliceVar(&flagAttrs, "attr", nil, "filter by attribute/annotation name (repeatable)")
 
 	hotspotsCmd.Flags().Int("top", 10, "number of top hotspots to return")
+
+	ffiCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+
+	packageMetricsCmd.Flags().Bool("check", false, "fail if any package exceeds the thresholds in .canopy.toml")
+
+	metricsCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	metricsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+
+	duplicatesCmd.Flags().Float64("min-similarity", 0.8, "minimum similarity between bodies (0-1)")
+	duplicatesCmd.Flags().Int("min-statements", 3, "ignore bodies with fewer statements")
 }
 
 func runTransitiveCallers(cmd *cobra.Command, args []string) error {
@@ -71,7 +186,10 @@ func runTransitiveCallers(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("transitive-callers", err)
+	}
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("transitive-callers", err)
@@ -106,7 +224,10 @@ func runTransitiveCallees(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("transitive-callees", err)
+	}
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("transitive-callees", err)
@@ -134,6 +255,118 @@ func runTransitiveCallees(cmd *cobra.Command, args []string) error {
 	})
 }
 
+func runTestsCovering(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("tests-covering", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("tests-covering", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	nodes, err := qb.TestsCovering(symID, maxDepth)
+	if err != nil {
+		return outputError("tests-covering", err)
+	}
+
+	cliNodes := make([]CLICallGraphNode, len(nodes))
+	for i, n := range nodes {
+		cliNodes[i] = CLICallGraphNode{
+			Symbol: symbolResultToCLI(n.Symbol),
+			Depth:  n.Depth,
+		}
+	}
+
+	paged, totalCount := paginateSlice(cliNodes)
+	return outputResult(CLIResult{
+		Command:    "tests-covering",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runBlockingInAsync(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("blocking-in-async", err)
+	}
+	defer s.Close()
+
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("blocking-in-async", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	calls, err := qb.BlockingCallsInAsync(maxDepth)
+	if err != nil {
+		return outputError("blocking-in-async", err)
+	}
+
+	cliCalls := make([]CLIBlockingCall, len(calls))
+	for i, c := range calls {
+		cliCalls[i] = CLIBlockingCall{
+			AsyncFn: symbolResultToCLI(c.AsyncFn),
+			API:     c.API,
+			Path:    callGraphEdgesToCLI(c.Path),
+		}
+	}
+
+	paged, totalCount := paginateSlice(cliCalls)
+	return outputResult(CLIResult{
+		Command:    "blocking-in-async",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runPanics(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("panics", err)
+	}
+	defer s.Close()
+
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("panics", err)
+	}
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("panics", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	kinds, _ := cmd.Flags().GetStringSlice("site-kind")
+	sites, err := qb.PanicsReachableFrom(symID, maxDepth, kinds)
+	if err != nil {
+		return outputError("panics", err)
+	}
+
+	cliSites := make([]CLIPanicSite, len(sites))
+	for i, site := range sites {
+		cliSites[i] = CLIPanicSite{
+			Kind:     site.Kind,
+			Name:     site.Name,
+			Location: locationToCLI(site.Location, nil),
+			Function: symbolResultToCLI(site.Function),
+			Path:     callGraphEdgesToCLI(site.Path),
+		}
+	}
+
+	paged, totalCount := paginateSlice(cliSites)
+	return outputResult(CLIResult{
+		Command:    "panics",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
 func runPackageGraph(cmd *cobra.Command, args []string) error {
 	s, err := openStore()
 	if err != nil {
@@ -141,7 +374,7 @@ func runPackageGraph(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	graph, err := qb.PackageDependencyGraph()
 	if err != nil {
 		return outputError("package-graph", err)
@@ -163,7 +396,7 @@ func runCircularDeps(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	cycles, err := qb.CircularDependencies()
 	if err != nil {
 		return outputError("circular-deps", err)
@@ -175,11 +408,14 @@ func runCircularDeps(cmd *cobra.Command, args []string) error {
 	}
 
 	count := len(cliCycles)
-	return outputResult(CLIResult{
+	if err := outputResult(CLIResult{
 		Command:    "circular-deps",
 		Results:    cliCycles,
 		TotalCount: &count,
-	})
+	}); err != nil {
+		return err
+	}
+	return checkRule("circular_deps", count)
 }
 
 func runUnused(cmd *cobra.Command, args []string) error {
@@ -199,8 +435,10 @@ func runUnused(cmd *cobra.Command, args []string) error {
 	if flagPathPrefix != "" {
 		filter.PathPrefix = &flagPathPrefix
 	}
+	filter.Annotations = flagAttrs
+	filter.ExcludeNames = projectConfig.Analysis.EntryPoints
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	result, err := qb.UnusedSymbols(filter, buildSort(), buildPagination())
 	if err != nil {
 		return outputError("unused", err)
@@ -212,10 +450,145 @@ func runUnused(cmd *cobra.Command, args []string) error {
 	}
 
 
-	return outputResult(CLIResult{
+	if err := outputResult(CLIResult{
 		Command:    "unused",
 		Results:    cliSyms,
 		TotalCount: &result.TotalCount,
+	}); err != nil {
+		return err
+	}
+	return checkRule("unused", result.TotalCount)
+}
+
+func runDeprecated(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("deprecated", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	result, err := qb.DeprecatedUsages(buildPagination())
+	if err != nil {
+		return outputError("deprecated", err)
+	}
+
+	cliUsages := make([]CLIDeprecatedUsage, len(result.Items))
+	for i, u := range result.Items {
+		cliUsages[i] = deprecatedUsageToCLI(u)
+	}
+
+	if err := outputResult(CLIResult{
+		Command:    "deprecated",
+		Results:    cliUsages,
+		TotalCount: &result.TotalCount,
+	}); err != nil {
+		return err
+	}
+	return checkRule("deprecated", result.TotalCount)
+}
+
+func runFeatures(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("features", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	apis, err := qb.FeatureGatedAPI(flagPathPrefix)
+	if err != nil {
+		return outputError("features", err)
+	}
+
+	cliAPIs := make([]CLIFeatureAPI, len(apis))
+	for i, a := range apis {
+		items := make([]CLIFeatureGatedItem, len(a.Items))
+		for j, it := range a.Items {
+			items[j] = CLIFeatureGatedItem{
+				Symbol:    symbolResultToCLI(it.Symbol),
+				Condition: it.Condition,
+			}
+		}
+		cliAPIs[i] = CLIFeatureAPI{Feature: a.Feature, Items: items}
+	}
+
+	paged, totalCount := paginateSlice(cliAPIs)
+	return outputResult(CLIResult{
+		Command:    "features",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runErrorFlows(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("error-flows", err)
+	}
+	defer s.Close()
+
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("error-flows", err)
+	}
+
+	errorType, _ := cmd.Flags().GetString("error")
+	publicOnly, _ := cmd.Flags().GetBool("public")
+	flows, err := qb.ErrorFlows(canopy.ErrorFlowFilter{
+		ErrorType:  errorType,
+		PublicOnly: publicOnly,
+		PathPrefix: flagPathPrefix,
+	})
+	if err != nil {
+		return outputError("error-flows", err)
+	}
+
+	cliFlows := make([]CLIErrorFlow, len(flows))
+	for i, f := range flows {
+		cliFlows[i] = CLIErrorFlow{
+			Function:   symbolResultToCLI(f.Function),
+			Declared:   f.Declared,
+			ErrorTypes: f.ErrorTypes,
+		}
+	}
+
+	paged, totalCount := paginateSlice(cliFlows)
+	return outputResult(CLIResult{
+		Command:    "error-flows",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+func runFFI(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("ffi", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	boundaries, err := qb.FFIBoundaries(flagPathPrefix)
+	if err != nil {
+		return outputError("ffi", err)
+	}
+
+	cliBoundaries := make([]CLIFFIBoundary, len(boundaries))
+	for i, b := range boundaries {
+		cliBoundaries[i] = CLIFFIBoundary{
+			Symbol:    symbolResultToCLI(b.Symbol),
+			Direction: b.Direction,
+			ABI:       b.ABI,
+			LinkName:  b.LinkName,
+		}
+	}
+
+	paged, totalCount := paginateSlice(cliBoundaries)
+	return outputResult(CLIResult{
+		Command:    "ffi",
+		Results:    paged,
+		TotalCount: &totalCount,
 	})
 }
 
@@ -228,7 +601,7 @@ func runHotspots(cmd *cobra.Command, args []string) error {
 
 	topN, _ := cmd.Flags().GetInt("top")
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	hotspots, err := qb.Hotspots(topN)
 	if err != nil {
 		return outputError("hotspots", err)
@@ -251,6 +624,41 @@ func runHotspots(cmd *cobra.Command, args []string) error {
 	})
 }
 
+func runDuplicates(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("duplicates", err)
+	}
+	defer s.Close()
+
+	minSimilarity, _ := cmd.Flags().GetFloat64("min-similarity")
+	minStatements, _ := cmd.Flags().GetInt("min-statements")
+
+	qb := queryBuilder(s)
+	pairs, err := qb.Duplicates(minSimilarity, minStatements)
+	if err != nil {
+		return outputError("duplicates", err)
+	}
+
+	cliPairs := make([]CLIClonePair, len(pairs))
+	for i, p := range pairs {
+		cliPairs[i] = CLIClonePair{
+			A:          symbolResultToCLI(p.A),
+			B:          symbolResultToCLI(p.B),
+			Similarity: p.Similarity,
+			Common:     p.Common,
+			Statements: p.Statements,
+		}
+	}
+
+	page, total := paginateSlice(cliPairs)
+	return outputResult(CLIResult{
+		Command:    "duplicates",
+		Results:    page,
+		TotalCount: &total,
+	})
+}
+
 // --- Converters ---
 
 func callGraphToCLI(g *canopy.CallGraph) CLICallGraph {
@@ -262,25 +670,32 @@ func callGraphToCLI(g *canopy.CallGraph) CLICallGraph {
 		}
 	}
 
-	edges := make([]CLICallGraphEdge, len(g.Edges))
-	for i, e := range g.Edges {
-		edges[i] = CLICallGraphEdge{
-			CallerID: e.CallerID,
-			CalleeID: e.CalleeID,
-			File:     e.File,
-			Line:     e.Line,
-			Col:      e.Col,
-		}
-	}
-
 	return CLICallGraph{
 		Root:     g.Root,
 		Nodes:    nodes,
-		Edges:    edges,
+		Edges:    callGraphEdgesToCLI(g.Edges),
 		Depth:    g.Depth,
 	}
 }
 
+func callGraphEdgesToCLI(edges []canopy.CallGraphEdge) []CLICallGraphEdge {
+	cliEdges := make([]CLICallGraphEdge, len(edges))
+	for i, e := range edges {
+		cliEdges[i] = CLICallGraphEdge{
+			CallerID:   e.CallerID,
+			CalleeID:   e.CalleeID,
+			File:       e.File,
+			Line:       e.Line,
+			Col:        e.Col,
+			Confidence: e.Confidence,
+			Awaited:    e.Awaited,
+			Kind:       e.Kind,
+			Propagated: e.Propagated,
+		}
+	}
+	return cliEdges
+}
+
 func dependencyGraphToCLI(g *canopy.DependencyGraph) CLIDependencyGraph {
 	packages := make([]CLIPackageNode, len(g.Packages))
 	for i, p := range g.Packages {
@@ -305,3 +720,108 @@ func dependencyGraphToCLI(g *canopy.DependencyGraph) CLIDependencyGraph {
 		Edges:    edges,
 	}
 }
+
+func runMetrics(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("metrics", err)
+	}
+	defer s.Close()
+
+	filter := canopy.SymbolFilter{}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
+	if flagPathPrefix != "" {
+		filter.PathPrefix = &flagPathPrefix
+	}
+
+	qb := queryBuilder(s)
+	result, err := qb.FunctionComplexity(filter, buildSort(), buildPagination())
+	if err != nil {
+		return outputError("metrics", err)
+	}
+
+	cliMetrics := make([]CLIComplexity, len(result.Items))
+	for i, cr := range result.Items {
+		cliMetrics[i] = CLIComplexity{
+			Symbol:     symbolResultToCLI(cr.Symbol),
+			Cyclomatic: cr.Cyclomatic,
+			Cognitive:  cr.Cognitive,
+		}
+	}
+
+	return outputResult(CLIResult{
+		Command:    "metrics",
+		Results:    cliMetrics,
+		TotalCount: &result.TotalCount,
+	})
+}
+
+func runPackageMetrics(cmd *cobra.Command, args []string) error {
+	check, _ := cmd.Flags().GetBool("check")
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("package-metrics", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	metrics, err := qb.PackageCouplingMetrics()
+	if err != nil {
+		return outputError("package-metrics", err)
+	}
+
+	cliMetrics, violationCount := packageMetricsToCLI(metrics, check)
+	paged, total := paginateSlice(cliMetrics)
+	if err := outputResult(CLIResult{
+		Command:    "package-metrics",
+		Results:    paged,
+		TotalCount: &total,
+	}); err != nil {
+		return err
+	}
+
+	return checkRule("package_metrics", violationCount)
+}
+
+// deprecatedUsageToCLI converts a DeprecatedUsage to its CLI form.
+func deprecatedUsageToCLI(u canopy.DeprecatedUsage) CLIDeprecatedUsage {
+	symID := u.Symbol.ID
+	return CLIDeprecatedUsage{
+		Symbol:   symbolResultToCLI(u.Symbol),
+		Note:     u.Note,
+		Location: locationToCLI(u.Location, &symID),
+		Context:  u.Context,
+	}
+}
+
+// packageMetricsToCLI converts package metrics to their CLI form. With check,
+// each package lists the thresholds in .canopy.toml it exceeds; the number
+// of violations is returned too.
+func packageMetricsToCLI(metrics []canopy.PackageMetrics, check bool) ([]CLIPackageMetrics, int) {
+	violations := map[string][]string{}
+	var violationCount int
+	if check {
+		for _, v := range canopy.CheckPackageThresholds(metrics, projectConfig.Metrics) {
+			violations[v.Package] = append(violations[v.Package],
+				fmt.Sprintf("%s %g > %g", v.Metric, v.Value, v.Limit))
+			violationCount++
+		}
+	}
+
+	cliMetrics := make([]CLIPackageMetrics, len(metrics))
+	for i, m := range metrics {
+		cliMetrics[i] = CLIPackageMetrics{
+			Package:     m.Package,
+			Afferent:    m.Afferent,
+			Efferent:    m.Efferent,
+			Instability: m.Instability,
+			FanIn:       m.FanIn,
+			FanOut:      m.FanOut,
+			Violations:  violations[m.Package],
+		}
+	}
+	return cliMetrics, violationCount
+}
@@ -54,7 +54,7 @@ func runTypeHierarchy(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("type-hierarchy", err)
@@ -88,7 +88,7 @@ func runImplements(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("implements", err)
@@ -123,7 +123,7 @@ func runExtensions(cmd *cobra.Command, args []string) error {
 	}
 	defer s.Close()
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	symID, err := resolveSymbolID(cmd, args, qb)
 	if err != nil {
 		return outputError("extensions", err)
@@ -167,7 +167,7 @@ func runReexports(cmd *cobra.Command, args []string) error {
 		return outputError("reexports", fmt.Errorf("file not found in index: %s", file))
 	}
 
-	qb := canopy.NewQueryBuilder(s)
+	qb := queryBuilder(s)
 	reexports, err := qb.Reexports(f.ID)
 	if err != nil {
 		return outputError("reexports", err)
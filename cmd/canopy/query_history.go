@@ -0,0 +1,144 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+// --- Git History Commands ---
+
+var staleCmd = &cobra.Command{
+	Use:   "stale",
+	Short: "List symbols not modified since a date",
+	Long:  "Lists symbols whose lines were all last changed before --before, oldest first.\nRequires an index built with --blame (or blame = true in .canopy.toml).",
+	Args:  cobra.NoArgs,
+	RunE:  runStale,
+}
+
+var busFactorCmd = &cobra.Command{
+	Use:   "bus-factor",
+	Short: "Rank files by how few authors wrote most of their lines",
+	Long:  "Computes each file's bus factor: the fewest authors who together last modified more than half of its lines.\nRequires an index built with --blame (or blame = true in .canopy.toml).",
+	Args:  cobra.NoArgs,
+	RunE:  runBusFactor,
+}
+
+func init() {
+	staleCmd.Flags().String("before", "365d", "cutoff as a date (2006-01-02), RFC 3339 time, or age (e.g. 180d, 720h)")
+	staleCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	staleCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+
+	busFactorCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+// parseBefore parses a --before value relative to now.
+func parseBefore(value string, now time.Time) (time.Time, error) {
+	if days, ok := strings.CutSuffix(value, "d"); ok {
+		if n, err := strconv.Atoi(days); err == nil {
+			return now.AddDate(0, 0, -n), nil
+		}
+	}
+	if d, err := time.ParseDuration(value); err == nil {
+		return now.Add(-d), nil
+	}
+	if t, err := time.Parse("2006-01-02", value); err == nil {
+		return t, nil
+	}
+	if t, err := time.Parse(time.RFC3339, value); err == nil {
+		return t, nil
+	}
+	return time.Time{}, fmt.Errorf("invalid --before %q: want a date, RFC 3339 time, or age like 180d", value)
+}
+
+func runStale(cmd *cobra.Command, args []string) error {
+	beforeFlag, _ := cmd.Flags().GetString("before")
+	before, err := parseBefore(beforeFlag, time.Now())
+	if err != nil {
+		return outputError("stale", err)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("stale", err)
+	}
+	defer s.Close()
+
+	filter := canopy.SymbolFilter{}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
+	if flagPathPrefix != "" {
+		filter.PathPrefix = &flagPathPrefix
+	}
+
+	qb := queryBuilder(s)
+	result, err := qb.StaleSymbols(before, filter, buildPagination())
+	if err != nil {
+		return outputError("stale", err)
+	}
+
+	cliStale := make([]CLIStaleSymbol, len(result.Items))
+	for i, st := range result.Items {
+		cliStale[i] = CLIStaleSymbol{
+			Symbol:  symbolResultToCLI(st.Symbol),
+			History: symbolHistoryToCLI(st.History),
+		}
+	}
+
+	return outputResult(CLIResult{
+		Command:    "stale",
+		Results:    cliStale,
+		TotalCount: &result.TotalCount,
+	})
+}
+
+func runBusFactor(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("bus-factor", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	factors, err := qb.BusFactor(flagPathPrefix)
+	if err != nil {
+		return outputError("bus-factor", err)
+	}
+
+	cliFactors := make([]CLIBusFactor, len(factors))
+	for i, f := range factors {
+		authors := make([]CLIAuthorLines, len(f.Authors))
+		for j, a := range f.Authors {
+			authors[j] = CLIAuthorLines{Author: a.Author, Lines: a.Lines}
+		}
+		cliFactors[i] = CLIBusFactor{
+			File:       f.File,
+			BusFactor:  f.BusFactor,
+			TotalLines: f.TotalLines,
+			Authors:    authors,
+		}
+	}
+
+	paged, total := paginateSlice(cliFactors)
+	return outputResult(CLIResult{
+		Command:    "bus-factor",
+		Results:    paged,
+		TotalCount: &total,
+	})
+}
+
+// symbolHistoryToCLI converts a SymbolHistory to its CLI form.
+func symbolHistoryToCLI(h canopy.SymbolHistory) CLISymbolHistory {
+	return CLISymbolHistory{
+		Commit:      h.Commit,
+		Author:      h.Author,
+		AuthorEmail: h.AuthorEmail,
+		Date:        h.Date.Format(time.RFC3339),
+		Authors:     h.Authors,
+	}
+}
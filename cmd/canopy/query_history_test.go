@@ -0,0 +1,29 @@
+package main
+
+import (
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseBefore(t *testing.T) {
+	t.Parallel()
+	now := time.Date(2024, 6, 15, 12, 0, 0, 0, time.UTC)
+
+	got, err := parseBefore("30d", now)
+	require.NoError(t, err)
+	assert.Equal(t, time.Date(2024, 5, 16, 12, 0, 0, 0, time.UTC), got)
+
+	got, err = parseBefore("48h", now)
+	require.NoError(t, err)
+	assert.Equal(t, time.Date(2024, 6, 13, 12, 0, 0, 0, time.UTC), got)
+
+	got, err = parseBefore("2023-01-02", now)
+	require.NoError(t, err)
+	assert.Equal(t, time.Date(2023, 1, 2, 0, 0, 0, 0, time.UTC), got)
+
+	_, err = parseBefore("last year", now)
+	assert.Error(t, err)
+}
@@ -422,11 +422,11 @@ func formatInt64(n int64) string {
 	return fmt.Sprintf("%d", n)
 }
 
-// runQueryRaw executes a canopy query and returns raw stdout/stderr strings.
-func runQueryRaw(t *testing.T, bin, fixtureDir string, args ...string) (stdout, stderr string) {
+// runCanopy executes canopy with the given args and returns raw stdout/stderr
+// strings.
+func runCanopy(t *testing.T, bin, fixtureDir string, args ...string) (stdout, stderr string) {
 	t.Helper()
-	fullArgs := append([]string{"query"}, args...)
-	cmd := exec.Command(bin, fullArgs...)
+	cmd := exec.Command(bin, args...)
 	cmd.Dir = fixtureDir
 	var stdoutBuf, stderrBuf bytes.Buffer
 	cmd.Stdout = &stdoutBuf
@@ -442,7 +442,7 @@ func TestQuery_FormatText_SymbolAt(t *testing.T) {
 	}
 	bin, fixtureDir, _ := indexFixture(t)
 
-	stdout, _ := runQueryRaw(t, bin, fixtureDir, "--format", "text", "symbol-at", "main.go", "4", "5")
+	stdout, _ := runCanopy(t, bin, fixtureDir, "query", "--format", "text", "symbol-at", "main.go", "4", "5")
 
 	// Should NOT be JSON.
 	assert.False(t, strings.HasPrefix(strings.TrimSpace(stdout), "{"), "text format should not produce JSON")
@@ -459,7 +459,7 @@ func TestQuery_FormatText_Symbols(t *testing.T) {
 	}
 	bin, fixtureDir, _ := indexFixture(t)
 
-	stdout, _ := runQueryRaw(t, bin, fixtureDir, "--format", "text", "symbols", "--kind", "function")
+	stdout, _ := runCanopy(t, bin, fixtureDir, "query", "--format", "text", "symbols", "--kind", "function")
 
 	// Should have column headers.
 	assert.Contains(t, stdout, "ID")
@@ -479,7 +479,7 @@ func TestQuery_FormatText_Summary(t *testing.T) {
 	}
 	bin, fixtureDir, _ := indexFixture(t)
 
-	stdout, _ := runQueryRaw(t, bin, fixtureDir, "--format", "text", "summary")
+	stdout, _ := runCanopy(t, bin, fixtureDir, "query", "--format", "text", "summary")
 
 	assert.Contains(t, stdout, "Project Summary")
 	assert.Contains(t, stdout, "Packages:")
@@ -493,7 +493,7 @@ func TestQuery_FormatJSON_ValidJSON(t *testing.T) {
 	}
 	bin, fixtureDir, _ := indexFixture(t)
 
-	stdout, _ := runQueryRaw(t, bin, fixtureDir, "--format", "json", "symbols")
+	stdout, _ := runCanopy(t, bin, fixtureDir, "query", "--format", "json", "symbols")
 
 	// Should be valid JSON.
 	var result map[string]any
@@ -501,6 +501,44 @@ func TestQuery_FormatJSON_ValidJSON(t *testing.T) {
 	assert.Equal(t, "symbols", result["command"])
 }
 
+func TestQuery_FormatNDJSON_OneObjectPerLine(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	stdout, _ := runCanopy(t, bin, fixtureDir, "query", "--format", "ndjson", "symbols", "--kind", "function")
+	lines := strings.Split(strings.TrimSpace(stdout), "\n")
+	require.NotEmpty(t, lines)
+	for _, line := range lines {
+		var sym map[string]any
+		require.NoError(t, json.Unmarshal([]byte(line), &sym), "each line should be a JSON object: %q", line)
+		assert.Equal(t, "function", sym["kind"])
+		assert.Nil(t, sym["command"], "ndjson has no envelope")
+	}
+}
+
+func TestQuery_FormatNDJSON_StreamsReferences(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	// helper is never referenced, so the stream is empty: no envelope, no lines.
+	stdout, stderr := runCanopy(t, bin, fixtureDir, "query", "--format", "ndjson", "references", "main.go", "8", "5")
+	assert.Empty(t, stderr)
+	assert.Empty(t, strings.TrimSpace(stdout))
+
+	// Errors are a single compact envelope line.
+	stdout, _ = runCanopy(t, bin, fixtureDir, "query", "--format", "ndjson", "references", "main.go", "99", "0")
+	lines := strings.Split(strings.TrimSpace(stdout), "\n")
+	require.Len(t, lines, 1)
+	var result map[string]any
+	require.NoError(t, json.Unmarshal([]byte(lines[0]), &result))
+	assert.Equal(t, "references", result["command"])
+	assert.NotEmpty(t, result["error"])
+}
+
 func TestQuery_FormatText_ErrorGoesToStderr(t *testing.T) {
 	if testing.Short() {
 		t.Skip("skipping integration test")
@@ -509,7 +547,7 @@ func TestQuery_FormatText_ErrorGoesToStderr(t *testing.T) {
 	fixture := createGoFixture(t)
 	// Do NOT index, so the DB won't exist.
 
-	stdout, stderr := runQueryRaw(t, bin, fixture, "--format", "text", "symbols")
+	stdout, stderr := runCanopy(t, bin, fixture, "query", "--format", "text", "symbols")
 
 	// Error should be on stderr, not stdout.
 	assert.Empty(t, stdout, "text format errors should not write to stdout")
@@ -825,3 +863,203 @@ func TestQuery_Unused_LimitZero(t *testing.T) {
 	require.True(t, ok, "results should be an array")
 	assert.Equal(t, 0, len(items), "--limit 0 should return 0 results")
 }
+
+func TestBatch(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	batchFile := filepath.Join(t.TempDir(), "queries.json")
+	require.NoError(t, os.WriteFile(batchFile, []byte(`[
+  {"id": "one", "args": ["query", "symbols", "--kind", "function", "--limit", "1"]},
+  {"id": "all", "args": ["query", "symbols", "--kind", "function"]},
+  {"id": "bad", "args": ["query", "callers"]},
+  {"id": "unknown", "args": ["no-such-command"]}
+]`), 0o644))
+
+	cmd := exec.Command(bin, "batch", batchFile)
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+	stdout, err := cmd.Output()
+	require.Error(t, err, "failed entries make the batch exit non-zero")
+
+	var result struct {
+		Command    string `json:"command"`
+		TotalCount int    `json:"total_count"`
+		Results    map[string]struct {
+			Command    string `json:"command"`
+			Results    []any  `json:"results"`
+			TotalCount int    `json:"total_count"`
+			Error      string `json:"error"`
+		} `json:"results"`
+	}
+	require.NoError(t, json.Unmarshal(stdout, &result), "invalid JSON output: %s", string(stdout))
+	assert.Equal(t, "batch", result.Command)
+	assert.Equal(t, 4, result.TotalCount)
+	require.Len(t, result.Results, 4)
+
+	one := result.Results["one"]
+	assert.Equal(t, "symbols", one.Command)
+	assert.Len(t, one.Results, 1)
+	assert.GreaterOrEqual(t, one.TotalCount, 2)
+
+	all := result.Results["all"]
+	assert.Empty(t, all.Error)
+	assert.Len(t, all.Results, all.TotalCount, "--limit from the previous entry does not carry over")
+
+	assert.NotEmpty(t, result.Results["bad"].Error)
+	assert.Contains(t, result.Results["unknown"].Error, "unknown command")
+}
+
+func TestRepl(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	cmd := exec.Command(bin, "repl", "--format", "json")
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+	cmd.Stdin = strings.NewReader("symbols --kind function --limit 1\n" +
+		"query symbols --kind function\n" +
+		"repl\n" +
+		"exit\n" +
+		"symbols\n")
+	var stderr bytes.Buffer
+	cmd.Stderr = &stderr
+	stdout, err := cmd.Output()
+	require.NoError(t, err, stderr.String())
+
+	type symbolsResult struct {
+		Command    string `json:"command"`
+		Results    []any  `json:"results"`
+		TotalCount int    `json:"total_count"`
+	}
+	dec := json.NewDecoder(bytes.NewReader(stdout))
+	var one, all symbolsResult
+	require.NoError(t, dec.Decode(&one))
+	require.NoError(t, dec.Decode(&all))
+	assert.False(t, dec.More(), "exit stops reading commands")
+
+	assert.Equal(t, "symbols", one.Command)
+	assert.Len(t, one.Results, 1)
+	assert.Len(t, all.Results, all.TotalCount, "--limit from the previous command does not carry over")
+	assert.Contains(t, stderr.String(), "repl cannot run inside the repl")
+
+	history, err := os.ReadFile(filepath.Join(fixtureDir, ".canopy", "repl_history"))
+	require.NoError(t, err)
+	assert.Equal(t, "symbols --kind function --limit 1\nquery symbols --kind function\nrepl\nexit\n", string(history))
+}
+
+func TestReport(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	cmd := exec.Command(bin, "report", "--format", "markdown", "--title", "Fixture")
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+	out, err := cmd.Output()
+	require.NoError(t, err)
+	md := string(out)
+	assert.True(t, strings.HasPrefix(md, "# Fixture\n\n## Overview\n\n"), md)
+	assert.Contains(t, md, "| go | 1 |")
+	for _, section := range []string{"## Modules", "## Top Types", "## Public API", "## Hotspots", "## Findings"} {
+		assert.Contains(t, md, "\n"+section+"\n", section)
+	}
+	assert.NotContains(t, md, fixtureDir, "paths are relative to the repo root")
+
+	cmd = exec.Command(bin, "report", "--top", "1")
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+	out, err = cmd.Output()
+	require.NoError(t, err)
+	var result struct {
+		Command string `json:"command"`
+		Results struct {
+			Title     string `json:"title"`
+			Languages []any  `json:"languages"`
+			Findings  []struct {
+				Rule  string `json:"rule"`
+				Count int    `json:"count"`
+			} `json:"findings"`
+		} `json:"results"`
+	}
+	require.NoError(t, json.Unmarshal(out, &result), string(out))
+	assert.Equal(t, "report", result.Command)
+	assert.Equal(t, "Architecture of "+filepath.Base(fixtureDir), result.Results.Title)
+	assert.Len(t, result.Results.Languages, 1)
+	assert.NotEmpty(t, result.Results.Findings)
+}
+
+func TestStats(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	cmd := exec.Command(bin, "stats")
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+	out, err := cmd.Output()
+	require.NoError(t, err)
+	var result struct {
+		Command string `json:"command"`
+		Results struct {
+			Files           int            `json:"files"`
+			FilesByLanguage map[string]int `json:"files_by_language"`
+			Symbols         int            `json:"symbols"`
+			ResolutionRate  float64        `json:"resolution_rate"`
+			IndexedAt       string         `json:"indexed_at"`
+			Timings         []struct {
+				Phase string `json:"phase"`
+			} `json:"timings"`
+		} `json:"results"`
+	}
+	require.NoError(t, json.Unmarshal(out, &result), string(out))
+	assert.Equal(t, "stats", result.Command)
+	assert.Equal(t, map[string]int{"go": result.Results.Files}, result.Results.FilesByLanguage)
+	assert.Positive(t, result.Results.Symbols)
+	assert.LessOrEqual(t, result.Results.ResolutionRate, 1.0)
+	assert.NotEmpty(t, result.Results.IndexedAt, "index records its timings")
+	require.Len(t, result.Results.Timings, 2)
+	assert.Equal(t, "extract", result.Results.Timings[0].Phase)
+}
+
+func TestFind_IncludeTests(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin := buildBinary(t)
+	fixtureDir := createGoFixture(t)
+	require.NoError(t, os.WriteFile(filepath.Join(fixtureDir, "main_test.go"), []byte("package main\n\nfunc fixtureName() string {\n\treturn \"test\"\n}\n"), 0o644))
+	cmd := exec.Command(bin, "index", fixtureDir)
+	cmd.Dir = fixtureDir
+	out, err := cmd.CombinedOutput()
+	require.NoError(t, err, "index failed: %s", string(out))
+
+	find := func(args ...string) []string {
+		t.Helper()
+		cmd := exec.Command(bin, append([]string{"find", "--signature", "() -> string"}, args...)...)
+		cmd.Dir = fixtureDir
+		cmd.Env = append(os.Environ(), "HOME="+t.TempDir())
+		stdout, err := cmd.Output()
+		require.NoError(t, err)
+		var result struct {
+			Results []struct {
+				Name string `json:"name"`
+			} `json:"results"`
+		}
+		require.NoError(t, json.Unmarshal(stdout, &result), "invalid JSON output: %s", string(stdout))
+		var names []string
+		for _, r := range result.Results {
+			names = append(names, r.Name)
+		}
+		return names
+	}
+
+	assert.Equal(t, []string{"helper"}, find(), "test code is left out by default")
+	assert.ElementsMatch(t, []string{"helper", "fixtureName"}, find("--include-tests"))
+}
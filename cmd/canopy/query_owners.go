@@ -0,0 +1,123 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+)
+
+// --- Ownership Commands ---
+
+var ownersCmd = &cobra.Command{
+	Use:   "owners [<file>]",
+	Short: "Show the CODEOWNERS owners of a file or symbol",
+	Long:  "Returns the owners assigned by the CODEOWNERS file of the file's indexed root.\nAccepts either a <file> argument or --symbol <id>.",
+	Args:  cobra.MaximumNArgs(1),
+	RunE:  runOwners,
+}
+
+var ownerImpactCmd = &cobra.Command{
+	Use:   "owner-impact [<file> <line> <col>]",
+	Short: "Group a symbol's references and callers by owning team",
+	Long:  "Counts references to the symbol and its transitive callers (up to --max-depth) per CODEOWNERS owner.\nAccepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args:  cobra.MaximumNArgs(3),
+	RunE:  runOwnerImpact,
+}
+
+func init() {
+	ownersCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+
+	ownerImpactCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	ownerImpactCmd.Flags().Int("max-depth", 5, "maximum caller traversal depth (0-100)")
+}
+
+func runOwners(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("owners", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	var result CLIOwners
+	if cmd.Flags().Changed("symbol") {
+		symID, _ := cmd.Flags().GetInt64("symbol")
+		sym, err := s.SymbolByID(symID)
+		if err != nil {
+			return outputError("owners", err)
+		}
+		if sym == nil {
+			return outputError("owners", fmt.Errorf("symbol not found: %d", symID))
+		}
+		result.File = lookupFilePath(s, sym.FileID)
+		result.SymbolID = &symID
+		result.Owners, err = qb.SymbolOwners(symID)
+		if err != nil {
+			return outputError("owners", err)
+		}
+	} else {
+		if len(args) < 1 {
+			return outputError("owners", fmt.Errorf("requires either a <file> argument or --symbol flag"))
+		}
+		filePath, err := resolveFilePath(args[0])
+		if err != nil {
+			return outputError("owners", err)
+		}
+		f, err := s.FileByPath(filePath)
+		if err != nil {
+			return outputError("owners", fmt.Errorf("looking up file %q: %w", args[0], err))
+		}
+		if f == nil {
+			return outputError("owners", fmt.Errorf("file not found: %s", args[0]))
+		}
+		result.File = f.Path
+		result.Owners, err = qb.FileOwners(f.ID)
+		if err != nil {
+			return outputError("owners", err)
+		}
+	}
+
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "owners",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
+
+func runOwnerImpact(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("owner-impact", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args, qb)
+	if err != nil {
+		return outputError("owner-impact", err)
+	}
+
+	maxDepth, _ := cmd.Flags().GetInt("max-depth")
+	impacts, err := qb.OwnerImpact(symID, maxDepth)
+	if err != nil {
+		return outputError("owner-impact", err)
+	}
+
+	cliImpacts := make([]CLIOwnerImpact, len(impacts))
+	for i, imp := range impacts {
+		cliImpacts[i] = CLIOwnerImpact{
+			Owner:      imp.Owner,
+			References: imp.References,
+			Callers:    imp.Callers,
+			Files:      imp.Files,
+		}
+	}
+
+	paged, total := paginateSlice(cliImpacts)
+	return outputResult(CLIResult{
+		Command:    "owner-impact",
+		Results:    paged,
+		TotalCount: &total,
+	})
+}
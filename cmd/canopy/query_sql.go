@@ -0,0 +1,48 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var sqlTableCmd = &cobra.Command{
+	Use:   "sql-table <table>",
+	Short: "List functions whose embedded SQL touches a table",
+	Long: "Lists the functions and methods whose sqlx query macros or diesel sql_query calls name the table or its columns,\n" +
+		"with the columns they name and where. Requires an index built with --sql-refs (or sql_refs = true in .canopy.toml).",
+	Args: cobra.ExactArgs(1),
+	RunE: runSQLTable,
+}
+
+func runSQLTable(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("sql-table", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	uses, err := qb.FunctionsTouchingTable(args[0])
+	if err != nil {
+		return outputError("sql-table", err)
+	}
+
+	cliUses := make([]CLISQLTableUse, len(uses))
+	for i, u := range uses {
+		sites := make([]CLILocation, len(u.Sites))
+		for j, loc := range u.Sites {
+			sites[j] = locationToCLI(loc, nil)
+		}
+		cliUses[i] = CLISQLTableUse{
+			Function: symbolResultToCLI(u.Function),
+			Columns:  u.Columns,
+			Sites:    sites,
+		}
+	}
+	paged, totalCount := paginateSlice(cliUses)
+	return outputResult(CLIResult{
+		Command:    "sql-table",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
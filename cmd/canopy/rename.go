@@ -0,0 +1,169 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"os"
+	"strconv"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/internal/store"
+	"github.com/spf13/cobra"
+)
+
+var (
+	flagDryRun        bool
+	flagFix           bool
+	flagWorkspaceEdit bool
+)
+
+var renameCmd = &cobra.Command{
+	Use:   "rename <symbol> <new-name>",
+	Short: "Rename a symbol across files",
+	Long: "Plans every span that renaming a symbol edits: its definition, references, and use/import/re-export paths.\n" +
+		"<symbol> is a symbol ID or an unambiguous symbol name. Pass --dry-run to print the plan or --fix to apply it.\n" +
+		"Edits are applied atomically: if any file changed since indexing, nothing is written.",
+	Args: cobra.ExactArgs(2),
+	RunE: runRename,
+}
+
+func init() {
+	renameCmd.ValidArgsFunction = completeSymbolNames
+	renameCmd.Flags().BoolVar(&flagDryRun, "dry-run", false, "only plan the rename and print the edits")
+	renameCmd.Flags().BoolVar(&flagFix, "fix", false, "apply the rename to the files")
+	renameCmd.Flags().BoolVar(&flagWorkspaceEdit, "workspace-edit", false, "print the plan as an LSP WorkspaceEdit")
+}
+
+func runRename(cmd *cobra.Command, args []string) error {
+	if flagDryRun == flagFix {
+		return outputError("rename", fmt.Errorf("pass exactly one of --dry-run or --fix"))
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("rename", err)
+	}
+	defer s.Close()
+
+	symID, err := resolveSymbolArg(s, args[0])
+	if err != nil {
+		return outputError("rename", err)
+	}
+
+	// A rename must reach test code too, whatever --include-tests says.
+	qb := queryBuilder(s).WithTestCode(true)
+	plan, err := qb.PlanRename(symID, args[1])
+	if err != nil {
+		return outputError("rename", err)
+	}
+
+	if flagFix {
+		res, err := canopy.ApplyEdits(plan.TextEdits())
+		if err != nil {
+			return outputError("rename", err)
+		}
+		fmt.Fprintf(os.Stderr, "Applied %d edit(s) to %d file(s); re-run 'canopy index' to refresh the database\n",
+			res.Edits, len(res.Files))
+		count := len(res.Files)
+		return outputResult(CLIResult{
+			Command:    "rename",
+			Results:    CLIApplyResult{Files: res.Files, Edits: res.Edits},
+			TotalCount: &count,
+		})
+	}
+
+	if flagWorkspaceEdit {
+		enc := json.NewEncoder(os.Stdout)
+		enc.SetIndent("", "  ")
+		return enc.Encode(renamePlanToWorkspaceEdit(plan))
+	}
+
+	cliPlan := renamePlanToCLI(plan)
+	count := len(cliPlan.Edits)
+	return outputResult(CLIResult{
+		Command:    "rename",
+		Results:    cliPlan,
+		TotalCount: &count,
+	})
+}
+
+// resolveSymbolArg resolves a symbol argument given as an ID or a name. A name
+// must match exactly one symbol that has a source file.
+func resolveSymbolArg(s *store.Store, arg string) (int64, error) {
+	if id, err := strconv.ParseInt(arg, 10, 64); err == nil {
+		return id, nil
+	}
+
+	rows, err := s.DB().Query(
+		`SELECT s.id, f.path, s.start_line FROM symbols s JOIN files f ON f.id = s.file_id
+		 WHERE s.name = ? ORDER BY f.path, s.start_line`, arg,
+	)
+	if err != nil {
+		return 0, fmt.Errorf("looking up symbol %q: %w", arg, err)
+	}
+	defer rows.Close()
+
+	var ids []int64
+	var candidates []string
+	for rows.Next() {
+		var id int64
+		var path string
+		var line int
+		if err := rows.Scan(&id, &path, &line); err != nil {
+			return 0, fmt.Errorf("looking up symbol %q: %w", arg, err)
+		}
+		ids = append(ids, id)
+		candidates = append(candidates, fmt.Sprintf("%d (%s:%d)", id, path, line))
+	}
+	if err := rows.Err(); err != nil {
+		return 0, fmt.Errorf("looking up symbol %q: %w", arg, err)
+	}
+
+	switch len(ids) {
+	case 0:
+		return 0, fmt.Errorf("no symbol named %q", arg)
+	case 1:
+		return ids[0], nil
+	default:
+		return 0, fmt.Errorf("symbol name %q is ambiguous; pass one of the IDs: %v", arg, candidates)
+	}
+}
+
+// renamePlanToCLI converts a canopy.RenamePlan to a CLIRenamePlan.
+func renamePlanToCLI(plan *canopy.RenamePlan) CLIRenamePlan {
+	files := map[string]bool{}
+	edits := make([]CLIRenameEdit, len(plan.Edits))
+	for i, e := range plan.Edits {
+		files[e.File] = true
+		edits[i] = CLIRenameEdit{
+			CLILocation: locationToCLI(e.Location, nil),
+			Kind:        e.Kind,
+			OldText:     e.OldText,
+			NewText:     e.NewText,
+		}
+	}
+	return CLIRenamePlan{
+		Symbol:  symbolResultToCLI(plan.Symbol),
+		NewName: plan.NewName,
+		Files:   len(files),
+		Edits:   edits,
+	}
+}
+
+// renamePlanToWorkspaceEdit converts a canopy.RenamePlan to an LSP
+// WorkspaceEdit with UTF-16 character offsets.
+func renamePlanToWorkspaceEdit(plan *canopy.RenamePlan) LSPWorkspaceEdit {
+	we := LSPWorkspaceEdit{Changes: map[string][]LSPTextEdit{}}
+	for _, e := range plan.Edits {
+		uri := (&url.URL{Scheme: "file", Path: e.File}).String()
+		we.Changes[uri] = append(we.Changes[uri], LSPTextEdit{
+			Range: LSPRange{
+				Start: LSPPosition{Line: e.StartLine, Character: e.UTF16StartCol},
+				End:   LSPPosition{Line: e.EndLine, Character: e.UTF16EndCol},
+			},
+			NewText: e.NewText,
+		})
+	}
+	return we
+}
@@ -0,0 +1,92 @@
+package main_test
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRename_DryRunPlan(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	stdout, _ := runCanopy(t, bin, fixtureDir, "rename", "helper", "assist", "--dry-run")
+	var result map[string]any
+	require.NoError(t, json.Unmarshal([]byte(stdout), &result))
+	assert.Equal(t, "rename", result["command"])
+	assert.Empty(t, result["error"])
+
+	plan := result["results"].(map[string]any)
+	assert.Equal(t, "assist", plan["new_name"])
+	edits := plan["edits"].([]any)
+	require.Len(t, edits, 1, "helper has no references, only its definition")
+	edit := edits[0].(map[string]any)
+	assert.Equal(t, "definition", edit["kind"])
+	assert.Equal(t, float64(8), edit["start_line"])
+	assert.Equal(t, float64(5), edit["start_col"])
+}
+
+func TestRename_WorkspaceEdit(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	var we struct {
+		Changes map[string][]struct {
+			Range struct {
+				Start struct{ Line, Character int }
+				End   struct{ Line, Character int }
+			}
+			NewText string `json:"newText"`
+		}
+	}
+	out, _ := runCanopy(t, bin, fixtureDir, "rename", "helper", "assist", "--dry-run", "--workspace-edit")
+	require.NoError(t, json.Unmarshal([]byte(out), &we), "output: %s", out)
+	require.Len(t, we.Changes, 1)
+	for uri, edits := range we.Changes {
+		assert.Contains(t, uri, "file://")
+		require.Len(t, edits, 1)
+		assert.Equal(t, "assist", edits[0].NewText)
+		assert.Equal(t, 8, edits[0].Range.Start.Line)
+		assert.Equal(t, 5, edits[0].Range.Start.Character)
+		assert.Equal(t, 11, edits[0].Range.End.Character)
+	}
+}
+
+func TestRename_RequiresDryRunOrFix(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	stdout, _ := runCanopy(t, bin, fixtureDir, "rename", "helper", "assist")
+	var result map[string]any
+	require.NoError(t, json.Unmarshal([]byte(stdout), &result))
+	assert.Contains(t, result["error"], "--dry-run or --fix")
+}
+
+func TestRename_FixAppliesEdits(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+
+	stdout, _ := runCanopy(t, bin, fixtureDir, "rename", "helper", "assist", "--fix")
+	var result map[string]any
+	require.NoError(t, json.Unmarshal([]byte(stdout), &result))
+	assert.Empty(t, result["error"])
+	res := result["results"].(map[string]any)
+	assert.Equal(t, float64(1), res["edits"])
+
+	content, err := os.ReadFile(filepath.Join(fixtureDir, "main.go"))
+	require.NoError(t, err)
+	assert.Contains(t, string(content), "func assist() string {")
+	assert.NotContains(t, string(content), "helper")
+}
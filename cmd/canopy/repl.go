@@ -0,0 +1,353 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"slices"
+	"strings"
+	"unicode"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/spf13/cobra"
+	"github.com/spf13/pflag"
+)
+
+// maxCompletions caps the symbol names offered for one completion, and
+// maxReplHistory the lines of history the REPL keeps.
+const (
+	maxCompletions = 100
+	maxReplHistory = 1000
+)
+
+var replCmd = &cobra.Command{
+	Use:   "repl",
+	Short: "Run successive commands at an interactive prompt over one open index",
+	Long: "Opens the index once and reads commands at a prompt, each the command line after 'canopy'. The 'query'\n" +
+		"prefix may be left off, e.g. 'callers --symbol 42'. Tab completes command names, flags, and symbol names;\n" +
+		"the up and down arrows recall earlier commands, which are kept in repl_history next to the database.\n" +
+		"Results print as text unless --format or the [output] format in .canopy.toml says otherwise. Type 'help'\n" +
+		"for help and 'exit' or Ctrl-D to quit. When stdin is not a terminal, commands are read one per line.",
+	Args: cobra.NoArgs,
+	RunE: runRepl,
+}
+
+func runRepl(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("repl", err)
+	}
+	defer s.Close()
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("repl", fmt.Errorf("getting cwd: %w", err))
+	}
+	historyPath := filepath.Join(filepath.Dir(indexDBPath(findRepoRoot(cwd))), "repl_history")
+
+	root := cmd.Root()
+	saved := flagFormat
+	format := flagFormat
+	if !cmd.Flags().Changed("format") && projectConfig.Output.Format == "" {
+		format = "text"
+	}
+	batchStore = s
+	defer func() {
+		batchStore = nil
+		flagFormat = saved
+		errorHandled = false
+	}()
+
+	in := bufio.NewReader(os.Stdin)
+	ed := &lineEditor{
+		in:      in,
+		out:     os.Stdout,
+		prompt:  "canopy> ",
+		history: loadReplHistory(historyPath),
+		complete: func(line string) (string, []string) {
+			return completeReplLine(root, s, line)
+		},
+	}
+	interactive := isTerminal(os.Stdin)
+	readLine := func() (string, error) {
+		if interactive {
+			if restore, err := enterRawMode(); err == nil {
+				defer restore()
+				return ed.readLine()
+			}
+		}
+		line, err := in.ReadString('\n')
+		if err == io.EOF && line != "" {
+			err = nil
+		}
+		return strings.TrimRight(line, "\r\n"), err
+	}
+
+	for {
+		line, err := readLine()
+		if err == io.EOF {
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+		words, err := splitArgs(line)
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Error: %s\n", err)
+			continue
+		}
+		if len(words) == 0 {
+			continue
+		}
+		if ed.addHistory(line) {
+			appendReplHistory(historyPath, line)
+		}
+
+		switch words[0] {
+		case "exit", "quit":
+			return nil
+		case "history":
+			for i, h := range ed.history {
+				fmt.Printf("%5d  %s\n", i+1, h)
+			}
+			continue
+		case "help":
+			if len(words) == 1 {
+				fmt.Println("Enter a canopy command line without 'canopy', e.g. 'snippet parse --context callers:1'\n" +
+					"or 'callers --symbol 42'. 'help <command>' describes a command, 'history' lists earlier\n" +
+					"commands, and 'exit' quits. Tab completes commands, flags, and symbol names.")
+			}
+		}
+
+		args := replArgs(root, words)
+		if c, _, err := root.Find(args); err == nil && (c == cmd || c == batchCmd) {
+			fmt.Fprintf(os.Stderr, "Error: %s cannot run inside the repl\n", c.Name())
+			continue
+		}
+		resetFlags(root)
+		flagFormat = format
+		errorHandled = false
+		root.SetArgs(args)
+		if err := root.Execute(); err != nil && !errorHandled {
+			fmt.Fprintf(os.Stderr, "Error: %s\n", err)
+		}
+	}
+}
+
+// replArgs returns the command line for words typed at the REPL, prefixed
+// with "query" when words start with a query subcommand rather than a
+// top-level command.
+func replArgs(root *cobra.Command, words []string) []string {
+	if c, _, err := root.Find(words); err == nil && c != root {
+		return words
+	}
+	withQuery := append([]string{queryCmd.Name()}, words...)
+	if c, _, err := root.Find(withQuery); err == nil && c != queryCmd && c != root {
+		return withQuery
+	}
+	return words
+}
+
+// completeReplLine returns the partial last word of line and its
+// completions: command names for the first word, subcommand names after a
+// command that has them, flag names for words starting with "-", and
+// symbol names otherwise.
+func completeReplLine(root *cobra.Command, s *store.Store, line string) (string, []string) {
+	start := strings.LastIndexFunc(line, unicode.IsSpace) + 1
+	word := line[start:]
+	words, err := splitArgs(line[:start])
+	if err != nil {
+		return word, nil
+	}
+
+	var cands []string
+	if len(words) == 0 {
+		cands = append(commandNames(root), commandNames(queryCmd)...)
+		cands = append(cands, "exit", "help", "history", "quit")
+	} else {
+		c, _, err := root.Find(replArgs(root, words))
+		if err != nil {
+			return word, nil
+		}
+		last := words[len(words)-1]
+		switch {
+		case strings.HasPrefix(word, "-"):
+			visit := func(f *pflag.Flag) {
+				if !f.Hidden {
+					cands = append(cands, "--"+f.Name)
+				}
+			}
+			c.Flags().VisitAll(visit)
+			c.InheritedFlags().VisitAll(visit)
+		case c.HasAvailableSubCommands():
+			cands = commandNames(c)
+		case strings.HasPrefix(last, "--") && !strings.Contains(last, "="):
+			// The word is the flag's value unless the flag is a boolean.
+			name := strings.TrimPrefix(last, "--")
+			f := c.Flags().Lookup(name)
+			if f == nil {
+				f = c.InheritedFlags().Lookup(name)
+			}
+			if f != nil && f.Value.Type() != "bool" {
+				return word, nil
+			}
+			fallthrough
+		default:
+			if cands, err = s.SymbolNamesWithPrefix(word, maxCompletions); err != nil {
+				return word, nil
+			}
+		}
+	}
+
+	var out []string
+	for _, c := range cands {
+		if strings.HasPrefix(c, word) {
+			out = append(out, c)
+		}
+	}
+	slices.Sort(out)
+	return word, slices.Compact(out)
+}
+
+// commandNames returns the names of cmd's available subcommands.
+func commandNames(cmd *cobra.Command) []string {
+	var names []string
+	for _, c := range cmd.Commands() {
+		if c.IsAvailableCommand() {
+			names = append(names, c.Name())
+		}
+	}
+	return names
+}
+
+// completeSymbolNames is a ValidArgsFunction completing a command's first
+// argument with the names of indexed symbols.
+func completeSymbolNames(cmd *cobra.Command, args []string, toComplete string) ([]string, cobra.ShellCompDirective) {
+	if len(args) > 0 {
+		return nil, cobra.ShellCompDirectiveNoFileComp
+	}
+	s, err := openStore()
+	if err != nil {
+		return nil, cobra.ShellCompDirectiveError
+	}
+	defer s.Close()
+	names, err := s.SymbolNamesWithPrefix(toComplete, maxCompletions)
+	if err != nil {
+		return nil, cobra.ShellCompDirectiveError
+	}
+	return names, cobra.ShellCompDirectiveNoFileComp
+}
+
+// splitArgs splits a command line into words the way a shell does:
+// whitespace separates words, single quotes keep their contents literal,
+// and double quotes group words while backslashes escape the next character.
+func splitArgs(line string) ([]string, error) {
+	var (
+		words   []string
+		cur     strings.Builder
+		inWord  bool
+		escaped bool
+		quote   rune
+	)
+	for _, r := range line {
+		switch {
+		case escaped:
+			cur.WriteRune(r)
+			escaped = false
+		case quote == '\'':
+			if r == '\'' {
+				quote = 0
+			} else {
+				cur.WriteRune(r)
+			}
+		case r == '\\':
+			escaped, inWord = true, true
+		case quote == '"':
+			if r == '"' {
+				quote = 0
+			} else {
+				cur.WriteRune(r)
+			}
+		case r == '\'' || r == '"':
+			quote, inWord = r, true
+		case unicode.IsSpace(r):
+			if inWord {
+				words = append(words, cur.String())
+				cur.Reset()
+				inWord = false
+			}
+		default:
+			cur.WriteRune(r)
+			inWord = true
+		}
+	}
+	switch {
+	case quote != 0:
+		return nil, fmt.Errorf("unterminated %c quote", quote)
+	case escaped:
+		return nil, fmt.Errorf("trailing backslash")
+	}
+	if inWord {
+		words = append(words, cur.String())
+	}
+	return words, nil
+}
+
+// loadReplHistory returns the last maxReplHistory lines of the history file
+// at path, none if it cannot be read.
+func loadReplHistory(path string) []string {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil
+	}
+	lines := strings.Split(strings.TrimRight(string(data), "\n"), "\n")
+	if len(lines) == 1 && lines[0] == "" {
+		return nil
+	}
+	if len(lines) > maxReplHistory {
+		lines = lines[len(lines)-maxReplHistory:]
+	}
+	return lines
+}
+
+// appendReplHistory appends line to the history file at path. History is a
+// convenience, so failing to write it is not an error.
+func appendReplHistory(path, line string) {
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644)
+	if err != nil {
+		return
+	}
+	defer f.Close()
+	fmt.Fprintln(f, line)
+}
+
+// isTerminal reports whether f is a terminal.
+func isTerminal(f *os.File) bool {
+	fi, err := f.Stat()
+	return err == nil && fi.Mode()&os.ModeCharDevice != 0
+}
+
+// enterRawMode switches the terminal on stdin to unbuffered, unechoed input
+// with stty, so the line editor sees each key, and returns a function that
+// restores the previous settings. Output processing is left alone.
+func enterRawMode() (func(), error) {
+	saved, err := stty("-g")
+	if err != nil {
+		return nil, err
+	}
+	if _, err := stty("-icanon", "-echo", "-isig", "-ixon", "min", "1", "time", "0"); err != nil {
+		return nil, err
+	}
+	return func() { stty(strings.TrimSpace(saved)) }, nil
+}
+
+// stty runs stty against the terminal on stdin.
+func stty(args ...string) (string, error) {
+	c := exec.Command("stty", args...)
+	c.Stdin = os.Stdin
+	out, err := c.Output()
+	return string(out), err
+}
@@ -0,0 +1,92 @@
+package main
+
+import (
+	"bufio"
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSplitArgs(t *testing.T) {
+	t.Parallel()
+	words, err := splitArgs(`  find --name 'a b'  --sig "fn(\"x\")" c\ d `)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"find", "--name", "a b", "--sig", `fn("x")`, "c d"}, words)
+
+	words, err = splitArgs(`symbols --kind ''`)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"symbols", "--kind", ""}, words)
+
+	words, err = splitArgs("   ")
+	require.NoError(t, err)
+	assert.Empty(t, words)
+
+	for _, line := range []string{`find 'a`, `find "a`, `find a\`} {
+		_, err := splitArgs(line)
+		assert.Error(t, err, line)
+	}
+}
+
+func TestReplArgs(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, []string{"query", "callers", "--symbol", "1"}, replArgs(rootCmd, []string{"callers", "--symbol", "1"}))
+	assert.Equal(t, []string{"query", "callers"}, replArgs(rootCmd, []string{"query", "callers"}))
+	assert.Equal(t, []string{"snippet", "parse"}, replArgs(rootCmd, []string{"snippet", "parse"}))
+	assert.Equal(t, []string{"no-such-command"}, replArgs(rootCmd, []string{"no-such-command"}))
+}
+
+func TestLineEditor(t *testing.T) {
+	t.Parallel()
+	newEditor := func(input string) *lineEditor {
+		return &lineEditor{
+			in:      bufio.NewReader(strings.NewReader(input)),
+			out:     io.Discard,
+			prompt:  "> ",
+			history: []string{"first", "second"},
+			complete: func(line string) (string, []string) {
+				word := line[strings.LastIndex(line, " ")+1:]
+				var out []string
+				for _, c := range []string{"parse", "parse_args", "print"} {
+					if strings.HasPrefix(c, word) {
+						out = append(out, c)
+					}
+				}
+				return word, out
+			},
+		}
+	}
+	read := func(input string) string {
+		line, err := newEditor(input).readLine()
+		require.NoError(t, err, "%q", input)
+		return line
+	}
+
+	assert.Equal(t, "hello", read("hello\r"))
+	assert.Equal(t, "help", read("helo\x7f\x7flp\r"), "backspace")
+	assert.Equal(t, "xab", read("ab\x1b[D\x1b[Dx\r"), "left arrow moves the cursor")
+	assert.Equal(t, "b", read("a b\x01\x1b[3~\x1b[3~\r"), "home and delete")
+	assert.Equal(t, "new", read("old words\x15new\r"), "Ctrl-U kills the line")
+	assert.Equal(t, "one ", read("one two\x17\r"), "Ctrl-W deletes a word")
+	assert.Equal(t, "again", read("discard\x03again\r"), "Ctrl-C clears the line")
+
+	assert.Equal(t, "second", read("\x1b[A\r"), "up recalls the last entry")
+	assert.Equal(t, "first", read("\x1b[A\x1b[A\x1b[A\r"), "up stops at the oldest entry")
+	assert.Equal(t, "draft", read("draft\x1b[A\x1b[B\r"), "down returns to the line being typed")
+
+	assert.Equal(t, "snippet print ", read("snippet pr\t\r"), "one candidate completes with a space")
+	assert.Equal(t, "snippet parse", read("snippet pa\t\r"), "several complete their common prefix")
+	assert.Equal(t, "snippet p", read("snippet p\t\r"), "no common progress lists them")
+
+	_, err := newEditor("\x04").readLine()
+	assert.ErrorIs(t, err, io.EOF, "Ctrl-D at an empty line")
+	_, err = newEditor("abc").readLine()
+	assert.ErrorIs(t, err, io.EOF)
+
+	e := newEditor("")
+	assert.True(t, e.addHistory("third"))
+	assert.False(t, e.addHistory("third"), "repeats are not recorded")
+	assert.Equal(t, []string{"first", "second", "third"}, e.history)
+}
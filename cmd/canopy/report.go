@@ -0,0 +1,166 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+// reportTypeKinds are the symbol kinds listed under a report's top types,
+// and reportAPIKinds those listed under its public API.
+var (
+	reportTypeKinds = []string{"struct", "enum", "union", "trait", "interface", "class", "object", "type_alias"}
+	reportAPIKinds  = append([]string{"function", "method", "constructor", "constant", "macro"}, reportTypeKinds...)
+)
+
+var reportCmd = &cobra.Command{
+	Use:   "report",
+	Short: "Write an architecture report: modules, top types, public API, hotspots, and findings",
+	Long: "Summarizes the index as a report with sections for the languages and modules of the codebase, its most\n" +
+		"referenced types, its public API, its hotspots, and the findings of the rules lint runs (inline\n" +
+		"suppressions apply; the baseline does not). Use --format markdown for a document to commit to a docs\n" +
+		"folder or post as a PR comment; paths are relative to the repo root. --top caps the rows per section.",
+	Args: cobra.NoArgs,
+	RunE: runReport,
+}
+
+func init() {
+	reportCmd.Flags().Int("top", 10, "rows per section for top types, public API, hotspots, and findings")
+	reportCmd.Flags().String("title", "", "report title (default: Architecture of <repo directory>)")
+}
+
+func runReport(cmd *cobra.Command, args []string) error {
+	top, _ := cmd.Flags().GetInt("top")
+	if top <= 0 {
+		return outputError("report", fmt.Errorf("--top must be positive, got %d", top))
+	}
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("report", fmt.Errorf("getting cwd: %w", err))
+	}
+	repoRoot := findRepoRoot(cwd)
+	title, _ := cmd.Flags().GetString("title")
+	if title == "" {
+		title = "Architecture of " + filepath.Base(repoRoot)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("report", err)
+	}
+	defer s.Close()
+
+	report, err := buildReport(queryBuilder(s), repoRoot, top)
+	if err != nil {
+		return outputError("report", err)
+	}
+	report.Title = title
+	return outputResult(CLIResult{Command: "report", Results: *report})
+}
+
+// buildReport gathers the sections of a report, with up to top rows in each
+// ranked section and paths relative to repoRoot.
+func buildReport(qb *canopy.QueryBuilder, repoRoot string, top int) (*CLIReport, error) {
+	report := &CLIReport{}
+	rel := func(sym CLISymbol) CLISymbol {
+		sym.File = relativeToRoot(sym.File, repoRoot)
+		return sym
+	}
+
+	summary, err := qb.ProjectSummary(0)
+	if err != nil {
+		return nil, err
+	}
+	report.PackageCount = summary.PackageCount
+	report.Languages = []CLILanguageStats{}
+	for _, ls := range summary.Languages {
+		report.Languages = append(report.Languages, CLILanguageStats{
+			Language:    ls.Language,
+			FileCount:   ls.FileCount,
+			LineCount:   ls.LineCount,
+			SymbolCount: ls.SymbolCount,
+			KindCounts:  ls.KindCounts,
+		})
+	}
+
+	metrics, err := qb.PackageCouplingMetrics()
+	if err != nil {
+		return nil, err
+	}
+	report.Modules, _ = packageMetricsToCLI(metrics, true)
+	if report.Modules == nil {
+		report.Modules = []CLIPackageMetrics{}
+	}
+
+	ranked := func(filter canopy.SymbolFilter) ([]CLISymbol, int, error) {
+		limit := top
+		page, err := qb.Symbols(filter, canopy.Sort{Field: canopy.SortByRefCount, Order: canopy.Desc}, canopy.Pagination{Limit: &limit})
+		if err != nil {
+			return nil, 0, err
+		}
+		syms := make([]CLISymbol, len(page.Items))
+		for i, sr := range page.Items {
+			syms[i] = rel(symbolResultToCLI(sr))
+		}
+		return syms, page.TotalCount, nil
+	}
+	if report.TopTypes, _, err = ranked(canopy.SymbolFilter{Kinds: reportTypeKinds}); err != nil {
+		return nil, err
+	}
+	public := "public"
+	if report.PublicAPI, report.PublicAPICount, err = ranked(canopy.SymbolFilter{Kinds: reportAPIKinds, Visibility: &public}); err != nil {
+		return nil, err
+	}
+
+	hotspots, err := qb.Hotspots(top)
+	if err != nil {
+		return nil, err
+	}
+	report.Hotspots = make([]CLIHotspot, len(hotspots))
+	for i, h := range hotspots {
+		report.Hotspots[i] = CLIHotspot{Symbol: rel(symbolResultToCLI(h.Symbol)), CallerCount: h.CallerCount, CalleeCount: h.CalleeCount}
+	}
+
+	report.Findings = []CLIReportRule{}
+	sources := map[string][]string{}
+	for _, rule := range lintRules {
+		severity := projectConfig.ruleSeverity(rule)
+		if severity == severityOff {
+			continue
+		}
+		found, err := lintRuleFindings(qb, rule)
+		if err != nil {
+			return nil, fmt.Errorf("rule %s: %w", rule, err)
+		}
+		r := CLIReportRule{Rule: rule, Severity: severity, Findings: []CLILintFinding{}}
+		for _, f := range found {
+			if lintSuppressed(f, sources) {
+				continue
+			}
+			r.Count++
+			if len(r.Findings) < top {
+				f.Location.File = relativeToRoot(f.Location.File, repoRoot)
+				r.Findings = append(r.Findings, f)
+			}
+		}
+		report.Findings = append(report.Findings, r)
+	}
+	sort.SliceStable(report.Findings, func(i, j int) bool {
+		return report.Findings[i].Count > report.Findings[j].Count
+	})
+	return report, nil
+}
+
+// relativeToRoot returns path relative to repoRoot, slash-separated, or
+// path itself when it is outside repoRoot.
+func relativeToRoot(path, repoRoot string) string {
+	if rel, err := filepath.Rel(repoRoot, path); err == nil && !strings.HasPrefix(rel, "..") {
+		return filepath.ToSlash(rel)
+	}
+	return path
+}
@@ -0,0 +1,127 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/spf13/cobra"
+)
+
+var resolveDebugCmd = &cobra.Command{
+	Use:   "resolve-debug <file>:<line>:<col>",
+	Short: "Trace how the reference at a position was resolved",
+	Long: "Prints the name-resolution procedure for the identifier at a 0-based position: the scopes searched,\n" +
+		"innermost first, with the declarations of the name in each; the imports of the file and which bind the\n" +
+		"name; and every declaration the name could have meant, bound or rejected with a reason:\n" +
+		"  other_language  declared in a file of another language\n" +
+		"  kind_mismatch   its kind cannot be used where the reference occurs, such as a call of a module\n" +
+		"  out_of_scope    declared in the same file, in a scope that does not enclose the reference\n" +
+		"  private         private, and declared in another directory\n" +
+		"  not_imported    declared in another directory, and no import binds the name or its qualifier\n" +
+		"  outranked       visible, but another declaration was bound\n" +
+		"  unmodeled       visible, yet nothing was bound: the resolver does not model the construct\n" +
+		"Bindings are what the resolver recorded; rejections approximate its rules. Attach the output to\n" +
+		"resolution bug reports.",
+	Args: cobra.ExactArgs(1),
+	RunE: runResolveDebug,
+}
+
+// parsePosition parses a <file>:<line>:<col> argument. The file may itself
+// contain colons, as Windows paths do.
+func parsePosition(arg string) (file string, line, col int, err error) {
+	rest, colStr, ok := cutLast(arg, ":")
+	if !ok {
+		return "", 0, 0, fmt.Errorf("invalid position %q: want <file>:<line>:<col>", arg)
+	}
+	file, lineStr, ok := cutLast(rest, ":")
+	if !ok || file == "" {
+		return "", 0, 0, fmt.Errorf("invalid position %q: want <file>:<line>:<col>", arg)
+	}
+	if line, err = parseIntArg(lineStr, "line"); err != nil {
+		return "", 0, 0, err
+	}
+	if col, err = parseIntArg(colStr, "col"); err != nil {
+		return "", 0, 0, err
+	}
+	return file, line, col, nil
+}
+
+// cutLast slices s around the last instance of sep.
+func cutLast(s, sep string) (before, after string, found bool) {
+	if i := strings.LastIndex(s, sep); i >= 0 {
+		return s[:i], s[i+len(sep):], true
+	}
+	return s, "", false
+}
+
+func runResolveDebug(cmd *cobra.Command, args []string) error {
+	file, line, col, err := parsePosition(args[0])
+	if err != nil {
+		return outputError("resolve-debug", err)
+	}
+	if file, err = resolveFilePath(file); err != nil {
+		return outputError("resolve-debug", err)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("resolve-debug", err)
+	}
+	defer s.Close()
+
+	tr, err := queryBuilder(s).ResolveDebug(file, line, col)
+	if err != nil {
+		return outputError("resolve-debug", err)
+	}
+	if tr == nil {
+		return outputError("resolve-debug", fmt.Errorf("no reference at %s:%d:%d", file, line, col))
+	}
+
+	out := CLIResolutionTrace{
+		ReferenceID:      tr.ReferenceID,
+		Name:             tr.Name,
+		Kind:             tr.Kind,
+		Qualifier:        tr.Qualifier,
+		Language:         tr.Language,
+		Location:         locationToCLI(tr.Reference, nil),
+		UnresolvedReason: tr.UnresolvedReason,
+	}
+	for _, sc := range tr.Scopes {
+		out.Scopes = append(out.Scopes, CLITracedScope{
+			Kind:       sc.Kind,
+			SymbolID:   sc.SymbolID,
+			SymbolName: sc.SymbolName,
+			StartLine:  sc.StartLine,
+			EndLine:    sc.EndLine,
+			Declares:   sc.Declares,
+		})
+	}
+	for _, imp := range tr.Imports {
+		out.Imports = append(out.Imports, CLITracedImport{
+			Source:       imp.Source,
+			ImportedName: imp.ImportedName,
+			LocalAlias:   imp.LocalAlias,
+			Kind:         imp.Kind,
+			Binds:        imp.Binds,
+			Matches:      imp.Matches,
+		})
+	}
+	for _, c := range tr.Candidates {
+		cc := CLIResolutionCandidate{
+			SymbolID:       c.SymbolID,
+			Name:           c.Name,
+			Kind:           c.Kind,
+			Visibility:     c.Visibility,
+			Bound:          c.Bound,
+			ResolutionKind: c.ResolutionKind,
+			Confidence:     c.Confidence,
+			Rejected:       c.Rejected,
+		}
+		if c.Location != nil {
+			loc := locationToCLI(*c.Location, &c.SymbolID)
+			cc.Location = &loc
+		}
+		out.Candidates = append(out.Candidates, cc)
+	}
+	return outputResult(CLIResult{Command: "resolve-debug", Results: out})
+}
@@ -0,0 +1,341 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/internal/store"
+)
+
+// SARIF 2.1.0, the format written by --format sarif for rule findings. Only
+// the properties canopy fills in are modeled.
+const (
+	sarifVersion = "2.1.0"
+	sarifSchema  = "https://json.schemastore.org/sarif-2.1.0.json"
+	sarifSrcRoot = "%SRCROOT%" // base of artifact URIs relative to the repo root
+)
+
+type sarifLog struct {
+	Schema  string     `json:"$schema"`
+	Version string     `json:"version"`
+	Runs    []sarifRun `json:"runs"`
+}
+
+type sarifRun struct {
+	Tool               sarifTool                   `json:"tool"`
+	OriginalURIBaseIDs map[string]sarifArtifactLoc `json:"originalUriBaseIds,omitempty"`
+	Results            []sarifResult               `json:"results"`
+}
+
+type sarifTool struct {
+	Driver sarifDriver `json:"driver"`
+}
+
+type sarifDriver struct {
+	Name  string      `json:"name"`
+	Rules []sarifRule `json:"rules"`
+}
+
+type sarifRule struct {
+	ID                   string             `json:"id"`
+	ShortDescription     sarifMessage       `json:"shortDescription"`
+	DefaultConfiguration sarifConfiguration `json:"defaultConfiguration"`
+}
+
+type sarifConfiguration struct {
+	Level string `json:"level"`
+}
+
+type sarifMessage struct {
+	Text string `json:"text"`
+}
+
+type sarifResult struct {
+	RuleID    string          `json:"ruleId"`
+	Level     string          `json:"level"`
+	Message   sarifMessage    `json:"message"`
+	Locations []sarifLocation `json:"locations"`
+}
+
+type sarifLocation struct {
+	PhysicalLocation sarifPhysicalLocation `json:"physicalLocation"`
+}
+
+type sarifPhysicalLocation struct {
+	ArtifactLocation sarifArtifactLoc `json:"artifactLocation"`
+	Region           *sarifRegion     `json:"region,omitempty"`
+}
+
+type sarifArtifactLoc struct {
+	URI       string `json:"uri"`
+	URIBaseID string `json:"uriBaseId,omitempty"`
+}
+
+// sarifRegion is 1-based, with columns in UTF-16 code units.
+type sarifRegion struct {
+	StartLine   int `json:"startLine"`
+	StartColumn int `json:"startColumn"`
+	EndLine     int `json:"endLine"`
+	EndColumn   int `json:"endColumn"`
+}
+
+// sarifRuleDescriptions describes the rules of the commands --format sarif
+// supports, keyed by rule name (see defaultRuleSeverities).
+var sarifRuleDescriptions = map[string]string{
+	"unused":          "Symbol is never referenced",
+	"deprecated":      "Use of a deprecated symbol",
+	"circular_deps":   "Packages depend on each other in a cycle",
+	"package_metrics": "Package exceeds a coupling threshold",
+	"glob_collisions": "Name brought in by two glob imports is ambiguous where used",
+}
+
+// findingCommandRules maps the commands whose results the finding formats
+// (sarif, github, gitlab) support to their rule.
+var findingCommandRules = map[string]string{
+	"unused":          "unused",
+	"deprecated":      "deprecated",
+	"circular-deps":   "circular_deps",
+	"package-metrics": "package_metrics",
+	"glob-collisions": "glob_collisions",
+}
+
+// sarifLevel returns the SARIF level for a rule severity. Findings of rules
+// turned off are still reported, as notes.
+func sarifLevel(severity string) string {
+	switch severity {
+	case severityError:
+		return "error"
+	case severityWarning:
+		return "warning"
+	}
+	return "note"
+}
+
+// outputResultSARIF writes the findings of a rule command or of lint as a
+// SARIF log.
+func outputResultSARIF(result CLIResult) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return fmt.Errorf("getting cwd: %w", err)
+	}
+	locatePackage, closeStore := storePackageLocator()
+	defer closeStore()
+
+	log, err := buildSARIF(result, findRepoRoot(cwd), locatePackage)
+	if err != nil {
+		return err
+	}
+	enc := json.NewEncoder(os.Stdout)
+	enc.SetIndent("", "  ")
+	return enc.Encode(log)
+}
+
+// storePackageLocator returns a locatePackage function for ruleFindings
+// that opens the store on first use, and a function that closes it.
+func storePackageLocator() (func(string) (*CLILocation, error), func()) {
+	var s *store.Store
+	locate := func(name string) (*CLILocation, error) {
+		if s == nil {
+			var err error
+			if s, err = openStore(); err != nil {
+				return nil, err
+			}
+		}
+		return packageLocation(queryBuilder(s), name)
+	}
+	return locate, func() {
+		if s != nil {
+			s.Close()
+		}
+	}
+}
+
+// packageLocation returns the location of the first declaration of the
+// package, module, or namespace name, or nil if there is none.
+func packageLocation(qb *canopy.QueryBuilder, name string) (*CLILocation, error) {
+	result, err := qb.SearchSymbols(name, canopy.SymbolFilter{Kinds: []string{"package", "module", "namespace"}},
+		canopy.Sort{Field: canopy.SortByFile, Order: canopy.Asc}, canopy.Pagination{})
+	if err != nil {
+		return nil, err
+	}
+	for _, sr := range result.Items {
+		if sr.Name == name && sr.FilePath != "" {
+			return &CLILocation{
+				File:          sr.FilePath,
+				StartLine:     sr.StartLine,
+				StartCol:      sr.StartCol,
+				EndLine:       sr.EndLine,
+				EndCol:        sr.EndCol,
+				UTF16StartCol: sr.UTF16StartCol,
+				UTF16EndCol:   sr.UTF16EndCol,
+				SymbolID:      &sr.ID,
+			}, nil
+		}
+	}
+	return nil, nil
+}
+
+// buildSARIF converts the results of a rule command, or a lint report, to a
+// SARIF log with one run. Paths under repoRoot are written relative to
+// %SRCROOT%.
+func buildSARIF(result CLIResult, repoRoot string, locatePackage func(string) (*CLILocation, error)) (*sarifLog, error) {
+	findings, rules, err := resultFindings(result, "sarif", locatePackage)
+	if err != nil {
+		return nil, err
+	}
+
+	driverRules := make([]sarifRule, len(rules))
+	for i, rule := range rules {
+		driverRules[i] = sarifRule{
+			ID:                   rule,
+			ShortDescription:     sarifMessage{Text: sarifRuleDescriptions[rule]},
+			DefaultConfiguration: sarifConfiguration{Level: sarifLevel(projectConfig.ruleSeverity(rule))},
+		}
+	}
+	results := make([]sarifResult, len(findings))
+	for i, f := range findings {
+		results[i] = sarifResult{
+			RuleID:    f.Rule,
+			Level:     sarifLevel(f.Severity),
+			Message:   sarifMessage{Text: f.Message},
+			Locations: []sarifLocation{{PhysicalLocation: sarifPhysical(f.Location, repoRoot)}},
+		}
+	}
+	baseIDs := map[string]sarifArtifactLoc{sarifSrcRoot: {URI: fileURI(repoRoot) + "/"}}
+	return &sarifLog{
+		Schema:  sarifSchema,
+		Version: sarifVersion,
+		Runs: []sarifRun{{
+			Tool:               sarifTool{Driver: sarifDriver{Name: "canopy", Rules: driverRules}},
+			OriginalURIBaseIDs: baseIDs,
+			Results:            results,
+		}},
+	}, nil
+}
+
+// resultFindings returns the findings in the results of a rule command, or
+// of a lint report, and the rules they belong to. format names the output
+// format in the error for other results.
+func resultFindings(result CLIResult, format string, locatePackage func(string) (*CLILocation, error)) ([]CLILintFinding, []string, error) {
+	if report, ok := result.Results.(CLILintReport); ok {
+		return report.Findings, report.Rules, nil
+	}
+	rule, ok := findingCommandRules[result.Command]
+	if !ok {
+		return nil, nil, fmt.Errorf("unsupported command for %s format: %s", format, result.Command)
+	}
+	findings, err := ruleFindings(rule, result.Results, locatePackage)
+	if err != nil {
+		return nil, nil, err
+	}
+	return findings, []string{rule}, nil
+}
+
+// ruleFindings converts the results of the command of rule to findings at
+// the rule's configured severity. Findings without a location in a file are
+// dropped, since SARIF consumers such as GitHub code scanning require one.
+func ruleFindings(rule string, results any, locatePackage func(string) (*CLILocation, error)) ([]CLILintFinding, error) {
+	severity := projectConfig.ruleSeverity(rule)
+	findings := []CLILintFinding{}
+	add := func(message string, loc *CLILocation) {
+		if loc == nil || loc.File == "" {
+			return
+		}
+		findings = append(findings, CLILintFinding{Rule: rule, Severity: severity, Message: message, Location: *loc})
+	}
+	addPackage := func(message, pkg string) error {
+		loc, err := locatePackage(pkg)
+		if err != nil {
+			return fmt.Errorf("locating package %s: %w", pkg, err)
+		}
+		add(message, loc)
+		return nil
+	}
+
+	switch v := results.(type) {
+	case []CLISymbol:
+		for _, sym := range v {
+			symID := sym.ID
+			add(fmt.Sprintf("%s %s is never referenced", sym.Kind, sym.Name), &CLILocation{
+				File:          sym.File,
+				StartLine:     sym.StartLine,
+				StartCol:      sym.StartCol,
+				EndLine:       sym.EndLine,
+				EndCol:        sym.EndCol,
+				UTF16StartCol: sym.UTF16StartCol,
+				UTF16EndCol:   sym.UTF16EndCol,
+				SymbolID:      &symID,
+			})
+		}
+	case []CLIDeprecatedUsage:
+		for _, u := range v {
+			msg := fmt.Sprintf("%s is deprecated", u.Symbol.Name)
+			if u.Note != "" {
+				msg += ": " + u.Note
+			}
+			loc := u.Location
+			add(msg, &loc)
+		}
+	case []CLICycle:
+		for _, c := range v {
+			if len(c.Packages) == 0 {
+				continue
+			}
+			msg := "Import cycle: " + strings.Join(c.Packages, " -> ")
+			if err := addPackage(msg, c.Packages[0]); err != nil {
+				return nil, err
+			}
+		}
+	case []CLIPackageMetrics:
+		for _, m := range v {
+			for _, violation := range m.Violations {
+				msg := fmt.Sprintf("Package %s exceeds a threshold: %s", m.Package, violation)
+				if err := addPackage(msg, m.Package); err != nil {
+					return nil, err
+				}
+			}
+		}
+	case []CLIGlobCollision:
+		for _, c := range v {
+			if len(c.Uses) == 0 {
+				continue
+			}
+			globs := make([]string, len(c.Sources))
+			for i, src := range c.Sources {
+				globs[i] = src.Import
+			}
+			loc := c.Uses[0]
+			add(fmt.Sprintf("%s is ambiguous: it comes from %s; add %s", c.Name, strings.Join(globs, " and "), c.Import), &loc)
+		}
+	default:
+		return nil, fmt.Errorf("unsupported result type for sarif format: %T", results)
+	}
+	return findings, nil
+}
+
+// sarifPhysical converts a 0-based location to a SARIF physical location.
+func sarifPhysical(loc CLILocation, repoRoot string) sarifPhysicalLocation {
+	artifact := sarifArtifactLoc{URI: fileURI(loc.File)}
+	if rel, err := filepath.Rel(repoRoot, loc.File); err == nil && !strings.HasPrefix(rel, "..") {
+		artifact = sarifArtifactLoc{URI: (&url.URL{Path: filepath.ToSlash(rel)}).EscapedPath(), URIBaseID: sarifSrcRoot}
+	}
+	return sarifPhysicalLocation{
+		ArtifactLocation: artifact,
+		Region: &sarifRegion{
+			StartLine:   loc.StartLine + 1,
+			StartColumn: loc.UTF16StartCol + 1,
+			EndLine:     loc.EndLine + 1,
+			EndColumn:   loc.UTF16EndCol + 1,
+		},
+	}
+}
+
+// fileURI returns the file URI of an absolute path.
+func fileURI(path string) string {
+	return (&url.URL{Scheme: "file", Path: filepath.ToSlash(path)}).String()
+}
@@ -0,0 +1,67 @@
+package main
+
+import (
+	"encoding/json"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestBuildSARIF_Unused(t *testing.T) {
+	result := CLIResult{Command: "unused", Results: []CLISymbol{
+		{Name: "helper", Kind: "function", File: "/repo/src/my lib.rs", StartLine: 4, EndLine: 6, UTF16EndCol: 1},
+		{Name: "stub", Kind: "function"}, // no file: dropped
+	}}
+	log, err := buildSARIF(result, "/repo", nil)
+	require.NoError(t, err)
+
+	data, err := json.Marshal(log)
+	require.NoError(t, err)
+	var got map[string]any
+	require.NoError(t, json.Unmarshal(data, &got))
+	assert.Equal(t, "2.1.0", got["version"])
+
+	require.Len(t, log.Runs, 1)
+	run := log.Runs[0]
+	assert.Equal(t, "file:///repo/", run.OriginalURIBaseIDs["%SRCROOT%"].URI)
+	require.Len(t, run.Tool.Driver.Rules, 1)
+	assert.Equal(t, "unused", run.Tool.Driver.Rules[0].ID)
+	assert.Equal(t, "note", run.Tool.Driver.Rules[0].DefaultConfiguration.Level, "unused is off by default")
+
+	require.Len(t, run.Results, 1)
+	r := run.Results[0]
+	assert.Equal(t, "unused", r.RuleID)
+	assert.Equal(t, "function helper is never referenced", r.Message.Text)
+	loc := r.Locations[0].PhysicalLocation
+	assert.Equal(t, sarifArtifactLoc{URI: "src/my%20lib.rs", URIBaseID: "%SRCROOT%"}, loc.ArtifactLocation)
+	assert.Equal(t, &sarifRegion{StartLine: 5, StartColumn: 1, EndLine: 7, EndColumn: 2}, loc.Region)
+}
+
+func TestBuildSARIF_PackageFindings(t *testing.T) {
+	orig := projectConfig
+	projectConfig = &Config{Rules: map[string]string{"circular_deps": severityWarning}}
+	t.Cleanup(func() { projectConfig = orig })
+
+	locate := func(name string) (*CLILocation, error) {
+		if name == "a" {
+			return &CLILocation{File: "/elsewhere/a/mod.rs", StartLine: 0, EndLine: 2}, nil
+		}
+		return nil, nil
+	}
+	result := CLIResult{Command: "circular-deps", Results: []CLICycle{
+		{Packages: []string{"a", "b", "a"}},
+		{Packages: []string{"c", "d", "c"}}, // c has no declaration: dropped
+	}}
+	log, err := buildSARIF(result, "/repo", locate)
+	require.NoError(t, err)
+
+	results := log.Runs[0].Results
+	require.Len(t, results, 1)
+	assert.Equal(t, "warning", results[0].Level)
+	assert.Equal(t, "Import cycle: a -> b -> a", results[0].Message.Text)
+	assert.Equal(t, sarifArtifactLoc{URI: "file:///elsewhere/a/mod.rs"}, results[0].Locations[0].PhysicalLocation.ArtifactLocation)
+
+	_, err = buildSARIF(CLIResult{Command: "symbols", Results: []CLISymbol{}}, "/repo", locate)
+	assert.Error(t, err)
+}
@@ -0,0 +1,182 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var (
+	flagEmbedBackend    string
+	flagEmbedURL        string
+	flagEmbedModel      string
+	flagEmbedAPIKeyEnv  string
+	flagEmbedDimensions int
+	flagSearchLimit     int
+)
+
+var embedCmd = &cobra.Command{
+	Use:   "embed",
+	Short: "Compute embeddings of indexed symbols for 'canopy search'",
+	Long: "Embeds the code of every indexed function, method, and type (its path, doc comment, and text) and stores\n" +
+		"the vectors for 'canopy search'. Symbols unchanged since the last run with the same model keep their\n" +
+		"vectors, so run it after each 'canopy index'. Backends:\n" +
+		"  local  a hashing model built into canopy; offline, matches by shared vocabulary (default)\n" +
+		"  http   any OpenAI-compatible embeddings endpoint, e.g. a local Ollama:\n" +
+		"         canopy embed --backend http --url http://localhost:11434/v1/embeddings --model nomic-embed-text\n" +
+		"Defaults come from the [embedding] section of .canopy.toml.",
+	Args: cobra.NoArgs,
+	RunE: runEmbed,
+}
+
+var semanticSearchCmd = &cobra.Command{
+	Use:   "search <query>",
+	Short: "Find code by meaning with the embeddings from 'canopy embed'",
+	Long: "Ranks the symbols embedded by 'canopy embed' by similarity to a natural-language query, e.g.\n" +
+		"  canopy search \"where do we compute shipping tax\"\n" +
+		"then boosts symbols whose callers or callees also match and, slightly, central ones. Each hit lists those\n" +
+		"matching neighbors. Use the same backend and model as 'canopy embed'. For name patterns, see 'canopy query search'.",
+	Args: cobra.ExactArgs(1),
+	RunE: runSemanticSearch,
+}
+
+func init() {
+	for _, c := range []*cobra.Command{embedCmd, semanticSearchCmd} {
+		c.Flags().StringVar(&flagEmbedBackend, "backend", "local", "embedding backend: local or http")
+		c.Flags().StringVar(&flagEmbedURL, "url", "", "embeddings endpoint for the http backend")
+		c.Flags().StringVar(&flagEmbedModel, "model", "", "model name for the http backend")
+		c.Flags().StringVar(&flagEmbedAPIKeyEnv, "api-key-env", "", "environment variable holding the http backend's API key")
+		c.Flags().IntVar(&flagEmbedDimensions, "dimensions", 0, "vector length of the local backend (default 512)")
+	}
+	embedCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+	semanticSearchCmd.Flags().IntVar(&flagSearchLimit, "limit", 10, "maximum number of results")
+}
+
+// validateEmbeddingBackend checks an embedding backend name.
+func validateEmbeddingBackend(backend string) error {
+	switch backend {
+	case "local", "http":
+		return nil
+	}
+	return fmt.Errorf("unknown embedding backend %q (want local or http)", backend)
+}
+
+// newEmbedder builds the embedder selected by the embedding flags, falling
+// back to the [embedding] config section for flags not set.
+func newEmbedder(cmd *cobra.Command) (canopy.Embedder, error) {
+	cfg := projectConfig.Embedding
+	pick := func(flag, value, configured string) string {
+		if !cmd.Flags().Changed(flag) && configured != "" {
+			return configured
+		}
+		return value
+	}
+	backend := pick("backend", flagEmbedBackend, cfg.Backend)
+	if err := validateEmbeddingBackend(backend); err != nil {
+		return nil, err
+	}
+	if backend == "local" {
+		dims := flagEmbedDimensions
+		if !cmd.Flags().Changed("dimensions") && cfg.Dimensions != nil {
+			dims = *cfg.Dimensions
+		}
+		return &canopy.HashEmbedder{Dimensions: dims}, nil
+	}
+
+	emb := &canopy.HTTPEmbedder{
+		URL:  pick("url", flagEmbedURL, cfg.URL),
+		Name: pick("model", flagEmbedModel, cfg.Model),
+	}
+	if emb.URL == "" || emb.Name == "" {
+		return nil, fmt.Errorf("the http backend needs --url and --model (or url and model under [embedding] in %s)", configFileName)
+	}
+	if env := pick("api-key-env", flagEmbedAPIKeyEnv, cfg.APIKeyEnv); env != "" {
+		if emb.APIKey = os.Getenv(env); emb.APIKey == "" {
+			return nil, fmt.Errorf("environment variable %s is not set", env)
+		}
+	}
+	return emb, nil
+}
+
+func runEmbed(cmd *cobra.Command, args []string) error {
+	emb, err := newEmbedder(cmd)
+	if err != nil {
+		return outputError("embed", err)
+	}
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("embed", fmt.Errorf("getting cwd: %w", err))
+	}
+	dbPath, err := resolveDBPath(findRepoRoot(cwd))
+	if err != nil {
+		return outputError("embed", err)
+	}
+	if _, err := os.Stat(dbPath); os.IsNotExist(err) {
+		return outputError("embed", fmt.Errorf("database not found: %s (run 'canopy index' first)", dbPath))
+	}
+
+	var opts []canopy.Option
+	if flagScriptsDir == "" {
+		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+	}
+	engine, err := canopy.New(dbPath, flagScriptsDir, opts...)
+	if err != nil {
+		return outputError("embed", fmt.Errorf("creating engine: %w", err))
+	}
+	defer engine.Close()
+
+	summary, err := engine.EmbedSymbols(context.Background(), emb)
+	if err != nil {
+		return outputError("embed", err)
+	}
+	return outputResult(CLIResult{
+		Command: "embed",
+		Results: CLIEmbeddingSummary{
+			Model:      summary.Model,
+			Symbols:    summary.Symbols,
+			Embedded:   summary.Embedded,
+			Reused:     summary.Reused,
+			Dimensions: summary.Dimensions,
+		},
+	})
+}
+
+func runSemanticSearch(cmd *cobra.Command, args []string) error {
+	emb, err := newEmbedder(cmd)
+	if err != nil {
+		return outputError("search", err)
+	}
+	s, err := openStore()
+	if err != nil {
+		return outputError("search", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	hits, err := qb.SemanticSearch(context.Background(), emb, args[0], flagSearchLimit)
+	if err != nil {
+		return outputError("search", err)
+	}
+
+	cliHits := make([]CLISemanticHit, len(hits))
+	for i, h := range hits {
+		cliHits[i] = CLISemanticHit{
+			Symbol:     symbolResultToCLI(*h.Symbol),
+			Similarity: h.Similarity,
+			Score:      h.Score,
+			Related:    make([]CLISymbol, len(h.Related)),
+		}
+		for j, r := range h.Related {
+			cliHits[i].Related[j] = symbolResultToCLI(*r)
+		}
+	}
+	return outputResult(CLIResult{
+		Command: "search",
+		Results: cliHits,
+	})
+}
@@ -0,0 +1,804 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"crypto/subtle"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"hash/fnv"
+	"io"
+	"io/fs"
+	"net"
+	"os"
+	"os/signal"
+	"path/filepath"
+	"slices"
+	"strings"
+	"sync"
+	"syscall"
+	"time"
+
+	canopyrt "github.com/jward/canopy/internal/runtime"
+	"github.com/spf13/cobra"
+)
+
+var serveCmd = &cobra.Command{
+	Use:   "serve [path...]",
+	Short: "Keep the index current and answer queries and subscriptions over a socket",
+	Long: "Indexes the given paths as 'canopy index' does, then watches them and re-indexes whenever a source file or\n" +
+		"Cargo.toml changes. Clients connect to a Unix socket (--socket, default serve.sock next to the database) and\n" +
+		"write one JSON request per line. {\"id\": ..., \"args\": [...]} runs a command, args being the command line\n" +
+		"after 'canopy' as in batch files, and is answered with {\"id\": ..., \"event\": \"result\", \"result\": ...}.\n" +
+		"Adding \"subscribe\": true also re-runs the command after every re-index and pushes\n" +
+		"{\"id\": ..., \"event\": \"update\", \"result\": ...} whenever its result changes, for live dashboards of unused\n" +
+		"symbols, layering violations, and the like. {\"id\": ..., \"unsubscribe\": true} ends a subscription.\n" +
+		"Commands run one at a time, each from default flags, and fail after --timeout; each client may send --rate\n" +
+		"requests a second. With a token in $CANOPY_SERVE_TOKEN or $CANOPY_SERVE_ADMIN_TOKEN, a client must first send\n" +
+		"{\"auth\": \"<token>\"}. Commands that write, such as coverage, trace, and import, and\n" +
+		"{\"id\": ..., \"reindex\": true} need admin access: with $CANOPY_SERVE_ADMIN_TOKEN set, only clients that\n" +
+		"authenticated with it have it. --listen serves on a TCP address instead of the socket and requires\n" +
+		"$CANOPY_SERVE_TOKEN. With --index-slice, the server answers while it indexes, in runs of that long: a request\n" +
+		"naming files or directories waits until they are indexed, and the next run starts with them and the files\n" +
+		"they import. Stop the server with Ctrl-C.",
+	Args: cobra.ArbitraryArgs,
+	RunE: runServe,
+}
+
+var (
+	flagSocket       string
+	flagListen       string
+	flagPollInterval time.Duration
+	flagServeTimeout time.Duration
+	flagServeRate    float64
+	flagMaxClients   int
+	flagIndexSlice   time.Duration
+)
+
+func init() {
+	serveCmd.Flags().StringVar(&flagSocket, "socket", "", "Unix socket path (default: serve.sock next to the database)")
+	serveCmd.Flags().StringVar(&flagListen, "listen", "", "TCP address to serve on instead of the socket, e.g. 127.0.0.1:7420 (requires $CANOPY_SERVE_TOKEN)")
+	serveCmd.Flags().DurationVar(&flagPollInterval, "interval", 2*time.Second, "how often to check the watched paths for changes")
+	serveCmd.Flags().DurationVar(&flagServeTimeout, "timeout", time.Minute, "fail a request not answered within this time, including time waiting for other commands")
+	serveCmd.Flags().Float64Var(&flagServeRate, "rate", 20, "requests a second each client may send, in bursts of up to one second's worth (0 for no limit)")
+	serveCmd.Flags().IntVar(&flagMaxClients, "max-clients", 64, "most clients connected at once")
+	serveCmd.Flags().DurationVar(&flagIndexSlice, "index-slice", 0, "serve while indexing at startup, in runs of this long that index the files queries name first (0 indexes fully before serving)")
+}
+
+// Environment variables holding the tokens, so they stay out of process
+// listings.
+const (
+	serveTokenEnv      = "CANOPY_SERVE_TOKEN"
+	serveAdminTokenEnv = "CANOPY_SERVE_ADMIN_TOKEN"
+)
+
+// Events of the messages the server writes.
+const (
+	serveEventResult        = "result"        // the answer to a request
+	serveEventUpdate        = "update"        // a subscription's result changed after re-indexing
+	serveEventUnsubscribed  = "unsubscribed"  // a subscription ended
+	serveEventAuthenticated = "authenticated" // the client's token was accepted
+	serveEventReindexed     = "reindexed"     // the index was rebuilt on request
+	serveEventError         = "error"         // the request could not be handled
+)
+
+// serveRequest is one line a client writes.
+type serveRequest struct {
+	ID          string   `json:"id"`
+	Args        []string `json:"args"`
+	Subscribe   bool     `json:"subscribe,omitempty"`
+	Unsubscribe bool     `json:"unsubscribe,omitempty"`
+	Reindex     bool     `json:"reindex,omitempty"`
+	Auth        string   `json:"auth,omitempty"`
+}
+
+// serveMessage is one line the server writes.
+type serveMessage struct {
+	ID     string     `json:"id"`
+	Event  string     `json:"event"`
+	Result *CLIResult `json:"result,omitempty"`
+	Role   string     `json:"role,omitempty"`
+	Error  string     `json:"error,omitempty"`
+}
+
+// Access a client has. Clients without a role may only authenticate.
+const (
+	serveRoleRead  = "read"
+	serveRoleAdmin = "admin"
+)
+
+// serveUnavailable are the commands a client cannot run: the server indexes
+// by itself, and the rest take over the terminal or serve in turn.
+var serveUnavailable = map[string]bool{
+	"index": true, "serve": true, "batch": true, "repl": true, "tui": true, "extract-worker": true,
+}
+
+// serveAdminCommands are the commands that change the index or write files,
+// which need admin access, by name or by path below the root command for a
+// subcommand. check re-indexes unless given --no-index.
+var serveAdminCommands = map[string]bool{
+	"coverage": true, "trace": true, "embed": true, "import": true, "merge": true, "export": true,
+	"rename": true, "sgrep": true, "gen-fixture": true, "bench": true, "history": true, "snapshot": true, "lint": true,
+	"query glob-collisions": true, "check": true,
+}
+
+// serveFileFlags are the flags, by command path, that read a file on the
+// server or its standard input, which clients may not set: the server would
+// open any path it is given, or block reading its own stdin.
+var serveFileFlags = map[string]string{
+	"query upgrade-impact": "--changes",
+}
+
+// watchSkipDirs are directories never watched, besides hidden ones: those
+// indexing skips, and Cargo's build output, which changes on every build.
+var watchSkipDirs = map[string]bool{
+	"node_modules": true, "vendor": true, "__pycache__": true, "target": true,
+}
+
+// server runs the commands of its clients against one index and keeps
+// their subscriptions current.
+type server struct {
+	root  *cobra.Command
+	index []string // command line that re-indexes
+	extra []string // flags appended to every command line, such as --db
+
+	token      string        // grants read access, or admin when adminToken is unset; "" for none
+	adminToken string        // grants admin access; "" for none
+	timeout    time.Duration // per request
+	rate       float64       // requests a second per client; 0 for no limit
+	maxClients int
+
+	mu sync.Mutex // held while a command runs; commands share the CLI's global flags
+
+	// Warm-up with --index-slice: the watched paths are indexed in runs of
+	// slice while requests are served between them.
+	slice     time.Duration
+	dirs      []string
+	warmMu    sync.Mutex      // guards the fields below
+	warming   bool            // runs remain
+	pending   map[string]bool // files the last run did not reach; nil before the first
+	demand    []string        // files and directories waiting requests name
+	warmRound chan struct{}   // closed when a run ends
+
+	connsMu sync.Mutex // guards conns and each connection's subs
+	conns   map[*serveClient]bool
+}
+
+// serveClient is a client connection. Answers and updates are written from
+// different goroutines, so writes hold mu.
+type serveClient struct {
+	mu   sync.Mutex
+	conn net.Conn
+	enc  *json.Encoder
+	role string
+	subs map[string]*subscription
+}
+
+// subscription is a command a client re-runs after each re-index.
+type subscription struct {
+	args []string
+	last []byte // the last result sent, as JSON
+}
+
+func runServe(cmd *cobra.Command, args []string) error {
+	targetDirs, repoRoot, err := resolveIndexTargets(args)
+	if err != nil {
+		return err
+	}
+	switch {
+	case flagPollInterval <= 0:
+		return fmt.Errorf("--interval must be positive")
+	case flagServeTimeout <= 0:
+		return fmt.Errorf("--timeout must be positive")
+	case flagServeRate < 0:
+		return fmt.Errorf("--rate must not be negative")
+	case flagMaxClients <= 0:
+		return fmt.Errorf("--max-clients must be positive")
+	case flagIndexSlice < 0:
+		return fmt.Errorf("--index-slice must not be negative")
+	}
+	interval := flagPollInterval
+	listen := flagListen
+	socketPath := flagSocket
+	if socketPath == "" {
+		dbPath, err := resolveDBPath(repoRoot)
+		if err != nil {
+			return err
+		}
+		socketPath = filepath.Join(filepath.Dir(dbPath), "serve.sock")
+	}
+
+	srv := &server{
+		root:       cmd.Root(),
+		index:      append([]string{"index"}, args...),
+		token:      os.Getenv(serveTokenEnv),
+		adminToken: os.Getenv(serveAdminTokenEnv),
+		timeout:    flagServeTimeout,
+		rate:       flagServeRate,
+		maxClients: flagMaxClients,
+		conns:      map[*serveClient]bool{},
+		slice:      flagIndexSlice,
+		dirs:       targetDirs,
+		warming:    flagIndexSlice > 0,
+		warmRound:  make(chan struct{}),
+	}
+	if flagDB != "" {
+		srv.extra = []string{"--db", flagDB}
+	}
+	if listen != "" && srv.token == "" {
+		return fmt.Errorf("--listen needs a read token in %s", serveTokenEnv)
+	}
+
+	// Fingerprint before indexing, so edits made meanwhile are picked up.
+	fingerprint := sourceFingerprint(targetDirs)
+	if !srv.warming {
+		if err := srv.reindex(); err != nil {
+			return err
+		}
+	}
+
+	ln, err := srv.listen(listen, socketPath)
+	if err != nil {
+		return err
+	}
+	if listen == "" {
+		defer os.Remove(socketPath)
+	}
+	fmt.Fprintf(os.Stderr, "Serving %s on %s\n", strings.Join(targetDirs, ", "), ln.Addr())
+
+	ctx, stop := signal.NotifyContext(context.Background(), os.Interrupt, syscall.SIGTERM)
+	defer stop()
+	go func() {
+		<-ctx.Done()
+		ln.Close()
+	}()
+	go func() {
+		if srv.warming {
+			fmt.Fprintf(os.Stderr, "Indexing in runs of %s while serving\n", srv.slice)
+			srv.warmUp()
+		}
+		srv.watch(ctx, targetDirs, fingerprint, interval)
+	}()
+
+	for {
+		conn, err := ln.Accept()
+		if err != nil {
+			if ctx.Err() != nil {
+				return nil
+			}
+			return fmt.Errorf("accepting connection: %w", err)
+		}
+		go srv.handleConn(conn)
+	}
+}
+
+// listen opens the TCP address, or if that is "" the Unix socket.
+func (s *server) listen(address, socketPath string) (net.Listener, error) {
+	if address != "" {
+		ln, err := net.Listen("tcp", address)
+		if err != nil {
+			return nil, fmt.Errorf("listening on %s: %w", address, err)
+		}
+		return ln, nil
+	}
+	// A socket left by a server that is gone is replaced; a live one is not.
+	if c, err := net.Dial("unix", socketPath); err == nil {
+		c.Close()
+		return nil, fmt.Errorf("a server is already listening on %s", socketPath)
+	}
+	if err := os.Remove(socketPath); err != nil && !os.IsNotExist(err) {
+		return nil, fmt.Errorf("removing stale socket: %w", err)
+	}
+	ln, err := net.Listen("unix", socketPath)
+	if err != nil {
+		return nil, fmt.Errorf("listening on %s: %w", socketPath, err)
+	}
+	return ln, nil
+}
+
+// execute runs one command line and returns its result envelope.
+func (s *server) execute(args []string) CLIResult {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return s.executeLocked(args)
+}
+
+// executeWithin runs one command line if it can start before deadline, and
+// waits for it no longer than that. A command that overruns still finishes,
+// as commands cannot be interrupted, but its result is dropped.
+func (s *server) executeWithin(args []string, deadline time.Time) (CLIResult, error) {
+	type outcome struct {
+		result CLIResult
+		ran    bool
+	}
+	done := make(chan outcome, 1)
+	go func() {
+		s.mu.Lock()
+		defer s.mu.Unlock()
+		if time.Now().After(deadline) {
+			done <- outcome{} // already answered with a timeout
+			return
+		}
+		done <- outcome{s.executeLocked(args), true}
+	}()
+	timer := time.NewTimer(time.Until(deadline))
+	defer timer.Stop()
+	select {
+	case o := <-done:
+		if o.ran {
+			return o.result, nil
+		}
+	case <-timer.C:
+	}
+	return CLIResult{}, fmt.Errorf("timed out after %s", s.timeout)
+}
+
+// executeLocked runs one command line while s.mu is held.
+func (s *server) executeLocked(args []string) CLIResult {
+	var result *CLIResult
+	resultSink = func(r CLIResult) { result = &r }
+	resetFlags(s.root)
+	s.root.SetArgs(append(append([]string{}, args...), s.extra...))
+	err := s.root.Execute()
+	resultSink = nil
+	errorHandled = false
+	if result == nil {
+		result = &CLIResult{}
+		if err != nil {
+			result.Error = err.Error()
+		}
+	}
+	return *result
+}
+
+// reindex re-indexes the watched paths.
+func (s *server) reindex() error {
+	if result := s.execute(s.index); result.Error != "" {
+		return errors.New(result.Error)
+	}
+	return nil
+}
+
+// warmUp indexes the watched paths in runs of at most s.slice until every
+// file is indexed. Each run starts with the files that waiting requests name
+// and the files they import; other requests run between the runs, against
+// the partial index. Subscriptions are refreshed after every run.
+func (s *server) warmUp() {
+	for {
+		s.warmMu.Lock()
+		args := append(slices.Clone(s.index), "--deadline", s.slice.String())
+		for _, p := range s.demand {
+			args = append(args, "--priority", p)
+		}
+		s.warmMu.Unlock()
+
+		s.mu.Lock()
+		result := s.executeLocked(args)
+		c := indexCompleteness
+		s.mu.Unlock()
+
+		s.warmMu.Lock()
+		s.pending = make(map[string]bool, len(c.Pending))
+		for _, p := range c.Pending {
+			s.pending[p] = true
+		}
+		s.demand = slices.DeleteFunc(s.demand, func(p string) bool { return !s.isPending(p) })
+		done := result.Error != "" || len(c.Pending) == 0
+		if done {
+			s.warming = false
+		}
+		close(s.warmRound)
+		s.warmRound = make(chan struct{})
+		s.warmMu.Unlock()
+
+		if result.Error != "" {
+			fmt.Fprintf(os.Stderr, "Error: indexing: %s\n", result.Error)
+			return
+		}
+		if done {
+			// Steps left out past a deadline, such as blame, need a
+			// run without one.
+			if len(c.Skipped) > 0 {
+				if err := s.reindex(); err != nil {
+					fmt.Fprintf(os.Stderr, "Error: indexing: %s\n", err)
+				}
+			}
+			s.refresh()
+			return
+		}
+		s.refresh()
+	}
+}
+
+// awaitIndexed waits during warm-up until none of paths is pending, moving
+// them to the front of the next run. It fails at deadline.
+func (s *server) awaitIndexed(paths []string, deadline time.Time) error {
+	s.warmMu.Lock()
+	if !s.warming || len(paths) == 0 {
+		s.warmMu.Unlock()
+		return nil
+	}
+	timer := time.NewTimer(time.Until(deadline))
+	defer timer.Stop()
+	for _, p := range paths {
+		if !slices.Contains(s.demand, p) {
+			s.demand = append(s.demand, p)
+		}
+	}
+	for s.warming && slices.ContainsFunc(paths, s.isPending) {
+		round := s.warmRound
+		s.warmMu.Unlock()
+		select {
+		case <-round:
+		case <-timer.C:
+			return fmt.Errorf("timed out after %s waiting for indexing", s.timeout)
+		}
+		s.warmMu.Lock()
+	}
+	s.warmMu.Unlock()
+	return nil
+}
+
+// isPending reports whether the file path, or a file under the directory
+// path, is not indexed yet. Everything is pending before the first run
+// ends. s.warmMu must be held.
+func (s *server) isPending(path string) bool {
+	if s.pending == nil || s.pending[path] {
+		return true
+	}
+	for p := range s.pending {
+		if strings.HasPrefix(p, path+string(filepath.Separator)) {
+			return true
+		}
+	}
+	return false
+}
+
+// demandedPaths returns the source files and directories under dirs that
+// the arguments of a command line name, as operands or flag values, as
+// absolute paths.
+func demandedPaths(args, dirs []string) []string {
+	var paths []string
+	for _, arg := range args[min(1, len(args)):] {
+		if strings.HasPrefix(arg, "-") {
+			_, value, ok := strings.Cut(arg, "=")
+			if !ok {
+				continue
+			}
+			arg = value
+		}
+		abs, err := filepath.Abs(arg)
+		if err != nil {
+			continue
+		}
+		info, err := os.Stat(abs)
+		if err != nil {
+			continue
+		}
+		if _, ok := canopyrt.LanguageForFile(abs); !ok && !info.IsDir() {
+			continue
+		}
+		under := slices.ContainsFunc(dirs, func(dir string) bool {
+			return abs == dir || strings.HasPrefix(abs, dir+string(filepath.Separator))
+		})
+		if under && !slices.Contains(paths, abs) {
+			paths = append(paths, abs)
+		}
+	}
+	return paths
+}
+
+// watch re-indexes whenever the fingerprint of dirs changes, then pushes
+// the subscriptions whose results changed.
+func (s *server) watch(ctx context.Context, dirs []string, last uint64, interval time.Duration) {
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+		}
+		fingerprint := sourceFingerprint(dirs)
+		if fingerprint == last {
+			continue
+		}
+		last = fingerprint
+		if err := s.reindex(); err != nil {
+			fmt.Fprintf(os.Stderr, "Error: re-indexing: %s\n", err)
+			continue
+		}
+		s.refresh()
+	}
+}
+
+// refresh re-runs every subscription, once per distinct command line, and
+// sends an update to each whose result differs from the last one sent.
+func (s *server) refresh() {
+	type pending struct {
+		client *serveClient
+		id     string
+		sub    *subscription
+	}
+	var subs []pending
+	s.connsMu.Lock()
+	for c := range s.conns {
+		for id, sub := range c.subs {
+			subs = append(subs, pending{c, id, sub})
+		}
+	}
+	s.connsMu.Unlock()
+
+	results := map[string]CLIResult{}
+	for _, p := range subs {
+		key := strings.Join(p.sub.args, "\x00")
+		result, ok := results[key]
+		if !ok {
+			result = s.execute(p.sub.args)
+			results[key] = result
+		}
+		encoded, _ := json.Marshal(result)
+		s.connsMu.Lock()
+		changed := !bytes.Equal(encoded, p.sub.last)
+		p.sub.last = encoded
+		s.connsMu.Unlock()
+		if changed {
+			p.client.send(serveMessage{ID: p.id, Event: serveEventUpdate, Result: &result})
+		}
+	}
+}
+
+// handleConn reads the requests of one connection until it closes.
+func (s *server) handleConn(conn net.Conn) {
+	defer conn.Close()
+	c := &serveClient{conn: conn, enc: json.NewEncoder(conn), role: s.initialRole(), subs: map[string]*subscription{}}
+	s.connsMu.Lock()
+	if len(s.conns) >= s.maxClients {
+		s.connsMu.Unlock()
+		c.send(serveMessage{Event: serveEventError, Error: fmt.Sprintf("too many clients (at most %d)", s.maxClients)})
+		return
+	}
+	s.conns[c] = true
+	s.connsMu.Unlock()
+	defer func() {
+		s.connsMu.Lock()
+		delete(s.conns, c)
+		s.connsMu.Unlock()
+	}()
+
+	limiter := newRateLimiter(s.rate, time.Now())
+	dec := json.NewDecoder(conn)
+	for {
+		var req serveRequest
+		if err := dec.Decode(&req); err != nil {
+			if !errors.Is(err, io.EOF) && !errors.Is(err, net.ErrClosed) {
+				c.send(serveMessage{Event: serveEventError, Error: fmt.Sprintf("invalid request: %s", err)})
+			}
+			return
+		}
+		if !limiter.allow(time.Now()) {
+			c.send(serveMessage{ID: req.ID, Event: serveEventError, Error: fmt.Sprintf("rate limit exceeded (%g requests a second)", s.rate)})
+			continue
+		}
+		if !s.handle(c, req) {
+			return
+		}
+	}
+}
+
+// initialRole returns the access of a client that has not authenticated:
+// none when any token is set, so it must authenticate first.
+func (s *server) initialRole() string {
+	if s.token != "" || s.adminToken != "" {
+		return ""
+	}
+	return serveRoleAdmin
+}
+
+// roleFor returns the access token grants, or "" if it grants none.
+func (s *server) roleFor(token string) string {
+	matches := func(want string) bool {
+		return want != "" && subtle.ConstantTimeCompare([]byte(token), []byte(want)) == 1
+	}
+	switch {
+	case matches(s.adminToken):
+		return serveRoleAdmin
+	case matches(s.token) && s.adminToken == "":
+		return serveRoleAdmin
+	case matches(s.token):
+		return serveRoleRead
+	}
+	return ""
+}
+
+// hasFlag reports whether args set flag, as "--flag value" or "--flag=value".
+// An empty flag is never set.
+func hasFlag(args []string, flag string) bool {
+	return flag != "" && slices.ContainsFunc(args, func(a string) bool { return a == flag || strings.HasPrefix(a, flag+"=") })
+}
+
+// serveCommand returns the command args run, as its path below the root
+// command, such as "query glob-collisions", or args[0] if there is none.
+func serveCommand(args []string) string {
+	cmd, _, err := rootCmd.Find(args)
+	if err != nil || cmd == rootCmd {
+		return args[0]
+	}
+	return strings.TrimPrefix(cmd.CommandPath(), rootCmd.Name()+" ")
+}
+
+// handle answers one request. It returns false when the connection should
+// be closed.
+func (s *server) handle(c *serveClient, req serveRequest) bool {
+	fail := func(format string, args ...any) {
+		c.send(serveMessage{ID: req.ID, Event: serveEventError, Error: fmt.Sprintf(format, args...)})
+	}
+	if req.Auth != "" {
+		role := s.roleFor(req.Auth)
+		if role == "" {
+			fail("invalid token")
+			return false
+		}
+		c.role = role
+		c.send(serveMessage{ID: req.ID, Event: serveEventAuthenticated, Role: role})
+		return true
+	}
+	if c.role == "" {
+		fail("authenticate first with {\"auth\": \"<token>\"}")
+		return false
+	}
+
+	if req.Unsubscribe {
+		s.connsMu.Lock()
+		_, ok := c.subs[req.ID]
+		delete(c.subs, req.ID)
+		s.connsMu.Unlock()
+		if !ok {
+			fail("no subscription %q", req.ID)
+			return true
+		}
+		c.send(serveMessage{ID: req.ID, Event: serveEventUnsubscribed})
+		return true
+	}
+	if req.Reindex {
+		if c.role != serveRoleAdmin {
+			fail("reindex needs admin access")
+			return true
+		}
+		if err := s.reindex(); err != nil {
+			fail("re-indexing: %s", err)
+			return true
+		}
+		c.send(serveMessage{ID: req.ID, Event: serveEventReindexed})
+		s.refresh()
+		return true
+	}
+
+	if len(req.Args) == 0 {
+		fail("request has no args")
+		return true
+	}
+	name := serveCommand(req.Args)
+	switch {
+	case serveUnavailable[req.Args[0]]:
+		fail("%s cannot be run by the server", req.Args[0])
+		return true
+	case serveAdminCommands[req.Args[0]] && c.role != serveRoleAdmin:
+		fail("%s needs admin access", req.Args[0])
+		return true
+	case serveAdminCommands[name] && c.role != serveRoleAdmin:
+		fail("%s needs admin access", name)
+		return true
+	case hasFlag(req.Args, "--db"):
+		fail("--db cannot be set; the server answers from its own database")
+		return true
+	case hasFlag(req.Args, serveFileFlags[name]):
+		fail("%s cannot be set through the server; pass the items as arguments", serveFileFlags[name])
+		return true
+	}
+	if req.Subscribe {
+		if req.ID == "" {
+			fail("a subscription needs an id")
+			return true
+		}
+		s.connsMu.Lock()
+		_, taken := c.subs[req.ID]
+		s.connsMu.Unlock()
+		if taken {
+			fail("duplicate subscription %q", req.ID)
+			return true
+		}
+	}
+
+	deadline := time.Now().Add(s.timeout)
+	if err := s.awaitIndexed(demandedPaths(req.Args, s.dirs), deadline); err != nil {
+		fail("%s", err)
+		return true
+	}
+	result, err := s.executeWithin(req.Args, deadline)
+	if err != nil {
+		fail("%s", err)
+		return true
+	}
+	if req.Subscribe {
+		encoded, _ := json.Marshal(result)
+		s.connsMu.Lock()
+		c.subs[req.ID] = &subscription{args: req.Args, last: encoded}
+		s.connsMu.Unlock()
+	}
+	c.send(serveMessage{ID: req.ID, Event: serveEventResult, Result: &result})
+	return true
+}
+
+// serveWriteTimeout bounds a write to a client, so one that stops reading
+// cannot hold up the updates of the others.
+const serveWriteTimeout = 10 * time.Second
+
+// send writes msg as one line. A failed write means the client is gone or
+// stuck; closing the connection ends its read loop.
+func (c *serveClient) send(msg serveMessage) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	_ = c.conn.SetWriteDeadline(time.Now().Add(serveWriteTimeout))
+	if err := c.enc.Encode(msg); err != nil {
+		c.conn.Close()
+	}
+}
+
+// rateLimiter is a token bucket refilled at rate tokens a second and
+// holding at most one second's worth, or one token if rate is below one.
+// A zero rate allows everything.
+type rateLimiter struct {
+	rate   float64
+	tokens float64
+	last   time.Time
+}
+
+func newRateLimiter(rate float64, now time.Time) *rateLimiter {
+	return &rateLimiter{rate: rate, tokens: max(rate, 1), last: now}
+}
+
+// allow takes a token if one is left at now.
+func (l *rateLimiter) allow(now time.Time) bool {
+	if l.rate <= 0 {
+		return true
+	}
+	l.tokens = min(max(l.rate, 1), l.tokens+now.Sub(l.last).Seconds()*l.rate)
+	l.last = now
+	if l.tokens < 1 {
+		return false
+	}
+	l.tokens--
+	return true
+}
+
+// sourceFingerprint hashes the path, size, and modification time of every
+// source file and Cargo manifest under dirs, so that any edit, addition, or
+// removal changes it.
+func sourceFingerprint(dirs []string) uint64 {
+	h := fnv.New64a()
+	for _, dir := range dirs {
+		_ = filepath.WalkDir(dir, func(path string, d fs.DirEntry, err error) error {
+			if err != nil {
+				return nil // gone since it was listed
+			}
+			if d.IsDir() {
+				if path != dir && (strings.HasPrefix(d.Name(), ".") || watchSkipDirs[d.Name()]) {
+					return filepath.SkipDir
+				}
+				return nil
+			}
+			if _, ok := canopyrt.LanguageForFile(path); !ok && d.Name() != "Cargo.toml" {
+				return nil
+			}
+			info, err := d.Info()
+			if err != nil {
+				return nil
+			}
+			fmt.Fprintf(h, "%s\x00%d\x00%d\n", path, info.Size(), info.ModTime().UnixNano())
+			return nil
+		})
+	}
+	return h.Sum64()
+}
@@ -0,0 +1,192 @@
+package main_test
+
+import (
+	"encoding/json"
+	"net"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// serveMessage is a line written by canopy serve.
+type serveMessage struct {
+	ID     string `json:"id"`
+	Event  string `json:"event"`
+	Role   string `json:"role"`
+	Error  string `json:"error"`
+	Result struct {
+		Command    string `json:"command"`
+		Results    []any  `json:"results"`
+		TotalCount int    `json:"total_count"`
+		Error      string `json:"error"`
+	} `json:"result"`
+}
+
+// startServer runs canopy serve in fixtureDir, with env added to its
+// environment, and returns its socket once it listens.
+func startServer(t *testing.T, bin, fixtureDir string, env ...string) string {
+	t.Helper()
+	cmd := exec.Command(bin, "serve", "--interval", "100ms")
+	cmd.Dir = fixtureDir
+	cmd.Env = append(append(os.Environ(), "HOME="+t.TempDir()), env...)
+	require.NoError(t, cmd.Start())
+	t.Cleanup(func() {
+		_ = cmd.Process.Signal(os.Interrupt)
+		_ = cmd.Wait()
+	})
+
+	socket := filepath.Join(fixtureDir, ".canopy", "serve.sock")
+	require.Eventually(t, func() bool {
+		conn, err := net.Dial("unix", socket)
+		if err == nil {
+			conn.Close()
+		}
+		return err == nil
+	}, 30*time.Second, 50*time.Millisecond, "server never listened on %s", socket)
+	return socket
+}
+
+// dialServer connects to the server's socket.
+func dialServer(t *testing.T, socket string) (*json.Encoder, *json.Decoder) {
+	t.Helper()
+	conn, err := net.Dial("unix", socket)
+	require.NoError(t, err)
+	t.Cleanup(func() { conn.Close() })
+	require.NoError(t, conn.SetDeadline(time.Now().Add(60*time.Second)))
+	return json.NewEncoder(conn), json.NewDecoder(conn)
+}
+
+func TestServe_SubscriptionPushesUpdates(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+	enc, dec := dialServer(t, startServer(t, bin, fixtureDir))
+	receive := func() serveMessage {
+		t.Helper()
+		var msg serveMessage
+		require.NoError(t, dec.Decode(&msg))
+		return msg
+	}
+
+	require.NoError(t, enc.Encode(map[string]any{"id": "once", "args": []string{"query", "symbols", "--kind", "function", "--limit", "1"}}))
+	msg := receive()
+	assert.Equal(t, "once", msg.ID)
+	assert.Equal(t, "result", msg.Event)
+	assert.Len(t, msg.Result.Results, 1)
+
+	require.NoError(t, enc.Encode(map[string]any{"id": "fns", "args": []string{"query", "symbols", "--kind", "function"}, "subscribe": true}))
+	msg = receive()
+	assert.Equal(t, "fns", msg.ID)
+	assert.Equal(t, "result", msg.Event)
+	assert.Len(t, msg.Result.Results, msg.Result.TotalCount, "--limit from the previous request does not carry over")
+	functions := msg.Result.TotalCount
+
+	require.NoError(t, enc.Encode(map[string]any{"id": "reindex", "args": []string{"index"}}))
+	msg = receive()
+	assert.Equal(t, "error", msg.Event)
+	assert.Contains(t, msg.Error, "index cannot be run")
+
+	require.NoError(t, os.WriteFile(filepath.Join(fixtureDir, "extra.go"), []byte("package main\n\nfunc extra() int { return 42 }\n"), 0o644))
+	msg = receive()
+	assert.Equal(t, "fns", msg.ID)
+	assert.Equal(t, "update", msg.Event)
+	assert.Equal(t, functions+1, msg.Result.TotalCount)
+
+	require.NoError(t, enc.Encode(map[string]any{"id": "fns", "unsubscribe": true}))
+	msg = receive()
+	assert.Equal(t, "unsubscribed", msg.Event)
+	require.NoError(t, enc.Encode(map[string]any{"id": "fns", "unsubscribe": true}))
+	msg = receive()
+	assert.Equal(t, "error", msg.Event)
+}
+
+func TestServe_TokensAndAdminAccess(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+	request := func(enc *json.Encoder, dec *json.Decoder, req map[string]any) serveMessage {
+		t.Helper()
+		require.NoError(t, enc.Encode(req))
+		var msg serveMessage
+		require.NoError(t, dec.Decode(&msg))
+		return msg
+	}
+
+	socket := startServer(t, bin, fixtureDir, "CANOPY_SERVE_TOKEN=team", "CANOPY_SERVE_ADMIN_TOKEN=ops")
+	enc, dec := dialServer(t, socket)
+	msg := request(enc, dec, map[string]any{"id": "q", "args": []string{"query", "summary"}})
+	assert.Equal(t, "error", msg.Event)
+	assert.Contains(t, msg.Error, "authenticate first")
+	var extra serveMessage
+	assert.Error(t, dec.Decode(&extra), "the connection is closed")
+
+	enc, dec = dialServer(t, socket)
+	msg = request(enc, dec, map[string]any{"auth": "team"})
+	assert.Equal(t, "authenticated", msg.Event)
+	assert.Equal(t, "read", msg.Role)
+	msg = request(enc, dec, map[string]any{"id": "q", "args": []string{"query", "summary"}})
+	assert.Equal(t, "result", msg.Event)
+	assert.Empty(t, msg.Result.Error)
+	msg = request(enc, dec, map[string]any{"id": "r", "reindex": true})
+	assert.Equal(t, "error", msg.Event)
+	assert.Contains(t, msg.Error, "admin")
+	msg = request(enc, dec, map[string]any{"id": "c", "args": []string{"coverage", "lcov.info"}})
+	assert.Contains(t, msg.Error, "coverage needs admin access")
+	msg = request(enc, dec, map[string]any{"id": "s", "args": []string{"snapshot", "save", "pre-refactor"}})
+	assert.Contains(t, msg.Error, "snapshot needs admin access")
+	msg = request(enc, dec, map[string]any{"id": "l", "args": []string{"lint", "--write-baseline"}})
+	assert.Contains(t, msg.Error, "lint needs admin access")
+	msg = request(enc, dec, map[string]any{"id": "g", "args": []string{"query", "glob-collisions", "--fix"}})
+	assert.Contains(t, msg.Error, "query glob-collisions needs admin access")
+	msg = request(enc, dec, map[string]any{"id": "k", "args": []string{"check"}})
+	assert.Contains(t, msg.Error, "check needs admin access")
+	msg = request(enc, dec, map[string]any{"id": "d", "args": []string{"query", "summary", "--db", "/tmp/other.db"}})
+	assert.Contains(t, msg.Error, "--db cannot be set")
+	for _, args := range [][]string{{"--changes", "-"}, {"--changes", "/etc/passwd"}, {"--changes=-"}} {
+		msg = request(enc, dec, map[string]any{"id": "u", "args": append([]string{"query", "upgrade-impact"}, args...)})
+		assert.Contains(t, msg.Error, "--changes cannot be set through the server", args)
+	}
+
+	msg = request(enc, dec, map[string]any{"auth": "ops"})
+	assert.Equal(t, "admin", msg.Role)
+	msg = request(enc, dec, map[string]any{"id": "r", "reindex": true})
+	assert.Equal(t, "reindexed", msg.Event)
+	assert.Equal(t, "r", msg.ID)
+}
+
+func TestServe_ListenNeedsReadToken(t *testing.T) {
+	if testing.Short() {
+		t.Skip("skipping integration test")
+	}
+	bin, fixtureDir, _ := indexFixture(t)
+	free, err := net.Listen("tcp", "127.0.0.1:0")
+	require.NoError(t, err)
+	addr := free.Addr().String()
+	require.NoError(t, free.Close())
+
+	cmd := exec.Command(bin, "serve", "--interval", "100ms", "--listen", addr)
+	cmd.Dir = fixtureDir
+	cmd.Env = append(os.Environ(), "HOME="+t.TempDir(), "CANOPY_SERVE_TOKEN=", "CANOPY_SERVE_ADMIN_TOKEN=ops")
+	out, err := cmd.CombinedOutput()
+	assert.Error(t, err)
+	assert.Contains(t, string(out), "--listen needs a read token")
+
+	_, err = net.DialTimeout("tcp", addr, time.Second)
+	assert.Error(t, err, "the server never listened, so no query gets through")
+
+	// On the socket, an admin token alone still keeps out clients that have
+	// not authenticated.
+	enc, dec := dialServer(t, startServer(t, bin, fixtureDir, "CANOPY_SERVE_ADMIN_TOKEN=ops"))
+	require.NoError(t, enc.Encode(map[string]any{"id": "q", "args": []string{"query", "summary"}}))
+	var msg serveMessage
+	require.NoError(t, dec.Decode(&msg))
+	assert.Equal(t, "error", msg.Event)
+	assert.Contains(t, msg.Error, "authenticate first")
+}
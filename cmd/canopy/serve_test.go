@@ -0,0 +1,102 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRateLimiter(t *testing.T) {
+	t.Parallel()
+	start := time.Unix(0, 0)
+	l := newRateLimiter(2, start)
+	assert.True(t, l.allow(start))
+	assert.True(t, l.allow(start))
+	assert.False(t, l.allow(start), "a burst holds one second's worth")
+	assert.False(t, l.allow(start.Add(400*time.Millisecond)))
+	assert.True(t, l.allow(start.Add(500*time.Millisecond)), "a token every half second")
+	assert.True(t, l.allow(start.Add(time.Hour)))
+	assert.True(t, l.allow(start.Add(time.Hour)))
+	assert.False(t, l.allow(start.Add(time.Hour)), "idle time does not build a bigger burst")
+
+	slow := newRateLimiter(0.5, start)
+	assert.True(t, slow.allow(start))
+	assert.False(t, slow.allow(start.Add(time.Second)))
+	assert.True(t, slow.allow(start.Add(2*time.Second)))
+
+	unlimited := newRateLimiter(0, start)
+	for range 100 {
+		assert.True(t, unlimited.allow(start))
+	}
+}
+
+func TestServeRoles(t *testing.T) {
+	t.Parallel()
+	tests := []struct {
+		name              string
+		token, adminToken string
+		initial           string
+		roles             map[string]string // token sent -> role granted
+	}{
+		{"no tokens", "", "", serveRoleAdmin, map[string]string{"anything": ""}},
+		{"token only", "t0k", "", "", map[string]string{"t0k": serveRoleAdmin, "t0": ""}},
+		{"admin token only", "", "adm", "", map[string]string{"adm": serveRoleAdmin, "t0k": ""}},
+		{"both", "t0k", "adm", "", map[string]string{"t0k": serveRoleRead, "adm": serveRoleAdmin, "": ""}},
+	}
+	for _, tt := range tests {
+		s := &server{token: tt.token, adminToken: tt.adminToken}
+		assert.Equal(t, tt.initial, s.initialRole(), tt.name)
+		for token, role := range tt.roles {
+			assert.Equal(t, role, s.roleFor(token), "%s: %q", tt.name, token)
+		}
+	}
+}
+
+func TestServeCommand(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, "query glob-collisions", serveCommand([]string{"query", "glob-collisions", "--fix"}))
+	assert.Equal(t, "snapshot save", serveCommand([]string{"snapshot", "save", "pre-refactor"}))
+	assert.Equal(t, "check", serveCommand([]string{"check"}))
+	assert.Equal(t, "no-such-command", serveCommand([]string{"no-such-command"}))
+}
+
+func TestHasFlag(t *testing.T) {
+	t.Parallel()
+	assert.True(t, hasFlag([]string{"query", "upgrade-impact", "--changes", "-"}, "--changes"))
+	assert.True(t, hasFlag([]string{"query", "upgrade-impact", "--changes=list.txt"}, "--changes"))
+	assert.False(t, hasFlag([]string{"query", "upgrade-impact", "serde::Value"}, "--changes"))
+	assert.False(t, hasFlag([]string{"query", "summary", "--changes"}, ""))
+}
+
+func TestServeDemand(t *testing.T) {
+	t.Parallel()
+	root := t.TempDir()
+	src := filepath.Join(root, "src")
+	require.NoError(t, os.MkdirAll(src, 0o755))
+	lib := filepath.Join(src, "lib.rs")
+	require.NoError(t, os.WriteFile(lib, []byte("pub fn f() {}\n"), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "notes.txt"), nil, 0o644))
+
+	args := []string{"outline", lib, "--path-prefix=" + src, filepath.Join(root, "notes.txt"), "--format", "text", "missing.rs"}
+	assert.Equal(t, []string{lib, src}, demandedPaths(args, []string{root}))
+	assert.Empty(t, demandedPaths(args, []string{filepath.Join(root, "other")}), "outside the served paths")
+
+	s := &server{timeout: time.Minute, warming: true, warmRound: make(chan struct{})}
+	assert.True(t, s.isPending(lib), "everything is pending before the first run")
+	s.pending = map[string]bool{lib: true}
+	assert.True(t, s.isPending(src))
+	delete(s.pending, lib)
+	assert.False(t, s.isPending(src))
+
+	s.pending[lib] = true
+	err := s.awaitIndexed([]string{lib}, time.Now().Add(10*time.Millisecond))
+	assert.ErrorContains(t, err, "waiting for indexing")
+	assert.Equal(t, []string{lib}, s.demand, "the next run starts with it")
+
+	s.warming = false
+	assert.NoError(t, s.awaitIndexed([]string{lib}, time.Now()), "no waiting once warm-up ends")
+}
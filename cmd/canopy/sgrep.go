@@ -0,0 +1,89 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var sgrepCmd = &cobra.Command{
+	Use:   "sgrep <pattern>",
+	Short: "Search code by syntax tree pattern, and rewrite the matches",
+	Long: "Finds the code in indexed files whose syntax tree has the shape of <pattern>, a snippet of code such as\n" +
+		"'ProductService::new()'. In the pattern, $NAME matches any one node, $$$NAME any run of nodes such as a\n" +
+		"call's arguments, and $_ and $$$ match without binding; a name used twice must match the same text.\n" +
+		"Whitespace and comments do not matter. --rewrite gives a template for each match using the same\n" +
+		"metavariables, such as '$X.expect(\"reason\")' for '$X.unwrap()'; the rewrites are printed, and --fix applies\n" +
+		"them. Matches nested in another are rewritten by running again. Files are read from disk, and edits are\n" +
+		"applied atomically: if any file changed since the search, nothing is written.",
+	Args: cobra.ExactArgs(1),
+	RunE: runSgrep,
+}
+
+func init() {
+	sgrepCmd.Flags().String("language", "", "language of the pattern (default: every indexed language it parses in)")
+	sgrepCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	sgrepCmd.Flags().String("rewrite", "", "template to rewrite each match to")
+	sgrepCmd.Flags().Bool("fix", false, "apply the rewrites to the files")
+	sgrepCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	sgrepCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+func runSgrep(cmd *cobra.Command, args []string) error {
+	language, _ := cmd.Flags().GetString("language")
+	rewrite, _ := cmd.Flags().GetString("rewrite")
+	fix, _ := cmd.Flags().GetBool("fix")
+	if fix && rewrite == "" {
+		return outputError("sgrep", fmt.Errorf("--fix requires --rewrite"))
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("sgrep", err)
+	}
+	defer s.Close()
+
+	matches, err := queryBuilder(s).StructuralSearch(context.Background(), args[0], canopy.StructuralSearchOptions{
+		Language:   language,
+		PathPrefix: flagPathPrefix,
+		Rewrite:    rewrite,
+	})
+	if err != nil {
+		return outputError("sgrep", err)
+	}
+
+	if fix {
+		res, err := canopy.ApplyEdits(canopy.StructuralEdits(matches))
+		if err != nil {
+			return outputError("sgrep", err)
+		}
+		fmt.Fprintf(os.Stderr, "Applied %d edit(s) to %d file(s); re-run 'canopy index' to refresh the database\n",
+			res.Edits, len(res.Files))
+		count := len(res.Files)
+		return outputResult(CLIResult{
+			Command:    "sgrep",
+			Results:    CLIApplyResult{Files: res.Files, Edits: res.Edits},
+			TotalCount: &count,
+		})
+	}
+
+	results := make([]CLIStructuralMatch, len(matches))
+	for i, m := range matches {
+		results[i] = CLIStructuralMatch{
+			Location:    locationToCLI(m.Location, nil),
+			Language:    m.Language,
+			Text:        m.Text,
+			Bindings:    m.Bindings,
+			Replacement: m.Replacement,
+		}
+	}
+	paged, totalCount := paginateSlice(results)
+	return outputResult(CLIResult{
+		Command:    "sgrep",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,71 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var shadowsCmd = &cobra.Command{
+	Use:   "shadows",
+	Short: "Find Rust names that shadow or collide with another definition",
+	Long: "Lists Rust names with two definitions where one is expected, each with both definitions:\n" +
+		"  local           a let binding or pattern rebinds a name bound earlier in the function\n" +
+		"  reuse           the same, from a value that uses the name, as in let x = x.trim()\n" +
+		"  glob            an item or use declaration hides a name a glob import brings in\n" +
+		"  glob_collision  two glob imports bring in different items of the same name (ambiguous where used)\n" +
+		"Glob imports are followed to modules of the importing crate; re-exports are not followed.",
+	Args: cobra.NoArgs,
+	RunE: runShadows,
+}
+
+func init() {
+	shadowsCmd.Flags().StringSlice("kind", nil, "only these kinds: local, reuse, glob, glob_collision (repeatable or comma-separated)")
+	shadowsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runShadows(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("shadows", err)
+	}
+	defer s.Close()
+
+	kinds, _ := cmd.Flags().GetStringSlice("kind")
+	shadows, err := queryBuilder(s).Shadows(kinds, flagPathPrefix)
+	if err != nil {
+		return outputError("shadows", err)
+	}
+
+	cliShadows := make([]CLIShadow, len(shadows))
+	for i, sh := range shadows {
+		cliShadows[i] = CLIShadow{
+			Kind:      sh.Kind,
+			Name:      sh.Name,
+			Shadowing: shadowDefinitionToCLI(sh.Shadowing),
+			Shadowed:  shadowDefinitionToCLI(sh.Shadowed),
+		}
+		if sh.Function != nil {
+			fn := symbolResultToCLI(*sh.Function)
+			cliShadows[i].Function = &fn
+		}
+	}
+	paged, totalCount := paginateSlice(cliShadows)
+	return outputResult(CLIResult{
+		Command:    "shadows",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// shadowDefinitionToCLI converts a definition of a shadowed name.
+func shadowDefinitionToCLI(def canopy.ShadowDefinition) CLIShadowDefinition {
+	out := CLIShadowDefinition{Import: def.Import}
+	if def.Symbol != nil {
+		sym := symbolResultToCLI(*def.Symbol)
+		out.Symbol = &sym
+		out.Location = locationToCLI(def.Location, &sym.ID)
+	} else {
+		out.Location = locationToCLI(def.Location, nil)
+	}
+	return out
+}
@@ -0,0 +1,315 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/internal/store"
+	"github.com/spf13/cobra"
+)
+
+// flagSnapshot names a snapshot that queries read instead of the index.
+var flagSnapshot string
+
+// snapshotNameRe is what a snapshot name may contain, so it is always a
+// plain file name.
+var snapshotNameRe = regexp.MustCompile(`^[A-Za-z0-9._-]+$`)
+
+var snapshotCmd = &cobra.Command{
+	Use:   "snapshot",
+	Short: "Save, list, and compare named snapshots of the index",
+	Long: "Named snapshots pin the index at a point in time, such as before a refactor. Any query runs against one\n" +
+		"with --snapshot <name>, and 'canopy snapshot diff' compares the package graph, dependency cycles, and public\n" +
+		"API of two snapshots, or of a snapshot and the current index.",
+}
+
+var snapshotSaveCmd = &cobra.Command{
+	Use:   "save <name>",
+	Short: "Save the current index as a named snapshot",
+	Args:  cobra.ExactArgs(1),
+	RunE:  runSnapshotSave,
+}
+
+var snapshotListCmd = &cobra.Command{
+	Use:   "list",
+	Short: "List the saved snapshots",
+	Args:  cobra.NoArgs,
+	RunE:  runSnapshotList,
+}
+
+var snapshotDeleteCmd = &cobra.Command{
+	Use:   "delete <name>",
+	Short: "Delete a saved snapshot",
+	Args:  cobra.ExactArgs(1),
+	RunE:  runSnapshotDelete,
+}
+
+var snapshotDiffCmd = &cobra.Command{
+	Use:   "diff <base> [<head>]",
+	Short: "Compare the architecture of two snapshots",
+	Long:  "Lists the package dependencies, dependency cycles, and public symbols added or removed between the base snapshot\nand the head snapshot, or the current index when no head is given.",
+	Args:  cobra.RangeArgs(1, 2),
+	RunE:  runSnapshotDiff,
+}
+
+func init() {
+	snapshotSaveCmd.Flags().Bool("force", false, "replace an existing snapshot of the same name")
+
+	snapshotCmd.AddCommand(snapshotSaveCmd)
+	snapshotCmd.AddCommand(snapshotListCmd)
+	snapshotCmd.AddCommand(snapshotDeleteCmd)
+	snapshotCmd.AddCommand(snapshotDiffCmd)
+}
+
+// snapshotWriteCommands are the commands that write into the database they
+// open, which --snapshot would turn into writes to a saved snapshot.
+var snapshotWriteCommands = map[string]bool{
+	"index": true, "import": true, "coverage": true, "trace": true, "embed": true, "merge": true,
+	"serve": true, "snapshot save": true,
+}
+
+// checkSnapshotFlag validates the --snapshot name given to cmd, and rejects
+// it for commands that write, so snapshots stay as they were saved. check
+// indexes first unless given --no-index, so it needs --no-index too.
+func checkSnapshotFlag(cmd *cobra.Command, name string) error {
+	if name == "" {
+		return nil
+	}
+	if _, err := snapshotPath("", name); err != nil {
+		return err
+	}
+	path := strings.TrimPrefix(cmd.CommandPath(), cmd.Root().Name()+" ")
+	if snapshotWriteCommands[path] {
+		return fmt.Errorf("--snapshot is read-only: %s cannot be run against a snapshot", path)
+	}
+	if noIndex, _ := cmd.Flags().GetBool("no-index"); path == "check" && !noIndex {
+		return fmt.Errorf("--snapshot is read-only: check indexes first; add --no-index to evaluate the snapshot")
+	}
+	return nil
+}
+
+// snapshotDir returns the directory snapshots are saved in, next to the
+// index database.
+func snapshotDir(repoRoot string) string {
+	return filepath.Join(filepath.Dir(indexDBPath(repoRoot)), "snapshots")
+}
+
+// snapshotPath returns the database path of the named snapshot.
+func snapshotPath(repoRoot, name string) (string, error) {
+	if !snapshotNameRe.MatchString(name) || name == "." || name == ".." {
+		return "", fmt.Errorf("invalid snapshot name %q: use letters, digits, '.', '_', and '-'", name)
+	}
+	return filepath.Join(snapshotDir(repoRoot), name+".db"), nil
+}
+
+// openSnapshot opens the named snapshot, or the current index for an empty
+// name.
+func openSnapshot(repoRoot, name string) (*store.Store, error) {
+	dbPath := indexDBPath(repoRoot)
+	if name != "" {
+		var err error
+		if dbPath, err = snapshotPath(repoRoot, name); err != nil {
+			return nil, err
+		}
+	}
+	if _, err := os.Stat(dbPath); os.IsNotExist(err) {
+		if name != "" {
+			return nil, fmt.Errorf("snapshot not found: %s", name)
+		}
+		return nil, fmt.Errorf("database not found: %s (run 'canopy index' first)", dbPath)
+	}
+	return store.NewStore(dbPath)
+}
+
+func runSnapshotSave(cmd *cobra.Command, args []string) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("snapshot save", fmt.Errorf("getting cwd: %w", err))
+	}
+	repoRoot := findRepoRoot(cwd)
+	path, err := snapshotPath(repoRoot, args[0])
+	if err != nil {
+		return outputError("snapshot save", err)
+	}
+	if _, err := os.Stat(path); err == nil {
+		if force, _ := cmd.Flags().GetBool("force"); !force {
+			return outputError("snapshot save", fmt.Errorf("snapshot %q already exists (use --force to replace it)", args[0]))
+		}
+	}
+
+	s, err := openSnapshot(repoRoot, "")
+	if err != nil {
+		return outputError("snapshot save", err)
+	}
+	defer s.Close()
+
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return outputError("snapshot save", fmt.Errorf("creating %s: %w", filepath.Dir(path), err))
+	}
+	// Copy beside the old snapshot, then swap, so a failed save keeps it.
+	tmp := path + ".tmp"
+	os.Remove(tmp)
+	if err := s.CopyTo(tmp); err != nil {
+		os.Remove(tmp)
+		return outputError("snapshot save", err)
+	}
+	if err := os.Rename(tmp, path); err != nil {
+		os.Remove(tmp)
+		return outputError("snapshot save", fmt.Errorf("saving snapshot: %w", err))
+	}
+
+	info, err := snapshotInfo(args[0], path)
+	if err != nil {
+		return outputError("snapshot save", err)
+	}
+	return outputResult(CLIResult{Command: "snapshot save", Results: []CLISnapshotInfo{info}})
+}
+
+func runSnapshotList(cmd *cobra.Command, args []string) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("snapshot list", fmt.Errorf("getting cwd: %w", err))
+	}
+	dir := snapshotDir(findRepoRoot(cwd))
+	entries, err := os.ReadDir(dir)
+	if err != nil && !os.IsNotExist(err) {
+		return outputError("snapshot list", fmt.Errorf("reading %s: %w", dir, err))
+	}
+
+	snapshots := []CLISnapshotInfo{}
+	for _, e := range entries {
+		name, ok := strings.CutSuffix(e.Name(), ".db")
+		if !ok || e.IsDir() || !snapshotNameRe.MatchString(name) {
+			continue
+		}
+		info, err := snapshotInfo(name, filepath.Join(dir, e.Name()))
+		if err != nil {
+			return outputError("snapshot list", err)
+		}
+		snapshots = append(snapshots, info)
+	}
+	sort.Slice(snapshots, func(i, j int) bool { return snapshots[i].Name < snapshots[j].Name })
+
+	total := len(snapshots)
+	return outputResult(CLIResult{
+		Command:    "snapshot list",
+		Results:    snapshots,
+		TotalCount: &total,
+	})
+}
+
+func runSnapshotDelete(cmd *cobra.Command, args []string) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("snapshot delete", fmt.Errorf("getting cwd: %w", err))
+	}
+	path, err := snapshotPath(findRepoRoot(cwd), args[0])
+	if err != nil {
+		return outputError("snapshot delete", err)
+	}
+	info, err := snapshotInfo(args[0], path)
+	if os.IsNotExist(err) {
+		return outputError("snapshot delete", fmt.Errorf("snapshot not found: %s", args[0]))
+	}
+	if err != nil {
+		return outputError("snapshot delete", err)
+	}
+	if err := os.Remove(path); err != nil {
+		return outputError("snapshot delete", fmt.Errorf("deleting snapshot: %w", err))
+	}
+	return outputResult(CLIResult{Command: "snapshot delete", Results: []CLISnapshotInfo{info}})
+}
+
+func runSnapshotDiff(cmd *cobra.Command, args []string) error {
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("snapshot diff", fmt.Errorf("getting cwd: %w", err))
+	}
+	repoRoot := findRepoRoot(cwd)
+	headName := ""
+	if len(args) > 1 {
+		headName = args[1]
+	}
+
+	base, err := openSnapshot(repoRoot, args[0])
+	if err != nil {
+		return outputError("snapshot diff", err)
+	}
+	defer base.Close()
+	head, err := openSnapshot(repoRoot, headName)
+	if err != nil {
+		return outputError("snapshot diff", err)
+	}
+	defer head.Close()
+
+	diff, err := canopy.CompareGraphs(queryBuilder(base), queryBuilder(head))
+	if err != nil {
+		return outputError("snapshot diff", err)
+	}
+	if headName == "" {
+		headName = "(index)"
+	}
+	return outputResult(CLIResult{
+		Command: "snapshot diff",
+		Results: toCLIGraphDiff(args[0], headName, diff),
+	})
+}
+
+// snapshotInfo describes the snapshot saved at path.
+func snapshotInfo(name, path string) (CLISnapshotInfo, error) {
+	fi, err := os.Stat(path)
+	if err != nil {
+		return CLISnapshotInfo{}, err
+	}
+	return CLISnapshotInfo{
+		Name:    name,
+		Path:    path,
+		Bytes:   fi.Size(),
+		Created: fi.ModTime().UTC().Format(time.RFC3339),
+	}, nil
+}
+
+func toCLIGraphDiff(base, head string, d *canopy.GraphDiff) CLIGraphDiff {
+	edges := func(in []canopy.DependencyEdge) []CLIDependencyEdge {
+		out := make([]CLIDependencyEdge, len(in))
+		for i, e := range in {
+			out[i] = CLIDependencyEdge{FromPackage: e.FromPackage, ToPackage: e.ToPackage, ImportCount: e.ImportCount}
+		}
+		return out
+	}
+	api := func(in []canopy.APISymbol) []CLIAPISymbol {
+		out := make([]CLIAPISymbol, len(in))
+		for i, s := range in {
+			out[i] = CLIAPISymbol{File: s.File, Name: s.Name, Kind: s.Kind}
+		}
+		return out
+	}
+	summary := func(s canopy.GraphSummary) CLIGraphSummary {
+		return CLIGraphSummary{
+			Files:        s.Files,
+			Symbols:      s.Symbols,
+			Packages:     s.Packages,
+			Dependencies: s.Dependencies,
+			Cycles:       s.Cycles,
+			PublicAPI:    s.PublicAPI,
+		}
+	}
+	return CLIGraphDiff{
+		Base:                base,
+		Head:                head,
+		BaseSummary:         summary(d.Base),
+		HeadSummary:         summary(d.Head),
+		AddedDependencies:   edges(d.AddedDependencies),
+		RemovedDependencies: edges(d.RemovedDependencies),
+		NewCycles:           d.NewCycles,
+		BrokenCycles:        d.BrokenCycles,
+		AddedAPI:            api(d.AddedAPI),
+		RemovedAPI:          api(d.RemovedAPI),
+	}
+}
@@ -0,0 +1,135 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var snippetCmd = &cobra.Command{
+	Use:   "snippet [<symbol>]",
+	Short: "Print a symbol's source, with its callers and callees, for reviews and prompts",
+	Long: "Prints a symbol's source, starting at its doc comment and attributes, as Markdown ready to paste into a\n" +
+		"review or an LLM prompt. --context adds the signatures of its callers and callees up to a number of calls\n" +
+		"away, e.g. --context callers:1,callees:2 (a bare 'callers' means callers:1). Every indexed symbol named\n" +
+		"<symbol> is shown; --symbol <id> selects one. With --format json, the Markdown is in each result's text.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runSnippet,
+}
+
+func init() {
+	snippetCmd.ValidArgsFunction = completeSymbolNames
+	snippetCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+	snippetCmd.Flags().String("context", "", "callers and callees to include, e.g. callers:1,callees:1")
+	snippetCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	snippetCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+// parseSnippetContext parses a --context value: comma-separated callers or
+// callees, each with an optional :depth (default 1).
+func parseSnippetContext(s string) (canopy.SnippetContext, error) {
+	var sc canopy.SnippetContext
+	if s == "" {
+		return sc, nil
+	}
+	for _, part := range strings.Split(s, ",") {
+		name, depthStr, hasDepth := strings.Cut(strings.TrimSpace(part), ":")
+		depth := 1
+		if hasDepth {
+			n, err := strconv.Atoi(depthStr)
+			if err != nil || n < 0 {
+				return sc, fmt.Errorf("invalid --context depth %q in %q", depthStr, part)
+			}
+			depth = n
+		}
+		switch name {
+		case "callers":
+			sc.Callers = depth
+		case "callees":
+			sc.Callees = depth
+		default:
+			return sc, fmt.Errorf("invalid --context %q (want callers or callees)", part)
+		}
+	}
+	return sc, nil
+}
+
+func runSnippet(cmd *cobra.Command, args []string) error {
+	contextFlag, _ := cmd.Flags().GetString("context")
+	sc, err := parseSnippetContext(contextFlag)
+	if err != nil {
+		return outputError("snippet", err)
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("snippet", err)
+	}
+	defer s.Close()
+
+	var ids []int64
+	if symID, _ := cmd.Flags().GetInt64("symbol"); symID != 0 {
+		ids = append(ids, symID)
+	} else {
+		if len(args) == 0 {
+			return outputError("snippet", fmt.Errorf("requires either a <symbol> argument or --symbol flag"))
+		}
+		syms, err := s.SymbolsByName(args[0])
+		if err != nil {
+			return outputError("snippet", err)
+		}
+		for _, sym := range syms {
+			if sym.FileID != nil {
+				ids = append(ids, sym.ID)
+			}
+		}
+		if len(ids) == 0 {
+			return outputError("snippet", fmt.Errorf("no symbol named %q", args[0]))
+		}
+	}
+
+	qb := queryBuilder(s)
+	var snippets []CLISnippet
+	for _, id := range ids {
+		snip, err := qb.Snippet(id, sc)
+		if err != nil {
+			return outputError("snippet", err)
+		}
+		if snip == nil {
+			return outputError("snippet", fmt.Errorf("symbol not found: %d", id))
+		}
+		snippets = append(snippets, CLISnippet{
+			Symbol:    symbolResultToCLI(snip.Symbol),
+			File:      snip.File,
+			Language:  snip.Language,
+			StartLine: snip.StartLine,
+			Source:    snip.Source,
+			Callers:   snippetNeighborsToCLI(snip.Callers),
+			Callees:   snippetNeighborsToCLI(snip.Callees),
+			Text:      snip.Markdown(),
+		})
+	}
+	paged, totalCount := paginateSlice(snippets)
+	return outputResult(CLIResult{
+		Command:    "snippet",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// snippetNeighborsToCLI converts a snippet's callers or callees.
+func snippetNeighborsToCLI(ns []canopy.SnippetNeighbor) []CLISnippetNeighbor {
+	out := make([]CLISnippetNeighbor, len(ns))
+	for i, n := range ns {
+		out[i] = CLISnippetNeighbor{
+			Symbol:    symbolResultToCLI(n.Symbol),
+			File:      n.File,
+			Signature: n.Signature,
+			Depth:     n.Depth,
+		}
+	}
+	return out
+}
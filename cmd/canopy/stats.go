@@ -0,0 +1,54 @@
+package main
+
+import (
+	"time"
+
+	"github.com/spf13/cobra"
+)
+
+var statsCmd = &cobra.Command{
+	Use:   "stats",
+	Short: "Report index statistics: files, symbols, edges, resolution rate, parse errors, fallback use, size, and timings",
+	Long: "Reports what the index holds and how well it was built: files per language, symbols per kind, resolution\n" +
+		"edges per kind, the share of references that resolved, syntax errors and the files containing them, the\n" +
+		"files indexed by the fallback scanner and the symbols it found, the database size, and the time each phase\n" +
+		"of the latest 'canopy index' run took. Use --format json to track index quality over time.",
+	Args: cobra.NoArgs,
+	RunE: runStats,
+}
+
+func runStats(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("stats", err)
+	}
+	defer s.Close()
+
+	stats, err := queryBuilder(s).IndexStats()
+	if err != nil {
+		return outputError("stats", err)
+	}
+	result := CLIIndexStats{
+		Files:           stats.Files,
+		FilesByLanguage: stats.FilesByLanguage,
+		Symbols:         stats.Symbols,
+		SymbolsByKind:   stats.SymbolsByKind,
+		References:      stats.References,
+		Resolved:        stats.Resolved,
+		ResolutionRate:  stats.ResolutionRate,
+		EdgesByKind:     stats.EdgesByKind,
+		ParseErrors:     stats.ParseErrors,
+		FilesWithErrors: stats.FilesWithErrors,
+		DegradedFiles:   stats.DegradedFiles,
+		FallbackSymbols: stats.FallbackSymbols,
+		DatabaseBytes:   stats.DatabaseBytes,
+		Timings:         make([]CLIIndexTiming, len(stats.Timings)),
+	}
+	if !stats.IndexedAt.IsZero() {
+		result.IndexedAt = stats.IndexedAt.Format(time.RFC3339)
+	}
+	for i, t := range stats.Timings {
+		result.Timings[i] = CLIIndexTiming{Phase: t.Phase, Millis: durationMillis(t.Duration)}
+	}
+	return outputResult(CLIResult{Command: "stats", Results: result})
+}
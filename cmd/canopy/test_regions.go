@@ -0,0 +1,48 @@
+package main
+
+import (
+	"github.com/spf13/cobra"
+)
+
+var testRegionsCmd = &cobra.Command{
+	Use:   "test-regions",
+	Short: "List the test code that partitions the index from production code",
+	Long: "Lists the code only tests use: files under a tests directory and Go _test.go files, then, in\n" +
+		"other files, the outermost items marked #[cfg(test)] (such as mod tests) or as tests (#[test],\n" +
+		"#[tokio::test]). Everything else is production code. Other queries leave this code out unless\n" +
+		"--include-tests is given.",
+	Args: cobra.NoArgs,
+	RunE: runTestRegions,
+}
+
+func init() {
+	testRegionsCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runTestRegions(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("test-regions", err)
+	}
+	defer s.Close()
+
+	regions, err := queryBuilder(s).TestRegions(flagPathPrefix)
+	if err != nil {
+		return outputError("test-regions", err)
+	}
+
+	cliRegions := make([]CLITestRegion, len(regions))
+	for i, r := range regions {
+		cliRegions[i] = CLITestRegion{
+			Location: locationToCLI(r.Location, nil),
+			Reason:   r.Reason,
+			Symbol:   optionalSymbolToCLI(r.Symbol),
+		}
+	}
+	paged, totalCount := paginateSlice(cliRegions)
+	return outputResult(CLIResult{
+		Command:    "test-regions",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
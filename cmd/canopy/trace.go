@@ -0,0 +1,124 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/jward/canopy/scripts"
+	"github.com/spf13/cobra"
+)
+
+var traceCmd = &cobra.Command{
+	Use:   "trace <file>",
+	Short: "Load an execution trace to rank hot symbols",
+	Long: "Loads an execution trace in folded stack format (one 'frame;frame;frame count' line per stack), as written by\n" +
+		"inferno-collapse-perf or tracing-flame, and maps its frames to indexed functions and methods.\n" +
+		"The trace replaces any loaded before; 'canopy query hot' lists the symbols by samples. Use - to read stdin.",
+	Args: cobra.ExactArgs(1),
+	RunE: runTrace,
+}
+
+var hotCmd = &cobra.Command{
+	Use:   "hot",
+	Short: "List symbols by execution trace samples",
+	Long:  "Lists the symbols sampled in the trace loaded by 'canopy trace', by self samples (time in the symbol itself),\nthen total samples (time in the symbol and its callees).",
+	Args:  cobra.NoArgs,
+	RunE:  runHot,
+}
+
+func init() {
+	traceCmd.Flags().StringVar(&flagScriptsDir, "scripts-dir", "", "load scripts from disk path instead of embedded")
+
+	hotCmd.Flags().StringVar(&flagKind, "kind", "", kindFlagUsage)
+	hotCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+}
+
+func runTrace(cmd *cobra.Command, args []string) error {
+	var in io.Reader = os.Stdin
+	if args[0] != "-" {
+		f, err := os.Open(args[0])
+		if err != nil {
+			return outputError("trace", err)
+		}
+		defer f.Close()
+		in = f
+	}
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("trace", fmt.Errorf("getting cwd: %w", err))
+	}
+	dbPath, err := resolveDBPath(findRepoRoot(cwd))
+	if err != nil {
+		return outputError("trace", err)
+	}
+	if _, err := os.Stat(dbPath); os.IsNotExist(err) {
+		return outputError("trace", fmt.Errorf("database not found: %s (run 'canopy index' first)", dbPath))
+	}
+
+	var opts []canopy.Option
+	if flagScriptsDir == "" {
+		opts = append(opts, canopy.WithScriptsFS(scripts.FS))
+	}
+	engine, err := canopy.New(dbPath, flagScriptsDir, opts...)
+	if err != nil {
+		return outputError("trace", fmt.Errorf("creating engine: %w", err))
+	}
+	defer engine.Close()
+
+	summary, err := engine.IngestTrace(in)
+	if err != nil {
+		return outputError("trace", err)
+	}
+	return outputResult(CLIResult{
+		Command: "trace",
+		Results: CLITraceSummary{
+			Stacks:       summary.Stacks,
+			Samples:      summary.Samples,
+			Frames:       summary.Frames,
+			MappedFrames: summary.MappedFrames,
+			Symbols:      summary.Symbols,
+		},
+	})
+}
+
+func runHot(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("hot", err)
+	}
+	defer s.Close()
+
+	filter := canopy.SymbolFilter{}
+	if flagKind != "" {
+		filter.Kinds = []string{flagKind}
+	}
+	if flagPathPrefix != "" {
+		filter.PathPrefix = &flagPathPrefix
+	}
+
+	qb := queryBuilder(s)
+	result, err := qb.HotSymbols(filter, buildPagination())
+	if err != nil {
+		return outputError("hot", err)
+	}
+
+	cliHot := make([]CLIHotSymbol, len(result.Items))
+	for i, h := range result.Items {
+		cliHot[i] = CLIHotSymbol{
+			Symbol:       symbolResultToCLI(h.Symbol),
+			SelfSamples:  h.Samples.Self,
+			TotalSamples: h.Samples.Total,
+			SelfPercent:  h.SelfPercent,
+			TotalPercent: h.TotalPercent,
+		}
+	}
+
+	return outputResult(CLIResult{
+		Command:    "hot",
+		Results:    cliHot,
+		TotalCount: &result.TotalCount,
+	})
+}
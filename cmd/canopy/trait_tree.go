@@ -0,0 +1,97 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var traitTreeCmd = &cobra.Command{
+	Use:   "trait-tree [<trait>]",
+	Short: "Show a Rust trait's supertraits, subtraits, and impls",
+	Long: "Shows the supertraits a Rust trait requires (trait Shape: Debug + Clone, or where Self: Clone), transitively,\n" +
+		"the traits that require it, and its impls. Each impl is checked against the whole supertrait chain: its\n" +
+		"self type must implement every supertrait by an impl, a blanket impl, or a derive. Auto traits such as\n" +
+		"Send and Sized count as implemented. Every indexed trait named <trait> is shown; --symbol <id> selects one.",
+	Args: cobra.MaximumNArgs(1),
+	RunE: runTraitTree,
+}
+
+func init() {
+	traitTreeCmd.ValidArgsFunction = completeSymbolNames
+	traitTreeCmd.Flags().Int64("symbol", 0, "trait symbol ID to query")
+	traitTreeCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	traitTreeCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+func runTraitTree(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("trait-tree", err)
+	}
+	defer s.Close()
+
+	var ids []int64
+	if symID, _ := cmd.Flags().GetInt64("symbol"); symID != 0 {
+		ids = append(ids, symID)
+	} else {
+		if len(args) == 0 {
+			return outputError("trait-tree", fmt.Errorf("requires either a <trait> argument or --symbol flag"))
+		}
+		syms, err := s.SymbolsByName(args[0])
+		if err != nil {
+			return outputError("trait-tree", err)
+		}
+		for _, sym := range syms {
+			if sym.Kind == "trait" {
+				ids = append(ids, sym.ID)
+			}
+		}
+		if len(ids) == 0 {
+			return outputError("trait-tree", fmt.Errorf("no trait named %q", args[0]))
+		}
+	}
+
+	qb := queryBuilder(s)
+	var trees []CLITraitTree
+	for _, id := range ids {
+		tree, err := qb.TraitTree(id)
+		if err != nil {
+			return outputError("trait-tree", err)
+		}
+		if tree == nil {
+			return outputError("trait-tree", fmt.Errorf("symbol not found: %d", id))
+		}
+		cliTree := CLITraitTree{
+			Trait:       symbolResultToCLI(tree.Trait),
+			Supertraits: traitTreeNodesToCLI(tree.Supertraits),
+			Subtraits:   traitTreeNodesToCLI(tree.Subtraits),
+			Impls:       make([]CLITraitChainImpl, len(tree.Impls)),
+		}
+		for i, ci := range tree.Impls {
+			cliTree.Impls[i] = CLITraitChainImpl{Impl: traitImplToCLI(ci.Impl), Missing: ci.Missing, Satisfied: ci.Satisfied}
+		}
+		trees = append(trees, cliTree)
+	}
+	paged, totalCount := paginateSlice(trees)
+	return outputResult(CLIResult{
+		Command:    "trait-tree",
+		Results:    paged,
+		TotalCount: &totalCount,
+	})
+}
+
+// traitTreeNodesToCLI converts a supertrait or subtrait tree.
+func traitTreeNodesToCLI(nodes []*canopy.TraitTreeNode) []CLITraitTreeNode {
+	out := make([]CLITraitTreeNode, len(nodes))
+	for i, n := range nodes {
+		out[i] = CLITraitTreeNode{
+			Name:     n.Name,
+			Bound:    n.Bound,
+			Symbol:   optionalSymbolToCLI(n.Symbol),
+			Children: traitTreeNodesToCLI(n.Children),
+		}
+	}
+	return out
+}
@@ -0,0 +1,591 @@
+package main
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var tuiCmd = &cobra.Command{
+	Use:   "tui",
+	Short: "Explore the index in a terminal UI",
+	Long: "Opens a full-screen explorer over the index: the module tree of files and their items on the left, the\n" +
+		"selected symbol's details and source at the top right, and its callers, callees, or references below.\n" +
+		"Arrow keys move and expand the tree, Tab switches to the list below, and Enter there jumps to that symbol.\n" +
+		"c, e, and r list callers, callees, and references; b goes back; q quits.",
+	Args: cobra.NoArgs,
+	RunE: runTUI,
+}
+
+func runTUI(cmd *cobra.Command, args []string) error {
+	if !isTerminal(os.Stdin) || !isTerminal(os.Stdout) {
+		return outputError("tui", fmt.Errorf("tui needs a terminal"))
+	}
+	s, err := openStore()
+	if err != nil {
+		return outputError("tui", err)
+	}
+	defer s.Close()
+
+	cwd, err := os.Getwd()
+	if err != nil {
+		return outputError("tui", fmt.Errorf("getting cwd: %w", err))
+	}
+	m, err := newTUIModel(&tuiQueryData{qb: queryBuilder(s), root: findRepoRoot(cwd)})
+	if err != nil {
+		return outputError("tui", err)
+	}
+
+	restore, err := enterRawMode()
+	if err != nil {
+		return outputError("tui", fmt.Errorf("setting up the terminal: %w", err))
+	}
+	defer restore()
+	out := bufio.NewWriter(os.Stdout)
+	fmt.Fprint(out, "\x1b[?1049h\x1b[?25l")
+	defer func() {
+		fmt.Fprint(out, "\x1b[?25h\x1b[?1049l")
+		out.Flush()
+	}()
+
+	in := bufio.NewReader(os.Stdin)
+	for {
+		rows, cols := terminalSize()
+		fmt.Fprint(out, "\x1b[H")
+		for i, line := range m.render(cols, rows) {
+			if i > 0 {
+				fmt.Fprint(out, "\n")
+			}
+			fmt.Fprint(out, line, "\x1b[K")
+		}
+		if err := out.Flush(); err != nil {
+			return err
+		}
+		key, err := readTUIKey(in)
+		if err == io.EOF {
+			return nil
+		}
+		if err != nil {
+			return err
+		}
+		if !m.handleKey(key) {
+			return nil
+		}
+	}
+}
+
+// terminalSize returns the rows and columns of the terminal on stdin,
+// 24x80 if stty cannot tell.
+func terminalSize() (rows, cols int) {
+	out, err := stty("size")
+	if err == nil {
+		if f := strings.Fields(out); len(f) == 2 {
+			rows, _ = strconv.Atoi(f[0])
+			cols, _ = strconv.Atoi(f[1])
+		}
+	}
+	if rows <= 0 || cols <= 0 {
+		return 24, 80
+	}
+	return rows, cols
+}
+
+// readTUIKey reads one key press and names it: "up", "enter", "tab", or
+// the character typed.
+func readTUIKey(in *bufio.Reader) (string, error) {
+	r, _, err := in.ReadRune()
+	if err != nil {
+		return "", err
+	}
+	switch r {
+	case '\r', '\n':
+		return "enter", nil
+	case keyTab:
+		return "tab", nil
+	case keyBackspace, keyDelete:
+		return "backspace", nil
+	case keyCtrlC, keyCtrlD:
+		return "quit", nil
+	case keyEscape:
+		switch escapeSequence(in) {
+		case "[A", "OA":
+			return "up", nil
+		case "[B", "OB":
+			return "down", nil
+		case "[C", "OC":
+			return "right", nil
+		case "[D", "OD":
+			return "left", nil
+		case "[H", "OH", "[1~":
+			return "home", nil
+		case "[F", "OF", "[4~":
+			return "end", nil
+		case "[5~":
+			return "pgup", nil
+		case "[6~":
+			return "pgdown", nil
+		}
+		return "", nil
+	}
+	return string(r), nil
+}
+
+// tuiNavMode selects what the navigation pane lists.
+type tuiNavMode int
+
+const (
+	tuiCallers tuiNavMode = iota
+	tuiCallees
+	tuiReferences
+)
+
+func (m tuiNavMode) String() string {
+	switch m {
+	case tuiCallees:
+		return "Callees"
+	case tuiReferences:
+		return "References"
+	}
+	return "Callers"
+}
+
+// tuiNavItem is a row of the navigation pane.
+type tuiNavItem struct {
+	label    string
+	symbolID int64 // symbol Enter jumps to, 0 for none
+}
+
+// tuiData is what the explorer shows, read from the index.
+type tuiData interface {
+	outline() ([]canopy.OutlineFile, error)
+	detail(symbolID int64) ([]string, error)
+	neighbors(mode tuiNavMode, symbolID int64) ([]tuiNavItem, error)
+}
+
+// tuiQueryData reads the explorer's data with a QueryBuilder, showing paths
+// relative to root.
+type tuiQueryData struct {
+	qb   *canopy.QueryBuilder
+	root string
+}
+
+func (d *tuiQueryData) rel(path string) string {
+	if rel, err := filepath.Rel(d.root, path); err == nil && !strings.HasPrefix(rel, "..") {
+		return filepath.ToSlash(rel)
+	}
+	return path
+}
+
+func (d *tuiQueryData) outline() ([]canopy.OutlineFile, error) {
+	files, err := d.qb.Outline("")
+	for i := range files {
+		files[i].File = d.rel(files[i].File)
+	}
+	return files, err
+}
+
+func (d *tuiQueryData) detail(symbolID int64) ([]string, error) {
+	snip, err := d.qb.Snippet(symbolID, canopy.SnippetContext{})
+	if err != nil {
+		return nil, err
+	}
+	if snip == nil {
+		return nil, fmt.Errorf("symbol not found: %d", symbolID)
+	}
+	sym := snip.Symbol
+	lines := []string{
+		strings.TrimSpace(sym.Visibility + " " + sym.Kind + " " + sym.Name),
+		fmt.Sprintf("%s:%d", snip.File, sym.StartLine+1),
+		fmt.Sprintf("%d references, %d from other files", sym.RefCount, sym.ExternalRefCount),
+		"",
+	}
+	return append(lines, strings.Split(snip.Source, "\n")...), nil
+}
+
+func (d *tuiQueryData) neighbors(mode tuiNavMode, symbolID int64) ([]tuiNavItem, error) {
+	var items []tuiNavItem
+	if mode == tuiReferences {
+		locs, err := d.qb.ReferencesTo(symbolID)
+		if err != nil {
+			return nil, err
+		}
+		for _, loc := range locs {
+			item := tuiNavItem{label: fmt.Sprintf("%s:%d", d.rel(loc.File), loc.StartLine+1)}
+			if sym, err := d.qb.SymbolAt(loc.File, loc.StartLine, loc.StartCol); err == nil && sym != nil && sym.ID != symbolID {
+				item.label += "  in " + sym.Name
+				item.symbolID = sym.ID
+			}
+			items = append(items, item)
+		}
+		return items, nil
+	}
+
+	var g *canopy.CallGraph
+	var err error
+	if mode == tuiCallers {
+		g, err = d.qb.TransitiveCallers(symbolID, 1)
+	} else {
+		g, err = d.qb.TransitiveCallees(symbolID, 1)
+	}
+	if err != nil {
+		return nil, err
+	}
+	for _, n := range g.Nodes {
+		if n.Depth == 0 {
+			continue
+		}
+		items = append(items, tuiNavItem{
+			label:    fmt.Sprintf("%s %s  %s:%d", n.Symbol.Kind, n.Symbol.Name, d.rel(n.Symbol.FilePath), n.Symbol.StartLine+1),
+			symbolID: n.Symbol.ID,
+		})
+	}
+	return items, nil
+}
+
+// tuiNode is a row of the module tree: a file or an item of its outline.
+type tuiNode struct {
+	label    string
+	symbolID int64 // 0 for files and impl blocks
+	depth    int
+	parent   *tuiNode
+	children []*tuiNode
+	expanded bool
+}
+
+// tuiModel is the explorer's state. Keys update it and render draws it, so
+// it runs without a terminal in tests.
+type tuiModel struct {
+	data tuiData
+
+	roots  []*tuiNode
+	byID   map[int64]*tuiNode
+	rows   []*tuiNode // the visible tree rows
+	cursor int
+	top    int
+
+	navFocused bool
+	mode       tuiNavMode
+	symbol     int64 // symbol in the detail and navigation panes, 0 for none
+	detail     []string
+	nav        []tuiNavItem
+	navCursor  int
+	navTop     int
+	back       []int64 // symbols shown before each jump
+
+	status string // the last error
+}
+
+func newTUIModel(data tuiData) (*tuiModel, error) {
+	files, err := data.outline()
+	if err != nil {
+		return nil, err
+	}
+	m := &tuiModel{data: data, byID: map[int64]*tuiNode{}}
+	var add func(parent *tuiNode, items []*canopy.OutlineNode)
+	add = func(parent *tuiNode, items []*canopy.OutlineNode) {
+		for _, it := range items {
+			n := &tuiNode{label: strings.TrimSpace(it.Kind + " " + it.Name), depth: parent.depth + 1, parent: parent}
+			if it.SymbolID != nil {
+				n.symbolID = *it.SymbolID
+				m.byID[n.symbolID] = n
+			} else {
+				n.label = it.Name
+			}
+			parent.children = append(parent.children, n)
+			add(n, it.Children)
+		}
+	}
+	for _, f := range files {
+		n := &tuiNode{label: f.File}
+		add(n, f.Items)
+		m.roots = append(m.roots, n)
+	}
+	m.flatten()
+	m.selectRow()
+	return m, nil
+}
+
+// flatten rebuilds the visible tree rows from the expanded nodes.
+func (m *tuiModel) flatten() {
+	m.rows = m.rows[:0]
+	var walk func(nodes []*tuiNode)
+	walk = func(nodes []*tuiNode) {
+		for _, n := range nodes {
+			m.rows = append(m.rows, n)
+			if n.expanded {
+				walk(n.children)
+			}
+		}
+	}
+	walk(m.roots)
+}
+
+// selectRow shows the symbol of the tree row under the cursor.
+func (m *tuiModel) selectRow() {
+	if len(m.rows) == 0 {
+		return
+	}
+	n := m.rows[m.cursor]
+	if n.symbolID == 0 {
+		m.symbol, m.detail, m.nav = 0, []string{n.label}, nil
+		return
+	}
+	m.show(n.symbolID)
+}
+
+// show loads the detail and navigation panes for a symbol.
+func (m *tuiModel) show(symbolID int64) {
+	m.symbol = symbolID
+	m.status = ""
+	detail, err := m.data.detail(symbolID)
+	if err != nil {
+		m.status = err.Error()
+	}
+	m.detail = detail
+	m.loadNav()
+}
+
+func (m *tuiModel) loadNav() {
+	m.nav, m.navCursor, m.navTop = nil, 0, 0
+	if m.symbol == 0 {
+		return
+	}
+	nav, err := m.data.neighbors(m.mode, m.symbol)
+	if err != nil {
+		m.status = err.Error()
+	}
+	m.nav = nav
+}
+
+// jump selects a symbol, expanding the tree down to it.
+func (m *tuiModel) jump(symbolID int64) {
+	n := m.byID[symbolID]
+	if n == nil {
+		m.show(symbolID)
+		return
+	}
+	for p := n.parent; p != nil; p = p.parent {
+		p.expanded = true
+	}
+	m.flatten()
+	for i, r := range m.rows {
+		if r == n {
+			m.cursor = i
+		}
+	}
+	m.show(symbolID)
+}
+
+// handleKey applies a key press, reporting false when the explorer should
+// quit.
+func (m *tuiModel) handleKey(key string) bool {
+	switch key {
+	case "q", "quit":
+		return false
+	case "tab":
+		m.navFocused = !m.navFocused
+		return true
+	case "c", "e", "r":
+		m.mode = map[string]tuiNavMode{"c": tuiCallers, "e": tuiCallees, "r": tuiReferences}[key]
+		m.loadNav()
+		return true
+	case "b", "backspace":
+		if n := len(m.back); n > 0 {
+			id := m.back[n-1]
+			m.back = m.back[:n-1]
+			m.jump(id)
+		}
+		return true
+	}
+	if m.navFocused {
+		m.handleNavKey(key)
+	} else {
+		m.handleTreeKey(key)
+	}
+	return true
+}
+
+func (m *tuiModel) handleTreeKey(key string) {
+	if len(m.rows) == 0 {
+		return
+	}
+	n := m.rows[m.cursor]
+	cursor := m.cursor
+	switch key {
+	case "up", "k":
+		cursor--
+	case "down", "j":
+		cursor++
+	case "pgup":
+		cursor -= 10
+	case "pgdown":
+		cursor += 10
+	case "home":
+		cursor = 0
+	case "end":
+		cursor = len(m.rows) - 1
+	case "right", "l":
+		if len(n.children) > 0 && !n.expanded {
+			n.expanded = true
+			m.flatten()
+		} else if n.expanded {
+			cursor++
+		}
+	case "left", "h":
+		if n.expanded {
+			n.expanded = false
+			m.flatten()
+		} else if n.parent != nil {
+			for i, r := range m.rows {
+				if r == n.parent {
+					cursor = i
+				}
+			}
+		}
+	case "enter", " ":
+		if len(n.children) > 0 {
+			n.expanded = !n.expanded
+			m.flatten()
+		}
+	}
+	cursor = max(0, min(cursor, len(m.rows)-1))
+	if cursor != m.cursor {
+		m.cursor = cursor
+		m.selectRow()
+	}
+}
+
+func (m *tuiModel) handleNavKey(key string) {
+	switch key {
+	case "up", "k":
+		m.navCursor--
+	case "down", "j":
+		m.navCursor++
+	case "pgup":
+		m.navCursor -= 10
+	case "pgdown":
+		m.navCursor += 10
+	case "home":
+		m.navCursor = 0
+	case "end":
+		m.navCursor = len(m.nav) - 1
+	case "enter", "right", "l":
+		if m.navCursor < len(m.nav) {
+			if id := m.nav[m.navCursor].symbolID; id != 0 {
+				if m.symbol != 0 {
+					m.back = append(m.back, m.symbol)
+				}
+				m.jump(id)
+			}
+		}
+		return
+	}
+	m.navCursor = max(0, min(m.navCursor, len(m.nav)-1))
+}
+
+// render draws the explorer as width x height lines: a title, the tree on
+// the left, the detail and navigation panes on the right, and a key help or
+// error line.
+func (m *tuiModel) render(width, height int) []string {
+	body := max(height-2, 2)
+	treeWidth := max(min(width*2/5, width-21), 10)
+	rightWidth := max(width-treeWidth-1, 10)
+	detailRows := body / 2
+	navRows := body - detailRows - 1
+
+	m.top = scrollTop(m.cursor, m.top, body)
+	m.navTop = scrollTop(m.navCursor, m.navTop, navRows)
+
+	title := " canopy"
+	if m.symbol != 0 && len(m.detail) > 0 {
+		title += "  " + m.detail[0]
+	}
+	lines := []string{"\x1b[7m" + fitWidth(title, width) + "\x1b[0m"}
+	for i := range body {
+		var left string
+		if r := m.top + i; r < len(m.rows) {
+			n := m.rows[r]
+			marker := "  "
+			if len(n.children) > 0 {
+				marker = "+ "
+				if n.expanded {
+					marker = "- "
+				}
+			}
+			left = fitWidth(strings.Repeat("  ", n.depth)+marker+n.label, treeWidth)
+			if r == m.cursor {
+				left = highlight(left, !m.navFocused)
+			}
+		} else {
+			left = fitWidth("", treeWidth)
+		}
+
+		var right string
+		switch {
+		case i < detailRows:
+			if i < len(m.detail) {
+				right = m.detail[i]
+			}
+			right = fitWidth(right, rightWidth)
+		case i == detailRows:
+			header := fmt.Sprintf("── %s (%d) ", m.mode, len(m.nav))
+			right = fitWidth(header+strings.Repeat("─", rightWidth), rightWidth)
+		default:
+			r := m.navTop + i - detailRows - 1
+			if r < len(m.nav) {
+				right = fitWidth(m.nav[r].label, rightWidth)
+				if r == m.navCursor {
+					right = highlight(right, m.navFocused)
+				}
+			} else {
+				right = fitWidth("", rightWidth)
+			}
+		}
+		lines = append(lines, left+"│"+right)
+	}
+
+	footer := "arrows move  Enter expand/jump  Tab switch pane  c callers  e callees  r references  b back  q quit"
+	if m.status != "" {
+		footer = "error: " + m.status
+	}
+	return append(lines, fitWidth(footer, width))
+}
+
+// scrollTop returns the first visible row of a list of rows lines that keeps
+// cursor in view, moving top as little as possible.
+func scrollTop(cursor, top, rows int) int {
+	switch {
+	case cursor < top:
+		return cursor
+	case cursor >= top+rows:
+		return cursor - rows + 1
+	}
+	return top
+}
+
+// fitWidth pads or truncates s to width columns, expanding tabs.
+func fitWidth(s string, width int) string {
+	s = strings.ReplaceAll(s, "\t", "    ")
+	if n := utf8.RuneCountInString(s); n <= width {
+		return s + strings.Repeat(" ", width-n)
+	}
+	r := []rune(s)
+	return string(r[:width-1]) + "…"
+}
+
+// highlight marks a selected row: reversed in the focused pane, bold in the
+// other.
+func highlight(s string, focused bool) string {
+	if focused {
+		return "\x1b[7m" + s + "\x1b[0m"
+	}
+	return "\x1b[1m" + s + "\x1b[0m"
+}
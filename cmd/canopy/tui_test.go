@@ -0,0 +1,101 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+	"testing"
+
+	"github.com/jward/canopy"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// fakeTUIData is a file with a struct, an impl block with a method, and two
+// functions; run calls report, which calls area.
+type fakeTUIData struct{}
+
+func (fakeTUIData) outline() ([]canopy.OutlineFile, error) {
+	id := func(n int64) *int64 { return &n }
+	return []canopy.OutlineFile{{
+		File:     "src/lib.rs",
+		Language: "rust",
+		Items: []*canopy.OutlineNode{
+			{SymbolID: id(1), Name: "Circle", Kind: "struct"},
+			{Name: "impl Circle", Kind: canopy.OutlineKindImpl, Children: []*canopy.OutlineNode{
+				{SymbolID: id(2), Name: "area", Kind: "method"},
+			}},
+			{SymbolID: id(3), Name: "report", Kind: "function"},
+			{SymbolID: id(4), Name: "run", Kind: "function"},
+		},
+	}}, nil
+}
+
+func (fakeTUIData) detail(symbolID int64) ([]string, error) {
+	return []string{fmt.Sprintf("symbol %d", symbolID)}, nil
+}
+
+func (fakeTUIData) neighbors(mode tuiNavMode, symbolID int64) ([]tuiNavItem, error) {
+	edges := map[tuiNavMode]map[int64]int64{
+		tuiCallers: {2: 3, 3: 4},
+		tuiCallees: {3: 2, 4: 3},
+	}
+	if to, ok := edges[mode][symbolID]; ok {
+		return []tuiNavItem{{label: fmt.Sprintf("to %d", to), symbolID: to}}, nil
+	}
+	return nil, nil
+}
+
+func TestTUIModel(t *testing.T) {
+	t.Parallel()
+	m, err := newTUIModel(fakeTUIData{})
+	require.NoError(t, err)
+	require.Len(t, m.rows, 1, "files start collapsed")
+	assert.Equal(t, int64(0), m.symbol)
+
+	m.handleKey("right")
+	require.Len(t, m.rows, 5)
+	assert.Equal(t, "impl Circle", m.rows[2].label)
+
+	m.handleKey("down")
+	m.handleKey("down")
+	m.handleKey("down")
+	assert.Equal(t, "function report", m.rows[m.cursor].label)
+	assert.Equal(t, int64(3), m.symbol)
+	assert.Equal(t, []string{"symbol 3"}, m.detail)
+	assert.Equal(t, []tuiNavItem{{label: "to 4", symbolID: 4}}, m.nav, "callers by default")
+
+	m.handleKey("tab")
+	m.handleKey("enter")
+	assert.Equal(t, int64(4), m.symbol, "Enter jumps to the caller")
+	assert.Equal(t, "function run", m.rows[m.cursor].label)
+
+	m.handleKey("b")
+	assert.Equal(t, int64(3), m.symbol, "b goes back")
+
+	m.handleKey("e")
+	assert.Equal(t, []tuiNavItem{{label: "to 2", symbolID: 2}}, m.nav)
+	m.handleKey("enter")
+	assert.Equal(t, "method area", m.rows[m.cursor].label, "jumping expands the impl block")
+	assert.Len(t, m.rows, 6)
+
+	m.handleKey("tab")
+	m.handleKey("left")
+	assert.Equal(t, "impl Circle", m.rows[m.cursor].label, "left moves to the parent")
+	m.handleKey("left")
+	assert.Len(t, m.rows, 5, "left collapses")
+
+	screen := m.render(60, 10)
+	require.Len(t, screen, 10)
+	assert.Contains(t, screen[0], "canopy")
+	assert.Contains(t, strings.Join(screen, "\n"), "── Callees (0) ")
+	assert.Contains(t, screen[3], "\x1b[7m  + impl Circle", "the selected row is highlighted")
+
+	assert.False(t, m.handleKey("q"))
+}
+
+func TestFitWidth(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, "ab  ", fitWidth("ab", 4))
+	assert.Equal(t, "abc…", fitWidth("abcdef", 4))
+	assert.Equal(t, "    x", fitWidth("\tx", 5))
+}
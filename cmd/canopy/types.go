@@ -8,11 +8,15 @@ type CLIResult struct {
 	Error      string `json:"error,omitempty"`
 }
 
+// CLIBatchResults maps the ID of each 'canopy batch' entry to its result.
+type CLIBatchResults map[string]CLIResult
+
 // CLISymbol is a JSON-friendly symbol representation.
 type CLISymbol struct {
 	ID               int64    `json:"id"`
 	Name             string   `json:"name"`
 	Kind             string   `json:"kind"`
+	Subkind          string   `json:"subkind,omitempty"`
 	Visibility       string   `json:"visibility"`
 	Modifiers        []string `json:"modifiers,omitempty"`
 	File             string   `json:"file,omitempty"`
@@ -20,6 +24,12 @@ type CLISymbol struct {
 	StartCol         int      `json:"start_col"`
 	EndLine          int      `json:"end_line"`
 	EndCol           int      `json:"end_col"`
+	StartByte        int      `json:"start_byte"`
+	EndByte          int      `json:"end_byte"`
+	UTF16StartCol    int      `json:"utf16_start_col"`
+	UTF16EndCol      int      `json:"utf16_end_col"`
+	PageRank         float64  `json:"pagerank"`
+	Betweenness      float64  `json:"betweenness"`
 	RefCount         int      `json:"ref_count"`
 	ExternalRefCount int      `json:"external_ref_count"`
 	InternalRefCount int      `json:"internal_ref_count"`
@@ -27,12 +37,21 @@ type CLISymbol struct {
 
 // CLILocation extends Location with the symbol ID for chaining.
 type CLILocation struct {
-	File      string `json:"file"`
-	StartLine int    `json:"start_line"`
-	StartCol  int    `json:"start_col"`
-	EndLine   int    `json:"end_line"`
-	EndCol    int    `json:"end_col"`
-	SymbolID  *int64 `json:"symbol_id,omitempty"`
+	File          string `json:"file"`
+	StartLine     int    `json:"start_line"`
+	StartCol      int    `json:"start_col"`
+	EndLine       int    `json:"end_line"`
+	EndCol        int    `json:"end_col"`
+	StartByte     int    `json:"start_byte"`
+	EndByte       int    `json:"end_byte"`
+	UTF16StartCol int    `json:"utf16_start_col"`
+	UTF16EndCol   int    `json:"utf16_end_col"`
+	SymbolID      *int64 `json:"symbol_id,omitempty"`
+	Kind          string `json:"kind,omitempty"`       // reference kind, for references results
+	Confidence    string `json:"confidence,omitempty"` // resolution confidence level, for references results
+
+	// Explanation says why the location matched, with --explain.
+	Explanation *CLIExplanation `json:"explanation,omitempty"`
 }
 
 // CLICallEdge is a JSON-friendly call graph edge.
@@ -44,6 +63,45 @@ type CLICallEdge struct {
 	File       string `json:"file,omitempty"`
 	Line       int    `json:"line"`
 	Col        int    `json:"col"`
+	Confidence string `json:"confidence"`
+	Awaited    bool   `json:"awaited,omitempty"`
+	Kind       string `json:"kind,omitempty"`
+	Propagated bool   `json:"propagated,omitempty"`
+
+	// Explanation explains the references at the call site that resolved
+	// to the callee, with --explain.
+	Explanation []CLIExplanation `json:"explanation,omitempty"`
+}
+
+// CLIExplanation is a JSON-friendly canopy.Explanation: why a reference
+// resolved to a result. Steps is the same explanation as readable sentences.
+type CLIExplanation struct {
+	ReferenceID    int64               `json:"reference_id"`
+	Name           string              `json:"name"`
+	Kind           string              `json:"kind,omitempty"`
+	TargetID       int64               `json:"target_id"`
+	ResolutionKind string              `json:"resolution_kind,omitempty"`
+	Confidence     string              `json:"confidence"`
+	Import         *CLIExplainedImport `json:"import,omitempty"`
+	Scopes         []CLIExplainedScope `json:"scopes,omitempty"`
+	Steps          []string            `json:"steps"`
+}
+
+// CLIExplainedImport is the import a reference was resolved through.
+type CLIExplainedImport struct {
+	Source       string `json:"source"`
+	ImportedName string `json:"imported_name,omitempty"`
+	LocalAlias   string `json:"local_alias,omitempty"`
+}
+
+// CLIExplainedScope is a scope searched for a reference's name, innermost
+// first.
+type CLIExplainedScope struct {
+	Kind       string `json:"kind"`
+	SymbolID   *int64 `json:"symbol_id,omitempty"`
+	SymbolName string `json:"symbol_name,omitempty"`
+	StartLine  int    `json:"start_line"`
+	EndLine    int    `json:"end_line"`
 }
 
 // CLIImport is a JSON-friendly import representation.
@@ -62,6 +120,14 @@ type CLIFile struct {
 	Path      string `json:"path"`
 	Language  string `json:"language"`
 	LineCount int    `json:"line_count"`
+	Root      string `json:"root,omitempty"`
+}
+
+// CLIRoot is an indexed root directory.
+type CLIRoot struct {
+	Root      string `json:"root"`
+	FileCount int    `json:"file_count"`
+	LineCount int    `json:"line_count"`
 }
 
 // CLILanguageStats is a JSON-friendly language stats representation.
@@ -86,6 +152,7 @@ type CLISymbolDetail struct {
 	Parameters  []CLIFunctionParam `json:"parameters"`
 	Members     []CLITypeMember    `json:"members"`
 	TypeParams  []CLITypeParam     `json:"type_params"`
+	TypeBounds  []CLITypeBound     `json:"type_bounds"`
 	Annotations []CLIAnnotation    `json:"annotations"`
 }
 
@@ -111,7 +178,15 @@ type CLITypeMember struct {
 type CLITypeParam struct {
 	Name        string `json:"name"`
 	Ordinal     int    `json:"ordinal"`
-	Constraints string `json:"constraints,omitempty"`
+	Kind        string `json:"kind,omitempty"`        // "type", "lifetime", or "const"
+	Constraints string `json:"constraints,omitempty"` // bounds; a const parameter's type
+}
+
+// CLITypeBound is a JSON-friendly generic bound.
+type CLITypeBound struct {
+	Subject       string `json:"subject"`
+	Bound         string `json:"bound"`
+	InWhereClause bool   `json:"in_where_clause,omitempty"`
 }
 
 // CLIAnnotation is a JSON-friendly annotation.
@@ -189,11 +264,31 @@ type CLICallGraphNode struct {
 
 // CLICallGraphEdge is an edge in a transitive call graph.
 type CLICallGraphEdge struct {
-	CallerID int64  `json:"caller_id"`
-	CalleeID int64  `json:"callee_id"`
-	File     string `json:"file,omitempty"`
-	Line     int    `json:"line"`
-	Col      int    `json:"col"`
+	CallerID   int64  `json:"caller_id"`
+	CalleeID   int64  `json:"callee_id"`
+	File       string `json:"file,omitempty"`
+	Line       int    `json:"line"`
+	Col        int    `json:"col"`
+	Confidence string `json:"confidence"`
+	Awaited    bool   `json:"awaited,omitempty"`
+	Kind       string `json:"kind,omitempty"`
+	Propagated bool   `json:"propagated,omitempty"`
+}
+
+// CLIBlockingCall is a blocking call reachable from an async function.
+type CLIBlockingCall struct {
+	AsyncFn CLISymbol          `json:"async_fn"`
+	API     string             `json:"api"`
+	Path    []CLICallGraphEdge `json:"path"`
+}
+
+// CLIPanicSite is a construct that can panic, reachable from a queried function.
+type CLIPanicSite struct {
+	Kind     string             `json:"kind"`
+	Name     string             `json:"name"`
+	Location CLILocation        `json:"location"`
+	Function CLISymbol          `json:"function"`
+	Path     []CLICallGraphEdge `json:"path"`
 }
 
 // CLIDependencyGraph is a JSON-friendly package dependency graph.
@@ -221,9 +316,1067 @@ type CLICycle struct {
 	Packages []string `json:"packages"`
 }
 
+// CLIPackageMetrics is a package with its coupling metrics. Violations lists
+// exceeded thresholds when run with --check.
+type CLIPackageMetrics struct {
+	Package     string   `json:"package"`
+	Afferent    int      `json:"afferent"`
+	Efferent    int      `json:"efferent"`
+	Instability float64  `json:"instability"`
+	FanIn       int      `json:"fan_in"`
+	FanOut      int      `json:"fan_out"`
+	Violations  []string `json:"violations,omitempty"`
+}
+
 // CLIHotspot is a heavily-referenced symbol with fan-in/fan-out metrics.
 type CLIHotspot struct {
 	Symbol      CLISymbol `json:"symbol"`
 	CallerCount int       `json:"caller_count"`
 	CalleeCount int       `json:"callee_count"`
 }
+
+// CLIClonePair is a pair of functions with structurally similar bodies.
+type CLIClonePair struct {
+	A          CLISymbol `json:"a"`
+	B          CLISymbol `json:"b"`
+	Similarity float64   `json:"similarity"`
+	Common     int       `json:"common_statements"`
+	Statements [2]int    `json:"statements"`
+}
+
+// CLICrateDuplicate is a pair of same-named Rust items in different crates
+// with nearly identical signatures.
+type CLICrateDuplicate struct {
+	A          CLISymbol `json:"a"`
+	B          CLISymbol `json:"b"`
+	CrateA     string    `json:"crate_a"`
+	CrateB     string    `json:"crate_b"`
+	SignatureA string    `json:"signature_a"`
+	SignatureB string    `json:"signature_b"`
+	Similarity float64   `json:"similarity"`
+}
+
+// CLIDeprecatedUsage is a reference to a deprecated symbol.
+type CLIDeprecatedUsage struct {
+	Symbol   CLISymbol   `json:"symbol"`
+	Note     string      `json:"note,omitempty"`
+	Location CLILocation `json:"location"`
+	Context  string      `json:"context,omitempty"`
+}
+
+// CLIFFIBoundary is a symbol that crosses the FFI boundary.
+type CLIFFIBoundary struct {
+	Symbol    CLISymbol `json:"symbol"`
+	Direction string    `json:"direction"`
+	ABI       string    `json:"abi,omitempty"`
+	LinkName  string    `json:"link_name,omitempty"`
+}
+
+// CLIErrorFlow is a function with the error types that can surface from it.
+type CLIErrorFlow struct {
+	Function   CLISymbol `json:"function"`
+	Declared   string    `json:"declared,omitempty"`
+	ErrorTypes []string  `json:"error_types"`
+}
+
+// CLIFeatureAPI is the public API a Cargo feature gates.
+type CLIFeatureAPI struct {
+	Feature string                `json:"feature"`
+	Items   []CLIFeatureGatedItem `json:"items"`
+}
+
+// CLIFeatureGatedItem is a public item with the cfg condition gating it.
+type CLIFeatureGatedItem struct {
+	Symbol    CLISymbol `json:"symbol"`
+	Condition string    `json:"condition"`
+}
+
+// CLIComplexity is a function or method with its complexity metrics.
+type CLIComplexity struct {
+	Symbol     CLISymbol `json:"symbol"`
+	Cyclomatic int       `json:"cyclomatic"`
+	Cognitive  int       `json:"cognitive"`
+}
+
+// CLIOwners lists the CODEOWNERS owners of a file (or of a symbol's file).
+type CLIOwners struct {
+	File     string   `json:"file"`
+	SymbolID *int64   `json:"symbol_id,omitempty"`
+	Owners   []string `json:"owners"`
+}
+
+// CLIOwnerImpact counts one owner's references to and callers of a symbol.
+// Owner is empty for unowned files.
+type CLIOwnerImpact struct {
+	Owner      string `json:"owner"`
+	References int    `json:"references"`
+	Callers    int    `json:"callers"`
+	Files      int    `json:"files"`
+}
+
+// CLIBuildTargets lists the Bazel or Buck targets that have a file (or a
+// symbol's file) as a source.
+type CLIBuildTargets struct {
+	File     string   `json:"file"`
+	SymbolID *int64   `json:"symbol_id,omitempty"`
+	Targets  []string `json:"targets"`
+}
+
+// CLITargetImpact is a build target affected by a change to a symbol. Via is
+// set for targets affected through a dependency.
+type CLITargetImpact struct {
+	Label  string `json:"label"`
+	Kind   string `json:"kind"`
+	Reason string `json:"reason"`
+	Via    string `json:"via,omitempty"`
+	Depth  int    `json:"depth"`
+}
+
+// CLISnapshotInfo is a named snapshot of the index.
+type CLISnapshotInfo struct {
+	Name    string `json:"name"`
+	Path    string `json:"path"`
+	Bytes   int64  `json:"bytes"`
+	Created string `json:"created"`
+}
+
+// CLIGraphSummary counts the parts of one index compared by snapshot diff.
+type CLIGraphSummary struct {
+	Files        int `json:"files"`
+	Symbols      int `json:"symbols"`
+	Packages     int `json:"packages"`
+	Dependencies int `json:"dependencies"`
+	Cycles       int `json:"cycles"`
+	PublicAPI    int `json:"public_api"`
+}
+
+// CLIAPISymbol is a public symbol, qualified by its parent.
+type CLIAPISymbol struct {
+	File string `json:"file"`
+	Name string `json:"name"`
+	Kind string `json:"kind"`
+}
+
+// CLIGraphDiff is how the architecture of head differs from base.
+type CLIGraphDiff struct {
+	Base                string              `json:"base"`
+	Head                string              `json:"head"`
+	BaseSummary         CLIGraphSummary     `json:"base_summary"`
+	HeadSummary         CLIGraphSummary     `json:"head_summary"`
+	AddedDependencies   []CLIDependencyEdge `json:"added_dependencies"`
+	RemovedDependencies []CLIDependencyEdge `json:"removed_dependencies"`
+	NewCycles           [][]string          `json:"new_cycles"`
+	BrokenCycles        [][]string          `json:"broken_cycles"`
+	AddedAPI            []CLIAPISymbol      `json:"added_api"`
+	RemovedAPI          []CLIAPISymbol      `json:"removed_api"`
+}
+
+// CLISymbolHistory is the git blame summary of a symbol's lines.
+type CLISymbolHistory struct {
+	Commit      string `json:"commit"`
+	Author      string `json:"author"`
+	AuthorEmail string `json:"author_email,omitempty"`
+	Date        string `json:"date"`
+	Authors     int    `json:"authors"`
+}
+
+// CLISQLTableUse is a function whose embedded SQL names a table.
+type CLISQLTableUse struct {
+	Function CLISymbol     `json:"function"`
+	Columns  []string      `json:"columns"`
+	Sites    []CLILocation `json:"sites"`
+}
+
+// CLIStaleSymbol is a symbol not modified since the --before cutoff.
+type CLIStaleSymbol struct {
+	Symbol  CLISymbol        `json:"symbol"`
+	History CLISymbolHistory `json:"history"`
+}
+
+// CLIAuthorLines is the number of a file's lines last modified by an author.
+type CLIAuthorLines struct {
+	Author string `json:"author"`
+	Lines  int    `json:"lines"`
+}
+
+// CLIBusFactor is the authorship concentration of one file.
+type CLIBusFactor struct {
+	File       string           `json:"file"`
+	BusFactor  int              `json:"bus_factor"`
+	TotalLines int              `json:"total_lines"`
+	Authors    []CLIAuthorLines `json:"authors"`
+}
+
+// CLITraceSummary describes an execution trace loaded by 'canopy trace'.
+type CLITraceSummary struct {
+	Stacks       int   `json:"stacks"`
+	Samples      int64 `json:"samples"`
+	Frames       int   `json:"frames"`
+	MappedFrames int   `json:"mapped_frames"`
+	Symbols      int   `json:"symbols"`
+}
+
+// CLIHotSymbol is a symbol with its execution trace samples.
+type CLIHotSymbol struct {
+	Symbol       CLISymbol `json:"symbol"`
+	SelfSamples  int64     `json:"self_samples"`
+	TotalSamples int64     `json:"total_samples"`
+	SelfPercent  float64   `json:"self_percent"`
+	TotalPercent float64   `json:"total_percent"`
+}
+
+// CLICoverageSummary describes a coverage report loaded by 'canopy coverage'.
+type CLICoverageSummary struct {
+	Files        int `json:"files"`
+	MappedFiles  int `json:"mapped_files"`
+	Lines        int `json:"lines"`
+	CoveredLines int `json:"covered_lines"`
+	Symbols      int `json:"symbols"`
+}
+
+// CLIEmbeddingSummary describes a run of 'canopy embed'.
+type CLIEmbeddingSummary struct {
+	Model      string `json:"model"`
+	Symbols    int    `json:"symbols"`
+	Embedded   int    `json:"embedded"`
+	Reused     int    `json:"reused"`
+	Dimensions int    `json:"dimensions"`
+}
+
+// CLISemanticHit is a symbol found by 'canopy search', with its callers
+// and callees that also match the query.
+type CLISemanticHit struct {
+	Symbol     CLISymbol   `json:"symbol"`
+	Similarity float64     `json:"similarity"`
+	Score      float64     `json:"score"`
+	Related    []CLISymbol `json:"related"`
+}
+
+// CLISnippetNeighbor is a caller or callee of a snippet's symbol.
+type CLISnippetNeighbor struct {
+	Symbol    CLISymbol `json:"symbol"`
+	File      string    `json:"file"`
+	Signature string    `json:"signature"`
+	Depth     int       `json:"depth"`
+}
+
+// CLISnippet is a symbol's source with its callers' and callees'
+// signatures, and Text, the whole rendered as Markdown.
+type CLISnippet struct {
+	Symbol    CLISymbol            `json:"symbol"`
+	File      string               `json:"file"`
+	Language  string               `json:"language"`
+	StartLine int                  `json:"start_line"`
+	Source    string               `json:"source"`
+	Callers   []CLISnippetNeighbor `json:"callers"`
+	Callees   []CLISnippetNeighbor `json:"callees"`
+	Text      string               `json:"text"`
+}
+
+// CLIUsageExamples is a symbol with real uses of it, and Text, the whole
+// rendered as Markdown.
+type CLIUsageExamples struct {
+	Symbol   CLISymbol         `json:"symbol"`
+	Examples []CLIUsageExample `json:"examples"`
+	Text     string            `json:"text"`
+}
+
+// CLIAnswer is the answer to a question asked with ask: the symbol it is
+// about, the results of each query run, and Text, the whole rendered as
+// Markdown. Symbol is nil if no indexed symbol matched.
+type CLIAnswer struct {
+	Question string             `json:"question"`
+	Intent   string             `json:"intent"`
+	Subject  string             `json:"subject,omitempty"`
+	Symbol   *CLISymbolDetail   `json:"symbol,omitempty"`
+	Others   []CLISymbol        `json:"others,omitempty"`
+	Queries  []string           `json:"queries"`
+	Callees  []CLICallGraphNode `json:"callees,omitempty"`
+	Types    []CLISymbol        `json:"types,omitempty"`
+	Callers  []CLICallGraphNode `json:"callers,omitempty"`
+	Tests    []CLICallGraphNode `json:"tests,omitempty"`
+	Text     string             `json:"text"`
+}
+
+// CLIStructuralMatch is code matching a structural search pattern, with
+// the text its metavariables bound and, with a rewrite, its replacement.
+type CLIStructuralMatch struct {
+	Location    CLILocation       `json:"location"`
+	Language    string            `json:"language"`
+	Text        string            `json:"text"`
+	Bindings    map[string]string `json:"bindings,omitempty"`
+	Replacement string            `json:"replacement,omitempty"`
+}
+
+// CLIUsageExample is a statement that uses a symbol.
+type CLIUsageExample struct {
+	File        string `json:"file"`
+	Language    string `json:"language"`
+	Line        int    `json:"line"`
+	Code        string `json:"code"`
+	Call        bool   `json:"call"`
+	Occurrences int    `json:"occurrences"`
+}
+
+// CLICoveredSymbol is a function or method with its test coverage.
+type CLICoveredSymbol struct {
+	Symbol       CLISymbol `json:"symbol"`
+	Lines        int       `json:"lines"`
+	CoveredLines int       `json:"covered_lines"`
+	Percent      float64   `json:"percent"`
+	Callers      int       `json:"callers"`
+}
+
+// CLIVisibilityStep is one item on the path to a symbol with the scope its
+// visibility allows.
+type CLIVisibilityStep struct {
+	Name       string `json:"name"`
+	Visibility string `json:"visibility"`
+	Scope      string `json:"scope"`
+	Visible    bool   `json:"visible"`
+}
+
+// CLISymbolVisibility answers whether a Rust symbol can be named from a
+// module.
+type CLISymbolVisibility struct {
+	Symbol  CLISymbol           `json:"symbol"`
+	Crate   string              `json:"crate"`
+	Module  string              `json:"module"`
+	From    string              `json:"from"`
+	Visible bool                `json:"visible"`
+	Steps   []CLIVisibilityStep `json:"steps"`
+	Reason  string              `json:"reason"`
+}
+
+// CLIShadowDefinition is one of the definitions of a shadowed name.
+type CLIShadowDefinition struct {
+	Location CLILocation `json:"location"`
+	Symbol   *CLISymbol  `json:"symbol,omitempty"`
+	Import   string      `json:"import,omitempty"`
+}
+
+// CLIShadow is a name with two definitions where one is expected.
+type CLIShadow struct {
+	Kind      string              `json:"kind"`
+	Name      string              `json:"name"`
+	Function  *CLISymbol          `json:"function,omitempty"`
+	Shadowing CLIShadowDefinition `json:"shadowing"`
+	Shadowed  CLIShadowDefinition `json:"shadowed"`
+}
+
+// CLIGlobCollision is a name two glob imports of a Rust file bring in as
+// different items, used in the file, with the explicit import that settles it.
+type CLIGlobCollision struct {
+	Name    string                `json:"name"`
+	File    string                `json:"file"`
+	Sources []CLIShadowDefinition `json:"sources"`
+	Uses    []CLILocation         `json:"uses"`
+	Import  string                `json:"import"`
+	Fix     CLITextEdit           `json:"fix"`
+}
+
+// CLITraitImpl is an impl of a trait for a type, with the crates of the
+// trait and type and the reason the impl's crate may define it.
+type CLITraitImpl struct {
+	Location    CLILocation `json:"location"`
+	Crate       string      `json:"crate,omitempty"`
+	Trait       string      `json:"trait"`
+	Type        string      `json:"type"`
+	Generics    []string    `json:"generics,omitempty"`
+	Negative    bool        `json:"negative,omitempty"`
+	TraitSymbol *CLISymbol  `json:"trait_symbol,omitempty"`
+	TypeSymbol  *CLISymbol  `json:"type_symbol,omitempty"`
+	TraitCrate  string      `json:"trait_crate,omitempty"`
+	TypeCrate   string      `json:"type_crate,omitempty"`
+	OwnedBy     string      `json:"owned_by,omitempty"`
+	Orphan      bool        `json:"orphan,omitempty"`
+}
+
+// CLIImplOverlap is a pair of impls of a trait that may both apply to some
+// type, as indexes into the trait's impls.
+type CLIImplOverlap struct {
+	Kind   string `json:"kind"`
+	First  int    `json:"first"`
+	Second int    `json:"second"`
+}
+
+// CLITraitImpls are the impls of one trait.
+type CLITraitImpls struct {
+	Trait       string           `json:"trait"`
+	TraitSymbol *CLISymbol       `json:"trait_symbol,omitempty"`
+	Impls       []CLITraitImpl   `json:"impls"`
+	Overlaps    []CLIImplOverlap `json:"overlaps,omitempty"`
+}
+
+// CLIInstantiations is how a generic is instantiated: each use with its
+// type arguments ("" where unknown), and the distinct argument lists.
+type CLIInstantiations struct {
+	Symbol     CLISymbol               `json:"symbol"`
+	TypeParams []string                `json:"type_params"`
+	Sites      []CLIInstantiationSite  `json:"sites"`
+	Usages     []CLIInstantiationUsage `json:"usages"`
+}
+
+// CLIInstantiationSite is one use of a generic; Location.Kind is the
+// reference kind.
+type CLIInstantiationSite struct {
+	Location CLILocation `json:"location"`
+	TypeArgs []string    `json:"type_args"`
+	Inferred bool        `json:"inferred,omitempty"`
+}
+
+// CLIInstantiationUsage is a list of type arguments with the indexes of the
+// sites using it.
+type CLIInstantiationUsage struct {
+	TypeArgs []string `json:"type_args"`
+	Count    int      `json:"count"`
+	Sites    []int    `json:"sites"`
+}
+
+// CLIImplBlock is a Rust impl block of a type with the methods it defines;
+// Trait is empty for an inherent impl.
+type CLIImplBlock struct {
+	Location    CLILocation `json:"location"`
+	Header      string      `json:"header"`
+	Trait       string      `json:"trait,omitempty"`
+	TraitSymbol *CLISymbol  `json:"trait_symbol,omitempty"`
+	Methods     []CLISymbol `json:"methods"`
+}
+
+// CLITraitObjectSafety is whether a Rust trait can be used as dyn Trait,
+// why not, and where it is.
+type CLITraitObjectSafety struct {
+	Trait         CLISymbol                  `json:"trait"`
+	DynCompatible bool                       `json:"dyn_compatible"`
+	Violations    []CLIObjectSafetyViolation `json:"violations"`
+	DynUses       []CLILocation              `json:"dyn_uses"`
+}
+
+// CLIObjectSafetyViolation is one reason a trait is not object safe.
+type CLIObjectSafetyViolation struct {
+	Kind   string `json:"kind"`
+	Member string `json:"member,omitempty"`
+	Detail string `json:"detail,omitempty"`
+}
+
+// CLIEntryPoint is a binary, example, test, or bench target of a Cargo
+// package with the functions it starts from.
+type CLIEntryPoint struct {
+	Package string      `json:"package"`
+	Kind    string      `json:"kind"`
+	Name    string      `json:"name"`
+	Path    string      `json:"path"`
+	Roots   []CLISymbol `json:"roots"`
+}
+
+// CLITestRegion is test code: a test file, or an item compiled for tests
+// only.
+type CLITestRegion struct {
+	Location CLILocation `json:"location"`
+	Reason   string      `json:"reason"`
+	Symbol   *CLISymbol  `json:"symbol,omitempty"`
+}
+
+// CLINewtype is a Rust struct with a single field and the type it wraps.
+type CLINewtype struct {
+	Symbol      CLISymbol  `json:"symbol"`
+	Inner       string     `json:"inner"`
+	InnerHead   string     `json:"inner_head,omitempty"`
+	InnerSymbol *CLISymbol `json:"inner_symbol,omitempty"`
+	Visibility  string     `json:"visibility"`
+}
+
+// CLIFieldEdge links a type to a type named in the declared type of one of
+// its fields.
+type CLIFieldEdge struct {
+	From     CLISymbol  `json:"from"`
+	Field    string     `json:"field"`
+	TypeExpr string     `json:"type_expr"`
+	Type     string     `json:"type"`
+	To       *CLISymbol `json:"to,omitempty"`
+	Depth    int        `json:"depth"`
+}
+
+// CLIFieldGraphType is a type reached in a field graph.
+type CLIFieldGraphType struct {
+	Name   string     `json:"name"`
+	Symbol *CLISymbol `json:"symbol,omitempty"`
+	Depth  int        `json:"depth"`
+}
+
+// CLIFieldGraph is the field dependency graph reachable from a type.
+type CLIFieldGraph struct {
+	Root  CLISymbol           `json:"root"`
+	Edges []CLIFieldEdge      `json:"edges"`
+	Types []CLIFieldGraphType `json:"types"`
+	Depth int                 `json:"depth"`
+}
+
+// CLITraitTreeNode is a trait in a supertrait or subtrait tree.
+type CLITraitTreeNode struct {
+	Name     string             `json:"name"`
+	Bound    string             `json:"bound,omitempty"`
+	Symbol   *CLISymbol         `json:"symbol,omitempty"`
+	Children []CLITraitTreeNode `json:"children,omitempty"`
+}
+
+// CLITraitChainImpl is an impl of a trait with the supertraits its self
+// type lacks.
+type CLITraitChainImpl struct {
+	Impl      CLITraitImpl `json:"impl"`
+	Missing   []string     `json:"missing,omitempty"`
+	Satisfied bool         `json:"satisfied"`
+}
+
+// CLITraitTree is the supertrait and subtrait hierarchy of a trait, with
+// its impls.
+type CLITraitTree struct {
+	Trait       CLISymbol           `json:"trait"`
+	Supertraits []CLITraitTreeNode  `json:"supertraits"`
+	Subtraits   []CLITraitTreeNode  `json:"subtraits"`
+	Impls       []CLITraitChainImpl `json:"impls"`
+}
+
+// CLIModuleMatrix is a design structure matrix of modules: Counts[i][j] is
+// the number of references from Modules[i] to items in Modules[j].
+type CLIModuleMatrix struct {
+	Modules []string `json:"modules"`
+	Counts  [][]int  `json:"counts"`
+	Edges   int      `json:"edges"`
+}
+
+// CLINoStdReport classifies Rust crates and symbols by the part of the
+// standard library they need.
+type CLINoStdReport struct {
+	Crates       []CLINoStdCrate    `json:"crates"`
+	Dependencies []CLIStdDependency `json:"dependencies"`
+}
+
+// CLINoStdCrate is a Rust crate with its symbol count per tier.
+type CLINoStdCrate struct {
+	Crate string `json:"crate"`
+	Root  string `json:"root"`
+	NoStd string `json:"no_std,omitempty"`
+	Core  int    `json:"core"`
+	Alloc int    `json:"alloc"`
+	Std   int    `json:"std"`
+}
+
+// CLIStdDependency is a symbol needing alloc or std, with the chain of
+// references that leads to the standard library.
+type CLIStdDependency struct {
+	Symbol CLISymbol   `json:"symbol"`
+	Crate  string      `json:"crate"`
+	Tier   string      `json:"tier"`
+	Via    []CLISymbol `json:"via"`
+}
+
+// CLIBinarySizeReport attributes the code size of a binary to modules and
+// functions.
+type CLIBinarySizeReport struct {
+	Source       string            `json:"source"`
+	Total        int64             `json:"total_bytes"`
+	Modules      []CLIModuleSize   `json:"modules"`
+	Functions    []CLIFunctionSize `json:"functions"`
+	Unattributed []CLICrateSize    `json:"unattributed"`
+}
+
+// CLIModuleSize is the code size attributed to a module.
+type CLIModuleSize struct {
+	Module    string  `json:"module"`
+	Bytes     int64   `json:"bytes"`
+	Functions int     `json:"functions"`
+	Share     float64 `json:"share"`
+}
+
+// CLIFunctionSize is the code size attributed to a function.
+type CLIFunctionSize struct {
+	Symbol  CLISymbol `json:"symbol"`
+	Module  string    `json:"module"`
+	Bytes   int64     `json:"bytes"`
+	Inlined bool      `json:"inlined,omitempty"`
+}
+
+// CLICrateSize is measured code no indexed function accounts for.
+type CLICrateSize struct {
+	Crate string  `json:"crate"`
+	Bytes int64   `json:"bytes"`
+	Share float64 `json:"share"`
+}
+
+// CLIInstantiationHotspot is a generic function used with many distinct
+// type argument lists.
+type CLIInstantiationHotspot struct {
+	Symbol     CLISymbol               `json:"symbol"`
+	TypeParams []string                `json:"type_params"`
+	Distinct   int                     `json:"distinct"`
+	Sites      int                     `json:"sites"`
+	Unknown    int                     `json:"unknown"`
+	Usages     []CLIInstantiationUsage `json:"usages"`
+}
+
+// CLISymbolAliases is a symbol with the names it is imported under.
+type CLISymbolAliases struct {
+	Symbol  CLISymbol        `json:"symbol"`
+	Aliases []CLISymbolAlias `json:"aliases"`
+}
+
+// CLISymbolAlias is an import of a symbol under another name.
+type CLISymbolAlias struct {
+	Alias      string `json:"alias"`
+	Source     string `json:"source"`
+	File       string `json:"file"`
+	References int    `json:"references"`
+}
+
+// CLIImportPaths is a Rust item with its canonical path and the paths its
+// re-exports add.
+type CLIImportPaths struct {
+	Symbol        CLISymbol       `json:"symbol"`
+	CanonicalPath string          `json:"canonical_path"`
+	Paths         []CLIImportPath `json:"paths"`
+}
+
+// CLIImportPath is a path a Rust item can be imported by, with the chain of
+// re-exports from its definition.
+type CLIImportPath struct {
+	Path   string           `json:"path"`
+	Public bool             `json:"public"`
+	Hops   []CLIReexportHop `json:"hops"`
+}
+
+// CLIReexportHop is one pub use in a chain of re-exports.
+type CLIReexportHop struct {
+	Path       string `json:"path"`
+	Source     string `json:"source"`
+	Visibility string `json:"visibility"`
+	File       string `json:"file"`
+	Line       int    `json:"line"`
+	Col        int    `json:"col"`
+}
+
+// CLIUpgradeImpact lists the uses of the items a dependency upgrade removes
+// or changes.
+type CLIUpgradeImpact struct {
+	Changes []CLIChangeImpact `json:"changes"`
+	Sites   int               `json:"sites"`
+	Files   []string          `json:"files"`
+}
+
+// CLIChangeImpact is one changed item with the references to it.
+type CLIChangeImpact struct {
+	Path    string        `json:"path"`
+	Note    string        `json:"note,omitempty"`
+	Symbols []CLISymbol   `json:"symbols"`
+	Sites   []CLILocation `json:"sites"`
+}
+
+// CLILintFinding is a finding of a rule, at the rule's configured severity.
+type CLILintFinding struct {
+	Rule     string      `json:"rule"`
+	Severity string      `json:"severity"`
+	Message  string      `json:"message"`
+	Location CLILocation `json:"location"`
+}
+
+// CLILintReport lists the findings of the rules lint ran that are neither
+// suppressed inline nor in the baseline. With --write-baseline, Baselined
+// is the number of findings written and Findings is empty.
+type CLILintReport struct {
+	Rules      []string         `json:"rules"`
+	Findings   []CLILintFinding `json:"findings"`
+	Suppressed int              `json:"suppressed"`
+	Baselined  int              `json:"baselined"`
+}
+
+// CLIReport is an architecture report of the index. The ranked sections
+// hold the top rows only; paths are relative to the repo root.
+type CLIReport struct {
+	Title          string              `json:"title"`
+	Languages      []CLILanguageStats  `json:"languages"`
+	PackageCount   int                 `json:"package_count"`
+	Modules        []CLIPackageMetrics `json:"modules"`
+	TopTypes       []CLISymbol         `json:"top_types"`
+	PublicAPI      []CLISymbol         `json:"public_api"`
+	PublicAPICount int                 `json:"public_api_count"`
+	Hotspots       []CLIHotspot        `json:"hotspots"`
+	Findings       []CLIReportRule     `json:"findings"`
+}
+
+// CLIReportRule is one rule's findings in a report: how many it has and the
+// first few.
+type CLIReportRule struct {
+	Rule     string           `json:"rule"`
+	Severity string           `json:"severity"`
+	Count    int              `json:"count"`
+	Findings []CLILintFinding `json:"findings"`
+}
+
+// CLIRenameEdit is one identifier span to change in a rename plan.
+type CLIRenameEdit struct {
+	CLILocation
+	Kind    string `json:"kind"`
+	OldText string `json:"old_text"`
+	NewText string `json:"new_text"`
+}
+
+// CLITextEdit is an edit a fix makes: NewText replaces the span, which is
+// empty for an insertion.
+type CLITextEdit struct {
+	CLILocation
+	NewText string `json:"new_text"`
+}
+
+// CLIRenamePlan lists the edits a rename would make.
+type CLIRenamePlan struct {
+	Symbol  CLISymbol       `json:"symbol"`
+	NewName string          `json:"new_name"`
+	Files   int             `json:"files"`
+	Edits   []CLIRenameEdit `json:"edits"`
+}
+
+// CLIApplyResult reports edits written by --fix.
+type CLIApplyResult struct {
+	Files []string `json:"files"`
+	Edits int      `json:"edits"`
+}
+
+// LSPPosition is an LSP Position: 0-based line and UTF-16 character offset.
+type LSPPosition struct {
+	Line      int `json:"line"`
+	Character int `json:"character"`
+}
+
+// LSPRange is an LSP Range.
+type LSPRange struct {
+	Start LSPPosition `json:"start"`
+	End   LSPPosition `json:"end"`
+}
+
+// LSPTextEdit is an LSP TextEdit.
+type LSPTextEdit struct {
+	Range   LSPRange `json:"range"`
+	NewText string   `json:"newText"`
+}
+
+// LSPWorkspaceEdit is an LSP WorkspaceEdit keyed by document URI.
+type LSPWorkspaceEdit struct {
+	Changes map[string][]LSPTextEdit `json:"changes"`
+}
+
+// CLIOutlineNode is one item of a file outline, with its nested items.
+type CLIOutlineNode struct {
+	SymbolID   *int64           `json:"symbol_id,omitempty"`
+	Name       string           `json:"name"`
+	Kind       string           `json:"kind"`
+	Visibility string           `json:"visibility,omitempty"`
+	StartLine  int              `json:"start_line"`
+	EndLine    int              `json:"end_line"`
+	Children   []CLIOutlineNode `json:"children,omitempty"`
+}
+
+// CLIOutlineFile is the outline of one file.
+type CLIOutlineFile struct {
+	File     string           `json:"file"`
+	Language string           `json:"language"`
+	Items    []CLIOutlineNode `json:"items"`
+}
+
+// CLIRepoMap is a ranked repo map truncated to a token budget.
+type CLIRepoMap struct {
+	Budget          int              `json:"budget"`
+	EstimatedTokens int              `json:"estimated_tokens"`
+	Symbols         int              `json:"symbols"`
+	Files           []CLIRepoMapFile `json:"files"`
+}
+
+// CLIRepoMapFile groups the repo map entries of one file.
+type CLIRepoMapFile struct {
+	File    string            `json:"file"`
+	Entries []CLIRepoMapEntry `json:"entries"`
+}
+
+// CLIRepoMapEntry is one symbol in a repo map.
+type CLIRepoMapEntry struct {
+	ID               int64   `json:"id"`
+	Name             string  `json:"name"`
+	Kind             string  `json:"kind"`
+	StartLine        int     `json:"start_line"`
+	EndLine          int     `json:"end_line"`
+	RefCount         int     `json:"ref_count"`
+	ExternalRefCount int     `json:"external_ref_count"`
+	Rank             float64 `json:"rank"`
+	Signature        string  `json:"signature"`
+}
+
+// CLIUnresolvedReport lists the references the resolver could not bind.
+type CLIUnresolvedReport struct {
+	TotalReferences int                      `json:"total_references"`
+	ByReason        map[string]int           `json:"by_reason"`
+	References      []CLIUnresolvedReference `json:"references"`
+}
+
+// CLIUnsafeReport summarizes unsafe blocks and unsafe functions.
+type CLIUnsafeReport struct {
+	Functions int              `json:"unsafe_fns"`
+	Blocks    int              `json:"unsafe_blocks"`
+	ByFile    map[string]int   `json:"by_file"`
+	Usages    []CLIUnsafeUsage `json:"usages"`
+}
+
+// CLIUnsafeUsage is one unsafe block or unsafe function.
+type CLIUnsafeUsage struct {
+	Kind     string             `json:"kind"`
+	Location CLILocation        `json:"location"`
+	Function CLISymbol          `json:"function"`
+	Path     []CLICallGraphEdge `json:"path,omitempty"`
+}
+
+// CLIUnresolvedReference is one unresolved reference with its likely reason.
+type CLIUnresolvedReference struct {
+	Name      string      `json:"name"`
+	Context   string      `json:"context,omitempty"`
+	Reason    string      `json:"reason"`
+	Qualifier string      `json:"qualifier,omitempty"`
+	Import    string      `json:"import,omitempty"`
+	Location  CLILocation `json:"location"`
+}
+
+// CLIResolutionTrace is the result of resolve-debug: how the reference at
+// a position was resolved.
+type CLIResolutionTrace struct {
+	ReferenceID      int64                    `json:"reference_id"`
+	Name             string                   `json:"name"`
+	Kind             string                   `json:"kind,omitempty"`
+	Qualifier        string                   `json:"qualifier,omitempty"`
+	Language         string                   `json:"language"`
+	Location         CLILocation              `json:"location"`
+	Scopes           []CLITracedScope         `json:"scopes"`
+	Imports          []CLITracedImport        `json:"imports"`
+	Candidates       []CLIResolutionCandidate `json:"candidates"`
+	UnresolvedReason string                   `json:"unresolved_reason,omitempty"`
+}
+
+// CLITracedScope is a scope searched for a traced reference's name,
+// innermost first, with the candidates declared in it.
+type CLITracedScope struct {
+	Kind       string  `json:"kind"`
+	SymbolID   *int64  `json:"symbol_id,omitempty"`
+	SymbolName string  `json:"symbol_name,omitempty"`
+	StartLine  int     `json:"start_line"`
+	EndLine    int     `json:"end_line"`
+	Declares   []int64 `json:"declares,omitempty"`
+}
+
+// CLITracedImport is an import of a traced reference's file.
+type CLITracedImport struct {
+	Source       string `json:"source"`
+	ImportedName string `json:"imported_name,omitempty"`
+	LocalAlias   string `json:"local_alias,omitempty"`
+	Kind         string `json:"kind"`
+	Binds        string `json:"binds,omitempty"`
+	Matches      bool   `json:"matches"`
+}
+
+// CLIResolutionCandidate is a declaration a traced reference could have
+// meant, bound or rejected with a reason.
+type CLIResolutionCandidate struct {
+	SymbolID       int64        `json:"symbol_id"`
+	Name           string       `json:"name"`
+	Kind           string       `json:"kind"`
+	Visibility     string       `json:"visibility,omitempty"`
+	Location       *CLILocation `json:"location,omitempty"`
+	Bound          bool         `json:"bound"`
+	ResolutionKind string       `json:"resolution_kind,omitempty"`
+	Confidence     string       `json:"confidence,omitempty"`
+	Rejected       string       `json:"rejected,omitempty"`
+}
+
+// CLIBenchReport is the result of a bench run: the workload, what indexing
+// it produced, and the median time of each phase.
+type CLIBenchReport struct {
+	Language   string           `json:"language"`
+	Files      int              `json:"files"`
+	Functions  int              `json:"functions_per_file"`
+	Seed       int64            `json:"seed"`
+	Runs       int              `json:"runs"`
+	Symbols    int              `json:"symbols"`
+	References int              `json:"references"`
+	CallEdges  int              `json:"call_edges"`
+	Timings    []CLIBenchTiming `json:"timings"`
+}
+
+// CLIIndexStats is the result of stats.
+type CLIIndexStats struct {
+	Files           int              `json:"files"`
+	FilesByLanguage map[string]int   `json:"files_by_language"`
+	Symbols         int              `json:"symbols"`
+	SymbolsByKind   map[string]int   `json:"symbols_by_kind"`
+	References      int              `json:"references"`
+	Resolved        int              `json:"resolved_references"`
+	ResolutionRate  float64          `json:"resolution_rate"`
+	EdgesByKind     map[string]int   `json:"edges_by_kind"`
+	ParseErrors     int              `json:"parse_errors"`
+	FilesWithErrors int              `json:"files_with_parse_errors"`
+	DegradedFiles   map[string]int   `json:"degraded_files"`
+	FallbackSymbols int              `json:"fallback_symbols"`
+	DatabaseBytes   int64            `json:"database_bytes"`
+	IndexedAt       string           `json:"indexed_at,omitempty"` // RFC 3339
+	Timings         []CLIIndexTiming `json:"timings"`
+}
+
+// CLIHistoryPoint is the metrics of one sampled revision, one element of
+// the result of history.
+type CLIHistoryPoint struct {
+	Commit         string  `json:"commit"`
+	Date           string  `json:"date"` // committer date, RFC 3339
+	Subject        string  `json:"subject"`
+	Files          int     `json:"files"`
+	Symbols        int     `json:"symbols"`
+	Unused         int     `json:"unused_symbols"`
+	Cycles         int     `json:"dependency_cycles"`
+	Functions      int     `json:"functions"`
+	MeanCyclomatic float64 `json:"mean_cyclomatic"`
+	MaxCyclomatic  int     `json:"max_cyclomatic"`
+}
+
+// CLIIndexTiming is the time of one phase of the latest index run.
+type CLIIndexTiming struct {
+	Phase  string  `json:"phase"`
+	Millis float64 `json:"ms"`
+}
+
+// CLIBenchTiming is the median time of one bench phase, compared with a
+// baseline when one was given.
+type CLIBenchTiming struct {
+	Phase          string   `json:"phase"`
+	Millis         float64  `json:"ms"`
+	BaselineMillis float64  `json:"baseline_ms,omitempty"`
+	ChangePct      *float64 `json:"change_pct,omitempty"`
+}
+
+// CLIFixture describes a fixture written by gen-fixture.
+type CLIFixture struct {
+	Dir             string   `json:"dir"`
+	Level           int      `json:"level"`
+	Features        []string `json:"features"`
+	Seed            int64    `json:"seed"`
+	Files           int      `json:"files"`
+	Definitions     int      `json:"definitions"`
+	Calls           int      `json:"calls"`
+	Implementations int      `json:"implementations"`
+}
+
+// CLISnapshot is the result of export and import.
+type CLISnapshot struct {
+	Path    string `json:"path"`
+	Format  string `json:"format"`
+	Version int    `json:"version"`
+	Tables  int    `json:"tables"`
+	Rows    int64  `json:"rows"`
+	Bytes   int64  `json:"bytes"`
+}
+
+// CLIChunkExport is the result of export --format chunks.
+type CLIChunkExport struct {
+	Path   string `json:"path"`
+	Format string `json:"format"`
+	Chunks int    `json:"chunks"`
+	Files  int    `json:"files"`
+	Bytes  int64  `json:"bytes"`
+}
+
+// CLIAccessExport is the result of export --format access.
+type CLIAccessExport struct {
+	Path       string `json:"path"`
+	Format     string `json:"format"`
+	Symbols    int    `json:"symbols"`
+	Restricted int    `json:"restricted"`
+	Bytes      int64  `json:"bytes"`
+}
+
+// CLISymbolAccess is one record of an access manifest.
+type CLISymbolAccess struct {
+	SymbolID   int64    `json:"symbol_id"`
+	Name       string   `json:"name"`
+	Kind       string   `json:"kind"`
+	File       string   `json:"file"`
+	Line       int      `json:"line"`
+	Language   string   `json:"language"`
+	Visibility string   `json:"visibility"`
+	Scope      string   `json:"scope,omitempty"`
+	Exported   bool     `json:"exported"`
+	Owners     []string `json:"owners"`
+	Teams      []string `json:"teams,omitempty"`
+	Restricted bool     `json:"restricted"`
+	Readers    []string `json:"readers,omitempty"`
+}
+
+// CLIFileExport is the result of export --format parquet and neo4j, which
+// write several files to a directory.
+type CLIFileExport struct {
+	Path   string          `json:"path"`
+	Format string          `json:"format"`
+	Files  []CLIExportFile `json:"files"`
+}
+
+// CLIExportFile is one file of a CLIFileExport.
+type CLIExportFile struct {
+	Path  string `json:"path"`
+	Rows  int64  `json:"rows"`
+	Bytes int64  `json:"bytes"`
+}
+
+// CLICodeChunk is one JSONL record of export --format chunks.
+type CLICodeChunk struct {
+	ID        string   `json:"id"`
+	File      string   `json:"file"`
+	Language  string   `json:"language"`
+	Kind      string   `json:"kind"`
+	Name      string   `json:"name"`
+	Path      string   `json:"path"`
+	Signature string   `json:"signature"`
+	Doc       string   `json:"doc,omitempty"`
+	StartLine int      `json:"start_line"`
+	EndLine   int      `json:"end_line"`
+	Text      string   `json:"text"`
+	Tokens    int      `json:"tokens"`
+	SymbolID  int64    `json:"symbol_id,omitempty"`
+	Part      int      `json:"part,omitempty"`
+	Parts     int      `json:"parts,omitempty"`
+	Parent    string   `json:"parent,omitempty"`
+	Children  []string `json:"children"`
+	Calls     []string `json:"calls"`
+	CalledBy  []string `json:"called_by"`
+}
+
+// CLIIndexValidation is the result of validate-index.
+type CLIIndexValidation struct {
+	Valid      bool           `json:"valid"`
+	ByCheck    map[string]int `json:"by_check"`
+	Violations []CLIViolation `json:"violations"`
+}
+
+// CLIGateReport is the result of check: each configured quality gate and
+// whether all passed.
+type CLIGateReport struct {
+	Passed bool      `json:"passed"`
+	Gates  []CLIGate `json:"gates"`
+}
+
+// CLIGate is one quality gate: the measured value and the configured limit,
+// a maximum or, for min_resolution_rate, a minimum.
+type CLIGate struct {
+	Gate   string  `json:"gate"`
+	Value  float64 `json:"value"`
+	Limit  float64 `json:"limit"`
+	Passed bool    `json:"passed"`
+}
+
+// CLIViolation is one broken index invariant.
+type CLIViolation struct {
+	Check  string `json:"check"`
+	Table  string `json:"table"`
+	RowID  int64  `json:"row_id"`
+	Detail string `json:"detail"`
+}
@@ -0,0 +1,65 @@
+package main
+
+import (
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var flagReasons []string
+
+var unresolvedCmd = &cobra.Command{
+	Use:   "unresolved",
+	Short: "List references the resolver could not bind",
+	Long: "Lists every reference that did not resolve to a symbol, with a likely reason:\n" +
+		"  missing_dependency  the name or its qualifier comes from an import that is not indexed,\n" +
+		"                      or it invokes a macro defined outside the index\n" +
+		"  build_generated     the name likely comes from build script output that is not indexed\n" +
+		"                      (index with --target-dir)\n" +
+		"  macro_generated     the target is likely produced by a derive or item-level macro\n" +
+		"  unsupported_syntax  the resolver does not model the construct\n" +
+		"The summary counts every reason; --reason narrows only the listed references.",
+	Args: cobra.NoArgs,
+	RunE: runUnresolved,
+}
+
+func init() {
+	unresolvedCmd.Flags().StringSliceVar(&flagReasons, "reason", nil, "filter by reason (missing_dependency, build_generated, macro_generated, unsupported_syntax; repeatable)")
+	unresolvedCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix")
+	unresolvedCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	unresolvedCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+func runUnresolved(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("unresolved", err)
+	}
+	defer s.Close()
+
+	filter := canopy.UnresolvedFilter{Reasons: flagReasons, PathPrefix: flagPathPrefix}
+	report, err := queryBuilder(s).UnresolvedReferences(filter, buildPagination())
+	if err != nil {
+		return outputError("unresolved", err)
+	}
+
+	refs := make([]CLIUnresolvedReference, len(report.Items))
+	for i, r := range report.Items {
+		refs[i] = CLIUnresolvedReference{
+			Name:      r.Name,
+			Context:   r.Context,
+			Reason:    r.Reason,
+			Qualifier: r.Qualifier,
+			Import:    r.Import,
+			Location:  locationToCLI(r.Location, nil),
+		}
+	}
+	return outputResult(CLIResult{
+		Command: "unresolved",
+		Results: CLIUnresolvedReport{
+			TotalReferences: report.TotalReferences,
+			ByReason:        report.ByReason,
+			References:      refs,
+		},
+		TotalCount: &report.TotalCount,
+	})
+}
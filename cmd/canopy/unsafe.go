@@ -0,0 +1,72 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var flagReachableFrom int64
+
+var unsafeCmd = &cobra.Command{
+	Use:   "unsafe",
+	Short: "Inventory unsafe blocks and unsafe functions",
+	Long: "Lists every unsafe block and unsafe fn with a summary by kind and file.\n" +
+		"With --reachable-from <symbol id>, lists only those in that function or in functions it calls up to --max-depth\n" +
+		"(spawned work included), each with the shortest call path to it.",
+	Args: cobra.NoArgs,
+	RunE: runUnsafe,
+}
+
+func init() {
+	unsafeCmd.Flags().Int64Var(&flagReachableFrom, "reachable-from", 0, "symbol ID whose reachable unsafe code to list")
+	unsafeCmd.Flags().Int("max-depth", 10, "maximum traversal depth for --reachable-from (1-100)")
+	unsafeCmd.Flags().StringVar(&flagPathPrefix, "path-prefix", "", "filter by file path prefix (inventory only)")
+	unsafeCmd.Flags().IntVar(&flagLimit, "limit", 50, "pagination limit (max 500)")
+	unsafeCmd.Flags().IntVar(&flagOffset, "offset", 0, "pagination offset")
+}
+
+func runUnsafe(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("unsafe", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	var report *canopy.UnsafeReport
+	if flagReachableFrom != 0 {
+		maxDepth, _ := cmd.Flags().GetInt("max-depth")
+		report, err = qb.UnsafeReachableFrom(flagReachableFrom, maxDepth)
+	} else {
+		report, err = qb.UnsafeUsages(flagPathPrefix)
+	}
+	if err != nil {
+		return outputError("unsafe", err)
+	}
+	if report == nil {
+		return outputError("unsafe", fmt.Errorf("symbol not found: %d", flagReachableFrom))
+	}
+
+	usages := make([]CLIUnsafeUsage, len(report.Items))
+	for i, u := range report.Items {
+		usages[i] = CLIUnsafeUsage{
+			Kind:     u.Kind,
+			Location: locationToCLI(u.Location, nil),
+			Function: symbolResultToCLI(u.Function),
+			Path:     callGraphEdgesToCLI(u.Path),
+		}
+	}
+	paged, totalCount := paginateSlice(usages)
+	return outputResult(CLIResult{
+		Command: "unsafe",
+		Results: CLIUnsafeReport{
+			Functions: report.Functions,
+			Blocks:    report.Blocks,
+			ByFile:    report.ByFile,
+			Usages:    paged,
+		},
+		TotalCount: &totalCount,
+	})
+}
@@ -0,0 +1,96 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/jward/canopy"
+	"github.com/spf13/cobra"
+)
+
+var upgradeImpactCmd = &cobra.Command{
+	Use:   "upgrade-impact [<path>...]",
+	Short: "List the uses of the items a dependency upgrade removes or changes",
+	Long: "Lists every reference in the workspace to the Rust items a dependency upgrade removes or changes, with the\n" +
+		"number of sites and files, to estimate the upgrade. Give the items' crate-qualified paths as arguments\n" +
+		"(serde::de::Deserialize) or with --changes, a file with one path per line optionally followed by a note\n" +
+		"(\"serde::Error removed\"); # starts a comment and - reads stdin. A dependency's items are known when its\n" +
+		"rustdoc JSON was loaded with 'canopy index --rustdoc-json'.",
+	RunE: runUpgradeImpact,
+}
+
+func init() {
+	upgradeImpactCmd.Flags().String("changes", "", "file listing the changed items, one per line (- for stdin)")
+}
+
+func runUpgradeImpact(cmd *cobra.Command, args []string) error {
+	var changes []canopy.ChangedSymbol
+	for _, arg := range args {
+		changes = append(changes, canopy.ChangedSymbol{Path: arg})
+	}
+	if path, _ := cmd.Flags().GetString("changes"); path != "" {
+		var in io.Reader = os.Stdin
+		if path != "-" {
+			f, err := os.Open(path)
+			if err != nil {
+				return outputError("upgrade-impact", err)
+			}
+			defer f.Close()
+			in = f
+		}
+		listed, err := canopy.ParseChangedSymbols(in)
+		if err != nil {
+			return outputError("upgrade-impact", err)
+		}
+		changes = append(changes, listed...)
+	}
+	if len(changes) == 0 {
+		return outputError("upgrade-impact", fmt.Errorf("requires <path> arguments or --changes"))
+	}
+
+	s, err := openStore()
+	if err != nil {
+		return outputError("upgrade-impact", err)
+	}
+	defer s.Close()
+
+	qb, err := edgeQueryBuilder(s)
+	if err != nil {
+		return outputError("upgrade-impact", err)
+	}
+	impact, err := qb.UpgradeImpact(changes)
+	if err != nil {
+		return outputError("upgrade-impact", err)
+	}
+
+	result := CLIUpgradeImpact{
+		Changes: make([]CLIChangeImpact, len(impact.Changes)),
+		Sites:   impact.Sites,
+		Files:   impact.Files,
+	}
+	for i, c := range impact.Changes {
+		ci := CLIChangeImpact{
+			Path:    c.Path,
+			Note:    c.Note,
+			Symbols: make([]CLISymbol, len(c.Symbols)),
+			Sites:   make([]CLILocation, len(c.Sites)),
+		}
+		for j, sym := range c.Symbols {
+			ci.Symbols[j] = symbolResultToCLI(sym)
+		}
+		for j, site := range c.Sites {
+			loc := locationToCLI(site.Location, nil)
+			loc.Kind = site.Kind
+			loc.Confidence = site.Confidence
+			ci.Sites[j] = loc
+		}
+		result.Changes[i] = ci
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command:    "upgrade-impact",
+		Results:    result,
+		TotalCount: &one,
+	})
+}
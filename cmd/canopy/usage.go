@@ -0,0 +1,82 @@
+package main
+
+import (
+	"encoding/json"
+	"os"
+	"sync"
+	"time"
+
+	"github.com/jward/canopy"
+)
+
+// usageLogEnv names a file each command appends an anonymized usage record
+// to, one JSON object per line, for platform teams to collect with their own
+// tooling. Unset, nothing is recorded.
+const usageLogEnv = "CANOPY_USAGE_LOG"
+
+// commandStart is when the running command started, set before each
+// command, including each command of a batch or a serve request.
+var commandStart time.Time
+
+// usageLogMu serializes appends from index callbacks and commands.
+var usageLogMu sync.Mutex
+
+// usageRecord is one line of the usage log.
+type usageRecord struct {
+	Time       string  `json:"time"`
+	Kind       string  `json:"kind"`
+	Name       string  `json:"name"`
+	DurationMS float64 `json:"duration_ms"`
+	Results    int     `json:"results"`
+	Failed     bool    `json:"failed,omitempty"`
+}
+
+// recordUsage appends ev to the usage log, if one is set. Write errors are
+// ignored: usage logging never fails a command.
+func recordUsage(ev canopy.UsageEvent) {
+	path := os.Getenv(usageLogEnv)
+	if path == "" {
+		return
+	}
+	data, err := json.Marshal(usageRecord{
+		Time:       ev.Start.UTC().Format(time.RFC3339),
+		Kind:       ev.Kind,
+		Name:       ev.Name,
+		DurationMS: float64(ev.Duration.Microseconds()) / 1000,
+		Results:    ev.Results,
+		Failed:     ev.Failed,
+	})
+	if err != nil {
+		return
+	}
+	usageLogMu.Lock()
+	defer usageLogMu.Unlock()
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0644)
+	if err != nil {
+		return
+	}
+	defer f.Close()
+	f.Write(append(data, '\n'))
+}
+
+// recordCommandUsage records the result of the running command as a query
+// event: its name, duration, and result count.
+func recordCommandUsage(result CLIResult) {
+	if os.Getenv(usageLogEnv) == "" || commandStart.IsZero() {
+		return
+	}
+	results := 0
+	if result.TotalCount != nil {
+		results = *result.TotalCount
+	} else if result.Results != nil {
+		results = resultLen(result.Results)
+	}
+	recordUsage(canopy.UsageEvent{
+		Kind:     canopy.UsageQuery,
+		Name:     result.Command,
+		Start:    commandStart,
+		Duration: time.Since(commandStart),
+		Results:  results,
+		Failed:   result.Error != "",
+	})
+}
@@ -0,0 +1,48 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+)
+
+var validateIndexCmd = &cobra.Command{
+	Use:   "validate-index",
+	Short: "Check the index for structural corruption",
+	Long: "Checks the structural invariants of the index and lists every violation:\n" +
+		"  dangling_reference  an id column points at a row that does not exist\n" +
+		"  duplicate_symbol    a symbol is recorded twice with the same name, kind, parent, and position\n" +
+		"  span_out_of_bounds  a symbol, reference, or scope span is inverted or runs past the end of its file\n" +
+		"Exits non-zero when any are found. Useful after incremental indexing to catch rows left behind.",
+	Args: cobra.NoArgs,
+	RunE: runValidateIndex,
+}
+
+func runValidateIndex(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("validate-index", err)
+	}
+	defer s.Close()
+
+	violations, err := s.Validate()
+	if err != nil {
+		return outputError("validate-index", err)
+	}
+	report := CLIIndexValidation{
+		Valid:      len(violations) == 0,
+		ByCheck:    map[string]int{},
+		Violations: make([]CLIViolation, len(violations)),
+	}
+	for i, v := range violations {
+		report.ByCheck[v.Check]++
+		report.Violations[i] = CLIViolation{Check: v.Check, Table: v.Table, RowID: v.RowID, Detail: v.Detail}
+	}
+	if err := outputResult(CLIResult{Command: "validate-index", Results: report}); err != nil {
+		return err
+	}
+	if !report.Valid {
+		return fmt.Errorf("validate-index: found %d violations", len(violations))
+	}
+	return nil
+}
@@ -0,0 +1,67 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/spf13/cobra"
+)
+
+var visibleFromCmd = &cobra.Command{
+	Use:   "visible-from <module> [<file> <line> <col>]",
+	Short: "Show whether a Rust symbol is accessible from a module, and why",
+	Long: "Applies Rust's visibility rules (pub, pub(crate), pub(super), pub(self), pub(in path), private) to the symbol\n" +
+		"and to each module enclosing it, and reports whether the symbol can be named from <module> and which\n" +
+		"declaration blocks it. <module> is a path such as crate::net::http, a path in another crate such as\n" +
+		"other_crate::api, or a module file such as src/net/http.rs. Re-exports are not considered.\n" +
+		"Accepts either <file> <line> <col> positional args or --symbol <id>.",
+	Args: cobra.RangeArgs(1, 4),
+	RunE: runVisibleFrom,
+}
+
+func init() {
+	visibleFromCmd.Flags().Int64("symbol", 0, "symbol ID to query")
+}
+
+func runVisibleFrom(cmd *cobra.Command, args []string) error {
+	s, err := openStore()
+	if err != nil {
+		return outputError("visible-from", err)
+	}
+	defer s.Close()
+
+	qb := queryBuilder(s)
+	symID, err := resolveSymbolID(cmd, args[1:], qb)
+	if err != nil {
+		return outputError("visible-from", err)
+	}
+
+	v, err := qb.VisibleFrom(args[0], symID)
+	if err != nil {
+		return outputError("visible-from", err)
+	}
+	if v == nil {
+		return outputError("visible-from", fmt.Errorf("no symbol with ID %d", symID))
+	}
+
+	steps := make([]CLIVisibilityStep, len(v.Steps))
+	for i, st := range v.Steps {
+		steps[i] = CLIVisibilityStep{Name: st.Name, Visibility: st.Visibility, Visible: st.Visible}
+		if st.Visibility != "" {
+			steps[i].Scope = st.Scope.String()
+		}
+	}
+	one := 1
+	return outputResult(CLIResult{
+		Command: "visible-from",
+		Results: CLISymbolVisibility{
+			Symbol:  symbolResultToCLI(v.Symbol),
+			Crate:   v.Crate,
+			Module:  v.Module,
+			From:    v.From,
+			Visible: v.Visible,
+			Steps:   steps,
+			Reason:  v.Reason,
+		},
+		TotalCount: &one,
+	})
+}
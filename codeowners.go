@@ -0,0 +1,134 @@
+package canopy
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// codeownersLocations are the paths, relative to a root, where a CODEOWNERS
+// file is looked up, in GitHub's order of precedence.
+var codeownersLocations = []string{
+	filepath.Join(".github", "CODEOWNERS"),
+	"CODEOWNERS",
+	filepath.Join("docs", "CODEOWNERS"),
+}
+
+// codeownersRule maps a .gitignore-style pattern to its owners.
+type codeownersRule struct {
+	rule   ignoreRule
+	owners []string
+}
+
+// CodeOwners is a parsed CODEOWNERS file.
+type CodeOwners struct {
+	rules []codeownersRule
+}
+
+// ParseCodeOwners parses a CODEOWNERS file: one pattern per line followed by
+// zero or more owners (@user, @org/team, or email). A pattern with no owners
+// marks paths as unowned. Comments and blank lines are skipped.
+func ParseCodeOwners(r io.Reader) (*CodeOwners, error) {
+	co := &CodeOwners{}
+	scanner := bufio.NewScanner(r)
+	for scanner.Scan() {
+		line := strings.TrimSpace(stripCodeownersComment(scanner.Text()))
+		if line == "" {
+			continue
+		}
+		fields := strings.Fields(line)
+		rule, ok := parseIgnorePattern(fields[0])
+		if !ok || rule.negate {
+			continue // negation is not supported by CODEOWNERS
+		}
+		co.rules = append(co.rules, codeownersRule{rule: rule, owners: fields[1:]})
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("parse codeowners: %w", err)
+	}
+	return co, nil
+}
+
+// stripCodeownersComment removes a trailing "#" comment. "\#" is a literal.
+func stripCodeownersComment(line string) string {
+	for i := 0; i < len(line); i++ {
+		if line[i] == '#' && (i == 0 || line[i-1] != '\\') {
+			return line[:i]
+		}
+	}
+	return line
+}
+
+// Owners returns the owners of rel, a slash- or OS-separated path relative to
+// the CODEOWNERS root. The last matching pattern wins; a pattern matches a
+// file or any of its parent directories. Returns nil if rel is unowned.
+func (c *CodeOwners) Owners(rel string) []string {
+	rel = filepath.ToSlash(rel)
+	segs := strings.Split(rel, "/")
+	for i := len(c.rules) - 1; i >= 0; i-- {
+		r := c.rules[i]
+		matched := r.rule.match(rel, false)
+		for j := 1; !matched && j < len(segs); j++ {
+			matched = r.rule.match(strings.Join(segs[:j], "/"), true)
+		}
+		if matched {
+			if len(r.owners) == 0 {
+				return nil
+			}
+			return r.owners
+		}
+	}
+	return nil
+}
+
+// loadCodeOwners reads the CODEOWNERS file of root. Returns nil with no error
+// if the root has none.
+func loadCodeOwners(root string) (*CodeOwners, error) {
+	for _, loc := range codeownersLocations {
+		f, err := os.Open(filepath.Join(root, loc))
+		if os.IsNotExist(err) {
+			continue
+		}
+		if err != nil {
+			return nil, fmt.Errorf("open codeowners: %w", err)
+		}
+		co, err := ParseCodeOwners(f)
+		f.Close()
+		return co, err
+	}
+	return nil, nil
+}
+
+// refreshOwners recomputes the owners of every file indexed under root from
+// its CODEOWNERS file. Files owned by a nested root are left to that root.
+func (e *Engine) refreshOwners(root string) error {
+	co, err := loadCodeOwners(root)
+	if err != nil {
+		return err
+	}
+
+	files, err := e.store.AllFiles()
+	if err != nil {
+		return fmt.Errorf("refresh owners: list files: %w", err)
+	}
+	root = filepath.Clean(root)
+	prefix := root + string(filepath.Separator)
+	owners := map[int64][]string{}
+	for id, path := range files {
+		if !strings.HasPrefix(path, prefix) || e.rootFor(path) != root {
+			continue
+		}
+		var o []string
+		if co != nil {
+			o = co.Owners(strings.TrimPrefix(path, prefix))
+		}
+		owners[id] = o
+	}
+	if err := e.store.ReplaceFileOwners(owners); err != nil {
+		return fmt.Errorf("refresh owners: %w", err)
+	}
+	return nil
+}
@@ -0,0 +1,72 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseCodeOwners_LastMatchWins(t *testing.T) {
+	t.Parallel()
+	co, err := ParseCodeOwners(strings.NewReader(`# Default owners
+*                 @org/core
+
+/docs/            @org/docs docs@example.com
+*.rs              @org/rust   # trailing comment
+src/gen/**        @org/codegen
+/vendor/
+`))
+	require.NoError(t, err)
+
+	assert.Equal(t, []string{"@org/core"}, co.Owners("main.go"))
+	assert.Equal(t, []string{"@org/docs", "docs@example.com"}, co.Owners("docs/guide/intro.md"))
+	assert.Equal(t, []string{"@org/rust"}, co.Owners("crates/a/lib.rs"))
+	assert.Equal(t, []string{"@org/codegen"}, co.Owners("src/gen/a/b.go"))
+	assert.Nil(t, co.Owners("vendor/lib/x.go"), "pattern without owners unassigns")
+}
+
+func TestParseCodeOwners_NoDefault(t *testing.T) {
+	t.Parallel()
+	co, err := ParseCodeOwners(strings.NewReader("api/ @org/api\n"))
+	require.NoError(t, err)
+
+	assert.Equal(t, []string{"@org/api"}, co.Owners("services/api/handler.go"), "unanchored directory matches at any depth")
+	assert.Nil(t, co.Owners("main.go"))
+}
+
+func TestIntegration_IndexDirectoryAppliesCodeOwners(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, ".github"), 0755))
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "billing"), 0755))
+	codeowners := filepath.Join(root, ".github", "CODEOWNERS")
+	require.NoError(t, os.WriteFile(codeowners, []byte("* @org/core\n/billing/ @org/billing\n"), 0644))
+
+	mainPath := writeGoFile(t, root, "main.go", "package main\n\nfunc main() {}\n")
+	billPath := writeGoFile(t, filepath.Join(root, "billing"), "bill.go", "package billing\n\nfunc Charge() {}\n")
+
+	require.NoError(t, e.IndexDirectory(ctx, root))
+
+	owners := func(path string) []string {
+		f, err := e.store.FileByPath(path)
+		require.NoError(t, err)
+		require.NotNil(t, f)
+		o, err := e.Query().FileOwners(f.ID)
+		require.NoError(t, err)
+		return o
+	}
+	assert.Equal(t, []string{"@org/core"}, owners(mainPath))
+	assert.Equal(t, []string{"@org/billing"}, owners(billPath))
+
+	// Changing CODEOWNERS alone updates ownership on the next index.
+	require.NoError(t, os.WriteFile(codeowners, []byte("* @org/platform\n"), 0644))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	assert.Equal(t, []string{"@org/platform"}, owners(billPath))
+}
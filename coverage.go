@@ -0,0 +1,142 @@
+package canopy
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"path/filepath"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// CoverageSummary describes an ingested coverage report.
+type CoverageSummary struct {
+	Files        int // source files in the report
+	MappedFiles  int // source files that are indexed
+	Lines        int // instrumented lines in indexed files
+	CoveredLines int // instrumented lines that ran
+	Symbols      int // functions and methods with instrumented lines
+}
+
+// IngestCoverage reads a coverage report in LCOV format, as written by
+// grcov, cargo llvm-cov --lcov, go tool cover via gcov2lcov, and most
+// JavaScript coverage tools, and records for each indexed function and
+// method how many of its instrumented lines ran. A line is charged to the
+// innermost function containing it, so a nested function's lines do not
+// count toward its parent. Relative source paths are taken relative to
+// root. Line counts from several records for one file are merged: a line
+// is covered if any record ran it.
+//
+// The report replaces any previous one; Coverage lists the result.
+// Re-indexing a file drops the coverage of its symbols.
+func (e *Engine) IngestCoverage(r io.Reader, root string) (*CoverageSummary, error) {
+	hits := map[string]map[int]bool{} // path -> 1-based line -> covered
+	var order []string
+	var lines map[int]bool
+
+	sc := bufio.NewScanner(r)
+	sc.Buffer(make([]byte, 64*1024), 16*1024*1024)
+	for sc.Scan() {
+		line := strings.TrimSpace(sc.Text())
+		switch {
+		case strings.HasPrefix(line, "SF:"):
+			path := strings.TrimPrefix(line, "SF:")
+			if !filepath.IsAbs(path) {
+				path = filepath.Join(root, path)
+			}
+			path = filepath.Clean(path)
+			if lines = hits[path]; lines == nil {
+				lines = map[int]bool{}
+				hits[path] = lines
+				order = append(order, path)
+			}
+		case strings.HasPrefix(line, "DA:") && lines != nil:
+			// DA:<line>,<count>[,<checksum>]
+			fields := strings.Split(strings.TrimPrefix(line, "DA:"), ",")
+			if len(fields) < 2 {
+				continue
+			}
+			n, err := strconv.Atoi(fields[0])
+			if err != nil || n <= 0 {
+				continue
+			}
+			count, err := strconv.ParseFloat(fields[1], 64) // some tools write counts as floats
+			if err != nil {
+				continue
+			}
+			lines[n] = lines[n] || count > 0
+		case line == "end_of_record":
+			lines = nil
+		}
+	}
+	if err := sc.Err(); err != nil {
+		return nil, fmt.Errorf("coverage: read: %w", err)
+	}
+
+	summary := &CoverageSummary{Files: len(order)}
+	var coverage []*SymbolCoverage
+	for _, path := range order {
+		f, err := e.store.FileByPath(path)
+		if err != nil {
+			return nil, fmt.Errorf("coverage: %s: %w", path, err)
+		}
+		if f == nil {
+			continue
+		}
+		summary.MappedFiles++
+		syms, err := e.store.SymbolsByFile(f.ID)
+		if err != nil {
+			return nil, fmt.Errorf("coverage: %s: %w", path, err)
+		}
+		funcs := syms[:0]
+		for _, s := range syms {
+			if s.Kind == "function" || s.Kind == "method" {
+				funcs = append(funcs, s)
+			}
+		}
+		// Later starts first, so the first function containing a line is the
+		// innermost one.
+		sort.SliceStable(funcs, func(i, j int) bool {
+			if funcs[i].StartLine != funcs[j].StartLine {
+				return funcs[i].StartLine > funcs[j].StartLine
+			}
+			return funcs[i].EndLine < funcs[j].EndLine
+		})
+
+		byID := map[int64]*SymbolCoverage{}
+		for n, covered := range hits[path] {
+			summary.Lines++
+			if covered {
+				summary.CoveredLines++
+			}
+			row := n - 1
+			for _, s := range funcs {
+				if s.StartLine > row || s.EndLine < row {
+					continue
+				}
+				c := byID[s.ID]
+				if c == nil {
+					c = &SymbolCoverage{SymbolID: s.ID}
+					byID[s.ID] = c
+				}
+				c.Lines++
+				if covered {
+					c.CoveredLines++
+				}
+				break
+			}
+		}
+		for _, s := range funcs {
+			if c := byID[s.ID]; c != nil {
+				coverage = append(coverage, c)
+			}
+		}
+	}
+
+	if err := e.store.ReplaceSymbolCoverage(coverage); err != nil {
+		return nil, fmt.Errorf("coverage: %w", err)
+	}
+	summary.Symbols = len(coverage)
+	return summary, nil
+}
@@ -0,0 +1,92 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestIngestCoverage(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`pub fn covered() -> u32 {
+    helper()
+}
+
+pub fn helper() -> u32 {
+    2
+}
+
+pub mod m {
+    pub fn outer() {
+        fn inner() {}
+        inner();
+    }
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	// Records for one file are merged, whether its path is relative or not.
+	lcov := `TN:
+SF:src/lib.rs
+DA:1,4
+DA:2,4
+DA:3,4
+DA:5,0
+DA:6,0
+DA:7,0
+end_of_record
+SF:` + filepath.Join(root, "src", "lib.rs") + `
+DA:9,1
+DA:10,0
+DA:11,0
+DA:12,0
+DA:13,0
+end_of_record
+SF:src/missing.rs
+DA:1,1
+end_of_record
+`
+	summary, err := e.IngestCoverage(strings.NewReader(lcov), root)
+	require.NoError(t, err)
+	assert.Equal(t, &CoverageSummary{Files: 2, MappedFiles: 1, Lines: 11, CoveredLines: 4, Symbols: 4}, summary)
+
+	result, err := e.Query().Coverage(SymbolFilter{}, CoverageFilter{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 4, result.TotalCount)
+	got := map[string][2]int{}
+	for _, c := range result.Items {
+		got[c.Symbol.Name] = [2]int{c.Coverage.CoveredLines, c.Coverage.Lines}
+	}
+	// inner's line is not charged to outer.
+	assert.Equal(t, map[string][2]int{
+		"covered": {3, 3},
+		"helper":  {0, 3},
+		"outer":   {0, 3},
+		"inner":   {0, 1},
+	}, got)
+	assert.Equal(t, "covered", result.Items[len(result.Items)-1].Symbol.Name, "least covered first")
+
+	public := "public"
+	maxPercent := 20.0
+	minCallers := 1
+	result, err = e.Query().Coverage(
+		SymbolFilter{Kinds: []string{"function"}, Visibility: &public},
+		CoverageFilter{MaxPercent: &maxPercent, MinCallers: &minCallers},
+		Pagination{},
+	)
+	require.NoError(t, err)
+	require.Len(t, result.Items, 1)
+	assert.Equal(t, "helper", result.Items[0].Symbol.Name)
+	assert.Equal(t, 1, result.Items[0].Callers)
+	assert.Zero(t, result.Items[0].Percent)
+}
@@ -0,0 +1,408 @@
+package canopy
+
+import (
+	"fmt"
+	"math"
+	"path/filepath"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy/internal/runtime"
+)
+
+// Steps of IndexDirectory left out once the WithDeadline deadline passes.
+const (
+	StepBuildOutput    = "build script output"
+	StepMacroExpansion = "macro expansion"
+	StepBlame          = "blame"
+)
+
+// WithDeadline makes IndexDirectory index on a best-effort basis until
+// deadline, for callers such as agents that need a usable graph quickly
+// rather than a complete one. Files are extracted most important first
+// (see rankFiles), and when the deadline passes extraction stops between
+// files without an error; the files already extracted stay indexed, and
+// the next run extracts the rest. Build script output, macro expansion,
+// and blame are left out once the deadline has passed. Resolution is not
+// bounded: Resolve links whatever was extracted. Completeness reports what
+// the index covers.
+func WithDeadline(deadline time.Time) Option {
+	return func(e *Engine) {
+		e.deadline = deadline
+	}
+}
+
+// WithPriority makes IndexDirectory extract the given files first, then
+// the files they import, nearest first, ahead of the usual order of
+// rankFiles. A directory stands for the files under it. Imports are those
+// of the previous index: a file not indexed before has its imports found
+// once it is, by the next run. With WithDeadline, a server can index in
+// short runs while answering queries, moving the files each query needs
+// to the front.
+func WithPriority(paths ...string) Option {
+	return func(e *Engine) {
+		e.priority = append(e.priority, paths...)
+	}
+}
+
+// IndexCompleteness reports how much of the discovered code the
+// IndexDirectory calls of an Engine with WithDeadline covered.
+type IndexCompleteness struct {
+	Deadline time.Time
+	Files    int // source files discovered in the indexed languages
+	Indexed  int // of Files, those extracted or found unchanged
+	// Pending lists the files extraction did not reach, most important
+	// first. Those indexed by an earlier run keep their older symbols; the
+	// rest are missing from the graph.
+	Pending []string
+	Stale   int      // of Pending, the files indexed by an earlier run
+	Skipped []string // steps left out past the deadline (Step* constants)
+}
+
+// Complete reports whether every discovered file was indexed and no step
+// was left out.
+func (c IndexCompleteness) Complete() bool {
+	return len(c.Pending) == 0 && len(c.Skipped) == 0
+}
+
+// Coverage returns the fraction of Files indexed, 1 when there are none.
+func (c IndexCompleteness) Coverage() float64 {
+	if c.Files == 0 {
+		return 1
+	}
+	return float64(c.Indexed) / float64(c.Files)
+}
+
+// Completeness returns what the IndexDirectory calls so far covered under
+// WithDeadline. Without a deadline it is the zero value.
+func (e *Engine) Completeness() IndexCompleteness {
+	c := e.completeness
+	c.Pending = slices.Clone(c.Pending)
+	c.Skipped = slices.Clone(c.Skipped)
+	return c
+}
+
+// pastDeadline reports whether a WithDeadline deadline has passed.
+func (e *Engine) pastDeadline() bool {
+	return !e.deadline.IsZero() && !time.Now().Before(e.deadline)
+}
+
+// skipStep records that step was left out past the deadline.
+func (e *Engine) skipStep(step string) {
+	if !slices.Contains(e.completeness.Skipped, step) {
+		e.completeness.Skipped = append(e.completeness.Skipped, step)
+	}
+}
+
+// recordCompleteness adds the files of one IndexDirectory call to the
+// completeness report. paths are in the order they were extracted, of
+// which the first e.extracted were reached.
+func (e *Engine) recordCompleteness(paths []string) error {
+	c := &e.completeness
+	c.Deadline = e.deadline
+	for i, path := range paths {
+		lang, ok := runtime.LanguageForFile(path)
+		if !ok || (e.languages != nil && !e.languages[lang]) {
+			continue
+		}
+		c.Files++
+		if i < e.extracted {
+			c.Indexed++
+			continue
+		}
+		c.Pending = append(c.Pending, path)
+		f, err := e.store.FileByPath(path)
+		if err != nil {
+			return fmt.Errorf("completeness: %w", err)
+		}
+		if f != nil {
+			c.Stale++
+		}
+	}
+	return nil
+}
+
+// entryPointFiles are the file names that start a crate, package, or
+// program, and are extracted first under a deadline.
+var entryPointFiles = map[string]bool{
+	"main.rs":     true,
+	"lib.rs":      true,
+	"main.go":     true,
+	"main.py":     true,
+	"__main__.py": true,
+	"__init__.py": true,
+	"index.ts":    true,
+	"index.tsx":   true,
+	"index.js":    true,
+}
+
+// rankFiles orders the paths found under root by importance, for
+// extraction under a deadline: the WithPriority files and their import
+// closure, then entry points (main.rs, lib.rs, src/bin
+// targets, main.go, index.ts, ...) first, then the files most other files
+// referred to in the previous index, then shallower paths before deeper
+// ones. Test files come last. A first index has no previous references to
+// count and goes by entry points and depth alone.
+func (e *Engine) rankFiles(root string, paths []string) ([]string, error) {
+	fanIn := map[string]int{}
+	rows, err := e.store.DB().Query(
+		`SELECT f.path, COUNT(DISTINCT r.file_id)
+		 FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 JOIN files f ON f.id = s.file_id
+		 WHERE r.file_id != s.file_id
+		 GROUP BY f.path`)
+	if err != nil {
+		return nil, fmt.Errorf("rank files: %w", err)
+	}
+	for rows.Next() {
+		var path string
+		var n int
+		if err := rows.Scan(&path, &n); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("rank files: %w", err)
+		}
+		fanIn[path] = n
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("rank files: %w", err)
+	}
+
+	demand, err := e.demandRanks(paths)
+	if err != nil {
+		return nil, err
+	}
+
+	type ranked struct {
+		path   string
+		rel    string
+		demand int
+		entry  bool
+		test   bool
+		depth  int
+	}
+	files := make([]ranked, len(paths))
+	for i, p := range paths {
+		rel, err := filepath.Rel(root, p)
+		if err != nil {
+			rel = p
+		}
+		rel = "/" + filepath.ToSlash(rel)
+		d, ok := demand[p]
+		if !ok {
+			d = math.MaxInt
+		}
+		files[i] = ranked{
+			path:   p,
+			rel:    rel,
+			demand: d,
+			entry:  entryPointFiles[filepath.Base(p)] || (strings.Contains(rel, "/src/bin/") && strings.HasSuffix(rel, ".rs")),
+			test:   strings.Contains(rel, "/tests/") || strings.HasSuffix(rel, "_test.go"),
+			depth:  strings.Count(rel, "/"),
+		}
+	}
+	slices.SortStableFunc(files, func(a, b ranked) int {
+		switch {
+		case a.demand != b.demand:
+			if a.demand < b.demand {
+				return -1
+			}
+			return 1
+		case a.test != b.test:
+			if a.test {
+				return 1
+			}
+			return -1
+		case a.entry != b.entry:
+			if a.entry {
+				return -1
+			}
+			return 1
+		case fanIn[a.path] != fanIn[b.path]:
+			return fanIn[b.path] - fanIn[a.path]
+		case a.depth != b.depth:
+			return a.depth - b.depth
+		}
+		return strings.Compare(a.rel, b.rel)
+	})
+	out := make([]string, len(files))
+	for i, f := range files {
+		out[i] = f.path
+	}
+	return out, nil
+}
+
+// demandRanks returns the rank of the paths the WithPriority files demand:
+// 0 for those files, and for the files they import, directly or not, the
+// number of imports away. Paths demanded by no one are left out.
+func (e *Engine) demandRanks(paths []string) (map[string]int, error) {
+	ranks := map[string]int{}
+	if len(e.priority) == 0 {
+		return ranks, nil
+	}
+	var queue []string
+	for _, p := range paths {
+		for _, want := range e.priority {
+			want = filepath.Clean(want)
+			if p == want || strings.HasPrefix(p, want+string(filepath.Separator)) {
+				ranks[p] = 0
+				queue = append(queue, p)
+				break
+			}
+		}
+	}
+	candidates := importCandidates(paths, e.rootFor)
+	for len(queue) > 0 {
+		path := queue[0]
+		queue = queue[1:]
+		next, err := e.importedFiles(path, candidates)
+		if err != nil {
+			return nil, err
+		}
+		for _, n := range next {
+			if _, seen := ranks[n]; !seen {
+				ranks[n] = ranks[path] + 1
+				queue = append(queue, n)
+			}
+		}
+	}
+	return ranks, nil
+}
+
+// importedFiles returns the candidate files path imports in the index: the
+// files its resolved references point into, and the files whose module
+// path matches one of its import declarations (see importCandidates), which
+// covers imports that did not resolve because their target was not indexed
+// yet.
+func (e *Engine) importedFiles(path string, candidates map[string][]importCandidate) ([]string, error) {
+	var files []string
+	rows, err := e.store.DB().Query(
+		`SELECT DISTINCT tf.path FROM files f
+		 JOIN references_ r ON r.file_id = f.id
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 JOIN files tf ON tf.id = s.file_id
+		 WHERE f.path = ? AND tf.id != f.id
+		 ORDER BY tf.path`, path)
+	if err != nil {
+		return nil, fmt.Errorf("import closure: %w", err)
+	}
+	for rows.Next() {
+		var target string
+		if err := rows.Scan(&target); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("import closure: %w", err)
+		}
+		files = append(files, target)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("import closure: %w", err)
+	}
+
+	rows, err = e.store.DB().Query(
+		`SELECT i.source FROM imports i JOIN files f ON f.id = i.file_id WHERE f.path = ? ORDER BY i.id`, path)
+	if err != nil {
+		return nil, fmt.Errorf("import closure: %w", err)
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var source string
+		if err := rows.Scan(&source); err != nil {
+			return nil, fmt.Errorf("import closure: %w", err)
+		}
+		segs := importSegments(source)
+		for j, seg := range segs {
+			for _, c := range candidates[seg] {
+				if c.path != path && c.matches(segs[:j+1]) && !slices.Contains(files, c.path) {
+					files = append(files, c.path)
+				}
+			}
+		}
+	}
+	return files, rows.Err()
+}
+
+// importCandidate is a file an import declaration may name, with the
+// components of its module path relative to its root: the path without
+// extension, less a mod.rs, index.ts, or __init__.py file name, and for Go
+// the package directory.
+type importCandidate struct {
+	path   string
+	module []string
+}
+
+// importCandidates indexes the files among paths by the last component of
+// their module path.
+func importCandidates(paths []string, rootFor func(string) string) map[string][]importCandidate {
+	byLast := map[string][]importCandidate{}
+	for _, p := range paths {
+		lang, ok := runtime.LanguageForFile(p)
+		if !ok {
+			continue
+		}
+		rel := p
+		if root := rootFor(p); root != "" {
+			if r, err := filepath.Rel(root, p); err == nil {
+				rel = r
+			}
+		}
+		rel = filepath.ToSlash(rel)
+		if lang == "go" {
+			rel = filepath.ToSlash(filepath.Dir(rel))
+		} else {
+			rel = strings.TrimSuffix(rel, filepath.Ext(rel))
+			switch filepath.Base(rel) {
+			case "mod", "index", "__init__":
+				rel = filepath.ToSlash(filepath.Dir(rel))
+			}
+		}
+		if rel == "." || rel == "" {
+			continue
+		}
+		module := strings.Split(rel, "/")
+		last := module[len(module)-1]
+		byLast[last] = append(byLast[last], importCandidate{path: p, module: module})
+	}
+	return byLast
+}
+
+// matches reports whether an import whose module path ends in segs names
+// the candidate: the trailing components of one match those of the other
+// for as long as both last. std::io matches neither src/io.rs nor
+// src/std.rs, while crate::net::tcp matches src/net/tcp.rs.
+func (c importCandidate) matches(segs []string) bool {
+	for i := 1; i <= len(segs) && i <= len(c.module); i++ {
+		if segs[len(segs)-i] != c.module[len(c.module)-i] {
+			return false
+		}
+	}
+	return true
+}
+
+// importSegments splits the source of an import declaration into the
+// components of its module path: crate::net::tcp::Conn, "./net/tcp", and
+// net.tcp all give net, tcp, and the imported name if any. Path keywords
+// such as crate, super, and .. are dropped.
+func importSegments(source string) []string {
+	source = strings.Trim(source, "\"'`")
+	sep := "::"
+	switch {
+	case strings.Contains(source, "/"):
+		sep = "/"
+	case !strings.Contains(source, "::") && strings.Contains(source, "."):
+		sep = "."
+	}
+	var segs []string
+	for _, seg := range strings.Split(source, sep) {
+		switch seg {
+		case "", ".", "..", "crate", "self", "super", "*":
+			continue
+		}
+		segs = append(segs, seg)
+	}
+	return segs
+}
@@ -0,0 +1,119 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"slices"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestIndexDeadline(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"app\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "pub mod util;\npub mod net;\n")
+	write("src/util.rs", "pub fn helper() {}\n")
+	write("src/net/mod.rs", "pub fn connect() { crate::util::helper(); }\n")
+	write("tests/it.rs", "#[test]\nfn works() { app::util::helper(); }\n")
+
+	t.Run("past deadline", func(t *testing.T) {
+		e := newIntegrationEngine(t, WithLanguages("rust"), WithDeadline(time.Now().Add(-time.Second)))
+		require.NoError(t, e.IndexDirectory(ctx, root), "the deadline is not an error")
+		require.NoError(t, e.Resolve(ctx))
+
+		c := e.Completeness()
+		assert.False(t, c.Complete())
+		assert.Equal(t, 4, c.Files)
+		assert.Equal(t, 0, c.Indexed)
+		assert.Equal(t, 0.0, c.Coverage())
+		assert.Equal(t, []string{
+			filepath.Join(root, "src", "lib.rs"),
+			filepath.Join(root, "src", "util.rs"),
+			filepath.Join(root, "src", "net", "mod.rs"),
+			filepath.Join(root, "tests", "it.rs"),
+		}, c.Pending, "entry points first, then by depth, tests last")
+		assert.Equal(t, 0, c.Stale)
+
+		files, err := e.Query().Files("", "", Sort{}, Pagination{})
+		require.NoError(t, err)
+		assert.Empty(t, files.Items, "nothing reached, nothing left half-indexed")
+	})
+
+	t.Run("within deadline", func(t *testing.T) {
+		e := newIntegrationEngine(t, WithLanguages("rust"), WithDeadline(time.Now().Add(time.Minute)))
+		require.NoError(t, e.IndexDirectory(ctx, root))
+		require.NoError(t, e.Resolve(ctx))
+
+		c := e.Completeness()
+		assert.True(t, c.Complete())
+		assert.Equal(t, 4, c.Files)
+		assert.Equal(t, 4, c.Indexed)
+		assert.Equal(t, 1.0, c.Coverage())
+
+		// With a previous index, files referred to from more files rank
+		// ahead of shallower or alphabetically earlier ones.
+		write("src/a.rs", "pub fn a() {}\n")
+		lib := filepath.Join(root, "src", "lib.rs")
+		util := filepath.Join(root, "src", "util.rs")
+		a := filepath.Join(root, "src", "a.rs")
+		net := filepath.Join(root, "src", "net", "mod.rs")
+		it := filepath.Join(root, "tests", "it.rs")
+		ranked, err := e.rankFiles(root, []string{it, a, net, util, lib})
+		require.NoError(t, err)
+		assert.Equal(t, lib, ranked[0])
+		assert.Less(t, slices.Index(ranked, util), slices.Index(ranked, a))
+		assert.Equal(t, it, ranked[4])
+
+		// Demanded files and what they import come before everything else.
+		e.priority = []string{filepath.Join(root, "src", "net")}
+		ranked, err = e.rankFiles(root, ranked)
+		require.NoError(t, err)
+		assert.Equal(t, net, ranked[0])
+		assert.Less(t, slices.Index(ranked, util), slices.Index(ranked, a), "util.rs is imported")
+		assert.Equal(t, it, ranked[4])
+	})
+}
+
+func TestImportCandidates(t *testing.T) {
+	root := filepath.Join("/", "repo")
+	paths := []string{
+		filepath.Join(root, "src", "net", "tcp.rs"),
+		filepath.Join(root, "src", "io", "mod.rs"),
+		filepath.Join(root, "web", "util", "index.ts"),
+		filepath.Join(root, "internal", "store", "store.go"),
+		filepath.Join(root, "pkg", "models.py"),
+	}
+	candidates := importCandidates(paths, func(string) string { return root })
+	names := func(source string) []string {
+		var found []string
+		segs := importSegments(source)
+		for j, seg := range segs {
+			for _, c := range candidates[seg] {
+				if c.matches(segs[:j+1]) && !slices.Contains(found, c.path) {
+					found = append(found, c.path)
+				}
+			}
+		}
+		return found
+	}
+
+	assert.Equal(t, []string{"net", "tcp", "Conn"}, importSegments("crate::net::tcp::Conn"))
+	assert.Equal(t, []string{"util"}, importSegments("'../util'"))
+	assert.Equal(t, []string{paths[0]}, names("crate::net::tcp::Conn"))
+	assert.Equal(t, []string{paths[0]}, names("super::tcp::Conn"))
+	assert.Empty(t, names("std::io::Read"), "std::io is not src/io")
+	assert.Equal(t, []string{paths[1]}, names("crate::io::Reader"))
+	assert.Equal(t, []string{paths[2]}, names("../util"))
+	assert.Equal(t, []string{paths[3]}, names("github.com/jward/canopy/internal/store"))
+	assert.Equal(t, []string{paths[4]}, names("pkg.models"))
+}
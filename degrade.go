@@ -0,0 +1,501 @@
+package canopy
+
+import (
+	"bytes"
+	"fmt"
+	"regexp"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// ModifierFallback marks a symbol found by the fallback scanner rather than
+// by extraction. Its span is approximate and it has no parameters, members,
+// or references of its own. Resolutions to or from it are capped at
+// heuristic confidence.
+const ModifierFallback = "fallback"
+
+// Reasons a file is degraded, recorded for each file the fallback scanner
+// added symbols to and counted by IndexStats.
+const (
+	DegradedExtractFailed = "extract_failed" // the extraction script failed; every symbol is from the scanner
+	DegradedSyntaxErrors  = "syntax_errors"  // syntax errors cover more of the file than the policy allows
+	DegradedMacros        = "macros"         // declarations inside Rust macro invocations
+)
+
+// DefaultDegradeErrorShare is the share of a file's bytes its syntax errors
+// may cover before WithDegradation scans it.
+const DefaultDegradeErrorShare = 0.2
+
+// fallbackConfidence is the confidence resolutions to and from fallback
+// symbols are capped at: below ConfidenceProbable.
+const fallbackConfidence = 0.5
+
+// WithDegradation enables the fallback scanner for files extraction cannot
+// fully handle. Rather than leaving such a file out, IndexFiles adds the
+// declarations a line-based scanner finds in it, marked ModifierFallback:
+// every declaration of a file whose extraction script fails, those missing
+// from a file whose syntax errors cover more than errorShare of its bytes
+// (0 means DefaultDegradeErrorShare), and those inside Rust macro
+// invocations such as lazy_static! and bitflags!, which extraction skips.
+// Without it, a file whose extraction fails is an indexing error.
+func WithDegradation(errorShare float64) Option {
+	return func(e *Engine) {
+		if errorShare <= 0 {
+			errorShare = DefaultDegradeErrorShare
+		}
+		e.degradeErrorShare = errorShare
+	}
+}
+
+// degradeFile adds the declarations the fallback scanner finds in a file
+// that extraction failed on (extractFailed), or whose syntax errors cover
+// too much of it, or that has Rust macro invocations, and records the file
+// as degraded if it found any. Must run after recordSpanOffsets. A no-op
+// without WithDegradation.
+func (e *Engine) degradeFile(fileID int64, lang string, content []byte, errs []runtime.ErrorSpan, extractFailed bool) error {
+	if e.degradeErrorShare <= 0 {
+		return nil
+	}
+	reason := DegradedMacros
+	var within [][2]int // byte ranges to keep declarations from; nil keeps all
+	switch {
+	case extractFailed:
+		reason = DegradedExtractFailed
+	case errorShare(errs, len(content)) > e.degradeErrorShare:
+		reason = DegradedSyntaxErrors
+	case lang == "rust":
+		within = rustMacroBodies(content)
+		if len(within) == 0 {
+			return nil
+		}
+	default:
+		return nil
+	}
+
+	decls := scanDeclarations(lang, content)
+	if len(decls) == 0 {
+		return nil
+	}
+	existing, err := e.store.SymbolsByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load symbols: %w", err)
+	}
+
+	li := newLineIndex(content)
+	ids := make([]int64, len(decls)) // symbol of each declaration, 0 if none
+	added := 0
+	for i, d := range decls {
+		if within != nil && !inRanges(within, d.nameByte) {
+			continue
+		}
+		if sym := extractedAs(existing, d); sym != nil {
+			ids[i] = sym.ID
+			continue
+		}
+		sym := &store.Symbol{
+			FileID:     &fileID,
+			Name:       d.name,
+			Kind:       d.kind,
+			Visibility: d.visibility,
+			Modifiers:  []string{ModifierFallback},
+			StartLine:  d.line,
+			StartCol:   d.indent,
+			EndLine:    d.endLine,
+			EndCol:     d.endCol,
+		}
+		if d.parent >= 0 && ids[d.parent] != 0 {
+			sym.ParentSymbolID = &ids[d.parent]
+		}
+		sym.StartByte, sym.UTF16StartCol = li.offsets(sym.StartLine, sym.StartCol)
+		sym.EndByte, sym.UTF16EndCol = li.offsets(sym.EndLine, sym.EndCol)
+		if ids[i], err = e.store.InsertSymbol(sym); err != nil {
+			return err
+		}
+		added++
+	}
+	if added == 0 && !extractFailed {
+		return nil
+	}
+	return e.store.SetFileDegraded(fileID, reason, added)
+}
+
+// capFallbackConfidence lowers the confidence of the resolutions and call
+// edges that involve fallback symbols to fallbackConfidence.
+func (e *Engine) capFallbackConfidence() error {
+	db := e.store.DB()
+	var degraded bool
+	if err := db.QueryRow("SELECT EXISTS (SELECT 1 FROM degraded_files)").Scan(&degraded); err != nil {
+		return fmt.Errorf("cap fallback confidence: %w", err)
+	}
+	if !degraded {
+		return nil
+	}
+	const fallbackSymbols = `SELECT s.id FROM symbols s
+		 JOIN degraded_files d ON d.file_id = s.file_id
+		 WHERE EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)`
+	for _, q := range []struct {
+		query string
+		args  []any
+	}{
+		{"UPDATE resolved_references SET confidence = ? WHERE confidence > ? AND target_symbol_id IN (" + fallbackSymbols + ")",
+			[]any{fallbackConfidence, fallbackConfidence, ModifierFallback}},
+		{"UPDATE call_graph SET confidence = ? WHERE confidence > ? AND (callee_symbol_id IN (" + fallbackSymbols + ") OR caller_symbol_id IN (" + fallbackSymbols + "))",
+			[]any{fallbackConfidence, fallbackConfidence, ModifierFallback, ModifierFallback}},
+	} {
+		if _, err := db.Exec(q.query, q.args...); err != nil {
+			return fmt.Errorf("cap fallback confidence: %w", err)
+		}
+	}
+	return nil
+}
+
+// errorShare returns the share of a file of size bytes its syntax errors
+// cover.
+func errorShare(errs []runtime.ErrorSpan, size int) float64 {
+	if size == 0 {
+		return 0
+	}
+	covered := 0
+	for _, span := range errs {
+		covered += span.EndByte - span.StartByte
+	}
+	return min(float64(covered)/float64(size), 1)
+}
+
+// extractedAs returns the extracted symbol a scanned declaration duplicates:
+// one of the same name whose span holds the declaration's name. nil if
+// there is none.
+func extractedAs(existing []*store.Symbol, d fallbackDecl) *store.Symbol {
+	for _, sym := range existing {
+		if sym.Name == d.name && sym.StartLine <= d.line && d.line <= sym.EndLine {
+			return sym
+		}
+	}
+	return nil
+}
+
+func inRanges(ranges [][2]int, offset int) bool {
+	for _, r := range ranges {
+		if r[0] <= offset && offset < r[1] {
+			return true
+		}
+	}
+	return false
+}
+
+// fallbackDecl is a declaration found by the fallback scanner.
+type fallbackDecl struct {
+	name, kind, visibility string
+	line                   int // 0-based line of the declaration
+	indent                 int // byte column of its first non-blank byte
+	nameByte               int // byte offset of its name in the file
+	endLine, endCol        int // approximate end, from indentation
+	parent                 int // index of the enclosing declaration, -1 if none
+}
+
+// declPattern matches a declaration at the start of a line, after its
+// indentation. Its "name" group is the declared name.
+type declPattern struct {
+	kind string
+	re   *regexp.Regexp
+}
+
+func decl(kind, pattern string) declPattern {
+	return declPattern{kind: kind, re: regexp.MustCompile(pattern)}
+}
+
+// Shared prefixes of declaration patterns.
+const (
+	rustVis   = `^(?:pub(?:\([^)]*\))?\s+)?`
+	jsExport  = `^(?:export\s+)?(?:default\s+)?(?:declare\s+)?`
+	javaMods  = `^(?:(?:public|protected|private|internal|static|final|abstract|sealed|open|data|inline|value|enum|annotation)\s+)*`
+	swiftMods = `^(?:(?:public|private|fileprivate|internal|open|final|static|class|override|mutating|@\w+)\s+)*`
+)
+
+// declPatterns are the declarations the fallback scanner finds, by
+// language, tried in order.
+var declPatterns = map[string][]declPattern{
+	"rust": {
+		decl("function", rustVis+`(?:default\s+)?(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*fn\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("struct", rustVis+`(?:struct|union)\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("enum", rustVis+`enum\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("trait", rustVis+`(?:unsafe\s+)?(?:auto\s+)?trait\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("type_alias", rustVis+`type\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("constant", rustVis+`const\s+(?P<name>[A-Za-z_]\w*)\s*:`),
+		decl("variable", rustVis+`static\s+(?:ref\s+|mut\s+)?(?P<name>[A-Za-z_]\w*)\s*:`),
+		decl("module", rustVis+`mod\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"go": {
+		decl("method", `^func\s+\([^)]*\)\s*(?P<name>[A-Za-z_]\w*)`),
+		decl("function", `^func\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("struct", `^type\s+(?P<name>[A-Za-z_]\w*)(?:\[[^\]]*\])?\s+struct\b`),
+		decl("interface", `^type\s+(?P<name>[A-Za-z_]\w*)(?:\[[^\]]*\])?\s+interface\b`),
+		decl("type_alias", `^type\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("constant", `^const\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("variable", `^var\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"python": {
+		decl("function", `^(?:async\s+)?def\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("class", `^class\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"ruby": {
+		decl("function", `^def\s+(?:self\.)?(?P<name>[A-Za-z_]\w*[?!=]?)`),
+		decl("class", `^class\s+(?P<name>[A-Z]\w*)`),
+		decl("module", `^module\s+(?P<name>[A-Z]\w*)`),
+	},
+	"javascript": {
+		decl("function", jsExport+`(?:async\s+)?function\s*\*?\s*(?P<name>[A-Za-z_$][\w$]*)`),
+		decl("class", jsExport+`class\s+(?P<name>[A-Za-z_$][\w$]*)`),
+	},
+	"typescript": {
+		decl("function", jsExport+`(?:async\s+)?function\s*\*?\s*(?P<name>[A-Za-z_$][\w$]*)`),
+		decl("class", jsExport+`(?:abstract\s+)?class\s+(?P<name>[A-Za-z_$][\w$]*)`),
+		decl("interface", jsExport+`interface\s+(?P<name>[A-Za-z_$][\w$]*)`),
+		decl("type_alias", jsExport+`type\s+(?P<name>[A-Za-z_$][\w$]*)\s*(?:<[^=]*>)?\s*=`),
+		decl("enum", jsExport+`(?:const\s+)?enum\s+(?P<name>[A-Za-z_$][\w$]*)`),
+	},
+	"c": {
+		decl("struct", `^(?:typedef\s+)?(?:struct|union)\s+(?P<name>[A-Za-z_]\w*)\s*\{`),
+		decl("enum", `^(?:typedef\s+)?enum\s+(?P<name>[A-Za-z_]\w*)\s*\{`),
+		decl("function", `^(?:[A-Za-z_][\w*]*\s+\**)+(?P<name>[A-Za-z_]\w*)\s*\([^;]*$`),
+	},
+	"cpp": {
+		decl("namespace", `^namespace\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("class", `^(?:template\s*<.*>\s*)?class\s+(?P<name>[A-Za-z_]\w*)\s*(?:final\s*)?[:{]`),
+		decl("struct", `^(?:template\s*<.*>\s*)?(?:typedef\s+)?(?:struct|union)\s+(?P<name>[A-Za-z_]\w*)\s*(?:final\s*)?[:{]`),
+		decl("enum", `^(?:typedef\s+)?enum\s+(?:class\s+|struct\s+)?(?P<name>[A-Za-z_]\w*)\s*[:{]`),
+		decl("function", `^(?:[A-Za-z_][\w*&:<>,]*\s+[*&]*)+(?P<name>[A-Za-z_~]\w*)\s*\([^;]*$`),
+	},
+	"java": {
+		decl("class", javaMods+`(?:class|record)\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("interface", javaMods+`@?interface\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("enum", javaMods+`enum\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("method", `^(?:(?:public|protected|private|static|final|abstract|synchronized|native|default)\s+)+(?:<[^>]*>\s*)?[\w<>\[\],.? ]+\s+(?P<name>[A-Za-z_]\w*)\s*\(`),
+	},
+	"kotlin": {
+		decl("function", javaMods+`(?:suspend\s+|override\s+|operator\s+|infix\s+)*fun\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>[A-Za-z_]\w*)`),
+		decl("interface", javaMods+`(?:fun\s+)?interface\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("class", javaMods+`(?:class|object)\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"swift": {
+		decl("function", swiftMods+`func\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("class", swiftMods+`(?:class|actor)\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("struct", swiftMods+`struct\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("enum", swiftMods+`enum\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("interface", swiftMods+`protocol\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"php": {
+		decl("function", `^(?:(?:public|protected|private|static|final|abstract)\s+)*function\s+&?(?P<name>[A-Za-z_]\w*)`),
+		decl("class", `^(?:(?:final|abstract|readonly)\s+)*class\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("interface", `^interface\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("trait", `^trait\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+	"protobuf": {
+		decl("message", `^message\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("service", `^service\s+(?P<name>[A-Za-z_]\w*)`),
+		decl("enum", `^enum\s+(?P<name>[A-Za-z_]\w*)`),
+	},
+}
+
+// notDeclared are words the C and C++ function patterns would take for a
+// return type or a name in a statement.
+var notDeclared = map[string]bool{
+	"return": true, "if": true, "else": true, "while": true, "for": true, "switch": true,
+	"case": true, "do": true, "sizeof": true, "new": true, "delete": true, "throw": true,
+	"goto": true, "typedef": true, "using": true,
+}
+
+// containers are the kinds whose functions the scanner reports as methods.
+var containers = map[string]bool{
+	"class": true, "struct": true, "interface": true, "trait": true, "enum": true,
+}
+
+// scanDeclarations finds the declarations of a file line by line, for the
+// files extraction cannot handle. A declaration is taken to end before the
+// next non-blank line indented no deeper, including that line if it closes
+// the declaration ("}", ")", "]", or "end"), and to be nested in the
+// declaration whose span holds it.
+func scanDeclarations(lang string, content []byte) []fallbackDecl {
+	patterns := declPatterns[lang]
+	if len(patterns) == 0 {
+		return nil
+	}
+	lines := bytes.Split(content, []byte{'\n'})
+	indents := make([]int, len(lines)) // -1 for blank lines
+	offset := 0
+	var decls []fallbackDecl
+	for i, raw := range lines {
+		lineStart := offset
+		offset += len(raw) + 1
+		line := strings.TrimRight(string(raw), "\r")
+		text := strings.TrimLeft(line, " \t")
+		if text == "" {
+			indents[i] = -1
+			continue
+		}
+		indent := len(line) - len(text)
+		indents[i] = indent
+		for _, p := range patterns {
+			m := p.re.FindStringSubmatchIndex(text)
+			if m == nil {
+				continue
+			}
+			g := p.re.SubexpIndex("name")
+			name := text[m[2*g]:m[2*g+1]]
+			if notDeclared[name] || notDeclared[firstWord(text)] {
+				break
+			}
+			decls = append(decls, fallbackDecl{
+				name:       name,
+				kind:       p.kind,
+				visibility: fallbackVisibility(lang, text, name),
+				line:       i,
+				indent:     indent,
+				nameByte:   lineStart + indent + m[2*g],
+				parent:     -1,
+			})
+			break
+		}
+	}
+
+	for i := range decls {
+		d := &decls[i]
+		d.endLine, d.endCol = d.line, len(strings.TrimRight(string(lines[d.line]), "\r"))
+		for j := d.line + 1; j < len(lines); j++ {
+			if indents[j] < 0 {
+				continue
+			}
+			if indents[j] <= d.indent {
+				if closesBlock(strings.TrimSpace(string(lines[j]))) {
+					d.endLine, d.endCol = j, len(strings.TrimRight(string(lines[j]), "\r"))
+				}
+				break
+			}
+			d.endLine, d.endCol = j, len(strings.TrimRight(string(lines[j]), "\r"))
+		}
+		for p := i - 1; p >= 0; p-- {
+			if decls[p].indent < d.indent && decls[p].endLine >= d.line {
+				d.parent = p
+				if d.kind == "function" && containers[decls[p].kind] {
+					d.kind = "method"
+				}
+				break
+			}
+		}
+	}
+	return decls
+}
+
+// closesBlock reports whether a line closes the block above it.
+func closesBlock(text string) bool {
+	return strings.HasPrefix(text, "}") || strings.HasPrefix(text, ")") ||
+		strings.HasPrefix(text, "]") || text == "end" || strings.HasPrefix(text, "end ")
+}
+
+func firstWord(text string) string {
+	end := strings.IndexFunc(text, func(r rune) bool { return !unicode.IsLetter(r) && r != '_' })
+	if end < 0 {
+		return text
+	}
+	return text[:end]
+}
+
+// fallbackVisibility guesses the visibility of a scanned declaration from
+// the language's conventions.
+func fallbackVisibility(lang, text, name string) string {
+	var private bool
+	switch lang {
+	case "rust":
+		private = !strings.HasPrefix(text, "pub")
+	case "go":
+		r, _ := utf8.DecodeRuneInString(name)
+		private = !unicode.IsUpper(r)
+	case "python":
+		private = strings.HasPrefix(name, "_")
+	default:
+		private = strings.HasPrefix(text, "private ") || strings.HasPrefix(text, "fileprivate ") ||
+			strings.Contains(text, " private ")
+	}
+	if private {
+		return "private"
+	}
+	return "public"
+}
+
+// macroInvocationRe matches the start of a Rust macro invocation: its name,
+// "!", and opening delimiter.
+var macroInvocationRe = regexp.MustCompile(`\b([A-Za-z_]\w*)!\s*[({\[]`)
+
+// rustMacroBodies returns the byte ranges of the bodies of the macro
+// invocations in a Rust file, other than macro_rules! definitions, whose
+// bodies are templates. Delimiters inside strings, character literals, and
+// comments are skipped.
+func rustMacroBodies(content []byte) [][2]int {
+	var bodies [][2]int
+	end := 0
+	for _, m := range macroInvocationRe.FindAllSubmatchIndex(content, -1) {
+		open := m[1] - 1
+		if open < end || string(content[m[2]:m[3]]) == "macro_rules" {
+			continue // inside the last body, or a definition
+		}
+		lineStart := bytes.LastIndexByte(content[:m[0]], '\n') + 1
+		if bytes.Contains(content[lineStart:m[0]], []byte("//")) {
+			continue
+		}
+		closeAt := matchDelimiter(content, open)
+		if closeAt < 0 {
+			continue
+		}
+		bodies = append(bodies, [2]int{open + 1, closeAt})
+		end = closeAt
+	}
+	return bodies
+}
+
+// matchDelimiter returns the offset of the delimiter closing the one at
+// open, or -1 if it is not closed.
+func matchDelimiter(content []byte, open int) int {
+	depth := 0
+	for i := open; i < len(content); i++ {
+		switch c := content[i]; c {
+		case '(', '{', '[':
+			depth++
+		case ')', '}', ']':
+			depth--
+			if depth == 0 {
+				return i
+			}
+		case '"':
+			for i++; i < len(content) && content[i] != '"'; i++ {
+				if content[i] == '\\' {
+					i++
+				}
+			}
+		case '\'':
+			// A character literal, not a lifetime: 'x', '\n', or '\u{..}'.
+			if j := bytes.IndexByte(content[i+1:min(i+12, len(content))], '\''); j > 0 &&
+				(j == 1 || content[i+1] == '\\') {
+				i += j + 1
+			}
+		case '/':
+			if i+1 < len(content) && content[i+1] == '/' {
+				if nl := bytes.IndexByte(content[i:], '\n'); nl >= 0 {
+					i += nl
+				} else {
+					return -1
+				}
+			} else if i+1 < len(content) && content[i+1] == '*' {
+				if j := bytes.Index(content[i+2:], []byte("*/")); j >= 0 {
+					i += j + 3
+				} else {
+					return -1
+				}
+			}
+		}
+	}
+	return -1
+}
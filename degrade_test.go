@@ -0,0 +1,136 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+	"testing/fstest"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestScanDeclarations(t *testing.T) {
+	t.Parallel()
+	src := `class Cart:
+    def add(self, item):
+        pass
+
+    def _total(self):
+        return 0
+
+def checkout(cart):
+    return cart
+`
+	decls := scanDeclarations("python", []byte(src))
+	require.Len(t, decls, 4)
+
+	assert.Equal(t, "Cart", decls[0].name)
+	assert.Equal(t, "class", decls[0].kind)
+	assert.Equal(t, 0, decls[0].line)
+	assert.Equal(t, 5, decls[0].endLine)
+	assert.Equal(t, -1, decls[0].parent)
+
+	assert.Equal(t, "add", decls[1].name)
+	assert.Equal(t, "method", decls[1].kind)
+	assert.Equal(t, 0, decls[1].parent)
+	assert.Equal(t, 2, decls[1].endLine)
+
+	assert.Equal(t, "_total", decls[2].name)
+	assert.Equal(t, "private", decls[2].visibility)
+
+	assert.Equal(t, "checkout", decls[3].name)
+	assert.Equal(t, "function", decls[3].kind)
+	assert.Equal(t, -1, decls[3].parent)
+	assert.Equal(t, len("class Cart:\n    def add(self, item):\n        pass\n\n    def _total(self):\n        return 0\n\ndef "), decls[3].nameByte)
+}
+
+func TestScanDeclarations_CSkipsStatements(t *testing.T) {
+	t.Parallel()
+	src := `static int helper(int x) {
+    return compute(x,
+        1);
+}
+`
+	decls := scanDeclarations("c", []byte(src))
+	require.Len(t, decls, 1)
+	assert.Equal(t, "helper", decls[0].name)
+	assert.Equal(t, 3, decls[0].endLine, "the closing brace belongs to the function")
+}
+
+func TestRustMacroBodies(t *testing.T) {
+	t.Parallel()
+	src := `macro_rules! make { ($n:ident) => { fn $n() {} }; }
+lazy_static! {
+    // a } in a comment
+    static ref NAMES: Vec<&'static str> = vec!["}", "{"];
+}
+`
+	bodies := rustMacroBodies([]byte(src))
+	require.Len(t, bodies, 1, "macro_rules! is skipped; vec! is inside lazy_static!")
+	body := src[bodies[0][0]:bodies[0][1]]
+	assert.Contains(t, body, "static ref NAMES")
+	assert.Equal(t, byte('}'), src[bodies[0][1]])
+}
+
+func TestWithDegradation_MacroDeclarations(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"), WithDegradation(0))
+	ctx := context.Background()
+	dir := t.TempDir()
+	path := writeGoFile(t, dir, "lib.rs", `pub fn visible() {}
+
+lazy_static! {
+    pub static ref REGISTRY: Vec<u32> = Vec::new();
+}
+`)
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+
+	syms, err := e.store.SymbolsByName("REGISTRY")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	assert.Equal(t, "variable", syms[0].Kind)
+	assert.Equal(t, "public", syms[0].Visibility)
+	assert.Contains(t, syms[0].Modifiers, ModifierFallback)
+
+	syms, err = e.store.SymbolsByName("visible")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	assert.NotContains(t, syms[0].Modifiers, ModifierFallback, "extracted symbols are not duplicated")
+
+	stats, err := e.Query().IndexStats()
+	require.NoError(t, err)
+	assert.Equal(t, map[string]int{DegradedMacros: 1}, stats.DegradedFiles)
+	assert.Equal(t, 1, stats.FallbackSymbols)
+}
+
+func TestWithDegradation_FailedExtraction(t *testing.T) {
+	scripts := fstest.MapFS{"extract/go.risor": {Data: []byte("undefined_function()\n")}}
+	src := "package main\n\nfunc Run() {}\n\ntype server struct {\n\tport int\n}\n"
+
+	strict := newIntegrationEngine(t, WithLanguages("go"), WithScriptsFS(scripts))
+	path := writeGoFile(t, t.TempDir(), "main.go", src)
+	assert.Error(t, strict.IndexFiles(context.Background(), []string{path}))
+
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithScriptsFS(scripts), WithParallel(parallel), WithDegradation(0))
+		path := writeGoFile(t, t.TempDir(), "main.go", src)
+		require.NoError(t, e.IndexFiles(context.Background(), []string{path}), "parallel=%v", parallel)
+
+		syms, err := e.store.SymbolsByName("Run")
+		require.NoError(t, err)
+		require.Len(t, syms, 1)
+		assert.Equal(t, "function", syms[0].Kind)
+		assert.Equal(t, []string{ModifierFallback}, syms[0].Modifiers)
+
+		syms, err = e.store.SymbolsByName("server")
+		require.NoError(t, err)
+		require.Len(t, syms, 1)
+		assert.Equal(t, "struct", syms[0].Kind)
+		assert.Equal(t, "private", syms[0].Visibility)
+		assert.Equal(t, 6, syms[0].EndLine)
+
+		stats, err := e.Query().IndexStats()
+		require.NoError(t, err)
+		assert.Equal(t, map[string]int{DegradedExtractFailed: 1}, stats.DegradedFiles)
+		assert.Equal(t, 2, stats.FallbackSymbols)
+	}
+}
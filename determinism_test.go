@@ -0,0 +1,103 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// writeDeterminismTree writes a Go package whose files all call one shared
+// function, and a same-named function per subpackage, so queries have
+// references, call edges, and sort ties to order.
+func writeDeterminismTree(t *testing.T, dir string) {
+	t.Helper()
+	writeGoFile(t, dir, "shared.go", "package main\n\nfunc shared() {}\n")
+	for i := range 8 {
+		writeGoFile(t, dir, fmt.Sprintf("f%d.go", i), fmt.Sprintf(
+			"package main\n\nfunc f%[1]d() {\n\tshared()\n\tshared()\n}\n\ntype T%[1]d struct{}\n", i))
+	}
+	for _, pkg := range []string{"zeta", "alpha", "mid"} {
+		writeGoFile(t, filepath.Join(dir, pkg), pkg+".go", "package "+pkg+"\n\nfunc Init() {}\n")
+	}
+}
+
+// determinismSnapshot indexes dir into a fresh database and renders the
+// query outputs whose order must not depend on how the index was built.
+func determinismSnapshot(t *testing.T, dir string, parallel bool) string {
+	t.Helper()
+	e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel))
+	ctx := context.Background()
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	var b strings.Builder
+	symbols, err := q.Symbols(SymbolFilter{}, Sort{Field: SortByName, Order: Asc}, Pagination{Limit: intP(500)})
+	require.NoError(t, err)
+	var shared int64
+	for _, s := range symbols.Items {
+		fmt.Fprintf(&b, "symbol %d %s %s:%d:%d\n", s.ID, s.Name, s.FilePath, s.StartLine, s.StartCol)
+		if s.Name == "shared" {
+			shared = s.ID
+		}
+	}
+	require.NotZero(t, shared)
+
+	refs, err := q.ReferencesTo(shared)
+	require.NoError(t, err)
+	for _, r := range refs {
+		fmt.Fprintf(&b, "ref %s:%d:%d\n", r.File, r.StartLine, r.StartCol)
+	}
+
+	callers, err := q.TransitiveCallers(shared, 3)
+	require.NoError(t, err)
+	for _, n := range callers.Nodes {
+		fmt.Fprintf(&b, "caller %d %s %d\n", n.Symbol.ID, n.Symbol.Name, n.Depth)
+	}
+	for _, edge := range callers.Edges {
+		fmt.Fprintf(&b, "edge %d->%d %s:%d:%d\n", edge.CallerID, edge.CalleeID, edge.File, edge.Line, edge.Col)
+	}
+
+	hotspots, err := q.Hotspots(10)
+	require.NoError(t, err)
+	for _, h := range hotspots {
+		fmt.Fprintf(&b, "hotspot %d %s\n", h.Symbol.ID, h.Symbol.Name)
+	}
+	return b.String()
+}
+
+func TestDeterministicOutput_ParallelIndexing(t *testing.T) {
+	dir := t.TempDir()
+	writeDeterminismTree(t, dir)
+
+	want := determinismSnapshot(t, dir, true)
+	for run := range 3 {
+		assert.Equal(t, want, determinismSnapshot(t, dir, true), "parallel run %d", run)
+	}
+	assert.Contains(t, want, "ref ", "snapshot covers references")
+	assert.Contains(t, want, "edge ", "snapshot covers call edges")
+}
+
+func TestSymbols_TiesOrderedByPath(t *testing.T) {
+	dir := t.TempDir()
+	writeDeterminismTree(t, dir)
+	e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(true))
+	require.NoError(t, e.IndexDirectory(context.Background(), dir))
+
+	for _, order := range []SortOrder{Asc, Desc} {
+		result, err := e.Query().Symbols(SymbolFilter{Kinds: []string{"function"}}, Sort{Field: SortByName, Order: order}, Pagination{Limit: intP(500)})
+		require.NoError(t, err)
+		var paths []string
+		for _, s := range result.Items {
+			if s.Name == "Init" {
+				paths = append(paths, filepath.Base(s.FilePath))
+			}
+		}
+		assert.Equal(t, []string{"alpha.go", "mid.go", "zeta.go"}, paths, "ties break by path whatever the sort order")
+	}
+}
@@ -39,6 +39,9 @@
 //     position is defined.
 //   - [QueryBuilder.ReferencesTo] — Find-references: all locations referencing a
 //     symbol.
+//     [QueryBuilder.ReferencesToPaged] and [QueryBuilder.EachReferenceTo]
+//     page or stream them, optionally filtered by kind (call, type usage,
+//     trait bound, pattern, field access, import).
 //   - [QueryBuilder.Implementations] — Find types implementing an interface or
 //     trait.
 //   - [QueryBuilder.Callers] — Call graph: who calls this function.
@@ -54,6 +57,10 @@
 // languages. Use [WithLanguages] to restrict which languages the Engine
 // processes.
 //
+// [Engine.SetOverlay] indexes unsaved editor contents in place of a file on
+// disk, re-resolving only what the change affects; [Engine.ClearOverlay]
+// reverts to the file on disk.
+//
 // # Scripts
 //
 // Language-specific logic lives in Risor scripts under the scripts directory:
@@ -0,0 +1,227 @@
+package canopy
+
+import (
+	"bytes"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+)
+
+// TextEdit replaces the span of a file described by its Location with
+// NewText. Columns are 0-based bytes and the end position is exclusive. When
+// OldText is non-empty the span must currently contain exactly that text,
+// which catches files that changed since they were indexed.
+type TextEdit struct {
+	Location
+	OldText string
+	NewText string
+}
+
+// ApplyResult summarizes a successful ApplyEdits call.
+type ApplyResult struct {
+	Files []string // changed files, sorted
+	Edits int      // number of edits applied (duplicates counted once)
+}
+
+// EditConflict describes why an edit could not be applied.
+type EditConflict struct {
+	Edit   TextEdit
+	Reason string
+}
+
+// EditConflictError is returned by ApplyEdits when any edit overlaps another,
+// falls outside its file, or no longer matches the file contents. No file is
+// modified when it is returned.
+type EditConflictError struct {
+	Conflicts []EditConflict
+}
+
+func (e *EditConflictError) Error() string {
+	msgs := make([]string, len(e.Conflicts))
+	for i, c := range e.Conflicts {
+		msgs[i] = fmt.Sprintf("%s:%d:%d: %s", c.Edit.File, c.Edit.StartLine, c.Edit.StartCol, c.Reason)
+	}
+	return fmt.Sprintf("%d conflicting edit(s): %s", len(e.Conflicts), strings.Join(msgs, "; "))
+}
+
+// ApplyEdits applies span-based edits across files as one unit. Every edit is
+// validated against the current file contents before anything is written;
+// on any conflict an *EditConflictError is returned and no file changes.
+// Each file is written to a temporary sibling and renamed into place; if a
+// write fails, files already replaced are restored. Identical duplicate
+// edits are applied once.
+func ApplyEdits(edits []TextEdit) (*ApplyResult, error) {
+	byFile := map[string][]TextEdit{}
+	for _, e := range edits {
+		byFile[e.File] = append(byFile[e.File], e)
+	}
+
+	type pending struct {
+		path     string
+		original []byte
+		updated  []byte
+		mode     os.FileMode
+	}
+	var files []pending
+	var conflicts []EditConflict
+	applied := 0
+
+	paths := make([]string, 0, len(byFile))
+	for path := range byFile {
+		paths = append(paths, path)
+	}
+	sort.Strings(paths)
+
+	for _, path := range paths {
+		info, err := os.Stat(path)
+		if err != nil {
+			return nil, fmt.Errorf("apply edits: %w", err)
+		}
+		content, err := os.ReadFile(path)
+		if err != nil {
+			return nil, fmt.Errorf("apply edits: %w", err)
+		}
+		updated, n, fileConflicts := applyFileEdits(content, byFile[path])
+		if len(fileConflicts) > 0 {
+			conflicts = append(conflicts, fileConflicts...)
+			continue
+		}
+		applied += n
+		if !bytes.Equal(content, updated) {
+			files = append(files, pending{path: path, original: content, updated: updated, mode: info.Mode().Perm()})
+		}
+	}
+	if len(conflicts) > 0 {
+		return nil, &EditConflictError{Conflicts: conflicts}
+	}
+
+	result := &ApplyResult{Files: []string{}, Edits: applied}
+	for i, f := range files {
+		if err := writeFileAtomic(f.path, f.updated, f.mode); err != nil {
+			// Roll back the files already replaced.
+			for _, done := range files[:i] {
+				_ = writeFileAtomic(done.path, done.original, done.mode)
+			}
+			return nil, fmt.Errorf("apply edits: %w", err)
+		}
+		result.Files = append(result.Files, f.path)
+	}
+	return result, nil
+}
+
+// editSpan is a TextEdit resolved to byte offsets within its file.
+type editSpan struct {
+	edit       TextEdit
+	start, end int
+}
+
+// applyFileEdits applies edits to one file's content. It returns the new
+// content and the number of distinct edits, or the conflicts found.
+func applyFileEdits(content []byte, edits []TextEdit) ([]byte, int, []EditConflict) {
+	lineStarts := []int{0}
+	for i, b := range content {
+		if b == '\n' {
+			lineStarts = append(lineStarts, i+1)
+		}
+	}
+	offset := func(line, col int) (int, bool) {
+		if line < 0 || line >= len(lineStarts) || col < 0 {
+			return 0, false
+		}
+		lineEnd := len(content)
+		if line+1 < len(lineStarts) {
+			lineEnd = lineStarts[line+1] - 1
+		}
+		off := lineStarts[line] + col
+		return off, off <= lineEnd
+	}
+
+	var conflicts []EditConflict
+	var spans []editSpan
+	for _, e := range edits {
+		start, okStart := offset(e.StartLine, e.StartCol)
+		end, okEnd := offset(e.EndLine, e.EndCol)
+		if !okStart || !okEnd || end < start {
+			conflicts = append(conflicts, EditConflict{Edit: e, Reason: "span is outside the file"})
+			continue
+		}
+		if e.OldText != "" && string(content[start:end]) != e.OldText {
+			conflicts = append(conflicts, EditConflict{
+				Edit:   e,
+				Reason: fmt.Sprintf("expected %q, found %q (file changed since indexing?)", e.OldText, content[start:end]),
+			})
+			continue
+		}
+		spans = append(spans, editSpan{edit: e, start: start, end: end})
+	}
+
+	sort.SliceStable(spans, func(i, j int) bool {
+		if spans[i].start != spans[j].start {
+			return spans[i].start < spans[j].start
+		}
+		return spans[i].end < spans[j].end
+	})
+
+	// Drop exact duplicates, then reject overlaps. Two insertions at the same
+	// offset also conflict since their order would be ambiguous.
+	var distinct []editSpan
+	for _, s := range spans {
+		if n := len(distinct); n > 0 {
+			prev := distinct[n-1]
+			if prev.start == s.start && prev.end == s.end && prev.edit.NewText == s.edit.NewText {
+				continue
+			}
+			if s.start < prev.end || (s.start == prev.start && s.end == prev.end) {
+				conflicts = append(conflicts, EditConflict{
+					Edit:   s.edit,
+					Reason: fmt.Sprintf("overlaps edit at %d:%d", prev.edit.StartLine, prev.edit.StartCol),
+				})
+				continue
+			}
+		}
+		distinct = append(distinct, s)
+	}
+	if len(conflicts) > 0 {
+		return nil, 0, conflicts
+	}
+
+	var buf bytes.Buffer
+	last := 0
+	for _, s := range distinct {
+		buf.Write(content[last:s.start])
+		buf.WriteString(s.edit.NewText)
+		last = s.end
+	}
+	buf.Write(content[last:])
+	return buf.Bytes(), len(distinct), nil
+}
+
+// writeFileAtomic replaces path with data via a temporary file in the same
+// directory, so readers never observe a partially written file.
+func writeFileAtomic(path string, data []byte, mode os.FileMode) error {
+	tmp, err := os.CreateTemp(filepath.Dir(path), "."+filepath.Base(path)+".canopy-*")
+	if err != nil {
+		return err
+	}
+	tmpName := tmp.Name()
+	if _, err := tmp.Write(data); err != nil {
+		tmp.Close()
+		os.Remove(tmpName)
+		return err
+	}
+	if err := tmp.Close(); err != nil {
+		os.Remove(tmpName)
+		return err
+	}
+	if err := os.Chmod(tmpName, mode); err != nil {
+		os.Remove(tmpName)
+		return err
+	}
+	if err := os.Rename(tmpName, path); err != nil {
+		os.Remove(tmpName)
+		return err
+	}
+	return nil
+}
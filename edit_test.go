@@ -0,0 +1,104 @@
+package canopy
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func writeEditFile(t *testing.T, dir, name, content string) string {
+	t.Helper()
+	path := filepath.Join(dir, name)
+	require.NoError(t, os.WriteFile(path, []byte(content), 0o644))
+	return path
+}
+
+func readEditFile(t *testing.T, path string) string {
+	t.Helper()
+	b, err := os.ReadFile(path)
+	require.NoError(t, err)
+	return string(b)
+}
+
+func editAt(path string, line, startCol, endCol int, oldText, newText string) TextEdit {
+	return TextEdit{
+		Location: Location{File: path, StartLine: line, StartCol: startCol, EndLine: line, EndCol: endCol},
+		OldText:  oldText,
+		NewText:  newText,
+	}
+}
+
+func TestApplyEdits_MultipleFiles(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+	a := writeEditFile(t, dir, "a.go", "func foo() {}\nfunc bar() { foo() }\n")
+	b := writeEditFile(t, dir, "b.go", "var x = foo()\n")
+
+	res, err := ApplyEdits([]TextEdit{
+		editAt(a, 0, 5, 8, "foo", "renamed"),
+		editAt(a, 1, 13, 16, "foo", "renamed"),
+		editAt(b, 0, 8, 11, "foo", "renamed"),
+		editAt(b, 0, 8, 11, "foo", "renamed"), // duplicate applied once
+	})
+	require.NoError(t, err)
+	assert.Equal(t, 3, res.Edits)
+	assert.Equal(t, []string{a, b}, res.Files)
+
+	assert.Equal(t, "func renamed() {}\nfunc bar() { renamed() }\n", readEditFile(t, a))
+	assert.Equal(t, "var x = renamed()\n", readEditFile(t, b))
+}
+
+func TestApplyEdits_ConflictWritesNothing(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+	a := writeEditFile(t, dir, "a.go", "func foo() {}\n")
+	b := writeEditFile(t, dir, "b.go", "var x = foo()\n")
+
+	_, err := ApplyEdits([]TextEdit{
+		editAt(a, 0, 5, 8, "foo", "renamed"),
+		editAt(b, 0, 8, 11, "bar", "renamed"), // stale: file no longer says "bar"
+	})
+	var conflictErr *EditConflictError
+	require.ErrorAs(t, err, &conflictErr)
+	require.Len(t, conflictErr.Conflicts, 1)
+	assert.Equal(t, b, conflictErr.Conflicts[0].Edit.File)
+
+	assert.Equal(t, "func foo() {}\n", readEditFile(t, a), "no file is modified on conflict")
+	assert.Equal(t, "var x = foo()\n", readEditFile(t, b))
+}
+
+func TestApplyEdits_OverlapAndOutOfRange(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+	a := writeEditFile(t, dir, "a.go", "func foo() {}\n")
+
+	_, err := ApplyEdits([]TextEdit{
+		editAt(a, 0, 5, 8, "", "x"),
+		editAt(a, 0, 6, 10, "", "y"),
+	})
+	assert.ErrorContains(t, err, "overlaps")
+
+	_, err = ApplyEdits([]TextEdit{editAt(a, 0, 20, 25, "", "x")})
+	assert.ErrorContains(t, err, "outside the file")
+
+	_, err = ApplyEdits([]TextEdit{editAt(a, 5, 0, 1, "", "x")})
+	assert.ErrorContains(t, err, "outside the file")
+}
+
+func TestApplyEdits_PreservesFileMode(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+	a := writeEditFile(t, dir, "run.sh", "echo foo\n")
+	require.NoError(t, os.Chmod(a, 0o755))
+
+	_, err := ApplyEdits([]TextEdit{editAt(a, 0, 5, 8, "foo", "bar")})
+	require.NoError(t, err)
+
+	info, err := os.Stat(a)
+	require.NoError(t, err)
+	assert.Equal(t, os.FileMode(0o755), info.Mode().Perm())
+	assert.Equal(t, "echo bar\n", readEditFile(t, a))
+}
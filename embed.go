@@ -0,0 +1,370 @@
+package canopy
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"hash/fnv"
+	"io"
+	"math"
+	"net/http"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// Embedder turns texts into embedding vectors for semantic search.
+// Implementations must return one vector per text, all of one length.
+// Model names the model and its configuration: embeddings are stored per
+// model, and a query is only compared with embeddings of the model that
+// embedded it.
+type Embedder interface {
+	Model() string
+	Embed(ctx context.Context, texts []string) ([][]float32, error)
+}
+
+// defaultHashDimensions is the vector length of a HashEmbedder built with
+// no explicit dimensions.
+const defaultHashDimensions = 512
+
+// HashEmbedder is a local embedding model that needs no network or model
+// files. It splits text into words on identifier boundaries (camelCase,
+// snake_case, punctuation), strips common English suffixes, drops stop
+// words, and hashes each word and its character trigrams into a fixed
+// number of dimensions. It matches a query against code by shared
+// vocabulary, so "compute shipping tax" finds fn compute_ship_tax, but not
+// by meaning; use an HTTPEmbedder backed by a neural model for that.
+type HashEmbedder struct {
+	Dimensions int // vector length; defaultHashDimensions if <= 0
+}
+
+func (h *HashEmbedder) dims() int {
+	if h.Dimensions <= 0 {
+		return defaultHashDimensions
+	}
+	return h.Dimensions
+}
+
+// Model returns "hash-<dimensions>".
+func (h *HashEmbedder) Model() string {
+	return fmt.Sprintf("hash-%d", h.dims())
+}
+
+// Embed returns the unit-length hashed bag-of-words vector of each text.
+func (h *HashEmbedder) Embed(ctx context.Context, texts []string) ([][]float32, error) {
+	dims := h.dims()
+	out := make([][]float32, len(texts))
+	for i, text := range texts {
+		if err := ctx.Err(); err != nil {
+			return nil, err
+		}
+		counts := map[string]float64{}
+		for _, w := range identifierWords(text) {
+			if len(w) < 2 || embedStopWords[w] {
+				continue
+			}
+			w = stemWord(w)
+			counts[w]++
+			if len(w) > 3 {
+				padded := "<" + w + ">"
+				for j := 0; j+3 <= len(padded); j++ {
+					counts["#"+padded[j:j+3]] += 0.25
+				}
+			}
+		}
+		v := make([]float32, dims)
+		for term, n := range counts {
+			hf := fnv.New32a()
+			hf.Write([]byte(term))
+			sum := hf.Sum32()
+			weight := 1 + math.Log(n)
+			if n < 1 {
+				weight = n
+			}
+			if sum&(1<<31) != 0 {
+				weight = -weight
+			}
+			v[int(sum%uint32(dims))] += float32(weight)
+		}
+		normalizeVector(v)
+		out[i] = v
+	}
+	return out, nil
+}
+
+// embedStopWords are words too common in queries and comments to say
+// anything about a symbol.
+var embedStopWords = map[string]bool{
+	"a": true, "an": true, "and": true, "are": true, "as": true, "at": true, "be": true, "by": true,
+	"do": true, "does": true, "for": true, "from": true, "how": true, "if": true, "in": true,
+	"is": true, "it": true, "of": true, "on": true, "or": true, "the": true, "this": true,
+	"to": true, "we": true, "what": true, "where": true, "which": true, "who": true, "with": true,
+}
+
+// identifierWords splits text into lowercase words at non-alphanumeric
+// characters and at camelCase boundaries: "parseHTTPRequest_v2" yields
+// parse, http, request, v2.
+func identifierWords(text string) []string {
+	var words []string
+	rs := []rune(text)
+	start := -1
+	flush := func(end int) {
+		if start >= 0 {
+			words = append(words, strings.ToLower(string(rs[start:end])))
+			start = -1
+		}
+	}
+	for i, r := range rs {
+		if !unicode.IsLetter(r) && !unicode.IsDigit(r) {
+			flush(i)
+			continue
+		}
+		if start >= 0 && unicode.IsUpper(r) {
+			prev := rs[i-1]
+			if unicode.IsLower(prev) || unicode.IsDigit(prev) ||
+				(unicode.IsUpper(prev) && i+1 < len(rs) && unicode.IsLower(rs[i+1])) {
+				flush(i)
+			}
+		}
+		if start < 0 {
+			start = i
+		}
+	}
+	flush(len(rs))
+	return words
+}
+
+// stemWord strips a common English suffix from w, so "shipping",
+// "shipped" and "ships" all become "ship", and "computes" and "computed"
+// match "compute".
+func stemWord(w string) string {
+	for _, suffix := range []string{"ing", "ed", "es", "s"} {
+		stem, ok := strings.CutSuffix(w, suffix)
+		if !ok || len(stem) < 3 || strings.HasSuffix(w, "ss") {
+			continue
+		}
+		if suffix == "es" && !strings.HasSuffix(stem, "x") && !strings.HasSuffix(stem, "s") &&
+			!strings.HasSuffix(stem, "ch") && !strings.HasSuffix(stem, "sh") {
+			continue
+		}
+		// Undouble a final consonant: shipp -> ship.
+		if n := len(stem); (suffix == "ing" || suffix == "ed") && stem[n-1] == stem[n-2] && !strings.ContainsRune("aeiou", rune(stem[n-1])) {
+			stem = stem[:n-1]
+		}
+		w = stem
+		break
+	}
+	if len(w) > 4 {
+		w = strings.TrimSuffix(w, "e")
+	}
+	return w
+}
+
+// normalizeVector scales v to unit length in place; a zero vector is left
+// as is.
+func normalizeVector(v []float32) {
+	var sq float64
+	for _, x := range v {
+		sq += float64(x) * float64(x)
+	}
+	if sq == 0 {
+		return
+	}
+	norm := float32(math.Sqrt(sq))
+	for i := range v {
+		v[i] /= norm
+	}
+}
+
+// HTTPEmbedder embeds texts with a model served over HTTP through the
+// OpenAI embeddings API: a POST of {"model", "input"} answered with
+// {"data": [{"index", "embedding"}]}. OpenAI, Ollama (/v1/embeddings),
+// llama.cpp's server, vLLM and Hugging Face text-embeddings-inference all
+// speak it, so a local model can be used by running one of those.
+type HTTPEmbedder struct {
+	URL    string // the embeddings endpoint, e.g. http://localhost:11434/v1/embeddings
+	Name   string // the model name sent with each request
+	APIKey string // sent as a bearer token if set
+	Client *http.Client
+}
+
+// Model returns "http:<name>".
+func (h *HTTPEmbedder) Model() string {
+	return "http:" + h.Name
+}
+
+// Embed posts texts to the endpoint in one request.
+func (h *HTTPEmbedder) Embed(ctx context.Context, texts []string) ([][]float32, error) {
+	body, err := json.Marshal(struct {
+		Model string   `json:"model"`
+		Input []string `json:"input"`
+	}{h.Name, texts})
+	if err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, h.URL, bytes.NewReader(body))
+	if err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	req.Header.Set("Content-Type", "application/json")
+	if h.APIKey != "" {
+		req.Header.Set("Authorization", "Bearer "+h.APIKey)
+	}
+	client := h.Client
+	if client == nil {
+		client = http.DefaultClient
+	}
+	resp, err := client.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	defer resp.Body.Close()
+	if resp.StatusCode != http.StatusOK {
+		msg, _ := io.ReadAll(io.LimitReader(resp.Body, 512))
+		return nil, fmt.Errorf("embed: %s: %s: %s", h.URL, resp.Status, strings.TrimSpace(string(msg)))
+	}
+
+	var decoded struct {
+		Data []struct {
+			Index     int       `json:"index"`
+			Embedding []float32 `json:"embedding"`
+		} `json:"data"`
+	}
+	if err := json.NewDecoder(resp.Body).Decode(&decoded); err != nil {
+		return nil, fmt.Errorf("embed: decode response: %w", err)
+	}
+	if len(decoded.Data) != len(texts) {
+		return nil, fmt.Errorf("embed: got %d embeddings for %d texts", len(decoded.Data), len(texts))
+	}
+	out := make([][]float32, len(texts))
+	for _, d := range decoded.Data {
+		if d.Index < 0 || d.Index >= len(texts) || out[d.Index] != nil {
+			return nil, fmt.Errorf("embed: bad embedding index %d", d.Index)
+		}
+		out[d.Index] = d.Embedding
+	}
+	return out, nil
+}
+
+// Embedding parameters: texts per Embed call, and the longest chunk text
+// embedded, in bytes, to stay within model context windows.
+const (
+	embedBatchSize    = 32
+	maxEmbedTextBytes = 8000
+)
+
+// EmbeddingSummary describes a run of EmbedSymbols.
+type EmbeddingSummary struct {
+	Model      string
+	Symbols    int // symbols with an embedding
+	Embedded   int // symbols sent to the embedder
+	Reused     int // symbols whose text was unchanged since the last run
+	Dimensions int
+}
+
+// EmbedSymbols stores an embedding per symbol that starts a code chunk
+// (functions, methods, types; see CodeChunks), computed by emb from the
+// chunk's path, doc comment and text. Symbols whose text is unchanged
+// since the last run with the same model keep their stored vectors, so
+// re-running after an incremental index only embeds what changed.
+// Embeddings of symbols no longer indexed are dropped; those of other
+// models are kept. Re-indexing a file drops the embeddings of its symbols.
+func (e *Engine) EmbedSymbols(ctx context.Context, emb Embedder) (*EmbeddingSummary, error) {
+	model := emb.Model()
+	chunks, err := e.Query().CodeChunks()
+	if err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	existing, err := e.store.SymbolEmbeddings(model)
+	if err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	stored := make(map[int64]*SymbolEmbedding, len(existing))
+	for _, se := range existing {
+		stored[se.SymbolID] = se
+	}
+
+	summary := &EmbeddingSummary{Model: model}
+	var embeddings []*SymbolEmbedding
+	var pending []*SymbolEmbedding
+	var texts []string
+	flush := func() error {
+		if len(texts) == 0 {
+			return nil
+		}
+		vecs, err := emb.Embed(ctx, texts)
+		if err != nil {
+			return err
+		}
+		if len(vecs) != len(texts) {
+			return fmt.Errorf("embed: %s returned %d vectors for %d texts", model, len(vecs), len(texts))
+		}
+		for i, v := range vecs {
+			pending[i].Vector = v
+		}
+		summary.Embedded += len(texts)
+		pending, texts = pending[:0], texts[:0]
+		return nil
+	}
+	for _, c := range chunks {
+		if c.SymbolID == 0 {
+			continue
+		}
+		text := chunkEmbedText(c)
+		sum := sha256.Sum256([]byte(text))
+		hash := hex.EncodeToString(sum[:16])
+		if se := stored[c.SymbolID]; se != nil && se.TextHash == hash {
+			embeddings = append(embeddings, se)
+			summary.Reused++
+			continue
+		}
+		se := &SymbolEmbedding{SymbolID: c.SymbolID, Model: model, TextHash: hash}
+		embeddings = append(embeddings, se)
+		pending = append(pending, se)
+		texts = append(texts, text)
+		if len(texts) == embedBatchSize {
+			if err := flush(); err != nil {
+				return nil, err
+			}
+		}
+	}
+	if err := flush(); err != nil {
+		return nil, err
+	}
+
+	for _, se := range embeddings {
+		if summary.Dimensions == 0 {
+			summary.Dimensions = len(se.Vector)
+		}
+		if len(se.Vector) != summary.Dimensions {
+			return nil, fmt.Errorf("embed: %s returned vectors of %d and %d dimensions", model, summary.Dimensions, len(se.Vector))
+		}
+	}
+	if err := e.store.ReplaceSymbolEmbeddings(model, embeddings); err != nil {
+		return nil, fmt.Errorf("embed: %w", err)
+	}
+	summary.Symbols = len(embeddings)
+	return summary, nil
+}
+
+// chunkEmbedText is the text embedded for a chunk: its path, doc comment
+// and text, cut to maxEmbedTextBytes at a UTF-8 boundary.
+func chunkEmbedText(c CodeChunk) string {
+	text := c.Path + "\n"
+	if c.Doc != "" {
+		text += c.Doc + "\n"
+	}
+	text += c.Text
+	if len(text) > maxEmbedTextBytes {
+		cut := maxEmbedTextBytes
+		for cut > 0 && !utf8.RuneStart(text[cut]) {
+			cut--
+		}
+		text = text[:cut]
+	}
+	return text
+}
+
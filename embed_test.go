@@ -0,0 +1,86 @@
+package canopy
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestIdentifierWords(t *testing.T) {
+	assert.Equal(t, []string{"parse", "http", "request", "v2"}, identifierWords("parseHTTPRequest_v2"))
+	assert.Equal(t, []string{"compute", "shipping", "tax"}, identifierWords("fn compute_shipping_tax()"))
+	assert.Empty(t, identifierWords("() -> {}"))
+}
+
+func TestStemWord(t *testing.T) {
+	for _, w := range []string{"shipping", "shipped", "ships", "ship"} {
+		assert.Equal(t, "ship", stemWord(w), w)
+	}
+	for _, w := range []string{"compute", "computes", "computed", "computing"} {
+		assert.Equal(t, "comput", stemWord(w), w)
+	}
+	assert.Equal(t, "tax", stemWord("taxes"))
+	assert.Equal(t, "class", stemWord("class"))
+}
+
+func TestHashEmbedder(t *testing.T) {
+	h := &HashEmbedder{}
+	assert.Equal(t, "hash-512", h.Model())
+	vecs, err := h.Embed(context.Background(), []string{
+		"compute shipping tax",
+		"fn compute_shipping_tax(weight: f64) -> f64",
+		"fn parse_config(text: &str) -> Config",
+		"",
+	})
+	require.NoError(t, err)
+	require.Len(t, vecs, 4)
+	for _, v := range vecs {
+		assert.Len(t, v, 512)
+	}
+	assert.InDelta(t, 1, cosineSimilarity(vecs[0], vecs[0]), 1e-6)
+	assert.Greater(t, cosineSimilarity(vecs[0], vecs[1]), cosineSimilarity(vecs[0], vecs[2]))
+	assert.Zero(t, cosineSimilarity(vecs[0], vecs[3]))
+}
+
+func TestHTTPEmbedder(t *testing.T) {
+	srv := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		assert.Equal(t, "Bearer secret", r.Header.Get("Authorization"))
+		var req struct {
+			Model string   `json:"model"`
+			Input []string `json:"input"`
+		}
+		require.NoError(t, json.NewDecoder(r.Body).Decode(&req))
+		if req.Model != "nomic-embed-text" {
+			http.Error(w, "unknown model", http.StatusNotFound)
+			return
+		}
+		// Answer out of order; the embedder places vectors by index.
+		type item struct {
+			Index     int       `json:"index"`
+			Embedding []float32 `json:"embedding"`
+		}
+		var resp struct {
+			Data []item `json:"data"`
+		}
+		for i := len(req.Input) - 1; i >= 0; i-- {
+			resp.Data = append(resp.Data, item{i, []float32{float32(len(req.Input[i])), 1}})
+		}
+		json.NewEncoder(w).Encode(resp)
+	}))
+	defer srv.Close()
+
+	h := &HTTPEmbedder{URL: srv.URL, Name: "nomic-embed-text", APIKey: "secret"}
+	assert.Equal(t, "http:nomic-embed-text", h.Model())
+	vecs, err := h.Embed(context.Background(), []string{"a", "abc"})
+	require.NoError(t, err)
+	assert.Equal(t, [][]float32{{1, 1}, {3, 1}}, vecs)
+
+	h.Name = "missing"
+	_, err = h.Embed(context.Background(), []string{"a"})
+	assert.ErrorContains(t, err, "404")
+}
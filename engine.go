@@ -4,11 +4,12 @@ import (
 	"bytes"
 	"context"
 	"crypto/sha256"
+	"errors"
 	"fmt"
 	"io/fs"
-	"os"
 	"os/exec"
 	"path/filepath"
+	"slices"
 	"sort"
 	"strings"
 	"sync"
@@ -26,6 +27,9 @@ type Engine struct {
 	scriptsDir string
 	scriptsFS  fs.FS
 	languages  map[string]bool // nil means all languages
+	include    []string        // globs relative to the indexed root; empty means all
+	exclude    []string        // globs relative to the indexed root
+	roots      []string        // directories passed to IndexDirectory, for file provenance
 
 	// blastRadius accumulates file IDs that need re-resolution after indexing.
 	// nil means "resolve everything" (first run or full reindex).
@@ -33,6 +37,74 @@ type Engine struct {
 
 	// useParallel enables the parallel extraction pipeline.
 	useParallel bool
+
+	// workerProcesses, if positive, is the number of processes parallel
+	// extraction runs in, each started by workerCommand.
+	workerProcesses int
+	workerCommand   func() *exec.Cmd
+
+	// maxMemory, if positive, bounds the bytes parallel extraction holds
+	// before committing to SQLite.
+	maxMemory int64
+
+	// blame enables git blame enrichment in IndexDirectory.
+	blame bool
+
+	// sqlRefs enables SQL reference extraction in Resolve.
+	sqlRefs bool
+
+	// targetDir, if set, is the Cargo target directory whose included build
+	// script output IndexDirectory indexes.
+	targetDir string
+
+	// expandDir, if set, enables Rust macro expansion in IndexDirectory and
+	// holds the expanded files.
+	expandDir string
+
+	// degradeErrorShare, if positive, enables the fallback scanner for files
+	// extraction cannot fully handle (see WithDegradation).
+	degradeErrorShare float64
+
+	// fsys is the file system indexed files are read from.
+	fsys FileSystem
+
+	// overlays holds unsaved buffer contents that replace files on disk.
+	overlayMu sync.RWMutex
+	overlays  map[string][]byte
+
+	// progress, if set, is called as IndexFiles and Resolve make progress.
+	progress func(Progress)
+
+	// tracer, if set, is called as each step of indexing ends.
+	tracer *spanTracer
+
+	// usage, if set, is called as each IndexDirectory, IndexFiles, and
+	// Resolve call ends.
+	usage *usageReporter
+
+	// parseCache, if set, holds extractions to reuse for identical files.
+	parseCacheDir string
+	parseCache    *store.Store
+
+	// memo, once created by Memo, remembers query answers between changes.
+	memo     *Memo
+	memoOnce sync.Once
+
+	// rootDeps, if set, restricts links between roots to declared
+	// dependencies (see WithRootDependencies).
+	rootDeps map[string][]string
+
+	// deadline, if set, bounds extraction in IndexDirectory (see
+	// WithDeadline); completeness reports what it covered.
+	deadline     time.Time
+	completeness IndexCompleteness
+
+	// priority lists the files IndexDirectory extracts first, with their
+	// imports (see WithPriority).
+	priority []string
+
+	// extracted counts the paths the last IndexFiles call reached, in order.
+	extracted int
 }
 
 // Option configures an Engine.
@@ -48,6 +120,22 @@ func WithLanguages(languages ...string) Option {
 	}
 }
 
+// WithInclude restricts IndexDirectory to files whose path relative to the
+// indexed root matches at least one glob. See matchPathGlob for syntax.
+func WithInclude(globs ...string) Option {
+	return func(e *Engine) {
+		e.include = append(e.include, globs...)
+	}
+}
+
+// WithExclude makes IndexDirectory skip files whose path relative to the
+// indexed root matches any glob. Exclusion wins over inclusion.
+func WithExclude(globs ...string) Option {
+	return func(e *Engine) {
+		e.exclude = append(e.exclude, globs...)
+	}
+}
+
 // WithParallel controls parallel extraction. When true (default), IndexFiles
 // uses a worker pool for parsing and script execution, with a single writer
 // goroutine committing batches to SQLite. Set to false for serial mode.
@@ -57,6 +145,22 @@ func WithParallel(parallel bool) Option {
 	}
 }
 
+// WithMaxMemory bounds the memory parallel extraction holds at once to
+// about bytes. Instead of reading every file and buffering its symbol
+// tables before committing, IndexFiles then works in chunks: it reads
+// files until their estimated extraction footprint reaches the budget,
+// extracts and commits them to SQLite, releases them, and moves on. Memory
+// then scales with the budget rather than with the repository, at some
+// cost in parallelism. The budget does not cover resolution, which loads
+// the index of each language it resolves. A single file larger than the
+// budget is still indexed, alone. Serial extraction holds one file at a
+// time and ignores the budget.
+func WithMaxMemory(bytes int64) Option {
+	return func(e *Engine) {
+		e.maxMemory = bytes
+	}
+}
+
 // WithScriptsFS configures the Engine to load Risor scripts from the given
 // filesystem instead of from the scriptsDir path on disk. This enables
 // embedding scripts via go:embed. When set, scriptsDir is ignored for
@@ -67,6 +171,17 @@ func WithScriptsFS(fsys fs.FS) Option {
 	}
 }
 
+// WithFileSystem makes the Engine read and discover the files it indexes
+// through fsys instead of the local disk, so it can index a workspace that
+// lives elsewhere, such as in a browser-based editor. IndexDirectory then
+// walks fsys rather than asking git for the file list. Cargo build script
+// output (WithTargetDir) and macro expansion still need the local disk.
+func WithFileSystem(fsys FileSystem) Option {
+	return func(e *Engine) {
+		e.fsys = fsys
+	}
+}
+
 // New creates an Engine backed by a SQLite database at dbPath.
 // Script loading priority:
 //  1. If WithScriptsFS is set, use the provided fs.FS
@@ -88,6 +203,7 @@ func New(dbPath string, scriptsDir string, opts ...Option) (*Engine, error) {
 	e := &Engine{
 		store:       s,
 		scriptsDir:  scriptsDir,
+		fsys:        OSFileSystem{},
 		useParallel: true, // default to parallel extraction
 	}
 	for _, opt := range opts {
@@ -95,17 +211,24 @@ func New(dbPath string, scriptsDir string, opts ...Option) (*Engine, error) {
 	}
 
 	// Build Runtime with the appropriate script source.
-	var rtOpts []runtime.RuntimeOption
+	rtOpts := []runtime.RuntimeOption{runtime.WithRuntimeReadFile(e.readSource)}
 	if e.scriptsFS != nil {
 		rtOpts = append(rtOpts, runtime.WithRuntimeFS(e.scriptsFS))
 	}
 	e.runtime = runtime.NewRuntime(s, scriptsDir, rtOpts...)
 
+	if err := e.openParseCache(); err != nil {
+		s.Close()
+		return nil, fmt.Errorf("canopy: %w", err)
+	}
 	return e, nil
 }
 
 // Close releases the Engine's database resources.
 func (e *Engine) Close() error {
+	if e.parseCache != nil {
+		e.parseCache.Close()
+	}
 	return e.store.Close()
 }
 
@@ -170,9 +293,15 @@ func (e *Engine) storeScriptsHash() {
 	_ = e.store.SetMetadata("scripts_hash", e.scriptsHash())
 }
 
+// clearScriptsHash forgets the stored scripts hash, so ScriptsChanged asks
+// for a rebuild.
+func (e *Engine) clearScriptsHash() {
+	_ = e.store.SetMetadata("scripts_hash", "")
+}
+
 // Query returns a new QueryBuilder wrapping the Store.
 func (e *Engine) Query() *QueryBuilder {
-	return &QueryBuilder{store: e.store}
+	return &QueryBuilder{store: e.store, readFile: e.readSource, usage: e.usage}
 }
 
 // symbolKey uniquely identifies a symbol by (name, kind, parent_symbol_id).
@@ -248,7 +377,14 @@ func (e *Engine) captureSymbols(fileID int64) ([]capturedSymbol, error) {
 // 7. Capture new symbols, compute blast radius
 //
 // Errors on individual files are logged and skipped; processing continues.
-func (e *Engine) IndexFiles(ctx context.Context, paths []string) error {
+// Cancelling ctx stops extraction between files and returns ctx's error;
+// files already extracted stay indexed, and the rest are extracted by the
+// next call.
+func (e *Engine) IndexFiles(ctx context.Context, paths []string) (err error) {
+	span := e.startSpan(SpanIndexFiles, 0, "files", len(paths))
+	defer func() { span.end(err) }()
+	start := time.Now()
+	defer func() { e.reportUsage(UsageIndex, SpanIndexFiles, start, len(paths), err) }()
 	if e.useParallel {
 		return e.IndexFilesParallel(ctx, paths)
 	}
@@ -261,11 +397,20 @@ func (e *Engine) indexFilesSerial(ctx context.Context, paths []string) error {
 	if e.blastRadius == nil {
 		e.blastRadius = make(map[int64]bool)
 	}
+	progress := e.startProgress(PhaseExtract, len(paths))
+	defer func() { e.extracted = progress.count() }()
 	var errs []error
 	for _, path := range paths {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
 		if err := e.indexFile(ctx, path); err != nil {
+			if ctx.Err() != nil {
+				return ctx.Err()
+			}
 			errs = append(errs, fmt.Errorf("index %s: %w", path, err))
 		}
+		progress.step(path)
 	}
 	if len(errs) > 0 {
 		return fmt.Errorf("indexing had %d error(s): %w", len(errs), errs[0])
@@ -282,7 +427,7 @@ func (e *Engine) indexFile(ctx context.Context, path string) error {
 		return nil // filtered out
 	}
 
-	content, err := os.ReadFile(path)
+	content, err := e.readSource(path)
 	if err != nil {
 		return fmt.Errorf("read file: %w", err)
 	}
@@ -315,27 +460,27 @@ func (e *Engine) indexFile(ctx context.Context, path string) error {
 		}
 	}
 
-	// Step 3: Insert new file record and run extraction.
-	lineCount := bytes.Count(content, []byte{'\n'}) + 1
-	fileID, err := e.store.InsertFile(&store.File{
+	// Step 3: Reuse the cached extraction of identical content, or insert
+	// a new file record and run extraction.
+	file := &store.File{
 		Path:        path,
 		Language:    lang,
 		Hash:        hash,
-		LineCount:   lineCount,
+		LineCount:   bytes.Count(content, []byte{'\n'}) + 1,
 		LastIndexed: time.Now(),
-	})
-	if err != nil {
-		return fmt.Errorf("insert file: %w", err)
+		Root:        e.rootFor(path),
 	}
-
-	scriptPath := runtime.ExtractionScriptPath(lang)
-	extras := map[string]any{
-		"file_path": path,
-		"file_id":   fileID,
+	span := e.startSpan(SpanExtractFile, 0, "path", path, "language", lang, "bytes", len(content))
+	cached, err := e.loadCachedFile(file)
+	if err == nil && !cached {
+		err = e.extractFileSerial(ctx, file, content)
 	}
-	if err := e.runtime.RunScript(ctx, scriptPath, extras); err != nil {
-		return fmt.Errorf("extraction script: %w", err)
+	span.set("cached", cached)
+	span.end(err)
+	if err != nil {
+		return err
 	}
+	fileID := file.ID
 
 	// Step 4: Capture new symbols and compute blast radius.
 	newSymbols, err := e.captureSymbols(fileID)
@@ -356,6 +501,66 @@ func (e *Engine) indexFile(ctx context.Context, path string) error {
 	return nil
 }
 
+// extractFileSerial inserts file and runs its extraction, then caches the
+// result.
+func (e *Engine) extractFileSerial(ctx context.Context, file *store.File, content []byte) (err error) {
+	fileID, err := e.store.InsertFile(file)
+	if err != nil {
+		return fmt.Errorf("insert file: %w", err)
+	}
+	// A cancelled extraction leaves no file record behind, so the next run
+	// extracts the file again rather than skipping it as unchanged.
+	defer func() {
+		if err != nil && ctx.Err() != nil {
+			e.removeFile(fileID)
+		}
+	}()
+
+	scriptPath := runtime.ExtractionScriptPath(file.Language)
+	extras := map[string]any{
+		"file_path": file.Path,
+		"file_id":   fileID,
+	}
+	if err := e.runtime.RunScript(ctx, scriptPath, extras); err != nil {
+		if e.degradeErrorShare <= 0 || ctx.Err() != nil {
+			return fmt.Errorf("extraction script: %w", err)
+		}
+		// Keep none of what the script wrote before failing.
+		if err := e.store.DeleteFileData(fileID); err != nil {
+			return fmt.Errorf("delete failed extraction: %w", err)
+		}
+		return e.degradeFile(fileID, file.Language, content, nil, true)
+	}
+	if err := e.recordSpanOffsets(fileID, content); err != nil {
+		return fmt.Errorf("record span offsets: %w", err)
+	}
+	syntaxErrs, err := runtime.SyntaxErrors(ctx, file.Language, content)
+	if err != nil {
+		return fmt.Errorf("syntax errors: %w", err)
+	}
+	if err := e.markPartialSymbols(fileID, syntaxErrs); err != nil {
+		return fmt.Errorf("mark partial symbols: %w", err)
+	}
+	if err := e.degradeFile(fileID, file.Language, content, syntaxErrs, false); err != nil {
+		return fmt.Errorf("degrade: %w", err)
+	}
+	if err := e.recordSyntaxErrors(fileID, syntaxErrs); err != nil {
+		return fmt.Errorf("record syntax errors: %w", err)
+	}
+	fingerprints, err := runtime.FunctionFingerprints(ctx, file.Language, content)
+	if err != nil {
+		return fmt.Errorf("function fingerprints: %w", err)
+	}
+	if err := e.recordFingerprints(fileID, fingerprints); err != nil {
+		return fmt.Errorf("record fingerprints: %w", err)
+	}
+	if err := e.recordImplBlocks(fileID, file.Language, content); err != nil {
+		return fmt.Errorf("record impl blocks: %w", err)
+	}
+	e.cacheFile(fileID, file.Path, file.Hash)
+	return nil
+}
+
 // computeBlastRadius compares old vs new symbols and returns file IDs that need
 // re-resolution.
 func (e *Engine) computeBlastRadius(fileID int64, oldSyms, newSyms []capturedSymbol) []int64 {
@@ -463,24 +668,176 @@ var skipDirs = map[string]bool{
 // IndexDirectory walks root and indexes all files with supported extensions.
 // If root is inside a git repository, uses git ls-files to respect .gitignore.
 // Falls back to filesystem walk (skipping hidden dirs, node_modules, vendor,
-// __pycache__) if git is unavailable.
+// __pycache__, and .gitignore matches) if git is unavailable. Paths matched by
+// .ignore files are skipped either way. WithInclude/WithExclude globs are then
+// applied to the discovered paths. With WithTargetDir, the build script
+// output included by the indexed Rust files is indexed as well, and with
+// WithMacroExpansion the items macro expansion adds to each crate.
 //
 // IndexDirectory is idempotent: after it returns, the database exactly reflects
 // the files currently on disk under root. Files previously indexed under root
 // that no longer exist are removed along with their extraction/resolution data.
-func (e *Engine) IndexDirectory(ctx context.Context, root string) error {
+func (e *Engine) IndexDirectory(ctx context.Context, root string) (err error) {
+	span := e.startSpan(SpanIndexDirectory, 0, "root", root)
+	defer func() { span.end(err) }()
+	start, listed := time.Now(), 0
+	defer func() { e.reportUsage(UsageIndex, SpanIndexDirectory, start, listed, err) }()
+	e.addRoot(root)
+	listSpan := e.startSpan(SpanListFiles, 0, "root", root)
 	paths, err := e.gitListFiles(root)
 	if err != nil {
 		// Not a git repo or git not available — fall back to walk.
 		paths, err = e.walkListFiles(root)
 		if err != nil {
+			listSpan.end(err)
 			return err
 		}
 	}
-	if err := e.removeStaleFiles(root, paths); err != nil {
+	paths = e.filterPaths(root, paths)
+	listSpan.set("files", len(paths))
+	listed = len(paths)
+	listSpan.end(nil)
+	// Build script output and expanded macros are pruned by
+	// indexGeneratedFiles and expandMacros instead.
+	kept := paths
+	for _, dir := range []string{e.targetDir, e.expandDir} {
+		if dir == "" {
+			continue
+		}
+		generated, err := e.indexedUnder(dir)
+		if err != nil {
+			return err
+		}
+		kept = slices.Clone(kept)
+		for _, p := range generated {
+			kept = append(kept, p)
+		}
+	}
+	if err := e.removeStaleFiles(root, kept); err != nil {
 		return fmt.Errorf("remove stale files: %w", err)
 	}
-	return e.IndexFiles(ctx, paths)
+	if len(e.priority) > 0 || !e.deadline.IsZero() {
+		if paths, err = e.rankFiles(root, paths); err != nil {
+			return err
+		}
+	}
+	if e.deadline.IsZero() {
+		err = e.IndexFiles(ctx, paths)
+	} else {
+		// Under a deadline, extract the most important files first and
+		// stop without an error when it passes.
+		extractCtx, cancel := context.WithDeadline(ctx, e.deadline)
+		err = e.IndexFiles(extractCtx, paths)
+		cancel()
+		if ctx.Err() == nil && errors.Is(err, context.DeadlineExceeded) {
+			err = nil
+		}
+		if cerr := e.recordCompleteness(paths); cerr != nil && err == nil {
+			err = cerr
+		}
+	}
+	if err != nil {
+		return err
+	}
+	if e.targetDir != "" {
+		if e.pastDeadline() {
+			e.skipStep(StepBuildOutput)
+		} else if err := e.indexGeneratedFiles(ctx, paths); err != nil {
+			return fmt.Errorf("index build script output: %w", err)
+		}
+	}
+	if e.expandDir != "" {
+		if e.pastDeadline() {
+			e.skipStep(StepMacroExpansion)
+		} else if err := e.expandMacros(ctx, root); err != nil {
+			return fmt.Errorf("expand macros: %w", err)
+		}
+	}
+	// Unchanged files indexed before root tracking keep an empty root.
+	if err := e.store.SetFileRoot(filepath.Clean(root), filepath.Clean(root)+string(filepath.Separator)); err != nil {
+		return err
+	}
+	// Re-apply CODEOWNERS to every file: re-indexed files have new IDs and
+	// the CODEOWNERS file itself may have changed.
+	if err := e.refreshOwners(root); err != nil {
+		return err
+	}
+	// Likewise re-read the Bazel or Buck build graph, which may have changed
+	// without any indexed file changing.
+	if err := e.refreshBuildGraph(root); err != nil {
+		return err
+	}
+	if e.blame && e.pastDeadline() {
+		e.skipStep(StepBlame)
+	} else if e.blame {
+		blameSpan := e.startSpan(SpanBlame, 0, "root", root)
+		err := e.refreshBlame(root)
+		blameSpan.end(err)
+		return err
+	}
+	return nil
+}
+
+// IndexDirectories indexes several root directories into the same database,
+// e.g. the repositories of a multi-repo workspace. Each file records the root
+// it was found under (see File.Root), so queries can span all roots while
+// keeping per-root provenance. Roots are indexed in order; nested roots are
+// allowed and files belong to the deepest enclosing root.
+func (e *Engine) IndexDirectories(ctx context.Context, roots []string) error {
+	for _, root := range roots {
+		e.addRoot(root)
+	}
+	for _, root := range roots {
+		if err := e.IndexDirectory(ctx, root); err != nil {
+			return fmt.Errorf("index %s: %w", root, err)
+		}
+	}
+	return nil
+}
+
+// addRoot registers root for provenance tracking.
+func (e *Engine) addRoot(root string) {
+	root = filepath.Clean(root)
+	for _, r := range e.roots {
+		if r == root {
+			return
+		}
+	}
+	e.roots = append(e.roots, root)
+}
+
+// rootFor returns the deepest registered root containing path, or "".
+func (e *Engine) rootFor(path string) string {
+	best := ""
+	for _, r := range e.roots {
+		if strings.HasPrefix(path, r+string(filepath.Separator)) && len(r) > len(best) {
+			best = r
+		}
+	}
+	return best
+}
+
+// filterPaths applies the include and exclude globs to discovered paths.
+// Globs are matched against each path relative to root.
+func (e *Engine) filterPaths(root string, paths []string) []string {
+	if len(e.include) == 0 && len(e.exclude) == 0 {
+		return paths
+	}
+	var kept []string
+	for _, p := range paths {
+		rel, err := filepath.Rel(root, p)
+		if err != nil {
+			rel = p
+		}
+		if len(e.include) > 0 && !matchAnyGlob(e.include, rel) {
+			continue
+		}
+		if matchAnyGlob(e.exclude, rel) {
+			continue
+		}
+		kept = append(kept, p)
+	}
+	return kept
 }
 
 // removeStaleFiles removes database records for files that were previously
@@ -511,30 +868,53 @@ func (e *Engine) removeStaleFiles(root string, discoveredPaths []string) error {
 		if !strings.HasPrefix(filePath, prefix) {
 			continue
 		}
+		// Files under a nested root are owned by that root's IndexDirectory.
+		if owner := e.rootFor(filePath); owner != "" && owner != filepath.Clean(root) {
+			continue
+		}
 		if discovered[filePath] {
 			continue // still exists on disk
 		}
 
 		// File was indexed but no longer on disk — remove it.
-		oldSymbols, _ := e.captureSymbols(fileID)
-		blastFileIDs := e.computeBlastRadius(fileID, oldSymbols, nil)
-		for _, fid := range blastFileIDs {
-			e.blastRadius[fid] = true
+		if err := e.removeFile(fileID); err != nil {
+			return fmt.Errorf("remove stale file %s: %w", filePath, err)
 		}
+	}
+	return nil
+}
 
-		if err := e.store.DeleteFileData(fileID); err != nil {
-			return fmt.Errorf("delete stale file data %s: %w", filePath, err)
-		}
-		if _, err := e.store.DB().Exec("DELETE FROM files WHERE id = ?", fileID); err != nil {
-			return fmt.Errorf("delete stale file record %s: %w", filePath, err)
+// removeFile deletes an indexed file and its data, adding the files that
+// referenced its symbols to the blast radius. The blast radius must be
+// initialized.
+func (e *Engine) removeFile(fileID int64) error {
+	if e.memo != nil {
+		var path string
+		if err := e.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", fileID).Scan(&path); err == nil {
+			e.memo.invalidate(memoFileDep(path))
 		}
 	}
+	oldSymbols, _ := e.captureSymbols(fileID)
+	blastFileIDs := e.computeBlastRadius(fileID, oldSymbols, nil)
+	for _, fid := range blastFileIDs {
+		e.blastRadius[fid] = true
+	}
+
+	if err := e.store.DeleteFileData(fileID); err != nil {
+		return fmt.Errorf("delete file data: %w", err)
+	}
+	if _, err := e.store.DB().Exec("DELETE FROM files WHERE id = ?", fileID); err != nil {
+		return fmt.Errorf("delete file record: %w", err)
+	}
 	return nil
 }
 
 // gitListFiles uses git ls-files to discover tracked and untracked (but not
 // ignored) files under root, filtered to supported languages.
 func (e *Engine) gitListFiles(root string) ([]string, error) {
+	if _, ok := e.fsys.(OSFileSystem); !ok {
+		return nil, errors.New("git ls-files: files are not on the local disk")
+	}
 	// --cached: tracked files, --others: untracked files,
 	// --exclude-standard: respect .gitignore, .git/info/exclude, global excludes.
 	cmd := exec.Command("git", "ls-files", "--cached", "--others", "--exclude-standard")
@@ -546,10 +926,13 @@ func (e *Engine) gitListFiles(root string) ([]string, error) {
 		return nil, fmt.Errorf("git ls-files: %w", err)
 	}
 
+	// git already applied .gitignore; .ignore files are canopy's own.
+	ignore := newIgnoreMatcher(root, ".ignore")
+
 	var paths []string
 	for _, line := range strings.Split(stdout.String(), "\n") {
 		line = strings.TrimSpace(line)
-		if line == "" {
+		if line == "" || ignore.ignored(line, false) {
 			continue
 		}
 		absPath := filepath.Join(root, line)
@@ -562,20 +945,30 @@ func (e *Engine) gitListFiles(root string) ([]string, error) {
 
 // walkListFiles discovers files by walking the filesystem, used as a fallback
 // when git is not available. Skips hidden directories, node_modules, vendor,
-// and __pycache__.
+// and __pycache__, and honors .gitignore and .ignore files.
 func (e *Engine) walkListFiles(root string) ([]string, error) {
+	ignore := newIgnoreMatcher(root, ".gitignore", ".ignore")
+	ignore.readFile = e.fsys.ReadFile
+
 	var paths []string
-	err := filepath.WalkDir(root, func(path string, d fs.DirEntry, err error) error {
+	err := e.fsys.WalkDir(root, func(path string, d fs.DirEntry, err error) error {
 		if err != nil {
 			return err
 		}
+		rel, _ := filepath.Rel(root, path)
 		if d.IsDir() {
+			if rel == "." {
+				return nil
+			}
 			name := d.Name()
-			if strings.HasPrefix(name, ".") || skipDirs[name] {
+			if strings.HasPrefix(name, ".") || skipDirs[name] || ignore.ignoredEntry(filepath.ToSlash(rel), true) {
 				return filepath.SkipDir
 			}
 			return nil
 		}
+		if ignore.ignoredEntry(filepath.ToSlash(rel), false) {
+			return nil
+		}
 		if _, ok := runtime.LanguageForFile(path); ok {
 			paths = append(paths, path)
 		}
@@ -594,18 +987,69 @@ func (e *Engine) walkListFiles(root string) ([]string, error) {
 // Resolution scripts receive a files_to_resolve function that returns only
 // the files needing resolution, while files_by_language continues to return
 // all files (needed for cross-file lookup caches).
-func (e *Engine) Resolve(ctx context.Context) error {
+//
+// Cancelling ctx stops the scripts and returns ctx's error. Resolution data
+// may then be incomplete, so Resolve clears the stored scripts hash: the next
+// ScriptsChanged call reports true and the caller rebuilds the database.
+func (e *Engine) Resolve(ctx context.Context) (err error) {
 	defer func() { e.blastRadius = nil }()
 
+	// Links between roots depend on the declared root dependencies.
+	changed, err := e.rootDependenciesChanged()
+	if err != nil {
+		return fmt.Errorf("check root dependencies: %w", err)
+	}
+	if changed {
+		e.blastRadius = nil
+	}
+
 	// Non-nil empty blast radius means no files changed — skip resolution.
 	if e.blastRadius != nil && len(e.blastRadius) == 0 {
 		return nil
 	}
+	if err := ctx.Err(); err != nil {
+		e.clearScriptsHash()
+		return err
+	}
 
 	langs, err := e.distinctLanguages()
 	if err != nil {
 		return fmt.Errorf("list languages: %w", err)
 	}
+	blastFiles := -1
+	if e.blastRadius != nil {
+		blastFiles = len(e.blastRadius)
+	}
+	span := e.startSpan(SpanResolve, 0, "languages", langs, "files", blastFiles)
+	defer func() { span.end(err) }()
+	start := time.Now()
+	defer func() { e.reportUsage(UsageResolve, SpanResolve, start, len(langs), err) }()
+
+	// Rust references to the standard library resolve to built-in stubs.
+	if slices.Contains(langs, "rust") {
+		n, err := e.loadRustStdStubs()
+		if err != nil {
+			return err
+		}
+		if n > 0 && e.blastRadius != nil {
+			if err := e.addRustFilesToBlastRadius(); err != nil {
+				return err
+			}
+		}
+	}
+
+	// Remembered answers about the blast radius go stale, and everything
+	// does if resolution fails or covers every file.
+	staleDeps, err := e.memoDeps()
+	if err != nil {
+		return err
+	}
+	memoUpdated := false
+	defer func() {
+		if !memoUpdated {
+			e.memo.reset()
+		}
+	}()
 
 	// Delete resolution data for affected files before re-running scripts.
 	if e.blastRadius != nil {
@@ -638,11 +1082,30 @@ func (e *Engine) Resolve(ctx context.Context) error {
 		}
 	}
 
+	// SQL references and FFI links are rebuilt after the scripts run;
+	// until then they must not be mistaken for references of the scripts.
+	if slices.Contains(langs, "rust") {
+		if err := e.clearSQLReferences(); err != nil {
+			return err
+		}
+		if err := e.clearForeignLinks(); err != nil {
+			return err
+		}
+	}
+
 	// Pass files_to_resolve as an extra global. It filters FilesByLanguage
 	// to only files in the blast radius (or returns all files on full resolve).
 	extras := map[string]any{
 		"files_to_resolve": runtime.MakeFilesToResolveFn(e.store, e.blastRadius),
 	}
+	// Rust use paths resolve by the edition of the file's package.
+	if slices.Contains(langs, "rust") {
+		crates, err := e.rustCrateEditions()
+		if err != nil {
+			return fmt.Errorf("rust crates: %w", err)
+		}
+		extras["rust_crates"] = crates
+	}
 
 	// Run resolution scripts in parallel (one per language).
 	var (
@@ -650,33 +1113,94 @@ func (e *Engine) Resolve(ctx context.Context) error {
 		wg   sync.WaitGroup
 		errs []error
 	)
-	for _, lang := range langs {
+	progress := e.startProgress(PhaseResolve, len(langs))
+	for i, lang := range langs {
 		wg.Add(1)
 		go func(l string) {
 			defer wg.Done()
+			span := e.startSpan(SpanResolveLanguage, i+1, "language", l)
 			scriptPath := runtime.ResolutionScriptPath(l)
-			if err := e.runtime.RunScript(ctx, scriptPath, extras); err != nil {
+			err := e.runtime.RunScript(ctx, scriptPath, extras)
+			span.end(err)
+			if err != nil {
 				mu.Lock()
 				errs = append(errs, fmt.Errorf("resolution script for %s: %w", l, err))
 				mu.Unlock()
+				return
 			}
+			progress.step(l)
 		}(lang)
 	}
 	wg.Wait()
 
+	if err := ctx.Err(); err != nil {
+		e.clearScriptsHash()
+		return err
+	}
 	if len(errs) > 0 {
 		return fmt.Errorf("resolution had %d error(s): %w", len(errs), errs[0])
 	}
 
+	// References to types generated from protobuf land on the schema.
+	if slices.Contains(langs, "protobuf") && slices.Contains(langs, "rust") {
+		if err := e.linkGeneratedTypes(); err != nil {
+			return err
+		}
+	}
+
+	// Rust FFI items link to their counterparts in C, C++, JavaScript, and
+	// TypeScript.
+	if slices.Contains(langs, "rust") {
+		if err := e.linkForeignFunctions(langs); err != nil {
+			return err
+		}
+	}
+
+	if e.sqlRefs && slices.Contains(langs, "rust") {
+		if err := e.indexSQLReferences(); err != nil {
+			return err
+		}
+	}
+
+	if err := e.pruneRootLinks(); err != nil {
+		return err
+	}
+
+	// Aliased imports are recorded after every resolution step, so they
+	// follow the links that survive pruning.
+	if _, err := e.store.RebuildImportAliases(); err != nil {
+		return err
+	}
+
+	if err := e.capFallbackConfidence(); err != nil {
+		return err
+	}
+
+	centralitySpan := e.startSpan(SpanCentrality, 0)
+	err = e.computeCentrality()
+	centralitySpan.end(err)
+	if err != nil {
+		return fmt.Errorf("compute centrality: %w", err)
+	}
+
 	// Store the current scripts hash so future runs can detect changes.
 	e.storeScriptsHash()
 
+	if e.blastRadius != nil {
+		resolvedDeps, err := e.memoDeps()
+		if err != nil {
+			return err
+		}
+		e.memo.invalidate(append(staleDeps, resolvedDeps...)...)
+		memoUpdated = true
+	}
 	return nil
 }
 
-// distinctLanguages returns all languages that have at least one file in the Store.
+// distinctLanguages returns all languages that have at least one file in the
+// Store. Rustdoc stub files are skipped: they have no resolution script.
 func (e *Engine) distinctLanguages() ([]string, error) {
-	rows, err := e.store.DB().Query("SELECT DISTINCT language FROM files")
+	rows, err := e.store.DB().Query("SELECT DISTINCT language FROM files WHERE language != ? ORDER BY language", rustdocStubLanguage)
 	if err != nil {
 		return nil, err
 	}
@@ -5,7 +5,6 @@ import (
 	"context"
 	"crypto/sha256"
 	"fmt"
-	"os"
 	"runtime"
 	"sync"
 	"time"
@@ -16,10 +15,18 @@ import (
 
 // workItem holds everything a parallel extraction worker needs.
 type workItem struct {
-	path   string
-	lang   string
-	fileID int64
-	batch  *store.BatchedStore
+	path    string
+	lang    string
+	fileID  int64
+	batch   *store.BatchedStore
+	content []byte
+	hash    string
+
+	// Syntax errors found by the worker, for marking partial symbols.
+	syntaxErrs []canopyrt.ErrorSpan
+
+	// Function body fingerprints computed by the worker, for clone detection.
+	fingerprints []canopyrt.BodyFingerprint
 
 	// Pre-captured old symbols for blast radius computation after commit.
 	oldSymbols []capturedSymbol
@@ -30,56 +37,118 @@ type workItem struct {
 //	Phase A (serial):  Hash check, delete old data, prepare file records.
 //	Phase B (parallel): Parse and extract via worker pool (each with own Runtime).
 //	Phase C (serial):  Commit batches to SQLite, compute blast radius.
+//
+// With WithMaxMemory, files go through the pipeline in chunks that fit the
+// budget, each committed to SQLite before the next is read.
 func (e *Engine) IndexFilesParallel(ctx context.Context, paths []string) error {
 	if e.blastRadius == nil {
 		e.blastRadius = make(map[int64]bool)
 	}
 
-	// ---- Phase A: Serial file preparation ----
-	var items []workItem
-	for _, path := range paths {
-		item, skip, err := e.prepareFile(ctx, path)
-		if err != nil {
-			return fmt.Errorf("prepare %s: %w", path, err)
+	workers, err := e.startWorkerProcesses()
+	if err != nil {
+		return err
+	}
+	defer stopWorkerProcesses(workers)
+
+	progress := e.startProgress(PhaseExtract, len(paths))
+	defer func() { e.extracted = progress.count() }()
+	var errs []error
+	for len(paths) > 0 {
+		// ---- Phase A: Serial file preparation ----
+		var items []workItem
+		var held int64
+		for len(paths) > 0 && (e.maxMemory <= 0 || held < e.maxMemory) {
+			path := paths[0]
+			paths = paths[1:]
+			if err := ctx.Err(); err != nil {
+				return e.abandonItems(items, err)
+			}
+			item, skip, err := e.prepareFile(ctx, path)
+			if err != nil {
+				return fmt.Errorf("prepare %s: %w", path, err)
+			}
+			if skip {
+				progress.step(path)
+				continue
+			}
+			items = append(items, item)
+			held += int64(len(item.content)) * extractMemoryFactor
 		}
-		if skip {
+		if len(items) == 0 {
 			continue
 		}
-		items = append(items, item)
+
+		chunkErrs, err := e.extractItems(ctx, items, workers, progress)
+		if err != nil {
+			return err
+		}
+		errs = append(errs, chunkErrs...)
 	}
 
-	if len(items) == 0 {
-		return nil
+	if len(errs) > 0 {
+		return fmt.Errorf("parallel indexing had %d error(s): %w", len(errs), errs[0])
 	}
+	return nil
+}
 
+// extractMemoryFactor estimates the memory extracting a file takes, as a
+// multiple of its size: its syntax tree and the symbols, scopes, and
+// references buffered for it until commit.
+const extractMemoryFactor = 32
+
+// extractItems runs Phases B and C over prepared items, in workers if
+// there are any. It returns the errors of individual files, and a non-nil
+// error only if ctx is cancelled.
+func (e *Engine) extractItems(ctx context.Context, items []workItem, workers []*workerProcess, progress *progressTracker) ([]error, error) {
 	// ---- Phase B: Parallel extraction ----
+	// Each goroutine takes the next item from workCh when it is done with
+	// its last, in this process or in its worker process.
 	numWorkers := min(runtime.NumCPU(), len(items))
+	if len(workers) > 0 {
+		numWorkers = min(len(workers), len(items))
+	}
 	if numWorkers < 1 {
 		numWorkers = 1
 	}
 
-	workCh := make(chan workItem, len(items))
-	for _, item := range items {
-		workCh <- item
+	type job struct {
+		index int
+		item  workItem
+	}
+	workCh := make(chan job, len(items))
+	for i, item := range items {
+		workCh <- job{index: i, item: item}
 	}
 	close(workCh)
 
 	type result struct {
-		item workItem
-		err  error
+		index int
+		item  workItem
+		err   error
 	}
 	resultCh := make(chan result, len(items))
 
 	var wg sync.WaitGroup
-	for range numWorkers {
+	for worker := range numWorkers {
 		wg.Add(1)
 		go func() {
 			defer wg.Done()
 			// Each worker gets its own Runtime with a fresh sourceStore.
 			// The BatchedStore per item handles write isolation.
-			for item := range workCh {
-				err := e.extractFile(ctx, item)
-				resultCh <- result{item: item, err: err}
+			for j := range workCh {
+				item := j.item
+				err := ctx.Err()
+				if err == nil {
+					span := e.startSpan(SpanExtractFile, worker+1, "path", item.path, "language", item.lang, "bytes", len(item.content))
+					if len(workers) > 0 {
+						err = workers[worker].extract(ctx, e, &item, span)
+					} else {
+						err = e.extractFile(ctx, &item)
+					}
+					span.end(err)
+				}
+				resultCh <- result{index: j.index, item: item, err: err}
 			}
 		}()
 	}
@@ -90,39 +159,115 @@ func (e *Engine) IndexFilesParallel(ctx context.Context, paths []string) error {
 	}()
 
 	// ---- Phase C: Serial commit ----
+	// Results are committed in the order of items, not the order workers
+	// finish them, so symbol and reference IDs, and any output ordered by
+	// them, do not vary from run to run.
 	var errs []error
-	for res := range resultCh {
-		if res.err != nil {
-			errs = append(errs, fmt.Errorf("extract %s: %w", res.item.path, res.err))
-			continue
+	var abandoned []workItem
+	pending := make(map[int]result)
+	next := 0
+	for arrived := range resultCh {
+		pending[arrived.index] = arrived
+		for {
+			res, ok := pending[next]
+			if !ok {
+				break
+			}
+			delete(pending, next)
+			next++
+			if err := e.commitResult(ctx, res.item, res.err, progress, &abandoned); err != nil {
+				errs = append(errs, err)
+			}
 		}
+	}
 
-		if err := e.store.CommitBatch(res.item.batch); err != nil {
-			errs = append(errs, fmt.Errorf("commit %s: %w", res.item.path, err))
-			continue
-		}
+	if err := ctx.Err(); err != nil {
+		return nil, e.abandonItems(abandoned, err)
+	}
+	return errs, nil
+}
 
-		// Capture new symbols and compute blast radius (now that data is committed).
-		newSymbols, err := e.captureSymbols(res.item.fileID)
-		if err != nil {
-			errs = append(errs, fmt.Errorf("capture new symbols %s: %w", res.item.path, err))
-			continue
+// commitResult runs Phase C for one extracted item: it writes the item's
+// batch and derived data and widens the blast radius. An item reached after
+// ctx is cancelled is added to abandoned instead. With WithDegradation, an
+// item whose extraction failed is indexed by the fallback scanner.
+func (e *Engine) commitResult(ctx context.Context, item workItem, extractErr error, progress *progressTracker, abandoned *[]workItem) error {
+	if ctx.Err() != nil {
+		*abandoned = append(*abandoned, item)
+		return nil
+	}
+	progress.step(item.path)
+	if extractErr != nil {
+		if e.degradeErrorShare <= 0 {
+			return fmt.Errorf("extract %s: %w", item.path, extractErr)
 		}
-
-		blastFileIDs := e.computeBlastRadius(res.item.fileID, res.item.oldSymbols, newSymbols)
-		for _, fid := range blastFileIDs {
-			e.blastRadius[fid] = true
+		// The failed extraction's batch is dropped uncommitted.
+		if err := e.degradeFile(item.fileID, item.lang, item.content, nil, true); err != nil {
+			return fmt.Errorf("degrade %s: %w", item.path, err)
 		}
+	} else if err := e.commitExtraction(item); err != nil {
+		return err
 	}
 
-	if len(errs) > 0 {
-		return fmt.Errorf("parallel indexing had %d error(s): %w", len(errs), errs[0])
+	// Capture new symbols and compute blast radius (now that data is committed).
+	newSymbols, err := e.captureSymbols(item.fileID)
+	if err != nil {
+		return fmt.Errorf("capture new symbols %s: %w", item.path, err)
+	}
+
+	blastFileIDs := e.computeBlastRadius(item.fileID, item.oldSymbols, newSymbols)
+	for _, fid := range blastFileIDs {
+		e.blastRadius[fid] = true
 	}
 	return nil
 }
 
+// commitExtraction writes an extracted item's batch and the data derived
+// from its extraction, and caches it.
+func (e *Engine) commitExtraction(item workItem) error {
+	span := e.startSpan(SpanCommitFile, 0, "path", item.path)
+	err := e.store.CommitBatch(item.batch)
+	span.end(err)
+	if err != nil {
+		return fmt.Errorf("commit %s: %w", item.path, err)
+	}
+	if err := e.recordSpanOffsets(item.fileID, item.content); err != nil {
+		return fmt.Errorf("record span offsets %s: %w", item.path, err)
+	}
+	if err := e.markPartialSymbols(item.fileID, item.syntaxErrs); err != nil {
+		return fmt.Errorf("mark partial symbols %s: %w", item.path, err)
+	}
+	if err := e.degradeFile(item.fileID, item.lang, item.content, item.syntaxErrs, false); err != nil {
+		return fmt.Errorf("degrade %s: %w", item.path, err)
+	}
+	if err := e.recordSyntaxErrors(item.fileID, item.syntaxErrs); err != nil {
+		return fmt.Errorf("record syntax errors %s: %w", item.path, err)
+	}
+	if err := e.recordFingerprints(item.fileID, item.fingerprints); err != nil {
+		return fmt.Errorf("record fingerprints %s: %w", item.path, err)
+	}
+	if err := e.recordImplBlocks(item.fileID, item.lang, item.content); err != nil {
+		return fmt.Errorf("record impl blocks %s: %w", item.path, err)
+	}
+	e.cacheFile(item.fileID, item.path, item.hash)
+	return nil
+}
+
+// abandonItems removes the file records prepared for items that will not be
+// extracted because indexing was cancelled, so the next run extracts them
+// rather than skipping them as unchanged, and returns cause.
+func (e *Engine) abandonItems(items []workItem, cause error) error {
+	for _, item := range items {
+		if err := e.removeFile(item.fileID); err != nil {
+			return fmt.Errorf("%w (and removing %s: %v)", cause, item.path, err)
+		}
+	}
+	return cause
+}
+
 // prepareFile does Phase A work for a single file: hash check, cleanup, file record.
-// Returns (item, skip, error). skip=true means the file is unchanged or unsupported.
+// Returns (item, skip, error). skip=true means the file is unchanged or
+// unsupported, or was indexed from the parse cache.
 func (e *Engine) prepareFile(_ context.Context, path string) (workItem, bool, error) {
 	lang, ok := canopyrt.LanguageForFile(path)
 	if !ok {
@@ -132,7 +277,7 @@ func (e *Engine) prepareFile(_ context.Context, path string) (workItem, bool, er
 		return workItem{}, true, nil
 	}
 
-	content, err := os.ReadFile(path)
+	content, err := e.readSource(path)
 	if err != nil {
 		return workItem{}, false, fmt.Errorf("read file: %w", err)
 	}
@@ -165,15 +310,33 @@ func (e *Engine) prepareFile(_ context.Context, path string) (workItem, bool, er
 		}
 	}
 
-	// Insert new file record (real ID assigned by SQLite).
-	lineCount := bytes.Count(content, []byte{'\n'}) + 1
-	fileID, err := e.store.InsertFile(&store.File{
+	file := &store.File{
 		Path:        path,
 		Language:    lang,
 		Hash:        hash,
-		LineCount:   lineCount,
+		LineCount:   bytes.Count(content, []byte{'\n'}) + 1,
 		LastIndexed: time.Now(),
-	})
+		Root:        e.rootFor(path),
+	}
+
+	// Identical content extracted before needs no worker.
+	cached, err := e.loadCachedFile(file)
+	if err != nil {
+		return workItem{}, false, err
+	}
+	if cached {
+		newSymbols, err := e.captureSymbols(file.ID)
+		if err != nil {
+			return workItem{}, false, fmt.Errorf("capture new symbols: %w", err)
+		}
+		for _, fid := range e.computeBlastRadius(file.ID, oldSymbols, newSymbols) {
+			e.blastRadius[fid] = true
+		}
+		return workItem{}, true, nil
+	}
+
+	// Insert new file record (real ID assigned by SQLite).
+	fileID, err := e.store.InsertFile(file)
 	if err != nil {
 		return workItem{}, false, fmt.Errorf("insert file: %w", err)
 	}
@@ -184,15 +347,18 @@ func (e *Engine) prepareFile(_ context.Context, path string) (workItem, bool, er
 		lang:       lang,
 		fileID:     fileID,
 		batch:      batch,
+		content:    content,
+		hash:       hash,
 		oldSymbols: oldSymbols,
 	}, false, nil
 }
 
-// extractFile runs the extraction script for a single file using a BatchedStore.
+// extractFile runs the extraction script for a single file using a BatchedStore
+// and records the file's syntax errors and function fingerprints on item.
 // Each call creates its own Runtime so tree-sitter parsing is goroutine-safe.
-func (e *Engine) extractFile(ctx context.Context, item workItem) error {
+func (e *Engine) extractFile(ctx context.Context, item *workItem) error {
 	// Create a per-worker Runtime backed by the BatchedStore.
-	var rtOpts []canopyrt.RuntimeOption
+	rtOpts := []canopyrt.RuntimeOption{canopyrt.WithRuntimeReadFile(e.readSource)}
 	if e.scriptsFS != nil {
 		rtOpts = append(rtOpts, canopyrt.WithRuntimeFS(e.scriptsFS))
 	}
@@ -206,5 +372,15 @@ func (e *Engine) extractFile(ctx context.Context, item workItem) error {
 	if err := rt.RunScript(ctx, scriptPath, extras); err != nil {
 		return fmt.Errorf("extraction script: %w", err)
 	}
+	syntaxErrs, err := canopyrt.SyntaxErrors(ctx, item.lang, item.content)
+	if err != nil {
+		return err
+	}
+	item.syntaxErrs = syntaxErrs
+	fingerprints, err := canopyrt.FunctionFingerprints(ctx, item.lang, item.content)
+	if err != nil {
+		return err
+	}
+	item.fingerprints = fingerprints
 	return nil
 }
@@ -14,10 +14,10 @@ import (
 	"github.com/stretchr/testify/require"
 )
 
-func newTestEngine(t *testing.T) *Engine {
+func newTestEngine(t *testing.T, opts ...Option) *Engine {
 	t.Helper()
 	dbPath := filepath.Join(t.TempDir(), "test.db")
-	e, err := New(dbPath, t.TempDir())
+	e, err := New(dbPath, t.TempDir(), opts...)
 	require.NoError(t, err)
 	t.Cleanup(func() { e.Close() })
 	return e
@@ -198,6 +198,38 @@ func TestIndexDirectory_SkipsExcludedDirs(t *testing.T) {
 	require.NoError(t, err)
 }
 
+func TestIndexDirectory_ExcludeGlobs(t *testing.T) {
+	root := t.TempDir()
+	gen := filepath.Join(root, "gen")
+	require.NoError(t, os.MkdirAll(gen, 0755))
+	require.NoError(t, os.WriteFile(filepath.Join(gen, "api.go"), []byte("package gen"), 0644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "schema.pb.go"), []byte("package main"), 0644))
+
+	e := newTestEngine(t, WithExclude("gen/**", "*.pb.go"))
+
+	// Every Go file is excluded → nothing reaches extraction → no error.
+	err := e.IndexDirectory(context.Background(), root)
+	require.NoError(t, err)
+}
+
+func TestIndexDirectory_IncludeGlobs(t *testing.T) {
+	root := t.TempDir()
+	src := filepath.Join(root, "src")
+	require.NoError(t, os.MkdirAll(src, 0755))
+	require.NoError(t, os.WriteFile(filepath.Join(src, "lib.go"), []byte("package src"), 0644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "tool.go"), []byte("package main"), 0644))
+
+	// Only docs/ is included, so neither Go file is indexed.
+	e := newTestEngine(t, WithInclude("docs/**"))
+	require.NoError(t, e.IndexDirectory(context.Background(), root))
+
+	// Including src/ reaches extraction (which fails without scripts).
+	e = newTestEngine(t, WithInclude("src/**"))
+	err := e.IndexDirectory(context.Background(), root)
+	require.Error(t, err)
+	require.Contains(t, err.Error(), "extraction script")
+}
+
 func TestResolve_NoFiles(t *testing.T) {
 	e := newTestEngine(t)
 
@@ -224,6 +256,7 @@ func TestDistinctLanguages(t *testing.T) {
 	assert.Len(t, langs, 2)
 	assert.Contains(t, langs, "go")
 	assert.Contains(t, langs, "python")
+	assert.Equal(t, []string{"go", "python"}, langs, "languages are sorted")
 }
 
 // --- WithScriptsFS tests ---
@@ -338,3 +371,21 @@ func TestNewQueryBuilder(t *testing.T) {
 	assert.Equal(t, 0, result.TotalCount)
 	assert.Empty(t, result.Items)
 }
+
+func TestIndexFilesParallel_MaxMemory(t *testing.T) {
+	// A budget below one file's footprint extracts and commits one file at
+	// a time; the result is the same as unbounded.
+	steps := 0
+	e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(true), WithMaxMemory(1),
+		WithProgress(func(Progress) { steps++ }))
+	paths := writeProgressFiles(t, 5)
+
+	require.NoError(t, e.IndexFiles(context.Background(), paths))
+	require.NoError(t, e.Resolve(context.Background()))
+	assert.Equal(t, len(paths), steps)
+	for i := range paths {
+		syms, err := e.Query().SearchSymbols(fmt.Sprintf("F%d", i), SymbolFilter{}, Sort{}, Pagination{})
+		require.NoError(t, err)
+		assert.Len(t, syms.Items, 1, "F%d", i)
+	}
+}
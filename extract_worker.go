@@ -0,0 +1,189 @@
+package canopy
+
+import (
+	"bufio"
+	"context"
+	"encoding/gob"
+	"errors"
+	"fmt"
+	"io"
+	"os/exec"
+	"path/filepath"
+
+	canopyrt "github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// WithWorkerProcesses makes parallel extraction run in n worker processes
+// rather than goroutines of this one, for very large repositories where
+// extraction stops scaling past a dozen or so threads because they contend
+// for one allocator and garbage collector. command starts a worker: a
+// process that calls RunExtractWorker on its stdin and stdout, usually the
+// host program itself re-run with an argument that selects that mode.
+// Files go to whichever worker is idle, and their extractions come back to
+// be committed here in the usual order. A worker that fails is not
+// restarted; its share of the files is extracted in this process instead,
+// and the failure is reported to a WithTracer callback as the
+// "worker_error" field of the file's extract_file span. The worker's
+// stderr is discarded unless command sets cmd.Stderr. Enables WithParallel.
+func WithWorkerProcesses(n int, command func() *exec.Cmd) Option {
+	return func(e *Engine) {
+		e.useParallel = true
+		e.workerProcesses = n
+		e.workerCommand = command
+	}
+}
+
+// extractRequest asks a worker process to extract one file.
+type extractRequest struct {
+	Path    string
+	Lang    string
+	FileID  int64
+	Content []byte
+}
+
+// extractResponse answers an extractRequest. It is followed on the stream
+// by the BatchedStore holding the file's extraction.
+type extractResponse struct {
+	Err          string
+	SyntaxErrs   []canopyrt.ErrorSpan
+	Fingerprints []canopyrt.BodyFingerprint
+}
+
+// RunExtractWorker serves the extraction requests of an Engine created
+// with WithWorkerProcesses, reading them from r and writing the results
+// to w, until r ends. dbPath is the parent's database, which extraction
+// scripts read for lookups but the worker never writes. scriptsDir and
+// opts are as for New; only the options that locate scripts matter.
+func RunExtractWorker(ctx context.Context, dbPath, scriptsDir string, r io.Reader, w io.Writer, opts ...Option) error {
+	s, err := store.NewStore(dbPath)
+	if err != nil {
+		return fmt.Errorf("extract worker: %w", err)
+	}
+	defer s.Close()
+	e := &Engine{store: s, scriptsDir: scriptsDir, fsys: OSFileSystem{}}
+	for _, opt := range opts {
+		opt(e)
+	}
+
+	bw := bufio.NewWriter(w)
+	dec := gob.NewDecoder(bufio.NewReader(r))
+	enc := gob.NewEncoder(bw)
+	for {
+		var req extractRequest
+		if err := dec.Decode(&req); err != nil {
+			if errors.Is(err, io.EOF) {
+				return nil
+			}
+			return fmt.Errorf("extract worker: %w", err)
+		}
+		// The request carries the content, which scripts read through
+		// the overlay rather than from disk.
+		e.overlays = map[string][]byte{filepath.Clean(req.Path): req.Content}
+		item := workItem{
+			path:    req.Path,
+			lang:    req.Lang,
+			fileID:  req.FileID,
+			batch:   store.NewBatchedStore(s),
+			content: req.Content,
+		}
+		var resp extractResponse
+		if err := e.extractFile(ctx, &item); err != nil {
+			resp.Err = err.Error()
+		}
+		resp.SyntaxErrs = item.syntaxErrs
+		resp.Fingerprints = item.fingerprints
+		if err := enc.Encode(resp); err != nil {
+			return fmt.Errorf("extract worker: %w", err)
+		}
+		if err := enc.Encode(item.batch); err != nil {
+			return fmt.Errorf("extract worker: %w", err)
+		}
+		if err := bw.Flush(); err != nil {
+			return fmt.Errorf("extract worker: %w", err)
+		}
+	}
+}
+
+// workerProcess is a running worker process. A nil *workerProcess, or one
+// that failed, extracts in this process.
+type workerProcess struct {
+	cmd    *exec.Cmd
+	stdin  io.WriteCloser
+	enc    *gob.Encoder
+	dec    *gob.Decoder
+	failed bool
+}
+
+// startWorkerProcesses starts the WithWorkerProcesses workers, or returns
+// nil without them.
+func (e *Engine) startWorkerProcesses() ([]*workerProcess, error) {
+	if e.workerProcesses <= 0 || e.workerCommand == nil {
+		return nil, nil
+	}
+	var workers []*workerProcess
+	for range e.workerProcesses {
+		cmd := e.workerCommand()
+		stdin, err := cmd.StdinPipe()
+		if err == nil {
+			var stdout io.ReadCloser
+			if stdout, err = cmd.StdoutPipe(); err == nil {
+				if err = cmd.Start(); err == nil {
+					workers = append(workers, &workerProcess{
+						cmd:   cmd,
+						stdin: stdin,
+						enc:   gob.NewEncoder(stdin),
+						dec:   gob.NewDecoder(bufio.NewReader(stdout)),
+					})
+					continue
+				}
+			}
+		}
+		stopWorkerProcesses(workers)
+		return nil, fmt.Errorf("start extract worker: %w", err)
+	}
+	return workers, nil
+}
+
+// stopWorkerProcesses ends the input of each worker and waits for it to
+// exit.
+func stopWorkerProcesses(workers []*workerProcess) {
+	for _, w := range workers {
+		w.stdin.Close()
+	}
+	for _, w := range workers {
+		_ = w.cmd.Wait()
+	}
+}
+
+// extract extracts item in the worker process, falling back to this
+// process if the worker is nil or has failed. Errors of the extraction
+// itself are returned; a broken worker is marked failed instead, and its
+// failure recorded on span.
+func (w *workerProcess) extract(ctx context.Context, e *Engine, item *workItem, span *activeSpan) error {
+	if w == nil || w.failed {
+		return e.extractFile(ctx, item)
+	}
+	req := extractRequest{Path: item.path, Lang: item.lang, FileID: item.fileID, Content: item.content}
+	var resp extractResponse
+	err := w.enc.Encode(req)
+	if err == nil {
+		err = w.dec.Decode(&resp)
+	}
+	if err == nil {
+		err = w.dec.Decode(item.batch)
+	}
+	if err != nil {
+		span.set("worker_error", fmt.Sprintf("extract worker %d failed: %v", w.cmd.Process.Pid, err))
+		w.failed = true
+		w.stdin.Close()
+		item.batch = store.NewBatchedStore(e.store)
+		return e.extractFile(ctx, item)
+	}
+	item.syntaxErrs = resp.SyntaxErrs
+	item.fingerprints = resp.Fingerprints
+	if resp.Err != "" {
+		return errors.New(resp.Err)
+	}
+	return nil
+}
@@ -0,0 +1,75 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// TestExtractWorkerProcess is the worker process of TestWorkerProcesses,
+// which runs this test binary with the worker's database in the
+// environment. Run as a test, it is skipped.
+func TestExtractWorkerProcess(t *testing.T) {
+	db := os.Getenv("CANOPY_TEST_WORKER_DB")
+	if db == "" {
+		t.Skip("only run as a worker process")
+	}
+	if err := RunExtractWorker(context.Background(), db, os.Getenv("CANOPY_TEST_WORKER_SCRIPTS"), os.Stdin, os.Stdout); err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+	os.Exit(0)
+}
+
+func TestWorkerProcesses(t *testing.T) {
+	scriptsDir := filepath.Join(findModuleRoot(t), "scripts")
+	for _, tc := range []struct {
+		name string
+		env  bool
+	}{
+		{"workers", true},
+		{"broken workers", false}, // they print test output, so files are extracted in process
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			dbPath := filepath.Join(t.TempDir(), "index.db")
+			started := 0
+			var workerErrs []string
+			tracer := WithTracer(func(s Span) {
+				if msg, ok := s.Fields["worker_error"].(string); ok {
+					workerErrs = append(workerErrs, msg)
+				}
+			})
+			e, err := New(dbPath, scriptsDir, WithLanguages("go"), tracer, WithWorkerProcesses(2, func() *exec.Cmd {
+				started++
+				cmd := exec.Command(os.Args[0], "-test.run=^TestExtractWorkerProcess$")
+				if tc.env {
+					cmd.Env = append(os.Environ(), "CANOPY_TEST_WORKER_DB="+dbPath, "CANOPY_TEST_WORKER_SCRIPTS="+scriptsDir)
+				}
+				return cmd
+			}))
+			require.NoError(t, err)
+			defer e.Close()
+
+			paths := writeProgressFiles(t, 6)
+			require.NoError(t, e.IndexFiles(context.Background(), paths))
+			require.NoError(t, e.Resolve(context.Background()))
+			assert.Equal(t, 2, started)
+			if tc.env {
+				assert.Empty(t, workerErrs)
+			} else {
+				assert.NotEmpty(t, workerErrs, "the failures are traced")
+			}
+			for i := range paths {
+				syms, err := e.Query().SearchSymbols(fmt.Sprintf("F%d", i), SymbolFilter{}, Sort{}, Pagination{})
+				require.NoError(t, err)
+				assert.Len(t, syms.Items, 1, "F%d", i)
+			}
+		})
+	}
+}
@@ -0,0 +1,501 @@
+package canopy
+
+import (
+	"database/sql"
+	"fmt"
+	"slices"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Languages Rust FFI items link to: C and C++ through the C ABI, and
+// JavaScript and TypeScript through wasm-bindgen.
+var (
+	ffiNativeLanguages = []string{"c", "cpp"}
+	ffiJSLanguages     = []string{"javascript", "typescript"}
+)
+
+// ffiConfidence is the confidence of a link across the FFI boundary: names
+// are matched as the linker or wasm-bindgen sees them, not bound by either
+// language's scopes.
+const ffiConfidence = 0.9
+
+// wasmBindgenSkipped are the #[wasm_bindgen] options marking an item that
+// does not link to a free function: methods, accessors, and constructors of
+// imported classes, namespaced imports such as console.log, and inline JS.
+var wasmBindgenSkipped = []string{
+	"method", "getter", "setter", "constructor", "static_method_of",
+	"js_namespace", "js_class", "inline_js",
+}
+
+// wasmBindgenItem is a Rust function marked #[wasm_bindgen], itself or
+// through its extern block.
+type wasmBindgenItem struct {
+	jsName  string // the name JavaScript sees: js_name, or the Rust name
+	module  string // module or raw_module of an import; "" for the global scope
+	foreign bool   // declared in an extern block, so imported from JavaScript
+	skip    bool   // carries one of wasmBindgenSkipped
+}
+
+// foreignFunction is a top-level function of another language.
+type foreignFunction struct {
+	sym         *store.Symbol
+	path        string
+	declaration bool // a C or C++ prototype, not a definition
+}
+
+// clearForeignLinks deletes the links recorded by linkForeignFunctions.
+func (e *Engine) clearForeignLinks() error {
+	if err := e.store.DeleteReferencesByContext(RefKindFFI); err != nil {
+		return fmt.Errorf("clear ffi links: %w", err)
+	}
+	if err := e.store.DeleteLinksByKind(CallKindFFI); err != nil {
+		return fmt.Errorf("clear ffi links: %w", err)
+	}
+	return nil
+}
+
+// linkForeignFunctions links Rust FFI items to their counterparts in the
+// other languages of the index, so call graphs cross the boundary. A link
+// is a reference of kind RefKindFFI at the declaration's name, resolving to
+// the definition, and a call edge of kind CallKindFFI between them:
+//
+//   - a function of an extern block links to the C or C++ function
+//     defining its link name;
+//   - a C or C++ prototype links to the Rust function exporting its name
+//     (#[no_mangle], #[export_name]) when no C or C++ file defines it;
+//   - a #[wasm_bindgen] function of an extern block links to the
+//     JavaScript or TypeScript function named by its js_name, in its
+//     module when one is given.
+//
+// JavaScript and TypeScript references to a #[wasm_bindgen] function that
+// their file imports by name from an unindexed module, such as the pkg
+// directory wasm-pack generates, resolve to the Rust function. Names with
+// several candidate counterparts are left unlinked.
+func (e *Engine) linkForeignFunctions(langs []string) error {
+	hasAny := func(want []string) bool {
+		return slices.ContainsFunc(langs, func(l string) bool { return slices.Contains(want, l) })
+	}
+	native, js := hasAny(ffiNativeLanguages), hasAny(ffiJSLanguages)
+	if !native && !js {
+		return nil
+	}
+	bindgen, err := e.wasmBindgenItems()
+	if err != nil {
+		return fmt.Errorf("link ffi: %w", err)
+	}
+	l := &ffiLinker{e: e, sources: map[int64]*lineIndex{}}
+	if native {
+		if err := l.linkNative(bindgen); err != nil {
+			return fmt.Errorf("link ffi: %w", err)
+		}
+	}
+	if js {
+		if err := l.linkWasmBindgen(bindgen); err != nil {
+			return fmt.Errorf("link ffi: %w", err)
+		}
+	}
+	return nil
+}
+
+// ffiLinker records FFI links, reading each declaring file once.
+type ffiLinker struct {
+	e       *Engine
+	sources map[int64]*lineIndex // by file ID
+}
+
+// linkNative links extern block functions and Rust exports to C and C++.
+func (l *ffiLinker) linkNative(bindgen map[int64]*wasmBindgenItem) error {
+	boundaries, err := l.e.Query().FFIBoundaries("")
+	if err != nil {
+		return err
+	}
+	fns, err := l.e.foreignFunctions(ffiNativeLanguages)
+	if err != nil {
+		return err
+	}
+	exports := map[string][]int64{}
+	var exported []string
+	for _, b := range boundaries {
+		if _, ok := bindgen[b.Symbol.ID]; ok || b.LinkName == "" || b.Symbol.Kind != "function" {
+			continue
+		}
+		switch b.Direction {
+		case FFIImport:
+			if def := onlyDefinition(fns[b.LinkName]); def != nil {
+				if err := l.link(b.Symbol.ID, def.sym.ID); err != nil {
+					return err
+				}
+			}
+		case FFIExport:
+			if _, seen := exports[b.LinkName]; !seen {
+				exported = append(exported, b.LinkName)
+			}
+			exports[b.LinkName] = append(exports[b.LinkName], b.Symbol.ID)
+		}
+	}
+	for _, name := range exported {
+		ids := exports[name]
+		if len(ids) != 1 || slices.ContainsFunc(fns[name], func(f foreignFunction) bool { return !f.declaration }) {
+			continue
+		}
+		for _, f := range fns[name] {
+			if err := l.link(f.sym.ID, ids[0]); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// linkWasmBindgen links #[wasm_bindgen] imports to JavaScript and
+// TypeScript functions, and resolves JavaScript and TypeScript references
+// to #[wasm_bindgen] exports.
+func (l *ffiLinker) linkWasmBindgen(bindgen map[int64]*wasmBindgenItem) error {
+	if len(bindgen) == 0 {
+		return nil
+	}
+	fns, err := l.e.foreignFunctions(ffiJSLanguages)
+	if err != nil {
+		return err
+	}
+	ids := make([]int64, 0, len(bindgen))
+	for id := range bindgen {
+		ids = append(ids, id)
+	}
+	slices.Sort(ids)
+	exports := map[string][]int64{}
+	for _, id := range ids {
+		item := bindgen[id]
+		if item.skip {
+			continue
+		}
+		if !item.foreign {
+			exports[item.jsName] = append(exports[item.jsName], id)
+			continue
+		}
+		var candidates []foreignFunction
+		for _, f := range fns[item.jsName] {
+			if item.module == "" || strings.HasSuffix(f.path, "/"+strings.TrimLeft(item.module, "./")) {
+				candidates = append(candidates, f)
+			}
+		}
+		if len(candidates) == 1 {
+			if err := l.link(id, candidates[0].sym.ID); err != nil {
+				return err
+			}
+		}
+	}
+	return l.resolveWasmImports(exports)
+}
+
+// resolveWasmImports resolves the unresolved JavaScript and TypeScript
+// references bound by a named import to the #[wasm_bindgen] export of the
+// imported name, when exports has exactly one. Calls also become call
+// edges from the enclosing function.
+func (l *ffiLinker) resolveWasmImports(exports map[string][]int64) error {
+	if len(exports) == 0 {
+		return nil
+	}
+	rows, err := l.e.store.DB().Query(
+		`SELECT r.id, r.file_id, r.scope_id, r.context, r.start_line, r.start_col, i.imported_name FROM references_ r
+		 JOIN files f ON f.id = r.file_id
+		 JOIN imports i ON i.file_id = r.file_id AND i.kind = 'named' AND COALESCE(NULLIF(i.local_alias, ''), i.imported_name) = r.name
+		 WHERE f.language IN (?, ?) AND r.context IN (?, ?, ?)
+			AND NOT EXISTS (SELECT 1 FROM resolved_references rr WHERE rr.reference_id = r.id)
+		 ORDER BY r.id`,
+		ffiJSLanguages[0], ffiJSLanguages[1], RefKindCall, RefKindRead, RefKindImport)
+	if err != nil {
+		return err
+	}
+	type site struct {
+		refID, fileID int64
+		scopeID       *int64
+		context       string
+		line, col     int
+		target        int64
+	}
+	var sites []site
+	for rows.Next() {
+		var s site
+		var name string
+		if err := rows.Scan(&s.refID, &s.fileID, &s.scopeID, &s.context, &s.line, &s.col, &name); err != nil {
+			rows.Close()
+			return err
+		}
+		if targets := exports[name]; len(targets) == 1 {
+			s.target = targets[0]
+			sites = append(sites, s)
+		}
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return err
+	}
+	for i, s := range sites {
+		if i > 0 && sites[i-1].refID == s.refID {
+			continue // imported by name twice
+		}
+		if _, err := l.e.store.InsertResolvedReference(&store.ResolvedReference{
+			ReferenceID:    s.refID,
+			TargetSymbolID: s.target,
+			Confidence:     ffiConfidence,
+			ResolutionKind: CallKindFFI,
+		}); err != nil {
+			return err
+		}
+		if s.context != RefKindCall || s.scopeID == nil {
+			continue
+		}
+		caller, err := l.e.callerSymbolID(*s.scopeID)
+		if err != nil {
+			return err
+		}
+		if caller == 0 {
+			continue
+		}
+		fileID := s.fileID
+		if _, err := l.e.store.InsertCallEdge(&store.CallEdge{
+			CallerSymbolID: caller,
+			CalleeSymbolID: s.target,
+			FileID:         &fileID,
+			Line:           s.line,
+			Col:            s.col,
+			Confidence:     ffiConfidence,
+			Kind:           CallKindFFI,
+		}); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// link records a link from the declaration from to the definition to: a
+// call edge, and a reference at from's name when its file can be read.
+func (l *ffiLinker) link(from, to int64) error {
+	decl, err := l.e.store.SymbolByID(from)
+	if err != nil || decl == nil || decl.FileID == nil {
+		return err
+	}
+	if _, err := l.e.store.InsertCallEdge(&store.CallEdge{
+		CallerSymbolID: decl.ID,
+		CalleeSymbolID: to,
+		FileID:         decl.FileID,
+		Line:           decl.StartLine,
+		Col:            decl.StartCol,
+		Confidence:     ffiConfidence,
+		Kind:           CallKindFFI,
+	}); err != nil {
+		return err
+	}
+
+	li, err := l.source(*decl.FileID)
+	if err != nil {
+		return err
+	}
+	start, ok := declarationNameOffset(li, decl)
+	if !ok {
+		return nil
+	}
+	end := start + len(decl.Name)
+	r := &store.Reference{
+		FileID:    *decl.FileID,
+		Name:      decl.Name,
+		Context:   RefKindFFI,
+		StartByte: start,
+		EndByte:   end,
+	}
+	r.StartLine, r.StartCol = li.position(start)
+	r.EndLine, r.EndCol = li.position(end)
+	_, r.UTF16StartCol = li.offsets(r.StartLine, r.StartCol)
+	_, r.UTF16EndCol = li.offsets(r.EndLine, r.EndCol)
+	refID, err := l.e.store.InsertReference(r)
+	if err != nil {
+		return err
+	}
+	_, err = l.e.store.InsertResolvedReference(&store.ResolvedReference{
+		ReferenceID:    refID,
+		TargetSymbolID: to,
+		Confidence:     ffiConfidence,
+		ResolutionKind: CallKindFFI,
+	})
+	return err
+}
+
+// source returns the line index of a file's content, empty when the file
+// cannot be read.
+func (l *ffiLinker) source(fileID int64) (*lineIndex, error) {
+	if li, ok := l.sources[fileID]; ok {
+		return li, nil
+	}
+	var path string
+	if err := l.e.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", fileID).Scan(&path); err != nil {
+		return nil, err
+	}
+	content, err := l.e.readSource(path)
+	if err != nil {
+		content = nil // deleted since indexing
+	}
+	li := newLineIndex(content)
+	l.sources[fileID] = li
+	return li, nil
+}
+
+// declarationNameOffset returns the byte offset of the first whole-word
+// occurrence of sym's name within its span, which is its name in a Rust
+// fn item and in a C or C++ prototype.
+func declarationNameOffset(li *lineIndex, sym *store.Symbol) (int, bool) {
+	start, _ := li.offsets(sym.StartLine, sym.StartCol)
+	end, _ := li.offsets(sym.EndLine, sym.EndCol)
+	text := string(li.content[start:end])
+	for from := 0; ; {
+		i := strings.Index(text[from:], sym.Name)
+		if i < 0 || sym.Name == "" {
+			return 0, false
+		}
+		i += from
+		j := i + len(sym.Name)
+		if (i == 0 || !isRustIdentByte(text[i-1])) && (j == len(text) || !isRustIdentByte(text[j])) {
+			return start + i, true
+		}
+		from = j
+	}
+}
+
+// onlyDefinition returns the only function of fns that is a definition, or
+// nil if there is none or several.
+func onlyDefinition(fns []foreignFunction) *foreignFunction {
+	var def *foreignFunction
+	for i := range fns {
+		if fns[i].declaration {
+			continue
+		}
+		if def != nil {
+			return nil
+		}
+		def = &fns[i]
+	}
+	return def
+}
+
+// foreignFunctions returns the top-level functions of files in languages,
+// by name.
+func (e *Engine) foreignFunctions(languages []string) (map[string][]foreignFunction, error) {
+	paths := map[int64]string{}
+	for _, lang := range languages {
+		files, err := e.store.FilesByLanguage(lang)
+		if err != nil {
+			return nil, err
+		}
+		for _, f := range files {
+			paths[f.ID] = f.Path
+		}
+	}
+	syms, err := e.store.SymbolsByKind("function")
+	if err != nil {
+		return nil, err
+	}
+	fns := map[string][]foreignFunction{}
+	for _, s := range syms {
+		if s.FileID == nil || s.ParentSymbolID != nil {
+			continue
+		}
+		path, ok := paths[*s.FileID]
+		if !ok {
+			continue
+		}
+		fns[s.Name] = append(fns[s.Name], foreignFunction{
+			sym:         s,
+			path:        path,
+			declaration: slices.Contains(s.Modifiers, "declaration"),
+		})
+	}
+	for _, list := range fns {
+		sort.SliceStable(list, func(i, j int) bool { return list[i].sym.ID < list[j].sym.ID })
+	}
+	return fns, nil
+}
+
+// wasmBindgenItems returns the Rust functions marked #[wasm_bindgen], by
+// symbol ID. The options of an extern block's attribute apply to its
+// functions, and the function's own attribute overrides them.
+func (e *Engine) wasmBindgenItems() (map[int64]*wasmBindgenItem, error) {
+	rows, err := e.store.DB().Query(
+		`SELECT s.id, s.name, COALESCE(a.arguments, ''),
+			EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)
+		 FROM annotations a
+		 JOIN symbols s ON s.id = a.target_symbol_id
+		 JOIN files f ON f.id = s.file_id
+		 WHERE f.language = 'rust' AND s.kind = 'function'
+			AND (a.name = 'wasm_bindgen' OR a.name LIKE '%::wasm_bindgen')
+		 ORDER BY s.id, a.line, a.col`, ModifierForeign)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	items := map[int64]*wasmBindgenItem{}
+	for rows.Next() {
+		var id int64
+		var name, args string
+		var foreign bool
+		if err := rows.Scan(&id, &name, &args, &foreign); err != nil {
+			return nil, err
+		}
+		item, ok := items[id]
+		if !ok {
+			item = &wasmBindgenItem{jsName: name, foreign: foreign}
+			items[id] = item
+		}
+		for opt, value := range wasmBindgenOptions(args) {
+			switch {
+			case opt == "js_name":
+				item.jsName = value
+			case opt == "module" || opt == "raw_module":
+				item.module = value
+			case slices.Contains(wasmBindgenSkipped, opt):
+				item.skip = true
+			}
+		}
+	}
+	return items, rows.Err()
+}
+
+// wasmBindgenOptions parses the arguments of a #[wasm_bindgen(...)]
+// attribute, as in (js_name = sayHello, module = "/js/util.js"), into
+// unquoted option values. Flags such as method have the value "".
+func wasmBindgenOptions(args string) map[string]string {
+	args = strings.TrimSpace(args)
+	args = strings.TrimSuffix(strings.TrimPrefix(args, "("), ")")
+	opts := map[string]string{}
+	for _, opt := range splitTypeList(args) {
+		name, value, _ := strings.Cut(opt, "=")
+		name = strings.TrimSpace(name)
+		if name == "" {
+			continue
+		}
+		opts[name] = strings.Trim(strings.TrimSpace(value), `"`)
+	}
+	return opts
+}
+
+// callerSymbolID returns the function or method enclosing a scope, found as
+// the resolution scripts do: the nearest function scope up the chain whose
+// symbol is a function or method. It returns 0 if there is none.
+func (e *Engine) callerSymbolID(scopeID int64) (int64, error) {
+	var id int64
+	err := e.store.DB().QueryRow(
+		`WITH RECURSIVE chain(id, parent_scope_id, symbol_id, kind, depth) AS (
+			SELECT id, parent_scope_id, symbol_id, kind, 0 FROM scopes WHERE id = ?
+			UNION ALL
+			SELECT s.id, s.parent_scope_id, s.symbol_id, s.kind, c.depth + 1 FROM scopes s JOIN chain c ON s.id = c.parent_scope_id
+		 )
+		 SELECT c.symbol_id FROM chain c JOIN symbols sym ON sym.id = c.symbol_id
+		 WHERE c.kind = 'function' AND sym.kind IN ('function', 'method')
+		 ORDER BY c.depth LIMIT 1`, scopeID).Scan(&id)
+	if err == sql.ErrNoRows {
+		return 0, nil
+	}
+	return id, err
+}
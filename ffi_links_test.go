@@ -0,0 +1,131 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWasmBindgenOptions(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, map[string]string{}, wasmBindgenOptions(""))
+	assert.Equal(t, map[string]string{"js_name": "sayHello"}, wasmBindgenOptions("(js_name = sayHello)"))
+	assert.Equal(t, map[string]string{"module": "/js/util.js", "catch": ""},
+		wasmBindgenOptions(`(module = "/js/util.js", catch)`))
+	assert.Equal(t, map[string]string{"js_namespace": `["window", "document"]`},
+		wasmBindgenOptions(`(js_namespace = ["window", "document"])`))
+}
+
+func TestLinkForeignFunctions(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("src/lib.rs", `extern "C" {
+    fn compress(src: *const u8, len: usize) -> usize;
+}
+
+pub fn pack(data: &[u8]) -> usize {
+    unsafe { compress(data.as_ptr(), data.len()) }
+}
+
+#[no_mangle]
+pub extern "C" fn rust_checksum(data: *const u8, len: usize) -> u32 {
+    0
+}
+
+#[wasm_bindgen(module = "/web/util.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = formatBytes)]
+    fn format_bytes(n: usize) -> String;
+}
+
+#[wasm_bindgen]
+pub fn greet(name: &str) -> String {
+    format_bytes(name.len())
+}
+`)
+	write("native/checksum.h", "unsigned int rust_checksum(const char *data, size_t len);\n")
+	write("native/compress.c", `#include "checksum.h"
+
+size_t compress(const char *src, size_t len) {
+    return rust_checksum(src, len);
+}
+`)
+	write("web/util.js", "export function formatBytes(n) {\n  return n + \" bytes\";\n}\n")
+	write("web/app.ts", `import { greet } from "../pkg/demo";
+
+export function main() {
+  greet("world");
+}
+`)
+
+	e := newIntegrationEngine(t, WithLanguages("rust", "c", "javascript", "typescript"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	symbolIn := func(name, file string) int64 {
+		t.Helper()
+		var id int64
+		require.NoError(t, e.store.DB().QueryRow(
+			"SELECT s.id FROM symbols s JOIN files f ON f.id = s.file_id WHERE s.name = ? AND f.path = ?",
+			name, filepath.Join(root, file)).Scan(&id), "%s in %s", name, file)
+		return id
+	}
+	ffiCallees := func(id int64) []int64 {
+		t.Helper()
+		edges, err := q.Callees(id)
+		require.NoError(t, err)
+		var out []int64
+		for _, edge := range edges {
+			if edge.Kind == CallKindFFI {
+				out = append(out, edge.CalleeSymbolID)
+			}
+		}
+		return out
+	}
+
+	rustCompress := symbolIn("compress", "src/lib.rs")
+	cCompress := symbolIn("compress", "native/compress.c")
+	assert.Equal(t, []int64{cCompress}, ffiCallees(rustCompress), "extern block function links to its C definition")
+
+	prototype := symbolIn("rust_checksum", "native/checksum.h")
+	assert.Equal(t, []int64{symbolIn("rust_checksum", "src/lib.rs")}, ffiCallees(prototype),
+		"C prototype links to the Rust export")
+
+	assert.Equal(t, []int64{symbolIn("formatBytes", "web/util.js")}, ffiCallees(symbolIn("format_bytes", "src/lib.rs")),
+		"wasm_bindgen import links to the JavaScript function of its js_name")
+
+	greet := symbolIn("greet", "src/lib.rs")
+	callers, err := q.Callers(greet)
+	require.NoError(t, err)
+	require.Len(t, callers, 1)
+	assert.Equal(t, symbolIn("main", "web/app.ts"), callers[0].CallerSymbolID, "TypeScript calls reach the wasm_bindgen export")
+
+	graph, err := q.TransitiveCallees(symbolIn("pack", "src/lib.rs"), 5)
+	require.NoError(t, err)
+	var reached []string
+	for _, n := range graph.Nodes {
+		reached = append(reached, n.Symbol.FilePath)
+	}
+	assert.Contains(t, reached, filepath.Join(root, "native/compress.c"), "the call graph crosses into C")
+
+	// The declaration's name resolves to the foreign definition.
+	locs, err := q.DefinitionAt(filepath.Join(root, "src/lib.rs"), 1, 8)
+	require.NoError(t, err)
+	require.Len(t, locs, 1)
+	assert.Equal(t, filepath.Join(root, "native/compress.c"), locs[0].File)
+
+	// Resolving again rebuilds the links rather than duplicating them.
+	require.NoError(t, e.Resolve(ctx))
+	assert.Equal(t, []int64{cCompress}, ffiCallees(rustCompress))
+}
@@ -0,0 +1,71 @@
+package canopy
+
+import (
+	"io/fs"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// FileSystem is the file access the Engine indexes through: reading source
+// and ignore files, checking that files exist, and walking directories.
+// The default, OSFileSystem, is the local disk. Hosts without one, such as
+// a browser or a VS Code web extension, supply their own with
+// WithFileSystem, for example an IOFileSystem over the workspace. Paths are
+// the ones the Engine is given, such as the root passed to IndexDirectory,
+// joined with filepath.
+type FileSystem interface {
+	ReadFile(path string) ([]byte, error)
+	Stat(path string) (fs.FileInfo, error)
+	WalkDir(root string, fn fs.WalkDirFunc) error
+}
+
+// OSFileSystem is the FileSystem of the local disk.
+type OSFileSystem struct{}
+
+// ReadFile reads the file at path with os.ReadFile.
+func (OSFileSystem) ReadFile(path string) ([]byte, error) { return os.ReadFile(path) }
+
+// Stat describes the file at path with os.Stat.
+func (OSFileSystem) Stat(path string) (fs.FileInfo, error) { return os.Stat(path) }
+
+// WalkDir walks the tree at root with filepath.WalkDir.
+func (OSFileSystem) WalkDir(root string, fn fs.WalkDirFunc) error { return filepath.WalkDir(root, fn) }
+
+// IOFileSystem is a FileSystem over an fs.FS, such as an in-memory tree of
+// a browser workspace. A path names the file at the same path within FS,
+// with any leading separator dropped: "/src/lib.rs" is "src/lib.rs".
+type IOFileSystem struct {
+	FS fs.FS
+}
+
+// name returns the FS name of path.
+func (f IOFileSystem) name(path string) string {
+	name := strings.TrimPrefix(filepath.ToSlash(filepath.Clean(path)), "/")
+	if name == "" {
+		return "."
+	}
+	return name
+}
+
+// ReadFile reads the file at path with fs.ReadFile.
+func (f IOFileSystem) ReadFile(path string) ([]byte, error) { return fs.ReadFile(f.FS, f.name(path)) }
+
+// Stat describes the file at path with fs.Stat.
+func (f IOFileSystem) Stat(path string) (fs.FileInfo, error) { return fs.Stat(f.FS, f.name(path)) }
+
+// WalkDir walks the tree at root with fs.WalkDir, passing fn paths in the
+// form of root: under root, with a leading separator if root has one.
+func (f IOFileSystem) WalkDir(root string, fn fs.WalkDirFunc) error {
+	root = filepath.Clean(root)
+	prefix := ""
+	if strings.HasPrefix(filepath.ToSlash(root), "/") {
+		prefix = "/"
+	}
+	return fs.WalkDir(f.FS, f.name(root), func(name string, d fs.DirEntry, err error) error {
+		if name == f.name(root) {
+			return fn(root, d, err)
+		}
+		return fn(filepath.FromSlash(prefix+name), d, err)
+	})
+}
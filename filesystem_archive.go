@@ -0,0 +1,196 @@
+package canopy
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"bytes"
+	"compress/gzip"
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"os"
+	"path"
+	"sort"
+	"strings"
+	"time"
+)
+
+// ArchiveFileSystem returns a FileSystem over the files of a zip, tar, or
+// gzip-compressed tar archive held in data, so a source tarball can be
+// indexed without unpacking it to disk. The format is detected from the
+// archive's leading bytes. Archive members sit under "/": index the whole
+// archive with IndexDirectory(ctx, "/"). Symlinks and other special members
+// of tar archives are skipped.
+func ArchiveFileSystem(data []byte) (IOFileSystem, error) {
+	switch {
+	case bytes.HasPrefix(data, []byte("PK\x03\x04")), bytes.HasPrefix(data, []byte("PK\x05\x06")):
+		zr, err := zip.NewReader(bytes.NewReader(data), int64(len(data)))
+		if err != nil {
+			return IOFileSystem{}, fmt.Errorf("reading zip archive: %w", err)
+		}
+		return IOFileSystem{FS: zr}, nil
+	case bytes.HasPrefix(data, []byte{0x1f, 0x8b}):
+		zr, err := gzip.NewReader(bytes.NewReader(data))
+		if err != nil {
+			return IOFileSystem{}, fmt.Errorf("reading gzip archive: %w", err)
+		}
+		defer zr.Close()
+		return tarFileSystem(zr)
+	case len(data) > 262 && string(data[257:262]) == "ustar":
+		return tarFileSystem(bytes.NewReader(data))
+	}
+	return IOFileSystem{}, errors.New("unrecognized archive format: expected zip, tar, or tar.gz")
+}
+
+// OpenArchive reads the archive at path and returns a FileSystem over its
+// files, as ArchiveFileSystem does.
+func OpenArchive(path string) (IOFileSystem, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return IOFileSystem{}, err
+	}
+	fsys, err := ArchiveFileSystem(data)
+	if err != nil {
+		return IOFileSystem{}, fmt.Errorf("%s: %w", path, err)
+	}
+	return fsys, nil
+}
+
+// tarFileSystem loads the regular files of the tar stream r into memory.
+func tarFileSystem(r io.Reader) (IOFileSystem, error) {
+	files := memFS{}
+	tr := tar.NewReader(r)
+	for {
+		h, err := tr.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return IOFileSystem{}, fmt.Errorf("reading tar archive: %w", err)
+		}
+		if h.Typeflag != tar.TypeReg {
+			continue
+		}
+		name := path.Clean(strings.TrimPrefix(h.Name, "/"))
+		if !fs.ValidPath(name) || name == "." {
+			continue
+		}
+		data, err := io.ReadAll(tr)
+		if err != nil {
+			return IOFileSystem{}, fmt.Errorf("reading %s from tar archive: %w", h.Name, err)
+		}
+		files[name] = &memFile{data: data, mode: fs.FileMode(h.Mode).Perm(), modTime: h.ModTime}
+	}
+	return IOFileSystem{FS: files}, nil
+}
+
+// memFS is a read-only fs.FS of files held in memory, keyed by their
+// slash-separated names. Directories are implied by the files under them.
+type memFS map[string]*memFile
+
+// memFile is the content of one file in a memFS.
+type memFile struct {
+	data    []byte
+	mode    fs.FileMode
+	modTime time.Time
+}
+
+// Open opens the file or implied directory called name.
+func (m memFS) Open(name string) (fs.File, error) {
+	if !fs.ValidPath(name) {
+		return nil, &fs.PathError{Op: "open", Path: name, Err: fs.ErrInvalid}
+	}
+	if f, ok := m[name]; ok {
+		info := memFileInfo{name: path.Base(name), size: int64(len(f.data)), mode: f.mode, modTime: f.modTime}
+		return &openMemFile{info: info, Reader: bytes.NewReader(f.data)}, nil
+	}
+	entries, err := m.ReadDir(name)
+	if err != nil {
+		return nil, &fs.PathError{Op: "open", Path: name, Err: fs.ErrNotExist}
+	}
+	return &openMemDir{info: memFileInfo{name: path.Base(name), mode: fs.ModeDir | 0o555}, entries: entries}, nil
+}
+
+// ReadDir lists the files and directories directly under the directory
+// called name, sorted by name.
+func (m memFS) ReadDir(name string) ([]fs.DirEntry, error) {
+	prefix := name + "/"
+	if name == "." {
+		prefix = ""
+	}
+	children := make(map[string]fs.FileInfo)
+	for file, f := range m {
+		rest, ok := strings.CutPrefix(file, prefix)
+		if !ok {
+			continue
+		}
+		if child, _, isDir := strings.Cut(rest, "/"); isDir {
+			children[child] = memFileInfo{name: child, mode: fs.ModeDir | 0o555}
+		} else {
+			children[child] = memFileInfo{name: child, size: int64(len(f.data)), mode: f.mode, modTime: f.modTime}
+		}
+	}
+	if len(children) == 0 && name != "." {
+		return nil, &fs.PathError{Op: "readdir", Path: name, Err: fs.ErrNotExist}
+	}
+	entries := make([]fs.DirEntry, 0, len(children))
+	for _, info := range children {
+		entries = append(entries, fs.FileInfoToDirEntry(info))
+	}
+	sort.Slice(entries, func(i, j int) bool { return entries[i].Name() < entries[j].Name() })
+	return entries, nil
+}
+
+// memFileInfo describes a file or directory of a memFS.
+type memFileInfo struct {
+	name    string
+	size    int64
+	mode    fs.FileMode
+	modTime time.Time
+}
+
+func (i memFileInfo) Name() string       { return i.name }
+func (i memFileInfo) Size() int64        { return i.size }
+func (i memFileInfo) Mode() fs.FileMode  { return i.mode }
+func (i memFileInfo) ModTime() time.Time { return i.modTime }
+func (i memFileInfo) IsDir() bool        { return i.mode.IsDir() }
+func (i memFileInfo) Sys() any           { return nil }
+
+// openMemFile is an open file of a memFS.
+type openMemFile struct {
+	info memFileInfo
+	*bytes.Reader
+}
+
+func (f *openMemFile) Stat() (fs.FileInfo, error) { return f.info, nil }
+func (f *openMemFile) Close() error               { return nil }
+
+// openMemDir is an open directory of a memFS.
+type openMemDir struct {
+	info    memFileInfo
+	entries []fs.DirEntry
+}
+
+func (d *openMemDir) Stat() (fs.FileInfo, error) { return d.info, nil }
+func (d *openMemDir) Close() error               { return nil }
+
+func (d *openMemDir) Read([]byte) (int, error) {
+	return 0, &fs.PathError{Op: "read", Path: d.info.name, Err: errors.New("is a directory")}
+}
+
+// ReadDir returns the next n entries of the directory, or all remaining
+// entries when n <= 0.
+func (d *openMemDir) ReadDir(n int) ([]fs.DirEntry, error) {
+	if n <= 0 || n >= len(d.entries) {
+		if n > 0 && len(d.entries) == 0 {
+			return nil, io.EOF
+		}
+		entries := d.entries
+		d.entries = nil
+		return entries, nil
+	}
+	entries := d.entries[:n]
+	d.entries = d.entries[n:]
+	return entries, nil
+}
@@ -0,0 +1,117 @@
+package canopy
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"fmt"
+	"io"
+	"os/exec"
+	"path"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy/internal/runtime"
+)
+
+// GitFileSystem returns a FileSystem over the tree of rev (a commit, branch,
+// or tag) in the git repository at repoDir, read from git's object store
+// rather than a checkout, so bare repositories and other revisions can be
+// indexed. Only files in supported languages and .gitignore and .ignore
+// files are loaded. Like an archive, the tree sits under "/". A rev
+// starting with "-" is rejected rather than passed to git as an option.
+func GitFileSystem(ctx context.Context, repoDir, rev string) (IOFileSystem, error) {
+	if err := checkGitRev(rev); err != nil {
+		return IOFileSystem{}, err
+	}
+	ls := exec.CommandContext(ctx, "git", "ls-tree", "-r", "-z", "--full-tree", "--end-of-options", rev)
+	ls.Dir = repoDir
+	var listing, stderr bytes.Buffer
+	ls.Stdout = &listing
+	ls.Stderr = &stderr
+	if err := ls.Run(); err != nil {
+		return IOFileSystem{}, fmt.Errorf("git ls-tree %s: %w: %s", rev, err, strings.TrimSpace(stderr.String()))
+	}
+
+	// Each entry is "<mode> <type> <object>\t<path>". Symlinks (mode 120000)
+	// are blobs too and are skipped.
+	paths := make(map[string][]string)
+	var objects []string
+	for _, entry := range strings.Split(listing.String(), "\x00") {
+		meta, name, ok := strings.Cut(entry, "\t")
+		fields := strings.Fields(meta)
+		if !ok || len(fields) != 3 || fields[1] != "blob" || fields[0] == "120000" {
+			continue
+		}
+		base := path.Base(name)
+		if _, ok := runtime.LanguageForFile(name); !ok && base != ".gitignore" && base != ".ignore" {
+			continue
+		}
+		if _, seen := paths[fields[2]]; !seen {
+			objects = append(objects, fields[2])
+		}
+		paths[fields[2]] = append(paths[fields[2]], name)
+	}
+
+	files := memFS{}
+	if len(objects) == 0 {
+		return IOFileSystem{FS: files}, nil
+	}
+
+	// One cat-file process reads every blob: "<object> <type> <size>\n"
+	// followed by the content and a newline.
+	cat := exec.CommandContext(ctx, "git", "cat-file", "--batch")
+	cat.Dir = repoDir
+	cat.Stdin = strings.NewReader(strings.Join(objects, "\n") + "\n")
+	stderr.Reset()
+	cat.Stderr = &stderr
+	stdout, err := cat.StdoutPipe()
+	if err != nil {
+		return IOFileSystem{}, err
+	}
+	if err := cat.Start(); err != nil {
+		return IOFileSystem{}, fmt.Errorf("git cat-file: %w", err)
+	}
+	r := bufio.NewReader(stdout)
+	for range objects {
+		header, err := r.ReadString('\n')
+		if err != nil {
+			cat.Wait()
+			return IOFileSystem{}, fmt.Errorf("git cat-file: %w", err)
+		}
+		fields := strings.Fields(header)
+		if len(fields) != 3 {
+			cat.Wait()
+			return IOFileSystem{}, fmt.Errorf("git cat-file: unexpected header %q", strings.TrimSpace(header))
+		}
+		size, err := strconv.Atoi(fields[2])
+		if err != nil {
+			cat.Wait()
+			return IOFileSystem{}, fmt.Errorf("git cat-file: unexpected header %q", strings.TrimSpace(header))
+		}
+		data := make([]byte, size+1)
+		if _, err := io.ReadFull(r, data); err != nil {
+			cat.Wait()
+			return IOFileSystem{}, fmt.Errorf("git cat-file: reading %s: %w", fields[0], err)
+		}
+		for _, name := range paths[fields[0]] {
+			files[name] = &memFile{data: data[:size], mode: 0o444}
+		}
+	}
+	if err := cat.Wait(); err != nil {
+		return IOFileSystem{}, fmt.Errorf("git cat-file: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return IOFileSystem{FS: files}, nil
+}
+
+// checkGitRev rejects a revision git would parse as an option, such as
+// --output=path. Callers also pass --end-of-options before it.
+func checkGitRev(rev string) error {
+	if rev == "" {
+		return fmt.Errorf("empty git revision")
+	}
+	if strings.HasPrefix(rev, "-") {
+		return fmt.Errorf("invalid git revision %q: must not start with -", rev)
+	}
+	return nil
+}
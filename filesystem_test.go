@@ -0,0 +1,189 @@
+package canopy
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"bytes"
+	"compress/gzip"
+	"context"
+	"io/fs"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+	"testing/fstest"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestIOFileSystem_WalkDirReportsPathsUnderRoot(t *testing.T) {
+	t.Parallel()
+
+	fsys := IOFileSystem{FS: fstest.MapFS{
+		"ws/main.go":     {Data: []byte("package main\n")},
+		"ws/lib/util.go": {Data: []byte("package lib\n")},
+	}}
+	var paths []string
+	require.NoError(t, fsys.WalkDir("/ws", func(path string, d fs.DirEntry, err error) error {
+		require.NoError(t, err)
+		paths = append(paths, filepath.ToSlash(path))
+		return nil
+	}))
+	assert.Equal(t, []string{"/ws", "/ws/lib", "/ws/lib/util.go", "/ws/main.go"}, paths)
+
+	content, err := fsys.ReadFile("/ws/main.go")
+	require.NoError(t, err)
+	assert.Equal(t, "package main\n", string(content))
+	_, err = fsys.Stat("/ws/missing.go")
+	assert.Error(t, err)
+}
+
+func TestWithFileSystem_IndexesWithoutDisk(t *testing.T) {
+	fsys := fstest.MapFS{
+		"ws/main.go": {Data: []byte(`package main
+
+func greet() {}
+
+func main() {
+	greet()
+}
+`)},
+		"ws/gen/skip.go": {Data: []byte("package gen\n\nfunc skipped() {}\n")},
+		"ws/.gitignore":  {Data: []byte("gen/\n")},
+	}
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel), WithFileSystem(IOFileSystem{FS: fsys}))
+		ctx := context.Background()
+		require.NoError(t, e.IndexDirectory(ctx, "/ws"))
+		require.NoError(t, e.Resolve(ctx))
+
+		greet, err := e.store.SymbolsByName("greet")
+		require.NoError(t, err)
+		require.Len(t, greet, 1)
+		locs, err := e.Query().ReferencesTo(greet[0].ID)
+		require.NoError(t, err)
+		require.Len(t, locs, 1)
+		assert.Equal(t, filepath.FromSlash("/ws/main.go"), locs[0].File)
+
+		skipped, err := e.store.SymbolsByName("skipped")
+		require.NoError(t, err)
+		assert.Empty(t, skipped, ".gitignore is read through the file system")
+	}
+}
+
+func TestMemFS_ConformsToFS(t *testing.T) {
+	t.Parallel()
+
+	files := memFS{
+		"main.go":         {data: []byte("package main\n"), mode: 0o444},
+		"lib/util.go":     {data: []byte("package lib\n"), mode: 0o444},
+		"lib/sub/deep.go": {data: []byte("package sub\n"), mode: 0o444},
+	}
+	require.NoError(t, fstest.TestFS(files, "main.go", "lib/util.go", "lib/sub/deep.go"))
+}
+
+func TestArchiveFileSystem_IndexesArchives(t *testing.T) {
+	src := map[string]string{
+		"demo/main.go":   "package main\n\nfunc greet() {}\n\nfunc main() {\n\tgreet()\n}\n",
+		"demo/README.md": "# demo\n",
+	}
+	var zipped bytes.Buffer
+	zw := zip.NewWriter(&zipped)
+	for name, content := range src {
+		w, err := zw.Create(name)
+		require.NoError(t, err)
+		_, err = w.Write([]byte(content))
+		require.NoError(t, err)
+	}
+	require.NoError(t, zw.Close())
+
+	var tarred bytes.Buffer
+	gz := gzip.NewWriter(&tarred)
+	tw := tar.NewWriter(gz)
+	require.NoError(t, tw.WriteHeader(&tar.Header{Name: "./demo/", Typeflag: tar.TypeDir, Mode: 0o755}))
+	for name, content := range src {
+		require.NoError(t, tw.WriteHeader(&tar.Header{Name: "./" + name, Typeflag: tar.TypeReg, Mode: 0o644, Size: int64(len(content))}))
+		_, err := tw.Write([]byte(content))
+		require.NoError(t, err)
+	}
+	require.NoError(t, tw.WriteHeader(&tar.Header{Name: "./demo/link.go", Typeflag: tar.TypeSymlink, Linkname: "main.go"}))
+	require.NoError(t, tw.Close())
+	require.NoError(t, gz.Close())
+
+	for format, data := range map[string][]byte{"zip": zipped.Bytes(), "tar.gz": tarred.Bytes()} {
+		t.Run(format, func(t *testing.T) {
+			fsys, err := ArchiveFileSystem(data)
+			require.NoError(t, err)
+			e := newIntegrationEngine(t, WithLanguages("go"), WithFileSystem(fsys))
+			ctx := context.Background()
+			require.NoError(t, e.IndexDirectory(ctx, "/"))
+			require.NoError(t, e.Resolve(ctx))
+
+			greet, err := e.store.SymbolsByName("greet")
+			require.NoError(t, err)
+			require.Len(t, greet, 1, "the symlink is not indexed as a second copy")
+			locs, err := e.Query().ReferencesTo(greet[0].ID)
+			require.NoError(t, err)
+			require.Len(t, locs, 1)
+			assert.Equal(t, filepath.FromSlash("/demo/main.go"), locs[0].File)
+		})
+	}
+
+	_, err := ArchiveFileSystem([]byte("not an archive"))
+	assert.Error(t, err)
+}
+
+func TestGitFileSystem_ReadsRevisionFromObjectStore(t *testing.T) {
+	if _, err := exec.LookPath("git"); err != nil {
+		t.Skip("git not available")
+	}
+	dir := t.TempDir()
+	git := func(args ...string) {
+		t.Helper()
+		cmd := exec.Command("git", append([]string{"-c", "user.name=Alice", "-c", "user.email=alice@example.com"}, args...)...)
+		cmd.Dir = dir
+		out, err := cmd.CombinedOutput()
+		require.NoError(t, err, string(out))
+	}
+	git("init", "-q")
+	writeGoFile(t, dir, "lib.go", "package lib\n\nfunc Old() {}\n")
+	require.NoError(t, os.MkdirAll(filepath.Join(dir, "gen"), 0o755))
+	writeGoFile(t, dir, "gen/skip.go", "package gen\n\nfunc Skipped() {}\n")
+	require.NoError(t, os.WriteFile(filepath.Join(dir, ".ignore"), []byte("gen/\n"), 0o644))
+	git("add", ".")
+	git("commit", "-q", "-m", "initial")
+	git("tag", "v1")
+	writeGoFile(t, dir, "lib.go", "package lib\n\nfunc New() {}\n")
+	git("commit", "-q", "-am", "rename")
+
+	// The bare clone has no working tree at all.
+	bare := filepath.Join(t.TempDir(), "repo.git")
+	git("clone", "-q", "--bare", dir, bare)
+
+	fsys, err := GitFileSystem(context.Background(), bare, "v1")
+	require.NoError(t, err)
+	e := newIntegrationEngine(t, WithLanguages("go"), WithFileSystem(fsys))
+	ctx := context.Background()
+	require.NoError(t, e.IndexDirectory(ctx, "/"))
+	require.NoError(t, e.Resolve(ctx))
+
+	old, err := e.store.SymbolsByName("Old")
+	require.NoError(t, err)
+	assert.Len(t, old, 1, "the tagged revision is indexed, not the latest commit")
+	newer, err := e.store.SymbolsByName("New")
+	require.NoError(t, err)
+	assert.Empty(t, newer)
+	skipped, err := e.store.SymbolsByName("Skipped")
+	require.NoError(t, err)
+	assert.Empty(t, skipped, ".ignore is read from the revision")
+
+	_, err = GitFileSystem(context.Background(), bare, "no-such-rev")
+	assert.Error(t, err)
+
+	// A rev that reads as an option never reaches git.
+	out := filepath.Join(t.TempDir(), "listing")
+	_, err = GitFileSystem(context.Background(), bare, "--output="+out)
+	assert.ErrorContains(t, err, "must not start with -")
+	assert.NoFileExists(t, out)
+}
@@ -0,0 +1,49 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/workload"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// FuzzRust feeds a snippet built from the input by workload.ArbitraryRust,
+// and the input itself as raw source, through extraction and resolution.
+// Both must succeed without panicking and leave an index that passes
+// store validation. Run it with
+//
+//	go test -run '^$' -fuzz FuzzRust .
+//
+// Without -fuzz the seed corpus runs as a regular test.
+func FuzzRust(f *testing.F) {
+	seeds, _ := filepath.Glob(filepath.Join("testdata", "rust", "*", "src", "*.rs"))
+	for _, path := range seeds {
+		if src, err := os.ReadFile(path); err == nil {
+			f.Add(src)
+		}
+	}
+	f.Add([]byte{})
+	f.Add([]byte("\x07\x03\x01\x09\x00\x05\x02\x0e\x06\x0c\x04\x0b"))
+	f.Add([]byte("impl<T> Foo<T> { fn new() -> Self { Self::new()?.await } }"))
+
+	f.Fuzz(func(t *testing.T, data []byte) {
+		e := newIntegrationEngine(t, WithLanguages("rust"))
+		ctx := context.Background()
+		dir := t.TempDir()
+
+		gen := filepath.Join(dir, "gen.rs")
+		raw := filepath.Join(dir, "raw.rs")
+		require.NoError(t, os.WriteFile(gen, []byte(workload.ArbitraryRust(data)), 0644))
+		require.NoError(t, os.WriteFile(raw, data, 0644))
+
+		require.NoError(t, e.IndexFiles(ctx, []string{gen, raw}))
+		require.NoError(t, e.Resolve(ctx))
+		violations, err := e.store.Validate()
+		require.NoError(t, err)
+		assert.Empty(t, violations)
+	})
+}
@@ -0,0 +1,202 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"time"
+)
+
+// WithTargetDir sets the Cargo target directory (usually <crate>/target)
+// whose build script output IndexDirectory indexes. Rust files that pull
+// generated code in with include!(concat!(env!("OUT_DIR"), "/bindings.rs"))
+// then resolve against it: IndexDirectory looks each included file up in
+// <dir>/<profile>/build/<package>-<hash>/out (or under a target triple when
+// cross compiling), indexes the newest copy, and drops output no longer
+// included. Without a target directory such names are reported as
+// UnresolvedBuildGenerated.
+func WithTargetDir(dir string) Option {
+	return func(e *Engine) {
+		e.targetDir = filepath.Clean(dir)
+	}
+}
+
+// outDirInclude prefixes the import source Rust extraction records for an
+// include! of build script output: "$OUT_DIR/bindings.rs".
+const outDirInclude = "$OUT_DIR/"
+
+// indexGeneratedFiles indexes the build script output included by indexed
+// Rust files and removes indexed files under the target directory that are
+// neither included any more nor among discovered.
+func (e *Engine) indexGeneratedFiles(ctx context.Context, discovered []string) error {
+	generated, err := e.generatedFiles()
+	if err != nil {
+		return err
+	}
+	keep := make(map[string]bool, len(generated)+len(discovered))
+	for _, p := range slices.Concat(generated, discovered) {
+		keep[p] = true
+	}
+	indexed, err := e.indexedUnder(e.targetDir)
+	if err != nil {
+		return err
+	}
+	if len(indexed) > 0 && e.blastRadius == nil {
+		e.blastRadius = make(map[int64]bool)
+	}
+	for id, path := range indexed {
+		if keep[path] {
+			continue
+		}
+		if err := e.removeFile(id); err != nil {
+			return fmt.Errorf("remove %s: %w", path, err)
+		}
+	}
+	return e.IndexFiles(ctx, generated)
+}
+
+// generatedFiles returns the paths of the build script output included by
+// indexed Rust files that exist under the target directory.
+func (e *Engine) generatedFiles() ([]string, error) {
+	rows, err := e.store.DB().Query(
+		`SELECT DISTINCT f.path, i.source FROM imports i JOIN files f ON f.id = i.file_id
+		 WHERE i.kind = 'include' AND f.language = 'rust' AND substr(i.source, 1, ?) = ?
+		 ORDER BY f.path, i.source`,
+		len(outDirInclude), outDirInclude)
+	if err != nil {
+		return nil, fmt.Errorf("build script includes: %w", err)
+	}
+	type include struct{ path, source string }
+	var includes []include
+	for rows.Next() {
+		var inc include
+		if err := rows.Scan(&inc.path, &inc.source); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("build script includes: scan: %w", err)
+		}
+		includes = append(includes, inc)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("build script includes: %w", err)
+	}
+
+	packages := map[string]cargoPackage{} // directory -> enclosing package
+	seen := map[string]bool{}
+	var paths []string
+	for _, inc := range includes {
+		pkg := cargoPackageFor(filepath.Dir(inc.path), packages)
+		if pkg.name == "" {
+			continue
+		}
+		out := buildOutput(e.targetDir, pkg.name, strings.TrimPrefix(inc.source, outDirInclude))
+		if out != "" && !seen[out] {
+			seen[out] = true
+			paths = append(paths, out)
+		}
+	}
+	return paths, nil
+}
+
+// indexedUnder returns the indexed files under dir by ID.
+func (e *Engine) indexedUnder(dir string) (map[int64]string, error) {
+	all, err := e.store.AllFiles()
+	if err != nil {
+		return nil, fmt.Errorf("list indexed files: %w", err)
+	}
+	prefix := filepath.Clean(dir) + string(filepath.Separator)
+	files := map[int64]string{}
+	for id, path := range all {
+		if strings.HasPrefix(path, prefix) {
+			files[id] = path
+		}
+	}
+	return files, nil
+}
+
+// cargoPackage is a Cargo package: its name, its edition, and the directory
+// of its Cargo.toml.
+type cargoPackage struct {
+	name    string
+	edition string
+	dir     string
+}
+
+// cargoPackageFor returns the package whose Cargo.toml is the nearest one in
+// dir or above it. The name is "" if there is none or it is a virtual
+// workspace manifest. Answers are cached by directory in cache.
+func cargoPackageFor(dir string, cache map[string]cargoPackage) cargoPackage {
+	if pkg, ok := cache[dir]; ok {
+		return pkg
+	}
+	var pkg cargoPackage
+	if manifest, err := os.ReadFile(filepath.Join(dir, "Cargo.toml")); err == nil {
+		pkg = cargoPackage{name: cargoPackageName(manifest), edition: cargoPackageEdition(manifest, dir), dir: dir}
+	} else if parent := filepath.Dir(dir); parent != dir {
+		pkg = cargoPackageFor(parent, cache)
+	}
+	cache[dir] = pkg
+	return pkg
+}
+
+// cargoPackageName returns the name in the [package] table of a Cargo.toml,
+// or "" for a virtual workspace manifest.
+func cargoPackageName(manifest []byte) string {
+	return cargoTableValue(manifest, "package", "name")
+}
+
+// cargoTableValue returns the value of key in table of a Cargo.toml, without
+// quotes or a trailing comment, or "" if it is not set. Dotted keys such as
+// edition.workspace are matched as written.
+func cargoTableValue(manifest []byte, table, key string) string {
+	current := ""
+	for _, line := range strings.Split(string(manifest), "\n") {
+		line = strings.TrimSpace(line)
+		if strings.HasPrefix(line, "[") {
+			current = strings.TrimSpace(strings.Trim(line, "[]"))
+			continue
+		}
+		k, value, ok := strings.Cut(line, "=")
+		if ok && current == table && strings.ReplaceAll(k, " ", "") == key {
+			value, _, _ = strings.Cut(value, "#")
+			return strings.Trim(strings.TrimSpace(value), `"'`)
+		}
+	}
+	return ""
+}
+
+// buildOutput returns the newest rel (a slash-separated path) in the out
+// directories of pkg's build script runs under targetDir, or "" if there is
+// none. Cargo runs build scripts in <profile>/build/<pkg>-<hash>, or
+// <triple>/<profile>/build/<pkg>-<hash> when cross compiling, with one
+// directory per configuration built.
+func buildOutput(targetDir, pkg, rel string) string {
+	rel = filepath.FromSlash(rel)
+	var best string
+	var bestTime time.Time
+	for _, pattern := range []string{
+		filepath.Join(targetDir, "*", "build", pkg+"-*", "out", rel),
+		filepath.Join(targetDir, "*", "*", "build", pkg+"-*", "out", rel),
+	} {
+		matches, _ := filepath.Glob(pattern) // the only error is a malformed pattern
+		for _, m := range matches {
+			runDir := filepath.Dir(strings.TrimSuffix(m, string(filepath.Separator)+rel))
+			// pkg-* also matches the build directories of pkg-suffixed packages.
+			hash := strings.TrimPrefix(filepath.Base(runDir), pkg+"-")
+			if hash == "" || strings.Trim(hash, "0123456789abcdef") != "" {
+				continue
+			}
+			info, err := os.Stat(m)
+			if err != nil || info.IsDir() {
+				continue
+			}
+			if best == "" || info.ModTime().After(bestTime) {
+				best, bestTime = m, info.ModTime()
+			}
+		}
+	}
+	return best
+}
@@ -0,0 +1,100 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestBuildScriptOutput(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	// A separate target directory, as with CARGO_TARGET_DIR.
+	target := t.TempDir()
+
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(path), 0o755))
+		require.NoError(t, os.WriteFile(path, []byte(src), 0o644))
+	}
+	write(filepath.Join(root, "Cargo.toml"), "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	lib := filepath.Join(root, "src", "lib.rs")
+	libSrc := `include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+pub fn open() -> Bindings {
+    Bindings::new()
+}
+`
+	write(lib, libSrc)
+	bindings := `pub struct Bindings;
+
+impl Bindings {
+    pub fn new() -> Bindings {
+        Bindings
+    }
+}
+`
+	// An older run of the build script and another package's output.
+	stale := filepath.Join(target, "debug", "build", "demo-fedcba9876543210", "out", "bindings.rs")
+	write(stale, bindings)
+	old := time.Now().Add(-time.Hour)
+	require.NoError(t, os.Chtimes(stale, old, old))
+	write(filepath.Join(target, "debug", "build", "demo-macros-0123456789abcdef", "out", "bindings.rs"), bindings)
+	generated := filepath.Join(target, "debug", "build", "demo-0123456789abcdef", "out", "bindings.rs")
+	write(generated, bindings)
+
+	line := strings.Split(libSrc, "\n")[2]
+	col := strings.Index(line, "Bindings")
+
+	// Without a target directory the generated type is unresolved.
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	report, err := e.Query().UnresolvedReferences(UnresolvedFilter{Reasons: []string{UnresolvedBuildGenerated}}, Pagination{})
+	require.NoError(t, err)
+	require.NotEmpty(t, report.Items)
+	assert.Equal(t, "Bindings", report.Items[0].Name)
+
+	e = newIntegrationEngine(t, WithLanguages("rust"), WithTargetDir(target))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	locs, err := e.Query().DefinitionAt(lib, 2, col)
+	require.NoError(t, err)
+	require.Len(t, locs, 1)
+	assert.Equal(t, generated, locs[0].File)
+	assert.Equal(t, 0, locs[0].StartLine)
+
+	report, err = e.Query().UnresolvedReferences(UnresolvedFilter{Reasons: []string{UnresolvedBuildGenerated}}, Pagination{})
+	require.NoError(t, err)
+	assert.Empty(t, report.Items)
+
+	// Output no longer included is dropped from the index.
+	write(lib, "pub fn open() {}\n")
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	f, err := e.store.FileByPath(generated)
+	require.NoError(t, err)
+	assert.Nil(t, f)
+}
+
+func TestCargoPackageName(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		manifest string
+		want     string
+	}{
+		{"[package]\nname = \"demo\"\nversion = \"0.1.0\"\n", "demo"},
+		{"[dependencies]\nname = \"x\"\n\n[package]\nedition = \"2021\"\nname = 'demo-core' # the core\n", "demo-core"},
+		{"[workspace]\nmembers = [\"crates/*\"]\n", ""},
+	}
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, cargoPackageName([]byte(tt.manifest)), tt.manifest)
+	}
+}
@@ -0,0 +1,76 @@
+package canopy
+
+import (
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// matchPathGlob reports whether rel (a slash- or OS-separated path relative
+// to the indexed root) matches pattern, or lies under a directory that does.
+//
+// Patterns use path.Match syntax per segment, plus "**" to match any number
+// of directories. A pattern without a slash (e.g. "target", "*.pb.go") matches
+// any single path component, like a .gitignore entry. A trailing slash is
+// ignored. Patterns with a slash are anchored at the root.
+func matchPathGlob(pattern, rel string) bool {
+	pattern = strings.TrimSuffix(filepath.ToSlash(pattern), "/")
+	pattern = strings.TrimPrefix(pattern, "/")
+	rel = strings.TrimPrefix(filepath.ToSlash(rel), "/")
+	if pattern == "" || rel == "" {
+		return false
+	}
+
+	segs := strings.Split(rel, "/")
+	if !strings.Contains(pattern, "/") {
+		for _, seg := range segs {
+			if ok, _ := path.Match(pattern, seg); ok {
+				return true
+			}
+		}
+		return false
+	}
+
+	pat := strings.Split(pattern, "/")
+	// Match against the full path and every parent directory.
+	for n := len(segs); n > 0; n-- {
+		if matchSegments(pat, segs[:n]) {
+			return true
+		}
+	}
+	return false
+}
+
+// matchSegments matches path segments against pattern segments, where a "**"
+// pattern segment matches zero or more path segments.
+func matchSegments(pat, segs []string) bool {
+	for len(pat) > 0 {
+		if pat[0] == "**" {
+			rest := pat[1:]
+			for i := 0; i <= len(segs); i++ {
+				if matchSegments(rest, segs[i:]) {
+					return true
+				}
+			}
+			return false
+		}
+		if len(segs) == 0 {
+			return false
+		}
+		if ok, _ := path.Match(pat[0], segs[0]); !ok {
+			return false
+		}
+		pat, segs = pat[1:], segs[1:]
+	}
+	return len(segs) == 0
+}
+
+// matchAnyGlob reports whether rel matches any of patterns.
+func matchAnyGlob(patterns []string, rel string) bool {
+	for _, p := range patterns {
+		if matchPathGlob(p, rel) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,43 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestMatchPathGlob(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		pattern string
+		rel     string
+		want    bool
+	}{
+		// Unanchored patterns match any component.
+		{"target", "target/debug/build.rs", true},
+		{"target", "crates/foo/target/out.rs", true},
+		{"target/", "target/out.rs", true},
+		{"*.pb.go", "api/v1/service.pb.go", true},
+		{"*.pb.go", "api/v1/service.go", false},
+
+		// Anchored patterns.
+		{"src/**", "src/lib.rs", true},
+		{"src/**", "src/a/b/c.rs", true},
+		{"src/**", "tests/lib.rs", false},
+		{"src", "src/lib.rs", true},
+		{"/src", "src/lib.rs", true},
+		{"crates/*/src", "crates/foo/src/lib.rs", true},
+		{"crates/*/src", "crates/foo/bar/src/lib.rs", false},
+		{"**/generated/*.rs", "generated/x.rs", true},
+		{"**/generated/*.rs", "a/b/generated/x.rs", true},
+		{"**/generated/*.rs", "a/b/generated/sub/x.rs", false},
+		{"a/**/z.rs", "a/z.rs", true},
+		{"a/**/z.rs", "a/b/c/z.rs", true},
+
+		{"", "src/lib.rs", false},
+	}
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, matchPathGlob(tt.pattern, tt.rel), "pattern %q, path %q", tt.pattern, tt.rel)
+	}
+}
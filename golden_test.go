@@ -3,11 +3,13 @@ package canopy
 import (
 	"context"
 	"encoding/json"
+	"fmt"
 	"os"
 	"path/filepath"
 	"testing"
 
 	"github.com/jward/canopy/internal/store"
+	"github.com/jward/canopy/internal/workload"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 )
@@ -135,6 +137,21 @@ func TestGolden(t *testing.T) {
 	}
 }
 
+// TestGoldenGeneratedFixtures runs the golden checks over generated Rust
+// fixtures at every level, with a few seeds.
+func TestGoldenGeneratedFixtures(t *testing.T) {
+	for level := 1; level <= workload.MaxFixtureLevel; level++ {
+		for _, seed := range []int64{1, 2, 3} {
+			t.Run(fmt.Sprintf("level-%d/seed-%d", level, seed), func(t *testing.T) {
+				dir := t.TempDir()
+				_, err := workload.GenerateFixture(dir, workload.FixtureSpec{Level: level, Files: 4, Functions: 5, Seed: seed})
+				require.NoError(t, err)
+				runGoldenTest(t, "rust", filepath.Join(dir, "src"), filepath.Join(dir, "golden.json"))
+			})
+		}
+	}
+}
+
 func runGoldenTest(t *testing.T, lang, srcDir, goldenPath string) {
 	t.Helper()
 
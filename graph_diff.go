@@ -0,0 +1,197 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// GraphSummary counts the parts of one index that CompareGraphs compares.
+type GraphSummary struct {
+	Files        int
+	Symbols      int
+	Packages     int
+	Dependencies int // package-to-package edges
+	Cycles       int
+	PublicAPI    int // public symbols
+}
+
+// APISymbol is a public symbol, identified across indexes by its file,
+// qualified name, and kind, as symbol IDs differ between indexes.
+type APISymbol struct {
+	File string
+	Name string // qualified by its parent, e.g. "Server.Start"
+	Kind string
+}
+
+// GraphDiff is how the architecture of one index (head) differs from an
+// earlier one (base), such as a snapshot taken before a refactor.
+type GraphDiff struct {
+	Base GraphSummary
+	Head GraphSummary
+
+	AddedDependencies   []DependencyEdge // package edges only head has
+	RemovedDependencies []DependencyEdge // package edges only base has
+	NewCycles           [][]string       // dependency cycles only head has
+	BrokenCycles        [][]string       // dependency cycles only base has
+	AddedAPI            []APISymbol      // public symbols only head has
+	RemovedAPI          []APISymbol      // public symbols only base has
+}
+
+// graphFacts is what CompareGraphs reads from one index.
+type graphFacts struct {
+	summary GraphSummary
+	edges   map[[2]string]DependencyEdge
+	cycles  map[string][]string
+	api     map[APISymbol]bool
+}
+
+// CompareGraphs compares the package dependency graph, dependency cycles,
+// and public API of two indexes of the same code. Symbols are matched by
+// file, qualified name, and kind, so a moved or renamed public symbol shows
+// as removed and added. Edges compare by their packages, not import
+// counts; cycles by their packages, whatever their order. Lists are sorted.
+func CompareGraphs(base, head *QueryBuilder) (*GraphDiff, error) {
+	b, err := base.graphFacts()
+	if err != nil {
+		return nil, fmt.Errorf("compare graphs: base: %w", err)
+	}
+	h, err := head.graphFacts()
+	if err != nil {
+		return nil, fmt.Errorf("compare graphs: head: %w", err)
+	}
+
+	diff := &GraphDiff{
+		Base:                b.summary,
+		Head:                h.summary,
+		AddedDependencies:   []DependencyEdge{},
+		RemovedDependencies: []DependencyEdge{},
+		NewCycles:           [][]string{},
+		BrokenCycles:        [][]string{},
+		AddedAPI:            []APISymbol{},
+		RemovedAPI:          []APISymbol{},
+	}
+	for k, e := range h.edges {
+		if _, ok := b.edges[k]; !ok {
+			diff.AddedDependencies = append(diff.AddedDependencies, e)
+		}
+	}
+	for k, e := range b.edges {
+		if _, ok := h.edges[k]; !ok {
+			diff.RemovedDependencies = append(diff.RemovedDependencies, e)
+		}
+	}
+	for k, c := range h.cycles {
+		if _, ok := b.cycles[k]; !ok {
+			diff.NewCycles = append(diff.NewCycles, c)
+		}
+	}
+	for k, c := range b.cycles {
+		if _, ok := h.cycles[k]; !ok {
+			diff.BrokenCycles = append(diff.BrokenCycles, c)
+		}
+	}
+	for s := range h.api {
+		if !b.api[s] {
+			diff.AddedAPI = append(diff.AddedAPI, s)
+		}
+	}
+	for s := range b.api {
+		if !h.api[s] {
+			diff.RemovedAPI = append(diff.RemovedAPI, s)
+		}
+	}
+
+	for _, edges := range [][]DependencyEdge{diff.AddedDependencies, diff.RemovedDependencies} {
+		sort.Slice(edges, func(i, j int) bool {
+			if edges[i].FromPackage != edges[j].FromPackage {
+				return edges[i].FromPackage < edges[j].FromPackage
+			}
+			return edges[i].ToPackage < edges[j].ToPackage
+		})
+	}
+	for _, cycles := range [][][]string{diff.NewCycles, diff.BrokenCycles} {
+		sort.Slice(cycles, func(i, j int) bool {
+			return strings.Join(cycles[i], " ") < strings.Join(cycles[j], " ")
+		})
+	}
+	for _, api := range [][]APISymbol{diff.AddedAPI, diff.RemovedAPI} {
+		sort.Slice(api, func(i, j int) bool {
+			if api[i].File != api[j].File {
+				return api[i].File < api[j].File
+			}
+			if api[i].Name != api[j].Name {
+				return api[i].Name < api[j].Name
+			}
+			return api[i].Kind < api[j].Kind
+		})
+	}
+	return diff, nil
+}
+
+// graphFacts reads the facts CompareGraphs compares from q's index.
+func (q *QueryBuilder) graphFacts() (*graphFacts, error) {
+	stats, err := q.IndexStats()
+	if err != nil {
+		return nil, err
+	}
+	graph, err := q.PackageDependencyGraph()
+	if err != nil {
+		return nil, err
+	}
+	cycles, err := q.CircularDependencies()
+	if err != nil {
+		return nil, err
+	}
+
+	facts := &graphFacts{
+		edges:  map[[2]string]DependencyEdge{},
+		cycles: map[string][]string{},
+		api:    map[APISymbol]bool{},
+	}
+	for _, e := range graph.Edges {
+		facts.edges[[2]string{e.FromPackage, e.ToPackage}] = e
+	}
+	for _, c := range cycles {
+		// A cycle lists its first package again at the end.
+		members := append([]string{}, c[:len(c)-1]...)
+		sort.Strings(members)
+		facts.cycles[strings.Join(members, "\x00")] = c
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT f.path, s.name, s.kind, COALESCE(p.name, '') FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id
+		 WHERE s.visibility = 'public' AND s.kind != 'package' AND f.language != ?`,
+		rustdocStubLanguage,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("public api: %w", err)
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var sym APISymbol
+		var parent string
+		if err := rows.Scan(&sym.File, &sym.Name, &sym.Kind, &parent); err != nil {
+			return nil, fmt.Errorf("public api: scan: %w", err)
+		}
+		if parent != "" {
+			sym.Name = parent + "." + sym.Name
+		}
+		facts.api[sym] = true
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("public api: %w", err)
+	}
+
+	facts.summary = GraphSummary{
+		Files:        stats.Files,
+		Symbols:      stats.Symbols,
+		Packages:     len(graph.Packages),
+		Dependencies: len(graph.Edges),
+		Cycles:       len(cycles),
+		PublicAPI:    len(facts.api),
+	}
+	return facts, nil
+}
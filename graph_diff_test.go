@@ -0,0 +1,65 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// insertPackageFile inserts a file of package pkg importing the given
+// packages, and returns its ID.
+func insertPackageFile(t *testing.T, s *store.Store, path, pkg string, imports ...string) int64 {
+	t.Helper()
+	fileID := insertFile(t, s, path, "go")
+	_, err := s.InsertSymbol(&store.Symbol{FileID: &fileID, Name: pkg, Kind: "package"})
+	require.NoError(t, err)
+	for _, imp := range imports {
+		_, err := s.InsertImport(&store.Import{FileID: fileID, Source: imp, Kind: "import"})
+		require.NoError(t, err)
+	}
+	return fileID
+}
+
+func TestCompareGraphs(t *testing.T) {
+	t.Parallel()
+	base, bs := newTestQueryBuilder(t)
+	insertPackageFile(t, bs, "/src/a/a.go", "a", "b")
+	bFile := insertPackageFile(t, bs, "/src/b/b.go", "b")
+	server := insertSymbol(t, bs, &bFile, "Server", "struct", "public", nil)
+	insertSymbolWithParent(t, bs, &bFile, "Start", "method", "public", server)
+	insertSymbol(t, bs, &bFile, "Charge", "function", "public", nil)
+	insertSymbol(t, bs, &bFile, "helper", "function", "private", nil)
+
+	head, hs := newTestQueryBuilder(t)
+	insertPackageFile(t, hs, "/src/a/a.go", "a", "b")
+	bFile = insertPackageFile(t, hs, "/src/b/b.go", "b", "a")
+	server = insertSymbol(t, hs, &bFile, "Server", "struct", "public", nil)
+	insertSymbolWithParent(t, hs, &bFile, "Start", "method", "public", server)
+	insertSymbol(t, hs, &bFile, "Refund", "function", "public", nil)
+
+	diff, err := CompareGraphs(base, head)
+	require.NoError(t, err)
+
+	assert.Equal(t, []DependencyEdge{{FromPackage: "b", ToPackage: "a", ImportCount: 1}}, diff.AddedDependencies)
+	assert.Empty(t, diff.RemovedDependencies)
+	require.Len(t, diff.NewCycles, 1)
+	assert.ElementsMatch(t, []string{"a", "b"}, diff.NewCycles[0][:2])
+	assert.Empty(t, diff.BrokenCycles)
+	assert.Equal(t, []APISymbol{{File: "/src/b/b.go", Name: "Refund", Kind: "function"}}, diff.AddedAPI)
+	assert.Equal(t, []APISymbol{{File: "/src/b/b.go", Name: "Charge", Kind: "function"}}, diff.RemovedAPI)
+
+	assert.Equal(t, 3, diff.Base.PublicAPI, "Server, Server.Start, and Charge")
+	assert.Equal(t, 1, diff.Base.Dependencies)
+	assert.Equal(t, 2, diff.Head.Dependencies)
+	assert.Equal(t, 0, diff.Base.Cycles)
+	assert.Equal(t, 1, diff.Head.Cycles)
+
+	same, err := CompareGraphs(head, head)
+	require.NoError(t, err)
+	assert.Empty(t, same.AddedDependencies)
+	assert.Empty(t, same.NewCycles)
+	assert.Empty(t, same.AddedAPI)
+	assert.Empty(t, same.RemovedAPI)
+}
@@ -0,0 +1,151 @@
+package canopy
+
+import (
+	"bufio"
+	"bytes"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// ignoreRule is a single pattern line from a .gitignore-style file.
+type ignoreRule struct {
+	pattern  []string // pattern segments ("**" allowed)
+	anchored bool     // pattern contained a non-trailing slash
+	negate   bool     // "!pattern" re-includes a path
+	dirOnly  bool     // "pattern/" matches only directories
+}
+
+// ignoreMatcher evaluates .gitignore-style files found under root. Rules are
+// loaded lazily per directory and cached. Supported: comments, blank lines,
+// negation, trailing-slash directory patterns, anchored patterns, and "**".
+type ignoreMatcher struct {
+	root     string
+	names    []string                // ignore file names to read in each directory
+	rules    map[string][]ignoreRule // slash-separated dir (relative to root) -> rules
+	readFile func(path string) ([]byte, error)
+}
+
+// newIgnoreMatcher creates a matcher that reads the given ignore file names
+// (e.g. ".gitignore", ".ignore") from root and its subdirectories.
+func newIgnoreMatcher(root string, names ...string) *ignoreMatcher {
+	return &ignoreMatcher{root: root, names: names, rules: map[string][]ignoreRule{}, readFile: os.ReadFile}
+}
+
+// dirRules returns the rules declared in dir (slash-separated, relative to
+// root; "" for root), reading the ignore files on first use.
+func (m *ignoreMatcher) dirRules(dir string) []ignoreRule {
+	if rules, ok := m.rules[dir]; ok {
+		return rules
+	}
+	var rules []ignoreRule
+	for _, name := range m.names {
+		rules = append(rules, m.readIgnoreFile(filepath.Join(m.root, filepath.FromSlash(dir), name))...)
+	}
+	m.rules[dir] = rules
+	return rules
+}
+
+// readIgnoreFile parses a .gitignore-style file. Missing or unreadable files
+// yield no rules.
+func (m *ignoreMatcher) readIgnoreFile(filename string) []ignoreRule {
+	content, err := m.readFile(filename)
+	if err != nil {
+		return nil
+	}
+
+	var rules []ignoreRule
+	scanner := bufio.NewScanner(bytes.NewReader(content))
+	for scanner.Scan() {
+		line := strings.TrimRight(scanner.Text(), " \t\r")
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		if r, ok := parseIgnorePattern(line); ok {
+			rules = append(rules, r)
+		}
+	}
+	return rules
+}
+
+// parseIgnorePattern parses one non-comment .gitignore-style pattern.
+// Reports false for patterns that match nothing (e.g. "/").
+func parseIgnorePattern(line string) (ignoreRule, bool) {
+	var r ignoreRule
+	if strings.HasPrefix(line, "!") {
+		r.negate = true
+		line = line[1:]
+	} else if strings.HasPrefix(line, `\`) {
+		line = line[1:] // escaped leading "#" or "!"
+	}
+	if strings.HasSuffix(line, "/") {
+		r.dirOnly = true
+		line = strings.TrimSuffix(line, "/")
+	}
+	if strings.Contains(line, "/") {
+		r.anchored = true
+		line = strings.TrimPrefix(line, "/")
+	}
+	if line == "" {
+		return ignoreRule{}, false
+	}
+	r.pattern = strings.Split(line, "/")
+	return r, true
+}
+
+// match reports whether the rule matches rel, a slash-separated path relative
+// to the directory that declared the rule.
+func (r ignoreRule) match(rel string, isDir bool) bool {
+	if r.dirOnly && !isDir {
+		return false
+	}
+	if !r.anchored {
+		ok, _ := path.Match(r.pattern[0], path.Base(rel))
+		return ok
+	}
+	return matchSegments(r.pattern, strings.Split(rel, "/"))
+}
+
+// ignoredEntry evaluates rel (slash-separated, relative to root) against the
+// rules of every ancestor directory, assuming its parents are not ignored.
+// Later rules and deeper files take precedence, as in git.
+func (m *ignoreMatcher) ignoredEntry(rel string, isDir bool) bool {
+	ignored := false
+	dir := ""
+	for {
+		relToDir := rel
+		if dir != "" {
+			relToDir = strings.TrimPrefix(rel, dir+"/")
+		}
+		for _, r := range m.dirRules(dir) {
+			if r.match(relToDir, isDir) {
+				ignored = !r.negate
+			}
+		}
+		next := strings.IndexByte(relToDir, '/')
+		if next < 0 {
+			break
+		}
+		if dir == "" {
+			dir = relToDir[:next]
+		} else {
+			dir = dir + "/" + relToDir[:next]
+		}
+	}
+	return ignored
+}
+
+// ignored reports whether rel (a path relative to root) or any of its parent
+// directories is ignored. Used to filter file lists that were not produced by
+// a walk that already pruned ignored directories.
+func (m *ignoreMatcher) ignored(rel string, isDir bool) bool {
+	rel = filepath.ToSlash(rel)
+	segs := strings.Split(rel, "/")
+	for i := 1; i < len(segs); i++ {
+		if m.ignoredEntry(strings.Join(segs[:i], "/"), true) {
+			return true
+		}
+	}
+	return m.ignoredEntry(rel, isDir)
+}
@@ -0,0 +1,73 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func writeIgnoreFile(t *testing.T, dir, name, content string) {
+	t.Helper()
+	require.NoError(t, os.MkdirAll(dir, 0755))
+	require.NoError(t, os.WriteFile(filepath.Join(dir, name), []byte(content), 0644))
+}
+
+func TestIgnoreMatcher_GitignoreSemantics(t *testing.T) {
+	t.Parallel()
+	root := t.TempDir()
+	writeIgnoreFile(t, root, ".gitignore", `
+# build output
+target/
+*.log
+/generated
+!keep.log
+docs/**/*.tmp
+`)
+	writeIgnoreFile(t, filepath.Join(root, "crates", "foo"), ".gitignore", "local.rs\n")
+
+	m := newIgnoreMatcher(root, ".gitignore")
+
+	tests := []struct {
+		rel   string
+		isDir bool
+		want  bool
+	}{
+		{"target", true, true},
+		{"target/debug/main.rs", false, true},
+		{"crates/foo/target/out.rs", false, true},
+		{"target", false, false}, // dir-only pattern
+		{"app.log", false, true},
+		{"src/app.log", false, true},
+		{"keep.log", false, false}, // negated
+		{"generated/api.rs", false, true},
+		{"src/generated/api.rs", false, false}, // anchored to root
+		{"docs/a/b/x.tmp", false, true},
+		{"crates/foo/local.rs", false, true},
+		{"crates/bar/local.rs", false, false}, // nested file only applies below it
+		{"src/main.rs", false, false},
+	}
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, m.ignored(tt.rel, tt.isDir), "path %q (dir=%v)", tt.rel, tt.isDir)
+	}
+}
+
+func TestIndexDirectory_WalkHonorsIgnoreFiles(t *testing.T) {
+	root := t.TempDir()
+	writeIgnoreFile(t, root, ".gitignore", "target/\n")
+	writeIgnoreFile(t, root, ".ignore", "bench/\n")
+	for _, dir := range []string{"target", "bench"} {
+		d := filepath.Join(root, dir)
+		require.NoError(t, os.MkdirAll(d, 0755))
+		require.NoError(t, os.WriteFile(filepath.Join(d, "lib.go"), []byte("package lib"), 0644))
+	}
+
+	e := newTestEngine(t)
+
+	// All Go files are ignored → nothing reaches extraction → no error.
+	err := e.IndexDirectory(context.Background(), root)
+	require.NoError(t, err)
+}
@@ -0,0 +1,41 @@
+package canopy
+
+import (
+	"bytes"
+	"fmt"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// recordImplBlocks stores the impl blocks of a Rust file, with the header
+// and trait of each parsed from content, so ImplBlocks reads no source.
+// Must run after the file's scopes are extracted.
+func (e *Engine) recordImplBlocks(fileID int64, lang string, content []byte) error {
+	if lang != "rust" {
+		return nil
+	}
+	scopes, err := e.store.ScopesByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load scopes: %w", err)
+	}
+	li := newLineIndex(content)
+	lines := bytes.Split(content, []byte{'\n'})
+	var rows []store.ImplBlock
+	for _, s := range scopes {
+		if s.Kind != "impl" {
+			continue
+		}
+		b := store.ImplBlock{
+			FileID: fileID, StartLine: s.StartLine, StartCol: s.StartCol, EndLine: s.EndLine, EndCol: s.EndCol,
+			Header: implHeader(lines, s.StartLine),
+		}
+		b.StartByte, b.UTF16StartCol = li.offsets(s.StartLine, s.StartCol)
+		b.EndByte, b.UTF16EndCol = li.offsets(s.EndLine, s.EndCol)
+		if h, ok := parseRustImplHeader(string(content[b.StartByte:b.EndByte])); ok {
+			b.Trait = h.trait
+			b.TraitStartByte, b.TraitEndByte = b.StartByte+h.traitStart, b.StartByte+h.traitEnd
+		}
+		rows = append(rows, b)
+	}
+	return e.store.InsertImplBlocks(rows)
+}
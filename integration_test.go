@@ -318,6 +318,50 @@ func Do() string {
 	require.NotEmpty(t, syms)
 }
 
+// TestIntegration_IndexDirectories verifies that several roots share one
+// graph while each file records the root it came from.
+func TestIntegration_IndexDirectories(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+
+	rootA := t.TempDir()
+	rootB := t.TempDir()
+	writeGoFile(t, rootA, "a.go", `package a
+
+func FromA() {}
+`)
+	writeGoFile(t, rootB, "b.go", `package b
+
+func FromB() {}
+`)
+
+	require.NoError(t, e.IndexDirectories(ctx, []string{rootA, rootB}))
+	require.NoError(t, e.Resolve(ctx))
+
+	fileA, err := e.store.FileByPath(filepath.Join(rootA, "a.go"))
+	require.NoError(t, err)
+	require.NotNil(t, fileA)
+	assert.Equal(t, rootA, fileA.Root)
+
+	fileB, err := e.store.FileByPath(filepath.Join(rootB, "b.go"))
+	require.NoError(t, err)
+	require.NotNil(t, fileB)
+	assert.Equal(t, rootB, fileB.Root)
+
+	roots, err := e.Query().Roots()
+	require.NoError(t, err)
+	require.Len(t, roots, 2)
+	for _, r := range roots {
+		assert.Equal(t, 1, r.FileCount)
+	}
+
+	// Re-indexing one root must not drop the other root's files.
+	require.NoError(t, e.IndexDirectory(ctx, rootA))
+	fileB, err = e.store.FileByPath(filepath.Join(rootB, "b.go"))
+	require.NoError(t, err)
+	require.NotNil(t, fileB)
+}
+
 // TestIntegration_IncrementalReindex verifies that re-indexing a changed file
 // works correctly (old data deleted, new data extracted).
 func TestIntegration_IncrementalReindex(t *testing.T) {
@@ -0,0 +1,362 @@
+// Package parquet writes flat tables as Apache Parquet files, for loading
+// code-graph data into data warehouses and dataframe libraries. It supports
+// the subset of the format that flat relational exports need: one level of
+// required or optional columns of 64-bit integers, doubles, booleans, and
+// UTF-8 strings, PLAIN-encoded in gzip-compressed version 1 data pages, one
+// page per column chunk.
+package parquet
+
+import (
+	"bytes"
+	"compress/gzip"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"math"
+)
+
+const magic = "PAR1"
+
+// DefaultRowGroupSize is the number of rows a Writer buffers before writing
+// them out as a row group.
+const DefaultRowGroupSize = 64 * 1024
+
+// Type is the type of a column's values.
+type Type int
+
+const (
+	Int64   Type = iota // int64 values
+	Double              // float64 values
+	Boolean             // bool values
+	String              // string values, stored as UTF-8 byte arrays
+)
+
+// Column describes one column of a table. Optional columns accept nil
+// values, which read back as nulls.
+type Column struct {
+	Name     string
+	Type     Type
+	Optional bool
+}
+
+// Physical types, encodings, and other enums of the Parquet thrift schema.
+const (
+	physicalBoolean   = 0
+	physicalInt64     = 2
+	physicalDouble    = 5
+	physicalByteArray = 6
+
+	repetitionRequired = 0
+	repetitionOptional = 1
+
+	convertedUTF8 = 0
+
+	encodingPlain = 0
+	encodingRLE   = 3
+
+	codecGzip = 2
+
+	pageTypeData = 0
+)
+
+func (t Type) physical() int32 {
+	switch t {
+	case Int64:
+		return physicalInt64
+	case Double:
+		return physicalDouble
+	case Boolean:
+		return physicalBoolean
+	default:
+		return physicalByteArray
+	}
+}
+
+// Writer writes rows to a Parquet file. Rows are buffered and written as a
+// row group every RowGroupSize rows; Close writes the last row group and
+// the file footer.
+type Writer struct {
+	// RowGroupSize is the number of rows per row group. Zero means
+	// DefaultRowGroupSize.
+	RowGroupSize int
+
+	w       io.Writer
+	columns []Column
+	offset  int64 // bytes written so far
+	values  [][]any
+	rows    int
+	total   int64
+	groups  []rowGroup
+	closed  bool
+}
+
+// rowGroup is the footer metadata of a written row group.
+type rowGroup struct {
+	rows    int64
+	bytes   int64
+	columns []columnChunk
+}
+
+// columnChunk is the footer metadata of a written column chunk.
+type columnChunk struct {
+	offset       int64
+	values       int64
+	uncompressed int64
+	compressed   int64
+}
+
+// NewWriter returns a Writer of a table with the given columns to w.
+func NewWriter(w io.Writer, columns []Column) *Writer {
+	return &Writer{w: w, columns: columns, values: make([][]any, len(columns))}
+}
+
+// Write adds a row, one value per column: int64, float64, bool, or string
+// as the column's Type requires, or nil in an optional column.
+func (w *Writer) Write(row []any) error {
+	if w.closed {
+		return errors.New("parquet: write after close")
+	}
+	if len(row) != len(w.columns) {
+		return fmt.Errorf("parquet: row has %d values, table has %d columns", len(row), len(w.columns))
+	}
+	for i, v := range row {
+		c := w.columns[i]
+		ok := false
+		switch v.(type) {
+		case nil:
+			ok = c.Optional
+		case int64:
+			ok = c.Type == Int64
+		case float64:
+			ok = c.Type == Double
+		case bool:
+			ok = c.Type == Boolean
+		case string:
+			ok = c.Type == String
+		}
+		if !ok {
+			return fmt.Errorf("parquet: column %s: unexpected value %T", c.Name, v)
+		}
+	}
+	for i, v := range row {
+		w.values[i] = append(w.values[i], v)
+	}
+	w.rows++
+	size := w.RowGroupSize
+	if size <= 0 {
+		size = DefaultRowGroupSize
+	}
+	if w.rows >= size {
+		return w.flush()
+	}
+	return nil
+}
+
+// Close writes any buffered rows and the file footer. It does not close
+// the underlying writer.
+func (w *Writer) Close() error {
+	if w.closed {
+		return nil
+	}
+	if err := w.flush(); err != nil {
+		return err
+	}
+	w.closed = true
+	if err := w.start(); err != nil {
+		return err
+	}
+	footer := w.footer()
+	footer = binary.LittleEndian.AppendUint32(footer, uint32(len(footer)))
+	footer = append(footer, magic...)
+	return w.write(footer)
+}
+
+// start writes the leading magic number of the file, once.
+func (w *Writer) start() error {
+	if w.offset > 0 {
+		return nil
+	}
+	return w.write([]byte(magic))
+}
+
+func (w *Writer) write(p []byte) error {
+	n, err := w.w.Write(p)
+	w.offset += int64(n)
+	if err != nil {
+		return fmt.Errorf("parquet: %w", err)
+	}
+	return nil
+}
+
+// flush writes the buffered rows as a row group.
+func (w *Writer) flush() error {
+	if w.rows == 0 {
+		return nil
+	}
+	if err := w.start(); err != nil {
+		return err
+	}
+	group := rowGroup{rows: int64(w.rows)}
+	for i, c := range w.columns {
+		chunk, err := w.writeColumn(c, w.values[i])
+		if err != nil {
+			return err
+		}
+		group.columns = append(group.columns, chunk)
+		group.bytes += chunk.uncompressed
+		w.values[i] = w.values[i][:0]
+	}
+	w.groups = append(w.groups, group)
+	w.total += group.rows
+	w.rows = 0
+	return nil
+}
+
+// writeColumn writes values as a column chunk of one data page.
+func (w *Writer) writeColumn(c Column, values []any) (columnChunk, error) {
+	var body bytes.Buffer
+	if c.Optional {
+		levels := encodeDefinitionLevels(values)
+		body.Write(binary.LittleEndian.AppendUint32(nil, uint32(len(levels))))
+		body.Write(levels)
+	}
+	body.Write(encodePlain(c.Type, values))
+
+	var compressed bytes.Buffer
+	zw := gzip.NewWriter(&compressed)
+	if _, err := zw.Write(body.Bytes()); err != nil {
+		return columnChunk{}, fmt.Errorf("parquet: %w", err)
+	}
+	if err := zw.Close(); err != nil {
+		return columnChunk{}, fmt.Errorf("parquet: %w", err)
+	}
+
+	var t thriftWriter
+	t.i32(1, pageTypeData)
+	t.i32(2, int32(body.Len()))
+	t.i32(3, int32(compressed.Len()))
+	t.beginStruct(5)
+	t.i32(1, int32(len(values)))
+	t.i32(2, encodingPlain)
+	t.i32(3, encodingRLE)
+	t.i32(4, encodingRLE)
+	t.endStruct()
+	t.stop()
+
+	chunk := columnChunk{
+		offset:       w.offset,
+		values:       int64(len(values)),
+		uncompressed: int64(len(t.buf) + body.Len()),
+		compressed:   int64(len(t.buf) + compressed.Len()),
+	}
+	if err := w.write(t.buf); err != nil {
+		return columnChunk{}, err
+	}
+	return chunk, w.write(compressed.Bytes())
+}
+
+// footer encodes the FileMetaData of the file.
+func (w *Writer) footer() []byte {
+	var t thriftWriter
+	t.i32(1, 1) // version
+	t.listBegin(2, thriftStruct, len(w.columns)+1)
+	t.elemBegin()
+	t.binary(4, "schema")
+	t.i32(5, int32(len(w.columns)))
+	t.elemEnd()
+	for _, c := range w.columns {
+		t.elemBegin()
+		t.i32(1, c.Type.physical())
+		repetition := int32(repetitionRequired)
+		if c.Optional {
+			repetition = repetitionOptional
+		}
+		t.i32(3, repetition)
+		t.binary(4, c.Name)
+		if c.Type == String {
+			t.i32(6, convertedUTF8)
+		}
+		t.elemEnd()
+	}
+	t.i64(3, w.total)
+	t.listBegin(4, thriftStruct, len(w.groups))
+	for _, g := range w.groups {
+		t.elemBegin()
+		t.listBegin(1, thriftStruct, len(g.columns))
+		for i, cc := range g.columns {
+			c := w.columns[i]
+			t.elemBegin()
+			t.i64(2, cc.offset)
+			t.beginStruct(3)
+			t.i32(1, c.Type.physical())
+			t.listBegin(2, thriftI32, 2)
+			t.listI32(encodingPlain)
+			t.listI32(encodingRLE)
+			t.listBegin(3, thriftBinary, 1)
+			t.listBinary(c.Name)
+			t.i32(4, codecGzip)
+			t.i64(5, cc.values)
+			t.i64(6, cc.uncompressed)
+			t.i64(7, cc.compressed)
+			t.i64(9, cc.offset)
+			t.endStruct()
+			t.elemEnd()
+		}
+		t.i64(2, g.bytes)
+		t.i64(3, g.rows)
+		t.elemEnd()
+	}
+	t.binary(6, "canopy")
+	t.stop()
+	return t.buf
+}
+
+// encodeDefinitionLevels encodes whether each value is present (1) or null
+// (0) as one bit-packed run of the RLE/bit-packing hybrid encoding, with a
+// bit width of 1.
+func encodeDefinitionLevels(values []any) []byte {
+	groups := (len(values) + 7) / 8
+	out := binary.AppendUvarint(nil, uint64(groups)<<1|1)
+	bits := make([]byte, groups)
+	for i, v := range values {
+		if v != nil {
+			bits[i/8] |= 1 << (i % 8)
+		}
+	}
+	return append(out, bits...)
+}
+
+// encodePlain encodes the non-null values with the PLAIN encoding.
+func encodePlain(typ Type, values []any) []byte {
+	var out []byte
+	if typ == Boolean {
+		var bits []byte
+		n := 0
+		for _, v := range values {
+			if v == nil {
+				continue
+			}
+			if n%8 == 0 {
+				bits = append(bits, 0)
+			}
+			if v.(bool) {
+				bits[n/8] |= 1 << (n % 8)
+			}
+			n++
+		}
+		return bits
+	}
+	for _, v := range values {
+		switch v := v.(type) {
+		case int64:
+			out = binary.LittleEndian.AppendUint64(out, uint64(v))
+		case float64:
+			out = binary.LittleEndian.AppendUint64(out, math.Float64bits(v))
+		case string:
+			out = binary.LittleEndian.AppendUint32(out, uint32(len(v)))
+			out = append(out, v...)
+		}
+	}
+	return out
+}
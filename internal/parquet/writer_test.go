@@ -0,0 +1,97 @@
+package parquet
+
+import (
+	"bytes"
+	"compress/gzip"
+	"encoding/binary"
+	"io"
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestThriftWriter(t *testing.T) {
+	t.Parallel()
+
+	var w thriftWriter
+	w.i32(1, 3)
+	w.binary(2, "ab")
+	w.beginStruct(5)
+	w.i64(1, -1)
+	w.endStruct()
+	w.i32(22, 1) // a delta over 15 takes the long form
+	w.listBegin(23, thriftI32, 2)
+	w.listI32(0)
+	w.listI32(3)
+	w.stop()
+	assert.Equal(t, []byte{
+		// field 1, i32 3 (zigzag 6)
+		0x15, 0x06,
+		// field 2, binary "ab"
+		0x18, 0x02, 'a', 'b',
+		// field 5, struct holding field 1, i64 -1 (zigzag 1)
+		0x3c, 0x16, 0x01, 0x00,
+		// field 22 in the long form, i32 1
+		0x05, 0x2c, 0x02,
+		// field 23, list of two i32
+		0x19, 0x25, 0x00, 0x06,
+		0x00,
+	}, w.buf)
+}
+
+func TestEncodeDefinitionLevels(t *testing.T) {
+	t.Parallel()
+
+	values := []any{int64(1), nil, int64(3), nil, nil, nil, nil, nil, int64(9)}
+	assert.Equal(t, []byte{0x05, 0b0000_0101, 0b0000_0001}, encodeDefinitionLevels(values))
+	assert.Equal(t, []byte{0x03, 0x01}, encodeDefinitionLevels(values[:1]))
+}
+
+func TestEncodePlain(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, []byte{1, 0, 0, 0, 0, 0, 0, 0, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff},
+		encodePlain(Int64, []any{int64(1), nil, int64(-2)}))
+	assert.Equal(t, []byte{2, 0, 0, 0, 'h', 'i', 0, 0, 0, 0}, encodePlain(String, []any{"hi", ""}))
+	assert.Equal(t, []byte{0b101}, encodePlain(Boolean, []any{true, false, nil, true}))
+	assert.Equal(t, binary.LittleEndian.AppendUint64(nil, math.Float64bits(0.5)), encodePlain(Double, []any{0.5}))
+}
+
+func TestWriter_FileLayout(t *testing.T) {
+	t.Parallel()
+
+	var buf bytes.Buffer
+	w := NewWriter(&buf, []Column{
+		{Name: "id", Type: Int64},
+		{Name: "name", Type: String, Optional: true},
+	})
+	w.RowGroupSize = 2
+	require.NoError(t, w.Write([]any{int64(1), "a"}))
+	require.NoError(t, w.Write([]any{int64(2), nil}))
+	require.NoError(t, w.Write([]any{int64(3), "c"}))
+	assert.Error(t, w.Write([]any{nil, "d"}), "id is required")
+	assert.Error(t, w.Write([]any{"4", "d"}), "id is an integer")
+	assert.Error(t, w.Write([]any{int64(4)}), "one value per column")
+	require.NoError(t, w.Close())
+
+	data := buf.Bytes()
+	require.True(t, bytes.HasPrefix(data, []byte(magic)))
+	require.True(t, bytes.HasSuffix(data, []byte(magic)))
+	footerLen := binary.LittleEndian.Uint32(data[len(data)-8:])
+	footer := data[len(data)-8-int(footerLen) : len(data)-8]
+	assert.Contains(t, string(footer), "canopy")
+	assert.Contains(t, string(footer), "name")
+
+	// The first page is the id column of the first row group: a page header,
+	// then the gzip-compressed PLAIN values.
+	start := bytes.Index(data, []byte{0x1f, 0x8b})
+	require.Greater(t, start, len(magic))
+	zr, err := gzip.NewReader(bytes.NewReader(data[start:]))
+	require.NoError(t, err)
+	zr.Multistream(false)
+	page, err := io.ReadAll(zr)
+	require.NoError(t, err)
+	assert.Equal(t, encodePlain(Int64, []any{int64(1), int64(2)}), page)
+}
@@ -0,0 +1,142 @@
+package runtime
+
+import (
+	"context"
+	"fmt"
+	"hash/fnv"
+	"strings"
+
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// BodyFingerprint is the structural fingerprint of one function body: the
+// hash of each of its statements, in order. Statements are hashed over their
+// syntax tree with identifiers, literals, and comments normalized away, so a
+// copy with renamed variables or changed constants fingerprints the same.
+type BodyFingerprint struct {
+	StartByte  int // span of the function node
+	EndByte    int
+	Statements []uint64
+}
+
+// FunctionFingerprints parses src as lang, with the same recovery as the
+// parse host function, and fingerprints the body of every function-like node
+// (functions, methods, constructors, lambdas, closures). Nested functions are
+// fingerprinted separately as well as within their enclosing body.
+func FunctionFingerprints(ctx context.Context, lang string, src []byte) ([]BodyFingerprint, error) {
+	grammar, ok := ParserForLanguage(lang)
+	if !ok {
+		return nil, fmt.Errorf("function fingerprints: unsupported language %q", lang)
+	}
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(grammar)
+
+	tree, _, _, err := parseRecovering(ctx, parser, src)
+	if err != nil {
+		return nil, fmt.Errorf("function fingerprints: %w", err)
+	}
+	defer tree.Close()
+
+	var fps []BodyFingerprint
+	var walk func(n *sitter.Node)
+	walk = func(n *sitter.Node) {
+		if isFunctionNode(n.Type()) {
+			if body := n.ChildByFieldName("body"); body != nil {
+				stmts := bodyStatements(body)
+				hashes := make([]uint64, 0, len(stmts))
+				for _, s := range stmts {
+					hashes = append(hashes, hashStatement(s))
+				}
+				fps = append(fps, BodyFingerprint{
+					StartByte:  int(n.StartByte()),
+					EndByte:    int(n.EndByte()),
+					Statements: hashes,
+				})
+			}
+		}
+		for i := 0; i < int(n.NamedChildCount()); i++ {
+			walk(n.NamedChild(i))
+		}
+	}
+	walk(tree.RootNode())
+	return fps, nil
+}
+
+// isFunctionNode reports whether a node type declares a function-like body
+// across the supported grammars (function_declaration, method_definition,
+// function_item, arrow_function, closure_expression, lambda, ...).
+func isFunctionNode(typ string) bool {
+	for _, s := range []string{"function", "method", "constructor", "lambda", "closure"} {
+		if strings.Contains(typ, s) {
+			return true
+		}
+	}
+	return false
+}
+
+// bodyStatements returns the statements of a function body. A block body
+// yields its named children (descending through a lone statement list); an
+// expression body (as in arrow functions) is a single statement.
+func bodyStatements(body *sitter.Node) []*sitter.Node {
+	typ := body.Type()
+	if !strings.Contains(typ, "block") && !strings.Contains(typ, "body") && !strings.Contains(typ, "compound") {
+		return []*sitter.Node{body}
+	}
+	if body.NamedChildCount() == 1 && strings.HasSuffix(body.NamedChild(0).Type(), "statement_list") {
+		body = body.NamedChild(0)
+	}
+	var stmts []*sitter.Node
+	for i := 0; i < int(body.NamedChildCount()); i++ {
+		child := body.NamedChild(i)
+		if !strings.Contains(child.Type(), "comment") {
+			stmts = append(stmts, child)
+		}
+	}
+	return stmts
+}
+
+// hashStatement returns the normalized structural hash of a statement.
+func hashStatement(n *sitter.Node) uint64 {
+	h := fnv.New64a()
+	var write func(n *sitter.Node)
+	write = func(n *sitter.Node) {
+		typ := n.Type()
+		switch {
+		case strings.Contains(typ, "comment"):
+			return
+		case n.IsNamed() && isLiteralNode(typ):
+			h.Write([]byte("$lit\x00"))
+			return
+		case n.IsNamed() && n.ChildCount() == 0 && strings.Contains(typ, "identifier"):
+			h.Write([]byte("$id\x00"))
+			return
+		}
+		h.Write([]byte(typ))
+		h.Write([]byte{0})
+		if n.ChildCount() == 0 {
+			return
+		}
+		h.Write([]byte("(\x00"))
+		for i := 0; i < int(n.ChildCount()); i++ {
+			write(n.Child(i))
+		}
+		h.Write([]byte(")\x00"))
+	}
+	write(n)
+	return h.Sum64()
+}
+
+// isLiteralNode reports whether a named node type is a literal value.
+func isLiteralNode(typ string) bool {
+	switch typ {
+	case "true", "false", "nil", "null", "none", "None", "undefined":
+		return true
+	}
+	for _, s := range []string{"literal", "string", "number", "integer", "float", "char"} {
+		if strings.Contains(typ, s) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,52 @@
+package runtime
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestFunctionFingerprints_NormalizesNamesAndLiterals(t *testing.T) {
+	const src = `package main
+
+func Sum(xs []int) int {
+	total := 0
+	for _, x := range xs {
+		total += x
+	}
+	return total
+}
+
+// Renamed copy with a different literal and a comment.
+func Add(values []int) int {
+	acc := 1
+	// accumulate
+	for _, v := range values {
+		acc += v
+	}
+	return acc
+}
+
+func Other(xs []int) int {
+	return len(xs)
+}
+`
+	fps, err := FunctionFingerprints(context.Background(), "go", []byte(src))
+	require.NoError(t, err)
+	require.Len(t, fps, 3)
+
+	assert.Len(t, fps[0].Statements, 3)
+	assert.Equal(t, fps[0].Statements, fps[1].Statements, "renamed copy fingerprints the same")
+	assert.NotEqual(t, fps[0].Statements, fps[2].Statements)
+
+	assert.Equal(t, len("package main\n\n"), fps[0].StartByte)
+}
+
+func TestFunctionFingerprints_ExpressionBody(t *testing.T) {
+	fps, err := FunctionFingerprints(context.Background(), "javascript", []byte("const f = (a) => a + 1;\n"))
+	require.NoError(t, err)
+	require.Len(t, fps, 1)
+	assert.Len(t, fps[0].Statements, 1)
+}
@@ -3,7 +3,6 @@ package runtime
 import (
 	"context"
 	"fmt"
-	"os"
 	"sync"
 	"unsafe"
 
@@ -62,10 +61,11 @@ func (s *sourceStore) languageForNode(node *sitter.Node) (*sitter.Language, bool
 	return lang, ok
 }
 
-// makeParseFn creates the "parse" host function.
+// makeParseFn creates the "parse" host function. Source files are read with
+// readFile.
 //
 // parse(path, language) → *sitter.Tree
-func makeParseFn(ss *sourceStore) *object.Builtin {
+func makeParseFn(ss *sourceStore, readFile func(path string) ([]byte, error)) *object.Builtin {
 	return object.NewBuiltin("parse", func(ctx context.Context, args ...object.Object) object.Object {
 		if len(args) != 2 {
 			return object.NewArgsError("parse", 2, len(args))
@@ -81,7 +81,7 @@ func makeParseFn(ss *sourceStore) *object.Builtin {
 			return object.Errorf("parse: language must be a string, got %s", args[1].Type())
 		}
 
-		src, err := os.ReadFile(pathStr.Value())
+		src, err := readFile(pathStr.Value())
 		if err != nil {
 			return object.Errorf("parse: reading %s: %v", pathStr.Value(), err)
 		}
@@ -113,7 +113,32 @@ func makeParseSrcFn(ss *sourceStore) *object.Builtin {
 	})
 }
 
-// parseSource is the shared implementation for parse and parse_src.
+// makeReadSourceFn creates "read_source", which returns the text of a
+// source file as read by parse, for scripts that scan regions the grammar
+// cannot parse (Objective-C in a C header).
+//
+// read_source(path) → string
+func makeReadSourceFn(readFile func(path string) ([]byte, error)) *object.Builtin {
+	return object.NewBuiltin("read_source", func(ctx context.Context, args ...object.Object) object.Object {
+		if len(args) != 1 {
+			return object.NewArgsError("read_source", 1, len(args))
+		}
+
+		pathStr, ok := args[0].(*object.String)
+		if !ok {
+			return object.Errorf("read_source: path must be a string, got %s", args[0].Type())
+		}
+
+		src, err := readFile(pathStr.Value())
+		if err != nil {
+			return object.Errorf("read_source: reading %s: %v", pathStr.Value(), err)
+		}
+		return object.NewString(string(src))
+	})
+}
+
+// parseSource is the shared implementation for parse and parse_src. Files
+// with syntax errors are recovered at item boundaries (see parseRecovering).
 func parseSource(ctx context.Context, ss *sourceStore, src []byte, langName string) object.Object {
 	lang, found := ParserForLanguage(langName)
 	if !found {
@@ -124,7 +149,7 @@ func parseSource(ctx context.Context, ss *sourceStore, src []byte, langName stri
 	defer parser.Close()
 	parser.SetLanguage(lang)
 
-	tree, err := parser.ParseCtx(ctx, nil, src)
+	tree, src, _, err := parseRecovering(ctx, parser, src)
 	if err != nil {
 		return object.Errorf("parse: tree-sitter parse failed: %v", err)
 	}
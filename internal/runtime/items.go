@@ -0,0 +1,144 @@
+package runtime
+
+import (
+	"context"
+	"fmt"
+	"strings"
+
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// RustItem is an item of a Rust file that declares something: a type,
+// function, trait, constant, macro, or impl block.
+type RustItem struct {
+	// Key identifies the item for comparison across files: the item kind and
+	// name ("struct Config", "fn parse"), or for impl blocks the trait and
+	// type without paths or generics ("impl Default for Config",
+	// "impl Config").
+	Key string
+	// Module is the path of the inline modules enclosing the item ("a::b"),
+	// or "" at the top level of the file.
+	Module    string
+	StartByte int // span of the item, including the attributes in front of it
+	EndByte   int
+	StartLine int // 0-based
+	StartCol  int
+	EndLine   int
+	EndCol    int
+}
+
+// rustItemKinds maps the node types of named items to their key prefix.
+var rustItemKinds = map[string]string{
+	"struct_item":      "struct",
+	"enum_item":        "enum",
+	"union_item":       "union",
+	"trait_item":       "trait",
+	"function_item":    "fn",
+	"type_item":        "type",
+	"const_item":       "const",
+	"static_item":      "static",
+	"macro_definition": "macro",
+}
+
+// RustItems parses src as Rust, with the same recovery as the parse host
+// function, and returns its items in source order. The items of inline
+// modules are listed with their module path in place of the module itself;
+// imports, extern crates, macro invocations, and items nested in functions
+// are not listed.
+func RustItems(ctx context.Context, src []byte) ([]RustItem, error) {
+	grammar, ok := ParserForLanguage("rust")
+	if !ok {
+		return nil, fmt.Errorf("rust items: rust grammar not available")
+	}
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(grammar)
+
+	tree, src, _, err := parseRecovering(ctx, parser, src)
+	if err != nil {
+		return nil, fmt.Errorf("rust items: %w", err)
+	}
+	defer tree.Close()
+
+	var items []RustItem
+	var walk func(list *sitter.Node, module string)
+	walk = func(list *sitter.Node, module string) {
+		var attrStart *sitter.Node // first of the attributes in front of the next item
+		for i := 0; i < int(list.NamedChildCount()); i++ {
+			n := list.NamedChild(i)
+			switch n.Type() {
+			case "attribute_item":
+				if attrStart == nil {
+					attrStart = n
+				}
+				continue
+			case "line_comment", "block_comment":
+				continue
+			}
+			start := n
+			if attrStart != nil {
+				start = attrStart
+			}
+			attrStart = nil
+
+			key := ""
+			switch n.Type() {
+			case "mod_item":
+				if body := n.ChildByFieldName("body"); body != nil {
+					walk(body, joinModule(module, rustNodeText(n.ChildByFieldName("name"), src)))
+				}
+			case "impl_item":
+				typ := rustTypeBase(rustNodeText(n.ChildByFieldName("type"), src))
+				key = "impl " + typ
+				if trait := n.ChildByFieldName("trait"); trait != nil {
+					key = "impl " + rustTypeBase(rustNodeText(trait, src)) + " for " + typ
+				}
+			default:
+				if kind, ok := rustItemKinds[n.Type()]; ok {
+					key = kind + " " + rustNodeText(n.ChildByFieldName("name"), src)
+				}
+			}
+			if key == "" {
+				continue
+			}
+			items = append(items, RustItem{
+				Key:       key,
+				Module:    module,
+				StartByte: int(start.StartByte()),
+				EndByte:   int(n.EndByte()),
+				StartLine: int(start.StartPoint().Row),
+				StartCol:  int(start.StartPoint().Column),
+				EndLine:   int(n.EndPoint().Row),
+				EndCol:    int(n.EndPoint().Column),
+			})
+		}
+	}
+	walk(tree.RootNode(), "")
+	return items, nil
+}
+
+// rustNodeText returns the source text of n, or "" for a nil node.
+func rustNodeText(n *sitter.Node, src []byte) string {
+	if n == nil {
+		return ""
+	}
+	return n.Content(src)
+}
+
+// rustTypeBase strips the path and generic arguments from a type or trait
+// name: "std::fmt::Display" -> "Display", "Vec<T>" -> "Vec".
+func rustTypeBase(name string) string {
+	name, _, _ = strings.Cut(name, "<")
+	if i := strings.LastIndex(name, "::"); i >= 0 {
+		name = name[i+2:]
+	}
+	return strings.TrimSpace(name)
+}
+
+// joinModule appends a module name to a module path.
+func joinModule(path, name string) string {
+	if path == "" {
+		return name
+	}
+	return path + "::" + name
+}
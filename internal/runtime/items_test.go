@@ -0,0 +1,60 @@
+package runtime
+
+import (
+	"context"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRustItems(t *testing.T) {
+	const src = `use std::fmt;
+
+#[derive(Debug)]
+/// A configuration.
+pub struct Config<T> {
+    value: T,
+}
+
+impl<T> fmt::Display for Config<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn nested() {}
+        Ok(())
+    }
+}
+
+mod inner {
+    pub const LIMIT: u32 = 3;
+    impl super::Config<u32> {}
+}
+
+mod declared;
+
+macro_rules! noop { () => {} }
+`
+	items, err := RustItems(context.Background(), []byte(src))
+	require.NoError(t, err)
+
+	var keys []string
+	for _, it := range items {
+		key := it.Key
+		if it.Module != "" {
+			key = it.Module + ": " + key
+		}
+		keys = append(keys, key)
+	}
+	assert.Equal(t, []string{
+		"struct Config",
+		"impl Display for Config",
+		"inner: const LIMIT",
+		"inner: impl Config",
+		"macro noop",
+	}, keys)
+
+	// The span of an item starts at its attributes.
+	assert.Equal(t, strings.Index(src, "#[derive"), items[0].StartByte)
+	assert.Equal(t, 2, items[0].StartLine)
+	assert.Equal(t, strings.Index(src, "}\n\nimpl")+1, items[0].EndByte)
+}
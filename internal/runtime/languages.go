@@ -11,10 +11,13 @@ import (
 	"github.com/smacker/go-tree-sitter/golang"
 	"github.com/smacker/go-tree-sitter/java"
 	"github.com/smacker/go-tree-sitter/javascript"
+	"github.com/smacker/go-tree-sitter/kotlin"
 	"github.com/smacker/go-tree-sitter/php"
+	"github.com/smacker/go-tree-sitter/protobuf"
 	"github.com/smacker/go-tree-sitter/python"
 	"github.com/smacker/go-tree-sitter/ruby"
 	"github.com/smacker/go-tree-sitter/rust"
+	"github.com/smacker/go-tree-sitter/swift"
 	ts "github.com/smacker/go-tree-sitter/typescript/typescript"
 )
 
@@ -33,9 +36,13 @@ var extToLanguage = map[string]string{
 	".cc":  "cpp",
 	".cxx": "cpp",
 	".hpp": "cpp",
-	".java": "java",
-	".php":  "php",
-	".rb":   "ruby",
+	".java":  "java",
+	".kt":    "kotlin",
+	".kts":   "kotlin",
+	".php":   "php",
+	".proto": "protobuf",
+	".rb":    "ruby",
+	".swift": "swift",
 }
 
 // langToGrammar maps language names to tree-sitter Language objects.
@@ -56,8 +63,11 @@ func initGrammars() {
 			"c":          c.GetLanguage(),
 			"cpp":        cpp.GetLanguage(),
 			"java":       java.GetLanguage(),
+			"kotlin":     kotlin.GetLanguage(),
 			"php":        php.GetLanguage(),
+			"protobuf":   protobuf.GetLanguage(),
 			"ruby":       ruby.GetLanguage(),
+			"swift":      swift.GetLanguage(),
 		}
 	})
 }
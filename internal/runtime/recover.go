@@ -0,0 +1,155 @@
+package runtime
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// maxRecoveryPasses bounds how many top-level syntax errors are skipped in a
+// single file.
+const maxRecoveryPasses = 32
+
+// ErrorSpan is the byte range of a syntax error in a source file.
+type ErrorSpan struct {
+	StartByte int
+	EndByte   int
+}
+
+// parseRecovering parses src and recovers from syntax errors at item
+// boundaries. When the parser leaves an ERROR node at the top level, the
+// node's text up to the next line starting in column 0 (where the next item
+// begins) is blanked out and the source is parsed again, so one broken item
+// cannot swallow the items after it. Blanking keeps newlines and byte
+// lengths, so positions in the recovered tree match src. A pass is kept only
+// if it loses no error-free top-level items.
+//
+// Returns the final tree, the source it was parsed from, and the skipped
+// spans. src itself is never modified.
+func parseRecovering(ctx context.Context, parser *sitter.Parser, src []byte) (*sitter.Tree, []byte, []ErrorSpan, error) {
+	tree, err := parser.ParseCtx(ctx, nil, src)
+	if err != nil {
+		return nil, nil, nil, err
+	}
+
+	var skipped []ErrorSpan
+	recovered := src
+	for pass := 0; pass < maxRecoveryPasses && tree.RootNode().HasError(); pass++ {
+		span, ok := topLevelErrorSpan(tree.RootNode(), recovered)
+		if !ok {
+			break
+		}
+		candidate := bytes.Clone(recovered)
+		for i := span.StartByte; i < span.EndByte; i++ {
+			if candidate[i] != '\n' {
+				candidate[i] = ' '
+			}
+		}
+		next, err := parser.ParseCtx(ctx, nil, candidate)
+		if err != nil {
+			tree.Close()
+			return nil, nil, nil, err
+		}
+		if cleanItems(next.RootNode()) < cleanItems(tree.RootNode()) {
+			next.Close()
+			break
+		}
+		tree.Close()
+		tree, recovered = next, candidate
+		skipped = append(skipped, span)
+	}
+	return tree, recovered, skipped, nil
+}
+
+// topLevelErrorSpan returns the span to skip for the first top-level ERROR
+// node under root: from its start to its end or the next item boundary,
+// whichever comes first.
+func topLevelErrorSpan(root *sitter.Node, src []byte) (ErrorSpan, bool) {
+	nodes := []*sitter.Node{root}
+	if root.Type() != "ERROR" {
+		nodes = nodes[:0]
+		for i := 0; i < int(root.ChildCount()); i++ {
+			nodes = append(nodes, root.Child(i))
+		}
+	}
+	for _, n := range nodes {
+		if n.Type() != "ERROR" {
+			continue
+		}
+		start := int(n.StartByte())
+		end := min(int(n.EndByte()), nextItemBoundary(src, start))
+		if end > start {
+			return ErrorSpan{StartByte: start, EndByte: end}, true
+		}
+	}
+	return ErrorSpan{}, false
+}
+
+// nextItemBoundary returns the offset of the first line after offset that
+// starts in column 0 with something other than whitespace or a closing
+// bracket, or len(src) if there is none.
+func nextItemBoundary(src []byte, offset int) int {
+	for i := offset; i < len(src)-1; i++ {
+		if src[i] != '\n' {
+			continue
+		}
+		switch src[i+1] {
+		case ' ', '\t', '\r', '\n', '}', ')', ']':
+		default:
+			return i + 1
+		}
+	}
+	return len(src)
+}
+
+// cleanItems counts the top-level nodes under root that contain no errors.
+func cleanItems(root *sitter.Node) int {
+	if root.Type() == "ERROR" {
+		return 0
+	}
+	n := 0
+	for i := 0; i < int(root.NamedChildCount()); i++ {
+		if !root.NamedChild(i).HasError() {
+			n++
+		}
+	}
+	return n
+}
+
+// SyntaxErrors parses src as lang, with the same recovery as the parse host
+// function, and returns the spans of its syntax errors: the spans skipped
+// during recovery plus the ERROR and MISSING nodes left in the final tree.
+// Returns nil for a file without errors.
+func SyntaxErrors(ctx context.Context, lang string, src []byte) ([]ErrorSpan, error) {
+	grammar, ok := ParserForLanguage(lang)
+	if !ok {
+		return nil, fmt.Errorf("syntax errors: unsupported language %q", lang)
+	}
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(grammar)
+
+	tree, _, spans, err := parseRecovering(ctx, parser, src)
+	if err != nil {
+		return nil, fmt.Errorf("syntax errors: %w", err)
+	}
+	defer tree.Close()
+
+	var walk func(n *sitter.Node)
+	walk = func(n *sitter.Node) {
+		if n.Type() == "ERROR" || n.IsMissing() {
+			spans = append(spans, ErrorSpan{StartByte: int(n.StartByte()), EndByte: int(n.EndByte())})
+			return
+		}
+		if !n.HasError() {
+			return
+		}
+		for i := 0; i < int(n.ChildCount()); i++ {
+			walk(n.Child(i))
+		}
+	}
+	walk(tree.RootNode())
+	return spans, nil
+}
@@ -0,0 +1,58 @@
+package runtime
+
+import (
+	"context"
+	"testing"
+
+	sitter "github.com/smacker/go-tree-sitter"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestNextItemBoundary(t *testing.T) {
+	src := []byte("func A( {\n\tx := 1\n}\n\nfunc B() {}\n")
+	assert.Equal(t, 21, nextItemBoundary(src, 0), "skips indented lines and closing brackets")
+	assert.Equal(t, len(src), nextItemBoundary(src, 21))
+}
+
+func TestSyntaxErrors_CleanSource(t *testing.T) {
+	spans, err := SyntaxErrors(context.Background(), "go", []byte(goTestSource))
+	require.NoError(t, err)
+	assert.Empty(t, spans)
+}
+
+func TestSyntaxErrors_BrokenItem(t *testing.T) {
+	src := "package main\n\nfunc Good() {}\n\nfunc Broken() {\n\tx := \n}\n\nfunc After() {}\n"
+	spans, err := SyntaxErrors(context.Background(), "go", []byte(src))
+	require.NoError(t, err)
+	require.NotEmpty(t, spans)
+	for _, span := range spans {
+		assert.GreaterOrEqual(t, span.StartByte, len("package main\n\nfunc Good() {}\n\n"), "error before Broken")
+		assert.LessOrEqual(t, span.EndByte, len(src)-len("\nfunc After() {}\n"), "error after Broken")
+	}
+}
+
+func TestParseRecovering_KeepsItemsAfterError(t *testing.T) {
+	const src = "package main\n\nfunc Good() {}\n\n)))) not go ((((\n\nfunc After() {}\n"
+	lang, ok := ParserForLanguage("go")
+	require.True(t, ok)
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(lang)
+
+	input := []byte(src)
+	tree, recovered, _, err := parseRecovering(context.Background(), parser, input)
+	require.NoError(t, err)
+	defer tree.Close()
+	assert.Equal(t, src, string(input), "input is not modified")
+	assert.Len(t, recovered, len(src), "positions are preserved")
+
+	var funcs []string
+	root := tree.RootNode()
+	for i := 0; i < int(root.NamedChildCount()); i++ {
+		if child := root.NamedChild(i); child.Type() == "function_declaration" {
+			funcs = append(funcs, child.ChildByFieldName("name").Content(recovered))
+		}
+	}
+	assert.Equal(t, []string{"Good", "After"}, funcs)
+}
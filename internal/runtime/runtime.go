@@ -22,6 +22,7 @@ type Runtime struct {
 	scriptsDir string
 	fsys       fs.FS
 	sources    *sourceStore
+	readFile   func(path string) ([]byte, error)
 }
 
 // RuntimeOption configures a Runtime.
@@ -36,6 +37,15 @@ func WithRuntimeFS(fsys fs.FS) RuntimeOption {
 	}
 }
 
+// WithRuntimeReadFile overrides how the parse host function reads source
+// files, e.g. to serve unsaved editor buffers instead of disk contents.
+// Defaults to os.ReadFile.
+func WithRuntimeReadFile(readFile func(path string) ([]byte, error)) RuntimeOption {
+	return func(r *Runtime) {
+		r.readFile = readFile
+	}
+}
+
 // NewRuntime creates a Runtime wired to the given DataStore and scripts directory.
 // Accepts optional RuntimeOptions for configuration such as fs.FS-based script loading.
 func NewRuntime(s store.DataStore, scriptsDir string, opts ...RuntimeOption) *Runtime {
@@ -43,6 +53,7 @@ func NewRuntime(s store.DataStore, scriptsDir string, opts ...RuntimeOption) *Ru
 		store:      s,
 		scriptsDir: scriptsDir,
 		sources:    newSourceStore(),
+		readFile:   os.ReadFile,
 	}
 	for _, opt := range opts {
 		opt(r)
@@ -165,12 +176,13 @@ func ResolutionScriptPath(language string) string {
 // underlying store is a real Store.
 func (r *Runtime) buildGlobals(extra map[string]any) map[string]any {
 	globals := map[string]any{
-		"parse":      makeParseFn(r.sources),
-		"parse_src":  makeParseSrcFn(r.sources),
-		"node_text":  makeNodeTextFn(r.sources),
-		"node_child": makeNodeChildFn(),
-		"query":      makeQueryFn(r.sources),
-		"log":        mustProxy(&logObject{prefix: "canopy"}),
+		"parse":       makeParseFn(r.sources, r.readFile),
+		"parse_src":   makeParseSrcFn(r.sources),
+		"read_source": makeReadSourceFn(r.readFile),
+		"node_text":   makeNodeTextFn(r.sources),
+		"node_child":  makeNodeChildFn(),
+		"query":       makeQueryFn(r.sources),
+		"log":         mustProxy(&logObject{prefix: "canopy"}),
 	}
 
 	// Expose extraction globals — these work with any DataStore.
@@ -183,7 +195,10 @@ func (r *Runtime) buildGlobals(extra map[string]any) map[string]any {
 		globals["insert_type_member"] = makeInsertTypeMemberFn(r.store)
 		globals["insert_function_param"] = makeInsertFunctionParamFn(r.store)
 		globals["insert_type_param"] = makeInsertTypeParamFn(r.store)
+		globals["insert_type_bound"] = makeInsertTypeBoundFn(r.store)
 		globals["insert_annotation"] = makeInsertAnnotationFn(r.store)
+		globals["insert_function_metrics"] = makeInsertFunctionMetricsFn(r.store)
+		globals["insert_flagged_site"] = makeInsertFlaggedSiteFn(r.store)
 
 		// Extraction query functions
 		globals["symbols_by_name"] = makeSymbolsByNameFn(r.store)
@@ -199,6 +214,7 @@ func (r *Runtime) buildGlobals(extra map[string]any) map[string]any {
 			globals["insert_implementation"] = makeInsertImplementationFn(realStore)
 			globals["insert_call_edge"] = makeInsertCallEdgeFn(realStore)
 			globals["insert_extension_binding"] = makeInsertExtensionBindingFn(realStore)
+			globals["insert_type_composition"] = makeInsertTypeCompositionFn(realStore)
 
 			// Resolution query functions
 			globals["references_by_file"] = makeReferencesByFileFn(realStore)
@@ -84,8 +84,12 @@ func TestLanguageForFile(t *testing.T) {
 		{"main.cxx", "cpp", true},
 		{"util.hpp", "cpp", true},
 		{"App.java", "java", true},
+		{"App.kt", "kotlin", true},
+		{"build.gradle.kts", "kotlin", true},
 		{"index.php", "php", true},
+		{"api/v1/user.proto", "protobuf", true},
 		{"app.rb", "ruby", true},
+		{"App.swift", "swift", true},
 		{"file.txt", "", false},
 		{"Makefile", "", false},
 		{"path/to/file.GO", "go", true}, // case insensitive
@@ -108,7 +112,7 @@ func TestLanguageForFile(t *testing.T) {
 func TestParserForLanguage(t *testing.T) {
 	t.Parallel()
 
-	supported := []string{"go", "typescript", "javascript", "python", "rust", "c", "cpp", "java", "php", "ruby"}
+	supported := []string{"go", "typescript", "javascript", "python", "rust", "c", "cpp", "java", "kotlin", "php", "protobuf", "ruby", "swift"}
 	for _, lang := range supported {
 		t.Run(lang, func(t *testing.T) {
 			t.Parallel()
@@ -28,11 +28,13 @@ func makeInsertSymbolFn(s store.DataStore) *object.Builtin {
 		sym := &store.Symbol{
 			Name:       getString(m, "name"),
 			Kind:       getString(m, "kind"),
+			Subkind:    getString(m, "subkind"),
 			Visibility: getString(m, "visibility"),
 			StartLine:  getInt(m, "start_line"),
 			StartCol:   getInt(m, "start_col"),
 			EndLine:    getInt(m, "end_line"),
 			EndCol:     getInt(m, "end_col"),
+			Modifiers:  getStringList(m, "modifiers"),
 		}
 		if v, ok := getOptionalInt64(m, "file_id"); ok {
 			sym.FileID = &v
@@ -104,6 +106,10 @@ func makeInsertReferenceFn(s store.DataStore) *object.Builtin {
 		if v, ok := getOptionalInt64(m, "scope_id"); ok {
 			ref.ScopeID = &v
 		}
+		ref.ReceiverType = getString(m, "receiver_type")
+		ref.Awaited = getBool(m, "awaited")
+		ref.Spawned = getBool(m, "spawned")
+		ref.Propagated = getBool(m, "propagated")
 
 		id, insertErr := s.InsertReference(ref)
 		if insertErr != nil {
@@ -226,6 +232,114 @@ func makeInsertTypeParamFn(s store.DataStore) *object.Builtin {
 	})
 }
 
+func makeInsertTypeBoundFn(s store.DataStore) *object.Builtin {
+	return object.NewBuiltin("insert_type_bound", func(ctx context.Context, args ...object.Object) object.Object {
+		if len(args) != 1 {
+			return object.NewArgsError("insert_type_bound", 1, len(args))
+		}
+		m, err := extractMap(args[0])
+		if err != nil {
+			return object.Errorf("insert_type_bound: %v", err)
+		}
+
+		bound := strings.TrimSpace(getString(m, "bound"))
+		tb := &store.TypeBound{
+			SymbolID:      getInt64(m, "symbol_id"),
+			Subject:       strings.TrimSpace(getString(m, "subject")),
+			Bound:         bound,
+			Name:          getStringDefault(m, "name", boundName(bound)),
+			InWhereClause: getBool(m, "in_where_clause"),
+		}
+		if tb.Subject == "" || tb.Bound == "" {
+			return object.Errorf("insert_type_bound: subject and bound are required")
+		}
+
+		id, insertErr := s.InsertTypeBound(tb)
+		if insertErr != nil {
+			return object.Errorf("insert_type_bound: %v", insertErr)
+		}
+		return object.NewInt(id)
+	})
+}
+
+// boundName reduces a bound as written to the bare trait or interface name
+// used for filtering: "?Sized" -> "Sized", "std::fmt::Debug" -> "Debug",
+// "Iterator<Item = u8>" -> "Iterator", "for<'a> Fn(&'a str)" -> "Fn",
+// "~int" -> "int". Lifetimes are kept as written.
+func boundName(bound string) string {
+	name := strings.TrimSpace(bound)
+	if strings.HasPrefix(name, "for<") {
+		if i := strings.Index(name, ">"); i >= 0 {
+			name = strings.TrimSpace(name[i+1:])
+		}
+	}
+	name = strings.TrimLeft(name, "?~")
+	name = strings.TrimPrefix(name, "const ")
+	if i := strings.IndexAny(name, "<({[ "); i > 0 {
+		name = name[:i]
+	}
+	if i := strings.LastIndex(name, "::"); i >= 0 {
+		name = name[i+2:]
+	}
+	if i := strings.LastIndex(name, "."); i >= 0 && !strings.HasPrefix(name, "'") {
+		name = name[i+1:]
+	}
+	return name
+}
+
+func makeInsertFunctionMetricsFn(s store.DataStore) *object.Builtin {
+	return object.NewBuiltin("insert_function_metrics", func(ctx context.Context, args ...object.Object) object.Object {
+		if len(args) != 1 {
+			return object.NewArgsError("insert_function_metrics", 1, len(args))
+		}
+		m, err := extractMap(args[0])
+		if err != nil {
+			return object.Errorf("insert_function_metrics: %v", err)
+		}
+
+		fm := &store.FunctionMetrics{
+			SymbolID:   getInt64(m, "symbol_id"),
+			Cyclomatic: getInt(m, "cyclomatic"),
+			Cognitive:  getInt(m, "cognitive"),
+		}
+
+		id, insertErr := s.InsertFunctionMetrics(fm)
+		if insertErr != nil {
+			return object.Errorf("insert_function_metrics: %v", insertErr)
+		}
+		return object.NewInt(id)
+	})
+}
+
+func makeInsertFlaggedSiteFn(s store.DataStore) *object.Builtin {
+	return object.NewBuiltin("insert_flagged_site", func(ctx context.Context, args ...object.Object) object.Object {
+		if len(args) != 1 {
+			return object.NewArgsError("insert_flagged_site", 1, len(args))
+		}
+		m, err := extractMap(args[0])
+		if err != nil {
+			return object.Errorf("insert_flagged_site: %v", err)
+		}
+
+		site := &store.FlaggedSite{
+			SymbolID:  getInt64(m, "symbol_id"),
+			FileID:    getInt64(m, "file_id"),
+			Kind:      getString(m, "kind"),
+			Name:      getString(m, "name"),
+			StartLine: getInt(m, "start_line"),
+			StartCol:  getInt(m, "start_col"),
+			EndLine:   getInt(m, "end_line"),
+			EndCol:    getInt(m, "end_col"),
+		}
+
+		id, insertErr := s.InsertFlaggedSite(site)
+		if insertErr != nil {
+			return object.Errorf("insert_flagged_site: %v", insertErr)
+		}
+		return object.NewInt(id)
+	})
+}
+
 func makeInsertAnnotationFn(s store.DataStore) *object.Builtin {
 	return object.NewBuiltin("insert_annotation", func(ctx context.Context, args ...object.Object) object.Object {
 		if len(args) != 1 {
@@ -282,6 +396,9 @@ func makeUpdateAnnotationResolvedFn(s *store.Store) *object.Builtin {
 // parent lookups, e.g., linking methods to receiver types).
 func makeSymbolsByNameFn(s store.DataStore) *object.Builtin {
 	return object.NewBuiltin("symbols_by_name", func(ctx context.Context, args ...object.Object) object.Object {
+		if err := ctx.Err(); err != nil {
+			return object.Errorf("symbols_by_name: %v", err)
+		}
 		if len(args) != 1 {
 			return object.NewArgsError("symbols_by_name", 1, len(args))
 		}
@@ -340,6 +457,26 @@ func getString(m map[string]object.Object, key string) string {
 	return ""
 }
 
+// getStringList returns the strings in the list at key, skipping other
+// elements. Returns nil if the key is missing or not a list.
+func getStringList(m map[string]object.Object, key string) []string {
+	v, ok := m[key]
+	if !ok {
+		return nil
+	}
+	list, ok := v.(*object.List)
+	if !ok {
+		return nil
+	}
+	var out []string
+	for _, item := range list.Value() {
+		if s, ok := item.(*object.String); ok {
+			out = append(out, s.Value())
+		}
+	}
+	return out
+}
+
 func getStringDefault(m map[string]object.Object, key, def string) string {
 	v := getString(m, key)
 	if v == "" {
@@ -428,6 +565,9 @@ func toString(obj object.Object) (string, error) {
 
 func makeInsertResolvedReferenceFn(s *store.Store) *object.Builtin {
 	return object.NewBuiltin("insert_resolved_reference", func(ctx context.Context, args ...object.Object) object.Object {
+		if err := ctx.Err(); err != nil {
+			return object.Errorf("insert_resolved_reference: %v", err)
+		}
 		if len(args) != 1 {
 			return object.NewArgsError("insert_resolved_reference", 1, len(args))
 		}
@@ -484,6 +624,9 @@ func makeInsertImplementationFn(s *store.Store) *object.Builtin {
 
 func makeInsertCallEdgeFn(s *store.Store) *object.Builtin {
 	return object.NewBuiltin("insert_call_edge", func(ctx context.Context, args ...object.Object) object.Object {
+		if err := ctx.Err(); err != nil {
+			return object.Errorf("insert_call_edge: %v", err)
+		}
 		if len(args) != 1 {
 			return object.NewArgsError("insert_call_edge", 1, len(args))
 		}
@@ -497,10 +640,17 @@ func makeInsertCallEdgeFn(s *store.Store) *object.Builtin {
 			CalleeSymbolID: getInt64(m, "callee_symbol_id"),
 			Line:           getInt(m, "line"),
 			Col:            getInt(m, "col"),
+			Confidence:     getFloat(m, "confidence"),
+			Awaited:        getBool(m, "awaited"),
+			Kind:           getString(m, "kind"),
+			Propagated:     getBool(m, "propagated"),
 		}
 		if v, ok := getOptionalInt64(m, "file_id"); ok {
 			edge.FileID = &v
 		}
+		if edge.Confidence == 0 {
+			edge.Confidence = 1.0
+		}
 
 		id, insertErr := s.InsertCallEdge(edge)
 		if insertErr != nil {
@@ -539,6 +689,30 @@ func makeInsertExtensionBindingFn(s *store.Store) *object.Builtin {
 	})
 }
 
+func makeInsertTypeCompositionFn(s *store.Store) *object.Builtin {
+	return object.NewBuiltin("insert_type_composition", func(ctx context.Context, args ...object.Object) object.Object {
+		if len(args) != 1 {
+			return object.NewArgsError("insert_type_composition", 1, len(args))
+		}
+		m, err := extractMap(args[0])
+		if err != nil {
+			return object.Errorf("insert_type_composition: %v", err)
+		}
+
+		tc := &store.TypeComposition{
+			CompositeSymbolID: getInt64(m, "composite_symbol_id"),
+			ComponentSymbolID: getInt64(m, "component_symbol_id"),
+			CompositionKind:   getString(m, "composition_kind"),
+		}
+
+		id, insertErr := s.InsertTypeComposition(tc)
+		if insertErr != nil {
+			return object.Errorf("insert_type_composition: %v", insertErr)
+		}
+		return object.NewInt(id)
+	})
+}
+
 // --- Resolution query bridge functions ---
 
 func makeReferencesByFileFn(s *store.Store) *object.Builtin {
@@ -570,6 +744,18 @@ func makeReferencesByFileFn(s *store.Store) *object.Builtin {
 			if r.ScopeID != nil {
 				m["scope_id"] = object.NewInt(*r.ScopeID)
 			}
+			if r.ReceiverType != "" {
+				m["receiver_type"] = object.NewString(r.ReceiverType)
+			}
+			if r.Awaited {
+				m["awaited"] = object.NewBool(true)
+			}
+			if r.Spawned {
+				m["spawned"] = object.NewBool(true)
+			}
+			if r.Propagated {
+				m["propagated"] = object.NewBool(true)
+			}
 			results = append(results, object.NewMap(m))
 		}
 		if results == nil {
@@ -1015,6 +1201,7 @@ func symbolsToList(syms []*store.Symbol) object.Object {
 			"id":         object.NewInt(sym.ID),
 			"name":       object.NewString(sym.Name),
 			"kind":       object.NewString(sym.Kind),
+			"subkind":    object.NewString(sym.Subkind),
 			"visibility": object.NewString(sym.Visibility),
 			"start_line": object.NewInt(int64(sym.StartLine)),
 			"start_col":  object.NewInt(int64(sym.StartCol)),
@@ -1027,6 +1214,13 @@ func symbolsToList(syms []*store.Symbol) object.Object {
 		if sym.ParentSymbolID != nil {
 			m["parent_symbol_id"] = object.NewInt(*sym.ParentSymbolID)
 		}
+		if len(sym.Modifiers) > 0 {
+			mods := make([]object.Object, len(sym.Modifiers))
+			for i, mod := range sym.Modifiers {
+				mods[i] = object.NewString(mod)
+			}
+			m["modifiers"] = object.NewList(mods)
+		}
 		results = append(results, object.NewMap(m))
 	}
 	if results == nil {
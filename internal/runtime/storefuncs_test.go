@@ -0,0 +1,25 @@
+package runtime
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestBoundName(t *testing.T) {
+	t.Parallel()
+	for bound, want := range map[string]string{
+		"Processor":           "Processor",
+		"std::fmt::Debug":     "Debug",
+		"Iterator<Item = u8>": "Iterator",
+		"?Sized":              "Sized",
+		"for<'a> Fn(&'a str)": "Fn",
+		"'static":             "'static",
+		"~int":                "int",
+		"constraints.Ordered": "Ordered",
+		"Comparable<T>":       "Comparable",
+		" Send ":              "Send",
+	} {
+		assert.Equal(t, want, boundName(bound), bound)
+	}
+}
@@ -0,0 +1,313 @@
+package runtime
+
+import (
+	"context"
+	"fmt"
+	"regexp"
+	"strings"
+
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// metavarPattern matches the metavariables of a structural pattern: $NAME
+// matches one node, $$$NAME any run of sibling nodes (possibly none), and
+// $_ and a bare $$$ match without binding a name.
+var metavarPattern = regexp.MustCompile(`\$\$\$([A-Z_][A-Z0-9_]*)?|\$([A-Z_][A-Z0-9_]*)`)
+
+// placeholderPattern matches the placeholder text of a metavariable.
+var placeholderPattern = regexp.MustCompile(`^canopyMV(S?)_([A-Z_][A-Z0-9_]*)?$`)
+
+// Placeholders stand in for metavariables while a pattern is parsed; they
+// are identifiers in every supported grammar.
+const (
+	singlePlaceholder = "canopyMV_"
+	multiPlaceholder  = "canopyMVS_"
+)
+
+// patternContexts are the snippets a pattern is tried in, per language,
+// after the pattern on its own: %s is the pattern. A grammar whose top level
+// is declarations needs a function body to parse an expression.
+var patternContexts = map[string][]string{
+	"rust":       {"fn canopy_pattern() { %s; }", "fn canopy_pattern() { %s }"},
+	"go":         {"package p\nfunc _() { %s }", "package p\n%s"},
+	"java":       {"class C { void m() { %s; } }", "class C { %s }"},
+	"kotlin":     {"fun f() { %s }"},
+	"c":          {"void f() { %s; }", "void f() { %s }"},
+	"cpp":        {"void f() { %s; }", "void f() { %s }"},
+	"php":        {"<?php %s;", "<?php %s"},
+	"swift":      {"func f() { %s }"},
+	"javascript": {"%s;"},
+	"typescript": {"%s;"},
+}
+
+// StructuralPattern is a code pattern compiled for one language: a syntax
+// tree to match, with metavariables at some of its nodes.
+type StructuralPattern struct {
+	Lang     string
+	Metavars []string // names of the binding metavariables, in order of appearance
+	root     *patternNode
+}
+
+// patternNode is a node of a compiled pattern.
+type patternNode struct {
+	typ      string
+	text     string // leaves only
+	metavar  string // metavariable name, "_" if it does not bind; "" if not one
+	multi    bool   // a $$$ metavariable
+	children []*patternNode
+}
+
+// StructuralMatch is a node of a file that matches a pattern, with the
+// text each metavariable bound. Lines and columns are 0-based, columns in
+// bytes, and ends exclusive.
+type StructuralMatch struct {
+	StartByte, EndByte int
+	StartLine, StartCol int
+	EndLine, EndCol     int
+	Bindings            map[string]string
+}
+
+// CompilePattern parses pattern as code of lang, such as
+// "ProductService::new()" or "$X.unwrap()" for Rust. The pattern is tried
+// on its own, then as a statement or expression inside a function, and the
+// first parse without syntax errors is used; its matching node is the
+// innermost one spanning the whole pattern. Returns an error if the pattern
+// does not parse in lang.
+func CompilePattern(ctx context.Context, lang, pattern string) (*StructuralPattern, error) {
+	grammar, ok := ParserForLanguage(lang)
+	if !ok {
+		return nil, fmt.Errorf("compile pattern: unsupported language %q", lang)
+	}
+	pattern = strings.TrimSpace(pattern)
+	if pattern == "" {
+		return nil, fmt.Errorf("compile pattern: empty pattern")
+	}
+	var metavars []string
+	seen := map[string]bool{}
+	placeheld := metavarPattern.ReplaceAllStringFunc(pattern, func(m string) string {
+		sub := metavarPattern.FindStringSubmatch(m)
+		if strings.HasPrefix(m, "$$$") {
+			name := sub[1]
+			if name != "" && name != "_" && !seen[name] {
+				seen[name] = true
+				metavars = append(metavars, name)
+			}
+			return multiPlaceholder + name
+		}
+		if sub[2] != "_" && !seen[sub[2]] {
+			seen[sub[2]] = true
+			metavars = append(metavars, sub[2])
+		}
+		return singlePlaceholder + sub[2]
+	})
+
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(grammar)
+	for _, wrapper := range append([]string{"%s"}, patternContexts[lang]...) {
+		i := strings.Index(wrapper, "%s")
+		src := []byte(wrapper[:i] + placeheld + wrapper[i+2:])
+		tree, err := parser.ParseCtx(ctx, nil, src)
+		if err != nil {
+			return nil, fmt.Errorf("compile pattern: %w", err)
+		}
+		root := tree.RootNode()
+		var node *sitter.Node
+		if !root.HasError() {
+			node = spanningNode(root, uint32(i), uint32(i+len(placeheld)))
+		}
+		if node != nil {
+			p := &StructuralPattern{Lang: lang, Metavars: metavars, root: buildPatternNode(node, src)}
+			tree.Close()
+			return p, nil
+		}
+		tree.Close()
+	}
+	return nil, fmt.Errorf("compile pattern: %q does not parse as %s", pattern, lang)
+}
+
+// spanningNode returns the innermost node of root spanning exactly
+// [start, end), or nil.
+func spanningNode(root *sitter.Node, start, end uint32) *sitter.Node {
+	n := root
+	for {
+		var next *sitter.Node
+		for i := 0; i < int(n.NamedChildCount()); i++ {
+			c := n.NamedChild(i)
+			if c.StartByte() <= start && c.EndByte() >= end {
+				next = c
+				break
+			}
+		}
+		if next == nil {
+			break
+		}
+		n = next
+	}
+	if n.StartByte() != start || n.EndByte() != end {
+		return nil
+	}
+	return n
+}
+
+// buildPatternNode converts a parsed pattern node, recognizing the
+// placeholders of metavariables. Comments are dropped.
+func buildPatternNode(n *sitter.Node, src []byte) *patternNode {
+	text := n.Content(src)
+	if m := placeholderPattern.FindStringSubmatch(text); m != nil && (m[1] == "S" || m[2] != "") {
+		name := m[2]
+		if name == "" {
+			name = "_"
+		}
+		return &patternNode{typ: n.Type(), metavar: name, multi: m[1] == "S"}
+	}
+	p := &patternNode{typ: n.Type()}
+	if n.ChildCount() == 0 {
+		p.text = text
+		return p
+	}
+	for i := 0; i < int(n.ChildCount()); i++ {
+		c := n.Child(i)
+		if strings.Contains(c.Type(), "comment") {
+			continue
+		}
+		p.children = append(p.children, buildPatternNode(c, src))
+	}
+	return p
+}
+
+// Match parses src and returns the nodes matching the pattern, in source
+// order, outer matches before those nested in them. A node spanning the
+// same text as a match enclosing it is not reported again.
+func (p *StructuralPattern) Match(ctx context.Context, src []byte) ([]StructuralMatch, error) {
+	grammar, _ := ParserForLanguage(p.Lang)
+	parser := sitter.NewParser()
+	defer parser.Close()
+	parser.SetLanguage(grammar)
+	tree, err := parser.ParseCtx(ctx, nil, src)
+	if err != nil {
+		return nil, fmt.Errorf("structural match: %w", err)
+	}
+	defer tree.Close()
+
+	var matches []StructuralMatch
+	var walk func(n *sitter.Node)
+	walk = func(n *sitter.Node) {
+		if bindings, ok := matchNode(p.root, n, src, map[string]string{}); ok {
+			if k := len(matches); k == 0 || matches[k-1].StartByte != int(n.StartByte()) || matches[k-1].EndByte != int(n.EndByte()) {
+				start, end := n.StartPoint(), n.EndPoint()
+				matches = append(matches, StructuralMatch{
+					StartByte: int(n.StartByte()),
+					EndByte:   int(n.EndByte()),
+					StartLine: int(start.Row),
+					StartCol:  int(start.Column),
+					EndLine:   int(end.Row),
+					EndCol:    int(end.Column),
+					Bindings:  bindings,
+				})
+			}
+		}
+		for i := 0; i < int(n.NamedChildCount()); i++ {
+			walk(n.NamedChild(i))
+		}
+	}
+	walk(tree.RootNode())
+	return matches, nil
+}
+
+// matchNode matches pattern node p against n, extending bindings. It
+// returns the bindings of a match, which the caller's map is not changed by.
+func matchNode(p *patternNode, n *sitter.Node, src []byte, bindings map[string]string) (map[string]string, bool) {
+	if p.metavar != "" {
+		return bind(bindings, p.metavar, n.Content(src))
+	}
+	if p.typ != n.Type() {
+		return nil, false
+	}
+	if p.children == nil {
+		return bindings, n.ChildCount() == 0 && p.text == n.Content(src)
+	}
+	var children []*sitter.Node
+	for i := 0; i < int(n.ChildCount()); i++ {
+		if c := n.Child(i); !strings.Contains(c.Type(), "comment") {
+			children = append(children, c)
+		}
+	}
+	return matchChildren(p.children, children, src, bindings)
+}
+
+// matchChildren matches a run of pattern nodes against a run of sibling
+// nodes. A $$$ metavariable takes as few siblings as lets the rest match.
+func matchChildren(ps []*patternNode, ns []*sitter.Node, src []byte, bindings map[string]string) (map[string]string, bool) {
+	if len(ps) == 0 {
+		return bindings, len(ns) == 0
+	}
+	if p := ps[0]; p.multi {
+		for k := 0; k <= len(ns); k++ {
+			text := ""
+			if k > 0 {
+				text = string(src[ns[0].StartByte():ns[k-1].EndByte()])
+			}
+			if b, ok := bind(bindings, p.metavar, text); ok {
+				if b, ok := matchChildren(ps[1:], ns[k:], src, b); ok {
+					return b, true
+				}
+			}
+		}
+		return nil, false
+	}
+	if len(ns) == 0 {
+		return nil, false
+	}
+	b, ok := matchNode(ps[0], ns[0], src, bindings)
+	if !ok {
+		return nil, false
+	}
+	return matchChildren(ps[1:], ns[1:], src, b)
+}
+
+// bind returns bindings with name bound to text, or false if name is
+// already bound to other text. "_" binds anything without being recorded.
+func bind(bindings map[string]string, name, text string) (map[string]string, bool) {
+	if name == "_" {
+		return bindings, true
+	}
+	if prev, ok := bindings[name]; ok {
+		return bindings, prev == text
+	}
+	b := make(map[string]string, len(bindings)+1)
+	for k, v := range bindings {
+		b[k] = v
+	}
+	b[name] = text
+	return b, true
+}
+
+// Rewrite returns template with the metavariables of a match replaced by
+// the text they bound. Metavariables the match did not bind are replaced
+// by nothing.
+func Rewrite(template string, bindings map[string]string) string {
+	return metavarPattern.ReplaceAllStringFunc(template, func(m string) string {
+		sub := metavarPattern.FindStringSubmatch(m)
+		if strings.HasPrefix(m, "$$$") {
+			return bindings[sub[1]]
+		}
+		return bindings[sub[2]]
+	})
+}
+
+// TemplateMetavars returns the names of the metavariables used in a
+// rewrite template.
+func TemplateMetavars(template string) []string {
+	var names []string
+	for _, sub := range metavarPattern.FindAllStringSubmatch(template, -1) {
+		name := sub[2]
+		if strings.HasPrefix(sub[0], "$$$") {
+			name = sub[1]
+		}
+		if name != "" && name != "_" {
+			names = append(names, name)
+		}
+	}
+	return names
+}
@@ -0,0 +1,70 @@
+package runtime
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestStructuralPattern_Rust(t *testing.T) {
+	ctx := context.Background()
+	src := []byte(`fn main() {
+    let s = ProductService::new();
+    let p = s.repo.find(7).unwrap();
+    let q = other::new();
+    add(1, 2);
+    add();
+    if p == p && p == q {}
+    // ProductService::new() in a comment
+}
+`)
+	matches := func(pattern string) []StructuralMatch {
+		t.Helper()
+		p, err := CompilePattern(ctx, "rust", pattern)
+		require.NoError(t, err)
+		ms, err := p.Match(ctx, src)
+		require.NoError(t, err)
+		return ms
+	}
+
+	ms := matches("ProductService::new()")
+	require.Len(t, ms, 1, "the comment is not code")
+	assert.Equal(t, 1, ms[0].StartLine)
+	assert.Equal(t, 12, ms[0].StartCol)
+	assert.Equal(t, "ProductService::new()", string(src[ms[0].StartByte:ms[0].EndByte]))
+
+	ms = matches("$X.unwrap()")
+	require.Len(t, ms, 1)
+	assert.Equal(t, map[string]string{"X": "s.repo.find(7)"}, ms[0].Bindings)
+	assert.Equal(t, `s.repo.find(7).expect("product")`, Rewrite(`$X.expect("product")`, ms[0].Bindings))
+
+	ms = matches("add($$$ARGS)")
+	require.Len(t, ms, 2)
+	assert.Equal(t, "1, 2", ms[0].Bindings["ARGS"])
+	assert.Equal(t, "", ms[1].Bindings["ARGS"])
+
+	ms = matches("$A == $A")
+	require.Len(t, ms, 1, "a repeated metavariable matches the same text")
+	assert.Equal(t, "p", ms[0].Bindings["A"])
+
+	ms = matches("$_ == $_")
+	assert.Len(t, ms, 2)
+	assert.Empty(t, ms[0].Bindings)
+
+	_, err := CompilePattern(ctx, "rust", "fn (")
+	assert.Error(t, err)
+	assert.Equal(t, []string{"X", "ARGS"}, TemplateMetavars("$X.call($$$ARGS, $_)"))
+}
+
+func TestStructuralPattern_Go(t *testing.T) {
+	ctx := context.Background()
+	p, err := CompilePattern(ctx, "go", "fmt.Println($$$)")
+	require.NoError(t, err)
+	ms, err := p.Match(ctx, []byte("package main\n\nfunc main() {\n\tfmt.Println(\"a\", 1)\n\tfmt.Printf(\"b\")\n}\n"))
+	require.NoError(t, err)
+	require.Len(t, ms, 1)
+	assert.Equal(t, 3, ms[0].StartLine)
+	assert.Equal(t, 1, ms[0].StartCol)
+}
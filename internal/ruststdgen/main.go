@@ -0,0 +1,142 @@
+// Command ruststdgen builds stdlib/rust-std.idx.gz, the std, core, and alloc
+// stub index embedded by rust_std.go, from the curated item list in
+// stdlib/rust-std.txt. Run it through `go generate` in the repository root.
+//
+// Each line of the list is "<kind> <std path>", then the crates that define
+// the item and std re-exports. The index lists every std item, then the
+// same items under each defining crate's path, in list order, so members
+// still follow their type.
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"compress/gzip"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"slices"
+	"strings"
+)
+
+// indexHeader opens the generated index.
+const indexHeader = `# Rust standard library stub index: one "<kind> <path>" per line.
+# Kinds: module struct enum trait function method type_alias constant macro.
+# Members of a type follow it; "function" members are associated functions.
+`
+
+// kinds are the item kinds the index may hold.
+var kinds = map[string]bool{
+	"module": true, "struct": true, "enum": true, "trait": true, "function": true,
+	"method": true, "type_alias": true, "constant": true, "macro": true,
+}
+
+// definingCrates are the crates std re-exports items from.
+var definingCrates = []string{"core", "alloc"}
+
+func main() {
+	in := flag.String("in", "stdlib/rust-std.txt", "curated item list")
+	out := flag.String("out", "stdlib/rust-std.idx.gz", "index to write")
+	flag.Parse()
+
+	src, err := os.Open(*in)
+	if err != nil {
+		fail(err)
+	}
+	defer src.Close()
+	index, err := buildIndex(src)
+	if err != nil {
+		fail(fmt.Errorf("%s: %w", *in, err))
+	}
+	// The gzip header has no time, so the output is the same on every run.
+	var buf bytes.Buffer
+	zw, err := gzip.NewWriterLevel(&buf, gzip.BestCompression)
+	if err != nil {
+		fail(err)
+	}
+	if _, err := zw.Write(index); err != nil {
+		fail(err)
+	}
+	if err := zw.Close(); err != nil {
+		fail(err)
+	}
+	if err := os.WriteFile(*out, buf.Bytes(), 0o644); err != nil {
+		fail(err)
+	}
+}
+
+func fail(err error) {
+	fmt.Fprintln(os.Stderr, "ruststdgen:", err)
+	os.Exit(1)
+}
+
+// item is one line of the curated list.
+type item struct {
+	kind   string
+	path   string // under std
+	crates []string
+}
+
+// buildIndex reads the curated list from r and returns the uncompressed
+// index. It rejects unknown kinds and crates, duplicates, and items whose
+// parent is not listed before them.
+func buildIndex(r io.Reader) ([]byte, error) {
+	var items []item
+	seen := map[string][]string{} // listed path -> its crates
+	sc := bufio.NewScanner(r)
+	for line := 1; sc.Scan(); line++ {
+		text := strings.TrimSpace(sc.Text())
+		if text == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		fields := strings.Fields(text)
+		if len(fields) < 2 {
+			return nil, fmt.Errorf("line %d: expected \"<kind> <path> [<crate>...]\"", line)
+		}
+		it := item{kind: fields[0], path: fields[1], crates: fields[2:]}
+		if !kinds[it.kind] {
+			return nil, fmt.Errorf("line %d: unknown kind %q", line, it.kind)
+		}
+		if it.path != "std" && !strings.HasPrefix(it.path, "std::") {
+			return nil, fmt.Errorf("line %d: %s is not a std path", line, it.path)
+		}
+		if _, ok := seen[it.path]; ok {
+			return nil, fmt.Errorf("line %d: %s is listed twice", line, it.path)
+		}
+		parent := ""
+		if i := strings.LastIndex(it.path, "::"); i >= 0 {
+			parent = it.path[:i]
+			if _, ok := seen[parent]; !ok {
+				return nil, fmt.Errorf("line %d: parent of %s is not listed before it", line, it.path)
+			}
+		}
+		for _, c := range it.crates {
+			if !slices.Contains(definingCrates, c) {
+				return nil, fmt.Errorf("line %d: unknown crate %q", line, c)
+			}
+			if parent != "" && !slices.Contains(seen[parent], c) {
+				return nil, fmt.Errorf("line %d: %s is in %s but its parent is not", line, it.path, c)
+			}
+		}
+		seen[it.path] = it.crates
+		items = append(items, it)
+	}
+	if err := sc.Err(); err != nil {
+		return nil, err
+	}
+
+	var b strings.Builder
+	b.WriteString(indexHeader)
+	for _, it := range items {
+		fmt.Fprintf(&b, "%s %s\n", it.kind, it.path)
+	}
+	for _, it := range items {
+		for _, c := range definingCrates {
+			if slices.Contains(it.crates, c) {
+				fmt.Fprintf(&b, "%s %s%s\n", it.kind, c, strings.TrimPrefix(it.path, "std"))
+			}
+		}
+	}
+	return []byte(b.String()), nil
+}
@@ -0,0 +1,63 @@
+package main
+
+import (
+	"bytes"
+	"compress/gzip"
+	"io"
+	"os"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestBuildIndex_MatchesEmbeddedIndex(t *testing.T) {
+	src, err := os.Open("../../stdlib/rust-std.txt")
+	require.NoError(t, err)
+	defer src.Close()
+	index, err := buildIndex(src)
+	require.NoError(t, err)
+
+	gz, err := os.ReadFile("../../stdlib/rust-std.idx.gz")
+	require.NoError(t, err)
+	zr, err := gzip.NewReader(bytes.NewReader(gz))
+	require.NoError(t, err)
+	embedded, err := io.ReadAll(zr)
+	require.NoError(t, err)
+	assert.Equal(t, string(embedded), string(index), "stdlib/rust-std.idx.gz is stale; run go generate")
+}
+
+func TestBuildIndex(t *testing.T) {
+	index, err := buildIndex(strings.NewReader(`# comment
+module std core alloc
+module std::vec alloc
+struct std::vec::Vec alloc
+method std::vec::Vec::push alloc
+module std::fs
+`))
+	require.NoError(t, err)
+	assert.Equal(t, indexHeader+`module std
+module std::vec
+struct std::vec::Vec
+method std::vec::Vec::push
+module std::fs
+module core
+module alloc
+module alloc::vec
+struct alloc::vec::Vec
+method alloc::vec::Vec::push
+`, string(index))
+
+	for _, tt := range []struct{ src, msg string }{
+		{"module std\nclass std::Foo\n", "unknown kind"},
+		{"module core\n", "not a std path"},
+		{"module std\nmodule std\n", "listed twice"},
+		{"module std\nstruct std::vec::Vec\n", "parent of std::vec::Vec"},
+		{"module std\nmodule std::vec liballoc\n", "unknown crate"},
+		{"module std\nmodule std::vec\nstruct std::vec::Vec alloc\n", "its parent is not"},
+	} {
+		_, err := buildIndex(strings.NewReader(tt.src))
+		assert.ErrorContains(t, err, tt.msg, "src: %q", tt.src)
+	}
+}
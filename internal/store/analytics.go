@@ -0,0 +1,169 @@
+//go:build !canopy_noexport
+
+package store
+
+import (
+	"fmt"
+	"io"
+
+	"github.com/jward/canopy/internal/parquet"
+)
+
+// --- Analytics export ---
+
+// An analytics table is a flat, denormalized view of the index for data
+// warehouses: file paths are spelled out next to file ids, and every kind
+// of edge between symbols shares one table, so the graph joins with
+// incident or ownership data without knowing canopy's schema.
+type analyticsTable struct {
+	name    string
+	columns []parquet.Column
+	query   string
+}
+
+var analyticsTables = []analyticsTable{
+	{
+		name: "files",
+		columns: []parquet.Column{
+			{Name: "id", Type: parquet.Int64},
+			{Name: "path", Type: parquet.String},
+			{Name: "language", Type: parquet.String},
+			{Name: "root", Type: parquet.String, Optional: true},
+			{Name: "line_count", Type: parquet.Int64, Optional: true},
+			{Name: "hash", Type: parquet.String, Optional: true},
+		},
+		query: `SELECT id, path, language, root, line_count, hash FROM files ORDER BY id`,
+	},
+	{
+		name: "symbols",
+		columns: []parquet.Column{
+			{Name: "id", Type: parquet.Int64},
+			{Name: "name", Type: parquet.String},
+			{Name: "kind", Type: parquet.String},
+			{Name: "subkind", Type: parquet.String},
+			{Name: "visibility", Type: parquet.String, Optional: true},
+			{Name: "modifiers", Type: parquet.String, Optional: true},
+			{Name: "file_id", Type: parquet.Int64, Optional: true},
+			{Name: "file", Type: parquet.String, Optional: true},
+			{Name: "language", Type: parquet.String, Optional: true},
+			{Name: "start_line", Type: parquet.Int64, Optional: true},
+			{Name: "end_line", Type: parquet.Int64, Optional: true},
+			{Name: "parent_symbol_id", Type: parquet.Int64, Optional: true},
+			{Name: "pagerank", Type: parquet.Double},
+			{Name: "betweenness", Type: parquet.Double},
+		},
+		query: `SELECT s.id, s.name, s.kind, s.subkind, s.visibility, s.modifiers, s.file_id, f.path, f.language,
+			s.start_line, s.end_line, s.parent_symbol_id, s.pagerank, s.betweenness
+		 FROM symbols s LEFT JOIN files f ON f.id = s.file_id ORDER BY s.id`,
+	},
+	{
+		// A reference's source is the innermost symbol whose scope encloses
+		// it, if any.
+		name: "edges",
+		columns: []parquet.Column{
+			{Name: "kind", Type: parquet.String},
+			{Name: "subkind", Type: parquet.String, Optional: true},
+			{Name: "source_symbol_id", Type: parquet.Int64, Optional: true},
+			{Name: "target_symbol_id", Type: parquet.Int64},
+			{Name: "file_id", Type: parquet.Int64, Optional: true},
+			{Name: "file", Type: parquet.String, Optional: true},
+			{Name: "line", Type: parquet.Int64, Optional: true},
+			{Name: "confidence", Type: parquet.Double, Optional: true},
+		},
+		query: `WITH RECURSIVE owner(scope_id, next_id, symbol_id) AS (
+			SELECT id, parent_scope_id, symbol_id FROM scopes
+			UNION ALL
+			SELECT o.scope_id, s.parent_scope_id, s.symbol_id FROM owner o JOIN scopes s ON s.id = o.next_id
+			WHERE o.symbol_id IS NULL
+		 )
+		 SELECT 'call' AS kind, NULLIF(c.kind, '') AS subkind, c.caller_symbol_id AS source_symbol_id,
+			c.callee_symbol_id AS target_symbol_id, c.file_id, f.path AS file, c.line, c.confidence
+		 FROM call_graph c LEFT JOIN files f ON f.id = c.file_id
+		 UNION ALL
+		 SELECT 'reference', r.context, o.symbol_id, rr.target_symbol_id, r.file_id, f.path, r.start_line, rr.confidence
+		 FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 JOIN files f ON f.id = r.file_id
+		 LEFT JOIN owner o ON o.scope_id = r.scope_id AND o.symbol_id IS NOT NULL
+		 UNION ALL
+		 SELECT 'implements', i.kind, i.type_symbol_id, i.interface_symbol_id, i.file_id, f.path, NULL, NULL
+		 FROM implementations i LEFT JOIN files f ON f.id = i.file_id`,
+	},
+}
+
+// AnalyticsTables returns the names of the tables WriteParquet exports:
+// files, symbols, and edges (calls, resolved references, and
+// implementations, distinguished by kind).
+func AnalyticsTables() []string {
+	names := make([]string, len(analyticsTables))
+	for i, t := range analyticsTables {
+		names[i] = t.name
+	}
+	return names
+}
+
+// WriteParquet writes the analytics table called name to w as a Parquet
+// file and returns the number of rows written. Positions are 0-based, as
+// everywhere in the index.
+func (s *Store) WriteParquet(name string, w io.Writer) (int64, error) {
+	var table *analyticsTable
+	for i := range analyticsTables {
+		if analyticsTables[i].name == name {
+			table = &analyticsTables[i]
+		}
+	}
+	if table == nil {
+		return 0, fmt.Errorf("write parquet: unknown table %q", name)
+	}
+
+	rows, err := s.db.Query(table.query)
+	if err != nil {
+		return 0, fmt.Errorf("write parquet: %s: %w", name, err)
+	}
+	defer rows.Close()
+
+	pw := parquet.NewWriter(w, table.columns)
+	values := make([]any, len(table.columns))
+	ptrs := make([]any, len(values))
+	for i := range values {
+		ptrs[i] = &values[i]
+	}
+	var n int64
+	for rows.Next() {
+		if err := rows.Scan(ptrs...); err != nil {
+			return 0, fmt.Errorf("write parquet: %s: %w", name, err)
+		}
+		for i, c := range table.columns {
+			values[i] = parquetValue(c.Type, values[i])
+		}
+		if err := pw.Write(values); err != nil {
+			return 0, fmt.Errorf("write parquet: %s: %w", name, err)
+		}
+		n++
+	}
+	if err := rows.Err(); err != nil {
+		return 0, fmt.Errorf("write parquet: %s: %w", name, err)
+	}
+	if err := pw.Close(); err != nil {
+		return 0, fmt.Errorf("write parquet: %s: %w", name, err)
+	}
+	return n, nil
+}
+
+// parquetValue converts a value scanned from SQLite to the Go type of a
+// Parquet column of type typ. SQLite's dynamic typing means a REAL column
+// can hold integers and text can arrive as bytes.
+func parquetValue(typ parquet.Type, v any) any {
+	switch v := v.(type) {
+	case []byte:
+		return string(v)
+	case int64:
+		switch typ {
+		case parquet.Double:
+			return float64(v)
+		case parquet.Boolean:
+			return v != 0
+		}
+	}
+	return v
+}
@@ -21,7 +21,10 @@ type BatchedStore struct {
 	TypeMembers     []TypeMember
 	FunctionParams  []FunctionParam
 	TypeParams      []TypeParam
+	TypeBounds      []TypeBound
 	Annotations     []Annotation
+	FunctionMetrics []FunctionMetrics
+	FlaggedSites    []FlaggedSite
 	SymbolFragments []SymbolFragment
 
 	nextFakeID int64 // starts at -1, decrements
@@ -107,6 +110,15 @@ func (b *BatchedStore) InsertTypeParam(tp *TypeParam) (int64, error) {
 	return fakeID, nil
 }
 
+func (b *BatchedStore) InsertTypeBound(tb *TypeBound) (int64, error) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	fakeID := b.allocFakeID()
+	tb.ID = fakeID
+	b.TypeBounds = append(b.TypeBounds, *tb)
+	return fakeID, nil
+}
+
 func (b *BatchedStore) InsertAnnotation(ann *Annotation) (int64, error) {
 	b.mu.Lock()
 	defer b.mu.Unlock()
@@ -116,6 +128,24 @@ func (b *BatchedStore) InsertAnnotation(ann *Annotation) (int64, error) {
 	return fakeID, nil
 }
 
+func (b *BatchedStore) InsertFunctionMetrics(fm *FunctionMetrics) (int64, error) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	fakeID := b.allocFakeID()
+	fm.ID = fakeID
+	b.FunctionMetrics = append(b.FunctionMetrics, *fm)
+	return fakeID, nil
+}
+
+func (b *BatchedStore) InsertFlaggedSite(site *FlaggedSite) (int64, error) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	fakeID := b.allocFakeID()
+	site.ID = fakeID
+	b.FlaggedSites = append(b.FlaggedSites, *site)
+	return fakeID, nil
+}
+
 func (b *BatchedStore) InsertSymbolFragment(frag *SymbolFragment) (int64, error) {
 	b.mu.Lock()
 	defer b.mu.Unlock()
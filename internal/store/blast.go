@@ -47,7 +47,8 @@ func (s *Store) FilesImportingSource(source string) ([]int64, error) {
 }
 
 // DeleteResolutionDataForSymbols removes all resolution data targeting the given symbols:
-// resolved_references, call_graph, implementations, extension_bindings, reexports, type_compositions.
+// resolved_references, call_graph, implementations, extension_bindings, reexports, import_aliases,
+// type_compositions.
 func (s *Store) DeleteResolutionDataForSymbols(symbolIDs []int64) error {
 	if len(symbolIDs) == 0 {
 		return nil
@@ -70,6 +71,7 @@ func (s *Store) DeleteResolutionDataForSymbols(symbolIDs []int64) error {
 		{"DELETE FROM implementations WHERE type_symbol_id IN (" + placeholders + ") OR interface_symbol_id IN (" + placeholders + ")", repeatArgs(args, 2)},
 		{"DELETE FROM extension_bindings WHERE member_symbol_id IN (" + placeholders + ") OR extended_type_symbol_id IN (" + placeholders + ")", repeatArgs(args, 2)},
 		{"DELETE FROM reexports WHERE original_symbol_id IN (" + placeholders + ")", args},
+		{"DELETE FROM import_aliases WHERE target_symbol_id IN (" + placeholders + ")", args},
 		{"DELETE FROM type_compositions WHERE composite_symbol_id IN (" + placeholders + ") OR component_symbol_id IN (" + placeholders + ")", repeatArgs(args, 2)},
 	}
 
@@ -84,7 +86,7 @@ func (s *Store) DeleteResolutionDataForSymbols(symbolIDs []int64) error {
 
 // DeleteResolutionDataForFiles removes all resolution data originating from the given files.
 // This means: resolved_references whose reference comes from those files, call_graph/implementations
-// with file_id in the set, reexports with file_id in the set, and any extension_bindings/type_compositions
+// with file_id in the set, reexports and import_aliases with file_id in the set, and any extension_bindings/type_compositions
 // whose member/composite symbol belongs to those files.
 func (s *Store) DeleteResolutionDataForFiles(fileIDs []int64) error {
 	if len(fileIDs) == 0 {
@@ -122,6 +124,11 @@ func (s *Store) DeleteResolutionDataForFiles(fileIDs []int64) error {
 		return fmt.Errorf("delete reexports for files: %w", err)
 	}
 
+	// Delete import aliases declared in these files.
+	if _, err := tx.Exec("DELETE FROM import_aliases WHERE file_id IN ("+placeholders+")", args...); err != nil {
+		return fmt.Errorf("delete import aliases for files: %w", err)
+	}
+
 	// Delete extension_bindings whose member symbol belongs to these files.
 	if _, err := tx.Exec(
 		`DELETE FROM extension_bindings WHERE member_symbol_id IN (
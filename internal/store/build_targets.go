@@ -0,0 +1,127 @@
+package store
+
+import "fmt"
+
+// --- Build graph overlay ---
+
+// ReplaceBuildGraph replaces the build targets of root with targets and sets
+// the targets of each file in fileTargets, replacing any previous ones. A nil
+// or empty slice clears a file's targets. Runs in one transaction.
+func (s *Store) ReplaceBuildGraph(root string, targets []*BuildTarget, fileTargets map[int64][]string) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	for _, q := range []string{
+		"DELETE FROM build_targets WHERE root = ?",
+		"DELETE FROM build_target_deps WHERE root = ?",
+	} {
+		if _, err := tx.Exec(q, root); err != nil {
+			return fmt.Errorf("clear build graph: %w", err)
+		}
+	}
+
+	insTarget, err := tx.Prepare("INSERT OR REPLACE INTO build_targets (root, label, kind) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare insert build target: %w", err)
+	}
+	defer insTarget.Close()
+	insDep, err := tx.Prepare("INSERT INTO build_target_deps (root, label, dep) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare insert build dep: %w", err)
+	}
+	defer insDep.Close()
+	for _, t := range targets {
+		if _, err := insTarget.Exec(root, t.Label, t.Kind); err != nil {
+			return fmt.Errorf("insert build target %s: %w", t.Label, err)
+		}
+		for _, dep := range t.Deps {
+			if _, err := insDep.Exec(root, t.Label, dep); err != nil {
+				return fmt.Errorf("insert dep of %s: %w", t.Label, err)
+			}
+		}
+	}
+
+	del, err := tx.Prepare("DELETE FROM file_build_targets WHERE file_id = ?")
+	if err != nil {
+		return fmt.Errorf("prepare delete file targets: %w", err)
+	}
+	defer del.Close()
+	ins, err := tx.Prepare("INSERT INTO file_build_targets (file_id, root, label) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare insert file target: %w", err)
+	}
+	defer ins.Close()
+	for fileID, labels := range fileTargets {
+		if _, err := del.Exec(fileID); err != nil {
+			return fmt.Errorf("delete targets of file %d: %w", fileID, err)
+		}
+		for _, label := range labels {
+			if _, err := ins.Exec(fileID, root, label); err != nil {
+				return fmt.Errorf("insert target of file %d: %w", fileID, err)
+			}
+		}
+	}
+	return tx.Commit()
+}
+
+// FileBuildTargets returns the labels of the build targets a file is a source
+// of, sorted. Returns nil if no target lists the file.
+func (s *Store) FileBuildTargets(fileID int64) ([]string, error) {
+	rows, err := s.db.Query("SELECT label FROM file_build_targets WHERE file_id = ? ORDER BY label", fileID)
+	if err != nil {
+		return nil, fmt.Errorf("file build targets: %w", err)
+	}
+	defer rows.Close()
+	var labels []string
+	for rows.Next() {
+		var label string
+		if err := rows.Scan(&label); err != nil {
+			return nil, fmt.Errorf("scan build target: %w", err)
+		}
+		labels = append(labels, label)
+	}
+	return labels, rows.Err()
+}
+
+// BuildTargets returns every build target of every root, with its deps,
+// ordered by root and label.
+func (s *Store) BuildTargets() ([]*BuildTarget, error) {
+	rows, err := s.db.Query("SELECT root, label, kind FROM build_targets ORDER BY root, label")
+	if err != nil {
+		return nil, fmt.Errorf("build targets: %w", err)
+	}
+	var targets []*BuildTarget
+	byKey := map[[2]string]*BuildTarget{}
+	for rows.Next() {
+		t := &BuildTarget{}
+		if err := rows.Scan(&t.Root, &t.Label, &t.Kind); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("scan build target: %w", err)
+		}
+		targets = append(targets, t)
+		byKey[[2]string{t.Root, t.Label}] = t
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("build targets: %w", err)
+	}
+
+	rows, err = s.db.Query("SELECT root, label, dep FROM build_target_deps ORDER BY rowid")
+	if err != nil {
+		return nil, fmt.Errorf("build target deps: %w", err)
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var root, label, dep string
+		if err := rows.Scan(&root, &label, &dep); err != nil {
+			return nil, fmt.Errorf("scan build target dep: %w", err)
+		}
+		if t, ok := byKey[[2]string{root, label}]; ok {
+			t.Deps = append(t.Deps, dep)
+		}
+	}
+	return targets, rows.Err()
+}
@@ -0,0 +1,34 @@
+package store
+
+import "fmt"
+
+// --- Centrality ---
+
+// SymbolCentrality holds the centrality scores of one symbol.
+type SymbolCentrality struct {
+	ID          int64
+	PageRank    float64
+	Betweenness float64
+}
+
+// UpdateSymbolCentrality records centrality scores for the given symbols.
+// Runs in one transaction.
+func (s *Store) UpdateSymbolCentrality(scores []SymbolCentrality) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	stmt, err := tx.Prepare("UPDATE symbols SET pagerank = ?, betweenness = ? WHERE id = ?")
+	if err != nil {
+		return fmt.Errorf("prepare centrality update: %w", err)
+	}
+	defer stmt.Close()
+	for _, sc := range scores {
+		if _, err := stmt.Exec(sc.PageRank, sc.Betweenness, sc.ID); err != nil {
+			return fmt.Errorf("update centrality: %w", err)
+		}
+	}
+	return tx.Commit()
+}
@@ -0,0 +1,107 @@
+package store
+
+import (
+	"regexp"
+	"slices"
+	"strings"
+)
+
+// --- cfg predicates ---
+
+// cfgTokenRe matches the tokens of a cfg predicate: string literals, words,
+// and single punctuation characters. Whitespace between them is skipped.
+var cfgTokenRe = regexp.MustCompile(`"(?:[^"\\]|\\.)*"|[\w:]+|\S`)
+
+// CanonicalCfg returns a cfg predicate, such as all(unix, feature = "serde"),
+// in a canonical form, so equivalent predicates compare equal: whitespace is
+// normalized, the predicates under all(...) and any(...) are sorted with
+// duplicates dropped, and all(p) and any(p) become p. A predicate that does
+// not parse is returned with its runs of whitespace collapsed.
+func CanonicalCfg(pred string) string {
+	p := &cfgParser{toks: cfgTokenRe.FindAllString(pred, -1)}
+	out, ok := p.predicate()
+	if !ok || p.pos != len(p.toks) {
+		return strings.Join(strings.Fields(pred), " ")
+	}
+	return out
+}
+
+// CfgIsTest reports whether the arguments of a cfg attribute, such as
+// "(test)", are a predicate equivalent to test: #[cfg( test )] and
+// #[cfg(any(test))] gate test code as #[cfg(test)] does.
+func CfgIsTest(args string) bool {
+	args = strings.TrimSpace(args)
+	if !strings.HasPrefix(args, "(") || !strings.HasSuffix(args, ")") {
+		return false
+	}
+	return CanonicalCfg(args[1:len(args)-1]) == "test"
+}
+
+// cfgParser parses the tokens of a cfg predicate.
+type cfgParser struct {
+	toks []string
+	pos  int
+}
+
+// peek returns the next token, or "" at the end.
+func (p *cfgParser) peek() string {
+	if p.pos < len(p.toks) {
+		return p.toks[p.pos]
+	}
+	return ""
+}
+
+// predicate parses one predicate, an option (test), a key-value pair
+// (feature = "serde"), or a combinator over predicates (all, any, not), and
+// returns its canonical form.
+func (p *cfgParser) predicate() (string, bool) {
+	name := p.peek()
+	if name == "" || !isCfgWord(name) {
+		return "", false
+	}
+	p.pos++
+	switch p.peek() {
+	case "=":
+		p.pos++
+		value := p.peek()
+		if !strings.HasPrefix(value, `"`) {
+			return "", false
+		}
+		p.pos++
+		return name + " = " + value, true
+	case "(":
+		p.pos++
+		var args []string
+		for p.peek() != ")" {
+			arg, ok := p.predicate()
+			if !ok {
+				return "", false
+			}
+			args = append(args, arg)
+			switch p.peek() {
+			case ",":
+				p.pos++
+			case ")":
+			default:
+				return "", false
+			}
+		}
+		p.pos++
+		if name == "all" || name == "any" {
+			slices.Sort(args)
+			args = slices.Compact(args)
+			if len(args) == 1 {
+				return args[0], true
+			}
+		}
+		return name + "(" + strings.Join(args, ", ") + ")", true
+	}
+	return name, true
+}
+
+// isCfgWord reports whether tok is a word rather than a string literal or
+// punctuation.
+func isCfgWord(tok string) bool {
+	c := tok[0]
+	return c == '_' || c == ':' || c >= '0' && c <= '9' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z'
+}
@@ -20,6 +20,9 @@ import (
 //  7. TypeParams (depend on symbol_id)
 //  8. Annotations (depend on symbol_id, file_id)
 //  9. SymbolFragments (depend on symbol_id, file_id)
+// 10. FunctionMetrics (depend on symbol_id)
+// 11. TypeBounds (depend on symbol_id)
+// 12. FlaggedSites (depend on symbol_id)
 func (s *Store) CommitBatch(batch *BatchedStore) error {
 	tx, err := s.db.Begin()
 	if err != nil {
@@ -157,6 +160,42 @@ func (s *Store) CommitBatch(batch *BatchedStore) error {
 		fakeToReal[sf.ID] = realID
 	}
 
+	// 10. FunctionMetrics
+	for _, fm := range batch.FunctionMetrics {
+		if fm.SymbolID < 0 {
+			fm.SymbolID = fakeToReal[fm.SymbolID]
+		}
+		realID, err := insertFunctionMetricsTx(tx, &fm)
+		if err != nil {
+			return fmt.Errorf("commit batch: function metrics: %w", err)
+		}
+		fakeToReal[fm.ID] = realID
+	}
+
+	// 11. TypeBounds
+	for _, tb := range batch.TypeBounds {
+		if tb.SymbolID < 0 {
+			tb.SymbolID = fakeToReal[tb.SymbolID]
+		}
+		realID, err := insertTypeBoundTx(tx, &tb)
+		if err != nil {
+			return fmt.Errorf("commit batch: type bound %q: %w", tb.Bound, err)
+		}
+		fakeToReal[tb.ID] = realID
+	}
+
+	// 12. FlaggedSites
+	for _, site := range batch.FlaggedSites {
+		if site.SymbolID < 0 {
+			site.SymbolID = fakeToReal[site.SymbolID]
+		}
+		realID, err := insertFlaggedSiteTx(tx, &site)
+		if err != nil {
+			return fmt.Errorf("commit batch: flagged site %q: %w", site.Name, err)
+		}
+		fakeToReal[site.ID] = realID
+	}
+
 	return tx.Commit()
 }
 
@@ -167,10 +206,12 @@ func insertSymbolTx(tx *sql.Tx, sym *Symbol) (int64, error) {
 	mods := marshalModifiers(sym.Modifiers)
 	res, err := tx.Exec(
 		`INSERT INTO symbols (file_id, name, kind, visibility, modifiers, signature_hash,
-			start_line, start_col, end_line, end_col, parent_symbol_id)
-		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+			start_line, start_col, end_line, end_col, parent_symbol_id,
+			start_byte, end_byte, utf16_start_col, utf16_end_col, subkind)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
 		sym.FileID, sym.Name, sym.Kind, sym.Visibility, mods, sym.SignatureHash,
 		sym.StartLine, sym.StartCol, sym.EndLine, sym.EndCol, sym.ParentSymbolID,
+		sym.StartByte, sym.EndByte, sym.UTF16StartCol, sym.UTF16EndCol, sym.Subkind,
 	)
 	if err != nil {
 		return 0, err
@@ -193,10 +234,12 @@ func insertScopeTx(tx *sql.Tx, scope *Scope) (int64, error) {
 
 func insertReferenceTx(tx *sql.Tx, ref *Reference) (int64, error) {
 	res, err := tx.Exec(
-		`INSERT INTO references_ (file_id, scope_id, name, start_line, start_col, end_line, end_col, context)
-		 VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+		`INSERT INTO references_ (file_id, scope_id, name, start_line, start_col, end_line, end_col, context,
+			start_byte, end_byte, utf16_start_col, utf16_end_col, receiver_type, awaited, spawned, propagated)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
 		ref.FileID, ref.ScopeID, ref.Name,
 		ref.StartLine, ref.StartCol, ref.EndLine, ref.EndCol, ref.Context,
+		ref.StartByte, ref.EndByte, ref.UTF16StartCol, ref.UTF16EndCol, ref.ReceiverType, ref.Awaited, ref.Spawned, ref.Propagated,
 	)
 	if err != nil {
 		return 0, err
@@ -253,6 +296,18 @@ func insertTypeParamTx(tx *sql.Tx, tp *TypeParam) (int64, error) {
 	return res.LastInsertId()
 }
 
+func insertTypeBoundTx(tx *sql.Tx, tb *TypeBound) (int64, error) {
+	res, err := tx.Exec(
+		`INSERT INTO type_bounds (symbol_id, subject, bound, name, in_where_clause)
+		 VALUES (?, ?, ?, ?, ?)`,
+		tb.SymbolID, tb.Subject, tb.Bound, tb.Name, tb.InWhereClause,
+	)
+	if err != nil {
+		return 0, err
+	}
+	return res.LastInsertId()
+}
+
 func insertAnnotationTx(tx *sql.Tx, ann *Annotation) (int64, error) {
 	res, err := tx.Exec(
 		`INSERT INTO annotations (target_symbol_id, name, resolved_symbol_id, arguments, file_id, line, col)
@@ -278,3 +333,28 @@ func insertSymbolFragmentTx(tx *sql.Tx, frag *SymbolFragment) (int64, error) {
 	}
 	return res.LastInsertId()
 }
+
+func insertFlaggedSiteTx(tx *sql.Tx, site *FlaggedSite) (int64, error) {
+	res, err := tx.Exec(
+		`INSERT INTO flagged_sites (symbol_id, file_id, kind, name, start_line, start_col, end_line, end_col)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+		site.SymbolID, site.FileID, site.Kind, site.Name,
+		site.StartLine, site.StartCol, site.EndLine, site.EndCol,
+	)
+	if err != nil {
+		return 0, err
+	}
+	return res.LastInsertId()
+}
+
+func insertFunctionMetricsTx(tx *sql.Tx, fm *FunctionMetrics) (int64, error) {
+	res, err := tx.Exec(
+		`INSERT INTO function_metrics (symbol_id, cyclomatic, cognitive)
+		 VALUES (?, ?, ?)`,
+		fm.SymbolID, fm.Cyclomatic, fm.Cognitive,
+	)
+	if err != nil {
+		return 0, err
+	}
+	return res.LastInsertId()
+}
@@ -0,0 +1,30 @@
+package store
+
+import "fmt"
+
+// --- Coverage overlay ---
+
+// ReplaceSymbolCoverage replaces the coverage of all symbols with coverage.
+// Runs in one transaction.
+func (s *Store) ReplaceSymbolCoverage(coverage []*SymbolCoverage) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.Exec("DELETE FROM symbol_coverage"); err != nil {
+		return fmt.Errorf("delete symbol coverage: %w", err)
+	}
+	ins, err := tx.Prepare("INSERT INTO symbol_coverage (symbol_id, lines, covered_lines) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare symbol coverage: %w", err)
+	}
+	defer ins.Close()
+	for _, c := range coverage {
+		if _, err := ins.Exec(c.SymbolID, c.Lines, c.CoveredLines); err != nil {
+			return fmt.Errorf("insert symbol coverage: %w", err)
+		}
+	}
+	return tx.Commit()
+}
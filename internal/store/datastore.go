@@ -12,7 +12,10 @@ type DataStore interface {
 	InsertTypeMember(tm *TypeMember) (int64, error)
 	InsertFunctionParam(fp *FunctionParam) (int64, error)
 	InsertTypeParam(tp *TypeParam) (int64, error)
+	InsertTypeBound(tb *TypeBound) (int64, error)
 	InsertAnnotation(ann *Annotation) (int64, error)
+	InsertFunctionMetrics(fm *FunctionMetrics) (int64, error)
+	InsertFlaggedSite(site *FlaggedSite) (int64, error)
 	InsertSymbolFragment(frag *SymbolFragment) (int64, error)
 
 	// Queries needed by extraction scripts for cross-file lookups.
@@ -0,0 +1,77 @@
+package store
+
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+)
+
+// --- Symbol embeddings ---
+
+// SymbolEmbeddings returns the embeddings stored for model, ordered by
+// symbol ID.
+func (s *Store) SymbolEmbeddings(model string) ([]*SymbolEmbedding, error) {
+	rows, err := s.db.Query(
+		"SELECT symbol_id, model, text_hash, vector FROM symbol_embeddings WHERE model = ? ORDER BY symbol_id",
+		model,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("query symbol embeddings: %w", err)
+	}
+	defer rows.Close()
+
+	var out []*SymbolEmbedding
+	for rows.Next() {
+		var e SymbolEmbedding
+		var blob []byte
+		if err := rows.Scan(&e.SymbolID, &e.Model, &e.TextHash, &blob); err != nil {
+			return nil, fmt.Errorf("scan symbol embedding: %w", err)
+		}
+		e.Vector = decodeVector(blob)
+		out = append(out, &e)
+	}
+	return out, rows.Err()
+}
+
+// ReplaceSymbolEmbeddings replaces all embeddings stored for model with
+// embeddings, leaving those of other models alone. Runs in one transaction.
+func (s *Store) ReplaceSymbolEmbeddings(model string, embeddings []*SymbolEmbedding) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.Exec("DELETE FROM symbol_embeddings WHERE model = ?", model); err != nil {
+		return fmt.Errorf("delete symbol embeddings: %w", err)
+	}
+	ins, err := tx.Prepare("INSERT INTO symbol_embeddings (symbol_id, model, text_hash, vector) VALUES (?, ?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare symbol embeddings: %w", err)
+	}
+	defer ins.Close()
+	for _, e := range embeddings {
+		if _, err := ins.Exec(e.SymbolID, model, e.TextHash, encodeVector(e.Vector)); err != nil {
+			return fmt.Errorf("insert symbol embedding: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// encodeVector packs v as little-endian float32s.
+func encodeVector(v []float32) []byte {
+	buf := make([]byte, 4*len(v))
+	for i, x := range v {
+		binary.LittleEndian.PutUint32(buf[4*i:], math.Float32bits(x))
+	}
+	return buf
+}
+
+// decodeVector unpacks a vector written by encodeVector.
+func decodeVector(buf []byte) []float32 {
+	v := make([]float32, len(buf)/4)
+	for i := range v {
+		v[i] = math.Float32frombits(binary.LittleEndian.Uint32(buf[4*i:]))
+	}
+	return v
+}
@@ -0,0 +1,24 @@
+//go:build canopy_noexport
+
+package store
+
+import (
+	"errors"
+	"io"
+)
+
+// ErrExportDisabled is returned by the Parquet and Neo4j exporters when
+// canopy is built with the canopy_noexport tag, which leaves them out.
+var ErrExportDisabled = errors.New("exporters not built in (built with -tags canopy_noexport)")
+
+// AnalyticsTables returns no tables: the Parquet exporter is not built in.
+func AnalyticsTables() []string { return nil }
+
+// WriteParquet returns ErrExportDisabled.
+func (s *Store) WriteParquet(name string, w io.Writer) (int64, error) { return 0, ErrExportDisabled }
+
+// Neo4jFiles returns no files: the Neo4j exporter is not built in.
+func Neo4jFiles() []string { return nil }
+
+// WriteNeo4j returns ErrExportDisabled.
+func (s *Store) WriteNeo4j(name string, w io.Writer) (int64, error) { return 0, ErrExportDisabled }
@@ -0,0 +1,93 @@
+//go:build !canopy_noexport
+
+package store
+
+import (
+	"bytes"
+	"fmt"
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWriteParquet(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/repo/lib.rs", "rust")
+	shape := insertTestSymbol(t, s, &f.ID, "Shape", "trait")
+	circle := insertTestSymbol(t, s, &f.ID, "Circle", "struct")
+	area := insertTestSymbol(t, s, &f.ID, "area", "function")
+	fnScope, err := s.InsertScope(&Scope{FileID: f.ID, SymbolID: &area.ID, Kind: "function"})
+	require.NoError(t, err)
+	block, err := s.InsertScope(&Scope{FileID: f.ID, Kind: "block", ParentScopeID: &fnScope})
+	require.NoError(t, err)
+	refID, err := s.InsertReference(&Reference{FileID: f.ID, ScopeID: &block, Name: "Circle", StartLine: 4})
+	require.NoError(t, err)
+	_, err = s.InsertResolvedReference(&ResolvedReference{ReferenceID: refID, TargetSymbolID: circle.ID, Confidence: 1})
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&CallEdge{CallerSymbolID: area.ID, CalleeSymbolID: circle.ID, FileID: &f.ID, Line: 4, Confidence: 1})
+	require.NoError(t, err)
+	_, err = s.InsertImplementation(&Implementation{TypeSymbolID: circle.ID, InterfaceSymbolID: shape.ID, Kind: "explicit"})
+	require.NoError(t, err)
+
+	// A reference's source is the symbol of the nearest enclosing scope
+	// that has one.
+	var source int64
+	require.NoError(t, s.db.QueryRow(
+		"SELECT source_symbol_id FROM ("+analyticsTables[2].query+") WHERE kind = 'reference'",
+	).Scan(&source))
+	assert.Equal(t, area.ID, source)
+
+	rows := map[string]int64{}
+	for _, table := range AnalyticsTables() {
+		var buf bytes.Buffer
+		n, err := s.WriteParquet(table, &buf)
+		require.NoError(t, err, table)
+		rows[table] = n
+		assert.True(t, bytes.HasPrefix(buf.Bytes(), []byte("PAR1")), table)
+		assert.True(t, bytes.HasSuffix(buf.Bytes(), []byte("PAR1")), table)
+	}
+	assert.Equal(t, map[string]int64{"files": 1, "symbols": 3, "edges": 3}, rows)
+
+	_, err = s.WriteParquet("scopes", io.Discard)
+	assert.ErrorContains(t, err, "unknown table")
+}
+
+func TestWriteNeo4j(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/repo/lib.rs", "rust")
+	alias := insertTestSymbol(t, s, &f.ID, "Id", "type_alias")
+	widget := insertTestSymbol(t, s, &f.ID, "Widget", "struct")
+	method := &Symbol{FileID: &f.ID, Name: "new", Kind: "method", ParentSymbolID: &widget.ID}
+	_, err := s.InsertSymbol(method)
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&CallEdge{CallerSymbolID: method.ID, CalleeSymbolID: alias.ID, FileID: &f.ID, Line: 3, Confidence: 0.9})
+	require.NoError(t, err)
+
+	write := func(name string) []string {
+		t.Helper()
+		var buf bytes.Buffer
+		_, err := s.WriteNeo4j(name, &buf)
+		require.NoError(t, err)
+		return strings.Split(strings.TrimSpace(buf.String()), "\n")
+	}
+	symbols := write("symbols.csv")
+	require.Len(t, symbols, 4)
+	assert.Equal(t, "symbolId:ID(Symbol),name,kind,subkind,visibility,file,startLine:int,endLine:int,pagerank:double,:LABEL", symbols[0])
+	assert.True(t, strings.HasSuffix(symbols[1], ",Symbol;TypeAlias"), symbols[1])
+
+	rels := write("relationships.csv")
+	assert.ElementsMatch(t, []string{
+		fmt.Sprintf("%d,%d,CALLS,,/repo/lib.rs,3,0.9", method.ID, alias.ID),
+		fmt.Sprintf("%d,%d,CONTAINS,,,,", widget.ID, method.ID),
+	}, rels[1:])
+	assert.Len(t, write("defined_in.csv"), 4)
+	assert.Len(t, write("files.csv"), 2)
+
+	_, err = s.WriteNeo4j("scopes.csv", io.Discard)
+	assert.ErrorContains(t, err, "unknown file")
+}
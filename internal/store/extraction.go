@@ -3,14 +3,15 @@ package store
 import (
 	"database/sql"
 	"fmt"
+	"strings"
 )
 
 // --- File operations ---
 
 func (s *Store) InsertFile(f *File) (int64, error) {
 	res, err := s.db.Exec(
-		"INSERT INTO files (path, language, hash, line_count, last_indexed) VALUES (?, ?, ?, ?, ?)",
-		f.Path, f.Language, f.Hash, f.LineCount, f.LastIndexed,
+		"INSERT INTO files (path, language, hash, line_count, last_indexed, root) VALUES (?, ?, ?, ?, ?, ?)",
+		f.Path, f.Language, f.Hash, f.LineCount, f.LastIndexed, f.Root,
 	)
 	if err != nil {
 		return 0, fmt.Errorf("insert file: %w", err)
@@ -26,8 +27,8 @@ func (s *Store) InsertFile(f *File) (int64, error) {
 func (s *Store) FileByPath(path string) (*File, error) {
 	f := &File{}
 	err := s.db.QueryRow(
-		"SELECT id, path, language, hash, line_count, last_indexed FROM files WHERE path = ?", path,
-	).Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed)
+		"SELECT id, path, language, hash, line_count, last_indexed, COALESCE(root, '') FROM files WHERE path = ?", path,
+	).Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed, &f.Root)
 	if err == sql.ErrNoRows {
 		return nil, nil
 	}
@@ -39,7 +40,7 @@ func (s *Store) FileByPath(path string) (*File, error) {
 
 func (s *Store) FilesByLanguage(language string) ([]*File, error) {
 	rows, err := s.db.Query(
-		"SELECT id, path, language, hash, line_count, last_indexed FROM files WHERE language = ?", language,
+		"SELECT id, path, language, hash, line_count, last_indexed, COALESCE(root, '') FROM files WHERE language = ?", language,
 	)
 	if err != nil {
 		return nil, fmt.Errorf("files by language: %w", err)
@@ -48,7 +49,7 @@ func (s *Store) FilesByLanguage(language string) ([]*File, error) {
 	var files []*File
 	for rows.Next() {
 		f := &File{}
-		if err := rows.Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed); err != nil {
+		if err := rows.Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed, &f.Root); err != nil {
 			return nil, fmt.Errorf("scan file: %w", err)
 		}
 		files = append(files, f)
@@ -75,16 +76,32 @@ func (s *Store) AllFiles() (map[int64]string, error) {
 	return result, rows.Err()
 }
 
+// SetFileRoot records root as the provenance of every file whose path is
+// under root and that has no root yet. Backfills databases indexed before
+// roots were tracked without touching files claimed by a nested root.
+func (s *Store) SetFileRoot(root, pathPrefix string) error {
+	_, err := s.db.Exec(
+		`UPDATE files SET root = ? WHERE (root IS NULL OR root = '') AND substr(path, 1, ?) = ?`,
+		root, len(pathPrefix), pathPrefix,
+	)
+	if err != nil {
+		return fmt.Errorf("set file root: %w", err)
+	}
+	return nil
+}
+
 // --- Symbol operations ---
 
 func (s *Store) InsertSymbol(sym *Symbol) (int64, error) {
 	mods := marshalModifiers(sym.Modifiers)
 	res, err := s.db.Exec(
 		`INSERT INTO symbols (file_id, name, kind, visibility, modifiers, signature_hash,
-			start_line, start_col, end_line, end_col, parent_symbol_id)
-		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+			start_line, start_col, end_line, end_col, parent_symbol_id,
+			start_byte, end_byte, utf16_start_col, utf16_end_col, subkind)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
 		sym.FileID, sym.Name, sym.Kind, sym.Visibility, mods, sym.SignatureHash,
 		sym.StartLine, sym.StartCol, sym.EndLine, sym.EndCol, sym.ParentSymbolID,
+		sym.StartByte, sym.EndByte, sym.UTF16StartCol, sym.UTF16EndCol, sym.Subkind,
 	)
 	if err != nil {
 		return 0, fmt.Errorf("insert symbol: %w", err)
@@ -97,13 +114,22 @@ func (s *Store) InsertSymbol(sym *Symbol) (int64, error) {
 	return id, nil
 }
 
+// SetSymbolModifiers replaces the modifiers of a symbol.
+func (s *Store) SetSymbolModifiers(symbolID int64, mods []string) error {
+	if _, err := s.db.Exec("UPDATE symbols SET modifiers = ? WHERE id = ?", marshalModifiers(mods), symbolID); err != nil {
+		return fmt.Errorf("set symbol modifiers: %w", err)
+	}
+	return nil
+}
+
 func (s *Store) scanSymbol(scanner interface{ Scan(...any) error }) (*Symbol, error) {
 	sym := &Symbol{}
 	var mods string
 	err := scanner.Scan(
 		&sym.ID, &sym.FileID, &sym.Name, &sym.Kind, &sym.Visibility, &mods,
 		&sym.SignatureHash, &sym.StartLine, &sym.StartCol, &sym.EndLine, &sym.EndCol,
-		&sym.ParentSymbolID,
+		&sym.ParentSymbolID, &sym.StartByte, &sym.EndByte, &sym.UTF16StartCol, &sym.UTF16EndCol,
+		&sym.PageRank, &sym.Betweenness, &sym.Subkind,
 	)
 	if err != nil {
 		return nil, err
@@ -119,7 +145,8 @@ func (s *Store) ScanSymbolRow(scanner interface{ Scan(...any) error }) (*Symbol,
 
 // SymbolCols is the column list for symbol queries, exported for use by QueryBuilder.
 const SymbolCols = `id, file_id, name, kind, visibility, modifiers, signature_hash,
-	start_line, start_col, end_line, end_col, parent_symbol_id`
+	start_line, start_col, end_line, end_col, parent_symbol_id,
+	start_byte, end_byte, utf16_start_col, utf16_end_col, pagerank, betweenness, subkind`
 
 func (s *Store) querySymbols(query string, args ...any) ([]*Symbol, error) {
 	rows, err := s.db.Query(query, args...)
@@ -159,6 +186,27 @@ func (s *Store) SymbolsByName(name string) ([]*Symbol, error) {
 	return s.querySymbols("SELECT "+SymbolCols+" FROM symbols WHERE name = ?", name)
 }
 
+// SymbolNamesWithPrefix returns up to limit distinct symbol names starting
+// with prefix (case-sensitive), in order, for completing names as they are
+// typed.
+func (s *Store) SymbolNamesWithPrefix(prefix string, limit int) ([]string, error) {
+	pattern := strings.NewReplacer("[", "[[]", "*", "[*]", "?", "[?]").Replace(prefix) + "*"
+	rows, err := s.db.Query("SELECT DISTINCT name FROM symbols WHERE name GLOB ? ORDER BY name LIMIT ?", pattern, limit)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var names []string
+	for rows.Next() {
+		var name string
+		if err := rows.Scan(&name); err != nil {
+			return nil, err
+		}
+		names = append(names, name)
+	}
+	return names, rows.Err()
+}
+
 func (s *Store) SymbolsByKind(kind string) ([]*Symbol, error) {
 	return s.querySymbols("SELECT "+SymbolCols+" FROM symbols WHERE kind = ?", kind)
 }
@@ -265,10 +313,12 @@ func (s *Store) ScopeAt(fileID int64, line, col int) (*Scope, error) {
 
 func (s *Store) InsertReference(ref *Reference) (int64, error) {
 	res, err := s.db.Exec(
-		`INSERT INTO references_ (file_id, scope_id, name, start_line, start_col, end_line, end_col, context)
-		 VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+		`INSERT INTO references_ (file_id, scope_id, name, start_line, start_col, end_line, end_col, context,
+			start_byte, end_byte, utf16_start_col, utf16_end_col, receiver_type, awaited, spawned, propagated)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
 		ref.FileID, ref.ScopeID, ref.Name,
 		ref.StartLine, ref.StartCol, ref.EndLine, ref.EndCol, ref.Context,
+		ref.StartByte, ref.EndByte, ref.UTF16StartCol, ref.UTF16EndCol, ref.ReceiverType, ref.Awaited, ref.Spawned, ref.Propagated,
 	)
 	if err != nil {
 		return 0, fmt.Errorf("insert reference: %w", err)
@@ -281,15 +331,36 @@ func (s *Store) InsertReference(ref *Reference) (int64, error) {
 	return id, nil
 }
 
+// DeleteReferencesByContext deletes the references recorded with the given
+// context in every file, with their resolutions.
+func (s *Store) DeleteReferencesByContext(context string) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+	if _, err := tx.Exec(
+		"DELETE FROM resolved_references WHERE reference_id IN (SELECT id FROM references_ WHERE context = ?)", context,
+	); err != nil {
+		return fmt.Errorf("delete resolved references by context: %w", err)
+	}
+	if _, err := tx.Exec("DELETE FROM references_ WHERE context = ?", context); err != nil {
+		return fmt.Errorf("delete references by context: %w", err)
+	}
+	return tx.Commit()
+}
+
 func (s *Store) scanReference(scanner interface{ Scan(...any) error }) (*Reference, error) {
 	r := &Reference{}
 	return r, scanner.Scan(
 		&r.ID, &r.FileID, &r.ScopeID, &r.Name,
 		&r.StartLine, &r.StartCol, &r.EndLine, &r.EndCol, &r.Context,
+		&r.StartByte, &r.EndByte, &r.UTF16StartCol, &r.UTF16EndCol, &r.ReceiverType, &r.Awaited, &r.Spawned, &r.Propagated,
 	)
 }
 
-const refCols = `id, file_id, scope_id, name, start_line, start_col, end_line, end_col, context`
+const refCols = `id, file_id, scope_id, name, start_line, start_col, end_line, end_col, context,
+	start_byte, end_byte, utf16_start_col, utf16_end_col, receiver_type, awaited, spawned, propagated`
 
 func (s *Store) queryReferences(query string, args ...any) ([]*Reference, error) {
 	rows, err := s.db.Query(query, args...)
@@ -503,6 +574,129 @@ func (s *Store) TypeParams(symbolID int64) ([]*TypeParam, error) {
 	return params, rows.Err()
 }
 
+// --- TypeBound operations ---
+
+func (s *Store) InsertTypeBound(tb *TypeBound) (int64, error) {
+	res, err := s.db.Exec(
+		`INSERT INTO type_bounds (symbol_id, subject, bound, name, in_where_clause)
+		 VALUES (?, ?, ?, ?, ?)`,
+		tb.SymbolID, tb.Subject, tb.Bound, tb.Name, tb.InWhereClause,
+	)
+	if err != nil {
+		return 0, fmt.Errorf("insert type bound: %w", err)
+	}
+	id, err := res.LastInsertId()
+	if err != nil {
+		return 0, fmt.Errorf("last insert id: %w", err)
+	}
+	tb.ID = id
+	return id, nil
+}
+
+func (s *Store) TypeBounds(symbolID int64) ([]*TypeBound, error) {
+	rows, err := s.db.Query(
+		`SELECT id, symbol_id, subject, bound, name, in_where_clause
+		 FROM type_bounds WHERE symbol_id = ? ORDER BY id`,
+		symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("type bounds: %w", err)
+	}
+	defer rows.Close()
+	var bounds []*TypeBound
+	for rows.Next() {
+		tb := &TypeBound{}
+		if err := rows.Scan(&tb.ID, &tb.SymbolID, &tb.Subject, &tb.Bound,
+			&tb.Name, &tb.InWhereClause); err != nil {
+			return nil, fmt.Errorf("scan type bound: %w", err)
+		}
+		bounds = append(bounds, tb)
+	}
+	return bounds, rows.Err()
+}
+
+// --- FunctionMetrics operations ---
+
+func (s *Store) InsertFunctionMetrics(fm *FunctionMetrics) (int64, error) {
+	res, err := s.db.Exec(
+		`INSERT INTO function_metrics (symbol_id, cyclomatic, cognitive)
+		 VALUES (?, ?, ?)`,
+		fm.SymbolID, fm.Cyclomatic, fm.Cognitive,
+	)
+	if err != nil {
+		return 0, fmt.Errorf("insert function metrics: %w", err)
+	}
+	id, err := res.LastInsertId()
+	if err != nil {
+		return 0, fmt.Errorf("last insert id: %w", err)
+	}
+	fm.ID = id
+	return id, nil
+}
+
+// FunctionMetricsBySymbol returns the complexity metrics for a function or
+// method symbol. Returns nil with no error if none were recorded.
+func (s *Store) FunctionMetricsBySymbol(symbolID int64) (*FunctionMetrics, error) {
+	fm := &FunctionMetrics{}
+	err := s.db.QueryRow(
+		`SELECT id, symbol_id, cyclomatic, cognitive
+		 FROM function_metrics WHERE symbol_id = ?`,
+		symbolID,
+	).Scan(&fm.ID, &fm.SymbolID, &fm.Cyclomatic, &fm.Cognitive)
+	if err == sql.ErrNoRows {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("function metrics by symbol: %w", err)
+	}
+	return fm, nil
+}
+
+// --- FlaggedSite operations ---
+
+func (s *Store) InsertFlaggedSite(site *FlaggedSite) (int64, error) {
+	res, err := s.db.Exec(
+		`INSERT INTO flagged_sites (symbol_id, file_id, kind, name, start_line, start_col, end_line, end_col)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?)`,
+		site.SymbolID, site.FileID, site.Kind, site.Name,
+		site.StartLine, site.StartCol, site.EndLine, site.EndCol,
+	)
+	if err != nil {
+		return 0, fmt.Errorf("insert flagged site: %w", err)
+	}
+	id, err := res.LastInsertId()
+	if err != nil {
+		return 0, fmt.Errorf("last insert id: %w", err)
+	}
+	site.ID = id
+	return id, nil
+}
+
+// FlaggedSitesBySymbol returns the flagged sites inside a function or method,
+// in source order.
+func (s *Store) FlaggedSitesBySymbol(symbolID int64) ([]*FlaggedSite, error) {
+	rows, err := s.db.Query(
+		`SELECT id, symbol_id, file_id, kind, name, start_line, start_col, end_line, end_col
+		 FROM flagged_sites WHERE symbol_id = ? ORDER BY start_line, start_col`,
+		symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("flagged sites by symbol: %w", err)
+	}
+	defer rows.Close()
+
+	var sites []*FlaggedSite
+	for rows.Next() {
+		site := &FlaggedSite{}
+		if err := rows.Scan(&site.ID, &site.SymbolID, &site.FileID, &site.Kind, &site.Name,
+			&site.StartLine, &site.StartCol, &site.EndLine, &site.EndCol); err != nil {
+			return nil, fmt.Errorf("scan flagged site: %w", err)
+		}
+		sites = append(sites, site)
+	}
+	return sites, rows.Err()
+}
+
 // --- Annotation operations ---
 
 func (s *Store) InsertAnnotation(ann *Annotation) (int64, error) {
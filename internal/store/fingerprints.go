@@ -0,0 +1,84 @@
+package store
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// --- Function fingerprints ---
+
+// InsertFunctionFingerprints records the body fingerprints of functions.
+// Runs in one transaction.
+func (s *Store) InsertFunctionFingerprints(fps []FunctionFingerprint) error {
+	if len(fps) == 0 {
+		return nil
+	}
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	stmt, err := tx.Prepare("INSERT INTO function_fingerprints (symbol_id, statements) VALUES (?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare fingerprint insert: %w", err)
+	}
+	defer stmt.Close()
+	for _, fp := range fps {
+		if _, err := stmt.Exec(fp.SymbolID, marshalStatementHashes(fp.Statements)); err != nil {
+			return fmt.Errorf("insert function fingerprint: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// FunctionFingerprints returns the fingerprints of all functions with at
+// least minStatements statements, ordered by symbol ID.
+func (s *Store) FunctionFingerprints(minStatements int) ([]*FunctionFingerprint, error) {
+	rows, err := s.db.Query("SELECT id, symbol_id, statements FROM function_fingerprints ORDER BY symbol_id")
+	if err != nil {
+		return nil, fmt.Errorf("function fingerprints: %w", err)
+	}
+	defer rows.Close()
+
+	var fps []*FunctionFingerprint
+	for rows.Next() {
+		fp := &FunctionFingerprint{}
+		var stmts string
+		if err := rows.Scan(&fp.ID, &fp.SymbolID, &stmts); err != nil {
+			return nil, fmt.Errorf("scan function fingerprint: %w", err)
+		}
+		fp.Statements, err = unmarshalStatementHashes(stmts)
+		if err != nil {
+			return nil, fmt.Errorf("function fingerprint %d: %w", fp.ID, err)
+		}
+		if len(fp.Statements) >= minStatements {
+			fps = append(fps, fp)
+		}
+	}
+	return fps, rows.Err()
+}
+
+// marshalStatementHashes encodes statement hashes as space-separated hex.
+func marshalStatementHashes(hashes []uint64) string {
+	parts := make([]string, len(hashes))
+	for i, h := range hashes {
+		parts[i] = strconv.FormatUint(h, 16)
+	}
+	return strings.Join(parts, " ")
+}
+
+// unmarshalStatementHashes decodes the output of marshalStatementHashes.
+func unmarshalStatementHashes(s string) ([]uint64, error) {
+	fields := strings.Fields(s)
+	hashes := make([]uint64, len(fields))
+	for i, f := range fields {
+		h, err := strconv.ParseUint(f, 16, 64)
+		if err != nil {
+			return nil, fmt.Errorf("invalid statement hash %q", f)
+		}
+		hashes[i] = h
+	}
+	return hashes, nil
+}
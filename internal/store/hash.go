@@ -9,7 +9,8 @@ import (
 
 // ComputeSignatureHash computes a deterministic hash from a symbol's semantic identity.
 // Covers: name, kind, visibility, modifiers, type_members, function_params, type_params.
-// Location changes do NOT affect the hash.
+// Location changes do NOT affect the hash, and neither do Rust lifetime
+// spellings that denote the same signature (see normalizeLifetimes).
 func ComputeSignatureHash(
 	name, kind, visibility string,
 	modifiers []string,
@@ -17,6 +18,7 @@ func ComputeSignatureHash(
 	params []*FunctionParam,
 	typeParams []*TypeParam,
 ) string {
+	members, params, typeParams = normalizeLifetimes(members, params, typeParams)
 	h := sha256.New()
 
 	// Core identity.
@@ -0,0 +1,87 @@
+package store
+
+import (
+	"database/sql"
+	"fmt"
+	"time"
+)
+
+// --- Git blame overlay ---
+
+// ReplaceFileBlame records the blame results of one file: lines per author
+// and the history of each of its symbols. Previous blame data of the file
+// and of its symbols is replaced. Runs in one transaction.
+func (s *Store) ReplaceFileBlame(fileID int64, authors []AuthorLines, history []*SymbolHistory) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.Exec("DELETE FROM file_authors WHERE file_id = ?", fileID); err != nil {
+		return fmt.Errorf("delete file authors: %w", err)
+	}
+	if _, err := tx.Exec(
+		"DELETE FROM symbol_history WHERE symbol_id IN (SELECT id FROM symbols WHERE file_id = ?)", fileID,
+	); err != nil {
+		return fmt.Errorf("delete symbol history: %w", err)
+	}
+
+	for _, a := range authors {
+		if _, err := tx.Exec(
+			"INSERT INTO file_authors (file_id, author, lines) VALUES (?, ?, ?)",
+			fileID, a.Author, a.Lines,
+		); err != nil {
+			return fmt.Errorf("insert file author: %w", err)
+		}
+	}
+	for _, h := range history {
+		if _, err := tx.Exec(
+			`INSERT INTO symbol_history (symbol_id, commit_hash, author, author_email, date, authors)
+			 VALUES (?, ?, ?, ?, ?, ?)`,
+			h.SymbolID, h.Commit, h.Author, h.AuthorEmail, h.Date.Unix(), h.Authors,
+		); err != nil {
+			return fmt.Errorf("insert symbol history: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// SymbolHistoryByID returns the blame history of a symbol, or nil if the
+// symbol has none.
+func (s *Store) SymbolHistoryByID(symbolID int64) (*SymbolHistory, error) {
+	h := &SymbolHistory{}
+	var email sql.NullString
+	var date int64
+	err := s.db.QueryRow(
+		`SELECT symbol_id, commit_hash, author, author_email, date, authors
+		 FROM symbol_history WHERE symbol_id = ?`, symbolID,
+	).Scan(&h.SymbolID, &h.Commit, &h.Author, &email, &date, &h.Authors)
+	if err == sql.ErrNoRows {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("symbol history: %w", err)
+	}
+	h.AuthorEmail = email.String
+	h.Date = time.Unix(date, 0).UTC()
+	return h, nil
+}
+
+// BlamedFileIDs returns the IDs of files that have blame data.
+func (s *Store) BlamedFileIDs() (map[int64]bool, error) {
+	rows, err := s.db.Query("SELECT DISTINCT file_id FROM file_authors")
+	if err != nil {
+		return nil, fmt.Errorf("blamed files: %w", err)
+	}
+	defer rows.Close()
+	ids := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, fmt.Errorf("scan blamed file: %w", err)
+		}
+		ids[id] = true
+	}
+	return ids, rows.Err()
+}
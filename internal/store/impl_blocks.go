@@ -0,0 +1,55 @@
+package store
+
+import "fmt"
+
+// --- Impl blocks ---
+
+// InsertImplBlocks records the impl blocks of files. Runs in one
+// transaction.
+func (s *Store) InsertImplBlocks(blocks []ImplBlock) error {
+	if len(blocks) == 0 {
+		return nil
+	}
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	stmt, err := tx.Prepare(`INSERT INTO impl_blocks (file_id, start_line, start_col, end_line, end_col,
+		start_byte, end_byte, utf16_start_col, utf16_end_col, header, trait, trait_start_byte, trait_end_byte)
+		VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`)
+	if err != nil {
+		return fmt.Errorf("prepare impl block insert: %w", err)
+	}
+	defer stmt.Close()
+	for _, b := range blocks {
+		if _, err := stmt.Exec(b.FileID, b.StartLine, b.StartCol, b.EndLine, b.EndCol,
+			b.StartByte, b.EndByte, b.UTF16StartCol, b.UTF16EndCol, b.Header, b.Trait, b.TraitStartByte, b.TraitEndByte); err != nil {
+			return fmt.Errorf("insert impl block: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// ImplBlocksByFile returns the impl blocks of a file, ordered by position.
+func (s *Store) ImplBlocksByFile(fileID int64) ([]*ImplBlock, error) {
+	rows, err := s.db.Query(`SELECT id, file_id, start_line, start_col, end_line, end_col,
+		start_byte, end_byte, utf16_start_col, utf16_end_col, header, trait, trait_start_byte, trait_end_byte
+		FROM impl_blocks WHERE file_id = ? ORDER BY start_line, start_col`, fileID)
+	if err != nil {
+		return nil, fmt.Errorf("impl blocks by file: %w", err)
+	}
+	defer rows.Close()
+
+	var blocks []*ImplBlock
+	for rows.Next() {
+		b := &ImplBlock{}
+		if err := rows.Scan(&b.ID, &b.FileID, &b.StartLine, &b.StartCol, &b.EndLine, &b.EndCol,
+			&b.StartByte, &b.EndByte, &b.UTF16StartCol, &b.UTF16EndCol, &b.Header, &b.Trait, &b.TraitStartByte, &b.TraitEndByte); err != nil {
+			return nil, fmt.Errorf("scan impl block: %w", err)
+		}
+		blocks = append(blocks, b)
+	}
+	return blocks, rows.Err()
+}
@@ -0,0 +1,320 @@
+package store
+
+import (
+	"database/sql"
+	"fmt"
+	"maps"
+	"slices"
+	"strings"
+)
+
+// --- Import ---
+
+// ImportFrom copies every file of src whose language is not in
+// skipLanguages into s, along with all the rows that hang off those files:
+// symbols, references, and the other extraction tables, and the resolution
+// data src computed for them. Copied rows get fresh ids and every id column
+// is remapped to them. A row pointing at a skipped file, or through a
+// required column at any other row that was not copied, is dropped; other
+// optional columns pointing at such rows are cleared. It returns the ids of
+// the copied files.
+func (s *Store) ImportFrom(src *Store, skipLanguages ...string) ([]int64, error) {
+	if len(skipLanguages) == 0 {
+		return s.importFiles(src, "", nil)
+	}
+	args := make([]any, len(skipLanguages))
+	for i, lang := range skipLanguages {
+		args[i] = lang
+	}
+	return s.importFiles(src, " WHERE language NOT IN ("+placeholderList(len(skipLanguages))+")", args)
+}
+
+// ImportFromExcept is ImportFrom copying every file of src except those in
+// skipFileIDs, which are src's ids.
+func (s *Store) ImportFromExcept(src *Store, skipFileIDs []int64) ([]int64, error) {
+	if len(skipFileIDs) == 0 {
+		return s.importFiles(src, "", nil)
+	}
+	return s.importFiles(src, " WHERE id NOT IN ("+placeholderList(len(skipFileIDs))+")", int64sToArgs(skipFileIDs))
+}
+
+// importFiles copies the files of src matching the where clause, and the
+// rows hanging off them.
+func (s *Store) importFiles(src *Store, where string, args []any) ([]int64, error) {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return nil, fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	im := newImporter(tx, src)
+	err = im.copyTables(func(table string) (string, []any) {
+		if table != "files" {
+			return "", nil
+		}
+		return where, args
+	})
+	if err != nil {
+		return nil, err
+	}
+	if err := tx.Commit(); err != nil {
+		return nil, fmt.Errorf("commit import: %w", err)
+	}
+	return slices.Sorted(maps.Values(im.ids["files"])), nil
+}
+
+// ImportFile copies file srcFileID of src into s, recording it as file
+// rather than as src recorded it, together with every row pointing at the
+// file directly or through its symbols, scopes, or references. Rows are
+// remapped as in ImportFrom. It sets file.ID and returns it.
+func (s *Store) ImportFile(src *Store, srcFileID int64, file *File) (int64, error) {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return 0, fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	res, err := tx.Exec(
+		"INSERT INTO files (path, language, hash, line_count, last_indexed, root) VALUES (?, ?, ?, ?, ?, ?)",
+		file.Path, file.Language, file.Hash, file.LineCount, file.LastIndexed, file.Root,
+	)
+	if err != nil {
+		return 0, fmt.Errorf("insert file: %w", err)
+	}
+	id, err := res.LastInsertId()
+	if err != nil {
+		return 0, fmt.Errorf("last insert id: %w", err)
+	}
+
+	im := newImporter(tx, src)
+	im.ids["files"] = map[int64]int64{srcFileID: id}
+	err = im.copyTables(func(table string) (string, []any) {
+		if table == "files" {
+			return " WHERE 0", nil // already recorded
+		}
+		var conds []string
+		var args []any
+		for _, col := range slices.Sorted(maps.Keys(im.refs[table])) {
+			if ref := im.refs[table][col]; ref == "files" {
+				conds = append(conds, col+" = ?")
+			} else {
+				conds = append(conds, col+" IN (SELECT id FROM "+ref+" WHERE file_id = ?)")
+			}
+			args = append(args, srcFileID)
+		}
+		if len(conds) == 0 {
+			return " WHERE 0", nil
+		}
+		return " WHERE " + strings.Join(conds, " OR "), args
+	})
+	if err != nil {
+		return 0, err
+	}
+	if err := tx.Commit(); err != nil {
+		return 0, fmt.Errorf("commit import: %w", err)
+	}
+	file.ID = id
+	return id, nil
+}
+
+// importer carries the id mappings of an ImportFrom call.
+type importer struct {
+	tx       *sql.Tx
+	src      *Store
+	refs     map[string]map[string]string // table -> id column -> referenced table
+	ids      map[string]map[int64]int64   // table -> source id -> copied id
+	selfRefs []selfRef
+}
+
+func newImporter(tx *sql.Tx, src *Store) *importer {
+	im := &importer{tx: tx, src: src, refs: map[string]map[string]string{}, ids: map[string]map[int64]int64{}}
+	for _, fk := range schemaForeignKeys() {
+		if im.refs[fk.table] == nil {
+			im.refs[fk.table] = map[string]string{}
+		}
+		im.refs[fk.table][fk.column] = fk.refTable
+	}
+	return im
+}
+
+// copyTables copies the rows of every table that where selects, then
+// patches the rows pointing into their own table.
+func (im *importer) copyTables(where func(table string) (string, []any)) error {
+	// schemaDDL declares tables after the tables they reference, so their
+	// ids are mapped by the time a row needs them.
+	for _, m := range createTableRe.FindAllStringSubmatch(schemaDDL, -1) {
+		table := m[1]
+		if table == "metadata" {
+			continue
+		}
+		clause, args := where(table)
+		if err := im.copyTable(table, clause, args); err != nil {
+			return fmt.Errorf("import %s: %w", table, err)
+		}
+	}
+
+	// Rows pointing at rows of their own table, such as a symbol's parent,
+	// are patched once the whole table is copied.
+	for _, p := range im.selfRefs {
+		newRef, ok := im.ids[p.table][p.ref]
+		if !ok {
+			continue
+		}
+		if _, err := im.tx.Exec("UPDATE "+p.table+" SET "+p.column+" = ? WHERE id = ?", newRef, p.row); err != nil {
+			return fmt.Errorf("import %s: %w", p.table, err)
+		}
+	}
+	return nil
+}
+
+// selfRef is a copied row whose column must be pointed at the copy of ref.
+type selfRef struct {
+	table, column string
+	row, ref      int64
+}
+
+// copyTable copies the rows of table in src matching where.
+func (im *importer) copyTable(table, where string, args []any) error {
+	cols, notNull, err := tableColumns(im.tx, table)
+	if err != nil {
+		return err
+	}
+	hasID := slices.Contains(cols, "id")
+	var insertCols []string
+	for _, c := range cols {
+		if c != "id" {
+			insertCols = append(insertCols, c)
+		}
+	}
+
+	stmt, err := im.tx.Prepare("INSERT INTO " + table + " (" + strings.Join(insertCols, ", ") +
+		") VALUES (" + placeholderList(len(insertCols)) + ")")
+	if err != nil {
+		return err
+	}
+	defer stmt.Close()
+
+	rows, err := im.src.db.Query("SELECT "+strings.Join(cols, ", ")+" FROM "+table+where+" ORDER BY rowid", args...)
+	if err != nil {
+		return err
+	}
+	defer rows.Close()
+
+	ids := im.ids[table]
+	if ids == nil {
+		ids = make(map[int64]int64)
+		im.ids[table] = ids
+	}
+	values := make([]any, len(cols))
+	ptrs := make([]any, len(cols))
+	for i := range values {
+		ptrs[i] = &values[i]
+	}
+	for rows.Next() {
+		if err := rows.Scan(ptrs...); err != nil {
+			return fmt.Errorf("scan: %w", err)
+		}
+		var oldID int64
+		var pending []selfRef
+		insert := make([]any, 0, len(insertCols))
+		keep := true
+		for i, col := range cols {
+			v := values[i]
+			if col == "id" {
+				oldID, _ = v.(int64)
+				continue
+			}
+			if refTable, ok := im.refs[table][col]; ok && v != nil {
+				ref, _ := v.(int64)
+				if refTable == table {
+					pending = append(pending, selfRef{table: table, column: col, ref: ref})
+					v = nil
+				} else if id, ok := im.ids[refTable][ref]; ok {
+					v = id
+				} else if notNull[col] || col == "file_id" {
+					keep = false
+					break
+				} else {
+					v = nil
+				}
+			}
+			insert = append(insert, v)
+		}
+		if !keep {
+			continue
+		}
+		res, err := stmt.Exec(insert...)
+		if err != nil {
+			return err
+		}
+		if !hasID {
+			continue
+		}
+		newID, err := res.LastInsertId()
+		if err != nil {
+			return fmt.Errorf("last insert id: %w", err)
+		}
+		ids[oldID] = newID
+		for _, p := range pending {
+			p.row = newID
+			im.selfRefs = append(im.selfRefs, p)
+		}
+	}
+	return rows.Err()
+}
+
+// tableColumns returns the columns of table and which of them are NOT NULL.
+func tableColumns(tx *sql.Tx, table string) ([]string, map[string]bool, error) {
+	rows, err := tx.Query("PRAGMA table_info(" + table + ")")
+	if err != nil {
+		return nil, nil, err
+	}
+	defer rows.Close()
+	var cols []string
+	notNull := make(map[string]bool)
+	for rows.Next() {
+		var (
+			cid, nn, pk int
+			name, typ   string
+			dflt        sql.NullString
+		)
+		if err := rows.Scan(&cid, &name, &typ, &nn, &dflt, &pk); err != nil {
+			return nil, nil, fmt.Errorf("table info: %w", err)
+		}
+		cols = append(cols, name)
+		notNull[name] = nn != 0
+	}
+	return cols, notNull, rows.Err()
+}
+
+// FilesWithUnlinkedReferences returns the files whose references a link of
+// fileIDs into the index may newly resolve: the files of fileIDs with
+// unresolved references, and any other file with an unresolved reference
+// named like a top-level symbol of fileIDs.
+func (s *Store) FilesWithUnlinkedReferences(fileIDs []int64) ([]int64, error) {
+	if len(fileIDs) == 0 {
+		return nil, nil
+	}
+	placeholders := placeholderList(len(fileIDs))
+	args := int64sToArgs(fileIDs)
+	rows, err := s.db.Query(
+		`SELECT DISTINCT r.file_id FROM references_ r
+		 WHERE (r.file_id IN (`+placeholders+`)
+		        OR r.name IN (SELECT name FROM symbols WHERE parent_symbol_id IS NULL AND file_id IN (`+placeholders+`)))
+		   AND NOT EXISTS (SELECT 1 FROM resolved_references rr WHERE rr.reference_id = r.id)
+		 ORDER BY r.file_id`,
+		repeatArgs(args, 2)...)
+	if err != nil {
+		return nil, fmt.Errorf("files with unlinked references: %w", err)
+	}
+	defer rows.Close()
+	var result []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, fmt.Errorf("files with unlinked references: scan: %w", err)
+		}
+		result = append(result, id)
+	}
+	return result, rows.Err()
+}
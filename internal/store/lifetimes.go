@@ -0,0 +1,204 @@
+package store
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+)
+
+// lifetimeRe matches a Rust lifetime such as 'a, 'de, 'static, or '_.
+var lifetimeRe = regexp.MustCompile(`'[A-Za-z_][A-Za-z0-9_]*\b`)
+
+// refLifetimeRe matches a reference's lifetime, as in &'a T.
+var refLifetimeRe = regexp.MustCompile(`&\s*'[A-Za-z_][A-Za-z0-9_]*\b\s*`)
+
+// elidedRefRe matches an explicitly elided reference lifetime, as in &'_ T.
+var elidedRefRe = regexp.MustCompile(`&\s*'_\b\s*`)
+
+// elidedPathLifetimeRe matches an elided lifetime argument of a path type:
+// the '_ in Formatter<'_> or Cow<'_, str>.
+var elidedPathLifetimeRe = regexp.MustCompile(`<\s*'_\s*>|'_\s*,\s*|,\s*'_\b`)
+
+// ElideLifetimes removes every lifetime from a type expression:
+// "&'a mut Cow<'a, str>" becomes "&mut Cow<str>". Signature search uses it
+// so that a pattern written without lifetimes matches types written with.
+func ElideLifetimes(expr string) string {
+	if !strings.Contains(expr, "'") {
+		return expr
+	}
+	expr = refLifetimeRe.ReplaceAllString(expr, "&")
+	return dropElidedPathLifetimes(lifetimeRe.ReplaceAllString(expr, "'_"))
+}
+
+// dropElidedPathLifetimes removes '_ path lifetime arguments, including
+// runs of them (Foo<'_, '_>).
+func dropElidedPathLifetimes(expr string) string {
+	for {
+		next := elidedPathLifetimeRe.ReplaceAllString(expr, "")
+		if next == expr {
+			return expr
+		}
+		expr = next
+	}
+}
+
+// normalizeLifetimes rewrites a symbol's signature parts so that spellings
+// Rust treats as the same signature hash the same:
+//
+//   - A declared lifetime the elision rules would infer is elided:
+//     fn f<'a>(x: &'a str) -> &'a str becomes fn f(x: &str) -> &str, and
+//     &'_ T becomes &T.
+//   - The remaining declared lifetimes are renamed by declaration order, so
+//     renaming 'a to 'b is not a change.
+//
+// Non-lifetime type parameters are renumbered after dropped lifetimes. The
+// inputs are not modified; symbols without lifetimes are returned as is.
+func normalizeLifetimes(members []*TypeMember, params []*FunctionParam, typeParams []*TypeParam) ([]*TypeMember, []*FunctionParam, []*TypeParam) {
+	if !usesLifetimes(members, params, typeParams) {
+		return members, params, typeParams
+	}
+
+	// Lifetimes the function's elision rules would infer.
+	elided := map[string]bool{}
+	if len(params) > 0 {
+		elided = elidableLifetimes(params, typeParams)
+	}
+
+	rename := map[string]string{}
+	for _, tp := range typeParams {
+		if tp.ParamKind == "lifetime" && !elided[tp.Name] {
+			rename[tp.Name] = fmt.Sprintf("'l%d", len(rename))
+		}
+	}
+	rewrite := func(expr string) string {
+		for lt := range elided {
+			expr = regexp.MustCompile(`&\s*`+regexp.QuoteMeta(lt)+`\b\s*`).ReplaceAllString(expr, "&")
+			expr = regexp.MustCompile(regexp.QuoteMeta(lt)+`\b`).ReplaceAllString(expr, "'_")
+		}
+		expr = elidedRefRe.ReplaceAllString(expr, "&")
+		expr = dropElidedPathLifetimes(expr)
+		return lifetimeRe.ReplaceAllStringFunc(expr, func(lt string) string {
+			if r, ok := rename[lt]; ok {
+				return r
+			}
+			return lt
+		})
+	}
+
+	normMembers := make([]*TypeMember, len(members))
+	for i, m := range members {
+		c := *m
+		c.TypeExpr = rewrite(c.TypeExpr)
+		normMembers[i] = &c
+	}
+	normParams := make([]*FunctionParam, len(params))
+	for i, p := range params {
+		c := *p
+		c.TypeExpr = rewrite(c.TypeExpr)
+		normParams[i] = &c
+	}
+	var normTypeParams []*TypeParam
+	for _, tp := range typeParams {
+		if tp.ParamKind == "lifetime" && elided[tp.Name] {
+			continue
+		}
+		c := *tp
+		c.Ordinal = len(normTypeParams)
+		if r, ok := rename[c.Name]; ok {
+			c.Name = r
+		}
+		c.Constraints = rewrite(c.Constraints)
+		normTypeParams = append(normTypeParams, &c)
+	}
+	return normMembers, normParams, normTypeParams
+}
+
+// usesLifetimes reports whether any part of a signature mentions a lifetime.
+func usesLifetimes(members []*TypeMember, params []*FunctionParam, typeParams []*TypeParam) bool {
+	for _, m := range members {
+		if strings.Contains(m.TypeExpr, "'") {
+			return true
+		}
+	}
+	for _, p := range params {
+		if strings.Contains(p.TypeExpr, "'") {
+			return true
+		}
+	}
+	for _, tp := range typeParams {
+		if tp.ParamKind == "lifetime" || strings.Contains(tp.Constraints, "'") {
+			return true
+		}
+	}
+	return false
+}
+
+// elidableLifetimes returns the declared lifetimes of a function that could
+// be left out without changing its meaning. A lifetime is elidable when it
+// is unconstrained, used by exactly one input, and used by the outputs only
+// where elision would infer it: from a &self receiver, or from the only
+// input lifetime position.
+func elidableLifetimes(params []*FunctionParam, typeParams []*TypeParam) map[string]bool {
+	inputUses := map[string]int{}
+	outputUses := map[string]int{}
+	inputPositions := 0
+	receiverLifetime := ""
+	for _, p := range params {
+		uses := inputUses
+		if p.IsReturn {
+			uses = outputUses
+		} else {
+			inputPositions += lifetimePositions(p.TypeExpr)
+		}
+		for _, lt := range lifetimeRe.FindAllString(p.TypeExpr, -1) {
+			uses[lt]++
+		}
+		if p.IsReceiver {
+			if expr := strings.TrimSpace(p.TypeExpr); strings.HasPrefix(expr, "&") {
+				receiverLifetime = "'_"
+				if lt := lifetimeRe.FindString(expr); lt != "" && strings.HasPrefix(strings.TrimSpace(expr[1:]), lt) {
+					receiverLifetime = lt
+				}
+			}
+		}
+	}
+
+	constrained := map[string]bool{}
+	for _, tp := range typeParams {
+		if tp.ParamKind == "lifetime" && tp.Constraints != "" {
+			constrained[tp.Name] = true
+		}
+		for _, lt := range lifetimeRe.FindAllString(tp.Constraints, -1) {
+			constrained[lt] = true
+		}
+	}
+
+	elided := map[string]bool{}
+	for _, tp := range typeParams {
+		lt := tp.Name
+		if tp.ParamKind != "lifetime" || constrained[lt] || inputUses[lt] != 1 {
+			continue
+		}
+		if outputUses[lt] > 0 && lt != receiverLifetime && (receiverLifetime != "" || inputPositions != 1) {
+			continue
+		}
+		elided[lt] = true
+	}
+	return elided
+}
+
+// lifetimePositions counts the lifetime positions in an input type: explicit
+// lifetimes plus references written without one.
+func lifetimePositions(expr string) int {
+	n := len(lifetimeRe.FindAllString(expr, -1))
+	for i := 0; i < len(expr); i++ {
+		if expr[i] != '&' {
+			continue
+		}
+		rest := strings.TrimLeft(expr[i+1:], " \t\n")
+		if !strings.HasPrefix(rest, "'") {
+			n++
+		}
+	}
+	return n
+}
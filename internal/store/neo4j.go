@@ -0,0 +1,170 @@
+//go:build !canopy_noexport
+
+package store
+
+import (
+	"encoding/csv"
+	"fmt"
+	"io"
+	"strconv"
+	"strings"
+)
+
+// --- Neo4j export ---
+
+// A Neo4j export is the code graph as CSV files in the format of
+// neo4j-admin database import: File and Symbol nodes, each symbol also
+// labelled with its kind (Function, TypeAlias, ...), and CALLS, REFERENCES,
+// IMPLEMENTS, CONTAINS, and DEFINED_IN relationships. Node ids live in the
+// File and Symbol id spaces and are the index's row ids.
+type neo4jFile struct {
+	name   string
+	header []string
+	query  string
+	// label, if set, rewrites the row's last column from a symbol kind to
+	// the node's labels.
+	label bool
+}
+
+var neo4jFiles = []neo4jFile{
+	{
+		name:   "files.csv",
+		header: []string{"fileId:ID(File)", "path", "language", "root", "lineCount:long", ":LABEL"},
+		query:  `SELECT id, path, language, root, line_count, 'File' FROM files ORDER BY id`,
+	},
+	{
+		name: "symbols.csv",
+		header: []string{"symbolId:ID(Symbol)", "name", "kind", "subkind", "visibility", "file",
+			"startLine:int", "endLine:int", "pagerank:double", ":LABEL"},
+		query: `SELECT s.id, s.name, s.kind, s.subkind, s.visibility, f.path, s.start_line, s.end_line, s.pagerank, s.kind
+		 FROM symbols s LEFT JOIN files f ON f.id = s.file_id ORDER BY s.id`,
+		label: true,
+	},
+	{
+		// References with no enclosing symbol have no start node and are
+		// left out.
+		name:   "relationships.csv",
+		header: []string{":START_ID(Symbol)", ":END_ID(Symbol)", ":TYPE", "subkind", "file", "line:int", "confidence:double"},
+		query: `SELECT source_symbol_id, target_symbol_id,
+			CASE kind WHEN 'call' THEN 'CALLS' WHEN 'reference' THEN 'REFERENCES' ELSE 'IMPLEMENTS' END,
+			subkind, file, line, confidence
+		 FROM (` + analyticsQuery("edges") + `) WHERE source_symbol_id IS NOT NULL
+		 UNION ALL
+		 SELECT parent_symbol_id, id, 'CONTAINS', NULL, NULL, NULL, NULL FROM symbols WHERE parent_symbol_id IS NOT NULL`,
+	},
+	{
+		name:   "defined_in.csv",
+		header: []string{":START_ID(Symbol)", ":END_ID(File)", ":TYPE"},
+		query:  `SELECT id, file_id, 'DEFINED_IN' FROM symbols WHERE file_id IS NOT NULL ORDER BY id`,
+	},
+}
+
+// analyticsQuery returns the query of the analytics table called name.
+func analyticsQuery(name string) string {
+	for _, t := range analyticsTables {
+		if t.name == name {
+			return t.query
+		}
+	}
+	panic("store: unknown analytics table " + name)
+}
+
+// Neo4jFiles returns the names of the CSV files WriteNeo4j writes: the node
+// files, then the relationship files.
+func Neo4jFiles() []string {
+	names := make([]string, len(neo4jFiles))
+	for i, f := range neo4jFiles {
+		names[i] = f.name
+	}
+	return names
+}
+
+// WriteNeo4j writes the Neo4j CSV file called name to w and returns the
+// number of rows written. Null properties are empty fields, which
+// neo4j-admin leaves unset.
+func (s *Store) WriteNeo4j(name string, w io.Writer) (int64, error) {
+	var file *neo4jFile
+	for i := range neo4jFiles {
+		if neo4jFiles[i].name == name {
+			file = &neo4jFiles[i]
+		}
+	}
+	if file == nil {
+		return 0, fmt.Errorf("write neo4j: unknown file %q", name)
+	}
+
+	rows, err := s.db.Query(file.query)
+	if err != nil {
+		return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+	}
+	defer rows.Close()
+
+	cw := csv.NewWriter(w)
+	if err := cw.Write(file.header); err != nil {
+		return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+	}
+	values := make([]any, len(file.header))
+	ptrs := make([]any, len(values))
+	for i := range values {
+		ptrs[i] = &values[i]
+	}
+	record := make([]string, len(values))
+	var n int64
+	for rows.Next() {
+		if err := rows.Scan(ptrs...); err != nil {
+			return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+		}
+		for i, v := range values {
+			record[i] = neo4jField(v)
+		}
+		if file.label {
+			labels := "Symbol"
+			if kind := neo4jLabel(record[len(record)-1]); kind != "" {
+				labels += ";" + kind
+			}
+			record[len(record)-1] = labels
+		}
+		if err := cw.Write(record); err != nil {
+			return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+		}
+		n++
+	}
+	if err := rows.Err(); err != nil {
+		return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+	}
+	cw.Flush()
+	if err := cw.Error(); err != nil {
+		return 0, fmt.Errorf("write neo4j: %s: %w", name, err)
+	}
+	return n, nil
+}
+
+// neo4jField formats a value scanned from SQLite as a CSV field.
+func neo4jField(v any) string {
+	switch v := v.(type) {
+	case nil:
+		return ""
+	case []byte:
+		return string(v)
+	case string:
+		return v
+	case int64:
+		return strconv.FormatInt(v, 10)
+	case float64:
+		return strconv.FormatFloat(v, 'g', -1, 64)
+	default:
+		return fmt.Sprint(v)
+	}
+}
+
+// neo4jLabel converts a symbol kind to a node label: "type_alias" becomes
+// "TypeAlias".
+func neo4jLabel(kind string) string {
+	var b strings.Builder
+	for _, part := range strings.Split(kind, "_") {
+		if part != "" {
+			b.WriteString(strings.ToUpper(part[:1]) + part[1:])
+		}
+	}
+	return b.String()
+}
@@ -0,0 +1,58 @@
+package store
+
+import "fmt"
+
+// --- File ownership overlay ---
+
+// ReplaceFileOwners sets the owners of each file in owners, replacing any
+// previous owners. A nil or empty slice clears a file's owners. Runs in one
+// transaction.
+func (s *Store) ReplaceFileOwners(owners map[int64][]string) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	del, err := tx.Prepare("DELETE FROM file_owners WHERE file_id = ?")
+	if err != nil {
+		return fmt.Errorf("prepare delete owners: %w", err)
+	}
+	defer del.Close()
+	ins, err := tx.Prepare("INSERT INTO file_owners (file_id, owner) VALUES (?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare insert owner: %w", err)
+	}
+	defer ins.Close()
+
+	for fileID, fileOwners := range owners {
+		if _, err := del.Exec(fileID); err != nil {
+			return fmt.Errorf("delete owners of file %d: %w", fileID, err)
+		}
+		for _, owner := range fileOwners {
+			if _, err := ins.Exec(fileID, owner); err != nil {
+				return fmt.Errorf("insert owner of file %d: %w", fileID, err)
+			}
+		}
+	}
+	return tx.Commit()
+}
+
+// FileOwners returns the owners of a file in CODEOWNERS order. Returns nil if
+// the file is unowned.
+func (s *Store) FileOwners(fileID int64) ([]string, error) {
+	rows, err := s.db.Query("SELECT owner FROM file_owners WHERE file_id = ? ORDER BY rowid", fileID)
+	if err != nil {
+		return nil, fmt.Errorf("file owners: %w", err)
+	}
+	defer rows.Close()
+	var owners []string
+	for rows.Next() {
+		var owner string
+		if err := rows.Scan(&owner); err != nil {
+			return nil, fmt.Errorf("scan owner: %w", err)
+		}
+		owners = append(owners, owner)
+	}
+	return owners, rows.Err()
+}
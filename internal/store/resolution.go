@@ -42,13 +42,13 @@ const resolvedRefCols = `id, reference_id, target_symbol_id, confidence, resolut
 
 func (s *Store) ResolvedReferencesByRef(referenceID int64) ([]*ResolvedReference, error) {
 	return s.queryResolvedRefs(
-		"SELECT "+resolvedRefCols+" FROM resolved_references WHERE reference_id = ?", referenceID,
+		"SELECT "+resolvedRefCols+" FROM resolved_references WHERE reference_id = ? ORDER BY target_symbol_id, id", referenceID,
 	)
 }
 
 func (s *Store) ResolvedReferencesByTarget(symbolID int64) ([]*ResolvedReference, error) {
 	return s.queryResolvedRefs(
-		"SELECT "+resolvedRefCols+" FROM resolved_references WHERE target_symbol_id = ?", symbolID,
+		"SELECT "+resolvedRefCols+" FROM resolved_references WHERE target_symbol_id = ? ORDER BY reference_id, id", symbolID,
 	)
 }
 
@@ -93,13 +93,13 @@ const implCols = `id, type_symbol_id, interface_symbol_id, kind, file_id, declar
 
 func (s *Store) ImplementationsByType(typeSymbolID int64) ([]*Implementation, error) {
 	return s.queryImplementations(
-		"SELECT "+implCols+" FROM implementations WHERE type_symbol_id = ?", typeSymbolID,
+		"SELECT "+implCols+" FROM implementations WHERE type_symbol_id = ? ORDER BY interface_symbol_id, id", typeSymbolID,
 	)
 }
 
 func (s *Store) ImplementationsByInterface(interfaceSymbolID int64) ([]*Implementation, error) {
 	return s.queryImplementations(
-		"SELECT "+implCols+" FROM implementations WHERE interface_symbol_id = ?", interfaceSymbolID,
+		"SELECT "+implCols+" FROM implementations WHERE interface_symbol_id = ? ORDER BY type_symbol_id, id", interfaceSymbolID,
 	)
 }
 
@@ -107,9 +107,9 @@ func (s *Store) ImplementationsByInterface(interfaceSymbolID int64) ([]*Implemen
 
 func (s *Store) InsertCallEdge(edge *CallEdge) (int64, error) {
 	res, err := s.db.Exec(
-		`INSERT INTO call_graph (caller_symbol_id, callee_symbol_id, file_id, line, col)
-		 VALUES (?, ?, ?, ?, ?)`,
-		edge.CallerSymbolID, edge.CalleeSymbolID, edge.FileID, edge.Line, edge.Col,
+		`INSERT INTO call_graph (caller_symbol_id, callee_symbol_id, file_id, line, col, confidence, awaited, kind, propagated)
+		 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+		edge.CallerSymbolID, edge.CalleeSymbolID, edge.FileID, edge.Line, edge.Col, edge.Confidence, edge.Awaited, edge.Kind, edge.Propagated,
 	)
 	if err != nil {
 		return 0, fmt.Errorf("insert call edge: %w", err)
@@ -122,6 +122,23 @@ func (s *Store) InsertCallEdge(edge *CallEdge) (int64, error) {
 	return id, nil
 }
 
+// DeleteLinksByKind deletes the call edges of the given kind and the
+// resolutions of the given resolution kind in every file.
+func (s *Store) DeleteLinksByKind(kind string) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+	if _, err := tx.Exec("DELETE FROM call_graph WHERE kind = ?", kind); err != nil {
+		return fmt.Errorf("delete call edges by kind: %w", err)
+	}
+	if _, err := tx.Exec("DELETE FROM resolved_references WHERE resolution_kind = ?", kind); err != nil {
+		return fmt.Errorf("delete resolved references by kind: %w", err)
+	}
+	return tx.Commit()
+}
+
 func (s *Store) queryCallEdges(query string, args ...any) ([]*CallEdge, error) {
 	rows, err := s.db.Query(query, args...)
 	if err != nil {
@@ -131,7 +148,7 @@ func (s *Store) queryCallEdges(query string, args ...any) ([]*CallEdge, error) {
 	var edges []*CallEdge
 	for rows.Next() {
 		e := &CallEdge{}
-		if err := rows.Scan(&e.ID, &e.CallerSymbolID, &e.CalleeSymbolID, &e.FileID, &e.Line, &e.Col); err != nil {
+		if err := rows.Scan(&e.ID, &e.CallerSymbolID, &e.CalleeSymbolID, &e.FileID, &e.Line, &e.Col, &e.Confidence, &e.Awaited, &e.Kind, &e.Propagated); err != nil {
 			return nil, fmt.Errorf("scan call edge: %w", err)
 		}
 		edges = append(edges, e)
@@ -139,23 +156,27 @@ func (s *Store) queryCallEdges(query string, args ...any) ([]*CallEdge, error) {
 	return edges, rows.Err()
 }
 
-const callEdgeCols = `id, caller_symbol_id, callee_symbol_id, file_id, line, col`
+const callEdgeCols = `id, caller_symbol_id, callee_symbol_id, file_id, line, col, confidence, awaited, kind, propagated`
+
+// callEdgeOrder orders call edges by call site, so lookups do not depend on
+// the order concurrent resolution scripts inserted them in.
+const callEdgeOrder = " ORDER BY file_id, line, col, caller_symbol_id, callee_symbol_id"
 
 // AllCallEdges returns all call graph edges. Used for bulk-loading into
 // in-memory adjacency maps for transitive traversal.
 func (s *Store) AllCallEdges() ([]*CallEdge, error) {
-	return s.queryCallEdges("SELECT " + callEdgeCols + " FROM call_graph")
+	return s.queryCallEdges("SELECT " + callEdgeCols + " FROM call_graph" + callEdgeOrder)
 }
 
 func (s *Store) CallersByCallee(calleeSymbolID int64) ([]*CallEdge, error) {
 	return s.queryCallEdges(
-		"SELECT "+callEdgeCols+" FROM call_graph WHERE callee_symbol_id = ?", calleeSymbolID,
+		"SELECT "+callEdgeCols+" FROM call_graph WHERE callee_symbol_id = ?"+callEdgeOrder, calleeSymbolID,
 	)
 }
 
 func (s *Store) CalleesByCaller(callerSymbolID int64) ([]*CallEdge, error) {
 	return s.queryCallEdges(
-		"SELECT "+callEdgeCols+" FROM call_graph WHERE caller_symbol_id = ?", callerSymbolID,
+		"SELECT "+callEdgeCols+" FROM call_graph WHERE caller_symbol_id = ?"+callEdgeOrder, callerSymbolID,
 	)
 }
 
@@ -180,7 +201,7 @@ func (s *Store) InsertReexport(re *Reexport) (int64, error) {
 
 func (s *Store) ReexportsByFile(fileID int64) ([]*Reexport, error) {
 	rows, err := s.db.Query(
-		"SELECT id, file_id, original_symbol_id, exported_name FROM reexports WHERE file_id = ?",
+		"SELECT id, file_id, original_symbol_id, exported_name FROM reexports WHERE file_id = ? ORDER BY id",
 		fileID,
 	)
 	if err != nil {
@@ -198,6 +219,65 @@ func (s *Store) ReexportsByFile(fileID int64) ([]*Reexport, error) {
 	return reexports, rows.Err()
 }
 
+// --- ImportAlias operations ---
+
+// RebuildImportAliases replaces the import_aliases table with the aliased
+// imports whose item resolved, through the imported name in the import or
+// through a reference to the alias in the same file, to a symbol of that
+// name. It returns the number of aliases recorded.
+func (s *Store) RebuildImportAliases() (int, error) {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return 0, fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.Exec("DELETE FROM import_aliases"); err != nil {
+		return 0, fmt.Errorf("clear import aliases: %w", err)
+	}
+	res, err := tx.Exec(
+		`INSERT INTO import_aliases (file_id, alias, imported_name, source, target_symbol_id)
+		 SELECT DISTINCT i.file_id, i.local_alias, i.imported_name, i.source, rr.target_symbol_id
+		 FROM imports i
+		 JOIN references_ r ON r.file_id = i.file_id
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 WHERE i.local_alias IS NOT NULL AND i.local_alias NOT IN ('', '_')
+		 AND i.imported_name IS NOT NULL AND i.imported_name NOT IN ('', '*')
+		 AND i.local_alias != i.imported_name
+		 AND s.name = i.imported_name
+		 AND ((r.context = 'import' AND r.name = i.imported_name) OR r.name = i.local_alias)
+		 ORDER BY i.file_id, i.id, rr.target_symbol_id`)
+	if err != nil {
+		return 0, fmt.Errorf("insert import aliases: %w", err)
+	}
+	n, err := res.RowsAffected()
+	if err != nil {
+		return 0, fmt.Errorf("rows affected: %w", err)
+	}
+	return int(n), tx.Commit()
+}
+
+func (s *Store) ImportAliasesByTarget(symbolID int64) ([]*ImportAlias, error) {
+	rows, err := s.db.Query(
+		"SELECT id, file_id, alias, imported_name, source, target_symbol_id FROM import_aliases WHERE target_symbol_id = ? ORDER BY id",
+		symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("import aliases by target: %w", err)
+	}
+	defer rows.Close()
+	var aliases []*ImportAlias
+	for rows.Next() {
+		a := &ImportAlias{}
+		if err := rows.Scan(&a.ID, &a.FileID, &a.Alias, &a.ImportedName, &a.Source, &a.TargetSymbolID); err != nil {
+			return nil, fmt.Errorf("scan import alias: %w", err)
+		}
+		aliases = append(aliases, a)
+	}
+	return aliases, rows.Err()
+}
+
 // --- ExtensionBinding operations ---
 
 func (s *Store) InsertExtensionBinding(eb *ExtensionBinding) (int64, error) {
@@ -221,7 +301,8 @@ func (s *Store) InsertExtensionBinding(eb *ExtensionBinding) (int64, error) {
 func (s *Store) ExtensionBindingsByType(typeSymbolID int64) ([]*ExtensionBinding, error) {
 	rows, err := s.db.Query(
 		`SELECT id, member_symbol_id, extended_type_expr, extended_type_symbol_id, kind, constraints, is_default_impl
-		 FROM extension_bindings WHERE extended_type_symbol_id = ?`,
+		 FROM extension_bindings WHERE extended_type_symbol_id = ?
+		 ORDER BY member_symbol_id, id`,
 		typeSymbolID,
 	)
 	if err != nil {
@@ -280,12 +361,12 @@ const typeCompCols = `id, composite_symbol_id, component_symbol_id, composition_
 
 func (s *Store) TypeCompositions(compositeSymbolID int64) ([]*TypeComposition, error) {
 	return s.queryTypeCompositions(
-		"SELECT "+typeCompCols+" FROM type_compositions WHERE composite_symbol_id = ?", compositeSymbolID,
+		"SELECT "+typeCompCols+" FROM type_compositions WHERE composite_symbol_id = ? ORDER BY component_symbol_id, id", compositeSymbolID,
 	)
 }
 
 func (s *Store) TypeComposedBy(componentSymbolID int64) ([]*TypeComposition, error) {
 	return s.queryTypeCompositions(
-		"SELECT "+typeCompCols+" FROM type_compositions WHERE component_symbol_id = ?", componentSymbolID,
+		"SELECT "+typeCompCols+" FROM type_compositions WHERE component_symbol_id = ? ORDER BY composite_symbol_id, id", componentSymbolID,
 	)
 }
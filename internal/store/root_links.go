@@ -0,0 +1,77 @@
+package store
+
+import "fmt"
+
+// --- Cross-root links ---
+
+// crossRootLinkQueries select, for each resolution table, the id of every
+// row linking a file to a symbol, with the roots of the file and of the
+// symbol's file. Rows of files without a root are left out.
+var crossRootLinkQueries = map[string]string{
+	"resolved_references": `SELECT rr.id, rf.root, sf.root FROM resolved_references rr
+		JOIN references_ r ON r.id = rr.reference_id
+		JOIN files rf ON rf.id = r.file_id
+		JOIN symbols s ON s.id = rr.target_symbol_id
+		JOIN files sf ON sf.id = s.file_id
+		WHERE rf.root <> '' AND sf.root <> '' AND rf.root <> sf.root`,
+	"call_graph": `SELECT cg.id, cf.root, sf.root FROM call_graph cg
+		JOIN files cf ON cf.id = cg.file_id
+		JOIN symbols s ON s.id = cg.callee_symbol_id
+		JOIN files sf ON sf.id = s.file_id
+		WHERE cf.root <> '' AND sf.root <> '' AND cf.root <> sf.root`,
+	"implementations": `SELECT i.id, f.root, sf.root FROM implementations i
+		JOIN files f ON f.id = i.file_id
+		JOIN symbols s ON s.id IN (i.interface_symbol_id, i.type_symbol_id)
+		JOIN files sf ON sf.id = s.file_id
+		WHERE f.root <> '' AND sf.root <> '' AND f.root <> sf.root`,
+}
+
+// DeleteCrossRootLinks deletes the resolved references, call edges, and
+// implementations that link a file under one root to a symbol under
+// another root, unless allowed(fileRoot, symbolRoot) reports true. It
+// returns the number of rows deleted.
+func (s *Store) DeleteCrossRootLinks(allowed func(from, to string) bool) (int, error) {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return 0, fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	deleted := 0
+	for _, table := range []string{"resolved_references", "call_graph", "implementations"} {
+		rows, err := tx.Query(crossRootLinkQueries[table])
+		if err != nil {
+			return 0, fmt.Errorf("cross-root links in %s: %w", table, err)
+		}
+		var ids []int64
+		seen := make(map[int64]bool)
+		for rows.Next() {
+			var (
+				id       int64
+				from, to string
+			)
+			if err := rows.Scan(&id, &from, &to); err != nil {
+				rows.Close()
+				return 0, fmt.Errorf("cross-root links in %s: scan: %w", table, err)
+			}
+			if !allowed(from, to) && !seen[id] {
+				seen[id] = true
+				ids = append(ids, id)
+			}
+		}
+		rows.Close()
+		if err := rows.Err(); err != nil {
+			return 0, fmt.Errorf("cross-root links in %s: %w", table, err)
+		}
+		for _, id := range ids {
+			if _, err := tx.Exec("DELETE FROM "+table+" WHERE id = ?", id); err != nil {
+				return 0, fmt.Errorf("delete cross-root links in %s: %w", table, err)
+			}
+		}
+		deleted += len(ids)
+	}
+	if err := tx.Commit(); err != nil {
+		return 0, fmt.Errorf("commit: %w", err)
+	}
+	return deleted, nil
+}
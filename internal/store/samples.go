@@ -0,0 +1,30 @@
+package store
+
+import "fmt"
+
+// --- Execution trace overlay ---
+
+// ReplaceSymbolSamples replaces the trace samples of all symbols with
+// samples. Runs in one transaction.
+func (s *Store) ReplaceSymbolSamples(samples []*SymbolSamples) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.Exec("DELETE FROM symbol_samples"); err != nil {
+		return fmt.Errorf("delete symbol samples: %w", err)
+	}
+	ins, err := tx.Prepare("INSERT INTO symbol_samples (symbol_id, self_samples, total_samples) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare symbol samples: %w", err)
+	}
+	defer ins.Close()
+	for _, sm := range samples {
+		if _, err := ins.Exec(sm.SymbolID, sm.Self, sm.Total); err != nil {
+			return fmt.Errorf("insert symbol samples: %w", err)
+		}
+	}
+	return tx.Commit()
+}
@@ -0,0 +1,86 @@
+package store
+
+import (
+	"fmt"
+	"path/filepath"
+	"strings"
+)
+
+// --- Schema types and the types generated from them ---
+
+// SchemaType is a message or enum of a schema file, or a type generated
+// from one.
+type SchemaType struct {
+	SymbolID int64
+	Name     string
+	Kind     string // "message" or "enum"; "struct" or "enum" for generated types
+	Parent   string // enclosing message, or the module nesting a generated type; "" at top level
+	Package  string // schema package; for generated types, the file's stem
+}
+
+// ProtoTypes returns the messages and enums declared in protobuf files.
+func (s *Store) ProtoTypes() ([]*SchemaType, error) {
+	return s.querySchemaTypes(`SELECT s.id, s.name, s.kind, COALESCE(p.name, ''),
+		COALESCE((SELECT pkg.name FROM symbols pkg WHERE pkg.file_id = s.file_id AND pkg.kind = 'package' LIMIT 1), '')
+		FROM symbols s
+		JOIN files f ON f.id = s.file_id
+		LEFT JOIN symbols p ON p.id = s.parent_symbol_id
+		WHERE f.language = 'protobuf' AND s.kind IN ('message', 'enum')
+		ORDER BY s.id`)
+}
+
+// ProstTypes returns the Rust structs and enums that derive prost's Message
+// or Enumeration, that is, the types prost generates from protobuf messages
+// and enums. prost names each generated file after its protobuf package
+// (foo.bar.rs), so the file's stem stands in for the package.
+func (s *Store) ProstTypes() ([]*SchemaType, error) {
+	types, err := s.querySchemaTypes(`SELECT s.id, s.name, s.kind,
+		COALESCE((SELECT m.name FROM symbols m WHERE m.file_id = s.file_id AND m.kind = 'module'
+			AND m.start_line <= s.start_line AND m.end_line >= s.end_line
+			ORDER BY m.start_line DESC LIMIT 1), ''),
+		f.path
+		FROM symbols s
+		JOIN files f ON f.id = s.file_id
+		WHERE f.language = 'rust' AND s.kind IN ('struct', 'enum')
+		  AND EXISTS (SELECT 1 FROM annotations a WHERE a.target_symbol_id = s.id AND a.name = 'derive'
+		    AND (a.arguments LIKE '%prost::Message%' OR a.arguments LIKE '%prost::Enumeration%'))
+		ORDER BY s.id`)
+	if err != nil {
+		return nil, err
+	}
+	for _, t := range types {
+		t.Package = strings.TrimSuffix(filepath.Base(t.Package), ".rs")
+	}
+	return types, nil
+}
+
+func (s *Store) querySchemaTypes(query string) ([]*SchemaType, error) {
+	rows, err := s.db.Query(query)
+	if err != nil {
+		return nil, fmt.Errorf("schema types: %w", err)
+	}
+	defer rows.Close()
+	var types []*SchemaType
+	for rows.Next() {
+		t := &SchemaType{}
+		if err := rows.Scan(&t.SymbolID, &t.Name, &t.Kind, &t.Parent, &t.Package); err != nil {
+			return nil, fmt.Errorf("scan schema type: %w", err)
+		}
+		types = append(types, t)
+	}
+	return types, rows.Err()
+}
+
+// RetargetResolvedReferences points the resolved references targeting
+// symbol from at symbol to instead, and returns how many it changed.
+func (s *Store) RetargetResolvedReferences(from, to int64) (int, error) {
+	res, err := s.db.Exec("UPDATE resolved_references SET target_symbol_id = ? WHERE target_symbol_id = ?", to, from)
+	if err != nil {
+		return 0, fmt.Errorf("retarget resolved references: %w", err)
+	}
+	n, err := res.RowsAffected()
+	if err != nil {
+		return 0, fmt.Errorf("rows affected: %w", err)
+	}
+	return int(n), nil
+}
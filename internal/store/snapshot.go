@@ -0,0 +1,424 @@
+package store
+
+import (
+	"bufio"
+	"compress/gzip"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"math"
+	"os"
+	"slices"
+	"strings"
+	"time"
+)
+
+// --- Index snapshots ---
+
+// A snapshot is the whole index in a compact, versioned binary form, for
+// shipping an index between machines or keeping it as a CI artifact. It
+// starts with a header:
+//
+//	magic   "CANOPYIX"
+//	version uint16, big-endian (SnapshotVersion)
+//	codec   byte: 1 = gzip
+//
+// followed by the compressed body: for each table, its name, its column
+// names, then its rows, each value tagged with its type. Integers are
+// zigzag varints and strings are length-prefixed, so a snapshot is a
+// fraction of the size of the database file.
+const (
+	snapshotMagic = "CANOPYIX"
+
+	// SnapshotVersion is the snapshot format version WriteSnapshot writes.
+	// LoadSnapshot reads this version and older ones.
+	SnapshotVersion = 1
+
+	snapshotCodecGzip = 1
+)
+
+// Value tags of the snapshot body.
+const (
+	tagNull byte = iota
+	tagInt
+	tagFloat
+	tagText
+	tagBlob
+	tagTime
+)
+
+// sqliteTimeFormat is how the SQLite driver writes time.Time values.
+const sqliteTimeFormat = "2006-01-02 15:04:05.999999999-07:00"
+
+// SnapshotStats summarizes a snapshot written or loaded.
+type SnapshotStats struct {
+	Tables int
+	Rows   int64
+}
+
+// WriteSnapshot writes every table of the index to w as a snapshot.
+func (s *Store) WriteSnapshot(w io.Writer) (*SnapshotStats, error) {
+	header := make([]byte, 0, len(snapshotMagic)+3)
+	header = append(header, snapshotMagic...)
+	header = binary.BigEndian.AppendUint16(header, SnapshotVersion)
+	header = append(header, snapshotCodecGzip)
+	if _, err := w.Write(header); err != nil {
+		return nil, fmt.Errorf("write snapshot: %w", err)
+	}
+
+	zw := gzip.NewWriter(w)
+	bw := bufio.NewWriter(zw)
+	enc := &snapshotEncoder{w: bw}
+	stats := &SnapshotStats{}
+	for _, m := range createTableRe.FindAllStringSubmatch(schemaDDL, -1) {
+		n, err := s.writeSnapshotTable(enc, m[1])
+		if err != nil {
+			return nil, fmt.Errorf("write snapshot: %s: %w", m[1], err)
+		}
+		stats.Tables++
+		stats.Rows += n
+	}
+	enc.string("") // end of tables
+	if enc.err == nil {
+		enc.err = bw.Flush()
+	}
+	if enc.err == nil {
+		enc.err = zw.Close()
+	}
+	if enc.err != nil {
+		return nil, fmt.Errorf("write snapshot: %w", enc.err)
+	}
+	return stats, nil
+}
+
+func (s *Store) writeSnapshotTable(enc *snapshotEncoder, table string) (int64, error) {
+	rows, err := s.db.Query("SELECT * FROM " + table + " ORDER BY rowid")
+	if err != nil {
+		return 0, err
+	}
+	defer rows.Close()
+	cols, err := rows.Columns()
+	if err != nil {
+		return 0, err
+	}
+	enc.string(table)
+	enc.uvarint(uint64(len(cols)))
+	for _, c := range cols {
+		enc.string(c)
+	}
+
+	values := make([]any, len(cols))
+	ptrs := make([]any, len(cols))
+	for i := range values {
+		ptrs[i] = &values[i]
+	}
+	var n int64
+	for rows.Next() {
+		if err := rows.Scan(ptrs...); err != nil {
+			return 0, err
+		}
+		enc.uvarint(1) // row follows
+		for _, v := range values {
+			if err := enc.value(v); err != nil {
+				return 0, err
+			}
+		}
+		n++
+	}
+	enc.uvarint(0) // end of rows
+	return n, rows.Err()
+}
+
+// LoadSnapshot reads a snapshot from r into the store, which must be
+// migrated and empty. Rows keep their ids. Columns the snapshot lacks, as
+// in one written by an older version, get their defaults; tables or
+// columns the schema lacks are an error.
+func (s *Store) LoadSnapshot(r io.Reader) (*SnapshotStats, error) {
+	header := make([]byte, len(snapshotMagic)+3)
+	if _, err := io.ReadFull(r, header); err != nil {
+		return nil, fmt.Errorf("load snapshot: read header: %w", err)
+	}
+	if string(header[:len(snapshotMagic)]) != snapshotMagic {
+		return nil, errors.New("load snapshot: not a canopy index snapshot")
+	}
+	if v := binary.BigEndian.Uint16(header[len(snapshotMagic):]); v > SnapshotVersion {
+		return nil, fmt.Errorf("load snapshot: format version %d is newer than supported version %d", v, SnapshotVersion)
+	}
+	if codec := header[len(header)-1]; codec != snapshotCodecGzip {
+		return nil, fmt.Errorf("load snapshot: unknown compression codec %d", codec)
+	}
+	var files int
+	if err := s.db.QueryRow("SELECT COUNT(*) FROM files").Scan(&files); err != nil {
+		return nil, fmt.Errorf("load snapshot: %w", err)
+	}
+	if files > 0 {
+		return nil, errors.New("load snapshot: database is not empty")
+	}
+
+	zr, err := gzip.NewReader(r)
+	if err != nil {
+		return nil, fmt.Errorf("load snapshot: %w", err)
+	}
+	defer zr.Close()
+	dec := &snapshotDecoder{r: bufio.NewReader(zr)}
+
+	tx, err := s.db.Begin()
+	if err != nil {
+		return nil, fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+	// A row may point at a later row of its own table, such as a symbol at
+	// its parent; check foreign keys once everything is loaded.
+	if _, err := tx.Exec("PRAGMA defer_foreign_keys = ON"); err != nil {
+		return nil, fmt.Errorf("load snapshot: %w", err)
+	}
+	// The snapshot's metadata replaces the empty store's.
+	if _, err := tx.Exec("DELETE FROM metadata"); err != nil {
+		return nil, fmt.Errorf("load snapshot: %w", err)
+	}
+
+	// Table names come from the snapshot and end up in SQL, so only the
+	// schema's own tables are accepted.
+	tables := map[string]bool{}
+	for _, m := range createTableRe.FindAllStringSubmatch(schemaDDL, -1) {
+		tables[m[1]] = true
+	}
+
+	stats := &SnapshotStats{}
+	for {
+		table := dec.string()
+		if dec.err != nil {
+			return nil, fmt.Errorf("load snapshot: %w", dec.err)
+		}
+		if table == "" {
+			break
+		}
+		if !tables[table] {
+			return nil, fmt.Errorf("load snapshot: unknown table %q", table)
+		}
+		known, _, err := tableColumns(tx, table)
+		if err != nil {
+			return nil, fmt.Errorf("load snapshot: %s: %w", table, err)
+		}
+		if len(known) == 0 {
+			return nil, fmt.Errorf("load snapshot: unknown table %q", table)
+		}
+		n := dec.uvarint()
+		if dec.err == nil && n > uint64(len(known)) {
+			dec.fail(fmt.Errorf("%d columns, but the table has %d", n, len(known)))
+		}
+		if dec.err != nil {
+			return nil, fmt.Errorf("load snapshot: %s: %w", table, dec.err)
+		}
+		cols := make([]string, n)
+		for i := range cols {
+			cols[i] = dec.string()
+		}
+		if dec.err != nil {
+			return nil, fmt.Errorf("load snapshot: %s: %w", table, dec.err)
+		}
+		for _, c := range cols {
+			if !slices.Contains(known, c) {
+				return nil, fmt.Errorf("load snapshot: unknown column %s.%s", table, c)
+			}
+		}
+
+		stmt, err := tx.Prepare("INSERT INTO " + table + " (" + strings.Join(cols, ", ") +
+			") VALUES (" + placeholderList(len(cols)) + ")")
+		if err != nil {
+			return nil, fmt.Errorf("load snapshot: %s: %w", table, err)
+		}
+		values := make([]any, len(cols))
+		for dec.uvarint() == 1 {
+			for i := range values {
+				values[i] = dec.value()
+			}
+			if dec.err != nil {
+				break
+			}
+			if _, err := stmt.Exec(values...); err != nil {
+				stmt.Close()
+				return nil, fmt.Errorf("load snapshot: %s: %w", table, err)
+			}
+			stats.Rows++
+		}
+		stmt.Close()
+		if dec.err != nil {
+			return nil, fmt.Errorf("load snapshot: %s: %w", table, dec.err)
+		}
+		stats.Tables++
+	}
+	// Reading to the end checks the gzip checksum.
+	if _, err := dec.r.ReadByte(); err != io.EOF {
+		if err == nil {
+			err = errors.New("trailing data")
+		}
+		return nil, fmt.Errorf("load snapshot: corrupt snapshot: %w", err)
+	}
+
+	if err := tx.Commit(); err != nil {
+		return nil, fmt.Errorf("commit snapshot: %w", err)
+	}
+	return stats, nil
+}
+
+// IsSnapshotFile reports whether the file at path starts like a snapshot,
+// as opposed to, say, a database.
+func IsSnapshotFile(path string) (bool, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return false, err
+	}
+	defer f.Close()
+	magic := make([]byte, len(snapshotMagic))
+	if _, err := io.ReadFull(f, magic); err != nil {
+		return false, nil
+	}
+	return string(magic) == snapshotMagic, nil
+}
+
+// snapshotEncoder writes snapshot body values, keeping the first error.
+type snapshotEncoder struct {
+	w   *bufio.Writer
+	buf [binary.MaxVarintLen64]byte
+	err error
+}
+
+func (e *snapshotEncoder) write(p []byte) {
+	if e.err == nil {
+		_, e.err = e.w.Write(p)
+	}
+}
+
+func (e *snapshotEncoder) uvarint(v uint64) {
+	e.write(e.buf[:binary.PutUvarint(e.buf[:], v)])
+}
+
+func (e *snapshotEncoder) string(v string) {
+	e.uvarint(uint64(len(v)))
+	if e.err == nil {
+		_, e.err = e.w.WriteString(v)
+	}
+}
+
+func (e *snapshotEncoder) value(v any) error {
+	switch v := v.(type) {
+	case nil:
+		e.write([]byte{tagNull})
+	case int64:
+		e.write([]byte{tagInt})
+		e.write(e.buf[:binary.PutVarint(e.buf[:], v)])
+	case float64:
+		e.write([]byte{tagFloat})
+		e.write(binary.BigEndian.AppendUint64(nil, math.Float64bits(v)))
+	case string:
+		e.write([]byte{tagText})
+		e.string(v)
+	case []byte:
+		e.write([]byte{tagBlob})
+		e.string(string(v))
+	case bool:
+		e.write([]byte{tagInt})
+		var n int64
+		if v {
+			n = 1
+		}
+		e.write(e.buf[:binary.PutVarint(e.buf[:], n)])
+	case time.Time:
+		e.write([]byte{tagTime})
+		e.string(v.Format(sqliteTimeFormat))
+	default:
+		return fmt.Errorf("unsupported value type %T", v)
+	}
+	return nil
+}
+
+// snapshotDecoder reads snapshot body values, keeping the first error.
+type snapshotDecoder struct {
+	r   *bufio.Reader
+	err error
+}
+
+func (d *snapshotDecoder) uvarint() uint64 {
+	if d.err != nil {
+		return 0
+	}
+	v, err := binary.ReadUvarint(d.r)
+	d.fail(err)
+	return v
+}
+
+func (d *snapshotDecoder) varint() int64 {
+	if d.err != nil {
+		return 0
+	}
+	v, err := binary.ReadVarint(d.r)
+	d.fail(err)
+	return v
+}
+
+// string reads a length-prefixed string. The body is a stream of unknown
+// size, so rather than allocating the length up front it copies what is
+// there and fails when the data ends first, so a corrupt length cannot
+// exhaust memory.
+func (d *snapshotDecoder) string() string {
+	n := d.uvarint()
+	if d.err != nil {
+		return ""
+	}
+	if n > math.MaxInt64 {
+		d.fail(fmt.Errorf("string length %d out of range", n))
+		return ""
+	}
+	var b strings.Builder
+	if copied, err := io.CopyN(&b, d.r, int64(n)); err != nil {
+		if err == io.EOF {
+			err = io.ErrUnexpectedEOF
+		}
+		d.fail(fmt.Errorf("string of length %d ends after %d bytes: %w", n, copied, err))
+		return ""
+	}
+	return b.String()
+}
+
+func (d *snapshotDecoder) value() any {
+	if d.err != nil {
+		return nil
+	}
+	tag, err := d.r.ReadByte()
+	if d.fail(err) {
+		return nil
+	}
+	switch tag {
+	case tagNull:
+		return nil
+	case tagInt:
+		return d.varint()
+	case tagFloat:
+		var b [8]byte
+		_, err := io.ReadFull(d.r, b[:])
+		d.fail(err)
+		return math.Float64frombits(binary.BigEndian.Uint64(b[:]))
+	case tagText, tagTime:
+		return d.string()
+	case tagBlob:
+		return []byte(d.string())
+	default:
+		d.fail(fmt.Errorf("unknown value tag %d", tag))
+		return nil
+	}
+}
+
+// fail records err, treating a truncated body as corruption, and reports
+// whether there was one.
+func (d *snapshotDecoder) fail(err error) bool {
+	if err == nil || d.err != nil {
+		return d.err != nil
+	}
+	if errors.Is(err, io.EOF) {
+		err = io.ErrUnexpectedEOF
+	}
+	d.err = fmt.Errorf("corrupt snapshot: %w", err)
+	return true
+}
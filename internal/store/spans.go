@@ -0,0 +1,91 @@
+package store
+
+import "fmt"
+
+// --- Span offsets ---
+
+// SpanOffsets holds the byte offsets and UTF-16 columns of one symbol or
+// reference span.
+type SpanOffsets struct {
+	ID            int64
+	StartByte     int
+	EndByte       int
+	UTF16StartCol int
+	UTF16EndCol   int
+}
+
+// UpdateSpanOffsets records byte offsets and UTF-16 columns for the given
+// symbols and references. Runs in one transaction.
+func (s *Store) UpdateSpanOffsets(symbols, refs []SpanOffsets) error {
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	for _, table := range []struct {
+		name  string
+		spans []SpanOffsets
+	}{{"symbols", symbols}, {"references_", refs}} {
+		if len(table.spans) == 0 {
+			continue
+		}
+		stmt, err := tx.Prepare(
+			"UPDATE " + table.name + " SET start_byte = ?, end_byte = ?, utf16_start_col = ?, utf16_end_col = ? WHERE id = ?",
+		)
+		if err != nil {
+			return fmt.Errorf("prepare %s span update: %w", table.name, err)
+		}
+		for _, sp := range table.spans {
+			if _, err := stmt.Exec(sp.StartByte, sp.EndByte, sp.UTF16StartCol, sp.UTF16EndCol, sp.ID); err != nil {
+				stmt.Close()
+				return fmt.Errorf("update %s span: %w", table.name, err)
+			}
+		}
+		stmt.Close()
+	}
+	return tx.Commit()
+}
+
+// --- Syntax errors ---
+
+// InsertSyntaxErrors records the syntax errors of files. Runs in one
+// transaction.
+func (s *Store) InsertSyntaxErrors(errs []SyntaxError) error {
+	if len(errs) == 0 {
+		return nil
+	}
+	tx, err := s.db.Begin()
+	if err != nil {
+		return fmt.Errorf("begin transaction: %w", err)
+	}
+	defer tx.Rollback()
+
+	stmt, err := tx.Prepare("INSERT INTO syntax_errors (file_id, start_byte, end_byte) VALUES (?, ?, ?)")
+	if err != nil {
+		return fmt.Errorf("prepare syntax error insert: %w", err)
+	}
+	defer stmt.Close()
+	for _, se := range errs {
+		if _, err := stmt.Exec(se.FileID, se.StartByte, se.EndByte); err != nil {
+			return fmt.Errorf("insert syntax error: %w", err)
+		}
+	}
+	return tx.Commit()
+}
+
+// --- Degraded files ---
+
+// SetFileDegraded records that a file was indexed with the fallback scanner,
+// for reason, which found symbols declarations extraction did not.
+func (s *Store) SetFileDegraded(fileID int64, reason string, symbols int) error {
+	_, err := s.db.Exec(
+		`INSERT INTO degraded_files (file_id, reason, symbols) VALUES (?, ?, ?)
+		 ON CONFLICT(file_id) DO UPDATE SET reason = excluded.reason, symbols = excluded.symbols`,
+		fileID, reason, symbols,
+	)
+	if err != nil {
+		return fmt.Errorf("set file degraded: %w", err)
+	}
+	return nil
+}
@@ -4,17 +4,31 @@ import (
 	"database/sql"
 	"fmt"
 
-	_ "github.com/mattn/go-sqlite3"
+	"github.com/mattn/go-sqlite3"
 )
 
-// Store is the SQLite data access layer for canopy's 17 tables.
+// driverName is the SQLite driver with canopy's SQL functions:
+// cfg_is_test(arguments) is CfgIsTest, for matching #[cfg(test)] in
+// queries however the predicate is written.
+const driverName = "sqlite3_canopy"
+
+func init() {
+	sql.Register(driverName, &sqlite3.SQLiteDriver{
+		ConnectHook: func(conn *sqlite3.SQLiteConn) error {
+			return conn.RegisterFunc("cfg_is_test", CfgIsTest, true)
+		},
+	})
+}
+
+// Store is the SQLite data access layer for canopy's 18 tables.
 type Store struct {
-	db *sql.DB
+	db     *sql.DB
+	shared bool // Close leaves db open; see Shared
 }
 
 // NewStore opens a SQLite database at dbPath with WAL mode enabled.
 func NewStore(dbPath string) (*Store, error) {
-	db, err := sql.Open("sqlite3", dbPath+"?_journal_mode=WAL&_foreign_keys=ON&_busy_timeout=30000")
+	db, err := sql.Open(driverName, dbPath+"?_journal_mode=WAL&_foreign_keys=ON&_busy_timeout=30000")
 	if err != nil {
 		return nil, fmt.Errorf("open database: %w", err)
 	}
@@ -25,17 +39,37 @@ func NewStore(dbPath string) (*Store, error) {
 	return &Store{db: db}, nil
 }
 
-// Close closes the underlying database connection.
+// Close closes the underlying database connection, unless s came from
+// Shared.
 func (s *Store) Close() error {
+	if s.shared {
+		return nil
+	}
 	return s.db.Close()
 }
 
+// Shared returns a Store over the same connection whose Close does
+// nothing, for handing to code that closes its store when done while the
+// owner keeps using the connection.
+func (s *Store) Shared() *Store {
+	return &Store{db: s.db, shared: true}
+}
+
 // DB returns the underlying *sql.DB for use in transactions.
 func (s *Store) DB() *sql.DB {
 	return s.db
 }
 
-// Migrate creates all 17 tables and indexes. Idempotent.
+// CopyTo writes a consistent copy of the database to path, which must not
+// exist. The copy is a standalone database, WAL contents included.
+func (s *Store) CopyTo(path string) error {
+	if _, err := s.db.Exec("VACUUM INTO ?", path); err != nil {
+		return fmt.Errorf("copy database: %w", err)
+	}
+	return nil
+}
+
+// Migrate creates all 18 tables and indexes. Idempotent.
 func (s *Store) Migrate() error {
 	_, err := s.db.Exec(schemaDDL)
 	if err != nil {
@@ -43,6 +77,23 @@ func (s *Store) Migrate() error {
 	}
 	// Idempotent column additions for existing databases.
 	s.db.Exec("ALTER TABLE files ADD COLUMN line_count INTEGER")
+	s.db.Exec("ALTER TABLE files ADD COLUMN root TEXT")
+	for _, table := range []string{"symbols", "references_"} {
+		for _, col := range []string{"start_byte", "end_byte", "utf16_start_col", "utf16_end_col"} {
+			s.db.Exec("ALTER TABLE " + table + " ADD COLUMN " + col + " INTEGER NOT NULL DEFAULT 0")
+		}
+	}
+	s.db.Exec("ALTER TABLE symbols ADD COLUMN pagerank REAL NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE symbols ADD COLUMN betweenness REAL NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE symbols ADD COLUMN subkind TEXT NOT NULL DEFAULT ''")
+	s.db.Exec("ALTER TABLE references_ ADD COLUMN receiver_type TEXT NOT NULL DEFAULT ''")
+	s.db.Exec("ALTER TABLE call_graph ADD COLUMN confidence REAL NOT NULL DEFAULT 1.0")
+	s.db.Exec("ALTER TABLE references_ ADD COLUMN awaited INTEGER NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE call_graph ADD COLUMN awaited INTEGER NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE references_ ADD COLUMN spawned INTEGER NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE call_graph ADD COLUMN kind TEXT NOT NULL DEFAULT ''")
+	s.db.Exec("ALTER TABLE references_ ADD COLUMN propagated INTEGER NOT NULL DEFAULT 0")
+	s.db.Exec("ALTER TABLE call_graph ADD COLUMN propagated INTEGER NOT NULL DEFAULT 0")
 	return nil
 }
 
@@ -55,7 +106,8 @@ CREATE TABLE IF NOT EXISTS files (
   language        TEXT NOT NULL,
   hash            TEXT,
   line_count      INTEGER,
-  last_indexed    TIMESTAMP
+  last_indexed    TIMESTAMP,
+  root            TEXT
 );
 
 CREATE TABLE IF NOT EXISTS symbols (
@@ -70,7 +122,14 @@ CREATE TABLE IF NOT EXISTS symbols (
   start_col       INTEGER,
   end_line        INTEGER,
   end_col         INTEGER,
-  parent_symbol_id INTEGER REFERENCES symbols(id)
+  parent_symbol_id INTEGER REFERENCES symbols(id),
+  start_byte      INTEGER NOT NULL DEFAULT 0,
+  end_byte        INTEGER NOT NULL DEFAULT 0,
+  utf16_start_col INTEGER NOT NULL DEFAULT 0,
+  utf16_end_col   INTEGER NOT NULL DEFAULT 0,
+  pagerank        REAL NOT NULL DEFAULT 0,
+  betweenness     REAL NOT NULL DEFAULT 0,
+  subkind         TEXT NOT NULL DEFAULT ''
 );
 
 CREATE TABLE IF NOT EXISTS symbol_fragments (
@@ -105,7 +164,15 @@ CREATE TABLE IF NOT EXISTS references_ (
   start_col       INTEGER,
   end_line        INTEGER,
   end_col         INTEGER,
-  context         TEXT
+  context         TEXT,
+  start_byte      INTEGER NOT NULL DEFAULT 0,
+  end_byte        INTEGER NOT NULL DEFAULT 0,
+  utf16_start_col INTEGER NOT NULL DEFAULT 0,
+  utf16_end_col   INTEGER NOT NULL DEFAULT 0,
+  receiver_type   TEXT NOT NULL DEFAULT '',
+  awaited         INTEGER NOT NULL DEFAULT 0,
+  spawned         INTEGER NOT NULL DEFAULT 0,
+  propagated      INTEGER NOT NULL DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS imports (
@@ -149,6 +216,15 @@ CREATE TABLE IF NOT EXISTS type_parameters (
   constraints     TEXT
 );
 
+CREATE TABLE IF NOT EXISTS type_bounds (
+  id              INTEGER PRIMARY KEY,
+  symbol_id       INTEGER NOT NULL REFERENCES symbols(id),
+  subject         TEXT NOT NULL,
+  bound           TEXT NOT NULL,
+  name            TEXT NOT NULL,
+  in_where_clause BOOLEAN DEFAULT FALSE
+);
+
 CREATE TABLE IF NOT EXISTS annotations (
   id              INTEGER PRIMARY KEY,
   target_symbol_id INTEGER NOT NULL REFERENCES symbols(id),
@@ -160,6 +236,62 @@ CREATE TABLE IF NOT EXISTS annotations (
   col             INTEGER
 );
 
+CREATE TABLE IF NOT EXISTS function_metrics (
+  id              INTEGER PRIMARY KEY,
+  symbol_id       INTEGER NOT NULL REFERENCES symbols(id),
+  cyclomatic      INTEGER NOT NULL DEFAULT 1,
+  cognitive       INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS flagged_sites (
+  id              INTEGER PRIMARY KEY,
+  symbol_id       INTEGER NOT NULL REFERENCES symbols(id),
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  kind            TEXT NOT NULL,
+  name            TEXT NOT NULL,
+  start_line      INTEGER NOT NULL,
+  start_col       INTEGER NOT NULL,
+  end_line        INTEGER NOT NULL,
+  end_col         INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS function_fingerprints (
+  id              INTEGER PRIMARY KEY,
+  symbol_id       INTEGER NOT NULL REFERENCES symbols(id),
+  statements      TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS syntax_errors (
+  id              INTEGER PRIMARY KEY,
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  start_byte      INTEGER NOT NULL,
+  end_byte        INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS impl_blocks (
+  id               INTEGER PRIMARY KEY,
+  file_id          INTEGER NOT NULL REFERENCES files(id),
+  start_line       INTEGER NOT NULL,
+  start_col        INTEGER NOT NULL,
+  end_line         INTEGER NOT NULL,
+  end_col          INTEGER NOT NULL,
+  start_byte       INTEGER NOT NULL,
+  end_byte         INTEGER NOT NULL,
+  utf16_start_col  INTEGER NOT NULL,
+  utf16_end_col    INTEGER NOT NULL,
+  header           TEXT NOT NULL,
+  trait            TEXT NOT NULL,
+  trait_start_byte INTEGER NOT NULL,
+  trait_end_byte   INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS degraded_files (
+  id              INTEGER PRIMARY KEY,
+  file_id         INTEGER NOT NULL UNIQUE REFERENCES files(id),
+  reason          TEXT NOT NULL,
+  symbols         INTEGER NOT NULL
+);
+
 -- Resolution tables
 
 CREATE TABLE IF NOT EXISTS resolved_references (
@@ -185,7 +317,11 @@ CREATE TABLE IF NOT EXISTS call_graph (
   callee_symbol_id INTEGER NOT NULL REFERENCES symbols(id),
   file_id         INTEGER REFERENCES files(id),
   line            INTEGER,
-  col             INTEGER
+  col             INTEGER,
+  confidence      REAL NOT NULL DEFAULT 1.0,
+  awaited         INTEGER NOT NULL DEFAULT 0,
+  kind            TEXT NOT NULL DEFAULT '',
+  propagated      INTEGER NOT NULL DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS reexports (
@@ -195,6 +331,15 @@ CREATE TABLE IF NOT EXISTS reexports (
   exported_name   TEXT NOT NULL
 );
 
+CREATE TABLE IF NOT EXISTS import_aliases (
+  id              INTEGER PRIMARY KEY,
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  alias           TEXT NOT NULL,
+  imported_name   TEXT NOT NULL,
+  source          TEXT NOT NULL,
+  target_symbol_id INTEGER NOT NULL REFERENCES symbols(id)
+);
+
 CREATE TABLE IF NOT EXISTS extension_bindings (
   id              INTEGER PRIMARY KEY,
   member_symbol_id INTEGER NOT NULL REFERENCES symbols(id),
@@ -212,6 +357,67 @@ CREATE TABLE IF NOT EXISTS type_compositions (
   composition_kind TEXT NOT NULL
 );
 
+-- Overlays
+
+CREATE TABLE IF NOT EXISTS file_owners (
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  owner           TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS build_targets (
+  root            TEXT NOT NULL,
+  label           TEXT NOT NULL,
+  kind            TEXT NOT NULL,
+  PRIMARY KEY (root, label)
+);
+
+CREATE TABLE IF NOT EXISTS build_target_deps (
+  root            TEXT NOT NULL,
+  label           TEXT NOT NULL,
+  dep             TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS file_build_targets (
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  root            TEXT NOT NULL,
+  label           TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_history (
+  symbol_id       INTEGER PRIMARY KEY REFERENCES symbols(id),
+  commit_hash     TEXT NOT NULL,
+  author          TEXT NOT NULL,
+  author_email    TEXT,
+  date            INTEGER NOT NULL,
+  authors         INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS file_authors (
+  file_id         INTEGER NOT NULL REFERENCES files(id),
+  author          TEXT NOT NULL,
+  lines           INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_samples (
+  symbol_id       INTEGER PRIMARY KEY REFERENCES symbols(id),
+  self_samples    INTEGER NOT NULL,
+  total_samples   INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_coverage (
+  symbol_id       INTEGER PRIMARY KEY REFERENCES symbols(id),
+  lines           INTEGER NOT NULL,
+  covered_lines   INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS symbol_embeddings (
+  symbol_id       INTEGER NOT NULL REFERENCES symbols(id),
+  model           TEXT NOT NULL,
+  text_hash       TEXT NOT NULL,
+  vector          BLOB NOT NULL,
+  PRIMARY KEY (symbol_id, model)
+);
+
 -- Metadata
 
 CREATE TABLE IF NOT EXISTS metadata (
@@ -237,7 +443,15 @@ CREATE INDEX IF NOT EXISTS idx_imports_source ON imports(source);
 CREATE INDEX IF NOT EXISTS idx_type_members_symbol ON type_members(symbol_id);
 CREATE INDEX IF NOT EXISTS idx_function_params_symbol ON function_parameters(symbol_id);
 CREATE INDEX IF NOT EXISTS idx_type_params_symbol ON type_parameters(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_type_bounds_symbol ON type_bounds(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_type_bounds_name ON type_bounds(name);
 CREATE INDEX IF NOT EXISTS idx_annotations_target ON annotations(target_symbol_id);
+CREATE INDEX IF NOT EXISTS idx_function_metrics_symbol ON function_metrics(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_flagged_sites_symbol ON flagged_sites(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_flagged_sites_kind ON flagged_sites(kind);
+CREATE INDEX IF NOT EXISTS idx_function_fingerprints_symbol ON function_fingerprints(symbol_id);
+CREATE INDEX IF NOT EXISTS idx_syntax_errors_file ON syntax_errors(file_id);
+CREATE INDEX IF NOT EXISTS idx_impl_blocks_file ON impl_blocks(file_id);
 CREATE INDEX IF NOT EXISTS idx_symbol_fragments_symbol ON symbol_fragments(symbol_id);
 CREATE INDEX IF NOT EXISTS idx_resolved_refs_reference ON resolved_references(reference_id);
 CREATE INDEX IF NOT EXISTS idx_resolved_refs_target ON resolved_references(target_symbol_id);
@@ -247,10 +461,18 @@ CREATE INDEX IF NOT EXISTS idx_call_graph_caller ON call_graph(caller_symbol_id)
 CREATE INDEX IF NOT EXISTS idx_call_graph_callee ON call_graph(callee_symbol_id);
 CREATE INDEX IF NOT EXISTS idx_reexports_file ON reexports(file_id);
 CREATE INDEX IF NOT EXISTS idx_reexports_original ON reexports(original_symbol_id);
+CREATE INDEX IF NOT EXISTS idx_import_aliases_file ON import_aliases(file_id);
+CREATE INDEX IF NOT EXISTS idx_import_aliases_target ON import_aliases(target_symbol_id);
 CREATE INDEX IF NOT EXISTS idx_extension_bindings_member ON extension_bindings(member_symbol_id);
 CREATE INDEX IF NOT EXISTS idx_extension_bindings_type ON extension_bindings(extended_type_symbol_id);
 CREATE INDEX IF NOT EXISTS idx_type_compositions_composite ON type_compositions(composite_symbol_id);
 CREATE INDEX IF NOT EXISTS idx_type_compositions_component ON type_compositions(component_symbol_id);
+CREATE INDEX IF NOT EXISTS idx_file_owners_file ON file_owners(file_id);
+CREATE INDEX IF NOT EXISTS idx_file_owners_owner ON file_owners(owner);
+CREATE INDEX IF NOT EXISTS idx_build_target_deps_dep ON build_target_deps(root, dep);
+CREATE INDEX IF NOT EXISTS idx_file_build_targets_file ON file_build_targets(file_id);
+CREATE INDEX IF NOT EXISTS idx_symbol_history_date ON symbol_history(date);
+CREATE INDEX IF NOT EXISTS idx_file_authors_file ON file_authors(file_id);
 `
 
 // GetMetadata returns the value for a metadata key, or empty string if not found.
@@ -325,6 +547,7 @@ func (s *Store) DeleteFileData(fileID int64) error {
 			"DELETE FROM type_compositions WHERE composite_symbol_id IN (" + placeholders + ") OR component_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM extension_bindings WHERE member_symbol_id IN (" + placeholders + ") OR extended_type_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM reexports WHERE original_symbol_id IN (" + placeholders + ")",
+			"DELETE FROM import_aliases WHERE target_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM call_graph WHERE caller_symbol_id IN (" + placeholders + ") OR callee_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM implementations WHERE type_symbol_id IN (" + placeholders + ") OR interface_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM resolved_references WHERE target_symbol_id IN (" + placeholders + ")",
@@ -353,6 +576,7 @@ func (s *Store) DeleteFileData(fileID int64) error {
 	// Delete resolution tables referencing this file directly.
 	for _, q := range []string{
 		"DELETE FROM reexports WHERE file_id = ?",
+		"DELETE FROM import_aliases WHERE file_id = ?",
 		"DELETE FROM call_graph WHERE file_id = ?",
 		"DELETE FROM implementations WHERE file_id = ?",
 	} {
@@ -368,6 +592,14 @@ func (s *Store) DeleteFileData(fileID int64) error {
 		for _, q := range []string{
 			"DELETE FROM annotations WHERE target_symbol_id IN (" + placeholders + ")",
 			"DELETE FROM type_parameters WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM type_bounds WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM function_metrics WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM flagged_sites WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM function_fingerprints WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM symbol_history WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM symbol_samples WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM symbol_coverage WHERE symbol_id IN (" + placeholders + ")",
+			"DELETE FROM symbol_embeddings WHERE symbol_id IN (" + placeholders + ")",
 			"DELETE FROM function_parameters WHERE symbol_id IN (" + placeholders + ")",
 			"DELETE FROM type_members WHERE symbol_id IN (" + placeholders + ")",
 			"DELETE FROM symbol_fragments WHERE symbol_id IN (" + placeholders + ")",
@@ -385,6 +617,12 @@ func (s *Store) DeleteFileData(fileID int64) error {
 
 	// Delete extraction tables for this file.
 	for _, q := range []string{
+		"DELETE FROM file_owners WHERE file_id = ?",
+		"DELETE FROM file_build_targets WHERE file_id = ?",
+		"DELETE FROM file_authors WHERE file_id = ?",
+		"DELETE FROM syntax_errors WHERE file_id = ?",
+		"DELETE FROM impl_blocks WHERE file_id = ?",
+		"DELETE FROM degraded_files WHERE file_id = ?",
 		"DELETE FROM references_ WHERE file_id = ?",
 		"DELETE FROM scopes WHERE file_id = ?",
 		"DELETE FROM imports WHERE file_id = ?",
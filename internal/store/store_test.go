@@ -1,6 +1,9 @@
 package store
 
 import (
+	"bytes"
+	"compress/gzip"
+	"encoding/binary"
 	"path/filepath"
 	"testing"
 	"time"
@@ -58,9 +61,13 @@ func TestMigrate_AllTablesExist(t *testing.T) {
 
 	expectedTables := []string{
 		"files", "symbols", "symbol_fragments", "scopes", "references_",
-		"imports", "type_members", "function_parameters", "type_parameters", "annotations",
-		"resolved_references", "implementations", "call_graph", "reexports",
+		"imports", "type_members", "function_parameters", "type_parameters", "type_bounds", "annotations",
+		"function_metrics", "function_fingerprints", "impl_blocks", "degraded_files",
+		"resolved_references", "implementations", "call_graph", "reexports", "import_aliases",
 		"extension_bindings", "type_compositions",
+		"file_owners", "build_targets", "build_target_deps", "file_build_targets",
+		"symbol_history", "file_authors", "symbol_samples", "symbol_coverage",
+		"symbol_embeddings",
 	}
 
 	for _, table := range expectedTables {
@@ -89,6 +96,37 @@ func TestMigrate_WALMode(t *testing.T) {
 	assert.Equal(t, "wal", mode)
 }
 
+func TestStore_SharedCloseKeepsConnectionOpen(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	shared := s.Shared()
+	require.NoError(t, shared.Close())
+	require.NoError(t, s.db.Ping())
+	_, err := shared.FilesByLanguage("rust")
+	require.NoError(t, err)
+}
+
+func TestStore_CopyTo(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	_, err := s.InsertFile(&File{Path: "/src/main.go", Language: "go"})
+	require.NoError(t, err)
+
+	path := filepath.Join(t.TempDir(), "copy.db")
+	require.NoError(t, s.CopyTo(path))
+	_, err = s.InsertFile(&File{Path: "/src/later.go", Language: "go"})
+	require.NoError(t, err)
+
+	c, err := NewStore(path)
+	require.NoError(t, err)
+	defer c.Close()
+	files, err := c.AllFiles()
+	require.NoError(t, err)
+	assert.Len(t, files, 1, "the copy does not see later writes")
+
+	assert.Error(t, s.CopyTo(path), "an existing file is not overwritten")
+}
+
 // =============================================================================
 // File operations
 // =============================================================================
@@ -136,6 +174,29 @@ func TestFile_ByLanguage(t *testing.T) {
 	assert.Len(t, pyFiles, 1)
 }
 
+func TestFile_SetFileRootBackfillsOnlyUnclaimedFiles(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	insertTestFile(t, s, "/ws/a/main.go", "go")
+	_, err := s.InsertFile(&File{Path: "/ws/a/inner/lib.go", Language: "go", Root: "/ws/a/inner"})
+	require.NoError(t, err)
+	insertTestFile(t, s, "/ws/b/main.go", "go")
+
+	require.NoError(t, s.SetFileRoot("/ws/a", "/ws/a/"))
+
+	got, err := s.FileByPath("/ws/a/main.go")
+	require.NoError(t, err)
+	assert.Equal(t, "/ws/a", got.Root)
+
+	got, err = s.FileByPath("/ws/a/inner/lib.go")
+	require.NoError(t, err)
+	assert.Equal(t, "/ws/a/inner", got.Root, "nested root must not be overwritten")
+
+	got, err = s.FileByPath("/ws/b/main.go")
+	require.NoError(t, err)
+	assert.Empty(t, got.Root)
+}
+
 // =============================================================================
 // Symbol operations
 // =============================================================================
@@ -189,6 +250,27 @@ func TestSymbol_QueryByKind(t *testing.T) {
 	assert.Equal(t, "MyStruct", syms[0].Name)
 }
 
+func TestSymbol_NamesWithPrefix(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/main.go", "go")
+	for _, name := range []string{"parse_args", "parse", "Parser", "parse_args", "par*", "print"} {
+		insertTestSymbol(t, s, &f.ID, name, "function")
+	}
+
+	names, err := s.SymbolNamesWithPrefix("par", 10)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"par*", "parse", "parse_args"}, names)
+
+	names, err = s.SymbolNamesWithPrefix("par", 2)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"par*", "parse"}, names)
+
+	names, err = s.SymbolNamesWithPrefix("par*", 10)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"par*"}, names)
+}
+
 func TestSymbol_Children(t *testing.T) {
 	t.Parallel()
 	s := newTestStore(t)
@@ -310,11 +392,18 @@ func TestReference_ByName(t *testing.T) {
 	f := insertTestFile(t, s, "/main.go", "go")
 	s.InsertReference(&Reference{FileID: f.ID, Name: "Foo", StartLine: 0, EndLine: 0, Context: "call"})
 	s.InsertReference(&Reference{FileID: f.ID, Name: "Bar", StartLine: 1, EndLine: 1, Context: "type_annotation"})
+	s.InsertReference(&Reference{FileID: f.ID, Name: "add", StartLine: 2, EndLine: 2, Context: "call", ReceiverType: "Point"})
 
 	refs, err := s.ReferencesByName("Foo")
 	require.NoError(t, err)
 	require.Len(t, refs, 1)
 	assert.Equal(t, "call", refs[0].Context)
+	assert.Equal(t, "", refs[0].ReceiverType)
+
+	refs, err = s.ReferencesByName("add")
+	require.NoError(t, err)
+	require.Len(t, refs, 1)
+	assert.Equal(t, "Point", refs[0].ReceiverType)
 }
 
 func TestReference_InScope(t *testing.T) {
@@ -482,6 +571,33 @@ func TestAnnotation_InsertAndQuery(t *testing.T) {
 	assert.Equal(t, `{"since":"v2"}`, got[0].Arguments)
 }
 
+// =============================================================================
+// FlaggedSite operations
+// =============================================================================
+
+func TestFlaggedSite_InsertAndQuery(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/lib.rs", "rust")
+	sym := insertTestSymbol(t, s, &f.ID, "parse", "function")
+
+	for _, site := range []*FlaggedSite{
+		{SymbolID: sym.ID, FileID: f.ID, Kind: "index", Name: "bytes[0]", StartLine: 4, StartCol: 8, EndLine: 4, EndCol: 16},
+		{SymbolID: sym.ID, FileID: f.ID, Kind: "unwrap", Name: "unwrap", StartLine: 2, StartCol: 20, EndLine: 2, EndCol: 26},
+	} {
+		id, err := s.InsertFlaggedSite(site)
+		require.NoError(t, err)
+		require.Positive(t, id)
+	}
+
+	got, err := s.FlaggedSitesBySymbol(sym.ID)
+	require.NoError(t, err)
+	require.Len(t, got, 2)
+	assert.Equal(t, "unwrap", got[0].Kind, "sites come back in source order")
+	assert.Equal(t, "bytes[0]", got[1].Name)
+	assert.Equal(t, f.ID, got[1].FileID)
+}
+
 // =============================================================================
 // SymbolFragment operations
 // =============================================================================
@@ -578,6 +694,70 @@ func TestImplementation_InsertAndQuery(t *testing.T) {
 	assert.Equal(t, typ.ID, byIface[0].TypeSymbolID)
 }
 
+func TestImplBlocks_InsertAndQuery(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/src/lib.rs", "rust")
+
+	require.NoError(t, s.InsertImplBlocks([]ImplBlock{
+		{FileID: f.ID, StartLine: 8, EndLine: 12, StartByte: 90, EndByte: 140, Header: "impl Drawable for Circle", Trait: "Drawable", TraitStartByte: 95, TraitEndByte: 103},
+		{FileID: f.ID, StartLine: 2, EndLine: 6, StartByte: 20, EndByte: 80, Header: "impl Circle"},
+	}))
+
+	blocks, err := s.ImplBlocksByFile(f.ID)
+	require.NoError(t, err)
+	require.Len(t, blocks, 2)
+	assert.Equal(t, "impl Circle", blocks[0].Header)
+	assert.Empty(t, blocks[0].Trait)
+	assert.Equal(t, "Drawable", blocks[1].Trait)
+	assert.Equal(t, 95, blocks[1].TraitStartByte)
+	assert.Equal(t, 103, blocks[1].TraitEndByte)
+	assert.Equal(t, 140, blocks[1].EndByte)
+}
+
+func TestCanonicalCfg(t *testing.T) {
+	t.Parallel()
+	for _, tc := range []struct {
+		pred, want string
+	}{
+		{"test", "test"},
+		{"  test ", "test"},
+		{`feature="serde"`, `feature = "serde"`},
+		{"all(unix, test)", "all(test, unix)"},
+		{"all( test ,unix, )", "all(test, unix)"},
+		{`any(feature = "b", feature = "a", feature="b")`, `any(feature = "a", feature = "b")`},
+		{"any(test)", "test"},
+		{"not( any(windows, unix) )", "not(any(unix, windows))"},
+		{"all(any(b, a), c)", "all(any(a, b), c)"},
+		{"all(test,", "all(test,"},
+		{"all(  test", "all( test"},
+	} {
+		assert.Equal(t, tc.want, CanonicalCfg(tc.pred), tc.pred)
+	}
+}
+
+func TestCfgIsTest_SQLFunction(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	for _, tc := range []struct {
+		args string
+		want bool
+	}{
+		{"(test)", true},
+		{"( test )", true},
+		{"(any(test))", true},
+		{"(all(test, test))", true},
+		{"(all(test, unix))", false},
+		{"(not(test))", false},
+		{"test", false},
+		{"", false},
+	} {
+		var got bool
+		require.NoError(t, s.DB().QueryRow("SELECT cfg_is_test(?)", tc.args).Scan(&got))
+		assert.Equal(t, tc.want, got, tc.args)
+	}
+}
+
 func TestCallEdge_InsertAndQuery(t *testing.T) {
 	t.Parallel()
 	s := newTestStore(t)
@@ -587,7 +767,7 @@ func TestCallEdge_InsertAndQuery(t *testing.T) {
 
 	edge := &CallEdge{
 		CallerSymbolID: caller.ID, CalleeSymbolID: callee.ID,
-		FileID: &f.ID, Line: 14, Col: 3,
+		FileID: &f.ID, Line: 14, Col: 3, Confidence: 0.8,
 	}
 	id, err := s.InsertCallEdge(edge)
 	require.NoError(t, err)
@@ -597,6 +777,7 @@ func TestCallEdge_InsertAndQuery(t *testing.T) {
 	require.NoError(t, err)
 	require.Len(t, callers, 1)
 	assert.Equal(t, caller.ID, callers[0].CallerSymbolID)
+	assert.Equal(t, 0.8, callers[0].Confidence)
 
 	callees, err := s.CalleesByCaller(caller.ID)
 	require.NoError(t, err)
@@ -621,6 +802,36 @@ func TestReexport_InsertAndQuery(t *testing.T) {
 	assert.Equal(t, "Component", got[0].ExportedName)
 }
 
+func TestRebuildImportAliases(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	lib := insertTestFile(t, s, "/src/shapes.rs", "rust")
+	circle := insertTestSymbol(t, s, &lib.ID, "Circle", "struct")
+	f := insertTestFile(t, s, "/src/main.rs", "rust")
+	name, alias := "Circle", "Round"
+	_, err := s.InsertImport(&Import{FileID: f.ID, Source: "crate::shapes::Circle", ImportedName: &name, LocalAlias: &alias, Kind: "module", Scope: "file"})
+	require.NoError(t, err)
+	ref := &Reference{FileID: f.ID, Name: "Round", StartLine: 4, EndLine: 4, Context: "call"}
+	_, err = s.InsertReference(ref)
+	require.NoError(t, err)
+	_, err = s.InsertResolvedReference(&ResolvedReference{ReferenceID: ref.ID, TargetSymbolID: circle.ID, Confidence: 0.9, ResolutionKind: "import"})
+	require.NoError(t, err)
+
+	// Rebuilding twice records each alias once.
+	for range 2 {
+		n, err := s.RebuildImportAliases()
+		require.NoError(t, err)
+		assert.Equal(t, 1, n)
+	}
+	got, err := s.ImportAliasesByTarget(circle.ID)
+	require.NoError(t, err)
+	require.Len(t, got, 1)
+	assert.Equal(t, f.ID, got[0].FileID)
+	assert.Equal(t, "Round", got[0].Alias)
+	assert.Equal(t, "Circle", got[0].ImportedName)
+	assert.Equal(t, "crate::shapes::Circle", got[0].Source)
+}
+
 func TestExtensionBinding_InsertAndQuery(t *testing.T) {
 	t.Parallel()
 	s := newTestStore(t)
@@ -689,6 +900,9 @@ func TestDeleteFileData(t *testing.T) {
 	s.InsertTypeParam(&TypeParam{SymbolID: sym.ID, Name: "T", Ordinal: 0, ParamKind: "type"})
 	s.InsertAnnotation(&Annotation{TargetSymbolID: sym.ID, Name: "Test", FileID: &f.ID})
 	s.InsertSymbolFragment(&SymbolFragment{SymbolID: sym.ID, FileID: f.ID, StartLine: 0, EndLine: 9, IsPrimary: true})
+	require.NoError(t, s.InsertSyntaxErrors([]SyntaxError{{FileID: f.ID, StartByte: 3, EndByte: 7}}))
+	require.NoError(t, s.InsertImplBlocks([]ImplBlock{{FileID: f.ID, StartLine: 2, EndLine: 4, Header: "impl Foo"}}))
+	require.NoError(t, s.SetFileDegraded(f.ID, "syntax_errors", 2))
 
 	// Populate resolution data.
 	ref := &Reference{FileID: f.ID, Name: "Baz", StartLine: 14, EndLine: 14}
@@ -714,6 +928,17 @@ func TestDeleteFileData(t *testing.T) {
 
 	imports, _ := s.ImportsByFile(f.ID)
 	assert.Empty(t, imports)
+
+	var syntaxErrors int
+	require.NoError(t, s.DB().QueryRow("SELECT COUNT(*) FROM syntax_errors WHERE file_id = ?", f.ID).Scan(&syntaxErrors))
+	assert.Zero(t, syntaxErrors)
+
+	implBlocks, _ := s.ImplBlocksByFile(f.ID)
+	assert.Empty(t, implBlocks)
+
+	var degraded int
+	require.NoError(t, s.DB().QueryRow("SELECT COUNT(*) FROM degraded_files WHERE file_id = ?", f.ID).Scan(&degraded))
+	assert.Zero(t, degraded)
 }
 
 func TestDeleteFileData_ReindexWithNewData(t *testing.T) {
@@ -789,6 +1014,83 @@ func TestSignatureHash_AddFunctionParam(t *testing.T) {
 	assert.NotEqual(t, h1, h2)
 }
 
+func TestSignatureHash_ElidedLifetimes(t *testing.T) {
+	t.Parallel()
+	hash := func(params []*FunctionParam, tps []*TypeParam) string {
+		return ComputeSignatureHash("first_word", "function", "public", nil, nil, params, tps)
+	}
+	elided := hash([]*FunctionParam{
+		{Name: "s", Ordinal: 0, TypeExpr: "&str"},
+		{Ordinal: 0, TypeExpr: "&str", IsReturn: true},
+	}, nil)
+	explicit := hash([]*FunctionParam{
+		{Name: "s", Ordinal: 0, TypeExpr: "&'a str"},
+		{Ordinal: 0, TypeExpr: "&'a str", IsReturn: true},
+	}, []*TypeParam{{Name: "'a", Ordinal: 0, ParamKind: "lifetime"}})
+	anonymous := hash([]*FunctionParam{
+		{Name: "s", Ordinal: 0, TypeExpr: "&'_ str"},
+		{Ordinal: 0, TypeExpr: "&str", IsReturn: true},
+	}, nil)
+	assert.Equal(t, elided, explicit, "fn f<'a>(s: &'a str) -> &'a str is fn f(s: &str) -> &str")
+	assert.Equal(t, elided, anonymous)
+
+	// With two inputs the output lifetime cannot be elided.
+	twoInputs := func(lt string) string {
+		return hash([]*FunctionParam{
+			{Name: "x", Ordinal: 0, TypeExpr: "&" + lt + " str"},
+			{Name: "y", Ordinal: 1, TypeExpr: "&str"},
+			{Ordinal: 0, TypeExpr: "&" + lt + " str", IsReturn: true},
+		}, []*TypeParam{{Name: lt, Ordinal: 0, ParamKind: "lifetime"}})
+	}
+	assert.Equal(t, twoInputs("'a"), twoInputs("'b"), "renaming a lifetime is not a change")
+	assert.NotEqual(t, elided, twoInputs("'a"))
+
+	// Receiver lifetimes flow to the output.
+	method := func(receiver string, tps []*TypeParam) string {
+		return ComputeSignatureHash("name", "method", "public", nil, nil, []*FunctionParam{
+			{Name: "self", Ordinal: 0, TypeExpr: receiver, IsReceiver: true},
+			{Name: "key", Ordinal: 1, TypeExpr: "&str"},
+			{Ordinal: 0, TypeExpr: "&str", IsReturn: true},
+		}, tps)
+	}
+	assert.Equal(t, method("&self", nil), method("&'a self", []*TypeParam{{Name: "'a", Ordinal: 0, ParamKind: "lifetime"}}))
+
+	// A bounded lifetime is part of the signature.
+	bounded := hash([]*FunctionParam{
+		{Name: "s", Ordinal: 0, TypeExpr: "&'a str"},
+		{Ordinal: 0, TypeExpr: "&'a str", IsReturn: true},
+	}, []*TypeParam{
+		{Name: "'a", Ordinal: 0, ParamKind: "lifetime"},
+		{Name: "T", Ordinal: 1, ParamKind: "type", Constraints: "'a"},
+	})
+	unbounded := hash([]*FunctionParam{
+		{Name: "s", Ordinal: 0, TypeExpr: "&str"},
+		{Ordinal: 0, TypeExpr: "&str", IsReturn: true},
+	}, []*TypeParam{{Name: "T", Ordinal: 0, ParamKind: "type"}})
+	assert.NotEqual(t, bounded, unbounded)
+}
+
+func TestSignatureHash_StructLifetimeRename(t *testing.T) {
+	t.Parallel()
+	hash := func(lt string) string {
+		return ComputeSignatureHash("Parser", "struct", "public", nil,
+			[]*TypeMember{{Name: "input", Kind: "field", TypeExpr: "&" + lt + " str"}}, nil,
+			[]*TypeParam{{Name: lt, Ordinal: 0, ParamKind: "lifetime"}})
+	}
+	assert.Equal(t, hash("'a"), hash("'src"))
+	assert.NotEqual(t, hash("'a"), ComputeSignatureHash("Parser", "struct", "public", nil,
+		[]*TypeMember{{Name: "input", Kind: "field", TypeExpr: "&'static str"}}, nil, nil))
+}
+
+func TestElideLifetimes(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, "&mut Cow<str>", ElideLifetimes("&'a mut Cow<'a, str>"))
+	assert.Equal(t, "&str", ElideLifetimes("&'static str"))
+	assert.Equal(t, "Formatter", ElideLifetimes("Formatter<'_>"))
+	assert.Equal(t, "Pair", ElideLifetimes("Pair<'a, 'b>"))
+	assert.Equal(t, "Vec<u8>", ElideLifetimes("Vec<u8>"))
+}
+
 func TestSignatureHash_UnchangedSymbol(t *testing.T) {
 	t.Parallel()
 	members := []*TypeMember{{Name: "x", Kind: "field", TypeExpr: "int", Visibility: "public"}}
@@ -901,3 +1203,283 @@ func TestDeleteResolutionDataForFiles(t *testing.T) {
 	reexports, _ := s.ReexportsByFile(f.ID)
 	assert.Empty(t, reexports)
 }
+
+// =============================================================================
+// Index validation
+// =============================================================================
+
+func TestValidate_CleanIndex(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := insertTestFile(t, s, "/main.go", "go")
+	caller := insertTestSymbol(t, s, &f.ID, "Foo", "function")
+	callee := insertTestSymbol(t, s, &f.ID, "Bar", "function")
+	_, err := s.InsertCallEdge(&CallEdge{CallerSymbolID: caller.ID, CalleeSymbolID: callee.ID, FileID: &f.ID, Line: 3})
+	require.NoError(t, err)
+
+	violations, err := s.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+}
+
+func TestValidate_FindsViolations(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	f := &File{Path: "/main.go", Language: "go", LineCount: 5, LastIndexed: time.Now()}
+	_, err := s.InsertFile(f)
+	require.NoError(t, err)
+	sym := insertTestSymbol(t, s, &f.ID, "Foo", "function") // ends on line 9 of 5
+	dup := &Symbol{FileID: &f.ID, Name: "Foo", Kind: "function", EndLine: 1}
+	_, err = s.InsertSymbol(dup)
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&CallEdge{CallerSymbolID: sym.ID, CalleeSymbolID: 999, FileID: &f.ID})
+	require.NoError(t, err)
+
+	violations, err := s.Validate()
+	require.NoError(t, err)
+	byCheck := map[string][]Violation{}
+	for _, v := range violations {
+		byCheck[v.Check] = append(byCheck[v.Check], v)
+	}
+
+	require.Len(t, byCheck["dangling_reference"], 1)
+	assert.Equal(t, "call_graph", byCheck["dangling_reference"][0].Table)
+	assert.Equal(t, "callee_symbol_id 999 not in symbols", byCheck["dangling_reference"][0].Detail)
+
+	require.Len(t, byCheck["duplicate_symbol"], 1)
+	assert.Equal(t, dup.ID, byCheck["duplicate_symbol"][0].RowID)
+
+	require.Len(t, byCheck["span_out_of_bounds"], 1)
+	assert.Equal(t, sym.ID, byCheck["span_out_of_bounds"][0].RowID)
+	assert.Equal(t, "span 0:0-9:0 in 5 lines", byCheck["span_out_of_bounds"][0].Detail)
+}
+
+func TestSchemaForeignKeys(t *testing.T) {
+	t.Parallel()
+	keys := schemaForeignKeys()
+	assert.Contains(t, keys, foreignKey{table: "call_graph", column: "callee_symbol_id", refTable: "symbols"})
+	assert.Contains(t, keys, foreignKey{table: "scopes", column: "parent_scope_id", refTable: "scopes"})
+	assert.Contains(t, keys, foreignKey{table: "symbol_history", column: "symbol_id", refTable: "symbols"})
+}
+
+// =============================================================================
+// Shard import
+// =============================================================================
+
+func TestImportFrom(t *testing.T) {
+	t.Parallel()
+	dst := newTestStore(t)
+	src := newTestStore(t)
+
+	// Give dst rows of its own so copied ids must be remapped.
+	existing := insertTestFile(t, dst, "/repo/existing.go", "go")
+	insertTestSymbol(t, dst, &existing.ID, "Existing", "function")
+
+	f := insertTestFile(t, src, "/repo/a/lib.rs", "rust")
+	stub := insertTestFile(t, src, "rustdoc:serde", "rustdoc")
+	parent := insertTestSymbol(t, src, &f.ID, "Widget", "struct")
+	child := &Symbol{FileID: &f.ID, Name: "new", Kind: "method", ParentSymbolID: &parent.ID}
+	_, err := src.InsertSymbol(child)
+	require.NoError(t, err)
+	stubSym := insertTestSymbol(t, src, &stub.ID, "Deserialize", "trait")
+	_, err = src.InsertCallEdge(&CallEdge{CallerSymbolID: child.ID, CalleeSymbolID: parent.ID, FileID: &f.ID})
+	require.NoError(t, err)
+	_, err = src.InsertCallEdge(&CallEdge{CallerSymbolID: child.ID, CalleeSymbolID: stubSym.ID, FileID: &f.ID})
+	require.NoError(t, err)
+
+	fileIDs, err := dst.ImportFrom(src, "rustdoc")
+	require.NoError(t, err)
+	require.Len(t, fileIDs, 1)
+	assert.NotEqual(t, existing.ID, fileIDs[0])
+
+	syms, err := dst.SymbolsByFile(fileIDs[0])
+	require.NoError(t, err)
+	require.Len(t, syms, 2)
+	byName := map[string]*Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	require.NotNil(t, byName["new"].ParentSymbolID)
+	assert.Equal(t, byName["Widget"].ID, *byName["new"].ParentSymbolID, "self-references point at the copies")
+
+	stubFile, err := dst.FileByPath("rustdoc:serde")
+	require.NoError(t, err)
+	assert.Nil(t, stubFile, "skipped languages are not copied")
+	var edges int
+	require.NoError(t, dst.db.QueryRow("SELECT COUNT(*) FROM call_graph WHERE callee_symbol_id = ?", byName["Widget"].ID).Scan(&edges))
+	assert.Equal(t, 1, edges)
+	require.NoError(t, dst.db.QueryRow("SELECT COUNT(*) FROM call_graph").Scan(&edges))
+	assert.Equal(t, 1, edges, "edges into skipped files are dropped")
+
+	violations, err := dst.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+}
+
+func TestFilesWithUnlinkedReferences(t *testing.T) {
+	t.Parallel()
+	s := newTestStore(t)
+	lib := insertTestFile(t, s, "/repo/a/a.go", "go")
+	user := insertTestFile(t, s, "/repo/b/b.go", "go")
+	other := insertTestFile(t, s, "/repo/c/c.go", "go")
+	insertTestSymbol(t, s, &lib.ID, "Helper", "function")
+	for _, r := range []*Reference{
+		{FileID: user.ID, Name: "Helper"},
+		{FileID: other.ID, Name: "Unrelated"},
+	} {
+		_, err := s.InsertReference(r)
+		require.NoError(t, err)
+	}
+
+	got, err := s.FilesWithUnlinkedReferences([]int64{lib.ID})
+	require.NoError(t, err)
+	assert.Equal(t, []int64{user.ID}, got)
+
+	got, err = s.FilesWithUnlinkedReferences([]int64{other.ID})
+	require.NoError(t, err)
+	assert.Equal(t, []int64{other.ID}, got)
+}
+
+func TestImportFromExcept(t *testing.T) {
+	t.Parallel()
+	dst := newTestStore(t)
+	src := newTestStore(t)
+	kept := insertTestFile(t, src, "/repo/a.go", "go")
+	skipped := insertTestFile(t, src, "/repo/b.go", "go")
+	insertTestSymbol(t, src, &skipped.ID, "Skipped", "function")
+
+	fileIDs, err := dst.ImportFromExcept(src, []int64{skipped.ID})
+	require.NoError(t, err)
+	require.Len(t, fileIDs, 1)
+	f, err := dst.FileByPath(kept.Path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	assert.Equal(t, fileIDs[0], f.ID)
+	f, err = dst.FileByPath(skipped.Path)
+	require.NoError(t, err)
+	assert.Nil(t, f)
+	var n int
+	require.NoError(t, dst.db.QueryRow("SELECT COUNT(*) FROM symbols").Scan(&n))
+	assert.Zero(t, n)
+}
+
+// =============================================================================
+// Index snapshots
+// =============================================================================
+
+func TestSnapshot_RoundTrip(t *testing.T) {
+	t.Parallel()
+	src := newTestStore(t)
+	f := insertTestFile(t, src, "/repo/lib.rs", "rust")
+	parent := insertTestSymbol(t, src, &f.ID, "Widget", "struct")
+	child := &Symbol{FileID: &f.ID, Name: "new", Kind: "method", ParentSymbolID: &parent.ID}
+	_, err := src.InsertSymbol(child)
+	require.NoError(t, err)
+	_, err = src.InsertCallEdge(&CallEdge{CallerSymbolID: child.ID, CalleeSymbolID: parent.ID, FileID: &f.ID})
+	require.NoError(t, err)
+	_, err = src.InsertReference(&Reference{FileID: f.ID, Name: "Widget"})
+	require.NoError(t, err)
+	require.NoError(t, src.SetMetadata("scripts_hash", "abc"))
+
+	var buf bytes.Buffer
+	written, err := src.WriteSnapshot(&buf)
+	require.NoError(t, err)
+	assert.Positive(t, written.Tables)
+
+	dst := newTestStore(t)
+	loaded, err := dst.LoadSnapshot(&buf)
+	require.NoError(t, err)
+	assert.Equal(t, written, loaded)
+
+	got, err := dst.FileByPath("/repo/lib.rs")
+	require.NoError(t, err)
+	require.NotNil(t, got)
+	assert.Equal(t, f.ID, got.ID)
+	assert.True(t, f.LastIndexed.Equal(got.LastIndexed))
+	want, err := src.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	syms, err := dst.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	assert.Equal(t, want, syms, "symbols keep their ids, parents, and modifiers")
+	hash, err := dst.GetMetadata("scripts_hash")
+	require.NoError(t, err)
+	assert.Equal(t, "abc", hash)
+
+	violations, err := dst.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+}
+
+func TestSnapshot_Rejects(t *testing.T) {
+	t.Parallel()
+	var snapshot bytes.Buffer
+	_, err := newTestStore(t).WriteSnapshot(&snapshot)
+	require.NoError(t, err)
+
+	_, err = newTestStore(t).LoadSnapshot(bytes.NewReader([]byte("SQLite format 3\x00")))
+	assert.ErrorContains(t, err, "not a canopy index snapshot")
+
+	newer := append([]byte(snapshotMagic), 0xff, 0xff, snapshotCodecGzip)
+	_, err = newTestStore(t).LoadSnapshot(bytes.NewReader(newer))
+	assert.ErrorContains(t, err, "newer than supported")
+
+	truncated := snapshot.Bytes()[:snapshot.Len()-8]
+	_, err = newTestStore(t).LoadSnapshot(bytes.NewReader(truncated))
+	assert.Error(t, err)
+
+	full := newTestStore(t)
+	insertTestFile(t, full, "/repo/a.go", "go")
+	_, err = full.LoadSnapshot(bytes.NewReader(snapshot.Bytes()))
+	assert.ErrorContains(t, err, "not empty")
+}
+
+func TestSnapshot_RejectsCorruptLengths(t *testing.T) {
+	t.Parallel()
+	// snapshot wraps body in a snapshot header and gzip, the way
+	// WriteSnapshot does.
+	snapshot := func(body ...byte) []byte {
+		var buf bytes.Buffer
+		buf.WriteString(snapshotMagic)
+		buf.Write([]byte{0, SnapshotVersion, snapshotCodecGzip})
+		zw := gzip.NewWriter(&buf)
+		_, err := zw.Write(body)
+		require.NoError(t, err)
+		require.NoError(t, zw.Close())
+		return buf.Bytes()
+	}
+	uvarint := func(v uint64) []byte { return binary.AppendUvarint(nil, v) }
+
+	// A table name claiming far more bytes than follow.
+	body := append(uvarint(1<<40), "files"...)
+	_, err := newTestStore(t).LoadSnapshot(bytes.NewReader(snapshot(body...)))
+	assert.ErrorContains(t, err, "corrupt snapshot")
+	assert.ErrorContains(t, err, "ends after 5 bytes")
+
+	// A column count beyond the table's columns.
+	body = append(append(uvarint(5), "files"...), uvarint(1<<40)...)
+	_, err = newTestStore(t).LoadSnapshot(bytes.NewReader(snapshot(body...)))
+	assert.ErrorContains(t, err, "corrupt snapshot")
+	assert.ErrorContains(t, err, "columns, but the table has")
+
+	// A snapshot cut off inside a column name.
+	body = append(append(append(uvarint(5), "files"...), uvarint(1)...), append(uvarint(4), "pa"...)...)
+	_, err = newTestStore(t).LoadSnapshot(bytes.NewReader(snapshot(body...)))
+	assert.ErrorContains(t, err, "unexpected EOF")
+}
+
+func TestSnapshot_RejectsUnknownTable(t *testing.T) {
+	t.Parallel()
+	var buf bytes.Buffer
+	buf.WriteString(snapshotMagic)
+	buf.Write([]byte{0, SnapshotVersion, snapshotCodecGzip})
+	zw := gzip.NewWriter(&buf)
+	copyPath := filepath.Join(t.TempDir(), "copy.db")
+	name := "files);VACUUM INTO '" + copyPath + "'--"
+	_, err := zw.Write(append(binary.AppendUvarint(nil, uint64(len(name))), name...))
+	require.NoError(t, err)
+	require.NoError(t, zw.Close())
+
+	_, err = newTestStore(t).LoadSnapshot(&buf)
+	assert.ErrorContains(t, err, "unknown table")
+	assert.NoFileExists(t, copyPath, "the name never reaches SQL")
+}
@@ -11,6 +11,7 @@ type File struct {
 	Hash        string
 	LineCount   int
 	LastIndexed time.Time
+	Root        string // indexed root directory the file was discovered under ("" if unknown)
 }
 
 type Symbol struct {
@@ -18,6 +19,7 @@ type Symbol struct {
 	FileID         *int64
 	Name           string
 	Kind           string
+	Subkind        string // finer kind within Kind, e.g. "associated_function" or "tuple_struct"; "" if none
 	Visibility     string
 	Modifiers      []string
 	SignatureHash  string
@@ -26,6 +28,12 @@ type Symbol struct {
 	EndLine        int // 0-based
 	EndCol         int // 0-based
 	ParentSymbolID *int64
+	StartByte      int     // byte offset from start of file
+	EndByte        int     // byte offset from start of file
+	UTF16StartCol  int     // 0-based, in UTF-16 code units
+	UTF16EndCol    int     // 0-based, in UTF-16 code units
+	PageRank       float64 // reference graph centrality, set by resolution
+	Betweenness    float64 // reference graph centrality, set by resolution
 }
 
 type SymbolFragment struct {
@@ -61,6 +69,27 @@ type Reference struct {
 	EndLine   int
 	EndCol    int
 	Context   string
+
+	// ReceiverType names the type a member reference is looked up on, when
+	// extraction knows it: Point for an overloaded a + b with a: Point.
+	ReceiverType string
+
+	// Awaited marks a call whose result is awaited on the spot, as in
+	// fetch(url).await.
+	Awaited bool
+
+	// Spawned marks a call that runs in a spawned task or thread, as in
+	// tokio::spawn(serve(conn)) or thread::spawn(worker).
+	Spawned bool
+
+	// Propagated marks a call whose error is propagated with the ? operator,
+	// as in File::open(path)?.
+	Propagated bool
+
+	StartByte     int // byte offset from start of file
+	EndByte       int // byte offset from start of file
+	UTF16StartCol int // 0-based, in UTF-16 code units
+	UTF16EndCol   int // 0-based, in UTF-16 code units
 }
 
 type Import struct {
@@ -104,6 +133,125 @@ type TypeParam struct {
 	Constraints string
 }
 
+// TypeBound is one trait/interface bound on a generic type, either inline on
+// a type parameter (T: Clone + Send) or from a where-clause predicate (where
+// Vec<T>: Debug). Each bound of a predicate is a separate row.
+type TypeBound struct {
+	ID            int64
+	SymbolID      int64
+	Subject       string // the bounded type: a type parameter name or, in where-clauses, any type
+	Bound         string // the bound as written, e.g. "Iterator<Item = u8>", "?Sized", "'a"
+	Name          string // bare bound name for filtering: "Iterator", "Sized", "'a"
+	InWhereClause bool
+}
+
+type FunctionMetrics struct {
+	ID         int64
+	SymbolID   int64
+	Cyclomatic int // McCabe cyclomatic complexity (1 + decision points)
+	Cognitive  int // cognitive complexity (nesting-weighted control flow)
+}
+
+// FlaggedSite is a construct inside a function body that audit queries look
+// for, such as a call that can panic or an unsafe block.
+type FlaggedSite struct {
+	ID        int64
+	SymbolID  int64  // the enclosing function or method
+	FileID    int64
+	Kind      string // "panic", "unwrap", "index", "unsafe", "shadow", or "shadow_reuse"
+	Name      string // the construct as written: "panic!", "expect", "items[i]", "unsafe"; the name for shadows
+	StartLine int
+	StartCol  int
+	EndLine   int
+	EndCol    int
+}
+
+// FunctionFingerprint is the structural fingerprint of a function body: the
+// normalized hash of each statement, in order. Used for clone detection.
+type FunctionFingerprint struct {
+	ID         int64
+	SymbolID   int64
+	Statements []uint64
+}
+
+// SyntaxError is the byte range of a syntax error in a file.
+type SyntaxError struct {
+	ID        int64
+	FileID    int64
+	StartByte int
+	EndByte   int
+}
+
+// ImplBlock is a Rust impl block, with its header as written. Trait is the
+// trait as written, with its arguments, and TraitStartByte and TraitEndByte
+// its span; Trait is empty for an inherent impl.
+type ImplBlock struct {
+	ID             int64
+	FileID         int64
+	StartLine      int
+	StartCol       int
+	EndLine        int
+	EndCol         int
+	StartByte      int
+	EndByte        int
+	UTF16StartCol  int
+	UTF16EndCol    int
+	Header         string
+	Trait          string
+	TraitStartByte int
+	TraitEndByte   int
+}
+
+// BuildTarget is a Bazel or Buck target of an indexed root.
+type BuildTarget struct {
+	Root  string
+	Label string   // normalized, e.g. "//pkg/path:name"
+	Kind  string   // rule name, e.g. "go_library"
+	Deps  []string // normalized labels of the targets it depends on
+}
+
+// SymbolHistory is the git blame summary of a symbol's lines.
+type SymbolHistory struct {
+	SymbolID    int64
+	Commit      string    // commit that last modified any line of the symbol
+	Author      string    // author of that commit
+	AuthorEmail string
+	Date        time.Time // author time of that commit
+	Authors     int       // distinct authors across the symbol's lines
+}
+
+// AuthorLines is the number of lines of a file last modified by an author.
+type AuthorLines struct {
+	Author string
+	Lines  int
+}
+
+// SymbolSamples is the number of execution trace samples attributed to a
+// symbol.
+type SymbolSamples struct {
+	SymbolID int64
+	Self     int64 // samples with the symbol as the innermost indexed frame
+	Total    int64 // samples with the symbol anywhere on the stack
+}
+
+// SymbolCoverage is the test coverage of a function or method: its
+// instrumented lines and how many of them ran.
+type SymbolCoverage struct {
+	SymbolID     int64
+	Lines        int
+	CoveredLines int
+}
+
+// SymbolEmbedding is the embedding vector of a symbol's code chunk under
+// one embedding model. TextHash identifies the text that was embedded, so
+// unchanged symbols can keep their vectors.
+type SymbolEmbedding struct {
+	SymbolID int64
+	Model    string
+	TextHash string
+	Vector   []float32
+}
+
 type Annotation struct {
 	ID               int64
 	TargetSymbolID   int64
@@ -141,6 +289,10 @@ type CallEdge struct {
 	FileID         *int64
 	Line           int
 	Col            int
+	Confidence     float64 // confidence of the resolved call reference
+	Awaited        bool    // the call's result is awaited on the spot
+	Kind           string  // "" for a direct call, "spawn" for a call run in a spawned task or thread, "ffi" across FFI
+	Propagated     bool    // the call's error is propagated with ?
 }
 
 type Reexport struct {
@@ -150,6 +302,17 @@ type Reexport struct {
 	ExportedName     string
 }
 
+// ImportAlias is an import that binds an item under another name, as in
+// use foo::Bar as Baz, with the symbol the imported name resolved to.
+type ImportAlias struct {
+	ID             int64
+	FileID         int64
+	Alias          string // the name bound in the file
+	ImportedName   string // the item's own name
+	Source         string // the import path
+	TargetSymbolID int64
+}
+
 type ExtensionBinding struct {
 	ID                   int64
 	MemberSymbolID       int64
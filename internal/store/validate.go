@@ -0,0 +1,115 @@
+package store
+
+import (
+	"fmt"
+	"regexp"
+)
+
+// --- Index validation ---
+
+// Violation is one broken structural invariant of the index.
+type Violation struct {
+	// Check names the invariant: "dangling_reference", "duplicate_symbol",
+	// or "span_out_of_bounds".
+	Check  string
+	Table  string
+	RowID  int64
+	Detail string
+}
+
+// foreignKey is an id column that must point at an existing row.
+type foreignKey struct {
+	table, column, refTable string
+}
+
+var (
+	createTableRe = regexp.MustCompile(`(?m)^CREATE TABLE IF NOT EXISTS (\w+) \(`)
+	referencesRe  = regexp.MustCompile(`(?m)^\s*(\w+)\s+INTEGER[^,\n]*REFERENCES (\w+)\(id\)`)
+)
+
+// schemaForeignKeys lists every REFERENCES column in schemaDDL. SQLite does
+// not enforce them, so incremental updates that forget a table leave rows
+// pointing at deleted ones.
+func schemaForeignKeys() []foreignKey {
+	var keys []foreignKey
+	tables := createTableRe.FindAllStringSubmatchIndex(schemaDDL, -1)
+	for i, t := range tables {
+		end := len(schemaDDL)
+		if i+1 < len(tables) {
+			end = tables[i+1][0]
+		}
+		table := schemaDDL[t[2]:t[3]]
+		for _, m := range referencesRe.FindAllStringSubmatch(schemaDDL[t[1]:end], -1) {
+			keys = append(keys, foreignKey{table: table, column: m[1], refTable: m[2]})
+		}
+	}
+	return keys
+}
+
+// Validate checks the structural invariants of the index: every id column
+// points at an existing row, no symbol is recorded twice at the same place,
+// and every symbol, reference, and scope span lies within its file. It
+// returns the violations found, ordered by check; none means the index is
+// consistent.
+func (s *Store) Validate() ([]Violation, error) {
+	var violations []Violation
+	collect := func(check, table, query, detail string) error {
+		rows, err := s.db.Query(query)
+		if err != nil {
+			return fmt.Errorf("validate %s: %w", table, err)
+		}
+		defer rows.Close()
+		for rows.Next() {
+			v := Violation{Check: check, Table: table}
+			var value string
+			if err := rows.Scan(&v.RowID, &value); err != nil {
+				return fmt.Errorf("validate %s: scan: %w", table, err)
+			}
+			v.Detail = fmt.Sprintf(detail, value)
+			violations = append(violations, v)
+		}
+		return rows.Err()
+	}
+
+	for _, fk := range schemaForeignKeys() {
+		query := fmt.Sprintf(
+			`SELECT rowid, %[2]s FROM %[1]s WHERE %[2]s IS NOT NULL AND %[2]s NOT IN (SELECT id FROM %[3]s) ORDER BY rowid`,
+			fk.table, fk.column, fk.refTable)
+		detail := fmt.Sprintf("%s %%s not in %s", fk.column, fk.refTable)
+		if err := collect("dangling_reference", fk.table, query, detail); err != nil {
+			return nil, err
+		}
+	}
+
+	err := collect("duplicate_symbol", "symbols",
+		`SELECT s.id, d.first_id FROM symbols s
+		 JOIN (SELECT MIN(id) AS first_id, file_id, name, kind, start_line, start_col,
+		         COALESCE(parent_symbol_id, 0) AS parent
+		       FROM symbols WHERE file_id IS NOT NULL
+		       GROUP BY file_id, name, kind, start_line, start_col, parent HAVING COUNT(*) > 1) d
+		   ON d.file_id = s.file_id AND d.name = s.name AND d.kind = s.kind
+		  AND d.start_line = s.start_line AND d.start_col = s.start_col
+		  AND d.parent = COALESCE(s.parent_symbol_id, 0)
+		 WHERE s.id != d.first_id
+		 ORDER BY s.id`,
+		"same name, kind, parent, and position as symbol %s")
+	if err != nil {
+		return nil, err
+	}
+
+	// Lines are 0-based and a file of n lines ends on line n-1. Files
+	// without a line count, such as dependency stubs, are not checked.
+	for _, table := range []string{"symbols", "references_", "scopes"} {
+		query := fmt.Sprintf(
+			`SELECT t.id, t.start_line || ':' || t.start_col || '-' || t.end_line || ':' || t.end_col || ' in ' || f.line_count || ' lines'
+			 FROM %s t JOIN files f ON f.id = t.file_id
+			 WHERE f.line_count > 0 AND (
+			   t.start_line < 0 OR t.start_col < 0 OR t.end_line >= f.line_count
+			   OR t.end_line < t.start_line OR (t.end_line = t.start_line AND t.end_col < t.start_col))
+			 ORDER BY t.id`, table)
+		if err := collect("span_out_of_bounds", table, query, "span %s"); err != nil {
+			return nil, err
+		}
+	}
+	return violations, nil
+}
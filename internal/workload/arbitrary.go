@@ -0,0 +1,213 @@
+package workload
+
+import (
+	"fmt"
+	"strings"
+)
+
+// ArbitraryRust builds a Rust snippet from data, for fuzzing: each byte
+// picks a production of a small Rust grammar that leans on the syntax the
+// extractor and resolver special-case (paths, generics, impls, closures,
+// ?, .await, macros, attributes, unsafe). Names come from a short list, so
+// definitions, uses, and calls collide. The snippet stops when data runs
+// out; a few bytes also cut or duplicate tokens, so it is not always valid
+// Rust. The same data always builds the same snippet.
+func ArbitraryRust(data []byte) string {
+	a := &arbitrary{data: data}
+	for len(a.data) > 0 && a.items < 64 {
+		a.item(0)
+	}
+	return a.b.String()
+}
+
+var (
+	arbitraryNames = []string{"a", "b", "new", "run", "Foo", "Bar", "T", "Self", "self", "crate", "super", "r#type", "Ok", "Err", "main", "default"}
+	arbitraryTypes = []string{"u8", "i32", "String", "&str", "Self", "T", "Vec<T>", "Option<Foo>", "Result<T, Bar>", "&'a mut Foo", "Box<dyn Fn(i32) -> i32>", "impl Iterator<Item = u8>", "[u8; 4]", "(T, Bar)", "!", "fn() -> ()"}
+	arbitraryAttrs = []string{"#[derive(Debug, Clone)]", "#[cfg(feature = \"x\")]", "#[test]", "#[inline]", "#![allow(unused)]", "#[doc = \"x\"]", "#[cfg(not(unix))]", "#[macro_export]"}
+	arbitraryJunk  = []string{"{", "}", "(", ")", "<", ">", "::", ";", ",", "'", "\"", "#", "=>", "|", "&&", "r#", "/*", "*/", "\n"}
+)
+
+type arbitrary struct {
+	data  []byte
+	b     strings.Builder
+	items int
+}
+
+// pick consumes one byte and returns it modulo n; with no data left it
+// returns 0, the simplest production.
+func (a *arbitrary) pick(n int) int {
+	if len(a.data) == 0 {
+		return 0
+	}
+	c := a.data[0]
+	a.data = a.data[1:]
+	return int(c) % n
+}
+
+func (a *arbitrary) name() string { return arbitraryNames[a.pick(len(arbitraryNames))] }
+func (a *arbitrary) typ() string  { return arbitraryTypes[a.pick(len(arbitraryTypes))] }
+
+func (a *arbitrary) printf(format string, args ...any) {
+	fmt.Fprintf(&a.b, format, args...)
+}
+
+// item writes one item; depth bounds nested modules, impls, and bodies.
+func (a *arbitrary) item(depth int) {
+	a.items++
+	if a.pick(4) == 0 {
+		a.printf("%s\n", arbitraryAttrs[a.pick(len(arbitraryAttrs))])
+	}
+	vis := []string{"", "pub ", "pub(crate) ", "pub(super) "}[a.pick(4)]
+	switch a.pick(14) {
+	case 0, 1, 2:
+		a.fn(vis, depth)
+	case 3:
+		a.printf("%sstruct %s<T> { %s: %s, pub %s: %s }\n", vis, a.name(), a.name(), a.typ(), a.name(), a.typ())
+	case 4:
+		a.printf("%sstruct %s(%s, %s);\n", vis, a.name(), a.typ(), a.typ())
+	case 5:
+		a.printf("%senum %s { %s, %s(%s), %s { x: %s } }\n", vis, a.name(), a.name(), a.name(), a.typ(), a.name(), a.typ())
+	case 6:
+		a.printf("%strait %s: %s { type Item; fn %s(&self) -> Self::Item; fn %s(&self) {} }\n", vis, a.name(), a.name(), a.name(), a.name())
+	case 7:
+		if depth > 2 {
+			a.printf("fn %s() {}\n", a.name())
+			return
+		}
+		switch a.pick(3) {
+		case 0:
+			a.printf("impl<T> %s<T> {\n", a.name())
+		case 1:
+			a.printf("impl<'a, T: Clone> %s for %s<'a, T> where T: Default {\n", a.name(), a.name())
+		default:
+			a.printf("unsafe impl %s for %s {\n", a.name(), a.typ())
+		}
+		for n := a.pick(4); n >= 0; n-- {
+			a.fn("", depth+1)
+		}
+		a.printf("}\n")
+	case 8:
+		if depth > 2 {
+			a.printf("mod %s;\n", a.name())
+			return
+		}
+		a.printf("%smod %s {\n", vis, a.name())
+		for n := a.pick(4); n >= 0; n-- {
+			a.item(depth + 1)
+		}
+		a.printf("}\n")
+	case 9:
+		switch a.pick(5) {
+		case 0:
+			a.printf("%suse %s::%s::%s;\n", vis, a.name(), a.name(), a.name())
+		case 1:
+			a.printf("%suse %s::{%s, %s as %s, self};\n", vis, a.name(), a.name(), a.name(), a.name())
+		case 2:
+			a.printf("%suse %s::*;\n", vis, a.name())
+		case 3:
+			a.printf("extern crate %s as %s;\n", a.name(), a.name())
+		default:
+			a.printf("%suse std::{collections::HashMap, fmt::%s};\n", vis, a.name())
+		}
+	case 10:
+		a.printf("%sconst %s: %s = %s;\n", vis, strings.ToUpper(a.name()), a.typ(), a.expr(depth))
+	case 11:
+		a.printf("%stype %s<T> = %s;\n", vis, a.name(), a.typ())
+	case 12:
+		a.printf("macro_rules! %s { ($x:expr) => { %s($x) }; () => {} }\n", a.name(), a.name())
+	default:
+		a.junk()
+	}
+}
+
+// fn writes a function, sometimes with a receiver, generics, async,
+// unsafe, or extern "C".
+func (a *arbitrary) fn(vis string, depth int) {
+	quals := []string{"", "async ", "unsafe ", "const ", "extern \"C\" ", "async unsafe "}[a.pick(6)]
+	params := []string{"", "&self", "&mut self, x: T", "self: Box<Self>", "x: " + a.typ(), "(a, b): (i32, i32)", "f: impl Fn(T) -> T"}[a.pick(7)]
+	generics := []string{"", "<T>", "<'a, T: Clone + 'a>", "<const N: usize>"}[a.pick(4)]
+	ret := ""
+	if a.pick(2) == 0 {
+		ret = " -> " + a.typ()
+	}
+	a.printf("%s%sfn %s%s(%s)%s {\n", vis, quals, a.name(), generics, params, ret)
+	for n := a.pick(5); n >= 0; n-- {
+		a.stmt(depth + 1)
+	}
+	a.printf("}\n")
+}
+
+func (a *arbitrary) stmt(depth int) {
+	switch a.pick(8) {
+	case 0:
+		a.printf("    let %s: %s = %s;\n", a.name(), a.typ(), a.expr(depth))
+	case 1:
+		a.printf("    let %s = %s;\n", a.name(), a.expr(depth))
+	case 2:
+		a.printf("    if let Some(%s) = %s { %s; }\n", a.name(), a.expr(depth), a.expr(depth))
+	case 3:
+		a.printf("    for %s in %s { %s; }\n", a.name(), a.expr(depth), a.expr(depth))
+	case 4:
+		a.printf("    match %s { %s::%s(x) => %s, _ => {} }\n", a.expr(depth), a.name(), a.name(), a.expr(depth))
+	case 5:
+		a.printf("    unsafe { %s; }\n", a.expr(depth))
+	case 6:
+		if depth < 3 {
+			a.item(depth + 1)
+			return
+		}
+		a.printf("    return;\n")
+	default:
+		a.printf("    %s;\n", a.expr(depth))
+	}
+}
+
+// expr returns an expression; depth bounds its nesting.
+func (a *arbitrary) expr(depth int) string {
+	if depth > 4 {
+		return a.name()
+	}
+	d := depth + 1
+	switch a.pick(16) {
+	case 0:
+		return a.name()
+	case 1:
+		return fmt.Sprintf("%s(%s)", a.name(), a.expr(d))
+	case 2:
+		return fmt.Sprintf("%s::%s(%s, %s)", a.name(), a.name(), a.expr(d), a.expr(d))
+	case 3:
+		return fmt.Sprintf("%s.%s(%s)", a.expr(d), a.name(), a.expr(d))
+	case 4:
+		return fmt.Sprintf("%s::<%s>()", a.name(), a.typ())
+	case 5:
+		return fmt.Sprintf("%s?", a.expr(d))
+	case 6:
+		return fmt.Sprintf("%s.await", a.expr(d))
+	case 7:
+		return fmt.Sprintf("|%s| %s", a.name(), a.expr(d))
+	case 8:
+		return fmt.Sprintf("move || { %s }", a.expr(d))
+	case 9:
+		return fmt.Sprintf("%s!(%s)", a.name(), a.expr(d))
+	case 10:
+		return fmt.Sprintf("%s { %s: %s, ..Default::default() }", a.name(), a.name(), a.expr(d))
+	case 11:
+		return fmt.Sprintf("%s.%s", a.expr(d), a.name())
+	case 12:
+		return fmt.Sprintf("<%s as %s>::%s(%s)", a.typ(), a.name(), a.name(), a.expr(d))
+	case 13:
+		return fmt.Sprintf("%s + %s[%s]", a.expr(d), a.expr(d), a.expr(d))
+	case 14:
+		return fmt.Sprintf("async { %s }", a.expr(d))
+	default:
+		return fmt.Sprintf("%q", a.name())
+	}
+}
+
+// junk writes a few stray tokens, leaving the snippet unbalanced.
+func (a *arbitrary) junk() {
+	for n := a.pick(4); n >= 0; n-- {
+		a.printf("%s ", arbitraryJunk[a.pick(len(arbitraryJunk))])
+	}
+	a.printf("\n")
+}
@@ -0,0 +1,25 @@
+package workload
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestArbitraryRust(t *testing.T) {
+	t.Parallel()
+
+	assert.Empty(t, ArbitraryRust(nil))
+	data := []byte("\x00\x01\x00\x00\x00\x00\x01\x00\x05\x03\x07\x02")
+	assert.Equal(t, ArbitraryRust(data), ArbitraryRust(data), "same data, same snippet")
+	assert.Contains(t, ArbitraryRust(data), "fn ")
+
+	// Every byte sequence builds something, and long inputs stay bounded.
+	long := make([]byte, 1<<16)
+	for i := range long {
+		long[i] = byte(i * 31)
+	}
+	src := ArbitraryRust(long)
+	assert.NotEmpty(t, src)
+	assert.Less(t, len(src), 1<<20)
+}
@@ -0,0 +1,302 @@
+package workload
+
+import (
+	"encoding/json"
+	"fmt"
+	"maps"
+	"math/rand"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+// MaxFixtureLevel is the highest level GenerateFixture accepts.
+const MaxFixtureLevel = 4
+
+// FixtureLevels names the resolution feature each fixture level adds; a
+// level exercises its own feature and those of every lower level.
+var FixtureLevels = []string{
+	1: "cross-file imports",
+	2: "generics",
+	3: "re-exports",
+	4: "trait dispatch",
+}
+
+// FixtureSpec describes a Rust resolver fixture.
+type FixtureSpec struct {
+	Level     int   // 1 to MaxFixtureLevel
+	Files     int   // modules, not counting the crate root
+	Functions int   // free functions per module
+	Seed      int64 // seeds the choice of callees
+}
+
+// FixtureStats summarizes a generated fixture.
+type FixtureStats struct {
+	Files           int // files written, crate root included
+	Definitions     int
+	Calls           int
+	Implementations int
+}
+
+// Golden is the expected outcome of indexing a fixture, in the format of
+// the golden.json files under testdata.
+type Golden struct {
+	Definitions     []GoldenDef  `json:"definitions"`
+	Implementations []GoldenImpl `json:"implementations,omitempty"`
+	Calls           []GoldenCall `json:"calls"`
+}
+
+// GoldenDef is an expected symbol; Line is 0-based.
+type GoldenDef struct {
+	Name string `json:"name"`
+	Kind string `json:"kind"`
+	File string `json:"file"`
+	Line int    `json:"line"`
+}
+
+// GoldenImpl is an expected trait implementation.
+type GoldenImpl struct {
+	Type      string `json:"type"`
+	Interface string `json:"interface"`
+}
+
+// GoldenCall is an expected call graph edge, by symbol name. Generated names
+// are unique, so each names exactly one edge.
+type GoldenCall struct {
+	Caller string `json:"caller"`
+	Callee string `json:"callee"`
+}
+
+// GenerateFixture writes a Rust crate exercising the resolution features of
+// spec.Level into dir/src, flat as the golden tests index it, and the
+// definitions, implementations, and calls the resolver should find into
+// dir/golden.json. The same spec always generates the same fixture.
+func GenerateFixture(dir string, spec FixtureSpec) (*FixtureStats, error) {
+	if spec.Level < 1 || spec.Level > MaxFixtureLevel {
+		return nil, fmt.Errorf("workload: fixture level must be 1 to %d, got %d", MaxFixtureLevel, spec.Level)
+	}
+	if spec.Files < 1 || spec.Functions < 1 {
+		return nil, fmt.Errorf("workload: need at least 1 file and 1 function per file, got %d and %d", spec.Files, spec.Functions)
+	}
+	g := &fixtureGenerator{
+		dir:    dir,
+		spec:   spec,
+		rng:    rand.New(rand.NewSource(spec.Seed)),
+		stats:  &FixtureStats{},
+		golden: &Golden{},
+		seen:   map[GoldenCall]bool{},
+	}
+	if err := os.MkdirAll(filepath.Join(dir, "src"), 0o755); err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+	for f := 0; f < spec.Files; f++ {
+		if err := g.module(f); err != nil {
+			return nil, fmt.Errorf("workload: %w", err)
+		}
+	}
+	if err := g.crateRoot(); err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+
+	data, err := json.MarshalIndent(g.golden, "", "  ")
+	if err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, "golden.json"), append(data, '\n'), 0o644); err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+	g.stats.Definitions = len(g.golden.Definitions)
+	g.stats.Calls = len(g.golden.Calls)
+	g.stats.Implementations = len(g.golden.Implementations)
+	return g.stats, nil
+}
+
+type fixtureGenerator struct {
+	dir    string
+	spec   FixtureSpec
+	rng    *rand.Rand
+	stats  *FixtureStats
+	golden *Golden
+	seen   map[GoldenCall]bool
+}
+
+// sourceFile accumulates the body of one file, recording definitions by
+// body line until the imports above them are known.
+type sourceFile struct {
+	name    string
+	body    strings.Builder
+	line    int
+	imports map[string]bool
+	defs    []GoldenDef
+}
+
+// println writes one line of source.
+func (s *sourceFile) println(format string, args ...any) {
+	fmt.Fprintf(&s.body, format, args...)
+	s.body.WriteString("\n")
+	s.line++
+}
+
+// def records a definition of name on the next line.
+func (s *sourceFile) def(name, kind string) {
+	s.defs = append(s.defs, GoldenDef{Name: name, Kind: kind, File: s.name, Line: s.line})
+}
+
+func (g *fixtureGenerator) call(caller, callee string) {
+	c := GoldenCall{Caller: caller, Callee: callee}
+	if !g.seen[c] {
+		g.seen[c] = true
+		g.golden.Calls = append(g.golden.Calls, c)
+	}
+}
+
+// write prepends the imports of s, shifts its definitions past them, and
+// writes it to src.
+func (g *fixtureGenerator) write(s *sourceFile) error {
+	var src strings.Builder
+	for _, imp := range slices.Sorted(maps.Keys(s.imports)) {
+		src.WriteString(imp)
+	}
+	offset := len(s.imports)
+	if offset > 0 {
+		src.WriteString("\n")
+		offset++
+	}
+	for _, d := range s.defs {
+		d.Line += offset
+		g.golden.Definitions = append(g.golden.Definitions, d)
+	}
+	src.WriteString(s.body.String())
+	g.stats.Files++
+	return os.WriteFile(filepath.Join(g.dir, "src", s.name), []byte(src.String()), 0o644)
+}
+
+// use imports item of module m into s unless s is m, as re-exported from
+// the crate root when reexported is set.
+func (g *fixtureGenerator) use(s *sourceFile, f, m int, item string, reexported bool) {
+	switch {
+	case m == f:
+	case reexported:
+		s.imports[fmt.Sprintf("use crate::%s;\n", item)] = true
+	default:
+		s.imports[fmt.Sprintf("use crate::m%04d::%s;\n", m, item)] = true
+	}
+}
+
+// module writes src/mNNNN.rs. Its types come first, at levels that have
+// them, then its functions, each calling into this or an earlier module.
+func (g *fixtureGenerator) module(f int) error {
+	s := &sourceFile{name: fmt.Sprintf("m%04d.rs", f), imports: map[string]bool{}}
+	level := g.spec.Level
+
+	if level >= 2 {
+		cell, wrap, pick := fmt.Sprintf("Cell%04d", f), fmt.Sprintf("wrap_%04d", f), fmt.Sprintf("pick_%04d", f)
+		s.def(cell, "struct")
+		s.println("pub struct %s<T> {", cell)
+		s.println("    pub value: T,")
+		s.println("}")
+		s.println("")
+		s.println("impl<T: Clone> %s<T> {", cell)
+		s.def(wrap, "function")
+		s.println("    pub fn %s(value: T) -> Self {", wrap)
+		s.println("        %s { value }", cell)
+		s.println("    }")
+		s.println("}")
+		s.println("")
+		s.def(pick, "function")
+		s.println("pub fn %s<T: PartialOrd>(a: T, b: T) -> T {", pick)
+		s.println("    if a > b { a } else { b }")
+		s.println("}")
+		s.println("")
+	}
+
+	if level >= 4 {
+		// Unit implements the trait of this or an earlier module.
+		shape, unit, measure := fmt.Sprintf("Shape%04d", f), fmt.Sprintf("Unit%04d", f), fmt.Sprintf("measure_%04d", f)
+		impl := g.rng.Intn(f + 1)
+		implShape, implArea := fmt.Sprintf("Shape%04d", impl), fmt.Sprintf("area_%04d", impl)
+		s.def(shape, "trait")
+		s.println("pub trait %s {", shape)
+		s.println("    fn area_%04d(&self) -> u64;", f)
+		s.println("}")
+		s.println("")
+		s.def(unit, "struct")
+		s.println("pub struct %s {", unit)
+		s.println("    pub size: u64,")
+		s.println("}")
+		s.println("")
+		g.use(s, f, impl, implShape, false)
+		s.println("impl %s for %s {", implShape, unit)
+		s.def(implArea, "method")
+		s.println("    fn %s(&self) -> u64 {", implArea)
+		s.println("        self.size")
+		s.println("    }")
+		s.println("}")
+		s.println("")
+		s.def(measure, "function")
+		s.println("pub fn %s(u: &%s) -> u64 {", measure, unit)
+		s.println("    u.%s()", implArea)
+		s.println("}")
+		s.println("")
+		g.golden.Implementations = append(g.golden.Implementations, GoldenImpl{Type: unit, Interface: implShape})
+		g.call(measure, implArea)
+	}
+
+	for n := 0; n < g.spec.Functions; n++ {
+		name := fmt.Sprintf("f%04d_%02d", f, n)
+		s.def(name, "function")
+		s.println("pub fn %s(x: u64) -> u64 {", name)
+		s.println("    if x == 0 {")
+		s.println("        return 0;")
+		s.println("    }")
+		m := g.rng.Intn(f + 1)
+		switch {
+		case level >= 4 && n%4 == 3:
+			unit, measure := fmt.Sprintf("Unit%04d", m), fmt.Sprintf("measure_%04d", m)
+			g.use(s, f, m, unit, false)
+			g.use(s, f, m, measure, false)
+			s.println("    let u = %s { size: x };", unit)
+			s.println("    %s(&u)", measure)
+			g.call(name, measure)
+		case level >= 2 && n%4 == 1:
+			cell, wrap, pick := fmt.Sprintf("Cell%04d", m), fmt.Sprintf("wrap_%04d", m), fmt.Sprintf("pick_%04d", m)
+			g.use(s, f, m, cell, false)
+			g.use(s, f, m, pick, false)
+			s.println("    let c = %s::%s(x - 1);", cell, wrap)
+			s.println("    %s(c.value, x - 1)", pick)
+			g.call(name, wrap)
+			g.call(name, pick)
+		default:
+			// At level 3 the crate root re-exports every module's first
+			// function, and other modules import it from there.
+			callee := fmt.Sprintf("f%04d_%02d", m, g.rng.Intn(g.spec.Functions))
+			if level >= 3 && m != f {
+				callee = fmt.Sprintf("f%04d_00", m)
+			}
+			g.use(s, f, m, callee, level >= 3)
+			s.println("    %s(x - 1) + 1", callee)
+			g.call(name, callee)
+		}
+		s.println("}")
+		s.println("")
+	}
+	return g.write(s)
+}
+
+// crateRoot writes src/lib.rs, declaring every module and at level 3 and
+// above re-exporting their first functions.
+func (g *fixtureGenerator) crateRoot() error {
+	s := &sourceFile{name: "lib.rs", imports: map[string]bool{}}
+	for f := 0; f < g.spec.Files; f++ {
+		s.def(fmt.Sprintf("m%04d", f), "module")
+		s.println("pub mod m%04d;", f)
+	}
+	if g.spec.Level >= 3 {
+		s.println("")
+		for f := 0; f < g.spec.Files; f++ {
+			s.println("pub use m%04d::f%04d_00;", f, f)
+		}
+	}
+	return g.write(s)
+}
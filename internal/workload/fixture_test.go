@@ -0,0 +1,69 @@
+package workload
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestGenerateFixture_Levels(t *testing.T) {
+	t.Parallel()
+
+	for level := 1; level <= MaxFixtureLevel; level++ {
+		dir := t.TempDir()
+		stats, err := GenerateFixture(dir, FixtureSpec{Level: level, Files: 3, Functions: 4, Seed: 3})
+		require.NoError(t, err)
+		assert.Equal(t, 4, stats.Files, "three modules and the crate root")
+
+		data, err := os.ReadFile(filepath.Join(dir, "golden.json"))
+		require.NoError(t, err)
+		var golden Golden
+		require.NoError(t, json.Unmarshal(data, &golden))
+		assert.Len(t, golden.Definitions, stats.Definitions)
+		assert.Len(t, golden.Calls, stats.Calls)
+
+		lib, err := os.ReadFile(filepath.Join(dir, "src", "lib.rs"))
+		require.NoError(t, err)
+		m2, err := os.ReadFile(filepath.Join(dir, "src", "m0002.rs"))
+		require.NoError(t, err)
+
+		assert.Equal(t, level >= 2, strings.Contains(string(m2), "pub struct Cell0002<T> {"), "level %d", level)
+		assert.Equal(t, level >= 3, strings.Contains(string(lib), "pub use m0000::f0000_00;\n"), "level %d", level)
+		assert.Equal(t, level >= 4, stats.Implementations == 3, "level %d", level)
+	}
+}
+
+func TestGenerateFixture_DefinitionLines(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+
+	_, err := GenerateFixture(dir, FixtureSpec{Level: MaxFixtureLevel, Files: 4, Functions: 5, Seed: 9})
+	require.NoError(t, err)
+	data, err := os.ReadFile(filepath.Join(dir, "golden.json"))
+	require.NoError(t, err)
+	var golden Golden
+	require.NoError(t, json.Unmarshal(data, &golden))
+
+	// Every definition's line declares it, imports above it included.
+	for _, d := range golden.Definitions {
+		src, err := os.ReadFile(filepath.Join(dir, "src", d.File))
+		require.NoError(t, err)
+		lines := strings.Split(string(src), "\n")
+		require.Less(t, d.Line, len(lines), "%+v", d)
+		assert.Contains(t, lines[d.Line], d.Name, "%+v", d)
+	}
+}
+
+func TestGenerateFixture_InvalidSpec(t *testing.T) {
+	t.Parallel()
+
+	_, err := GenerateFixture(t.TempDir(), FixtureSpec{Level: MaxFixtureLevel + 1, Files: 1, Functions: 1})
+	assert.ErrorContains(t, err, "fixture level must be 1 to")
+	_, err = GenerateFixture(t.TempDir(), FixtureSpec{Level: 1, Files: 0, Functions: 1})
+	assert.ErrorContains(t, err, "need at least 1 file")
+}
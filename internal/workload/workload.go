@@ -0,0 +1,190 @@
+// Package workload generates synthetic source for exercising the indexer:
+// benchmark trees of N files of M functions each, wired together with
+// cross-file calls, imports, and method calls so extraction and every
+// resolution pass have work to do; Rust resolver fixtures with their
+// expected results; and arbitrary Rust snippets for fuzzing. Generation is
+// deterministic in its inputs.
+package workload
+
+import (
+	"fmt"
+	"maps"
+	"math/rand"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+// Languages are the languages Generate can write.
+var Languages = []string{"go", "rust"}
+
+// Spec describes a workload.
+type Spec struct {
+	Language  string // one of Languages
+	Files     int    // source files, not counting a Rust crate root
+	Functions int    // free functions per file; each file also gets a type with one method
+	Seed      int64  // seeds the choice of callees
+}
+
+// Stats summarizes a generated workload.
+type Stats struct {
+	Files     int    // files written, crate root included
+	Functions int    // functions and methods
+	Calls     int    // call sites of generated functions and methods
+	Hot       string // a function every file calls, for timing reference queries
+}
+
+// Generate writes the workload described by spec into dir, which is created
+// if needed.
+func Generate(dir string, spec Spec) (*Stats, error) {
+	if spec.Files < 1 || spec.Functions < 1 {
+		return nil, fmt.Errorf("workload: need at least 1 file and 1 function per file, got %d and %d", spec.Files, spec.Functions)
+	}
+	var gen func(*generator) error
+	switch spec.Language {
+	case "go":
+		gen = (*generator).goFiles
+	case "rust":
+		gen = (*generator).rustFiles
+	default:
+		return nil, fmt.Errorf("workload: unknown language %q (want %s)", spec.Language, strings.Join(Languages, ", "))
+	}
+	g := &generator{
+		dir:   dir,
+		spec:  spec,
+		rng:   rand.New(rand.NewSource(spec.Seed)),
+		stats: &Stats{},
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+	if err := gen(g); err != nil {
+		return nil, fmt.Errorf("workload: %w", err)
+	}
+	return g.stats, nil
+}
+
+type generator struct {
+	dir   string
+	spec  Spec
+	rng   *rand.Rand
+	stats *Stats
+}
+
+// callee picks a function of file or an earlier one, as (file, function).
+func (g *generator) callee(file int) (int, int) {
+	return g.rng.Intn(file + 1), g.rng.Intn(g.spec.Functions)
+}
+
+func (g *generator) write(name, content string) error {
+	path := filepath.Join(g.dir, name)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	g.stats.Files++
+	return os.WriteFile(path, []byte(content), 0o644)
+}
+
+// goFiles writes one package of files file0000.go, file0001.go, ... Function
+// 0 of every file calls Func0000_00, the hot function.
+func (g *generator) goFiles() error {
+	g.stats.Hot = "Func0000_00"
+	for f := 0; f < g.spec.Files; f++ {
+		var b strings.Builder
+		b.WriteString("package bench\n\nimport \"fmt\"\n\n")
+		fmt.Fprintf(&b, "// Type%04d is a generated type.\ntype Type%04d struct {\n\tID   int\n\tName string\n}\n\n", f, f)
+
+		cf, cn := g.callee(f)
+		fmt.Fprintf(&b, "// Run calls a generated function.\nfunc (t *Type%04d) Run(x int) int {\n", f)
+		fmt.Fprintf(&b, "\treturn Func%04d_%02d(x) + t.ID + len(fmt.Sprint(t.Name))\n}\n\n", cf, cn)
+		g.stats.Functions++
+		g.stats.Calls++
+
+		for n := 0; n < g.spec.Functions; n++ {
+			fmt.Fprintf(&b, "// Func%04d_%02d is a generated function.\nfunc Func%04d_%02d(x int) int {\n", f, n, f, n)
+			b.WriteString("\tif x <= 0 {\n\t\treturn 0\n\t}\n")
+			g.stats.Functions++
+			switch {
+			case n == 0:
+				b.WriteString("\treturn Func0000_00(x - 1)\n")
+				g.stats.Calls++
+			case n%3 == 1:
+				tf, _ := g.callee(f)
+				fmt.Fprintf(&b, "\tt := &Type%04d{ID: x}\n\treturn t.Run(x - 1)\n", tf)
+				g.stats.Calls++
+			default:
+				af, an := g.callee(f)
+				bf, bn := g.callee(f)
+				fmt.Fprintf(&b, "\treturn Func%04d_%02d(x-1) + Func%04d_%02d(x-2)\n", af, an, bf, bn)
+				g.stats.Calls += 2
+			}
+			b.WriteString("}\n\n")
+		}
+		if err := g.write(fmt.Sprintf("file%04d.go", f), b.String()); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// rustFiles writes a crate: src/lib.rs declaring modules src/m0000.rs,
+// src/m0001.rs, ..., which import the functions they call from other
+// modules. Function 0 of every module calls f0000_00, the hot function.
+func (g *generator) rustFiles() error {
+	g.stats.Hot = "f0000_00"
+	var lib strings.Builder
+	for f := 0; f < g.spec.Files; f++ {
+		fmt.Fprintf(&lib, "pub mod m%04d;\n", f)
+
+		var body strings.Builder
+		imports := map[string]bool{}
+		call := func(cf, cn int) string {
+			name := fmt.Sprintf("f%04d_%02d", cf, cn)
+			if cf != f {
+				imports[fmt.Sprintf("use crate::m%04d::%s;\n", cf, name)] = true
+			}
+			g.stats.Calls++
+			return name
+		}
+
+		fmt.Fprintf(&body, "pub struct Type%04d {\n    pub id: u64,\n}\n\n", f)
+		fmt.Fprintf(&body, "impl Type%04d {\n    pub fn run(&self, x: u64) -> u64 {\n", f)
+		fmt.Fprintf(&body, "        %s(x) + self.id\n    }\n}\n\n", call(g.callee(f)))
+		g.stats.Functions++
+
+		for n := 0; n < g.spec.Functions; n++ {
+			fmt.Fprintf(&body, "pub fn f%04d_%02d(x: u64) -> u64 {\n    if x == 0 {\n        return 0;\n    }\n", f, n)
+			g.stats.Functions++
+			switch {
+			case n == 0:
+				fmt.Fprintf(&body, "    %s(x - 1)\n", call(0, 0))
+			case n%3 == 1:
+				tf, _ := g.callee(f)
+				typeName := fmt.Sprintf("Type%04d", tf)
+				if tf != f {
+					imports[fmt.Sprintf("use crate::m%04d::%s;\n", tf, typeName)] = true
+				}
+				fmt.Fprintf(&body, "    let t = %s { id: x };\n    t.run(x - 1)\n", typeName)
+				g.stats.Calls++
+			default:
+				a := call(g.callee(f))
+				fmt.Fprintf(&body, "    %s(x - 1) + %s(x - 1)\n", a, call(g.callee(f)))
+			}
+			body.WriteString("}\n\n")
+		}
+
+		var src strings.Builder
+		for _, imp := range slices.Sorted(maps.Keys(imports)) {
+			src.WriteString(imp)
+		}
+		if len(imports) > 0 {
+			src.WriteString("\n")
+		}
+		src.WriteString(body.String())
+		if err := g.write(filepath.Join("src", fmt.Sprintf("m%04d.rs", f)), src.String()); err != nil {
+			return err
+		}
+	}
+	return g.write(filepath.Join("src", "lib.rs"), lib.String())
+}
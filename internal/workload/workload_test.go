@@ -0,0 +1,75 @@
+package workload
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestGenerate_Go(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+
+	stats, err := Generate(dir, Spec{Language: "go", Files: 3, Functions: 4, Seed: 7})
+	require.NoError(t, err)
+	assert.Equal(t, 3, stats.Files)
+	assert.Equal(t, 3*(4+1), stats.Functions, "4 functions and a method per file")
+	assert.Equal(t, "Func0000_00", stats.Hot)
+
+	src, err := os.ReadFile(filepath.Join(dir, "file0002.go"))
+	require.NoError(t, err)
+	assert.Contains(t, string(src), "func (t *Type0002) Run(x int) int {")
+	assert.Contains(t, string(src), "func Func0002_00(x int) int {")
+	assert.Contains(t, string(src), "return Func0000_00(x - 1)")
+}
+
+func TestGenerate_Rust(t *testing.T) {
+	t.Parallel()
+	dir := t.TempDir()
+
+	stats, err := Generate(dir, Spec{Language: "rust", Files: 3, Functions: 2, Seed: 7})
+	require.NoError(t, err)
+	assert.Equal(t, 4, stats.Files, "three modules and the crate root")
+	assert.Equal(t, "f0000_00", stats.Hot)
+
+	lib, err := os.ReadFile(filepath.Join(dir, "src", "lib.rs"))
+	require.NoError(t, err)
+	assert.Equal(t, "pub mod m0000;\npub mod m0001;\npub mod m0002;\n", string(lib))
+
+	src, err := os.ReadFile(filepath.Join(dir, "src", "m0002.rs"))
+	require.NoError(t, err)
+	assert.Contains(t, string(src), "use crate::m0000::f0000_00;\n")
+	assert.NotContains(t, string(src), "use crate::m0002::", "a module never imports its own items")
+	assert.Positive(t, stats.Calls)
+}
+
+func TestGenerate_Deterministic(t *testing.T) {
+	t.Parallel()
+	spec := Spec{Language: "rust", Files: 5, Functions: 6, Seed: 42}
+	a, b := t.TempDir(), t.TempDir()
+	_, err := Generate(a, spec)
+	require.NoError(t, err)
+	_, err = Generate(b, spec)
+	require.NoError(t, err)
+
+	for _, name := range []string{"lib.rs", "m0003.rs", "m0004.rs"} {
+		x, err := os.ReadFile(filepath.Join(a, "src", name))
+		require.NoError(t, err)
+		y, err := os.ReadFile(filepath.Join(b, "src", name))
+		require.NoError(t, err)
+		assert.Equal(t, string(x), string(y), name)
+	}
+}
+
+func TestGenerate_InvalidSpec(t *testing.T) {
+	t.Parallel()
+
+	_, err := Generate(t.TempDir(), Spec{Language: "cobol", Files: 1, Functions: 1})
+	assert.ErrorContains(t, err, `unknown language "cobol"`)
+
+	_, err = Generate(t.TempDir(), Spec{Language: "go", Files: 0, Functions: 1})
+	assert.ErrorContains(t, err, "at least 1 file")
+}
@@ -0,0 +1,52 @@
+package canopy
+
+import (
+	"go/build"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// TestLibraryImports checks what depending on the library compiles: the
+// packages the root package imports, directly or through the module's own
+// packages. The CLI's dependencies stay out, and so do the exporters when
+// built with canopy_noexport.
+func TestLibraryImports(t *testing.T) {
+	const module = "github.com/jward/canopy"
+	imports := func(tags ...string) map[string]bool {
+		ctx := build.Default
+		ctx.BuildTags = tags
+		seen := map[string]bool{}
+		var walk func(dir string)
+		walk = func(dir string) {
+			pkg, err := ctx.ImportDir(dir, 0)
+			require.NoError(t, err, dir)
+			for _, imp := range pkg.Imports {
+				if seen[imp] {
+					continue
+				}
+				seen[imp] = true
+				if rel, ok := strings.CutPrefix(imp, module+"/"); ok {
+					walk(filepath.FromSlash(rel))
+				}
+			}
+		}
+		walk(".")
+		return seen
+	}
+
+	full := imports()
+	assert.True(t, full[module+"/internal/store"])
+	assert.True(t, full[module+"/internal/parquet"])
+	for imp := range full {
+		assert.False(t, strings.HasPrefix(imp, "github.com/spf13/"), "the CLI's %s", imp)
+		assert.False(t, strings.HasPrefix(imp, module+"/cmd/"), imp)
+	}
+
+	core := imports("canopy_noexport")
+	assert.True(t, core[module+"/internal/store"])
+	assert.False(t, core[module+"/internal/parquet"], "exporters left out")
+}
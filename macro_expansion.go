@@ -0,0 +1,447 @@
+package canopy
+
+import (
+	"bytes"
+	"context"
+	"crypto/sha256"
+	"fmt"
+	"io/fs"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// WithMacroExpansion enables procedural macro expansion for Rust crates.
+// IndexDirectory runs cargo expand on each crate under the root (its
+// library, or its binary when there is no src/lib.rs) and keeps the items
+// expansion added: derive impls, builder types, the rewritten methods of
+// #[async_trait], and so on. They are written under dir, one file beside
+// each module file they belong to (<dir>/<package>/src/...), indexed as
+// Rust, and resolved together with that module. Each of their symbols maps
+// back to the item whose macro produced it, so definitions and
+// implementations land in the original source. A crate is expanded again
+// only when one of its files or its Cargo.toml changes. Requires cargo-expand; off by default
+// because expansion compiles every crate.
+func WithMacroExpansion(dir string) Option {
+	return func(e *Engine) {
+		e.expandDir = filepath.Clean(dir)
+	}
+}
+
+// expansionImportKind is the kind of the import linking an expanded file to
+// the module file it was expanded from. The Rust resolver treats it like an
+// include!.
+const expansionImportKind = "expansion"
+
+// cargoExpand runs cargo expand in dir, the directory of a crate's
+// Cargo.toml, for the target selected by args ("--lib" or "--bin <name>"),
+// and returns the expanded source. A variable so tests can stand in for
+// cargo.
+var cargoExpand = func(ctx context.Context, dir string, args ...string) ([]byte, error) {
+	cmd := exec.CommandContext(ctx, "cargo", append([]string{"expand", "--color", "never"}, args...)...)
+	cmd.Dir = dir
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		return nil, fmt.Errorf("cargo expand: %w: %s", err, strings.TrimSpace(stderr.String()))
+	}
+	return stdout.Bytes(), nil
+}
+
+// rustCrate is a Cargo package with indexed sources under an indexed root.
+type rustCrate struct {
+	cargoPackage
+	root  string            // src/lib.rs, or src/main.rs of a binary crate
+	files map[string]string // indexed module files under src -> content hash
+}
+
+// expandedFile is the output of expansion for one module file.
+type expandedFile struct {
+	path     string         // under the expansion directory
+	original string         // the module file
+	spans    []expandedSpan // the generated items, in order
+}
+
+// expandedSpan is a generated item in an expanded file and the original
+// item it maps back to.
+type expandedSpan struct {
+	startByte, endByte int
+	origin             runtime.RustItem
+}
+
+// expandMacros expands the Rust crates under root whose sources changed
+// since they were last expanded and indexes the generated items. Crates
+// that fail to expand are reported together after the others are done.
+func (e *Engine) expandMacros(ctx context.Context, root string) error {
+	crates, err := e.rustCrates(root)
+	if err != nil {
+		return err
+	}
+	var errs []error
+	for _, c := range crates {
+		if err := e.expandCrate(ctx, c); err != nil {
+			if ctx.Err() != nil {
+				return ctx.Err()
+			}
+			errs = append(errs, fmt.Errorf("expand %s: %w", c.name, err))
+		}
+	}
+	if len(errs) > 0 {
+		return fmt.Errorf("macro expansion had %d error(s): %w", len(errs), errs[0])
+	}
+	return nil
+}
+
+// rustCrates returns the crates owning the indexed Rust files under root,
+// ordered by directory. Crates without src/lib.rs or src/main.rs, and files
+// outside src or under src/bin, are left out.
+func (e *Engine) rustCrates(root string) ([]*rustCrate, error) {
+	rows, err := e.store.DB().Query("SELECT path, hash FROM files WHERE language = 'rust' ORDER BY path")
+	if err != nil {
+		return nil, fmt.Errorf("rust files: %w", err)
+	}
+	type rustFile struct{ path, hash string }
+	var files []rustFile
+	for rows.Next() {
+		var f rustFile
+		if err := rows.Scan(&f.path, &f.hash); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("rust files: scan: %w", err)
+		}
+		files = append(files, f)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("rust files: %w", err)
+	}
+
+	sep := string(filepath.Separator)
+	rootPrefix := filepath.Clean(root) + sep
+	packages := map[string]cargoPackage{}
+	byDir := map[string]*rustCrate{}
+	for _, f := range files {
+		if !strings.HasPrefix(f.path, rootPrefix) || strings.HasPrefix(f.path, e.expandDir+sep) ||
+			(e.targetDir != "" && strings.HasPrefix(f.path, e.targetDir+sep)) {
+			continue
+		}
+		pkg := cargoPackageFor(filepath.Dir(f.path), packages)
+		if pkg.name == "" {
+			continue
+		}
+		rel, err := filepath.Rel(filepath.Join(pkg.dir, "src"), f.path)
+		if err != nil || strings.HasPrefix(rel, "..") || strings.HasPrefix(rel, "bin"+sep) {
+			continue
+		}
+		c, ok := byDir[pkg.dir]
+		if !ok {
+			c = &rustCrate{cargoPackage: pkg, files: map[string]string{}}
+			byDir[pkg.dir] = c
+		}
+		c.files[f.path] = f.hash
+	}
+
+	var crates []*rustCrate
+	for _, c := range byDir {
+		for _, name := range []string{"lib.rs", "main.rs"} {
+			if p := filepath.Join(c.dir, "src", name); c.files[p] != "" {
+				c.root = p
+				break
+			}
+		}
+		if c.root != "" {
+			crates = append(crates, c)
+		}
+	}
+	sort.Slice(crates, func(i, j int) bool { return crates[i].dir < crates[j].dir })
+	return crates, nil
+}
+
+// modulePath returns the module path of one of the crate's files: "" for
+// the crate root, "a::b" for src/a/b.rs or src/a/b/mod.rs.
+func (c *rustCrate) modulePath(path string) string {
+	if path == c.root {
+		return ""
+	}
+	rel, _ := filepath.Rel(filepath.Join(c.dir, "src"), path)
+	rel = strings.TrimSuffix(strings.TrimSuffix(filepath.ToSlash(rel), ".rs"), "/mod")
+	return strings.ReplaceAll(rel, "/", "::")
+}
+
+// fingerprint identifies the crate's sources and manifest, so an unchanged
+// crate is not expanded again.
+func (c *rustCrate) fingerprint() string {
+	paths := make([]string, 0, len(c.files))
+	for p := range c.files {
+		paths = append(paths, p)
+	}
+	sort.Strings(paths)
+	h := sha256.New()
+	for _, p := range paths {
+		fmt.Fprintf(h, "%s\x00%s\n", p, c.files[p])
+	}
+	manifest, _ := os.ReadFile(filepath.Join(c.dir, "Cargo.toml")) // a missing manifest just changes the hash
+	h.Write(manifest)
+	return fmt.Sprintf("%x", h.Sum(nil))
+}
+
+// expandCrate expands one crate, if it changed since its last expansion
+// into this index, and indexes the result. The fingerprint of the sources
+// is recorded last, so a failed run is retried on the next one.
+func (e *Engine) expandCrate(ctx context.Context, c *rustCrate) error {
+	dir := filepath.Join(e.expandDir, c.name)
+	fingerprintKey := "macro_expansion:" + c.dir
+	fingerprint := c.fingerprint()
+	old, err := e.store.GetMetadata(fingerprintKey)
+	if err != nil {
+		return err
+	}
+	if old == fingerprint {
+		return nil
+	}
+
+	target := []string{"--lib"}
+	if filepath.Base(c.root) == "main.rs" {
+		target = []string{"--bin", c.name}
+	}
+	expanded, err := cargoExpand(ctx, c.dir, target...)
+	if err != nil {
+		return err
+	}
+	files, err := e.generatedItems(ctx, c, expanded)
+	if err != nil {
+		return err
+	}
+
+	// Replace the previous expansion: write the new files, then drop the
+	// ones no longer produced, from disk and from the index.
+	written := map[string]bool{}
+	var paths []string
+	for _, f := range files {
+		if err := os.MkdirAll(filepath.Dir(f.path), 0o755); err != nil {
+			return err
+		}
+		if err := os.WriteFile(f.path, f.content(expanded), 0o644); err != nil {
+			return err
+		}
+		written[f.path] = true
+		paths = append(paths, f.path)
+	}
+	err = filepath.WalkDir(dir, func(path string, d fs.DirEntry, err error) error {
+		if err != nil || d.IsDir() || written[path] {
+			return err
+		}
+		return os.Remove(path)
+	})
+	if err != nil && !os.IsNotExist(err) {
+		return fmt.Errorf("prune expanded files: %w", err)
+	}
+	indexed, err := e.indexedUnder(dir)
+	if err != nil {
+		return err
+	}
+	for id, path := range indexed {
+		if !written[path] {
+			if err := e.removeFile(id); err != nil {
+				return fmt.Errorf("remove %s: %w", path, err)
+			}
+		}
+	}
+
+	if err := e.IndexFiles(ctx, paths); err != nil {
+		return err
+	}
+	for _, f := range files {
+		if err := e.linkExpansion(f); err != nil {
+			return err
+		}
+	}
+	return e.store.SetMetadata(fingerprintKey, fingerprint)
+}
+
+// generatedItems compares the items of a crate's expanded source with the
+// items of its module files and returns the items expansion added, grouped
+// by the module file they belong to. Items are compared per module by kind
+// and name (impl blocks by trait and type); the leftovers of each key are
+// generated. A generated item maps back to the original item before it in
+// the same module, which is the item its macro was applied to, or failing
+// that the one after it.
+func (e *Engine) generatedItems(ctx context.Context, c *rustCrate, expanded []byte) ([]*expandedFile, error) {
+	type original struct {
+		path string
+		item runtime.RustItem
+	}
+	originals := map[string][]original{} // module + "\x00" + key -> items in source order
+	moduleFiles := map[string]string{}    // module path -> the file declaring it
+	for path := range c.files {
+		module := c.modulePath(path)
+		moduleFiles[module] = path
+		src, err := e.readSource(path)
+		if err != nil {
+			return nil, fmt.Errorf("read %s: %w", path, err)
+		}
+		items, err := runtime.RustItems(ctx, src)
+		if err != nil {
+			return nil, err
+		}
+		for _, it := range items {
+			key := joinRustModule(module, it.Module) + "\x00" + it.Key
+			originals[key] = append(originals[key], original{path, it})
+		}
+	}
+
+	items, err := runtime.RustItems(ctx, expanded)
+	if err != nil {
+		return nil, err
+	}
+	// Match the expanded items to the originals in order.
+	matches := make([]*original, len(items))
+	for i, it := range items {
+		key := it.Module + "\x00" + it.Key
+		if list := originals[key]; len(list) > 0 {
+			matches[i] = &list[0]
+			originals[key] = list[1:]
+		}
+	}
+
+	byPath := map[string]*expandedFile{}
+	var files []*expandedFile
+	for i, it := range items {
+		if matches[i] != nil {
+			continue
+		}
+		var anchor *original
+		for j := i - 1; j >= 0 && anchor == nil; j-- {
+			if matches[j] != nil && items[j].Module == it.Module {
+				anchor = matches[j]
+			}
+		}
+		for j := i + 1; j < len(items) && anchor == nil; j++ {
+			if matches[j] != nil && items[j].Module == it.Module {
+				anchor = matches[j]
+			}
+		}
+		if anchor == nil {
+			// Nothing of the module survived to anchor to: map back to the
+			// start of the file declaring the module.
+			anchor = &original{path: moduleFileFor(moduleFiles, it.Module, c.root)}
+		}
+		f, ok := byPath[anchor.path]
+		if !ok {
+			rel, _ := filepath.Rel(c.dir, anchor.path)
+			f = &expandedFile{path: filepath.Join(e.expandDir, c.name, rel), original: anchor.path}
+			byPath[anchor.path] = f
+			files = append(files, f)
+		}
+		f.spans = append(f.spans, expandedSpan{startByte: it.StartByte, endByte: it.EndByte, origin: anchor.item})
+	}
+	return files, nil
+}
+
+// content renders an expanded file from the crate's expanded source,
+// rewriting each span's offsets to its position in the file.
+func (f *expandedFile) content(expanded []byte) []byte {
+	var buf bytes.Buffer
+	fmt.Fprintf(&buf, "// Items generated by macro expansion of %s.\n", filepath.Base(f.original))
+	for i := range f.spans {
+		s := &f.spans[i]
+		buf.WriteString("\n")
+		text := expanded[s.startByte:s.endByte]
+		s.startByte = buf.Len()
+		buf.Write(text)
+		s.endByte = buf.Len()
+		buf.WriteString("\n")
+	}
+	return buf.Bytes()
+}
+
+// linkExpansion links an indexed expanded file to its module file: an
+// import of kind expansionImportKind for the resolver, and for each symbol
+// a primary fragment at the original item, which symbol locations report.
+func (e *Engine) linkExpansion(f *expandedFile) error {
+	ef, err := e.store.FileByPath(f.path)
+	if err != nil {
+		return err
+	}
+	of, err := e.store.FileByPath(f.original)
+	if err != nil {
+		return err
+	}
+	if ef == nil || of == nil {
+		return nil
+	}
+
+	db := e.store.DB()
+	if _, err := db.Exec("DELETE FROM imports WHERE file_id = ? AND kind = ?", ef.ID, expansionImportKind); err != nil {
+		return fmt.Errorf("link expansion: %w", err)
+	}
+	if _, err := e.store.InsertImport(&store.Import{
+		FileID: ef.ID, Source: f.original, ImportedName: &f.original, Kind: expansionImportKind, Scope: "file",
+	}); err != nil {
+		return fmt.Errorf("link expansion: %w", err)
+	}
+	if _, err := db.Exec(
+		"DELETE FROM symbol_fragments WHERE symbol_id IN (SELECT id FROM symbols WHERE file_id = ?)", ef.ID,
+	); err != nil {
+		return fmt.Errorf("link expansion: %w", err)
+	}
+	syms, err := e.store.SymbolsByFile(ef.ID)
+	if err != nil {
+		return fmt.Errorf("link expansion: %w", err)
+	}
+	for _, sym := range syms {
+		for _, s := range f.spans {
+			if sym.StartByte < s.startByte || sym.StartByte >= s.endByte {
+				continue
+			}
+			if _, err := e.store.InsertSymbolFragment(&store.SymbolFragment{
+				SymbolID:  sym.ID,
+				FileID:    of.ID,
+				StartLine: s.origin.StartLine,
+				StartCol:  s.origin.StartCol,
+				EndLine:   s.origin.EndLine,
+				EndCol:    s.origin.EndCol,
+				IsPrimary: true,
+			}); err != nil {
+				return fmt.Errorf("link expansion: %w", err)
+			}
+			break
+		}
+	}
+	// Both sides see each other's names through the new import.
+	if e.blastRadius == nil {
+		e.blastRadius = make(map[int64]bool)
+	}
+	e.blastRadius[ef.ID] = true
+	e.blastRadius[of.ID] = true
+	return nil
+}
+
+// moduleFileFor returns the file declaring module, or that of its nearest
+// enclosing module, which for inline modules is the file containing them.
+func moduleFileFor(moduleFiles map[string]string, module, root string) string {
+	for module != "" {
+		if path, ok := moduleFiles[module]; ok {
+			return path
+		}
+		i := strings.LastIndex(module, "::")
+		if i < 0 {
+			break
+		}
+		module = module[:i]
+	}
+	return root
+}
+
+// joinRustModule appends an inline module path to a file's module path.
+func joinRustModule(path, inline string) string {
+	if path == "" || inline == "" {
+		return path + inline
+	}
+	return path + "::" + inline
+}
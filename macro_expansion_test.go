@@ -0,0 +1,93 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMacroExpansion(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	expandDir := t.TempDir()
+
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte("[package]\nname = \"demo\"\n"), 0o644))
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	lib := filepath.Join(root, "src", "lib.rs")
+	libSrc := `use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Config {
+    pub port: u16,
+}
+
+pub fn build() -> ConfigBuilder {
+    ConfigBuilder::default()
+}
+`
+	require.NoError(t, os.WriteFile(lib, []byte(libSrc), 0o644))
+
+	// What cargo expand prints for the crate: the derive is gone and the
+	// builder it generated follows the struct.
+	expanded := `#![feature(prelude_import)]
+#[prelude_import]
+use std::prelude::rust_2021::*;
+#[macro_use]
+extern crate std;
+use derive_builder::Builder;
+pub struct Config {
+    pub port: u16,
+}
+pub struct ConfigBuilder {
+    port: Option<u16>,
+}
+impl ConfigBuilder {
+    pub fn port(&mut self, value: u16) -> &mut Self {
+        self.port = Some(value);
+        self
+    }
+}
+pub fn build() -> ConfigBuilder {
+    ConfigBuilder::default()
+}
+`
+	var calls []string
+	orig := cargoExpand
+	cargoExpand = func(_ context.Context, dir string, args ...string) ([]byte, error) {
+		calls = append(calls, dir+" "+strings.Join(args, " "))
+		return []byte(expanded), nil
+	}
+	t.Cleanup(func() { cargoExpand = orig })
+
+	e := newIntegrationEngine(t, WithLanguages("rust"), WithMacroExpansion(expandDir))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	assert.Equal(t, []string{root + " --lib"}, calls)
+
+	// Only the generated items are kept, beside the module they came from.
+	generated, err := os.ReadFile(filepath.Join(expandDir, "demo", "src", "lib.rs"))
+	require.NoError(t, err)
+	assert.Contains(t, string(generated), "pub struct ConfigBuilder")
+	assert.Contains(t, string(generated), "impl ConfigBuilder")
+	assert.NotContains(t, string(generated), "pub struct Config {")
+	assert.NotContains(t, string(generated), "pub fn build")
+
+	// The generated type resolves and maps back to the derive.
+	lines := strings.Split(libSrc, "\n")
+	locs, err := e.Query().DefinitionAt(lib, 7, strings.Index(lines[7], "ConfigBuilder"))
+	require.NoError(t, err)
+	require.Len(t, locs, 1)
+	assert.Equal(t, lib, locs[0].File)
+	assert.Equal(t, 2, locs[0].StartLine)
+	assert.Equal(t, 5, locs[0].EndLine)
+	assert.Equal(t, strings.Index(libSrc, "#[derive"), locs[0].StartByte)
+
+	// An unchanged crate is not expanded again.
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	assert.Len(t, calls, 1)
+}
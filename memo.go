@@ -0,0 +1,265 @@
+package canopy
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+	"sync"
+)
+
+// Memo answers queries on demand and remembers the answers, for long-lived
+// clients such as an LSP server or a watch loop that ask the same questions
+// between edits. Each answer records what it was computed from: the files
+// of the queried symbol or position and of the locations it returns, and
+// the queried symbol itself. When IndexFiles and Resolve change the index,
+// only the answers depending on what changed are dropped, and they are
+// recomputed the next time they are asked for. Files whose symbols move or
+// change, files removed from the index, and every symbol a re-resolved
+// file's references, calls, or implementations pointed at before or point
+// at after count as changed. A full resolve drops every answer.
+//
+// A Memo is safe for concurrent use. Get one with Engine.Memo.
+type Memo struct {
+	e *Engine
+
+	mu      sync.Mutex
+	entries map[memoKey]*memoEntry
+	byDep   map[string]map[memoKey]bool // dependency -> entries using it
+	gen     uint64                      // bumped by every invalidation
+	hits    int
+	misses  int
+}
+
+// MemoStats counts a Memo's answers.
+type MemoStats struct {
+	Entries int // answers currently remembered
+	Hits    int // queries answered from memory
+	Misses  int // queries computed
+}
+
+// memoKey identifies a query and its arguments.
+type memoKey struct {
+	query     string
+	symbolID  int64
+	file      string
+	line, col int
+}
+
+type memoEntry struct {
+	value any
+	deps  []string
+}
+
+// Memo returns the Engine's memoized query view, creating it on first use.
+// Until then IndexFiles and Resolve do no bookkeeping for it.
+func (e *Engine) Memo() *Memo {
+	e.memoOnce.Do(func() {
+		e.memo = &Memo{e: e, entries: map[memoKey]*memoEntry{}, byDep: map[string]map[memoKey]bool{}}
+	})
+	return e.memo
+}
+
+// ReferencesTo is QueryBuilder.ReferencesTo, memoized.
+func (m *Memo) ReferencesTo(symbolID int64) ([]Location, error) {
+	return memoLocations(m, memoKey{query: "references", symbolID: symbolID}, func(q *QueryBuilder) ([]Location, error) {
+		return q.ReferencesTo(symbolID)
+	})
+}
+
+// Implementations is QueryBuilder.Implementations, memoized.
+func (m *Memo) Implementations(symbolID int64) ([]Location, error) {
+	return memoLocations(m, memoKey{query: "implementations", symbolID: symbolID}, func(q *QueryBuilder) ([]Location, error) {
+		return q.Implementations(symbolID)
+	})
+}
+
+// DefinitionAt is QueryBuilder.DefinitionAt, memoized.
+func (m *Memo) DefinitionAt(file string, line, col int) ([]Location, error) {
+	return memoLocations(m, memoKey{query: "definition", file: file, line: line, col: col}, func(q *QueryBuilder) ([]Location, error) {
+		return q.DefinitionAt(file, line, col)
+	})
+}
+
+// Callers is QueryBuilder.Callers, memoized.
+func (m *Memo) Callers(symbolID int64) ([]*CallEdge, error) {
+	return memoCallEdges(m, memoKey{query: "callers", symbolID: symbolID}, func(q *QueryBuilder) ([]*CallEdge, error) {
+		return q.Callers(symbolID)
+	})
+}
+
+// Callees is QueryBuilder.Callees, memoized.
+func (m *Memo) Callees(symbolID int64) ([]*CallEdge, error) {
+	return memoCallEdges(m, memoKey{query: "callees", symbolID: symbolID}, func(q *QueryBuilder) ([]*CallEdge, error) {
+		return q.Callees(symbolID)
+	})
+}
+
+// Stats reports how many answers the Memo holds and how queries were
+// answered so far.
+func (m *Memo) Stats() MemoStats {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	return MemoStats{Entries: len(m.entries), Hits: m.hits, Misses: m.misses}
+}
+
+func memoLocations(m *Memo, key memoKey, compute func(*QueryBuilder) ([]Location, error)) ([]Location, error) {
+	return memoize(m, key, func(q *QueryBuilder) ([]Location, []string, error) {
+		locs, err := compute(q)
+		if err != nil {
+			return nil, nil, err
+		}
+		var deps []string
+		for _, loc := range locs {
+			deps = append(deps, memoFileDep(loc.File))
+		}
+		return locs, deps, nil
+	})
+}
+
+func memoCallEdges(m *Memo, key memoKey, compute func(*QueryBuilder) ([]*CallEdge, error)) ([]*CallEdge, error) {
+	return memoize(m, key, func(q *QueryBuilder) ([]*CallEdge, []string, error) {
+		edges, err := compute(q)
+		if err != nil {
+			return nil, nil, err
+		}
+		var deps []string
+		for _, edge := range edges {
+			deps = append(deps, memoSymbolDep(edge.CallerSymbolID), memoSymbolDep(edge.CalleeSymbolID))
+		}
+		return edges, deps, nil
+	})
+}
+
+// memoize returns the remembered answer to key, or computes and remembers
+// it. Besides the dependencies compute reports, an answer depends on its
+// queried symbol and that symbol's file, or on its queried file.
+func memoize[T any](m *Memo, key memoKey, compute func(*QueryBuilder) (T, []string, error)) (T, error) {
+	m.mu.Lock()
+	if entry, ok := m.entries[key]; ok {
+		m.hits++
+		m.mu.Unlock()
+		return entry.value.(T), nil
+	}
+	m.misses++
+	gen := m.gen
+	m.mu.Unlock()
+
+	q := m.e.Query()
+	value, deps, err := compute(q)
+	if err != nil {
+		var zero T
+		return zero, err
+	}
+	if key.file != "" {
+		deps = append(deps, memoFileDep(key.file))
+	} else {
+		deps = append(deps, memoSymbolDep(key.symbolID))
+		loc, err := q.symbolLocation(key.symbolID)
+		if err != nil {
+			var zero T
+			return zero, fmt.Errorf("memo: %w", err)
+		}
+		if loc != nil {
+			deps = append(deps, memoFileDep(loc.File))
+		}
+	}
+
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	// An answer computed across an invalidation may already be stale.
+	if m.gen == gen {
+		m.entries[key] = &memoEntry{value: value, deps: deps}
+		for _, dep := range deps {
+			if m.byDep[dep] == nil {
+				m.byDep[dep] = map[memoKey]bool{}
+			}
+			m.byDep[dep][key] = true
+		}
+	}
+	return value, nil
+}
+
+func memoFileDep(path string) string { return "file:" + path }
+func memoSymbolDep(id int64) string  { return "sym:" + strconv.FormatInt(id, 10) }
+
+// invalidate drops the answers depending on any of deps. A nil Memo
+// ignores it.
+func (m *Memo) invalidate(deps ...string) {
+	if m == nil || len(deps) == 0 {
+		return
+	}
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.gen++
+	for _, dep := range deps {
+		for key := range m.byDep[dep] {
+			m.drop(key)
+		}
+	}
+}
+
+// drop forgets one answer. m.mu must be held.
+func (m *Memo) drop(key memoKey) {
+	entry, ok := m.entries[key]
+	if !ok {
+		return
+	}
+	delete(m.entries, key)
+	for _, dep := range entry.deps {
+		delete(m.byDep[dep], key)
+		if len(m.byDep[dep]) == 0 {
+			delete(m.byDep, dep)
+		}
+	}
+}
+
+// reset drops every answer. A nil Memo ignores it.
+func (m *Memo) reset() {
+	if m == nil {
+		return
+	}
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.gen++
+	m.entries = map[memoKey]*memoEntry{}
+	m.byDep = map[string]map[memoKey]bool{}
+}
+
+// memoDeps returns the Memo dependencies the files in the blast radius
+// touch: their paths, and the symbols their resolved references, call
+// edges, and implementations point at. Resolve invalidates them both
+// before and after re-resolving the files.
+func (e *Engine) memoDeps() ([]string, error) {
+	if e.memo == nil || len(e.blastRadius) == 0 {
+		return nil, nil
+	}
+	var fileIDs []any
+	for fid := range e.blastRadius {
+		fileIDs = append(fileIDs, fid)
+	}
+	in := "(" + strings.Repeat("?,", len(fileIDs)-1) + "?)"
+	query := `SELECT 'file:' || path FROM files WHERE id IN ` + in + `
+		UNION SELECT 'sym:' || rr.target_symbol_id FROM resolved_references rr
+		  JOIN references_ r ON r.id = rr.reference_id WHERE r.file_id IN ` + in + `
+		UNION SELECT 'sym:' || callee_symbol_id FROM call_graph WHERE file_id IN ` + in + `
+		UNION SELECT 'sym:' || interface_symbol_id FROM implementations WHERE file_id IN ` + in + `
+		UNION SELECT 'sym:' || type_symbol_id FROM implementations WHERE file_id IN ` + in
+	var args []any
+	for range 5 {
+		args = append(args, fileIDs...)
+	}
+	rows, err := e.store.DB().Query(query, args...)
+	if err != nil {
+		return nil, fmt.Errorf("memo dependencies: %w", err)
+	}
+	defer rows.Close()
+	var deps []string
+	for rows.Next() {
+		var dep string
+		if err := rows.Scan(&dep); err != nil {
+			return nil, fmt.Errorf("memo dependencies: scan: %w", err)
+		}
+		deps = append(deps, dep)
+	}
+	return deps, rows.Err()
+}
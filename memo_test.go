@@ -0,0 +1,60 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMemo(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	dir := t.TempDir()
+	libPath := writeGoFile(t, dir, "lib.go", "package main\n\nfunc Helper() string {\n\treturn \"hello\"\n}\n")
+	mainPath := writeGoFile(t, dir, "main.go", "package main\n\nfunc main() {\n\tHelper()\n}\n")
+	otherPath := writeGoFile(t, dir, "other.go", "package main\n\nfunc Other() int { return 1 }\n")
+	require.NoError(t, e.IndexFiles(ctx, []string{libPath, mainPath, otherPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	helper, err := e.Query().SymbolAt(libPath, 2, 5)
+	require.NoError(t, err)
+	require.NotNil(t, helper)
+	require.Equal(t, "Helper", helper.Name)
+
+	m := e.Memo()
+	refs, err := m.ReferencesTo(helper.ID)
+	require.NoError(t, err)
+	assert.Len(t, refs, 1)
+	again, err := m.ReferencesTo(helper.ID)
+	require.NoError(t, err)
+	assert.Equal(t, refs, again)
+	callers, err := m.Callers(helper.ID)
+	require.NoError(t, err)
+	assert.Len(t, callers, 1)
+	assert.Equal(t, MemoStats{Entries: 2, Hits: 1, Misses: 2}, m.Stats())
+
+	// A change unrelated to Helper keeps its answers.
+	writeGoFile(t, dir, "other.go", "package main\n\nfunc Other() int { return 2 }\n")
+	require.NoError(t, e.IndexFiles(ctx, []string{otherPath}))
+	require.NoError(t, e.Resolve(ctx))
+	_, err = m.ReferencesTo(helper.ID)
+	require.NoError(t, err)
+	assert.Equal(t, MemoStats{Entries: 2, Hits: 2, Misses: 2}, m.Stats())
+
+	// A new call to Helper drops them, and they are recomputed on demand.
+	writeGoFile(t, dir, "main.go", "package main\n\nfunc main() {\n\tHelper()\n\tHelper()\n}\n")
+	require.NoError(t, e.IndexFiles(ctx, []string{mainPath}))
+	require.NoError(t, e.Resolve(ctx))
+	assert.Zero(t, m.Stats().Entries)
+	refs, err = m.ReferencesTo(helper.ID)
+	require.NoError(t, err)
+	assert.Len(t, refs, 2)
+	assert.Equal(t, MemoStats{Entries: 1, Hits: 2, Misses: 3}, m.Stats())
+
+	// A full resolve drops everything.
+	e.blastRadius = nil
+	require.NoError(t, e.Resolve(ctx))
+	assert.Zero(t, m.Stats().Entries)
+}
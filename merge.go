@@ -0,0 +1,84 @@
+package canopy
+
+import (
+	"context"
+	"errors"
+	"fmt"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Merge copies the indexes in the databases at dbPaths, such as the
+// indexes of disjoint source roots or of CI shards, into the Engine's
+// database, then resolves the files the merge may link: files with
+// references their own index could not resolve, and files with unresolved
+// references named like a merged top-level symbol. References one index
+// resolved stay resolved.
+//
+// The indexes must cover disjoint files and be built with the Engine's
+// scripts. Dependency stubs are the exception to disjointness: a stub
+// already in the database is kept, and references to another index's copy
+// of it are resolved again, against the kept one.
+func (e *Engine) Merge(ctx context.Context, dbPaths ...string) error {
+	if e.blastRadius == nil {
+		e.blastRadius = make(map[int64]bool)
+	}
+	for _, path := range dbPaths {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if err := e.mergeIndex(path); err != nil {
+			return fmt.Errorf("merge %s: %w", path, err)
+		}
+	}
+	return e.Resolve(ctx)
+}
+
+// mergeIndex copies the index at dbPath into the Engine's database, adding
+// the files it may link to the blast radius.
+func (e *Engine) mergeIndex(dbPath string) error {
+	src, err := store.NewStore(dbPath)
+	if err != nil {
+		return err
+	}
+	defer src.Close()
+	hash, err := src.GetMetadata("scripts_hash")
+	if err != nil {
+		return err
+	}
+	if hash != e.scriptsHash() {
+		return errors.New("index was not built with the Engine's scripts; reindex it first")
+	}
+
+	files, err := src.AllFiles()
+	if err != nil {
+		return err
+	}
+	var shared []int64
+	for _, f := range files {
+		existing, err := e.store.FileByPath(f.Path)
+		if err != nil {
+			return err
+		}
+		if existing == nil {
+			continue
+		}
+		if f.Language != rustdocStubLanguage {
+			return fmt.Errorf("%s is already indexed; merged indexes must cover disjoint files", f.Path)
+		}
+		shared = append(shared, f.ID)
+	}
+
+	fileIDs, err := e.store.ImportFromExcept(src, shared)
+	if err != nil {
+		return err
+	}
+	blastFileIDs, err := e.store.FilesWithUnlinkedReferences(fileIDs)
+	if err != nil {
+		return err
+	}
+	for _, fid := range blastFileIDs {
+		e.blastRadius[fid] = true
+	}
+	return nil
+}
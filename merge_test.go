@@ -0,0 +1,44 @@
+package canopy
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// indexToDatabase indexes and resolves dir into a new database and returns
+// its path.
+func indexToDatabase(t *testing.T, dir string) string {
+	t.Helper()
+	dbPath := filepath.Join(t.TempDir(), "index.db")
+	e, err := New(dbPath, filepath.Join(findModuleRoot(t), "scripts"), WithLanguages("go"))
+	require.NoError(t, err)
+	defer e.Close()
+	ctx := context.Background()
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	return dbPath
+}
+
+func TestMerge(t *testing.T) {
+	ctx := context.Background()
+	root := writeShardRepo(t)
+	a := indexToDatabase(t, filepath.Join(root, "a"))
+	b := indexToDatabase(t, filepath.Join(root, "b"))
+
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	require.NoError(t, e.Merge(ctx, a, b))
+	files, err := e.store.AllFiles()
+	require.NoError(t, err)
+	assert.Len(t, files, 2)
+	assert.Equal(t, 1, resolvedRefsTo(t, e, "Helper"), "b's call into a is linked")
+	violations, err := e.store.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+
+	err = e.Merge(ctx, a)
+	assert.ErrorContains(t, err, "disjoint")
+}
@@ -0,0 +1,76 @@
+package canopy
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io/fs"
+	"path/filepath"
+)
+
+// SetOverlay makes the Engine read path from content instead of disk, as
+// for an unsaved editor buffer, then re-indexes the file and resolves the
+// change so queries reflect the buffer. Only the file and the files its
+// change affects are re-resolved. The overlay stays in effect for later
+// indexing runs until ClearOverlay.
+func (e *Engine) SetOverlay(ctx context.Context, path string, content []byte) error {
+	path = filepath.Clean(path)
+	e.overlayMu.Lock()
+	if e.overlays == nil {
+		e.overlays = make(map[string][]byte)
+	}
+	e.overlays[path] = bytes.Clone(content)
+	e.overlayMu.Unlock()
+
+	if err := e.IndexFiles(ctx, []string{path}); err != nil {
+		return fmt.Errorf("set overlay: %w", err)
+	}
+	if err := e.Resolve(ctx); err != nil {
+		return fmt.Errorf("set overlay: %w", err)
+	}
+	return nil
+}
+
+// ClearOverlay drops the overlay for path and re-indexes it from disk, as
+// when a buffer is saved or closed. A file that does not exist on disk is
+// removed from the index.
+func (e *Engine) ClearOverlay(ctx context.Context, path string) error {
+	path = filepath.Clean(path)
+	e.overlayMu.Lock()
+	delete(e.overlays, path)
+	e.overlayMu.Unlock()
+
+	if _, err := e.fsys.Stat(path); errors.Is(err, fs.ErrNotExist) {
+		if e.blastRadius == nil {
+			e.blastRadius = make(map[int64]bool)
+		}
+		f, err := e.store.FileByPath(path)
+		if err != nil {
+			return fmt.Errorf("clear overlay: %w", err)
+		}
+		if f != nil {
+			if err := e.removeFile(f.ID); err != nil {
+				return fmt.Errorf("clear overlay: %w", err)
+			}
+		}
+	} else if err := e.IndexFiles(ctx, []string{path}); err != nil {
+		return fmt.Errorf("clear overlay: %w", err)
+	}
+	if err := e.Resolve(ctx); err != nil {
+		return fmt.Errorf("clear overlay: %w", err)
+	}
+	return nil
+}
+
+// readSource returns the content of path: its overlay if one is set,
+// otherwise the file in the Engine's file system.
+func (e *Engine) readSource(path string) ([]byte, error) {
+	e.overlayMu.RLock()
+	content, ok := e.overlays[filepath.Clean(path)]
+	e.overlayMu.RUnlock()
+	if ok {
+		return content, nil
+	}
+	return e.fsys.ReadFile(path)
+}
@@ -0,0 +1,86 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSetOverlay_ReplacesFileUntilCleared(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel))
+		ctx := context.Background()
+		dir := t.TempDir()
+		const onDisk = `package main
+
+func greet() {}
+
+func main() {
+	greet()
+}
+`
+		path := writeGoFile(t, dir, "main.go", onDisk)
+		require.NoError(t, e.IndexDirectory(ctx, dir))
+		require.NoError(t, e.Resolve(ctx))
+
+		require.NoError(t, e.SetOverlay(ctx, path, []byte(`package main
+
+func hello() {}
+
+func main() {
+	hello()
+}
+`)))
+
+		greet, err := e.store.SymbolsByName("greet")
+		require.NoError(t, err)
+		assert.Empty(t, greet)
+		hello, err := e.store.SymbolsByName("hello")
+		require.NoError(t, err)
+		require.Len(t, hello, 1)
+		locs, err := e.Query().ReferencesTo(hello[0].ID)
+		require.NoError(t, err)
+		require.Len(t, locs, 1)
+		assert.Equal(t, 5, locs[0].StartLine)
+
+		sym, err := e.Query().SymbolAt(path, 2, 5)
+		require.NoError(t, err)
+		require.NotNil(t, sym)
+		assert.Equal(t, "hello", sym.Name)
+
+		disk, err := os.ReadFile(path)
+		require.NoError(t, err)
+		assert.Equal(t, onDisk, string(disk), "overlay never touches disk")
+
+		require.NoError(t, e.ClearOverlay(ctx, path))
+		greet, err = e.store.SymbolsByName("greet")
+		require.NoError(t, err)
+		assert.Len(t, greet, 1)
+		hello, err = e.store.SymbolsByName("hello")
+		require.NoError(t, err)
+		assert.Empty(t, hello)
+	}
+}
+
+func TestClearOverlay_RemovesUnsavedFile(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	path := filepath.Join(t.TempDir(), "scratch.go")
+
+	require.NoError(t, e.SetOverlay(ctx, path, []byte("package main\n\nfunc Scratch() {}\n")))
+	syms, err := e.store.SymbolsByName("Scratch")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+
+	require.NoError(t, e.ClearOverlay(ctx, path))
+	syms, err = e.store.SymbolsByName("Scratch")
+	require.NoError(t, err)
+	assert.Empty(t, syms)
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	assert.Nil(t, f)
+}
@@ -0,0 +1,83 @@
+package canopy
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// WithParseCache makes IndexFiles keep what it extracts from each file in a
+// cache database under dir, keyed by the file's path and content hash, and
+// reuse it whenever the same content turns up at that path again. After a
+// switch to another git branch and back, files identical to an earlier
+// version are then copied from the cache instead of parsed and extracted.
+// Several databases, and several Engines at once, can share one cache
+// directory. Each version of the extraction scripts gets its own cache
+// database, so entries from other versions are never reused; delete the
+// directory to reclaim their space.
+func WithParseCache(dir string) Option {
+	return func(e *Engine) {
+		e.parseCacheDir = dir
+	}
+}
+
+// openParseCache opens the cache database for the current scripts, if a
+// cache directory is configured.
+func (e *Engine) openParseCache() error {
+	if e.parseCacheDir == "" {
+		return nil
+	}
+	if err := os.MkdirAll(e.parseCacheDir, 0o755); err != nil {
+		return fmt.Errorf("create parse cache: %w", err)
+	}
+	s, err := store.NewStore(filepath.Join(e.parseCacheDir, "extract-"+e.scriptsHash()[:16]+".db"))
+	if err != nil {
+		return fmt.Errorf("open parse cache: %w", err)
+	}
+	if err := s.Migrate(); err != nil {
+		s.Close()
+		return fmt.Errorf("migrate parse cache: %w", err)
+	}
+	e.parseCache = s
+	return nil
+}
+
+// parseCacheKey names a file's cache entry: extraction depends on both the
+// content and the path, which places the file in its package or module.
+func parseCacheKey(path, hash string) string {
+	return hash + ":" + path
+}
+
+// loadCachedFile records file with the extraction cached for its path and
+// content hash, and reports whether the cache had one.
+func (e *Engine) loadCachedFile(file *store.File) (bool, error) {
+	if e.parseCache == nil {
+		return false, nil
+	}
+	cached, err := e.parseCache.FileByPath(parseCacheKey(file.Path, file.Hash))
+	if err != nil || cached == nil {
+		return false, err
+	}
+	if _, err := e.store.ImportFile(e.parseCache, cached.ID, file); err != nil {
+		return false, fmt.Errorf("load cached extraction: %w", err)
+	}
+	return true, nil
+}
+
+// cacheFile copies the extraction of file fileID into the parse cache. A
+// cache that cannot be written only costs the reuse, so errors, such as
+// another Engine caching the same content first, are ignored.
+func (e *Engine) cacheFile(fileID int64, path, hash string) {
+	if e.parseCache == nil {
+		return
+	}
+	f, err := e.store.FileByPath(path)
+	if err != nil || f == nil {
+		return
+	}
+	f.Path = parseCacheKey(path, hash)
+	f.Root = ""
+	_, _ = e.parseCache.ImportFile(e.store, fileID, f)
+}
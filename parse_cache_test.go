@@ -0,0 +1,88 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// symbolNames returns the names of the symbols indexed for path.
+func symbolNames(t *testing.T, e *Engine, path string) []string {
+	t.Helper()
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f, path)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	var names []string
+	for _, s := range syms {
+		names = append(names, s.Name)
+	}
+	return names
+}
+
+func TestWithParseCache(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		t.Run(fmt.Sprintf("parallel=%v", parallel), func(t *testing.T) {
+			ctx := context.Background()
+			cacheDir := t.TempDir()
+			dir := t.TempDir()
+			path := writeGoFile(t, dir, "lib.go", "package lib\n\nfunc Helper() int { return 1 }\n")
+
+			first := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel), WithParseCache(cacheDir))
+			require.NoError(t, first.IndexFiles(ctx, []string{path}))
+			assert.Contains(t, symbolNames(t, first, path), "Helper")
+
+			// Tamper with the cached entry, so a reuse is visible.
+			cached, err := first.parseCache.FileByPath(parseCacheKey(path, fileHash(t, first, path)))
+			require.NoError(t, err)
+			require.NotNil(t, cached)
+			_, err = first.parseCache.DB().Exec("UPDATE symbols SET name = 'FromCache' WHERE name = 'Helper' AND file_id = ?", cached.ID)
+			require.NoError(t, err)
+
+			second := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel), WithParseCache(cacheDir))
+			require.NoError(t, second.IndexFiles(ctx, []string{path}))
+			assert.Contains(t, symbolNames(t, second, path), "FromCache", "identical content is copied from the cache")
+
+			// New content is extracted; switching back reuses the cache.
+			writeGoFile(t, dir, "lib.go", "package lib\n\nfunc Other() int { return 2 }\n")
+			require.NoError(t, second.IndexFiles(ctx, []string{path}))
+			assert.Contains(t, symbolNames(t, second, path), "Other")
+			writeGoFile(t, dir, "lib.go", "package lib\n\nfunc Helper() int { return 1 }\n")
+			require.NoError(t, second.IndexFiles(ctx, []string{path}))
+			assert.Contains(t, symbolNames(t, second, path), "FromCache")
+			require.NoError(t, second.Resolve(ctx))
+
+			violations, err := second.store.Validate()
+			require.NoError(t, err)
+			assert.Empty(t, violations)
+		})
+	}
+}
+
+func TestWithParseCache_KeyedByPath(t *testing.T) {
+	ctx := context.Background()
+	cacheDir := t.TempDir()
+	src := "package lib\n\nfunc Helper() int { return 1 }\n"
+	a := writeGoFile(t, t.TempDir(), "lib.go", src)
+	b := writeGoFile(t, t.TempDir(), "lib.go", src)
+
+	e := newIntegrationEngine(t, WithLanguages("go"), WithParseCache(cacheDir))
+	require.NoError(t, e.IndexFiles(ctx, []string{a, b}))
+	files, err := e.parseCache.AllFiles()
+	require.NoError(t, err)
+	assert.Len(t, files, 2, "the same content at another path is cached separately")
+	assert.FileExists(t, filepath.Join(cacheDir, "extract-"+e.scriptsHash()[:16]+".db"))
+}
+
+func fileHash(t *testing.T, e *Engine, path string) string {
+	t.Helper()
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	return f.Hash
+}
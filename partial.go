@@ -0,0 +1,59 @@
+package canopy
+
+import (
+	"fmt"
+	"slices"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// ModifierPartial marks a symbol whose source contains syntax errors. Its
+// extracted data (parameters, members, references) may be incomplete.
+const ModifierPartial = "partial"
+
+// markPartialSymbols adds ModifierPartial to the symbols of a file whose span
+// overlaps one of its syntax errors. Must run after recordSpanOffsets.
+func (e *Engine) markPartialSymbols(fileID int64, errs []runtime.ErrorSpan) error {
+	if len(errs) == 0 {
+		return nil
+	}
+	syms, err := e.store.SymbolsByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load symbols: %w", err)
+	}
+	for _, sym := range syms {
+		if slices.Contains(sym.Modifiers, ModifierPartial) {
+			continue
+		}
+		for _, span := range errs {
+			if spanOverlaps(span, sym.StartByte, sym.EndByte) {
+				if err := e.store.SetSymbolModifiers(sym.ID, append(sym.Modifiers, ModifierPartial)); err != nil {
+					return err
+				}
+				break
+			}
+		}
+	}
+	return nil
+}
+
+// recordSyntaxErrors stores the syntax errors of a file, which IndexStats
+// counts.
+func (e *Engine) recordSyntaxErrors(fileID int64, errs []runtime.ErrorSpan) error {
+	rows := make([]store.SyntaxError, len(errs))
+	for i, span := range errs {
+		rows[i] = store.SyntaxError{FileID: fileID, StartByte: span.StartByte, EndByte: span.EndByte}
+	}
+	return e.store.InsertSyntaxErrors(rows)
+}
+
+// spanOverlaps reports whether an error span overlaps [start, end). A
+// zero-width span (a MISSING node) overlaps if it lies within or at the end
+// of the range.
+func spanOverlaps(span runtime.ErrorSpan, start, end int) bool {
+	if span.StartByte == span.EndByte {
+		return start <= span.StartByte && span.StartByte <= end
+	}
+	return span.StartByte < end && start < span.EndByte
+}
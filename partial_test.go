@@ -0,0 +1,56 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSpanOverlaps(t *testing.T) {
+	t.Parallel()
+	assert.True(t, spanOverlaps(runtime.ErrorSpan{StartByte: 5, EndByte: 8}, 0, 6))
+	assert.False(t, spanOverlaps(runtime.ErrorSpan{StartByte: 6, EndByte: 8}, 0, 6))
+	assert.True(t, spanOverlaps(runtime.ErrorSpan{StartByte: 6, EndByte: 6}, 0, 6), "missing node at end")
+	assert.False(t, spanOverlaps(runtime.ErrorSpan{StartByte: 7, EndByte: 7}, 0, 6))
+}
+
+func TestIndexFiles_MarksPartialSymbols(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel))
+		ctx := context.Background()
+		path := writeGoFile(t, t.TempDir(), "main.go", `package main
+
+func Good() {}
+
+func Broken() {
+	x := 
+}
+
+func After() {}
+`)
+		require.NoError(t, e.IndexFiles(ctx, []string{path}))
+
+		modifiers := func(name string) []string {
+			t.Helper()
+			syms, err := e.store.SymbolsByName(name)
+			require.NoError(t, err)
+			require.Len(t, syms, 1, name)
+			return syms[0].Modifiers
+		}
+		assert.Contains(t, modifiers("Broken"), ModifierPartial)
+		assert.NotContains(t, modifiers("Good"), ModifierPartial)
+		assert.NotContains(t, modifiers("After"), ModifierPartial)
+
+		result, err := e.Query().SearchSymbols("*", SymbolFilter{Modifiers: []string{ModifierPartial}}, Sort{}, Pagination{})
+		require.NoError(t, err)
+		var names []string
+		for _, sr := range result.Items {
+			names = append(names, sr.Name)
+		}
+		assert.Contains(t, names, "Broken")
+		assert.NotContains(t, names, "Good")
+	}
+}
@@ -0,0 +1,89 @@
+package canopy
+
+import (
+	"sync"
+	"time"
+)
+
+// Progress phases reported to a WithProgress callback.
+const (
+	PhaseExtract = "extract" // Done counts files, Total the files passed to IndexFiles
+	PhaseResolve = "resolve" // Done counts languages whose resolution script finished
+)
+
+// Progress reports how far an IndexFiles or Resolve call has got.
+type Progress struct {
+	Phase string
+	Done  int
+	Total int
+	// Path is the file just extracted, or the language just resolved. Files
+	// skipped as unchanged or unsupported count as done with their path.
+	Path    string
+	Elapsed time.Duration // since the phase started
+	// ETA is the estimated time left in the phase, extrapolated from the
+	// pace so far; 0 before the first step and once the phase is done.
+	ETA time.Duration
+}
+
+// WithProgress registers fn to be called after each file IndexFiles
+// extracts and each language Resolve resolves, so a UI can show progress
+// while a long index runs. Calls are serialized but may come from any
+// goroutine; fn should return quickly.
+//
+// To abort a long index, cancel the context passed to IndexFiles,
+// IndexDirectory, or Resolve: extraction stops between files and
+// resolution scripts stop at their next query, and the call returns the
+// context's error.
+func WithProgress(fn func(Progress)) Option {
+	return func(e *Engine) {
+		e.progress = fn
+	}
+}
+
+// progressTracker counts the steps of one phase for a WithProgress
+// callback. A nil tracker, for an Engine without one, ignores steps.
+type progressTracker struct {
+	mu    sync.Mutex
+	fn    func(Progress)
+	phase string
+	total int
+	done  int
+	start time.Time
+}
+
+// startProgress begins reporting a phase of total steps, or returns nil
+// when no callback is registered and no deadline needs the count.
+func (e *Engine) startProgress(phase string, total int) *progressTracker {
+	if e.progress == nil && e.deadline.IsZero() {
+		return nil
+	}
+	return &progressTracker{fn: e.progress, phase: phase, total: total, start: time.Now()}
+}
+
+// step records one finished step and reports it.
+func (p *progressTracker) step(path string) {
+	if p == nil {
+		return
+	}
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	p.done++
+	elapsed := time.Since(p.start)
+	var eta time.Duration
+	if p.done < p.total {
+		eta = elapsed / time.Duration(p.done) * time.Duration(p.total-p.done)
+	}
+	if p.fn != nil {
+		p.fn(Progress{Phase: p.phase, Done: p.done, Total: p.total, Path: path, Elapsed: elapsed, ETA: eta})
+	}
+}
+
+// count returns the steps recorded so far.
+func (p *progressTracker) count() int {
+	if p == nil {
+		return 0
+	}
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	return p.done
+}
@@ -0,0 +1,90 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// writeProgressFiles writes n small Go files and returns their paths.
+func writeProgressFiles(t *testing.T, n int) []string {
+	t.Helper()
+	dir := t.TempDir()
+	var paths []string
+	for i := 0; i < n; i++ {
+		src := fmt.Sprintf("package p\n\nfunc F%d() int { return %d }\n", i, i)
+		paths = append(paths, writeGoFile(t, dir, fmt.Sprintf("f%d.go", i), src))
+	}
+	return paths
+}
+
+func TestWithProgress(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		t.Run(fmt.Sprintf("parallel=%v", parallel), func(t *testing.T) {
+			var got []Progress
+			e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel),
+				WithProgress(func(p Progress) { got = append(got, p) }))
+			ctx := context.Background()
+			paths := append(writeProgressFiles(t, 3), filepath.Join(t.TempDir(), "notes.txt"))
+
+			require.NoError(t, e.IndexFiles(ctx, paths))
+			require.Len(t, got, 4, "one step per path, unsupported ones included")
+			for i, p := range got {
+				assert.Equal(t, PhaseExtract, p.Phase)
+				assert.Equal(t, i+1, p.Done)
+				assert.Equal(t, 4, p.Total)
+			}
+			assert.Zero(t, got[3].ETA, "no time left once done")
+
+			got = nil
+			require.NoError(t, e.Resolve(ctx))
+			require.Len(t, got, 1)
+			assert.Equal(t, Progress{Phase: PhaseResolve, Done: 1, Total: 1, Path: "go", Elapsed: got[0].Elapsed}, got[0])
+		})
+	}
+}
+
+func TestIndexFiles_Cancelled(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		t.Run(fmt.Sprintf("parallel=%v", parallel), func(t *testing.T) {
+			ctx, cancel := context.WithCancel(context.Background())
+			steps := 0
+			e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel),
+				WithProgress(func(Progress) {
+					steps++
+					cancel()
+				}))
+			paths := writeProgressFiles(t, 6)
+
+			err := e.IndexFiles(ctx, paths)
+			require.ErrorIs(t, err, context.Canceled)
+			assert.Less(t, steps, len(paths))
+
+			// Files the cancelled run did not finish are extracted by the next.
+			require.NoError(t, e.IndexFiles(context.Background(), paths))
+			require.NoError(t, e.Resolve(context.Background()))
+			for i := range paths {
+				syms, err := e.Query().SearchSymbols(fmt.Sprintf("F%d", i), SymbolFilter{}, Sort{}, Pagination{})
+				require.NoError(t, err)
+				assert.Len(t, syms.Items, 1, "F%d", i)
+			}
+		})
+	}
+}
+
+func TestResolve_Cancelled(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	require.NoError(t, e.IndexFiles(context.Background(), writeProgressFiles(t, 2)))
+	require.NoError(t, e.Resolve(context.Background()))
+	require.False(t, e.ScriptsChanged())
+
+	require.NoError(t, e.IndexFiles(context.Background(), writeProgressFiles(t, 2)))
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	require.ErrorIs(t, e.Resolve(ctx), context.Canceled)
+	assert.True(t, e.ScriptsChanged(), "a cancelled resolve asks for a rebuild")
+}
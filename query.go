@@ -5,6 +5,7 @@ import (
 	"database/sql"
 	"fmt"
 	"os"
+	"time"
 
 	"github.com/jward/canopy/internal/store"
 )
@@ -12,6 +13,20 @@ import (
 // QueryBuilder provides a query API over the Store.
 type QueryBuilder struct {
 	store *store.Store
+
+	// readFile reads source files, e.g. to check positions. nil means
+	// os.ReadFile; the Engine sets it so queries see overlays.
+	readFile func(path string) ([]byte, error)
+
+	// minConfidence is the lowest resolution confidence reference and call
+	// graph queries return; see WithMinConfidence.
+	minConfidence float64
+
+	// excludeTests leaves test code out of queries; see WithTestCode.
+	excludeTests bool
+
+	// usage reports queries to the Engine's WithUsage callback; nil for none.
+	usage *usageReporter
 }
 
 // NewQueryBuilder creates a QueryBuilder from a Store.
@@ -22,18 +37,40 @@ func NewQueryBuilder(s *store.Store) *QueryBuilder {
 
 // Location represents a source code position range.
 // All line and column numbers are 0-based, matching the tree-sitter convention.
+// Columns are in bytes; UTF16StartCol/UTF16EndCol are the same columns in
+// UTF-16 code units, as LSP clients expect. StartByte/EndByte are offsets
+// from the start of the file.
 type Location struct {
-	File      string
-	StartLine int
-	StartCol  int
-	EndLine   int
-	EndCol    int
+	File          string
+	StartLine     int
+	StartCol      int
+	EndLine       int
+	EndCol        int
+	StartByte     int
+	EndByte       int
+	UTF16StartCol int
+	UTF16EndCol   int
+}
+
+// readSource returns the content of path as the index sees it.
+func (q *QueryBuilder) readSource(path string) ([]byte, error) {
+	if q.readFile != nil {
+		return q.readFile(path)
+	}
+	return os.ReadFile(path)
 }
 
 // SymbolAt returns the most specific (narrowest) symbol whose range contains the
 // given file position. Line and col are 0-based (tree-sitter convention).
 // Returns nil with no error if no symbol exists at that location.
 func (q *QueryBuilder) SymbolAt(file string, line, col int) (*Symbol, error) {
+	start := time.Now()
+	sym, err := q.symbolAt(file, line, col)
+	q.reportUsage("symbol_at", start, usageCount(sym != nil), err)
+	return sym, err
+}
+
+func (q *QueryBuilder) symbolAt(file string, line, col int) (*Symbol, error) {
 	f, err := q.store.FileByPath(file)
 	if err != nil {
 		return nil, fmt.Errorf("symbol at: lookup file: %w", err)
@@ -45,7 +82,7 @@ func (q *QueryBuilder) SymbolAt(file string, line, col int) (*Symbol, error) {
 	// Validate line/col against actual file content if available.
 	// Without this, multi-line symbols match any column on their start line
 	// because the SQL only checks start_col <= col (with no upper bound).
-	if content, err := os.ReadFile(file); err == nil {
+	if content, err := q.readSource(file); err == nil {
 		fileLines := bytes.Split(content, []byte{'\n'})
 		if line >= len(fileLines) {
 			return nil, nil
@@ -83,6 +120,13 @@ func (q *QueryBuilder) SymbolAt(file string, line, col int) (*Symbol, error) {
 // Line and col are 0-based (tree-sitter convention). It looks up references at
 // (file, line, col), resolves them, and returns the target symbol locations.
 func (q *QueryBuilder) DefinitionAt(file string, line, col int) ([]Location, error) {
+	start := time.Now()
+	locs, err := q.definitionAt(file, line, col)
+	q.reportUsage("definition_at", start, len(locs), err)
+	return locs, err
+}
+
+func (q *QueryBuilder) definitionAt(file string, line, col int) ([]Location, error) {
 	f, err := q.store.FileByPath(file)
 	if err != nil {
 		return nil, fmt.Errorf("definition at: lookup file: %w", err)
@@ -125,6 +169,9 @@ func (q *QueryBuilder) DefinitionAt(file string, line, col int) ([]Location, err
 			return nil, fmt.Errorf("definition at: resolve ref %d: %w", refID, err)
 		}
 		for _, rr := range resolved {
+			if rr.Confidence < q.minConfidence {
+				continue
+			}
 			loc, err := q.symbolLocation(rr.TargetSymbolID)
 			if err != nil {
 				return nil, fmt.Errorf("definition at: symbol location: %w", err)
@@ -139,26 +186,33 @@ func (q *QueryBuilder) DefinitionAt(file string, line, col int) ([]Location, err
 
 // ReferencesTo finds all source locations that reference the given symbol.
 func (q *QueryBuilder) ReferencesTo(symbolID int64) ([]Location, error) {
-	resolved, err := q.store.ResolvedReferencesByTarget(symbolID)
-	if err != nil {
-		return nil, fmt.Errorf("references to: %w", err)
-	}
+	start := time.Now()
+	locs, err := q.referencesTo(symbolID)
+	q.reportUsage("references_to", start, len(locs), err)
+	return locs, err
+}
 
+func (q *QueryBuilder) referencesTo(symbolID int64) ([]Location, error) {
 	var locations []Location
-	for _, rr := range resolved {
-		loc, err := q.referenceLocation(rr.ReferenceID)
-		if err != nil {
-			return nil, fmt.Errorf("references to: ref location: %w", err)
-		}
-		if loc != nil {
-			locations = append(locations, *loc)
-		}
+	err := q.EachReferenceTo(symbolID, ReferenceFilter{}, func(site ReferenceSite) error {
+		locations = append(locations, site.Location)
+		return nil
+	})
+	if err != nil {
+		return nil, err
 	}
 	return locations, nil
 }
 
 // Implementations finds all types implementing the given interface/trait symbol.
 func (q *QueryBuilder) Implementations(symbolID int64) ([]Location, error) {
+	start := time.Now()
+	locs, err := q.implementations(symbolID)
+	q.reportUsage("implementations", start, len(locs), err)
+	return locs, err
+}
+
+func (q *QueryBuilder) implementations(symbolID int64) ([]Location, error) {
 	impls, err := q.store.ImplementationsByInterface(symbolID)
 	if err != nil {
 		return nil, fmt.Errorf("implementations: %w", err)
@@ -179,16 +233,45 @@ func (q *QueryBuilder) Implementations(symbolID int64) ([]Location, error) {
 
 // Callers returns call graph edges where the given symbol is the callee.
 func (q *QueryBuilder) Callers(symbolID int64) ([]*CallEdge, error) {
-	return q.store.CallersByCallee(symbolID)
+	start := time.Now()
+	edges, err := q.callers(symbolID)
+	q.reportUsage("callers", start, len(edges), err)
+	return edges, err
+}
+
+func (q *QueryBuilder) callers(symbolID int64) ([]*CallEdge, error) {
+	edges, err := q.store.CallersByCallee(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	return q.filterCallEdges(edges)
 }
 
 // Callees returns call graph edges where the given symbol is the caller.
 func (q *QueryBuilder) Callees(symbolID int64) ([]*CallEdge, error) {
-	return q.store.CalleesByCaller(symbolID)
+	start := time.Now()
+	edges, err := q.callees(symbolID)
+	q.reportUsage("callees", start, len(edges), err)
+	return edges, err
+}
+
+func (q *QueryBuilder) callees(symbolID int64) ([]*CallEdge, error) {
+	edges, err := q.store.CalleesByCaller(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	return q.filterCallEdges(edges)
 }
 
 // Dependencies returns all imports for the given file.
 func (q *QueryBuilder) Dependencies(fileID int64) ([]*Import, error) {
+	start := time.Now()
+	imports, err := q.dependencies(fileID)
+	q.reportUsage("dependencies", start, len(imports), err)
+	return imports, err
+}
+
+func (q *QueryBuilder) dependencies(fileID int64) ([]*Import, error) {
 	return q.store.ImportsByFile(fileID)
 }
 
@@ -196,6 +279,13 @@ func (q *QueryBuilder) Dependencies(fileID int64) ([]*Import, error) {
 // Matches both exact source strings and suffix matches (e.g. "util" matches
 // "github.com/example/util").
 func (q *QueryBuilder) Dependents(source string) ([]*Import, error) {
+	start := time.Now()
+	imports, err := q.dependents(source)
+	q.reportUsage("dependents", start, len(imports), err)
+	return imports, err
+}
+
+func (q *QueryBuilder) dependents(source string) ([]*Import, error) {
 	rows, err := q.store.DB().Query(
 		"SELECT id, file_id, source, imported_name, local_alias, kind, scope FROM imports WHERE source = ? OR source LIKE ?",
 		source, "%/"+source,
@@ -227,47 +317,41 @@ func (q *QueryBuilder) symbolLocation(symbolID int64) (*Location, error) {
 		return nil, nil
 	}
 
-	var path string
-	err = q.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", *sym.FileID).Scan(&path)
-	if err != nil {
-		return nil, err
-	}
-
-	return &Location{
-		File:      path,
-		StartLine: sym.StartLine,
-		StartCol:  sym.StartCol,
-		EndLine:   sym.EndLine,
-		EndCol:    sym.EndCol,
-	}, nil
-}
-
-// referenceLocation resolves a reference ID to its file path and position.
-func (q *QueryBuilder) referenceLocation(referenceID int64) (*Location, error) {
-	var fileID int64
-	var startLine, startCol, endLine, endCol int
-	err := q.store.DB().QueryRow(
-		`SELECT file_id, start_line, start_col, end_line, end_col
-		 FROM references_ WHERE id = ?`, referenceID,
-	).Scan(&fileID, &startLine, &startCol, &endLine, &endCol)
-	if err == sql.ErrNoRows {
-		return nil, nil
+	// A symbol produced by macro expansion maps back to the item the macro
+	// was applied to (see WithMacroExpansion).
+	origin := Location{}
+	err = q.store.DB().QueryRow(
+		`SELECT f.path, sf.start_line, sf.start_col, sf.end_line, sf.end_col
+		 FROM symbol_fragments sf JOIN files f ON f.id = sf.file_id
+		 WHERE sf.symbol_id = ? AND sf.is_primary LIMIT 1`, symbolID,
+	).Scan(&origin.File, &origin.StartLine, &origin.StartCol, &origin.EndLine, &origin.EndCol)
+	if err == nil {
+		if src, err := q.readSource(origin.File); err == nil {
+			li := newLineIndex(src)
+			origin.StartByte, origin.UTF16StartCol = li.offsets(origin.StartLine, origin.StartCol)
+			origin.EndByte, origin.UTF16EndCol = li.offsets(origin.EndLine, origin.EndCol)
+		}
+		return &origin, nil
 	}
-	if err != nil {
+	if err != sql.ErrNoRows {
 		return nil, err
 	}
 
 	var path string
-	err = q.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", fileID).Scan(&path)
+	err = q.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", *sym.FileID).Scan(&path)
 	if err != nil {
 		return nil, err
 	}
 
 	return &Location{
-		File:      path,
-		StartLine: startLine,
-		StartCol:  startCol,
-		EndLine:   endLine,
-		EndCol:    endCol,
+		File:          path,
+		StartLine:     sym.StartLine,
+		StartCol:      sym.StartCol,
+		EndLine:       sym.EndLine,
+		EndCol:        sym.EndCol,
+		StartByte:     sym.StartByte,
+		EndByte:       sym.EndByte,
+		UTF16StartCol: sym.UTF16StartCol,
+		UTF16EndCol:   sym.UTF16EndCol,
 	}, nil
 }
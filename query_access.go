@@ -0,0 +1,108 @@
+package canopy
+
+import (
+	"fmt"
+	"slices"
+)
+
+// SymbolAccess is one symbol's entry in an access manifest: who may see it
+// and how far its declaration reaches, for code-search portals that filter
+// results per team.
+type SymbolAccess struct {
+	SymbolID   int64
+	Name       string
+	Kind       string
+	File       string // path relative to the indexed root
+	Line       int    // 0-based
+	Language   string
+	Visibility string   // as extracted ("public", "private", "pub(crate)", ...)
+	Scope      string   // for Rust, where the item may be named: "everywhere", "crate", "crate::net"; "" otherwise
+	Exported   bool     // declared public, so part of its package's or crate's API
+	Owners     []string // from the CODEOWNERS file of the symbol's root
+	Teams      []string // from the last access rule matching the symbol's file; nil if none or one listing no teams
+	Restricted bool     // Teams is set: only those teams and the owners may see the symbol
+	Readers    []string // with Restricted, Teams followed by the Owners not among them; nil otherwise
+}
+
+// AccessManifest returns every indexed symbol with its visibility, owners,
+// and the teams allowed to see it, ordered by file and position. rules are
+// path rules in CODEOWNERS syntax (see ParseCodeOwners), matched against
+// paths relative to each symbol's indexed root with the last matching rule
+// winning; a rule's owners are the teams allowed to read matching files,
+// and a rule with none makes them readable by everyone again. A nil rules
+// restricts nothing. A portal shows a symbol to a user when it is not
+// Restricted or one of the user's teams is among its Readers. Dependency
+// items ingested from rustdoc JSON are left out.
+func (q *QueryBuilder) AccessManifest(rules *CodeOwners) ([]SymbolAccess, error) {
+	owners := map[int64][]string{}
+	ownerRows, err := q.store.DB().Query(`SELECT file_id, owner FROM file_owners ORDER BY rowid`)
+	if err != nil {
+		return nil, fmt.Errorf("access manifest: %w", err)
+	}
+	for ownerRows.Next() {
+		var fileID int64
+		var owner string
+		if err := ownerRows.Scan(&fileID, &owner); err != nil {
+			ownerRows.Close()
+			return nil, fmt.Errorf("access manifest: %w", err)
+		}
+		owners[fileID] = append(owners[fileID], owner)
+	}
+	ownerRows.Close()
+	if err := ownerRows.Err(); err != nil {
+		return nil, fmt.Errorf("access manifest: %w", err)
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, s.name, s.kind, COALESCE(s.visibility, ''), s.start_line, f.id, f.path, f.language, COALESCE(f.root, '')
+		 FROM symbols s JOIN files f ON f.id = s.file_id
+		 WHERE f.language != ?
+		 ORDER BY f.path, s.start_line, s.start_col, s.id`,
+		rustdocStubLanguage,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("access manifest: %w", err)
+	}
+	defer rows.Close()
+
+	packages := map[string]cargoPackage{}
+	manifest := []SymbolAccess{}
+	for rows.Next() {
+		var a SymbolAccess
+		var fileID int64
+		var path, root string
+		if err := rows.Scan(&a.SymbolID, &a.Name, &a.Kind, &a.Visibility, &a.Line, &fileID, &path, &a.Language, &root); err != nil {
+			return nil, fmt.Errorf("access manifest: %w", err)
+		}
+		a.File = relativeFilePath(path, root)
+		a.Exported = a.Visibility == "public"
+		a.Owners = owners[fileID]
+		if a.Owners == nil {
+			a.Owners = []string{}
+		}
+		if a.Language == "rust" {
+			if m, ok := q.rustModuleOf(path, packages); ok {
+				if scope, err := RustVisibilityScope(a.Visibility, m.path); err == nil {
+					a.Scope = scope.String()
+				}
+			}
+		}
+		if rules != nil {
+			a.Teams = rules.Owners(a.File)
+		}
+		if len(a.Teams) > 0 {
+			a.Restricted = true
+			a.Readers = append([]string(nil), a.Teams...)
+			for _, o := range a.Owners {
+				if !slices.Contains(a.Readers, o) {
+					a.Readers = append(a.Readers, o)
+				}
+			}
+		}
+		manifest = append(manifest, a)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("access manifest: %w", err)
+	}
+	return manifest, nil
+}
@@ -0,0 +1,67 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestAccessManifest(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"shop\"\nversion = \"0.1.0\"\n")
+	write("CODEOWNERS", "* @org/core\n/src/billing.rs @org/billing\n")
+	write("src/lib.rs", "pub mod billing;\n\npub fn open() {}\n\nfn helper() {}\n")
+	write("src/billing.rs", "pub(crate) fn charge() {}\n")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	rules, err := ParseCodeOwners(strings.NewReader("/src/billing.rs @org/finance @org/billing\n/src/public/ \n"))
+	require.NoError(t, err)
+	manifest, err := e.Query().AccessManifest(rules)
+	require.NoError(t, err)
+	byName := map[string]SymbolAccess{}
+	for _, a := range manifest {
+		byName[a.Name] = a
+	}
+
+	open := byName["open"]
+	assert.Equal(t, "src/lib.rs", open.File)
+	assert.Equal(t, 2, open.Line)
+	assert.Equal(t, "everywhere", open.Scope)
+	assert.True(t, open.Exported)
+	assert.Equal(t, []string{"@org/core"}, open.Owners)
+	assert.False(t, open.Restricted)
+	assert.Nil(t, open.Readers)
+
+	helper := byName["helper"]
+	assert.Equal(t, "crate", helper.Scope)
+	assert.False(t, helper.Exported)
+
+	charge := byName["charge"]
+	assert.Equal(t, "pub(crate)", charge.Visibility)
+	assert.Equal(t, "crate", charge.Scope)
+	assert.Equal(t, []string{"@org/billing"}, charge.Owners)
+	assert.True(t, charge.Restricted)
+	assert.Equal(t, []string{"@org/finance", "@org/billing"}, charge.Teams)
+	assert.Equal(t, []string{"@org/finance", "@org/billing"}, charge.Readers, "owners already listed are not repeated")
+
+	unrestricted, err := e.Query().AccessManifest(nil)
+	require.NoError(t, err)
+	assert.Len(t, unrestricted, len(manifest))
+	for _, a := range unrestricted {
+		assert.False(t, a.Restricted, a.Name)
+	}
+}
@@ -0,0 +1,62 @@
+package canopy
+
+import "fmt"
+
+// SymbolAlias is a name other than its own that a file imports a symbol
+// under, as with use foo::Bar as Baz.
+type SymbolAlias struct {
+	Alias      string
+	Source     string // the import path
+	FilePath   string // the importing file
+	References int    // references to the alias in the file that resolve to the symbol
+}
+
+// SymbolAliases is a symbol with the aliases it is imported under.
+type SymbolAliases struct {
+	Symbol  SymbolResult
+	Aliases []SymbolAlias // by alias, then file
+}
+
+// Aliases returns the names symbolID is imported under across the index,
+// other than its own. Aliases are recorded at the end of Resolve from the
+// aliased imports whose item resolved to the symbol. It returns nil when no
+// symbol has the ID.
+func (q *QueryBuilder) Aliases(symbolID int64) (*SymbolAliases, error) {
+	sym, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("aliases: %w", err)
+	}
+	if sym == nil {
+		return nil, nil
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT a.alias, a.source, f.path,
+			(SELECT COUNT(*) FROM references_ r
+			 JOIN resolved_references rr ON rr.reference_id = r.id
+			 WHERE r.file_id = a.file_id AND r.name = a.alias
+			 AND rr.target_symbol_id = a.target_symbol_id
+			 AND COALESCE(r.context, '') != 'import')
+		 FROM import_aliases a
+		 JOIN files f ON f.id = a.file_id
+		 WHERE a.target_symbol_id = ?
+		 ORDER BY a.alias, f.path, a.id`,
+		symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("aliases: %w", err)
+	}
+	defer rows.Close()
+	result := &SymbolAliases{Symbol: *sym, Aliases: []SymbolAlias{}}
+	for rows.Next() {
+		var a SymbolAlias
+		if err := rows.Scan(&a.Alias, &a.Source, &a.FilePath, &a.References); err != nil {
+			return nil, fmt.Errorf("aliases: scan: %w", err)
+		}
+		result.Aliases = append(result.Aliases, a)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("aliases: %w", err)
+	}
+	return result, nil
+}
@@ -0,0 +1,63 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestAliases(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "pub mod shapes;\npub mod draw;\npub mod paint;\n")
+	write("src/shapes.rs", "pub struct Circle;\n")
+	write("src/draw.rs", `use crate::shapes::Circle as Round;
+
+pub fn outline(shape: &Round) -> Round {
+    todo!()
+}
+`)
+	write("src/paint.rs", `use crate::shapes::{Circle as Disc};
+
+pub fn fill(shape: &Disc) {}
+`)
+	shapes := filepath.Join(root, "src", "shapes.rs")
+	draw := filepath.Join(root, "src", "draw.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	defs, err := q.DefinitionAt(draw, 2, 23)
+	require.NoError(t, err)
+	require.Len(t, defs, 1, "Round resolves through the alias")
+	assert.Equal(t, shapes, defs[0].File)
+	assert.Equal(t, 0, defs[0].StartLine)
+
+	circle, err := q.SymbolAt(shapes, 0, 11)
+	require.NoError(t, err)
+	require.NotNil(t, circle)
+	aliases, err := q.Aliases(circle.ID)
+	require.NoError(t, err)
+	require.NotNil(t, aliases)
+	assert.Equal(t, "Circle", aliases.Symbol.Name)
+	assert.Equal(t, []SymbolAlias{
+		{Alias: "Disc", Source: "crate::shapes::Circle", FilePath: filepath.Join(root, "src", "paint.rs"), References: 1},
+		{Alias: "Round", Source: "crate::shapes::Circle", FilePath: draw, References: 2},
+	}, aliases.Aliases)
+
+	missing, err := q.Aliases(-1)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
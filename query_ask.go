@@ -0,0 +1,337 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"slices"
+	"strings"
+)
+
+// Intents of a question, as Ask classifies it.
+const (
+	AskBehavior   = "behavior"   // what happens when it runs: its calls, the types it touches, its tests
+	AskUsage      = "usage"      // who calls it
+	AskTests      = "tests"      // which tests exercise it
+	AskImpact     = "impact"     // what a change to it affects: callers further out, and tests
+	AskDefinition = "definition" // where and how it is declared
+)
+
+// Depths Ask follows the call graph to.
+const (
+	askCallDepth   = 2
+	askImpactDepth = 4
+	askTestDepth   = 5
+)
+
+// askTypeKinds are the symbol kinds Ask lists among the types a symbol
+// touches.
+var askTypeKinds = []string{"struct", "enum", "union", "trait", "interface", "class", "object", "type_alias"}
+
+// askIntents maps phrasings to intents, tried in order; questions matching
+// none ask about behavior.
+var askIntents = []struct {
+	intent string
+	re     *regexp.Regexp
+}{
+	{AskTests, regexp.MustCompile(`\btest(s|ed|ing)?\b|\bcover(s|ed|age)?\b`)},
+	{AskImpact, regexp.MustCompile(`\bbreaks?\b|\bimpact\b|\baffect(s|ed)?\b|\bchang(e|es|ed|ing)\b|\bsafe to\b`)},
+	{AskUsage, regexp.MustCompile(`\bwho calls\b|\bcallers?\b|\bused\b|\buses\b|\busages?\b|\bwhere\b.*\bcalled\b|\bcalls? to\b`)},
+	{AskDefinition, regexp.MustCompile(`\bdefined\b|\bdeclared\b|\bdefinition\b|\bsignature\b|^\s*what('s| is| are)\b`)},
+}
+
+var (
+	askQuoted = regexp.MustCompile("`([^`]+)`")
+	askWord   = regexp.MustCompile(`[A-Za-z_][A-Za-z0-9_]*(?:(?:::|\.)[A-Za-z_][A-Za-z0-9_]*)*(\(\))?`)
+)
+
+// askStopWords are the words of a question never taken for its subject.
+var askStopWords = map[string]bool{
+	"a": true, "about": true, "affect": true, "an": true, "and": true, "any": true, "are": true,
+	"break": true, "call": true, "called": true, "calls": true, "can": true, "change": true,
+	"cover": true, "covers": true, "defined": true, "do": true, "does": true, "for": true,
+	"from": true, "function": true, "happen": true, "happens": true, "how": true, "i": true,
+	"if": true, "in": true, "is": true, "it": true, "me": true, "method": true, "of": true,
+	"on": true, "run": true, "runs": true, "show": true, "tell": true, "test": true, "tests": true,
+	"the": true, "this": true, "to": true, "type": true, "use": true, "used": true, "uses": true,
+	"what": true, "when": true, "where": true, "which": true, "who": true, "why": true, "with": true,
+}
+
+// Answer is Ask's answer to a question about one symbol: the results of
+// the queries the question's intent calls for.
+type Answer struct {
+	Question string
+	Intent   string         // one of the Ask* intents
+	Subject  string         // the name the question is taken to be about; "" if none
+	Symbol   *SymbolDetail  // the symbol answered about; nil if no indexed symbol matched
+	Others   []SymbolResult // other symbols of the same name, for asking about one of them instead
+	Queries  []string       // the queries run, in order
+	Callees  []CallGraphNode
+	Types    []SymbolResult // types the symbol's declaration and body refer to
+	Callers  []CallGraphNode
+	Tests    []CallGraphNode
+}
+
+// Ask answers a question in plain English about a symbol, such as "what
+// happens when add_product is called?", by running the queries its intent
+// calls for: behavior questions get the definition, callees, types
+// touched, and tests covering it; usage questions the callers; test
+// questions the tests; impact questions callers further out and tests;
+// definition questions the definition and types. The subject is the first
+// name in backquotes, else the first identifier-like word (snake_case,
+// CamelCase, a::path, or name()) naming an indexed symbol, else any other
+// word that does. Among symbols of that name, functions are preferred for
+// questions about calls, then the most referenced. symbolID, if not 0,
+// answers about that symbol instead. An Answer without a Symbol means no
+// indexed symbol matched.
+func (q *QueryBuilder) Ask(question string, symbolID int64) (*Answer, error) {
+	a := &Answer{Question: question, Intent: askIntent(question)}
+	var sym *SymbolResult
+	if symbolID != 0 {
+		sr, err := q.symbolResultByID(symbolID)
+		if err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		if sr == nil {
+			return nil, fmt.Errorf("ask: symbol not found: %d", symbolID)
+		}
+		sym = sr
+		a.Subject = sr.Name
+	} else {
+		var err error
+		if sym, err = q.askSubject(a); err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		if sym == nil {
+			return a, nil
+		}
+	}
+
+	detail, err := q.SymbolDetail(sym.ID)
+	if err != nil {
+		return nil, fmt.Errorf("ask: %w", err)
+	}
+	if detail == nil {
+		return nil, fmt.Errorf("ask: symbol not found: %d", sym.ID)
+	}
+	a.Symbol = detail
+	a.Queries = append(a.Queries, "SymbolDetail")
+
+	callees := a.Intent == AskBehavior
+	types := a.Intent == AskBehavior || a.Intent == AskDefinition
+	callerDepth := 0
+	switch a.Intent {
+	case AskUsage:
+		callerDepth = askCallDepth
+	case AskImpact:
+		callerDepth = askImpactDepth
+	}
+	tests := a.Intent == AskBehavior || a.Intent == AskTests || a.Intent == AskImpact
+
+	if callees {
+		g, err := q.TransitiveCallees(sym.ID, askCallDepth)
+		if err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		a.Callees = graphNodesBeyondRoot(g)
+		a.Queries = append(a.Queries, fmt.Sprintf("TransitiveCallees(depth %d)", askCallDepth))
+	}
+	if types {
+		if a.Types, err = q.typesTouched(sym); err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		a.Queries = append(a.Queries, "types touched")
+	}
+	if callerDepth > 0 {
+		g, err := q.TransitiveCallers(sym.ID, callerDepth)
+		if err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		a.Callers = graphNodesBeyondRoot(g)
+		a.Queries = append(a.Queries, fmt.Sprintf("TransitiveCallers(depth %d)", callerDepth))
+	}
+	if tests {
+		if a.Tests, err = q.TestsCovering(sym.ID, askTestDepth); err != nil {
+			return nil, fmt.Errorf("ask: %w", err)
+		}
+		a.Queries = append(a.Queries, fmt.Sprintf("TestsCovering(depth %d)", askTestDepth))
+	}
+	return a, nil
+}
+
+// askIntent classifies a question by its phrasing.
+func askIntent(question string) string {
+	lower := strings.ToLower(question)
+	for _, in := range askIntents {
+		if in.re.MatchString(lower) {
+			return in.intent
+		}
+	}
+	return AskBehavior
+}
+
+// askSubject finds the symbol a question is about, setting a.Subject and
+// a.Others, or returns nil if no word of it names an indexed symbol.
+func (q *QueryBuilder) askSubject(a *Answer) (*SymbolResult, error) {
+	var quoted, codeLike, plain []string
+	for _, m := range askQuoted.FindAllStringSubmatch(a.Question, -1) {
+		quoted = append(quoted, m[1])
+	}
+	for _, m := range askWord.FindAllStringSubmatch(a.Question, -1) {
+		word := m[0]
+		switch {
+		case m[1] != "" || strings.ContainsAny(word, "_.:") || strings.ToLower(word[1:]) != word[1:]:
+			codeLike = append(codeLike, word)
+		case !askStopWords[strings.ToLower(word)] && len(word) > 2:
+			plain = append(plain, word)
+		}
+	}
+	for _, candidate := range slices.Concat(quoted, codeLike, plain) {
+		name := strings.TrimSuffix(strings.TrimSpace(candidate), "()")
+		if i := strings.LastIndexAny(name, ".:"); i >= 0 {
+			name = name[i+1:]
+		}
+		if name == "" {
+			continue
+		}
+		syms, err := q.store.SymbolsByName(name)
+		if err != nil {
+			return nil, err
+		}
+		var results []SymbolResult
+		for _, s := range syms {
+			if s.FileID == nil {
+				continue
+			}
+			sr, err := q.symbolResultByID(s.ID)
+			if err != nil {
+				return nil, err
+			}
+			if sr != nil {
+				results = append(results, *sr)
+			}
+		}
+		if len(results) == 0 {
+			continue
+		}
+		wantCallable := a.Intent != AskDefinition
+		callable := func(sr SymbolResult) bool { return sr.Kind == "function" || sr.Kind == "method" }
+		slices.SortStableFunc(results, func(x, y SymbolResult) int {
+			if wantCallable && callable(x) != callable(y) {
+				if callable(x) {
+					return -1
+				}
+				return 1
+			}
+			return y.RefCount - x.RefCount
+		})
+		a.Subject = name
+		a.Others = results[1:]
+		return &results[0], nil
+	}
+	return nil, nil
+}
+
+// graphNodesBeyondRoot returns the nodes of g other than its root.
+func graphNodesBeyondRoot(g *CallGraph) []CallGraphNode {
+	if g == nil {
+		return nil
+	}
+	var nodes []CallGraphNode
+	for _, n := range g.Nodes {
+		if n.Depth > 0 {
+			nodes = append(nodes, n)
+		}
+	}
+	return nodes
+}
+
+// typesTouched returns the types that references within sym's span
+// resolve to, by name.
+func (q *QueryBuilder) typesTouched(sym *SymbolResult) ([]SymbolResult, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT t.id FROM references_ r
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN symbols t ON t.id = rr.target_symbol_id
+		 JOIN symbols s ON s.id = ?
+		 WHERE r.file_id = s.file_id AND r.start_byte >= s.start_byte AND r.end_byte <= s.end_byte
+		 AND t.id != s.id AND t.kind IN ('`+strings.Join(askTypeKinds, "', '")+`')
+		 ORDER BY t.name, t.id`, sym.ID)
+	if err != nil {
+		return nil, err
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	var types []SymbolResult
+	for _, id := range ids {
+		sr, err := q.symbolResultByID(id)
+		if err != nil {
+			return nil, err
+		}
+		if sr != nil {
+			types = append(types, *sr)
+		}
+	}
+	return types, nil
+}
+
+// Markdown renders the answer: the symbol and where it is declared, then a
+// section for each query's results, and the other symbols of the name.
+func (a *Answer) Markdown() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "## %s\n", a.Question)
+	if a.Symbol == nil {
+		if a.Subject == "" {
+			b.WriteString("\nNo symbol named in the question is indexed. Put the name in backquotes, such as `add_product`.\n")
+		}
+		return b.String()
+	}
+	s := a.Symbol.Symbol
+	fmt.Fprintf(&b, "\n%s `%s` at %s:%d, referenced %d times.\n", s.Kind, s.Name, s.FilePath, s.StartLine+1, s.RefCount)
+
+	nodes := func(title, empty string, ns []CallGraphNode) {
+		fmt.Fprintf(&b, "\n### %s\n\n", title)
+		if len(ns) == 0 {
+			fmt.Fprintf(&b, "%s\n", empty)
+			return
+		}
+		for _, n := range ns {
+			fmt.Fprintf(&b, "%s- %s `%s` (%s:%d)\n", strings.Repeat("  ", n.Depth-1), n.Symbol.Kind, n.Symbol.Name, n.Symbol.FilePath, n.Symbol.StartLine+1)
+		}
+	}
+	switch a.Intent {
+	case AskBehavior:
+		nodes("Calls", "Calls nothing indexed.", a.Callees)
+	case AskUsage, AskImpact:
+		nodes("Called by", "No indexed callers.", a.Callers)
+	}
+	if a.Intent == AskBehavior || a.Intent == AskDefinition {
+		b.WriteString("\n### Types touched\n\n")
+		if len(a.Types) == 0 {
+			b.WriteString("None.\n")
+		}
+		for _, t := range a.Types {
+			fmt.Fprintf(&b, "- %s `%s` (%s:%d)\n", t.Kind, t.Name, t.FilePath, t.StartLine+1)
+		}
+	}
+	if a.Intent == AskBehavior || a.Intent == AskTests || a.Intent == AskImpact {
+		nodes("Tests", "No test reaches it.", a.Tests)
+	}
+	if len(a.Others) > 0 {
+		fmt.Fprintf(&b, "\nAlso named `%s`:\n", a.Subject)
+		for _, o := range a.Others {
+			fmt.Fprintf(&b, "- %s (%s:%d), symbol %d\n", o.Kind, o.FilePath, o.StartLine+1, o.ID)
+		}
+	}
+	return b.String()
+}
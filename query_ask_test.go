@@ -0,0 +1,105 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestAskIntent(t *testing.T) {
+	for question, want := range map[string]string{
+		"what happens when add_product is called?": AskBehavior,
+		"who calls add_product?":                   AskUsage,
+		"where is Cart used":                       AskUsage,
+		"which tests cover add_product?":           AskTests,
+		"what breaks if I change add_product":      AskImpact,
+		"where is Cart defined?":                   AskDefinition,
+		"What is Cart?":                            AskDefinition,
+	} {
+		assert.Equal(t, want, askIntent(question), question)
+	}
+}
+
+func TestAsk(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`pub struct Product { pub price: u32 }
+
+pub struct Cart { pub items: Vec<Product> }
+
+fn validate(p: &Product) -> bool { p.price > 0 }
+
+pub fn add_product(cart: &mut Cart, p: Product) {
+    if validate(&p) {
+        cart.items.push(p);
+    }
+}
+
+pub fn checkout(cart: &mut Cart) {
+    add_product(cart, Product { price: 1 });
+}
+
+#[test]
+fn adds_product() {
+    let mut cart = Cart { items: Vec::new() };
+    add_product(&mut cart, Product { price: 2 });
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	names := func(nodes []CallGraphNode) []string {
+		var out []string
+		for _, n := range nodes {
+			out = append(out, n.Symbol.Name)
+		}
+		return out
+	}
+
+	a, err := q.Ask("what happens when add_product is called?", 0)
+	require.NoError(t, err)
+	require.NotNil(t, a.Symbol)
+	assert.Equal(t, AskBehavior, a.Intent)
+	assert.Equal(t, "add_product", a.Subject)
+	assert.Equal(t, "add_product", a.Symbol.Symbol.Name)
+	assert.Contains(t, names(a.Callees), "validate")
+	var types []string
+	for _, ty := range a.Types {
+		types = append(types, ty.Name)
+	}
+	assert.ElementsMatch(t, []string{"Cart", "Product"}, types)
+	assert.Equal(t, []string{"adds_product"}, names(a.Tests))
+	assert.Empty(t, a.Callers, "behavior questions do not ask for callers")
+
+	md := a.Markdown()
+	assert.Contains(t, md, "## what happens when add_product is called?\n")
+	assert.Contains(t, md, "### Calls\n")
+	assert.Contains(t, md, "### Types touched\n")
+	assert.Contains(t, md, "### Tests\n")
+
+	a, err = q.Ask("who calls `add_product`?", 0)
+	require.NoError(t, err)
+	assert.Equal(t, AskUsage, a.Intent)
+	assert.Contains(t, names(a.Callers), "checkout")
+	assert.Empty(t, a.Callees)
+
+	a, err = q.Ask("who calls it?", a.Symbol.Symbol.ID)
+	require.NoError(t, err)
+	assert.Equal(t, "add_product", a.Subject)
+
+	a, err = q.Ask("what does frobnicate do?", 0)
+	require.NoError(t, err)
+	assert.Nil(t, a.Symbol)
+	assert.Contains(t, a.Markdown(), "No symbol named in the question is indexed")
+
+	_, err = q.Ask("who calls it?", -1)
+	assert.Error(t, err)
+}
@@ -0,0 +1,248 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// ModifierAsync marks an async function or method (Rust async fn).
+const ModifierAsync = "async"
+
+// rustBlockingAPIs are the std functions and methods that block the calling
+// thread, by stub path: module::function or module::Type::method.
+var rustBlockingAPIs = map[string]bool{
+	"std::thread::sleep":                      true,
+	"std::thread::park":                       true,
+	"std::thread::JoinHandle::join":           true,
+	"std::fs::read":                           true,
+	"std::fs::read_to_string":                 true,
+	"std::fs::write":                          true,
+	"std::fs::read_dir":                       true,
+	"std::fs::create_dir":                     true,
+	"std::fs::create_dir_all":                 true,
+	"std::fs::remove_file":                    true,
+	"std::fs::remove_dir":                     true,
+	"std::fs::remove_dir_all":                 true,
+	"std::fs::rename":                         true,
+	"std::fs::copy":                           true,
+	"std::fs::metadata":                       true,
+	"std::fs::canonicalize":                   true,
+	"std::fs::File::open":                     true,
+	"std::fs::File::create":                   true,
+	"std::fs::OpenOptions::open":              true,
+	"std::io::Stdin::read_line":               true,
+	"std::sync::Mutex::lock":                  true,
+	"std::sync::RwLock::read":                 true,
+	"std::sync::RwLock::write":                true,
+	"std::sync::Condvar::wait":                true,
+	"std::sync::Condvar::wait_timeout":        true,
+	"std::sync::Barrier::wait":                true,
+	"std::sync::mpsc::Receiver::recv":         true,
+	"std::sync::mpsc::Receiver::recv_timeout": true,
+	"std::net::TcpStream::connect":            true,
+	"std::net::TcpListener::accept":           true,
+	"std::net::UdpSocket::recv":               true,
+	"std::net::UdpSocket::recv_from":          true,
+	"std::net::UdpSocket::send_to":            true,
+	"std::process::Command::output":           true,
+	"std::process::Command::status":           true,
+	"std::process::Child::wait":               true,
+	"std::process::Child::wait_with_output":   true,
+}
+
+// BlockingCall is a call to a blocking API made while an async function
+// runs: from its body, or from synchronous functions it calls.
+type BlockingCall struct {
+	AsyncFn SymbolResult    // the async function the blocking call runs under
+	API     string          // the blocking API, as in std::thread::sleep
+	Path    []CallGraphEdge // calls from AsyncFn to the API; the last edge is the blocking call
+}
+
+// BlockingCallsInAsync finds calls to blocking APIs (rustBlockingAPIs, bound
+// through the std stubs) reachable from async functions within maxDepth
+// calls, ordered by async function then path length. The walk follows
+// calls into synchronous functions only: an async callee runs as its own
+// root, an awaited call yields instead of blocking, and spawned work
+// (CallKindSpawn) runs off the caller's task. Same depth rules as
+// TransitiveCallees.
+func (q *QueryBuilder) BlockingCallsInAsync(maxDepth int) ([]BlockingCall, error) {
+	if maxDepth < 0 {
+		return nil, fmt.Errorf("blocking calls in async: maxDepth must be non-negative, got %d", maxDepth)
+	}
+	if maxDepth > 100 {
+		maxDepth = 100
+	}
+
+	asyncIDs, err := q.symbolIDsWithModifier(ModifierAsync)
+	if err != nil {
+		return nil, fmt.Errorf("blocking calls in async: %w", err)
+	}
+	blocking, err := q.blockingAPISymbols()
+	if err != nil {
+		return nil, fmt.Errorf("blocking calls in async: %w", err)
+	}
+	result := []BlockingCall{}
+	if len(asyncIDs) == 0 || len(blocking) == 0 || maxDepth == 0 {
+		return result, nil
+	}
+
+	data, err := q.buildCallGraph()
+	if err != nil {
+		return nil, fmt.Errorf("blocking calls in async: %w", err)
+	}
+
+	type finding struct {
+		root int64
+		api  string
+		path []*CallEdge
+	}
+	var findings []finding
+	roots := make([]int64, 0, len(asyncIDs))
+	for id := range asyncIDs {
+		roots = append(roots, id)
+	}
+	sort.Slice(roots, func(i, j int) bool { return roots[i] < roots[j] })
+
+	for _, root := range roots {
+		// BFS over synchronous callees, remembering the edge each was reached by.
+		via := map[int64]*CallEdge{root: nil}
+		depth := map[int64]int{root: 0}
+		queue := []int64{root}
+		for len(queue) > 0 {
+			current := queue[0]
+			queue = queue[1:]
+			if depth[current] >= maxDepth {
+				continue
+			}
+			for _, edge := range data.edgesByCaller[current] {
+				if edge.Awaited || edge.Kind == CallKindSpawn {
+					continue
+				}
+				callee := edge.CalleeSymbolID
+				if api, ok := blocking[callee]; ok {
+					path := []*CallEdge{edge}
+					for e := via[current]; e != nil; e = via[e.CallerSymbolID] {
+						path = append(path, e)
+					}
+					for i, j := 0, len(path)-1; i < j; i, j = i+1, j-1 {
+						path[i], path[j] = path[j], path[i]
+					}
+					findings = append(findings, finding{root: root, api: api, path: path})
+					continue
+				}
+				if _, seen := via[callee]; seen || asyncIDs[callee] {
+					continue
+				}
+				via[callee] = edge
+				depth[callee] = depth[current] + 1
+				queue = append(queue, callee)
+			}
+		}
+	}
+	if len(findings) == 0 {
+		return result, nil
+	}
+
+	var rootIDs []int64
+	for _, f := range findings {
+		rootIDs = append(rootIDs, f.root)
+	}
+	symbols, err := q.symbolResultsByIDs(rootIDs)
+	if err != nil {
+		return nil, fmt.Errorf("blocking calls in async: load symbols: %w", err)
+	}
+	for _, f := range findings {
+		sr, ok := symbols[f.root]
+		if !ok {
+			continue
+		}
+		call := BlockingCall{AsyncFn: *sr, API: f.api, Path: make([]CallGraphEdge, len(f.path))}
+		for i, e := range f.path {
+			call.Path[i] = resolveCallGraphEdge(e, data.filePaths)
+		}
+		result = append(result, call)
+	}
+	sort.SliceStable(result, func(i, j int) bool {
+		a, b := result[i], result[j]
+		if a.AsyncFn.FilePath != b.AsyncFn.FilePath {
+			return a.AsyncFn.FilePath < b.AsyncFn.FilePath
+		}
+		if a.AsyncFn.StartLine != b.AsyncFn.StartLine {
+			return a.AsyncFn.StartLine < b.AsyncFn.StartLine
+		}
+		return len(a.Path) < len(b.Path)
+	})
+	return result, nil
+}
+
+// symbolIDsWithModifier returns the IDs of symbols carrying modifier, leaving
+// out test code unless q includes it.
+func (q *QueryBuilder) symbolIDsWithModifier(modifier string) (map[int64]bool, error) {
+	query := `SELECT s.id FROM symbols s
+		 LEFT JOIN files f ON f.id = s.file_id
+		 WHERE EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)`
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		query += " AND " + clause
+	}
+	rows, err := q.store.DB().Query(query, modifier)
+	if err != nil {
+		return nil, fmt.Errorf("%s symbols: %w", modifier, err)
+	}
+	defer rows.Close()
+
+	ids := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, fmt.Errorf("%s symbols: scan: %w", modifier, err)
+		}
+		ids[id] = true
+	}
+	return ids, rows.Err()
+}
+
+// blockingAPISymbols returns the stub symbols of rustBlockingAPIs, mapped to
+// their paths.
+func (q *QueryBuilder) blockingAPISymbols() (map[int64]string, error) {
+	paths, err := q.stubFunctionPaths()
+	if err != nil {
+		return nil, fmt.Errorf("blocking APIs: %w", err)
+	}
+	apis := map[int64]string{}
+	for id, path := range paths {
+		if rustBlockingAPIs[path] {
+			apis[id] = path
+		}
+	}
+	return apis, nil
+}
+
+// stubFunctionPaths returns the functions and methods of the rustdoc stubs,
+// mapped to their paths: module::function or module::Type::method.
+func (q *QueryBuilder) stubFunctionPaths() (map[int64]string, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, s.name, f.path, COALESCE(p.name, '') FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id AND p.kind != 'module'
+		 WHERE f.language = ? AND s.kind IN ('function', 'method')`, rustdocStubLanguage)
+	if err != nil {
+		return nil, fmt.Errorf("stub functions: %w", err)
+	}
+	defer rows.Close()
+
+	paths := map[int64]string{}
+	for rows.Next() {
+		var id int64
+		var name, file, parent string
+		if err := rows.Scan(&id, &name, &file, &parent); err != nil {
+			return nil, fmt.Errorf("stub functions: scan: %w", err)
+		}
+		path := strings.TrimPrefix(file, rustdocStubPrefix) + "::"
+		if parent != "" {
+			path += parent + "::"
+		}
+		paths[id] = path + name
+	}
+	return paths, rows.Err()
+}
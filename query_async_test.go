@@ -0,0 +1,171 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestBlockingCallsInAsync(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	path := filepath.Join(t.TempDir(), "main.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`use std::thread::sleep;
+
+fn millis(n: u64) -> u64 {
+    n
+}
+
+fn wait_a_bit() {
+    sleep(millis(10));
+}
+
+async fn fetch() -> u32 {
+    wait_a_bit();
+    1
+}
+
+async fn handler() -> u32 {
+    wait_a_bit();
+    fetch().await
+}
+
+async fn direct() {
+    sleep(millis(1));
+}
+
+fn sync_caller() {
+    wait_a_bit();
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	q := e.Query()
+	calls, err := q.BlockingCallsInAsync(5)
+	require.NoError(t, err)
+
+	type found struct {
+		fn    string
+		depth int
+	}
+	var got []found
+	for _, c := range calls {
+		assert.Equal(t, "std::thread::sleep", c.API)
+		assert.Contains(t, c.AsyncFn.Modifiers, ModifierAsync)
+		got = append(got, found{c.AsyncFn.Name, len(c.Path)})
+	}
+	assert.Equal(t, []found{{"fetch", 2}, {"handler", 2}, {"direct", 1}}, got,
+		"handler reaches sleep through wait_a_bit, not again through the awaited fetch; sync_caller is not async")
+	last := calls[2].Path[0]
+	assert.Equal(t, path, last.File)
+	assert.Equal(t, 21, last.Line)
+
+	none, err := q.BlockingCallsInAsync(1)
+	require.NoError(t, err)
+	require.Len(t, none, 1, "only direct calls sleep itself")
+	assert.Equal(t, "direct", none[0].AsyncFn.Name)
+
+	// The await point is recorded on the call edge.
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	var handlerID int64
+	for _, s := range syms {
+		if s.Name == "handler" {
+			handlerID = s.ID
+		}
+	}
+	callees, err := q.Callees(handlerID)
+	require.NoError(t, err)
+	awaited := map[int64]bool{}
+	for _, c := range callees {
+		awaited[c.CalleeSymbolID] = c.Awaited
+	}
+	assert.Len(t, awaited, 2)
+	for id, a := range awaited {
+		sym, err := e.store.SymbolByID(id)
+		require.NoError(t, err)
+		assert.Equal(t, sym.Name == "fetch", a, "%s awaited", sym.Name)
+	}
+}
+
+func TestSpawnEdges(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	path := filepath.Join(t.TempDir(), "main.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`use std::thread;
+
+fn worker() {}
+
+fn process(n: u32) -> u32 {
+    n
+}
+
+async fn serve(n: u32) -> u32 {
+    process(n)
+}
+
+fn start() {
+    thread::spawn(worker);
+    thread::spawn(move || process(1));
+    tokio::spawn(serve(2));
+    process(3);
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	names := map[int64]string{}
+	var startID int64
+	for _, s := range syms {
+		names[s.ID] = s.Name
+		if s.Name == "start" {
+			startID = s.ID
+		}
+	}
+	require.NotZero(t, startID)
+
+	type call struct {
+		callee string
+		line   int
+		kind   string
+	}
+	edges, err := e.Query().Callees(startID)
+	require.NoError(t, err)
+	var got []call
+	for _, edge := range edges {
+		if name, ok := names[edge.CalleeSymbolID]; ok {
+			got = append(got, call{name, edge.Line, edge.Kind})
+		}
+	}
+	assert.ElementsMatch(t, []call{
+		{"worker", 13, CallKindSpawn},
+		{"process", 14, CallKindSpawn},
+		{"serve", 15, CallKindSpawn},
+		{"process", 16, ""},
+	}, got)
+
+	graph, err := e.Query().TransitiveCallees(startID, 1)
+	require.NoError(t, err)
+	spawns := 0
+	for _, edge := range graph.Edges {
+		if edge.CallerID == startID && edge.Kind == CallKindSpawn {
+			spawns++
+		}
+	}
+	assert.Equal(t, 3, spawns, "spawn edges are kept in the transitive call graph")
+}
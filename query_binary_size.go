@@ -0,0 +1,377 @@
+package canopy
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"io"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+)
+
+// Where the byte counts of a BinarySizeReport come from.
+const (
+	SizeSourceMeasured = "measured" // code sizes from cargo bloat or nm
+	SizeSourceEstimate = "estimate" // source size of the functions the call graph reaches
+)
+
+// MeasuredSize is the size of a function's code in a built binary.
+type MeasuredSize struct {
+	Name  string // demangled path: demo::net::parse, <demo::Point as core::fmt::Debug>::fmt
+	Crate string // the crate the tool attributed it to, "" if not given
+	Bytes int64
+}
+
+// FunctionSize is the code size attributed to a Rust function or method.
+type FunctionSize struct {
+	Symbol SymbolResult
+	Module string // crate-qualified module path: demo, demo::net
+	Bytes  int64
+	// Inlined is set when sizes were measured and the function, though
+	// the call graph reaches it from an entry point, has no code of its
+	// own: it was inlined into its callers, whose sizes include it.
+	Inlined bool
+}
+
+// ModuleSize is the code size attributed to the functions of a Rust module.
+type ModuleSize struct {
+	Module    string
+	Bytes     int64
+	Functions int     // functions with code of their own
+	Share     float64 // of BinarySizeReport.Total
+}
+
+// CrateSize is measured code no indexed function accounts for, by crate:
+// the standard library, dependencies, and generated code.
+type CrateSize struct {
+	Crate string
+	Bytes int64
+	Share float64 // of BinarySizeReport.Total
+}
+
+// BinarySizeReport attributes the code size of a binary to source modules
+// and functions.
+type BinarySizeReport struct {
+	Source       string         // one of the SizeSource constants
+	Total        int64          // all bytes, attributed or not
+	Modules      []ModuleSize   // largest first
+	Functions    []FunctionSize // largest first, inlined functions last
+	Unattributed []CrateSize    // largest first; empty for an estimate
+}
+
+// ParseBloatJSON reads the output of cargo bloat -n 0 --message-format json.
+func ParseBloatJSON(r io.Reader) ([]MeasuredSize, error) {
+	var doc struct {
+		Functions []struct {
+			Crate string `json:"crate"`
+			Name  string `json:"name"`
+			Size  int64  `json:"size"`
+		} `json:"functions"`
+	}
+	if err := json.NewDecoder(r).Decode(&doc); err != nil {
+		return nil, fmt.Errorf("cargo bloat json: %w", err)
+	}
+	sizes := make([]MeasuredSize, 0, len(doc.Functions))
+	for _, f := range doc.Functions {
+		sizes = append(sizes, MeasuredSize{Name: f.Name, Crate: f.Crate, Bytes: f.Size})
+	}
+	return sizes, nil
+}
+
+// ParseNmSizes reads the output of nm --print-size, one "address size type
+// name" line per symbol, and returns the text (code) symbols. Legacy Rust
+// mangled names (_ZN...E) are demangled; run nm with --demangle for v0
+// names.
+func ParseNmSizes(r io.Reader) ([]MeasuredSize, error) {
+	sizes := []MeasuredSize{}
+	sc := bufio.NewScanner(r)
+	sc.Buffer(make([]byte, 0, 64*1024), 1024*1024)
+	for line := 1; sc.Scan(); line++ {
+		fields := strings.SplitN(strings.TrimSpace(sc.Text()), " ", 4)
+		if len(fields) < 4 {
+			continue // undefined symbols have no address or size
+		}
+		if t := fields[2]; t != "T" && t != "t" {
+			continue
+		}
+		size, err := strconv.ParseInt(fields[1], 16, 64)
+		if err != nil {
+			return nil, fmt.Errorf("nm line %d: size %q: %w", line, fields[1], err)
+		}
+		sizes = append(sizes, MeasuredSize{Name: demangleRustLegacy(fields[3]), Bytes: size})
+	}
+	if err := sc.Err(); err != nil {
+		return nil, fmt.Errorf("nm: %w", err)
+	}
+	return sizes, nil
+}
+
+// BinarySize attributes code size to the Rust functions and modules of the
+// index. With measured sizes, from ParseBloatJSON or ParseNmSizes, each
+// measured function is matched to the function at its path; monomorphized
+// copies and closures add to the function they come from, and what matches
+// nothing is reported by crate as unattributed. Without them, each function
+// the call graph reaches from the entry points target selects (as for
+// EntryPoints; a library without any counts every function) is estimated
+// at the size of its source, since the linker drops the rest: the numbers
+// rank modules but are not bytes of machine code.
+func (q *QueryBuilder) BinarySize(measured []MeasuredSize, target string) (*BinarySizeReport, error) {
+	reach, err := q.WithTestCode(false).Reachability(target, "")
+	if err != nil {
+		return nil, fmt.Errorf("binary size: %w", err)
+	}
+	unreachable := map[int64]bool{}
+	if len(reach.EntryPoints) > 0 {
+		for _, s := range reach.Unreachable {
+			unreachable[s.ID] = true
+		}
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, s.name, COALESCE(p.name, ''), f.path, s.end_byte - s.start_byte
+		 FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id
+		 WHERE s.kind IN ('function', 'method') AND f.language = 'rust'
+		 ORDER BY s.id`)
+	if err != nil {
+		return nil, fmt.Errorf("binary size: %w", err)
+	}
+	type function struct {
+		id     int64
+		module string
+		source int64
+	}
+	var functions []function
+	byPath := map[string]int64{}
+	byTail := map[string][]int64{} // crate::Type::name and crate::name -> functions
+	packages := map[string]cargoPackage{}
+	for rows.Next() {
+		var id, source int64
+		var name, parent, path string
+		if err := rows.Scan(&id, &name, &parent, &path, &source); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("binary size: scan: %w", err)
+		}
+		m, ok := q.rustModuleOf(path, packages)
+		if !ok {
+			continue
+		}
+		module := m.crate
+		if m.path != "" {
+			module += "::" + m.path
+		}
+		functions = append(functions, function{id, module, source})
+		item := name
+		if parent != "" {
+			item = parent + "::" + name
+		}
+		byPath[module+"::"+item] = id
+		byTail[m.crate+"::"+item] = append(byTail[m.crate+"::"+item], id)
+		if parent != "" {
+			byTail[m.crate+"::"+name] = append(byTail[m.crate+"::"+name], id)
+		}
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("binary size: %w", err)
+	}
+
+	report := &BinarySizeReport{
+		Source:       SizeSourceEstimate,
+		Modules:      []ModuleSize{},
+		Functions:    []FunctionSize{},
+		Unattributed: []CrateSize{},
+	}
+	bytes := map[int64]int64{}
+	if measured == nil {
+		for _, f := range functions {
+			if !unreachable[f.id] {
+				bytes[f.id] = f.source
+				report.Total += f.source
+			}
+		}
+	} else {
+		report.Source = SizeSourceMeasured
+		unattributed := map[string]int64{}
+		for _, m := range measured {
+			report.Total += m.Bytes
+			path := rustSymbolPath(m.Name)
+			segs := strings.Split(path, "::")
+			id, ok := byPath[path]
+			if !ok && len(segs) > 1 {
+				// An impl in another module than its type, or a path
+				// through a re-export: match the crate and the last
+				// segments if only one function has them.
+				for _, tail := range []string{segs[0] + "::" + strings.Join(segs[max(1, len(segs)-2):], "::"), segs[0] + "::" + segs[len(segs)-1]} {
+					if ids := byTail[tail]; len(ids) == 1 {
+						id, ok = ids[0], true
+						break
+					}
+				}
+			}
+			if ok {
+				bytes[id] += m.Bytes
+				continue
+			}
+			crate := m.Crate
+			if crate == "" {
+				crate = segs[0]
+			}
+			unattributed[crate] += m.Bytes
+		}
+		for crate, n := range unattributed {
+			report.Unattributed = append(report.Unattributed, CrateSize{Crate: crate, Bytes: n, Share: share(n, report.Total)})
+		}
+		sort.Slice(report.Unattributed, func(i, j int) bool {
+			a, b := report.Unattributed[i], report.Unattributed[j]
+			if a.Bytes != b.Bytes {
+				return a.Bytes > b.Bytes
+			}
+			return a.Crate < b.Crate
+		})
+	}
+
+	var ids []int64
+	for _, f := range functions {
+		if bytes[f.id] > 0 || (measured != nil && len(reach.EntryPoints) > 0 && !unreachable[f.id]) {
+			ids = append(ids, f.id)
+		}
+	}
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("binary size: load symbols: %w", err)
+	}
+	modules := map[string]*ModuleSize{}
+	for _, f := range functions {
+		sym, ok := symbols[f.id]
+		if !ok {
+			continue
+		}
+		n := bytes[f.id]
+		report.Functions = append(report.Functions, FunctionSize{Symbol: *sym, Module: f.module, Bytes: n, Inlined: n == 0})
+		if n == 0 {
+			continue
+		}
+		ms := modules[f.module]
+		if ms == nil {
+			ms = &ModuleSize{Module: f.module}
+			modules[f.module] = ms
+		}
+		ms.Bytes += n
+		ms.Functions++
+	}
+	for _, ms := range modules {
+		ms.Share = share(ms.Bytes, report.Total)
+		report.Modules = append(report.Modules, *ms)
+	}
+	sort.Slice(report.Modules, func(i, j int) bool {
+		a, b := report.Modules[i], report.Modules[j]
+		if a.Bytes != b.Bytes {
+			return a.Bytes > b.Bytes
+		}
+		return a.Module < b.Module
+	})
+	sort.SliceStable(report.Functions, func(i, j int) bool {
+		return report.Functions[i].Bytes > report.Functions[j].Bytes
+	})
+	return report, nil
+}
+
+// share returns n as a fraction of total, 0 for an empty total.
+func share(n, total int64) float64 {
+	if total == 0 {
+		return 0
+	}
+	return float64(n) / float64(total)
+}
+
+// rustHashSuffixRe matches the hash rustc appends to legacy symbol paths.
+var rustHashSuffixRe = regexp.MustCompile(`::h[0-9a-f]{16}$`)
+
+// rustSymbolPath reduces a demangled Rust symbol to the path of the source
+// function it comes from: the hash, generic arguments, and closure and shim
+// segments are dropped, and a qualified path <T as Trait>::f becomes T::f.
+func rustSymbolPath(name string) string {
+	name = rustHashSuffixRe.ReplaceAllString(strings.TrimSpace(name), "")
+	if strings.HasPrefix(name, "<") {
+		depth := 0
+		for i, r := range name {
+			if r == '<' {
+				depth++
+			} else if r == '>' {
+				if depth--; depth == 0 {
+					self := name[1:i]
+					if j := strings.Index(self, " as "); j >= 0 {
+						self = self[:j]
+					}
+					for _, p := range []string{"&", "mut ", "dyn "} {
+						self = strings.TrimPrefix(self, p)
+					}
+					name = self + name[i+1:]
+					break
+				}
+			}
+		}
+	}
+
+	var b strings.Builder
+	depth := 0
+	for _, r := range name {
+		switch {
+		case r == '<':
+			depth++
+		case r == '>' && depth > 0:
+			depth--
+		case depth == 0:
+			b.WriteRune(r)
+		}
+	}
+	var segs []string
+	for _, seg := range strings.Split(b.String(), "::") {
+		if seg != "" && !strings.HasPrefix(seg, "{{") {
+			segs = append(segs, seg)
+		}
+	}
+	return strings.Join(segs, "::")
+}
+
+// rustLegacyEscapes are the escapes of legacy Rust symbol mangling.
+var rustLegacyEscapes = strings.NewReplacer(
+	"$LT$", "<", "$GT$", ">", "$RF$", "&", "$BP$", "*", "$SP$", "@",
+	"$C$", ",", "$u20$", " ", "$u27$", "'", "$u5b$", "[", "$u5d$", "]",
+	"$u7b$", "{", "$u7d$", "}", "$u7e$", "~", "..", "::",
+)
+
+// demangleRustLegacy demangles a legacy Rust symbol (_ZN3foo3barE, or
+// __ZN on macOS) to foo::bar, and returns any other name unchanged.
+func demangleRustLegacy(name string) string {
+	rest, ok := strings.CutPrefix(name, "_ZN")
+	if !ok {
+		rest, ok = strings.CutPrefix(name, "__ZN")
+	}
+	if !ok || !strings.HasSuffix(rest, "E") {
+		return name
+	}
+	rest = strings.TrimSuffix(rest, "E")
+	var segs []string
+	for rest != "" {
+		n := 0
+		for n < len(rest) && rest[n] >= '0' && rest[n] <= '9' {
+			n++
+		}
+		size, err := strconv.Atoi(rest[:n])
+		if err != nil || n+size > len(rest) {
+			return name
+		}
+		seg := rest[n : n+size]
+		rest = rest[n+size:]
+		if strings.HasPrefix(seg, "_$") {
+			seg = seg[1:]
+		}
+		segs = append(segs, rustLegacyEscapes.Replace(seg))
+	}
+	return strings.Join(segs, "::")
+}
@@ -0,0 +1,142 @@
+package canopy
+
+import (
+	"context"
+	"maps"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRustSymbolPath(t *testing.T) {
+	t.Parallel()
+	tests := map[string]string{
+		"demo::net::parse::h0123456789abcdef":                "demo::net::parse",
+		"<demo::shapes::Circle as demo::shapes::Area>::area": "demo::shapes::Circle::area",
+		"<demo::Stack<T>>::push":                             "demo::Stack::push",
+		"<&T as core::fmt::Debug>::fmt":                      "T::fmt",
+		"demo::run::{{closure}}":                             "demo::run",
+		"core::ptr::drop_in_place<demo::Config>":             "core::ptr::drop_in_place",
+	}
+	for in, want := range tests {
+		assert.Equal(t, want, rustSymbolPath(in), in)
+	}
+}
+
+func TestDemangleRustLegacy(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, "demo::net::parse::h0123456789abcdef",
+		demangleRustLegacy("_ZN4demo3net5parse17h0123456789abcdefE"))
+	assert.Equal(t, "<demo::Point as core::fmt::Debug>::fmt::h0123456789abcdef",
+		demangleRustLegacy("__ZN48_$LT$demo..Point$u20$as$u20$core..fmt..Debug$GT$3fmt17h0123456789abcdefE"))
+	assert.Equal(t, "_RNvCs1234_4demo4main", demangleRustLegacy("_RNvCs1234_4demo4main"))
+	assert.Equal(t, "_ZN12shortE", demangleRustLegacy("_ZN12shortE"))
+}
+
+func TestParseMeasuredSizes(t *testing.T) {
+	t.Parallel()
+	sizes, err := ParseBloatJSON(strings.NewReader(`{"file-size": 4096, "text-section-size": 2048,
+		"functions": [{"crate": "std", "name": "std::rt::lang_start", "size": 500}, {"crate": "app", "name": "app::main", "size": 100}]}`))
+	require.NoError(t, err)
+	assert.Equal(t, []MeasuredSize{
+		{Name: "std::rt::lang_start", Crate: "std", Bytes: 500},
+		{Name: "app::main", Crate: "app", Bytes: 100},
+	}, sizes)
+
+	sizes, err = ParseNmSizes(strings.NewReader(`0000000000001040 0000000000000064 T _ZN3app4main17h0123456789abcdefE
+                 U malloc
+0000000000004000 0000000000000008 D app_VERSION
+0000000000001100 00000000000001f4 t std::rt::lang_start
+`))
+	require.NoError(t, err)
+	assert.Equal(t, []MeasuredSize{
+		{Name: "app::main::h0123456789abcdef", Bytes: 100},
+		{Name: "std::rt::lang_start", Bytes: 500},
+	}, sizes)
+
+	_, err = ParseNmSizes(strings.NewReader("0000000000001040 zz T main\n"))
+	assert.Error(t, err)
+}
+
+func TestBinarySize(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"),
+		[]byte("[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"), 0o644))
+	mainPath := filepath.Join(root, "src", "main.rs")
+	netPath := filepath.Join(root, "src", "net.rs")
+	require.NoError(t, os.WriteFile(mainPath, []byte(`mod net;
+use net::parse;
+
+fn main() {
+    parse();
+}
+
+fn unused() {}
+`), 0o644))
+	require.NoError(t, os.WriteFile(netPath, []byte(`pub fn parse() {
+    helper();
+}
+
+fn helper() {}
+
+pub struct Conn;
+
+impl Conn {
+    pub fn open(&self) {}
+}
+`), 0o644))
+	require.NoError(t, e.IndexFiles(ctx, []string{mainPath, netPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	// Estimated: the reachable functions at their source size.
+	report, err := e.Query().BinarySize(nil, "")
+	require.NoError(t, err)
+	assert.Equal(t, SizeSourceEstimate, report.Source)
+	names := map[string]int64{}
+	for _, f := range report.Functions {
+		names[f.Symbol.Name] = f.Bytes
+	}
+	assert.Equal(t, []string{"helper", "main", "parse"}, slices.Sorted(maps.Keys(names)), "unused and open are never called")
+	assert.Equal(t, int64(len("fn helper() {}")), names["helper"])
+	var total int64
+	for _, m := range report.Modules {
+		total += m.Bytes
+	}
+	assert.Equal(t, report.Total, total)
+
+	// Measured: monomorphized copies and closures add up, the rest is
+	// unattributed.
+	report, err = e.Query().BinarySize([]MeasuredSize{
+		{Name: "app::main", Bytes: 100},
+		{Name: "app::net::parse::h0123456789abcdef", Bytes: 300},
+		{Name: "app::net::parse::{{closure}}", Bytes: 20},
+		{Name: "<app::net::Conn>::open", Bytes: 50},
+		{Name: "std::rt::lang_start", Crate: "std", Bytes: 500},
+	}, "")
+	require.NoError(t, err)
+	assert.Equal(t, SizeSourceMeasured, report.Source)
+	assert.Equal(t, int64(970), report.Total)
+	type fn struct {
+		name    string
+		bytes   int64
+		inlined bool
+	}
+	var fns []fn
+	for _, f := range report.Functions {
+		fns = append(fns, fn{f.Symbol.Name, f.Bytes, f.Inlined})
+	}
+	assert.Equal(t, []fn{{"parse", 320, false}, {"main", 100, false}, {"open", 50, false}, {"helper", 0, true}}, fns)
+	require.Len(t, report.Modules, 2)
+	assert.Equal(t, ModuleSize{Module: "app::net", Bytes: 370, Functions: 2, Share: 370.0 / 970}, report.Modules[0])
+	assert.Equal(t, "app", report.Modules[1].Module)
+	assert.Equal(t, []CrateSize{{Crate: "std", Bytes: 500, Share: 500.0 / 970}}, report.Unattributed)
+}
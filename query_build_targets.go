@@ -0,0 +1,199 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+)
+
+// Reasons a build target is affected by a change to a symbol, in the order
+// ImpactedTargets reports them.
+const (
+	TargetReasonDefines    = "defines"    // a source file defines the symbol
+	TargetReasonReferences = "references" // a source file references the symbol
+	TargetReasonCalls      = "calls"      // a source file defines a transitive caller
+	TargetReasonDepends    = "depends"    // the target depends on an affected target
+)
+
+var targetReasonRank = map[string]int{
+	TargetReasonDefines:    0,
+	TargetReasonReferences: 1,
+	TargetReasonCalls:      2,
+	TargetReasonDepends:    3,
+}
+
+// TargetImpact is a Bazel or Buck target affected by a change to a symbol.
+type TargetImpact struct {
+	Label  string
+	Kind   string // rule name, e.g. "go_library"
+	Reason string // one of the TargetReason constants
+	Via    string // for TargetReasonDepends: the affected dependency it reaches through
+	Depth  int    // dependency hops from a directly affected target (0 = direct)
+}
+
+// FileBuildTargets returns the labels of the build targets that list a file
+// as a source. Returns an empty slice if none do or its root has no build
+// graph.
+func (q *QueryBuilder) FileBuildTargets(fileID int64) ([]string, error) {
+	labels, err := q.store.FileBuildTargets(fileID)
+	if err != nil {
+		return nil, fmt.Errorf("file build targets: %w", err)
+	}
+	if labels == nil {
+		labels = []string{}
+	}
+	return labels, nil
+}
+
+// SymbolBuildTargets returns the build targets of the file that defines a
+// symbol.
+func (q *QueryBuilder) SymbolBuildTargets(symbolID int64) ([]string, error) {
+	sym, err := q.store.SymbolByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("symbol build targets: %w", err)
+	}
+	if sym == nil || sym.FileID == nil {
+		return []string{}, nil
+	}
+	return q.FileBuildTargets(*sym.FileID)
+}
+
+// ImpactedTargets returns the build targets a change to a symbol affects,
+// answering "which targets must be rebuilt and retested". The targets whose
+// sources define the symbol, reference it, or define one of its transitive
+// callers (up to maxDepth) are affected directly; every target that depends
+// on an affected target, transitively, is affected too. Sorted by Depth, then
+// Reason, then Label. Returns nil, nil if the symbol does not exist.
+func (q *QueryBuilder) ImpactedTargets(symbolID int64, maxDepth int) ([]TargetImpact, error) {
+	graph, err := q.TransitiveCallers(symbolID, maxDepth)
+	if err != nil {
+		return nil, fmt.Errorf("impacted targets: %w", err)
+	}
+	if graph == nil {
+		return nil, nil
+	}
+
+	// Targets are keyed by root and label: two roots may reuse a label.
+	type targetKey struct{ root, label string }
+
+	fileTargets := map[int64][]targetKey{}
+	rows, err := q.store.DB().Query("SELECT file_id, root, label FROM file_build_targets")
+	if err != nil {
+		return nil, fmt.Errorf("impacted targets: load file targets: %w", err)
+	}
+	for rows.Next() {
+		var fileID int64
+		var k targetKey
+		if err := rows.Scan(&fileID, &k.root, &k.label); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("impacted targets: scan file target: %w", err)
+		}
+		fileTargets[fileID] = append(fileTargets[fileID], k)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("impacted targets: load file targets: %w", err)
+	}
+
+	targets, err := q.store.BuildTargets()
+	if err != nil {
+		return nil, fmt.Errorf("impacted targets: %w", err)
+	}
+	kinds := map[targetKey]string{}
+	rdeps := map[targetKey][]targetKey{}
+	for _, t := range targets {
+		k := targetKey{t.Root, t.Label}
+		kinds[k] = t.Kind
+		for _, dep := range t.Deps {
+			dk := targetKey{t.Root, dep}
+			rdeps[dk] = append(rdeps[dk], k)
+		}
+	}
+
+	impacts := map[targetKey]*TargetImpact{}
+	var queue []targetKey
+	affect := func(fileID int64, reason string) {
+		for _, k := range fileTargets[fileID] {
+			if imp, ok := impacts[k]; ok {
+				if targetReasonRank[reason] < targetReasonRank[imp.Reason] {
+					imp.Reason = reason
+				}
+				continue
+			}
+			impacts[k] = &TargetImpact{Label: k.label, Kind: kinds[k], Reason: reason}
+			queue = append(queue, k)
+		}
+	}
+
+	for _, node := range graph.Nodes {
+		if node.Symbol.FileID == nil {
+			continue
+		}
+		if node.Depth == 0 {
+			affect(*node.Symbol.FileID, TargetReasonDefines)
+		} else {
+			affect(*node.Symbol.FileID, TargetReasonCalls)
+		}
+	}
+
+	rows, err = q.store.DB().Query(
+		`SELECT DISTINCT r.file_id FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 WHERE rr.target_symbol_id = ?`, symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("impacted targets: references: %w", err)
+	}
+	var refFiles []int64
+	for rows.Next() {
+		var fileID int64
+		if err := rows.Scan(&fileID); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("impacted targets: scan reference: %w", err)
+		}
+		refFiles = append(refFiles, fileID)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("impacted targets: references: %w", err)
+	}
+	for _, fileID := range refFiles {
+		affect(fileID, TargetReasonReferences)
+	}
+
+	// Walk reverse dependencies breadth-first so each target records its
+	// shortest path to a directly affected one.
+	for len(queue) > 0 {
+		k := queue[0]
+		queue = queue[1:]
+		depth := impacts[k].Depth
+		for _, rk := range rdeps[k] {
+			if _, ok := impacts[rk]; ok {
+				continue
+			}
+			impacts[rk] = &TargetImpact{
+				Label:  rk.label,
+				Kind:   kinds[rk],
+				Reason: TargetReasonDepends,
+				Via:    k.label,
+				Depth:  depth + 1,
+			}
+			queue = append(queue, rk)
+		}
+	}
+
+	result := make([]TargetImpact, 0, len(impacts))
+	for _, imp := range impacts {
+		result = append(result, *imp)
+	}
+	sort.Slice(result, func(i, j int) bool {
+		if result[i].Depth != result[j].Depth {
+			return result[i].Depth < result[j].Depth
+		}
+		ri, rj := targetReasonRank[result[i].Reason], targetReasonRank[result[j].Reason]
+		if ri != rj {
+			return ri < rj
+		}
+		return result[i].Label < result[j].Label
+	})
+	return result, nil
+}
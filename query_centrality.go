@@ -0,0 +1,237 @@
+package canopy
+
+import (
+	"fmt"
+	"math"
+	"sort"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// PageRank parameters: the damping factor, and the iteration cap and L1
+// convergence threshold of the power iteration.
+const (
+	pageRankDamping    = 0.85
+	pageRankIterations = 100
+	pageRankEpsilon    = 1e-9
+)
+
+// maxBetweennessSources caps the number of source nodes in the betweenness
+// computation. Larger graphs use evenly spaced sample sources and scale the
+// result, which keeps resolution time linear in the size of the graph.
+const maxBetweennessSources = 256
+
+// symbolGraph is the directed symbol reference graph. There is an edge from
+// symbol A to symbol B for each resolved reference to B made inside A's span
+// (A being the narrowest enclosing symbol), weighted by the number of such
+// references. Self-references and references outside any symbol are dropped.
+type symbolGraph struct {
+	ids   []int64       // symbol ID of each node, ascending
+	index map[int64]int // symbol ID -> node
+	out   []map[int]float64
+}
+
+// symbolReferenceEdgesSQL selects (source, target, count) for every pair of
+// symbols connected by resolved references. The source is the narrowest
+// symbol in the reference's file whose span contains it, ignoring
+// package-like symbols that span whole files.
+const symbolReferenceEdgesSQL = `SELECT src, target, COUNT(*) FROM (
+	SELECT (SELECT s.id FROM symbols s
+		WHERE s.file_id = r.file_id AND s.kind NOT IN ('package', 'module', 'namespace')
+		AND (s.start_line < r.start_line OR (s.start_line = r.start_line AND s.start_col <= r.start_col))
+		AND (s.end_line > r.end_line OR (s.end_line = r.end_line AND s.end_col >= r.end_col))
+		ORDER BY s.start_line DESC, s.start_col DESC, s.id DESC LIMIT 1) AS src,
+		rr.target_symbol_id AS target
+	FROM resolved_references rr
+	JOIN references_ r ON r.id = rr.reference_id
+) WHERE src IS NOT NULL AND src != target
+GROUP BY src, target`
+
+// buildSymbolGraph loads the symbol reference graph over all symbols.
+func (q *QueryBuilder) buildSymbolGraph() (*symbolGraph, error) {
+	rows, err := q.store.DB().Query("SELECT id FROM symbols ORDER BY id")
+	if err != nil {
+		return nil, fmt.Errorf("query symbols: %w", err)
+	}
+	g := &symbolGraph{index: map[int64]int{}}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("scan symbol: %w", err)
+		}
+		g.index[id] = len(g.ids)
+		g.ids = append(g.ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("symbol rows: %w", err)
+	}
+	g.out = make([]map[int]float64, len(g.ids))
+
+	edgeRows, err := q.store.DB().Query(symbolReferenceEdgesSQL)
+	if err != nil {
+		return nil, fmt.Errorf("query reference edges: %w", err)
+	}
+	defer edgeRows.Close()
+	for edgeRows.Next() {
+		var src, target int64
+		var count int
+		if err := edgeRows.Scan(&src, &target, &count); err != nil {
+			return nil, fmt.Errorf("scan reference edge: %w", err)
+		}
+		from, ok1 := g.index[src]
+		to, ok2 := g.index[target]
+		if !ok1 || !ok2 {
+			continue
+		}
+		if g.out[from] == nil {
+			g.out[from] = map[int]float64{}
+		}
+		g.out[from][to] += float64(count)
+	}
+	if err := edgeRows.Err(); err != nil {
+		return nil, fmt.Errorf("reference edge rows: %w", err)
+	}
+	return g, nil
+}
+
+// pageRank computes the weighted PageRank of every node by power iteration.
+// The ranks sum to 1. Rank held by nodes without outgoing edges is spread
+// evenly over all nodes.
+func (g *symbolGraph) pageRank() []float64 {
+	n := len(g.ids)
+	if n == 0 {
+		return nil
+	}
+	outWeight := make([]float64, n)
+	for i, edges := range g.out {
+		for _, w := range edges {
+			outWeight[i] += w
+		}
+	}
+
+	rank := make([]float64, n)
+	for i := range rank {
+		rank[i] = 1 / float64(n)
+	}
+	next := make([]float64, n)
+	for iter := 0; iter < pageRankIterations; iter++ {
+		dangling := 0.0
+		for i, r := range rank {
+			if outWeight[i] == 0 {
+				dangling += r
+			}
+		}
+		base := (1-pageRankDamping)/float64(n) + pageRankDamping*dangling/float64(n)
+		for i := range next {
+			next[i] = base
+		}
+		for i, edges := range g.out {
+			if outWeight[i] == 0 {
+				continue
+			}
+			share := pageRankDamping * rank[i] / outWeight[i]
+			for j, w := range edges {
+				next[j] += share * w
+			}
+		}
+
+		delta := 0.0
+		for i := range rank {
+			delta += math.Abs(next[i] - rank[i])
+		}
+		rank, next = next, rank
+		if delta < pageRankEpsilon {
+			break
+		}
+	}
+	return rank
+}
+
+// betweenness computes the betweenness centrality of every node with
+// Brandes' algorithm, treating edges as unweighted: the number of shortest
+// paths between other nodes that pass through the node, summed over all
+// pairs (estimated from sample sources on large graphs).
+func (g *symbolGraph) betweenness() []float64 {
+	n := len(g.ids)
+	bc := make([]float64, n)
+	if n == 0 {
+		return bc
+	}
+	adj := make([][]int, n)
+	for i, edges := range g.out {
+		for j := range edges {
+			adj[i] = append(adj[i], j)
+		}
+		sort.Ints(adj[i])
+	}
+
+	step := 1
+	if n > maxBetweennessSources {
+		step = (n + maxBetweennessSources - 1) / maxBetweennessSources
+	}
+	sigma := make([]float64, n) // shortest paths from the source
+	dist := make([]int, n)
+	delta := make([]float64, n)
+	preds := make([][]int, n)
+	var order, queue []int
+	sources := 0
+	for src := 0; src < n; src += step {
+		sources++
+		for i := range sigma {
+			sigma[i], dist[i], delta[i], preds[i] = 0, -1, 0, preds[i][:0]
+		}
+		sigma[src], dist[src] = 1, 0
+		order = order[:0]
+		queue = append(queue[:0], src)
+		for len(queue) > 0 {
+			v := queue[0]
+			queue = queue[1:]
+			order = append(order, v)
+			for _, w := range adj[v] {
+				if dist[w] < 0 {
+					dist[w] = dist[v] + 1
+					queue = append(queue, w)
+				}
+				if dist[w] == dist[v]+1 {
+					sigma[w] += sigma[v]
+					preds[w] = append(preds[w], v)
+				}
+			}
+		}
+		for i := len(order) - 1; i >= 0; i-- {
+			w := order[i]
+			for _, v := range preds[w] {
+				delta[v] += sigma[v] / sigma[w] * (1 + delta[w])
+			}
+			if w != src {
+				bc[w] += delta[w]
+			}
+		}
+	}
+	if sources < n {
+		scale := float64(n) / float64(sources)
+		for i := range bc {
+			bc[i] *= scale
+		}
+	}
+	return bc
+}
+
+// computeCentrality scores every symbol by PageRank and betweenness over the
+// reference graph. Centrality depends on the whole graph, so it is
+// recomputed in full after every resolution, including incremental ones.
+func (e *Engine) computeCentrality() error {
+	g, err := e.Query().buildSymbolGraph()
+	if err != nil {
+		return err
+	}
+	ranks := g.pageRank()
+	bc := g.betweenness()
+	scores := make([]store.SymbolCentrality, len(g.ids))
+	for i, id := range g.ids {
+		scores[i] = store.SymbolCentrality{ID: id, PageRank: ranks[i], Betweenness: bc[i]}
+	}
+	return e.store.UpdateSymbolCentrality(scores)
+}
@@ -0,0 +1,94 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSymbolGraph_PageRank(t *testing.T) {
+	t.Parallel()
+	// 0 -> 2, 1 -> 2 (twice), 2 -> 3; 3 is dangling.
+	g := &symbolGraph{
+		ids: []int64{10, 11, 12, 13},
+		out: []map[int]float64{{2: 1}, {2: 2}, {3: 1}, nil},
+	}
+	ranks := g.pageRank()
+	require.Len(t, ranks, 4)
+
+	sum := 0.0
+	for _, r := range ranks {
+		sum += r
+	}
+	assert.InDelta(t, 1.0, sum, 1e-6)
+	assert.Greater(t, ranks[2], ranks[0])
+	assert.Greater(t, ranks[3], ranks[2], "a node referenced only by a central node inherits its rank")
+	assert.InDelta(t, ranks[0], ranks[1], 1e-12)
+}
+
+func TestSymbolGraph_Betweenness(t *testing.T) {
+	t.Parallel()
+	// 0 -> 1 -> 2 and 0 -> 3 -> 2: 1 and 3 each carry half of the 0 -> 2 paths.
+	// 2 -> 4: 2 lies on the paths from 0, 1, and 3 to 4.
+	g := &symbolGraph{
+		ids: []int64{1, 2, 3, 4, 5},
+		out: []map[int]float64{{1: 1, 3: 1}, {2: 5}, {4: 1}, {2: 1}, nil},
+	}
+	bc := g.betweenness()
+	require.Len(t, bc, 5)
+	assert.InDelta(t, 0.0, bc[0], 1e-12)
+	assert.InDelta(t, 1.0, bc[1], 1e-12, "half of 0->2 and half of 0->4")
+	assert.InDelta(t, 1.0, bc[3], 1e-12, "half of 0->2 and half of 0->4")
+	assert.InDelta(t, 3.0, bc[2], 1e-12)
+	assert.InDelta(t, 0.0, bc[4], 1e-12)
+}
+
+func TestResolve_ComputesCentrality(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	path := writeGoFile(t, t.TempDir(), "main.go", `package main
+
+type Store struct{}
+
+func (s *Store) Get(key string) string {
+	return key
+}
+
+func handlerA(s *Store) string { return s.Get("a") }
+
+func handlerB(s *Store) string { return s.Get("b") }
+
+func main() {
+	st := &Store{}
+	handlerA(st)
+	handlerB(st)
+}
+`)
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	byName := map[string]SymbolResult{}
+	res, err := e.Query().Symbols(SymbolFilter{}, Sort{Field: SortByPageRank, Order: Desc}, Pagination{})
+	require.NoError(t, err)
+	for i, sr := range res.Items {
+		if i > 0 {
+			assert.GreaterOrEqual(t, res.Items[i-1].PageRank, sr.PageRank, "sorted by pagerank")
+		}
+		byName[sr.Name] = sr
+	}
+	assert.Greater(t, byName["Get"].PageRank, byName["main"].PageRank)
+	assert.Greater(t, byName["Get"].PageRank, byName["handlerA"].PageRank)
+
+	assert.Greater(t, byName["handlerA"].Betweenness, 0.0, "main reaches Get through the handlers")
+	assert.InDelta(t, 0.0, byName["main"].Betweenness, 1e-12)
+
+	res, err = e.Query().Symbols(SymbolFilter{}, Sort{Field: SortByBetweenness, Order: Desc}, Pagination{})
+	require.NoError(t, err)
+	require.NotEmpty(t, res.Items)
+	assert.Greater(t, res.Items[0].Betweenness, 0.0)
+	for i := 1; i < len(res.Items); i++ {
+		assert.GreaterOrEqual(t, res.Items[i-1].Betweenness, res.Items[i].Betweenness, "sorted by betweenness")
+	}
+}
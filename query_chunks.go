@@ -0,0 +1,440 @@
+package canopy
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"path/filepath"
+	"sort"
+	"strings"
+)
+
+// CodeChunk is a slice of the codebase along a symbol boundary, sized and
+// linked for use as input to an embedding pipeline: a function or method
+// with its doc comment and signature, a type declaration, or a Rust impl
+// block. The text of a chunk containing other chunks (an impl block, a
+// class, a trait) keeps their signatures but elides their bodies, which are
+// chunks of their own.
+type CodeChunk struct {
+	ID        string // stable across reindexing; see CodeChunks
+	File      string // path relative to the indexed root
+	Language  string
+	Kind      string // the symbol kind, or "impl" for a Rust impl block
+	Name      string // the symbol name, or the header of an impl block
+	Path      string // the names of the enclosing chunks and the chunk's own, joined by "::"
+	Signature string // declaration header, e.g. "pub fn area(&self) -> f64"
+	Doc       string // the comment block directly above the declaration, as written
+	StartLine int    // 0-based
+	EndLine   int    // 0-based
+	Text      string
+	Tokens    int   // estimated size of Doc and Text in LLM tokens
+	SymbolID  int64 // 0 for an impl block
+	Part      int   // 1-based position among the parts of a chunk split by SplitChunks; 0 if not split
+	Parts     int   // the number of parts the chunk was split into; 0 if not split
+
+	Parent   string   // ID of the enclosing chunk, "" at top level
+	Children []string // IDs of the chunks inside this one, in source order
+	Calls    []string // IDs of the chunks this one calls
+	CalledBy []string // IDs of the chunks calling this one
+}
+
+// chunkSymbolKinds are the symbol kinds that start a chunk.
+var chunkSymbolKinds = map[string]bool{
+	"function": true, "method": true, "constructor": true,
+	"struct": true, "enum": true, "union": true, "trait": true, "interface": true,
+	"class": true, "object": true, "type_alias": true,
+}
+
+// chunkBodyKinds are the chunk kinds with bodies whose nested symbols
+// (locals, closures) are part of the chunk rather than chunks of their own.
+var chunkBodyKinds = map[string]bool{"function": true, "method": true, "constructor": true}
+
+// CodeChunks slices every indexed source file into chunks along symbol
+// boundaries, ordered by file and position. A chunk's ID hashes the file's
+// path relative to its indexed root, the chunk's kind and its Path, plus an
+// ordinal among chunks sharing all three (overloads), so it stays the same
+// across reindexing and checkouts as long as the declaration keeps its place
+// in the code. Chunks link to their enclosing chunk and to the chunks they
+// call and are called by, for retrieval that expands a hit to its
+// neighbors. Files whose source cannot be read are skipped.
+func (q *QueryBuilder) CodeChunks() ([]CodeChunk, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT id, path, language, COALESCE(root, '') FROM files WHERE language != ? ORDER BY path`,
+		rustdocStubLanguage,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("code chunks: %w", err)
+	}
+	type fileInfo struct {
+		id                   int64
+		path, language, root string
+	}
+	var files []fileInfo
+	for rows.Next() {
+		var f fileInfo
+		if err := rows.Scan(&f.id, &f.path, &f.language, &f.root); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("code chunks: %w", err)
+		}
+		files = append(files, f)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("code chunks: %w", err)
+	}
+
+	var chunks []CodeChunk
+	bySymbol := map[int64]int{}
+	for _, f := range files {
+		src, err := q.readSource(f.path)
+		if err != nil {
+			continue
+		}
+		fileChunks, err := q.fileChunks(f.id, f.language, relativeFilePath(f.path, f.root), newLineIndex(src))
+		if err != nil {
+			return nil, fmt.Errorf("code chunks: %w", err)
+		}
+		for _, c := range fileChunks {
+			if c.SymbolID != 0 {
+				bySymbol[c.SymbolID] = len(chunks)
+			}
+			chunks = append(chunks, c)
+		}
+	}
+
+	edges, err := q.store.DB().Query(`SELECT DISTINCT caller_symbol_id, callee_symbol_id FROM call_graph`)
+	if err != nil {
+		return nil, fmt.Errorf("code chunks: call graph: %w", err)
+	}
+	calls := map[int]map[int]bool{}
+	calledBy := map[int]map[int]bool{}
+	for edges.Next() {
+		var caller, callee int64
+		if err := edges.Scan(&caller, &callee); err != nil {
+			edges.Close()
+			return nil, fmt.Errorf("code chunks: call graph: %w", err)
+		}
+		from, ok := bySymbol[caller]
+		if !ok {
+			continue
+		}
+		to, ok := bySymbol[callee]
+		if !ok || to == from {
+			continue
+		}
+		if calls[from] == nil {
+			calls[from] = map[int]bool{}
+		}
+		if calledBy[to] == nil {
+			calledBy[to] = map[int]bool{}
+		}
+		calls[from][to] = true
+		calledBy[to][from] = true
+	}
+	edges.Close()
+	if err := edges.Err(); err != nil {
+		return nil, fmt.Errorf("code chunks: call graph: %w", err)
+	}
+
+	chunkIDs := func(set map[int]bool) []string {
+		idx := make([]int, 0, len(set))
+		for i := range set {
+			idx = append(idx, i)
+		}
+		sort.Ints(idx)
+		ids := make([]string, len(idx))
+		for k, i := range idx {
+			ids[k] = chunks[i].ID
+		}
+		return ids
+	}
+	for i := range chunks {
+		chunks[i].Calls = chunkIDs(calls[i])
+		chunks[i].CalledBy = chunkIDs(calledBy[i])
+	}
+	return chunks, nil
+}
+
+// ChunkLimits bounds the size of code chunks; see SplitChunks.
+type ChunkLimits struct {
+	MaxTokens int // estimated size above which a function chunk is split; 0 for no limit
+	Overlap   int // lines each part after the first repeats from the end of the part before it
+}
+
+// SplitChunks returns chunks with each function, method, or constructor
+// chunk estimated above limits.MaxTokens split into parts that fit it, for
+// consumers that need units of bounded size. Parts break at statement
+// boundaries of the body: after a line that closes every bracket opened
+// since the end of the signature's first line, before a line indented like
+// the first line of the body, so indentation-scoped bodies split too. A
+// statement larger than the limit is a part of its own. Each part after the
+// first starts with the last limits.Overlap lines of the part before it.
+// Parts keep the chunk's Kind, Name, Path, Signature, SymbolID, Parent, and
+// call links, which refer them back to their symbol; the first also keeps
+// its ID and Doc, so links to the chunk lead to the start of the symbol,
+// and the others get IDs hashed from the chunk's ID and their part number.
+// Other chunks are returned unchanged.
+func SplitChunks(chunks []CodeChunk, limits ChunkLimits) []CodeChunk {
+	if limits.MaxTokens <= 0 {
+		return chunks
+	}
+	var out []CodeChunk
+	for _, c := range chunks {
+		if !chunkBodyKinds[c.Kind] || c.Tokens <= limits.MaxTokens {
+			out = append(out, c)
+			continue
+		}
+		out = append(out, splitChunk(c, limits)...)
+	}
+	return out
+}
+
+// splitChunk splits a function chunk into parts; see SplitChunks.
+func splitChunk(c CodeChunk, limits ChunkLimits) []CodeChunk {
+	lines := strings.Split(c.Text, "\n")
+	indent := func(line string) string { return line[:len(line)-len(strings.TrimLeft(line, " \t"))] }
+
+	// Statements, as ranges of lines [start, end).
+	var stmts [][2]int
+	depth, base, start := 0, 0, 0
+	for i, line := range lines {
+		for _, r := range line {
+			switch r {
+			case '(', '[', '{':
+				depth++
+			case ')', ']', '}':
+				depth--
+			}
+		}
+		if i == 0 {
+			base = depth
+		}
+		if i+1 < len(lines) && depth == base && indent(lines[i+1]) == indent(lines[1]) {
+			stmts = append(stmts, [2]int{start, i + 1})
+			start = i + 1
+		}
+	}
+	stmts = append(stmts, [2]int{start, len(lines)})
+
+	docTokens := estimateTokens(c.Doc)
+	tokens := func(part [2]int, first bool) int {
+		n := estimateTokens(strings.Join(lines[part[0]:part[1]], "\n"))
+		if first {
+			n += docTokens
+		}
+		return n
+	}
+	var parts [][2]int
+	cur := stmts[0]
+	for _, s := range stmts[1:] {
+		if tokens([2]int{cur[0], s[1]}, len(parts) == 0) <= limits.MaxTokens {
+			cur[1] = s[1]
+			continue
+		}
+		parts = append(parts, cur)
+		cur = [2]int{max(s[0]-limits.Overlap, cur[0]+1), s[1]}
+	}
+	parts = append(parts, cur)
+	if len(parts) == 1 {
+		return []CodeChunk{c}
+	}
+
+	out := make([]CodeChunk, len(parts))
+	for k, part := range parts {
+		p := c
+		p.Part, p.Parts = k+1, len(parts)
+		p.StartLine, p.EndLine = c.StartLine+part[0], c.StartLine+part[1]-1
+		p.Text = strings.Join(lines[part[0]:part[1]], "\n")
+		if k > 0 {
+			sum := sha256.Sum256([]byte(fmt.Sprintf("%s\x00%d", c.ID, p.Part)))
+			p.ID = hex.EncodeToString(sum[:8])
+			p.Doc = ""
+		}
+		p.Tokens = estimateTokens(p.Doc) + estimateTokens(p.Text)
+		out[k] = p
+	}
+	return out
+}
+
+// chunkSpan is a chunk being built, with its byte span in the file.
+type chunkSpan struct {
+	chunk      CodeChunk
+	start, end int
+	parent     int // index of the enclosing span, -1 at top level
+}
+
+// fileChunks returns the chunks of one file, in source order, without
+// their call neighbors.
+func (q *QueryBuilder) fileChunks(fileID int64, language, rel string, li *lineIndex) ([]CodeChunk, error) {
+	syms, err := q.store.SymbolsByFile(fileID)
+	if err != nil {
+		return nil, err
+	}
+	var spans []chunkSpan
+	for _, s := range syms {
+		if !chunkSymbolKinds[s.Kind] {
+			continue
+		}
+		start, _ := li.offsets(s.StartLine, s.StartCol)
+		end, _ := li.offsets(s.EndLine, s.EndCol)
+		spans = append(spans, chunkSpan{
+			chunk: CodeChunk{
+				Kind: s.Kind, Name: s.Name, Signature: symbolSignature(li, s),
+				StartLine: s.StartLine, EndLine: s.EndLine, SymbolID: s.ID,
+			},
+			start: start, end: end,
+		})
+	}
+	if language == "rust" {
+		scopes, err := q.store.ScopesByFile(fileID)
+		if err != nil {
+			return nil, err
+		}
+		for _, sc := range scopes {
+			if sc.Kind != "impl" {
+				continue
+			}
+			block := &Symbol{Kind: "impl", Name: "impl", StartLine: sc.StartLine, StartCol: sc.StartCol, EndLine: sc.EndLine, EndCol: sc.EndCol}
+			start, _ := li.offsets(sc.StartLine, sc.StartCol)
+			end, _ := li.offsets(sc.EndLine, sc.EndCol)
+			sig := symbolSignature(li, block)
+			spans = append(spans, chunkSpan{
+				chunk: CodeChunk{Kind: "impl", Name: sig, Signature: sig, StartLine: sc.StartLine, EndLine: sc.EndLine},
+				start: start, end: end,
+			})
+		}
+	}
+	// Outer spans sort before the spans they contain.
+	sort.SliceStable(spans, func(i, j int) bool {
+		if spans[i].start != spans[j].start {
+			return spans[i].start < spans[j].start
+		}
+		return spans[i].end > spans[j].end
+	})
+
+	// Find each span's parent with a stack of open spans, dropping spans
+	// inside function bodies.
+	var kept []chunkSpan
+	var open []int
+	for _, sp := range spans {
+		for len(open) > 0 && kept[open[len(open)-1]].end <= sp.start {
+			open = open[:len(open)-1]
+		}
+		sp.parent = -1
+		if len(open) > 0 {
+			top := open[len(open)-1]
+			if chunkBodyKinds[kept[top].chunk.Kind] || (kept[top].start == sp.start && kept[top].end == sp.end) {
+				continue
+			}
+			sp.parent = top
+		}
+		open = append(open, len(kept))
+		kept = append(kept, sp)
+	}
+
+	ordinals := map[string]int{}
+	chunks := make([]CodeChunk, len(kept))
+	for i, sp := range kept {
+		c := sp.chunk
+		c.File, c.Language = rel, language
+		c.Path = c.Name
+		if sp.parent >= 0 {
+			c.Path = chunks[sp.parent].Path + "::" + c.Name
+			c.Parent = chunks[sp.parent].ID
+		}
+		key := rel + "\x00" + c.Kind + "\x00" + c.Path
+		sum := sha256.Sum256([]byte(fmt.Sprintf("%s\x00%d", key, ordinals[key])))
+		ordinals[key]++
+		c.ID = hex.EncodeToString(sum[:8])
+		c.Doc = leadingComment(li, c.StartLine, hashCommentLanguages[language])
+
+		var text strings.Builder
+		pos := sp.start
+		for j := i + 1; j < len(kept); j++ {
+			child := kept[j]
+			if child.start >= sp.end {
+				break
+			}
+			if child.parent != i {
+				continue
+			}
+			text.Write(li.content[pos:child.start])
+			childText := string(li.content[child.start:child.end])
+			if strings.Contains(childText, "{") {
+				childText = child.chunk.Signature + " { ... }"
+			}
+			text.WriteString(childText)
+			pos = child.end
+		}
+		text.Write(li.content[pos:sp.end])
+		c.Text = text.String()
+		c.Tokens = estimateTokens(c.Doc) + estimateTokens(c.Text)
+		chunks[i] = c
+	}
+	for i, sp := range kept {
+		if sp.parent >= 0 {
+			chunks[sp.parent].Children = append(chunks[sp.parent].Children, chunks[i].ID)
+		}
+	}
+	return chunks, nil
+}
+
+// hashCommentLanguages are the languages whose line comments start with #.
+var hashCommentLanguages = map[string]bool{"python": true, "ruby": true}
+
+// leadingComment returns the comment block on the lines directly above
+// line, as written, skipping attribute and annotation lines between it and
+// the declaration (#[derive(Debug)], @Override). hashComments counts lines
+// starting with # as comments. "" if there is none.
+func leadingComment(li *lineIndex, line int, hashComments bool) string {
+	var lines []string
+	for l := leadingCommentStart(li, line, hashComments); l < line; l++ {
+		if text := lineText(li, l); isCommentLine(text, hashComments) {
+			lines = append(lines, text)
+		}
+	}
+	return strings.Join(lines, "\n")
+}
+
+// leadingCommentStart returns the first line of the comment block and
+// attribute lines directly above line, or line if there are none.
+func leadingCommentStart(li *lineIndex, line int, hashComments bool) int {
+	start := line
+	inComment := false
+	for l := line - 1; l >= 0; l-- {
+		switch text := lineText(li, l); {
+		case isCommentLine(text, hashComments):
+			inComment = true
+		case !inComment && (strings.HasPrefix(text, "#[") || strings.HasPrefix(text, "@")):
+		default:
+			return start
+		}
+		start = l
+	}
+	return start
+}
+
+// lineText returns line l of li without surrounding whitespace.
+func lineText(li *lineIndex, l int) string {
+	start, _ := li.offsets(l, 0)
+	end, _ := li.offsets(l+1, 0)
+	return strings.TrimSpace(string(li.content[start:end]))
+}
+
+// isCommentLine reports whether a trimmed line is part of a comment.
+func isCommentLine(text string, hashComments bool) bool {
+	return strings.HasPrefix(text, "//") || strings.HasPrefix(text, "/*") || strings.HasPrefix(text, "*") ||
+		strings.HasSuffix(text, "*/") || hashComments && strings.HasPrefix(text, "#")
+}
+
+// relativeFilePath returns path relative to the indexed root it was found
+// under, with forward slashes, or path itself if root is "" or not an
+// ancestor.
+func relativeFilePath(path, root string) string {
+	if root != "" {
+		if r, err := filepath.Rel(root, path); err == nil && !strings.HasPrefix(r, "..") {
+			return filepath.ToSlash(r)
+		}
+	}
+	return path
+}
@@ -0,0 +1,144 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestLeadingComment(t *testing.T) {
+	li := newLineIndex([]byte("use std::fmt;\n\n/// Area of a shape.\n/// In square units.\n#[inline]\nfn area() {}\n# not a comment\nfn other() {}\n"))
+	assert.Equal(t, "/// Area of a shape.\n/// In square units.", leadingComment(li, 5, false))
+	assert.Equal(t, "", leadingComment(li, 7, false))
+	assert.Equal(t, "# not a comment", leadingComment(li, 7, true))
+	assert.Equal(t, "", leadingComment(li, 0, false))
+}
+
+func TestCodeChunks(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", `/// A circle.
+#[derive(Debug)]
+pub struct Circle {
+    pub r: f64,
+}
+
+impl Circle {
+    /// The area.
+    pub fn area(&self) -> f64 {
+        let sq = |x: f64| x * x;
+        3.14 * sq(self.r)
+    }
+}
+
+pub fn total(c: &Circle) -> f64 {
+    c.area() * 2.0
+}
+`)
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	chunks, err := e.Query().CodeChunks()
+	require.NoError(t, err)
+	byPath := map[string]CodeChunk{}
+	var paths []string
+	for _, c := range chunks {
+		byPath[c.Path] = c
+		paths = append(paths, c.Path)
+	}
+	assert.Equal(t, []string{"Circle", "impl Circle", "impl Circle::area", "total"}, paths)
+
+	circle := byPath["Circle"]
+	assert.Equal(t, "src/lib.rs", circle.File)
+	assert.Equal(t, "struct", circle.Kind)
+	assert.Equal(t, "/// A circle.", circle.Doc)
+	assert.Equal(t, "pub struct Circle", circle.Signature)
+
+	impl := byPath["impl Circle"]
+	area := byPath["impl Circle::area"]
+	assert.Equal(t, "impl", impl.Kind)
+	assert.Zero(t, impl.SymbolID)
+	assert.Equal(t, []string{area.ID}, impl.Children)
+	assert.Contains(t, impl.Text, "/// The area.\n    pub fn area(&self) -> f64 { ... }")
+	assert.NotContains(t, impl.Text, "3.14")
+
+	assert.Equal(t, impl.ID, area.Parent)
+	assert.Equal(t, "/// The area.", area.Doc)
+	assert.Contains(t, area.Text, "let sq = |x: f64| x * x;", "a closure stays in its function's chunk")
+	assert.Positive(t, area.Tokens)
+
+	total := byPath["total"]
+	assert.Equal(t, []string{area.ID}, total.Calls)
+	assert.Equal(t, []string{total.ID}, area.CalledBy)
+
+	// IDs are stable across reindexing.
+	e2 := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e2.IndexDirectory(ctx, root))
+	require.NoError(t, e2.Resolve(ctx))
+	again, err := e2.Query().CodeChunks()
+	require.NoError(t, err)
+	require.Len(t, again, len(chunks))
+	for i := range chunks {
+		assert.Equal(t, chunks[i].ID, again[i].ID)
+	}
+}
+
+func TestSplitChunks(t *testing.T) {
+	text := `fn big() {
+    let a = compute(1, 2);
+    let b = vec![
+        a,
+        a + 1,
+    ];
+    println!("{:?}", b);
+}`
+	big := CodeChunk{ID: "big", Kind: "function", Name: "big", Path: "big", Signature: "fn big()", Doc: "/// Big.",
+		StartLine: 10, EndLine: 17, Text: text, SymbolID: 7, Parent: "outer", Calls: []string{"compute"}}
+	big.Tokens = estimateTokens(big.Doc) + estimateTokens(text)
+	small := CodeChunk{ID: "small", Kind: "function", Text: "fn small() {}", Tokens: 4}
+	typ := CodeChunk{ID: "typ", Kind: "struct", Text: text, Tokens: big.Tokens}
+	chunks := []CodeChunk{big, small, typ}
+
+	assert.Equal(t, chunks, SplitChunks(chunks, ChunkLimits{}), "no limit")
+
+	split := SplitChunks(chunks, ChunkLimits{MaxTokens: 14})
+	require.Len(t, split, 5)
+	assert.Equal(t, small, split[3])
+	assert.Equal(t, typ, split[4], "only function bodies are split")
+	parts := split[:3]
+	assert.Equal(t, "fn big() {\n    let a = compute(1, 2);", parts[0].Text)
+	assert.Equal(t, "    let b = vec![\n        a,\n        a + 1,\n    ];", parts[1].Text, "a statement is not broken")
+	assert.Equal(t, "    println!(\"{:?}\", b);\n}", parts[2].Text)
+	for i, p := range parts {
+		assert.Equal(t, i+1, p.Part)
+		assert.Equal(t, 3, p.Parts)
+		assert.Equal(t, "big", p.Path)
+		assert.Equal(t, "fn big()", p.Signature)
+		assert.Equal(t, int64(7), p.SymbolID)
+		assert.Equal(t, "outer", p.Parent)
+		assert.Equal(t, []string{"compute"}, p.Calls)
+	}
+	assert.Equal(t, "big", parts[0].ID)
+	assert.Equal(t, "/// Big.", parts[0].Doc)
+	assert.NotEqual(t, parts[1].ID, parts[2].ID)
+	assert.Empty(t, parts[1].Doc)
+	assert.Equal(t, []int{10, 11, 12, 15, 16, 17}, []int{parts[0].StartLine, parts[0].EndLine, parts[1].StartLine, parts[1].EndLine, parts[2].StartLine, parts[2].EndLine})
+
+	overlapped := SplitChunks([]CodeChunk{big}, ChunkLimits{MaxTokens: 14, Overlap: 1})
+	require.Len(t, overlapped, 3)
+	assert.Equal(t, 11, overlapped[1].StartLine, "repeats the last line of the part before")
+	assert.Equal(t, "    ];\n    println!(\"{:?}\", b);\n}", overlapped[2].Text)
+	assert.Equal(t, parts[1].ID, overlapped[1].ID, "IDs depend on the part number only")
+}
@@ -0,0 +1,139 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// maxClonePostings bounds how many functions may share a statement hash for
+// that hash to propose candidate pairs. Ubiquitous statements such as
+// "return nil" would otherwise pair up every function in the codebase.
+const maxClonePostings = 64
+
+// ClonePair is a pair of functions with structurally similar bodies.
+type ClonePair struct {
+	A, B       SymbolResult
+	Similarity float64 // 2*common / (len(A)+len(B)); 1 for identical bodies
+	Common     int     // statements in the longest common statement subsequence
+	Statements [2]int  // statement counts of A and B
+}
+
+// Duplicates reports pairs of functions and methods whose bodies are
+// structural clones, most similar first. Bodies are compared as sequences of
+// statement hashes computed at index time over normalized syntax trees
+// (identifiers, literals, and comments ignored), so copies with renamed
+// variables or changed constants still match. Similarity is the share of
+// statements in their longest common subsequence; pairs below minSimilarity
+// (0..1) and bodies with fewer than minStatements statements are skipped.
+func (q *QueryBuilder) Duplicates(minSimilarity float64, minStatements int) ([]ClonePair, error) {
+	if minSimilarity < 0 || minSimilarity > 1 {
+		return nil, fmt.Errorf("duplicates: minSimilarity must be between 0 and 1, got %g", minSimilarity)
+	}
+	fps, err := q.store.FunctionFingerprints(max(minStatements, 1))
+	if err != nil {
+		return nil, fmt.Errorf("duplicates: %w", err)
+	}
+
+	// Candidate pairs share at least one statement hash that is not ubiquitous.
+	postings := map[uint64][]int{}
+	for i, fp := range fps {
+		seen := map[uint64]bool{}
+		for _, h := range fp.Statements {
+			if !seen[h] {
+				seen[h] = true
+				postings[h] = append(postings[h], i)
+			}
+		}
+	}
+	type pairKey struct{ a, b int }
+	candidates := map[pairKey]bool{}
+	for _, list := range postings {
+		if len(list) < 2 || len(list) > maxClonePostings {
+			continue
+		}
+		for x := 0; x < len(list); x++ {
+			for y := x + 1; y < len(list); y++ {
+				candidates[pairKey{list[x], list[y]}] = true
+			}
+		}
+	}
+
+	type match struct {
+		a, b   *store.FunctionFingerprint
+		common int
+		sim    float64
+	}
+	var matches []match
+	for pk := range candidates {
+		a, b := fps[pk.a], fps[pk.b]
+		total := len(a.Statements) + len(b.Statements)
+		// The common subsequence is at most the shorter body.
+		if 2*float64(min(len(a.Statements), len(b.Statements)))/float64(total) < minSimilarity {
+			continue
+		}
+		common := lcsLength(a.Statements, b.Statements)
+		sim := 2 * float64(common) / float64(total)
+		if sim >= minSimilarity && common > 0 {
+			matches = append(matches, match{a: a, b: b, common: common, sim: sim})
+		}
+	}
+
+	ids := make([]int64, 0, 2*len(matches))
+	for _, m := range matches {
+		ids = append(ids, m.a.SymbolID, m.b.SymbolID)
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("duplicates: load symbols: %w", err)
+	}
+
+	pairs := make([]ClonePair, 0, len(matches))
+	for _, m := range matches {
+		a, b := syms[m.a.SymbolID], syms[m.b.SymbolID]
+		if a == nil || b == nil {
+			continue
+		}
+		pairs = append(pairs, ClonePair{
+			A:          *a,
+			B:          *b,
+			Similarity: m.sim,
+			Common:     m.common,
+			Statements: [2]int{len(m.a.Statements), len(m.b.Statements)},
+		})
+	}
+	sort.Slice(pairs, func(i, j int) bool {
+		if pairs[i].Similarity != pairs[j].Similarity {
+			return pairs[i].Similarity > pairs[j].Similarity
+		}
+		if pairs[i].Common != pairs[j].Common {
+			return pairs[i].Common > pairs[j].Common
+		}
+		if pairs[i].A.ID != pairs[j].A.ID {
+			return pairs[i].A.ID < pairs[j].A.ID
+		}
+		return pairs[i].B.ID < pairs[j].B.ID
+	})
+	return pairs, nil
+}
+
+// lcsLength returns the length of the longest common subsequence of a and b.
+func lcsLength(a, b []uint64) int {
+	prev := make([]int, len(b)+1)
+	cur := make([]int, len(b)+1)
+	for i := 1; i <= len(a); i++ {
+		for j := 1; j <= len(b); j++ {
+			switch {
+			case a[i-1] == b[j-1]:
+				cur[j] = prev[j-1] + 1
+			case prev[j] >= cur[j-1]:
+				cur[j] = prev[j]
+			default:
+				cur[j] = cur[j-1]
+			}
+		}
+		prev, cur = cur, prev
+	}
+	return prev[len(b)]
+}
@@ -0,0 +1,93 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestLCSLength(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, 0, lcsLength(nil, []uint64{1}))
+	assert.Equal(t, 3, lcsLength([]uint64{1, 2, 3}, []uint64{1, 2, 3}))
+	assert.Equal(t, 2, lcsLength([]uint64{1, 2, 3, 4}, []uint64{2, 9, 4}))
+}
+
+func TestDuplicates_FindsClonesAcrossFiles(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel))
+		ctx := context.Background()
+		dir := t.TempDir()
+
+		a := writeGoFile(t, dir, "a.go", `package main
+
+func Sum(xs []int) int {
+	total := 0
+	for _, x := range xs {
+		total += x
+	}
+	return total
+}
+
+func Small() int {
+	return 1
+}
+`)
+		b := writeGoFile(t, dir, "b.go", `package main
+
+func Add(values []int) int {
+	acc := 1
+	for _, v := range values {
+		acc += v
+	}
+	return acc
+}
+
+func AddLogged(values []int) int {
+	acc := 1
+	for _, v := range values {
+		acc += v
+	}
+	println(acc)
+	return acc
+}
+
+func Small2() int {
+	return 2
+}
+`)
+		require.NoError(t, e.IndexFiles(ctx, []string{a, b}))
+		require.NoError(t, e.Resolve(ctx))
+
+		pairs, err := e.Query().Duplicates(0.8, 3)
+		require.NoError(t, err)
+		require.NotEmpty(t, pairs)
+
+		assert.InDelta(t, 1.0, pairs[0].Similarity, 1e-9, "exact structural clone first")
+		assert.ElementsMatch(t, []string{"Sum", "Add"}, []string{pairs[0].A.Name, pairs[0].B.Name})
+		assert.Equal(t, 3, pairs[0].Common)
+
+		var near *ClonePair
+		for i := range pairs {
+			if pairs[i].A.Name == "AddLogged" || pairs[i].B.Name == "AddLogged" {
+				near = &pairs[i]
+				break
+			}
+		}
+		require.NotNil(t, near, "near-duplicate with an extra statement")
+		assert.InDelta(t, 6.0/7.0, near.Similarity, 1e-9)
+
+		for _, p := range pairs {
+			assert.NotContains(t, []string{"Small", "Small2"}, p.A.Name, "bodies below min statements are skipped")
+		}
+
+		pairs, err = e.Query().Duplicates(0.95, 3)
+		require.NoError(t, err)
+		require.Len(t, pairs, 1)
+
+		_, err = e.Query().Duplicates(1.5, 3)
+		assert.Error(t, err)
+	}
+}
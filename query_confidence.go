@@ -0,0 +1,76 @@
+package canopy
+
+import "fmt"
+
+// Confidence levels of resolved references and call edges, from most to
+// least precise. They bucket the numeric confidence the resolve scripts
+// record with each resolution.
+const (
+	// ConfidenceExact: bound through scopes to a declaration the resolver can
+	// see (confidence 1.0).
+	ConfidenceExact = "exact"
+	// ConfidenceProbable: bound through an import, a qualifier, or a receiver
+	// type whose target could not be checked as strictly, such as stubs of
+	// dependency crates (confidence 0.9).
+	ConfidenceProbable = "probable"
+	// ConfidenceHeuristic: matched by name only, such as a method call on a
+	// value of unknown type or through dynamic dispatch (below 0.9).
+	ConfidenceHeuristic = "heuristic"
+)
+
+// confidenceFloors are the lowest numeric confidences of each level.
+var confidenceFloors = map[string]float64{
+	ConfidenceExact:     1.0,
+	ConfidenceProbable:  0.9,
+	ConfidenceHeuristic: 0,
+}
+
+// ConfidenceLevel returns the level (one of the Confidence constants) of a
+// numeric resolution confidence.
+func ConfidenceLevel(confidence float64) string {
+	switch {
+	case confidence >= confidenceFloors[ConfidenceExact]:
+		return ConfidenceExact
+	case confidence >= confidenceFloors[ConfidenceProbable]:
+		return ConfidenceProbable
+	default:
+		return ConfidenceHeuristic
+	}
+}
+
+// WithMinConfidence returns a copy of q whose reference and call graph
+// queries (DefinitionAt, the References* and Each* reference queries,
+// Callers, Callees, and the transitive call graphs) skip resolutions below
+// the given level. "" keeps every resolution.
+func (q *QueryBuilder) WithMinConfidence(level string) (*QueryBuilder, error) {
+	floor, ok := confidenceFloors[level]
+	if level != "" && !ok {
+		return nil, fmt.Errorf("unknown confidence level %q (want %s, %s, or %s)",
+			level, ConfidenceExact, ConfidenceProbable, ConfidenceHeuristic)
+	}
+	c := *q
+	c.minConfidence = floor
+	return &c, nil
+}
+
+// filterCallEdges returns the edges at or above q's minimum confidence,
+// without those to or from test code when q excludes it.
+func (q *QueryBuilder) filterCallEdges(edges []*CallEdge) ([]*CallEdge, error) {
+	if q.minConfidence == 0 && !q.excludeTests {
+		return edges, nil
+	}
+	var tests map[int64]bool
+	if q.excludeTests {
+		var err error
+		if tests, err = q.testCodeSymbolIDs(); err != nil {
+			return nil, err
+		}
+	}
+	var kept []*CallEdge
+	for _, e := range edges {
+		if e.Confidence >= q.minConfidence && !tests[e.CallerSymbolID] && !tests[e.CalleeSymbolID] {
+			kept = append(kept, e)
+		}
+	}
+	return kept, nil
+}
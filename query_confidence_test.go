@@ -0,0 +1,106 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestConfidenceLevel(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, ConfidenceExact, ConfidenceLevel(1.0))
+	assert.Equal(t, ConfidenceProbable, ConfidenceLevel(0.9))
+	assert.Equal(t, ConfidenceHeuristic, ConfidenceLevel(0.8))
+	assert.Equal(t, ConfidenceHeuristic, ConfidenceLevel(0.7))
+}
+
+func TestWithMinConfidence_UnknownLevel(t *testing.T) {
+	t.Parallel()
+	q, _ := newTestQueryBuilder(t)
+
+	_, err := q.WithMinConfidence("certain")
+	assert.ErrorContains(t, err, `unknown confidence level "certain"`)
+}
+
+func TestWithMinConfidence_FiltersReferences(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/test.go", "go")
+	target := insertSymbol(t, s, &fID, "Run", "method", "public", nil)
+	for line, confidence := range []float64{1.0, 0.9, 0.8} {
+		refID, err := s.InsertReference(&store.Reference{
+			FileID: fID, Name: "Run", Context: RefKindCall,
+			StartLine: line, StartCol: 0, EndLine: line, EndCol: 3,
+		})
+		require.NoError(t, err)
+		_, err = s.InsertResolvedReference(&store.ResolvedReference{
+			ReferenceID: refID, TargetSymbolID: target, Confidence: confidence, ResolutionKind: "direct",
+		})
+		require.NoError(t, err)
+	}
+
+	all, err := q.ReferencesToPaged(target, ReferenceFilter{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, all.Items, 3)
+	assert.Equal(t, ConfidenceExact, all.Items[0].Confidence)
+	assert.Equal(t, ConfidenceProbable, all.Items[1].Confidence)
+	assert.Equal(t, ConfidenceHeuristic, all.Items[2].Confidence)
+
+	probable, err := q.WithMinConfidence(ConfidenceProbable)
+	require.NoError(t, err)
+	result, err := probable.ReferencesToPaged(target, ReferenceFilter{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 2, result.TotalCount)
+	locs, err := probable.ReferencesTo(target)
+	require.NoError(t, err)
+	assert.Len(t, locs, 2)
+
+	exact, err := q.WithMinConfidence(ConfidenceExact)
+	require.NoError(t, err)
+	defs, err := exact.DefinitionAt("/test.go", 2, 1)
+	require.NoError(t, err)
+	assert.Empty(t, defs, "the name-only match on line 2 is heuristic")
+	defs, err = exact.DefinitionAt("/test.go", 0, 1)
+	require.NoError(t, err)
+	assert.Len(t, defs, 1)
+}
+
+func TestWithMinConfidence_FiltersCallEdges(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/test.go", "go")
+	a := insertSymbol(t, s, &fID, "a", "function", "public", nil)
+	b := insertSymbol(t, s, &fID, "b", "function", "public", nil)
+	c := insertSymbol(t, s, &fID, "c", "method", "public", nil)
+	_, err := s.InsertCallEdge(&store.CallEdge{CallerSymbolID: a, CalleeSymbolID: b, FileID: &fID, Line: 1, Confidence: 1.0})
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&store.CallEdge{CallerSymbolID: b, CalleeSymbolID: c, FileID: &fID, Line: 2, Confidence: 0.8})
+	require.NoError(t, err)
+
+	graph, err := q.TransitiveCallees(a, 5)
+	require.NoError(t, err)
+	require.Len(t, graph.Edges, 2)
+	confidences := map[int64]string{}
+	for _, e := range graph.Edges {
+		confidences[e.CalleeID] = e.Confidence
+	}
+	assert.Equal(t, ConfidenceExact, confidences[b])
+	assert.Equal(t, ConfidenceHeuristic, confidences[c])
+
+	probable, err := q.WithMinConfidence(ConfidenceProbable)
+	require.NoError(t, err)
+	graph, err = probable.TransitiveCallees(a, 5)
+	require.NoError(t, err)
+	require.Len(t, graph.Edges, 1)
+	assert.Equal(t, b, graph.Edges[0].CalleeID)
+
+	callers, err := probable.Callers(c)
+	require.NoError(t, err)
+	assert.Empty(t, callers)
+	callees, err := probable.Callees(a)
+	require.NoError(t, err)
+	assert.Len(t, callees, 1)
+}
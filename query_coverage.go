@@ -0,0 +1,100 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// CoveredSymbol is a function or method with its test coverage.
+type CoveredSymbol struct {
+	Symbol   SymbolResult
+	Coverage SymbolCoverage
+	Percent  float64 // covered lines as a percentage of instrumented lines
+	Callers  int     // distinct functions that call the symbol
+}
+
+// CoverageFilter narrows Coverage results.
+type CoverageFilter struct {
+	MaxPercent *float64 // only symbols with at most this percentage covered
+	MinCallers *int     // only symbols with at least this many distinct callers
+}
+
+// Coverage returns the functions and methods with coverage from the report
+// loaded by IngestCoverage, least covered first, then by most callers.
+// Public functions at most 20% covered with at least 10 callers are
+// SymbolFilter{Kinds: []string{"function"}, Visibility: &public} with
+// CoverageFilter{MaxPercent: &twenty, MinCallers: &ten}.
+// RefCountMin/RefCountMax in filter are ignored.
+func (q *QueryBuilder) Coverage(filter SymbolFilter, cov CoverageFilter, page Pagination) (*PagedResult[CoveredSymbol], error) {
+	page = page.normalize()
+
+	where, args := q.symbolFilterClauses(filter)
+	if cov.MaxPercent != nil {
+		where = append(where, "c.covered_lines * 100.0 <= c.lines * ?")
+		args = append(args, *cov.MaxPercent)
+	}
+	const callers = "(SELECT COUNT(DISTINCT cg.caller_symbol_id) FROM call_graph cg WHERE cg.callee_symbol_id = s.id)"
+	if cov.MinCallers != nil {
+		where = append(where, callers+" >= ?")
+		args = append(args, *cov.MinCallers)
+	}
+	whereClause := ""
+	if len(where) > 0 {
+		whereClause = "WHERE " + strings.Join(where, " AND ")
+	}
+	from := `FROM symbol_coverage c
+		 JOIN symbols s ON s.id = c.symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id `
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+from+whereClause, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("coverage: count: %w", err)
+	}
+
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, COALESCE(f.path, '') AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr WHERE rr.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr JOIN references_ r ON r.id = rr.reference_id WHERE rr.target_symbol_id = s.id AND r.file_id != s.file_id) AS external_ref_count,
+			c.lines, c.covered_lines, %s AS caller_count
+		 %s%s
+		 ORDER BY c.covered_lines * 1.0 / c.lines, caller_count DESC, f.path, s.start_line, s.start_col, s.id
+		 LIMIT ? OFFSET ?`,
+		prefixSymbolCols("s"), callers, from, whereClause,
+	)
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+
+	rows, err := q.store.DB().Query(dataSQL, dataArgs...)
+	if err != nil {
+		return nil, fmt.Errorf("coverage: query: %w", err)
+	}
+	defer rows.Close()
+
+	items := []CoveredSymbol{}
+	for rows.Next() {
+		var cs CoveredSymbol
+		var mods string
+		sr := &cs.Symbol
+		if err := rows.Scan(
+			&sr.ID, &sr.FileID, &sr.Name, &sr.Kind, &sr.Visibility, &mods,
+			&sr.SignatureHash, &sr.StartLine, &sr.StartCol, &sr.EndLine, &sr.EndCol,
+			&sr.ParentSymbolID, &sr.StartByte, &sr.EndByte, &sr.UTF16StartCol, &sr.UTF16EndCol,
+			&sr.PageRank, &sr.Betweenness, &sr.Subkind,
+			&sr.FilePath, &sr.RefCount, &sr.ExternalRefCount,
+			&cs.Coverage.Lines, &cs.Coverage.CoveredLines, &cs.Callers,
+		); err != nil {
+			return nil, fmt.Errorf("coverage: scan: %w", err)
+		}
+		sr.Modifiers = store.UnmarshalModifiers(mods)
+		sr.InternalRefCount = sr.RefCount - sr.ExternalRefCount
+		cs.Coverage.SymbolID = sr.ID
+		cs.Percent = 100 * float64(cs.Coverage.CoveredLines) / float64(cs.Coverage.Lines)
+		items = append(items, cs)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("coverage: rows: %w", err)
+	}
+
+	return &PagedResult[CoveredSymbol]{Items: items, TotalCount: totalCount}, nil
+}
@@ -0,0 +1,207 @@
+package canopy
+
+import (
+	"fmt"
+	"hash/fnv"
+	"sort"
+	"strings"
+)
+
+// CrateDuplicate is a pair of same-named Rust items in different crates of
+// the workspace whose signatures are nearly identical, usually a copy that
+// drifted. Each is a candidate for moving into a shared crate.
+type CrateDuplicate struct {
+	A, B                   SymbolResult
+	CrateA, CrateB         string
+	SignatureA, SignatureB string  // "fn parse(s: &str) -> u16", "struct Config { name: String, port: u16 }"
+	Similarity             float64 // 2*common / (len(A)+len(B)) over signature elements; 1 for identical signatures
+}
+
+// crateDuplicateKinds are the kinds of items compared across crates.
+var crateDuplicateKinds = []string{"function", "struct", "enum", "trait", "type_alias"}
+
+// CrateDuplicates reports pairs of Rust functions, structs, enums, traits,
+// and type aliases that share a name and kind, are declared in different
+// Cargo packages, and have signatures with at least minSimilarity (0..1)
+// in common, most similar first. A function's signature is its parameter
+// types in order and its return type, so renamed parameters still match; a
+// type's is its fields, variants, or trait items with their types, in any
+// order. Only module-level items are compared: methods, associated
+// functions, and main are expected to repeat across crates.
+func (q *QueryBuilder) CrateDuplicates(minSimilarity float64) ([]CrateDuplicate, error) {
+	if minSimilarity < 0 || minSimilarity > 1 {
+		return nil, fmt.Errorf("crate duplicates: minSimilarity must be between 0 and 1, got %g", minSimilarity)
+	}
+	query := `SELECT s.id, s.kind, s.name, f.path FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id
+		 WHERE f.language = 'rust' AND s.kind IN ('` + strings.Join(crateDuplicateKinds, "', '") + `')
+		 AND s.subkind != 'associated_function' AND s.name != 'main'
+		 AND (p.id IS NULL OR p.kind = 'module')`
+	if q.excludeTests {
+		query += ` AND NOT ` + testCodeSQL("s", "f")
+	}
+	rows, err := q.store.DB().Query(query + ` ORDER BY f.path, s.id`)
+	if err != nil {
+		return nil, fmt.Errorf("crate duplicates: %w", err)
+	}
+	type item struct {
+		id        int64
+		kind      string
+		name      string
+		crate     string
+		pkg       string
+		signature string
+		elements  []uint64
+	}
+	groups := map[string][]*item{}
+	var keys []string
+	packages := map[string]cargoPackage{}
+	for rows.Next() {
+		var it item
+		var path string
+		if err := rows.Scan(&it.id, &it.kind, &it.name, &path); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("crate duplicates: scan: %w", err)
+		}
+		m, ok := q.rustModuleOf(path, packages)
+		if !ok {
+			continue
+		}
+		it.crate, it.pkg = m.crate, m.dir
+		key := it.kind + " " + it.name
+		if groups[key] == nil {
+			keys = append(keys, key)
+		}
+		groups[key] = append(groups[key], &it)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("crate duplicates: %w", err)
+	}
+
+	var dups []CrateDuplicate
+	for _, key := range keys {
+		group := groups[key]
+		crossCrate := false
+		for _, it := range group[1:] {
+			crossCrate = crossCrate || it.pkg != group[0].pkg
+		}
+		if !crossCrate {
+			continue
+		}
+		for _, it := range group {
+			if it.signature, it.elements, err = q.itemSignature(it.id, it.kind, it.name); err != nil {
+				return nil, fmt.Errorf("crate duplicates: %w", err)
+			}
+		}
+		for x := 0; x < len(group); x++ {
+			for y := x + 1; y < len(group); y++ {
+				a, b := group[x], group[y]
+				if a.pkg == b.pkg {
+					continue
+				}
+				sim := 1.0
+				if total := len(a.elements) + len(b.elements); total > 0 {
+					sim = 2 * float64(lcsLength(a.elements, b.elements)) / float64(total)
+				}
+				if sim < minSimilarity {
+					continue
+				}
+				sa, err := q.symbolResultByID(a.id)
+				if err != nil {
+					return nil, fmt.Errorf("crate duplicates: %w", err)
+				}
+				sb, err := q.symbolResultByID(b.id)
+				if err != nil {
+					return nil, fmt.Errorf("crate duplicates: %w", err)
+				}
+				if sa == nil || sb == nil {
+					continue
+				}
+				dups = append(dups, CrateDuplicate{
+					A:          *sa,
+					B:          *sb,
+					CrateA:     a.crate,
+					CrateB:     b.crate,
+					SignatureA: a.signature,
+					SignatureB: b.signature,
+					Similarity: sim,
+				})
+			}
+		}
+	}
+	sort.SliceStable(dups, func(i, j int) bool {
+		if dups[i].Similarity != dups[j].Similarity {
+			return dups[i].Similarity > dups[j].Similarity
+		}
+		return dups[i].A.Name < dups[j].A.Name
+	})
+	return dups, nil
+}
+
+// itemSignature returns the signature of the item symbolID for display and
+// as hashed elements to compare. A function's elements are its parameter
+// types and return type in order; a type's are its members, sorted.
+func (q *QueryBuilder) itemSignature(symbolID int64, kind, name string) (string, []uint64, error) {
+	hash := func(s string) uint64 {
+		h := fnv.New64a()
+		h.Write([]byte(s))
+		return h.Sum64()
+	}
+	if kind == "function" {
+		params, err := q.store.FunctionParams(symbolID)
+		if err != nil {
+			return "", nil, err
+		}
+		var args, returns []string
+		var elements []uint64
+		for _, p := range params {
+			switch {
+			case p.IsReceiver:
+			case p.IsReturn:
+				returns = append(returns, p.TypeExpr)
+				elements = append(elements, hash("-> "+p.TypeExpr))
+			default:
+				args = append(args, p.Name+": "+p.TypeExpr)
+				elements = append(elements, hash(p.TypeExpr))
+			}
+		}
+		sig := "fn " + name + "(" + strings.Join(args, ", ") + ")"
+		if len(returns) > 0 {
+			sig += " -> " + strings.Join(returns, ", ")
+		}
+		return sig, elements, nil
+	}
+
+	members, err := q.store.TypeMembers(symbolID)
+	if err != nil {
+		return "", nil, err
+	}
+	var shown, compared []string
+	for _, m := range members {
+		if m.Kind == "wraps" {
+			continue // repeats the single field of a newtype
+		}
+		member := m.Name
+		if m.TypeExpr != "" {
+			member += ": " + m.TypeExpr
+		}
+		shown = append(shown, member)
+		compared = append(compared, m.Kind+" "+member)
+	}
+	sort.Strings(compared)
+	elements := make([]uint64, len(compared))
+	for i, c := range compared {
+		elements[i] = hash(c)
+	}
+	label := kind
+	if kind == "type_alias" {
+		label = "type"
+	}
+	sig := label + " " + name
+	if len(shown) > 0 {
+		sig += " { " + strings.Join(shown, ", ") + " }"
+	}
+	return sig, elements, nil
+}
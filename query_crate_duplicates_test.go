@@ -0,0 +1,87 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCrateDuplicates(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("alpha/Cargo.toml", "[package]\nname = \"alpha\"\nversion = \"0.1.0\"\n")
+	write("alpha/src/lib.rs", `pub struct Config {
+    pub name: String,
+    pub port: u16,
+}
+
+pub fn parse_port(s: &str) -> Option<u16> {
+    s.parse().ok()
+}
+
+pub fn render(x: u8) {}
+
+pub struct Local;
+
+impl Local {
+    pub fn new() -> Self { Local }
+}
+`)
+	write("beta/Cargo.toml", "[package]\nname = \"beta\"\nversion = \"0.1.0\"\n")
+	write("beta/src/lib.rs", `pub struct Config {
+    pub name: String,
+    pub port: u16,
+    pub verbose: bool,
+}
+
+pub fn parse_port(input: &str) -> Option<u16> {
+    input.trim().parse().ok()
+}
+
+pub fn render(x: String, y: Vec<u8>) -> bool { true }
+
+pub struct Other;
+
+impl Other {
+    pub fn new() -> Self { Other }
+}
+`)
+	write("beta/src/main.rs", "fn main() {}\n")
+	write("alpha/src/main.rs", "fn main() {}\n")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	dups, err := q.CrateDuplicates(0.5)
+	require.NoError(t, err)
+	type pair struct {
+		name, crateA, crateB, sigA, sigB string
+		similarity                       float64
+	}
+	var got []pair
+	for _, d := range dups {
+		got = append(got, pair{d.A.Name, d.CrateA, d.CrateB, d.SignatureA, d.SignatureB, d.Similarity})
+	}
+	assert.Equal(t, []pair{
+		{"parse_port", "alpha", "beta", "fn parse_port(s: &str) -> Option<u16>", "fn parse_port(input: &str) -> Option<u16>", 1},
+		{"Config", "alpha", "beta", "struct Config { name: String, port: u16 }", "struct Config { name: String, port: u16, verbose: bool }", 0.8},
+	}, got, "render differs too much; main and associated functions are expected to repeat")
+
+	all, err := q.CrateDuplicates(0)
+	require.NoError(t, err)
+	assert.Len(t, all, 3)
+
+	_, err = q.CrateDuplicates(1.5)
+	assert.Error(t, err)
+}
@@ -0,0 +1,129 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// DeprecatedUsage is a single resolved reference to a symbol that is marked
+// deprecated (#[deprecated], @Deprecated, @deprecated).
+type DeprecatedUsage struct {
+	Symbol   SymbolResult // the deprecated symbol being used
+	Note     string       // deprecation note, if one was given (empty otherwise)
+	Location Location     // the referencing site
+	Context  string       // reference context ("call", "type_annotation", ...)
+}
+
+// deprecatedAnnotationNames lists the annotation names that mark a symbol as
+// deprecated across the supported languages.
+var deprecatedAnnotationNames = []string{"deprecated", "Deprecated"}
+
+// deprecationNoteRe extracts note = "..." from #[deprecated(since = "1.2", note = "...")].
+var deprecationNoteRe = regexp.MustCompile(`note\s*=\s*"((?:[^"\\]|\\.)*)"`)
+
+// deprecationNote extracts a human-readable note from raw annotation arguments.
+// Handles Rust's note = "..." form, bare string arguments ("..." or ("...")),
+// and returns "" when no note is present.
+func deprecationNote(args string) string {
+	if m := deprecationNoteRe.FindStringSubmatch(args); m != nil {
+		return m[1]
+	}
+	s := strings.TrimSpace(args)
+	s = strings.TrimPrefix(s, "(")
+	s = strings.TrimSuffix(s, ")")
+	s = strings.TrimSpace(s)
+	if len(s) >= 2 && (s[0] == '"' || s[0] == '\'') && s[len(s)-1] == s[0] {
+		return s[1 : len(s)-1]
+	}
+	return ""
+}
+
+// DeprecatedUsages returns every resolved reference to a symbol marked
+// deprecated, ordered by file and position. References from inside the
+// deprecated symbol's own definition are included; filter by Location if
+// that is not wanted. Without test code (see WithTestCode), references
+// from test code are left out.
+func (q *QueryBuilder) DeprecatedUsages(page Pagination) (*PagedResult[DeprecatedUsage], error) {
+	page = page.normalize()
+
+	placeholders := strings.Repeat("?,", len(deprecatedAnnotationNames)-1) + "?"
+	args := make([]any, len(deprecatedAnnotationNames))
+	for i, n := range deprecatedAnnotationNames {
+		args[i] = n
+	}
+
+	fromClause := fmt.Sprintf(
+		`FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 JOIN files rf ON rf.id = r.file_id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id
+		 JOIN annotations a ON a.id = (
+			SELECT MIN(a2.id) FROM annotations a2
+			WHERE a2.target_symbol_id = s.id AND a2.name IN (%s))`,
+		placeholders,
+	)
+	if clause := q.testCodeClause("r", "rf"); clause != "" {
+		fromClause += " WHERE " + clause
+	}
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+fromClause, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("deprecated usages: count: %w", err)
+	}
+
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, COALESCE(f.path, '') AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr2 WHERE rr2.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr2 JOIN references_ r2 ON r2.id = rr2.reference_id WHERE rr2.target_symbol_id = s.id AND r2.file_id != s.file_id) AS external_ref_count,
+			COALESCE(a.arguments, ''), rf.path, r.start_line, r.start_col, r.end_line, r.end_col,
+			r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col, COALESCE(r.context, '')
+		 %s
+		 ORDER BY rf.path, r.start_line, r.start_col
+		 LIMIT ? OFFSET ?`,
+		prefixSymbolCols("s"), fromClause,
+	)
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+
+	rows, err := q.store.DB().Query(dataSQL, dataArgs...)
+	if err != nil {
+		return nil, fmt.Errorf("deprecated usages: query: %w", err)
+	}
+	defer rows.Close()
+
+	var items []DeprecatedUsage
+	for rows.Next() {
+		var u DeprecatedUsage
+		var mods, annArgs string
+		err := rows.Scan(
+			&u.Symbol.ID, &u.Symbol.FileID, &u.Symbol.Name, &u.Symbol.Kind,
+			&u.Symbol.Visibility, &mods, &u.Symbol.SignatureHash,
+			&u.Symbol.StartLine, &u.Symbol.StartCol, &u.Symbol.EndLine, &u.Symbol.EndCol,
+			&u.Symbol.ParentSymbolID, &u.Symbol.StartByte, &u.Symbol.EndByte, &u.Symbol.UTF16StartCol, &u.Symbol.UTF16EndCol,
+			&u.Symbol.PageRank, &u.Symbol.Betweenness, &u.Symbol.Subkind,
+			&u.Symbol.FilePath, &u.Symbol.RefCount, &u.Symbol.ExternalRefCount,
+			&annArgs, &u.Location.File,
+			&u.Location.StartLine, &u.Location.StartCol, &u.Location.EndLine, &u.Location.EndCol,
+			&u.Location.StartByte, &u.Location.EndByte, &u.Location.UTF16StartCol, &u.Location.UTF16EndCol,
+			&u.Context,
+		)
+		if err != nil {
+			return nil, fmt.Errorf("deprecated usages: scan: %w", err)
+		}
+		u.Symbol.Modifiers = store.UnmarshalModifiers(mods)
+		u.Symbol.InternalRefCount = u.Symbol.RefCount - u.Symbol.ExternalRefCount
+		u.Note = deprecationNote(annArgs)
+		items = append(items, u)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("deprecated usages: rows: %w", err)
+	}
+	if items == nil {
+		items = []DeprecatedUsage{}
+	}
+
+	return &PagedResult[DeprecatedUsage]{Items: items, TotalCount: totalCount}, nil
+}
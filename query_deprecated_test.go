@@ -0,0 +1,72 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDeprecationNote(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		args string
+		want string
+	}{
+		{`(note = "use run_v2")`, "use run_v2"},
+		{`(since = "1.2.0", note = "use \"run_v2\"")`, `use \"run_v2\"`},
+		{`"use run_v2"`, "use run_v2"},
+		{`("use run_v2")`, "use run_v2"},
+		{`'old'`, "old"},
+		{``, ""},
+		{`(since = "1.2.0")`, ""},
+	}
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, deprecationNote(tt.args), "args: %s", tt.args)
+	}
+}
+
+func TestDeprecatedUsages_ReturnsReferencesWithNote(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	libID := insertFile(t, s, "/lib.rs", "rust")
+	mainID := insertFile(t, s, "/main.rs", "rust")
+
+	oldID := insertSymbol(t, s, &libID, "old_api", "function", "public", nil)
+	newID := insertSymbol(t, s, &libID, "new_api", "function", "public", nil)
+	_, err := s.InsertAnnotation(&store.Annotation{
+		TargetSymbolID: oldID, Name: "deprecated", Arguments: `(note = "use new_api")`, FileID: &libID,
+	})
+	require.NoError(t, err)
+
+	insertResolvedRef(t, s, mainID, oldID)
+	insertResolvedRef(t, s, mainID, oldID)
+	insertResolvedRef(t, s, mainID, newID)
+
+	result, err := q.DeprecatedUsages(Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 2, result.TotalCount)
+	require.Len(t, result.Items, 2)
+	for _, u := range result.Items {
+		assert.Equal(t, "old_api", u.Symbol.Name)
+		assert.Equal(t, "use new_api", u.Note)
+		assert.Equal(t, "/main.rs", u.Location.File)
+		assert.Equal(t, "call", u.Context)
+	}
+}
+
+func TestDeprecatedUsages_NoDeprecatedSymbols(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+	symID := insertSymbol(t, s, &fID, "api", "function", "public", nil)
+	insertResolvedRef(t, s, fID, symID)
+
+	result, err := q.DeprecatedUsages(Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 0, result.TotalCount)
+	assert.NotNil(t, result.Items)
+	assert.Empty(t, result.Items)
+}
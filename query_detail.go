@@ -13,11 +13,13 @@ type SymbolDetail struct {
 	Parameters  []*FunctionParam // function/method params, receiver, returns (empty for non-functions)
 	Members     []*TypeMember    // struct fields, class methods, interface contracts (empty for non-types)
 	TypeParams  []*TypeParam     // generic type parameters with constraints (empty if non-generic)
+	TypeBounds  []*TypeBound     // individual generic bounds, inline and from where-clauses (empty if none)
 	Annotations []*Annotation    // decorators, annotations, attributes (empty if none)
 }
 
 // SymbolDetail returns a combined response with the symbol and all its
-// structural metadata (parameters, members, type parameters, annotations).
+// structural metadata (parameters, members, type parameters and bounds,
+// annotations).
 // Returns nil with no error if the symbol ID does not exist.
 func (q *QueryBuilder) SymbolDetail(symbolID int64) (*SymbolDetail, error) {
 	sr, err := q.symbolResultByID(symbolID)
@@ -43,6 +45,11 @@ func (q *QueryBuilder) SymbolDetail(symbolID int64) (*SymbolDetail, error) {
 		return nil, fmt.Errorf("symbol detail: type params: %w", err)
 	}
 
+	typeBounds, err := q.store.TypeBounds(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("symbol detail: type bounds: %w", err)
+	}
+
 	annotations, err := q.store.AnnotationsByTarget(symbolID)
 	if err != nil {
 		return nil, fmt.Errorf("symbol detail: annotations: %w", err)
@@ -57,6 +64,9 @@ func (q *QueryBuilder) SymbolDetail(symbolID int64) (*SymbolDetail, error) {
 	if typeParams == nil {
 		typeParams = []*TypeParam{}
 	}
+	if typeBounds == nil {
+		typeBounds = []*TypeBound{}
+	}
 	if annotations == nil {
 		annotations = []*Annotation{}
 	}
@@ -66,6 +76,7 @@ func (q *QueryBuilder) SymbolDetail(symbolID int64) (*SymbolDetail, error) {
 		Parameters:  params,
 		Members:     members,
 		TypeParams:  typeParams,
+		TypeBounds:  typeBounds,
 		Annotations: annotations,
 	}, nil
 }
@@ -74,7 +85,7 @@ func (q *QueryBuilder) SymbolDetail(symbolID int64) (*SymbolDetail, error) {
 // symbol at (file, line, col) and returns its SymbolDetail.
 // Line and col are 0-based. Returns nil with no error if no symbol exists.
 func (q *QueryBuilder) SymbolDetailAt(file string, line, col int) (*SymbolDetail, error) {
-	sym, err := q.SymbolAt(file, line, col)
+	sym, err := q.symbolAt(file, line, col)
 	if err != nil {
 		return nil, fmt.Errorf("symbol detail at: %w", err)
 	}
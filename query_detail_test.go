@@ -1,6 +1,9 @@
 package canopy
 
 import (
+	"context"
+	"os"
+	"path/filepath"
 	"testing"
 
 	"github.com/jward/canopy/internal/store"
@@ -173,6 +176,121 @@ func TestSymbolDetail_GenericTypeReturnsTypeParams(t *testing.T) {
 	assert.Empty(t, detail.Members)
 }
 
+func TestSymbolDetail_RustBoundsFromWhereClause(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`pub trait Processor {}
+
+pub fn transform<P: Processor + Clone>(p: &P) {}
+
+pub fn transform_where<P, T>(p: &P, t: T)
+where
+    P: Processor,
+    Vec<T>: std::fmt::Debug,
+{
+}
+
+func TestSymbolDetail_RustConstGenerics(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`pub struct Buf<'a, T, const N: usize> {
+    data: &'a [T; N],
+}
+
+pub fn first<const N: usize>(bytes: [u8; N]) -> u8 {
+    bytes[0]
+}
+
+pub fn plain<T: Clone>(t: T) {}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+
+	q := e.Query()
+	res, err := q.Symbols(SymbolFilter{Bounds: []string{"usize"}}, Sort{Field: SortByName}, Pagination{})
+	require.NoError(t, err)
+	var names []string
+	for _, sr := range res.Items {
+		names = append(names, sr.Name)
+	}
+	assert.Equal(t, []string{"Buf", "first"}, names, "const params are found by their type")
+
+	detail, err := q.SymbolDetail(res.Items[0].ID)
+	require.NoError(t, err)
+	require.NotNil(t, detail)
+	require.Len(t, detail.TypeParams, 3)
+	assert.Equal(t, "lifetime", detail.TypeParams[0].ParamKind)
+	assert.Equal(t, "T", detail.TypeParams[1].Name)
+	assert.Equal(t, "N", detail.TypeParams[2].Name)
+	assert.Equal(t, 2, detail.TypeParams[2].Ordinal)
+	assert.Equal(t, "const", detail.TypeParams[2].ParamKind)
+	assert.Equal(t, "usize", detail.TypeParams[2].Constraints)
+	require.Len(t, detail.Members, 1)
+	assert.Equal(t, "&'a [T; N]", detail.Members[0].TypeExpr)
+
+	detail, err = q.SymbolDetail(res.Items[1].ID)
+	require.NoError(t, err)
+	var params []string
+	for _, p := range detail.Parameters {
+		if !p.IsReturn {
+			params = append(params, p.TypeExpr)
+		}
+	}
+	assert.Equal(t, []string{"[u8; N]"}, params)
+}
+
+pub struct Wrapper<T> where T: Processor {
+    inner: T,
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+
+	q := e.Query()
+	res, err := q.Symbols(SymbolFilter{Bounds: []string{"Processor"}}, Sort{Field: SortByName}, Pagination{})
+	require.NoError(t, err)
+	var names []string
+	for _, sr := range res.Items {
+		names = append(names, sr.Name)
+	}
+	assert.Equal(t, []string{"Wrapper", "transform", "transform_where"}, names)
+
+	res, err = q.Symbols(SymbolFilter{Bounds: []string{"Debug"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, res.Items, 1)
+	detail, err := q.SymbolDetail(res.Items[0].ID)
+	require.NoError(t, err)
+	require.NotNil(t, detail)
+	assert.Equal(t, "transform_where", detail.Symbol.Name)
+
+	require.Len(t, detail.TypeParams, 2)
+	assert.Equal(t, "P", detail.TypeParams[0].Name)
+	assert.Equal(t, "Processor", detail.TypeParams[0].Constraints, "where-clause bounds merge into constraints")
+	assert.Equal(t, "", detail.TypeParams[1].Constraints)
+
+	require.Len(t, detail.TypeBounds, 2)
+	assert.Equal(t, "P", detail.TypeBounds[0].Subject)
+	assert.Equal(t, "Processor", detail.TypeBounds[0].Bound)
+	assert.True(t, detail.TypeBounds[0].InWhereClause)
+	assert.Equal(t, "Vec<T>", detail.TypeBounds[1].Subject)
+	assert.Equal(t, "std::fmt::Debug", detail.TypeBounds[1].Bound)
+	assert.Equal(t, "Debug", detail.TypeBounds[1].Name)
+
+	res, err = q.Symbols(SymbolFilter{Bounds: []string{"Processor", "Clone"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, res.Items, 1)
+	detail, err = q.SymbolDetail(res.Items[0].ID)
+	require.NoError(t, err)
+	assert.Equal(t, "Processor + Clone", detail.TypeParams[0].Constraints)
+	for _, tb := range detail.TypeBounds {
+		assert.False(t, tb.InWhereClause)
+	}
+}
+
 func TestSymbolDetail_NonExistentReturnsNil(t *testing.T) {
 	t.Parallel()
 	q, _ := newTestQueryBuilder(t)
@@ -2,8 +2,10 @@ package canopy
 
 import (
 	"database/sql"
+	"encoding/json"
 	"fmt"
 	"strings"
+	"time"
 
 	"github.com/jward/canopy/internal/store"
 )
@@ -50,6 +52,10 @@ const (
 	SortByLineCount        SortField = "line_count"
 	SortByRefCount         SortField = "ref_count"
 	SortByExternalRefCount SortField = "external_ref_count"
+	SortByCyclomatic       SortField = "cyclomatic"
+	SortByCognitive        SortField = "cognitive"
+	SortByPageRank         SortField = "pagerank"
+	SortByBetweenness      SortField = "betweenness"
 )
 
 // SortOrder specifies ascending or descending.
@@ -83,14 +89,18 @@ type PagedResult[T any] struct {
 
 // SymbolFilter specifies which symbols to include.
 type SymbolFilter struct {
-	Kinds      []string // match any of these kinds
+	Kinds      []string // match any of these kinds or subkinds
 	Visibility *string  // exact match
 	Modifiers  []string // symbol must have ALL of these modifiers
+	Annotations []string // symbol must carry ALL of these annotations/attributes (by name)
+	Bounds     []string // symbol must have generic bounds on ALL of these traits/interfaces (by bare name); a Rust const parameter is bounded by its type
 	FileID     *int64   // restrict to a single file
 	ParentID   *int64   // restrict to direct children of this symbol
 	PathPrefix *string  // restrict to symbols in files under this path
 	RefCountMin *int    // only symbols with ref_count >= this value
 	RefCountMax *int    // only symbols with ref_count <= this value
+	ExcludeNames []string // omit symbols with any of these names (e.g. dead-code entry points)
+	IDs        []int64  // restrict to these symbol IDs (ignored when empty)
 }
 
 // --- Internal Helpers ---
@@ -107,6 +117,76 @@ func normalizePathPrefix(prefix string) string {
 	return prefix
 }
 
+// symbolFilterClauses translates the WHERE-level fields of a SymbolFilter
+// into SQL conditions and args. Expects the symbols table aliased as "s" and
+// files as "f". RefCountMin/RefCountMax are applied separately via HAVING.
+// Test code is left out when q excludes it.
+func (q *QueryBuilder) symbolFilterClauses(filter SymbolFilter) ([]string, []any) {
+	var where []string
+	var args []any
+
+	if len(filter.Kinds) > 0 {
+		placeholders := strings.Repeat("?,", len(filter.Kinds)-1) + "?"
+		where = append(where, "(s.kind IN ("+placeholders+") OR s.subkind IN ("+placeholders+"))")
+		for range 2 {
+			for _, k := range filter.Kinds {
+				args = append(args, k)
+			}
+		}
+	}
+	if filter.Visibility != nil {
+		where = append(where, "s.visibility = ?")
+		args = append(args, *filter.Visibility)
+	}
+	if filter.FileID != nil {
+		where = append(where, "s.file_id = ?")
+		args = append(args, *filter.FileID)
+	}
+	if filter.ParentID != nil {
+		where = append(where, "s.parent_symbol_id = ?")
+		args = append(args, *filter.ParentID)
+	}
+	if filter.PathPrefix != nil {
+		prefix := normalizePathPrefix(*filter.PathPrefix)
+		if prefix != "" {
+			where = append(where, "f.path LIKE ? ESCAPE '\\'")
+			args = append(args, escapeLike(prefix)+"%")
+		}
+	}
+	// Modifier filtering using json_each
+	for _, mod := range filter.Modifiers {
+		where = append(where, "EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)")
+		args = append(args, mod)
+	}
+	for _, ann := range filter.Annotations {
+		where = append(where, "EXISTS (SELECT 1 FROM annotations a WHERE a.target_symbol_id = s.id AND a.name = ?)")
+		args = append(args, ann)
+	}
+	for _, b := range filter.Bounds {
+		where = append(where, "EXISTS (SELECT 1 FROM type_bounds tb WHERE tb.symbol_id = s.id AND tb.name = ?)")
+		args = append(args, b)
+	}
+	if len(filter.ExcludeNames) > 0 {
+		placeholders := strings.Repeat("?,", len(filter.ExcludeNames)-1) + "?"
+		where = append(where, "s.name NOT IN ("+placeholders+")")
+		for _, n := range filter.ExcludeNames {
+			args = append(args, n)
+		}
+	}
+	if len(filter.IDs) > 0 {
+		// One JSON array argument keeps large ID sets under SQLite's bound-variable limit.
+		ids, _ := json.Marshal(filter.IDs)
+		where = append(where, "s.id IN (SELECT value FROM json_each(?))")
+		args = append(args, string(ids))
+	}
+
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		where = append(where, clause)
+	}
+
+	return where, args
+}
+
 // symbolSortColumn returns the SQL ORDER BY expression for symbol queries.
 // Falls back to "s.name" for unknown fields.
 func symbolSortColumn(field SortField) string {
@@ -121,11 +201,19 @@ func symbolSortColumn(field SortField) string {
 		return "ref_count"
 	case SortByExternalRefCount:
 		return "external_ref_count"
+	case SortByPageRank:
+		return "s.pagerank"
+	case SortByBetweenness:
+		return "s.betweenness"
 	default:
 		return "s.name"
 	}
 }
 
+// symbolTiebreak orders symbols that tie on the requested sort, so equal
+// keys come out in the same order however the index was built.
+const symbolTiebreak = "f.path, s.start_line, s.start_col, s.id"
+
 // fileSortColumn returns the SQL ORDER BY expression for file queries.
 // Falls back to "path" for inapplicable fields.
 func fileSortColumn(field SortField) string {
@@ -151,42 +239,16 @@ func sortDirection(order SortOrder) string {
 
 // Symbols is the primary listing/filtering endpoint. All filter fields are optional.
 func (q *QueryBuilder) Symbols(filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
+	start := time.Now()
+	res, err := q.symbols(filter, sort, page)
+	q.reportUsage("symbols", start, res.total(), err)
+	return res, err
+}
+
+func (q *QueryBuilder) symbols(filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
 	page = page.normalize()
 
-	var where []string
-	var args []any
-
-	if len(filter.Kinds) > 0 {
-		placeholders := strings.Repeat("?,", len(filter.Kinds)-1) + "?"
-		where = append(where, "s.kind IN ("+placeholders+")")
-		for _, k := range filter.Kinds {
-			args = append(args, k)
-		}
-	}
-	if filter.Visibility != nil {
-		where = append(where, "s.visibility = ?")
-		args = append(args, *filter.Visibility)
-	}
-	if filter.FileID != nil {
-		where = append(where, "s.file_id = ?")
-		args = append(args, *filter.FileID)
-	}
-	if filter.ParentID != nil {
-		where = append(where, "s.parent_symbol_id = ?")
-		args = append(args, *filter.ParentID)
-	}
-	if filter.PathPrefix != nil {
-		prefix := normalizePathPrefix(*filter.PathPrefix)
-		if prefix != "" {
-			where = append(where, "f.path LIKE ? ESCAPE '\\'")
-			args = append(args, escapeLike(prefix)+"%")
-		}
-	}
-	// Modifier filtering using json_each
-	for _, mod := range filter.Modifiers {
-		where = append(where, "EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)")
-		args = append(args, mod)
-	}
+	where, args := q.symbolFilterClauses(filter)
 
 	whereClause := ""
 	if len(where) > 0 {
@@ -241,9 +303,9 @@ func (q *QueryBuilder) Symbols(filter SymbolFilter, sort Sort, page Pagination)
 		 FROM symbols s
 		 LEFT JOIN files f ON s.file_id = f.id
 		 %s%s%s
-		 ORDER BY %s %s
+		 ORDER BY %s %s, %s
 		 LIMIT ? OFFSET ?`,
-		prefixSymbolCols("s"), whereClause, groupByClause, havingClause, orderCol, orderDir,
+		prefixSymbolCols("s"), whereClause, groupByClause, havingClause, orderCol, orderDir, symbolTiebreak,
 	)
 	dataArgs := append(append([]any{}, args...), havingArgs...)
 	dataArgs = append(dataArgs, *page.Limit, page.Offset)
@@ -274,6 +336,13 @@ func (q *QueryBuilder) Symbols(filter SymbolFilter, sort Sort, page Pagination)
 
 // Files is a convenience method for listing files.
 func (q *QueryBuilder) Files(pathPrefix string, language string, sort Sort, page Pagination) (*PagedResult[File], error) {
+	start := time.Now()
+	res, err := q.files(pathPrefix, language, sort, page)
+	q.reportUsage("files", start, res.total(), err)
+	return res, err
+}
+
+func (q *QueryBuilder) files(pathPrefix string, language string, sort Sort, page Pagination) (*PagedResult[File], error) {
 	page = page.normalize()
 
 	var where []string
@@ -306,7 +375,7 @@ func (q *QueryBuilder) Files(pathPrefix string, language string, sort Sort, page
 	orderDir := sortDirection(sort.Order)
 
 	dataSQL := fmt.Sprintf(
-		`SELECT id, path, language, hash, line_count, last_indexed FROM files %s ORDER BY %s %s LIMIT ? OFFSET ?`,
+		`SELECT id, path, language, hash, line_count, last_indexed, COALESCE(root, '') FROM files %s ORDER BY %s %s, path LIMIT ? OFFSET ?`,
 		whereClause, orderCol, orderDir,
 	)
 	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
@@ -320,7 +389,7 @@ func (q *QueryBuilder) Files(pathPrefix string, language string, sort Sort, page
 	var items []File
 	for rows.Next() {
 		var f File
-		if err := rows.Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed); err != nil {
+		if err := rows.Scan(&f.ID, &f.Path, &f.Language, &f.Hash, &f.LineCount, &f.LastIndexed, &f.Root); err != nil {
 			return nil, fmt.Errorf("files: scan: %w", err)
 		}
 		items = append(items, f)
@@ -335,6 +404,41 @@ func (q *QueryBuilder) Files(pathPrefix string, language string, sort Sort, page
 	return &PagedResult[File]{Items: items, TotalCount: totalCount}, nil
 }
 
+// RootSummary describes one indexed root directory (see Engine.IndexDirectories).
+type RootSummary struct {
+	Root      string // "" groups files indexed without a known root
+	FileCount int
+	LineCount int
+}
+
+// Roots returns every indexed root with its file and line counts, ordered by
+// root path.
+func (q *QueryBuilder) Roots() ([]RootSummary, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT COALESCE(root, ''), COUNT(*), COALESCE(SUM(line_count), 0)
+		 FROM files
+		 GROUP BY COALESCE(root, '')
+		 ORDER BY 1`,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("roots: query: %w", err)
+	}
+	defer rows.Close()
+
+	roots := []RootSummary{}
+	for rows.Next() {
+		var r RootSummary
+		if err := rows.Scan(&r.Root, &r.FileCount, &r.LineCount); err != nil {
+			return nil, fmt.Errorf("roots: scan: %w", err)
+		}
+		roots = append(roots, r)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("roots: rows: %w", err)
+	}
+	return roots, nil
+}
+
 // Packages is a convenience method for listing packages, modules, and namespaces.
 func (q *QueryBuilder) Packages(pathPrefix string, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
 	filter := SymbolFilter{
@@ -343,7 +447,7 @@ func (q *QueryBuilder) Packages(pathPrefix string, sort Sort, page Pagination) (
 	if pathPrefix != "" {
 		filter.PathPrefix = &pathPrefix
 	}
-	return q.Symbols(filter, sort, page)
+	return q.symbols(filter, sort, page)
 }
 
 // --- Search ---
@@ -351,6 +455,13 @@ func (q *QueryBuilder) Packages(pathPrefix string, sort Sort, page Pagination) (
 // SearchSymbols performs glob-style search on symbol names.
 // '*' is the wildcard (mapped to SQL '%').
 func (q *QueryBuilder) SearchSymbols(pattern string, filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
+	start := time.Now()
+	res, err := q.searchSymbols(pattern, filter, sort, page)
+	q.reportUsage("search_symbols", start, res.total(), err)
+	return res, err
+}
+
+func (q *QueryBuilder) searchSymbols(pattern string, filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
 	page = page.normalize()
 
 	var where []string
@@ -365,36 +476,9 @@ func (q *QueryBuilder) SearchSymbols(pattern string, filter SymbolFilter, sort S
 	}
 
 	// Apply the same structured filters as Symbols
-	if len(filter.Kinds) > 0 {
-		placeholders := strings.Repeat("?,", len(filter.Kinds)-1) + "?"
-		where = append(where, "s.kind IN ("+placeholders+")")
-		for _, k := range filter.Kinds {
-			args = append(args, k)
-		}
-	}
-	if filter.Visibility != nil {
-		where = append(where, "s.visibility = ?")
-		args = append(args, *filter.Visibility)
-	}
-	if filter.FileID != nil {
-		where = append(where, "s.file_id = ?")
-		args = append(args, *filter.FileID)
-	}
-	if filter.ParentID != nil {
-		where = append(where, "s.parent_symbol_id = ?")
-		args = append(args, *filter.ParentID)
-	}
-	if filter.PathPrefix != nil {
-		prefix := normalizePathPrefix(*filter.PathPrefix)
-		if prefix != "" {
-			where = append(where, "f.path LIKE ? ESCAPE '\\'")
-			args = append(args, escapeLike(prefix)+"%")
-		}
-	}
-	for _, mod := range filter.Modifiers {
-		where = append(where, "EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)")
-		args = append(args, mod)
-	}
+	filterWhere, filterArgs := q.symbolFilterClauses(filter)
+	where = append(where, filterWhere...)
+	args = append(args, filterArgs...)
 
 	whereClause := ""
 	if len(where) > 0 {
@@ -448,9 +532,9 @@ func (q *QueryBuilder) SearchSymbols(pattern string, filter SymbolFilter, sort S
 		 FROM symbols s
 		 LEFT JOIN files f ON s.file_id = f.id
 		 %s%s%s
-		 ORDER BY %s %s
+		 ORDER BY %s %s, %s
 		 LIMIT ? OFFSET ?`,
-		prefixSymbolCols("s"), whereClause, groupByClause, havingClause, orderCol, orderDir,
+		prefixSymbolCols("s"), whereClause, groupByClause, havingClause, orderCol, orderDir, symbolTiebreak,
 	)
 	dataArgs := append(append([]any{}, args...), havingArgs...)
 	dataArgs = append(dataArgs, *page.Limit, page.Offset)
@@ -577,9 +661,9 @@ func (q *QueryBuilder) ProjectSummary(topN int) (*ProjectSummary, error) {
 			 FROM symbols s
 			 LEFT JOIN files f ON s.file_id = f.id
 			 WHERE (SELECT COUNT(*) FROM resolved_references rr2 WHERE rr2.target_symbol_id = s.id) > 0
-			 ORDER BY external_ref_count DESC
+			 ORDER BY external_ref_count DESC, %s
 			 LIMIT ?`,
-			prefixSymbolCols("s"),
+			prefixSymbolCols("s"), symbolTiebreak,
 		)
 		topRows, err := q.store.DB().Query(topSQL, topN)
 		if err != nil {
@@ -703,8 +787,8 @@ func (q *QueryBuilder) PackageSummary(packagePath string, packageID *int64) (*Pa
 			 WHERE f.path LIKE ? ESCAPE '\'
 			   AND s.visibility = 'public'
 			   AND s.kind NOT IN ('package', 'module', 'namespace')
-			 ORDER BY external_ref_count DESC`,
-			prefixSymbolCols("s"),
+			 ORDER BY external_ref_count DESC, %s`,
+			prefixSymbolCols("s"), symbolTiebreak,
 		)
 		expRows, err := q.store.DB().Query(expSQL, escapeLike(pathPrefix)+"%")
 		if err != nil {
@@ -826,6 +910,7 @@ func prefixSymbolCols(prefix string) string {
 	cols := []string{
 		"id", "file_id", "name", "kind", "visibility", "modifiers", "signature_hash",
 		"start_line", "start_col", "end_line", "end_col", "parent_symbol_id",
+		"start_byte", "end_byte", "utf16_start_col", "utf16_end_col", "pagerank", "betweenness", "subkind",
 	}
 	prefixed := make([]string, len(cols))
 	for i, c := range cols {
@@ -846,7 +931,8 @@ func scanSymbolResult(row scanner) (SymbolResult, error) {
 	err := row.Scan(
 		&sr.ID, &sr.FileID, &sr.Name, &sr.Kind, &sr.Visibility, &mods,
 		&sr.SignatureHash, &sr.StartLine, &sr.StartCol, &sr.EndLine, &sr.EndCol,
-		&sr.ParentSymbolID,
+		&sr.ParentSymbolID, &sr.StartByte, &sr.EndByte, &sr.UTF16StartCol, &sr.UTF16EndCol,
+		&sr.PageRank, &sr.Betweenness, &sr.Subkind,
 		&sr.FilePath, &sr.RefCount, &sr.ExternalRefCount,
 	)
 	if err != nil {
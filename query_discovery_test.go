@@ -166,6 +166,38 @@ func TestSymbols_FilterByKind(t *testing.T) {
 	assert.Equal(t, 2, result.TotalCount)
 }
 
+func TestSymbols_FilterBySubkind(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "lib.rs", "rust")
+	for _, sym := range []*store.Symbol{
+		{FileID: &fID, Name: "free", Kind: "function"},
+		{FileID: &fID, Name: "new", Kind: "function", Subkind: "associated_function"},
+		{FileID: &fID, Name: "Pair", Kind: "struct", Subkind: "tuple_struct"},
+		{FileID: &fID, Name: "Point", Kind: "struct", Subkind: "record_struct"},
+	} {
+		_, err := s.InsertSymbol(sym)
+		require.NoError(t, err)
+	}
+
+	result, err := q.Symbols(SymbolFilter{Kinds: []string{"associated_function"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 1, result.TotalCount)
+	assert.Equal(t, "new", result.Items[0].Name)
+	assert.Equal(t, "associated_function", result.Items[0].Subkind)
+
+	// A kind still matches symbols of every subkind.
+	result, err = q.Symbols(SymbolFilter{Kinds: []string{"function"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 2, result.TotalCount)
+
+	result, err = q.Symbols(SymbolFilter{Kinds: []string{"tuple_struct", "record_struct"}}, Sort{Field: SortByName}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 2, result.TotalCount)
+	assert.Equal(t, "Pair", result.Items[0].Name)
+	assert.Equal(t, "Point", result.Items[1].Name)
+}
+
 func TestSymbols_FilterByVisibility(t *testing.T) {
 	t.Parallel()
 	q, s := newTestQueryBuilder(t)
@@ -199,6 +231,73 @@ func TestSymbols_FilterByModifiers(t *testing.T) {
 	assert.Equal(t, "Foo", result.Items[0].Name)
 }
 
+func TestSymbols_FilterByAnnotations(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "lib.rs", "rust")
+	oldID := insertSymbol(t, s, &fID, "old_api", "function", "public", nil)
+	testID := insertSymbol(t, s, &fID, "it_works", "function", "private", nil)
+	insertSymbol(t, s, &fID, "new_api", "function", "public", nil)
+
+	_, err := s.InsertAnnotation(&store.Annotation{
+		TargetSymbolID: oldID, Name: "deprecated", Arguments: `(note = "use new_api")`, FileID: &fID,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertAnnotation(&store.Annotation{
+		TargetSymbolID: oldID, Name: "inline", FileID: &fID,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertAnnotation(&store.Annotation{
+		TargetSymbolID: testID, Name: "test", FileID: &fID,
+	})
+	require.NoError(t, err)
+
+	result, err := q.Symbols(SymbolFilter{Annotations: []string{"deprecated"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 1, result.TotalCount)
+	assert.Equal(t, "old_api", result.Items[0].Name)
+
+	// Multiple annotations (AND)
+	result, err = q.Symbols(SymbolFilter{Annotations: []string{"deprecated", "test"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 0, result.TotalCount)
+
+	result, err = q.SearchSymbols("it_*", SymbolFilter{Annotations: []string{"test"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 1, result.TotalCount)
+	assert.Equal(t, "it_works", result.Items[0].Name)
+}
+
+func TestSymbols_FilterByBounds(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "lib.rs", "rust")
+	inlineID := insertSymbol(t, s, &fID, "transform", "function", "public", nil)
+	whereID := insertSymbol(t, s, &fID, "transform_where", "function", "public", nil)
+	insertSymbol(t, s, &fID, "plain", "function", "public", nil)
+
+	for _, tb := range []*store.TypeBound{
+		{SymbolID: inlineID, Subject: "P", Bound: "Processor", Name: "Processor"},
+		{SymbolID: inlineID, Subject: "P", Bound: "Clone", Name: "Clone"},
+		{SymbolID: whereID, Subject: "P", Bound: "crate::Processor", Name: "Processor", InWhereClause: true},
+	} {
+		_, err := s.InsertTypeBound(tb)
+		require.NoError(t, err)
+	}
+
+	result, err := q.Symbols(SymbolFilter{Bounds: []string{"Processor"}}, Sort{Field: SortByName}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 2, result.TotalCount)
+	assert.Equal(t, "transform", result.Items[0].Name)
+	assert.Equal(t, "transform_where", result.Items[1].Name)
+
+	// Multiple bounds (AND)
+	result, err = q.Symbols(SymbolFilter{Bounds: []string{"Processor", "Clone"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 1, result.TotalCount)
+	assert.Equal(t, "transform", result.Items[0].Name)
+}
+
 func TestSymbols_FilterByFileID(t *testing.T) {
 	t.Parallel()
 	q, s := newTestQueryBuilder(t)
@@ -0,0 +1,419 @@
+package canopy
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+)
+
+// Cargo target kinds that are entry points of a package.
+const (
+	CargoTargetBin     = "bin"
+	CargoTargetExample = "example"
+	CargoTargetTest    = "test"
+	CargoTargetBench   = "bench"
+)
+
+// cargoTargetDirs are the directories Cargo discovers targets of each kind
+// in, besides src/main.rs for the package's default binary.
+var cargoTargetDirs = []struct{ kind, dir, auto string }{
+	{CargoTargetBin, "src/bin", "autobins"},
+	{CargoTargetExample, "examples", "autoexamples"},
+	{CargoTargetTest, "tests", "autotests"},
+	{CargoTargetBench, "benches", "autobenches"},
+}
+
+// EntryPoint is a binary, example, integration test, or bench target of a
+// Cargo package, with the functions execution starts from.
+type EntryPoint struct {
+	Package string         // the Cargo package name
+	Kind    string         // one of the CargoTarget* kinds
+	Name    string         // the target name, as cargo --bin, --example, --test or --bench takes it
+	Path    string         // the target's crate root
+	Roots   []SymbolResult // main, and in a test or bench target its #[test] functions
+}
+
+// Reachability is which Rust functions and methods the call graph reaches
+// from a set of entry points.
+type Reachability struct {
+	EntryPoints []EntryPoint
+	Reachable   int            // functions and methods reached, roots included
+	Unreachable []SymbolResult // by file and position
+}
+
+// EntryPoints returns the entry points of the Cargo packages in the index,
+// ordered by package directory, kind, and name. Targets are read from each
+// Cargo.toml: [[bin]], [[example]], [[test]] and [[bench]] tables, and the
+// targets Cargo discovers unless autobins (and so on) is false:
+// src/main.rs, and <name>.rs or <name>/main.rs under src/bin, examples,
+// tests and benches. target selects targets by name, kind, or kind:name
+// ("" for all). A bench without main or tests (a Criterion bench, whose
+// main a macro generates) is rooted at its root file's functions.
+func (q *QueryBuilder) EntryPoints(target string) ([]EntryPoint, error) {
+	files, err := q.store.FilesByLanguage("rust")
+	if err != nil {
+		return nil, fmt.Errorf("entry points: %w", err)
+	}
+	packages := map[string]cargoPackage{}
+	pkgFiles := map[string][]string{} // package directory -> its Rust files
+	pkgNames := map[string]string{}
+	fileIDs := map[string]int64{}
+	for _, f := range files {
+		pkg := cargoPackageFor(filepath.Dir(f.Path), packages)
+		if pkg.name == "" {
+			continue
+		}
+		pkgFiles[pkg.dir] = append(pkgFiles[pkg.dir], f.Path)
+		pkgNames[pkg.dir] = pkg.name
+		fileIDs[f.Path] = f.ID
+	}
+	dirs := make([]string, 0, len(pkgFiles))
+	for dir := range pkgFiles {
+		dirs = append(dirs, dir)
+	}
+	sort.Strings(dirs)
+
+	tests, err := q.testSymbolIDs()
+	if err != nil {
+		return nil, fmt.Errorf("entry points: %w", err)
+	}
+	var out []EntryPoint
+	for _, dir := range dirs {
+		manifest, err := os.ReadFile(filepath.Join(dir, "Cargo.toml"))
+		if err != nil {
+			return nil, fmt.Errorf("entry points: %w", err)
+		}
+		for _, t := range cargoTargets(pkgNames[dir], dir, manifest, pkgFiles[dir]) {
+			if target != "" && target != t.Name && target != t.Kind && target != t.Kind+":"+t.Name {
+				continue
+			}
+			if t.Roots, err = q.targetRoots(t, pkgFiles[dir], fileIDs, tests); err != nil {
+				return nil, fmt.Errorf("entry points: %w", err)
+			}
+			out = append(out, t)
+		}
+	}
+	return out, nil
+}
+
+// cargoTargets returns the entry points of the package name in dir, with
+// the Rust files files, from its manifest.
+func cargoTargets(name, dir string, manifest []byte, files []string) []EntryPoint {
+	indexed := map[string]bool{}
+	for _, f := range files {
+		indexed[f] = true
+	}
+	decls, auto := parseCargoTargets(manifest)
+
+	byKey := map[string]EntryPoint{}
+	claimed := map[string]bool{} // root files of declared targets
+	for _, d := range decls {
+		path := d.path
+		if path == "" {
+			path = cargoTargetPath(name, d.kind, d.name, dir, indexed)
+		}
+		path = filepath.Join(dir, filepath.FromSlash(path))
+		byKey[d.kind+":"+d.name] = EntryPoint{Package: name, Kind: d.kind, Name: d.name, Path: path}
+		claimed[path] = true
+	}
+	add := func(kind, target, path string) {
+		if _, ok := byKey[kind+":"+target]; !ok && !claimed[path] && indexed[path] {
+			byKey[kind+":"+target] = EntryPoint{Package: name, Kind: kind, Name: target, Path: path}
+		}
+	}
+	if auto["autobins"] {
+		add(CargoTargetBin, name, filepath.Join(dir, "src", "main.rs"))
+	}
+	for _, td := range cargoTargetDirs {
+		if !auto[td.auto] {
+			continue
+		}
+		base := filepath.Join(dir, filepath.FromSlash(td.dir))
+		for _, f := range files {
+			rel, err := filepath.Rel(base, f)
+			if err != nil || strings.HasPrefix(rel, "..") {
+				continue
+			}
+			switch parts := strings.Split(filepath.ToSlash(rel), "/"); {
+			case len(parts) == 1 && strings.HasSuffix(parts[0], ".rs"):
+				add(td.kind, strings.TrimSuffix(parts[0], ".rs"), f)
+			case len(parts) == 2 && parts[1] == "main.rs":
+				add(td.kind, parts[0], f)
+			}
+		}
+	}
+
+	kindOrder := map[string]int{CargoTargetBin: 0, CargoTargetExample: 1, CargoTargetTest: 2, CargoTargetBench: 3}
+	out := make([]EntryPoint, 0, len(byKey))
+	for _, t := range byKey {
+		out = append(out, t)
+	}
+	sort.Slice(out, func(i, j int) bool {
+		if out[i].Kind != out[j].Kind {
+			return kindOrder[out[i].Kind] < kindOrder[out[j].Kind]
+		}
+		return out[i].Name < out[j].Name
+	})
+	return out
+}
+
+// cargoTargetPath returns the default root, relative to the package
+// directory, of a target declared without a path.
+func cargoTargetPath(pkgName, kind, name, dir string, indexed map[string]bool) string {
+	if kind == CargoTargetBin && name == pkgName && indexed[filepath.Join(dir, "src", "main.rs")] {
+		return "src/main.rs"
+	}
+	for _, td := range cargoTargetDirs {
+		if td.kind != kind {
+			continue
+		}
+		if nested := td.dir + "/" + name + "/main.rs"; indexed[filepath.Join(dir, filepath.FromSlash(nested))] {
+			return nested
+		}
+		return td.dir + "/" + name + ".rs"
+	}
+	return ""
+}
+
+// cargoTargetDecl is a [[bin]], [[example]], [[test]] or [[bench]] table.
+type cargoTargetDecl struct {
+	kind, name, path string
+}
+
+// parseCargoTargets returns the targets a Cargo.toml declares, and which of
+// autobins, autoexamples, autotests and autobenches are on.
+func parseCargoTargets(manifest []byte) ([]cargoTargetDecl, map[string]bool) {
+	auto := map[string]bool{}
+	for _, td := range cargoTargetDirs {
+		auto[td.auto] = true
+	}
+	var decls []cargoTargetDecl
+	table := ""
+	for _, line := range strings.Split(string(manifest), "\n") {
+		line = strings.TrimSpace(line)
+		if strings.HasPrefix(line, "[") {
+			table = strings.TrimSpace(strings.Trim(line, "[]"))
+			if strings.HasPrefix(line, "[[") {
+				for _, td := range cargoTargetDirs {
+					if td.kind == table {
+						decls = append(decls, cargoTargetDecl{kind: table})
+					}
+				}
+			}
+			continue
+		}
+		key, value, ok := strings.Cut(line, "=")
+		if !ok {
+			continue
+		}
+		key = strings.TrimSpace(key)
+		value, _, _ = strings.Cut(value, "#")
+		value = strings.Trim(strings.TrimSpace(value), `"'`)
+		_, isAuto := auto[key]
+		switch {
+		case table == "package" && isAuto:
+			auto[key] = value != "false"
+		case len(decls) > 0 && table == decls[len(decls)-1].kind && key == "name":
+			decls[len(decls)-1].name = value
+		case len(decls) > 0 && table == decls[len(decls)-1].kind && key == "path":
+			decls[len(decls)-1].path = value
+		}
+	}
+	named := decls[:0]
+	for _, d := range decls {
+		if d.name != "" {
+			named = append(named, d)
+		}
+	}
+	return named, auto
+}
+
+// targetRoots returns the functions an entry point starts from: main in its
+// root file and, for a test or bench target, the tests in its root file and
+// in the directory of its submodules. A bench with neither is rooted at its
+// root file's free functions.
+func (q *QueryBuilder) targetRoots(t EntryPoint, files []string, fileIDs map[string]int64, tests map[int64]bool) ([]SymbolResult, error) {
+	modDir := strings.TrimSuffix(t.Path, ".rs") + string(filepath.Separator)
+	if filepath.Base(t.Path) == "main.rs" {
+		modDir = filepath.Dir(t.Path) + string(filepath.Separator)
+	}
+	var ids, free []int64
+	for _, path := range files {
+		inTarget := path == t.Path || (t.Kind != CargoTargetBin && t.Kind != CargoTargetExample && strings.HasPrefix(path, modDir))
+		if !inTarget {
+			continue
+		}
+		syms, err := q.store.SymbolsByFile(fileIDs[path])
+		if err != nil {
+			return nil, err
+		}
+		for _, s := range syms {
+			switch {
+			case path == t.Path && s.Kind == "function" && s.Name == "main" && s.ParentSymbolID == nil:
+				ids = append(ids, s.ID)
+			case tests[s.ID]:
+				ids = append(ids, s.ID)
+			case path == t.Path && s.Kind == "function" && s.ParentSymbolID == nil:
+				free = append(free, s.ID)
+			}
+		}
+	}
+	if len(ids) == 0 && t.Kind == CargoTargetBench {
+		ids = free
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+	roots := []SymbolResult{}
+	for _, id := range ids {
+		if s, ok := syms[id]; ok {
+			roots = append(roots, *s)
+		}
+	}
+	sortSymbolResultsByPosition(roots)
+	return roots, nil
+}
+
+// Reachability walks the call graph from the roots of the entry points
+// target selects (as for EntryPoints) and lists the Rust functions and
+// methods in files under pathPrefix it does not reach. With no target, the
+// tests of every package are roots too, as cargo test runs them, unless q
+// excludes test code. Tests are
+// never listed, and neither are methods of trait impls, which are called
+// through their trait (Display::fmt by format!) where the call graph does
+// not follow.
+func (q *QueryBuilder) Reachability(target, pathPrefix string) (*Reachability, error) {
+	entries, err := q.EntryPoints(target)
+	if err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+	tests, err := q.testSymbolIDs()
+	if err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+	graph, err := q.buildCallGraph()
+	if err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+
+	reached := map[int64]bool{}
+	var queue []int64
+	visit := func(id int64) {
+		if !reached[id] {
+			reached[id] = true
+			queue = append(queue, id)
+		}
+	}
+	for _, e := range entries {
+		for _, r := range e.Roots {
+			visit(r.ID)
+		}
+	}
+	if target == "" && !q.excludeTests {
+		for id := range tests {
+			visit(id)
+		}
+	}
+	for len(queue) > 0 {
+		id := queue[0]
+		queue = queue[1:]
+		for _, callee := range graph.forward[id] {
+			visit(callee)
+		}
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT s.id FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 WHERE s.kind IN ('function', 'method') AND f.language = 'rust' AND f.path LIKE ? ESCAPE '\'`,
+		escapeLike(pathPrefix)+"%",
+	)
+	if err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+	var ids []int64
+	result := &Reachability{EntryPoints: entries, Unreachable: []SymbolResult{}}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("reachability: %w", err)
+		}
+		switch {
+		case reached[id]:
+			result.Reachable++
+		case !tests[id]:
+			ids = append(ids, id)
+		}
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("reachability: %w", err)
+	}
+	traitImpls := map[string][]TraitImpl{}
+	packages := map[string]cargoPackage{}
+	crateOf := func(path string) string {
+		if m, ok := q.rustModuleOf(path, packages); ok {
+			return m.crate
+		}
+		return ""
+	}
+	for _, id := range ids {
+		s, ok := syms[id]
+		if !ok {
+			continue
+		}
+		impls, ok := traitImpls[s.FilePath]
+		if !ok {
+			f, err := q.store.FileByPath(s.FilePath)
+			if err != nil {
+				return nil, fmt.Errorf("reachability: %w", err)
+			}
+			if f != nil {
+				if impls, err = q.fileTraitImpls(f, crateOf); err != nil {
+					return nil, fmt.Errorf("reachability: %w", err)
+				}
+			}
+			traitImpls[s.FilePath] = impls
+		}
+		inTraitImpl := false
+		for _, im := range impls {
+			inTraitImpl = inTraitImpl || locationContains(im.Location, s.StartLine, s.StartCol)
+		}
+		if !inTraitImpl {
+			result.Unreachable = append(result.Unreachable, *s)
+		}
+	}
+	sortSymbolResultsByPosition(result.Unreachable)
+	return result, nil
+}
+
+// locationContains reports whether the position line:col is within loc.
+func locationContains(loc Location, line, col int) bool {
+	if line < loc.StartLine || line > loc.EndLine {
+		return false
+	}
+	return (line > loc.StartLine || col >= loc.StartCol) && (line < loc.EndLine || col <= loc.EndCol)
+}
+
+// sortSymbolResultsByPosition orders symbols by file and position.
+func sortSymbolResultsByPosition(syms []SymbolResult) {
+	sort.Slice(syms, func(i, j int) bool {
+		a, b := syms[i], syms[j]
+		if a.FilePath != b.FilePath {
+			return a.FilePath < b.FilePath
+		}
+		if a.StartLine != b.StartLine {
+			return a.StartLine < b.StartLine
+		}
+		return a.StartCol < b.StartCol
+	})
+}
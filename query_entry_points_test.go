@@ -0,0 +1,148 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseCargoTargets(t *testing.T) {
+	decls, auto := parseCargoTargets([]byte(`[package]
+name = "demo"
+autotests = false # integration tests are declared
+
+[[bin]]
+name = "tool"
+path = "src/tools/tool.rs"
+
+[[test]]
+name = "smoke"
+
+[dependencies]
+name = "not a target"
+`))
+	assert.Equal(t, []cargoTargetDecl{
+		{kind: CargoTargetBin, name: "tool", path: "src/tools/tool.rs"},
+		{kind: CargoTargetTest, name: "smoke"},
+	}, decls)
+	assert.Equal(t, map[string]bool{"autobins": true, "autoexamples": true, "autotests": false, "autobenches": true}, auto)
+}
+
+func TestEntryPoints(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", `[package]
+name = "demo"
+version = "0.1.0"
+
+[[bin]]
+name = "tool"
+path = "src/tools/tool.rs"
+
+[[example]]
+name = "walkthrough"
+`)
+	write("src/lib.rs", `pub fn tested() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit() {
+        tested();
+    }
+}
+`)
+	write("src/main.rs", `use std::fmt;
+
+struct Shown;
+
+impl fmt::Display for Shown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+fn main() {
+    helper();
+}
+
+fn helper() {}
+
+fn dead() {}
+`)
+	write("src/bin/extra.rs", "fn main() {}\n")
+	write("src/tools/tool.rs", "fn main() {}\n")
+	write("examples/walkthrough/main.rs", "fn main() {}\n")
+	write("tests/it.rs", "#[test]\nfn works() {}\n")
+	write("benches/speed.rs", "fn bench_a() {}\nfn bench_b() {}\n")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	entries, err := q.EntryPoints("")
+	require.NoError(t, err)
+	type target struct{ kind, name, path string }
+	var got []target
+	roots := map[string][]string{}
+	for _, ep := range entries {
+		assert.Equal(t, "demo", ep.Package)
+		rel, err := filepath.Rel(root, ep.Path)
+		require.NoError(t, err)
+		got = append(got, target{ep.Kind, ep.Name, filepath.ToSlash(rel)})
+		for _, r := range ep.Roots {
+			roots[ep.Name] = append(roots[ep.Name], r.Name)
+		}
+	}
+	assert.Equal(t, []target{
+		{CargoTargetBin, "demo", "src/main.rs"},
+		{CargoTargetBin, "extra", "src/bin/extra.rs"},
+		{CargoTargetBin, "tool", "src/tools/tool.rs"},
+		{CargoTargetExample, "walkthrough", "examples/walkthrough/main.rs"},
+		{CargoTargetTest, "it", "tests/it.rs"},
+		{CargoTargetBench, "speed", "benches/speed.rs"},
+	}, got)
+	assert.Equal(t, []string{"main"}, roots["demo"])
+	assert.Equal(t, []string{"works"}, roots["it"])
+	assert.Equal(t, []string{"bench_a", "bench_b"}, roots["speed"], "a bench without main is rooted at its functions")
+
+	filtered, err := q.EntryPoints("bin")
+	require.NoError(t, err)
+	assert.Len(t, filtered, 3)
+	filtered, err = q.EntryPoints("example:walkthrough")
+	require.NoError(t, err)
+	require.Len(t, filtered, 1)
+	assert.Equal(t, "walkthrough", filtered[0].Name)
+
+	names := func(syms []SymbolResult) []string {
+		var out []string
+		for _, s := range syms {
+			out = append(out, s.Name)
+		}
+		return out
+	}
+	all, err := q.Reachability("", "")
+	require.NoError(t, err)
+	assert.Equal(t, []string{"dead"}, names(all.Unreachable), "tests and trait impl methods are not listed")
+
+	demo, err := q.Reachability("bin:demo", filepath.Join(root, "src")+string(filepath.Separator))
+	require.NoError(t, err)
+	require.Len(t, demo.EntryPoints, 1)
+	unreachable := names(demo.Unreachable)
+	assert.Contains(t, unreachable, "tested", "only the unit tests reach it")
+	assert.Contains(t, unreachable, "dead")
+	assert.NotContains(t, unreachable, "helper")
+	assert.NotContains(t, unreachable, "unit")
+}
@@ -0,0 +1,226 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// rustStdErrorTypes maps std functions and methods to the error type of the
+// Result they return, by stub path, or by path prefix for entries ending in
+// "::". The first match wins.
+var rustStdErrorTypes = []struct {
+	path      string
+	errorType string
+}{
+	{"std::fs::", "std::io::Error"},
+	{"std::io::", "std::io::Error"},
+	{"std::net::", "std::io::Error"},
+	{"std::process::Command::", "std::io::Error"},
+	{"std::process::Child::", "std::io::Error"},
+	{"std::env::var", "std::env::VarError"},
+	{"std::str::from_utf8", "std::str::Utf8Error"},
+	{"std::string::String::from_utf8", "std::string::FromUtf8Error"},
+}
+
+// ErrorFlow is a function and the error types that can surface from it.
+type ErrorFlow struct {
+	Function SymbolResult
+	// Declared is the error type of the function's Result return type, as
+	// written: "io::Error" for io::Result<T>, "MyError" for Result<T, MyError>.
+	// "" when it cannot be told, as for a bare Result<T> alias.
+	Declared string
+	// ErrorTypes are Declared and every error type propagated into the
+	// function with ?, directly or through the functions it calls, sorted.
+	// A ? may convert a propagated error into Declared with From; its type
+	// is listed all the same, as the error it started as.
+	ErrorTypes []string
+}
+
+// ErrorFlowFilter narrows ErrorFlows.
+type ErrorFlowFilter struct {
+	ErrorType  string // keep functions an error of this type can surface from; io::Error also matches std::io::Error
+	PublicOnly bool   // keep public functions only
+	PathPrefix string // keep functions in files under this prefix
+}
+
+// ErrorFlows maps which error types flow through which Rust functions, ordered
+// by file then line. A function's error types are the one its Result return
+// type declares, plus those of every function whose error it propagates with
+// the ? operator (std functions through their stubs, see rustStdErrorTypes).
+// Spawned work (CallKindSpawn) is not followed: its errors end in the
+// spawned task or thread. Functions with no known error type are left out.
+func (q *QueryBuilder) ErrorFlows(filter ErrorFlowFilter) ([]ErrorFlow, error) {
+	declared, err := q.declaredErrorTypes()
+	if err != nil {
+		return nil, fmt.Errorf("error flows: %w", err)
+	}
+	stubs, err := q.stubFunctionPaths()
+	if err != nil {
+		return nil, fmt.Errorf("error flows: %w", err)
+	}
+	data, err := q.buildCallGraph()
+	if err != nil {
+		return nil, fmt.Errorf("error flows: %w", err)
+	}
+
+	flows := map[int64]map[string]bool{}
+	add := func(id int64, errorType string) bool {
+		if flows[id] == nil {
+			flows[id] = map[string]bool{}
+		}
+		if flows[id][errorType] {
+			return false
+		}
+		flows[id][errorType] = true
+		return true
+	}
+	for id, errorType := range declared {
+		add(id, errorType)
+	}
+	for id, path := range stubs {
+		if errorType := stdErrorType(path); errorType != "" {
+			add(id, errorType)
+		}
+	}
+
+	var propagated []*CallEdge
+	for _, edges := range data.edgesByCaller {
+		for _, e := range edges {
+			if e.Propagated && e.Kind != CallKindSpawn {
+				propagated = append(propagated, e)
+			}
+		}
+	}
+	// Push error types up propagating calls until nothing changes; recursion
+	// ends because each function gains at most every known error type.
+	for changed := true; changed; {
+		changed = false
+		for _, e := range propagated {
+			for errorType := range flows[e.CalleeSymbolID] {
+				if add(e.CallerSymbolID, errorType) {
+					changed = true
+				}
+			}
+		}
+	}
+
+	var ids []int64
+	for id := range flows {
+		if _, isStub := stubs[id]; !isStub {
+			ids = append(ids, id)
+		}
+	}
+	result := []ErrorFlow{}
+	if len(ids) == 0 {
+		return result, nil
+	}
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("error flows: load symbols: %w", err)
+	}
+	for _, id := range ids {
+		sr, ok := symbols[id]
+		if !ok || !strings.HasPrefix(sr.FilePath, filter.PathPrefix) {
+			continue
+		}
+		if filter.PublicOnly && sr.Visibility != "public" {
+			continue
+		}
+		flow := ErrorFlow{Function: *sr, Declared: declared[id]}
+		matched := filter.ErrorType == ""
+		for errorType := range flows[id] {
+			flow.ErrorTypes = append(flow.ErrorTypes, errorType)
+			if !matched && errorTypeMatches(filter.ErrorType, errorType) {
+				matched = true
+			}
+		}
+		if !matched {
+			continue
+		}
+		sort.Strings(flow.ErrorTypes)
+		result = append(result, flow)
+	}
+	sort.SliceStable(result, func(i, j int) bool {
+		a, b := result[i].Function, result[j].Function
+		if a.FilePath != b.FilePath {
+			return a.FilePath < b.FilePath
+		}
+		return a.StartLine < b.StartLine
+	})
+	return result, nil
+}
+
+// declaredErrorTypes returns the Rust functions and methods whose return
+// type is a Result with a known error type, mapped to that type.
+func (q *QueryBuilder) declaredErrorTypes() (map[int64]string, error) {
+	query := `SELECT p.symbol_id, p.type_expr FROM function_parameters p
+		 JOIN symbols s ON s.id = p.symbol_id
+		 JOIN files f ON f.id = s.file_id
+		 WHERE p.is_return AND p.type_expr IS NOT NULL AND f.language = 'rust'`
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		query += " AND " + clause
+	}
+	rows, err := q.store.DB().Query(query)
+	if err != nil {
+		return nil, fmt.Errorf("declared error types: %w", err)
+	}
+	defer rows.Close()
+
+	declared := map[int64]string{}
+	for rows.Next() {
+		var id int64
+		var typeExpr string
+		if err := rows.Scan(&id, &typeExpr); err != nil {
+			return nil, fmt.Errorf("declared error types: scan: %w", err)
+		}
+		if errorType := resultErrorType(typeExpr); errorType != "" {
+			declared[id] = errorType
+		}
+	}
+	return declared, rows.Err()
+}
+
+// resultErrorType returns the error type of a Result type expression:
+// E for Result<T, E>, io::Error for io::Result<T>, fmt::Error for
+// fmt::Result. It returns "" for other types and for a bare Result<T>,
+// whose alias could live anywhere.
+func resultErrorType(typeExpr string) string {
+	t := normalizeTypeExpr(typeExpr)
+	head, args := t, ""
+	if i := strings.IndexByte(t, '<'); i >= 0 && strings.HasSuffix(t, ">") {
+		head, args = t[:i], t[i+1:len(t)-1]
+	}
+	qualifier, name := "", head
+	if i := strings.LastIndex(head, "::"); i >= 0 {
+		qualifier, name = head[:i], head[i+2:]
+	}
+	if name != "Result" {
+		return ""
+	}
+	if params := splitTypeList(args); len(params) >= 2 {
+		return params[1]
+	}
+	if qualifier != "" && qualifier != "std::result" && qualifier != "core::result" {
+		return qualifier + "::Error"
+	}
+	return ""
+}
+
+// stdErrorType returns the error type of a std function or method by its
+// stub path, or "" if it is not in rustStdErrorTypes.
+func stdErrorType(path string) string {
+	for _, e := range rustStdErrorTypes {
+		if path == e.path || strings.HasSuffix(e.path, "::") && strings.HasPrefix(path, e.path) {
+			return e.errorType
+		}
+	}
+	return ""
+}
+
+// errorTypeMatches reports whether two error type paths name the same type
+// when one is a path suffix of the other, as io::Error and std::io::Error.
+func errorTypeMatches(want, got string) bool {
+	want = normalizeTypeExpr(want)
+	return got == want || strings.HasSuffix(got, "::"+want) || strings.HasSuffix(want, "::"+got)
+}
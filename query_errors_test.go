@@ -0,0 +1,99 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestResultErrorType(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, "MyError", resultErrorType("Result<u32, MyError>"))
+	assert.Equal(t, "Box<dyn Error + Send>", resultErrorType("Result<Vec<(u8, u8)>, Box<dyn Error + Send>>"))
+	assert.Equal(t, "io::Error", resultErrorType("io::Result<()>"))
+	assert.Equal(t, "std::io::Error", resultErrorType("std::io::Result<String>"))
+	assert.Equal(t, "fmt::Error", resultErrorType("fmt::Result"))
+	assert.Equal(t, "E", resultErrorType("std::result::Result<T, E>"))
+	assert.Equal(t, "", resultErrorType("Result<u32>"), "a bare alias could live anywhere")
+	assert.Equal(t, "", resultErrorType("Option<u32>"))
+}
+
+func TestErrorTypeMatches(t *testing.T) {
+	t.Parallel()
+
+	assert.True(t, errorTypeMatches("io::Error", "std::io::Error"))
+	assert.True(t, errorTypeMatches("std::io::Error", "io::Error"))
+	assert.True(t, errorTypeMatches("ConfigError", "ConfigError"))
+	assert.False(t, errorTypeMatches("Error", "MyError"))
+	assert.False(t, errorTypeMatches("io::Error", "fmt::Error"))
+}
+
+func TestErrorFlows(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	dir := t.TempDir()
+	path := filepath.Join(dir, "config.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`use std::env::var;
+use std::io;
+
+pub struct ConfigError;
+
+fn home() -> Result<String, std::env::VarError> {
+    let h = var("HOME")?;
+    Ok(h)
+}
+
+fn read_config(path: &str) -> io::Result<String> {
+    Ok(path.to_string())
+}
+
+pub fn load(path: &str) -> Result<String, ConfigError> {
+    let dir = home()?;
+    let text = read_config(path)?;
+    Ok(text)
+}
+
+pub fn lenient(path: &str) -> Option<String> {
+    read_config(path).ok()
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	type found struct {
+		name, declared string
+		errorTypes     []string
+	}
+	flows := func(filter ErrorFlowFilter) []found {
+		result, err := e.Query().ErrorFlows(filter)
+		require.NoError(t, err)
+		var got []found
+		for _, f := range result {
+			got = append(got, found{f.Function.Name, f.Declared, f.ErrorTypes})
+		}
+		return got
+	}
+
+	assert.Equal(t, []found{
+		{"home", "std::env::VarError", []string{"std::env::VarError"}},
+		{"read_config", "io::Error", []string{"io::Error"}},
+		{"load", "ConfigError", []string{"ConfigError", "io::Error", "std::env::VarError"}},
+	}, flows(ErrorFlowFilter{}), "lenient neither returns a Result nor propagates")
+
+	assert.Equal(t, []found{
+		{"read_config", "io::Error", []string{"io::Error"}},
+		{"load", "ConfigError", []string{"ConfigError", "io::Error", "std::env::VarError"}},
+	}, flows(ErrorFlowFilter{ErrorType: "std::io::Error"}))
+
+	assert.Equal(t, []found{
+		{"load", "ConfigError", []string{"ConfigError", "io::Error", "std::env::VarError"}},
+	}, flows(ErrorFlowFilter{ErrorType: "VarError", PublicOnly: true}))
+
+	assert.Empty(t, flows(ErrorFlowFilter{PathPrefix: filepath.Join(dir, "other")}))
+}
@@ -0,0 +1,178 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+)
+
+// maxExampleLines bounds the statement taken around a reference; longer
+// statements make poor examples and are skipped.
+const maxExampleLines = 8
+
+// UsageExample is a statement from the index that uses a symbol.
+type UsageExample struct {
+	File        string // path relative to the indexed root
+	Language    string
+	Line        int    // 0-based first line of Code
+	Code        string // the statement containing the reference, dedented
+	Call        bool   // the reference is a call (or macro invocation) of the symbol
+	Occurrences int    // sites with the same code up to literals and whitespace
+}
+
+// UsageExamples is a symbol with examples of its use.
+type UsageExamples struct {
+	Symbol   SymbolResult
+	Examples []UsageExample // simplest first
+}
+
+var (
+	exampleStringLiteral = regexp.MustCompile(`"(?:[^"\\]|\\.)*"`)
+	exampleNumber        = regexp.MustCompile(`\b[0-9][0-9_.]*[a-z0-9]*\b`)
+	exampleSpace         = regexp.MustCompile(`\s+`)
+)
+
+// UsageExamples returns real uses of symbolID from the index, for
+// documentation: the statement around each reference other than imports
+// and type annotations, starting at the reference's line and running until
+// its brackets close. Uses that differ only in literals and whitespace are
+// one example, with the number of sites. Calls come first, then shorter
+// statements, then the more common, so the simplest call leads. References
+// inside the symbol's own definition and statements longer than
+// maxExampleLines lines are skipped. Returns nil, nil if the symbol does
+// not exist.
+func (q *QueryBuilder) UsageExamples(symbolID int64) (*UsageExamples, error) {
+	sr, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("usage examples: %w", err)
+	}
+	if sr == nil {
+		return nil, nil
+	}
+
+	query := `SELECT f.path, r.start_line, MAX(COALESCE(r.context, '') IN ('call', 'macro')) FROM references_ r
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN files f ON f.id = r.file_id
+		 WHERE rr.target_symbol_id = ?
+		 AND COALESCE(r.context, '') NOT IN ('import', 'type_annotation')
+		 AND NOT (f.path = ? AND r.start_line >= ? AND r.end_line <= ?)`
+	if q.excludeTests {
+		query += ` AND NOT ` + testCodeSQL("r", "f")
+	}
+	rows, err := q.store.DB().Query(query+` GROUP BY f.path, r.start_line ORDER BY f.path, r.start_line`, symbolID, sr.FilePath, sr.StartLine, sr.EndLine)
+	if err != nil {
+		return nil, fmt.Errorf("usage examples: %w", err)
+	}
+	type site struct {
+		path string
+		line int
+		call bool
+	}
+	var sites []site
+	for rows.Next() {
+		var s site
+		var call int
+		if err := rows.Scan(&s.path, &s.line, &call); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("usage examples: scan: %w", err)
+		}
+		s.call = call == 1
+		sites = append(sites, s)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("usage examples: %w", err)
+	}
+
+	files := map[string]*snippetFile{}
+	index := map[string]int{}
+	result := &UsageExamples{Symbol: *sr, Examples: []UsageExample{}}
+	for _, s := range sites {
+		sf, err := q.snippetFile(files, s.path)
+		if err != nil {
+			return nil, fmt.Errorf("usage examples: %w", err)
+		}
+		code, ok := exampleStatement(sf.li, s.line)
+		if !ok {
+			continue
+		}
+		key := exampleSpace.ReplaceAllString(exampleNumber.ReplaceAllString(exampleStringLiteral.ReplaceAllString(code, `""`), "0"), " ")
+		if i, ok := index[key]; ok {
+			result.Examples[i].Occurrences++
+			result.Examples[i].Call = result.Examples[i].Call || s.call
+			continue
+		}
+		index[key] = len(result.Examples)
+		result.Examples = append(result.Examples, UsageExample{
+			File:        sf.rel,
+			Language:    sf.language,
+			Line:        s.line,
+			Code:        code,
+			Call:        s.call,
+			Occurrences: 1,
+		})
+	}
+	sort.SliceStable(result.Examples, func(i, j int) bool {
+		a, b := result.Examples[i], result.Examples[j]
+		if a.Call != b.Call {
+			return a.Call
+		}
+		if la, lb := strings.Count(a.Code, "\n"), strings.Count(b.Code, "\n"); la != lb {
+			return la < lb
+		}
+		if len(a.Code) != len(b.Code) {
+			return len(a.Code) < len(b.Code)
+		}
+		return a.Occurrences > b.Occurrences
+	})
+	return result, nil
+}
+
+// exampleStatement returns the statement starting on line: that line and
+// the following ones until the brackets opened on it close, dedented. ok
+// is false if they do not close within maxExampleLines lines.
+func exampleStatement(li *lineIndex, line int) (code string, ok bool) {
+	var lines []string
+	depth := 0
+	for l := line; l < line+maxExampleLines && l < len(li.starts); l++ {
+		end := len(li.content)
+		if l+1 < len(li.starts) {
+			end = li.starts[l+1] - 1
+		}
+		text := string(li.content[li.starts[l]:end])
+		lines = append(lines, text)
+		for _, c := range text {
+			switch c {
+			case '(', '[', '{':
+				depth++
+			case ')', ']', '}':
+				depth--
+			}
+		}
+		if depth <= 0 {
+			return strings.TrimSpace(dedent(strings.Join(lines, "\n"))), true
+		}
+	}
+	return "", false
+}
+
+// Markdown renders the examples for a documentation page: a heading with
+// the symbol's name, then each example in a fenced code block under a
+// comment with its location.
+func (u *UsageExamples) Markdown() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "### Examples: %s\n", u.Symbol.Name)
+	for _, ex := range u.Examples {
+		comment := "//"
+		if hashCommentLanguages[ex.Language] {
+			comment = "#"
+		}
+		fmt.Fprintf(&b, "\n```%s\n%s %s:%d", ex.Language, comment, ex.File, ex.Line+1)
+		if ex.Occurrences > 1 {
+			fmt.Fprintf(&b, " (and %d similar)", ex.Occurrences-1)
+		}
+		fmt.Fprintf(&b, "\n%s\n```\n", ex.Code)
+	}
+	return b.String()
+}
@@ -0,0 +1,68 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestUsageExamples(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`pub mod app;
+
+pub fn connect(host: &str, port: u16) -> bool {
+    if port == 0 { return connect(host, 80); }
+    !host.is_empty()
+}
+`), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "app.rs"), []byte(`use crate::connect;
+
+pub fn start() {
+    let ok = connect(
+        "db",
+        5432,
+    );
+    connect("localhost", 8080);
+    connect("example.com",  443);
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	sym, err := q.SymbolAt(filepath.Join(root, "src", "lib.rs"), 2, 7)
+	require.NoError(t, err)
+	require.NotNil(t, sym)
+	ex, err := q.UsageExamples(sym.ID)
+	require.NoError(t, err)
+	require.NotNil(t, ex)
+	assert.Equal(t, []UsageExample{
+		{File: "src/app.rs", Language: "rust", Line: 7, Code: `connect("localhost", 8080);`, Call: true, Occurrences: 2},
+		{File: "src/app.rs", Language: "rust", Line: 3, Code: "let ok = connect(\n    \"db\",\n    5432,\n);", Call: true, Occurrences: 1},
+	}, ex.Examples, "the import and the recursive call are not examples")
+
+	md := ex.Markdown()
+	assert.Contains(t, md, "### Examples: connect\n")
+	assert.Contains(t, md, "```rust\n// src/app.rs:8 (and 1 similar)\nconnect(\"localhost\", 8080);\n```\n")
+
+	missing, err := q.UsageExamples(-1)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
+
+func TestExampleStatement(t *testing.T) {
+	li := newLineIndex([]byte("    foo(1,\n        2);\n    bar(\n"))
+	code, ok := exampleStatement(li, 0)
+	assert.True(t, ok)
+	assert.Equal(t, "foo(1,\n    2);", code)
+	_, ok = exampleStatement(li, 2)
+	assert.False(t, ok, "unclosed at the end of the file")
+}
@@ -0,0 +1,272 @@
+package canopy
+
+import (
+	"database/sql"
+	"fmt"
+	"strings"
+)
+
+// Explanation says why a reference resolved to its target: what the
+// reference is, the scopes enclosing it, how the resolver bound its name,
+// and where the target is declared. It is the answer to "why does this
+// result show up?" for references, definitions, and call edges.
+type Explanation struct {
+	ReferenceID    int64
+	Name           string // the name as written at the reference
+	Kind           string // one of the RefKind constants, or "" if unclassified
+	Reference      Location
+	TargetSymbolID int64
+	TargetName     string
+	TargetKind     string
+	Target         *Location // nil for a stub without source
+	ResolutionKind string    // how the resolver bound the name: direct, import, alias, ffi, sql, ...
+	Confidence     string    // one of the Confidence constants
+	// Import is the import of the reference's file that brings the name
+	// into scope, for references resolved through an import.
+	Import *ExplainedImport
+	// Scopes are the scopes enclosing the reference, innermost first, which
+	// the resolver searched for the name.
+	Scopes []ExplainedScope
+}
+
+// ExplainedImport is an import statement of an Explanation.
+type ExplainedImport struct {
+	Source       string
+	ImportedName string
+	LocalAlias   string
+	Kind         string
+}
+
+// ExplainedScope is one scope enclosing an explained reference.
+type ExplainedScope struct {
+	Kind       string // file, module, function, block, ...
+	SymbolID   *int64 // the symbol the scope belongs to, if any
+	SymbolName string
+	StartLine  int
+	EndLine    int
+}
+
+// ExplainReference explains why the reference with ID referenceID resolved
+// to the symbol targetSymbolID. It returns nil if the reference does not
+// resolve to that symbol.
+func (q *QueryBuilder) ExplainReference(referenceID, targetSymbolID int64) (*Explanation, error) {
+	ex := &Explanation{ReferenceID: referenceID, TargetSymbolID: targetSymbolID}
+	var scopeID sql.NullInt64
+	var fileID int64
+	var resolutionKind sql.NullString
+	var confidence float64
+	err := q.store.DB().QueryRow(
+		`SELECT r.name, COALESCE(r.context, ''), r.file_id, r.scope_id, f.path,
+			r.start_line, r.start_col, r.end_line, r.end_col,
+			r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col,
+			rr.resolution_kind, COALESCE(rr.confidence, 1.0), s.name, s.kind
+		 FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 JOIN files f ON f.id = r.file_id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 WHERE rr.reference_id = ? AND rr.target_symbol_id = ?
+		 ORDER BY rr.id LIMIT 1`, referenceID, targetSymbolID,
+	).Scan(&ex.Name, &ex.Kind, &fileID, &scopeID, &ex.Reference.File,
+		&ex.Reference.StartLine, &ex.Reference.StartCol, &ex.Reference.EndLine, &ex.Reference.EndCol,
+		&ex.Reference.StartByte, &ex.Reference.EndByte, &ex.Reference.UTF16StartCol, &ex.Reference.UTF16EndCol,
+		&resolutionKind, &confidence, &ex.TargetName, &ex.TargetKind)
+	if err == sql.ErrNoRows {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("explain reference: %w", err)
+	}
+	ex.ResolutionKind = resolutionKind.String
+	ex.Confidence = ConfidenceLevel(confidence)
+
+	if ex.Target, err = q.symbolLocation(targetSymbolID); err != nil {
+		return nil, fmt.Errorf("explain reference: target location: %w", err)
+	}
+	if scopeID.Valid {
+		if ex.Scopes, err = q.scopeChain(scopeID.Int64); err != nil {
+			return nil, fmt.Errorf("explain reference: scopes: %w", err)
+		}
+	}
+	if ex.ResolutionKind == "import" || ex.Kind == RefKindImport {
+		if ex.Import, err = q.explainImport(fileID, ex.Name, ex.TargetName); err != nil {
+			return nil, fmt.Errorf("explain reference: import: %w", err)
+		}
+	}
+	return ex, nil
+}
+
+// scopeChain returns the scope scopeID and its ancestors, innermost first.
+func (q *QueryBuilder) scopeChain(scopeID int64) ([]ExplainedScope, error) {
+	rows, err := q.store.DB().Query(
+		`WITH RECURSIVE chain(id, parent_scope_id, symbol_id, kind, start_line, end_line, depth) AS (
+			SELECT id, parent_scope_id, symbol_id, kind, start_line, end_line, 0 FROM scopes WHERE id = ?
+			UNION ALL
+			SELECT s.id, s.parent_scope_id, s.symbol_id, s.kind, s.start_line, s.end_line, c.depth + 1
+			FROM scopes s JOIN chain c ON s.id = c.parent_scope_id
+		 )
+		 SELECT c.kind, c.symbol_id, COALESCE(sym.name, ''), COALESCE(c.start_line, 0), COALESCE(c.end_line, 0)
+		 FROM chain c LEFT JOIN symbols sym ON sym.id = c.symbol_id
+		 ORDER BY c.depth`, scopeID)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var scopes []ExplainedScope
+	for rows.Next() {
+		var sc ExplainedScope
+		var symbolID sql.NullInt64
+		if err := rows.Scan(&sc.Kind, &symbolID, &sc.SymbolName, &sc.StartLine, &sc.EndLine); err != nil {
+			return nil, err
+		}
+		if symbolID.Valid {
+			sc.SymbolID = &symbolID.Int64
+		}
+		scopes = append(scopes, sc)
+	}
+	return scopes, rows.Err()
+}
+
+// explainImport returns the import of the file fileID that binds name,
+// preferring one that names target, or nil if none does.
+func (q *QueryBuilder) explainImport(fileID int64, name, target string) (*ExplainedImport, error) {
+	var imp ExplainedImport
+	err := q.store.DB().QueryRow(
+		`SELECT source, COALESCE(imported_name, ''), COALESCE(local_alias, ''), COALESCE(kind, '')
+		 FROM imports
+		 WHERE file_id = ? AND (local_alias = ? OR imported_name = ? OR imported_name = ?)
+		 ORDER BY local_alias = ? DESC, imported_name = ? DESC, id LIMIT 1`,
+		fileID, name, name, target, name, target,
+	).Scan(&imp.Source, &imp.ImportedName, &imp.LocalAlias, &imp.Kind)
+	if err == sql.ErrNoRows {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	return &imp, nil
+}
+
+// ExplainDefinitionAt explains each result DefinitionAt returns for the
+// position: one Explanation per reference at the position and target it
+// resolves to.
+func (q *QueryBuilder) ExplainDefinitionAt(file string, line, col int) ([]Explanation, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT rr.reference_id, rr.target_symbol_id
+		 FROM references_ r
+		 JOIN files f ON f.id = r.file_id
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 WHERE f.path = ? AND r.start_line <= ? AND r.end_line >= ?
+		   AND (r.start_line < ? OR (r.start_line = ? AND r.start_col <= ?))
+		   AND (r.end_line > ? OR (r.end_line = ? AND r.end_col >= ?))
+		   AND COALESCE(rr.confidence, 1.0) >= ?
+		 ORDER BY r.id, rr.id`,
+		file, line, line, line, line, col, line, line, col, q.minConfidence)
+	if err != nil {
+		return nil, fmt.Errorf("explain definition: %w", err)
+	}
+	return q.explainPairs(rows)
+}
+
+// ExplainCall explains a call edge by the references at its call site that
+// resolved to the callee. Edges that no reference accounts for, such as
+// those linked across FFI without a call site, have no explanations.
+func (q *QueryBuilder) ExplainCall(edge *CallEdge) ([]Explanation, error) {
+	if edge.FileID == nil {
+		return nil, nil
+	}
+	rows, err := q.store.DB().Query(
+		`SELECT rr.reference_id, rr.target_symbol_id
+		 FROM references_ r
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 WHERE r.file_id = ? AND r.start_line = ? AND rr.target_symbol_id = ?
+		 ORDER BY r.start_col = ? DESC, r.id, rr.id`,
+		*edge.FileID, edge.Line, edge.CalleeSymbolID, edge.Col)
+	if err != nil {
+		return nil, fmt.Errorf("explain call: %w", err)
+	}
+	return q.explainPairs(rows)
+}
+
+// explainPairs explains each (reference_id, target_symbol_id) row.
+func (q *QueryBuilder) explainPairs(rows *sql.Rows) ([]Explanation, error) {
+	type pair struct{ ref, target int64 }
+	var pairs []pair
+	for rows.Next() {
+		var p pair
+		if err := rows.Scan(&p.ref, &p.target); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		pairs = append(pairs, p)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	var out []Explanation
+	for _, p := range pairs {
+		ex, err := q.ExplainReference(p.ref, p.target)
+		if err != nil {
+			return nil, err
+		}
+		if ex != nil {
+			out = append(out, *ex)
+		}
+	}
+	return out, nil
+}
+
+// Steps describes the explanation as a chain of short sentences, from the
+// reference to its target. Lines are 0-based, like every position in the
+// index.
+func (ex *Explanation) Steps() []string {
+	kind := ex.Kind
+	if kind == "" {
+		kind = "unclassified"
+	}
+	steps := []string{fmt.Sprintf("%q at %s:%d:%d is a %s reference",
+		ex.Name, ex.Reference.File, ex.Reference.StartLine, ex.Reference.StartCol, kind)}
+
+	if len(ex.Scopes) > 0 {
+		var chain []string
+		for _, sc := range ex.Scopes {
+			if sc.SymbolName != "" {
+				chain = append(chain, sc.Kind+" "+sc.SymbolName)
+			} else {
+				chain = append(chain, sc.Kind)
+			}
+		}
+		steps = append(steps, "searched scopes: "+strings.Join(chain, " > "))
+	}
+
+	switch ex.ResolutionKind {
+	case "import":
+		if ex.Import != nil {
+			imported := ex.Import.ImportedName
+			if ex.Import.LocalAlias != "" && ex.Import.LocalAlias != imported {
+				imported += " as " + ex.Import.LocalAlias
+			}
+			steps = append(steps, fmt.Sprintf("bound through the import of %s from %s", imported, ex.Import.Source))
+		} else {
+			steps = append(steps, "bound through an import")
+		}
+	case "alias":
+		steps = append(steps, "bound through a type or import alias")
+	case "direct", "":
+		steps = append(steps, "bound directly to a declaration visible from these scopes")
+	case RefKindFFI:
+		steps = append(steps, "linked across the FFI boundary by its link name")
+	case RefKindSQL:
+		steps = append(steps, "matched a table or column named in embedded SQL")
+	default:
+		steps = append(steps, "bound by "+ex.ResolutionKind+" resolution")
+	}
+
+	target := fmt.Sprintf("resolved to %s %s (#%d)", ex.TargetKind, ex.TargetName, ex.TargetSymbolID)
+	if ex.Target != nil {
+		target += fmt.Sprintf(" at %s:%d:%d", ex.Target.File, ex.Target.StartLine, ex.Target.StartCol)
+	} else {
+		target += ", a stub without source"
+	}
+	return append(steps, target+", confidence "+ex.Confidence)
+}
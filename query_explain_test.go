@@ -0,0 +1,88 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestExplainReference_ImportThroughScopes(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	viewID := insertFile(t, s, "/view.ts", "typescript")
+	mainID := insertFile(t, s, "/main.ts", "typescript")
+	render := insertSymbol(t, s, &viewID, "render", "function", "public", nil)
+	start := insertSymbol(t, s, &mainID, "start", "function", "public", nil)
+
+	fileScope, err := s.InsertScope(&store.Scope{FileID: mainID, Kind: "file", EndLine: 9})
+	require.NoError(t, err)
+	fnScope, err := s.InsertScope(&store.Scope{FileID: mainID, SymbolID: &start, Kind: "function", StartLine: 1, EndLine: 4, ParentScopeID: &fileScope})
+	require.NoError(t, err)
+	imported, alias := "render", "draw"
+	_, err = s.InsertImport(&store.Import{FileID: mainID, Source: "./view", ImportedName: &imported, LocalAlias: &alias, Kind: "named", Scope: "file"})
+	require.NoError(t, err)
+	refID, err := s.InsertReference(&store.Reference{
+		FileID: mainID, ScopeID: &fnScope, Name: "draw", Context: RefKindCall,
+		StartLine: 2, StartCol: 2, EndLine: 2, EndCol: 6,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertResolvedReference(&store.ResolvedReference{
+		ReferenceID: refID, TargetSymbolID: render, Confidence: 1.0, ResolutionKind: "import",
+	})
+	require.NoError(t, err)
+
+	ex, err := q.ExplainReference(refID, render)
+	require.NoError(t, err)
+	require.NotNil(t, ex)
+	assert.Equal(t, "draw", ex.Name)
+	assert.Equal(t, RefKindCall, ex.Kind)
+	assert.Equal(t, "/main.ts", ex.Reference.File)
+	assert.Equal(t, "import", ex.ResolutionKind)
+	assert.Equal(t, ConfidenceExact, ex.Confidence)
+	require.NotNil(t, ex.Target)
+	assert.Equal(t, "/view.ts", ex.Target.File)
+	require.NotNil(t, ex.Import)
+	assert.Equal(t, "./view", ex.Import.Source)
+	assert.Equal(t, "draw", ex.Import.LocalAlias)
+	require.Len(t, ex.Scopes, 2)
+	assert.Equal(t, "function", ex.Scopes[0].Kind)
+	assert.Equal(t, "start", ex.Scopes[0].SymbolName)
+	assert.Equal(t, "file", ex.Scopes[1].Kind)
+	assert.Nil(t, ex.Scopes[1].SymbolID)
+
+	steps := ex.Steps()
+	require.Len(t, steps, 4)
+	assert.Equal(t, `"draw" at /main.ts:2:2 is a call reference`, steps[0])
+	assert.Equal(t, "searched scopes: function start > file", steps[1])
+	assert.Equal(t, "bound through the import of render as draw from ./view", steps[2])
+	assert.Contains(t, steps[3], "resolved to function render")
+
+	// A target the reference doesn't resolve to has no explanation.
+	ex, err = q.ExplainReference(refID, start)
+	require.NoError(t, err)
+	assert.Nil(t, ex)
+
+	defs, err := q.ExplainDefinitionAt("/main.ts", 2, 4)
+	require.NoError(t, err)
+	require.Len(t, defs, 1)
+	assert.Equal(t, refID, defs[0].ReferenceID)
+
+	_, err = s.InsertCallEdge(&store.CallEdge{
+		CallerSymbolID: start, CalleeSymbolID: render, FileID: &mainID, Line: 2, Col: 2, Confidence: 1.0,
+	})
+	require.NoError(t, err)
+	callers, err := q.Callers(render)
+	require.NoError(t, err)
+	require.Len(t, callers, 1)
+	calls, err := q.ExplainCall(callers[0])
+	require.NoError(t, err)
+	require.Len(t, calls, 1)
+	assert.Equal(t, refID, calls[0].ReferenceID)
+
+	sites, err := q.ReferencesToPaged(render, ReferenceFilter{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, sites.Items, 1)
+	assert.Equal(t, refID, sites.Items[0].ReferenceID)
+}
@@ -0,0 +1,310 @@
+package canopy
+
+import (
+	"fmt"
+	"path/filepath"
+	"slices"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// FeatureGatedItem is a public item that is compiled in only under a cfg
+// condition naming Cargo features.
+type FeatureGatedItem struct {
+	Symbol SymbolResult
+	// Condition is the cfg predicate gating the item, as written:
+	// feature = "serde". The gates of enclosing modules, the parent type, and
+	// the impl block are combined with the item's own in all(...), outermost
+	// first, with equivalent predicates listed once.
+	Condition string
+}
+
+// FeatureAPI is the public API a Cargo feature gates.
+type FeatureAPI struct {
+	Feature string
+	Items   []FeatureGatedItem // ordered by file then line
+}
+
+// featureGatedKinds are the symbol kinds FeatureGatedAPI reports; fields and
+// variants follow their type.
+var featureGatedKinds = map[string]bool{
+	"function":   true,
+	"method":     true,
+	"struct":     true,
+	"enum":       true,
+	"trait":      true,
+	"type_alias": true,
+	"constant":   true,
+	"variable":   true,
+	"module":     true,
+}
+
+// FeatureGatedAPI maps each Cargo feature to the public Rust items that are
+// only available under it, in files under pathPrefix ("" for all files),
+// ordered by feature. An item is gated by its own #[cfg(...)] attributes,
+// those of its parent type and impl block, and those of the modules
+// enclosing it, inline or declared with mod name; in a gated file. An item
+// whose condition names several features, as in all(...) or any(...), is
+// listed under each; features that appear only under not(...) are not.
+func (q *QueryBuilder) FeatureGatedAPI(pathPrefix string) ([]FeatureAPI, error) {
+	gates, err := q.cfgGates()
+	if err != nil {
+		return nil, fmt.Errorf("feature gated api: %w", err)
+	}
+	result := []FeatureAPI{}
+	if len(gates) == 0 {
+		return result, nil
+	}
+
+	query := `SELECT s.id, s.name, s.kind, COALESCE(s.visibility, ''), COALESCE(s.parent_symbol_id, 0), s.file_id, f.path,
+			s.start_line, s.start_col, s.end_line, s.end_col
+		 FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 WHERE f.language = 'rust'`
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		query += " AND " + clause
+	}
+	rows, err := q.store.DB().Query(query)
+	if err != nil {
+		return nil, fmt.Errorf("feature gated api: %w", err)
+	}
+	type item struct {
+		id, parentID, fileID int64
+		name, kind, vis      string
+		path                 string
+		loc                  Location
+	}
+	var items []item
+	byID := map[int64]*item{}
+	for rows.Next() {
+		var it item
+		if err := rows.Scan(&it.id, &it.name, &it.kind, &it.vis, &it.parentID, &it.fileID, &it.path,
+			&it.loc.StartLine, &it.loc.StartCol, &it.loc.EndLine, &it.loc.EndCol); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("feature gated api: scan: %w", err)
+		}
+		items = append(items, it)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("feature gated api: %w", err)
+	}
+	for i := range items {
+		byID[items[i].id] = &items[i]
+	}
+
+	// Gated modules, with the file and directory a mod name; declaration
+	// maps to: name.rs and name/ next to lib.rs, main.rs, or mod.rs, and in
+	// a directory named after any other file.
+	type gatedModule struct {
+		item
+		modFile, modDir string
+	}
+	var modules []gatedModule
+	for _, it := range items {
+		if it.kind != "module" || gates[it.id] == nil {
+			continue
+		}
+		dir := filepath.Dir(it.path)
+		if stem := strings.TrimSuffix(filepath.Base(it.path), ".rs"); stem != "lib" && stem != "main" && stem != "mod" {
+			dir = filepath.Join(dir, stem)
+		}
+		modules = append(modules, gatedModule{
+			item:    it,
+			modFile: filepath.Join(dir, it.name+".rs"),
+			modDir:  filepath.Join(dir, it.name) + string(filepath.Separator),
+		})
+	}
+	// Nested modules start later in a file, and an enclosing module's
+	// directory is shorter.
+	sort.SliceStable(modules, func(i, j int) bool {
+		return len(modules[i].modDir) < len(modules[j].modDir)
+	})
+
+	byFeature := map[string][]FeatureGatedItem{}
+	var ids []int64
+	conditions := map[int64]string{}
+	features := map[int64][]string{}
+	for _, it := range items {
+		if it.vis != "public" || !featureGatedKinds[it.kind] || !strings.HasPrefix(it.path, pathPrefix) {
+			continue
+		}
+		// Outermost first: the gates of the item's file, then of the inline
+		// modules around it, then of its parent and itself.
+		var preds []string
+		var inline []gatedModule
+		for _, m := range modules {
+			if it.path == m.modFile || strings.HasPrefix(it.path, m.modDir) {
+				preds = append(preds, gates[m.id]...)
+			} else if m.fileID == it.fileID && m.id != it.id && spanContains(m.loc, it.loc) {
+				inline = append(inline, m)
+			}
+		}
+		sort.SliceStable(inline, func(i, j int) bool { return spanContains(inline[i].loc, inline[j].loc) })
+		for _, m := range inline {
+			preds = append(preds, gates[m.id]...)
+		}
+		var ancestors [][]string
+		for p, depth := byID[it.parentID], 0; p != nil && depth < 32; p, depth = byID[p.parentID], depth+1 {
+			ancestors = append(ancestors, gates[p.id])
+		}
+		for i := len(ancestors) - 1; i >= 0; i-- {
+			preds = append(preds, ancestors[i]...)
+		}
+		preds = append(preds, gates[it.id]...)
+
+		var unique []string
+		seen := map[string]bool{}
+		for _, p := range preds {
+			if key := store.CanonicalCfg(p); !seen[key] {
+				seen[key] = true
+				unique = append(unique, p)
+			}
+		}
+		if len(unique) == 0 {
+			continue
+		}
+		condition := unique[0]
+		if len(unique) > 1 {
+			condition = "all(" + strings.Join(unique, ", ") + ")"
+		}
+		if fs := cfgFeatures(condition); len(fs) > 0 {
+			ids = append(ids, it.id)
+			conditions[it.id] = condition
+			features[it.id] = fs
+		}
+	}
+	if len(ids) == 0 {
+		return result, nil
+	}
+
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("feature gated api: load symbols: %w", err)
+	}
+	for _, id := range ids {
+		sr, ok := symbols[id]
+		if !ok {
+			continue
+		}
+		for _, f := range features[id] {
+			byFeature[f] = append(byFeature[f], FeatureGatedItem{Symbol: *sr, Condition: conditions[id]})
+		}
+	}
+	for f, gated := range byFeature {
+		sort.SliceStable(gated, func(i, j int) bool {
+			a, b := gated[i].Symbol, gated[j].Symbol
+			if a.FilePath != b.FilePath {
+				return a.FilePath < b.FilePath
+			}
+			return a.StartLine < b.StartLine
+		})
+		result = append(result, FeatureAPI{Feature: f, Items: gated})
+	}
+	sort.Slice(result, func(i, j int) bool { return result[i].Feature < result[j].Feature })
+	return result, nil
+}
+
+// cfgGates returns the predicates of the #[cfg(...)] attributes on Rust
+// symbols, by symbol, in source order.
+func (q *QueryBuilder) cfgGates() (map[int64][]string, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT a.target_symbol_id, COALESCE(a.arguments, '') FROM annotations a
+		 JOIN symbols s ON s.id = a.target_symbol_id
+		 JOIN files f ON f.id = s.file_id
+		 WHERE a.name = 'cfg' AND f.language = 'rust'
+		 ORDER BY a.line, a.col`)
+	if err != nil {
+		return nil, fmt.Errorf("cfg gates: %w", err)
+	}
+	defer rows.Close()
+
+	gates := map[int64][]string{}
+	for rows.Next() {
+		var id int64
+		var args string
+		if err := rows.Scan(&id, &args); err != nil {
+			return nil, fmt.Errorf("cfg gates: scan: %w", err)
+		}
+		pred := strings.TrimSpace(args)
+		if strings.HasPrefix(pred, "(") && matchingParen(pred) == len(pred)-1 {
+			pred = strings.TrimSpace(pred[1 : len(pred)-1])
+		}
+		if pred != "" {
+			gates[id] = append(gates[id], pred)
+		}
+	}
+	return gates, rows.Err()
+}
+
+// cfgFeatures returns the Cargo features a cfg predicate names outside any
+// not(...), as in "serde" for feature = "serde", in order of appearance.
+func cfgFeatures(pred string) []string {
+	toks := cfgTokens(pred)
+	var features []string
+	negated := []bool{false}
+	for i, tok := range toks {
+		switch tok {
+		case "(":
+			neg := negated[len(negated)-1]
+			if i > 0 && toks[i-1] == "not" {
+				neg = !neg
+			}
+			negated = append(negated, neg)
+		case ")":
+			if len(negated) > 1 {
+				negated = negated[:len(negated)-1]
+			}
+		default:
+			if !strings.HasPrefix(tok, `"`) || i < 2 || toks[i-1] != "=" || toks[i-2] != "feature" || negated[len(negated)-1] {
+				continue
+			}
+			if name := strings.Trim(tok, `"`); !slices.Contains(features, name) {
+				features = append(features, name)
+			}
+		}
+	}
+	return features
+}
+
+// cfgTokens splits a cfg predicate into words, string literals (quotes
+// kept), and single punctuation characters.
+func cfgTokens(s string) []string {
+	var toks []string
+	for i := 0; i < len(s); {
+		c := s[i]
+		switch {
+		case c == ' ' || c == '\t' || c == '\n' || c == '\r':
+			i++
+		case c == '"':
+			end := strings.IndexByte(s[i+1:], '"')
+			if end < 0 {
+				return append(toks, s[i:])
+			}
+			toks = append(toks, s[i:i+end+2])
+			i += end + 2
+		case isTypeWordByte(c):
+			j := i
+			for j < len(s) && isTypeWordByte(s[j]) {
+				j++
+			}
+			toks = append(toks, s[i:j])
+			i = j
+		default:
+			toks = append(toks, s[i:i+1])
+			i++
+		}
+	}
+	return toks
+}
+
+// spanContains reports whether outer encloses inner.
+func spanContains(outer, inner Location) bool {
+	startsBefore := outer.StartLine < inner.StartLine ||
+		outer.StartLine == inner.StartLine && outer.StartCol <= inner.StartCol
+	endsAfter := outer.EndLine > inner.EndLine ||
+		outer.EndLine == inner.EndLine && outer.EndCol >= inner.EndCol
+	return startsBefore && endsAfter
+}
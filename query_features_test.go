@@ -0,0 +1,110 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCfgFeatures(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, []string{"serde"}, cfgFeatures(`feature = "serde"`))
+	assert.Equal(t, []string{"a", "b"}, cfgFeatures(`all(feature = "a", any(feature="b", unix), feature = "a")`))
+	assert.Equal(t, []string{"b"}, cfgFeatures(`all(not(feature = "a"), feature = "b")`))
+	assert.Equal(t, []string{"a"}, cfgFeatures(`not(not(feature = "a"))`))
+	assert.Empty(t, cfgFeatures(`not(feature = "std")`))
+	assert.Empty(t, cfgFeatures(`target_feature = "avx2"`))
+	assert.Empty(t, cfgFeatures("unix"))
+}
+
+func TestFeatureGatedAPI(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	src := filepath.Join(t.TempDir(), "src")
+	require.NoError(t, os.MkdirAll(filepath.Join(src, "net"), 0755))
+	files := map[string]string{
+		"lib.rs": `#[cfg(feature = "net")]
+pub mod net;
+
+#[cfg(feature = "serde")]
+pub struct Wire;
+
+pub struct Client;
+
+#[cfg(all(feature = "serde", feature = "json"))]
+impl Client {
+    pub fn to_json(&self) -> String {
+        String::new()
+    }
+}
+
+impl Client {
+    pub fn new() -> Client {
+        Client
+    }
+
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    pub fn tls(&self) {}
+}
+
+#[cfg(not(feature = "std"))]
+pub fn no_std_alloc() {}
+
+#[cfg(feature = "serde")]
+fn private_helper() {}
+
+#[cfg(unix)]
+pub mod unix {
+    #[cfg(feature = "net")]
+    pub fn socket() {}
+}
+`,
+		"net.rs":     "pub fn connect() {}\n\npub mod tls;\n",
+		"net/tls.rs": "pub fn handshake() {}\n",
+	}
+	var paths []string
+	for name, content := range files {
+		path := filepath.Join(src, name)
+		require.NoError(t, os.WriteFile(path, []byte(content), 0644))
+		paths = append(paths, path)
+	}
+	require.NoError(t, e.IndexFiles(ctx, paths))
+	require.NoError(t, e.Resolve(ctx))
+
+	apis, err := e.Query().FeatureGatedAPI("")
+	require.NoError(t, err)
+
+	got := map[string][]string{}
+	var features []string
+	for _, api := range apis {
+		features = append(features, api.Feature)
+		for _, it := range api.Items {
+			got[api.Feature] = append(got[api.Feature], it.Symbol.Name+" "+it.Condition)
+		}
+	}
+	assert.Equal(t, []string{"json", "native-tls", "net", "rustls", "serde"}, features)
+	assert.Equal(t, []string{`to_json all(feature = "serde", feature = "json")`}, got["json"])
+	assert.Equal(t, []string{`tls any(feature = "rustls", feature = "native-tls")`}, got["rustls"])
+	assert.Equal(t, []string{
+		`net feature = "net"`,
+		`socket all(unix, feature = "net")`,
+		`connect feature = "net"`,
+		`tls feature = "net"`,
+		`handshake feature = "net"`,
+	}, got["net"], "a gated mod declaration gates its file and submodules")
+	assert.Equal(t, []string{
+		`Wire feature = "serde"`,
+		`to_json all(feature = "serde", feature = "json")`,
+	}, got["serde"], "private items and not(...) gates are left out")
+
+	netOnly, err := e.Query().FeatureGatedAPI(filepath.Join(src, "net"))
+	require.NoError(t, err)
+	require.Len(t, netOnly, 1)
+	assert.Len(t, netOnly[0].Items, 3, "net.rs and net/tls.rs")
+}
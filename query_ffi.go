@@ -0,0 +1,138 @@
+package canopy
+
+import (
+	"fmt"
+	"slices"
+	"sort"
+	"strings"
+)
+
+// FFI modifiers, set by Rust extraction.
+const (
+	ModifierExtern  = "extern"  // an extern fn definition, callable through a foreign ABI
+	ModifierForeign = "foreign" // a function or static declared in an extern block
+)
+
+// FFI boundary directions.
+const (
+	FFIImport = "import" // declared in an extern block and implemented outside Rust: an exit point
+	FFIExport = "export" // callable from outside Rust: an extern fn or a #[no_mangle] / #[export_name] item
+)
+
+// FFIBoundary is a symbol that crosses the FFI boundary.
+type FFIBoundary struct {
+	Symbol    SymbolResult
+	Direction string // FFIImport or FFIExport
+	ABI       string // "C", "system", ...; "" for an exported item with the Rust ABI
+	LinkName  string // the linker symbol; "" for an extern fn whose name is still mangled
+}
+
+// FFIBoundaries lists the FFI entry and exit points in files under
+// pathPrefix ("" for all files), ordered by file then line: functions and
+// statics of extern blocks, extern fn definitions, and #[no_mangle] or
+// #[export_name] items.
+func (q *QueryBuilder) FFIBoundaries(pathPrefix string) ([]FFIBoundary, error) {
+	query := `SELECT s.id, COALESCE(a.name, ''), COALESCE(a.arguments, '') FROM symbols s
+		 LEFT JOIN files f ON f.id = s.file_id
+		 LEFT JOIN annotations a ON a.target_symbol_id = s.id
+			AND a.name IN ('extern', 'no_mangle', 'export_name', 'link_name', 'unsafe')
+		 WHERE (EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value IN (?, ?))
+			OR EXISTS (SELECT 1 FROM annotations a2 WHERE a2.target_symbol_id = s.id
+				AND (a2.name IN ('no_mangle', 'export_name') OR (a2.name = 'unsafe'
+					AND (a2.arguments LIKE '%no_mangle%' OR a2.arguments LIKE '%export_name%')))))`
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		query += " AND " + clause
+	}
+	rows, err := q.store.DB().Query(query, ModifierExtern, ModifierForeign)
+	if err != nil {
+		return nil, fmt.Errorf("ffi boundaries: %w", err)
+	}
+	defer rows.Close()
+
+	type attrs struct {
+		abi, exportName, linkName string
+		noMangle                  bool
+	}
+	byID := map[int64]*attrs{}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		var name, args string
+		if err := rows.Scan(&id, &name, &args); err != nil {
+			return nil, fmt.Errorf("ffi boundaries: scan: %w", err)
+		}
+		a, ok := byID[id]
+		if !ok {
+			a = &attrs{}
+			byID[id] = a
+			ids = append(ids, id)
+		}
+		// #[unsafe(no_mangle)] and #[unsafe(export_name = "f")] (Rust 2024)
+		// wrap the attribute in unsafe(...).
+		if name == "unsafe" {
+			inner := strings.TrimSpace(strings.TrimSuffix(strings.TrimPrefix(args, "("), ")"))
+			name, args, _ = strings.Cut(inner, "=")
+			name = strings.TrimSpace(name)
+		}
+		switch name {
+		case "extern":
+			a.abi = args
+		case "no_mangle":
+			a.noMangle = true
+		case "export_name":
+			a.exportName = quotedValue(args)
+		case "link_name":
+			a.linkName = quotedValue(args)
+		}
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("ffi boundaries: %w", err)
+	}
+
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("ffi boundaries: load symbols: %w", err)
+	}
+	result := []FFIBoundary{}
+	for _, id := range ids {
+		sr, ok := symbols[id]
+		if !ok || !strings.HasPrefix(sr.FilePath, pathPrefix) {
+			continue
+		}
+		a := byID[id]
+		b := FFIBoundary{Symbol: *sr, Direction: FFIExport, ABI: a.abi}
+		switch {
+		case slices.Contains(sr.Modifiers, ModifierForeign):
+			b.Direction = FFIImport
+			b.LinkName = sr.Name
+			if a.linkName != "" {
+				b.LinkName = a.linkName
+			}
+		case a.exportName != "":
+			b.LinkName = a.exportName
+		case a.noMangle:
+			b.LinkName = sr.Name
+		}
+		result = append(result, b)
+	}
+	sort.SliceStable(result, func(i, j int) bool {
+		a, b := result[i].Symbol, result[j].Symbol
+		if a.FilePath != b.FilePath {
+			return a.FilePath < b.FilePath
+		}
+		return a.StartLine < b.StartLine
+	})
+	return result, nil
+}
+
+// quotedValue returns the text between the first pair of double quotes in s,
+// as in f for = "f", or "" if there is none.
+func quotedValue(s string) string {
+	_, rest, ok := strings.Cut(s, `"`)
+	if !ok {
+		return ""
+	}
+	value, _, _ := strings.Cut(rest, `"`)
+	return value
+}
+
@@ -0,0 +1,77 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestQuotedValue(t *testing.T) {
+	t.Parallel()
+
+	assert.Equal(t, "canopy_version", quotedValue(`"canopy_version"`))
+	assert.Equal(t, "f", quotedValue(` = "f"`))
+	assert.Equal(t, "", quotedValue("no_mangle"))
+}
+
+func TestFFIBoundaries(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	dir := t.TempDir()
+	path := filepath.Join(dir, "ffi.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`use std::os::raw::c_int;
+
+extern "C" {
+    fn strlen(s: *const u8) -> usize;
+    #[link_name = "errno_location"]
+    fn errno() -> *mut c_int;
+    static environ: *const *const u8;
+}
+
+#[no_mangle]
+pub extern "C" fn canopy_add(a: c_int, b: c_int) -> c_int {
+    a + b
+}
+
+#[export_name = "canopy_version"]
+pub fn version() -> u32 {
+    1
+}
+
+extern "system" fn callback() {}
+
+pub fn plain() -> usize {
+    unsafe { strlen(std::ptr::null()) }
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	boundaries, err := e.Query().FFIBoundaries("")
+	require.NoError(t, err)
+
+	type found struct {
+		name, direction, abi, linkName string
+	}
+	var got []found
+	for _, b := range boundaries {
+		got = append(got, found{b.Symbol.Name, b.Direction, b.ABI, b.LinkName})
+	}
+	assert.Equal(t, []found{
+		{"strlen", FFIImport, "C", "strlen"},
+		{"errno", FFIImport, "C", "errno_location"},
+		{"environ", FFIImport, "C", "environ"},
+		{"canopy_add", FFIExport, "C", "canopy_add"},
+		{"version", FFIExport, "", "canopy_version"},
+		{"callback", FFIExport, "system", ""},
+	}, got, "plain is ordinary Rust")
+
+	other, err := e.Query().FFIBoundaries(filepath.Join(dir, "other"))
+	require.NoError(t, err)
+	assert.Empty(t, other)
+}
@@ -0,0 +1,302 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// FieldEdge links a type to a type named in the declared type of one of its
+// fields: Product.price : f64, or Order.items : Vec<Item>, which names
+// both Vec and Item.
+type FieldEdge struct {
+	From     SymbolResult  // the type declaring the field
+	Field    string        // the field's name
+	TypeExpr string        // the field's declared type as written
+	Type     string        // the name of the type the edge leads to: f64, Vec, Item
+	To       *SymbolResult // the type's symbol; nil for a primitive or a type not indexed
+	Depth    int           // distance from the root: 1 for edges at the root
+}
+
+// FieldGraphType is a type reached in a field graph, at the depth it is
+// first reached.
+type FieldGraphType struct {
+	Name   string
+	Symbol *SymbolResult // nil for a primitive or a type not indexed
+	Depth  int
+}
+
+// FieldGraph is the part of the field dependency graph reachable from a
+// type, in one direction: the types it contains, or the types containing it.
+type FieldGraph struct {
+	Root  SymbolResult
+	Edges []FieldEdge      // in breadth-first order
+	Types []FieldGraphType // every type reached except the root, by depth then name
+	Depth int              // the deepest depth reached
+}
+
+// fieldMemberKinds are the type member kinds that declare a field with a
+// type: struct fields and class properties.
+var fieldMemberKinds = map[string]bool{"field": true, "property": true}
+
+// fieldTypeKinds are the symbol kinds a field's type can name.
+var fieldTypeKinds = map[string]bool{
+	"struct": true, "class": true, "enum": true, "interface": true, "trait": true,
+	"union": true, "type_alias": true, "type": true, "message": true,
+}
+
+// typeExprKeywords are words in a type expression that do not name a type.
+var typeExprKeywords = map[string]bool{
+	"mut": true, "dyn": true, "impl": true, "const": true, "fn": true, "for": true,
+	"unsafe": true, "extern": true, "func": true, "map": true, "chan": true,
+	"struct": true, "interface": true, "readonly": true, "typeof": true, "keyof": true,
+}
+
+// FieldDependencies returns the types the type symbolID transitively
+// contains through the declared types of its fields, up to maxDepth levels
+// of fields. Each type named in a field's type is an edge, generic
+// arguments included, so Vec<Item> leads to both Vec and Item; the type's
+// own generic parameters are not. A type reached again is not expanded
+// again. maxDepth of 0 returns only the root; negative returns an error;
+// it is capped at 100. Returns nil, nil if symbolID does not exist.
+func (q *QueryBuilder) FieldDependencies(symbolID int64, maxDepth int) (*FieldGraph, error) {
+	g, err := q.fieldGraph(symbolID, maxDepth, false)
+	if err != nil {
+		return nil, fmt.Errorf("field dependencies: %w", err)
+	}
+	return g, nil
+}
+
+// FieldDependents returns the types that transitively contain the type
+// symbolID through the declared types of their fields, up to maxDepth
+// levels: the types a change to it reaches when serialized or migrated.
+// Edges lead from the containing type to the contained one. maxDepth is as
+// for FieldDependencies. Returns nil, nil if symbolID does not exist.
+func (q *QueryBuilder) FieldDependents(symbolID int64, maxDepth int) (*FieldGraph, error) {
+	g, err := q.fieldGraph(symbolID, maxDepth, true)
+	if err != nil {
+		return nil, fmt.Errorf("field dependents: %w", err)
+	}
+	return g, nil
+}
+
+func (q *QueryBuilder) fieldGraph(symbolID int64, maxDepth int, reverse bool) (*FieldGraph, error) {
+	if maxDepth < 0 {
+		return nil, fmt.Errorf("maxDepth must be non-negative, got %d", maxDepth)
+	}
+	maxDepth = min(maxDepth, 100)
+
+	root, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	if root == nil {
+		return nil, nil
+	}
+
+	g := &FieldGraph{Root: *root, Edges: []FieldEdge{}, Types: []FieldGraphType{}}
+	seen := map[string]bool{fmt.Sprintf("#%d", root.ID): true}
+	frontier := []*SymbolResult{root}
+	for depth := 1; depth <= maxDepth && len(frontier) > 0; depth++ {
+		var next []*SymbolResult
+		for _, sym := range frontier {
+			var edges []FieldEdge
+			if reverse {
+				edges, err = q.fieldEdgesTo(sym)
+			} else {
+				edges, err = q.fieldEdgesFrom(sym)
+			}
+			if err != nil {
+				return nil, err
+			}
+			for _, e := range edges {
+				e.Depth = depth
+				g.Edges = append(g.Edges, e)
+				g.Depth = depth
+
+				reached, name := e.To, e.Type
+				if reverse {
+					reached, name = &e.From, e.From.Name
+				}
+				key := "name:" + name
+				if reached != nil {
+					key = fmt.Sprintf("#%d", reached.ID)
+				}
+				if seen[key] {
+					continue
+				}
+				seen[key] = true
+				g.Types = append(g.Types, FieldGraphType{Name: name, Symbol: reached, Depth: depth})
+				if reached != nil {
+					next = append(next, reached)
+				}
+			}
+		}
+		frontier = next
+	}
+	sort.SliceStable(g.Types, func(i, j int) bool {
+		if g.Types[i].Depth != g.Types[j].Depth {
+			return g.Types[i].Depth < g.Types[j].Depth
+		}
+		return g.Types[i].Name < g.Types[j].Name
+	})
+	return g, nil
+}
+
+// fieldEdgesFrom returns the edges from the fields of a type, in field
+// order. The names in a field's type are resolved through the references
+// within the type's declaration.
+func (q *QueryBuilder) fieldEdgesFrom(sym *SymbolResult) ([]FieldEdge, error) {
+	members, err := q.store.TypeMembers(sym.ID)
+	if err != nil {
+		return nil, err
+	}
+	var fields []*TypeMember
+	for _, m := range members {
+		if fieldMemberKinds[m.Kind] && m.TypeExpr != "" {
+			fields = append(fields, m)
+		}
+	}
+	if len(fields) == 0 || sym.FileID == nil {
+		return nil, nil
+	}
+
+	params, err := q.store.TypeParams(sym.ID)
+	if err != nil {
+		return nil, err
+	}
+	isParam := map[string]bool{}
+	for _, p := range params {
+		isParam[p.Name] = true
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT r.name, rr.target_symbol_id FROM references_ r
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 WHERE r.file_id = ? AND r.start_byte >= ? AND r.start_byte < ?
+		 ORDER BY r.start_byte, rr.confidence DESC`,
+		*sym.FileID, sym.StartByte, sym.EndByte,
+	)
+	if err != nil {
+		return nil, err
+	}
+	targetIDs := map[string]int64{}
+	for rows.Next() {
+		var name string
+		var id int64
+		if err := rows.Scan(&name, &id); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		if _, ok := targetIDs[name]; !ok {
+			targetIDs[name] = id
+		}
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	ids := make([]int64, 0, len(targetIDs))
+	for _, id := range targetIDs {
+		ids = append(ids, id)
+	}
+	targets, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+
+	var edges []FieldEdge
+	for _, f := range fields {
+		for _, name := range typeExprNames(f.TypeExpr) {
+			if isParam[name] {
+				continue
+			}
+			e := FieldEdge{From: *sym, Field: f.Name, TypeExpr: f.TypeExpr, Type: name}
+			if to, ok := targets[targetIDs[name]]; ok && fieldTypeKinds[to.Kind] {
+				e.To = to
+			}
+			edges = append(edges, e)
+		}
+	}
+	return edges, nil
+}
+
+// fieldEdgesTo returns the edges from the fields of other types to a type.
+// Candidates are the types with a field whose type mentions its name.
+func (q *QueryBuilder) fieldEdgesTo(sym *SymbolResult) ([]FieldEdge, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT symbol_id FROM type_members
+		 WHERE kind IN ('field', 'property') AND type_expr LIKE ? ESCAPE '\'
+		 ORDER BY symbol_id`,
+		"%"+escapeLike(sym.Name)+"%",
+	)
+	if err != nil {
+		return nil, err
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, err
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+	containers, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+
+	var edges []FieldEdge
+	for _, id := range ids {
+		c, ok := containers[id]
+		if !ok {
+			continue
+		}
+		from, err := q.fieldEdgesFrom(c)
+		if err != nil {
+			return nil, err
+		}
+		for _, e := range from {
+			if e.To != nil && e.To.ID == sym.ID {
+				edges = append(edges, e)
+			}
+		}
+	}
+	return edges, nil
+}
+
+// typeExprNames returns the distinct type names in a type expression, in
+// order: the last segment of each path, skipping lifetimes, keywords and
+// path qualifiers (std::collections::HashMap<K, V> names HashMap, K and V).
+func typeExprNames(expr string) []string {
+	var names []string
+	seen := map[string]bool{}
+	for i := 0; i < len(expr); {
+		if !isRustIdentByte(expr[i]) || ('0' <= expr[i] && expr[i] <= '9') {
+			i++
+			continue
+		}
+		start := i
+		for i < len(expr) && isRustIdentByte(expr[i]) {
+			i++
+		}
+		name := expr[start:i]
+		rest := strings.TrimLeft(expr[i:], " ")
+		switch {
+		case start > 0 && expr[start-1] == '\'':
+		case typeExprKeywords[name]:
+		case strings.HasPrefix(rest, "::"), strings.HasPrefix(rest, "."):
+		case seen[name]:
+		default:
+			seen[name] = true
+			names = append(names, name)
+		}
+	}
+	return names
+}
@@ -0,0 +1,115 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestTypeExprNames(t *testing.T) {
+	assert.Equal(t, []string{"HashMap", "K", "Vec", "V"}, typeExprNames("std::collections::HashMap<K, Vec<V>>"))
+	assert.Equal(t, []string{"str"}, typeExprNames("&'a mut str"))
+	assert.Equal(t, []string{"u8"}, typeExprNames("[u8; 32]"))
+	assert.Equal(t, []string{"string", "Item"}, typeExprNames("map[string]*models.Item"))
+}
+
+func TestFieldGraph(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", `pub struct Money {
+    pub cents: i64,
+    pub currency: String,
+}
+
+pub struct Product {
+    pub price: Money,
+    pub tags: Vec<Tag>,
+}
+
+pub struct Tag {
+    pub name: String,
+}
+
+pub struct Order<T> {
+    pub items: Vec<Product>,
+    pub extra: T,
+    pub next: Option<Box<Order<T>>>,
+}
+`)
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	symbolID := func(name string) int64 {
+		t.Helper()
+		syms, err := e.store.SymbolsByName(name)
+		require.NoError(t, err)
+		for _, s := range syms {
+			if s.Kind == "struct" {
+				return s.ID
+			}
+		}
+		t.Fatalf("no struct %s", name)
+		return 0
+	}
+	reached := func(g *FieldGraph) []string {
+		var out []string
+		for _, ty := range g.Types {
+			out = append(out, fmt.Sprintf("%d:%s", ty.Depth, ty.Name))
+		}
+		return out
+	}
+
+	g, err := e.Query().FieldDependencies(symbolID("Order"), 10)
+	require.NoError(t, err)
+	require.NotNil(t, g)
+	assert.Equal(t, "Order", g.Root.Name)
+	assert.Equal(t, []string{"1:Box", "1:Option", "1:Product", "1:Vec", "2:Money", "2:Tag", "3:String", "3:i64"}, reached(g))
+	assert.Equal(t, 3, g.Depth)
+	for _, edge := range g.Edges {
+		assert.NotEqual(t, "extra", edge.Field, "a generic parameter is not an edge")
+		if edge.Field == "price" {
+			assert.Equal(t, "Product", edge.From.Name)
+			assert.Equal(t, "Money", edge.TypeExpr)
+			require.NotNil(t, edge.To)
+			assert.Equal(t, symbolID("Money"), edge.To.ID)
+			assert.Equal(t, 2, edge.Depth)
+		}
+		if edge.Type == "i64" {
+			assert.Nil(t, edge.To)
+		}
+	}
+
+	g, err = e.Query().FieldDependencies(symbolID("Order"), 1)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"1:Box", "1:Option", "1:Product", "1:Vec"}, reached(g))
+
+	g, err = e.Query().FieldDependencies(symbolID("Order"), 0)
+	require.NoError(t, err)
+	assert.Empty(t, g.Edges)
+
+	g, err = e.Query().FieldDependents(symbolID("Money"), 5)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"1:Product", "2:Order"}, reached(g))
+	require.NotEmpty(t, g.Edges)
+	assert.Equal(t, "Product", g.Edges[0].From.Name)
+	assert.Equal(t, "price", g.Edges[0].Field)
+
+	_, err = e.Query().FieldDependencies(symbolID("Order"), -1)
+	assert.Error(t, err)
+	g, err = e.Query().FieldDependencies(999999, 3)
+	require.NoError(t, err)
+	assert.Nil(t, g)
+}
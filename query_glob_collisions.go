@@ -0,0 +1,288 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// GlobCollision is a name that two or more glob imports of a Rust file bring
+// in as different items of the same namespace, and that the file uses: an
+// error (E0659) until an explicit import says which item is meant.
+type GlobCollision struct {
+	Name    string
+	File    string
+	Sources []ShadowDefinition // the colliding items, in import order, each with the glob it comes through
+	Uses    []Location         // the file's references to the name, by position
+	Suggest int                // index in Sources of the item Import names
+	Import  string             // the explicit import that settles the name: "use crate::a::Config;"
+	Fix     TextEdit           // inserts Import on the line after its glob's use declaration
+}
+
+// GlobCollisions finds names that two or more glob imports of a Rust file
+// under pathPrefix ("" for all files) bring in as different items, and that
+// the file uses without declaring or importing them explicitly. Items
+// collide only when they share a namespace: a record struct and a function
+// of the same name do not. A colliding name compiles only while unused, so
+// each reported name comes with an explicit import to add, as a TextEdit
+// for ApplyEdits: of the item most of the file's references resolve to, or
+// of the first glob's item when none resolve to one. Uses in test code are
+// left out when q excludes it, and a name left with no use is not reported.
+// Globs are followed as in Shadows. Results are ordered by file, then by
+// the first use.
+func (q *QueryBuilder) GlobCollisions(pathPrefix string) ([]GlobCollision, error) {
+	files, fileIDs, byFile, err := q.rustImportsByFile(pathPrefix)
+	if err != nil {
+		return nil, fmt.Errorf("glob collisions: %w", err)
+	}
+	r := &rustItemResolver{q: q, packages: map[string]cargoPackage{}, items: map[string]map[string]*store.Symbol{}}
+	sources := map[string]*snippetFile{}
+
+	var collisions []GlobCollision
+	for _, fileID := range fileIDs {
+		path := files[fileID]
+		home, ok := q.rustModuleOf(path, r.packages)
+		if !ok {
+			continue
+		}
+		globs, err := r.fileGlobs(home, byFile[fileID])
+		if err != nil {
+			return nil, fmt.Errorf("glob collisions: %w", err)
+		}
+
+		// The distinct items each name comes in as, in import order.
+		var names []string
+		byName := map[string][]globImport{}
+		for _, g := range globs {
+			known := false
+			for _, prev := range byName[g.item.Name] {
+				known = known || prev.item.ID == g.item.ID
+			}
+			if known {
+				continue
+			}
+			if byName[g.item.Name] == nil {
+				names = append(names, g.item.Name)
+			}
+			byName[g.item.Name] = append(byName[g.item.Name], g)
+		}
+		var explicit map[string]bool
+		for _, name := range names {
+			colliding := collidingGlobs(byName[name])
+			if len(colliding) < 2 {
+				continue
+			}
+			if explicit == nil {
+				if explicit, err = r.explicitNames(home, byFile[fileID]); err != nil {
+					return nil, fmt.Errorf("glob collisions: %w", err)
+				}
+			}
+			if explicit[name] {
+				continue
+			}
+			uses, targets, err := q.nameUses(fileID, name)
+			if err != nil {
+				return nil, fmt.Errorf("glob collisions: %w", err)
+			}
+			if len(uses) == 0 {
+				continue
+			}
+
+			c := GlobCollision{Name: name, File: path, Uses: uses}
+			most := 0
+			for i, g := range colliding {
+				sr, err := q.symbolResultByID(g.item.ID)
+				if err != nil {
+					return nil, fmt.Errorf("glob collisions: %w", err)
+				}
+				def := ShadowDefinition{Symbol: sr, Import: g.source}
+				if sr != nil {
+					def.Location = symbolLocationOf(sr)
+				}
+				c.Sources = append(c.Sources, def)
+				if targets[g.item.ID] > most {
+					most, c.Suggest = targets[g.item.ID], i
+				}
+			}
+			sf, err := q.snippetFile(sources, path)
+			if err != nil {
+				return nil, fmt.Errorf("glob collisions: %w", err)
+			}
+			c.Import, c.Fix = explicitImportFix(sf.li, path, colliding[c.Suggest].source, name)
+			collisions = append(collisions, c)
+		}
+	}
+
+	sort.SliceStable(collisions, func(i, j int) bool {
+		a, b := collisions[i], collisions[j]
+		if a.File != b.File {
+			return a.File < b.File
+		}
+		if a.Uses[0].StartLine != b.Uses[0].StartLine {
+			return a.Uses[0].StartLine < b.Uses[0].StartLine
+		}
+		return a.Uses[0].StartCol < b.Uses[0].StartCol
+	})
+	return collisions, nil
+}
+
+// GlobCollisionEdits returns the fixes of collisions as edits ApplyEdits
+// accepts: fixes inserting at the same place, which it would reject as
+// conflicting, are joined into one edit in the order given.
+func GlobCollisionEdits(collisions []GlobCollision) []TextEdit {
+	var edits []TextEdit
+	at := map[Location]int{}
+	for _, c := range collisions {
+		if i, ok := at[c.Fix.Location]; ok {
+			edits[i].NewText += c.Fix.NewText
+			continue
+		}
+		at[c.Fix.Location] = len(edits)
+		edits = append(edits, c.Fix)
+	}
+	return edits
+}
+
+// collidingGlobs returns the first of globs, the items a name comes in as,
+// with the later ones sharing a namespace with it; nil if none does.
+func collidingGlobs(globs []globImport) []globImport {
+	if len(globs) < 2 {
+		return nil
+	}
+	types, values := rustNamespaces(globs[0].item)
+	colliding := globs[:1:1]
+	for _, g := range globs[1:] {
+		t, v := rustNamespaces(g.item)
+		if (types && t) || (values && v) {
+			colliding = append(colliding, g)
+		}
+	}
+	if len(colliding) < 2 {
+		return nil
+	}
+	return colliding
+}
+
+// rustNamespaces reports whether a Rust item is in the type namespace, the
+// value namespace, or both, as a tuple or unit struct is.
+func rustNamespaces(item *store.Symbol) (types, values bool) {
+	switch item.Kind {
+	case "struct":
+		return true, item.Subkind == "tuple_struct" || item.Subkind == "unit_struct"
+	case "enum", "trait", "type_alias", "module", "extern_crate":
+		return true, false
+	}
+	return false, true
+}
+
+// explicitNames returns the names a file of module home declares as items
+// of the module or imports by name, either of which takes precedence over
+// a glob import.
+func (r *rustItemResolver) explicitNames(home rustModule, imports []*store.Import) (map[string]bool, error) {
+	own, err := r.moduleItems(home, home.path)
+	if err != nil {
+		return nil, err
+	}
+	names := map[string]bool{}
+	for name := range own {
+		names[name] = true
+	}
+	for _, imp := range imports {
+		switch {
+		case imp.Kind == "wildcard":
+		case imp.LocalAlias != nil:
+			names[*imp.LocalAlias] = true
+		case imp.ImportedName != nil:
+			names[*imp.ImportedName] = true
+		}
+	}
+	return names, nil
+}
+
+// nameUses returns the references to name in a file other than imports, by
+// position, and how many of them resolve to each symbol.
+func (q *QueryBuilder) nameUses(fileID int64, name string) ([]Location, map[int64]int, error) {
+	query := `SELECT f.path, r.id, r.start_line, r.start_col, r.end_line, r.end_col, r.start_byte, r.end_byte,
+			r.utf16_start_col, r.utf16_end_col, COALESCE(rr.target_symbol_id, 0)
+		 FROM references_ r
+		 JOIN files f ON f.id = r.file_id
+		 LEFT JOIN resolved_references rr ON rr.reference_id = r.id
+		 WHERE r.file_id = ? AND r.name = ? AND COALESCE(r.context, '') != 'import'`
+	if q.excludeTests {
+		query += ` AND NOT ` + testCodeSQL("r", "f")
+	}
+	rows, err := q.store.DB().Query(query+` ORDER BY r.start_line, r.start_col, r.id`, fileID, name)
+	if err != nil {
+		return nil, nil, err
+	}
+	defer rows.Close()
+	var uses []Location
+	targets := map[int64]int{}
+	seen := map[int64]bool{}
+	for rows.Next() {
+		var loc Location
+		var refID, target int64
+		if err := rows.Scan(&loc.File, &refID, &loc.StartLine, &loc.StartCol, &loc.EndLine, &loc.EndCol,
+			&loc.StartByte, &loc.EndByte, &loc.UTF16StartCol, &loc.UTF16EndCol, &target); err != nil {
+			return nil, nil, err
+		}
+		if target != 0 {
+			targets[target]++
+		}
+		if !seen[refID] {
+			seen[refID] = true
+			uses = append(uses, loc)
+		}
+	}
+	return uses, targets, rows.Err()
+}
+
+// explicitImportFix returns a use declaration importing name from the
+// module of the glob source ("crate::a::*"), and an edit inserting it on
+// the line after the glob's, indented alike. A glob not written on a line
+// of its own, as in use std::{io::*}, has the import inserted before the
+// file's first use declaration instead.
+func explicitImportFix(li *lineIndex, path, source, name string) (string, TextEdit) {
+	decl := "use " + strings.TrimSuffix(source, "*") + name + ";"
+	line := func(l int) string {
+		end := len(li.content)
+		if l+1 < len(li.starts) {
+			end = li.starts[l+1] - 1
+		}
+		return string(li.content[li.starts[l]:end])
+	}
+
+	at, after := -1, false
+	for l := range li.starts {
+		text := strings.TrimSpace(line(l))
+		if strings.Contains(text, "use "+source+";") {
+			at, after = l, true
+			break
+		}
+		if at < 0 && (strings.HasPrefix(text, "use ") || strings.HasPrefix(text, "pub use ")) {
+			at = l
+		}
+	}
+	if at < 0 {
+		at = 0
+	}
+	text := line(at)
+	indent := text[:len(text)-len(strings.TrimLeft(text, " \t"))]
+	loc := Location{File: path, StartLine: at, EndLine: at, StartByte: li.starts[at], EndByte: li.starts[at]}
+	newText := indent + decl + "\n"
+	if after {
+		if at+1 < len(li.starts) {
+			loc.StartLine, loc.EndLine = at+1, at+1
+			loc.StartByte, loc.EndByte = li.starts[at+1], li.starts[at+1]
+		} else {
+			// The glob is on the file's last line, with no newline after it.
+			loc.StartCol, loc.EndCol = len(text), len(text)
+			loc.StartByte, loc.UTF16StartCol = li.offsets(at, len(text))
+			loc.EndByte, loc.UTF16EndCol = loc.StartByte, loc.UTF16StartCol
+			newText = "\n" + indent + decl
+		}
+	}
+	return decl, TextEdit{Location: loc, NewText: newText}
+}
@@ -0,0 +1,95 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestGlobCollisions(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "mod a;\nmod b;\nmod quiet;\nmod user;\n")
+	write("src/a.rs", "pub struct Config;\n\npub fn parse() {}\n\npub struct Record { pub id: u32 }\n")
+	write("src/b.rs", "pub struct Config;\n\npub fn parse() {}\n\npub fn Record() {}\n")
+	write("src/quiet.rs", "use crate::a::*;\nuse crate::b::*;\n")
+	write("src/user.rs", `use crate::a::*;
+use crate::b::*;
+use crate::a::parse;
+
+pub fn run(c: Config, _r: Record) -> Config {
+    parse();
+    c
+}
+`)
+	user := filepath.Join(root, "src", "user.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	collisions, err := e.Query().GlobCollisions("")
+	require.NoError(t, err)
+	require.Len(t, collisions, 1, "parse is imported explicitly, Records are in different namespaces, quiet.rs uses nothing")
+	c := collisions[0]
+	assert.Equal(t, "Config", c.Name)
+	assert.Equal(t, user, c.File)
+	require.Len(t, c.Sources, 2)
+	assert.Equal(t, "crate::a::*", c.Sources[0].Import)
+	assert.Equal(t, filepath.Join(root, "src", "a.rs"), c.Sources[0].Location.File)
+	assert.Equal(t, "crate::b::*", c.Sources[1].Import)
+	assert.Equal(t, filepath.Join(root, "src", "b.rs"), c.Sources[1].Location.File)
+	require.Len(t, c.Uses, 2)
+	assert.Equal(t, 4, c.Uses[0].StartLine)
+	assert.Less(t, c.Uses[0].StartCol, c.Uses[1].StartCol)
+
+	// The import names the suggested item and follows its glob.
+	module := strings.TrimSuffix(c.Sources[c.Suggest].Import, "*")
+	assert.Equal(t, "use "+module+"Config;", c.Import)
+	assert.Equal(t, c.Suggest+1, c.Fix.StartLine)
+	assert.Equal(t, 0, c.Fix.StartCol)
+
+	_, err = ApplyEdits(GlobCollisionEdits(collisions))
+	require.NoError(t, err)
+	data, err := os.ReadFile(user)
+	require.NoError(t, err)
+	assert.Equal(t, c.Import, strings.Split(string(data), "\n")[c.Suggest+1])
+
+	none, err := e.Query().GlobCollisions(filepath.Join(root, "src", "quiet.rs"))
+	require.NoError(t, err)
+	assert.Empty(t, none)
+}
+
+func TestExplicitImportFix(t *testing.T) {
+	li := newLineIndex([]byte("mod inner {\n    use super::a::*;\n    use super::b::*;\n}\n"))
+	decl, fix := explicitImportFix(li, "f.rs", "super::b::*", "Config")
+	assert.Equal(t, "use super::b::Config;", decl)
+	assert.Equal(t, 3, fix.StartLine)
+	assert.Equal(t, "    use super::b::Config;\n", fix.NewText)
+
+	li = newLineIndex([]byte("use a::*;"))
+	_, fix = explicitImportFix(li, "f.rs", "a::*", "Config")
+	assert.Equal(t, 0, fix.StartLine)
+	assert.Equal(t, 9, fix.StartCol)
+	assert.Equal(t, "\nuse a::Config;", fix.NewText)
+
+	li = newLineIndex([]byte("//! Docs\nuse std::{io::*, fmt::*};\n"))
+	_, fix = explicitImportFix(li, "f.rs", "std::io::*", "Error")
+	assert.Equal(t, 1, fix.StartLine, "before the first use declaration")
+	assert.Equal(t, "use std::io::Error;\n", fix.NewText)
+
+	edits := GlobCollisionEdits([]GlobCollision{{Fix: fix}, {Fix: TextEdit{Location: fix.Location, NewText: "use std::fmt::Error;\n"}}})
+	require.Len(t, edits, 1)
+	assert.Equal(t, "use std::io::Error;\nuse std::fmt::Error;\n", edits[0].NewText)
+}
@@ -2,6 +2,7 @@ package canopy
 
 import (
 	"fmt"
+	"sort"
 	"strings"
 
 	"github.com/jward/canopy/internal/store"
@@ -23,13 +24,27 @@ type CallGraphNode struct {
 	Depth  int // BFS depth from root (0 = root itself)
 }
 
+// CallKindSpawn is the kind of a call edge whose callee runs in a task or
+// thread the caller spawns, as in tokio::spawn(serve(conn)) or
+// thread::spawn(worker). Direct calls have kind "".
+const CallKindSpawn = "spawn"
+
+// CallKindFFI is the kind of a call edge that crosses the FFI boundary:
+// from a Rust extern block function or a C prototype to its definition in
+// the other language, or from JavaScript to a #[wasm_bindgen] export.
+const CallKindFFI = "ffi"
+
 // CallGraphEdge is a single caller-callee relationship in the call graph.
 type CallGraphEdge struct {
-	CallerID int64
-	CalleeID int64
-	File     string
-	Line     int
-	Col      int
+	CallerID   int64
+	CalleeID   int64
+	File       string
+	Line       int
+	Col        int
+	Confidence string // one of the Confidence constants
+	Awaited    bool   // the call's result is awaited on the spot
+	Kind       string // "" for a direct call, CallKindSpawn for a spawned task or thread, CallKindFFI across FFI
+	Propagated bool   // the call's error is propagated with ?
 }
 
 // callGraphData holds the bulk-loaded call graph adjacency maps and file path index.
@@ -62,6 +77,10 @@ func (q *QueryBuilder) buildCallGraph() (*callGraphData, error) {
 		filePaths:     filePaths,
 	}
 
+	edges, err = q.filterCallEdges(edges)
+	if err != nil {
+		return nil, fmt.Errorf("build call graph: %w", err)
+	}
 	for _, e := range edges {
 		data.forward[e.CallerSymbolID] = append(data.forward[e.CallerSymbolID], e.CalleeSymbolID)
 		data.reverse[e.CalleeSymbolID] = append(data.reverse[e.CalleeSymbolID], e.CallerSymbolID)
@@ -80,11 +99,15 @@ func resolveCallGraphEdge(edge *CallEdge, filePaths map[int64]string) CallGraphE
 		file = filePaths[*edge.FileID]
 	}
 	return CallGraphEdge{
-		CallerID: edge.CallerSymbolID,
-		CalleeID: edge.CalleeSymbolID,
-		File:     file,
-		Line:     edge.Line,
-		Col:      edge.Col,
+		CallerID:   edge.CallerSymbolID,
+		CalleeID:   edge.CalleeSymbolID,
+		File:       file,
+		Line:       edge.Line,
+		Col:        edge.Col,
+		Confidence: ConfidenceLevel(edge.Confidence),
+		Awaited:    edge.Awaited,
+		Kind:       edge.Kind,
+		Propagated: edge.Propagated,
 	}
 }
 
@@ -126,6 +149,7 @@ func (q *QueryBuilder) TransitiveCallers(symbolID int64, maxDepth int) (*CallGra
 
 	// BFS on reverse adjacency map
 	visited := map[int64]int{symbolID: 0} // symbol ID -> depth
+	order := []int64{symbolID}           // visited IDs in BFS order, for stable output
 	type bfsEntry struct {
 		id    int64
 		depth int
@@ -145,6 +169,7 @@ func (q *QueryBuilder) TransitiveCallers(symbolID int64, maxDepth int) (*CallGra
 			if _, seen := visited[callerID]; !seen {
 				newDepth := current.depth + 1
 				visited[callerID] = newDepth
+				order = append(order, callerID)
 				if newDepth > result.Depth {
 					result.Depth = newDepth
 				}
@@ -154,12 +179,7 @@ func (q *QueryBuilder) TransitiveCallers(symbolID int64, maxDepth int) (*CallGra
 	}
 
 	// Collect all visited node IDs (except root, already added)
-	nodeIDs := make([]int64, 0, len(visited)-1)
-	for id := range visited {
-		if id != symbolID {
-			nodeIDs = append(nodeIDs, id)
-		}
-	}
+	nodeIDs := order[1:]
 
 	// Bulk load symbols
 	symbols, err := q.symbolResultsByIDs(nodeIDs)
@@ -177,7 +197,7 @@ func (q *QueryBuilder) TransitiveCallers(symbolID int64, maxDepth int) (*CallGra
 	// For reverse traversal (callers), an edge is relevant if both caller and callee
 	// are in the visited set.
 	edgeSeen := make(map[int64]bool)
-	for id := range visited {
+	for _, id := range order {
 		for _, edge := range data.edgesByCallee[id] {
 			if _, callerVisited := visited[edge.CallerSymbolID]; callerVisited {
 				if !edgeSeen[edge.ID] {
@@ -229,6 +249,7 @@ func (q *QueryBuilder) TransitiveCallees(symbolID int64, maxDepth int) (*CallGra
 
 	// BFS on forward adjacency map
 	visited := map[int64]int{symbolID: 0}
+	order := []int64{symbolID}
 	type bfsEntry struct {
 		id    int64
 		depth int
@@ -247,6 +268,7 @@ func (q *QueryBuilder) TransitiveCallees(symbolID int64, maxDepth int) (*CallGra
 			if _, seen := visited[calleeID]; !seen {
 				newDepth := current.depth + 1
 				visited[calleeID] = newDepth
+				order = append(order, calleeID)
 				if newDepth > result.Depth {
 					result.Depth = newDepth
 				}
@@ -256,12 +278,7 @@ func (q *QueryBuilder) TransitiveCallees(symbolID int64, maxDepth int) (*CallGra
 	}
 
 	// Collect visited node IDs (except root)
-	nodeIDs := make([]int64, 0, len(visited)-1)
-	for id := range visited {
-		if id != symbolID {
-			nodeIDs = append(nodeIDs, id)
-		}
-	}
+	nodeIDs := order[1:]
 
 	symbols, err := q.symbolResultsByIDs(nodeIDs)
 	if err != nil {
@@ -276,7 +293,7 @@ func (q *QueryBuilder) TransitiveCallees(symbolID int64, maxDepth int) (*CallGra
 
 	// Collect edges that connect visited nodes.
 	edgeSeen := make(map[int64]bool)
-	for id := range visited {
+	for _, id := range order {
 		for _, edge := range data.edgesByCaller[id] {
 			if _, calleeVisited := visited[edge.CalleeSymbolID]; calleeVisited {
 				if !edgeSeen[edge.ID] {
@@ -298,7 +315,8 @@ type HotspotResult struct {
 	CalleeCount int // direct callees (fan-out from call_graph)
 }
 
-// UnusedSymbols returns symbols with zero resolved references.
+// UnusedSymbols returns symbols with zero resolved references. When q
+// excludes test code, references from tests do not count.
 // Hardcoded exclusion of kinds "package", "module", "namespace" (never
 // meaningfully referenced). Supports the same SymbolFilter and Pagination
 // as Symbols().
@@ -308,43 +326,24 @@ func (q *QueryBuilder) UnusedSymbols(filter SymbolFilter, sort Sort, page Pagina
 	var where []string
 	var args []any
 
-	// Core condition: no resolved references targeting this symbol
-	where = append(where, "NOT EXISTS (SELECT 1 FROM resolved_references rr WHERE rr.target_symbol_id = s.id)")
+	// Core condition: no resolved references targeting this symbol, from
+	// production code when test code is excluded
+	if clause := q.testCodeClause("r", "rf"); clause != "" {
+		where = append(where, `NOT EXISTS (SELECT 1 FROM resolved_references rr
+			JOIN references_ r ON r.id = rr.reference_id
+			JOIN files rf ON rf.id = r.file_id
+			WHERE rr.target_symbol_id = s.id AND `+clause+`)`)
+	} else {
+		where = append(where, "NOT EXISTS (SELECT 1 FROM resolved_references rr WHERE rr.target_symbol_id = s.id)")
+	}
 
 	// Hardcoded exclusion of package-like kinds
 	where = append(where, "s.kind NOT IN ('package', 'module', 'namespace')")
 
 	// Apply SymbolFilter fields
-	if len(filter.Kinds) > 0 {
-		placeholders := strings.Repeat("?,", len(filter.Kinds)-1) + "?"
-		where = append(where, "s.kind IN ("+placeholders+")")
-		for _, k := range filter.Kinds {
-			args = append(args, k)
-		}
-	}
-	if filter.Visibility != nil {
-		where = append(where, "s.visibility = ?")
-		args = append(args, *filter.Visibility)
-	}
-	if filter.FileID != nil {
-		where = append(where, "s.file_id = ?")
-		args = append(args, *filter.FileID)
-	}
-	if filter.ParentID != nil {
-		where = append(where, "s.parent_symbol_id = ?")
-		args = append(args, *filter.ParentID)
-	}
-	if filter.PathPrefix != nil {
-		prefix := normalizePathPrefix(*filter.PathPrefix)
-		if prefix != "" {
-			where = append(where, "f.path LIKE ? ESCAPE '\\'")
-			args = append(args, escapeLike(prefix)+"%")
-		}
-	}
-	for _, mod := range filter.Modifiers {
-		where = append(where, "EXISTS (SELECT 1 FROM json_each(s.modifiers) WHERE json_each.value = ?)")
-		args = append(args, mod)
-	}
+	filterWhere, filterArgs := q.symbolFilterClauses(filter)
+	where = append(where, filterWhere...)
+	args = append(args, filterArgs...)
 
 	whereClause := "WHERE " + strings.Join(where, " AND ")
 
@@ -367,9 +366,9 @@ func (q *QueryBuilder) UnusedSymbols(filter SymbolFilter, sort Sort, page Pagina
 		 FROM symbols s
 		 LEFT JOIN files f ON s.file_id = f.id
 		 %s
-		 ORDER BY %s %s
+		 ORDER BY %s %s, %s
 		 LIMIT ? OFFSET ?`,
-		prefixSymbolCols("s"), whereClause, orderCol, orderDir,
+		prefixSymbolCols("s"), whereClause, orderCol, orderDir, symbolTiebreak,
 	)
 	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
 
@@ -417,9 +416,9 @@ func (q *QueryBuilder) Hotspots(topN int) ([]*HotspotResult, error) {
 		 FROM symbols s
 		 LEFT JOIN files f ON s.file_id = f.id
 		 WHERE EXISTS (SELECT 1 FROM resolved_references rr2 WHERE rr2.target_symbol_id = s.id)
-		 ORDER BY external_ref_count DESC
+		 ORDER BY external_ref_count DESC, %s
 		 LIMIT ?`,
-		prefixSymbolCols("s"),
+		prefixSymbolCols("s"), symbolTiebreak,
 	)
 
 	rows, err := q.store.DB().Query(dataSQL, topN)
@@ -455,7 +454,8 @@ func scanHotspotResult(row scanner) (*HotspotResult, error) {
 		&hr.Symbol.ID, &hr.Symbol.FileID, &hr.Symbol.Name, &hr.Symbol.Kind,
 		&hr.Symbol.Visibility, &mods, &hr.Symbol.SignatureHash,
 		&hr.Symbol.StartLine, &hr.Symbol.StartCol, &hr.Symbol.EndLine, &hr.Symbol.EndCol,
-		&hr.Symbol.ParentSymbolID,
+		&hr.Symbol.ParentSymbolID, &hr.Symbol.StartByte, &hr.Symbol.EndByte, &hr.Symbol.UTF16StartCol, &hr.Symbol.UTF16EndCol,
+		&hr.Symbol.PageRank, &hr.Symbol.Betweenness, &hr.Symbol.Subkind,
 		&hr.Symbol.FilePath, &hr.Symbol.RefCount, &hr.Symbol.ExternalRefCount,
 		&hr.CallerCount, &hr.CalleeCount,
 	)
@@ -466,3 +466,76 @@ func scanHotspotResult(row scanner) (*HotspotResult, error) {
 	hr.Symbol.InternalRefCount = hr.Symbol.RefCount - hr.Symbol.ExternalRefCount
 	return &hr, nil
 }
+
+// testSymbolsSQL selects the IDs of test functions: functions carrying a
+// test attribute (#[test], #[tokio::test], ...), functions gated with
+// #[cfg(test)], and functions nested inside a #[cfg(test)] module.
+const testSymbolsSQL = `SELECT s.id FROM symbols s
+	WHERE s.kind IN ('function', 'method') AND (
+		EXISTS (SELECT 1 FROM annotations a WHERE a.target_symbol_id = s.id
+			AND (a.name = 'test' OR a.name LIKE '%::test' OR (a.name = 'cfg' AND cfg_is_test(COALESCE(a.arguments, '')))))
+		OR EXISTS (SELECT 1 FROM symbols m JOIN annotations a ON a.target_symbol_id = m.id
+			WHERE m.kind = 'module' AND m.file_id = s.file_id
+			AND a.name = 'cfg' AND cfg_is_test(COALESCE(a.arguments, ''))
+			AND (m.start_line < s.start_line OR (m.start_line = s.start_line AND m.start_col <= s.start_col))
+			AND (m.end_line > s.end_line OR (m.end_line = s.end_line AND m.end_col >= s.end_col)))
+	)`
+
+// testSymbolIDs returns the set of symbol IDs identified as tests.
+func (q *QueryBuilder) testSymbolIDs() (map[int64]bool, error) {
+	rows, err := q.store.DB().Query(testSymbolsSQL)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	ids := make(map[int64]bool)
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids[id] = true
+	}
+	return ids, rows.Err()
+}
+
+// TestsCovering returns the test functions whose call graph reaches the given
+// symbol within maxDepth hops, ordered by distance then name. Each node's
+// Depth is the number of calls between the test and the symbol. A symbol that
+// is itself a test is not included in its own result.
+// Same depth rules as TransitiveCallers. Returns nil, nil if symbolID does not exist.
+func (q *QueryBuilder) TestsCovering(symbolID int64, maxDepth int) ([]CallGraphNode, error) {
+	graph, err := q.WithTestCode(true).TransitiveCallers(symbolID, maxDepth)
+	if err != nil {
+		return nil, fmt.Errorf("tests covering: %w", err)
+	}
+	if graph == nil {
+		return nil, nil
+	}
+
+	tests, err := q.testSymbolIDs()
+	if err != nil {
+		return nil, fmt.Errorf("tests covering: load tests: %w", err)
+	}
+
+	result := []CallGraphNode{}
+	for _, n := range graph.Nodes {
+		if n.Depth > 0 && tests[n.Symbol.ID] {
+			result = append(result, n)
+		}
+	}
+	sort.Slice(result, func(i, j int) bool {
+		if result[i].Depth != result[j].Depth {
+			return result[i].Depth < result[j].Depth
+		}
+		if result[i].Symbol.Name != result[j].Symbol.Name {
+			return result[i].Symbol.Name < result[j].Symbol.Name
+		}
+		if result[i].Symbol.FilePath != result[j].Symbol.FilePath {
+			return result[i].Symbol.FilePath < result[j].Symbol.FilePath
+		}
+		return result[i].Symbol.ID < result[j].Symbol.ID
+	})
+	return result, nil
+}
@@ -462,6 +462,21 @@ func TestUnusedSymbols_FilterByKind(t *testing.T) {
 	assert.Equal(t, "UnusedFunc", result.Items[0].Name)
 }
 
+func TestUnusedSymbols_ExcludeNamesSkipsEntryPoints(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "main.rs", "rust")
+	insertSymbol(t, s, &fID, "main", "function", "private", nil)
+	insertSymbol(t, s, &fID, "handler", "function", "public", nil)
+	insertSymbol(t, s, &fID, "dead", "function", "private", nil)
+
+	result, err := q.UnusedSymbols(SymbolFilter{ExcludeNames: []string{"main", "handler"}}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 1, result.TotalCount)
+	require.Len(t, result.Items, 1)
+	assert.Equal(t, "dead", result.Items[0].Name)
+}
+
 func TestUnusedSymbols_FilterByVisibility(t *testing.T) {
 	t.Parallel()
 	q, s := newTestQueryBuilder(t)
@@ -685,3 +700,109 @@ func TestHotspots_NegativeTopNReturnsError(t *testing.T) {
 	assert.Nil(t, result)
 	assert.Contains(t, err.Error(), "topN must be non-negative")
 }
+
+// =============================================================================
+// TestsCovering
+// =============================================================================
+
+func TestTestsCovering_ReturnsTestsReachingSymbol(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+
+	target := insertSymbol(t, s, &fID, "parse", "function", "public", nil)
+	helper := insertSymbol(t, s, &fID, "parse_all", "function", "public", nil)
+	direct := insertSymbol(t, s, &fID, "parses_one", "function", "private", nil)
+	indirect := insertSymbol(t, s, &fID, "parses_many", "function", "private", nil)
+	unrelated := insertSymbol(t, s, &fID, "formats", "function", "private", nil)
+
+	for _, id := range []int64{direct, indirect, unrelated} {
+		_, err := s.InsertAnnotation(&store.Annotation{TargetSymbolID: id, Name: "test", FileID: &fID})
+		require.NoError(t, err)
+	}
+
+	// parses_one -> parse; parses_many -> parse_all -> parse
+	for _, e := range [][2]int64{{direct, target}, {indirect, helper}, {helper, target}} {
+		_, err := s.InsertCallEdge(&store.CallEdge{CallerSymbolID: e[0], CalleeSymbolID: e[1], FileID: &fID})
+		require.NoError(t, err)
+	}
+
+	tests, err := q.TestsCovering(target, 10)
+	require.NoError(t, err)
+	require.Len(t, tests, 2)
+	assert.Equal(t, "parses_one", tests[0].Symbol.Name)
+	assert.Equal(t, 1, tests[0].Depth)
+	assert.Equal(t, "parses_many", tests[1].Symbol.Name)
+	assert.Equal(t, 2, tests[1].Depth)
+
+	// Depth limit cuts off the indirect test.
+	tests, err = q.TestsCovering(target, 1)
+	require.NoError(t, err)
+	require.Len(t, tests, 1)
+	assert.Equal(t, "parses_one", tests[0].Symbol.Name)
+}
+
+func TestTestsCovering_FunctionsInsideCfgTestModule(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+
+	target := insertSymbol(t, s, &fID, "parse", "function", "public", nil)
+	mod, err := s.InsertSymbol(&store.Symbol{
+		FileID: &fID, Name: "tests", Kind: "module", Visibility: "private",
+		StartLine: 20, StartCol: 0, EndLine: 40, EndCol: 1,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertAnnotation(&store.Annotation{TargetSymbolID: mod, Name: "cfg", Arguments: "(test)", FileID: &fID})
+	require.NoError(t, err)
+	inner, err := s.InsertSymbol(&store.Symbol{
+		FileID: &fID, Name: "check", Kind: "function", Visibility: "private",
+		StartLine: 22, StartCol: 4, EndLine: 25, EndCol: 5,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&store.CallEdge{CallerSymbolID: inner, CalleeSymbolID: target, FileID: &fID})
+	require.NoError(t, err)
+
+	tests, err := q.TestsCovering(target, 5)
+	require.NoError(t, err)
+	require.Len(t, tests, 1)
+	assert.Equal(t, "check", tests[0].Symbol.Name)
+}
+
+func TestTestsCovering_EquivalentCfgTestForms(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+
+	target := insertSymbol(t, s, &fID, "parse", "function", "public", nil)
+	// #[cfg( test )] and #[cfg(any(test, test))] gate tests as #[cfg(test)]
+	// does; #[cfg(any(test, unix))] does not.
+	for i, args := range []string{"( test )", "(any(test, test))", "(any(test, unix))"} {
+		fn, err := s.InsertSymbol(&store.Symbol{
+			FileID: &fID, Name: fmt.Sprintf("check_%d", i), Kind: "function", Visibility: "private",
+			StartLine: 10 * (i + 1), EndLine: 10*(i+1) + 3, EndCol: 1,
+		})
+		require.NoError(t, err)
+		_, err = s.InsertAnnotation(&store.Annotation{TargetSymbolID: fn, Name: "cfg", Arguments: args, FileID: &fID})
+		require.NoError(t, err)
+		_, err = s.InsertCallEdge(&store.CallEdge{CallerSymbolID: fn, CalleeSymbolID: target, FileID: &fID})
+		require.NoError(t, err)
+	}
+
+	tests, err := q.TestsCovering(target, 5)
+	require.NoError(t, err)
+	var names []string
+	for _, n := range tests {
+		names = append(names, n.Symbol.Name)
+	}
+	assert.Equal(t, []string{"check_0", "check_1"}, names)
+}
+
+func TestTestsCovering_NonExistentSymbolReturnsNil(t *testing.T) {
+	t.Parallel()
+	q, _ := newTestQueryBuilder(t)
+
+	tests, err := q.TestsCovering(99999, 5)
+	require.NoError(t, err)
+	assert.Nil(t, tests)
+}
@@ -7,7 +7,7 @@ import (
 // TypeRelation represents a relationship between two types in a hierarchy.
 type TypeRelation struct {
 	Symbol SymbolResult
-	Kind   string // "inheritance", "interface_impl", "composition", "embedding", "implicit"
+	Kind   string // "inheritance", "interface_impl", "composition", "embedding", "implicit", "wraps", "supertrait"
 }
 
 // TypeHierarchy is a complete hierarchy view for a single type, combining
@@ -0,0 +1,168 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// StaleSymbol is a symbol paired with its git blame history.
+type StaleSymbol struct {
+	Symbol  SymbolResult
+	History SymbolHistory
+}
+
+// FileBusFactor is the authorship concentration of one file.
+type FileBusFactor struct {
+	File       string
+	BusFactor  int           // fewest authors who together last touched more than half the lines
+	TotalLines int           // blamed lines
+	Authors    []AuthorLines // sorted by lines descending
+}
+
+// SymbolHistory returns the git blame history of a symbol. Returns nil, nil
+// if the symbol has none (blame disabled, untracked file, or unknown symbol).
+func (q *QueryBuilder) SymbolHistory(symbolID int64) (*SymbolHistory, error) {
+	h, err := q.store.SymbolHistoryByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("symbol history: %w", err)
+	}
+	return h, nil
+}
+
+// StaleSymbols returns symbols whose lines were all last modified before the
+// given time, oldest first. Only symbols with blame history are considered.
+// RefCountMin/RefCountMax in filter are ignored.
+func (q *QueryBuilder) StaleSymbols(before time.Time, filter SymbolFilter, page Pagination) (*PagedResult[StaleSymbol], error) {
+	page = page.normalize()
+
+	where, args := q.symbolFilterClauses(filter)
+	where = append([]string{"h.date < ?"}, where...)
+	args = append([]any{before.Unix()}, args...)
+	whereClause := "WHERE " + strings.Join(where, " AND ")
+
+	from := `FROM symbol_history h
+		 JOIN symbols s ON s.id = h.symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id `
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+from+whereClause, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("stale symbols: count: %w", err)
+	}
+
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, COALESCE(f.path, '') AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr WHERE rr.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr JOIN references_ r ON r.id = rr.reference_id WHERE rr.target_symbol_id = s.id AND r.file_id != s.file_id) AS external_ref_count,
+			h.commit_hash, h.author, COALESCE(h.author_email, ''), h.date, h.authors
+		 %s%s
+		 ORDER BY h.date ASC, f.path, s.start_line, s.start_col, s.id
+		 LIMIT ? OFFSET ?`,
+		prefixSymbolCols("s"), from, whereClause,
+	)
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+
+	rows, err := q.store.DB().Query(dataSQL, dataArgs...)
+	if err != nil {
+		return nil, fmt.Errorf("stale symbols: query: %w", err)
+	}
+	defer rows.Close()
+
+	items := []StaleSymbol{}
+	for rows.Next() {
+		var st StaleSymbol
+		var date int64
+		var mods string
+		sr := &st.Symbol
+		if err := rows.Scan(
+			&sr.ID, &sr.FileID, &sr.Name, &sr.Kind, &sr.Visibility, &mods,
+			&sr.SignatureHash, &sr.StartLine, &sr.StartCol, &sr.EndLine, &sr.EndCol,
+			&sr.ParentSymbolID, &sr.StartByte, &sr.EndByte, &sr.UTF16StartCol, &sr.UTF16EndCol,
+			&sr.PageRank, &sr.Betweenness,
+			&sr.FilePath, &sr.RefCount, &sr.ExternalRefCount,
+			&st.History.Commit, &st.History.Author, &st.History.AuthorEmail, &date, &st.History.Authors,
+		); err != nil {
+			return nil, fmt.Errorf("stale symbols: scan: %w", err)
+		}
+		sr.Modifiers = store.UnmarshalModifiers(mods)
+		sr.InternalRefCount = sr.RefCount - sr.ExternalRefCount
+		st.History.SymbolID = sr.ID
+		st.History.Date = time.Unix(date, 0).UTC()
+		items = append(items, st)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("stale symbols: rows: %w", err)
+	}
+
+	return &PagedResult[StaleSymbol]{Items: items, TotalCount: totalCount}, nil
+}
+
+// BusFactor computes the bus factor of every blamed file under pathPrefix
+// (all files if empty): the fewest authors who together last modified more
+// than half of the file's lines. Sorted by bus factor ascending, then by
+// line count descending, so the riskiest large files come first.
+func (q *QueryBuilder) BusFactor(pathPrefix string) ([]FileBusFactor, error) {
+	query := `SELECT f.path, fa.author, fa.lines FROM file_authors fa
+		 JOIN files f ON f.id = fa.file_id`
+	var args []any
+	if prefix := normalizePathPrefix(pathPrefix); prefix != "" {
+		query += ` WHERE f.path LIKE ? ESCAPE '\'`
+		args = append(args, escapeLike(prefix)+"%")
+	}
+	rows, err := q.store.DB().Query(query, args...)
+	if err != nil {
+		return nil, fmt.Errorf("bus factor: %w", err)
+	}
+	defer rows.Close()
+
+	byFile := map[string]*FileBusFactor{}
+	for rows.Next() {
+		var path string
+		var a AuthorLines
+		if err := rows.Scan(&path, &a.Author, &a.Lines); err != nil {
+			return nil, fmt.Errorf("bus factor: scan: %w", err)
+		}
+		fb, ok := byFile[path]
+		if !ok {
+			fb = &FileBusFactor{File: path}
+			byFile[path] = fb
+		}
+		fb.Authors = append(fb.Authors, a)
+		fb.TotalLines += a.Lines
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("bus factor: %w", err)
+	}
+
+	result := make([]FileBusFactor, 0, len(byFile))
+	for _, fb := range byFile {
+		sort.Slice(fb.Authors, func(i, j int) bool {
+			if fb.Authors[i].Lines != fb.Authors[j].Lines {
+				return fb.Authors[i].Lines > fb.Authors[j].Lines
+			}
+			return fb.Authors[i].Author < fb.Authors[j].Author
+		})
+		covered := 0
+		for _, a := range fb.Authors {
+			fb.BusFactor++
+			covered += a.Lines
+			if covered*2 > fb.TotalLines {
+				break
+			}
+		}
+		result = append(result, *fb)
+	}
+	sort.Slice(result, func(i, j int) bool {
+		if result[i].BusFactor != result[j].BusFactor {
+			return result[i].BusFactor < result[j].BusFactor
+		}
+		if result[i].TotalLines != result[j].TotalLines {
+			return result[i].TotalLines > result[j].TotalLines
+		}
+		return result[i].File < result[j].File
+	})
+	return result, nil
+}
@@ -0,0 +1,78 @@
+package canopy
+
+import (
+	"testing"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestStaleSymbols_FiltersByDate(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/repo/lib/lib.go", "go")
+	oldFn := insertSymbol(t, s, &fID, "Old", "function", "public", nil)
+	oldType := insertSymbol(t, s, &fID, "OldType", "struct", "public", nil)
+	newFn := insertSymbol(t, s, &fID, "New", "function", "public", nil)
+	insertSymbol(t, s, &fID, "Unblamed", "function", "public", nil)
+
+	day := func(d int) time.Time { return time.Date(2020, 1, d, 0, 0, 0, 0, time.UTC) }
+	require.NoError(t, s.ReplaceFileBlame(fID, nil, []*store.SymbolHistory{
+		{SymbolID: oldFn, Commit: "aaa", Author: "Alice", Date: day(2), Authors: 1},
+		{SymbolID: oldType, Commit: "bbb", Author: "Bob", Date: day(1), Authors: 2},
+		{SymbolID: newFn, Commit: "ccc", Author: "Alice", Date: day(20), Authors: 1},
+	}))
+
+	result, err := q.StaleSymbols(day(10), SymbolFilter{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 2, result.TotalCount)
+	require.Len(t, result.Items, 2)
+	assert.Equal(t, "OldType", result.Items[0].Symbol.Name, "oldest first")
+	assert.Equal(t, "bbb", result.Items[0].History.Commit)
+	assert.Equal(t, day(1), result.Items[0].History.Date)
+	assert.Equal(t, "Old", result.Items[1].Symbol.Name)
+
+	result, err = q.StaleSymbols(day(10), SymbolFilter{Kinds: []string{"function"}}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, result.Items, 1)
+	assert.Equal(t, "Old", result.Items[0].Symbol.Name)
+
+	h, err := q.SymbolHistory(newFn)
+	require.NoError(t, err)
+	require.NotNil(t, h)
+	assert.Equal(t, "ccc", h.Commit)
+}
+
+func TestBusFactor(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	soloID := insertFile(t, s, "/repo/lib/solo.go", "go")
+	sharedID := insertFile(t, s, "/repo/lib/shared.go", "go")
+	otherID := insertFile(t, s, "/repo/cmd/main.go", "go")
+
+	require.NoError(t, s.ReplaceFileBlame(soloID, []store.AuthorLines{
+		{Author: "Alice", Lines: 90}, {Author: "Bob", Lines: 10},
+	}, nil))
+	require.NoError(t, s.ReplaceFileBlame(sharedID, []store.AuthorLines{
+		{Author: "Alice", Lines: 40}, {Author: "Bob", Lines: 40}, {Author: "Carol", Lines: 20},
+	}, nil))
+	require.NoError(t, s.ReplaceFileBlame(otherID, []store.AuthorLines{
+		{Author: "Dave", Lines: 5},
+	}, nil))
+
+	factors, err := q.BusFactor("/repo/lib")
+	require.NoError(t, err)
+	require.Len(t, factors, 2)
+	assert.Equal(t, "/repo/lib/solo.go", factors[0].File)
+	assert.Equal(t, 1, factors[0].BusFactor)
+	assert.Equal(t, 100, factors[0].TotalLines)
+	assert.Equal(t, "/repo/lib/shared.go", factors[1].File)
+	assert.Equal(t, 2, factors[1].BusFactor)
+	assert.Equal(t, "Alice", factors[1].Authors[0].Author)
+
+	all, err := q.BusFactor("")
+	require.NoError(t, err)
+	assert.Len(t, all, 3)
+}
@@ -0,0 +1,921 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Reasons the orphan rule lets a crate implement a trait for a type.
+const (
+	ImplOwnedByTrait    = "trait"          // the crate declares the trait
+	ImplOwnedByType     = "type"           // the crate declares the self type, or the type under &, &mut, Box or Pin
+	ImplOwnedByArgument = "trait_argument" // the crate declares a type among the trait's arguments: impl From<Local> for Vec<u8>
+)
+
+// Kinds of overlap between two impls of a trait.
+const (
+	ImplOverlapIdentical = "identical" // the impls are for the same type with the same trait arguments
+	ImplOverlapBlanket   = "blanket"   // one impl is for any type: impl<T> Trait for T
+	ImplOverlapGeneric   = "generic"   // the types unify once generic parameters are substituted
+)
+
+// TraitImpl is a Rust impl of a trait for a type: impl<T> Trait for Type<T>.
+type TraitImpl struct {
+	Location Location // the impl block
+	Crate    string   // the crate declaring the impl; "" outside a Cargo package
+	Trait    string   // the trait as written, with its arguments: From<u8>
+	Type     string   // the self type as written: Vec<T>
+	Generics []string // the impl's type and const parameters
+	Negative bool     // impl !Trait for Type
+
+	TraitSymbol *SymbolResult // the trait or its std or dependency stub; nil if not indexed
+	TypeSymbol  *SymbolResult // the self type's item, under &, &mut, Box or Pin; nil if not indexed
+	// TraitCrate and TypeCrate are the crates declaring the trait and the
+	// self type: the crate of the indexed item or stub, or else the crate
+	// the path names through the file's use declarations or the prelude.
+	// Empty when unknown, or for a self type that is a generic parameter.
+	TraitCrate string
+	TypeCrate  string
+
+	// OwnedBy is the ImplOwnedBy reason the impl's crate may define the
+	// impl. Orphan marks an impl whose crate declares neither the trait nor
+	// any type in it, which rustc rejects; it is only set when the crates of
+	// the trait and the types are known.
+	OwnedBy string
+	Orphan  bool
+
+	traitType rustType
+	selfType  rustType
+}
+
+// ImplOverlap is a pair of impls of one trait that may both apply to some
+// type, which rustc rejects unless where clauses or cfg attributes keep
+// them apart.
+type ImplOverlap struct {
+	Kind   string // one of the ImplOverlap constants
+	First  int    // index into the trait's Impls
+	Second int    // index into the trait's Impls, after First
+}
+
+// TraitImpls are the impls of one trait.
+type TraitImpls struct {
+	Trait       string        // the trait's name
+	TraitSymbol *SymbolResult // nil if the trait is not indexed
+	Impls       []TraitImpl   // ordered by file and position
+	Overlaps    []ImplOverlap
+}
+
+// ImplAudit lists the Rust trait impls in files under pathPrefix ("" for
+// all files), grouped by trait, with the crates owning each trait/type
+// pair and the pairs of impls that overlap. Impls of an indexed trait are
+// grouped by its symbol and others by name. Two impls overlap when their
+// self types and trait arguments unify, taking each impl's generic
+// parameters as any type; bounds and where clauses are not checked, so a
+// generic or blanket overlap is a candidate rather than a certain
+// conflict. Groups are ordered by trait name.
+func (q *QueryBuilder) ImplAudit(pathPrefix string) ([]TraitImpls, error) {
+	files, err := q.store.FilesByLanguage("rust")
+	if err != nil {
+		return nil, fmt.Errorf("impl audit: %w", err)
+	}
+	sort.Slice(files, func(i, j int) bool { return files[i].Path < files[j].Path })
+
+	packages := map[string]cargoPackage{}
+	crateOf := func(path string) string {
+		if m, ok := q.rustModuleOf(path, packages); ok {
+			return m.crate
+		}
+		return ""
+	}
+
+	groups := map[string]*TraitImpls{}
+	var keys []string
+	for _, f := range files {
+		if pathPrefix != "" && !strings.HasPrefix(f.Path, pathPrefix) {
+			continue
+		}
+		impls, err := q.fileTraitImpls(f, crateOf)
+		if err != nil {
+			return nil, fmt.Errorf("impl audit: %w", err)
+		}
+		for _, im := range impls {
+			key, name := "name:"+im.traitType.head, im.traitType.head
+			if im.TraitSymbol != nil {
+				key, name = fmt.Sprintf("id:%d", im.TraitSymbol.ID), im.TraitSymbol.Name
+			}
+			g := groups[key]
+			if g == nil {
+				g = &TraitImpls{Trait: name, TraitSymbol: im.TraitSymbol}
+				groups[key] = g
+				keys = append(keys, key)
+			}
+			g.Impls = append(g.Impls, im)
+		}
+	}
+
+	out := make([]TraitImpls, 0, len(keys))
+	for _, key := range keys {
+		g := groups[key]
+		g.Overlaps = implOverlaps(g.Impls)
+		out = append(out, *g)
+	}
+	sort.SliceStable(out, func(i, j int) bool { return out[i].Trait < out[j].Trait })
+	return out, nil
+}
+
+// fileTraitImpls parses the headers of the trait impls in a file and
+// resolves the trait and types they name.
+func (q *QueryBuilder) fileTraitImpls(f *store.File, crateOf func(string) string) ([]TraitImpl, error) {
+	scopes, err := q.store.ScopesByFile(f.ID)
+	if err != nil {
+		return nil, err
+	}
+	var implScopes []*store.Scope
+	for _, s := range scopes {
+		if s.Kind == "impl" {
+			implScopes = append(implScopes, s)
+		}
+	}
+	if len(implScopes) == 0 {
+		return nil, nil
+	}
+	sort.Slice(implScopes, func(i, j int) bool {
+		if implScopes[i].StartLine != implScopes[j].StartLine {
+			return implScopes[i].StartLine < implScopes[j].StartLine
+		}
+		return implScopes[i].StartCol < implScopes[j].StartCol
+	})
+	src, err := q.readSource(f.Path)
+	if err != nil {
+		return nil, nil // a file that cannot be read has no headers to parse
+	}
+	names, err := q.fileImplNames(f, crateOf)
+	if err != nil {
+		return nil, err
+	}
+
+	li := newLineIndex(src)
+	var impls []TraitImpl
+	for _, s := range implScopes {
+		start, startUTF16 := li.offsets(s.StartLine, s.StartCol)
+		end, endUTF16 := li.offsets(s.EndLine, s.EndCol)
+		h, ok := parseRustImplHeader(string(src[start:end]))
+		if !ok {
+			continue // an inherent impl
+		}
+		im := TraitImpl{
+			Location: Location{
+				File: f.Path, StartLine: s.StartLine, StartCol: s.StartCol, EndLine: s.EndLine, EndCol: s.EndCol,
+				StartByte: start, EndByte: end, UTF16StartCol: startUTF16, UTF16EndCol: endUTF16,
+			},
+			Crate:     names.crate,
+			Trait:     h.trait,
+			Type:      h.self,
+			Generics:  h.generics,
+			Negative:  h.negative,
+			traitType: parseRustType(h.trait),
+			selfType:  parseRustType(h.self),
+		}
+		generics := map[string]bool{}
+		for _, g := range h.generics {
+			generics[g] = true
+		}
+		traitStart, traitEnd := start+h.traitStart, start+h.traitEnd
+		selfStart, selfEnd := start+h.selfStart, start+h.selfEnd
+
+		var traitKnown, typeKnown bool
+		if im.TraitSymbol, im.TraitCrate, traitKnown, err = names.owner(im.traitType, nil, traitStart, traitEnd); err != nil {
+			return nil, err
+		}
+		if im.TypeSymbol, im.TypeCrate, typeKnown, err = names.owner(im.selfType.ownedType(), generics, selfStart, selfEnd); err != nil {
+			return nil, err
+		}
+		if names.crate == "" {
+			impls = append(impls, im)
+			continue
+		}
+		known := traitKnown && typeKnown
+		switch {
+		case im.TraitCrate == names.crate:
+			im.OwnedBy = ImplOwnedByTrait
+		case im.TypeCrate == names.crate:
+			im.OwnedBy = ImplOwnedByType
+		default:
+			for _, arg := range im.traitType.args {
+				_, crate, argKnown, err := names.owner(arg.ownedType(), generics, traitStart, traitEnd)
+				if err != nil {
+					return nil, err
+				}
+				if crate == names.crate {
+					im.OwnedBy = ImplOwnedByArgument
+					break
+				}
+				known = known && argKnown
+			}
+		}
+		im.Orphan = im.OwnedBy == "" && known
+		impls = append(impls, im)
+	}
+	return impls, nil
+}
+
+// ImplBlock is a Rust impl block defining methods of a type: an inherent
+// impl (impl Circle) or an impl of a trait (impl Drawable for Circle).
+type ImplBlock struct {
+	Location    Location
+	Header      string         // the block header, e.g. "impl<T> Drawable for Circle<T>"
+	Trait       string         // the trait as written, with its arguments; "" for an inherent impl
+	TraitSymbol *SymbolResult  // nil for an inherent impl or a trait that is not indexed
+	Methods     []SymbolResult // methods and associated functions, ordered by position
+}
+
+// ImplBlocks returns the impl blocks defining the methods and associated
+// functions of a Rust type, ordered by file and position, so "all methods
+// of Circle" come with the trait each implements. Methods of other
+// languages, which have no impl blocks, are not returned. The blocks are
+// those recorded when their files were indexed, so no source is read.
+// Returns nil, nil if the symbol does not exist.
+func (q *QueryBuilder) ImplBlocks(typeSymbolID int64) ([]ImplBlock, error) {
+	typ, err := q.symbolResultByID(typeSymbolID)
+	if err != nil {
+		return nil, fmt.Errorf("impl blocks: %w", err)
+	}
+	if typ == nil {
+		return nil, nil
+	}
+	children, err := q.store.SymbolChildren(typeSymbolID)
+	if err != nil {
+		return nil, fmt.Errorf("impl blocks: %w", err)
+	}
+	var ids []int64
+	for _, c := range children {
+		if c.Kind == "function" || c.Kind == "method" {
+			ids = append(ids, c.ID)
+		}
+	}
+	methods, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("impl blocks: %w", err)
+	}
+	byFile := map[string][]*SymbolResult{}
+	var paths []string
+	for _, id := range ids {
+		m, ok := methods[id]
+		if !ok || m.FilePath == "" {
+			continue
+		}
+		if _, seen := byFile[m.FilePath]; !seen {
+			paths = append(paths, m.FilePath)
+		}
+		byFile[m.FilePath] = append(byFile[m.FilePath], m)
+	}
+	sort.Strings(paths)
+
+	packages := map[string]cargoPackage{}
+	crateOf := func(path string) string {
+		if m, ok := q.rustModuleOf(path, packages); ok {
+			return m.crate
+		}
+		return ""
+	}
+	blocks := []ImplBlock{}
+	for _, path := range paths {
+		fileBlocks, err := q.fileImplBlocks(path, byFile[path], crateOf)
+		if err != nil {
+			return nil, fmt.Errorf("impl blocks: %w", err)
+		}
+		blocks = append(blocks, fileBlocks...)
+	}
+	return blocks, nil
+}
+
+// fileImplBlocks groups the methods of one file by the innermost impl block
+// containing each, among the blocks recorded when the file was indexed.
+func (q *QueryBuilder) fileImplBlocks(path string, methods []*SymbolResult, crateOf func(string) string) ([]ImplBlock, error) {
+	f, err := q.store.FileByPath(path)
+	if err != nil || f == nil {
+		return nil, err
+	}
+	stored, err := q.store.ImplBlocksByFile(f.ID)
+	if err != nil || len(stored) == 0 {
+		return nil, err
+	}
+
+	var names *implNames // loaded for the first block implementing a trait
+	byBlock := map[int64]*ImplBlock{}
+	var order []*store.ImplBlock
+	for _, m := range methods {
+		var innermost *store.ImplBlock
+		for _, sb := range stored {
+			if implBlockContains(sb, m.StartLine, m.StartCol) && (innermost == nil || implBlockContains(innermost, sb.StartLine, sb.StartCol)) {
+				innermost = sb
+			}
+		}
+		if innermost == nil {
+			continue
+		}
+		b := byBlock[innermost.ID]
+		if b == nil {
+			b = &ImplBlock{
+				Location: Location{
+					File: path, StartLine: innermost.StartLine, StartCol: innermost.StartCol,
+					EndLine: innermost.EndLine, EndCol: innermost.EndCol,
+					StartByte: innermost.StartByte, EndByte: innermost.EndByte,
+					UTF16StartCol: innermost.UTF16StartCol, UTF16EndCol: innermost.UTF16EndCol,
+				},
+				Header: innermost.Header,
+				Trait:  innermost.Trait,
+			}
+			if innermost.Trait != "" {
+				if names == nil {
+					if names, err = q.fileImplNames(f, crateOf); err != nil {
+						return nil, err
+					}
+				}
+				if b.TraitSymbol, _, _, err = names.owner(parseRustType(innermost.Trait), nil, innermost.TraitStartByte, innermost.TraitEndByte); err != nil {
+					return nil, err
+				}
+			}
+			byBlock[innermost.ID] = b
+			order = append(order, innermost)
+		}
+		b.Methods = append(b.Methods, *m)
+	}
+
+	blocks := make([]ImplBlock, 0, len(order))
+	for _, sb := range order {
+		b := byBlock[sb.ID]
+		sort.Slice(b.Methods, func(i, j int) bool {
+			if b.Methods[i].StartLine != b.Methods[j].StartLine {
+				return b.Methods[i].StartLine < b.Methods[j].StartLine
+			}
+			return b.Methods[i].StartCol < b.Methods[j].StartCol
+		})
+		blocks = append(blocks, *b)
+	}
+	sort.Slice(blocks, func(i, j int) bool {
+		if blocks[i].Location.StartLine != blocks[j].Location.StartLine {
+			return blocks[i].Location.StartLine < blocks[j].Location.StartLine
+		}
+		return blocks[i].Location.StartCol < blocks[j].Location.StartCol
+	})
+	return blocks, nil
+}
+
+// implBlockContains reports whether the position line:col lies within b.
+func implBlockContains(b *store.ImplBlock, line, col int) bool {
+	if line < b.StartLine || line > b.EndLine {
+		return false
+	}
+	if line == b.StartLine && col < b.StartCol {
+		return false
+	}
+	return line != b.EndLine || col <= b.EndCol
+}
+
+// implNames resolves the names in the impl headers of one file.
+type implNames struct {
+	q       *QueryBuilder
+	crate   string
+	crateOf func(path string) string
+	refs    []*store.Reference
+	imports []*store.Import
+}
+
+// fileImplNames loads the references and imports resolving the names in
+// the impl headers of f.
+func (q *QueryBuilder) fileImplNames(f *store.File, crateOf func(string) string) (*implNames, error) {
+	refs, err := q.store.ReferencesByFile(f.ID)
+	if err != nil {
+		return nil, err
+	}
+	imports, err := q.store.ImportsByFile(f.ID)
+	if err != nil {
+		return nil, err
+	}
+	return &implNames{q: q, crate: crateOf(f.Path), crateOf: crateOf, refs: refs, imports: imports}, nil
+}
+
+// owner returns the indexed item named by t's head, looked for among the
+// references between byte offsets start and end, and the crate declaring
+// it. known is false when the crate cannot be told. A generic parameter
+// has no item and no crate, but is known.
+func (n *implNames) owner(t rustType, generics map[string]bool, start, end int) (*SymbolResult, string, bool, error) {
+	switch {
+	case t.isParam(generics):
+		return nil, "", true, nil
+	case rustBuiltinTypes[t.head]:
+		return nil, "std", true, nil
+	}
+	for _, ref := range n.refs {
+		if ref.Name != t.head || ref.StartByte < start || ref.StartByte >= end {
+			continue
+		}
+		resolved, err := n.q.store.ResolvedReferencesByRef(ref.ID)
+		if err != nil {
+			return nil, "", false, err
+		}
+		if len(resolved) == 0 {
+			break
+		}
+		sym, err := n.q.symbolResultByID(resolved[0].TargetSymbolID)
+		if err != nil {
+			return nil, "", false, err
+		}
+		if sym == nil {
+			break
+		}
+		var crate string
+		if stub, ok := strings.CutPrefix(sym.FilePath, rustdocStubPrefix); ok {
+			crate, _, _ = strings.Cut(stub, "::")
+		} else if sym.FilePath != "" {
+			crate = n.crateOf(sym.FilePath)
+		}
+		if crate == "" {
+			crate = rustPathCrate(t.path, n.crate, n.imports)
+		}
+		return sym, crate, crate != "", nil
+	}
+	crate := rustPathCrate(t.path, n.crate, n.imports)
+	return nil, crate, crate != "", nil
+}
+
+// rustPathCrate returns the crate an item path written in a file of crate
+// names, judging by its first segment: crate for crate::, self:: and
+// super::, the crate a use declaration of the segment imports from, the
+// crate a multi-segment path starts with, and "std" for the prelude. It
+// returns "" for a name it cannot place.
+func rustPathCrate(path, crate string, imports []*store.Import) string {
+	first, _, qualified := strings.Cut(path, "::")
+	switch first {
+	case "":
+		return ""
+	case "crate", "self", "super":
+		return crate
+	}
+	for _, imp := range imports {
+		name := imp.ImportedName
+		if imp.LocalAlias != nil {
+			name = imp.LocalAlias
+		}
+		if name == nil || *name != first || imp.Source == "" {
+			continue
+		}
+		switch from, _, _ := strings.Cut(imp.Source, "::"); from {
+		case "crate", "self", "super":
+			return crate
+		default:
+			return from
+		}
+	}
+	if qualified {
+		return first
+	}
+	if rustPreludeTraits[first] || rustPreludeTypes[first] {
+		return "std"
+	}
+	return ""
+}
+
+// rustPreludeTraits are the traits the Rust prelude brings into every module.
+var rustPreludeTraits = map[string]bool{
+	"AsMut": true, "AsRef": true, "Clone": true, "Copy": true, "Default": true,
+	"DoubleEndedIterator": true, "Drop": true, "Eq": true, "ExactSizeIterator": true,
+	"Extend": true, "Fn": true, "FnMut": true, "FnOnce": true, "From": true,
+	"FromIterator": true, "Into": true, "IntoIterator": true, "Iterator": true,
+	"Ord": true, "PartialEq": true, "PartialOrd": true, "Send": true, "Sized": true,
+	"Sync": true, "ToOwned": true, "ToString": true, "TryFrom": true, "TryInto": true,
+	"Unpin": true,
+}
+
+// rustPreludeTypes are the types the Rust prelude brings into every module.
+var rustPreludeTypes = map[string]bool{
+	"Box": true, "Option": true, "Result": true, "String": true, "Vec": true,
+}
+
+// rustBuiltinTypes are the heads of the primitive and structural types,
+// which belong to no crate of the workspace.
+var rustBuiltinTypes = map[string]bool{
+	"bool": true, "char": true, "str": true, "f32": true, "f64": true,
+	"i8": true, "i16": true, "i32": true, "i64": true, "i128": true, "isize": true,
+	"u8": true, "u16": true, "u32": true, "u64": true, "u128": true, "usize": true,
+	"&": true, "&mut": true, "*const": true, "*mut": true, "()": true, "[]": true, "[;]": true,
+	"fn": true, "!": true, "impl": true,
+}
+
+// implOverlaps returns the pairs of impls whose self types and trait
+// arguments unify, taking each impl's generic parameters as any type.
+func implOverlaps(impls []TraitImpl) []ImplOverlap {
+	var overlaps []ImplOverlap
+	params := make([]map[string]bool, len(impls))
+	for i, im := range impls {
+		params[i] = map[string]bool{}
+		for _, g := range im.Generics {
+			params[i][g] = true
+		}
+	}
+	for i := range impls {
+		for j := i + 1; j < len(impls); j++ {
+			a, b := impls[i], impls[j]
+			if a.TypeSymbol != nil && b.TypeSymbol != nil && a.TypeSymbol.ID != b.TypeSymbol.ID {
+				continue
+			}
+			pa, pb := params[i], params[j]
+			if !unifyRustTypes(a.selfType, b.selfType, pa, pb) || !unifyRustTypeArgs(a.traitType.args, b.traitType.args, pa, pb) {
+				continue
+			}
+			kind := ImplOverlapGeneric
+			switch {
+			case a.selfType.isParam(pa) || b.selfType.isParam(pb):
+				kind = ImplOverlapBlanket
+			case !a.selfType.mentions(pa) && !b.selfType.mentions(pb) &&
+				!a.traitType.mentions(pa) && !b.traitType.mentions(pb):
+				kind = ImplOverlapIdentical
+			}
+			overlaps = append(overlaps, ImplOverlap{Kind: kind, First: i, Second: j})
+		}
+	}
+	return overlaps
+}
+
+// unifyRustTypes reports whether some substitution of the generic
+// parameters pa in a and pb in b makes the types equal. Each occurrence of
+// a parameter is matched independently, so the answer errs toward unifying.
+func unifyRustTypes(a, b rustType, pa, pb map[string]bool) bool {
+	if a.isParam(pa) || b.isParam(pb) {
+		return true
+	}
+	return a.head == b.head && unifyRustTypeArgs(a.args, b.args, pa, pb)
+}
+
+func unifyRustTypeArgs(a, b []rustType, pa, pb map[string]bool) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for i := range a {
+		if !unifyRustTypes(a[i], b[i], pa, pb) {
+			return false
+		}
+	}
+	return true
+}
+
+// rustType is a parsed Rust type. A path type has the path as written
+// without generic arguments ("fmt::Display"), its last segment as head
+// and its generic arguments as args. Other types have a head naming their
+// form ("&", "&mut", "*const", "*mut", "()", "[]" for a slice, "[;]" for
+// an array, "fn", "dyn", "impl", "!") and their element types as args.
+type rustType struct {
+	path string
+	head string
+	args []rustType
+}
+
+// isParam reports whether t is one of the generic parameters, or a
+// projection such as T::Item that depends on one.
+func (t rustType) isParam(params map[string]bool) bool {
+	first, _, _ := strings.Cut(t.path, "::")
+	return params[first] && (len(t.args) == 0 || t.path != first)
+}
+
+// mentions reports whether t or any of its arguments is a generic parameter.
+func (t rustType) mentions(params map[string]bool) bool {
+	if t.isParam(params) {
+		return true
+	}
+	for _, a := range t.args {
+		if a.mentions(params) {
+			return true
+		}
+	}
+	return false
+}
+
+// ownedType returns the type the orphan rule looks at for t: t itself, the
+// type under the fundamental &, &mut, Box and Pin, or the trait of a trait
+// object.
+func (t rustType) ownedType() rustType {
+	for len(t.args) > 0 {
+		switch t.head {
+		case "&", "&mut", "Box", "Pin", "dyn":
+			t = t.args[0]
+			continue
+		}
+		break
+	}
+	return t
+}
+
+// rustImplHeader is the header of an impl block, with the byte offsets of
+// the trait and self type in the block's text.
+type rustImplHeader struct {
+	generics             []string
+	negative             bool
+	trait, self          string // as written, with runs of whitespace collapsed
+	traitStart, traitEnd int
+	selfStart, selfEnd   int
+}
+
+// parseRustImplHeader parses the header of the impl block text, up to its
+// where clause or body. ok is false for an inherent impl.
+func parseRustImplHeader(text string) (h rustImplHeader, ok bool) {
+	i := strings.Index(text, "impl")
+	if i < 0 {
+		return h, false
+	}
+	i = skipRustSpace(text, i+len("impl"))
+	if i < len(text) && text[i] == '<' {
+		end := rustHeaderScan(text, i+1, ">")
+		if end >= len(text) {
+			return h, false
+		}
+		h.generics = rustGenericParams(text[i+1 : end])
+		i = end + 1
+	}
+	end := rustHeaderScan(text, i, "{")
+	if where := rustHeaderScan(text, i, "where"); where < end {
+		end = where
+	}
+	forAt := rustHeaderScan(text, i, "for")
+	if forAt >= end {
+		return h, false
+	}
+	h.traitStart, h.traitEnd = trimRustSpan(text, i, forAt)
+	if h.traitStart < h.traitEnd && text[h.traitStart] == '!' {
+		h.negative = true
+		h.traitStart, h.traitEnd = trimRustSpan(text, h.traitStart+1, h.traitEnd)
+	}
+	h.selfStart, h.selfEnd = trimRustSpan(text, forAt+len("for"), end)
+	h.trait = strings.Join(strings.Fields(text[h.traitStart:h.traitEnd]), " ")
+	h.self = strings.Join(strings.Fields(text[h.selfStart:h.selfEnd]), " ")
+	return h, h.trait != "" && h.self != ""
+}
+
+// rustHeaderScan returns the offset of the first stop from offset i of
+// text that is not nested in brackets, or len(text). A stop of letters is
+// matched as a whole word; "->" does not close an angle bracket.
+func rustHeaderScan(text string, i int, stop string) int {
+	depth := 0
+	word := isRustIdentByte(stop[0])
+	for ; i < len(text); i++ {
+		c := text[i]
+		if c == '>' && i > 0 && text[i-1] == '-' {
+			continue
+		}
+		if depth == 0 && strings.HasPrefix(text[i:], stop) &&
+			(!word || ((i == 0 || !isRustIdentByte(text[i-1])) &&
+				(i+len(stop) == len(text) || !isRustIdentByte(text[i+len(stop)])))) {
+			return i
+		}
+		switch c {
+		case '<', '(', '[':
+			depth++
+		case ')', ']', '>':
+			depth--
+		}
+	}
+	return len(text)
+}
+
+// rustGenericParams returns the names of the type and const parameters in
+// a generic parameter list, skipping lifetimes.
+func rustGenericParams(list string) []string {
+	var names []string
+	for i := 0; i < len(list); {
+		end := rustHeaderScan(list, i, ",")
+		param := strings.TrimSpace(list[i:end])
+		i = end + 1
+		if param == "" || param[0] == '\'' {
+			continue
+		}
+		param = strings.TrimSpace(strings.TrimPrefix(param, "const "))
+		n := 0
+		for n < len(param) && isRustIdentByte(param[n]) {
+			n++
+		}
+		if n > 0 {
+			names = append(names, param[:n])
+		}
+	}
+	return names
+}
+
+// parseRustType parses a type as written in an impl header. It is lenient:
+// lifetimes, associated type bindings and array lengths are skipped.
+func parseRustType(s string) rustType {
+	p := &rustTypeParser{toks: rustTypeTokens(s)}
+	return p.parseType()
+}
+
+type rustTypeParser struct {
+	toks []string
+	pos  int
+}
+
+func (p *rustTypeParser) peek() string {
+	if p.pos < len(p.toks) {
+		return p.toks[p.pos]
+	}
+	return ""
+}
+
+func (p *rustTypeParser) next() string {
+	tok := p.peek()
+	if tok != "" {
+		p.pos++
+	}
+	return tok
+}
+
+// skipTo consumes tokens up to the first of stops not nested in brackets,
+// leaving it unconsumed.
+func (p *rustTypeParser) skipTo(stops ...string) {
+	depth := 0
+	for tok := p.peek(); tok != ""; tok = p.peek() {
+		if depth == 0 {
+			for _, s := range stops {
+				if tok == s {
+					return
+				}
+			}
+		}
+		switch tok {
+		case "<", "(", "[":
+			depth++
+		case ">", ")", "]":
+			depth--
+		}
+		p.next()
+	}
+}
+
+func (p *rustTypeParser) parseType() rustType {
+	switch tok := p.next(); tok {
+	case "":
+		return rustType{}
+	case "&":
+		if strings.HasPrefix(p.peek(), "'") {
+			p.next()
+		}
+		head := "&"
+		if p.peek() == "mut" {
+			p.next()
+			head = "&mut"
+		}
+		return rustType{head: head, args: []rustType{p.parseType()}}
+	case "*":
+		head := "*" + p.next()
+		return rustType{head: head, args: []rustType{p.parseType()}}
+	case "(":
+		t := rustType{head: "()"}
+		for p.peek() != ")" && p.peek() != "" {
+			t.args = append(t.args, p.parseType())
+			if p.peek() == "," {
+				p.next()
+			}
+		}
+		p.next()
+		return t
+	case "[":
+		t := rustType{head: "[]", args: []rustType{p.parseType()}}
+		if p.peek() == ";" {
+			t.head = "[;]"
+			p.skipTo("]")
+		}
+		p.next()
+		return t
+	case "dyn", "impl":
+		t := rustType{head: tok, args: []rustType{p.parseType()}}
+		for p.peek() == "+" {
+			p.next()
+			p.parseType()
+		}
+		return t
+	case "!":
+		return rustType{head: "!"}
+	case "fn", "unsafe", "extern", "for":
+		p.skipTo(",", ">", ")", "]", ";")
+		return rustType{head: "fn"}
+	case "<":
+		// A qualified path, <T as Trait>::Name: kept as the projection.
+		p.skipTo(">")
+		p.next()
+		t := rustType{head: "<>"}
+		for p.peek() == "::" {
+			p.next()
+			t.head = p.next()
+		}
+		return t
+	case "::":
+		return p.parsePath(p.next())
+	default:
+		return p.parsePath(tok)
+	}
+}
+
+// parsePath parses the rest of a path type whose first segment is first.
+func (p *rustTypeParser) parsePath(first string) rustType {
+	t := rustType{path: first, head: first}
+	for {
+		if p.peek() == "<" {
+			t.args = p.parseArgs()
+		} else if p.peek() == "(" && (t.head == "Fn" || t.head == "FnMut" || t.head == "FnOnce") {
+			p.next()
+			args := rustType{head: "()"}
+			for p.peek() != ")" && p.peek() != "" {
+				args.args = append(args.args, p.parseType())
+				if p.peek() == "," {
+					p.next()
+				}
+			}
+			p.next()
+			t.args = []rustType{args}
+			if p.peek() == "->" {
+				p.next()
+				t.args = append(t.args, p.parseType())
+			}
+		}
+		if p.peek() != "::" {
+			return t
+		}
+		p.next()
+		seg := p.next()
+		t.path += "::" + seg
+		t.head = seg
+	}
+}
+
+// parseArgs parses a generic argument list, skipping lifetimes and
+// associated type bindings.
+func (p *rustTypeParser) parseArgs() []rustType {
+	p.next() // <
+	var args []rustType
+	for tok := p.peek(); tok != ">" && tok != ""; tok = p.peek() {
+		switch {
+		case tok == ",":
+			p.next()
+		case strings.HasPrefix(tok, "'"):
+			p.next()
+		case p.pos+1 < len(p.toks) && p.toks[p.pos+1] == "=":
+			p.next()
+			p.next()
+			p.parseType()
+		case tok == "{":
+			p.skipTo(",", ">")
+		default:
+			args = append(args, p.parseType())
+		}
+	}
+	p.next()
+	return args
+}
+
+// rustTypeTokens splits a type into identifiers, lifetimes, "::", "->" and
+// single punctuation characters.
+func rustTypeTokens(s string) []string {
+	var toks []string
+	for i := 0; i < len(s); {
+		c := s[i]
+		switch {
+		case c == ' ' || c == '\t' || c == '\n' || c == '\r':
+			i++
+		case strings.HasPrefix(s[i:], "::"), strings.HasPrefix(s[i:], "->"):
+			toks = append(toks, s[i:i+2])
+			i += 2
+		case c == '\'' || isRustIdentByte(c):
+			j := i + 1
+			for j < len(s) && isRustIdentByte(s[j]) {
+				j++
+			}
+			toks = append(toks, s[i:j])
+			i = j
+		default:
+			toks = append(toks, s[i:i+1])
+			i++
+		}
+	}
+	return toks
+}
+
+func isRustIdentByte(c byte) bool {
+	return c == '_' || c >= 0x80 || ('a' <= c && c <= 'z') || ('A' <= c && c <= 'Z') || ('0' <= c && c <= '9')
+}
+
+func skipRustSpace(text string, i int) int {
+	for i < len(text) && strings.IndexByte(" \t\r\n", text[i]) >= 0 {
+		i++
+	}
+	return i
+}
+
+// trimRustSpan returns [start, end) with surrounding whitespace removed.
+func trimRustSpan(text string, start, end int) (int, int) {
+	start = skipRustSpace(text, start)
+	for end > start && strings.IndexByte(" \t\r\n", text[end-1]) >= 0 {
+		end--
+	}
+	return start, end
+}
@@ -0,0 +1,239 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseRustImplHeader(t *testing.T) {
+	h, ok := parseRustImplHeader("unsafe impl<'a, T: Into<u8>, const N: usize> !Send for &'a [T; N]\nwhere\n    T: Fn() -> u8,\n{}")
+	require.True(t, ok)
+	assert.Equal(t, []string{"T", "N"}, h.generics)
+	assert.True(t, h.negative)
+	assert.Equal(t, "Send", h.trait)
+	assert.Equal(t, "&'a [T; N]", h.self)
+
+	h, ok = parseRustImplHeader("impl<F: Fn() -> u8> Transform for F {\n    fn apply(&self) {}\n}")
+	require.True(t, ok)
+	assert.Equal(t, []string{"F"}, h.generics)
+	assert.Equal(t, "Transform", h.trait)
+	assert.Equal(t, "F", h.self)
+	text := "impl<F: Fn() -> u8> Transform for F {"
+	assert.Equal(t, "Transform", text[h.traitStart:h.traitEnd])
+	assert.Equal(t, "F", text[h.selfStart:h.selfEnd])
+
+	_, ok = parseRustImplHeader("impl<T> Wrapper<T> {\n    fn get(&self) {}\n}")
+	assert.False(t, ok, "inherent impl")
+}
+
+func TestParseRustType(t *testing.T) {
+	assert.Equal(t, rustType{path: "HashMap", head: "HashMap", args: []rustType{
+		{path: "String", head: "String"},
+		{path: "Vec", head: "Vec", args: []rustType{
+			{head: "()", args: []rustType{
+				{path: "u8", head: "u8"},
+				{head: "&", args: []rustType{{path: "str", head: "str"}}},
+			}},
+		}},
+	}}, parseRustType("HashMap<String, Vec<(u8, &'static str)>>"))
+	assert.Equal(t, rustType{path: "std::iter::Iterator", head: "Iterator"}, parseRustType("std::iter::Iterator<Item = u8>"))
+	assert.Equal(t, rustType{path: "Local", head: "Local"}, parseRustType("Box<dyn Local + Send>").ownedType())
+
+	params := map[string]bool{"T": true}
+	assert.True(t, unifyRustTypes(parseRustType("Vec<T>"), parseRustType("Vec<u8>"), params, nil))
+	assert.True(t, unifyRustTypes(parseRustType("T"), parseRustType("&Config"), params, nil))
+	assert.False(t, unifyRustTypes(parseRustType("Vec<u8>"), parseRustType("Vec<u16>"), nil, nil))
+	assert.False(t, unifyRustTypes(parseRustType("Vec<T>"), parseRustType("Option<u8>"), params, nil))
+}
+
+func TestImplAudit(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", `use std::fmt;
+
+pub trait Greet {}
+pub trait Named {}
+pub struct Config;
+pub struct Wrapper<T>(T);
+
+impl Greet for Config {}
+impl<T> Greet for T {}
+
+impl Named for Wrapper<u8> {}
+impl<T: Clone> Named for Wrapper<T> where T: Send {}
+impl Named for Wrapper<u16> {}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl From<Config> for Vec<u8> {
+    fn from(_: Config) -> Self {
+        Vec::new()
+    }
+}
+
+impl Clone for String {
+    fn clone(&self) -> Self {
+        String::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config
+    }
+}
+`)
+	lib := filepath.Join(root, "src", "lib.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	traits, err := e.Query().ImplAudit("")
+	require.NoError(t, err)
+	var names []string
+	byTrait := map[string]TraitImpls{}
+	for _, tr := range traits {
+		names = append(names, tr.Trait)
+		byTrait[tr.Trait] = tr
+	}
+	require.Equal(t, []string{"Clone", "Display", "From", "Greet", "Named"}, names)
+
+	greet := byTrait["Greet"]
+	require.NotNil(t, greet.TraitSymbol)
+	assert.Equal(t, lib, greet.TraitSymbol.FilePath)
+	require.Len(t, greet.Impls, 2)
+	assert.Equal(t, "Config", greet.Impls[0].Type)
+	assert.Equal(t, lib, greet.Impls[0].Location.File)
+	assert.Equal(t, 7, greet.Impls[0].Location.StartLine)
+	assert.Equal(t, 24, greet.Impls[0].Location.EndCol)
+	assert.Equal(t, []string{"T"}, greet.Impls[1].Generics)
+	for _, im := range greet.Impls {
+		assert.Equal(t, "demo", im.Crate)
+		assert.Equal(t, "demo", im.TraitCrate)
+		assert.Equal(t, ImplOwnedByTrait, im.OwnedBy)
+		assert.False(t, im.Orphan)
+	}
+	assert.Empty(t, greet.Impls[1].TypeCrate, "a generic parameter belongs to no crate")
+	assert.Equal(t, []ImplOverlap{{Kind: ImplOverlapBlanket, First: 0, Second: 1}}, greet.Overlaps)
+
+	named := byTrait["Named"]
+	require.Len(t, named.Impls, 3)
+	assert.Equal(t, "Wrapper<T>", named.Impls[1].Type)
+	require.NotNil(t, named.Impls[1].TypeSymbol)
+	assert.Equal(t, "Wrapper", named.Impls[1].TypeSymbol.Name)
+	assert.Equal(t, []ImplOverlap{
+		{Kind: ImplOverlapGeneric, First: 0, Second: 1},
+		{Kind: ImplOverlapGeneric, First: 1, Second: 2},
+	}, named.Overlaps)
+
+	display := byTrait["Display"].Impls
+	require.Len(t, display, 1)
+	assert.Equal(t, "fmt::Display", display[0].Trait)
+	assert.Contains(t, []string{"std", "core"}, display[0].TraitCrate)
+	assert.Equal(t, "demo", display[0].TypeCrate)
+	assert.Equal(t, ImplOwnedByType, display[0].OwnedBy)
+
+	from := byTrait["From"].Impls
+	require.Len(t, from, 1)
+	assert.Equal(t, "From<Config>", from[0].Trait)
+	assert.Equal(t, "Vec<u8>", from[0].Type)
+	assert.Equal(t, ImplOwnedByArgument, from[0].OwnedBy)
+	assert.False(t, from[0].Orphan)
+
+	clone := byTrait["Clone"].Impls
+	require.Len(t, clone, 1)
+	assert.Empty(t, clone[0].OwnedBy)
+	assert.True(t, clone[0].Orphan)
+	assert.Empty(t, byTrait["Clone"].Overlaps)
+
+	none, err := e.Query().ImplAudit(filepath.Join(root, "tests"))
+	require.NoError(t, err)
+	assert.Empty(t, none)
+}
+
+func TestImplBlocks(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n"), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`pub trait Drawable {
+    fn draw(&self) -> String;
+    fn area(&self) -> f64;
+}
+
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Circle {
+        Circle { radius }
+    }
+}
+
+impl Drawable for Circle {
+    fn draw(&self) -> String {
+        "circle".to_string()
+    }
+
+    fn area(&self) -> f64 {
+        3.14 * self.radius * self.radius
+    }
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	// The blocks are recorded at index time, so the query needs no source.
+	require.NoError(t, os.Remove(filepath.Join(root, "src", "lib.rs")))
+
+	structs, err := e.Query().Symbols(SymbolFilter{Kinds: []string{"struct"}}, Sort{Field: SortByName, Order: Asc}, Pagination{Limit: intP(10)})
+	require.NoError(t, err)
+	require.Len(t, structs.Items, 1)
+	blocks, err := e.Query().ImplBlocks(structs.Items[0].ID)
+	require.NoError(t, err)
+	require.Len(t, blocks, 2)
+
+	inherent := blocks[0]
+	assert.Equal(t, "impl Circle", inherent.Header)
+	assert.Empty(t, inherent.Trait)
+	assert.Nil(t, inherent.TraitSymbol)
+	assert.Equal(t, 9, inherent.Location.StartLine)
+	assert.Equal(t, 125, inherent.Location.StartByte)
+	require.Len(t, inherent.Methods, 1)
+	assert.Equal(t, "new", inherent.Methods[0].Name)
+	assert.Equal(t, "associated_function", inherent.Methods[0].Subkind)
+
+	drawable := blocks[1]
+	assert.Equal(t, "impl Drawable for Circle", drawable.Header)
+	assert.Equal(t, "Drawable", drawable.Trait)
+	require.NotNil(t, drawable.TraitSymbol)
+	assert.Equal(t, "trait", drawable.TraitSymbol.Kind)
+	var names []string
+	for _, m := range drawable.Methods {
+		names = append(names, m.Name)
+	}
+	assert.Equal(t, []string{"draw", "area"}, names)
+
+	missing, err := e.Query().ImplBlocks(-1)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
@@ -0,0 +1,225 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// ReexportHop is one pub use that makes a Rust item importable by another
+// path.
+type ReexportHop struct {
+	Path       string // the path the re-export adds, crate-qualified ("demo::Client")
+	Source     string // the use path as written ("self::net::Client")
+	Visibility string // "public", "pub(crate)", ...
+	FilePath   string // the re-exporting file
+	Line       int    // position of the re-exported name in the use declaration
+	Col        int
+}
+
+// ImportPath is a path a Rust item can be imported by, with the chain of
+// re-exports that leads from the item's definition to it.
+type ImportPath struct {
+	Path   string
+	Hops   []ReexportHop // from the definition outward; the last hop adds Path
+	Public bool          // every hop is pub, so the path is usable from other crates
+}
+
+// SymbolImportPaths is a Rust item with its canonical path and the paths
+// its re-exports add.
+type SymbolImportPaths struct {
+	Symbol        SymbolResult
+	CanonicalPath string       // where the item is defined, crate-qualified ("demo::net::client::Client")
+	Paths         []ImportPath // by length of the path, then number of hops, then path
+}
+
+// ImportPaths returns the canonical path of the Rust item symbolID and
+// every path a pub use (at any visibility) re-exports it under, each with
+// its full chain of re-exports. A re-export's parent in the chain is the
+// path its use path names: the canonical path or another re-export. A use
+// path that names neither is taken to point at the definition. Re-exports
+// of standard library and dependency items are found when the dependency
+// was ingested from rustdoc JSON. Glob re-exports (pub use m::*) and
+// re-exports inside inline modules are not followed. Returns nil with no
+// error if the symbol does not exist.
+func (q *QueryBuilder) ImportPaths(symbolID int64) (*SymbolImportPaths, error) {
+	sr, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("import paths: %w", err)
+	}
+	if sr == nil {
+		return nil, nil
+	}
+	packages := map[string]cargoPackage{}
+	canonical, err := q.rustItemPath(sr, packages)
+	if err != nil {
+		return nil, fmt.Errorf("import paths: %w", err)
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT i.source, i.imported_name, COALESCE(i.local_alias, ''), i.scope, f.path,
+			MIN(r.start_line), r.start_col
+		 FROM imports i
+		 JOIN files f ON f.id = i.file_id
+		 JOIN references_ r ON r.file_id = i.file_id AND r.context = 'import' AND r.name = i.imported_name
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 WHERE rr.target_symbol_id = ? AND f.language = 'rust'
+		 AND i.kind = 'module' AND i.scope NOT IN ('file', 'macro_use')
+		 GROUP BY i.id
+		 ORDER BY f.path, i.id`,
+		symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("import paths: %w", err)
+	}
+	defer rows.Close()
+
+	// A re-export's use path is resolved later, once every path is known.
+	type reexport struct {
+		hop        ReexportHop
+		candidates []string
+	}
+	var reexports []reexport
+	seen := map[string]bool{canonical: true}
+	for rows.Next() {
+		var hop ReexportHop
+		var importedName, alias string
+		if err := rows.Scan(&hop.Source, &importedName, &alias, &hop.Visibility, &hop.FilePath, &hop.Line, &hop.Col); err != nil {
+			return nil, fmt.Errorf("import paths: scan: %w", err)
+		}
+		m, ok := q.rustModuleOf(hop.FilePath, packages)
+		if !ok {
+			continue
+		}
+		name := importedName
+		if alias != "" {
+			name = alias
+		}
+		if name == "_" {
+			continue
+		}
+		hop.Path = rustQualifiedPath(m, name)
+		if seen[hop.Path] {
+			continue
+		}
+		seen[hop.Path] = true
+		reexports = append(reexports, reexport{hop: hop, candidates: rustUsePathCandidates(m, hop.Source)})
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("import paths: %w", err)
+	}
+
+	// parent[i] is the index of the re-export that re-export i re-exports,
+	// or -1 for the definition.
+	index := map[string]int{}
+	for i, re := range reexports {
+		index[re.hop.Path] = i
+	}
+	parent := make([]int, len(reexports))
+	for i, re := range reexports {
+		parent[i] = -1
+		for _, c := range re.candidates {
+			if c == canonical {
+				break
+			}
+			if j, ok := index[c]; ok && j != i {
+				parent[i] = j
+				break
+			}
+		}
+	}
+
+	result := &SymbolImportPaths{Symbol: *sr, CanonicalPath: canonical, Paths: []ImportPath{}}
+	for i, re := range reexports {
+		path := ImportPath{Path: re.hop.Path, Public: true}
+		visited := map[int]bool{}
+		for j := i; j >= 0 && !visited[j]; j = parent[j] {
+			visited[j] = true
+			path.Hops = append(path.Hops, reexports[j].hop)
+			path.Public = path.Public && reexports[j].hop.Visibility == "public"
+		}
+		for a, b := 0, len(path.Hops)-1; a < b; a, b = a+1, b-1 {
+			path.Hops[a], path.Hops[b] = path.Hops[b], path.Hops[a]
+		}
+		result.Paths = append(result.Paths, path)
+	}
+	sort.SliceStable(result.Paths, func(i, j int) bool {
+		a, b := result.Paths[i], result.Paths[j]
+		if la, lb := strings.Count(a.Path, "::"), strings.Count(b.Path, "::"); la != lb {
+			return la < lb
+		}
+		if len(a.Hops) != len(b.Hops) {
+			return len(a.Hops) < len(b.Hops)
+		}
+		return a.Path < b.Path
+	})
+	return result, nil
+}
+
+// rustItemPath returns the crate-qualified path of the Rust item sr,
+// including the inline modules and the type or trait enclosing it in its
+// file (demo::net::Client::connect). An item of a dependency ingested from
+// rustdoc JSON is placed by its stub module.
+func (q *QueryBuilder) rustItemPath(sr *SymbolResult, packages map[string]cargoPackage) (string, error) {
+	segments := []string{sr.Name}
+	for id := sr.ParentSymbolID; id != nil; {
+		p, err := q.store.SymbolByID(*id)
+		if err != nil {
+			return "", err
+		}
+		if p == nil || p.FileID == nil || sr.FileID == nil || *p.FileID != *sr.FileID {
+			break
+		}
+		segments = append([]string{p.Name}, segments...)
+		id = p.ParentSymbolID
+	}
+	if module, ok := strings.CutPrefix(sr.FilePath, rustdocStubPrefix); ok {
+		return module + "::" + strings.Join(segments, "::"), nil
+	}
+	m, ok := q.rustModuleOf(sr.FilePath, packages)
+	if !ok {
+		return "", fmt.Errorf("%s is not in a Rust crate", sr.FilePath)
+	}
+	return rustQualifiedPath(m, strings.Join(segments, "::")), nil
+}
+
+// rustQualifiedPath returns the crate-qualified path of name in module m.
+func rustQualifiedPath(m rustModule, name string) string {
+	if m.path == "" {
+		return m.crate + "::" + name
+	}
+	return m.crate + "::" + m.path + "::" + name
+}
+
+// rustUsePathCandidates returns the crate-qualified paths a use path
+// written in module m may name, most likely first. crate::, self::, super::
+// and :: paths name one; a bare path names an item relative to m (2018
+// edition), relative to the crate root (2015), or in another crate.
+func rustUsePathCandidates(m rustModule, source string) []string {
+	segments := strings.Split(source, "::")
+	module := []string{}
+	if m.path != "" {
+		module = strings.Split(m.path, "::")
+	}
+	join := func(prefix []string, rest []string) string {
+		return strings.Join(append(append([]string{m.crate}, prefix...), rest...), "::")
+	}
+	switch segments[0] {
+	case "crate":
+		return []string{join(nil, segments[1:])}
+	case "":
+		return []string{strings.Join(segments[1:], "::")}
+	case "self":
+		return []string{join(module, segments[1:])}
+	case "super":
+		for len(segments) > 0 && segments[0] == "super" && len(module) > 0 {
+			segments, module = segments[1:], module[:len(module)-1]
+		}
+		return []string{join(module, segments)}
+	}
+	candidates := []string{join(module, segments)}
+	if len(module) > 0 {
+		candidates = append(candidates, join(nil, segments))
+	}
+	return append(candidates, source)
+}
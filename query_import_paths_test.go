@@ -0,0 +1,55 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestImportPaths(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "pub mod net;\npub use net::Client;\nmod prelude;\n")
+	write("src/net/mod.rs", "pub mod client;\npub use self::client::Client;\n")
+	write("src/net/client.rs", "pub struct Client;\n")
+	write("src/prelude.rs", "pub(crate) use crate::Client as Conn;\n")
+	lib := filepath.Join(root, "src", "lib.rs")
+	net := filepath.Join(root, "src", "net", "mod.rs")
+	prelude := filepath.Join(root, "src", "prelude.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	client, err := q.SymbolAt(filepath.Join(root, "src", "net", "client.rs"), 0, 11)
+	require.NoError(t, err)
+	require.NotNil(t, client)
+	paths, err := q.ImportPaths(client.ID)
+	require.NoError(t, err)
+	require.NotNil(t, paths)
+	assert.Equal(t, "demo::net::client::Client", paths.CanonicalPath)
+
+	viaNet := ReexportHop{Path: "demo::net::Client", Source: "self::client::Client", Visibility: "public", FilePath: net, Line: 1, Col: 22}
+	viaLib := ReexportHop{Path: "demo::Client", Source: "net::Client", Visibility: "public", FilePath: lib, Line: 1, Col: 13}
+	viaPrelude := ReexportHop{Path: "demo::prelude::Conn", Source: "crate::Client", Visibility: "pub(crate)", FilePath: prelude, Line: 0, Col: 22}
+	assert.Equal(t, []ImportPath{
+		{Path: "demo::Client", Hops: []ReexportHop{viaNet, viaLib}, Public: true},
+		{Path: "demo::net::Client", Hops: []ReexportHop{viaNet}, Public: true},
+		{Path: "demo::prelude::Conn", Hops: []ReexportHop{viaNet, viaLib, viaPrelude}, Public: false},
+	}, paths.Paths)
+
+	missing, err := q.ImportPaths(-1)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
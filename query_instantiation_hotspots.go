@@ -0,0 +1,139 @@
+package canopy
+
+import (
+	"fmt"
+	"math"
+	"sort"
+)
+
+// Orders for InstantiationHotspots, both most first.
+const (
+	HotspotsByDistinct = "distinct" // distinct type argument lists
+	HotspotsBySites    = "sites"    // calls and type references
+)
+
+// minHotspotDistinct is the lowest automatic threshold: a generic used with
+// two or three argument lists is not unusual however uniform the rest are.
+const minHotspotDistinct = 4
+
+// InstantiationHotspot is a generic function used with many distinct type
+// argument lists, each of which the compiler monomorphizes into a copy.
+type InstantiationHotspot struct {
+	Symbol     SymbolResult
+	TypeParams []string
+	Distinct   int                  // distinct known type argument lists
+	Sites      int                  // calls and type references
+	Unknown    int                  // sites whose type arguments are all unknown
+	Usages     []InstantiationUsage // as in Instantiations, most used first; Sites index the symbol's own sites
+}
+
+// InstantiationHotspots returns the generic functions and methods under
+// pathPrefix ("" for all) used with at least minDistinct distinct type
+// argument lists, sorted by HotspotsByDistinct or HotspotsBySites. Each
+// distinct list is a monomorphized copy, so these are the likely compile
+// time and binary size hotspots. With minDistinct <= 0 the threshold is two
+// standard deviations above the mean over the instantiated generics, and at
+// least 4. Type arguments are found as by Instantiations; sites with none
+// known count toward Unknown, not Distinct, so the counts are a lower bound.
+func (q *QueryBuilder) InstantiationHotspots(minDistinct int, sortBy, pathPrefix string) ([]InstantiationHotspot, error) {
+	if sortBy == "" {
+		sortBy = HotspotsByDistinct
+	}
+	if sortBy != HotspotsByDistinct && sortBy != HotspotsBySites {
+		return nil, fmt.Errorf("instantiation hotspots: unknown order %q (want %s or %s)", sortBy, HotspotsByDistinct, HotspotsBySites)
+	}
+
+	query := `SELECT DISTINCT s.id FROM symbols s
+		JOIN type_parameters tp ON tp.symbol_id = s.id
+		JOIN files f ON f.id = s.file_id
+		WHERE s.kind IN ('function', 'method', 'default_method')
+		AND COALESCE(tp.param_kind, 'type') != 'lifetime'`
+	var args []any
+	if pathPrefix != "" {
+		query += ` AND f.path LIKE ? ESCAPE '\'`
+		args = append(args, escapeLike(pathPrefix)+"%")
+	}
+	rows, err := q.store.DB().Query(query+` ORDER BY s.id`, args...)
+	if err != nil {
+		return nil, fmt.Errorf("instantiation hotspots: %w", err)
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("instantiation hotspots: scan: %w", err)
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("instantiation hotspots: %w", err)
+	}
+
+	var all []InstantiationHotspot
+	for _, id := range ids {
+		inst, err := q.Instantiations(id)
+		if err != nil {
+			return nil, fmt.Errorf("instantiation hotspots: %w", err)
+		}
+		if inst == nil || len(inst.Usages) == 0 {
+			continue
+		}
+		h := InstantiationHotspot{
+			Symbol:     inst.Symbol,
+			TypeParams: inst.TypeParams,
+			Distinct:   len(inst.Usages),
+			Sites:      len(inst.Sites),
+			Usages:     inst.Usages,
+		}
+		h.Unknown = h.Sites
+		for _, u := range inst.Usages {
+			h.Unknown -= len(u.Sites)
+		}
+		all = append(all, h)
+	}
+
+	if minDistinct <= 0 {
+		minDistinct = hotspotThreshold(all)
+	}
+	hotspots := []InstantiationHotspot{}
+	for _, h := range all {
+		if h.Distinct >= minDistinct {
+			hotspots = append(hotspots, h)
+		}
+	}
+	sort.SliceStable(hotspots, func(i, j int) bool {
+		a, b := hotspots[i], hotspots[j]
+		ka, kb := a.Distinct, b.Distinct
+		if sortBy == HotspotsBySites {
+			ka, kb = a.Sites, b.Sites
+		}
+		if ka != kb {
+			return ka > kb
+		}
+		if a.Symbol.FilePath != b.Symbol.FilePath {
+			return a.Symbol.FilePath < b.Symbol.FilePath
+		}
+		return a.Symbol.StartLine < b.Symbol.StartLine
+	})
+	return hotspots, nil
+}
+
+// hotspotThreshold returns the automatic minimum distinct count: the mean
+// plus two standard deviations, rounded up, and at least minHotspotDistinct.
+func hotspotThreshold(all []InstantiationHotspot) int {
+	if len(all) == 0 {
+		return minHotspotDistinct
+	}
+	var sum, sumSq float64
+	for _, h := range all {
+		d := float64(h.Distinct)
+		sum += d
+		sumSq += d * d
+	}
+	n := float64(len(all))
+	mean := sum / n
+	stddev := math.Sqrt(math.Max(sumSq/n-mean*mean, 0))
+	return max(minHotspotDistinct, int(math.Ceil(mean+2*stddev)))
+}
@@ -0,0 +1,94 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestInstantiationHotspots(t *testing.T) {
+	ctx := context.Background()
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "main.rs"), []byte(`fn show<T>(value: T) {}
+
+fn wrap<T>(value: T) -> Vec<T> {
+    vec![value]
+}
+
+fn plain(x: u8) {}
+
+fn main() {
+    show(1u8);
+    show(2u16);
+    show(3u32);
+    show("s");
+    show(4u8);
+    wrap(1u8);
+    wrap(2u8);
+    wrap(3u8);
+    wrap(4u8);
+    wrap(count());
+    plain(1);
+}
+
+fn count() -> u8 {
+    0
+}
+`), 0o644))
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	hotspots, err := q.InstantiationHotspots(1, HotspotsByDistinct, "")
+	require.NoError(t, err)
+	require.Len(t, hotspots, 2, "plain is not generic")
+	assert.Equal(t, "show", hotspots[0].Symbol.Name)
+	assert.Equal(t, []string{"T"}, hotspots[0].TypeParams)
+	assert.Equal(t, 4, hotspots[0].Distinct)
+	assert.Equal(t, 5, hotspots[0].Sites)
+	assert.Equal(t, []string{"u8"}, hotspots[0].Usages[0].TypeArgs, "most used first")
+	assert.Equal(t, "wrap", hotspots[1].Symbol.Name)
+	assert.Equal(t, 1, hotspots[1].Distinct)
+	assert.Equal(t, 1, hotspots[1].Unknown, "wrap(count()) has no known type")
+
+	hotspots, err = q.InstantiationHotspots(1, HotspotsBySites, "")
+	require.NoError(t, err)
+	require.Len(t, hotspots, 2)
+	assert.Equal(t, "show", hotspots[0].Symbol.Name, "ties keep source order")
+
+	hotspots, err = q.InstantiationHotspots(2, "", "")
+	require.NoError(t, err)
+	require.Len(t, hotspots, 1)
+	assert.Equal(t, "show", hotspots[0].Symbol.Name)
+
+	hotspots, err = q.InstantiationHotspots(0, "", "")
+	require.NoError(t, err)
+	assert.Empty(t, hotspots, "4 distinct is within two standard deviations of the mean")
+
+	hotspots, err = q.InstantiationHotspots(1, "", filepath.Join(dir, "lib"))
+	require.NoError(t, err)
+	assert.Empty(t, hotspots)
+
+	_, err = q.InstantiationHotspots(1, "name", "")
+	assert.Error(t, err)
+}
+
+func TestHotspotThreshold(t *testing.T) {
+	t.Parallel()
+	hotspots := func(distinct ...int) []InstantiationHotspot {
+		var all []InstantiationHotspot
+		for _, d := range distinct {
+			all = append(all, InstantiationHotspot{Distinct: d})
+		}
+		return all
+	}
+	assert.Equal(t, 4, hotspotThreshold(nil))
+	assert.Equal(t, 4, hotspotThreshold(hotspots(1, 1, 2)), "never below 4")
+	// Mean 3, standard deviation 4.
+	assert.Equal(t, 11, hotspotThreshold(hotspots(1, 1, 1, 1, 11)))
+}
@@ -0,0 +1,365 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"unicode"
+)
+
+// Instantiations is how a generic function or type is used across the
+// index: the concrete types each call or type reference supplies for its
+// type parameters.
+type Instantiations struct {
+	Symbol     SymbolResult
+	TypeParams []string             // type and const parameters, in order; lifetimes are excluded
+	Sites      []InstantiationSite  // every call and type reference, in reference order
+	Usages     []InstantiationUsage // distinct type argument lists, most used first
+}
+
+// InstantiationSite is one use of a generic with the type arguments it
+// supplies.
+type InstantiationSite struct {
+	Location
+	Kind     string   // RefKindCall or RefKindType
+	TypeArgs []string // one per type parameter; "" where not known
+	Inferred bool     // inferred from the call's arguments rather than written out
+}
+
+// InstantiationUsage is a list of type arguments and the sites using it.
+type InstantiationUsage struct {
+	TypeArgs []string // as in InstantiationSite; at least one is known
+	Sites    []int    // indexes into Instantiations.Sites
+}
+
+// Instantiations returns where the generic function or type symbolID is
+// used and with which type arguments. Arguments written out at the site
+// (convert::<u16>(x), Stack<int>, Box[str]) are read as written. Otherwise
+// the arguments of a call are matched against the parameters declared with
+// a bare type parameter type (T, &T, *T, ...T): literals, composite literals
+// (Point{...}), constructor calls (new Point(...)), and paths into a type
+// (Point::new(...)) give their type; any other argument leaves the
+// parameter unknown. Returns nil, nil if the symbol does not exist.
+func (q *QueryBuilder) Instantiations(symbolID int64) (*Instantiations, error) {
+	sym, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("instantiations: %w", err)
+	}
+	if sym == nil {
+		return nil, nil
+	}
+	typeParams, err := q.store.TypeParams(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("instantiations: %w", err)
+	}
+	result := &Instantiations{Symbol: *sym, Sites: []InstantiationSite{}, Usages: []InstantiationUsage{}}
+	for _, tp := range typeParams {
+		if tp.ParamKind != "lifetime" {
+			result.TypeParams = append(result.TypeParams, tp.Name)
+		}
+	}
+	if len(result.TypeParams) == 0 {
+		return result, nil
+	}
+	fnParams, err := q.store.FunctionParams(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("instantiations: %w", err)
+	}
+	var paramTypes []string
+	for _, p := range fnParams {
+		if !p.IsReceiver && !p.IsReturn {
+			paramTypes = append(paramTypes, p.TypeExpr)
+		}
+	}
+
+	var sites []ReferenceSite
+	err = q.EachReferenceTo(symbolID, ReferenceFilter{Kinds: []string{RefKindCall, RefKindType}}, func(site ReferenceSite) error {
+		sites = append(sites, site)
+		return nil
+	})
+	if err != nil {
+		return nil, fmt.Errorf("instantiations: %w", err)
+	}
+
+	type source struct {
+		src      []byte
+		language string
+	}
+	sources := map[string]*source{}
+	for _, site := range sites {
+		s, ok := sources[site.File]
+		if !ok {
+			s = &source{}
+			if f, err := q.store.FileByPath(site.File); err == nil && f != nil {
+				s.language = f.Language
+			}
+			s.src, _ = q.readSource(site.File) // without source, the types are unknown
+			sources[site.File] = s
+		}
+		inst := InstantiationSite{Location: site.Location, Kind: site.Kind, TypeArgs: make([]string, len(result.TypeParams))}
+		if site.EndByte <= len(s.src) {
+			rest := string(s.src[site.EndByte:])
+			if args, ok := explicitTypeArgs(rest, s.language); ok {
+				for i, a := range args {
+					if i < len(inst.TypeArgs) && a != "_" {
+						inst.TypeArgs[i] = normalizeTypeExpr(a)
+					}
+				}
+			} else if site.Kind == RefKindCall {
+				inst.Inferred = inferTypeArgs(inst.TypeArgs, result.TypeParams, paramTypes, rest, s.language)
+			}
+		}
+		result.Sites = append(result.Sites, inst)
+	}
+
+	usages := map[string]*InstantiationUsage{}
+	var keys []string
+	for i, site := range result.Sites {
+		known := false
+		for _, a := range site.TypeArgs {
+			known = known || a != ""
+		}
+		if !known {
+			continue
+		}
+		key := strings.Join(site.TypeArgs, "\x00")
+		u, ok := usages[key]
+		if !ok {
+			u = &InstantiationUsage{TypeArgs: site.TypeArgs}
+			usages[key] = u
+			keys = append(keys, key)
+		}
+		u.Sites = append(u.Sites, i)
+	}
+	sort.SliceStable(keys, func(i, j int) bool {
+		if ni, nj := len(usages[keys[i]].Sites), len(usages[keys[j]].Sites); ni != nj {
+			return ni > nj
+		}
+		return keys[i] < keys[j]
+	})
+	for _, k := range keys {
+		result.Usages = append(result.Usages, *usages[k])
+	}
+	return result, nil
+}
+
+// explicitTypeArgs parses the type argument list written right after a
+// generic's name: <...> (Rust also ::<...>), or [...] in Go and Python.
+// Lifetimes are left out of the arguments returned.
+func explicitTypeArgs(rest, language string) ([]string, bool) {
+	i := 0
+	if language == "rust" && strings.HasPrefix(rest, "::<") {
+		i = 2
+	}
+	if i >= len(rest) {
+		return nil, false
+	}
+	open, close := byte('<'), byte('>')
+	if language == "go" || language == "python" {
+		open, close = '[', ']'
+	}
+	if rest[i] != open {
+		return nil, false
+	}
+	depth := 0
+	for j := i; j < len(rest); j++ {
+		switch rest[j] {
+		case '<', '[', '(', '{':
+			depth++
+		case '>', ']', ')', '}':
+			if rest[j] == '>' && j > 0 && rest[j-1] == '-' {
+				continue
+			}
+			depth--
+			if depth == 0 {
+				if rest[j] != close {
+					return nil, false
+				}
+				var args []string
+				for _, a := range splitTypeList(rest[i+1 : j]) {
+					if !strings.HasPrefix(a, "'") {
+						args = append(args, a)
+					}
+				}
+				return args, len(args) > 0
+			}
+		case '\n', ';':
+			return nil, false
+		}
+	}
+	return nil, false
+}
+
+// inferTypeArgs fills in typeArgs from the arguments of the call whose
+// argument list starts rest, for each parameter declared with a bare type
+// parameter type. It reports whether any type was inferred.
+func inferTypeArgs(typeArgs, typeParams, paramTypes []string, rest, language string) bool {
+	rest = strings.TrimLeft(rest, " \t")
+	if !strings.HasPrefix(rest, "(") {
+		return false
+	}
+	end := matchingParen(rest)
+	if end < 0 {
+		return false
+	}
+	args := splitCallArgs(rest[1:end])
+	inferred := false
+	for i, pt := range paramTypes {
+		if i >= len(args) {
+			break
+		}
+		norm := normalizeTypeExpr(pt)
+		param, arg := stripTypeWrapper(norm, args[i])
+		for j, tp := range typeParams {
+			if param != tp || typeArgs[j] != "" {
+				continue
+			}
+			t := literalType(arg, language)
+			if param != norm && strings.HasPrefix(t, "&") {
+				t = t[1:] // "s" passed as &T makes T str
+			}
+			if t != "" {
+				typeArgs[j] = t
+				inferred = true
+			}
+		}
+	}
+	return inferred
+}
+
+// stripTypeWrapper removes a reference, pointer, or variadic wrapper from a
+// parameter type, and the matching borrow from the argument.
+func stripTypeWrapper(param, arg string) (string, string) {
+	for _, w := range []struct{ param, arg string }{{"&mut ", "&mut "}, {"&", "&"}, {"*", "&"}, {"...", ""}} {
+		if inner, ok := strings.CutPrefix(param, w.param); ok {
+			return inner, strings.TrimSpace(strings.TrimPrefix(arg, w.arg))
+		}
+	}
+	return param, arg
+}
+
+// literalTypes names the types of literals per language: integer, float,
+// string, single-quoted, and boolean.
+var literalTypes = map[string][5]string{
+	"rust":       {"i32", "f64", "&str", "char", "bool"},
+	"go":         {"int", "float64", "string", "rune", "bool"},
+	"typescript": {"number", "number", "string", "string", "boolean"},
+	"javascript": {"number", "number", "string", "string", "boolean"},
+	"java":       {"int", "double", "String", "char", "boolean"},
+	"kotlin":     {"Int", "Double", "String", "Char", "Boolean"},
+	"python":     {"int", "float", "str", "str", "bool"},
+	"c":          {"int", "double", "const char*", "char", "bool"},
+	"cpp":        {"int", "double", "const char*", "char", "bool"},
+	"php":        {"int", "float", "string", "string", "bool"},
+	"ruby":       {"Integer", "Float", "String", "String", "TrueClass"},
+	"swift":      {"Int", "Double", "String", "String", "Bool"},
+}
+
+// literalType returns the type of an argument expression whose type is
+// evident from its text, or "".
+func literalType(arg, language string) string {
+	arg = strings.TrimSpace(arg)
+	if arg == "" {
+		return ""
+	}
+	types, ok := literalTypes[language]
+	if !ok {
+		return ""
+	}
+	switch {
+	case arg == "true" || arg == "false" || arg == "True" || arg == "False":
+		return types[4]
+	case arg[0] == '"' || arg[0] == '`':
+		return types[2]
+	case arg[0] == '\'':
+		return types[3]
+	case arg[0] >= '0' && arg[0] <= '9' || arg[0] == '-' && len(arg) > 1 && arg[1] >= '0' && arg[1] <= '9':
+		return numericLiteralType(strings.TrimPrefix(arg, "-"), language, types)
+	}
+	// new Point(...), new Point<T>(...)
+	if name, ok := strings.CutPrefix(arg, "new "); ok {
+		return leadingTypeName(strings.TrimSpace(name))
+	}
+	name := leadingTypeName(arg)
+	if name == "" || !unicode.IsUpper(rune(name[strings.LastIndexByte(name, '.')+1])) {
+		return ""
+	}
+	after := strings.TrimSpace(arg[len(name):])
+	switch {
+	case strings.HasPrefix(after, "{"): // Point{...}, Point { ... }
+		return name
+	case strings.HasPrefix(after, "::") && (language == "rust" || language == "cpp"): // Point::new(...)
+		return name
+	case strings.HasPrefix(after, "(") && !strings.Contains(name, ".") && (language == "python" || language == "kotlin" || language == "swift"): // Point(...)
+		return name
+	}
+	return ""
+}
+
+// numericLiteralType returns the type of a number literal, honoring Rust's
+// type suffixes (5u8, 2.0f32).
+func numericLiteralType(lit, language string, types [5]string) string {
+	if strings.Contains(lit, "..") {
+		return "" // a range
+	}
+	end := 0
+	for end < len(lit) && (lit[end] >= '0' && lit[end] <= '9' || lit[end] == '_' || lit[end] == '.' || lit[end] == 'x' || lit[end] == 'e') {
+		end++
+	}
+	if language == "rust" {
+		for _, suffix := range []string{"u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64"} {
+			if lit[end:] == suffix {
+				return suffix
+			}
+		}
+	}
+	if end != len(lit) {
+		return "" // an expression such as 1 + x, or a suffix naming another type
+	}
+	if strings.ContainsAny(lit[:end], ".e") && !strings.HasPrefix(lit, "0x") {
+		return types[1]
+	}
+	return types[0]
+}
+
+// leadingTypeName returns the identifier or package-qualified name (pkg.T)
+// starting s.
+func leadingTypeName(s string) string {
+	end := 0
+	for end < len(s) && (isTypeWordByte(s[end]) && s[end] != '\'' || s[end] == '.' && end > 0) {
+		end++
+	}
+	return strings.TrimSuffix(s[:end], ".")
+}
+
+// splitCallArgs splits a call's argument list at top-level commas, skipping
+// commas inside brackets and string literals.
+func splitCallArgs(s string) []string {
+	if strings.TrimSpace(s) == "" {
+		return nil
+	}
+	var args []string
+	depth, start := 0, 0
+	var quote byte
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case quote != 0:
+			if c == '\\' {
+				i++
+			} else if c == quote {
+				quote = 0
+			}
+		case c == '"' || c == '`':
+			quote = c
+		case c == '(' || c == '[' || c == '{':
+			depth++
+		case c == ')' || c == ']' || c == '}':
+			depth--
+		case c == ',' && depth == 0:
+			args = append(args, strings.TrimSpace(s[start:i]))
+			start = i + 1
+		}
+	}
+	return append(args, strings.TrimSpace(s[start:]))
+}
@@ -0,0 +1,157 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// symbolIDNamed returns the ID of the only symbol named name.
+func symbolIDNamed(t *testing.T, q *QueryBuilder, name string) int64 {
+	t.Helper()
+	result, err := q.Symbols(SymbolFilter{}, Sort{Field: SortByName, Order: Asc}, Pagination{Limit: intP(500)})
+	require.NoError(t, err)
+	var id int64
+	for _, s := range result.Items {
+		if s.Name == name {
+			require.Zero(t, id, "more than one symbol named %s", name)
+			id = s.ID
+		}
+	}
+	require.NotZero(t, id, "no symbol named %s", name)
+	return id
+}
+
+func TestInstantiations_Go(t *testing.T) {
+	ctx := context.Background()
+	dir := t.TempDir()
+	writeGoFile(t, dir, "main.go", `package main
+
+type Point struct{ X int }
+
+type Stack[T any] struct{ items []T }
+
+func Max[T int | float64](a, b T) T {
+	if a > b {
+		return a
+	}
+	return b
+}
+
+func Pick[T any](v *T) {}
+
+func main() {
+	Max(1, 2)
+	Max(2.5, 1.0)
+	Max(3, 4)
+	n := 5
+	Max(n, n)
+	var s Stack[string]
+	_ = s
+	Pick(&Point{X: 1})
+}
+`)
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	maxFn, err := q.Instantiations(symbolIDNamed(t, q, "Max"))
+	require.NoError(t, err)
+	require.NotNil(t, maxFn)
+	assert.Equal(t, []string{"T"}, maxFn.TypeParams)
+	require.Len(t, maxFn.Sites, 4)
+	assert.Equal(t, []string{"int"}, maxFn.Sites[0].TypeArgs)
+	assert.True(t, maxFn.Sites[0].Inferred)
+	assert.Equal(t, []string{"float64"}, maxFn.Sites[1].TypeArgs)
+	assert.Equal(t, []string{""}, maxFn.Sites[3].TypeArgs, "a variable's type is not inferred")
+	assert.False(t, maxFn.Sites[3].Inferred)
+	assert.Equal(t, []InstantiationUsage{
+		{TypeArgs: []string{"int"}, Sites: []int{0, 2}},
+		{TypeArgs: []string{"float64"}, Sites: []int{1}},
+	}, maxFn.Usages)
+
+	stack, err := q.Instantiations(symbolIDNamed(t, q, "Stack"))
+	require.NoError(t, err)
+	require.Len(t, stack.Sites, 1)
+	assert.Equal(t, RefKindType, stack.Sites[0].Kind)
+	assert.Equal(t, []string{"string"}, stack.Sites[0].TypeArgs)
+	assert.False(t, stack.Sites[0].Inferred)
+
+	pick, err := q.Instantiations(symbolIDNamed(t, q, "Pick"))
+	require.NoError(t, err)
+	require.Len(t, pick.Sites, 1)
+	assert.Equal(t, []string{"Point"}, pick.Sites[0].TypeArgs)
+
+	point, err := q.Instantiations(symbolIDNamed(t, q, "Point"))
+	require.NoError(t, err)
+	assert.Empty(t, point.TypeParams)
+	assert.Empty(t, point.Sites, "a non-generic type has no instantiations")
+
+	missing, err := q.Instantiations(-1)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
+
+func TestInstantiations_Rust(t *testing.T) {
+	ctx := context.Background()
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "main.rs"), []byte(`fn convert<'a, T>(x: &'a str) -> T {
+    todo!()
+}
+
+fn wrap<T>(value: T) -> Vec<T> {
+    vec![value]
+}
+
+fn main() {
+    let a = convert::<u16>("1");
+    let b = wrap(5u8);
+    let c = wrap("s");
+}
+`), 0o644))
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	convert, err := q.Instantiations(symbolIDNamed(t, q, "convert"))
+	require.NoError(t, err)
+	assert.Equal(t, []string{"T"}, convert.TypeParams, "lifetimes are not type parameters")
+	require.Len(t, convert.Sites, 1)
+	assert.Equal(t, []string{"u16"}, convert.Sites[0].TypeArgs)
+	assert.False(t, convert.Sites[0].Inferred)
+
+	wrap, err := q.Instantiations(symbolIDNamed(t, q, "wrap"))
+	require.NoError(t, err)
+	require.Len(t, wrap.Sites, 2)
+	assert.Equal(t, []string{"u8"}, wrap.Sites[0].TypeArgs)
+	assert.Equal(t, []string{"&str"}, wrap.Sites[1].TypeArgs)
+	assert.True(t, wrap.Sites[1].Inferred)
+}
+
+func TestLiteralType(t *testing.T) {
+	for _, c := range []struct{ arg, language, want string }{
+		{"42", "go", "int"},
+		{"-1.5", "go", "float64"},
+		{"`raw`", "go", "string"},
+		{"'x'", "go", "rune"},
+		{"2.0f32", "rust", "f32"},
+		{"0..3", "rust", ""},
+		{"Point { x: 1 }", "rust", "Point"},
+		{"Point::new(1)", "rust", "Point"},
+		{"new Box<String>()", "java", "Box"},
+		{"'a'", "python", "str"},
+		{"Point(1, 2)", "python", "Point"},
+		{"pkg.Point{}", "go", "pkg.Point"},
+		{"x + 1", "go", ""},
+		{"1 + x", "go", ""},
+		{"count()", "go", ""},
+	} {
+		assert.Equal(t, c.want, literalType(c.arg, c.language), "%s (%s)", c.arg, c.language)
+	}
+}
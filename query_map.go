@@ -0,0 +1,208 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"unicode/utf8"
+)
+
+// maxSignatureLen caps the length in bytes of a repo map signature.
+const maxSignatureLen = 200
+
+// RepoMap is a ranked summary of the most central symbols in the index,
+// truncated to a token budget, for use as context by AI coding tools.
+type RepoMap struct {
+	Files           []RepoMapFile
+	Budget          int // requested budget in tokens
+	EstimatedTokens int // estimated size of the rendered map in tokens
+	Symbols         int // symbols included
+	TotalSymbols    int // candidate symbols before truncation
+}
+
+// RepoMapFile groups the map entries of one file, in source order.
+type RepoMapFile struct {
+	File    string
+	Entries []RepoMapEntry
+}
+
+// RepoMapEntry is one symbol in a repo map.
+type RepoMapEntry struct {
+	Symbol    SymbolResult
+	Signature string // declaration header, e.g. "func (s *Server) Start(ctx context.Context) error"
+}
+
+// estimateTokens approximates the number of LLM tokens in s at four bytes
+// per token.
+func estimateTokens(s string) int {
+	return (len(s) + 3) / 4
+}
+
+// repoMapFileHeader and repoMapEntryLine are the lines of the text rendering
+// of a repo map: each file's path followed by its entries' signatures,
+// indented.
+func repoMapFileHeader(file string) string { return file + ":\n" }
+
+func repoMapEntryLine(sig string) string { return "  " + sig + "\n" }
+
+// Text renders the map as plain text, the form its budget is measured
+// against.
+func (m *RepoMap) Text() string {
+	var b strings.Builder
+	for _, f := range m.Files {
+		b.WriteString(repoMapFileHeader(f.File))
+		for _, e := range f.Entries {
+			b.WriteString(repoMapEntryLine(e.Signature))
+		}
+	}
+	return b.String()
+}
+
+// RepoMap ranks symbols by PageRank over the reference graph (a symbol is
+// central if central symbols reference it), breaking ties by external
+// reference count, and returns the highest-ranked ones with their
+// signatures until the rendered map would exceed budgetTokens. Package-like
+// symbols and symbols declared inside function bodies are left out. Files
+// are ordered by their highest-ranked symbol. Ranks are computed by Resolve.
+func (q *QueryBuilder) RepoMap(budgetTokens int) (*RepoMap, error) {
+	if budgetTokens <= 0 {
+		return nil, fmt.Errorf("repo map: budget must be positive, got %d", budgetTokens)
+	}
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, f.path AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr WHERE rr.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr JOIN references_ r ON r.id = rr.reference_id WHERE rr.target_symbol_id = s.id AND r.file_id != s.file_id) AS external_ref_count
+		 FROM symbols s
+		 JOIN files f ON s.file_id = f.id
+		 WHERE f.language != ? AND s.kind NOT IN ('package', 'module', 'namespace')
+		 AND NOT EXISTS (SELECT 1 FROM symbols b
+			WHERE b.file_id = s.file_id AND b.id != s.id AND b.kind IN ('function', 'method', 'constructor')
+			AND (b.start_line < s.start_line OR (b.start_line = s.start_line AND b.start_col <= s.start_col))
+			AND (b.end_line > s.end_line OR (b.end_line = s.end_line AND b.end_col >= s.end_col)))`,
+		prefixSymbolCols("s"),
+	)
+	rows, err := q.store.DB().Query(dataSQL, rustdocStubLanguage)
+	if err != nil {
+		return nil, fmt.Errorf("repo map: query: %w", err)
+	}
+	var candidates []RepoMapEntry
+	for rows.Next() {
+		sr, err := scanSymbolResult(rows)
+		if err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("repo map: scan: %w", err)
+		}
+		candidates = append(candidates, RepoMapEntry{Symbol: sr})
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("repo map: rows: %w", err)
+	}
+
+	sort.SliceStable(candidates, func(i, j int) bool {
+		a, b := candidates[i], candidates[j]
+		if a.Symbol.PageRank != b.Symbol.PageRank {
+			return a.Symbol.PageRank > b.Symbol.PageRank
+		}
+		if a.Symbol.ExternalRefCount != b.Symbol.ExternalRefCount {
+			return a.Symbol.ExternalRefCount > b.Symbol.ExternalRefCount
+		}
+		if a.Symbol.FilePath != b.Symbol.FilePath {
+			return a.Symbol.FilePath < b.Symbol.FilePath
+		}
+		return a.Symbol.StartLine < b.Symbol.StartLine
+	})
+
+	m := &RepoMap{Budget: budgetTokens, TotalSymbols: len(candidates)}
+	fileIndex := map[string]int{}
+	sources := map[string]*lineIndex{}
+	for _, c := range candidates {
+		li, ok := sources[c.Symbol.FilePath]
+		if !ok {
+			if content, err := q.readSource(c.Symbol.FilePath); err == nil {
+				li = newLineIndex(content)
+			}
+			sources[c.Symbol.FilePath] = li
+		}
+		c.Signature = symbolSignature(li, &c.Symbol.Symbol)
+
+		cost := estimateTokens(repoMapEntryLine(c.Signature))
+		fi, seen := fileIndex[c.Symbol.FilePath]
+		if !seen {
+			cost += estimateTokens(repoMapFileHeader(c.Symbol.FilePath))
+		}
+		if m.EstimatedTokens+cost > budgetTokens {
+			break
+		}
+		m.EstimatedTokens += cost
+		m.Symbols++
+		if !seen {
+			fi = len(m.Files)
+			fileIndex[c.Symbol.FilePath] = fi
+			m.Files = append(m.Files, RepoMapFile{File: c.Symbol.FilePath})
+		}
+		m.Files[fi].Entries = append(m.Files[fi].Entries, c)
+	}
+
+	for i := range m.Files {
+		entries := m.Files[i].Entries
+		sort.SliceStable(entries, func(a, b int) bool {
+			return entries[a].Symbol.StartLine < entries[b].Symbol.StartLine
+		})
+	}
+	return m, nil
+}
+
+// symbolSignature returns the declaration header of sym: its source text up
+// to the body (an opening brace, or a semicolon or line break outside
+// brackets), with whitespace collapsed. Falls back to "kind name" when the
+// source is unavailable.
+func symbolSignature(li *lineIndex, sym *Symbol) string {
+	fallback := sym.Kind + " " + sym.Name
+	if li == nil {
+		return fallback
+	}
+	start, _ := li.offsets(sym.StartLine, sym.StartCol)
+	end, _ := li.offsets(sym.EndLine, sym.EndCol)
+	if end <= start {
+		return fallback
+	}
+	text := li.content[start:end]
+
+	depth := 0
+	cut := len(text)
+scan:
+	for i, b := range text {
+		switch b {
+		case '(', '[', '<':
+			depth++
+		case ')', ']', '>':
+			if depth > 0 {
+				depth--
+			}
+		case '{':
+			if depth == 0 {
+				cut = i
+				break scan
+			}
+		case ';', '\n':
+			if depth == 0 {
+				cut = i
+				break scan
+			}
+		}
+	}
+
+	sig := strings.Join(strings.Fields(string(text[:cut])), " ")
+	if len(sig) > maxSignatureLen {
+		sig = sig[:maxSignatureLen]
+		for !utf8.ValidString(sig) {
+			sig = sig[:len(sig)-1]
+		}
+		sig += "..."
+	}
+	if sig == "" {
+		return fallback
+	}
+	return sig
+}
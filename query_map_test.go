@@ -0,0 +1,91 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSymbolSignature(t *testing.T) {
+	t.Parallel()
+	src := "func (s *Store) Get(\n\tkey string,\n) (string, error) {\n\treturn key, nil\n}\n" +
+		"pub fn area(&self) -> Vec<f64> where T: Fn() -> u8 { 1 }\n" +
+		"def run(x):\n    pass\n"
+	li := newLineIndex([]byte(src))
+
+	get := &Symbol{Name: "Get", Kind: "method", StartLine: 0, EndLine: 4, EndCol: 1}
+	assert.Equal(t, "func (s *Store) Get( key string, ) (string, error)", symbolSignature(li, get))
+
+	area := &Symbol{Name: "area", Kind: "method", StartLine: 5, EndLine: 5, EndCol: 56}
+	assert.Equal(t, "pub fn area(&self) -> Vec<f64> where T: Fn() -> u8", symbolSignature(li, area))
+
+	run := &Symbol{Name: "run", Kind: "function", StartLine: 6, EndLine: 7, EndCol: 8}
+	assert.Equal(t, "def run(x):", symbolSignature(li, run))
+
+	assert.Equal(t, "function run", symbolSignature(nil, run), "falls back without source")
+}
+
+func TestRepoMap_RanksCentralSymbolsWithinBudget(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	path := writeGoFile(t, t.TempDir(), "main.go", `package main
+
+type Store struct{}
+
+func (s *Store) Get(key string) string {
+	return key
+}
+
+func handlerA(s *Store) string { return s.Get("a") }
+
+func handlerB(s *Store) string { return s.Get("b") }
+
+func handlerC(s *Store) string { return s.Get("c") }
+
+func main() {
+	st := &Store{}
+	handlerA(st)
+	handlerB(st)
+	handlerC(st)
+}
+`)
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	full, err := e.Query().RepoMap(10000)
+	require.NoError(t, err)
+	require.Len(t, full.Files, 1)
+	assert.Equal(t, path, full.Files[0].File)
+	assert.Equal(t, full.TotalSymbols, full.Symbols, "everything fits a large budget")
+	assert.LessOrEqual(t, estimateTokens(full.Text()), full.EstimatedTokens)
+
+	entries := map[string]RepoMapEntry{}
+	for _, en := range full.Files[0].Entries {
+		entries[en.Symbol.Name] = en
+	}
+	assert.NotContains(t, entries, "st", "locals are left out")
+	require.Contains(t, entries, "Get")
+	require.Contains(t, entries, "main")
+	assert.Equal(t, "func (s *Store) Get(key string) string", entries["Get"].Signature)
+	assert.Greater(t, entries["Get"].Symbol.PageRank, entries["main"].Symbol.PageRank)
+	assert.Greater(t, entries["Store"].Symbol.PageRank, entries["main"].Symbol.PageRank)
+
+	for i := 1; i < len(full.Files[0].Entries); i++ {
+		assert.LessOrEqual(t, full.Files[0].Entries[i-1].Symbol.StartLine, full.Files[0].Entries[i].Symbol.StartLine,
+			"entries are in source order")
+	}
+
+	small, err := e.Query().RepoMap(60)
+	require.NoError(t, err)
+	assert.LessOrEqual(t, small.EstimatedTokens, 60)
+	assert.Less(t, small.Symbols, full.Symbols)
+	require.NotEmpty(t, small.Files)
+	for _, en := range small.Files[0].Entries {
+		assert.NotEqual(t, "main", en.Symbol.Name, "the least central symbols are cut first")
+	}
+
+	_, err = e.Query().RepoMap(0)
+	assert.Error(t, err)
+}
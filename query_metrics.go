@@ -0,0 +1,134 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// ComplexityResult pairs a function or method symbol with the complexity
+// metrics computed for it at extraction time.
+type ComplexityResult struct {
+	Symbol     SymbolResult
+	Cyclomatic int // McCabe cyclomatic complexity (1 + decision points)
+	Cognitive  int // cognitive complexity (nesting-weighted control flow)
+}
+
+// complexitySortColumn returns the SQL ORDER BY expression for complexity
+// queries. Complexity fields map to metric columns; everything else falls
+// back to symbolSortColumn.
+func complexitySortColumn(field SortField) string {
+	switch field {
+	case SortByCyclomatic:
+		return "m.cyclomatic"
+	case SortByCognitive:
+		return "m.cognitive"
+	default:
+		return symbolSortColumn(field)
+	}
+}
+
+// FunctionComplexity returns functions and methods that have complexity
+// metrics, filtered by SymbolFilter. Sort by SortByCyclomatic or
+// SortByCognitive (typically Desc) to surface the most complex code first.
+// Ties are broken by file path and position for stable output.
+func (q *QueryBuilder) FunctionComplexity(filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[ComplexityResult], error) {
+	page = page.normalize()
+
+	where, args := q.symbolFilterClauses(filter)
+	whereClause := ""
+	if len(where) > 0 {
+		whereClause = "WHERE " + strings.Join(where, " AND ")
+	}
+
+	fromClause := `FROM function_metrics m
+		 JOIN symbols s ON s.id = m.symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id ` + whereClause
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+fromClause, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("function complexity: count: %w", err)
+	}
+
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, COALESCE(f.path, '') AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr WHERE rr.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr JOIN references_ r ON r.id = rr.reference_id WHERE rr.target_symbol_id = s.id AND r.file_id != s.file_id) AS external_ref_count,
+			m.cyclomatic, m.cognitive
+		 %s
+		 ORDER BY %s %s, f.path, s.start_line, s.start_col, s.id
+		 LIMIT ? OFFSET ?`,
+		prefixSymbolCols("s"), fromClause,
+		complexitySortColumn(sort.Field), sortDirection(sort.Order),
+	)
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+
+	rows, err := q.store.DB().Query(dataSQL, dataArgs...)
+	if err != nil {
+		return nil, fmt.Errorf("function complexity: query: %w", err)
+	}
+	defer rows.Close()
+
+	var items []ComplexityResult
+	for rows.Next() {
+		var cr ComplexityResult
+		var mods string
+		err := rows.Scan(
+			&cr.Symbol.ID, &cr.Symbol.FileID, &cr.Symbol.Name, &cr.Symbol.Kind,
+			&cr.Symbol.Visibility, &mods, &cr.Symbol.SignatureHash,
+			&cr.Symbol.StartLine, &cr.Symbol.StartCol, &cr.Symbol.EndLine, &cr.Symbol.EndCol,
+			&cr.Symbol.ParentSymbolID, &cr.Symbol.StartByte, &cr.Symbol.EndByte, &cr.Symbol.UTF16StartCol, &cr.Symbol.UTF16EndCol,
+			&cr.Symbol.PageRank, &cr.Symbol.Betweenness, &cr.Symbol.Subkind,
+			&cr.Symbol.FilePath, &cr.Symbol.RefCount, &cr.Symbol.ExternalRefCount,
+			&cr.Cyclomatic, &cr.Cognitive,
+		)
+		if err != nil {
+			return nil, fmt.Errorf("function complexity: scan: %w", err)
+		}
+		cr.Symbol.Modifiers = store.UnmarshalModifiers(mods)
+		cr.Symbol.InternalRefCount = cr.Symbol.RefCount - cr.Symbol.ExternalRefCount
+		items = append(items, cr)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("function complexity: rows: %w", err)
+	}
+	if items == nil {
+		items = []ComplexityResult{}
+	}
+
+	return &PagedResult[ComplexityResult]{Items: items, TotalCount: totalCount}, nil
+}
+
+// ComplexitySummary aggregates the complexity metrics of every function and
+// method matching a filter.
+type ComplexitySummary struct {
+	Functions      int     // functions and methods with metrics
+	MeanCyclomatic float64 // 0 when Functions is 0
+	MaxCyclomatic  int
+	MeanCognitive  float64
+	MaxCognitive   int
+}
+
+// ComplexitySummary returns the mean and maximum complexity of the functions
+// and methods FunctionComplexity would return for filter, for tracking how
+// complexity changes over time without paging through every function.
+func (q *QueryBuilder) ComplexitySummary(filter SymbolFilter) (*ComplexitySummary, error) {
+	where, args := q.symbolFilterClauses(filter)
+	whereClause := ""
+	if len(where) > 0 {
+		whereClause = "WHERE " + strings.Join(where, " AND ")
+	}
+	var cs ComplexitySummary
+	err := q.store.DB().QueryRow(`SELECT COUNT(*),
+			COALESCE(AVG(m.cyclomatic), 0), COALESCE(MAX(m.cyclomatic), 0),
+			COALESCE(AVG(m.cognitive), 0), COALESCE(MAX(m.cognitive), 0)
+		 FROM function_metrics m
+		 JOIN symbols s ON s.id = m.symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id `+whereClause, args...).
+		Scan(&cs.Functions, &cs.MeanCyclomatic, &cs.MaxCyclomatic, &cs.MeanCognitive, &cs.MaxCognitive)
+	if err != nil {
+		return nil, fmt.Errorf("complexity summary: %w", err)
+	}
+	return &cs, nil
+}
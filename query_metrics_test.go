@@ -0,0 +1,92 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func insertMetrics(t *testing.T, s *store.Store, symID int64, cyclomatic, cognitive int) {
+	t.Helper()
+	_, err := s.InsertFunctionMetrics(&store.FunctionMetrics{
+		SymbolID: symID, Cyclomatic: cyclomatic, Cognitive: cognitive,
+	})
+	require.NoError(t, err)
+}
+
+func TestFunctionComplexity_SortByCyclomaticDesc(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "simple", "function", "public", nil), 1, 0)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "gnarly", "function", "public", nil), 12, 20)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "medium", "method", "private", nil), 4, 3)
+	insertSymbol(t, s, &fID, "Config", "struct", "public", nil)
+
+	result, err := q.FunctionComplexity(SymbolFilter{}, Sort{Field: SortByCyclomatic, Order: Desc}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 3, result.TotalCount)
+	require.Len(t, result.Items, 3)
+	assert.Equal(t, "gnarly", result.Items[0].Symbol.Name)
+	assert.Equal(t, 12, result.Items[0].Cyclomatic)
+	assert.Equal(t, 20, result.Items[0].Cognitive)
+	assert.Equal(t, "/lib.rs", result.Items[0].Symbol.FilePath)
+	assert.Equal(t, "medium", result.Items[1].Symbol.Name)
+	assert.Equal(t, "simple", result.Items[2].Symbol.Name)
+}
+
+func TestFunctionComplexity_FilterAndPaginate(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/lib.rs", "rust")
+
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "a", "function", "public", nil), 2, 1)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "b", "function", "public", nil), 3, 2)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "m", "method", "public", nil), 9, 9)
+
+	result, err := q.FunctionComplexity(
+		SymbolFilter{Kinds: []string{"function"}},
+		Sort{Field: SortByCognitive, Order: Desc},
+		Pagination{Limit: intP(1)},
+	)
+	require.NoError(t, err)
+	assert.Equal(t, 2, result.TotalCount)
+	require.Len(t, result.Items, 1)
+	assert.Equal(t, "b", result.Items[0].Symbol.Name)
+}
+
+func TestFunctionComplexity_Empty(t *testing.T) {
+	t.Parallel()
+	q, _ := newTestQueryBuilder(t)
+
+	result, err := q.FunctionComplexity(SymbolFilter{}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 0, result.TotalCount)
+	assert.NotNil(t, result.Items)
+}
+
+func TestComplexitySummary(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+
+	empty, err := q.ComplexitySummary(SymbolFilter{})
+	require.NoError(t, err)
+	assert.Equal(t, ComplexitySummary{}, *empty)
+
+	fID := insertFile(t, s, "/lib.rs", "rust")
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "a", "function", "public", nil), 2, 1)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "b", "function", "public", nil), 4, 5)
+	insertMetrics(t, s, insertSymbol(t, s, &fID, "m", "method", "public", nil), 9, 0)
+
+	all, err := q.ComplexitySummary(SymbolFilter{})
+	require.NoError(t, err)
+	assert.Equal(t, ComplexitySummary{Functions: 3, MeanCyclomatic: 5, MaxCyclomatic: 9, MeanCognitive: 2, MaxCognitive: 5}, *all)
+
+	funcs, err := q.ComplexitySummary(SymbolFilter{Kinds: []string{"function"}})
+	require.NoError(t, err)
+	assert.Equal(t, 2, funcs.Functions)
+	assert.Equal(t, 4, funcs.MaxCyclomatic)
+}
@@ -0,0 +1,105 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// ModuleMatrix is a design structure matrix (DSM) of Rust modules: the
+// number of references from each module to the items of each other one.
+type ModuleMatrix struct {
+	Modules []string // crate-qualified module paths (demo, demo::net::http), sorted
+	// Counts[i][j] is the number of references in Modules[i] that resolve
+	// to items declared in Modules[j]; the diagonal is always zero.
+	Counts [][]int
+	Edges  int // the number of nonzero cells
+}
+
+// ModuleDependencyMatrix returns the module-by-module dependency matrix of
+// the Rust files under pathPrefix ("" for all files), from the resolved
+// references between them. A module's count covers all of its files; inline
+// mod blocks count toward the module of their file. depth truncates module
+// paths to that many segments below the crate root, aggregating submodules
+// into their ancestor (0 keeps full paths); a negative depth returns an
+// error. Files outside a Cargo package are not included.
+func (q *QueryBuilder) ModuleDependencyMatrix(pathPrefix string, depth int) (*ModuleMatrix, error) {
+	if depth < 0 {
+		return nil, fmt.Errorf("module dependency matrix: depth must be non-negative, got %d", depth)
+	}
+	files, err := q.store.FilesByLanguage("rust")
+	if err != nil {
+		return nil, fmt.Errorf("module dependency matrix: %w", err)
+	}
+
+	packages := map[string]cargoPackage{}
+	fileModule := map[int64]string{}
+	index := map[string]int{}
+	for _, f := range files {
+		if pathPrefix != "" && !strings.HasPrefix(f.Path, pathPrefix) {
+			continue
+		}
+		m, ok := q.rustModuleOf(f.Path, packages)
+		if !ok {
+			continue
+		}
+		name := m.crate
+		if m.path != "" {
+			segs := strings.Split(m.path, "::")
+			if depth > 0 && len(segs) > depth {
+				segs = segs[:depth]
+			}
+			name += "::" + strings.Join(segs, "::")
+		}
+		fileModule[f.ID] = name
+		index[name] = 0
+	}
+
+	mm := &ModuleMatrix{Modules: make([]string, 0, len(index))}
+	for name := range index {
+		mm.Modules = append(mm.Modules, name)
+	}
+	sort.Strings(mm.Modules)
+	for i, name := range mm.Modules {
+		index[name] = i
+	}
+	mm.Counts = make([][]int, len(mm.Modules))
+	for i := range mm.Counts {
+		mm.Counts[i] = make([]int, len(mm.Modules))
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT r.file_id, s.file_id, COUNT(DISTINCT r.id) FROM references_ r
+		 JOIN resolved_references rr ON rr.reference_id = r.id
+		 JOIN symbols s ON s.id = rr.target_symbol_id
+		 WHERE s.file_id IS NOT NULL AND s.file_id != r.file_id
+		 GROUP BY r.file_id, s.file_id`)
+	if err != nil {
+		return nil, fmt.Errorf("module dependency matrix: %w", err)
+	}
+	defer rows.Close()
+	for rows.Next() {
+		var from, to int64
+		var n int
+		if err := rows.Scan(&from, &to, &n); err != nil {
+			return nil, fmt.Errorf("module dependency matrix: scan: %w", err)
+		}
+		fromModule, ok := fileModule[from]
+		if !ok {
+			continue
+		}
+		toModule, ok := fileModule[to]
+		if !ok || toModule == fromModule {
+			continue
+		}
+		i, j := index[fromModule], index[toModule]
+		if mm.Counts[i][j] == 0 {
+			mm.Edges++
+		}
+		mm.Counts[i][j] += n
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("module dependency matrix: %w", err)
+	}
+	return mm, nil
+}
@@ -0,0 +1,90 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestModuleDependencyMatrix(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo-app\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "pub mod model;\npub mod store;\n")
+	write("src/model.rs", `pub struct User {
+    pub name: String,
+}
+
+pub fn new_user(name: String) -> User {
+    User { name }
+}
+`)
+	write("src/store/mod.rs", `pub mod cache;
+
+use crate::model::{new_user, User};
+
+pub fn load() -> User {
+    new_user(String::new())
+}
+`)
+	write("src/store/cache.rs", `use crate::model::User;
+use crate::store::load;
+
+pub fn cached() -> User {
+    load()
+}
+`)
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	cell := func(mm *ModuleMatrix, from, to string) int {
+		t.Helper()
+		i, j := -1, -1
+		for k, m := range mm.Modules {
+			if m == from {
+				i = k
+			}
+			if m == to {
+				j = k
+			}
+		}
+		require.True(t, i >= 0 && j >= 0, "modules %s and %s", from, to)
+		return mm.Counts[i][j]
+	}
+
+	mm, err := e.Query().ModuleDependencyMatrix("", 0)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"demo_app", "demo_app::model", "demo_app::store", "demo_app::store::cache"}, mm.Modules)
+	assert.Positive(t, cell(mm, "demo_app::store", "demo_app::model"))
+	assert.Positive(t, cell(mm, "demo_app::store::cache", "demo_app::store"))
+	assert.Positive(t, cell(mm, "demo_app::store::cache", "demo_app::model"))
+	assert.Zero(t, cell(mm, "demo_app::model", "demo_app::store"))
+	for i := range mm.Modules {
+		assert.Zero(t, mm.Counts[i][i])
+	}
+
+	mm, err = e.Query().ModuleDependencyMatrix("", 1)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"demo_app", "demo_app::model", "demo_app::store"}, mm.Modules)
+	assert.Equal(t, 1, mm.Edges, "cache folds into store, so only store -> model remains")
+	assert.Positive(t, cell(mm, "demo_app::store", "demo_app::model"))
+
+	mm, err = e.Query().ModuleDependencyMatrix(filepath.Join(root, "src", "store"), 0)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"demo_app::store", "demo_app::store::cache"}, mm.Modules)
+	assert.Equal(t, 1, mm.Edges)
+
+	_, err = e.Query().ModuleDependencyMatrix("", -1)
+	assert.Error(t, err)
+}
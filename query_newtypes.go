@@ -0,0 +1,139 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+)
+
+// CompositionKindWraps is the kind of the type composition linking a Rust
+// newtype to the type it wraps.
+const CompositionKindWraps = "wraps"
+
+// Newtype is a Rust struct with a single field, not counting PhantomData
+// markers, such as struct Meters(f64). It wraps the field's type.
+type Newtype struct {
+	Symbol      SymbolResult  // the wrapping struct
+	Inner       string        // the wrapped type as written: f64, Vec<u8>
+	InnerHead   string        // the wrapped type's head: f64, Vec ("" for a tuple or array)
+	InnerSymbol *SymbolResult // the type the head names; nil for a primitive or a type not indexed
+	Visibility  string        // the field's visibility
+}
+
+// Newtypes returns the Rust newtypes in files under pathPrefix ("" for all
+// files) that wrap a type named inner ("" for any). inner matches the
+// wrapped type as written (Vec<u8>), its head (Vec), or the name of the
+// symbol the head resolves to, which covers a head renamed on import.
+// Results are ordered by file and position.
+func (q *QueryBuilder) Newtypes(inner, pathPrefix string) ([]Newtype, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT tm.symbol_id FROM type_members tm
+		 JOIN symbols s ON s.id = tm.symbol_id
+		 JOIN files f ON f.id = s.file_id
+		 WHERE tm.kind = 'wraps' AND f.path LIKE ? ESCAPE '\'`,
+		escapeLike(pathPrefix)+"%",
+	)
+	if err != nil {
+		return nil, fmt.Errorf("newtypes: %w", err)
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("newtypes: %w", err)
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("newtypes: %w", err)
+	}
+
+	newtypes, err := q.newtypes(ids)
+	if err != nil {
+		return nil, fmt.Errorf("newtypes: %w", err)
+	}
+	if inner == "" {
+		return newtypes, nil
+	}
+	var matched []Newtype
+	for _, n := range newtypes {
+		if n.Inner == inner || n.InnerHead == inner || (n.InnerSymbol != nil && n.InnerSymbol.Name == inner) {
+			matched = append(matched, n)
+		}
+	}
+	return matched, nil
+}
+
+// WrappersOf returns the Rust newtypes wrapping the type with the given
+// symbol ID, ordered by file and position.
+func (q *QueryBuilder) WrappersOf(symbolID int64) ([]Newtype, error) {
+	comps, err := q.store.TypeComposedBy(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("wrappers of: %w", err)
+	}
+	var ids []int64
+	for _, tc := range comps {
+		if tc.CompositionKind == CompositionKindWraps {
+			ids = append(ids, tc.CompositeSymbolID)
+		}
+	}
+	newtypes, err := q.newtypes(ids)
+	if err != nil {
+		return nil, fmt.Errorf("wrappers of: %w", err)
+	}
+	return newtypes, nil
+}
+
+// newtypes loads the newtypes with the given struct symbol IDs.
+func (q *QueryBuilder) newtypes(ids []int64) ([]Newtype, error) {
+	if len(ids) == 0 {
+		return nil, nil
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+	var newtypes []Newtype
+	for _, id := range ids {
+		sr, ok := syms[id]
+		if !ok {
+			continue
+		}
+		n := Newtype{Symbol: *sr}
+		members, err := q.store.TypeMembers(id)
+		if err != nil {
+			return nil, err
+		}
+		for _, m := range members {
+			if m.Kind == CompositionKindWraps {
+				n.Inner, n.InnerHead, n.Visibility = m.TypeExpr, m.Name, m.Visibility
+			}
+		}
+		comps, err := q.store.TypeCompositions(id)
+		if err != nil {
+			return nil, err
+		}
+		for _, tc := range comps {
+			if tc.CompositionKind != CompositionKindWraps {
+				continue
+			}
+			if n.InnerSymbol, err = q.symbolResultByID(tc.ComponentSymbolID); err != nil {
+				return nil, err
+			}
+			break
+		}
+		newtypes = append(newtypes, n)
+	}
+	sort.Slice(newtypes, func(i, j int) bool {
+		a, b := newtypes[i].Symbol, newtypes[j].Symbol
+		if a.FilePath != b.FilePath {
+			return a.FilePath < b.FilePath
+		}
+		if a.StartLine != b.StartLine {
+			return a.StartLine < b.StartLine
+		}
+		return a.StartCol < b.StartCol
+	})
+	return newtypes, nil
+}
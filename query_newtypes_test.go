@@ -0,0 +1,84 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestNewtypes(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "pub mod units;\npub mod route;\n")
+	write("src/units.rs", `pub struct Meters(pub f64);
+
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+`)
+	write("src/route.rs", `use crate::units::Meters;
+
+pub struct Distance(Meters);
+
+pub struct Leg {
+    length: Meters,
+}
+`)
+	units := filepath.Join(root, "src", "units.rs")
+	route := filepath.Join(root, "src", "route.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	all, err := e.Query().Newtypes("", "")
+	require.NoError(t, err)
+	var names []string
+	for _, n := range all {
+		names = append(names, n.Symbol.Name)
+	}
+	assert.Equal(t, []string{"Distance", "Leg", "Meters"}, names, "ordered by file")
+
+	meters := all[2]
+	assert.Equal(t, "f64", meters.Inner)
+	assert.Equal(t, "f64", meters.InnerHead)
+	assert.Nil(t, meters.InnerSymbol, "a primitive has no symbol")
+	assert.Equal(t, "public", meters.Visibility)
+
+	distance := all[0]
+	assert.Equal(t, "Meters", distance.Inner)
+	require.NotNil(t, distance.InnerSymbol)
+	assert.Equal(t, units, distance.InnerSymbol.FilePath)
+	assert.Equal(t, "private", distance.Visibility)
+
+	ofF64, err := e.Query().Newtypes("f64", "")
+	require.NoError(t, err)
+	require.Len(t, ofF64, 1)
+	assert.Equal(t, "Meters", ofF64[0].Symbol.Name)
+
+	inRoute, err := e.Query().Newtypes("Meters", route)
+	require.NoError(t, err)
+	assert.Len(t, inRoute, 2)
+
+	wrappers, err := e.Query().WrappersOf(meters.Symbol.ID)
+	require.NoError(t, err)
+	require.Len(t, wrappers, 2)
+	assert.Equal(t, "Distance", wrappers[0].Symbol.Name)
+	assert.Equal(t, "Leg", wrappers[1].Symbol.Name)
+
+	h, err := e.Query().TypeHierarchy(meters.Symbol.ID)
+	require.NoError(t, err)
+	require.Len(t, h.ComposedBy, 2)
+	assert.Equal(t, CompositionKindWraps, h.ComposedBy[0].Kind)
+}
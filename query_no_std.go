@@ -0,0 +1,312 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+)
+
+// Standard library tiers a Rust symbol needs, from least to most: core is
+// available everywhere, alloc in a #![no_std] crate with a global allocator,
+// std only with an operating system.
+const (
+	StdTierCore  = "core"
+	StdTierAlloc = "alloc"
+	StdTierStd   = "std"
+)
+
+// stdTiers orders the tiers; a symbol's tier is the highest of its dependencies.
+var stdTiers = []string{StdTierCore, StdTierAlloc, StdTierStd}
+
+// How a crate root opts out of std.
+const (
+	NoStdAlways      = "always"   // #![no_std]
+	NoStdConditional = "cfg_attr" // #![cfg_attr(..., no_std)], usually behind a "std" feature
+)
+
+var (
+	noStdRe        = regexp.MustCompile(`(?m)^\s*#!\[\s*no_std\s*\]`)
+	noStdCfgAttrRe = regexp.MustCompile(`(?m)^\s*#!\[\s*cfg_attr\s*\(.*\bno_std\b.*\)\s*\]`)
+)
+
+// noStdReportKinds are the kinds of symbols a NoStdReport classifies.
+var noStdReportKinds = map[string]bool{
+	"function": true, "method": true, "default_method": true, "struct": true, "enum": true,
+	"trait": true, "type_alias": true, "constant": true, "impl": true,
+}
+
+// NoStdCrate is a Rust crate with the number of its symbols in each tier.
+type NoStdCrate struct {
+	Crate string // crate name, with - replaced by _
+	Root  string // crate root file
+	NoStd string // NoStdAlways, NoStdConditional, or "" for a std crate
+	Core  int    // symbols that need only core
+	Alloc int    // symbols that need alloc but not std
+	Std   int    // symbols that need std
+}
+
+// StdDependency is a symbol that needs more than core, with a chain of
+// references that shows why.
+type StdDependency struct {
+	Symbol SymbolResult
+	Crate  string
+	Tier   string // StdTierAlloc or StdTierStd
+	// Via is the chain of symbols from Symbol to the standard library
+	// item that puts it in Tier, each referenced by the one before; the
+	// last is the item's stub.
+	Via []SymbolResult
+}
+
+// NoStdReport classifies the symbols of each Rust crate by the part of the
+// standard library they need.
+type NoStdReport struct {
+	Crates       []NoStdCrate    // sorted by crate then root
+	Dependencies []StdDependency // symbols needing alloc or std, std first, then by file and line
+}
+
+// StdDependencies reports which symbols of the Rust crates under pathPrefix
+// ("" for all) need alloc or std, directly or through the symbols they
+// reference, and which crates are #![no_std]. A symbol needs what the items
+// it references need; a struct or enum also needs what its fields and
+// variants need. Standard library items are the std, core, and alloc stubs
+// loaded at resolution; a std item that is a re-export of a core or alloc
+// item (std::option::Option) counts as the latter. Symbols whose references
+// are unresolved count as core. Files outside a Cargo package are not
+// included.
+func (q *QueryBuilder) StdDependencies(pathPrefix string) (*NoStdReport, error) {
+	tier, err := q.stdStubTiers()
+	if err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+
+	// edges[src] are the symbols src depends on.
+	edges := map[int64][]int64{}
+	rows, err := q.store.DB().Query(symbolReferenceEdgesSQL)
+	if err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+	for rows.Next() {
+		var src, target int64
+		var n int
+		if err := rows.Scan(&src, &target, &n); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("std dependencies: scan: %w", err)
+		}
+		edges[src] = append(edges[src], target)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+	rows, err = q.store.DB().Query(
+		`SELECT s.parent_symbol_id, s.id FROM symbols s
+		 JOIN symbols p ON p.id = s.parent_symbol_id
+		 WHERE s.kind IN ('field', 'variant') AND p.kind IN ('struct', 'enum')`)
+	if err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+	for rows.Next() {
+		var parent, child int64
+		if err := rows.Scan(&parent, &child); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("std dependencies: scan: %w", err)
+		}
+		edges[parent] = append(edges[parent], child)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+
+	// Raise each symbol to the highest tier among its dependencies until
+	// nothing changes. via[id] is the dependency that last raised id; it
+	// reached that tier earlier, so following via never loops.
+	srcs := make([]int64, 0, len(edges))
+	for src := range edges {
+		srcs = append(srcs, src)
+	}
+	sort.Slice(srcs, func(i, j int) bool { return srcs[i] < srcs[j] })
+	via := map[int64]int64{}
+	for changed := true; changed; {
+		changed = false
+		for _, src := range srcs {
+			for _, target := range edges[src] {
+				if tier[target] > tier[src] {
+					tier[src] = tier[target]
+					via[src] = target
+					changed = true
+				}
+			}
+		}
+	}
+
+	files, err := q.store.FilesByLanguage("rust")
+	if err != nil {
+		return nil, fmt.Errorf("std dependencies: %w", err)
+	}
+	packages := map[string]cargoPackage{}
+	crates := map[string]*NoStdCrate{} // crate root -> crate
+	report := &NoStdReport{Crates: []NoStdCrate{}, Dependencies: []StdDependency{}}
+	var depIDs []int64
+	depCrate := map[int64]string{}
+	for _, f := range files {
+		if pathPrefix != "" && !strings.HasPrefix(f.Path, pathPrefix) {
+			continue
+		}
+		m, ok := q.rustModuleOf(f.Path, packages)
+		if !ok {
+			continue
+		}
+		c := crates[m.root]
+		if c == nil {
+			c = &NoStdCrate{Crate: m.crate, Root: m.root}
+			if src, err := q.readSource(m.root); err == nil {
+				c.NoStd = noStdAttribute(src)
+			}
+			crates[m.root] = c
+		}
+		syms, err := q.store.SymbolsByFile(f.ID)
+		if err != nil {
+			return nil, fmt.Errorf("std dependencies: %w", err)
+		}
+		for _, s := range syms {
+			if !noStdReportKinds[s.Kind] {
+				continue
+			}
+			switch stdTiers[tier[s.ID]] {
+			case StdTierCore:
+				c.Core++
+			case StdTierAlloc:
+				c.Alloc++
+			case StdTierStd:
+				c.Std++
+			}
+			if tier[s.ID] > 0 {
+				depIDs = append(depIDs, s.ID)
+				depCrate[s.ID] = m.crate
+			}
+		}
+	}
+	for _, c := range crates {
+		report.Crates = append(report.Crates, *c)
+	}
+	sort.Slice(report.Crates, func(i, j int) bool {
+		a, b := report.Crates[i], report.Crates[j]
+		if a.Crate != b.Crate {
+			return a.Crate < b.Crate
+		}
+		return a.Root < b.Root
+	})
+
+	// Load every symbol on the dependencies' chains at once.
+	seen := map[int64]bool{}
+	var ids []int64
+	for _, id := range depIDs {
+		for next, ok := id, true; ok && !seen[next]; next, ok = via[next] {
+			seen[next] = true
+			ids = append(ids, next)
+		}
+	}
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("std dependencies: load symbols: %w", err)
+	}
+	for _, id := range depIDs {
+		sym, ok := symbols[id]
+		if !ok {
+			continue
+		}
+		dep := StdDependency{Symbol: *sym, Crate: depCrate[id], Tier: stdTiers[tier[id]]}
+		for next, ok := via[id]; ok; next, ok = via[next] {
+			if s, found := symbols[next]; found {
+				dep.Via = append(dep.Via, *s)
+			}
+		}
+		report.Dependencies = append(report.Dependencies, dep)
+	}
+	sort.SliceStable(report.Dependencies, func(i, j int) bool {
+		a, b := report.Dependencies[i], report.Dependencies[j]
+		if a.Tier != b.Tier {
+			return a.Tier == StdTierStd
+		}
+		if a.Symbol.FilePath != b.Symbol.FilePath {
+			return a.Symbol.FilePath < b.Symbol.FilePath
+		}
+		return a.Symbol.StartLine < b.Symbol.StartLine
+	})
+	return report, nil
+}
+
+// noStdAttribute returns how the crate root src opts out of std:
+// NoStdAlways, NoStdConditional, or "".
+func noStdAttribute(src []byte) string {
+	switch {
+	case noStdRe.Match(src):
+		return NoStdAlways
+	case noStdCfgAttrRe.Match(src):
+		return NoStdConditional
+	}
+	return ""
+}
+
+// stdStubTiers returns the index in stdTiers of each alloc and std stub
+// symbol; symbols not in the map are core. A std item whose path, with the
+// crate swapped, is also a core or alloc item takes that crate's tier.
+func (q *QueryBuilder) stdStubTiers() (map[int64]int, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, s.name, s.kind, f.path, COALESCE(p.name, '') FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id AND p.kind != 'module'
+		 WHERE f.language = ?
+		 AND (f.path IN (?, ?, ?) OR f.path LIKE ? OR f.path LIKE ? OR f.path LIKE ?)`,
+		rustdocStubLanguage,
+		rustdocStubPrefix+"core", rustdocStubPrefix+"alloc", rustdocStubPrefix+"std",
+		rustdocStubPrefix+"core::%", rustdocStubPrefix+"alloc::%", rustdocStubPrefix+"std::%",
+	)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	// An item's key is its path below the crate, with its kind.
+	type stub struct {
+		id   int64
+		tier int
+		key  string
+	}
+	var stubs []stub
+	lowest := map[string]int{} // key -> lowest tier of a core or alloc item
+	for rows.Next() {
+		var id int64
+		var name, kind, path, parent string
+		if err := rows.Scan(&id, &name, &kind, &path, &parent); err != nil {
+			return nil, err
+		}
+		crate, mod, _ := strings.Cut(strings.TrimPrefix(path, rustdocStubPrefix), "::")
+		key := strings.Join([]string{mod, parent, name, kind}, "\x00")
+		t := 0
+		for t < len(stdTiers)-1 && stdTiers[t] != crate {
+			t++
+		}
+		stubs = append(stubs, stub{id, t, key})
+		if l, ok := lowest[key]; t < len(stdTiers)-1 && (!ok || t < l) {
+			lowest[key] = t
+		}
+	}
+	if err := rows.Err(); err != nil {
+		return nil, err
+	}
+
+	tiers := map[int64]int{}
+	for _, s := range stubs {
+		t := s.tier
+		if l, ok := lowest[s.key]; ok && l < t {
+			t = l
+		}
+		if t > 0 {
+			tiers[s.id] = t
+		}
+	}
+	return tiers, nil
+}
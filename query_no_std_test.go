@@ -0,0 +1,84 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestStdDependencies(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"),
+		[]byte("[package]\nname = \"geo-core\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"), 0o644))
+	lib := filepath.Join(root, "src", "lib.rs")
+	require.NoError(t, os.WriteFile(lib, []byte(`#![cfg_attr(not(feature = "std"), no_std)]
+use std::fs::read_to_string;
+
+pub struct Point {
+    x: i32,
+}
+
+pub struct Names {
+    list: Vec<u8>,
+}
+
+pub fn load(p: &str) -> usize {
+    read_to_string(p).len()
+}
+
+pub fn outer(p: &str) -> usize {
+    load(p)
+}
+
+pub fn biggest(a: i32, b: i32) -> i32 {
+    std::cmp::max(a, b)
+}
+`), 0o644))
+	require.NoError(t, e.IndexFiles(ctx, []string{lib}))
+	require.NoError(t, e.Resolve(ctx))
+
+	report, err := e.Query().StdDependencies("")
+	require.NoError(t, err)
+	assert.Equal(t, []NoStdCrate{{
+		Crate: "geo_core", Root: lib, NoStd: NoStdConditional, Core: 2, Alloc: 1, Std: 2,
+	}}, report.Crates)
+
+	type dep struct {
+		name, tier string
+		via        []string
+	}
+	var got []dep
+	for _, d := range report.Dependencies {
+		var via []string
+		for _, v := range d.Via {
+			via = append(via, v.Name)
+		}
+		assert.Equal(t, "geo_core", d.Crate)
+		got = append(got, dep{d.Symbol.Name, d.Tier, via})
+	}
+	assert.Equal(t, []dep{
+		{"load", StdTierStd, []string{"read_to_string"}},
+		{"outer", StdTierStd, []string{"load", "read_to_string"}},
+		{"Names", StdTierAlloc, []string{"list", "Vec"}},
+	}, got, "std::vec::Vec is alloc's Vec, and std::cmp::max core's")
+
+	report, err = e.Query().StdDependencies(filepath.Join(root, "tests"))
+	require.NoError(t, err)
+	assert.Empty(t, report.Crates)
+	assert.Empty(t, report.Dependencies)
+}
+
+func TestNoStdAttribute(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, NoStdAlways, noStdAttribute([]byte("//! Docs\n#![no_std]\n\npub fn f() {}\n")))
+	assert.Equal(t, NoStdConditional, noStdAttribute([]byte("#![cfg_attr(not(feature = \"std\"), no_std)]\n")))
+	assert.Equal(t, "", noStdAttribute([]byte("#![deny(missing_docs)]\n// #![no_std] once\n")))
+}
@@ -0,0 +1,422 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+)
+
+// Object safety violation kinds: why a Rust trait cannot be used as dyn Trait.
+const (
+	ObjectSafetySizedSelf       = "sized_self"              // the trait requires Self: Sized
+	ObjectSafetySupertrait      = "supertrait"              // a supertrait is not object safe
+	ObjectSafetyAssociatedConst = "associated_const"        // the trait declares a const
+	ObjectSafetyGenericType     = "generic_associated_type" // an associated type has generic parameters
+	ObjectSafetyNoReceiver      = "no_receiver"             // an associated function takes no self
+	ObjectSafetyReceiver        = "receiver"                // self has a type dyn Trait cannot dispatch on
+	ObjectSafetyGenericMethod   = "generic_method"          // a method has type parameters or impl Trait arguments
+	ObjectSafetySelfType        = "self_type"               // a method names Self outside its receiver
+	ObjectSafetyAsync           = "async_method"            // an async fn
+	ObjectSafetyImplTrait       = "impl_trait_return"       // a method returns impl Trait
+)
+
+// ObjectSafetyViolation is one reason a trait is not object safe.
+type ObjectSafetyViolation struct {
+	Kind   string // one of the ObjectSafety* kinds
+	Member string // the method, associated item, or supertrait at fault ("" for the trait itself)
+	Detail string // the member's declaration, whitespace collapsed; for a supertrait, its first violation kind
+}
+
+// TraitObjectSafety is whether a Rust trait can be used as a trait object,
+// and where it is.
+type TraitObjectSafety struct {
+	Trait         SymbolResult
+	DynCompatible bool                    // no violations: dyn Trait is allowed
+	Violations    []ObjectSafetyViolation // the trait's own first, then its members'
+	DynUses       []Location              // dyn Trait types naming the trait, by file and position
+}
+
+// ObjectSafety reports the object safety of the Rust traits declared in
+// files under pathPrefix ("" for all files), ordered by file and position.
+// A trait is object safe (dyn compatible) when it does not require
+// Self: Sized, its indexed supertraits are object safe, it declares no
+// associated consts or generic associated types, and each method is
+// dispatchable: it takes self, &self, &mut self, or self as Box, Rc, Arc or
+// Pin of those, has no type parameters or impl Trait arguments, names Self
+// only through projections such as Self::Item, is not async, and does not
+// return impl Trait. A method with `where Self: Sized` is exempt, since it
+// is not callable on a trait object. DynUses lists the dyn types naming the
+// trait, so a trait with violations and uses is a compile error to fix.
+func (q *QueryBuilder) ObjectSafety(pathPrefix string) ([]TraitObjectSafety, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT s.id FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 WHERE s.kind = 'trait' AND f.language = 'rust' AND f.path LIKE ? ESCAPE '\'
+		 ORDER BY f.path, s.start_line, s.start_col`,
+		escapeLike(pathPrefix)+"%",
+	)
+	if err != nil {
+		return nil, fmt.Errorf("object safety: %w", err)
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("object safety: %w", err)
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("object safety: %w", err)
+	}
+
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("object safety: %w", err)
+	}
+	memo := map[int64][]ObjectSafetyViolation{}
+	sources := map[string][]byte{}
+	out := make([]TraitObjectSafety, 0, len(ids))
+	for _, id := range ids {
+		sym, ok := syms[id]
+		if !ok {
+			continue
+		}
+		violations, err := q.objectSafetyViolations(id, memo, map[int64]bool{})
+		if err != nil {
+			return nil, fmt.Errorf("object safety: %w", err)
+		}
+		uses, err := q.dynUses(id, sources)
+		if err != nil {
+			return nil, fmt.Errorf("object safety: %w", err)
+		}
+		out = append(out, TraitObjectSafety{
+			Trait:         *sym,
+			DynCompatible: len(violations) == 0,
+			Violations:    violations,
+			DynUses:       uses,
+		})
+	}
+	return out, nil
+}
+
+// objectSafetyViolations returns why the trait traitID is not object safe.
+// memo caches each trait's violations; path holds the traits being checked,
+// so a supertrait cycle stops.
+func (q *QueryBuilder) objectSafetyViolations(traitID int64, memo map[int64][]ObjectSafetyViolation, path map[int64]bool) ([]ObjectSafetyViolation, error) {
+	if v, ok := memo[traitID]; ok {
+		return v, nil
+	}
+	violations := []ObjectSafetyViolation{}
+
+	bounds, err := q.store.TypeBounds(traitID)
+	if err != nil {
+		return nil, err
+	}
+	for _, b := range bounds {
+		if b.Subject == "Self" && b.Name == "Sized" && !strings.HasPrefix(b.Bound, "?") {
+			violations = append(violations, ObjectSafetyViolation{Kind: ObjectSafetySizedSelf, Detail: "Self: " + b.Bound})
+			break
+		}
+	}
+
+	comps, err := q.store.TypeCompositions(traitID)
+	if err != nil {
+		return nil, err
+	}
+	path[traitID] = true
+	for _, tc := range comps {
+		if tc.CompositionKind != CompositionKindSupertrait || path[tc.ComponentSymbolID] {
+			continue
+		}
+		super, err := q.objectSafetyViolations(tc.ComponentSymbolID, memo, path)
+		if err != nil {
+			return nil, err
+		}
+		if len(super) > 0 {
+			name := ""
+			if s, err := q.symbolResultByID(tc.ComponentSymbolID); err == nil && s != nil {
+				name = s.Name
+			}
+			violations = append(violations, ObjectSafetyViolation{Kind: ObjectSafetySupertrait, Member: name, Detail: super[0].Kind})
+		}
+	}
+	delete(path, traitID)
+
+	members, err := q.store.TypeMembers(traitID)
+	if err != nil {
+		return nil, err
+	}
+	for _, m := range members {
+		detail := strings.Join(strings.Fields(m.TypeExpr), " ")
+		switch m.Kind {
+		case "associated_const":
+			violations = append(violations, ObjectSafetyViolation{Kind: ObjectSafetyAssociatedConst, Member: m.Name, Detail: detail})
+		case "associated_type":
+			i := strings.Index(detail, m.Name)
+			if i < 0 {
+				continue
+			}
+			rest := strings.TrimSpace(detail[i+len(m.Name):])
+			if strings.HasPrefix(rest, "<") {
+				violations = append(violations, ObjectSafetyViolation{Kind: ObjectSafetyGenericType, Member: m.Name, Detail: detail})
+			}
+		case "method", "default_method":
+			for _, kind := range rustMethodObjectSafety(m.TypeExpr) {
+				violations = append(violations, ObjectSafetyViolation{Kind: kind, Member: m.Name, Detail: rustFnSignatureText(m.TypeExpr)})
+			}
+		}
+	}
+	memo[traitID] = violations
+	return violations, nil
+}
+
+// dynUses returns the type references to traitID written as dyn Trait,
+// including later bounds of dyn A + B. sources caches file contents.
+func (q *QueryBuilder) dynUses(traitID int64, sources map[string][]byte) ([]Location, error) {
+	uses := []Location{}
+	err := q.EachReferenceTo(traitID, ReferenceFilter{Kinds: []string{RefKindType, RefKindTraitBound}}, func(site ReferenceSite) error {
+		src, ok := sources[site.File]
+		if !ok {
+			src, _ = q.readSource(site.File) // without source, no use is seen as dyn
+			sources[site.File] = src
+		}
+		if site.StartByte <= len(src) && isDynBound(string(src[:site.StartByte])) {
+			uses = append(uses, site.Location)
+		}
+		return nil
+	})
+	return uses, err
+}
+
+// isDynBound reports whether the source before a trait name puts it in a
+// dyn type: dyn Trait, dyn path::Trait, or dyn A + Trait.
+func isDynBound(before string) bool {
+	i := len(before)
+	for {
+		for i > 0 && (isRustIdentByte(before[i-1]) || before[i-1] == ':') {
+			i-- // the path leading to the name
+		}
+		j := skipRustSpaceBack(before, i)
+		if j >= 3 && before[j-3:j] == "dyn" && j < i && (j == 3 || !isRustIdentByte(before[j-4])) {
+			return true
+		}
+		if j == 0 || before[j-1] != '+' {
+			return false
+		}
+		// dyn A + Trait: step back over the bound before the +.
+		i = skipRustSpaceBack(before, j-1)
+		if i > 0 && before[i-1] == '>' {
+			return false // a generic bound; not followed further
+		}
+	}
+}
+
+func skipRustSpaceBack(text string, i int) int {
+	for i > 0 && strings.IndexByte(" \t\r\n", text[i-1]) >= 0 {
+		i--
+	}
+	return i
+}
+
+// rustFnSig is the parts of a Rust function signature that object safety
+// depends on.
+type rustFnSig struct {
+	async    bool
+	generics []string // type and const parameters; lifetimes are skipped
+	params   []string // as written, the receiver first if there is one
+	ret      string   // the return type, "" for none
+	where    string   // the where clause's predicates
+}
+
+// parseRustFnSig parses a function signature, or a function item whose
+// body is ignored.
+func parseRustFnSig(text string) (sig rustFnSig, ok bool) {
+	fnAt := rustHeaderScan(text, 0, "fn")
+	if fnAt >= len(text) {
+		return sig, false
+	}
+	sig.async = rustHeaderScan(text[:fnAt], 0, "async") < fnAt
+	i := skipRustSpace(text, fnAt+len("fn"))
+	for i < len(text) && isRustIdentByte(text[i]) {
+		i++
+	}
+	i = skipRustSpace(text, i)
+	if i < len(text) && text[i] == '<' {
+		end := rustHeaderScan(text, i+1, ">")
+		if end >= len(text) {
+			return sig, false
+		}
+		sig.generics = rustGenericParams(text[i+1 : end])
+		i = skipRustSpace(text, end+1)
+	}
+	if i >= len(text) || text[i] != '(' {
+		return sig, false
+	}
+	end := rustHeaderScan(text, i+1, ")")
+	if end >= len(text) {
+		return sig, false
+	}
+	for _, p := range splitTypeList(text[i+1 : end]) {
+		if p != "" {
+			sig.params = append(sig.params, strings.Join(strings.Fields(p), " "))
+		}
+	}
+	i = end + 1
+	end = rustSignatureEnd(text, i)
+	if where := rustHeaderScan(text, i, "where"); where < end {
+		sig.where = strings.TrimSpace(text[where+len("where") : end])
+		end = where
+	}
+	sig.ret = strings.TrimSpace(strings.TrimPrefix(strings.TrimSpace(text[i:end]), "->"))
+	return sig, true
+}
+
+// rustSignatureEnd returns the offset of the body or closing semicolon of
+// a function, scanning from offset i.
+func rustSignatureEnd(text string, i int) int {
+	end := rustHeaderScan(text, i, "{")
+	if semi := rustHeaderScan(text, i, ";"); semi < end {
+		end = semi
+	}
+	return end
+}
+
+// rustFnSignatureText returns a function's signature without its body,
+// whitespace collapsed.
+func rustFnSignatureText(text string) string {
+	return strings.Join(strings.Fields(text[:rustSignatureEnd(text, 0)]), " ")
+}
+
+// rustMethodObjectSafety returns the object safety violation kinds of a
+// trait method, given its signature or declaration.
+func rustMethodObjectSafety(text string) []string {
+	sig, ok := parseRustFnSig(text)
+	if !ok || rustWhereSelfSized(sig.where) {
+		return nil
+	}
+	var kinds []string
+	params := sig.params
+	if len(params) == 0 {
+		kinds = append(kinds, ObjectSafetyNoReceiver)
+	} else if isReceiver, dispatchable := rustReceiver(params[0]); !isReceiver {
+		kinds = append(kinds, ObjectSafetyNoReceiver)
+	} else {
+		params = params[1:]
+		if !dispatchable {
+			kinds = append(kinds, ObjectSafetyReceiver)
+		}
+	}
+	generic := len(sig.generics) > 0
+	mentionsSelf := rustMentionsSelfType(sig.ret)
+	for _, p := range params {
+		ty := p
+		if colon := rustHeaderScan(p, 0, ":"); colon < len(p) {
+			ty = p[colon+1:]
+		}
+		generic = generic || rustWordIndex(ty, "impl") >= 0
+		mentionsSelf = mentionsSelf || rustMentionsSelfType(ty)
+	}
+	if generic {
+		kinds = append(kinds, ObjectSafetyGenericMethod)
+	}
+	if mentionsSelf {
+		kinds = append(kinds, ObjectSafetySelfType)
+	}
+	if sig.async {
+		kinds = append(kinds, ObjectSafetyAsync)
+	}
+	if rustWordIndex(sig.ret, "impl") >= 0 {
+		kinds = append(kinds, ObjectSafetyImplTrait)
+	}
+	return kinds
+}
+
+// rustWhereSelfSized reports whether where clause predicates bound Self by
+// Sized.
+func rustWhereSelfSized(where string) bool {
+	for _, pred := range splitTypeList(where) {
+		colon := rustHeaderScan(pred, 0, ":")
+		if colon >= len(pred) || strings.TrimSpace(pred[:colon]) != "Self" {
+			continue
+		}
+		for _, b := range strings.Split(pred[colon+1:], "+") {
+			b = strings.TrimSpace(b)
+			if b == "Sized" || strings.HasSuffix(b, "::Sized") {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// rustReceiver reports whether a parameter is a self receiver and whether
+// a trait object can dispatch on it.
+func rustReceiver(param string) (isReceiver, dispatchable bool) {
+	p := strings.TrimPrefix(param, "mut ")
+	if p == "self" {
+		return true, true
+	}
+	if rest, ok := strings.CutPrefix(p, "&"); ok {
+		rest = strings.TrimSpace(rest)
+		if strings.HasPrefix(rest, "'") {
+			n := 1
+			for n < len(rest) && isRustIdentByte(rest[n]) {
+				n++
+			}
+			rest = strings.TrimSpace(rest[n:])
+		}
+		rest = strings.TrimSpace(strings.TrimPrefix(rest, "mut "))
+		return rest == "self", true
+	}
+	if t, ok := strings.CutPrefix(p, "self:"); ok {
+		return true, rustDispatchableSelf(parseRustType(t))
+	}
+	return false, false
+}
+
+// rustDispatchableSelf reports whether an explicit receiver type is Self
+// behind references and Box, Rc, Arc or Pin.
+func rustDispatchableSelf(t rustType) bool {
+	switch t.head {
+	case "Self":
+		return len(t.args) == 0
+	case "&", "&mut", "Box", "Rc", "Arc", "Pin":
+		return len(t.args) == 1 && rustDispatchableSelf(t.args[0])
+	}
+	return false
+}
+
+// rustMentionsSelfType reports whether a type names Self other than through
+// a projection (Self::Item, <Self as Trait>::Item).
+func rustMentionsSelfType(ty string) bool {
+	for i := 0; ; {
+		at := rustWordIndex(ty[i:], "Self")
+		if at < 0 {
+			return false
+		}
+		i += at + len("Self")
+		rest := strings.TrimLeft(ty[i:], " ")
+		if !strings.HasPrefix(rest, "::") && !strings.HasPrefix(rest, "as ") {
+			return true
+		}
+	}
+}
+
+// rustWordIndex returns the offset of the first occurrence of word in s as
+// a whole word, or -1.
+func rustWordIndex(s, word string) int {
+	for i := 0; ; {
+		at := strings.Index(s[i:], word)
+		if at < 0 {
+			return -1
+		}
+		at += i
+		end := at + len(word)
+		if (at == 0 || !isRustIdentByte(s[at-1])) && (end == len(s) || !isRustIdentByte(s[end])) {
+			return at
+		}
+		i = end
+	}
+}
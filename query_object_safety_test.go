@@ -0,0 +1,143 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestObjectSafety(t *testing.T) {
+	ctx := context.Background()
+	dir := t.TempDir()
+	require.NoError(t, os.WriteFile(filepath.Join(dir, "lib.rs"), []byte(`pub trait Plugin {
+    type Output;
+    fn name(&self) -> &str;
+    fn run(&mut self, input: &[u8]) -> Self::Output;
+    fn boxed(self: Box<Self>) {}
+    fn create() -> Self where Self: Sized;
+}
+
+pub trait Shape: Clone {
+    const SIDES: u32;
+    fn scale<T: Into<f64>>(&self, by: T);
+    fn merge(&self, other: &Self) -> Self;
+    fn new() -> Self;
+}
+
+pub trait Labeled: Shape {
+    fn label(&self) -> String;
+}
+
+pub trait Sink: Sized {
+    fn put(&self, b: u8);
+}
+
+pub fn load(plugins: Vec<Box<dyn Plugin<Output = u8>>>, first: &dyn Plugin<Output = u8>) {}
+`), 0o644))
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+
+	traits, err := e.Query().ObjectSafety("")
+	require.NoError(t, err)
+	byName := map[string]TraitObjectSafety{}
+	for _, ts := range traits {
+		byName[ts.Trait.Name] = ts
+	}
+	require.Len(t, byName, 4)
+
+	plugin := byName["Plugin"]
+	assert.True(t, plugin.DynCompatible, "%v", plugin.Violations)
+	assert.Len(t, plugin.DynUses, 2)
+
+	kinds := func(ts TraitObjectSafety) map[string]string {
+		out := map[string]string{}
+		for _, v := range ts.Violations {
+			out[v.Member+" "+v.Kind] = v.Detail
+		}
+		return out
+	}
+	shape := byName["Shape"]
+	assert.False(t, shape.DynCompatible)
+	assert.Equal(t, map[string]string{
+		"SIDES associated_const": "const SIDES: u32;",
+		"scale generic_method":   "fn scale<T: Into<f64>>(&self, by: T);",
+		"merge self_type":        "fn merge(&self, other: &Self) -> Self;",
+		"new no_receiver":        "fn new() -> Self;",
+		"new self_type":          "fn new() -> Self;",
+	}, kinds(shape))
+	assert.Empty(t, shape.DynUses)
+
+	labeled := byName["Labeled"]
+	assert.False(t, labeled.DynCompatible)
+	require.Len(t, labeled.Violations, 1)
+	assert.Equal(t, ObjectSafetySupertrait, labeled.Violations[0].Kind)
+	assert.Equal(t, "Shape", labeled.Violations[0].Member)
+
+	sink := byName["Sink"]
+	assert.False(t, sink.DynCompatible)
+	assert.Equal(t, map[string]string{" sized_self": "Self: Sized"}, kinds(sink))
+}
+
+func TestRustMethodObjectSafety(t *testing.T) {
+	for _, c := range []struct {
+		sig  string
+		want []string
+	}{
+		{"fn get(&self) -> Option<Self::Item>;", nil},
+		{"fn get<'a>(&'a mut self) -> &'a str;", nil},
+		{"fn pinned(self: Pin<&mut Self>);", nil},
+		{"fn rc(self: Rc<Self>);", nil},
+		{"fn consume(self);", nil},
+		{"fn cell(self: Cell<Self>);", []string{ObjectSafetyReceiver}},
+		{"fn each(&self, f: impl Fn(u8));", []string{ObjectSafetyGenericMethod}},
+		{"async fn fetch(&self) -> u8;", []string{ObjectSafetyAsync}},
+		{"fn iter(&self) -> impl Iterator<Item = u8>;", []string{ObjectSafetyImplTrait}},
+		{"fn cmp(&self, other: &Self) -> bool where Self: Sized;", nil},
+		{"fn dup(&self) -> Self where Self: Clone + Sized { self.clone() }", nil},
+		{"fn dup(&self) -> Self { self.clone() }", []string{ObjectSafetySelfType}},
+	} {
+		assert.Equal(t, c.want, rustMethodObjectSafety(c.sig), c.sig)
+	}
+}
+
+func TestIsDynBound(t *testing.T) {
+	for _, c := range []struct {
+		before string
+		want   bool
+	}{
+		{"x: &dyn ", true},
+		{"Box<dyn std::fmt::", true},
+		{"Box<dyn Send + ", true},
+		{"x: impl Send + ", false},
+		{"x: &", false},
+		{"fn f<T: ", false},
+		{"let dyn_x: ", false},
+	} {
+		assert.Equal(t, c.want, isDynBound(c.before), c.before)
+	}
+}
+
+func TestObjectSafety_AssociatedTypeWithoutName(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+
+	fileID := insertFile(t, s, "/src/lib.rs", "rust")
+	traitID, err := s.InsertSymbol(&store.Symbol{FileID: &fileID, Name: "Plugin", Kind: "trait"})
+	require.NoError(t, err)
+	// A type expression that does not name the member is skipped, not
+	// sliced out of range.
+	_, err = s.InsertTypeMember(&store.TypeMember{SymbolID: traitID, Name: "Output", Kind: "associated_type", TypeExpr: ""})
+	require.NoError(t, err)
+
+	traits, err := q.ObjectSafety("")
+	require.NoError(t, err)
+	require.Len(t, traits, 1)
+	assert.True(t, traits[0].DynCompatible)
+	assert.Empty(t, traits[0].Violations)
+}
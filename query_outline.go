@@ -0,0 +1,223 @@
+package canopy
+
+import (
+	"bytes"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// OutlineKindImpl is the Kind of outline nodes for impl blocks, which group
+// the methods they define. Impl blocks are scopes, not symbols.
+const OutlineKindImpl = "impl"
+
+// OutlineNode is one entry of a file outline: a symbol, or an impl block,
+// with the items nested inside it.
+type OutlineNode struct {
+	SymbolID   *int64 // nil for impl blocks
+	Name       string // for impl blocks, the block header (e.g. "impl Area for Circle")
+	Kind       string
+	Visibility string
+	StartLine  int
+	EndLine    int
+	Children   []*OutlineNode
+}
+
+// OutlineFile is the outline of one indexed file.
+type OutlineFile struct {
+	File     string
+	Language string
+	Items    []*OutlineNode
+}
+
+// outlineBodyKinds are symbol kinds whose bodies are not outlined: symbols
+// declared inside them (locals, closures) are implementation detail.
+var outlineBodyKinds = map[string]bool{
+	"function":    true,
+	"method":      true,
+	"constructor": true,
+}
+
+// Outline returns the hierarchical outline of every indexed file under
+// pathPrefix (all files if empty), ordered by path: modules, their items,
+// impl blocks, and methods. A path naming a single file outlines just that
+// file. Symbols nest under the symbol or impl block whose span contains
+// them, or under their parent symbol when it is declared in the same file
+// (e.g. Go methods under their receiver type).
+func (q *QueryBuilder) Outline(pathPrefix string) ([]OutlineFile, error) {
+	query := "SELECT id, path, language FROM files WHERE language != ?"
+	args := []any{rustdocStubLanguage}
+	if pathPrefix != "" {
+		query += " AND (path = ? OR path LIKE ? ESCAPE '\\')"
+		args = append(args, pathPrefix, escapeLike(normalizePathPrefix(pathPrefix))+"%")
+	}
+	rows, err := q.store.DB().Query(query+" ORDER BY path", args...)
+	if err != nil {
+		return nil, fmt.Errorf("outline: %w", err)
+	}
+	type fileRow struct {
+		id         int64
+		path, lang string
+	}
+	var files []fileRow
+	for rows.Next() {
+		var f fileRow
+		if err := rows.Scan(&f.id, &f.path, &f.lang); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("outline: scan: %w", err)
+		}
+		files = append(files, f)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("outline: %w", err)
+	}
+
+	outlines := make([]OutlineFile, 0, len(files))
+	for _, f := range files {
+		items, err := q.outlineFile(f.id, f.path)
+		if err != nil {
+			return nil, fmt.Errorf("outline %s: %w", f.path, err)
+		}
+		outlines = append(outlines, OutlineFile{File: f.path, Language: f.lang, Items: items})
+	}
+	return outlines, nil
+}
+
+// outlineEntry is a node under construction with its full span.
+type outlineEntry struct {
+	node                                 *OutlineNode
+	sym                                  *store.Symbol // nil for impl blocks
+	startLine, startCol, endLine, endCol int
+}
+
+// contains reports whether b nests inside a. Of two entries with the same
+// span, an impl block encloses a symbol and an earlier symbol encloses a
+// later one.
+func (a *outlineEntry) contains(b *outlineEntry) bool {
+	if a == b {
+		return false
+	}
+	startsBefore := a.startLine < b.startLine || (a.startLine == b.startLine && a.startCol <= b.startCol)
+	endsAfter := a.endLine > b.endLine || (a.endLine == b.endLine && a.endCol >= b.endCol)
+	if !startsBefore || !endsAfter {
+		return false
+	}
+	if a.startLine != b.startLine || a.startCol != b.startCol || a.endLine != b.endLine || a.endCol != b.endCol {
+		return true
+	}
+	if a.sym == nil || b.sym == nil {
+		return a.sym == nil && b.sym != nil
+	}
+	return a.sym.ID < b.sym.ID
+}
+
+// outlineFile builds the outline tree of one file.
+func (q *QueryBuilder) outlineFile(fileID int64, path string) ([]*OutlineNode, error) {
+	syms, err := q.store.SymbolsByFile(fileID)
+	if err != nil {
+		return nil, err
+	}
+	scopes, err := q.store.ScopesByFile(fileID)
+	if err != nil {
+		return nil, err
+	}
+
+	var lines [][]byte
+	var entries []*outlineEntry
+	for _, sc := range scopes {
+		if sc.Kind != OutlineKindImpl {
+			continue
+		}
+		if lines == nil {
+			if content, err := q.readSource(path); err == nil {
+				lines = bytes.Split(content, []byte{'\n'})
+			}
+		}
+		entries = append(entries, &outlineEntry{
+			node: &OutlineNode{
+				Name:      implHeader(lines, sc.StartLine),
+				Kind:      OutlineKindImpl,
+				StartLine: sc.StartLine,
+				EndLine:   sc.EndLine,
+			},
+			startLine: sc.StartLine, startCol: sc.StartCol, endLine: sc.EndLine, endCol: sc.EndCol,
+		})
+	}
+	byID := make(map[int64]*outlineEntry, len(syms))
+	for _, sym := range syms {
+		id := sym.ID
+		e := &outlineEntry{
+			node: &OutlineNode{
+				SymbolID:   &id,
+				Name:       sym.Name,
+				Kind:       sym.Kind,
+				Visibility: sym.Visibility,
+				StartLine:  sym.StartLine,
+				EndLine:    sym.EndLine,
+			},
+			sym:       sym,
+			startLine: sym.StartLine, startCol: sym.StartCol, endLine: sym.EndLine, endCol: sym.EndCol,
+		}
+		entries = append(entries, e)
+		byID[id] = e
+	}
+
+	var roots []*OutlineNode
+	for _, e := range entries {
+		// The narrowest enclosing entry is the parent. Entries inside a
+		// function body are dropped; their own children attach to them and
+		// are dropped with them.
+		var parent *outlineEntry
+		for _, c := range entries {
+			if c.contains(e) && (parent == nil || parent.contains(c)) {
+				parent = c
+			}
+		}
+		if parent != nil && parent.sym != nil && outlineBodyKinds[parent.sym.Kind] {
+			continue
+		}
+		if parent == nil && e.sym != nil && e.sym.ParentSymbolID != nil {
+			if p, ok := byID[*e.sym.ParentSymbolID]; ok && !outlineBodyKinds[p.sym.Kind] {
+				parent = p
+			}
+		}
+		if parent == nil {
+			roots = append(roots, e.node)
+		} else {
+			parent.node.Children = append(parent.node.Children, e.node)
+		}
+	}
+
+	sortOutline(roots)
+	return roots, nil
+}
+
+// implHeader returns the header of the impl block starting at line, e.g.
+// "impl<T> Area for Circle<T>", or "impl" if the source is unavailable.
+func implHeader(lines [][]byte, line int) string {
+	if line < 0 || line >= len(lines) {
+		return OutlineKindImpl
+	}
+	header := string(lines[line])
+	if i := strings.Index(header, "{"); i >= 0 {
+		header = header[:i]
+	}
+	header = strings.TrimSpace(header)
+	if header == "" {
+		return OutlineKindImpl
+	}
+	return header
+}
+
+// sortOutline orders every level of an outline by position.
+func sortOutline(nodes []*OutlineNode) {
+	sort.SliceStable(nodes, func(i, j int) bool {
+		return nodes[i].StartLine < nodes[j].StartLine
+	})
+	for _, n := range nodes {
+		sortOutline(n.Children)
+	}
+}
@@ -0,0 +1,104 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// outlineChild returns the node named name among nodes, or nil.
+func outlineChild(nodes []*OutlineNode, name string) *OutlineNode {
+	for _, n := range nodes {
+		if n.Name == name {
+			return n
+		}
+	}
+	return nil
+}
+
+func TestOutline_RustModulesAndImpls(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`pub mod shapes {
+    pub fn unit() -> f64 {
+        1.0
+    }
+}
+
+pub struct Circle {
+    r: f64,
+}
+
+impl Circle {
+    pub fn area(&self) -> f64 {
+        self.r * self.r
+    }
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	files, err := e.Query().Outline(dir)
+	require.NoError(t, err)
+	require.Len(t, files, 1)
+	assert.Equal(t, path, files[0].File)
+	assert.Equal(t, "rust", files[0].Language)
+	items := files[0].Items
+
+	mod := outlineChild(items, "shapes")
+	require.NotNil(t, mod)
+	assert.Equal(t, "module", mod.Kind)
+	require.NotNil(t, outlineChild(mod.Children, "unit"), "module functions nest under the module")
+
+	circle := outlineChild(items, "Circle")
+	require.NotNil(t, circle)
+	assert.Equal(t, "struct", circle.Kind)
+
+	impl := outlineChild(items, "impl Circle")
+	require.NotNil(t, impl)
+	assert.Equal(t, OutlineKindImpl, impl.Kind)
+	assert.Nil(t, impl.SymbolID)
+	area := outlineChild(impl.Children, "area")
+	require.NotNil(t, area, "methods nest under their impl block")
+	assert.Empty(t, area.Children)
+
+	assert.Less(t, mod.StartLine, circle.StartLine)
+	assert.Less(t, circle.StartLine, impl.StartLine)
+}
+
+func TestOutline_GoDropsLocals(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := writeGoFile(t, dir, "main.go", `package main
+
+type Server struct{}
+
+func (s *Server) Start() {
+	port := 8080
+	_ = port
+}
+`)
+	other := writeGoFile(t, t.TempDir(), "other.go", "package main\n\nfunc other() {}\n")
+	require.NoError(t, e.IndexFiles(ctx, []string{path, other}))
+	require.NoError(t, e.Resolve(ctx))
+
+	files, err := e.Query().Outline(path)
+	require.NoError(t, err)
+	require.Len(t, files, 1, "a file path outlines just that file")
+
+	server := outlineChild(files[0].Items, "Server")
+	require.NotNil(t, server)
+	start := outlineChild(server.Children, "Start")
+	require.NotNil(t, start, "methods nest under their receiver type")
+	assert.Empty(t, start.Children, "locals are not outlined")
+	assert.Nil(t, outlineChild(files[0].Items, "port"))
+}
@@ -0,0 +1,137 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+)
+
+// OwnerImpact summarizes how much of one owner's code depends on a symbol.
+// Owners come from the CODEOWNERS file of each indexed root.
+type OwnerImpact struct {
+	Owner      string // "" for files no CODEOWNERS rule assigns
+	References int    // resolved references from the owner's files
+	Callers    int    // transitive callers (within maxDepth) defined in the owner's files
+	Files      int    // distinct owner files that reference or call the symbol
+}
+
+// FileOwners returns the owners of a file. Returns an empty slice if the file
+// is unowned or its root has no CODEOWNERS file.
+func (q *QueryBuilder) FileOwners(fileID int64) ([]string, error) {
+	owners, err := q.store.FileOwners(fileID)
+	if err != nil {
+		return nil, fmt.Errorf("file owners: %w", err)
+	}
+	if owners == nil {
+		owners = []string{}
+	}
+	return owners, nil
+}
+
+// SymbolOwners returns the owners of the file that defines a symbol.
+func (q *QueryBuilder) SymbolOwners(symbolID int64) ([]string, error) {
+	sym, err := q.store.SymbolByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("symbol owners: %w", err)
+	}
+	if sym == nil || sym.FileID == nil {
+		return []string{}, nil
+	}
+	return q.FileOwners(*sym.FileID)
+}
+
+// OwnerImpact groups the references to a symbol and its transitive callers
+// (up to maxDepth) by the owners of the files they occur in, answering "which
+// teams' code depends on this". A file with several owners counts toward
+// each. Sorted by References+Callers descending, then owner. Returns nil, nil
+// if the symbol does not exist.
+func (q *QueryBuilder) OwnerImpact(symbolID int64, maxDepth int) ([]OwnerImpact, error) {
+	graph, err := q.TransitiveCallers(symbolID, maxDepth)
+	if err != nil {
+		return nil, fmt.Errorf("owner impact: %w", err)
+	}
+	if graph == nil {
+		return nil, nil
+	}
+
+	fileOwners := map[int64][]string{}
+	rows, err := q.store.DB().Query("SELECT file_id, owner FROM file_owners ORDER BY rowid")
+	if err != nil {
+		return nil, fmt.Errorf("owner impact: load owners: %w", err)
+	}
+	for rows.Next() {
+		var fileID int64
+		var owner string
+		if err := rows.Scan(&fileID, &owner); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("owner impact: scan owner: %w", err)
+		}
+		fileOwners[fileID] = append(fileOwners[fileID], owner)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("owner impact: load owners: %w", err)
+	}
+
+	impacts := map[string]*OwnerImpact{}
+	ownerFiles := map[string]map[int64]bool{}
+	add := func(fileID int64, refs, callers int) {
+		owners := fileOwners[fileID]
+		if len(owners) == 0 {
+			owners = []string{""}
+		}
+		for _, o := range owners {
+			imp, ok := impacts[o]
+			if !ok {
+				imp = &OwnerImpact{Owner: o}
+				impacts[o] = imp
+				ownerFiles[o] = map[int64]bool{}
+			}
+			imp.References += refs
+			imp.Callers += callers
+			ownerFiles[o][fileID] = true
+		}
+	}
+
+	rows, err = q.store.DB().Query(
+		`SELECT r.file_id FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 WHERE rr.target_symbol_id = ?`, symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("owner impact: references: %w", err)
+	}
+	for rows.Next() {
+		var fileID int64
+		if err := rows.Scan(&fileID); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("owner impact: scan reference: %w", err)
+		}
+		add(fileID, 1, 0)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("owner impact: references: %w", err)
+	}
+
+	for _, node := range graph.Nodes {
+		if node.Depth == 0 || node.Symbol.FileID == nil {
+			continue
+		}
+		add(*node.Symbol.FileID, 0, 1)
+	}
+
+	result := make([]OwnerImpact, 0, len(impacts))
+	for o, imp := range impacts {
+		imp.Files = len(ownerFiles[o])
+		result = append(result, *imp)
+	}
+	sort.Slice(result, func(i, j int) bool {
+		wi := result[i].References + result[i].Callers
+		wj := result[j].References + result[j].Callers
+		if wi != wj {
+			return wi > wj
+		}
+		return result[i].Owner < result[j].Owner
+	})
+	return result, nil
+}
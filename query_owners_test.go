@@ -0,0 +1,75 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestOwnerImpact_GroupsReferencesAndCallersByOwner(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	libID := insertFile(t, s, "/repo/lib/lib.go", "go")
+	apiID := insertFile(t, s, "/repo/api/api.go", "go")
+	webID := insertFile(t, s, "/repo/web/web.go", "go")
+	require.NoError(t, s.ReplaceFileOwners(map[int64][]string{
+		libID: {"@org/core"},
+		apiID: {"@org/api", "@org/core"},
+	}))
+
+	target := insertSymbol(t, s, &libID, "Charge", "function", "public", nil)
+	handler := insertSymbol(t, s, &apiID, "Handle", "function", "public", nil)
+	page := insertSymbol(t, s, &webID, "Render", "function", "public", nil)
+
+	insertResolvedRef(t, s, apiID, target)
+	insertResolvedRef(t, s, apiID, target)
+	insertResolvedRef(t, s, webID, target)
+	_, err := s.InsertCallEdge(&store.CallEdge{CallerSymbolID: handler, CalleeSymbolID: target, FileID: &apiID})
+	require.NoError(t, err)
+	_, err = s.InsertCallEdge(&store.CallEdge{CallerSymbolID: page, CalleeSymbolID: handler, FileID: &webID})
+	require.NoError(t, err)
+
+	impacts, err := q.OwnerImpact(target, 5)
+	require.NoError(t, err)
+	assert.Equal(t, []OwnerImpact{
+		{Owner: "@org/api", References: 2, Callers: 1, Files: 1},
+		{Owner: "@org/core", References: 2, Callers: 1, Files: 1},
+		{Owner: "", References: 1, Callers: 1, Files: 1},
+	}, impacts)
+
+	impacts, err = q.OwnerImpact(target, 1)
+	require.NoError(t, err)
+	for _, imp := range impacts {
+		if imp.Owner == "" {
+			assert.Equal(t, 0, imp.Callers, "web caller is two hops away")
+		}
+	}
+}
+
+func TestOwnerImpact_MissingSymbol(t *testing.T) {
+	t.Parallel()
+	q, _ := newTestQueryBuilder(t)
+
+	impacts, err := q.OwnerImpact(9999, 5)
+	require.NoError(t, err)
+	assert.Nil(t, impacts)
+}
+
+func TestFileOwners_ReplaceClearsPrevious(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/repo/a.go", "go")
+	symID := insertSymbol(t, s, &fID, "A", "function", "public", nil)
+
+	require.NoError(t, s.ReplaceFileOwners(map[int64][]string{fID: {"@a", "@b"}}))
+	owners, err := q.SymbolOwners(symID)
+	require.NoError(t, err)
+	assert.Equal(t, []string{"@a", "@b"}, owners)
+
+	require.NoError(t, s.ReplaceFileOwners(map[int64][]string{fID: nil}))
+	owners, err = q.FileOwners(fID)
+	require.NoError(t, err)
+	assert.Empty(t, owners)
+}
@@ -2,6 +2,7 @@ package canopy
 
 import (
 	"fmt"
+	"path/filepath"
 	"sort"
 	"strings"
 )
@@ -31,7 +32,11 @@ type DependencyEdge struct {
 // PackageDependencyGraph returns the package-to-package dependency graph.
 // Aggregates file-level imports: for each import, determines the source file's
 // package and the import target's package, then counts edges between packages.
-// Packages are identified by "package"/"module"/"namespace" symbols.
+// Packages are identified by "package"/"module"/"namespace" symbols. Ruby
+// requires and PHP includes resolve through the file they load, and PHP
+// use statements through the namespace of the class they name. Without
+// test code (see WithTestCode), test files and their imports, and modules
+// that are test code, such as #[cfg(test)] mod tests, are left out.
 func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 	// 1. Load all files.
 	type fileInfo struct {
@@ -40,7 +45,11 @@ func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 		language  string
 		lineCount int
 	}
-	fileRows, err := q.store.DB().Query("SELECT id, path, language, COALESCE(line_count, 0) FROM files")
+	var testFiles string
+	if q.excludeTests {
+		testFiles = " WHERE NOT " + testFileSQL("f")
+	}
+	fileRows, err := q.store.DB().Query("SELECT f.id, f.path, f.language, COALESCE(f.line_count, 0) FROM files f" + testFiles)
 	if err != nil {
 		return nil, fmt.Errorf("package dependency graph: query files: %w", err)
 	}
@@ -59,9 +68,13 @@ func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 	}
 
 	// 2. Load all package/module/namespace symbols to build file_id -> package name map.
-	pkgRows, err := q.store.DB().Query(
-		"SELECT id, file_id, name FROM symbols WHERE kind IN ('package', 'module', 'namespace')",
-	)
+	pkgQuery := `SELECT s.id, s.file_id, s.name FROM symbols s
+		 LEFT JOIN files f ON f.id = s.file_id
+		 WHERE s.kind IN ('package', 'module', 'namespace')`
+	if clause := q.testCodeClause("s", "f"); clause != "" {
+		pkgQuery += " AND " + clause
+	}
+	pkgRows, err := q.store.DB().Query(pkgQuery)
 	if err != nil {
 		return nil, fmt.Errorf("package dependency graph: query package symbols: %w", err)
 	}
@@ -90,7 +103,7 @@ func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 		source string
 		kind   string
 	}
-	impRows, err := q.store.DB().Query("SELECT file_id, source, kind FROM imports")
+	impRows, err := q.store.DB().Query("SELECT i.file_id, i.source, i.kind FROM imports i JOIN files f ON f.id = i.file_id" + testFiles)
 	if err != nil {
 		return nil, fmt.Errorf("package dependency graph: query imports: %w", err)
 	}
@@ -144,6 +157,61 @@ func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 		}
 	}
 
+	// Ruby's require and PHP's include load a file by path, relative to the
+	// importing file or a load path, and PHP's use names a class by its
+	// namespace; these resolve per importing file, before the rules above.
+	pathToID := make(map[string]int64, len(files))
+	for id, f := range files {
+		pathToID[f.path] = id
+	}
+	pathImportPackage := func(from *fileInfo, imp importInfo) (string, bool) {
+		loaded := func(path string) (string, bool) {
+			if id, ok := pathToID[path]; ok {
+				pkg, ok := fileIDToPkg[id]
+				return pkg, ok
+			}
+			return "", false
+		}
+		source := imp.source
+		switch {
+		case from.language == "ruby" && (imp.kind == "relative" || imp.kind == "module"):
+			if filepath.Ext(source) == "" {
+				source += ".rb"
+			}
+		case from.language == "php" && imp.kind == "file":
+		case from.language == "php" && imp.kind == "type":
+			// use App\Models\User: the namespace itself, or the one it is in.
+			name := strings.TrimPrefix(source, `\`)
+			for name != "" {
+				if allPackages[name] {
+					return name, true
+				}
+				i := strings.LastIndex(name, `\`)
+				if i < 0 {
+					break
+				}
+				name = name[:i]
+			}
+			return "", false
+		default:
+			return "", false
+		}
+		if pkg, ok := loaded(filepath.Join(filepath.Dir(from.path), source)); ok {
+			return pkg, true
+		}
+		if imp.kind == "relative" {
+			return "", false
+		}
+		for _, f := range files {
+			if strings.HasSuffix(f.path, "/"+strings.TrimPrefix(source, "/")) {
+				if pkg, ok := fileIDToPkg[f.id]; ok {
+					return pkg, true
+				}
+			}
+		}
+		return "", false
+	}
+
 	// 5. Aggregate file-level imports to package-level edges.
 	type edgeKey struct {
 		from, to string
@@ -154,7 +222,13 @@ func (q *QueryBuilder) PackageDependencyGraph() (*DependencyGraph, error) {
 		if !ok {
 			continue // file has no package symbol, skip
 		}
-		toPkg, ok := importSourceToPkg[imp.source]
+		toPkg, ok := "", false
+		if from := files[imp.fileID]; from != nil {
+			toPkg, ok = pathImportPackage(from, imp)
+		}
+		if !ok {
+			toPkg, ok = importSourceToPkg[imp.source]
+		}
 		if !ok {
 			continue // external import, skip
 		}
@@ -309,3 +383,98 @@ func (q *QueryBuilder) CircularDependencies() ([][]string, error) {
 
 	return result, nil
 }
+
+// PackageMetrics holds coupling metrics for a single package, derived from
+// the package dependency graph. Self-edges are ignored.
+type PackageMetrics struct {
+	Package     string
+	Afferent    int     // Ca: number of distinct packages that depend on this one
+	Efferent    int     // Ce: number of distinct packages this one depends on
+	Instability float64 // Ce / (Ca + Ce); 0 for an isolated package
+	FanIn       int     // total file-level imports into this package
+	FanOut      int     // total file-level imports out of this package
+}
+
+// PackageCouplingMetrics returns afferent/efferent coupling, instability, and
+// fan-in/fan-out for every package, sorted by package name.
+func (q *QueryBuilder) PackageCouplingMetrics() ([]PackageMetrics, error) {
+	graph, err := q.PackageDependencyGraph()
+	if err != nil {
+		return nil, fmt.Errorf("package coupling metrics: %w", err)
+	}
+
+	byName := map[string]*PackageMetrics{}
+	metrics := make([]PackageMetrics, len(graph.Packages))
+	for i, pkg := range graph.Packages {
+		metrics[i].Package = pkg.Name
+		byName[pkg.Name] = &metrics[i]
+	}
+
+	// Edges are already aggregated per (from, to) pair, so each edge adds one
+	// to the distinct-package counts and ImportCount to the fan totals.
+	for _, edge := range graph.Edges {
+		if edge.FromPackage == edge.ToPackage {
+			continue
+		}
+		if from, ok := byName[edge.FromPackage]; ok {
+			from.Efferent++
+			from.FanOut += edge.ImportCount
+		}
+		if to, ok := byName[edge.ToPackage]; ok {
+			to.Afferent++
+			to.FanIn += edge.ImportCount
+		}
+	}
+
+	for i := range metrics {
+		m := &metrics[i]
+		if total := m.Afferent + m.Efferent; total > 0 {
+			m.Instability = float64(m.Efferent) / float64(total)
+		}
+	}
+
+	return metrics, nil
+}
+
+// MetricThresholds caps package coupling metrics. Nil fields are unchecked.
+type MetricThresholds struct {
+	MaxAfferent    *int
+	MaxEfferent    *int
+	MaxInstability *float64
+	MaxFanIn       *int
+	MaxFanOut      *int
+}
+
+// ThresholdViolation records a package metric that exceeded its configured limit.
+type ThresholdViolation struct {
+	Package string
+	Metric  string // "afferent", "efferent", "instability", "fan_in", "fan_out"
+	Value   float64
+	Limit   float64
+}
+
+// CheckPackageThresholds returns every metric in metrics that exceeds its
+// limit in t, in package order. Returns an empty slice when all are within
+// limits.
+func CheckPackageThresholds(metrics []PackageMetrics, t MetricThresholds) []ThresholdViolation {
+	violations := []ThresholdViolation{}
+	checkInt := func(pkg, metric string, value int, limit *int) {
+		if limit != nil && value > *limit {
+			violations = append(violations, ThresholdViolation{
+				Package: pkg, Metric: metric, Value: float64(value), Limit: float64(*limit),
+			})
+		}
+	}
+	for _, m := range metrics {
+		checkInt(m.Package, "afferent", m.Afferent, t.MaxAfferent)
+		checkInt(m.Package, "efferent", m.Efferent, t.MaxEfferent)
+		if t.MaxInstability != nil && m.Instability > *t.MaxInstability {
+			violations = append(violations, ThresholdViolation{
+				Package: m.Package, Metric: "instability", Value: m.Instability, Limit: *t.MaxInstability,
+			})
+		}
+		checkInt(m.Package, "fan_in", m.FanIn, t.MaxFanIn)
+		checkInt(m.Package, "fan_out", m.FanOut, t.MaxFanOut)
+	}
+	return violations
+}
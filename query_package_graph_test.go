@@ -114,6 +114,49 @@ func TestPackageDependencyGraph_ExcludesExternalImports(t *testing.T) {
 	assert.Empty(t, graph.Edges)
 }
 
+func TestPackageDependencyGraph_RubyAndPHPImports(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+
+	orders := insertFile(t, s, "/shop/app/orders/service.rb", "ruby")
+	billing := insertFile(t, s, "/shop/lib/billing/invoice.rb", "ruby")
+	models := insertFile(t, s, "/legacy/src/Models/User.php", "php")
+	controllers := insertFile(t, s, "/legacy/src/Http/UserController.php", "php")
+	helpers := insertFile(t, s, "/legacy/src/Http/helpers.php", "php")
+
+	for fileID, name := range map[int64]string{orders: "Orders", billing: "Billing"} {
+		_, err := s.InsertSymbol(&store.Symbol{FileID: &fileID, Name: name, Kind: "module"})
+		require.NoError(t, err)
+	}
+	for fileID, name := range map[int64]string{models: `App\Models`, controllers: `App\Http`, helpers: `App\Http\Support`} {
+		_, err := s.InsertSymbol(&store.Symbol{FileID: &fileID, Name: name, Kind: "namespace"})
+		require.NoError(t, err)
+	}
+	for _, imp := range []*store.Import{
+		{FileID: orders, Source: "billing/invoice", Kind: "module"},             // require, from a load path
+		{FileID: billing, Source: "../../app/orders/service", Kind: "relative"}, // require_relative
+		{FileID: controllers, Source: `App\Models\User`, Kind: "type"},          // use
+		{FileID: controllers, Source: "helpers.php", Kind: "file"},              // require_once
+		{FileID: orders, Source: "json", Kind: "module"},                        // a gem
+	} {
+		_, err := s.InsertImport(imp)
+		require.NoError(t, err)
+	}
+
+	graph, err := q.PackageDependencyGraph()
+	require.NoError(t, err)
+	var edges []string
+	for _, e := range graph.Edges {
+		edges = append(edges, e.FromPackage+" -> "+e.ToPackage)
+	}
+	assert.Equal(t, []string{
+		`App\Http -> App\Http\Support`,
+		`App\Http -> App\Models`,
+		"Billing -> Orders",
+		"Orders -> Billing",
+	}, edges)
+}
+
 func TestPackageDependencyGraph_FileCountAndLineCount(t *testing.T) {
 	t.Parallel()
 	q, s := newTestQueryBuilder(t)
@@ -205,6 +248,45 @@ func TestCircularDependencies_SimpleABCycle(t *testing.T) {
 	assert.Contains(t, cycle, "b")
 }
 
+func TestCircularDependencies_WithoutTestCode(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+
+	fileA := insertFile(t, s, "/src/a/main.go", "go")
+	fileB := insertFile(t, s, "/src/b/main.go", "go")
+	fileBTest := insertFile(t, s, "/src/b/main_test.go", "go")
+
+	_, err := s.InsertSymbol(&store.Symbol{FileID: &fileA, Name: "a", Kind: "package"})
+	require.NoError(t, err)
+	_, err = s.InsertSymbol(&store.Symbol{FileID: &fileB, Name: "b", Kind: "package"})
+	require.NoError(t, err)
+	_, err = s.InsertSymbol(&store.Symbol{FileID: &fileBTest, Name: "b", Kind: "package"})
+	require.NoError(t, err)
+
+	// a -> b, and only b's test file imports a.
+	_, err = s.InsertImport(&store.Import{FileID: fileA, Source: "b", Kind: "import"})
+	require.NoError(t, err)
+	_, err = s.InsertImport(&store.Import{FileID: fileBTest, Source: "a", Kind: "import"})
+	require.NoError(t, err)
+
+	cycles, err := q.CircularDependencies()
+	require.NoError(t, err)
+	assert.Len(t, cycles, 1, "test code is included by default")
+
+	prod := q.WithTestCode(false)
+	cycles, err = prod.CircularDependencies()
+	require.NoError(t, err)
+	assert.Empty(t, cycles, "the import from the test file is dropped")
+
+	graph, err := prod.PackageDependencyGraph()
+	require.NoError(t, err)
+	for _, p := range graph.Packages {
+		if p.Name == "b" {
+			assert.Equal(t, 1, p.FileCount, "the test file is not counted")
+		}
+	}
+}
+
 func TestCircularDependencies_LongerABCACycle(t *testing.T) {
 	t.Parallel()
 	q, s := newTestQueryBuilder(t)
@@ -284,3 +366,75 @@ func insertFileWithLines(t *testing.T, s *store.Store, path, lang string, lineCo
 	require.NoError(t, err)
 	return id
 }
+
+// =============================================================================
+// PackageCouplingMetrics / CheckPackageThresholds
+// =============================================================================
+
+func TestPackageCouplingMetrics_ComputesCouplingAndFan(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+
+	// a -> b (2 files), a -> c, b -> c, c -> c (self, ignored)
+	fileA1 := insertFile(t, s, "/src/a/one.go", "go")
+	fileA2 := insertFile(t, s, "/src/a/two.go", "go")
+	fileB := insertFile(t, s, "/src/b/main.go", "go")
+	fileC := insertFile(t, s, "/src/c/main.go", "go")
+	for fid, name := range map[int64]string{fileA1: "a", fileA2: "a", fileB: "b", fileC: "c"} {
+		_, err := s.InsertSymbol(&store.Symbol{FileID: &fid, Name: name, Kind: "package"})
+		require.NoError(t, err)
+	}
+	for _, imp := range []struct {
+		fileID int64
+		source string
+	}{
+		{fileA1, "b"}, {fileA2, "b"}, {fileA1, "c"}, {fileB, "c"}, {fileC, "c"},
+	} {
+		_, err := s.InsertImport(&store.Import{FileID: imp.fileID, Source: imp.source, Kind: "import"})
+		require.NoError(t, err)
+	}
+
+	metrics, err := q.PackageCouplingMetrics()
+	require.NoError(t, err)
+	require.Len(t, metrics, 3)
+
+	a, b, c := metrics[0], metrics[1], metrics[2]
+	assert.Equal(t, "a", a.Package)
+	assert.Equal(t, 0, a.Afferent)
+	assert.Equal(t, 2, a.Efferent)
+	assert.Equal(t, 3, a.FanOut)
+	assert.InDelta(t, 1.0, a.Instability, 0.001)
+
+	assert.Equal(t, "b", b.Package)
+	assert.Equal(t, 1, b.Afferent)
+	assert.Equal(t, 1, b.Efferent)
+	assert.Equal(t, 2, b.FanIn)
+	assert.InDelta(t, 0.5, b.Instability, 0.001)
+
+	assert.Equal(t, "c", c.Package)
+	assert.Equal(t, 2, c.Afferent)
+	assert.Equal(t, 0, c.Efferent)
+	assert.Equal(t, 2, c.FanIn)
+	assert.Equal(t, 0, c.FanOut)
+	assert.InDelta(t, 0.0, c.Instability, 0.001)
+}
+
+func TestCheckPackageThresholds(t *testing.T) {
+	t.Parallel()
+	metrics := []PackageMetrics{
+		{Package: "a", Afferent: 0, Efferent: 5, Instability: 1.0, FanOut: 12},
+		{Package: "b", Afferent: 3, Efferent: 1, Instability: 0.25, FanIn: 4, FanOut: 1},
+	}
+
+	maxEff, maxFanOut, maxInst := 4, 20, 0.9
+	violations := CheckPackageThresholds(metrics, MetricThresholds{
+		MaxEfferent:    &maxEff,
+		MaxFanOut:      &maxFanOut,
+		MaxInstability: &maxInst,
+	})
+	require.Len(t, violations, 2)
+	assert.Equal(t, ThresholdViolation{Package: "a", Metric: "efferent", Value: 5, Limit: 4}, violations[0])
+	assert.Equal(t, "instability", violations[1].Metric)
+
+	assert.Empty(t, CheckPackageThresholds(metrics, MetricThresholds{}))
+}
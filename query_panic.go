@@ -0,0 +1,210 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// Kinds of flagged sites that can panic, recorded per function at extraction.
+const (
+	SiteKindPanic  = "panic"  // a panicking macro: panic!, unreachable!, todo!, unimplemented!, assert!...
+	SiteKindUnwrap = "unwrap" // an unwrap() or expect() call
+	SiteKindIndex  = "index"  // indexing, which panics out of bounds or on a missing key
+)
+
+// panicSiteKinds are the flagged site kinds PanicsReachableFrom reports.
+var panicSiteKinds = []string{SiteKindPanic, SiteKindUnwrap, SiteKindIndex}
+
+// PanicSite is a construct that can panic, reached from a queried function.
+type PanicSite struct {
+	Kind     string          // one of the SiteKind constants
+	Name     string          // the construct as written: "panic!", "expect", "items[i]"
+	Location Location        // where the construct is
+	Function SymbolResult    // the function containing the site
+	Path     []CallGraphEdge // shortest calls from the queried function to Function; empty when they are the same
+}
+
+// PanicsReachableFrom reports the sites that can panic in a function or in the
+// functions it calls within maxDepth calls, each with the shortest call path
+// that reaches it, ordered by path length then location. kinds narrows the
+// sites to the given SiteKind constants; none reports every kind. Spawned
+// work (CallKindSpawn) is not followed: a panic there ends the spawned task
+// or thread, not the caller. Same depth rules as TransitiveCallees; returns
+// nil, nil if symbolID does not exist.
+func (q *QueryBuilder) PanicsReachableFrom(symbolID int64, maxDepth int, kinds []string) ([]PanicSite, error) {
+	if maxDepth < 0 {
+		return nil, fmt.Errorf("panics reachable from: maxDepth must be non-negative, got %d", maxDepth)
+	}
+	if maxDepth > 100 {
+		maxDepth = 100
+	}
+	if len(kinds) == 0 {
+		kinds = panicSiteKinds
+	}
+	for _, k := range kinds {
+		if k != SiteKindPanic && k != SiteKindUnwrap && k != SiteKindIndex {
+			return nil, fmt.Errorf("panics reachable from: unknown site kind %q (want %s)", k, strings.Join(panicSiteKinds, ", "))
+		}
+	}
+
+	rootSym, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("panics reachable from: %w", err)
+	}
+	if rootSym == nil {
+		return nil, nil
+	}
+
+	data, err := q.buildCallGraph()
+	if err != nil {
+		return nil, fmt.Errorf("panics reachable from: %w", err)
+	}
+	via := shortestCallPaths(data, symbolID, maxDepth, false)
+
+	ids := make([]int64, 0, len(via))
+	for id := range via {
+		ids = append(ids, id)
+	}
+	sites, err := q.flaggedSites(ids, kinds)
+	if err != nil {
+		return nil, fmt.Errorf("panics reachable from: %w", err)
+	}
+	result := []PanicSite{}
+	if len(sites) == 0 {
+		return result, nil
+	}
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("panics reachable from: load symbols: %w", err)
+	}
+
+	for _, site := range sites {
+		fn, ok := symbols[site.symbolID]
+		if !ok {
+			continue
+		}
+		edges := callPathTo(via, site.symbolID)
+		path := make([]CallGraphEdge, len(edges))
+		for i, e := range edges {
+			path[i] = resolveCallGraphEdge(e, data.filePaths)
+		}
+		result = append(result, PanicSite{
+			Kind:     site.kind,
+			Name:     site.name,
+			Location: site.location,
+			Function: *fn,
+			Path:     path,
+		})
+	}
+	sort.SliceStable(result, func(i, j int) bool {
+		a, b := result[i], result[j]
+		if len(a.Path) != len(b.Path) {
+			return len(a.Path) < len(b.Path)
+		}
+		if a.Location.File != b.Location.File {
+			return a.Location.File < b.Location.File
+		}
+		if a.Location.StartLine != b.Location.StartLine {
+			return a.Location.StartLine < b.Location.StartLine
+		}
+		return a.Location.StartCol < b.Location.StartCol
+	})
+	return result, nil
+}
+
+// shortestCallPaths walks callees breadth-first from root up to maxDepth
+// calls, following spawn edges only when followSpawn is set, and returns each
+// reached symbol (root included) mapped to the edge it was first reached by
+// (nil for root).
+func shortestCallPaths(data *callGraphData, root int64, maxDepth int, followSpawn bool) map[int64]*CallEdge {
+	via := map[int64]*CallEdge{root: nil}
+	depth := map[int64]int{root: 0}
+	queue := []int64{root}
+	for len(queue) > 0 {
+		current := queue[0]
+		queue = queue[1:]
+		if depth[current] >= maxDepth {
+			continue
+		}
+		for _, edge := range data.edgesByCaller[current] {
+			if edge.Kind == CallKindSpawn && !followSpawn {
+				continue
+			}
+			if _, seen := via[edge.CalleeSymbolID]; seen {
+				continue
+			}
+			via[edge.CalleeSymbolID] = edge
+			depth[edge.CalleeSymbolID] = depth[current] + 1
+			queue = append(queue, edge.CalleeSymbolID)
+		}
+	}
+	return via
+}
+
+// callPathTo returns the edges from the root of a shortestCallPaths walk to
+// id, in call order.
+func callPathTo(via map[int64]*CallEdge, id int64) []*CallEdge {
+	var path []*CallEdge
+	for e := via[id]; e != nil; e = via[e.CallerSymbolID] {
+		path = append(path, e)
+	}
+	for i, j := 0, len(path)-1; i < j; i, j = i+1, j-1 {
+		path[i], path[j] = path[j], path[i]
+	}
+	return path
+}
+
+// flaggedSite is a flagged_sites row with its location resolved.
+type flaggedSite struct {
+	symbolID int64
+	kind     string
+	name     string
+	location Location
+}
+
+// flaggedSites returns the flagged sites of the given kinds inside the given
+// functions; nil symbolIDs selects every function. Sites in test code are
+// left out unless q includes it.
+func (q *QueryBuilder) flaggedSites(symbolIDs []int64, kinds []string) ([]flaggedSite, error) {
+	if (symbolIDs != nil && len(symbolIDs) == 0) || len(kinds) == 0 {
+		return nil, nil
+	}
+	where := "fs.kind IN (" + strings.Repeat("?,", len(kinds)-1) + "?)"
+	args := make([]any, 0, len(symbolIDs)+len(kinds))
+	for _, k := range kinds {
+		args = append(args, k)
+	}
+	if symbolIDs != nil {
+		where += " AND fs.symbol_id IN (" + strings.Repeat("?,", len(symbolIDs)-1) + "?)"
+		for _, id := range symbolIDs {
+			args = append(args, id)
+		}
+	}
+	if clause := q.testCodeClause("fs", "f"); clause != "" {
+		where += " AND " + clause
+	}
+	rows, err := q.store.DB().Query(
+		`SELECT fs.symbol_id, fs.kind, fs.name, f.path,
+			fs.start_line, fs.start_col, fs.end_line, fs.end_col
+		 FROM flagged_sites fs
+		 JOIN files f ON f.id = fs.file_id
+		 WHERE `+where,
+		args...,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("flagged sites: %w", err)
+	}
+	defer rows.Close()
+
+	var sites []flaggedSite
+	for rows.Next() {
+		var s flaggedSite
+		if err := rows.Scan(&s.symbolID, &s.kind, &s.name, &s.location.File,
+			&s.location.StartLine, &s.location.StartCol, &s.location.EndLine, &s.location.EndCol); err != nil {
+			return nil, fmt.Errorf("flagged sites: scan: %w", err)
+		}
+		sites = append(sites, s)
+	}
+	return sites, rows.Err()
+}
@@ -0,0 +1,105 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestPanicsReachableFrom(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	path := filepath.Join(t.TempDir(), "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`fn first(items: &[u32]) -> u32 {
+    items[0]
+}
+
+fn to_num(s: &str) -> u32 {
+    s.parse().unwrap()
+}
+
+fn checked(n: u32) -> u32 {
+    if n == 0 {
+        panic!("zero");
+    }
+    n
+}
+
+fn helper() {
+    Some(1).expect("value");
+}
+
+pub fn entry(items: &[u32]) -> u32 {
+    thread::spawn(helper);
+    checked(first(items) + to_num("1"))
+}
+
+pub fn safe(n: u32) -> u32 {
+    n + 1
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	ids := map[string]int64{}
+	for _, s := range syms {
+		ids[s.Name] = s.ID
+	}
+
+	q := e.Query()
+	sites, err := q.PanicsReachableFrom(ids["entry"], 10, nil)
+	require.NoError(t, err)
+
+	type found struct {
+		kind, name, fn string
+		line, depth    int
+	}
+	var got []found
+	for _, s := range sites {
+		got = append(got, found{s.Kind, s.Name, s.Function.Name, s.Location.StartLine, len(s.Path)})
+	}
+	assert.Equal(t, []found{
+		{SiteKindIndex, "items[0]", "first", 1, 1},
+		{SiteKindUnwrap, "unwrap", "to_num", 5, 1},
+		{SiteKindPanic, "panic!", "checked", 10, 1},
+	}, got, "the spawned helper's expect is not reported")
+	require.Len(t, sites[0].Path, 1)
+	assert.Equal(t, ids["entry"], sites[0].Path[0].CallerID)
+	assert.Equal(t, ids["first"], sites[0].Path[0].CalleeID)
+
+	own, err := q.PanicsReachableFrom(ids["helper"], 10, nil)
+	require.NoError(t, err)
+	require.Len(t, own, 1)
+	assert.Equal(t, "expect", own[0].Name)
+	assert.Empty(t, own[0].Path, "the site is in the queried function itself")
+
+	unwraps, err := q.PanicsReachableFrom(ids["entry"], 10, []string{SiteKindUnwrap})
+	require.NoError(t, err)
+	require.Len(t, unwraps, 1)
+	assert.Equal(t, "to_num", unwraps[0].Function.Name)
+
+	none, err := q.PanicsReachableFrom(ids["entry"], 0, nil)
+	require.NoError(t, err)
+	assert.Empty(t, none, "entry itself cannot panic")
+
+	clean, err := q.PanicsReachableFrom(ids["safe"], 10, nil)
+	require.NoError(t, err)
+	assert.Empty(t, clean)
+
+	_, err = q.PanicsReachableFrom(ids["entry"], 10, []string{"overflow"})
+	assert.ErrorContains(t, err, `unknown site kind "overflow"`)
+
+	missing, err := q.PanicsReachableFrom(999999, 10, nil)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
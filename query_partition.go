@@ -0,0 +1,176 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// Reasons code is test code, as reported by TestRegions.
+const (
+	TestRegionFile      = "test_file"      // a file under a tests directory, or a Go _test.go file
+	TestRegionCfgTest   = "cfg_test"       // an item under #[cfg(test)], such as mod tests
+	TestRegionAttribute = "test_attribute" // a function marked #[test], #[tokio::test], and the like
+)
+
+// testAttributeSQL matches the annotations (aliased a) that make the item
+// they are on test code.
+const testAttributeSQL = `(a.name = 'test' OR a.name LIKE '%::test' OR (a.name = 'cfg' AND cfg_is_test(COALESCE(a.arguments, ''))))`
+
+// TestRegion is a part of the index that only tests use: a whole test
+// file, or an item of a production file that is compiled for tests only.
+type TestRegion struct {
+	Location
+	Reason string        // one of the TestRegion* reasons
+	Symbol *SymbolResult // the item; nil for a test file
+}
+
+// WithTestCode returns a copy of q that includes test code, or with include
+// false leaves it out: symbol listings (Symbols, SearchSymbols,
+// UnusedSymbols, FunctionComplexity, HotSymbols, StaleSymbols, Coverage)
+// skip test code, references from test code are not counted or listed, so
+// a function only tests call is unused, and call graph queries drop edges
+// to or from test code. Test code is what TestRegions reports. Queries about
+// tests themselves, such as TestsCovering, always see it.
+func (q *QueryBuilder) WithTestCode(include bool) *QueryBuilder {
+	c := *q
+	c.excludeTests = !include
+	return &c
+}
+
+// testFileSQL is the SQL condition that the file aliased file is a test
+// file. Paths are matched below the indexed root, so a checkout under a
+// tests directory is not all test code.
+func testFileSQL(file string) string {
+	rel := "('/' || CASE WHEN COALESCE(" + file + ".root, '') != '' AND substr(" + file + ".path, 1, length(" + file + ".root)) = " + file + ".root" +
+		" THEN substr(" + file + ".path, length(" + file + ".root) + 1) ELSE COALESCE(" + file + ".path, '') END)"
+	return "(" + rel + " GLOB '*/tests/*' OR " + rel + " GLOB '*_test.go')"
+}
+
+// testCodeSQL is the SQL condition that the symbol or reference aliased
+// node, with its file aliased file, is test code: it is in a test file, or
+// within an item marked as a test or #[cfg(test)] (the item itself
+// included).
+func testCodeSQL(node, file string) string {
+	return "(" + testFileSQL(file) + ` OR EXISTS (SELECT 1 FROM symbols t JOIN annotations a ON a.target_symbol_id = t.id
+		WHERE t.file_id = ` + node + `.file_id AND ` + testAttributeSQL + `
+		AND (t.start_line < ` + node + `.start_line OR (t.start_line = ` + node + `.start_line AND t.start_col <= ` + node + `.start_col))
+		AND (t.end_line > ` + node + `.end_line OR (t.end_line = ` + node + `.end_line AND t.end_col >= ` + node + `.end_col))))`
+}
+
+// testCodeSymbolIDs returns the IDs of the symbols that are test code.
+func (q *QueryBuilder) testCodeSymbolIDs() (map[int64]bool, error) {
+	rows, err := q.store.DB().Query(`SELECT s.id FROM symbols s LEFT JOIN files f ON f.id = s.file_id WHERE ` + testCodeSQL("s", "f"))
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	ids := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, err
+		}
+		ids[id] = true
+	}
+	return ids, rows.Err()
+}
+
+// TestRegions partitions the files under pathPrefix ("" for all files)
+// into production and test code by listing the test code: test files,
+// which are files under a tests directory and Go _test.go files, and in
+// other files the outermost items marked #[cfg(test)] or as tests.
+// Everything else is production code. Regions are ordered by file and
+// position.
+func (q *QueryBuilder) TestRegions(pathPrefix string) ([]TestRegion, error) {
+	regions := []TestRegion{}
+	rows, err := q.store.DB().Query(
+		`SELECT f.path, f.line_count FROM files f
+		 WHERE f.path LIKE ? ESCAPE '\' AND `+testFileSQL("f")+`
+		 ORDER BY f.path`,
+		escapeLike(pathPrefix)+"%",
+	)
+	if err != nil {
+		return nil, fmt.Errorf("test regions: %w", err)
+	}
+	for rows.Next() {
+		var r TestRegion
+		var lines int
+		if err := rows.Scan(&r.File, &lines); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("test regions: %w", err)
+		}
+		r.EndLine = lines
+		r.Reason = TestRegionFile
+		regions = append(regions, r)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("test regions: %w", err)
+	}
+
+	// Items marked as tests in other files, unless an enclosing item is.
+	rows, err = q.store.DB().Query(
+		`SELECT DISTINCT s.id, CASE WHEN a.name = 'cfg' THEN 1 ELSE 0 END FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 JOIN annotations a ON a.target_symbol_id = s.id
+		 WHERE f.path LIKE ? ESCAPE '\' AND `+testAttributeSQL+` AND NOT `+testFileSQL("f")+`
+		 AND NOT EXISTS (SELECT 1 FROM symbols t JOIN annotations a ON a.target_symbol_id = t.id
+			WHERE t.file_id = s.file_id AND t.id != s.id AND `+testAttributeSQL+`
+			AND (t.start_line < s.start_line OR (t.start_line = s.start_line AND t.start_col <= s.start_col))
+			AND (t.end_line > s.end_line OR (t.end_line = s.end_line AND t.end_col >= s.end_col)))`,
+		escapeLike(pathPrefix)+"%",
+	)
+	if err != nil {
+		return nil, fmt.Errorf("test regions: %w", err)
+	}
+	var ids []int64
+	cfg := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		var isCfg bool
+		if err := rows.Scan(&id, &isCfg); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("test regions: %w", err)
+		}
+		if _, seen := cfg[id]; !seen {
+			ids = append(ids, id)
+		}
+		cfg[id] = cfg[id] || isCfg
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("test regions: %w", err)
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("test regions: %w", err)
+	}
+	var items []SymbolResult
+	for _, id := range ids {
+		if s, ok := syms[id]; ok {
+			items = append(items, *s)
+		}
+	}
+	sortSymbolResultsByPosition(items)
+	for i := range items {
+		s := &items[i]
+		r := TestRegion{Location: symbolLocationOf(s), Reason: TestRegionAttribute, Symbol: s}
+		if cfg[s.ID] {
+			r.Reason = TestRegionCfgTest
+		}
+		regions = append(regions, r)
+	}
+	sort.SliceStable(regions, func(i, j int) bool { return regions[i].File < regions[j].File })
+	return regions, nil
+}
+
+// testCodeClause returns the WHERE condition leaving out test code for the
+// symbol or reference aliased node in the file aliased file, or "" when q
+// includes test code.
+func (q *QueryBuilder) testCodeClause(node, file string) string {
+	if !q.excludeTests {
+		return ""
+	}
+	return "NOT " + strings.TrimSpace(testCodeSQL(node, file))
+}
@@ -0,0 +1,110 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestTestRegions(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("src/lib.rs", `pub fn api() {
+    helper();
+}
+
+fn helper() {}
+
+pub fn only_tested() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() {}
+
+    #[test]
+    fn unit() {
+        fixture();
+        only_tested();
+        helper();
+    }
+}
+
+#[tokio::test]
+async fn async_unit() {}
+`)
+	write("tests/it.rs", "#[test]\nfn works() {}\n")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+	q := e.Query()
+
+	regions, err := q.TestRegions("")
+	require.NoError(t, err)
+	type region struct{ file, reason, item string }
+	var got []region
+	for _, r := range regions {
+		rel, err := filepath.Rel(root, r.File)
+		require.NoError(t, err)
+		item := ""
+		if r.Symbol != nil {
+			item = r.Symbol.Name
+		}
+		got = append(got, region{filepath.ToSlash(rel), r.Reason, item})
+	}
+	assert.Equal(t, []region{
+		{"src/lib.rs", TestRegionCfgTest, "tests"},
+		{"src/lib.rs", TestRegionAttribute, "async_unit"},
+		{"tests/it.rs", TestRegionFile, ""},
+	}, got, "items nested in mod tests are covered by it")
+
+	names := func(syms []SymbolResult) []string {
+		var out []string
+		for _, s := range syms {
+			out = append(out, s.Name)
+		}
+		return out
+	}
+	fns := SymbolFilter{Kinds: []string{"function"}}
+	all, err := q.Symbols(fns, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Contains(t, names(all.Items), "unit", "test code is included by default")
+
+	prod := q.WithTestCode(false)
+	syms, err := prod.Symbols(fns, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.ElementsMatch(t, []string{"api", "helper", "only_tested"}, names(syms.Items))
+
+	unused, err := q.UnusedSymbols(fns, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.NotContains(t, names(unused.Items), "only_tested")
+	unused, err = prod.UnusedSymbols(fns, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Contains(t, names(unused.Items), "only_tested", "only tests call it")
+	assert.NotContains(t, names(unused.Items), "helper")
+
+	var helperID int64
+	for _, s := range all.Items {
+		if s.Name == "helper" {
+			helperID = s.ID
+		}
+	}
+	require.NotZero(t, helperID)
+	callers, err := q.Callers(helperID)
+	require.NoError(t, err)
+	assert.Len(t, callers, 2)
+	callers, err = prod.Callers(helperID)
+	require.NoError(t, err)
+	assert.Len(t, callers, 1, "the call from the unit test is dropped")
+}
@@ -0,0 +1,146 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+)
+
+// Reference kinds: the syntactic context a reference occurs in, as recorded
+// by the extraction scripts. Languages classify as finely as their grammar
+// allows; Rust distinguishes all of these, recording reads only in const
+// and static initializers.
+const (
+	RefKindCall        = "call"            // function or method call
+	RefKindType        = "type_annotation" // type usage in a signature, field, or expression
+	RefKindTraitBound  = "trait_bound"     // T: Trait, where clauses, impl Trait
+	RefKindPattern     = "pattern"         // enum variant or struct in a pattern
+	RefKindFieldAccess = "field_access"    // obj.field
+	RefKindImport      = "import"          // name in a use/import statement
+	RefKindRead        = "read"            // plain identifier read
+	RefKindMacro       = "macro"           // macro invocation, as in println!
+	RefKindSQL         = "sql"             // table or column named in an embedded SQL string
+	RefKindFFI         = "ffi"             // FFI declaration naming its definition in another language
+)
+
+// ReferenceSite is a location that references a symbol, with its kind.
+type ReferenceSite struct {
+	Location
+	ReferenceID int64  // the reference's row ID, for ExplainReference
+	Kind        string // one of the RefKind constants, or "" if unclassified
+	Confidence  string // one of the Confidence constants
+}
+
+// ReferenceFilter narrows reference queries. The zero value matches all.
+type ReferenceFilter struct {
+	Kinds []string // match any of these kinds
+}
+
+// referenceSitesFrom returns the FROM/WHERE clause selecting the resolved
+// references to symbolID that pass filter and q's minimum confidence, and
+// are outside test code when q excludes it, with its args. Tables are
+// aliased rr (resolved_references), r (references_), and f (files).
+func (q *QueryBuilder) referenceSitesFrom(symbolID int64, filter ReferenceFilter) (string, []any) {
+	from := `FROM resolved_references rr
+	 JOIN references_ r ON r.id = rr.reference_id
+	 JOIN files f ON f.id = r.file_id
+	 WHERE rr.target_symbol_id = ?`
+	args := []any{symbolID}
+	if q.minConfidence > 0 {
+		from += " AND COALESCE(rr.confidence, 1.0) >= ?"
+		args = append(args, q.minConfidence)
+	}
+	if len(filter.Kinds) > 0 {
+		from += " AND COALESCE(r.context, '') IN (" + strings.Repeat("?,", len(filter.Kinds)-1) + "?)"
+		for _, k := range filter.Kinds {
+			args = append(args, k)
+		}
+	}
+	if clause := q.testCodeClause("r", "f"); clause != "" {
+		from += " AND " + clause
+	}
+	return from, args
+}
+
+// referenceSiteCols are the columns scanned by scanReferenceSite.
+const referenceSiteCols = `f.path, r.start_line, r.start_col, r.end_line, r.end_col,
+	r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col, COALESCE(r.context, ''),
+	COALESCE(rr.confidence, 1.0), r.id`
+
+// referenceSiteOrder lists references in the order they were extracted,
+// which is fixed by the files' order, rather than the order concurrent
+// resolution scripts bound them in.
+const referenceSiteOrder = "ORDER BY rr.reference_id, rr.id"
+
+func scanReferenceSite(row scanner) (ReferenceSite, error) {
+	var site ReferenceSite
+	var confidence float64
+	err := row.Scan(
+		&site.File, &site.StartLine, &site.StartCol, &site.EndLine, &site.EndCol,
+		&site.StartByte, &site.EndByte, &site.UTF16StartCol, &site.UTF16EndCol, &site.Kind,
+		&confidence, &site.ReferenceID,
+	)
+	site.Confidence = ConfidenceLevel(confidence)
+	return site, err
+}
+
+// ReferencesToPaged returns one page of the references to the given symbol
+// that pass filter, in the same order as ReferencesTo, with the total count.
+// Paging happens in SQL, so only the requested page is loaded.
+func (q *QueryBuilder) ReferencesToPaged(symbolID int64, filter ReferenceFilter, page Pagination) (*PagedResult[ReferenceSite], error) {
+	page = page.normalize()
+	from, args := q.referenceSitesFrom(symbolID, filter)
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+from, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("references to: count: %w", err)
+	}
+
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+	rows, err := q.store.DB().Query(
+		"SELECT "+referenceSiteCols+" "+from+" "+referenceSiteOrder+" LIMIT ? OFFSET ?", dataArgs...,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("references to: %w", err)
+	}
+	defer rows.Close()
+
+	items := []ReferenceSite{}
+	for rows.Next() {
+		site, err := scanReferenceSite(rows)
+		if err != nil {
+			return nil, fmt.Errorf("references to: scan: %w", err)
+		}
+		items = append(items, site)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("references to: %w", err)
+	}
+	return &PagedResult[ReferenceSite]{Items: items, TotalCount: totalCount}, nil
+}
+
+// EachReferenceTo calls fn with each reference to the given symbol that
+// passes filter, in the same order as ReferencesTo, without loading them all
+// into memory. Iteration stops at the first error returned by fn, which is
+// returned unwrapped.
+func (q *QueryBuilder) EachReferenceTo(symbolID int64, filter ReferenceFilter, fn func(ReferenceSite) error) error {
+	from, args := q.referenceSitesFrom(symbolID, filter)
+	rows, err := q.store.DB().Query("SELECT "+referenceSiteCols+" "+from+" "+referenceSiteOrder, args...)
+	if err != nil {
+		return fmt.Errorf("references to: %w", err)
+	}
+	defer rows.Close()
+
+	for rows.Next() {
+		site, err := scanReferenceSite(rows)
+		if err != nil {
+			return fmt.Errorf("references to: scan: %w", err)
+		}
+		if err := fn(site); err != nil {
+			return err
+		}
+	}
+	if err := rows.Err(); err != nil {
+		return fmt.Errorf("references to: %w", err)
+	}
+	return nil
+}
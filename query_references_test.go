@@ -0,0 +1,150 @@
+package canopy
+
+import (
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// insertRefOfKind inserts a reference with the given context at line and
+// resolves it to target.
+func insertRefOfKind(t *testing.T, s *store.Store, fileID, target int64, kind string, line int) {
+	t.Helper()
+	refID, err := s.InsertReference(&store.Reference{
+		FileID: fileID, Name: "ref", Context: kind,
+		StartLine: line, StartCol: 0, EndLine: line, EndCol: 3,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertResolvedReference(&store.ResolvedReference{
+		ReferenceID: refID, TargetSymbolID: target, Confidence: 1.0, ResolutionKind: "direct",
+	})
+	require.NoError(t, err)
+}
+
+func TestReferencesToPaged_FiltersByKind(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/test.rs", "rust")
+	target := insertSymbol(t, s, &fID, "Circle", "struct", "public", nil)
+	insertRefOfKind(t, s, fID, target, RefKindImport, 0)
+	insertRefOfKind(t, s, fID, target, RefKindType, 3)
+	insertRefOfKind(t, s, fID, target, RefKindCall, 5)
+	insertRefOfKind(t, s, fID, target, RefKindCall, 7)
+	insertRefOfKind(t, s, fID, target, RefKindPattern, 9)
+
+	all, err := q.ReferencesToPaged(target, ReferenceFilter{}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 5, all.TotalCount)
+	assert.Equal(t, RefKindImport, all.Items[0].Kind)
+
+	calls, err := q.ReferencesToPaged(target, ReferenceFilter{Kinds: []string{RefKindCall}}, Pagination{})
+	require.NoError(t, err)
+	assert.Equal(t, 2, calls.TotalCount)
+	for _, site := range calls.Items {
+		assert.Equal(t, RefKindCall, site.Kind)
+	}
+
+	page, err := q.ReferencesToPaged(target, ReferenceFilter{Kinds: []string{RefKindCall, RefKindPattern}}, Pagination{Offset: 1, Limit: intP(1)})
+	require.NoError(t, err)
+	assert.Equal(t, 3, page.TotalCount)
+	require.Len(t, page.Items, 1)
+	assert.Equal(t, 7, page.Items[0].StartLine)
+
+	page, err = q.ReferencesToPaged(target, ReferenceFilter{}, Pagination{Limit: intP(0)})
+	require.NoError(t, err)
+	assert.Equal(t, 5, page.TotalCount)
+	assert.Empty(t, page.Items)
+}
+
+func TestEachReferenceTo_StopsOnError(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/test.go", "go")
+	target := insertSymbol(t, s, &fID, "Bar", "function", "public", nil)
+	insertRefOfKind(t, s, fID, target, RefKindCall, 2)
+	insertRefOfKind(t, s, fID, target, RefKindCall, 4)
+
+	var streamed []ReferenceSite
+	stop := errors.New("stop")
+	err := q.EachReferenceTo(target, ReferenceFilter{}, func(site ReferenceSite) error {
+		streamed = append(streamed, site)
+		return stop
+	})
+	assert.ErrorIs(t, err, stop)
+	require.Len(t, streamed, 1)
+	assert.Equal(t, "/test.go", streamed[0].File)
+	assert.Equal(t, 2, streamed[0].StartLine)
+}
+
+func TestReferenceKinds_RustExtraction(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	shapesPath := filepath.Join(dir, "shapes.rs")
+	require.NoError(t, os.WriteFile(shapesPath, []byte(`pub trait Area {
+    fn area(&self) -> f64;
+}
+
+pub struct Circle {
+    pub r: f64,
+}
+
+impl Circle {
+    pub fn new(r: f64) -> Circle {
+        Circle { r }
+    }
+}
+`), 0644))
+	mainPath := filepath.Join(dir, "main.rs")
+	require.NoError(t, os.WriteFile(mainPath, []byte(`use crate::shapes::{Area, Circle};
+
+fn total<T: Area>(items: &[T]) -> f64 {
+    0.0
+}
+
+fn radius(c: &Circle) -> f64 {
+    match c {
+        Circle { r } => *r,
+    }
+}
+
+fn main() {
+    let c = Circle::new(1.0);
+    radius(&c);
+}
+`), 0644))
+
+	require.NoError(t, e.IndexFiles(ctx, []string{shapesPath, mainPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	kindsOf := func(name string) map[string]int {
+		t.Helper()
+		syms, err := e.store.SymbolsByName(name)
+		require.NoError(t, err)
+		require.Len(t, syms, 1)
+		kinds := map[string]int{}
+		require.NoError(t, e.Query().EachReferenceTo(syms[0].ID, ReferenceFilter{}, func(site ReferenceSite) error {
+			if site.File == mainPath {
+				kinds[site.Kind]++
+			}
+			return nil
+		}))
+		return kinds
+	}
+
+	area := kindsOf("Area")
+	assert.Equal(t, 1, area[RefKindImport], "use statement")
+	assert.Equal(t, 1, area[RefKindTraitBound], "T: Area")
+
+	circle := kindsOf("Circle")
+	assert.Equal(t, 1, circle[RefKindImport], "use statement")
+	assert.Equal(t, 2, circle[RefKindType], "&Circle parameter, Circle::new qualifier")
+	assert.Equal(t, 1, circle[RefKindPattern], "struct pattern")
+}
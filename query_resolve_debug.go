@@ -0,0 +1,344 @@
+package canopy
+
+import (
+	"database/sql"
+	"fmt"
+	"path"
+	"sort"
+	"strings"
+)
+
+// Reasons a ResolutionTrace gives for not binding a candidate declaration,
+// checked in this order.
+const (
+	// RejectedOtherLanguage: declared in a file of another language.
+	RejectedOtherLanguage = "other_language"
+	// RejectedKindMismatch: its kind cannot be used in the reference's
+	// context, such as a call of a module or a type annotation naming a
+	// function.
+	RejectedKindMismatch = "kind_mismatch"
+	// RejectedOutOfScope: declared in the reference's file, inside a scope
+	// that does not enclose the reference.
+	RejectedOutOfScope = "out_of_scope"
+	// RejectedPrivate: private, and declared outside the reference's
+	// directory.
+	RejectedPrivate = "private"
+	// RejectedNotImported: declared outside the reference's directory, and
+	// no import of the reference's file binds the name or its qualifier.
+	RejectedNotImported = "not_imported"
+	// RejectedOutranked: passes these checks, but the resolver bound
+	// another candidate.
+	RejectedOutranked = "outranked"
+	// RejectedUnmodeled: passes these checks, yet the resolver bound
+	// nothing: it does not model the construct.
+	RejectedUnmodeled = "unmodeled"
+)
+
+// maxResolutionCandidates caps the candidates a ResolutionTrace lists, so
+// a common name (new, get) stays readable.
+const maxResolutionCandidates = 100
+
+// ResolutionTrace reconstructs how the resolver handled one reference: the
+// scopes it searched, the imports it considered, and each declaration the
+// name could have meant, bound or rejected with a reason. The bindings are
+// what the resolve scripts recorded; the rejections are checks over the
+// index that approximate the scripts' rules, to point a resolution bug
+// report at the right step.
+type ResolutionTrace struct {
+	ReferenceID int64
+	Name        string
+	Kind        string // one of the RefKind constants, or "" if unclassified
+	Qualifier   string // the name in front of "." or "::", if any
+	Language    string
+	Reference   Location
+	Scopes      []TracedScope         // the scopes enclosing the reference, innermost first
+	Imports     []TracedImport        // the imports of the reference's file
+	Candidates  []ResolutionCandidate // bound candidates first, then by symbol ID
+	// UnresolvedReason is why the reference is unresolved, one of the
+	// Unresolved* constants, or "" if it resolved.
+	UnresolvedReason string
+}
+
+// TracedScope is a scope searched by a ResolutionTrace.
+type TracedScope struct {
+	ExplainedScope
+	Declares []int64 // IDs of the candidates declared directly in the scope
+}
+
+// TracedImport is an import considered by a ResolutionTrace.
+type TracedImport struct {
+	ExplainedImport
+	Binds   string // the name the import brings into scope; "" for a glob import
+	Matches bool   // Binds is the reference's name or qualifier
+}
+
+// ResolutionCandidate is a declaration a traced reference could have
+// meant: one with the reference's name, the name an import aliases to it,
+// or a symbol the reference resolved to.
+type ResolutionCandidate struct {
+	SymbolID       int64
+	Name           string
+	Kind           string
+	Visibility     string
+	Location       *Location // nil for a stub without source
+	Bound          bool      // the resolver bound the reference to it
+	ResolutionKind string    // for bound candidates
+	Confidence     string    // for bound candidates, one of the Confidence constants
+	Rejected       string    // for other candidates, one of the Rejected* constants
+}
+
+// ResolveDebug traces the resolution of the reference at the given
+// position, the narrowest one if several overlap. Line and col are 0-based.
+// It returns nil if there is no reference at the position.
+func (q *QueryBuilder) ResolveDebug(file string, line, col int) (*ResolutionTrace, error) {
+	tr := &ResolutionTrace{}
+	var fileID int64
+	var scopeID sql.NullInt64
+	loc := &tr.Reference
+	err := q.store.DB().QueryRow(
+		`SELECT r.id, r.file_id, r.scope_id, r.name, COALESCE(r.context, ''), f.language, f.path,
+			r.start_line, r.start_col, r.end_line, r.end_col,
+			r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col
+		 FROM references_ r JOIN files f ON f.id = r.file_id
+		 WHERE f.path = ? AND r.start_line <= ? AND r.end_line >= ?
+		   AND (r.start_line < ? OR (r.start_line = ? AND r.start_col <= ?))
+		   AND (r.end_line > ? OR (r.end_line = ? AND r.end_col >= ?))
+		 ORDER BY r.end_byte - r.start_byte, r.id LIMIT 1`,
+		file, line, line, line, line, col, line, line, col,
+	).Scan(&tr.ReferenceID, &fileID, &scopeID, &tr.Name, &tr.Kind, &tr.Language, &loc.File,
+		&loc.StartLine, &loc.StartCol, &loc.EndLine, &loc.EndCol,
+		&loc.StartByte, &loc.EndByte, &loc.UTF16StartCol, &loc.UTF16EndCol)
+	if err == sql.ErrNoRows {
+		return nil, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("resolve debug: %w", err)
+	}
+	if src, err := q.readSource(loc.File); err == nil {
+		tr.Qualifier = referenceQualifier(src, loc.StartByte)
+	}
+
+	if scopeID.Valid {
+		scopes, err := q.scopeChain(scopeID.Int64)
+		if err != nil {
+			return nil, fmt.Errorf("resolve debug: scopes: %w", err)
+		}
+		for _, sc := range scopes {
+			tr.Scopes = append(tr.Scopes, TracedScope{ExplainedScope: sc})
+		}
+	}
+
+	// The names a candidate may have: the reference's own, and the
+	// imported names behind an alias it matches.
+	names := []any{tr.Name}
+	bindings := map[string]string{}
+	importMatched := false
+	rows, err := q.store.DB().Query(
+		`SELECT source, COALESCE(imported_name, ''), COALESCE(local_alias, ''), COALESCE(kind, '')
+		 FROM imports WHERE file_id = ? ORDER BY id`, fileID)
+	if err != nil {
+		return nil, fmt.Errorf("resolve debug: imports: %w", err)
+	}
+	for rows.Next() {
+		var imp TracedImport
+		if err := rows.Scan(&imp.Source, &imp.ImportedName, &imp.LocalAlias, &imp.Kind); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("resolve debug: imports: %w", err)
+		}
+		imp.Binds = importBinding(imp.Source, imp.ImportedName, imp.LocalAlias)
+		imp.Matches = imp.Binds != "" && (imp.Binds == tr.Name || imp.Binds == tr.Qualifier)
+		if imp.Binds != "" {
+			bindings[imp.Binds] = imp.Source
+		}
+		if imp.Matches {
+			importMatched = true
+			if imp.Binds == tr.Name && imp.ImportedName != "" && imp.ImportedName != tr.Name {
+				names = append(names, imp.ImportedName)
+			}
+		}
+		tr.Imports = append(tr.Imports, imp)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("resolve debug: imports: %w", err)
+	}
+
+	bound := map[int64]traceBinding{}
+	rows, err = q.store.DB().Query(
+		`SELECT target_symbol_id, COALESCE(resolution_kind, ''), COALESCE(confidence, 1.0)
+		 FROM resolved_references WHERE reference_id = ? ORDER BY id`, tr.ReferenceID)
+	if err != nil {
+		return nil, fmt.Errorf("resolve debug: resolutions: %w", err)
+	}
+	for rows.Next() {
+		var id int64
+		var b traceBinding
+		if err := rows.Scan(&id, &b.kind, &b.confidence); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("resolve debug: resolutions: %w", err)
+		}
+		if _, ok := bound[id]; !ok {
+			bound[id] = b
+		}
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("resolve debug: resolutions: %w", err)
+	}
+
+	candidates, err := q.resolutionCandidates(tr.ReferenceID, names)
+	if err != nil {
+		return nil, fmt.Errorf("resolve debug: candidates: %w", err)
+	}
+	for _, c := range candidates {
+		rc := ResolutionCandidate{SymbolID: c.id, Name: c.name, Kind: c.kind, Visibility: c.visibility}
+		if rc.Location, err = q.symbolLocation(c.id); err != nil {
+			return nil, fmt.Errorf("resolve debug: candidate location: %w", err)
+		}
+		// A candidate in the reference's file is declared in the scope of its
+		// parent symbol, or in the outermost scope if it has none.
+		declaredIn := -1
+		if c.fileID != nil && *c.fileID == fileID {
+			for i, sc := range tr.Scopes {
+				outermost := i == len(tr.Scopes)-1
+				if (c.parentID == nil && outermost) ||
+					(c.parentID != nil && sc.SymbolID != nil && *sc.SymbolID == *c.parentID) {
+					declaredIn = i
+					break
+				}
+			}
+			if declaredIn >= 0 {
+				tr.Scopes[declaredIn].Declares = append(tr.Scopes[declaredIn].Declares, c.id)
+			}
+		}
+
+		if b, ok := bound[c.id]; ok {
+			rc.Bound, rc.ResolutionKind, rc.Confidence = true, b.kind, ConfidenceLevel(b.confidence)
+			tr.Candidates = append(tr.Candidates, rc)
+			continue
+		}
+		sameFile := c.fileID != nil && *c.fileID == fileID
+		switch {
+		case c.language != "" && !languagesMix(tr.Language, c.language):
+			rc.Rejected = RejectedOtherLanguage
+		case !kindFitsReference(tr.Kind, c.kind):
+			rc.Rejected = RejectedKindMismatch
+		case sameFile && tr.Qualifier == "" && declaredIn < 0:
+			rc.Rejected = RejectedOutOfScope
+		case !sameFile && c.path != "" && path.Dir(c.path) != path.Dir(loc.File) && c.visibility == "private":
+			rc.Rejected = RejectedPrivate
+		case !sameFile && c.path != "" && path.Dir(c.path) != path.Dir(loc.File) && !importMatched:
+			rc.Rejected = RejectedNotImported
+		case len(bound) > 0:
+			rc.Rejected = RejectedOutranked
+		default:
+			rc.Rejected = RejectedUnmodeled
+		}
+		tr.Candidates = append(tr.Candidates, rc)
+	}
+	sort.SliceStable(tr.Candidates, func(i, j int) bool {
+		return tr.Candidates[i].Bound && !tr.Candidates[j].Bound
+	})
+
+	if len(bound) == 0 {
+		derived, err := q.macroDerivedTypes()
+		if err != nil {
+			return nil, fmt.Errorf("resolve debug: %w", err)
+		}
+		macroFiles, err := q.itemMacroFiles()
+		if err != nil {
+			return nil, fmt.Errorf("resolve debug: %w", err)
+		}
+		buildFiles, err := q.missingBuildOutputFiles()
+		if err != nil {
+			return nil, fmt.Errorf("resolve debug: %w", err)
+		}
+		ref := UnresolvedReference{Name: tr.Name, Context: tr.Kind, Qualifier: tr.Qualifier}
+		tr.UnresolvedReason, _ = unresolvedReason(ref, bindings, derived, macroFiles[fileID], buildFiles[fileID])
+	}
+	return tr, nil
+}
+
+// traceBinding is how a traced reference was bound to one target.
+type traceBinding struct {
+	kind       string
+	confidence float64
+}
+
+// resolutionCandidate is a symbol row considered by ResolveDebug.
+type resolutionCandidate struct {
+	id                     int64
+	name, kind, visibility string
+	fileID, parentID       *int64
+	path, language         string
+}
+
+// resolutionCandidates returns the symbols named any of names, and the
+// symbols reference referenceID resolved to, by ID.
+func (q *QueryBuilder) resolutionCandidates(referenceID int64, names []any) ([]resolutionCandidate, error) {
+	args := append(append([]any{}, names...), referenceID, maxResolutionCandidates)
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, s.name, s.kind, COALESCE(s.visibility, ''), s.file_id, s.parent_symbol_id,
+			COALESCE(f.path, ''), COALESCE(f.language, '')
+		 FROM symbols s LEFT JOIN files f ON f.id = s.file_id
+		 WHERE s.name IN (`+strings.Repeat("?,", len(names)-1)+`?)
+		    OR s.id IN (SELECT target_symbol_id FROM resolved_references WHERE reference_id = ?)
+		 ORDER BY s.id LIMIT ?`, args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var out []resolutionCandidate
+	for rows.Next() {
+		var c resolutionCandidate
+		var fileID, parentID sql.NullInt64
+		if err := rows.Scan(&c.id, &c.name, &c.kind, &c.visibility, &fileID, &parentID, &c.path, &c.language); err != nil {
+			return nil, err
+		}
+		if fileID.Valid {
+			c.fileID = &fileID.Int64
+		}
+		if parentID.Valid {
+			c.parentID = &parentID.Int64
+		}
+		out = append(out, c)
+	}
+	return out, rows.Err()
+}
+
+// languagesMix reports whether a reference in a file of language from can
+// bind to a declaration in a file of language to: the same language, C and
+// C++ sharing headers, JavaScript and TypeScript importing each other, and
+// Rust and its rustdoc stubs.
+func languagesMix(from, to string) bool {
+	family := func(lang string) string {
+		switch lang {
+		case "cpp":
+			return "c"
+		case "typescript":
+			return "javascript"
+		case rustdocStubLanguage:
+			return "rust"
+		}
+		return lang
+	}
+	return family(from) == family(to)
+}
+
+// kindFitsReference reports whether a symbol of the given kind can be used
+// in a reference of context refKind. Only clear mismatches are ruled out.
+func kindFitsReference(refKind, symbolKind string) bool {
+	switch refKind {
+	case RefKindCall:
+		switch symbolKind {
+		case "module", "package", "namespace", "interface", "trait":
+			return false
+		}
+	case RefKindType, RefKindTraitBound:
+		switch symbolKind {
+		case "function", "method", "variable", "field", "constant":
+			return false
+		}
+	}
+	return true
+}
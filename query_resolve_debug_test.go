@@ -0,0 +1,83 @@
+package canopy
+
+import (
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestResolveDebug_RejectsCandidatesWithReasons(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	mainID := insertFile(t, s, "/app/main.go", "go")
+	utilID := insertFile(t, s, "/app/util.go", "go")
+	typesID := insertFile(t, s, "/app/types.go", "go")
+	otherID := insertFile(t, s, "/lib/other.go", "go")
+	pyID := insertFile(t, s, "/x/helper.py", "python")
+
+	run := insertSymbol(t, s, &mainID, "run", "function", "private", nil)
+	setup := insertSymbol(t, s, &mainID, "setup", "function", "private", nil)
+	nested := insertSymbolWithParent(t, s, &mainID, "helper", "function", "private", setup)
+	bound := insertSymbol(t, s, &utilID, "helper", "function", "private", nil)
+	iface := insertSymbol(t, s, &typesID, "helper", "interface", "public", nil)
+	other := insertSymbol(t, s, &otherID, "helper", "function", "public", nil)
+	py := insertSymbol(t, s, &pyID, "helper", "function", "public", nil)
+
+	fileScope, err := s.InsertScope(&store.Scope{FileID: mainID, Kind: "file", EndLine: 20})
+	require.NoError(t, err)
+	fnScope, err := s.InsertScope(&store.Scope{FileID: mainID, SymbolID: &run, Kind: "function", StartLine: 2, EndLine: 6, ParentScopeID: &fileScope})
+	require.NoError(t, err)
+	refID, err := s.InsertReference(&store.Reference{
+		FileID: mainID, ScopeID: &fnScope, Name: "helper", Context: RefKindCall,
+		StartLine: 3, StartCol: 1, EndLine: 3, EndCol: 7,
+	})
+	require.NoError(t, err)
+	_, err = s.InsertResolvedReference(&store.ResolvedReference{
+		ReferenceID: refID, TargetSymbolID: bound, Confidence: 1.0, ResolutionKind: "direct",
+	})
+	require.NoError(t, err)
+	_, err = s.InsertReference(&store.Reference{
+		FileID: mainID, ScopeID: &fnScope, Name: "nowhere", Context: RefKindRead,
+		StartLine: 4, StartCol: 1, EndLine: 4, EndCol: 8,
+	})
+	require.NoError(t, err)
+
+	tr, err := q.ResolveDebug("/app/main.go", 3, 3)
+	require.NoError(t, err)
+	require.NotNil(t, tr)
+	assert.Equal(t, refID, tr.ReferenceID)
+	assert.Equal(t, "helper", tr.Name)
+	assert.Equal(t, "go", tr.Language)
+	assert.Empty(t, tr.UnresolvedReason)
+	require.Len(t, tr.Scopes, 2)
+	assert.Equal(t, "run", tr.Scopes[0].SymbolName)
+	assert.Empty(t, tr.Scopes[1].Declares, "the nested helper is declared in setup, not in an enclosing scope")
+
+	require.Len(t, tr.Candidates, 5)
+	assert.Equal(t, bound, tr.Candidates[0].SymbolID)
+	assert.True(t, tr.Candidates[0].Bound)
+	assert.Equal(t, ConfidenceExact, tr.Candidates[0].Confidence)
+	rejected := map[int64]string{}
+	for _, c := range tr.Candidates[1:] {
+		assert.False(t, c.Bound)
+		rejected[c.SymbolID] = c.Rejected
+	}
+	assert.Equal(t, map[int64]string{
+		nested: RejectedOutOfScope,
+		iface:  RejectedKindMismatch,
+		other:  RejectedNotImported,
+		py:     RejectedOtherLanguage,
+	}, rejected)
+
+	tr, err = q.ResolveDebug("/app/main.go", 4, 2)
+	require.NoError(t, err)
+	require.NotNil(t, tr)
+	assert.Empty(t, tr.Candidates)
+	assert.Equal(t, UnresolvedUnsupportedSyntax, tr.UnresolvedReason)
+
+	tr, err = q.ResolveDebug("/app/main.go", 10, 0)
+	require.NoError(t, err)
+	assert.Nil(t, tr)
+}
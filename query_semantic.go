@@ -0,0 +1,194 @@
+package canopy
+
+import (
+	"context"
+	"fmt"
+	"math"
+	"sort"
+	"strings"
+)
+
+// Semantic search ranking: how many candidates per requested hit are
+// weighed against each other for graph context, and the weights of the
+// graph context in a hit's score.
+const (
+	semanticCandidatesPerHit  = 5
+	minSemanticCandidates     = 50
+	semanticNeighborWeight    = 0.15
+	semanticCentralityWeight  = 0.05
+	maxSemanticRelatedSymbols = 3
+)
+
+// SemanticHit is a symbol matching a natural-language query.
+type SemanticHit struct {
+	Symbol     *SymbolResult
+	Similarity float64 // cosine similarity of the query and the symbol's code
+	Score      float64 // Similarity plus graph context; hits are ordered by it
+	// Related are the symbol's callers and callees that also match the
+	// query, most similar first.
+	Related []*SymbolResult
+}
+
+// SemanticSearch embeds query with emb and returns up to limit symbols
+// (all candidates if limit <= 0) ranked by similarity to it, over the
+// embeddings EmbedSymbols stored for emb's model. The best candidates are
+// then re-ranked with graph context: a symbol gains from the best
+// similarity among its callers and callees, so a function sitting in a
+// cluster of matching code outranks an isolated one that only shares a
+// word with the query, and a little from its PageRank, so central code
+// wins ties. Returns an error if no symbols were embedded with emb's model.
+func (q *QueryBuilder) SemanticSearch(ctx context.Context, emb Embedder, query string, limit int) ([]*SemanticHit, error) {
+	if strings.TrimSpace(query) == "" {
+		return nil, fmt.Errorf("semantic search: empty query")
+	}
+	model := emb.Model()
+	stored, err := q.store.SymbolEmbeddings(model)
+	if err != nil {
+		return nil, fmt.Errorf("semantic search: %w", err)
+	}
+	if len(stored) == 0 {
+		return nil, fmt.Errorf("semantic search: no symbols embedded with %s", model)
+	}
+	vecs, err := emb.Embed(ctx, []string{query})
+	if err != nil {
+		return nil, fmt.Errorf("semantic search: %w", err)
+	}
+	if len(vecs) != 1 || len(vecs[0]) != len(stored[0].Vector) {
+		return nil, fmt.Errorf("semantic search: %s returned a query vector that does not match the stored ones", model)
+	}
+	qv := vecs[0]
+
+	type candidate struct {
+		id  int64
+		sim float64
+	}
+	var cands []candidate
+	for _, se := range stored {
+		if sim := cosineSimilarity(qv, se.Vector); sim > 0 {
+			cands = append(cands, candidate{se.SymbolID, sim})
+		}
+	}
+	sort.Slice(cands, func(i, j int) bool {
+		if cands[i].sim != cands[j].sim {
+			return cands[i].sim > cands[j].sim
+		}
+		return cands[i].id < cands[j].id
+	})
+	if n := max(limit*semanticCandidatesPerHit, minSemanticCandidates); len(cands) > n {
+		cands = cands[:n]
+	}
+	if len(cands) == 0 {
+		return nil, nil
+	}
+
+	ids := make([]int64, len(cands))
+	sim := make(map[int64]float64, len(cands))
+	for i, c := range cands {
+		ids[i] = c.id
+		sim[c.id] = c.sim
+	}
+	results, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("semantic search: %w", err)
+	}
+	neighbors, err := q.callNeighbors(ids)
+	if err != nil {
+		return nil, fmt.Errorf("semantic search: %w", err)
+	}
+	var maxRank float64
+	for _, sr := range results {
+		maxRank = math.Max(maxRank, sr.PageRank)
+	}
+
+	var hits []*SemanticHit
+	for _, c := range cands {
+		sr := results[c.id]
+		if sr == nil {
+			continue
+		}
+		hit := &SemanticHit{Symbol: sr, Similarity: c.sim, Score: c.sim}
+		related := neighbors[c.id]
+		sort.Slice(related, func(i, j int) bool {
+			if sim[related[i]] != sim[related[j]] {
+				return sim[related[i]] > sim[related[j]]
+			}
+			return related[i] < related[j]
+		})
+		if len(related) > 0 {
+			hit.Score += semanticNeighborWeight * sim[related[0]]
+		}
+		for _, id := range related {
+			if len(hit.Related) == maxSemanticRelatedSymbols {
+				break
+			}
+			if r := results[id]; r != nil {
+				hit.Related = append(hit.Related, r)
+			}
+		}
+		if maxRank > 0 {
+			hit.Score += semanticCentralityWeight * sr.PageRank / maxRank
+		}
+		hits = append(hits, hit)
+	}
+	sort.SliceStable(hits, func(i, j int) bool {
+		return hits[i].Score > hits[j].Score
+	})
+	if limit > 0 && len(hits) > limit {
+		hits = hits[:limit]
+	}
+	return hits, nil
+}
+
+// callNeighbors returns, for each of ids, its callers and callees among ids.
+func (q *QueryBuilder) callNeighbors(ids []int64) (map[int64][]int64, error) {
+	placeholders := strings.Repeat("?,", len(ids)-1) + "?"
+	args := make([]any, 0, 2*len(ids))
+	for range 2 {
+		for _, id := range ids {
+			args = append(args, id)
+		}
+	}
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT caller_symbol_id, callee_symbol_id FROM call_graph
+		 WHERE caller_symbol_id IN (`+placeholders+`) AND callee_symbol_id IN (`+placeholders+`)
+		   AND caller_symbol_id != callee_symbol_id`,
+		args...,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("call neighbors: %w", err)
+	}
+	defer rows.Close()
+	seen := map[[2]int64]bool{}
+	out := map[int64][]int64{}
+	for rows.Next() {
+		var caller, callee int64
+		if err := rows.Scan(&caller, &callee); err != nil {
+			return nil, fmt.Errorf("call neighbors: %w", err)
+		}
+		for _, pair := range [][2]int64{{caller, callee}, {callee, caller}} {
+			if !seen[pair] {
+				seen[pair] = true
+				out[pair[0]] = append(out[pair[0]], pair[1])
+			}
+		}
+	}
+	return out, rows.Err()
+}
+
+// cosineSimilarity returns the cosine of the angle between a and b, 0 if
+// either is zero or their lengths differ.
+func cosineSimilarity(a, b []float32) float64 {
+	if len(a) != len(b) {
+		return 0
+	}
+	var dot, na, nb float64
+	for i := range a {
+		dot += float64(a[i]) * float64(b[i])
+		na += float64(a[i]) * float64(a[i])
+		nb += float64(b[i]) * float64(b[i])
+	}
+	if na == 0 || nb == 0 {
+		return 0
+	}
+	return dot / math.Sqrt(na*nb)
+}
@@ -0,0 +1,72 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSemanticSearch(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte("[package]\nname = \"shop\"\nversion = \"0.1.0\"\n"), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`/// Tax owed on shipping a parcel.
+pub fn compute_shipping_tax(p: &Parcel) -> f64 {
+    shipping_rate(p) * 0.2
+}
+
+/// The carrier's rate for shipping a parcel.
+pub fn shipping_rate(p: &Parcel) -> f64 {
+    p.weight * 1.5
+}
+
+/// Reads settings from text.
+pub fn parse_config(text: &str) -> usize {
+    text.len()
+}
+
+pub struct Parcel {
+    pub weight: f64,
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	emb := &HashEmbedder{Dimensions: 256}
+	_, err := e.Query().SemanticSearch(ctx, emb, "shipping tax", 5)
+	assert.ErrorContains(t, err, "no symbols embedded")
+
+	summary, err := e.EmbedSymbols(ctx, emb)
+	require.NoError(t, err)
+	assert.Equal(t, "hash-256", summary.Model)
+	assert.Equal(t, 4, summary.Symbols)
+	assert.Equal(t, 4, summary.Embedded)
+	assert.Equal(t, 256, summary.Dimensions)
+
+	hits, err := e.Query().SemanticSearch(ctx, emb, "where do we compute shipping tax", 2)
+	require.NoError(t, err)
+	require.Len(t, hits, 2)
+	assert.Equal(t, "compute_shipping_tax", hits[0].Symbol.Name)
+	assert.Equal(t, "shipping_rate", hits[1].Symbol.Name)
+	require.NotEmpty(t, hits[0].Related)
+	assert.Equal(t, "shipping_rate", hits[0].Related[0].Name, "callees matching the query are graph context")
+	assert.GreaterOrEqual(t, hits[0].Score, hits[0].Similarity+semanticNeighborWeight*hits[1].Similarity)
+
+	// Unchanged symbols keep their vectors; other models are stored apart.
+	summary, err = e.EmbedSymbols(ctx, emb)
+	require.NoError(t, err)
+	assert.Equal(t, 4, summary.Reused)
+	assert.Zero(t, summary.Embedded)
+	_, err = e.Query().SemanticSearch(ctx, &HashEmbedder{Dimensions: 128}, "shipping tax", 3)
+	assert.ErrorContains(t, err, "hash-128")
+
+	_, err = e.Query().SemanticSearch(ctx, emb, "  ", 3)
+	assert.Error(t, err)
+}
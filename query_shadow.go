@@ -0,0 +1,533 @@
+package canopy
+
+import (
+	"fmt"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Flagged site kinds of shadowed local bindings. A site spans from the
+// identifier of the earlier binding to that of the later one, and is named
+// by the identifier.
+const (
+	SiteKindShadow      = "shadow"       // the later binding's value does not mention the name
+	SiteKindShadowReuse = "shadow_reuse" // the later binding's value mentions the name, as in let x = x.trim()
+)
+
+// Kinds of shadowing.
+const (
+	ShadowKindLocal         = "local"          // a local binding rebinds a name bound earlier in the function
+	ShadowKindReuse         = "reuse"          // a local binding rebinds a name from a value using it: let x = x.trim()
+	ShadowKindGlob          = "glob"           // an item or use declaration hides a name a glob import brings in
+	ShadowKindGlobCollision = "glob_collision" // two glob imports bring in different items of the same name
+)
+
+// shadowKinds are the kinds Shadows reports by default.
+var shadowKinds = []string{ShadowKindLocal, ShadowKindReuse, ShadowKindGlob, ShadowKindGlobCollision}
+
+// ShadowDefinition is one of the definitions of a shadowed name.
+type ShadowDefinition struct {
+	Location Location
+	Symbol   *SymbolResult // the item; nil for a local binding or an import of an item that is not indexed
+	Import   string        // the use path the name comes through ("crate::net::*"), if imported
+}
+
+// Shadow is a name with two definitions where one is expected.
+type Shadow struct {
+	Kind     string // one of the ShadowKind constants
+	Name     string
+	File     string
+	Function *SymbolResult // for local and reuse, the function binding the name
+	// Shadowing is the definition the name refers to, and Shadowed the one
+	// it hides. For a glob collision, where the name is ambiguous, they are
+	// the items of the later and earlier glob import.
+	Shadowing ShadowDefinition
+	Shadowed  ShadowDefinition
+}
+
+// Shadows finds Rust names with two definitions in files under pathPrefix
+// ("" for all files): local bindings that rebind a name a parameter or
+// binding of the enclosing function already binds, items and use
+// declarations that hide a name a glob import brings in, and glob imports
+// that bring in different items of the same name, which is an error
+// wherever the name is used. kinds limits results to the given ShadowKind
+// constants; none reports every kind. Glob imports are followed to modules
+// of the importing crate only, and re-exports are not followed. Results are
+// ordered by file, then by the position of the shadowing definition.
+func (q *QueryBuilder) Shadows(kinds []string, pathPrefix string) ([]Shadow, error) {
+	if len(kinds) == 0 {
+		kinds = shadowKinds
+	}
+	want := map[string]bool{}
+	for _, k := range kinds {
+		if k != ShadowKindLocal && k != ShadowKindReuse && k != ShadowKindGlob && k != ShadowKindGlobCollision {
+			return nil, fmt.Errorf("shadows: unknown kind %q (want %s)", k, strings.Join(shadowKinds, ", "))
+		}
+		want[k] = true
+	}
+
+	var shadows []Shadow
+	if want[ShadowKindLocal] || want[ShadowKindReuse] {
+		locals, err := q.localShadows(want, pathPrefix)
+		if err != nil {
+			return nil, fmt.Errorf("shadows: %w", err)
+		}
+		shadows = append(shadows, locals...)
+	}
+	if want[ShadowKindGlob] || want[ShadowKindGlobCollision] {
+		globs, err := q.globShadows(want, pathPrefix)
+		if err != nil {
+			return nil, fmt.Errorf("shadows: %w", err)
+		}
+		shadows = append(shadows, globs...)
+	}
+
+	sort.SliceStable(shadows, func(i, j int) bool {
+		a, b := shadows[i], shadows[j]
+		if a.File != b.File {
+			return a.File < b.File
+		}
+		if a.Shadowing.Location.StartLine != b.Shadowing.Location.StartLine {
+			return a.Shadowing.Location.StartLine < b.Shadowing.Location.StartLine
+		}
+		if a.Shadowing.Location.StartCol != b.Shadowing.Location.StartCol {
+			return a.Shadowing.Location.StartCol < b.Shadowing.Location.StartCol
+		}
+		return a.Name < b.Name
+	})
+	return shadows, nil
+}
+
+// localShadows returns the shadowed local bindings recorded by extraction.
+func (q *QueryBuilder) localShadows(want map[string]bool, pathPrefix string) ([]Shadow, error) {
+	var siteKinds []string
+	if want[ShadowKindLocal] {
+		siteKinds = append(siteKinds, SiteKindShadow)
+	}
+	if want[ShadowKindReuse] {
+		siteKinds = append(siteKinds, SiteKindShadowReuse)
+	}
+	sites, err := q.flaggedSites(nil, siteKinds)
+	if err != nil {
+		return nil, err
+	}
+
+	functions := map[int64]*SymbolResult{}
+	var shadows []Shadow
+	for _, site := range sites {
+		loc := site.location
+		if !strings.HasPrefix(loc.File, pathPrefix) {
+			continue
+		}
+		fn, ok := functions[site.symbolID]
+		if !ok {
+			if fn, err = q.symbolResultByID(site.symbolID); err != nil {
+				return nil, err
+			}
+			functions[site.symbolID] = fn
+		}
+		kind := ShadowKindLocal
+		if site.kind == SiteKindShadowReuse {
+			kind = ShadowKindReuse
+		}
+		// Both bindings are identifiers named site.name, each on one line.
+		n := len(site.name)
+		shadows = append(shadows, Shadow{
+			Kind:     kind,
+			Name:     site.name,
+			File:     loc.File,
+			Function: fn,
+			Shadowing: ShadowDefinition{Location: Location{
+				File: loc.File, StartLine: loc.EndLine, StartCol: loc.EndCol - n, EndLine: loc.EndLine, EndCol: loc.EndCol,
+			}},
+			Shadowed: ShadowDefinition{Location: Location{
+				File: loc.File, StartLine: loc.StartLine, StartCol: loc.StartCol, EndLine: loc.StartLine, EndCol: loc.StartCol + n,
+			}},
+		})
+	}
+	return shadows, nil
+}
+
+// globImport is a name a glob import brings in.
+type globImport struct {
+	source string // the glob, as written: "crate::net::*"
+	item   *store.Symbol
+}
+
+// globShadows returns the names glob imports bring into Rust files that are
+// hidden by an item or use declaration of the file, or that two glob
+// imports bring in.
+func (q *QueryBuilder) globShadows(want map[string]bool, pathPrefix string) ([]Shadow, error) {
+	files, fileIDs, byFile, err := q.rustImportsByFile(pathPrefix)
+	if err != nil {
+		return nil, err
+	}
+
+	r := &rustItemResolver{q: q, packages: map[string]cargoPackage{}, items: map[string]map[string]*store.Symbol{}}
+	symbols := map[int64]*SymbolResult{}
+	definition := func(item *store.Symbol, source string) (ShadowDefinition, error) {
+		sr, ok := symbols[item.ID]
+		if !ok {
+			if sr, err = q.symbolResultByID(item.ID); err != nil {
+				return ShadowDefinition{}, err
+			}
+			symbols[item.ID] = sr
+		}
+		def := ShadowDefinition{Symbol: sr, Import: source}
+		if sr != nil {
+			def.Location = symbolLocationOf(sr)
+		}
+		return def, nil
+	}
+
+	var shadows []Shadow
+	for _, fileID := range fileIDs {
+		path := files[fileID]
+		home, ok := q.rustModuleOf(path, r.packages)
+		if !ok {
+			continue
+		}
+
+		globs, err := r.fileGlobs(home, byFile[fileID])
+		if err != nil {
+			return nil, err
+		}
+		if len(globs) == 0 {
+			continue
+		}
+
+		if want[ShadowKindGlobCollision] {
+			first := map[string]globImport{}
+			for _, g := range globs {
+				prev, ok := first[g.item.Name]
+				if !ok {
+					first[g.item.Name] = g
+					continue
+				}
+				if prev.item.ID == g.item.ID || prev.source == g.source {
+					continue
+				}
+				shadowing, err := definition(g.item, g.source)
+				if err != nil {
+					return nil, err
+				}
+				shadowed, err := definition(prev.item, prev.source)
+				if err != nil {
+					return nil, err
+				}
+				shadows = append(shadows, Shadow{
+					Kind: ShadowKindGlobCollision, Name: g.item.Name, File: path,
+					Shadowing: shadowing, Shadowed: shadowed,
+				})
+			}
+		}
+
+		if want[ShadowKindGlob] {
+			found, err := q.explicitGlobShadows(r, home, fileID, path, byFile[fileID], globs, definition)
+			if err != nil {
+				return nil, err
+			}
+			shadows = append(shadows, found...)
+		}
+	}
+	return shadows, nil
+}
+
+// rustImportsByFile returns the paths of all indexed files by ID, and the
+// imports of the Rust files under pathPrefix by file, with those files' IDs
+// in the order of their first import.
+func (q *QueryBuilder) rustImportsByFile(pathPrefix string) (map[int64]string, []int64, map[int64][]*store.Import, error) {
+	imports, err := q.store.AllImports()
+	if err != nil {
+		return nil, nil, nil, err
+	}
+	files, err := q.store.AllFiles()
+	if err != nil {
+		return nil, nil, nil, err
+	}
+	byFile := map[int64][]*store.Import{}
+	var fileIDs []int64
+	for _, imp := range imports {
+		path := files[imp.FileID]
+		if filepath.Ext(path) != ".rs" || !strings.HasPrefix(path, pathPrefix) {
+			continue
+		}
+		if byFile[imp.FileID] == nil {
+			fileIDs = append(fileIDs, imp.FileID)
+		}
+		byFile[imp.FileID] = append(byFile[imp.FileID], imp)
+	}
+	return files, fileIDs, byFile, nil
+}
+
+// fileGlobs returns what the glob imports among imports, of a file of
+// module home, bring in, in import order: the items of each glob's module
+// visible from home, by name.
+func (r *rustItemResolver) fileGlobs(home rustModule, imports []*store.Import) ([]globImport, error) {
+	var globs []globImport
+	for _, imp := range imports {
+		if imp.Kind != "wildcard" || !strings.HasSuffix(imp.Source, "::*") {
+			continue
+		}
+		module, ok, err := r.resolvePath(home, strings.TrimSuffix(imp.Source, "::*"))
+		if err != nil {
+			return nil, err
+		}
+		if !ok {
+			continue
+		}
+		items, err := r.moduleItems(home, module)
+		if err != nil {
+			return nil, err
+		}
+		names := make([]string, 0, len(items))
+		for name := range items {
+			names = append(names, name)
+		}
+		sort.Strings(names)
+		for _, name := range names {
+			item := items[name]
+			scope, err := RustVisibilityScope(item.Visibility, module)
+			if err != nil || !scope.contains(home.path) {
+				continue
+			}
+			globs = append(globs, globImport{source: imp.Source, item: item})
+		}
+	}
+	return globs, nil
+}
+
+// explicitGlobShadows returns the names the globs bring into the file at
+// path that an item of the file's module or a use declaration hides.
+func (q *QueryBuilder) explicitGlobShadows(r *rustItemResolver, home rustModule, fileID int64, path string,
+	imports []*store.Import, globs []globImport, definition func(*store.Symbol, string) (ShadowDefinition, error)) ([]Shadow, error) {
+	fromGlob := map[string]globImport{}
+	for _, g := range globs {
+		if _, ok := fromGlob[g.item.Name]; !ok {
+			fromGlob[g.item.Name] = g
+		}
+	}
+	var shadows []Shadow
+	hidden := map[string]bool{}
+	add := func(name string, shadowing ShadowDefinition) error {
+		g := fromGlob[name]
+		if hidden[name] || (shadowing.Symbol != nil && shadowing.Symbol.ID == g.item.ID) {
+			return nil
+		}
+		hidden[name] = true
+		shadowed, err := definition(g.item, g.source)
+		if err != nil {
+			return err
+		}
+		shadows = append(shadows, Shadow{Kind: ShadowKindGlob, Name: name, File: path, Shadowing: shadowing, Shadowed: shadowed})
+		return nil
+	}
+
+	// Items declared in the file's module.
+	own, err := r.moduleItems(home, home.path)
+	if err != nil {
+		return nil, err
+	}
+	names := make([]string, 0, len(own))
+	for name := range own {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	for _, name := range names {
+		if _, ok := fromGlob[name]; !ok {
+			continue
+		}
+		def, err := definition(own[name], "")
+		if err != nil {
+			return nil, err
+		}
+		if err := add(name, def); err != nil {
+			return nil, err
+		}
+	}
+
+	// Use declarations, located at the name they import.
+	refs, err := q.store.ReferencesByFile(fileID)
+	if err != nil {
+		return nil, err
+	}
+	for _, imp := range imports {
+		if imp.Kind == "wildcard" || imp.ImportedName == nil || imp.LocalAlias != nil {
+			continue
+		}
+		name := *imp.ImportedName
+		if _, ok := fromGlob[name]; !ok {
+			continue
+		}
+		def := ShadowDefinition{Import: imp.Source}
+		if i := strings.LastIndex(imp.Source, "::"); i >= 0 {
+			module, ok, err := r.resolvePath(home, imp.Source[:i])
+			if err != nil {
+				return nil, err
+			}
+			if ok {
+				items, err := r.moduleItems(home, module)
+				if err != nil {
+					return nil, err
+				}
+				if item := items[name]; item != nil {
+					if def, err = definition(item, imp.Source); err != nil {
+						return nil, err
+					}
+				}
+			}
+		}
+		def.Location = Location{File: path}
+		for _, ref := range refs {
+			if ref.Context == "import" && ref.Name == name {
+				def.Location = Location{
+					File:          path,
+					StartLine:     ref.StartLine,
+					StartCol:      ref.StartCol,
+					EndLine:       ref.EndLine,
+					EndCol:        ref.EndCol,
+					StartByte:     ref.StartByte,
+					EndByte:       ref.EndByte,
+					UTF16StartCol: ref.UTF16StartCol,
+					UTF16EndCol:   ref.UTF16EndCol,
+				}
+				break
+			}
+		}
+		if err := add(name, def); err != nil {
+			return nil, err
+		}
+	}
+	return shadows, nil
+}
+
+// symbolLocationOf returns the location of a symbol's declaration.
+func symbolLocationOf(sr *SymbolResult) Location {
+	loc := symbolSpan(&sr.Symbol)
+	loc.File = sr.FilePath
+	loc.StartByte, loc.EndByte = sr.StartByte, sr.EndByte
+	loc.UTF16StartCol, loc.UTF16EndCol = sr.UTF16StartCol, sr.UTF16EndCol
+	return loc
+}
+
+// rustItemResolver looks up the items of Rust modules, caching them by
+// crate root and module path.
+type rustItemResolver struct {
+	q        *QueryBuilder
+	packages map[string]cargoPackage
+	items    map[string]map[string]*store.Symbol
+}
+
+// resolvePath resolves a use path of a module, as written in a file of
+// module home, to a module path of home's crate. ok is false if the path
+// leaves the crate or names no indexed module.
+func (r *rustItemResolver) resolvePath(home rustModule, path string) (module string, ok bool, err error) {
+	segments := strings.Split(path, "::")
+	switch segments[0] {
+	case "crate", home.crate:
+		segments = segments[1:]
+	case "self":
+		module, segments = home.path, segments[1:]
+	case "super":
+		module = home.path
+		for len(segments) > 0 && segments[0] == "super" {
+			module, segments = rustParentModule(module), segments[1:]
+		}
+	default:
+		// A path relative to the module, unless it names another crate.
+		module = home.path
+	}
+	for _, seg := range segments {
+		module = joinRustModule(module, seg)
+	}
+	items, err := r.moduleItems(home, module)
+	return module, items != nil, err
+}
+
+// moduleItems returns by name the items declared directly in the module at
+// path of m's crate, or nil if the module is not indexed. Members of types
+// and items inside functions or nested inline modules are not its items.
+func (r *rustItemResolver) moduleItems(m rustModule, path string) (map[string]*store.Symbol, error) {
+	key := m.root + "\x00" + path
+	if items, ok := r.items[key]; ok {
+		return items, nil
+	}
+
+	// The file of the module, or of the nearest enclosing module with one;
+	// the rest of the path names inline modules in it.
+	segments := strings.Split(path, "::")
+	if path == "" {
+		segments = nil
+	}
+	var file *store.File
+	var inline []string
+	for i := len(segments); i >= 0 && file == nil; i-- {
+		candidates := []string{m.root}
+		if i > 0 {
+			base := filepath.Join(m.dir, "src", filepath.Join(segments[:i]...))
+			candidates = []string{base + ".rs", filepath.Join(base, "mod.rs")}
+		}
+		for _, c := range candidates {
+			f, err := r.q.store.FileByPath(c)
+			if err != nil {
+				return nil, err
+			}
+			if f != nil {
+				file, inline = f, segments[i:]
+				break
+			}
+		}
+	}
+	if file == nil {
+		r.items[key] = nil
+		return nil, nil
+	}
+
+	syms, err := r.q.store.SymbolsByFile(file.ID)
+	if err != nil {
+		return nil, err
+	}
+	// enclosing returns the innermost inline module or function around s.
+	enclosing := func(s *store.Symbol) *store.Symbol {
+		var inner *store.Symbol
+		for _, o := range syms {
+			if o.ID == s.ID || (o.Kind != "module" && o.Kind != "function" && o.Kind != "method") {
+				continue
+			}
+			if spanContains(symbolSpan(o), symbolSpan(s)) && (inner == nil || o.StartByte > inner.StartByte) {
+				inner = o
+			}
+		}
+		return inner
+	}
+	var container *store.Symbol
+	for _, name := range inline {
+		var next *store.Symbol
+		for _, s := range syms {
+			if s.Kind == "module" && s.Name == name && enclosing(s) == container {
+				next = s
+				break
+			}
+		}
+		if next == nil {
+			r.items[key] = nil
+			return nil, nil
+		}
+		container = next
+	}
+
+	items := map[string]*store.Symbol{}
+	for _, s := range syms {
+		if s.ParentSymbolID != nil || enclosing(s) != container {
+			continue
+		}
+		if _, ok := items[s.Name]; !ok {
+			items[s.Name] = s
+		}
+	}
+	r.items[key] = items
+	return items, nil
+}
@@ -0,0 +1,89 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestShadows(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", "mod a;\nmod b;\nmod user;\n")
+	write("src/a.rs", "pub struct Config;\n\npub fn helper() {}\n\nfn hidden() {}\n")
+	write("src/b.rs", "pub struct Config;\n\npub fn other() {}\n\npub fn hidden() {}\n")
+	write("src/user.rs", `use crate::a::*;
+use super::b::*;
+use crate::b::other;
+
+pub fn helper() {}
+
+pub fn run(x: u32) -> u32 {
+    let x = x + 1;
+    x
+}
+`)
+	user := filepath.Join(root, "src", "user.rs")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	shadows, err := e.Query().Shadows(nil, "")
+	require.NoError(t, err)
+	byKind := map[string]Shadow{}
+	for _, s := range shadows {
+		assert.Equal(t, user, s.File)
+		_, dup := byKind[s.Kind]
+		require.False(t, dup, "one %s shadow expected, got %+v", s.Kind, shadows)
+		byKind[s.Kind] = s
+	}
+	// a::hidden is private to a, and other is imported from b both ways.
+	require.Len(t, byKind, 3, "%+v", shadows)
+
+	reuse := byKind[ShadowKindReuse]
+	assert.Equal(t, "x", reuse.Name)
+	require.NotNil(t, reuse.Function)
+	assert.Equal(t, "run", reuse.Function.Name)
+	assert.Equal(t, Location{File: user, StartLine: 7, StartCol: 8, EndLine: 7, EndCol: 9}, reuse.Shadowing.Location)
+	assert.Equal(t, Location{File: user, StartLine: 6, StartCol: 11, EndLine: 6, EndCol: 12}, reuse.Shadowed.Location)
+
+	collision := byKind[ShadowKindGlobCollision]
+	assert.Equal(t, "Config", collision.Name)
+	assert.Equal(t, "super::b::*", collision.Shadowing.Import)
+	require.NotNil(t, collision.Shadowing.Symbol)
+	assert.Equal(t, filepath.Join(root, "src", "b.rs"), collision.Shadowing.Location.File)
+	assert.Equal(t, "crate::a::*", collision.Shadowed.Import)
+	require.NotNil(t, collision.Shadowed.Symbol)
+	assert.Equal(t, filepath.Join(root, "src", "a.rs"), collision.Shadowed.Symbol.FilePath)
+
+	glob := byKind[ShadowKindGlob]
+	assert.Equal(t, "helper", glob.Name)
+	require.NotNil(t, glob.Shadowing.Symbol)
+	assert.Equal(t, user, glob.Shadowing.Symbol.FilePath)
+	assert.Empty(t, glob.Shadowing.Import)
+	assert.Equal(t, "crate::a::*", glob.Shadowed.Import)
+	assert.Equal(t, 2, glob.Shadowed.Location.StartLine)
+
+	only, err := e.Query().Shadows([]string{ShadowKindGlob}, "")
+	require.NoError(t, err)
+	require.Len(t, only, 1)
+	assert.Equal(t, ShadowKindGlob, only[0].Kind)
+
+	none, err := e.Query().Shadows(nil, filepath.Join(root, "src", "a.rs"))
+	require.NoError(t, err)
+	assert.Empty(t, none)
+
+	_, err = e.Query().Shadows([]string{"typo"}, "")
+	assert.Error(t, err)
+}
@@ -0,0 +1,264 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// SignaturePattern is a parsed function signature shape such as
+// "(&str, usize) -> String". Receivers (self, Go method receivers) are not
+// part of the shape.
+type SignaturePattern struct {
+	Params     []string // parameter types, in order
+	AnyParams  bool     // no parameter list given: any parameters match
+	RestParams bool     // the list ended with "..": further parameters match
+	Returns    []string // return types; empty for "-> ()"
+	AnyReturns bool     // no "->" given: any return types match
+}
+
+// ParseSignaturePattern parses a signature shape: an optional parenthesized
+// parameter type list followed by an optional "-> R". A multi-value return is
+// written as a tuple, "-> (string, error)". The type "_" matches any type,
+// also nested ("Vec<_>"), and a trailing ".." in the parameter list matches
+// any further parameters. Whitespace in types is insignificant.
+func ParseSignaturePattern(sig string) (*SignaturePattern, error) {
+	rest := strings.TrimSpace(sig)
+	p := &SignaturePattern{}
+	if strings.HasPrefix(rest, "(") {
+		end := matchingParen(rest)
+		if end < 0 {
+			return nil, fmt.Errorf("signature %q: unbalanced parentheses", sig)
+		}
+		p.Params = splitTypeList(rest[1:end])
+		if n := len(p.Params); n > 0 && (p.Params[n-1] == ".." || p.Params[n-1] == "...") {
+			p.Params, p.RestParams = p.Params[:n-1], true
+		}
+		rest = strings.TrimSpace(rest[end+1:])
+	} else {
+		p.AnyParams = true
+	}
+
+	switch {
+	case rest == "":
+		p.AnyReturns = true
+	case strings.HasPrefix(rest, "->"):
+		ret := strings.TrimSpace(rest[2:])
+		if ret == "" {
+			return nil, fmt.Errorf("signature %q: missing return type after ->", sig)
+		}
+		p.Returns = returnTypeList([]string{ret})
+	default:
+		return nil, fmt.Errorf("signature %q: expected \"(types) -> type\"", sig)
+	}
+	if p.AnyParams && p.AnyReturns {
+		return nil, fmt.Errorf("signature %q: expected \"(types) -> type\"", sig)
+	}
+	for _, t := range append(append([]string{}, p.Params...), p.Returns...) {
+		if t == "" {
+			return nil, fmt.Errorf("signature %q: empty type", sig)
+		}
+	}
+	return p, nil
+}
+
+// matches reports whether a function's non-receiver parameter types and
+// return types fit the pattern.
+func (p *SignaturePattern) matches(params, returns []string) bool {
+	if !p.AnyParams {
+		if len(params) < len(p.Params) || (!p.RestParams && len(params) != len(p.Params)) {
+			return false
+		}
+		for i, t := range p.Params {
+			if !typeMatches(t, params[i]) {
+				return false
+			}
+		}
+	}
+	if !p.AnyReturns {
+		returns = returnTypeList(returns)
+		if len(returns) != len(p.Returns) {
+			return false
+		}
+		for i, t := range p.Returns {
+			if !typeMatches(t, returns[i]) {
+				return false
+			}
+		}
+	}
+	return true
+}
+
+// returnTypeList normalizes a function's return types to a flat list: a
+// single tuple type (Rust "(i32, bool)", Go "(int, error)") is split into its
+// elements, and the unit type "()" becomes no types, so both languages'
+// multi-value and empty returns compare alike.
+func returnTypeList(types []string) []string {
+	if len(types) == 1 {
+		t := strings.TrimSpace(types[0])
+		if strings.HasPrefix(t, "(") && matchingParen(t) == len(t)-1 {
+			return splitTypeList(t[1 : len(t)-1])
+		}
+	}
+	return types
+}
+
+// matchingParen returns the index of the parenthesis closing s[0], or -1.
+func matchingParen(s string) int {
+	depth := 0
+	for i, r := range s {
+		switch r {
+		case '(':
+			depth++
+		case ')':
+			depth--
+			if depth == 0 {
+				return i
+			}
+		}
+	}
+	return -1
+}
+
+// splitTypeList splits a comma-separated type list at top-level commas
+// (outside any brackets). An empty or blank list yields no types.
+func splitTypeList(s string) []string {
+	if strings.TrimSpace(s) == "" {
+		return nil
+	}
+	var types []string
+	depth, start := 0, 0
+	for i, r := range s {
+		switch r {
+		case '(', '[', '{', '<':
+			depth++
+		case ')', ']', '}', '>':
+			// The '>' of "->" inside fn types does not close a bracket.
+			if r == '>' && i > 0 && s[i-1] == '-' {
+				continue
+			}
+			depth--
+		case ',':
+			if depth == 0 {
+				types = append(types, strings.TrimSpace(s[start:i]))
+				start = i + 1
+			}
+		}
+	}
+	return append(types, strings.TrimSpace(s[start:]))
+}
+
+// normalizeTypeExpr collapses whitespace in a type expression, keeping a
+// single space only between two word characters ("&mut str", "impl Fn").
+func normalizeTypeExpr(t string) string {
+	fields := strings.Fields(t)
+	var b strings.Builder
+	for i, f := range fields {
+		if i > 0 && isTypeWordByte(fields[i-1][len(fields[i-1])-1]) && isTypeWordByte(f[0]) {
+			b.WriteByte(' ')
+		}
+		b.WriteString(f)
+	}
+	return b.String()
+}
+
+// isTypeWordByte reports whether c can be part of an identifier or lifetime.
+func isTypeWordByte(c byte) bool {
+	return c == '_' || c == '\'' || c >= '0' && c <= '9' || c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z' || c >= 0x80
+}
+
+// typeMatches reports whether a type expression fits a pattern type, where
+// a standalone "_" stands for any type. Lifetimes in the type are ignored
+// unless the pattern spells out lifetimes itself.
+func typeMatches(pattern, typ string) bool {
+	if !strings.Contains(pattern, "'") {
+		typ = store.ElideLifetimes(typ)
+	}
+	pattern, typ = normalizeTypeExpr(pattern), normalizeTypeExpr(typ)
+	if pattern == "_" {
+		return true
+	}
+	if !strings.Contains(pattern, "_") {
+		return pattern == typ
+	}
+	var re strings.Builder
+	re.WriteString("^")
+	for i := 0; i < len(pattern); i++ {
+		standalone := pattern[i] == '_' &&
+			(i == 0 || !isTypeWordByte(pattern[i-1])) &&
+			(i == len(pattern)-1 || !isTypeWordByte(pattern[i+1]))
+		if standalone {
+			re.WriteString(".+")
+		} else {
+			re.WriteString(regexp.QuoteMeta(pattern[i : i+1]))
+		}
+	}
+	re.WriteString("$")
+	ok, err := regexp.MatchString(re.String(), typ)
+	return err == nil && ok
+}
+
+// SymbolsBySignature finds functions and methods whose parameter and return
+// types fit a signature shape (see ParseSignaturePattern), e.g.
+// "(&str) -> String" or "(context.Context, ..) -> (_, error)". Types are
+// compared as written in the source, not resolved. The filter, sort, and
+// pagination apply as in Symbols.
+func (q *QueryBuilder) SymbolsBySignature(signature string, filter SymbolFilter, sort Sort, page Pagination) (*PagedResult[SymbolResult], error) {
+	pattern, err := ParseSignaturePattern(signature)
+	if err != nil {
+		return nil, fmt.Errorf("symbols by signature: %w", err)
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT s.id, p.type_expr, COALESCE(p.is_receiver, 0), COALESCE(p.is_return, 0)
+		 FROM symbols s
+		 LEFT JOIN function_parameters p ON p.symbol_id = s.id
+		 WHERE s.kind IN ('function', 'method') OR s.id IN (SELECT symbol_id FROM function_parameters)
+		 ORDER BY s.id, p.is_return, p.ordinal`)
+	if err != nil {
+		return nil, fmt.Errorf("symbols by signature: query parameters: %w", err)
+	}
+	var ids []int64
+	var curID int64 = -1
+	var params, returns []string
+	flush := func() {
+		if curID >= 0 && pattern.matches(params, returns) {
+			ids = append(ids, curID)
+		}
+		params, returns = nil, nil
+	}
+	for rows.Next() {
+		var id int64
+		var typeExpr *string
+		var isReceiver, isReturn bool
+		if err := rows.Scan(&id, &typeExpr, &isReceiver, &isReturn); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("symbols by signature: scan: %w", err)
+		}
+		if id != curID {
+			flush()
+			curID = id
+		}
+		if typeExpr == nil || isReceiver {
+			continue
+		}
+		if isReturn {
+			returns = append(returns, *typeExpr)
+		} else {
+			params = append(params, *typeExpr)
+		}
+	}
+	flush()
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("symbols by signature: rows: %w", err)
+	}
+
+	if len(ids) == 0 {
+		return &PagedResult[SymbolResult]{Items: []SymbolResult{}}, nil
+	}
+	filter.IDs = ids
+	return q.symbols(filter, sort, page)
+}
@@ -0,0 +1,150 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseSignaturePattern(t *testing.T) {
+	t.Parallel()
+	p, err := ParseSignaturePattern("(&str, HashMap<String, u32>) -> String")
+	require.NoError(t, err)
+	assert.Equal(t, []string{"&str", "HashMap<String, u32>"}, p.Params)
+	assert.Equal(t, []string{"String"}, p.Returns)
+	assert.False(t, p.AnyParams)
+	assert.False(t, p.AnyReturns)
+
+	p, err = ParseSignaturePattern("(ctx context.Context, ..) -> (_, error)")
+	require.NoError(t, err)
+	assert.True(t, p.RestParams)
+	assert.Equal(t, []string{"_", "error"}, p.Returns)
+
+	p, err = ParseSignaturePattern("-> ()")
+	require.NoError(t, err)
+	assert.True(t, p.AnyParams)
+	assert.Empty(t, p.Returns)
+	assert.False(t, p.AnyReturns)
+
+	p, err = ParseSignaturePattern("()")
+	require.NoError(t, err)
+	assert.Empty(t, p.Params)
+	assert.True(t, p.AnyReturns)
+
+	for _, bad := range []string{"", "String", "(&str", "(&str) String", "(&str) ->", "(a, , b)"} {
+		_, err := ParseSignaturePattern(bad)
+		assert.Error(t, err, bad)
+	}
+}
+
+func TestSignaturePattern_Matches(t *testing.T) {
+	t.Parallel()
+	match := func(sig string, params, returns []string) bool {
+		p, err := ParseSignaturePattern(sig)
+		require.NoError(t, err, sig)
+		return p.matches(params, returns)
+	}
+
+	assert.True(t, match("(&str) -> String", []string{"& str"}, []string{"String"}), "whitespace is ignored")
+	assert.False(t, match("(&str) -> String", []string{"&mut str"}, []string{"String"}))
+	assert.False(t, match("(&str) -> String", []string{"&str", "usize"}, []string{"String"}))
+	assert.True(t, match("(&str, ..)", []string{"&str", "usize"}, nil))
+	assert.True(t, match("(_) -> Vec<_>", []string{"u8"}, []string{"Vec<Option<u8>>"}))
+	assert.False(t, match("(_) -> Vec<_>", []string{"u8"}, []string{"HashSet<u8>"}))
+	assert.True(t, match("() -> (int, error)", nil, []string{"int", "error"}), "Go results")
+	assert.True(t, match("() -> (i32, bool)", nil, []string{"(i32, bool)"}), "Rust tuple return")
+	assert.True(t, match("-> ()", []string{"u8"}, nil))
+	assert.True(t, match("-> ()", nil, []string{"()"}))
+	assert.False(t, match("-> ()", nil, []string{"u8"}))
+	assert.True(t, match("(&str) -> &str", []string{"&'a str"}, []string{"&'a str"}), "lifetimes are elided")
+	assert.True(t, match("(Formatter) -> _", []string{"Formatter<'_>"}, []string{"fmt::Result"}))
+	assert.False(t, match("(&'static str)", []string{"&str"}, nil), "explicit lifetimes in the pattern must match")
+	assert.True(t, match("(my_type)", []string{"my_type"}, nil), "underscores inside names are literal")
+	assert.False(t, match("(my_type)", []string{"myXtype"}, nil))
+}
+
+func TestSymbolsBySignature_Rust(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`pub fn greet(name: &str) -> String {
+    format!("hi {}", name)
+}
+
+pub fn shout(name: &str) -> String {
+    name.to_uppercase()
+}
+
+pub fn count(name: &str) -> usize {
+    name.len()
+}
+
+pub fn log(msg: &str) {}
+
+pub struct Greeter;
+
+impl Greeter {
+    pub fn render(&self, name: &str) -> String {
+        name.to_string()
+    }
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	names := func(sig string, filter SymbolFilter) []string {
+		res, err := e.Query().SymbolsBySignature(sig, filter, Sort{Field: SortByName}, Pagination{})
+		require.NoError(t, err)
+		var out []string
+		for _, sr := range res.Items {
+			out = append(out, sr.Name)
+		}
+		assert.Equal(t, len(out), res.TotalCount)
+		return out
+	}
+
+	assert.Equal(t, []string{"greet", "render", "shout"}, names("(&str) -> String", SymbolFilter{}),
+		"the self receiver is not part of the shape")
+	assert.Equal(t, []string{"render"}, names("(&str) -> String", SymbolFilter{Kinds: []string{"method"}}))
+	assert.Equal(t, []string{"count"}, names("(_) -> usize", SymbolFilter{}))
+	assert.Equal(t, []string{"log"}, names("(&str) -> ()", SymbolFilter{}))
+	assert.Empty(t, names("(String) -> String", SymbolFilter{}))
+
+	_, err := e.Query().SymbolsBySignature("&str", SymbolFilter{}, Sort{}, Pagination{})
+	assert.Error(t, err)
+}
+
+func TestSymbolsBySignature_GoResults(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	path := writeGoFile(t, t.TempDir(), "main.go", `package main
+
+type Store struct{}
+
+func (s *Store) Get(key string) (string, error) { return key, nil }
+
+func Parse(a, b string) (int, error) { return 0, nil }
+
+func Len(s string) int { return len(s) }
+`)
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	res, err := e.Query().SymbolsBySignature("(string, ..) -> (_, error)", SymbolFilter{}, Sort{Field: SortByName}, Pagination{})
+	require.NoError(t, err)
+	var got []string
+	for _, sr := range res.Items {
+		got = append(got, sr.Name)
+	}
+	assert.Equal(t, []string{"Get", "Parse"}, got)
+
+	res, err = e.Query().SymbolsBySignature("(string, string) -> _", SymbolFilter{}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, res.Items, 0, "a multi-value result does not fit a single type")
+}
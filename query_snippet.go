@@ -0,0 +1,208 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// SnippetContext selects the neighbors a Snippet includes: callers and
+// callees up to that many calls away, 0 for none.
+type SnippetContext struct {
+	Callers int
+	Callees int
+}
+
+// SnippetNeighbor is a caller or callee of a snippet's symbol.
+type SnippetNeighbor struct {
+	Symbol    SymbolResult
+	File      string // path relative to the indexed root
+	Signature string // declaration header, e.g. "pub fn area(&self) -> f64"
+	Depth     int    // calls between it and the snippet's symbol
+}
+
+// Snippet is a symbol's source, with the signatures of its callers and
+// callees for context.
+type Snippet struct {
+	Symbol    SymbolResult
+	File      string // path relative to the indexed root
+	Language  string
+	StartLine int    // 0-based first line of Source
+	Source    string // the declaration with its doc comment and attributes, dedented
+	Callers   []SnippetNeighbor
+	Callees   []SnippetNeighbor
+}
+
+// Snippet returns the source of a symbol, starting at the doc comment and
+// attributes directly above it, plus the signatures of its callers and
+// callees up to the depths in sc, nearest first. Returns nil, nil if the
+// symbol does not exist, and an error if a depth is negative or the
+// symbol's file cannot be read.
+func (q *QueryBuilder) Snippet(symbolID int64, sc SnippetContext) (*Snippet, error) {
+	if sc.Callers < 0 || sc.Callees < 0 {
+		return nil, fmt.Errorf("snippet: context depths must be non-negative, got callers:%d callees:%d", sc.Callers, sc.Callees)
+	}
+	sr, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("snippet: %w", err)
+	}
+	if sr == nil {
+		return nil, nil
+	}
+
+	files := map[string]*snippetFile{}
+	sf, err := q.snippetFile(files, sr.FilePath)
+	if err != nil {
+		return nil, fmt.Errorf("snippet: %w", err)
+	}
+	start := leadingCommentStart(sf.li, sr.StartLine, hashCommentLanguages[sf.language])
+	from, _ := sf.li.offsets(start, 0)
+	to, _ := sf.li.offsets(sr.EndLine, sr.EndCol)
+	s := &Snippet{
+		Symbol:    *sr,
+		File:      sf.rel,
+		Language:  sf.language,
+		StartLine: start,
+		Source:    dedent(string(sf.li.content[from:to])),
+	}
+
+	neighbors := func(g *CallGraph) ([]SnippetNeighbor, error) {
+		var out []SnippetNeighbor
+		for _, n := range g.Nodes {
+			if n.Depth == 0 {
+				continue
+			}
+			nf, err := q.snippetFile(files, n.Symbol.FilePath)
+			if err != nil {
+				return nil, err
+			}
+			out = append(out, SnippetNeighbor{
+				Symbol:    n.Symbol,
+				File:      nf.rel,
+				Signature: symbolSignature(nf.li, &n.Symbol.Symbol),
+				Depth:     n.Depth,
+			})
+		}
+		sort.Slice(out, func(i, j int) bool {
+			if out[i].Depth != out[j].Depth {
+				return out[i].Depth < out[j].Depth
+			}
+			if out[i].File != out[j].File {
+				return out[i].File < out[j].File
+			}
+			if out[i].Symbol.StartLine != out[j].Symbol.StartLine {
+				return out[i].Symbol.StartLine < out[j].Symbol.StartLine
+			}
+			return out[i].Symbol.StartCol < out[j].Symbol.StartCol
+		})
+		return out, nil
+	}
+	if sc.Callers > 0 {
+		g, err := q.TransitiveCallers(symbolID, sc.Callers)
+		if err != nil {
+			return nil, fmt.Errorf("snippet: %w", err)
+		}
+		if s.Callers, err = neighbors(g); err != nil {
+			return nil, fmt.Errorf("snippet: callers: %w", err)
+		}
+	}
+	if sc.Callees > 0 {
+		g, err := q.TransitiveCallees(symbolID, sc.Callees)
+		if err != nil {
+			return nil, fmt.Errorf("snippet: %w", err)
+		}
+		if s.Callees, err = neighbors(g); err != nil {
+			return nil, fmt.Errorf("snippet: callees: %w", err)
+		}
+	}
+	return s, nil
+}
+
+// snippetFile is a source file read for a snippet.
+type snippetFile struct {
+	rel      string
+	language string
+	li       *lineIndex
+}
+
+// snippetFile reads the file at path, caching it in files.
+func (q *QueryBuilder) snippetFile(files map[string]*snippetFile, path string) (*snippetFile, error) {
+	if sf, ok := files[path]; ok {
+		return sf, nil
+	}
+	f, err := q.store.FileByPath(path)
+	if err != nil {
+		return nil, err
+	}
+	if f == nil {
+		return nil, fmt.Errorf("file not indexed: %q", path)
+	}
+	src, err := q.readSource(path)
+	if err != nil {
+		return nil, err
+	}
+	sf := &snippetFile{rel: relativeFilePath(f.Path, f.Root), language: f.Language, li: newLineIndex(src)}
+	files[path] = sf
+	return sf, nil
+}
+
+// Markdown renders the snippet for pasting into a review or a prompt: a
+// heading with the symbol's location, its source in a fenced code block,
+// then its callers' and callees' signatures, each under a comment with its
+// location.
+func (s *Snippet) Markdown() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "### %s %s (%s:%d-%d)\n\n", s.Symbol.Kind, s.Symbol.Name, s.File, s.StartLine+1, s.Symbol.EndLine+1)
+	fmt.Fprintf(&b, "```%s\n%s\n```\n", s.Language, strings.TrimRight(s.Source, "\n"))
+
+	comment := "//"
+	if hashCommentLanguages[s.Language] {
+		comment = "#"
+	}
+	section := func(title string, ns []SnippetNeighbor) {
+		if len(ns) == 0 {
+			return
+		}
+		fmt.Fprintf(&b, "\n%s:\n\n```%s\n", title, s.Language)
+		for i, n := range ns {
+			if i > 0 {
+				b.WriteString("\n")
+			}
+			fmt.Fprintf(&b, "%s %s:%d", comment, n.File, n.Symbol.StartLine+1)
+			if n.Depth > 1 {
+				fmt.Fprintf(&b, " (%d calls away)", n.Depth)
+			}
+			fmt.Fprintf(&b, "\n%s\n", n.Signature)
+		}
+		b.WriteString("```\n")
+	}
+	section("Called by", s.Callers)
+	section("Calls", s.Callees)
+	return b.String()
+}
+
+// dedent removes the indentation common to the non-blank lines of text.
+func dedent(text string) string {
+	lines := strings.Split(text, "\n")
+	indent := -1
+	for _, l := range lines {
+		if strings.TrimSpace(l) == "" {
+			continue
+		}
+		n := len(l) - len(strings.TrimLeft(l, " \t"))
+		if indent < 0 || n < indent {
+			indent = n
+		}
+	}
+	if indent <= 0 {
+		return text
+	}
+	for i, l := range lines {
+		if len(l) >= indent {
+			lines[i] = l[indent:]
+		} else {
+			lines[i] = strings.TrimLeft(l, " \t")
+		}
+	}
+	return strings.Join(lines, "\n")
+}
@@ -0,0 +1,86 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestDedent(t *testing.T) {
+	assert.Equal(t, "fn a() {\n    b();\n\n}", dedent("    fn a() {\n        b();\n\n    }"))
+	assert.Equal(t, "fn a() {}", dedent("fn a() {}"))
+}
+
+func TestSnippet(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "lib.rs"), []byte(`pub struct Circle {
+    pub r: f64,
+}
+
+impl Circle {
+    /// Area of the circle.
+    #[inline]
+    pub fn area(&self) -> f64 {
+        square(self.r) * 3.14
+    }
+}
+
+fn square(x: f64) -> f64 {
+    x * x
+}
+
+pub fn report(c: &Circle) -> f64 {
+    c.area()
+}
+
+pub fn run() {
+    report(&Circle { r: 1.0 });
+}
+`), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	syms, err := e.store.SymbolsByName("area")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	area := syms[0].ID
+
+	s, err := e.Query().Snippet(area, SnippetContext{})
+	require.NoError(t, err)
+	require.NotNil(t, s)
+	assert.Equal(t, "src/lib.rs", s.File)
+	assert.Equal(t, "rust", s.Language)
+	assert.Equal(t, 5, s.StartLine)
+	assert.Equal(t, "/// Area of the circle.\n#[inline]\npub fn area(&self) -> f64 {\n    square(self.r) * 3.14\n}", s.Source)
+	assert.Empty(t, s.Callers)
+	assert.Empty(t, s.Callees)
+
+	s, err = e.Query().Snippet(area, SnippetContext{Callers: 2, Callees: 1})
+	require.NoError(t, err)
+	require.Len(t, s.Callers, 2)
+	assert.Equal(t, "pub fn report(c: &Circle) -> f64", s.Callers[0].Signature)
+	assert.Equal(t, 1, s.Callers[0].Depth)
+	assert.Equal(t, "pub fn run()", s.Callers[1].Signature)
+	assert.Equal(t, 2, s.Callers[1].Depth)
+	require.Len(t, s.Callees, 1)
+	assert.Equal(t, "fn square(x: f64) -> f64", s.Callees[0].Signature)
+
+	md := s.Markdown()
+	assert.Contains(t, md, "### method area (src/lib.rs:6-10)\n\n```rust\n/// Area of the circle.\n")
+	assert.Contains(t, md, "Called by:\n\n```rust\n// src/lib.rs:17\npub fn report(c: &Circle) -> f64\n\n// src/lib.rs:21 (2 calls away)\npub fn run()\n```\n")
+	assert.Contains(t, md, "Calls:\n\n```rust\n// src/lib.rs:13\nfn square(x: f64) -> f64\n```\n")
+
+	_, err = e.Query().Snippet(area, SnippetContext{Callers: -1})
+	assert.Error(t, err)
+	s, err = e.Query().Snippet(999999, SnippetContext{})
+	require.NoError(t, err)
+	assert.Nil(t, s)
+}
@@ -0,0 +1,94 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+)
+
+// SQLTableUse is a function whose embedded SQL names a table.
+type SQLTableUse struct {
+	Function SymbolResult
+	Columns  []string   // the table's columns it names, sorted
+	Sites    []Location // each mention of the table or one of its columns, in order
+}
+
+// FunctionsTouchingTable returns the functions and methods whose embedded
+// SQL names table or one of its columns, ordered by file then line. Table
+// names compare case-insensitively. Requires an index built with
+// WithSQLReferences; mentions outside any function are not reported.
+func (q *QueryBuilder) FunctionsTouchingTable(table string) ([]SQLTableUse, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT f.path, r.start_line, r.start_col, r.end_line, r.end_col,
+			r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col,
+			CASE WHEN t.kind = 'column' THEN t.name ELSE '' END,
+			(SELECT b.id FROM symbols b
+				WHERE b.file_id = r.file_id AND b.kind IN ('function', 'method')
+				AND (b.start_line < r.start_line OR (b.start_line = r.start_line AND b.start_col <= r.start_col))
+				AND (b.end_line > r.end_line OR (b.end_line = r.end_line AND b.end_col >= r.end_col))
+				ORDER BY b.start_line DESC, b.start_col DESC LIMIT 1)
+		 FROM resolved_references rr
+		 JOIN references_ r ON r.id = rr.reference_id
+		 JOIN files f ON f.id = r.file_id
+		 JOIN symbols t ON t.id = rr.target_symbol_id
+		 LEFT JOIN symbols p ON p.id = t.parent_symbol_id
+		 WHERE r.context = ?
+			AND LOWER(CASE WHEN t.kind = 'column' THEN p.name ELSE t.name END) = LOWER(?)
+		 ORDER BY f.path, r.start_line, r.start_col`,
+		RefKindSQL, table)
+	if err != nil {
+		return nil, fmt.Errorf("functions touching table: %w", err)
+	}
+	defer rows.Close()
+
+	type use struct {
+		columns map[string]bool
+		sites   []Location
+	}
+	uses := map[int64]*use{}
+	var ids []int64
+	for rows.Next() {
+		var loc Location
+		var column string
+		var fnID *int64
+		if err := rows.Scan(&loc.File, &loc.StartLine, &loc.StartCol, &loc.EndLine, &loc.EndCol,
+			&loc.StartByte, &loc.EndByte, &loc.UTF16StartCol, &loc.UTF16EndCol, &column, &fnID); err != nil {
+			return nil, fmt.Errorf("functions touching table: scan: %w", err)
+		}
+		if fnID == nil {
+			continue
+		}
+		u, ok := uses[*fnID]
+		if !ok {
+			u = &use{columns: map[string]bool{}}
+			uses[*fnID] = u
+			ids = append(ids, *fnID)
+		}
+		if column != "" {
+			u.columns[column] = true
+		}
+		u.sites = append(u.sites, loc)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("functions touching table: %w", err)
+	}
+
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("functions touching table: load symbols: %w", err)
+	}
+	result := []SQLTableUse{}
+	for _, id := range ids {
+		fn, ok := symbols[id]
+		if !ok {
+			continue
+		}
+		u := uses[id]
+		columns := make([]string, 0, len(u.columns))
+		for c := range u.columns {
+			columns = append(columns, c)
+		}
+		sort.Strings(columns)
+		result = append(result, SQLTableUse{Function: *fn, Columns: columns, Sites: u.sites})
+	}
+	return result, nil
+}
@@ -0,0 +1,130 @@
+package canopy
+
+import (
+	"encoding/json"
+	"fmt"
+	"time"
+)
+
+// IndexStats measures the contents and quality of the index.
+type IndexStats struct {
+	Files           int
+	FilesByLanguage map[string]int
+	Symbols         int
+	SymbolsByKind   map[string]int
+	References      int
+	Resolved        int            // references bound to a symbol
+	ResolutionRate  float64        // Resolved / References; 1 for an index without references
+	EdgesByKind     map[string]int // resolution edges: "reference", "call", "implementation", ...
+	ParseErrors     int            // syntax errors across all files
+	FilesWithErrors int            // files with at least one syntax error
+	DegradedFiles   map[string]int // files the fallback scanner indexed, by Degraded* reason
+	FallbackSymbols int            // symbols found by the fallback scanner
+	DatabaseBytes   int64          // size of the database, excluding the write-ahead log
+
+	// IndexedAt and Timings describe the latest run recorded with
+	// RecordIndexTimings; zero if none was.
+	IndexedAt time.Time
+	Timings   []IndexTiming
+}
+
+// statsEdgeTables maps each edge kind IndexStats counts to its table.
+var statsEdgeTables = []struct{ kind, table string }{
+	{"reference", "resolved_references"},
+	{"call", "call_graph"},
+	{"implementation", "implementations"},
+	{"reexport", "reexports"},
+	{"extension", "extension_bindings"},
+	{"composition", "type_compositions"},
+}
+
+// IndexStats returns counts of the index's files, symbols, references, and
+// edges, how many references resolved, its syntax errors, how often the
+// fallback scanner was used, its size on disk, and the timings of the
+// latest recorded index run.
+func (q *QueryBuilder) IndexStats() (*IndexStats, error) {
+	db := q.store.DB()
+	stats := &IndexStats{}
+
+	grouped := func(query string) (map[string]int, int, error) {
+		rows, err := db.Query(query)
+		if err != nil {
+			return nil, 0, err
+		}
+		defer rows.Close()
+		counts := map[string]int{}
+		total := 0
+		for rows.Next() {
+			var key string
+			var n int
+			if err := rows.Scan(&key, &n); err != nil {
+				return nil, 0, err
+			}
+			counts[key] = n
+			total += n
+		}
+		return counts, total, rows.Err()
+	}
+	var err error
+	if stats.FilesByLanguage, stats.Files, err = grouped("SELECT language, COUNT(*) FROM files GROUP BY language"); err != nil {
+		return nil, fmt.Errorf("index stats: files: %w", err)
+	}
+	if stats.SymbolsByKind, stats.Symbols, err = grouped("SELECT kind, COUNT(*) FROM symbols GROUP BY kind"); err != nil {
+		return nil, fmt.Errorf("index stats: symbols: %w", err)
+	}
+	if stats.DegradedFiles, _, err = grouped("SELECT reason, COUNT(*) FROM degraded_files GROUP BY reason"); err != nil {
+		return nil, fmt.Errorf("index stats: degraded files: %w", err)
+	}
+	if err := db.QueryRow("SELECT COALESCE(SUM(symbols), 0) FROM degraded_files").Scan(&stats.FallbackSymbols); err != nil {
+		return nil, fmt.Errorf("index stats: fallback symbols: %w", err)
+	}
+
+	for _, c := range []struct {
+		query string
+		dest  *int
+	}{
+		{"SELECT COUNT(*) FROM references_", &stats.References},
+		{"SELECT COUNT(DISTINCT reference_id) FROM resolved_references", &stats.Resolved},
+		{"SELECT COUNT(*) FROM syntax_errors", &stats.ParseErrors},
+		{"SELECT COUNT(DISTINCT file_id) FROM syntax_errors", &stats.FilesWithErrors},
+	} {
+		if err := db.QueryRow(c.query).Scan(c.dest); err != nil {
+			return nil, fmt.Errorf("index stats: count: %w", err)
+		}
+	}
+	stats.ResolutionRate = 1
+	if stats.References > 0 {
+		stats.ResolutionRate = float64(stats.Resolved) / float64(stats.References)
+	}
+
+	stats.EdgesByKind = map[string]int{}
+	for _, e := range statsEdgeTables {
+		var n int
+		if err := db.QueryRow("SELECT COUNT(*) FROM " + e.table).Scan(&n); err != nil {
+			return nil, fmt.Errorf("index stats: %s edges: %w", e.kind, err)
+		}
+		stats.EdgesByKind[e.kind] = n
+	}
+
+	var pages, pageSize int64
+	if err := db.QueryRow("PRAGMA page_count").Scan(&pages); err != nil {
+		return nil, fmt.Errorf("index stats: page count: %w", err)
+	}
+	if err := db.QueryRow("PRAGMA page_size").Scan(&pageSize); err != nil {
+		return nil, fmt.Errorf("index stats: page size: %w", err)
+	}
+	stats.DatabaseBytes = pages * pageSize
+
+	value, err := q.store.GetMetadata(indexRunKey)
+	if err != nil {
+		return nil, fmt.Errorf("index stats: %w", err)
+	}
+	if value != "" {
+		var run indexRun
+		if err := json.Unmarshal([]byte(value), &run); err != nil {
+			return nil, fmt.Errorf("index stats: index run: %w", err)
+		}
+		stats.IndexedAt, stats.Timings = run.Finished, run.Timings
+	}
+	return stats, nil
+}
@@ -0,0 +1,55 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestIndexStats(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	dir := t.TempDir()
+	mainPath := writeGoFile(t, dir, "main.go", `package main
+
+func helper() int { return 1 }
+
+func main() {
+	helper()
+	missing()
+}
+`)
+	brokenPath := writeGoFile(t, dir, "broken.go", `package main
+
+func Broken() {
+	x := 
+}
+`)
+	require.NoError(t, e.IndexFiles(ctx, []string{mainPath, brokenPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	stats, err := e.Query().IndexStats()
+	require.NoError(t, err)
+	assert.Equal(t, 2, stats.Files)
+	assert.Equal(t, map[string]int{"go": 2}, stats.FilesByLanguage)
+	assert.Equal(t, 3, stats.SymbolsByKind["function"], "helper, main, and Broken")
+	assert.Positive(t, stats.References)
+	assert.Less(t, stats.Resolved, stats.References, "missing() does not resolve")
+	assert.InDelta(t, float64(stats.Resolved)/float64(stats.References), stats.ResolutionRate, 1e-9)
+	assert.Positive(t, stats.EdgesByKind["call"])
+	assert.Positive(t, stats.ParseErrors)
+	assert.Equal(t, 1, stats.FilesWithErrors)
+	assert.Positive(t, stats.DatabaseBytes)
+	assert.True(t, stats.IndexedAt.IsZero(), "no run recorded")
+	assert.Empty(t, stats.Timings)
+
+	timings := []IndexTiming{{Phase: "extract", Duration: 2 * time.Second}, {Phase: "resolve", Duration: time.Second}}
+	require.NoError(t, e.RecordIndexTimings(timings))
+	stats, err = e.Query().IndexStats()
+	require.NoError(t, err)
+	assert.Equal(t, timings, stats.Timings)
+	assert.False(t, stats.IndexedAt.IsZero())
+}
@@ -0,0 +1,172 @@
+package canopy
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io/fs"
+	"slices"
+	"strings"
+
+	canopyrt "github.com/jward/canopy/internal/runtime"
+)
+
+// StructuralSearchOptions narrows a structural search and asks for
+// rewrites.
+type StructuralSearchOptions struct {
+	Language   string // the pattern's language; "" tries it in every indexed language it parses in
+	PathPrefix string // only files under this path
+	Rewrite    string // template each match is rewritten to, with the pattern's metavariables; "" for none
+}
+
+// StructuralMatch is a piece of code matching a structural pattern.
+type StructuralMatch struct {
+	Location
+	Language    string
+	Text        string            // the matched code
+	Bindings    map[string]string // text bound to each metavariable
+	Replacement string            // the match rewritten, with a Rewrite template
+}
+
+// StructuralSearch finds the code in indexed files matching pattern, a
+// snippet of code in which $NAME matches any one syntax node, $$$NAME any
+// run of sibling nodes such as a call's arguments, and $_ and $$$ match
+// without binding. A metavariable used twice must match the same text
+// both times. Code matches when its syntax tree has the pattern's shape:
+// whitespace, comments, and layout do not matter. "$X.unwrap()" matches
+// every unwrap call and binds the receiver to X. With a Rewrite template,
+// each match's Replacement is the template with its metavariables
+// substituted; StructuralEdits turns those into edits for ApplyEdits.
+// Files are read as the index sees them, in path order, and matches come
+// in source order, outer matches before those nested in them.
+func (q *QueryBuilder) StructuralSearch(ctx context.Context, pattern string, opts StructuralSearchOptions) ([]StructuralMatch, error) {
+	query := `SELECT path, language FROM files`
+	var where []string
+	var args []any
+	if opts.PathPrefix != "" {
+		where = append(where, `path LIKE ? ESCAPE '\'`)
+		args = append(args, escapeLike(normalizePathPrefix(opts.PathPrefix))+"%")
+	}
+	if opts.Language != "" {
+		where = append(where, `language = ?`)
+		args = append(args, opts.Language)
+	}
+	if len(where) > 0 {
+		query += " WHERE " + strings.Join(where, " AND ")
+	}
+	rows, err := q.store.DB().Query(query+` ORDER BY path`, args...)
+	if err != nil {
+		return nil, fmt.Errorf("structural search: %w", err)
+	}
+	type file struct{ path, lang string }
+	var files []file
+	for rows.Next() {
+		var f file
+		if err := rows.Scan(&f.path, &f.lang); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("structural search: %w", err)
+		}
+		files = append(files, f)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("structural search: %w", err)
+	}
+
+	// Compile the pattern once per language. A pattern that does not parse
+	// in a language has no matches there, unless it is the one asked for.
+	if opts.Language != "" {
+		if _, ok := canopyrt.ParserForLanguage(opts.Language); !ok {
+			return nil, fmt.Errorf("structural search: unsupported language %q", opts.Language)
+		}
+	}
+	patterns := map[string]*canopyrt.StructuralPattern{}
+	var metavars []string
+	var compileErr error
+	compiled := false
+	for _, f := range files {
+		if _, done := patterns[f.lang]; done {
+			continue
+		}
+		patterns[f.lang] = nil
+		if _, ok := canopyrt.ParserForLanguage(f.lang); !ok {
+			continue
+		}
+		p, err := canopyrt.CompilePattern(ctx, f.lang, pattern)
+		if err != nil {
+			compileErr = err
+			continue
+		}
+		patterns[f.lang] = p
+		metavars = p.Metavars
+		compiled = true
+	}
+	if compileErr != nil && (opts.Language != "" || !compiled) {
+		return nil, fmt.Errorf("structural search: %w", compileErr)
+	}
+	if opts.Rewrite != "" {
+		for _, name := range canopyrt.TemplateMetavars(opts.Rewrite) {
+			if !slices.Contains(metavars, name) {
+				return nil, fmt.Errorf("structural search: rewrite uses $%s, which the pattern does not bind", name)
+			}
+		}
+	}
+
+	var matches []StructuralMatch
+	for _, f := range files {
+		p := patterns[f.lang]
+		if p == nil {
+			continue
+		}
+		src, err := q.readSource(f.path)
+		if errors.Is(err, fs.ErrNotExist) {
+			continue
+		}
+		if err != nil {
+			return nil, fmt.Errorf("structural search: %w", err)
+		}
+		ms, err := p.Match(ctx, src)
+		if err != nil {
+			return nil, fmt.Errorf("structural search: %s: %w", f.path, err)
+		}
+		for _, m := range ms {
+			sm := StructuralMatch{
+				Location: Location{
+					File:      f.path,
+					StartLine: m.StartLine,
+					StartCol:  m.StartCol,
+					EndLine:   m.EndLine,
+					EndCol:    m.EndCol,
+					StartByte: m.StartByte,
+					EndByte:   m.EndByte,
+				},
+				Language: f.lang,
+				Text:     string(src[m.StartByte:m.EndByte]),
+				Bindings: m.Bindings,
+			}
+			if opts.Rewrite != "" {
+				sm.Replacement = canopyrt.Rewrite(opts.Rewrite, m.Bindings)
+			}
+			matches = append(matches, sm)
+		}
+	}
+	return matches, nil
+}
+
+// StructuralEdits returns the edits replacing each match with its
+// Replacement, for ApplyEdits. A match nested inside an earlier match of
+// the same file is left out, since the outer rewrite replaces it; run the
+// search again after applying to rewrite those too.
+func StructuralEdits(matches []StructuralMatch) []TextEdit {
+	var edits []TextEdit
+	var outer *StructuralMatch
+	for i := range matches {
+		m := &matches[i]
+		if outer != nil && outer.File == m.File && m.StartByte < outer.EndByte {
+			continue
+		}
+		outer = m
+		edits = append(edits, TextEdit{Location: m.Location, OldText: m.Text, NewText: m.Replacement})
+	}
+	return edits
+}
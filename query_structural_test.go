@@ -0,0 +1,62 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestStructuralSearch(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	lib := filepath.Join(root, "src", "lib.rs")
+	require.NoError(t, os.WriteFile(lib, []byte(`pub struct ProductService;
+
+impl ProductService {
+    pub fn new() -> Self { ProductService }
+    pub fn find(&self, id: u32) -> Option<u32> { Some(id) }
+}
+
+pub fn run() -> u32 {
+    let s = ProductService::new();
+    s.find(s.find(1).unwrap()).unwrap()
+}
+`), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "main.go"), []byte("package main\n\nfunc main() { println(1) }\n"), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust", "go"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	q := e.Query()
+
+	ms, err := q.StructuralSearch(ctx, "ProductService::new()", StructuralSearchOptions{})
+	require.NoError(t, err, "the pattern only parses as Rust, so Go files are skipped")
+	require.Len(t, ms, 1)
+	assert.Equal(t, lib, ms[0].File)
+	assert.Equal(t, 8, ms[0].StartLine)
+	assert.Equal(t, "rust", ms[0].Language)
+
+	ms, err = q.StructuralSearch(ctx, "$X.unwrap()", StructuralSearchOptions{Language: "rust", Rewrite: "$X.expect(\"found\")"})
+	require.NoError(t, err)
+	require.Len(t, ms, 2)
+	assert.Equal(t, "s.find(s.find(1).unwrap())", ms[0].Bindings["X"])
+	assert.Equal(t, "s.find(1)", ms[1].Bindings["X"], "nested in the first")
+	assert.Equal(t, "s.find(1).expect(\"found\")", ms[1].Replacement)
+
+	edits := StructuralEdits(ms)
+	require.Len(t, edits, 1, "the nested match is rewritten by a second pass")
+	_, err = ApplyEdits(edits)
+	require.NoError(t, err)
+	src, err := os.ReadFile(lib)
+	require.NoError(t, err)
+	assert.Contains(t, string(src), "    s.find(s.find(1).unwrap()).expect(\"found\")\n")
+
+	_, err = q.StructuralSearch(ctx, "$X.unwrap()", StructuralSearchOptions{Rewrite: "$Y"})
+	assert.ErrorContains(t, err, "$Y")
+	_, err = q.StructuralSearch(ctx, "fn (", StructuralSearchOptions{Language: "rust"})
+	assert.Error(t, err)
+}
@@ -0,0 +1,93 @@
+package canopy
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// HotSymbol is a symbol with the execution trace samples attributed to it.
+type HotSymbol struct {
+	Symbol       SymbolResult
+	Samples      SymbolSamples
+	SelfPercent  float64 // self samples as a percentage of the trace
+	TotalPercent float64 // total samples as a percentage of the trace
+}
+
+// HotSymbols returns the symbols with samples in the trace loaded by
+// IngestTrace, hottest first: by self samples, then total samples.
+// RefCountMin/RefCountMax in filter are ignored.
+func (q *QueryBuilder) HotSymbols(filter SymbolFilter, page Pagination) (*PagedResult[HotSymbol], error) {
+	page = page.normalize()
+
+	var traceSamples int64
+	if value, err := q.store.GetMetadata(traceSamplesKey); err != nil {
+		return nil, fmt.Errorf("hot symbols: %w", err)
+	} else if value != "" {
+		traceSamples, _ = strconv.ParseInt(value, 10, 64)
+	}
+
+	where, args := q.symbolFilterClauses(filter)
+	whereClause := ""
+	if len(where) > 0 {
+		whereClause = "WHERE " + strings.Join(where, " AND ")
+	}
+	from := `FROM symbol_samples t
+		 JOIN symbols s ON s.id = t.symbol_id
+		 LEFT JOIN files f ON s.file_id = f.id `
+
+	var totalCount int
+	if err := q.store.DB().QueryRow("SELECT COUNT(*) "+from+whereClause, args...).Scan(&totalCount); err != nil {
+		return nil, fmt.Errorf("hot symbols: count: %w", err)
+	}
+
+	dataSQL := fmt.Sprintf(
+		`SELECT %s, COALESCE(f.path, '') AS file_path,
+			(SELECT COUNT(*) FROM resolved_references rr WHERE rr.target_symbol_id = s.id) AS ref_count,
+			(SELECT COUNT(*) FROM resolved_references rr JOIN references_ r ON r.id = rr.reference_id WHERE rr.target_symbol_id = s.id AND r.file_id != s.file_id) AS external_ref_count,
+			t.self_samples, t.total_samples
+		 %s%s
+		 ORDER BY t.self_samples DESC, t.total_samples DESC, f.path, s.start_line, s.start_col, s.id
+		 LIMIT ? OFFSET ?`,
+		prefixSymbolCols("s"), from, whereClause,
+	)
+	dataArgs := append(append([]any{}, args...), *page.Limit, page.Offset)
+
+	rows, err := q.store.DB().Query(dataSQL, dataArgs...)
+	if err != nil {
+		return nil, fmt.Errorf("hot symbols: query: %w", err)
+	}
+	defer rows.Close()
+
+	items := []HotSymbol{}
+	for rows.Next() {
+		var hs HotSymbol
+		var mods string
+		sr := &hs.Symbol
+		if err := rows.Scan(
+			&sr.ID, &sr.FileID, &sr.Name, &sr.Kind, &sr.Visibility, &mods,
+			&sr.SignatureHash, &sr.StartLine, &sr.StartCol, &sr.EndLine, &sr.EndCol,
+			&sr.ParentSymbolID, &sr.StartByte, &sr.EndByte, &sr.UTF16StartCol, &sr.UTF16EndCol,
+			&sr.PageRank, &sr.Betweenness, &sr.Subkind,
+			&sr.FilePath, &sr.RefCount, &sr.ExternalRefCount,
+			&hs.Samples.Self, &hs.Samples.Total,
+		); err != nil {
+			return nil, fmt.Errorf("hot symbols: scan: %w", err)
+		}
+		sr.Modifiers = store.UnmarshalModifiers(mods)
+		sr.InternalRefCount = sr.RefCount - sr.ExternalRefCount
+		hs.Samples.SymbolID = sr.ID
+		if traceSamples > 0 {
+			hs.SelfPercent = 100 * float64(hs.Samples.Self) / float64(traceSamples)
+			hs.TotalPercent = 100 * float64(hs.Samples.Total) / float64(traceSamples)
+		}
+		items = append(items, hs)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("hot symbols: rows: %w", err)
+	}
+
+	return &PagedResult[HotSymbol]{Items: items, TotalCount: totalCount}, nil
+}
@@ -0,0 +1,289 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// CompositionKindSupertrait is the kind of the type composition linking a
+// Rust trait to one of its supertraits.
+const CompositionKindSupertrait = "supertrait"
+
+// rustAutoTraits are the traits the compiler implements for a type unless
+// it opts out, so a supertrait chain never lacks them.
+var rustAutoTraits = map[string]bool{
+	"Sized": true, "Send": true, "Sync": true, "Unpin": true, "UnwindSafe": true, "RefUnwindSafe": true,
+}
+
+// TraitTreeNode is a trait in a supertrait or subtrait tree.
+type TraitTreeNode struct {
+	Name     string           // the trait's name
+	Bound    string           // the supertrait bound as written: std::fmt::Debug ("" in a subtrait tree)
+	Symbol   *SymbolResult    // nil for a supertrait that is not indexed
+	Children []*TraitTreeNode // its supertraits, or in a subtrait tree its subtraits
+}
+
+// TraitChainImpl is an impl of a trait, with the traits of the trait's
+// supertrait chain that its self type has no impl or derive of.
+type TraitChainImpl struct {
+	Impl      TraitImpl
+	Missing   []string // supertraits, transitively, that the self type lacks
+	Satisfied bool     // the self type implements the whole chain
+}
+
+// TraitTree is the supertrait and subtrait hierarchy of a Rust trait, with
+// the impls of the trait and whether each satisfies the whole chain.
+type TraitTree struct {
+	Trait       SymbolResult
+	Supertraits []*TraitTreeNode // in declaration order
+	Subtraits   []*TraitTreeNode // by name
+	Impls       []TraitChainImpl // ordered by file and position
+}
+
+// TraitTree returns the hierarchy of the Rust trait symbolID: the traits
+// it requires, through supertrait bounds (trait Shape: Debug + Clone) and
+// `where Self:` bounds, the traits requiring it, and its impls. An impl
+// satisfies the chain when its self type also implements every supertrait,
+// transitively, by an impl, a blanket impl (impl<T> Trait for T, bounds
+// not checked), or a derive; auto traits such as Send and Sized are taken
+// as implemented. A blanket impl of the trait itself is listed as
+// satisfied, since its bounds decide which types it covers.
+// Returns nil, nil if symbolID does not exist.
+func (q *QueryBuilder) TraitTree(symbolID int64) (*TraitTree, error) {
+	sym, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("trait tree: %w", err)
+	}
+	if sym == nil {
+		return nil, nil
+	}
+	if sym.Kind != "trait" {
+		return nil, fmt.Errorf("trait tree: symbol %d is a %s, not a trait", symbolID, sym.Kind)
+	}
+
+	tree := &TraitTree{Trait: *sym, Impls: []TraitChainImpl{}}
+	if tree.Supertraits, err = q.supertraitNodes(symbolID, map[int64]bool{symbolID: true}); err != nil {
+		return nil, fmt.Errorf("trait tree: %w", err)
+	}
+	if tree.Subtraits, err = q.subtraitNodes(symbolID, map[int64]bool{symbolID: true}); err != nil {
+		return nil, fmt.Errorf("trait tree: %w", err)
+	}
+
+	audit, err := q.ImplAudit("")
+	if err != nil {
+		return nil, fmt.Errorf("trait tree: %w", err)
+	}
+	derives, err := q.derivedTraits()
+	if err != nil {
+		return nil, fmt.Errorf("trait tree: %w", err)
+	}
+
+	// implemented holds trait/type key pairs; blanket the traits with an
+	// impl for any type. An impl's trait is keyed by its symbol when it
+	// resolved and by name otherwise; its type likewise.
+	implemented := map[string]bool{}
+	blanket := map[string]bool{}
+	var rootImpls []TraitImpl
+	for _, g := range audit {
+		for _, im := range g.Impls {
+			if im.Negative {
+				continue
+			}
+			traitKey := "name:" + im.traitType.head
+			if im.TraitSymbol != nil {
+				traitKey = fmt.Sprintf("id:%d", im.TraitSymbol.ID)
+				if im.TraitSymbol.ID == symbolID {
+					rootImpls = append(rootImpls, im)
+				}
+			}
+			if isBlanketImpl(im) {
+				blanket[traitKey] = true
+			} else {
+				implemented[traitKey+" "+implTypeKey(im)] = true
+			}
+		}
+	}
+
+	chain := flattenTraitTree(tree.Supertraits)
+	for _, im := range rootImpls {
+		ci := TraitChainImpl{Impl: im, Missing: []string{}}
+		if !isBlanketImpl(im) {
+			typeKey := implTypeKey(im)
+			for _, n := range chain {
+				keys := []string{"name:" + n.Name}
+				if n.Symbol != nil {
+					keys = append(keys, fmt.Sprintf("id:%d", n.Symbol.ID))
+				}
+				found := rustAutoTraits[n.Name] || (im.TypeSymbol != nil && derives[im.TypeSymbol.ID][n.Name])
+				for _, k := range keys {
+					found = found || blanket[k] || implemented[k+" "+typeKey]
+				}
+				if !found {
+					ci.Missing = append(ci.Missing, n.Name)
+				}
+			}
+		}
+		ci.Satisfied = len(ci.Missing) == 0
+		tree.Impls = append(tree.Impls, ci)
+	}
+	return tree, nil
+}
+
+// supertraitNodes returns the supertraits of a trait, each with its own
+// supertraits. path holds the traits being expanded, so a cycle stops.
+func (q *QueryBuilder) supertraitNodes(symbolID int64, path map[int64]bool) ([]*TraitTreeNode, error) {
+	bounds, err := q.store.TypeBounds(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	comps, err := q.store.TypeCompositions(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	var ids []int64
+	for _, tc := range comps {
+		if tc.CompositionKind == CompositionKindSupertrait {
+			ids = append(ids, tc.ComponentSymbolID)
+		}
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+	byName := map[string]*SymbolResult{}
+	for _, id := range ids {
+		if s, ok := syms[id]; ok {
+			byName[s.Name] = s
+		}
+	}
+
+	nodes := []*TraitTreeNode{}
+	seen := map[string]bool{}
+	for _, b := range bounds {
+		if b.Subject != "Self" || seen[b.Name] || strings.HasPrefix(b.Bound, "?") || strings.HasPrefix(b.Name, "'") {
+			continue
+		}
+		seen[b.Name] = true
+		n := &TraitTreeNode{Name: b.Name, Bound: b.Bound, Symbol: byName[b.Name], Children: []*TraitTreeNode{}}
+		if n.Symbol != nil && !path[n.Symbol.ID] {
+			path[n.Symbol.ID] = true
+			n.Children, err = q.supertraitNodes(n.Symbol.ID, path)
+			delete(path, n.Symbol.ID)
+			if err != nil {
+				return nil, err
+			}
+		}
+		nodes = append(nodes, n)
+	}
+	return nodes, nil
+}
+
+// subtraitNodes returns the traits with a trait as a supertrait, each with
+// its own subtraits, by name. path is as for supertraitNodes.
+func (q *QueryBuilder) subtraitNodes(symbolID int64, path map[int64]bool) ([]*TraitTreeNode, error) {
+	comps, err := q.store.TypeComposedBy(symbolID)
+	if err != nil {
+		return nil, err
+	}
+	var ids []int64
+	for _, tc := range comps {
+		if tc.CompositionKind == CompositionKindSupertrait {
+			ids = append(ids, tc.CompositeSymbolID)
+		}
+	}
+	syms, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, err
+	}
+
+	nodes := []*TraitTreeNode{}
+	for _, id := range ids {
+		s, ok := syms[id]
+		if !ok || path[id] {
+			continue
+		}
+		path[id] = true
+		children, err := q.subtraitNodes(id, path)
+		delete(path, id)
+		if err != nil {
+			return nil, err
+		}
+		nodes = append(nodes, &TraitTreeNode{Name: s.Name, Symbol: s, Children: children})
+	}
+	sort.SliceStable(nodes, func(i, j int) bool { return nodes[i].Name < nodes[j].Name })
+	return nodes, nil
+}
+
+// derivedTraits returns the traits each type derives, by type symbol ID:
+// #[derive(Debug, serde::Serialize)] derives Debug and Serialize.
+func (q *QueryBuilder) derivedTraits() (map[int64]map[string]bool, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT target_symbol_id, COALESCE(arguments, '') FROM annotations
+		 WHERE name = 'derive'`)
+	if err != nil {
+		return nil, fmt.Errorf("derive attributes: %w", err)
+	}
+	defer rows.Close()
+
+	derives := map[int64]map[string]bool{}
+	for rows.Next() {
+		var id int64
+		var arguments string
+		if err := rows.Scan(&id, &arguments); err != nil {
+			return nil, fmt.Errorf("derive attributes: scan: %w", err)
+		}
+		for _, d := range strings.Split(strings.Trim(strings.TrimSpace(arguments), "()"), ",") {
+			d = strings.TrimSpace(d)
+			d = d[strings.LastIndex(d, ":")+1:]
+			if d == "" {
+				continue
+			}
+			if derives[id] == nil {
+				derives[id] = map[string]bool{}
+			}
+			derives[id][d] = true
+		}
+	}
+	return derives, rows.Err()
+}
+
+// flattenTraitTree returns each trait in a tree once, in depth-first order.
+func flattenTraitTree(nodes []*TraitTreeNode) []*TraitTreeNode {
+	var out []*TraitTreeNode
+	seen := map[string]bool{}
+	var walk func([]*TraitTreeNode)
+	walk = func(nodes []*TraitTreeNode) {
+		for _, n := range nodes {
+			key := "name:" + n.Name
+			if n.Symbol != nil {
+				key = fmt.Sprintf("id:%d", n.Symbol.ID)
+			}
+			if !seen[key] {
+				seen[key] = true
+				out = append(out, n)
+			}
+			walk(n.Children)
+		}
+	}
+	walk(nodes)
+	return out
+}
+
+// isBlanketImpl reports whether an impl is for any type: impl<T> Trait for T.
+func isBlanketImpl(im TraitImpl) bool {
+	params := map[string]bool{}
+	for _, g := range im.Generics {
+		params[g] = true
+	}
+	return im.selfType.isParam(params)
+}
+
+// implTypeKey keys an impl's self type by its symbol, or by name when the
+// type is not indexed.
+func implTypeKey(im TraitImpl) string {
+	if im.TypeSymbol != nil {
+		return fmt.Sprintf("id:%d", im.TypeSymbol.ID)
+	}
+	return "name:" + im.selfType.ownedType().head
+}
@@ -0,0 +1,140 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestTraitTree(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", `pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+pub trait Shape: Named + Clone + Send {
+    fn area(&self) -> f64;
+}
+
+pub trait Solid: Shape where Self: Describe {
+    fn volume(&self) -> f64;
+}
+
+impl<T: Clone> Describe for T {
+    fn describe(&self) -> String {
+        String::new()
+    }
+}
+
+#[derive(Clone)]
+pub struct Circle;
+
+impl Named for Circle {
+    fn name(&self) -> String {
+        String::from("circle")
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14
+    }
+}
+
+pub struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        1.0
+    }
+}
+
+impl Solid for Circle {
+    fn volume(&self) -> f64 {
+        0.0
+    }
+}
+`)
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	traitID := func(name string) int64 {
+		t.Helper()
+		syms, err := e.store.SymbolsByName(name)
+		require.NoError(t, err)
+		for _, s := range syms {
+			if s.Kind == "trait" {
+				return s.ID
+			}
+		}
+		t.Fatalf("no trait %s", name)
+		return 0
+	}
+	names := func(nodes []*TraitTreeNode) []string {
+		var out []string
+		for _, n := range nodes {
+			out = append(out, n.Name)
+		}
+		return out
+	}
+
+	tree, err := e.Query().TraitTree(traitID("Shape"))
+	require.NoError(t, err)
+	require.NotNil(t, tree)
+	assert.Equal(t, []string{"Named", "Clone", "Send"}, names(tree.Supertraits))
+	require.NotNil(t, tree.Supertraits[0].Symbol)
+	assert.Equal(t, traitID("Named"), tree.Supertraits[0].Symbol.ID)
+	assert.Equal(t, []string{"Solid"}, names(tree.Subtraits))
+
+	require.Len(t, tree.Impls, 2)
+	assert.Equal(t, "Circle", tree.Impls[0].Impl.Type)
+	assert.True(t, tree.Impls[0].Satisfied, "Named by impl, Clone by derive, Send as an auto trait")
+	assert.Equal(t, "Square", tree.Impls[1].Impl.Type)
+	assert.False(t, tree.Impls[1].Satisfied)
+	assert.Equal(t, []string{"Named", "Clone"}, tree.Impls[1].Missing)
+
+	tree, err = e.Query().TraitTree(traitID("Solid"))
+	require.NoError(t, err)
+	assert.Equal(t, []string{"Shape", "Describe"}, names(tree.Supertraits))
+	assert.Equal(t, []string{"Named", "Clone", "Send"}, names(tree.Supertraits[0].Children))
+	assert.Empty(t, tree.Subtraits)
+	require.Len(t, tree.Impls, 1)
+	assert.True(t, tree.Impls[0].Satisfied, "Describe by the blanket impl")
+
+	h, err := e.Query().TypeHierarchy(traitID("Solid"))
+	require.NoError(t, err)
+	var composes []string
+	for _, rel := range h.Composes {
+		assert.Equal(t, CompositionKindSupertrait, rel.Kind)
+		composes = append(composes, rel.Symbol.Name)
+	}
+	assert.ElementsMatch(t, []string{"Shape", "Describe"}, composes)
+
+	_, err = e.Query().TraitTree(func() int64 {
+		syms, err := e.store.SymbolsByName("Circle")
+		require.NoError(t, err)
+		require.NotEmpty(t, syms)
+		return syms[0].ID
+	}())
+	assert.Error(t, err, "not a trait")
+	tree, err = e.Query().TraitTree(999999)
+	require.NoError(t, err)
+	assert.Nil(t, tree)
+}
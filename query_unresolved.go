@@ -0,0 +1,336 @@
+package canopy
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+)
+
+// Reasons a reference is unresolved, from most to least specific.
+const (
+	// UnresolvedMissingDependency: the name, or the qualifier in front of it
+	// (fmt.Println, serde_json::to_string), comes from an import whose
+	// target is not indexed, or it invokes a macro defined outside the index.
+	UnresolvedMissingDependency = "missing_dependency"
+	// UnresolvedBuildGenerated: a bare name in a Rust file that include!s
+	// build script output from OUT_DIR which is not indexed (see
+	// WithTargetDir).
+	UnresolvedBuildGenerated = "build_generated"
+	// UnresolvedMacroGenerated: the target is likely produced by macro
+	// expansion: an associated item of a type with a non-std derive, or a
+	// bare name in a file with item-level macro invocations.
+	UnresolvedMacroGenerated = "macro_generated"
+	// UnresolvedUnsupportedSyntax: everything else. The name should be
+	// knowable from the index, but the resolver does not model the construct
+	// (calls on values of unknown type, dynamic dispatch, builtins, ...).
+	UnresolvedUnsupportedSyntax = "unsupported_syntax"
+)
+
+// stdDerives are the derive macros provided by the standard library. They
+// add trait impls, not new names, so they never explain an unresolved name.
+var stdDerives = map[string]bool{
+	"Debug": true, "Clone": true, "Copy": true, "PartialEq": true, "Eq": true,
+	"PartialOrd": true, "Ord": true, "Hash": true, "Default": true,
+}
+
+// identTailRe matches the identifier at the end of a string.
+var identTailRe = regexp.MustCompile(`[A-Za-z_$][A-Za-z0-9_$]*$`)
+
+// UnresolvedReference is a reference the resolver could not bind to any
+// symbol, with the likely reason.
+type UnresolvedReference struct {
+	Location  Location
+	Name      string
+	Context   string // reference context ("call", "type_annotation", ...)
+	Reason    string // one of the Unresolved* constants
+	Qualifier string // the name in front of "." or "::", if any
+	Import    string // for missing dependencies, the import source the name comes from
+}
+
+// UnresolvedFilter narrows UnresolvedReferences. The zero value matches all.
+type UnresolvedFilter struct {
+	Reasons    []string // match any of these reasons
+	PathPrefix string   // only references in files under this path
+}
+
+// UnresolvedReport lists unresolved references and measures how complete
+// resolution is.
+type UnresolvedReport struct {
+	Items           []UnresolvedReference // the requested page, by file and position
+	TotalCount      int                   // unresolved references matching the filter
+	ByReason        map[string]int        // unresolved references per reason, ignoring the reason filter
+	TotalReferences int                   // all references in files under the path prefix
+}
+
+// UnresolvedReferences lists every reference the resolver could not bind,
+// categorized by reason (see the Unresolved* constants), ordered by file and
+// position. Reasons are heuristics over the index: imports, derive
+// attributes, macro invocations, and the source text in front of each
+// reference.
+func (q *QueryBuilder) UnresolvedReferences(filter UnresolvedFilter, page Pagination) (*UnresolvedReport, error) {
+	page = page.normalize()
+
+	where := "f.language != ?"
+	args := []any{rustdocStubLanguage}
+	if prefix := normalizePathPrefix(filter.PathPrefix); prefix != "" {
+		where += " AND f.path LIKE ? ESCAPE '\\'"
+		args = append(args, escapeLike(prefix)+"%")
+	}
+
+	report := &UnresolvedReport{ByReason: map[string]int{}, Items: []UnresolvedReference{}}
+	if err := q.store.DB().QueryRow(
+		"SELECT COUNT(*) FROM references_ r JOIN files f ON f.id = r.file_id WHERE "+where, args...,
+	).Scan(&report.TotalReferences); err != nil {
+		return nil, fmt.Errorf("unresolved references: count: %w", err)
+	}
+
+	imports, err := q.importedNames()
+	if err != nil {
+		return nil, fmt.Errorf("unresolved references: %w", err)
+	}
+	derived, err := q.macroDerivedTypes()
+	if err != nil {
+		return nil, fmt.Errorf("unresolved references: %w", err)
+	}
+	macroFiles, err := q.itemMacroFiles()
+	if err != nil {
+		return nil, fmt.Errorf("unresolved references: %w", err)
+	}
+	buildFiles, err := q.missingBuildOutputFiles()
+	if err != nil {
+		return nil, fmt.Errorf("unresolved references: %w", err)
+	}
+
+	rows, err := q.store.DB().Query(
+		`SELECT r.file_id, f.path, r.name, COALESCE(r.context, ''),
+			r.start_line, r.start_col, r.end_line, r.end_col,
+			r.start_byte, r.end_byte, r.utf16_start_col, r.utf16_end_col
+		 FROM references_ r JOIN files f ON f.id = r.file_id
+		 WHERE `+where+`
+		   AND NOT EXISTS (SELECT 1 FROM resolved_references rr WHERE rr.reference_id = r.id)
+		 ORDER BY f.path, r.start_line, r.start_col, r.id`, args...)
+	if err != nil {
+		return nil, fmt.Errorf("unresolved references: query: %w", err)
+	}
+	type unresolvedRow struct {
+		fileID int64
+		ref    UnresolvedReference
+	}
+	var all []unresolvedRow
+	for rows.Next() {
+		var row unresolvedRow
+		loc := &row.ref.Location
+		if err := rows.Scan(&row.fileID, &loc.File, &row.ref.Name, &row.ref.Context,
+			&loc.StartLine, &loc.StartCol, &loc.EndLine, &loc.EndCol,
+			&loc.StartByte, &loc.EndByte, &loc.UTF16StartCol, &loc.UTF16EndCol); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("unresolved references: scan: %w", err)
+		}
+		all = append(all, row)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("unresolved references: rows: %w", err)
+	}
+
+	wanted := map[string]bool{}
+	for _, r := range filter.Reasons {
+		wanted[r] = true
+	}
+	sources := map[string][]byte{}
+	for _, row := range all {
+		ref := row.ref
+		src, ok := sources[ref.Location.File]
+		if !ok {
+			src, _ = q.readSource(ref.Location.File) // a missing file just has no qualifiers
+			sources[ref.Location.File] = src
+		}
+		ref.Qualifier = referenceQualifier(src, ref.Location.StartByte)
+		ref.Reason, ref.Import = unresolvedReason(ref, imports[row.fileID], derived,
+			macroFiles[row.fileID], buildFiles[row.fileID])
+
+		report.ByReason[ref.Reason]++
+		if len(wanted) > 0 && !wanted[ref.Reason] {
+			continue
+		}
+		if report.TotalCount >= page.Offset && len(report.Items) < *page.Limit {
+			report.Items = append(report.Items, ref)
+		}
+		report.TotalCount++
+	}
+	return report, nil
+}
+
+// unresolvedReason returns why ref, whose Qualifier is set, is unresolved,
+// and for missing dependencies the import source it comes from. imports
+// are the names the imports of ref's file bind (see importedNames); derived
+// the types with non-std derives; macroFile and buildFile whether ref's
+// file has item-level macro invocations or missing build script output.
+func unresolvedReason(ref UnresolvedReference, imports map[string]string, derived map[string]bool, macroFile, buildFile bool) (reason, source string) {
+	switch {
+	case imports[ref.Qualifier] != "":
+		return UnresolvedMissingDependency, imports[ref.Qualifier]
+	case imports[ref.Name] != "":
+		return UnresolvedMissingDependency, imports[ref.Name]
+	case ref.Context == RefKindMacro:
+		return UnresolvedMissingDependency, ""
+	case ref.Qualifier == "" && buildFile:
+		return UnresolvedBuildGenerated, ""
+	case derived[ref.Qualifier]:
+		return UnresolvedMacroGenerated, ""
+	case ref.Qualifier == "" && macroFile:
+		return UnresolvedMacroGenerated, ""
+	default:
+		return UnresolvedUnsupportedSyntax, ""
+	}
+}
+
+// referenceQualifier returns the identifier in front of a reference
+// starting at byte start when they are joined by ".", "::", or "->", as in
+// fmt.Println or Vec::new; otherwise "".
+func referenceQualifier(src []byte, start int) string {
+	if start <= 0 || start > len(src) {
+		return ""
+	}
+	// Qualifiers are short; a bounded window keeps this linear per file.
+	before := strings.TrimRight(string(src[max(0, start-256):start]), " \t\r\n")
+	trimmed := before
+	for _, sep := range []string{"::", "->", "."} {
+		if strings.HasSuffix(before, sep) {
+			trimmed = strings.TrimRight(strings.TrimSuffix(before, sep), " \t\r\n")
+			break
+		}
+	}
+	if trimmed == before {
+		return ""
+	}
+	return identTailRe.FindString(trimmed)
+}
+
+// importedNames returns, per file ID, the names each import brings into
+// scope mapped to the import source. The name is the alias, the imported
+// name, or the last segment of the source (fmt, os.path -> path).
+func (q *QueryBuilder) importedNames() (map[int64]map[string]string, error) {
+	rows, err := q.store.DB().Query(
+		"SELECT file_id, source, COALESCE(imported_name, ''), COALESCE(local_alias, '') FROM imports")
+	if err != nil {
+		return nil, fmt.Errorf("imports: %w", err)
+	}
+	defer rows.Close()
+
+	names := map[int64]map[string]string{}
+	for rows.Next() {
+		var fileID int64
+		var source, imported, alias string
+		if err := rows.Scan(&fileID, &source, &imported, &alias); err != nil {
+			return nil, fmt.Errorf("imports: scan: %w", err)
+		}
+		name := importBinding(source, imported, alias)
+		if name == "" {
+			continue
+		}
+		if names[fileID] == nil {
+			names[fileID] = map[string]string{}
+		}
+		names[fileID][name] = source
+	}
+	return names, rows.Err()
+}
+
+// importBinding returns the name an import brings into scope: the alias,
+// the imported name, or the last segment of the source. It returns "" for
+// glob imports.
+func importBinding(source, imported, alias string) string {
+	name := alias
+	if name == "" || name == "_" || name == "." {
+		name = imported
+	}
+	if name == "" {
+		trimmed := strings.Trim(source, `"'<>`)
+		name = trimmed[strings.LastIndexAny(trimmed, "/.:\\")+1:]
+	}
+	if name == "*" {
+		return ""
+	}
+	return name
+}
+
+// macroDerivedTypes returns the names of types with a derive attribute that
+// names a macro outside std (#[derive(Builder)]), whose generated items the
+// index cannot see.
+func (q *QueryBuilder) macroDerivedTypes() (map[string]bool, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT s.name, a.arguments FROM annotations a
+		 JOIN symbols s ON s.id = a.target_symbol_id
+		 WHERE a.name = 'derive'`)
+	if err != nil {
+		return nil, fmt.Errorf("derive attributes: %w", err)
+	}
+	defer rows.Close()
+
+	types := map[string]bool{}
+	for rows.Next() {
+		var name, arguments string
+		if err := rows.Scan(&name, &arguments); err != nil {
+			return nil, fmt.Errorf("derive attributes: scan: %w", err)
+		}
+		for _, d := range strings.Split(strings.Trim(strings.TrimSpace(arguments), "()"), ",") {
+			d = strings.TrimSpace(d)
+			d = d[strings.LastIndex(d, ":")+1:]
+			if d != "" && !stdDerives[d] {
+				types[name] = true
+			}
+		}
+	}
+	return types, rows.Err()
+}
+
+// itemMacroFiles returns the IDs of files with a macro invocation at item
+// level (in a file or module scope), which can define items the index
+// cannot see.
+func (q *QueryBuilder) itemMacroFiles() (map[int64]bool, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT r.file_id FROM references_ r
+		 JOIN scopes sc ON sc.id = r.scope_id
+		 WHERE r.context = ? AND sc.kind IN ('file', 'module')`, RefKindMacro)
+	if err != nil {
+		return nil, fmt.Errorf("macro invocations: %w", err)
+	}
+	defer rows.Close()
+
+	files := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, fmt.Errorf("macro invocations: scan: %w", err)
+		}
+		files[id] = true
+	}
+	return files, rows.Err()
+}
+
+// missingBuildOutputFiles returns the IDs of files that include! build
+// script output (an import of kind "include" from "$OUT_DIR/<path>") with
+// no indexed file at <path> under an out directory.
+func (q *QueryBuilder) missingBuildOutputFiles() (map[int64]bool, error) {
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT i.file_id FROM imports i
+		 WHERE i.kind = 'include' AND substr(i.source, 1, ?) = ?
+		   AND NOT EXISTS (SELECT 1 FROM files g
+			WHERE substr(g.path, -(length(i.source) - ? + 5)) = '/out/' || substr(i.source, ? + 1))`,
+		len(outDirInclude), outDirInclude, len(outDirInclude), len(outDirInclude))
+	if err != nil {
+		return nil, fmt.Errorf("build script includes: %w", err)
+	}
+	defer rows.Close()
+
+	files := map[int64]bool{}
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			return nil, fmt.Errorf("build script includes: scan: %w", err)
+		}
+		files[id] = true
+	}
+	return files, rows.Err()
+}
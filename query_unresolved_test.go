@@ -0,0 +1,105 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestReferenceQualifier(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		src  string
+		want string
+	}{
+		{"fmt.Println", "fmt"},
+		{"Vec::new", "Vec"},
+		{"self->handle", "self"},
+		{"builder\n    .build", "builder"},
+		{"std::collections::HashMap::new", "HashMap"},
+		{"foo(bar)", ""},
+		{"new", ""},
+	}
+	for _, tt := range tests {
+		// The reference is the trailing identifier of src.
+		start := len(tt.src) - len(identTailRe.FindString(tt.src))
+		assert.Equal(t, tt.want, referenceQualifier([]byte(tt.src), start), "src: %q", tt.src)
+	}
+}
+
+func TestUnresolvedReferences_Reasons(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go", "rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	goPath := writeGoFile(t, dir, "main.go", `package main
+
+import "fmt"
+
+func main() {
+	fmt.Println("hi")
+}
+`)
+	rsPath := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(rsPath, []byte(`#[derive(Debug, Builder)]
+struct Config {
+    name: String,
+}
+
+fn run() {
+    let c = Config::builder();
+    let v = make_value();
+    v.frobnicate();
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{goPath, rsPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	report, err := e.Query().UnresolvedReferences(UnresolvedFilter{}, Pagination{})
+	require.NoError(t, err)
+	assert.Greater(t, report.TotalReferences, report.TotalCount)
+	assert.Equal(t, report.TotalCount, len(report.Items))
+
+	byName := map[string]UnresolvedReference{}
+	for _, r := range report.Items {
+		byName[r.Name] = r
+	}
+	require.Contains(t, byName, "Println")
+	assert.Equal(t, UnresolvedMissingDependency, byName["Println"].Reason)
+	assert.Equal(t, "fmt", byName["Println"].Qualifier)
+	assert.Equal(t, "fmt", byName["Println"].Import)
+
+	require.Contains(t, byName, "builder")
+	assert.Equal(t, UnresolvedMacroGenerated, byName["builder"].Reason, "Config derives Builder")
+	assert.Equal(t, "Config", byName["builder"].Qualifier)
+
+	require.Contains(t, byName, "frobnicate")
+	assert.Equal(t, UnresolvedUnsupportedSyntax, byName["frobnicate"].Reason)
+	require.Contains(t, byName, "make_value")
+	assert.Equal(t, UnresolvedUnsupportedSyntax, byName["make_value"].Reason)
+
+	assert.NotContains(t, byName, "Config", "resolved references are not listed")
+	assert.NotContains(t, byName, "String", "std stubs resolve prelude types")
+
+	// The reason filter narrows the items, not the per-reason summary.
+	limit := 1
+	filtered, err := e.Query().UnresolvedReferences(
+		UnresolvedFilter{Reasons: []string{UnresolvedUnsupportedSyntax}, PathPrefix: dir},
+		Pagination{Limit: &limit})
+	require.NoError(t, err)
+	assert.Equal(t, report.ByReason, filtered.ByReason)
+	assert.Equal(t, report.ByReason[UnresolvedUnsupportedSyntax], filtered.TotalCount)
+	require.Len(t, filtered.Items, 1)
+	assert.Equal(t, UnresolvedUnsupportedSyntax, filtered.Items[0].Reason)
+
+	goOnly, err := e.Query().UnresolvedReferences(UnresolvedFilter{PathPrefix: goPath}, Pagination{})
+	require.NoError(t, err)
+	for _, r := range goOnly.Items {
+		assert.Equal(t, goPath, r.Location.File)
+	}
+}
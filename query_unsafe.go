@@ -0,0 +1,197 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+)
+
+// ModifierUnsafe marks a function or method declared unsafe fn.
+const ModifierUnsafe = "unsafe"
+
+// SiteKindUnsafe is the flagged site kind of an unsafe block.
+const SiteKindUnsafe = "unsafe"
+
+// Kinds of unsafe usage.
+const (
+	UnsafeKindBlock    = "unsafe_block" // an unsafe { ... } block
+	UnsafeKindFunction = "unsafe_fn"    // a function or method declared unsafe fn
+)
+
+// UnsafeUsage is an unsafe block or an unsafe function.
+type UnsafeUsage struct {
+	Kind     string          // UnsafeKindBlock or UnsafeKindFunction
+	Location Location        // the block, or the function's declaration
+	Function SymbolResult    // the function containing the block, or the unsafe function itself
+	Path     []CallGraphEdge // for UnsafeReachableFrom, the shortest calls from the queried function to Function
+}
+
+// UnsafeReport summarizes a set of unsafe usages.
+type UnsafeReport struct {
+	Functions int            // usages of kind UnsafeKindFunction
+	Blocks    int            // usages of kind UnsafeKindBlock
+	ByFile    map[string]int // usages per file path
+	Items     []UnsafeUsage
+}
+
+// UnsafeUsages inventories the unsafe blocks and unsafe functions in files
+// under pathPrefix ("" for all files), ordered by file then line.
+func (q *QueryBuilder) UnsafeUsages(pathPrefix string) (*UnsafeReport, error) {
+	fnIDs, err := q.symbolIDsWithModifier(ModifierUnsafe)
+	if err != nil {
+		return nil, fmt.Errorf("unsafe usages: %w", err)
+	}
+	blocks, err := q.flaggedSites(nil, []string{SiteKindUnsafe})
+	if err != nil {
+		return nil, fmt.Errorf("unsafe usages: %w", err)
+	}
+	items, err := q.unsafeUsages(fnIDs, blocks)
+	if err != nil {
+		return nil, fmt.Errorf("unsafe usages: %w", err)
+	}
+
+	var kept []UnsafeUsage
+	for _, u := range items {
+		if strings.HasPrefix(u.Location.File, pathPrefix) {
+			kept = append(kept, u)
+		}
+	}
+	sortUnsafeUsages(kept)
+	return newUnsafeReport(kept), nil
+}
+
+// UnsafeReachableFrom reports the unsafe blocks and unsafe functions in a
+// function or in the functions it calls within maxDepth calls, each with the
+// shortest call path that reaches it, ordered by path length then location.
+// Spawned work (CallKindSpawn) is followed: it runs because the function
+// does. Same depth rules as TransitiveCallees; returns nil, nil if symbolID
+// does not exist.
+func (q *QueryBuilder) UnsafeReachableFrom(symbolID int64, maxDepth int) (*UnsafeReport, error) {
+	if maxDepth < 0 {
+		return nil, fmt.Errorf("unsafe reachable from: maxDepth must be non-negative, got %d", maxDepth)
+	}
+	if maxDepth > 100 {
+		maxDepth = 100
+	}
+
+	rootSym, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("unsafe reachable from: %w", err)
+	}
+	if rootSym == nil {
+		return nil, nil
+	}
+
+	data, err := q.buildCallGraph()
+	if err != nil {
+		return nil, fmt.Errorf("unsafe reachable from: %w", err)
+	}
+	via := shortestCallPaths(data, symbolID, maxDepth, true)
+
+	ids := make([]int64, 0, len(via))
+	for id := range via {
+		ids = append(ids, id)
+	}
+	unsafeFns, err := q.symbolIDsWithModifier(ModifierUnsafe)
+	if err != nil {
+		return nil, fmt.Errorf("unsafe reachable from: %w", err)
+	}
+	fnIDs := map[int64]bool{}
+	for id := range via {
+		if unsafeFns[id] {
+			fnIDs[id] = true
+		}
+	}
+	blocks, err := q.flaggedSites(ids, []string{SiteKindUnsafe})
+	if err != nil {
+		return nil, fmt.Errorf("unsafe reachable from: %w", err)
+	}
+	items, err := q.unsafeUsages(fnIDs, blocks)
+	if err != nil {
+		return nil, fmt.Errorf("unsafe reachable from: %w", err)
+	}
+
+	for i := range items {
+		edges := callPathTo(via, items[i].Function.ID)
+		items[i].Path = make([]CallGraphEdge, len(edges))
+		for j, e := range edges {
+			items[i].Path[j] = resolveCallGraphEdge(e, data.filePaths)
+		}
+	}
+	sortUnsafeUsages(items)
+	sort.SliceStable(items, func(i, j int) bool { return len(items[i].Path) < len(items[j].Path) })
+	return newUnsafeReport(items), nil
+}
+
+// unsafeUsages builds the usages of the given unsafe functions and unsafe
+// blocks, loading the functions involved.
+func (q *QueryBuilder) unsafeUsages(fnIDs map[int64]bool, blocks []flaggedSite) ([]UnsafeUsage, error) {
+	var ids []int64
+	for id := range fnIDs {
+		ids = append(ids, id)
+	}
+	for _, b := range blocks {
+		ids = append(ids, b.symbolID)
+	}
+	symbols, err := q.symbolResultsByIDs(ids)
+	if err != nil {
+		return nil, fmt.Errorf("load symbols: %w", err)
+	}
+
+	var items []UnsafeUsage
+	for id := range fnIDs {
+		fn, ok := symbols[id]
+		if !ok {
+			continue
+		}
+		items = append(items, UnsafeUsage{
+			Kind: UnsafeKindFunction,
+			Location: Location{
+				File:      fn.FilePath,
+				StartLine: fn.StartLine,
+				StartCol:  fn.StartCol,
+				EndLine:   fn.EndLine,
+				EndCol:    fn.EndCol,
+			},
+			Function: *fn,
+		})
+	}
+	for _, b := range blocks {
+		fn, ok := symbols[b.symbolID]
+		if !ok {
+			continue
+		}
+		items = append(items, UnsafeUsage{Kind: UnsafeKindBlock, Location: b.location, Function: *fn})
+	}
+	return items, nil
+}
+
+// sortUnsafeUsages orders usages by file, then position.
+func sortUnsafeUsages(items []UnsafeUsage) {
+	sort.SliceStable(items, func(i, j int) bool {
+		a, b := items[i].Location, items[j].Location
+		if a.File != b.File {
+			return a.File < b.File
+		}
+		if a.StartLine != b.StartLine {
+			return a.StartLine < b.StartLine
+		}
+		return a.StartCol < b.StartCol
+	})
+}
+
+// newUnsafeReport tallies items into a report.
+func newUnsafeReport(items []UnsafeUsage) *UnsafeReport {
+	report := &UnsafeReport{ByFile: map[string]int{}, Items: []UnsafeUsage{}}
+	for _, u := range items {
+		switch u.Kind {
+		case UnsafeKindFunction:
+			report.Functions++
+		case UnsafeKindBlock:
+			report.Blocks++
+		}
+		report.ByFile[u.Location.File]++
+		report.Items = append(report.Items, u)
+	}
+	return report
+}
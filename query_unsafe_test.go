@@ -0,0 +1,102 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestUnsafeUsages(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	dir := t.TempDir()
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`unsafe fn raw_read(p: *const u32) -> u32 {
+    *p
+}
+
+fn read(p: *const u32) -> u32 {
+    unsafe { raw_read(p) }
+}
+
+fn poke(v: &mut [u32]) {
+    unsafe {
+        *v.as_mut_ptr() = 1;
+    }
+}
+
+fn worker() {
+    let v = [1u32];
+    read(v.as_ptr());
+}
+
+pub fn entry() {
+    thread::spawn(worker);
+}
+
+pub fn clean() {}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	ids := map[string]int64{}
+	for _, s := range syms {
+		ids[s.Name] = s.ID
+	}
+
+	type found struct {
+		kind, fn    string
+		line, depth int
+	}
+	collect := func(r *UnsafeReport) []found {
+		var got []found
+		for _, u := range r.Items {
+			got = append(got, found{u.Kind, u.Function.Name, u.Location.StartLine, len(u.Path)})
+		}
+		return got
+	}
+
+	q := e.Query()
+	report, err := q.UnsafeUsages("")
+	require.NoError(t, err)
+	assert.Equal(t, 1, report.Functions)
+	assert.Equal(t, 2, report.Blocks)
+	assert.Equal(t, map[string]int{path: 3}, report.ByFile)
+	assert.Equal(t, []found{
+		{UnsafeKindFunction, "raw_read", 0, 0},
+		{UnsafeKindBlock, "read", 5, 0},
+		{UnsafeKindBlock, "poke", 9, 0},
+	}, collect(report))
+
+	other, err := q.UnsafeUsages(filepath.Join(dir, "other"))
+	require.NoError(t, err)
+	assert.Empty(t, other.Items)
+
+	reach, err := q.UnsafeReachableFrom(ids["entry"], 10)
+	require.NoError(t, err)
+	assert.Equal(t, []found{
+		{UnsafeKindBlock, "read", 5, 2},
+		{UnsafeKindFunction, "raw_read", 0, 3},
+	}, collect(reach), "spawned work is followed; poke is not reachable")
+	assert.Equal(t, 1, reach.Functions)
+	assert.Equal(t, 1, reach.Blocks)
+	assert.Equal(t, CallKindSpawn, reach.Items[0].Path[0].Kind)
+
+	clean, err := q.UnsafeReachableFrom(ids["clean"], 10)
+	require.NoError(t, err)
+	assert.Empty(t, clean.Items)
+
+	missing, err := q.UnsafeReachableFrom(999999, 10)
+	require.NoError(t, err)
+	assert.Nil(t, missing)
+}
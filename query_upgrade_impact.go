@@ -0,0 +1,154 @@
+package canopy
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"sort"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// ChangedSymbol is an item removed or changed in a new version of a
+// dependency, as listed in its changelog or a diff of two versions.
+type ChangedSymbol struct {
+	Path string // crate-qualified path, as in serde::de::Deserializer::deserialize_any
+	Note string // what changed, as given ("removed", "signature changed"); may be empty
+}
+
+// ChangeImpact is one changed item with the places in the workspace that
+// use it.
+type ChangeImpact struct {
+	ChangedSymbol
+	Symbols []SymbolResult  // the indexed items the path matched; none if it matched nothing
+	Sites   []ReferenceSite // references to them from the workspace, by file and position
+}
+
+// UpgradeImpact is the work a dependency upgrade brings: every use of the
+// items it removes or changes.
+type UpgradeImpact struct {
+	Changes []ChangeImpact // in the order given
+	Sites   int            // references across all changes
+	Files   []string       // workspace files with at least one reference, sorted
+}
+
+// ParseChangedSymbols reads a list of changed items, one per line: a
+// crate-qualified path, optionally followed by a note on what changed
+// (serde::de::Error::custom removed). Blank lines and lines starting with #
+// are skipped; backticks, a leading "- " bullet, and a trailing () on the
+// path are dropped, so a list pasted from a changelog works as is.
+func ParseChangedSymbols(r io.Reader) ([]ChangedSymbol, error) {
+	var changes []ChangedSymbol
+	sc := bufio.NewScanner(r)
+	for sc.Scan() {
+		line := strings.TrimSpace(sc.Text())
+		line = strings.TrimSpace(strings.TrimPrefix(line, "- "))
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		path, note, _ := strings.Cut(line, " ")
+		path = strings.TrimSuffix(strings.Trim(path, "`"), "()")
+		changes = append(changes, ChangedSymbol{Path: path, Note: strings.TrimSpace(note)})
+	}
+	if err := sc.Err(); err != nil {
+		return nil, fmt.Errorf("parse changed symbols: %w", err)
+	}
+	return changes, nil
+}
+
+// UpgradeImpact returns the references from the workspace to each changed
+// Rust item, so an upgrade can be sized before it starts. A path matches
+// the indexed items with that crate-qualified path: dependency items
+// ingested from rustdoc JSON, or items of a workspace crate. When none has
+// it exactly, as for a path through a re-export, it matches the items of
+// the same crate and name, and of the same type for a method or field
+// (the segment before the name is capitalized). References from test code
+// are left out when q excludes it, and those below q's minimum confidence
+// are dropped.
+func (q *QueryBuilder) UpgradeImpact(changes []ChangedSymbol) (*UpgradeImpact, error) {
+	packages := map[string]cargoPackage{}
+	result := &UpgradeImpact{Changes: []ChangeImpact{}, Files: []string{}}
+	files := map[string]bool{}
+	for _, change := range changes {
+		segments := strings.Split(change.Path, "::")
+		name, crate := segments[len(segments)-1], segments[0]
+		owner := "" // the type of a method or associated item
+		if len(segments) >= 3 && startsUpper(segments[len(segments)-2]) {
+			owner = segments[len(segments)-2]
+		}
+		candidates, err := q.store.SymbolsByName(name)
+		if err != nil {
+			return nil, fmt.Errorf("upgrade impact: %w", err)
+		}
+		var exact, sameCrate []SymbolResult
+		for _, c := range candidates {
+			sr, err := q.symbolResultByID(c.ID)
+			if err != nil {
+				return nil, fmt.Errorf("upgrade impact: %w", err)
+			}
+			if sr == nil {
+				continue
+			}
+			path, err := q.rustItemPath(sr, packages)
+			if err != nil {
+				continue // not a Rust item
+			}
+			switch {
+			case path == change.Path:
+				exact = append(exact, *sr)
+			case strings.HasPrefix(path, crate+"::"):
+				found := strings.Split(path, "::")
+				if p := found[len(found)-2]; p == owner || (owner == "" && !startsUpper(p)) {
+					sameCrate = append(sameCrate, *sr)
+				}
+			}
+		}
+		impact := ChangeImpact{ChangedSymbol: change, Symbols: exact, Sites: []ReferenceSite{}}
+		if len(exact) == 0 {
+			impact.Symbols = sameCrate
+		}
+		if impact.Symbols == nil {
+			impact.Symbols = []SymbolResult{}
+		}
+
+		for _, sym := range impact.Symbols {
+			err := q.EachReferenceTo(sym.ID, ReferenceFilter{}, func(site ReferenceSite) error {
+				if !strings.HasPrefix(site.File, rustdocStubPrefix) {
+					impact.Sites = append(impact.Sites, site)
+				}
+				return nil
+			})
+			if err != nil {
+				return nil, fmt.Errorf("upgrade impact: %w", err)
+			}
+		}
+		sort.SliceStable(impact.Sites, func(i, j int) bool {
+			a, b := impact.Sites[i], impact.Sites[j]
+			if a.File != b.File {
+				return a.File < b.File
+			}
+			if a.StartLine != b.StartLine {
+				return a.StartLine < b.StartLine
+			}
+			return a.StartCol < b.StartCol
+		})
+		for _, site := range impact.Sites {
+			files[site.File] = true
+		}
+		result.Sites += len(impact.Sites)
+		result.Changes = append(result.Changes, impact)
+	}
+	for f := range files {
+		result.Files = append(result.Files, f)
+	}
+	sort.Strings(result.Files)
+	return result, nil
+}
+
+// startsUpper reports whether s starts with an upper case letter, as Rust
+// type names do.
+func startsUpper(s string) bool {
+	r, _ := utf8.DecodeRuneInString(s)
+	return unicode.IsUpper(r)
+}
@@ -0,0 +1,78 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseChangedSymbols(t *testing.T) {
+	changes, err := ParseChangedSymbols(strings.NewReader(`# serde 2.0
+
+- ` + "`serde::de::Deserialize`" + ` removed
+serde::Serializer::serialize_str() now takes &[u8]
+serde::Error
+`))
+	require.NoError(t, err)
+	assert.Equal(t, []ChangedSymbol{
+		{Path: "serde::de::Deserialize", Note: "removed"},
+		{Path: "serde::Serializer::serialize_str", Note: "now takes &[u8]"},
+		{Path: "serde::Error"},
+	}, changes)
+}
+
+func TestUpgradeImpact(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	_, _, err := e.IngestRustdocJSON(strings.NewReader(rustdocSample))
+	require.NoError(t, err)
+
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte("[package]\nname = \"app\"\nversion = \"0.1.0\"\n"), 0o644))
+	lib := filepath.Join(root, "src", "lib.rs")
+	require.NoError(t, os.WriteFile(lib, []byte(`use serde::de::Deserialize;
+use serde::Serializer;
+
+pub fn load<T: Deserialize>() {}
+pub fn save<S: Serializer>(s: S) {}
+`), 0o644))
+	require.NoError(t, e.IndexFiles(ctx, []string{lib}))
+	require.NoError(t, e.Resolve(ctx))
+
+	impact, err := e.Query().UpgradeImpact([]ChangedSymbol{
+		{Path: "serde::de::Deserialize", Note: "removed"},
+		{Path: "serde::Deserialize", Note: "through a re-export"},
+		{Path: "serde::Serializer"},
+		{Path: "serde::Missing"},
+	})
+	require.NoError(t, err)
+	require.Len(t, impact.Changes, 4)
+
+	lines := func(c ChangeImpact) []int {
+		var out []int
+		for _, s := range c.Sites {
+			assert.Equal(t, lib, s.File)
+			out = append(out, s.StartLine)
+		}
+		return out
+	}
+	deserialize := impact.Changes[0]
+	require.Len(t, deserialize.Symbols, 1)
+	assert.Equal(t, "rustdoc:serde::de", deserialize.Symbols[0].FilePath)
+	assert.Equal(t, []int{0, 3}, lines(deserialize), "the import and the bound")
+	assert.Equal(t, "removed", deserialize.Note)
+
+	assert.Equal(t, deserialize.Symbols, impact.Changes[1].Symbols, "matched by crate and name")
+	assert.Equal(t, []int{1, 4}, lines(impact.Changes[2]))
+	assert.Empty(t, impact.Changes[3].Symbols)
+	assert.Empty(t, impact.Changes[3].Sites)
+
+	assert.Equal(t, 6, impact.Sites)
+	assert.Equal(t, []string{lib}, impact.Files)
+}
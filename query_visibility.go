@@ -0,0 +1,347 @@
+package canopy
+
+import (
+	"fmt"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// RustScope is where a Rust item's visibility lets it be used: everywhere
+// (pub), or within a module of its crate and that module's descendants.
+type RustScope struct {
+	Everywhere bool
+	Module     string // module path in the crate, "" for the crate root
+}
+
+// String renders the scope as "everywhere" or a crate-relative path
+// ("crate", "crate::net").
+func (s RustScope) String() string {
+	if s.Everywhere {
+		return "everywhere"
+	}
+	return rustCratePath(s.Module)
+}
+
+// contains reports whether module, a module path of the same crate, is in
+// the scope.
+func (s RustScope) contains(module string) bool {
+	return s.Everywhere || s.Module == "" || module == s.Module || strings.HasPrefix(module, s.Module+"::")
+}
+
+// RustVisibilityScope resolves a Rust visibility as extraction records it
+// ("public", "pub(crate)", "pub(super)", "pub(self)", "pub(in path)", or
+// "private") for an item declared in module, a module path in its crate.
+func RustVisibilityScope(visibility, module string) (RustScope, error) {
+	v := strings.Join(strings.Fields(visibility), "")
+	switch v {
+	case "public", "pub":
+		return RustScope{Everywhere: true}, nil
+	case "pub(crate)", "crate":
+		return RustScope{}, nil
+	case "private", "", "pub(self)":
+		return RustScope{Module: module}, nil
+	case "pub(super)":
+		return RustScope{Module: rustParentModule(module)}, nil
+	}
+	path, ok := strings.CutPrefix(v, "pub(in")
+	if !ok || !strings.HasSuffix(path, ")") {
+		return RustScope{}, fmt.Errorf("unknown visibility %q", visibility)
+	}
+	segments := strings.Split(strings.TrimSuffix(path, ")"), "::")
+	scope := module
+	switch segments[0] {
+	case "crate":
+		scope = ""
+	case "self":
+	case "super":
+		scope = rustParentModule(module)
+	default:
+		return RustScope{}, fmt.Errorf("visibility %q: path must start with crate, self, or super", visibility)
+	}
+	for _, seg := range segments[1:] {
+		if seg == "super" {
+			scope = rustParentModule(scope)
+		} else {
+			scope = joinRustModule(scope, seg)
+		}
+	}
+	return RustScope{Module: scope}, nil
+}
+
+// rustParentModule returns the module enclosing module; the crate root is
+// its own parent.
+func rustParentModule(module string) string {
+	if i := strings.LastIndex(module, "::"); i >= 0 {
+		return module[:i]
+	}
+	return ""
+}
+
+// rustCratePath renders a module path in the crate as a path from the crate
+// root: "" -> "crate", "net::http" -> "crate::net::http".
+func rustCratePath(module string) string {
+	if module == "" {
+		return "crate"
+	}
+	return "crate::" + module
+}
+
+// VisibilityStep is one item on the path to a symbol, an enclosing module
+// or the symbol itself, with the scope its visibility allows.
+type VisibilityStep struct {
+	Name       string    // "crate::net" for a module, "crate::net::Client" for the symbol
+	Visibility string    // as declared; "" when the declaration is not indexed
+	Scope      RustScope // where the step is visible
+	Visible    bool      // whether the scope admits the module asked about
+}
+
+// SymbolVisibility answers whether a Rust symbol can be named from a module.
+type SymbolVisibility struct {
+	Symbol  SymbolResult
+	Crate   string // crate of the symbol
+	Module  string // module declaring the symbol, as a crate path ("crate::net")
+	From    string // module asked about: a crate path, or "<crate>::..." for another crate
+	Visible bool
+	// Steps are the enclosing modules from the crate root down, then the
+	// symbol. The symbol is visible when every step is.
+	Steps  []VisibilityStep
+	Reason string
+}
+
+// VisibleFrom reports whether the Rust symbol can be named from module by
+// its path, applying Rust's privacy rules to the symbol and each module
+// enclosing it. module is a Rust path — "crate::a::b" in the symbol's
+// crate, "<crate>::a" in the same or another crate — or the path of a
+// module file. Trait items and enum variants take the visibility of
+// their trait or enum. Re-exports, which can make an item reachable by
+// another path, are not considered. Returns nil with no error if the
+// symbol does not exist.
+func (q *QueryBuilder) VisibleFrom(module string, symbolID int64) (*SymbolVisibility, error) {
+	sr, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("visible from: %w", err)
+	}
+	if sr == nil {
+		return nil, nil
+	}
+	packages := map[string]cargoPackage{}
+	home, ok := q.rustModuleOf(sr.FilePath, packages)
+	if !ok {
+		return nil, fmt.Errorf("visible from: %s is not in a Rust crate", sr.FilePath)
+	}
+
+	// The module asked about.
+	var target rustModule
+	if strings.Contains(module, "::") || !strings.ContainsAny(module, `/\.`) {
+		segments := strings.Split(module, "::")
+		switch segments[0] {
+		case "crate", home.crate:
+			target = home
+			target.path = strings.Join(segments[1:], "::")
+		default:
+			target = rustModule{crate: segments[0], path: strings.Join(segments[1:], "::")}
+		}
+	} else {
+		path, err := filepath.Abs(module)
+		if err != nil {
+			return nil, fmt.Errorf("visible from: %w", err)
+		}
+		if target, ok = q.rustModuleOf(path, packages); !ok {
+			return nil, fmt.Errorf("visible from: %s is not in a Rust crate", module)
+		}
+	}
+	sameCrate := target.crate == home.crate && target.root == home.root
+	from := rustCratePath(target.path)
+	if !sameCrate {
+		from = target.crate
+		if target.path != "" {
+			from += "::" + target.path
+		}
+	}
+
+	steps, declaredIn, err := q.visibilitySteps(home, sr)
+	if err != nil {
+		return nil, fmt.Errorf("visible from: %w", err)
+	}
+	result := &SymbolVisibility{
+		Symbol:  *sr,
+		Crate:   home.crate,
+		Module:  rustCratePath(declaredIn),
+		From:    from,
+		Visible: true,
+		Steps:   steps,
+	}
+	for i := range result.Steps {
+		st := &result.Steps[i]
+		st.Visible = st.Visibility == "" || st.Scope.Everywhere || (sameCrate && st.Scope.contains(target.path))
+		if !st.Visible && result.Visible {
+			result.Visible = false
+			result.Reason = fmt.Sprintf("%s is %s: visible only in %s and its descendants, not %s",
+				st.Name, st.Visibility, st.Scope, from)
+			if !sameCrate {
+				result.Reason = fmt.Sprintf("%s is %s: not visible outside crate %s, as from %s",
+					st.Name, st.Visibility, home.crate, from)
+			}
+		}
+	}
+	if result.Visible {
+		result.Reason = fmt.Sprintf("%s and every module enclosing it are visible from %s", sr.Name, from)
+	}
+	return result, nil
+}
+
+// visibilitySteps returns the visibility steps to sr, which is declared in
+// home's file, and the module path sr is declared in.
+func (q *QueryBuilder) visibilitySteps(home rustModule, sr *SymbolResult) ([]VisibilityStep, string, error) {
+	var steps []VisibilityStep
+	addStep := func(name, visibility, declaredIn string) error {
+		step := VisibilityStep{Name: name, Visibility: visibility}
+		if visibility != "" {
+			scope, err := RustVisibilityScope(visibility, declaredIn)
+			if err != nil {
+				return fmt.Errorf("%s: %w", name, err)
+			}
+			step.Scope = scope
+		}
+		steps = append(steps, step)
+		return nil
+	}
+
+	// Modules of files: each is declared by a mod item in its parent's file.
+	var prefix string
+	for _, seg := range strings.Split(home.path, "::") {
+		if seg == "" {
+			break
+		}
+		decl, err := q.rustModuleDecl(home, prefix, seg)
+		if err != nil {
+			return nil, "", err
+		}
+		visibility := ""
+		if decl != nil {
+			visibility = decl.Visibility
+		}
+		if err := addStep(rustCratePath(joinRustModule(prefix, seg)), visibility, prefix); err != nil {
+			return nil, "", err
+		}
+		prefix = joinRustModule(prefix, seg)
+	}
+
+	// Inline modules of the file enclosing the symbol, outermost first.
+	if sr.FileID == nil {
+		return nil, "", fmt.Errorf("symbol %d has no file", sr.ID)
+	}
+	syms, err := q.store.SymbolsByFile(*sr.FileID)
+	if err != nil {
+		return nil, "", err
+	}
+	var enclosing []*store.Symbol
+	var owner *store.Symbol // trait or enum whose visibility the symbol takes
+	for _, s := range syms {
+		if s.ID == sr.ID {
+			continue
+		}
+		if sr.ParentSymbolID != nil && s.ID == *sr.ParentSymbolID && (s.Kind == "trait" || s.Kind == "enum") {
+			owner = s
+		}
+		if s.Kind == "module" && spanContains(symbolSpan(s), symbolSpan(&sr.Symbol)) {
+			enclosing = append(enclosing, s)
+		}
+	}
+	sort.Slice(enclosing, func(i, j int) bool { return enclosing[i].StartByte < enclosing[j].StartByte })
+	module := home.path
+	for _, s := range enclosing {
+		if err := addStep(rustCratePath(joinRustModule(module, s.Name)), s.Visibility, module); err != nil {
+			return nil, "", err
+		}
+		module = joinRustModule(module, s.Name)
+	}
+
+	name := rustCratePath(joinRustModule(module, sr.Name))
+	if owner != nil {
+		name = rustCratePath(joinRustModule(module, owner.Name+"::"+sr.Name))
+		if err := addStep(name, owner.Visibility, module); err != nil {
+			return nil, "", err
+		}
+		return steps, module, nil
+	}
+	if err := addStep(name, sr.Visibility, module); err != nil {
+		return nil, "", err
+	}
+	return steps, module, nil
+}
+
+// symbolSpan returns the span of a symbol, without its file.
+func symbolSpan(s *store.Symbol) Location {
+	return Location{StartLine: s.StartLine, StartCol: s.StartCol, EndLine: s.EndLine, EndCol: s.EndCol}
+}
+
+// rustModule is the place of a Rust source file in its crate.
+type rustModule struct {
+	crate string // crate name, with - replaced by _
+	dir   string // package directory
+	root  string // crate root file
+	path  string // module path in the crate, "" for the crate root
+}
+
+// rustModuleOf returns the crate and module path of the Rust file at path.
+// A package's library crate is rooted at src/lib.rs; src/main.rs and
+// src/bin/<name>.rs each root a binary crate.
+func (q *QueryBuilder) rustModuleOf(path string, packages map[string]cargoPackage) (rustModule, bool) {
+	pkg := cargoPackageFor(filepath.Dir(path), packages)
+	if pkg.name == "" {
+		return rustModule{}, false
+	}
+	src := filepath.Join(pkg.dir, "src")
+	rel, err := filepath.Rel(src, path)
+	if err != nil || strings.HasPrefix(rel, "..") {
+		return rustModule{}, false
+	}
+	m := rustModule{crate: strings.ReplaceAll(pkg.name, "-", "_"), dir: pkg.dir}
+	switch rel := filepath.ToSlash(rel); {
+	case rel == "main.rs" || (strings.HasPrefix(rel, "bin/") && strings.Count(rel, "/") == 1):
+		m.root = path
+		return m, true
+	case strings.HasPrefix(rel, "bin/"):
+		return rustModule{}, false
+	}
+	m.root = filepath.Join(src, "lib.rs")
+	if f, err := q.store.FileByPath(m.root); err != nil || f == nil {
+		m.root = filepath.Join(src, "main.rs")
+	}
+	m.path = (&rustCrate{cargoPackage: pkg, root: m.root}).modulePath(path)
+	return m, true
+}
+
+// rustModuleDecl returns the mod item declaring module name in module
+// parent of m's crate, or nil if it is not indexed. It is looked for in the
+// file of parent: the crate root, parent.rs, or parent/mod.rs.
+func (q *QueryBuilder) rustModuleDecl(m rustModule, parent, name string) (*store.Symbol, error) {
+	files := []string{m.root}
+	if parent != "" {
+		base := filepath.Join(m.dir, "src", filepath.FromSlash(strings.ReplaceAll(parent, "::", "/")))
+		files = []string{base + ".rs", filepath.Join(base, "mod.rs")}
+	}
+	for _, path := range files {
+		f, err := q.store.FileByPath(path)
+		if err != nil {
+			return nil, err
+		}
+		if f == nil {
+			continue
+		}
+		syms, err := q.store.SymbolsByFile(f.ID)
+		if err != nil {
+			return nil, err
+		}
+		for _, s := range syms {
+			if s.Kind == "module" && s.Name == name {
+				return s, nil
+			}
+		}
+	}
+	return nil, nil
+}
@@ -0,0 +1,136 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRustVisibilityScope(t *testing.T) {
+	tests := []struct {
+		visibility string
+		module     string
+		want       RustScope
+	}{
+		{"public", "a::b", RustScope{Everywhere: true}},
+		{"pub(crate)", "a::b", RustScope{}},
+		{"private", "a::b", RustScope{Module: "a::b"}},
+		{"pub(self)", "a::b", RustScope{Module: "a::b"}},
+		{"pub(super)", "a::b", RustScope{Module: "a"}},
+		{"pub(super)", "a", RustScope{}},
+		{"pub(in crate::a)", "a::b::c", RustScope{Module: "a"}},
+		{"pub(in super::super)", "a::b::c", RustScope{Module: "a"}},
+		{"pub(in self::d)", "a", RustScope{Module: "a::d"}},
+		{"pub( in crate :: a )", "a::b", RustScope{Module: "a"}},
+	}
+	for _, tt := range tests {
+		got, err := RustVisibilityScope(tt.visibility, tt.module)
+		require.NoError(t, err, tt.visibility)
+		assert.Equal(t, tt.want, got, "%s in %s", tt.visibility, tt.module)
+	}
+
+	for _, vis := range []string{"pub(in a::b)", "protected"} {
+		_, err := RustVisibilityScope(vis, "a")
+		assert.Error(t, err, vis)
+	}
+
+	assert.True(t, RustScope{Module: "a"}.contains("a::b"))
+	assert.False(t, RustScope{Module: "a"}.contains("ab"))
+	assert.Equal(t, "crate::a", RustScope{Module: "a"}.String())
+}
+
+func TestVisibleFrom(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	write := func(path, src string) {
+		t.Helper()
+		require.NoError(t, os.MkdirAll(filepath.Dir(filepath.Join(root, path)), 0o755))
+		require.NoError(t, os.WriteFile(filepath.Join(root, path), []byte(src), 0o644))
+	}
+	write("Cargo.toml", "[package]\nname = \"demo-crate\"\nversion = \"0.1.0\"\n")
+	write("src/lib.rs", `pub mod api;
+mod internal;
+
+pub(crate) fn crate_fn() {}
+
+mod inline {
+    pub fn nested() {}
+}
+`)
+	write("src/api.rs", `pub mod v1;
+
+pub fn open() {}
+
+fn private() {}
+`)
+	write("src/api/v1.rs", `pub(in crate::api) fn scoped() {}
+
+pub(super) fn up() {}
+`)
+	write("src/internal.rs", "pub fn helper() {}\n")
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	symbolID := func(name string) int64 {
+		t.Helper()
+		syms, err := e.store.SymbolsByName(name)
+		require.NoError(t, err)
+		require.Len(t, syms, 1, name)
+		return syms[0].ID
+	}
+
+	tests := []struct {
+		symbol  string
+		from    string
+		visible bool
+		blocked string // name of the first step that is not visible
+	}{
+		{"open", "other::x", true, ""},
+		{"open", "demo_crate::internal", true, ""},
+		{"helper", "crate::api", true, ""},
+		{"helper", "other", false, "crate::internal"},
+		{"crate_fn", "crate::api::v1", true, ""},
+		{"crate_fn", "other", false, "crate::crate_fn"},
+		{"scoped", "crate::api", true, ""},
+		{"scoped", "crate::api::v1", true, ""},
+		{"scoped", "crate", false, "crate::api::v1::scoped"},
+		{"up", "crate::internal", false, "crate::api::v1::up"},
+		{"private", "crate", false, "crate::api::private"},
+		{"private", filepath.Join(root, "src", "api", "v1.rs"), true, ""},
+		{"nested", "crate::api", true, ""},
+		{"nested", "other", false, "crate::inline"},
+	}
+	for _, tt := range tests {
+		v, err := e.Query().VisibleFrom(tt.from, symbolID(tt.symbol))
+		require.NoError(t, err, "%s from %s", tt.symbol, tt.from)
+		require.NotNil(t, v)
+		assert.Equal(t, "demo_crate", v.Crate)
+		assert.Equal(t, tt.visible, v.Visible, "%s from %s: %s", tt.symbol, tt.from, v.Reason)
+		blocked := ""
+		for _, st := range v.Steps {
+			if !st.Visible {
+				blocked = st.Name
+				break
+			}
+		}
+		assert.Equal(t, tt.blocked, blocked, "%s from %s", tt.symbol, tt.from)
+		assert.Contains(t, v.Reason, v.From)
+	}
+
+	v, err := e.Query().VisibleFrom("crate::api", symbolID("scoped"))
+	require.NoError(t, err)
+	assert.Equal(t, "crate::api::v1", v.Module)
+	require.Len(t, v.Steps, 3)
+	assert.Equal(t, VisibilityStep{Name: "crate::api", Visibility: "public", Scope: RustScope{Everywhere: true}, Visible: true}, v.Steps[0])
+	assert.Equal(t, VisibilityStep{Name: "crate::api::v1::scoped", Visibility: "pub(in crate::api)", Scope: RustScope{Module: "api"}, Visible: true}, v.Steps[2])
+
+	v, err = e.Query().VisibleFrom("crate", 999999)
+	require.NoError(t, err)
+	assert.Nil(t, v)
+}
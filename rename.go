@@ -0,0 +1,300 @@
+package canopy
+
+import (
+	"bytes"
+	"fmt"
+	"regexp"
+	"sort"
+	"unicode"
+	"unicode/utf16"
+	"unicode/utf8"
+)
+
+// RenameEdit is a single span that must change to rename a symbol. The span
+// covers exactly the identifier (always a single line).
+type RenameEdit struct {
+	Location
+	Kind    string // "definition", "reference", or "import"
+	OldText string
+	NewText string
+}
+
+// RenamePlan lists every edit needed to rename a symbol. Building a plan
+// never modifies files.
+type RenamePlan struct {
+	Symbol  SymbolResult
+	NewName string
+	Edits   []RenameEdit // sorted by file, then position
+}
+
+// TextEdits returns the plan as edits for ApplyEdits. Each edit expects the
+// old name at its span, so files changed since indexing are rejected.
+func (p *RenamePlan) TextEdits() []TextEdit {
+	edits := make([]TextEdit, len(p.Edits))
+	for i, e := range p.Edits {
+		edits[i] = TextEdit{Location: e.Location, OldText: e.OldText, NewText: e.NewText}
+	}
+	return edits
+}
+
+// Rename edit kinds.
+const (
+	RenameEditDefinition = "definition"
+	RenameEditReference  = "reference"
+	RenameEditImport     = "import"
+)
+
+var identRe = regexp.MustCompile(`^[\p{L}_][\p{L}\p{N}_]*$`)
+
+// importLineRe matches the first line of an import-like statement:
+// Rust `use`/`pub use`, `import`, Python `from`, JS/TS `export {`/`export *`.
+var importLineRe = regexp.MustCompile(`^\s*(?:(?:pub(?:\([^)]*\))?\s+)?use\s|import\b|from\s|export\s*[{*])`)
+
+// PlanRename computes the edits needed to rename a symbol to newName: its
+// definition, every resolved reference, and occurrences in import/use/re-export
+// statements of files that reference or re-export it. Spans are located by
+// reading the indexed files (or their overlays, see Engine.SetOverlay), so
+// the plan reflects their current contents. Symbols without a source file (e.g. rustdoc stubs) cannot be
+// renamed.
+func (q *QueryBuilder) PlanRename(symbolID int64, newName string) (*RenamePlan, error) {
+	if !identRe.MatchString(newName) {
+		return nil, fmt.Errorf("plan rename: %q is not a valid identifier", newName)
+	}
+	sr, err := q.symbolResultByID(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("plan rename: %w", err)
+	}
+	if sr == nil {
+		return nil, fmt.Errorf("plan rename: symbol %d not found", symbolID)
+	}
+	if sr.FileID == nil || sr.FilePath == "" {
+		return nil, fmt.Errorf("plan rename: symbol %s has no source file", sr.Name)
+	}
+	if sr.Name == newName {
+		return nil, fmt.Errorf("plan rename: symbol is already named %s", newName)
+	}
+
+	p := &renamePlanner{
+		name:    sr.Name,
+		newName: newName,
+		read:    q.readSource,
+		files:   map[string][][]byte{},
+		seen:    map[Location]bool{},
+		module:  sr.Kind == "module" || sr.Kind == "package" || sr.Kind == "namespace",
+	}
+	plan := &RenamePlan{Symbol: *sr, NewName: newName}
+
+	// Definition: first occurrence of the name inside the declaration span.
+	defLoc, err := q.symbolLocation(symbolID)
+	if err != nil {
+		return nil, fmt.Errorf("plan rename: symbol location: %w", err)
+	}
+	if defLoc != nil {
+		if e, ok := p.locate(*defLoc, RenameEditDefinition, false); ok {
+			plan.Edits = append(plan.Edits, e)
+		}
+	}
+
+	// References: last occurrence inside the reference span, so qualified
+	// paths like a::b::name or pkg.Name edit only the final segment.
+	importFiles := map[string]bool{}
+	err = q.EachReferenceTo(symbolID, ReferenceFilter{}, func(site ReferenceSite) error {
+		importFiles[site.File] = true
+		kind := RenameEditReference
+		if site.Kind == RefKindImport {
+			kind = RenameEditImport
+		}
+		if e, ok := p.locate(site.Location, kind, true); ok {
+			plan.Edits = append(plan.Edits, e)
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, fmt.Errorf("plan rename: %w", err)
+	}
+
+	// Files re-exporting the symbol also carry its name in a use/export.
+	rows, err := q.store.DB().Query(
+		`SELECT DISTINCT f.path FROM reexports re JOIN files f ON f.id = re.file_id
+		 WHERE re.original_symbol_id = ?`, symbolID,
+	)
+	if err != nil {
+		return nil, fmt.Errorf("plan rename: reexports: %w", err)
+	}
+	for rows.Next() {
+		var path string
+		if err := rows.Scan(&path); err != nil {
+			rows.Close()
+			return nil, fmt.Errorf("plan rename: scan reexport: %w", err)
+		}
+		importFiles[path] = true
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("plan rename: reexports: %w", err)
+	}
+
+	for path := range importFiles {
+		plan.Edits = append(plan.Edits, p.importEdits(path)...)
+	}
+
+	sort.Slice(plan.Edits, func(i, j int) bool {
+		a, b := plan.Edits[i], plan.Edits[j]
+		if a.File != b.File {
+			return a.File < b.File
+		}
+		if a.StartLine != b.StartLine {
+			return a.StartLine < b.StartLine
+		}
+		return a.StartCol < b.StartCol
+	})
+	return plan, nil
+}
+
+// renamePlanner locates identifier spans in file contents, caching each file
+// and skipping spans that were already planned.
+type renamePlanner struct {
+	name    string
+	newName string
+	module  bool // the symbol is a module, so path-prefix occurrences count too
+	read    func(path string) ([]byte, error)
+	files   map[string][][]byte
+	seen    map[Location]bool
+}
+
+// lines returns the lines of path, or nil if it cannot be read.
+func (p *renamePlanner) lines(path string) [][]byte {
+	if lines, ok := p.files[path]; ok {
+		return lines
+	}
+	var lines [][]byte
+	if content, err := p.read(path); err == nil {
+		lines = bytes.Split(content, []byte{'\n'})
+	}
+	p.files[path] = lines
+	return lines
+}
+
+// locate finds the first (or last) whole-word occurrence of the name within
+// loc and returns it as an edit.
+func (p *renamePlanner) locate(loc Location, kind string, last bool) (RenameEdit, bool) {
+	lines := p.lines(loc.File)
+	found := false
+	var line, col int
+	for l := loc.StartLine; l <= loc.EndLine && l < len(lines); l++ {
+		text := bytes.TrimRight(lines[l], "\r")
+		lo, hi := 0, len(text)
+		if l == loc.StartLine {
+			lo = min(loc.StartCol, len(text))
+		}
+		if l == loc.EndLine {
+			hi = min(max(loc.EndCol, lo), len(text))
+		}
+		for _, c := range identOccurrences(text, p.name, lo, hi) {
+			line, col, found = l, c, true
+			if !last {
+				break
+			}
+		}
+		if found && !last {
+			break
+		}
+	}
+	if !found {
+		return RenameEdit{}, false
+	}
+	return p.edit(loc.File, line, col, kind)
+}
+
+// importEdits returns edits for occurrences of the name inside import-like
+// statements of path. Occurrences followed by a path separator are module
+// prefixes, not the symbol, unless the symbol itself is a module.
+func (p *renamePlanner) importEdits(path string) []RenameEdit {
+	var edits []RenameEdit
+	lines := p.lines(path)
+	depth := 0
+	for l, raw := range lines {
+		text := bytes.TrimRight(raw, "\r")
+		if depth <= 0 {
+			depth = 0
+			if !importLineRe.Match(text) {
+				continue
+			}
+		}
+		depth += bytes.Count(text, []byte("{")) + bytes.Count(text, []byte("(")) -
+			bytes.Count(text, []byte("}")) - bytes.Count(text, []byte(")"))
+		for _, c := range identOccurrences(text, p.name, 0, len(text)) {
+			rest := text[c+len(p.name):]
+			if !p.module && (bytes.HasPrefix(rest, []byte("::")) || bytes.HasPrefix(rest, []byte(".")) ||
+				bytes.HasPrefix(rest, []byte("/")) || bytes.HasPrefix(rest, []byte("\\"))) {
+				continue
+			}
+			if e, ok := p.edit(path, l, c, RenameEditImport); ok {
+				edits = append(edits, e)
+			}
+		}
+	}
+	return edits
+}
+
+// edit builds the edit replacing the name at (line, col), or reports false if
+// that span was already planned.
+func (p *renamePlanner) edit(path string, line, col int, kind string) (RenameEdit, bool) {
+	loc := Location{File: path, StartLine: line, StartCol: col, EndLine: line, EndCol: col + len(p.name)}
+	if p.seen[loc] {
+		return RenameEdit{}, false
+	}
+	p.seen[loc] = true
+	lines := p.lines(path)
+	lineStart := 0
+	for _, l := range lines[:line] {
+		lineStart += len(l) + 1
+	}
+	text := lines[line]
+	loc.StartByte = lineStart + col
+	loc.EndByte = lineStart + col + len(p.name)
+	loc.UTF16StartCol = utf16Len(text[:col])
+	loc.UTF16EndCol = utf16Len(text[:col+len(p.name)])
+	return RenameEdit{
+		Location: loc,
+		Kind:     kind,
+		OldText:  p.name,
+		NewText:  p.newName,
+	}, true
+}
+
+// identOccurrences returns the byte columns of whole-word occurrences of name
+// in text[lo:hi].
+func identOccurrences(text []byte, name string, lo, hi int) []int {
+	var cols []int
+	for i := lo; i+len(name) <= hi; {
+		j := bytes.Index(text[i:hi], []byte(name))
+		if j < 0 {
+			break
+		}
+		c := i + j
+		end := c + len(name)
+		before, _ := utf8.DecodeLastRune(text[:c])
+		after, _ := utf8.DecodeRune(text[end:])
+		if (c == 0 || !isIdentRune(before)) && (end == len(text) || !isIdentRune(after)) {
+			cols = append(cols, c)
+		}
+		i = c + 1
+	}
+	return cols
+}
+
+func isIdentRune(r rune) bool {
+	return r == '_' || r == '$' || unicode.IsLetter(r) || unicode.IsDigit(r)
+}
+
+// utf16Len returns the length of b in UTF-16 code units.
+func utf16Len(b []byte) int {
+	n := 0
+	for len(b) > 0 {
+		r, size := utf8.DecodeRune(b)
+		n += utf16.RuneLen(r)
+		b = b[size:]
+	}
+	return n
+}
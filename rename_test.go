@@ -0,0 +1,183 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestPlanRename_GoDefinitionAndReferences(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	libPath := writeGoFile(t, dir, "lib.go", `package main
+
+func greet() string {
+	return "hi"
+}
+`)
+	mainPath := writeGoFile(t, dir, "main.go", `package main
+
+func main() {
+	greet()
+	_ = greet() + "greet"
+}
+`)
+
+	require.NoError(t, e.IndexFiles(ctx, []string{libPath, mainPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	syms, err := e.store.SymbolsByName("greet")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+
+	plan, err := e.Query().PlanRename(syms[0].ID, "salute")
+	require.NoError(t, err)
+	assert.Equal(t, "salute", plan.NewName)
+
+	type span struct {
+		file      string
+		line, col int
+		kind      string
+	}
+	var got []span
+	for _, ed := range plan.Edits {
+		assert.Equal(t, "greet", ed.OldText)
+		assert.Equal(t, "salute", ed.NewText)
+		assert.Equal(t, ed.StartCol+len("greet"), ed.EndCol)
+		got = append(got, span{ed.File, ed.StartLine, ed.StartCol, ed.Kind})
+	}
+	assert.Equal(t, []span{
+		{libPath, 2, 5, RenameEditDefinition},
+		{mainPath, 3, 1, RenameEditReference},
+		{mainPath, 4, 5, RenameEditReference},
+	}, got, "string literal occurrences are not edited")
+}
+
+func TestPlanRename_RustUseStatement(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	utilPath := filepath.Join(dir, "util.rs")
+	require.NoError(t, os.WriteFile(utilPath, []byte(`pub fn greet(name: &str) -> String {
+    format!("Hello, {}", name)
+}
+`), 0644))
+	mainPath := filepath.Join(dir, "main.rs")
+	require.NoError(t, os.WriteFile(mainPath, []byte(`use crate::util::{greet, other};
+
+fn main() {
+    greet("world");
+}
+`), 0644))
+
+	require.NoError(t, e.IndexFiles(ctx, []string{utilPath, mainPath}))
+	require.NoError(t, e.Resolve(ctx))
+
+	syms, err := e.store.SymbolsByName("greet")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+
+	plan, err := e.Query().PlanRename(syms[0].ID, "salute")
+	require.NoError(t, err)
+
+	kinds := map[string]int{}
+	for _, ed := range plan.Edits {
+		kinds[ed.Kind]++
+		if ed.Kind == RenameEditImport {
+			assert.Equal(t, mainPath, ed.File)
+			assert.Equal(t, 0, ed.StartLine)
+			assert.Equal(t, 18, ed.StartCol)
+		}
+	}
+	assert.Equal(t, map[string]int{
+		RenameEditDefinition: 1,
+		RenameEditReference:  1,
+		RenameEditImport:     1,
+	}, kinds)
+}
+
+func TestPlanRename_RustStructPatterns(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+	dir := t.TempDir()
+
+	path := filepath.Join(dir, "lib.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`pub struct Config {
+    pub name: String,
+    pub port: u16,
+}
+
+fn describe(cfg: Config, opt: Option<Config>) -> u16 {
+    let Config { name, .. } = cfg;
+    if let Some(Config { port: p, .. }) = opt {
+        return p;
+    }
+    match opt {
+        Some(Config { port, name: _ }) => port,
+        None => 0,
+    }
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	syms, err := e.store.SymbolsByName("Config")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+
+	type pos struct{ line, col int }
+	fields, err := e.Query().ReferencesToPaged(syms[0].ID, ReferenceFilter{Kinds: []string{RefKindFieldAccess}}, Pagination{})
+	require.NoError(t, err)
+	var fieldSites []pos
+	for _, site := range fields.Items {
+		fieldSites = append(fieldSites, pos{site.StartLine, site.StartCol})
+	}
+	assert.Equal(t, []pos{{6, 17}, {7, 25}, {11, 22}, {11, 28}}, fieldSites, "fields named by let, if let, and match patterns")
+
+	plan, err := e.Query().PlanRename(syms[0].ID, "Settings")
+	require.NoError(t, err)
+	var edits []pos
+	for _, ed := range plan.Edits {
+		assert.Equal(t, "Config", ed.OldText)
+		edits = append(edits, pos{ed.StartLine, ed.StartCol})
+	}
+	assert.Equal(t, []pos{{0, 11}, {5, 17}, {5, 37}, {6, 8}, {7, 16}, {11, 13}}, edits,
+		"pattern types are renamed; the fields they name are not")
+}
+
+func TestPlanRename_RejectsInvalidNames(t *testing.T) {
+	t.Parallel()
+	q, s := newTestQueryBuilder(t)
+	fID := insertFile(t, s, "/test.go", "go")
+	symID := insertSymbol(t, s, &fID, "Foo", "function", "public", nil)
+
+	_, err := q.PlanRename(symID, "not valid")
+	assert.ErrorContains(t, err, "not a valid identifier")
+
+	_, err = q.PlanRename(symID, "Foo")
+	assert.ErrorContains(t, err, "already named")
+
+	_, err = q.PlanRename(9999, "Bar")
+	assert.ErrorContains(t, err, "not found")
+}
+
+func TestIdentOccurrences(t *testing.T) {
+	t.Parallel()
+	text := []byte("greet(greeting, greet_x, greet)")
+	assert.Equal(t, []int{0, 25}, identOccurrences(text, "greet", 0, len(text)))
+	assert.Equal(t, []int{25}, identOccurrences(text, "greet", 1, len(text)))
+}
+
+func TestUTF16Len(t *testing.T) {
+	t.Parallel()
+	assert.Equal(t, 3, utf16Len([]byte("abc")))
+	assert.Equal(t, 1, utf16Len([]byte("é")))
+	assert.Equal(t, 2, utf16Len([]byte("😀")))
+}
@@ -0,0 +1,69 @@
+package canopy
+
+import (
+	"encoding/json"
+	"fmt"
+	"path/filepath"
+	"slices"
+)
+
+// WithRootDependencies declares which of the roots indexed together, such
+// as the repositories of an organization, depend on which: deps maps a
+// root to the roots whose symbols it uses. Resolve then links a reference,
+// call, or implementation in one root to a symbol of another root only if
+// the first declares the second a dependency, so "who uses this API"
+// queries on a repository's symbols find exactly its declared dependents.
+// A root without an entry links to no other root. Links within a root and
+// links to files without a root, such as dependency stubs, are always
+// kept. Without WithRootDependencies every root links to every other.
+//
+// Declaring different dependencies than the database was resolved with
+// makes the next Resolve re-resolve every file.
+func WithRootDependencies(deps map[string][]string) Option {
+	return func(e *Engine) {
+		e.rootDeps = make(map[string][]string, len(deps))
+		for root, on := range deps {
+			cleaned := make([]string, len(on))
+			for i, r := range on {
+				cleaned[i] = filepath.Clean(r)
+			}
+			slices.Sort(cleaned)
+			e.rootDeps[filepath.Clean(root)] = slices.Compact(cleaned)
+		}
+	}
+}
+
+// rootDependenciesKey encodes the declared root dependencies for the
+// database's metadata, "" if there are none.
+func (e *Engine) rootDependenciesKey() string {
+	if e.rootDeps == nil {
+		return ""
+	}
+	// Maps marshal with sorted keys, so equal declarations encode equally.
+	data, _ := json.Marshal(e.rootDeps)
+	return string(data)
+}
+
+// rootDependenciesChanged reports whether the database was last resolved
+// with other root dependencies than the Engine declares.
+func (e *Engine) rootDependenciesChanged() (bool, error) {
+	stored, err := e.store.GetMetadata("root_dependencies")
+	if err != nil {
+		return false, err
+	}
+	return stored != e.rootDependenciesKey(), nil
+}
+
+// pruneRootLinks deletes the links between roots that the declared root
+// dependencies do not allow, and records the declaration.
+func (e *Engine) pruneRootLinks() error {
+	if e.rootDeps != nil {
+		_, err := e.store.DeleteCrossRootLinks(func(from, to string) bool {
+			return slices.Contains(e.rootDeps[from], to)
+		})
+		if err != nil {
+			return fmt.Errorf("prune root links: %w", err)
+		}
+	}
+	return e.store.SetMetadata("root_dependencies", e.rootDependenciesKey())
+}
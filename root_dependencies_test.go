@@ -0,0 +1,33 @@
+package canopy
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWithRootDependencies(t *testing.T) {
+	ctx := context.Background()
+	root := writeShardRepo(t)
+	roots := []string{filepath.Join(root, "a"), filepath.Join(root, "b")}
+
+	// b does not declare a, so its call into a stays unlinked.
+	e := newIntegrationEngine(t, WithLanguages("go"), WithRootDependencies(map[string][]string{}))
+	require.NoError(t, e.IndexDirectories(ctx, roots))
+	require.NoError(t, e.Resolve(ctx))
+	assert.Zero(t, resolvedRefsTo(t, e, "Helper"))
+
+	// Declaring the dependency re-resolves everything, though no file changed.
+	WithRootDependencies(map[string][]string{roots[1]: {roots[0]}})(e)
+	require.NoError(t, e.IndexDirectories(ctx, roots))
+	assert.Empty(t, e.blastRadius)
+	require.NoError(t, e.Resolve(ctx))
+	assert.Equal(t, 1, resolvedRefsTo(t, e, "Helper"))
+
+	violations, err := e.store.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+}
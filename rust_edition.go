@@ -0,0 +1,62 @@
+package canopy
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// defaultRustEdition is the edition Cargo assumes for a package whose
+// manifest does not name one.
+const defaultRustEdition = "2015"
+
+// cargoPackageEdition returns the edition in the [package] table of the
+// Cargo.toml in dir. An edition inherited with edition.workspace = true is
+// read from the [workspace.package] table of the nearest manifest at or
+// above dir that has one.
+func cargoPackageEdition(manifest []byte, dir string) string {
+	edition := cargoTableValue(manifest, "package", "edition")
+	inherited := cargoTableValue(manifest, "package", "edition.workspace") == "true" ||
+		strings.HasPrefix(edition, "{")
+	if !inherited {
+		if edition == "" {
+			return defaultRustEdition
+		}
+		return edition
+	}
+	for {
+		if ws, err := os.ReadFile(filepath.Join(dir, "Cargo.toml")); err == nil {
+			if edition := cargoTableValue(ws, "workspace.package", "edition"); edition != "" {
+				return edition
+			}
+		}
+		parent := filepath.Dir(dir)
+		if parent == dir {
+			return defaultRustEdition
+		}
+		dir = parent
+	}
+}
+
+// rustCrateEditions maps the path of each indexed Rust file in a Cargo
+// package to the package's edition and directory, for the Rust resolution
+// script: in the 2015 edition a use path starts at the crate root unless its
+// first segment names an extern crate, and extern crates are declared once
+// per package. Files outside any package, or under a virtual workspace
+// manifest, are left out and resolve by the rules of later editions.
+func (e *Engine) rustCrateEditions() (map[string]any, error) {
+	files, err := e.store.FilesByLanguage("rust")
+	if err != nil {
+		return nil, err
+	}
+	packages := map[string]cargoPackage{}
+	crates := make(map[string]any, len(files))
+	for _, f := range files {
+		pkg := cargoPackageFor(filepath.Dir(f.Path), packages)
+		if pkg.name == "" {
+			continue
+		}
+		crates[f.Path] = map[string]any{"edition": pkg.edition, "package": pkg.dir}
+	}
+	return crates, nil
+}
@@ -0,0 +1,101 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestCargoPackageEdition(t *testing.T) {
+	t.Parallel()
+	root := t.TempDir()
+	member := filepath.Join(root, "crates", "core")
+	require.NoError(t, os.MkdirAll(member, 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"),
+		[]byte("[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nedition = \"2021\"\n"), 0o644))
+
+	tests := []struct {
+		manifest string
+		want     string
+	}{
+		{"[package]\nname = \"demo\"\nedition = \"2018\"\n", "2018"},
+		{"[package]\nname = \"demo\"\nversion = \"0.1.0\"\n", "2015"},
+		{"[package]\nname = \"demo\"\nedition.workspace = true\n", "2021"},
+		{"[package]\nname = \"demo\"\nedition = { workspace = true }\n", "2021"},
+		{"[dependencies]\nedition = \"2024\"\n\n[package]\nname = \"demo\"\n", "2015"},
+	}
+	for _, tt := range tests {
+		assert.Equal(t, tt.want, cargoPackageEdition([]byte(tt.manifest), member), tt.manifest)
+	}
+}
+
+func TestRustEdition2015_UsePathsStartAtCrateRoot(t *testing.T) {
+	// The crate has a module named like the serde crate, whose stubs are
+	// ingested, and reaches the real serde through an extern crate alias.
+	lib := `extern crate serde as sd;
+mod serde;
+use serde::Serializer;
+use sd::de::Deserialize;
+
+pub fn check(s: &Serializer, d: &Deserialize) {}
+`
+	for _, tt := range []struct {
+		edition    string
+		serializer string // the file Serializer on line 5 resolves to
+	}{
+		{"", "src/serde.rs"},
+		{"2018", "rustdoc:serde"},
+	} {
+		t.Run("edition="+tt.edition, func(t *testing.T) {
+			e := newIntegrationEngine(t, WithLanguages("rust"))
+			ctx := context.Background()
+			_, _, err := e.IngestRustdocJSON(strings.NewReader(rustdocSample))
+			require.NoError(t, err)
+
+			root := t.TempDir()
+			manifest := "[package]\nname = \"legacy\"\nversion = \"0.1.0\"\n"
+			if tt.edition != "" {
+				manifest += "edition = \"" + tt.edition + "\"\n"
+			}
+			require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+			require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte(manifest), 0o644))
+			libPath := filepath.Join(root, "src", "lib.rs")
+			serdePath := filepath.Join(root, "src", "serde.rs")
+			require.NoError(t, os.WriteFile(libPath, []byte(lib), 0o644))
+			require.NoError(t, os.WriteFile(serdePath, []byte("pub trait Serializer {}\n"), 0o644))
+			require.NoError(t, e.IndexFiles(ctx, []string{libPath, serdePath}))
+			require.NoError(t, e.Resolve(ctx))
+
+			// targetFile returns the file the reference to name on line 5 resolved to.
+			targetFile := func(name string) string {
+				t.Helper()
+				refs, err := e.store.ReferencesByName(name)
+				require.NoError(t, err)
+				for _, r := range refs {
+					if r.StartLine != 5 {
+						continue
+					}
+					resolved, err := e.store.ResolvedReferencesByRef(r.ID)
+					require.NoError(t, err)
+					require.NotEmpty(t, resolved, "%s on line 5 is unresolved", name)
+					sym, err := e.store.SymbolByID(resolved[0].TargetSymbolID)
+					require.NoError(t, err)
+					require.NotNil(t, sym.FileID)
+					var file string
+					require.NoError(t, e.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", *sym.FileID).Scan(&file))
+					return strings.TrimPrefix(filepath.ToSlash(file), filepath.ToSlash(root)+"/")
+				}
+				t.Fatalf("no reference to %s on line 5", name)
+				return ""
+			}
+
+			assert.Equal(t, tt.serializer, targetFile("Serializer"))
+			assert.Equal(t, "rustdoc:serde::de", targetFile("Deserialize"), "the alias names the serde crate in every edition")
+		})
+	}
+}
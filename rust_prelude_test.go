@@ -0,0 +1,95 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// rustdocLogSample is a rustdoc JSON document for a crate "log" exporting
+// one macro.
+const rustdocLogSample = `{
+  "root": "0:0",
+  "crate_version": "0.4.20",
+  "index": {
+    "0:1": {"crate_id": 0, "visibility": "public"}
+  },
+  "paths": {
+    "0:0": {"crate_id": 0, "path": ["log"], "kind": "module"},
+    "0:1": {"crate_id": 0, "path": ["log", "info"], "kind": "macro"}
+  }
+}`
+
+func TestRustPrelude_GlobsPreludeAndMacroUse(t *testing.T) {
+	lib := `#[macro_use]
+extern crate log;
+mod util;
+use crate::util::*;
+
+pub fn run<T: TryFrom<u8>>(v: Vec<u8>) {
+    helper();
+    info!("ran");
+}
+`
+	for _, tt := range []struct {
+		edition string
+		tryFrom string // the file TryFrom resolves to, "" if unresolved
+	}{
+		{"2015", ""},
+		{"2021", "rustdoc:std::convert"},
+	} {
+		t.Run("edition="+tt.edition, func(t *testing.T) {
+			e := newIntegrationEngine(t, WithLanguages("rust"))
+			ctx := context.Background()
+			_, _, err := e.IngestRustdocJSON(strings.NewReader(rustdocLogSample))
+			require.NoError(t, err)
+
+			root := t.TempDir()
+			manifest := "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"" + tt.edition + "\"\n"
+			require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+			require.NoError(t, os.WriteFile(filepath.Join(root, "Cargo.toml"), []byte(manifest), 0o644))
+			libPath := filepath.Join(root, "src", "lib.rs")
+			utilPath := filepath.Join(root, "src", "util.rs")
+			require.NoError(t, os.WriteFile(libPath, []byte(lib), 0o644))
+			require.NoError(t, os.WriteFile(utilPath, []byte("pub fn helper() {}\n"), 0o644))
+			require.NoError(t, e.IndexFiles(ctx, []string{libPath, utilPath}))
+			require.NoError(t, e.Resolve(ctx))
+
+			// targetFile returns the file the reference to name on line
+			// resolved to, or "" if it is unresolved.
+			targetFile := func(name string, line int) string {
+				t.Helper()
+				refs, err := e.store.ReferencesByName(name)
+				require.NoError(t, err)
+				for _, r := range refs {
+					if r.StartLine != line {
+						continue
+					}
+					resolved, err := e.store.ResolvedReferencesByRef(r.ID)
+					require.NoError(t, err)
+					if len(resolved) == 0 {
+						return ""
+					}
+					sym, err := e.store.SymbolByID(resolved[0].TargetSymbolID)
+					require.NoError(t, err)
+					require.NotNil(t, sym.FileID)
+					var file string
+					require.NoError(t, e.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", *sym.FileID).Scan(&file))
+					return strings.TrimPrefix(filepath.ToSlash(file), filepath.ToSlash(root)+"/")
+				}
+				t.Fatalf("no reference to %s on line %d", name, line)
+				return ""
+			}
+
+			assert.Equal(t, "src/util.rs", targetFile("helper", 6), "through the glob import")
+			assert.Equal(t, "rustdoc:log", targetFile("info", 7), "through #[macro_use] extern crate log")
+			assert.Equal(t, "rustdoc:std::vec", targetFile("Vec", 5), "Vec is in every edition's prelude")
+			assert.Equal(t, tt.tryFrom, targetFile("TryFrom", 5), "TryFrom joined the prelude in 2021")
+		})
+	}
+}
@@ -0,0 +1,101 @@
+package canopy
+
+import (
+	"bufio"
+	"bytes"
+	"compress/gzip"
+	"crypto/sha256"
+	_ "embed"
+	"encoding/hex"
+	"fmt"
+	"strings"
+)
+
+// rustStdIndex lists the public items of std, core, and alloc, one
+// "<kind> <path>" per line (e.g. "function std::vec::Vec::new"), gzipped.
+// Lines starting with '#' are comments. Members of a type follow the type's
+// path; "function" members are associated functions, "method" members take
+// self. It is generated from the curated list in stdlib/rust-std.txt; edit
+// the list and run go generate to change it.
+//
+//go:generate go run ./internal/ruststdgen -in stdlib/rust-std.txt -out stdlib/rust-std.idx.gz
+//go:embed stdlib/rust-std.idx.gz
+var rustStdIndex []byte
+
+// rustStdCrates are the crates covered by rustStdIndex.
+var rustStdCrates = []string{"std", "core", "alloc"}
+
+// rustStdVersionPrefix marks stub files created from rustStdIndex, as
+// opposed to std stubs a user ingested from rustdoc JSON.
+const rustStdVersionPrefix = "index-"
+
+// loadRustStdStubs records the items of rustStdIndex as stub symbols, so
+// references such as Vec::new, String::from, and println! resolve like
+// references to ingested dependency crates. A crate whose stubs are already
+// current, or were ingested from rustdoc JSON, is left alone. Returns the
+// number of stub symbols created.
+func (e *Engine) loadRustStdStubs() (int, error) {
+	sum := sha256.Sum256(rustStdIndex)
+	version := rustStdVersionPrefix + hex.EncodeToString(sum[:8])
+
+	var pending []string
+	for _, crate := range rustStdCrates {
+		f, err := e.store.FileByPath(rustdocStubPrefix + crate)
+		if err != nil {
+			return 0, fmt.Errorf("rust std stubs: %w", err)
+		}
+		if f == nil || (f.Hash != version && strings.HasPrefix(f.Hash, rustStdVersionPrefix)) {
+			pending = append(pending, crate)
+		}
+	}
+	if len(pending) == 0 {
+		return 0, nil
+	}
+
+	items, err := parseRustStdIndex(rustStdIndex)
+	if err != nil {
+		return 0, err
+	}
+	n := 0
+	for _, crate := range pending {
+		var crateItems []rustStubItem
+		for _, it := range items {
+			if it.path[0] == crate {
+				crateItems = append(crateItems, it)
+			}
+		}
+		if err := e.insertRustStubs(crate, version, crateItems); err != nil {
+			return 0, err
+		}
+		n += len(crateItems)
+	}
+	return n, nil
+}
+
+// parseRustStdIndex decompresses and parses a stub index in the format of
+// rustStdIndex.
+func parseRustStdIndex(data []byte) ([]rustStubItem, error) {
+	zr, err := gzip.NewReader(bytes.NewReader(data))
+	if err != nil {
+		return nil, fmt.Errorf("rust std stubs: %w", err)
+	}
+	defer zr.Close()
+
+	var items []rustStubItem
+	sc := bufio.NewScanner(zr)
+	for line := 1; sc.Scan(); line++ {
+		text := strings.TrimSpace(sc.Text())
+		if text == "" || strings.HasPrefix(text, "#") {
+			continue
+		}
+		kind, path, ok := strings.Cut(text, " ")
+		if !ok || path == "" {
+			return nil, fmt.Errorf("rust std stubs: line %d: expected \"<kind> <path>\"", line)
+		}
+		items = append(items, rustStubItem{path: strings.Split(path, "::"), kind: kind})
+	}
+	if err := sc.Err(); err != nil {
+		return nil, fmt.Errorf("rust std stubs: %w", err)
+	}
+	return items, nil
+}
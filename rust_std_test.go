@@ -0,0 +1,154 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseRustStdIndex(t *testing.T) {
+	items, err := parseRustStdIndex(rustStdIndex)
+	require.NoError(t, err)
+
+	kinds := map[string]string{}
+	for _, it := range items {
+		kinds[strings.Join(it.path, "::")] = it.kind
+	}
+	assert.Equal(t, "struct", kinds["std::vec::Vec"])
+	assert.Equal(t, "function", kinds["std::vec::Vec::new"])
+	assert.Equal(t, "method", kinds["std::vec::Vec::push"])
+	assert.Equal(t, "function", kinds["std::string::String::from"])
+	assert.Equal(t, "macro", kinds["std::println"])
+	assert.Equal(t, "enum", kinds["core::option::Option"])
+	assert.Equal(t, "struct", kinds["alloc::vec::Vec"])
+
+	// Every item's parent is listed, so stubs nest under a module or type.
+	for path := range kinds {
+		if i := strings.LastIndex(path, "::"); i >= 0 {
+			assert.Contains(t, kinds, path[:i], "parent of %s", path)
+		}
+	}
+}
+
+func TestLoadRustStdStubs(t *testing.T) {
+	e := newTestEngine(t)
+
+	n, err := e.loadRustStdStubs()
+	require.NoError(t, err)
+	assert.Greater(t, n, 0)
+
+	f, err := e.store.FileByPath("rustdoc:std::vec")
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	syms, err := e.store.SymbolsByFile(f.ID)
+	require.NoError(t, err)
+	byName := map[string]int64{}
+	for _, s := range syms {
+		byName[s.Name] = s.ID
+		assert.Contains(t, s.Modifiers, "stub")
+	}
+	require.Contains(t, byName, "Vec")
+	require.Contains(t, byName, "push", "members live in their type's module file")
+	push, err := e.store.SymbolByID(byName["push"])
+	require.NoError(t, err)
+	require.NotNil(t, push.ParentSymbolID)
+	assert.Equal(t, byName["Vec"], *push.ParentSymbolID)
+
+	// Current stubs are not reloaded.
+	n, err = e.loadRustStdStubs()
+	require.NoError(t, err)
+	assert.Equal(t, 0, n)
+}
+
+func TestLoadRustStdStubs_KeepsIngestedStd(t *testing.T) {
+	e := newTestEngine(t)
+
+	require.NoError(t, e.insertRustStubs("std", "1.80.0", []rustStubItem{
+		{path: []string{"std"}, kind: "module"},
+		{path: []string{"std", "fmt"}, kind: "module"},
+	}))
+	_, err := e.loadRustStdStubs()
+	require.NoError(t, err)
+
+	f, err := e.store.FileByPath("rustdoc:std")
+	require.NoError(t, err)
+	require.NotNil(t, f)
+	assert.Equal(t, "1.80.0", f.Hash, "std stubs from rustdoc JSON take precedence")
+	vec, err := e.store.FileByPath("rustdoc:std::vec")
+	require.NoError(t, err)
+	assert.Nil(t, vec)
+
+	core, err := e.store.FileByPath("rustdoc:core")
+	require.NoError(t, err)
+	require.NotNil(t, core, "other crates still load from the index")
+}
+
+func TestRustStdStubs_Resolve(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	ctx := context.Background()
+
+	path := filepath.Join(t.TempDir(), "main.rs")
+	require.NoError(t, os.WriteFile(path, []byte(`struct Config {
+    name: String,
+}
+
+impl Config {
+    fn new() -> Config {
+        Config { name: String::new() }
+    }
+}
+
+fn main() {
+    let mut v: Vec<u8> = Vec::new();
+    v.push(1);
+    let s = String::from("x");
+    println!("{} {:?}", s, v);
+}
+`), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	// targetOf returns the stub file and kind a reference on line resolved to.
+	targetOf := func(name, refCtx string, line int) (string, string) {
+		t.Helper()
+		refs, err := e.store.ReferencesByName(name)
+		require.NoError(t, err)
+		for _, r := range refs {
+			if r.Context != refCtx || r.StartLine != line {
+				continue
+			}
+			resolved, err := e.store.ResolvedReferencesByRef(r.ID)
+			require.NoError(t, err)
+			require.NotEmpty(t, resolved, "%s on line %d is unresolved", name, line)
+			sym, err := e.store.SymbolByID(resolved[0].TargetSymbolID)
+			require.NoError(t, err)
+			require.NotNil(t, sym.FileID)
+			var file string
+			require.NoError(t, e.store.DB().QueryRow("SELECT path FROM files WHERE id = ?", *sym.FileID).Scan(&file))
+			return file, sym.Kind
+		}
+		t.Fatalf("no %s reference to %s on line %d", refCtx, name, line)
+		return "", ""
+	}
+
+	file, kind := targetOf("Vec", "type_annotation", 11)
+	assert.Equal(t, "rustdoc:std::vec", file)
+	assert.Equal(t, "struct", kind)
+	file, kind = targetOf("new", "call", 11)
+	assert.Equal(t, "rustdoc:std::vec", file, "Vec::new is not the Config::new in this file")
+	assert.Equal(t, "function", kind)
+	file, _ = targetOf("from", "call", 13)
+	assert.Equal(t, "rustdoc:std::string", file)
+	file, kind = targetOf("println", "macro", 14)
+	assert.Equal(t, "rustdoc:std", file)
+	assert.Equal(t, "macro", kind)
+	file, _ = targetOf("new", "call", 6)
+	assert.Equal(t, "rustdoc:std::string", file)
+	file, _ = targetOf("Config", "type_annotation", 5)
+	assert.Equal(t, path, file, "source types are not shadowed by stubs")
+}
@@ -0,0 +1,259 @@
+package canopy
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Stub files created from rustdoc JSON use this language so no extraction or
+// resolution script runs for them. Each stub file holds the items of one
+// module and is named rustdocStubPrefix + module path, e.g. "rustdoc:serde::de".
+const (
+	rustdocStubLanguage = "rustdoc"
+	rustdocStubPrefix   = "rustdoc:"
+)
+
+// rustdocKinds maps rustdoc item kinds to canopy symbol kinds. Kinds not
+// listed (impls, fields, variants, primitives, ...) are not stubbed.
+var rustdocKinds = map[string]string{
+	"module":         "module",
+	"struct":         "struct",
+	"union":          "struct",
+	"enum":           "enum",
+	"trait":          "trait",
+	"trait_alias":    "trait",
+	"function":       "function",
+	"type_alias":     "type_alias",
+	"typedef":        "type_alias", // format_version < 24
+	"constant":       "constant",
+	"static":         "variable",
+	"macro":          "macro",
+	"proc_macro":     "macro",
+	"proc_attribute": "macro",
+	"proc_derive":    "macro",
+}
+
+// rustdocCrate is the subset of rustdoc's JSON output format that stubs need.
+type rustdocCrate struct {
+	CrateVersion *string                `json:"crate_version"`
+	Index        map[string]rustdocItem `json:"index"`
+	Paths        map[string]rustdocPath `json:"paths"`
+}
+
+type rustdocItem struct {
+	CrateID    int             `json:"crate_id"`
+	Visibility json.RawMessage `json:"visibility"` // "public", "default", "crate", or {"restricted": ...}
+}
+
+type rustdocPath struct {
+	CrateID int      `json:"crate_id"`
+	Path    []string `json:"path"`
+	Kind    string   `json:"kind"`
+}
+
+// IngestRustdocJSON reads the JSON output of rustdoc
+// (`cargo rustdoc -- -Z unstable-options --output-format json`) for a
+// dependency crate and records its public items as stub symbols, so Rust
+// references to that crate resolve to typed stubs without parsing its
+// source. Stubs have no source positions and carry the "stub" modifier.
+//
+// Re-ingesting a crate replaces its previous stubs. All Rust files are added
+// to the blast radius so the next Resolve picks up the new targets. Returns
+// the crate name and the number of stub symbols created.
+func (e *Engine) IngestRustdocJSON(r io.Reader) (string, int, error) {
+	var doc rustdocCrate
+	if err := json.NewDecoder(r).Decode(&doc); err != nil {
+		return "", 0, fmt.Errorf("rustdoc json: decode: %w", err)
+	}
+
+	// Collect public items of the documented crate (crate_id 0).
+	var items []rustStubItem
+	crateName := ""
+	for id, p := range doc.Paths {
+		if p.CrateID != 0 || len(p.Path) == 0 {
+			continue
+		}
+		kind, ok := rustdocKinds[p.Kind]
+		if !ok {
+			continue
+		}
+		if item, ok := doc.Index[id]; ok && !rustdocPublic(item.Visibility) {
+			continue
+		}
+		if crateName == "" {
+			crateName = p.Path[0]
+		}
+		items = append(items, rustStubItem{path: p.Path, kind: kind})
+	}
+	if crateName == "" {
+		return "", 0, fmt.Errorf("rustdoc json: no public items for the documented crate")
+	}
+
+	version := ""
+	if doc.CrateVersion != nil {
+		version = *doc.CrateVersion
+	}
+	if err := e.insertRustStubs(crateName, version, items); err != nil {
+		return "", 0, err
+	}
+
+	// New stub targets can change how any Rust reference resolves.
+	if e.blastRadius == nil {
+		e.blastRadius = make(map[int64]bool)
+	}
+	if err := e.addRustFilesToBlastRadius(); err != nil {
+		return "", 0, err
+	}
+
+	return crateName, len(items), nil
+}
+
+// rustStubItem is one item to record as a stub symbol: its full path
+// (crate first) and canopy symbol kind.
+type rustStubItem struct {
+	path []string
+	kind string
+}
+
+// insertRustStubs replaces the stubs of a crate with items, recording
+// version as the stub files' hash. Items live in the stub file of their
+// module; members of a type (methods, associated functions) live in the
+// file of the type's module and are parented to the type.
+func (e *Engine) insertRustStubs(crateName, version string, items []rustStubItem) error {
+	// Parents before children, then by name for deterministic IDs.
+	sort.Slice(items, func(i, j int) bool {
+		if len(items[i].path) != len(items[j].path) {
+			return len(items[i].path) < len(items[j].path)
+		}
+		return strings.Join(items[i].path, "::") < strings.Join(items[j].path, "::")
+	})
+
+	if err := e.deleteRustdocStubs(crateName); err != nil {
+		return err
+	}
+
+	// One stub file per module path; items live in their module's file.
+	fileIDs := map[string]int64{}
+	stubFile := func(modPath string) (int64, error) {
+		if id, ok := fileIDs[modPath]; ok {
+			return id, nil
+		}
+		id, err := e.store.InsertFile(&store.File{
+			Path:        rustdocStubPrefix + modPath,
+			Language:    rustdocStubLanguage,
+			Hash:        version,
+			LastIndexed: time.Now(),
+		})
+		if err != nil {
+			return 0, fmt.Errorf("rustdoc json: stub file %s: %w", modPath, err)
+		}
+		fileIDs[modPath] = id
+		return id, nil
+	}
+
+	type stubInfo struct {
+		id      int64
+		kind    string
+		modPath string // module whose stub file holds the item
+	}
+	stubs := map[string]stubInfo{} // item path -> inserted stub
+	for _, it := range items {
+		path := strings.Join(it.path, "::")
+		parentPath := crateName
+		if len(it.path) > 1 {
+			parentPath = strings.Join(it.path[:len(it.path)-1], "::")
+		}
+		modPath := parentPath
+		parent, hasParent := stubs[parentPath]
+		if hasParent && parent.kind != "module" {
+			modPath = parent.modPath
+		}
+		fileID, err := stubFile(modPath)
+		if err != nil {
+			return err
+		}
+		sym := &store.Symbol{
+			FileID:     &fileID,
+			Name:       it.path[len(it.path)-1],
+			Kind:       it.kind,
+			Visibility: "public",
+			Modifiers:  []string{"stub"},
+		}
+		if hasParent && len(it.path) > 1 {
+			sym.ParentSymbolID = &parent.id
+		}
+		id, err := e.store.InsertSymbol(sym)
+		if err != nil {
+			return fmt.Errorf("rustdoc json: stub symbol %s: %w", path, err)
+		}
+		stubs[path] = stubInfo{id: id, kind: it.kind, modPath: modPath}
+	}
+	return nil
+}
+
+// addRustFilesToBlastRadius marks every Rust file for re-resolution.
+func (e *Engine) addRustFilesToBlastRadius() error {
+	rustFiles, err := e.store.FilesByLanguage("rust")
+	if err != nil {
+		return fmt.Errorf("list rust files: %w", err)
+	}
+	for _, f := range rustFiles {
+		e.blastRadius[f.ID] = true
+	}
+	return nil
+}
+
+// deleteRustdocStubs removes the stub files and symbols of a crate.
+func (e *Engine) deleteRustdocStubs(crateName string) error {
+	rows, err := e.store.DB().Query(
+		`SELECT id FROM files WHERE language = ? AND (path = ? OR substr(path, 1, ?) = ?)`,
+		rustdocStubLanguage,
+		rustdocStubPrefix+crateName,
+		len(rustdocStubPrefix+crateName+"::"), rustdocStubPrefix+crateName+"::",
+	)
+	if err != nil {
+		return fmt.Errorf("rustdoc json: list stubs: %w", err)
+	}
+	var ids []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return fmt.Errorf("rustdoc json: scan stub: %w", err)
+		}
+		ids = append(ids, id)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return fmt.Errorf("rustdoc json: list stubs: %w", err)
+	}
+
+	for _, id := range ids {
+		if err := e.store.DeleteFileData(id); err != nil {
+			return fmt.Errorf("rustdoc json: delete stub data: %w", err)
+		}
+		if _, err := e.store.DB().Exec("DELETE FROM files WHERE id = ?", id); err != nil {
+			return fmt.Errorf("rustdoc json: delete stub file: %w", err)
+		}
+	}
+	return nil
+}
+
+// rustdocPublic reports whether a rustdoc visibility value is "public".
+// Trait items and enum variants use "default", which is also reachable.
+func rustdocPublic(raw json.RawMessage) bool {
+	if len(raw) == 0 {
+		return true
+	}
+	var vis string
+	if err := json.Unmarshal(raw, &vis); err != nil {
+		return false // {"restricted": ...}
+	}
+	return vis == "public" || vis == "default"
+}
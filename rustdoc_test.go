@@ -0,0 +1,85 @@
+package canopy
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// rustdocSample is a trimmed rustdoc JSON document for a crate "serde"
+// with a public module, two public items, a private item, and an item
+// from another crate.
+const rustdocSample = `{
+  "root": "0:0",
+  "crate_version": "1.0.200",
+  "index": {
+    "0:1": {"crate_id": 0, "visibility": "public"},
+    "0:2": {"crate_id": 0, "visibility": "public"},
+    "0:3": {"crate_id": 0, "visibility": "public"},
+    "0:4": {"crate_id": 0, "visibility": "crate"}
+  },
+  "paths": {
+    "0:0": {"crate_id": 0, "path": ["serde"], "kind": "module"},
+    "0:1": {"crate_id": 0, "path": ["serde", "de"], "kind": "module"},
+    "0:2": {"crate_id": 0, "path": ["serde", "de", "Deserialize"], "kind": "trait"},
+    "0:3": {"crate_id": 0, "path": ["serde", "Serializer"], "kind": "trait"},
+    "0:4": {"crate_id": 0, "path": ["serde", "private", "Helper"], "kind": "struct"},
+    "1:9": {"crate_id": 1, "path": ["core", "option", "Option"], "kind": "enum"}
+  }
+}`
+
+func TestIngestRustdocJSON_CreatesStubs(t *testing.T) {
+	e := newTestEngine(t)
+
+	crateName, n, err := e.IngestRustdocJSON(strings.NewReader(rustdocSample))
+	require.NoError(t, err)
+	assert.Equal(t, "serde", crateName)
+	assert.Equal(t, 4, n) // serde, serde::de, Deserialize, Serializer
+
+	files, err := e.store.FilesByLanguage(rustdocStubLanguage)
+	require.NoError(t, err)
+	paths := map[string]int64{}
+	for _, f := range files {
+		paths[f.Path] = f.ID
+	}
+	assert.Contains(t, paths, "rustdoc:serde")
+	assert.Contains(t, paths, "rustdoc:serde::de")
+	assert.NotContains(t, paths, "rustdoc:serde::private")
+
+	syms, err := e.store.SymbolsByFile(paths["rustdoc:serde::de"])
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	assert.Equal(t, "Deserialize", syms[0].Name)
+	assert.Equal(t, "trait", syms[0].Kind)
+	assert.Equal(t, "public", syms[0].Visibility)
+	assert.Contains(t, syms[0].Modifiers, "stub")
+	assert.NotNil(t, syms[0].ParentSymbolID, "Deserialize should be parented to the serde::de module stub")
+}
+
+func TestIngestRustdocJSON_ReingestReplacesStubs(t *testing.T) {
+	e := newTestEngine(t)
+
+	_, _, err := e.IngestRustdocJSON(strings.NewReader(rustdocSample))
+	require.NoError(t, err)
+	_, n, err := e.IngestRustdocJSON(strings.NewReader(rustdocSample))
+	require.NoError(t, err)
+
+	var count int
+	require.NoError(t, e.store.DB().QueryRow(
+		`SELECT COUNT(*) FROM symbols s JOIN files f ON f.id = s.file_id WHERE f.language = ?`,
+		rustdocStubLanguage,
+	).Scan(&count))
+	assert.Equal(t, n, count)
+}
+
+func TestIngestRustdocJSON_InvalidInput(t *testing.T) {
+	e := newTestEngine(t)
+
+	_, _, err := e.IngestRustdocJSON(strings.NewReader("not json"))
+	assert.Error(t, err)
+
+	_, _, err = e.IngestRustdocJSON(strings.NewReader(`{"index": {}, "paths": {}}`))
+	assert.Error(t, err)
+}
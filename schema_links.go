@@ -0,0 +1,69 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// linkGeneratedTypes points the references to Rust types that prost
+// generated from protobuf messages and enums at those messages and enums,
+// so "find definition" on a generated type lands on its schema. The
+// generated types themselves stay indexed.
+func (e *Engine) linkGeneratedTypes() error {
+	protos, err := e.store.ProtoTypes()
+	if err != nil {
+		return fmt.Errorf("link generated types: %w", err)
+	}
+	generated, err := e.store.ProstTypes()
+	if err != nil {
+		return fmt.Errorf("link generated types: %w", err)
+	}
+	for _, gen := range generated {
+		schema := schemaTypeFor(gen, protos)
+		if schema == nil {
+			continue
+		}
+		if _, err := e.store.RetargetResolvedReferences(gen.SymbolID, schema.SymbolID); err != nil {
+			return fmt.Errorf("link generated types: %w", err)
+		}
+	}
+	return nil
+}
+
+// schemaTypeFor returns the protobuf message or enum gen was generated
+// from, or nil if there is none or several equally good ones. prost
+// converts names to UpperCamelCase and nests the types of message Outer in
+// module outer, so names compare case- and underscore-insensitively, and
+// a candidate from the package gen's file is named after wins over others.
+func schemaTypeFor(gen *store.SchemaType, protos []*store.SchemaType) *store.SchemaType {
+	wantKind := "message"
+	if gen.Kind == "enum" {
+		wantKind = "enum"
+	}
+	var candidates, inPackage []*store.SchemaType
+	for _, p := range protos {
+		if p.Kind != wantKind || schemaName(p.Name) != schemaName(gen.Name) || schemaName(p.Parent) != schemaName(gen.Parent) {
+			continue
+		}
+		candidates = append(candidates, p)
+		if p.Package == gen.Package {
+			inPackage = append(inPackage, p)
+		}
+	}
+	switch {
+	case len(inPackage) == 1:
+		return inPackage[0]
+	case len(inPackage) == 0 && len(candidates) == 1:
+		return candidates[0]
+	}
+	return nil
+}
+
+// schemaName normalizes a type or module name for comparison across
+// naming conventions: HTTPRequest, HttpRequest, and http_request all
+// become "httprequest".
+func schemaName(name string) string {
+	return strings.ToLower(strings.ReplaceAll(name, "_", ""))
+}
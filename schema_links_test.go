@@ -0,0 +1,90 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSchemaTypeFor(t *testing.T) {
+	t.Parallel()
+
+	protos := []*store.SchemaType{
+		{SymbolID: 1, Name: "HTTPRequest", Kind: "message", Package: "api.v1"},
+		{SymbolID: 2, Name: "Status", Kind: "enum", Package: "api.v1"},
+		{SymbolID: 3, Name: "Status", Kind: "enum", Package: "api.v2"},
+		{SymbolID: 4, Name: "Inner", Kind: "message", Parent: "Outer", Package: "api.v1"},
+		{SymbolID: 5, Name: "Inner", Kind: "message", Package: "api.v1"},
+	}
+	find := func(gen store.SchemaType) int64 {
+		if p := schemaTypeFor(&gen, protos); p != nil {
+			return p.SymbolID
+		}
+		return 0
+	}
+
+	assert.Equal(t, int64(1), find(store.SchemaType{Name: "HttpRequest", Kind: "struct", Package: "lib"}), "renamed to UpperCamelCase, unique")
+	assert.Equal(t, int64(3), find(store.SchemaType{Name: "Status", Kind: "enum", Package: "api.v2"}), "the file's package wins")
+	assert.Zero(t, find(store.SchemaType{Name: "Status", Kind: "enum", Package: "lib"}), "ambiguous")
+	assert.Zero(t, find(store.SchemaType{Name: "Status", Kind: "struct", Package: "api.v1"}), "a struct is not generated from an enum")
+	assert.Equal(t, int64(4), find(store.SchemaType{Name: "Inner", Kind: "struct", Parent: "outer", Package: "api.v1"}), "nested in its message's module")
+	assert.Equal(t, int64(5), find(store.SchemaType{Name: "Inner", Kind: "struct", Package: "api.v1"}))
+}
+
+func TestLinkGeneratedTypes(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust", "protobuf"))
+	ctx := context.Background()
+
+	dir := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(dir, "proto"), 0755))
+	require.NoError(t, os.MkdirAll(filepath.Join(dir, "src"), 0755))
+	files := map[string]string{
+		"proto/helloworld.proto": `syntax = "proto3";
+
+package helloworld;
+
+message HelloRequest {
+  string name = 1;
+}
+`,
+		// As prost generates it for package helloworld.
+		"src/helloworld.rs": `#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HelloRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+`,
+		"src/main.rs": `use crate::helloworld::HelloRequest;
+
+fn build() -> HelloRequest {
+    HelloRequest { name: String::new() }
+}
+`,
+	}
+	var paths []string
+	for name, content := range files {
+		path := filepath.Join(dir, name)
+		require.NoError(t, os.WriteFile(path, []byte(content), 0644))
+		paths = append(paths, path)
+	}
+	require.NoError(t, e.IndexFiles(ctx, paths))
+	require.NoError(t, e.Resolve(ctx))
+
+	// "find definition" on the return type lands on the proto message.
+	locs, err := e.Query().DefinitionAt(filepath.Join(dir, "src", "main.rs"), 2, 14)
+	require.NoError(t, err)
+	require.Len(t, locs, 1)
+	assert.Equal(t, filepath.Join(dir, "proto", "helloworld.proto"), locs[0].File)
+	assert.Equal(t, 4, locs[0].StartLine)
+
+	// The generated struct is still indexed.
+	generated, err := e.store.ProstTypes()
+	require.NoError(t, err)
+	require.Len(t, generated, 1)
+	assert.Equal(t, "helloworld", generated[0].Package)
+}
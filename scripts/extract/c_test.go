@@ -39,9 +39,15 @@ func newCTestEnv(t *testing.T) *cTestEnv {
 // and runs the extraction script. Returns the file ID.
 func (e *cTestEnv) extractCSource(src string) int64 {
 	e.t.Helper()
+	return e.extractCFile(src, "test.c")
+}
+
+// extractCFile is extractCSource with the file's name, such as a header's.
+func (e *cTestEnv) extractCFile(src, filename string) int64 {
+	e.t.Helper()
 
 	dir := e.t.TempDir()
-	cFile := filepath.Join(dir, "test.c")
+	cFile := filepath.Join(dir, filename)
 	require.NoError(e.t, os.WriteFile(cFile, []byte(src), 0644))
 
 	fileID, err := e.store.InsertFile(&store.File{
@@ -83,6 +89,7 @@ func TestCExtract_FunctionDefinition(t *testing.T) {
 	assert.Equal(t, "function", fn.Kind)
 	assert.Equal(t, "public", fn.Visibility)
 	assert.Equal(t, 0, fn.StartLine)
+	assert.NotContains(t, fn.Modifiers, "declaration")
 }
 
 func TestCExtract_FunctionDeclaration(t *testing.T) {
@@ -102,6 +109,7 @@ func TestCExtract_FunctionDeclaration(t *testing.T) {
 	require.NotNil(t, fn, "expected function symbol")
 	assert.Equal(t, "hello", fn.Name)
 	assert.Equal(t, "public", fn.Visibility)
+	assert.Contains(t, fn.Modifiers, "declaration")
 }
 
 func TestCExtract_StructWithFields(t *testing.T) {
@@ -467,3 +475,87 @@ int main() {
 	require.NoError(t, err)
 	assert.Greater(t, len(refs), 0)
 }
+
+func TestCExtract_ObjectiveCHeader(t *testing.T) {
+	env := newCTestEnv(t)
+	fileID := env.extractCFile(`#import <UIKit/UIKit.h>
+@import Foundation;
+
+typedef NS_ENUM(NSInteger, ClientState) {
+    ClientStateIdle,
+};
+
+@protocol ClientDelegate <NSObject>
+- (void)clientDidFinish:(Client *)client;
+@end
+
+@interface Client : NSObject <NSCopying, ClientDelegate>
+@property (nonatomic, copy, readonly) NSString *name;
+@property (nonatomic, copy) void (^onChange)(NSString *value);
+- (instancetype)initWithName:(NSString *)name;
+- (void)fetchUser:(NSString *)userID
+       completion:(void (^)(NSError *error))completion;
+- (void)reloadData NS_SWIFT_NAME(refresh());
++ (Client *)shared;
+@end
+
+@interface Client (Networking)
+- (void)cancel;
+@end
+`, "Client.h")
+
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		if s.Kind == "class" || s.Kind == "interface" || s.ParentSymbolID != nil {
+			byName[s.Name+"/"+s.Kind] = s
+		}
+	}
+
+	client := byName["Client/class"]
+	require.NotNil(t, client, "expected the @interface as a class")
+	assert.Equal(t, 11, client.StartLine)
+	assert.Equal(t, 19, client.EndLine)
+	require.NotNil(t, byName["ClientDelegate/interface"], "expected the @protocol as an interface")
+
+	for _, key := range []string{"name/property", "onChange/property", "Client/constructor", "fetchUser/method", "refresh/method", "shared/method", "cancel/method"} {
+		sym := byName[key]
+		require.NotNil(t, sym, "expected %s", key)
+		require.NotNil(t, sym.ParentSymbolID)
+		assert.Equal(t, client.ID, *sym.ParentSymbolID, key)
+	}
+	assert.Contains(t, byName["name/property"].Modifiers, "readonly")
+	assert.Contains(t, byName["shared/method"].Modifiers, "static")
+	assert.Equal(t, 15, byName["fetchUser/method"].StartLine)
+	assert.Equal(t, 16, byName["fetchUser/method"].EndLine)
+
+	params, err := env.store.FunctionParams(byName["fetchUser/method"].ID)
+	require.NoError(t, err)
+	var names []string
+	for _, p := range params {
+		if !p.IsReturn {
+			names = append(names, p.Name+": "+p.TypeExpr)
+		}
+	}
+	assert.Equal(t, []string{"userID: NSString *", "completion: void (^)(NSError *error)"}, names)
+
+	members, err := env.store.TypeMembers(client.ID)
+	require.NoError(t, err)
+	var supers []string
+	for _, m := range members {
+		if m.Kind == "embedded" {
+			supers = append(supers, m.Name)
+		}
+	}
+	assert.ElementsMatch(t, []string{"NSObject", "NSCopying", "ClientDelegate"}, supers)
+
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+	sources := map[string]string{}
+	for _, imp := range imports {
+		sources[imp.Source] = imp.Kind
+	}
+	assert.Equal(t, "header", sources["UIKit/UIKit.h"])
+	assert.Equal(t, "module", sources["Foundation"])
+}
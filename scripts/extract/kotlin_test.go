@@ -0,0 +1,293 @@
+package go_extract_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// extractKotlinSource writes Kotlin source to a temp file, inserts a file
+// record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractKotlinSource(src string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	ktFile := filepath.Join(dir, "Test.kt")
+	require.NoError(e.t, os.WriteFile(ktFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     ktFile,
+		Language: "kotlin",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": ktFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "kotlin.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// ---------- Kotlin Tests ----------
+
+func TestKotlin_ClassWithPropertiesAndMethods(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`class Person(val name: String, age: Int) {
+    private var nickname: String? = null
+
+    fun greet(other: Person): String {
+        return "hi"
+    }
+
+    internal fun rename(value: String) {}
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	var classSym *store.Symbol
+	methods := map[string]*store.Symbol{}
+	for _, s := range syms {
+		if s.Kind == "class" {
+			classSym = s
+		}
+		if s.Kind == "method" {
+			methods[s.Name] = s
+		}
+	}
+	require.NotNil(t, classSym, "expected class symbol")
+	assert.Equal(t, "Person", classSym.Name)
+	assert.Equal(t, "public", classSym.Visibility)
+
+	require.Len(t, methods, 2)
+	for _, m := range methods {
+		require.NotNil(t, m.ParentSymbolID, "method should have parent_symbol_id")
+		assert.Equal(t, classSym.ID, *m.ParentSymbolID)
+	}
+	assert.Equal(t, "public", methods["greet"].Visibility)
+	assert.Equal(t, "internal", methods["rename"].Visibility)
+
+	params, err := env.store.FunctionParams(methods["greet"].ID)
+	require.NoError(t, err)
+	var paramTypes, returnTypes []string
+	for _, p := range params {
+		if p.IsReturn {
+			returnTypes = append(returnTypes, p.TypeExpr)
+		} else {
+			paramTypes = append(paramTypes, p.Name+": "+p.TypeExpr)
+		}
+	}
+	assert.Equal(t, []string{"other: Person"}, paramTypes)
+	assert.Equal(t, []string{"String"}, returnTypes)
+
+	// Properties, including the val constructor parameter but not the plain one.
+	members, err := env.store.TypeMembers(classSym.ID)
+	require.NoError(t, err)
+	props := map[string]*store.TypeMember{}
+	for _, m := range members {
+		if m.Kind == "property" {
+			props[m.Name] = m
+		}
+	}
+	require.Len(t, props, 2)
+	assert.Equal(t, "String", props["name"].TypeExpr)
+	assert.Equal(t, "String?", props["nickname"].TypeExpr)
+	assert.Equal(t, "private", props["nickname"].Visibility)
+}
+
+func TestKotlin_InterfaceObjectAndSupertypes(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`interface Repository {
+    fun save(item: String)
+}
+
+abstract class Base
+
+data class Entry(val id: Int) : Base(), Repository {
+    override fun save(item: String) {}
+
+    companion object {
+        fun empty() = Entry(0)
+    }
+}
+
+object Registry : Repository {
+    override fun save(item: String) {}
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		if s.Kind == "class" || s.Kind == "interface" {
+			byName[s.Name] = s
+		}
+	}
+	require.Contains(t, byName, "Repository")
+	assert.Equal(t, "interface", byName["Repository"].Kind)
+	require.Contains(t, byName, "Entry")
+	assert.Contains(t, byName["Entry"].Modifiers, "data")
+	assert.Contains(t, byName["Base"].Modifiers, "abstract")
+	require.Contains(t, byName, "Registry")
+	assert.Contains(t, byName["Registry"].Modifiers, "object")
+
+	companion := byName["Companion"]
+	require.NotNil(t, companion, "expected companion object symbol")
+	assert.Contains(t, companion.Modifiers, "companion")
+	require.NotNil(t, companion.ParentSymbolID)
+	assert.Equal(t, byName["Entry"].ID, *companion.ParentSymbolID)
+
+	// Supertypes are embedded members.
+	members, err := env.store.TypeMembers(byName["Entry"].ID)
+	require.NoError(t, err)
+	var supers []string
+	for _, m := range members {
+		if m.Kind == "embedded" {
+			supers = append(supers, m.Name)
+		}
+	}
+	assert.ElementsMatch(t, []string{"Base", "Repository"}, supers)
+}
+
+func TestKotlin_PackageAndImports(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`package com.example.app
+
+import com.example.util.Formatter
+import com.example.model.*
+import com.example.net.Client as HttpClient
+
+fun main() {}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	var pkgSym *store.Symbol
+	for _, s := range syms {
+		if s.Kind == "package" {
+			pkgSym = s
+		}
+	}
+	require.NotNil(t, pkgSym)
+	assert.Equal(t, "com.example.app", pkgSym.Name)
+
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, imports, 3)
+	bySource := map[string]*store.Import{}
+	for _, imp := range imports {
+		bySource[imp.Source] = imp
+	}
+
+	formatter := bySource["com.example.util.Formatter"]
+	require.NotNil(t, formatter)
+	require.NotNil(t, formatter.ImportedName)
+	assert.Equal(t, "Formatter", *formatter.ImportedName)
+
+	star := bySource["com.example.model.*"]
+	require.NotNil(t, star)
+	require.NotNil(t, star.ImportedName)
+	assert.Equal(t, "*", *star.ImportedName)
+
+	aliased := bySource["com.example.net.Client"]
+	require.NotNil(t, aliased)
+	require.NotNil(t, aliased.ImportedName)
+	assert.Equal(t, "Client", *aliased.ImportedName)
+	require.NotNil(t, aliased.LocalAlias)
+	assert.Equal(t, "HttpClient", *aliased.LocalAlias)
+}
+
+func TestKotlin_TopLevelDeclarations(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`const val MAX = 10
+val greeting = "hi"
+
+fun String.shout(times: Int): String = this.uppercase()
+
+private fun helper() {}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	require.Contains(t, byName, "MAX")
+	assert.Equal(t, "constant", byName["MAX"].Kind)
+	require.Contains(t, byName, "greeting")
+	assert.Equal(t, "variable", byName["greeting"].Kind)
+	require.Contains(t, byName, "helper")
+	assert.Equal(t, "function", byName["helper"].Kind)
+	assert.Equal(t, "private", byName["helper"].Visibility)
+
+	// An extension function records its receiver.
+	require.Contains(t, byName, "shout")
+	assert.Equal(t, "function", byName["shout"].Kind)
+	params, err := env.store.FunctionParams(byName["shout"].ID)
+	require.NoError(t, err)
+	var receiver *store.FunctionParam
+	for _, p := range params {
+		if p.IsReceiver {
+			receiver = p
+		}
+	}
+	require.NotNil(t, receiver, "expected receiver param")
+	assert.Equal(t, "String", receiver.TypeExpr)
+}
+
+func TestKotlin_ScopeTree(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`class Processor {
+    fun process(x: Int) {
+        if (x > 0) {
+            for (i in 0..x) {
+            }
+        }
+    }
+}
+`)
+	scopes, err := env.store.ScopesByFile(fileID)
+	require.NoError(t, err)
+
+	kinds := map[string]int{}
+	for _, s := range scopes {
+		kinds[s.Kind]++
+	}
+	assert.Equal(t, 1, kinds["file"])
+	assert.Equal(t, 1, kinds["class"])
+	assert.Equal(t, 1, kinds["function"])
+	assert.GreaterOrEqual(t, kinds["block"], 2, "expected block scopes for if and for")
+}
+
+func TestKotlin_References(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractKotlinSource(`class App {
+    fun run(repo: Repository) {
+        val user = User("ann")
+        repo.save(user)
+        println(user.name)
+    }
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	refsByContext := map[string][]string{}
+	for _, r := range refs {
+		refsByContext[r.Context] = append(refsByContext[r.Context], r.Name)
+	}
+	assert.Contains(t, refsByContext["call"], "User")
+	assert.Contains(t, refsByContext["call"], "save")
+	assert.Contains(t, refsByContext["call"], "println")
+	assert.Contains(t, refsByContext["field_access"], "name")
+	assert.Contains(t, refsByContext["type_annotation"], "Repository")
+	assert.NotContains(t, refsByContext["type_annotation"], "App")
+}
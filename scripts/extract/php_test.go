@@ -279,6 +279,21 @@ use App\Interfaces\Printable;
 	assert.Equal(t, "App\\Interfaces\\Printable", printImp.Source)
 }
 
+func TestPHP_UseStatementAlias(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractPHPSource(`<?php
+use App\Models\User as Account;
+`)
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, imports, 1)
+	assert.Equal(t, "App\\Models\\User", imports[0].Source)
+	require.NotNil(t, imports[0].ImportedName)
+	assert.Equal(t, "User", *imports[0].ImportedName)
+	require.NotNil(t, imports[0].LocalAlias)
+	assert.Equal(t, "Account", *imports[0].LocalAlias)
+}
+
 func TestPHP_ScopeTree(t *testing.T) {
 	env := newTestEnv(t)
 	fileID := env.extractPHPSource(`<?php
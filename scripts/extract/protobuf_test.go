@@ -0,0 +1,181 @@
+package go_extract_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// extractProtoSource writes a .proto file to a temp dir, inserts a file
+// record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractProtoSource(src string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	protoFile := filepath.Join(dir, "test.proto")
+	require.NoError(e.t, os.WriteFile(protoFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     protoFile,
+		Language: "protobuf",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": protoFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "protobuf.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// ---------- Protobuf Tests ----------
+
+const protoUserService = `syntax = "proto3";
+
+package api.v1;
+
+import "google/protobuf/timestamp.proto";
+
+message User {
+  string name = 1;
+  repeated string emails = 2;
+  map<string, string> labels = 3;
+  Status status = 4;
+  google.protobuf.Timestamp created = 5;
+
+  message Address {
+    string city = 1;
+  }
+
+  oneof contact {
+    Address home = 6;
+    string phone = 7;
+  }
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_ACTIVE = 1;
+}
+
+service UserService {
+  rpc GetUser(GetUserRequest) returns (User);
+}
+`
+
+func TestProtobuf_MessagesEnumsAndServices(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractProtoSource(protoUserService)
+
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+
+	require.Contains(t, byName, "api.v1")
+	assert.Equal(t, "package", byName["api.v1"].Kind)
+	require.Contains(t, byName, "User")
+	assert.Equal(t, "message", byName["User"].Kind)
+	require.Contains(t, byName, "Status")
+	assert.Equal(t, "enum", byName["Status"].Kind)
+
+	// Nested messages have their message as parent.
+	require.Contains(t, byName, "Address")
+	require.NotNil(t, byName["Address"].ParentSymbolID)
+	assert.Equal(t, byName["User"].ID, *byName["Address"].ParentSymbolID)
+
+	// RPCs are methods of their service.
+	require.Contains(t, byName, "UserService")
+	assert.Equal(t, "service", byName["UserService"].Kind)
+	require.Contains(t, byName, "GetUser")
+	assert.Equal(t, "method", byName["GetUser"].Kind)
+	require.NotNil(t, byName["GetUser"].ParentSymbolID)
+	assert.Equal(t, byName["UserService"].ID, *byName["GetUser"].ParentSymbolID)
+
+	params, err := env.store.FunctionParams(byName["GetUser"].ID)
+	require.NoError(t, err)
+	require.Len(t, params, 2)
+	for _, p := range params {
+		if p.IsReturn {
+			assert.Equal(t, "User", p.TypeExpr)
+		} else {
+			assert.Equal(t, "GetUserRequest", p.TypeExpr)
+		}
+	}
+}
+
+func TestProtobuf_FieldsAndValues(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractProtoSource(protoUserService)
+
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	var user, status *store.Symbol
+	for _, s := range syms {
+		switch s.Name {
+		case "User":
+			user = s
+		case "Status":
+			status = s
+		}
+	}
+	require.NotNil(t, user)
+	require.NotNil(t, status)
+
+	members, err := env.store.TypeMembers(user.ID)
+	require.NoError(t, err)
+	fields := map[string]string{}
+	for _, m := range members {
+		if m.Kind == "field" {
+			fields[m.Name] = m.TypeExpr
+		}
+	}
+	assert.Equal(t, map[string]string{
+		"name":    "string",
+		"emails":  "repeated string",
+		"labels":  "map<string, string>",
+		"status":  "Status",
+		"created": "google.protobuf.Timestamp",
+		"home":    "Address",
+		"phone":   "string",
+	}, fields)
+
+	values, err := env.store.TypeMembers(status.ID)
+	require.NoError(t, err)
+	var names []string
+	for _, v := range values {
+		assert.Equal(t, "variant", v.Kind)
+		names = append(names, v.Name)
+	}
+	assert.Equal(t, []string{"STATUS_UNSPECIFIED", "STATUS_ACTIVE"}, names)
+}
+
+func TestProtobuf_ImportsAndTypeReferences(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractProtoSource(protoUserService)
+
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, imports, 1)
+	assert.Equal(t, "google/protobuf/timestamp.proto", imports[0].Source)
+
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+	var names []string
+	for _, r := range refs {
+		assert.Equal(t, "type_annotation", r.Context)
+		names = append(names, r.Name)
+	}
+	// Scalar types are not references; qualified names keep their last segment.
+	assert.ElementsMatch(t, []string{"Status", "Timestamp", "Address", "GetUserRequest", "User"}, names)
+}
@@ -119,6 +119,32 @@ end
 	}
 }
 
+func TestRuby_ScopedModuleAndClassNames(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractRubySource(`module Api::V1
+end
+
+class Api::V1::UsersController < ApplicationController
+  def index
+  end
+end
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byKind := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byKind[s.Kind] = s
+	}
+	require.NotNil(t, byKind["module"])
+	assert.Equal(t, "V1", byKind["module"].Name)
+	require.NotNil(t, byKind["class"])
+	assert.Equal(t, "UsersController", byKind["class"].Name)
+	require.NotNil(t, byKind["method"])
+	require.NotNil(t, byKind["method"].ParentSymbolID)
+	assert.Equal(t, byKind["class"].ID, *byKind["method"].ParentSymbolID)
+}
+
 func TestRuby_IncludeExtendMixins(t *testing.T) {
 	env := newTestEnv(t)
 	fileID := env.extractRubySource(`module Serializable; end
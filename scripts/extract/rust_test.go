@@ -2,6 +2,7 @@ package go_extract_test
 
 import (
 	"context"
+	"fmt"
 	"os"
 	"path/filepath"
 	"testing"
@@ -135,6 +136,106 @@ struct Point {
 	assert.Equal(t, "f64", fieldMap["y"])
 }
 
+func TestRustExtract_Newtypes(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+pub struct Meters(pub f64);
+struct Tagged<T> {
+    id: Vec<u8>,
+    _kind: PhantomData<T>,
+}
+struct Pair(u32, #[allow(dead_code)] u32);
+struct Hash([u8; 32]);
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	members := map[string][]*store.TypeMember{}
+	for _, s := range syms {
+		if s.Kind == "struct" {
+			mems, err := env.store.TypeMembers(s.ID)
+			require.NoError(t, err)
+			members[s.Name] = mems
+		}
+	}
+	wraps := func(name string) *store.TypeMember {
+		var found *store.TypeMember
+		for _, m := range members[name] {
+			if m.Kind == "wraps" {
+				require.Nil(t, found, "one wraps member expected on %s", name)
+				found = m
+			}
+		}
+		return found
+	}
+
+	require.Len(t, members["Meters"], 2)
+	for _, m := range members["Meters"] {
+		if m.Kind == "field" {
+			assert.Equal(t, "0", m.Name)
+			assert.Equal(t, "f64", m.TypeExpr)
+			assert.Equal(t, "public", m.Visibility)
+		}
+	}
+	w := wraps("Meters")
+	require.NotNil(t, w)
+	assert.Equal(t, "f64", w.Name)
+	assert.Equal(t, "f64", w.TypeExpr)
+	assert.Equal(t, "public", w.Visibility)
+
+	w = wraps("Tagged")
+	require.NotNil(t, w, "PhantomData markers do not count")
+	assert.Equal(t, "Vec", w.Name)
+	assert.Equal(t, "Vec<u8>", w.TypeExpr)
+	assert.Equal(t, "private", w.Visibility)
+
+	assert.Nil(t, wraps("Pair"))
+	var pairFields []string
+	for _, m := range members["Pair"] {
+		pairFields = append(pairFields, m.Name+": "+m.TypeExpr)
+	}
+	assert.ElementsMatch(t, []string{"0: u32", "1: u32"}, pairFields)
+
+	w = wraps("Hash")
+	require.NotNil(t, w)
+	assert.Equal(t, "", w.Name, "an array has no head type")
+	assert.Equal(t, "[u8; 32]", w.TypeExpr)
+}
+
+func TestRustExtract_Supertraits(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+trait Shape: std::fmt::Debug + Clone + 'static {
+    fn area(&self) -> f64;
+}
+trait Solid: Shape where Self: Sized {}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	bounds := map[string][]string{}
+	for _, s := range syms {
+		if s.Kind != "trait" {
+			continue
+		}
+		tbs, err := env.store.TypeBounds(s.ID)
+		require.NoError(t, err)
+		for _, b := range tbs {
+			assert.Equal(t, "Self", b.Subject)
+			bounds[s.Name] = append(bounds[s.Name], fmt.Sprintf("%s (%s, where=%v)", b.Bound, b.Name, b.InWhereClause))
+		}
+	}
+	assert.ElementsMatch(t, []string{
+		"std::fmt::Debug (Debug, where=false)",
+		"Clone (Clone, where=false)",
+		"'static ('static, where=false)",
+	}, bounds["Shape"])
+	assert.ElementsMatch(t, []string{
+		"Shape (Shape, where=false)",
+		"Sized (Sized, where=true)",
+	}, bounds["Solid"])
+}
+
 func TestRustExtract_EnumWithVariants(t *testing.T) {
 	env := newRustTestEnv(t)
 	fileID := env.extractRustSource(`
@@ -270,6 +371,53 @@ impl Point {
 	assert.Equal(t, "&self", selfParam.TypeExpr)
 }
 
+func TestRustExtract_Subkinds(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+struct Point { x: f64 }
+struct Pair(i32, i32);
+struct Marker;
+
+impl Point {
+    pub const fn new(x: f64) -> Self { Point { x } }
+    fn norm(&self) -> f64 { self.x }
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+    fn describe(&self) -> String { String::new() }
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	for name, subkind := range map[string]string{
+		"Point":  "record_struct",
+		"Pair":   "tuple_struct",
+		"Marker": "unit_struct",
+		"new":    "associated_function",
+		"norm":   "",
+	} {
+		require.Contains(t, byName, name)
+		assert.Equal(t, subkind, byName[name].Subkind, name)
+	}
+	assert.Equal(t, "function", byName["new"].Kind)
+	assert.Contains(t, byName["new"].Modifiers, "const")
+	assert.Equal(t, "method", byName["norm"].Kind)
+
+	members, err := env.store.TypeMembers(byName["Shape"].ID)
+	require.NoError(t, err)
+	kinds := map[string]string{}
+	for _, m := range members {
+		kinds[m.Name] = m.Kind
+	}
+	assert.Equal(t, map[string]string{"area": "method", "describe": "default_method"}, kinds)
+}
+
 func TestRustExtract_UseStatements(t *testing.T) {
 	env := newRustTestEnv(t)
 	fileID := env.extractRustSource(`
@@ -449,6 +597,43 @@ fn get_x(p: Point) -> f64 {
 	require.NotNil(t, fieldRef, "expected field_access reference to x")
 }
 
+func TestRustExtract_References_Kinds(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+use crate::shapes::{Shape, Area as _};
+
+fn total<T: Area + Clone>(items: &[T]) -> Vec<Shape> where T: Send {
+    Vec::new()
+}
+
+fn describe(s: Shape) -> impl Display {
+    match s {
+        Shape::Circle(r) => r,
+        Shape::Empty => 0.0,
+        Point { x } => x,
+    }
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	kinds := map[string][]string{}
+	for _, r := range refs {
+		kinds[r.Name] = append(kinds[r.Name], r.Context)
+	}
+	assert.Contains(t, kinds["Shape"], "import")
+	assert.Contains(t, kinds["Shape"], "type_annotation")
+	assert.Contains(t, kinds["Area"], "import")
+	assert.Contains(t, kinds["Area"], "trait_bound")
+	assert.Contains(t, kinds["Clone"], "trait_bound")
+	assert.Contains(t, kinds["Send"], "trait_bound")
+	assert.Contains(t, kinds["Display"], "trait_bound")
+	assert.Equal(t, []string{"pattern"}, kinds["Circle"])
+	assert.Equal(t, []string{"pattern"}, kinds["Empty"])
+	assert.Equal(t, []string{"pattern"}, kinds["Point"])
+	assert.NotContains(t, kinds["T"], "trait_bound", "the bounded parameter itself is a type usage")
+}
+
 func TestRustExtract_FunctionParamsWithTypes(t *testing.T) {
 	env := newRustTestEnv(t)
 	fileID := env.extractRustSource(`
@@ -591,6 +776,19 @@ type Result<T> = std::result::Result<T, Error>;
 	}
 	assert.True(t, names["Pair"])
 	assert.True(t, names["Result"])
+
+	targets := map[string]*store.TypeMember{}
+	for _, a := range aliases {
+		members, err := env.store.TypeMembers(a.ID)
+		require.NoError(t, err)
+		require.Len(t, members, 1)
+		assert.Equal(t, "alias_target", members[0].Kind)
+		targets[a.Name] = members[0]
+	}
+	assert.Equal(t, "(i32, i32)", targets["Pair"].TypeExpr)
+	assert.Equal(t, "", targets["Pair"].Name, "a tuple has no head type")
+	assert.Equal(t, "std::result::Result<T, Error>", targets["Result"].TypeExpr)
+	assert.Equal(t, "Result", targets["Result"].Name)
 }
 
 func TestRustExtract_ConstantsAndStatics(t *testing.T) {
@@ -666,6 +864,7 @@ pub async fn fetch_data() -> String {
 	require.NotNil(t, fnSym, "expected function symbol")
 	assert.Equal(t, "fetch_data", fnSym.Name)
 	assert.Equal(t, "public", fnSym.Visibility)
+	assert.Equal(t, []string{"async"}, fnSym.Modifiers)
 
 	// Verify return type
 	params, err := env.store.FunctionParams(fnSym.ID)
@@ -681,6 +880,237 @@ pub async fn fetch_data() -> String {
 	assert.Equal(t, "String", retParam.TypeExpr)
 }
 
+func TestRustExtract_AwaitedCalls(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+async fn run(client: Client) {
+    fetch(1).await;
+    client.get("x").send().await;
+    Pool::connect("db").await;
+    let fut = prepare();
+    fut.await;
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	awaited := map[string]bool{}
+	for _, r := range refs {
+		if r.Context == "call" {
+			awaited[r.Name] = r.Awaited
+		}
+	}
+	assert.Equal(t, map[string]bool{
+		"fetch": true, "get": false, "send": true, "connect": true, "prepare": false,
+	}, awaited)
+}
+
+func TestRustExtract_SpawnedCalls(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn start(n: u32) {
+    thread::spawn(worker);
+    std::thread::spawn(move || process(n));
+    tokio::spawn(async move { serve(n).await });
+    tokio::task::spawn_blocking(load);
+    run(n);
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	spawned := map[string]bool{}
+	for _, r := range refs {
+		if r.Context == "call" {
+			spawned[r.Name] = r.Spawned
+		}
+	}
+	assert.Equal(t, map[string]bool{
+		"spawn": false, "spawn_blocking": false,
+		"worker": true, "process": true, "serve": true, "load": true, "run": false,
+	}, spawned)
+}
+
+func TestRustExtract_PropagatedCalls(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+async fn load(path: &str, client: Client) -> io::Result<String> {
+    let f = File::open(path)?;
+    let body = client.get(path).send().await?;
+    read(f)?.trim();
+    let n = count(path);
+    finish(body)
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	propagated := map[string]bool{}
+	for _, r := range refs {
+		if r.Context == "call" {
+			propagated[r.Name] = r.Propagated
+		}
+	}
+	assert.Equal(t, map[string]bool{
+		"open": true, "get": false, "send": true, "read": true, "trim": false, "count": false, "finish": false,
+	}, propagated)
+}
+
+func TestRustExtract_PanicSites(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn run(v: Vec<u32>, m: HashMap<u32, u32>) -> u32 {
+    fn nested() { todo!() }
+    let x = v.get(0).copied().unwrap_or(0);
+    assert!(x > 0);
+    let c = || v.first().expect("non-empty");
+    std::unreachable!();
+    m[&1] + v[1]
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	ids := map[string]int64{}
+	for _, s := range syms {
+		ids[s.Name] = s.ID
+	}
+	require.Contains(t, ids, "run")
+	require.Contains(t, ids, "nested")
+
+	type site struct{ kind, name string }
+	sitesOf := func(symbolID int64) []site {
+		sites, err := env.store.FlaggedSitesBySymbol(symbolID)
+		require.NoError(t, err)
+		var got []site
+		for _, s := range sites {
+			got = append(got, site{s.Kind, s.Name})
+		}
+		return got
+	}
+	assert.Equal(t, []site{
+		{"panic", "assert!"},
+		{"unwrap", "expect"},
+		{"panic", "unreachable!"},
+		{"index", "m[&1]"},
+		{"index", "v[1]"},
+	}, sitesOf(ids["run"]), "unwrap_or cannot panic; the nested fn's todo! is its own")
+	assert.Equal(t, []site{{"panic", "todo!"}}, sitesOf(ids["nested"]))
+}
+
+func TestRustExtract_Unsafe(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+pub async unsafe fn raw(p: *const u8) -> u8 {
+    *p
+}
+
+fn safe_wrapper(p: *const u8) -> u8 {
+    let first = unsafe { *p };
+    first + unsafe { *p.add(1) }
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	ids := map[string]int64{}
+	for _, s := range syms {
+		ids[s.Name] = s.ID
+		switch s.Name {
+		case "raw":
+			assert.Equal(t, []string{"async", "unsafe"}, s.Modifiers)
+		case "safe_wrapper":
+			assert.Empty(t, s.Modifiers)
+		}
+	}
+
+	sites, err := env.store.FlaggedSitesBySymbol(ids["safe_wrapper"])
+	require.NoError(t, err)
+	var unsafeBlocks int
+	for _, site := range sites {
+		if site.Kind == "unsafe" {
+			unsafeBlocks++
+		}
+	}
+	assert.Equal(t, 2, unsafeBlocks)
+}
+
+func TestRustExtract_ShadowSites(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn run(x: &str, n: u32) -> u32 {
+    let x = x.trim();
+    let total = n;
+    {
+        let total = 5;
+    }
+    let items = vec![1];
+    for n in items.iter() {}
+    let c = |x: u32| x + 1;
+    let r: Result<u32, u32> = Ok(1);
+    if let Ok(v) | Err(v) = r {}
+    match Some(total) {
+        Some(total) => total,
+        None => 0,
+    }
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+
+	sites, err := env.store.FlaggedSitesBySymbol(syms[0].ID)
+	require.NoError(t, err)
+	type site struct {
+		kind, name string
+		start, end [2]int // earlier binding's start, later binding's end
+	}
+	var got []site
+	for _, s := range sites {
+		got = append(got, site{s.Kind, s.Name, [2]int{s.StartLine, s.StartCol}, [2]int{s.EndLine, s.EndCol}})
+	}
+	assert.ElementsMatch(t, []site{
+		{"shadow_reuse", "x", [2]int{1, 7}, [2]int{2, 9}},
+		{"shadow", "n", [2]int{1, 16}, [2]int{8, 9}},
+		{"shadow", "total", [2]int{3, 8}, [2]int{5, 17}},
+		{"shadow", "x", [2]int{2, 8}, [2]int{9, 14}},
+		{"shadow_reuse", "total", [2]int{3, 8}, [2]int{13, 18}},
+	}, got, "the inner total is out of scope at the match; v is bound once by its or-pattern")
+}
+
+func TestRustExtract_FFI(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+extern {
+    fn abs(x: i32) -> i32;
+    static errno: i32;
+}
+
+pub extern "C" fn exported() {}
+
+pub fn plain() {}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	modifiers := map[string][]string{}
+	abis := map[string]string{}
+	for _, s := range syms {
+		modifiers[s.Name] = s.Modifiers
+		anns, err := env.store.AnnotationsByTarget(s.ID)
+		require.NoError(t, err)
+		for _, a := range anns {
+			if a.Name == "extern" {
+				abis[s.Name] = a.Arguments
+			}
+		}
+	}
+	assert.Equal(t, []string{"foreign"}, modifiers["abs"])
+	assert.Equal(t, []string{"foreign"}, modifiers["errno"])
+	assert.Equal(t, []string{"extern"}, modifiers["exported"])
+	assert.Empty(t, modifiers["plain"])
+	assert.Equal(t, map[string]string{"abs": "C", "errno": "C", "exported": "C"}, abis,
+		"a bare extern block defaults to the C ABI")
+}
+
 func TestRustExtract_ImplScope(t *testing.T) {
 	env := newRustTestEnv(t)
 	fileID := env.extractRustSource(`
@@ -807,6 +1237,45 @@ fn main() {
 	require.NotNil(t, callRef, "expected call reference to String::from")
 }
 
+func TestRustExtract_MacroInvocationReference(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn main() {
+    let v = vec![1, 2];
+    std::println!("{:?}", v);
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	macros := map[string]bool{}
+	for _, r := range refs {
+		if r.Context == "macro" {
+			macros[r.Name] = true
+		}
+	}
+	assert.Equal(t, map[string]bool{"vec": true, "println": true}, macros)
+}
+
+func TestRustExtract_IncludeImports(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+include!("generated/tables.rs");
+include!(concat!(env!("GEN_DIR"), "/other.rs"));
+`)
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+
+	var sources []string
+	for _, imp := range imports {
+		if imp.Kind == "include" {
+			sources = append(sources, imp.Source)
+		}
+	}
+	assert.Equal(t, []string{"$OUT_DIR/bindings.rs", "generated/tables.rs"}, sources)
+}
+
 func TestRustExtract_ComprehensiveFile(t *testing.T) {
 	env := newRustTestEnv(t)
 	fileID := env.extractRustSource(`
@@ -935,9 +1404,31 @@ use std::io::*;
 	imports, err := env.store.ImportsByFile(fileID)
 	require.NoError(t, err)
 
-	// The scoped_use_list won't match here; need to handle use_wildcard differently
-	// Let's check what we get
 	require.GreaterOrEqual(t, len(imports), 1, "expected at least 1 import from wildcard use")
+	assert.Equal(t, "std::io::*", imports[0].Source)
+	assert.Equal(t, "wildcard", imports[0].Kind)
+}
+
+func TestRustExtract_PubUseScope(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+use std::fmt;
+pub use self::net::{Client, http::Server};
+pub(crate) use crate::util::Helper as Aid;
+`)
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+
+	scopes := map[string]string{}
+	for _, imp := range imports {
+		scopes[imp.Source] = imp.Scope
+	}
+	assert.Equal(t, map[string]string{
+		"std::fmt":                "file",
+		"self::net::Client":       "public",
+		"self::net::http::Server": "public",
+		"crate::util::Helper":     "pub(crate)",
+	}, scopes)
 }
 
 func TestRustExtract_StructGenericTypeParams(t *testing.T) {
@@ -964,3 +1455,218 @@ struct Container<T: Clone> {
 	assert.Equal(t, "T", tps[0].Name)
 	assert.Equal(t, "Clone", tps[0].Constraints)
 }
+
+func TestRustExtract_Attributes(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+#[derive(Debug, Clone)]
+pub struct Config {}
+
+/// Old entry point.
+#[deprecated(note = "use run_v2")]
+#[inline]
+pub fn run() {}
+
+fn helper() {}
+
+#[tokio::main]
+async fn main() {}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	require.Contains(t, byName, "Config")
+	require.Contains(t, byName, "run")
+	require.Contains(t, byName, "helper")
+	require.Contains(t, byName, "main")
+
+	anns, err := env.store.AnnotationsByTarget(byName["Config"].ID)
+	require.NoError(t, err)
+	require.Len(t, anns, 1)
+	assert.Equal(t, "derive", anns[0].Name)
+	assert.Equal(t, "(Debug, Clone)", anns[0].Arguments)
+
+	anns, err = env.store.AnnotationsByTarget(byName["run"].ID)
+	require.NoError(t, err)
+	names := map[string]string{}
+	for _, a := range anns {
+		names[a.Name] = a.Arguments
+	}
+	assert.Len(t, names, 2)
+	assert.Equal(t, `(note = "use run_v2")`, names["deprecated"])
+	assert.Contains(t, names, "inline")
+
+	anns, err = env.store.AnnotationsByTarget(byName["helper"].ID)
+	require.NoError(t, err)
+	assert.Empty(t, anns, "attributes must not leak onto the next item")
+
+	anns, err = env.store.AnnotationsByTarget(byName["main"].ID)
+	require.NoError(t, err)
+	require.Len(t, anns, 1)
+	assert.Equal(t, "tokio::main", anns[0].Name)
+}
+
+func TestRustExtract_ImplCfgOnMethods(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+pub struct Client;
+
+#[cfg(feature = "json")]
+#[allow(dead_code)]
+impl Client {
+    #[inline]
+    pub fn to_json(&self) -> String { String::new() }
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	var method *store.Symbol
+	for _, s := range syms {
+		if s.Name == "to_json" {
+			method = s
+		}
+	}
+	require.NotNil(t, method)
+
+	anns, err := env.store.AnnotationsByTarget(method.ID)
+	require.NoError(t, err)
+	names := map[string]string{}
+	for _, a := range anns {
+		names[a.Name] = a.Arguments
+	}
+	assert.Equal(t, map[string]string{"inline": "", "cfg": `(feature = "json")`}, names,
+		"only the impl's cfg carries over to its methods")
+}
+
+func TestRustExtract_FunctionMetrics(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn simple() {}
+
+fn branchy(x: i32, y: bool) -> i32 {
+    if x > 0 && y {
+        match x {
+            1 => 10,
+            2 => 20,
+            _ => 30,
+        }
+    } else {
+        0
+    }
+}
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	require.Contains(t, byName, "simple")
+	require.Contains(t, byName, "branchy")
+	require.Contains(t, byName, "area")
+
+	m, err := env.store.FunctionMetricsBySymbol(byName["simple"].ID)
+	require.NoError(t, err)
+	require.NotNil(t, m)
+	assert.Equal(t, 1, m.Cyclomatic)
+	assert.Equal(t, 0, m.Cognitive)
+
+	// if (+1), && (+1), match with 3 arms (+2) => 5
+	// cognitive: if (1) + && (1) + nested match (2) + else (1) => 5
+	m, err = env.store.FunctionMetricsBySymbol(byName["branchy"].ID)
+	require.NoError(t, err)
+	require.NotNil(t, m)
+	assert.Equal(t, 5, m.Cyclomatic)
+	assert.Equal(t, 5, m.Cognitive)
+
+	// Bodiless trait method signatures get no metrics.
+	m, err = env.store.FunctionMetricsBySymbol(byName["area"].ID)
+	require.NoError(t, err)
+	assert.Nil(t, m)
+}
+
+func TestRustExtract_TurbofishCalls(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn convert<T>(x: u8) -> T { todo!() }
+
+fn main() {
+    let a = convert::<u16>(1);
+    let b: Vec<u8> = (0..3).collect::<Vec<u8>>();
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	calls := map[string]int{}
+	for _, r := range refs {
+		if r.Context == "call" {
+			calls[r.Name]++
+		}
+	}
+	assert.Equal(t, 1, calls["convert"])
+	assert.Equal(t, 1, calls["collect"])
+}
+
+func TestRustExtract_StructPatternFields(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+fn f(cfg: Config) {
+    let Config { name, port: p, .. } = cfg;
+    if let Shape::Circle { radius } = s {}
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	fields := map[string]string{}
+	for _, r := range refs {
+		if r.Context == "field_access" {
+			fields[r.Name] = r.ReceiverType
+		}
+	}
+	assert.Equal(t, map[string]string{"name": "Config", "port": "Config", "radius": "Circle"}, fields)
+}
+
+func TestRustExtract_TraitAssociatedItems(t *testing.T) {
+	env := newRustTestEnv(t)
+	fileID := env.extractRustSource(`
+trait Store {
+    type Key: Hash;
+    type Iter<'a> where Self: 'a;
+    const CAPACITY: usize = 16;
+    fn get(&self, key: &Self::Key) -> Option<u8>;
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+	var traitID int64
+	for _, s := range syms {
+		if s.Name == "Store" {
+			traitID = s.ID
+		}
+	}
+	require.NotZero(t, traitID)
+
+	members, err := env.store.TypeMembers(traitID)
+	require.NoError(t, err)
+	byName := map[string]*store.TypeMember{}
+	for _, m := range members {
+		byName[m.Name] = m
+	}
+	require.Len(t, byName, 4)
+	assert.Equal(t, "associated_type", byName["Key"].Kind)
+	assert.Equal(t, "type Key: Hash;", byName["Key"].TypeExpr)
+	assert.Equal(t, "associated_type", byName["Iter"].Kind)
+	assert.Equal(t, "associated_const", byName["CAPACITY"].Kind)
+	assert.Equal(t, "method", byName["get"].Kind)
+}
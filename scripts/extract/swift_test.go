@@ -0,0 +1,231 @@
+package go_extract_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/jward/canopy/internal/store"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// extractSwiftSource writes Swift source to a temp file, inserts a file
+// record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractSwiftSource(src string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	swiftFile := filepath.Join(dir, "Test.swift")
+	require.NoError(e.t, os.WriteFile(swiftFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     swiftFile,
+		Language: "swift",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": swiftFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "swift.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// ---------- Swift Tests ----------
+
+func TestSwift_TypesMembersAndConformances(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractSwiftSource(`import UIKit
+
+protocol Repository {
+    func save(_ item: String)
+}
+
+public struct User {
+    let id: Int
+    private(set) var name: String
+
+    init(id: Int, name: String) {
+        self.id = id
+        self.name = name
+    }
+
+    func greet(other: User) -> String {
+        return "hi"
+    }
+}
+
+enum Status {
+    case active, banned
+}
+
+final class Store: NSObject, Repository {
+    func save(_ item: String) {}
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	types := map[string]*store.Symbol{}
+	members := map[string]*store.Symbol{}
+	for _, s := range syms {
+		if s.ParentSymbolID == nil {
+			types[s.Name] = s
+		} else {
+			members[s.Name+"/"+s.Kind] = s
+		}
+	}
+	require.Contains(t, types, "Repository")
+	assert.Equal(t, "interface", types["Repository"].Kind)
+	require.Contains(t, types, "User")
+	assert.Equal(t, "struct", types["User"].Kind)
+	assert.Equal(t, "public", types["User"].Visibility)
+	require.Contains(t, types, "Status")
+	assert.Equal(t, "enum", types["Status"].Kind)
+	require.Contains(t, types, "Store")
+	assert.Equal(t, "class", types["Store"].Kind)
+	assert.Equal(t, "internal", types["Store"].Visibility)
+	assert.Contains(t, types["Store"].Modifiers, "final")
+
+	require.Contains(t, members, "User/constructor")
+	assert.Equal(t, types["User"].ID, *members["User/constructor"].ParentSymbolID)
+	require.Contains(t, members, "greet/method")
+	assert.Equal(t, types["User"].ID, *members["greet/method"].ParentSymbolID)
+	require.Contains(t, members, "name/property")
+	assert.Equal(t, "private", members["name/property"].Visibility)
+
+	params, err := env.store.FunctionParams(members["greet/method"].ID)
+	require.NoError(t, err)
+	var paramTypes, returnTypes []string
+	for _, p := range params {
+		if p.IsReturn {
+			returnTypes = append(returnTypes, p.TypeExpr)
+		} else {
+			paramTypes = append(paramTypes, p.Name+": "+p.TypeExpr)
+		}
+	}
+	assert.Equal(t, []string{"other: User"}, paramTypes)
+	assert.Equal(t, []string{"String"}, returnTypes)
+
+	userMembers, err := env.store.TypeMembers(types["User"].ID)
+	require.NoError(t, err)
+	props := map[string]string{}
+	for _, m := range userMembers {
+		if m.Kind == "property" {
+			props[m.Name] = m.TypeExpr
+		}
+	}
+	assert.Equal(t, map[string]string{"id": "Int", "name": "String"}, props)
+
+	statusMembers, err := env.store.TypeMembers(types["Status"].ID)
+	require.NoError(t, err)
+	var variants []string
+	for _, m := range statusMembers {
+		if m.Kind == "variant" {
+			variants = append(variants, m.Name)
+		}
+	}
+	assert.ElementsMatch(t, []string{"active", "banned"}, variants)
+
+	storeMembers, err := env.store.TypeMembers(types["Store"].ID)
+	require.NoError(t, err)
+	var supers []string
+	for _, m := range storeMembers {
+		if m.Kind == "embedded" {
+			supers = append(supers, m.Name)
+		}
+	}
+	assert.ElementsMatch(t, []string{"NSObject", "Repository"}, supers)
+
+	imports, err := env.store.ImportsByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, imports, 1)
+	assert.Equal(t, "UIKit", imports[0].Source)
+	assert.Equal(t, "module", imports[0].Kind)
+}
+
+func TestSwift_Extensions(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractSwiftSource(`struct Point {
+    var x: Double
+}
+
+extension Point {
+    func scaled(by factor: Double) -> Point {
+        return Point(x: x * factor)
+    }
+}
+
+extension String {
+    func shouted() -> String {
+        return uppercased()
+    }
+}
+`)
+	syms, err := env.store.SymbolsByFile(fileID)
+	require.NoError(t, err)
+
+	byName := map[string]*store.Symbol{}
+	for _, s := range syms {
+		byName[s.Name] = s
+	}
+	require.Contains(t, byName, "Point")
+	assert.NotContains(t, byName, "String", "an extension declares no type")
+
+	scaled := byName["scaled"]
+	require.NotNil(t, scaled)
+	assert.Equal(t, "method", scaled.Kind)
+	require.NotNil(t, scaled.ParentSymbolID, "extension members join a type declared in the file")
+	assert.Equal(t, byName["Point"].ID, *scaled.ParentSymbolID)
+
+	shouted := byName["shouted"]
+	require.NotNil(t, shouted)
+	assert.Equal(t, "method", shouted.Kind)
+	assert.Nil(t, shouted.ParentSymbolID)
+	params, err := env.store.FunctionParams(shouted.ID)
+	require.NoError(t, err)
+	var receiver string
+	for _, p := range params {
+		if p.IsReceiver {
+			receiver = p.TypeExpr
+		}
+	}
+	assert.Equal(t, "String", receiver, "a foreign type's extension records it as the receiver")
+}
+
+func TestSwift_References(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractSwiftSource(`func run(service: Service) {
+    let result = service.fetch()
+    log(result.count)
+    if result.isEmpty {
+        print("empty")
+    }
+}
+`)
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+
+	byContext := map[string][]string{}
+	for _, r := range refs {
+		byContext[r.Context] = append(byContext[r.Context], r.Name)
+	}
+	assert.Subset(t, byContext["call"], []string{"fetch", "service", "log", "print"})
+	assert.Subset(t, byContext["field_access"], []string{"count", "isEmpty"})
+	assert.Contains(t, byContext["type_annotation"], "Service")
+
+	scopes, err := env.store.ScopesByFile(fileID)
+	require.NoError(t, err)
+	kinds := map[string]int{}
+	for _, sc := range scopes {
+		kinds[sc.Kind]++
+	}
+	assert.Equal(t, 1, kinds["file"])
+	assert.Equal(t, 1, kinds["function"])
+	assert.Equal(t, 1, kinds["block"])
+}
@@ -325,3 +325,48 @@ Point origin() {
 	assert.Equal(t, "Point", targetSym.Name)
 	assert.Equal(t, "type_alias", targetSym.Kind)
 }
+
+func TestCResolve_HeaderSourcePairing(t *testing.T) {
+	env := newCTestEnv(t)
+	env.extractCSource(`int send_msg(int fd);
+`, "net.h")
+	defID := env.extractCSource(`int send_msg(int fd) {
+    return fd;
+}
+`, "net.c")
+	env.extractCSource(`#include "net.h"
+
+int main() {
+    send_msg(1);
+    return 0;
+}
+`, "main.c")
+
+	env.resolveC()
+
+	refs, err := env.store.ReferencesByName("send_msg")
+	require.NoError(t, err)
+	var callRef *store.Reference
+	for _, r := range refs {
+		if r.Context == "call" {
+			callRef = r
+			break
+		}
+	}
+	require.NotNil(t, callRef, "expected call reference to send_msg")
+
+	resolved, err := env.store.ResolvedReferencesByRef(callRef.ID)
+	require.NoError(t, err)
+	require.NotEmpty(t, resolved)
+	target := findSymbolByID(t, env.store, resolved[0].TargetSymbolID)
+	assert.Equal(t, "send_msg", target.Name)
+	require.NotNil(t, target.FileID)
+	assert.Equal(t, defID, *target.FileID, "the call resolves through the header's prototype to the definition in net.c")
+
+	mainSym := findSymbolByName(t, env.store, "main", "function")
+	require.NotNil(t, mainSym)
+	callees, err := env.store.CalleesByCaller(mainSym.ID)
+	require.NoError(t, err)
+	require.Len(t, callees, 1)
+	assert.Equal(t, target.ID, callees[0].CalleeSymbolID)
+}
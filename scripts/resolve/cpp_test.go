@@ -373,3 +373,51 @@ public:
 	duckSym := findSymbolByID(t, env.store, flyableImpls[0].TypeSymbolID)
 	assert.Equal(t, "Duck", duckSym.Name)
 }
+
+func TestCppResolve_CHeaderSourcePairing(t *testing.T) {
+	env := newCppTestEnv(t)
+
+	// .h headers are indexed as C, even in C++ code.
+	header := filepath.Join(t.TempDir(), "codec.h")
+	require.NoError(t, os.WriteFile(header, []byte("int encode(int x);\n"), 0644))
+	headerID, err := env.store.InsertFile(&store.File{Path: header, Language: "c"})
+	require.NoError(t, err)
+	err = env.rt.RunScript(context.Background(), filepath.Join("extract", "c.risor"), map[string]any{
+		"file_path": header,
+		"file_id":   headerID,
+	})
+	require.NoError(t, err)
+
+	defID := env.extractCppSource(`int encode(int x) {
+    return x + 1;
+}
+`, "codec.cpp")
+	env.extractCppSource(`#include "codec.h"
+
+int main() {
+    encode(1);
+    return 0;
+}
+`, "main.cpp")
+
+	env.resolveCpp()
+
+	refs, err := env.store.ReferencesByName("encode")
+	require.NoError(t, err)
+	var callRef *store.Reference
+	for _, r := range refs {
+		if r.Context == "call" {
+			callRef = r
+			break
+		}
+	}
+	require.NotNil(t, callRef, "expected call reference to encode")
+
+	resolved, err := env.store.ResolvedReferencesByRef(callRef.ID)
+	require.NoError(t, err)
+	require.NotEmpty(t, resolved)
+	target := findSymbolByID(t, env.store, resolved[0].TargetSymbolID)
+	require.NotNil(t, target.FileID)
+	assert.Equal(t, defID, *target.FileID, "the call resolves through codec.h to the definition in codec.cpp")
+	assert.Equal(t, "import", resolved[0].ResolutionKind)
+}
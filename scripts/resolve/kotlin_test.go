@@ -0,0 +1,269 @@
+package go_resolve_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// extractKotlinSource writes Kotlin source to a temp file, inserts a file
+// record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractKotlinSource(src string, filename string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	ktFile := filepath.Join(dir, filename)
+	require.NoError(e.t, os.WriteFile(ktFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     ktFile,
+		Language: "kotlin",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": ktFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "kotlin.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// resolveKotlin runs the Kotlin resolution script.
+func (e *testEnv) resolveKotlin() {
+	e.t.Helper()
+	extras := map[string]any{
+		"files_to_resolve": runtime.MakeFilesToResolveFn(e.store, nil),
+	}
+	err := e.rt.RunScript(context.Background(), filepath.Join("resolve", "kotlin.risor"), extras)
+	require.NoError(e.t, err)
+}
+
+// resolvedTarget returns the symbol the first reference named name with the
+// given context resolves to and its resolution, or nils if it is unresolved.
+func resolvedTarget(t *testing.T, s *store.Store, name, refContext string) (*store.Symbol, *store.ResolvedReference) {
+	t.Helper()
+	refs, err := s.ReferencesByName(name)
+	require.NoError(t, err)
+	for _, r := range refs {
+		if r.Context != refContext {
+			continue
+		}
+		resolved, err := s.ResolvedReferencesByRef(r.ID)
+		require.NoError(t, err)
+		if len(resolved) == 0 {
+			return nil, nil
+		}
+		return findSymbolByID(t, s, resolved[0].TargetSymbolID), resolved[0]
+	}
+	t.Fatalf("no %s reference to %s", refContext, name)
+	return nil, nil
+}
+
+// --- Tests ---
+
+func TestKotlinResolve_SameFileMethodCallAndCallGraph(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`class Processor {
+    fun process() {
+        helper()
+    }
+
+    private fun helper() {}
+}
+`, "Processor.kt")
+
+	env.resolveKotlin()
+
+	target, _ := resolvedTarget(t, env.store, "helper", "call")
+	require.NotNil(t, target, "expected helper call to be resolved")
+	assert.Equal(t, "method", target.Kind)
+
+	processSym := findSymbolByName(t, env.store, "process", "method")
+	require.NotNil(t, processSym)
+	edges, err := env.store.CalleesByCaller(processSym.ID)
+	require.NoError(t, err)
+	require.Len(t, edges, 1)
+	assert.Equal(t, target.ID, edges[0].CalleeSymbolID)
+}
+
+func TestKotlinResolve_ImportsAndAliases(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`package com.example.util
+
+class Formatter {
+    fun format(s: String): String = s
+}
+
+class Client
+`, "Util.kt")
+
+	env.extractKotlinSource(`package com.example.app
+
+import com.example.util.Formatter
+import com.example.util.Client as HttpClient
+
+fun run(f: Formatter, c: HttpClient) {}
+`, "Main.kt")
+
+	env.resolveKotlin()
+
+	target, rr := resolvedTarget(t, env.store, "Formatter", "type_annotation")
+	require.NotNil(t, target, "expected Formatter to resolve through its import")
+	assert.Equal(t, "class", target.Kind)
+	assert.Equal(t, "import", rr.ResolutionKind)
+
+	target, _ = resolvedTarget(t, env.store, "HttpClient", "type_annotation")
+	require.NotNil(t, target, "expected the aliased import to resolve")
+	assert.Equal(t, "Client", target.Name)
+}
+
+func TestKotlinResolve_WildcardImport(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`package com.example.model
+
+class User
+`, "User.kt")
+
+	env.extractKotlinSource(`package com.example.app
+
+import com.example.model.*
+
+fun load(u: User) {}
+`, "Main.kt")
+
+	env.resolveKotlin()
+
+	target, _ := resolvedTarget(t, env.store, "User", "type_annotation")
+	require.NotNil(t, target, "expected User to resolve through the wildcard import")
+	assert.Equal(t, "class", target.Kind)
+}
+
+func TestKotlinResolve_Supertypes(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`package com.example
+
+interface Shape {
+    fun area(): Double
+}
+
+open class Base
+`, "Shape.kt")
+
+	env.extractKotlinSource(`package com.example
+
+class Square(val side: Double) : Base(), Shape {
+    override fun area(): Double = side * side
+}
+`, "Square.kt")
+
+	env.resolveKotlin()
+
+	squareSym := findSymbolByName(t, env.store, "Square", "class")
+	shapeSym := findSymbolByName(t, env.store, "Shape", "interface")
+	baseSym := findSymbolByName(t, env.store, "Base", "class")
+	require.NotNil(t, squareSym)
+	require.NotNil(t, shapeSym)
+	require.NotNil(t, baseSym)
+
+	impls, err := env.store.ImplementationsByType(squareSym.ID)
+	require.NoError(t, err)
+	kinds := map[int64]string{}
+	for _, impl := range impls {
+		kinds[impl.InterfaceSymbolID] = impl.Kind
+	}
+	assert.Equal(t, "explicit", kinds[shapeSym.ID], "expected Square implements Shape")
+	assert.Equal(t, "extends", kinds[baseSym.ID], "expected Square extends Base")
+}
+
+func TestKotlinResolve_JavaInterop(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractJavaSource(`package com.example.legacy;
+
+public interface Listener {
+    void onEvent(String name);
+}
+`, "Listener.java")
+
+	env.extractJavaSource(`package com.example.app;
+
+public class Config {
+    public String get(String key) { return key; }
+}
+`, "Config.java")
+
+	env.extractKotlinSource(`package com.example.app
+
+import com.example.legacy.Listener
+
+class Logger(val config: Config) : Listener {
+    override fun onEvent(name: String) {}
+}
+`, "Logger.kt")
+
+	env.resolveKotlin()
+
+	// Config is in the same package, Listener is imported.
+	target, _ := resolvedTarget(t, env.store, "Config", "type_annotation")
+	require.NotNil(t, target, "expected Config to resolve to the Java class")
+	assert.Equal(t, "class", target.Kind)
+
+	loggerSym := findSymbolByName(t, env.store, "Logger", "class")
+	listenerSym := findSymbolByName(t, env.store, "Listener", "interface")
+	require.NotNil(t, loggerSym)
+	require.NotNil(t, listenerSym)
+	impls, err := env.store.ImplementationsByInterface(listenerSym.ID)
+	require.NoError(t, err)
+	require.Len(t, impls, 1)
+	assert.Equal(t, loggerSym.ID, impls[0].TypeSymbolID)
+}
+
+func TestKotlinResolve_ExtensionBindings(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`package com.example
+
+class Server {
+    fun start() {}
+}
+
+fun Server.restart() {
+    start()
+}
+`, "Server.kt")
+
+	env.resolveKotlin()
+
+	serverSym := findSymbolByName(t, env.store, "Server", "class")
+	require.NotNil(t, serverSym)
+
+	bindings, err := env.store.ExtensionBindingsByType(serverSym.ID)
+	require.NoError(t, err)
+	names := map[string]bool{}
+	for _, b := range bindings {
+		names[findSymbolByID(t, env.store, b.MemberSymbolID).Name] = true
+		assert.Equal(t, "method", b.Kind)
+	}
+	assert.Equal(t, map[string]bool{"start": true, "restart": true}, names)
+}
+
+func TestKotlinResolve_UnresolvedReference(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractKotlinSource(`fun run() {
+    nonExistent()
+}
+`, "App.kt")
+
+	env.resolveKotlin()
+
+	target, _ := resolvedTarget(t, env.store, "nonExistent", "call")
+	assert.Nil(t, target, "nonExistent should not be resolved")
+}
@@ -0,0 +1,154 @@
+package go_resolve_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// extractProtoSource writes a .proto file at the given path below dir,
+// inserts a file record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractProtoSource(src, dir, name string) int64 {
+	e.t.Helper()
+
+	protoFile := filepath.Join(dir, name)
+	require.NoError(e.t, os.MkdirAll(filepath.Dir(protoFile), 0755))
+	require.NoError(e.t, os.WriteFile(protoFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     protoFile,
+		Language: "protobuf",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": protoFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "protobuf.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// resolveProto runs the protobuf resolution script.
+func (e *testEnv) resolveProto() {
+	e.t.Helper()
+	extras := map[string]any{
+		"files_to_resolve": runtime.MakeFilesToResolveFn(e.store, nil),
+	}
+	err := e.rt.RunScript(context.Background(), filepath.Join("resolve", "protobuf.risor"), extras)
+	require.NoError(e.t, err)
+}
+
+// protoRefTargets maps each type reference name of a file to the
+// "parent.name" of the symbol it resolves to ("name" at top level), or ""
+// if it is unresolved.
+func protoRefTargets(t *testing.T, s *store.Store, fileID int64) map[string]string {
+	t.Helper()
+	refs, err := s.ReferencesByFile(fileID)
+	require.NoError(t, err)
+	targets := map[string]string{}
+	for _, r := range refs {
+		resolved, err := s.ResolvedReferencesByRef(r.ID)
+		require.NoError(t, err)
+		if len(resolved) == 0 {
+			targets[r.Name] = ""
+			continue
+		}
+		sym := findSymbolByID(t, s, resolved[0].TargetSymbolID)
+		name := sym.Name
+		if sym.ParentSymbolID != nil {
+			name = findSymbolByID(t, s, *sym.ParentSymbolID).Name + "." + name
+		}
+		targets[r.Name] = name
+	}
+	return targets
+}
+
+// --- Tests ---
+
+func TestProtoResolve_NestedTypesShadowTopLevel(t *testing.T) {
+	env := newTestEnv(t)
+	fileID := env.extractProtoSource(`syntax = "proto3";
+
+message Item {}
+
+message Order {
+  message Item {
+    string sku = 1;
+  }
+  repeated Item items = 1;
+}
+
+message Cart {
+  repeated Item items = 1;
+}
+`, t.TempDir(), "order.proto")
+
+	env.resolveProto()
+
+	refs, err := env.store.ReferencesByFile(fileID)
+	require.NoError(t, err)
+	require.Len(t, refs, 2)
+	var targets []string
+	for _, r := range refs {
+		resolved, err := env.store.ResolvedReferencesByRef(r.ID)
+		require.NoError(t, err)
+		require.Len(t, resolved, 1)
+		sym := findSymbolByID(t, env.store, resolved[0].TargetSymbolID)
+		if sym.ParentSymbolID != nil {
+			targets = append(targets, "nested")
+		} else {
+			targets = append(targets, "top-level")
+		}
+	}
+	assert.Equal(t, []string{"nested", "top-level"}, targets, "Order.items uses Order.Item, Cart.items the top-level Item")
+}
+
+func TestProtoResolve_PackageAndImports(t *testing.T) {
+	env := newTestEnv(t)
+	root := t.TempDir()
+	env.extractProtoSource(`syntax = "proto3";
+package common;
+
+message Money {
+  int64 units = 1;
+}
+`, root, "common/money.proto")
+
+	env.extractProtoSource(`syntax = "proto3";
+package shop;
+
+message Product {
+  string sku = 1;
+}
+`, root, "shop/product.proto")
+
+	orderID := env.extractProtoSource(`syntax = "proto3";
+package shop;
+
+import "common/money.proto";
+
+message Order {
+  Product product = 1;
+  common.Money total = 2;
+  Coupon coupon = 3;
+}
+`, root, "shop/order.proto")
+
+	env.resolveProto()
+
+	assert.Equal(t, map[string]string{
+		"Product": "Product",
+		"Money":   "Money",
+		"Coupon":  "",
+	}, protoRefTargets(t, env.store, orderID))
+}
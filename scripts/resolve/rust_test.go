@@ -51,6 +51,7 @@ func (e *rustTestEnv) resolveRust() {
 	e.t.Helper()
 	extras := map[string]any{
 		"files_to_resolve": runtime.MakeFilesToResolveFn(e.store, nil),
+		"rust_crates":      map[string]any{},
 	}
 	err := e.rt.RunScript(context.Background(), filepath.Join("resolve", "rust.risor"), extras)
 	require.NoError(e.t, err)
@@ -235,6 +236,147 @@ fn main() {
 	assert.True(t, found, "expected call edge from main to helper")
 }
 
+func TestRustResolve_InitializerDependencyEdges(t *testing.T) {
+	env := newRustTestEnv(t)
+	env.extractRustSource(`const BASE: u32 = 10;
+
+fn scale(x: u32) -> u32 { x * 2 }
+
+const LIMIT: u32 = scale(BASE) + 1;
+
+static TABLE: [u32; 2] = [BASE, LIMIT];
+`, "main.rs")
+
+	env.resolveRust()
+
+	callees := func(name, kind string) map[string]bool {
+		sym := findSymbolByName(t, env.store, name, kind)
+		require.NotNil(t, sym, name)
+		edges, err := env.store.CalleesByCaller(sym.ID)
+		require.NoError(t, err)
+		out := map[string]bool{}
+		for _, e := range edges {
+			var calleeName string
+			require.NoError(t, env.store.DB().QueryRow("SELECT name FROM symbols WHERE id = ?", e.CalleeSymbolID).Scan(&calleeName))
+			out[calleeName] = true
+		}
+		return out
+	}
+
+	assert.Equal(t, map[string]bool{"scale": true, "BASE": true}, callees("LIMIT", "constant"))
+	assert.Equal(t, map[string]bool{"BASE": true, "LIMIT": true}, callees("TABLE", "variable"))
+	assert.Empty(t, callees("BASE", "constant"))
+}
+
+func TestRustResolve_OperatorOverloading(t *testing.T) {
+	env := newRustTestEnv(t)
+	env.extractRustSource(`use std::ops::{Add, Index};
+
+struct Point { x: i32 }
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point { x: self.x + other.x }
+    }
+}
+
+struct Grid { cells: Vec<i32> }
+
+impl Grid {
+    fn new() -> Grid { Grid { cells: vec![] } }
+}
+
+impl Index<usize> for Grid {
+    type Output = i32;
+    fn index(&self, i: usize) -> &i32 { &self.cells[i] }
+}
+
+fn add(a: i32, b: i32) -> i32 { a + b }
+
+fn shift(a: Point, b: Point) -> Point {
+    a + b
+}
+
+fn first() -> i32 {
+    let g = Grid::new();
+    g[0]
+}
+`, "main.rs")
+
+	env.resolveRust()
+
+	calleeNames := func(caller string) []string {
+		sym := findSymbolByName(t, env.store, caller, "function")
+		require.NotNil(t, sym, caller)
+		edges, err := env.store.CalleesByCaller(sym.ID)
+		require.NoError(t, err)
+		var names []string
+		for _, e := range edges {
+			var name string
+			var parent *int64
+			require.NoError(t, env.store.DB().QueryRow(
+				"SELECT name, parent_symbol_id FROM symbols WHERE id = ?", e.CalleeSymbolID).Scan(&name, &parent))
+			if parent != nil {
+				name = "method " + name
+			}
+			names = append(names, name)
+		}
+		return names
+	}
+
+	assert.Equal(t, []string{"method add"}, calleeNames("shift"), "a + b on Point calls Point's Add::add, not fn add")
+	assert.ElementsMatch(t, []string{"method new", "method index"}, calleeNames("first"))
+	assert.Empty(t, calleeNames("add"), "+ on i32 is built in")
+}
+
+func TestRustResolve_StdCombinatorChains(t *testing.T) {
+	env := newRustTestEnv(t)
+	env.extractRustSource(`struct Filter;
+
+impl Filter {
+    fn filter(&self) {}
+    fn map(&self) {}
+}
+
+fn is_valid(x: &i32) -> bool { *x > 0 }
+
+fn double(x: i32) -> i32 { x * 2 }
+
+fn valid(items: Vec<i32>) -> Vec<i32> {
+    items.iter().copied().filter(is_valid).collect()
+}
+
+fn parsed(s: &str) -> Option<i32> {
+    s.parse::<i32>().ok().map(double)
+}
+`, "main.rs")
+
+	env.resolveRust()
+
+	callees := func(caller string) map[string]bool {
+		sym := findSymbolByName(t, env.store, caller, "function")
+		require.NotNil(t, sym, caller)
+		edges, err := env.store.CalleesByCaller(sym.ID)
+		require.NoError(t, err)
+		out := map[string]bool{}
+		for _, e := range edges {
+			var name string
+			require.NoError(t, env.store.DB().QueryRow("SELECT name FROM symbols WHERE id = ?", e.CalleeSymbolID).Scan(&name))
+			out[name] = true
+		}
+		return out
+	}
+
+	valid := callees("valid")
+	assert.True(t, valid["is_valid"], "filter(is_valid) calls is_valid")
+	assert.False(t, valid["filter"], "Iterator::filter is not Filter::filter")
+
+	parsed := callees("parsed")
+	assert.True(t, parsed["double"], "map(double) calls double")
+	assert.False(t, parsed["map"], "Option::map is not Filter::map")
+}
+
 func TestRustResolve_ExtensionBindings(t *testing.T) {
 	env := newRustTestEnv(t)
 	env.extractRustSource(`struct Server {}
@@ -445,3 +587,51 @@ fn compute() {
 	require.NoError(t, err)
 	require.NotEmpty(t, resolved, "expected area call to be resolved")
 }
+
+func TestRustResolve_ImportPrefersRustdocStub(t *testing.T) {
+	env := newRustTestEnv(t)
+
+	// A local function shares its name with the external one.
+	env.extractRustSource(`pub fn from_str() {}
+`, "local.rs")
+
+	env.extractRustSource(`use serde_json::from_str;
+
+fn main() {
+    from_str();
+}
+`, "main.rs")
+
+	// Stub as created by rustdoc JSON ingestion.
+	stubFileID, err := env.store.InsertFile(&store.File{
+		Path:     "rustdoc:serde_json",
+		Language: "rustdoc",
+	})
+	require.NoError(t, err)
+	stubID, err := env.store.InsertSymbol(&store.Symbol{
+		FileID:     &stubFileID,
+		Name:       "from_str",
+		Kind:       "function",
+		Visibility: "public",
+		Modifiers:  []string{"stub"},
+	})
+	require.NoError(t, err)
+
+	env.resolveRust()
+
+	refs, err := env.store.ReferencesByName("from_str")
+	require.NoError(t, err)
+	var callRef *store.Reference
+	for _, r := range refs {
+		if r.Context == "call" {
+			callRef = r
+			break
+		}
+	}
+	require.NotNil(t, callRef, "expected call reference to from_str")
+
+	resolved, err := env.store.ResolvedReferencesByRef(callRef.ID)
+	require.NoError(t, err)
+	require.NotEmpty(t, resolved, "expected from_str call to be resolved")
+	assert.Equal(t, stubID, resolved[0].TargetSymbolID)
+}
@@ -0,0 +1,257 @@
+package go_resolve_test
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/jward/canopy/internal/runtime"
+	"github.com/jward/canopy/internal/store"
+)
+
+// extractSwiftSource writes Swift source to a temp file, inserts a file
+// record, and runs the extraction script. Returns the file ID.
+func (e *testEnv) extractSwiftSource(src string, filename string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	swiftFile := filepath.Join(dir, filename)
+	require.NoError(e.t, os.WriteFile(swiftFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     swiftFile,
+		Language: "swift",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": swiftFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "swift.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// extractObjCHeader writes an Objective-C header to a temp file and runs
+// the C extraction script on it. Returns the file ID.
+func (e *testEnv) extractObjCHeader(src string, filename string) int64 {
+	e.t.Helper()
+
+	dir := e.t.TempDir()
+	hFile := filepath.Join(dir, filename)
+	require.NoError(e.t, os.WriteFile(hFile, []byte(src), 0644))
+
+	fileID, err := e.store.InsertFile(&store.File{
+		Path:     hFile,
+		Language: "c",
+	})
+	require.NoError(e.t, err)
+
+	extras := map[string]any{
+		"file_path": hFile,
+		"file_id":   fileID,
+	}
+	err = e.rt.RunScript(context.Background(), filepath.Join("extract", "c.risor"), extras)
+	require.NoError(e.t, err)
+
+	return fileID
+}
+
+// resolveSwift runs the Swift resolution script.
+func (e *testEnv) resolveSwift() {
+	e.t.Helper()
+	extras := map[string]any{
+		"files_to_resolve": runtime.MakeFilesToResolveFn(e.store, nil),
+	}
+	err := e.rt.RunScript(context.Background(), filepath.Join("resolve", "swift.risor"), extras)
+	require.NoError(e.t, err)
+}
+
+// --- Tests ---
+
+func TestSwiftResolve_SameFileMethodCallAndCallGraph(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractSwiftSource(`class Processor {
+    func process() {
+        helper()
+    }
+
+    private func helper() {}
+}
+`, "Processor.swift")
+
+	env.resolveSwift()
+
+	target, _ := resolvedTarget(t, env.store, "helper", "call")
+	require.NotNil(t, target, "expected helper call to be resolved")
+	assert.Equal(t, "method", target.Kind)
+
+	processSym := findSymbolByName(t, env.store, "process", "method")
+	require.NotNil(t, processSym)
+	edges, err := env.store.CalleesByCaller(processSym.ID)
+	require.NoError(t, err)
+	require.Len(t, edges, 1)
+	assert.Equal(t, target.ID, edges[0].CalleeSymbolID)
+}
+
+func TestSwiftResolve_ModuleWideAndConformances(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractSwiftSource(`protocol Shape {
+    func area() -> Double
+}
+
+class Base {}
+
+func makeSquare() -> Square {
+    return Square(side: 2)
+}
+`, "Shape.swift")
+
+	env.extractSwiftSource(`class Square: Base, Shape {
+    let side: Double
+
+    init(side: Double) {
+        self.side = side
+    }
+
+    func area() -> Double {
+        return side * side
+    }
+}
+
+func report() {
+    let s = makeSquare()
+    print(s.area())
+}
+`, "Square.swift")
+
+	env.resolveSwift()
+
+	target, rr := resolvedTarget(t, env.store, "makeSquare", "call")
+	require.NotNil(t, target, "expected a top-level function of another file to resolve without an import")
+	assert.Equal(t, "function", target.Kind)
+	assert.Equal(t, "direct", rr.ResolutionKind)
+
+	target, _ = resolvedTarget(t, env.store, "area", "call")
+	require.NotNil(t, target, "expected the method call to resolve")
+	assert.Equal(t, "method", target.Kind)
+
+	squareSym := findSymbolByName(t, env.store, "Square", "class")
+	shapeSym := findSymbolByName(t, env.store, "Shape", "interface")
+	baseSym := findSymbolByName(t, env.store, "Base", "class")
+	require.NotNil(t, squareSym)
+	require.NotNil(t, shapeSym)
+	require.NotNil(t, baseSym)
+
+	impls, err := env.store.ImplementationsByType(squareSym.ID)
+	require.NoError(t, err)
+	kinds := map[int64]string{}
+	for _, impl := range impls {
+		kinds[impl.InterfaceSymbolID] = impl.Kind
+	}
+	assert.Equal(t, "explicit", kinds[shapeSym.ID], "expected Square conforms to Shape")
+	assert.Equal(t, "extends", kinds[baseSym.ID], "expected Square extends Base")
+}
+
+func TestSwiftResolve_ExtensionBindings(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractSwiftSource(`struct Server {
+    func start() {}
+}
+`, "Server.swift")
+
+	env.extractSwiftSource(`extension Server {
+    func restart() {
+        start()
+    }
+}
+`, "Server+Restart.swift")
+
+	env.resolveSwift()
+
+	serverSym := findSymbolByName(t, env.store, "Server", "struct")
+	require.NotNil(t, serverSym)
+
+	bindings, err := env.store.ExtensionBindingsByType(serverSym.ID)
+	require.NoError(t, err)
+	names := map[string]bool{}
+	for _, b := range bindings {
+		names[findSymbolByID(t, env.store, b.MemberSymbolID).Name] = true
+		assert.Equal(t, "method", b.Kind)
+	}
+	assert.Equal(t, map[string]bool{"start": true, "restart": true}, names)
+
+	target, _ := resolvedTarget(t, env.store, "start", "call")
+	require.NotNil(t, target, "expected the extension's call to resolve to the type's method")
+	assert.Equal(t, serverSym.ID, *target.ParentSymbolID)
+}
+
+func TestSwiftResolve_ObjectiveCHeader(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractObjCHeader(`#import <Foundation/Foundation.h>
+
+@protocol LegacyDelegate <NSObject>
+- (void)clientDidFinish:(LegacyClient *)client;
+@end
+
+@interface LegacyClient : NSObject
+@property (nonatomic, copy) NSString *name;
+- (instancetype)initWithName:(NSString *)name;
+- (void)fetchUser:(NSString *)userID
+       completion:(void (^)(NSError *error))completion;
++ (LegacyClient *)shared;
+@end
+`, "LegacyClient.h")
+
+	env.extractSwiftSource(`class Controller: LegacyDelegate {
+    func load(client: LegacyClient) {
+        client.fetchUser("42", completion: { _ in })
+        print(client.name)
+    }
+
+    func clientDidFinish(_ client: LegacyClient) {}
+}
+`, "Controller.swift")
+
+	env.resolveSwift()
+
+	target, _ := resolvedTarget(t, env.store, "LegacyClient", "type_annotation")
+	require.NotNil(t, target, "expected the Objective-C class to be visible to Swift")
+	assert.Equal(t, "class", target.Kind)
+
+	target, _ = resolvedTarget(t, env.store, "fetchUser", "call")
+	require.NotNil(t, target, "expected the call to resolve to the Objective-C method")
+	assert.Equal(t, "method", target.Kind)
+
+	target, _ = resolvedTarget(t, env.store, "name", "field_access")
+	require.NotNil(t, target)
+	assert.Equal(t, "property", target.Kind)
+
+	controllerSym := findSymbolByName(t, env.store, "Controller", "class")
+	delegateSym := findSymbolByName(t, env.store, "LegacyDelegate", "interface")
+	require.NotNil(t, controllerSym)
+	require.NotNil(t, delegateSym)
+	impls, err := env.store.ImplementationsByInterface(delegateSym.ID)
+	require.NoError(t, err)
+	require.Len(t, impls, 1)
+	assert.Equal(t, controllerSym.ID, impls[0].TypeSymbolID)
+}
+
+func TestSwiftResolve_UnresolvedReference(t *testing.T) {
+	env := newTestEnv(t)
+	env.extractSwiftSource(`func run() {
+    undefinedThing()
+}
+`, "Main.swift")
+
+	env.resolveSwift()
+
+	target, _ := resolvedTarget(t, env.store, "undefinedThing", "call")
+	assert.Nil(t, target)
+}
@@ -0,0 +1,287 @@
+package canopy
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"io/fs"
+	"maps"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// Shard is one independently indexed part of a monorepo, such as a crate,
+// Go module, or npm package.
+type Shard struct {
+	Name string // unique among the shards; names the shard's database
+	Root string // directory holding the shard's manifest
+}
+
+// shardManifests are the files that make a directory a shard root.
+var shardManifests = []string{"Cargo.toml", "go.mod", "package.json"}
+
+// DiscoverShards returns a shard named "root" for root itself and one for
+// every directory under it holding a Cargo.toml, go.mod, or package.json.
+// Hidden directories, build output (target), and the directories
+// IndexDirectory skips are not searched. Other shards are named by their
+// path relative to root with slashes replaced by dashes, e.g.
+// "crates-parser".
+func DiscoverShards(root string) ([]Shard, error) {
+	root = filepath.Clean(root)
+	shards := []Shard{{Name: "root", Root: root}}
+	err := filepath.WalkDir(root, func(path string, d fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if !d.IsDir() || path == root {
+			return nil
+		}
+		if name := d.Name(); strings.HasPrefix(name, ".") || skipDirs[name] || name == "target" {
+			return filepath.SkipDir
+		}
+		for _, m := range shardManifests {
+			if _, err := os.Stat(filepath.Join(path, m)); err == nil {
+				rel, _ := filepath.Rel(root, path)
+				shards = append(shards, Shard{Name: strings.ReplaceAll(filepath.ToSlash(rel), "/", "-"), Root: path})
+				break
+			}
+		}
+		return nil
+	})
+	if err != nil {
+		return nil, fmt.Errorf("discover shards: %w", err)
+	}
+	return shards, nil
+}
+
+// IndexShards indexes a monorepo one shard at a time, so that a change to
+// one crate or package re-extracts and re-resolves that shard alone
+// instead of the whole index.
+//
+// Each shard is indexed with the Engine's options into its own database,
+// dir/<name>.db, and resolved there on its own. A shard owns the files
+// under its root that no nested shard owns, and include and exclude globs
+// are matched relative to its root. Shards whose database changed, or that
+// are not yet linked, are then linked into the Engine's database: their
+// previous files are removed and the shard's files, symbols, and
+// resolution data are copied in. Linked shards no longer listed are
+// removed, along with their databases.
+//
+// References between shards are left to Resolve, which afterwards
+// re-resolves only the files a link may affect: files of the linked shards
+// with references their shard could not resolve, other files with
+// unresolved references named like a linked shard's top-level symbols,
+// and files that referenced symbols of a replaced shard.
+func (e *Engine) IndexShards(ctx context.Context, dir string, shards []Shard) error {
+	seen := make(map[string]bool, len(shards))
+	for _, sh := range shards {
+		if seen[sh.Name] {
+			return fmt.Errorf("duplicate shard name %q", sh.Name)
+		}
+		seen[sh.Name] = true
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return fmt.Errorf("create shard directory: %w", err)
+	}
+	linked, err := e.linkedShards()
+	if err != nil {
+		return err
+	}
+	if e.blastRadius == nil {
+		e.blastRadius = make(map[int64]bool)
+	}
+
+	for _, sh := range shards {
+		sh.Root = filepath.Clean(sh.Root)
+		dbPath := filepath.Join(dir, sh.Name+".db")
+		changed, err := e.indexShard(ctx, dbPath, sh, nestedShardGlobs(sh, shards))
+		if err != nil {
+			return fmt.Errorf("index shard %s: %w", sh.Name, err)
+		}
+		root, ok := linked[sh.Name]
+		if ok && root == sh.Root && !changed {
+			continue
+		}
+		// Forget the link first, so an interrupted relink is redone.
+		delete(linked, sh.Name)
+		if err := e.saveLinkedShards(linked); err != nil {
+			return err
+		}
+		// Files under the shard's root may also come from an index built
+		// without shards.
+		if err := e.unlinkShard(sh.Root); err != nil {
+			return fmt.Errorf("unlink shard %s: %w", sh.Name, err)
+		}
+		if ok && root != sh.Root {
+			if err := e.unlinkShard(root); err != nil {
+				return fmt.Errorf("unlink shard %s: %w", sh.Name, err)
+			}
+		}
+		if err := e.linkShard(dbPath); err != nil {
+			return fmt.Errorf("link shard %s: %w", sh.Name, err)
+		}
+		linked[sh.Name] = sh.Root
+		if err := e.saveLinkedShards(linked); err != nil {
+			return err
+		}
+	}
+
+	for _, name := range slices.Sorted(maps.Keys(linked)) {
+		if seen[name] {
+			continue
+		}
+		if err := e.unlinkShard(linked[name]); err != nil {
+			return fmt.Errorf("unlink shard %s: %w", name, err)
+		}
+		delete(linked, name)
+		if err := e.saveLinkedShards(linked); err != nil {
+			return err
+		}
+		removeDatabase(filepath.Join(dir, name+".db"))
+	}
+	return nil
+}
+
+// nestedShardGlobs returns exclude globs for the shards nested inside sh.
+func nestedShardGlobs(sh Shard, shards []Shard) []string {
+	var globs []string
+	for _, other := range shards {
+		root := filepath.Clean(other.Root)
+		if root == sh.Root || !strings.HasPrefix(root, sh.Root+string(filepath.Separator)) {
+			continue
+		}
+		rel, _ := filepath.Rel(sh.Root, root)
+		// A glob with a slash is anchored at the shard root.
+		globs = append(globs, filepath.ToSlash(rel)+"/**")
+	}
+	return globs
+}
+
+// indexShard indexes and resolves one shard into the database at dbPath,
+// rebuilding it if the scripts changed since it was built, and reports
+// whether any of the shard's files changed.
+func (e *Engine) indexShard(ctx context.Context, dbPath string, sh Shard, exclude []string) (bool, error) {
+	shardEngine, err := e.newShardEngine(dbPath, exclude)
+	if err != nil {
+		return false, err
+	}
+	if shardEngine.ScriptsChanged() {
+		shardEngine.Close()
+		removeDatabase(dbPath)
+		if shardEngine, err = e.newShardEngine(dbPath, exclude); err != nil {
+			return false, err
+		}
+	}
+	defer shardEngine.Close()
+
+	if err := shardEngine.IndexDirectory(ctx, sh.Root); err != nil {
+		return false, err
+	}
+	changed := len(shardEngine.blastRadius) > 0
+	if err := shardEngine.Resolve(ctx); err != nil {
+		return false, err
+	}
+	return changed, nil
+}
+
+// newShardEngine opens an Engine on dbPath configured like e, additionally
+// excluding the given globs.
+func (e *Engine) newShardEngine(dbPath string, exclude []string) (*Engine, error) {
+	e.overlayMu.RLock()
+	overlays := maps.Clone(e.overlays)
+	e.overlayMu.RUnlock()
+	return New(dbPath, e.scriptsDir, func(s *Engine) {
+		s.scriptsFS = e.scriptsFS
+		s.languages = e.languages
+		s.include = e.include
+		s.exclude = append(slices.Clone(e.exclude), exclude...)
+		s.useParallel = e.useParallel
+		s.maxMemory = e.maxMemory
+		s.blame = e.blame
+		s.progress = e.progress
+		s.tracer = e.tracer
+		s.parseCacheDir = e.parseCacheDir
+		s.overlays = overlays
+	})
+}
+
+// linkShard copies the shard database at dbPath into the index, adding the
+// files whose references the link may resolve to the blast radius.
+// Dependency stubs are left out: the index loads its own.
+func (e *Engine) linkShard(dbPath string) error {
+	src, err := store.NewStore(dbPath)
+	if err != nil {
+		return err
+	}
+	defer src.Close()
+	fileIDs, err := e.store.ImportFrom(src, rustdocStubLanguage)
+	if err != nil {
+		return err
+	}
+	blastFileIDs, err := e.store.FilesWithUnlinkedReferences(fileIDs)
+	if err != nil {
+		return err
+	}
+	for _, fid := range blastFileIDs {
+		e.blastRadius[fid] = true
+	}
+	return nil
+}
+
+// unlinkShard removes the files a shard rooted at root linked into the
+// index, adding the files that referenced them to the blast radius.
+func (e *Engine) unlinkShard(root string) error {
+	rows, err := e.store.DB().Query("SELECT id FROM files WHERE root = ?", root)
+	if err != nil {
+		return fmt.Errorf("list shard files: %w", err)
+	}
+	var fileIDs []int64
+	for rows.Next() {
+		var id int64
+		if err := rows.Scan(&id); err != nil {
+			rows.Close()
+			return fmt.Errorf("list shard files: scan: %w", err)
+		}
+		fileIDs = append(fileIDs, id)
+	}
+	rows.Close()
+	for _, id := range fileIDs {
+		if err := e.removeFile(id); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// linkedShards returns the root of each shard linked into the index, by
+// name.
+func (e *Engine) linkedShards() (map[string]string, error) {
+	linked := make(map[string]string)
+	value, err := e.store.GetMetadata("shards")
+	if err != nil || value == "" {
+		return linked, err
+	}
+	if err := json.Unmarshal([]byte(value), &linked); err != nil {
+		return nil, fmt.Errorf("decode linked shards: %w", err)
+	}
+	return linked, nil
+}
+
+func (e *Engine) saveLinkedShards(linked map[string]string) error {
+	data, err := json.Marshal(linked)
+	if err != nil {
+		return fmt.Errorf("encode linked shards: %w", err)
+	}
+	return e.store.SetMetadata("shards", string(data))
+}
+
+// removeDatabase deletes a SQLite database and its WAL files.
+func removeDatabase(dbPath string) {
+	for _, suffix := range []string{"", "-wal", "-shm"} {
+		_ = os.Remove(dbPath + suffix)
+	}
+}
@@ -0,0 +1,135 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+// writeShardRepo writes a monorepo of two Go modules, b calling into a.
+func writeShardRepo(t *testing.T) string {
+	t.Helper()
+	root := t.TempDir()
+	for _, mod := range []string{"a", "b"} {
+		require.NoError(t, os.MkdirAll(filepath.Join(root, mod), 0o755))
+		writeGoFile(t, filepath.Join(root, mod), "go.mod", "module example.com/"+mod+"\n")
+	}
+	writeGoFile(t, filepath.Join(root, "a"), "a.go", `package a
+
+func Helper() string {
+	return "hello"
+}
+`)
+	writeGoFile(t, filepath.Join(root, "b"), "b.go", `package b
+
+import "example.com/a"
+
+func Run() string {
+	return a.Helper()
+}
+`)
+	return root
+}
+
+// resolvedRefsTo counts the resolved references targeting symbols named name.
+func resolvedRefsTo(t *testing.T, e *Engine, name string) int {
+	t.Helper()
+	var n int
+	require.NoError(t, e.store.DB().QueryRow(
+		`SELECT COUNT(*) FROM resolved_references rr JOIN symbols s ON s.id = rr.target_symbol_id WHERE s.name = ?`,
+		name).Scan(&n))
+	return n
+}
+
+func fileIDByPath(t *testing.T, e *Engine, path string) int64 {
+	t.Helper()
+	f, err := e.store.FileByPath(path)
+	require.NoError(t, err)
+	require.NotNil(t, f, path)
+	return f.ID
+}
+
+func TestDiscoverShards(t *testing.T) {
+	root := writeShardRepo(t)
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "b", "sub"), 0o755))
+	writeGoFile(t, filepath.Join(root, "b", "sub"), "go.mod", "module example.com/b/sub\n")
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "node_modules", "x"), 0o755))
+	writeGoFile(t, filepath.Join(root, "node_modules", "x"), "package.json", "{}\n")
+
+	shards, err := DiscoverShards(root)
+	require.NoError(t, err)
+	assert.Equal(t, []Shard{
+		{Name: "root", Root: root},
+		{Name: "a", Root: filepath.Join(root, "a")},
+		{Name: "b", Root: filepath.Join(root, "b")},
+		{Name: "b-sub", Root: filepath.Join(root, "b", "sub")},
+	}, shards)
+	assert.Equal(t, []string{"sub/**"}, nestedShardGlobs(shards[2], shards))
+}
+
+func TestIndexShards(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	ctx := context.Background()
+	root := writeShardRepo(t)
+	shardDir := filepath.Join(t.TempDir(), "shards")
+	shards, err := DiscoverShards(root)
+	require.NoError(t, err)
+
+	require.NoError(t, e.IndexShards(ctx, shardDir, shards))
+	require.NoError(t, e.Resolve(ctx))
+	for _, name := range []string{"root", "a", "b"} {
+		assert.FileExists(t, filepath.Join(shardDir, name+".db"))
+	}
+	files, err := e.store.AllFiles()
+	require.NoError(t, err)
+	assert.Len(t, files, 2)
+	assert.Equal(t, 1, resolvedRefsTo(t, e, "Helper"), "b's call into a resolves across shards")
+	violations, err := e.store.Validate()
+	require.NoError(t, err)
+	assert.Empty(t, violations)
+
+	// Changing b relinks b alone.
+	aID := fileIDByPath(t, e, filepath.Join(root, "a", "a.go"))
+	bID := fileIDByPath(t, e, filepath.Join(root, "b", "b.go"))
+	writeGoFile(t, filepath.Join(root, "b"), "b.go", `package b
+
+import "example.com/a"
+
+func Run() string {
+	return a.Helper() + a.Helper()
+}
+`)
+	require.NoError(t, e.IndexShards(ctx, shardDir, shards))
+	require.NoError(t, e.Resolve(ctx))
+	assert.Equal(t, aID, fileIDByPath(t, e, filepath.Join(root, "a", "a.go")))
+	assert.NotEqual(t, bID, fileIDByPath(t, e, filepath.Join(root, "b", "b.go")))
+	assert.Equal(t, 2, resolvedRefsTo(t, e, "Helper"))
+
+	// Nothing changed: no shard is relinked and nothing re-resolved.
+	require.NoError(t, e.IndexShards(ctx, shardDir, shards))
+	assert.Empty(t, e.blastRadius)
+	require.NoError(t, e.Resolve(ctx))
+
+	// Dropping b unlinks it and deletes its database.
+	require.NoError(t, e.IndexShards(ctx, shardDir, shards[:2]))
+	require.NoError(t, e.Resolve(ctx))
+	files, err = e.store.AllFiles()
+	require.NoError(t, err)
+	assert.Len(t, files, 1)
+	assert.Zero(t, resolvedRefsTo(t, e, "Helper"))
+	assert.NoFileExists(t, filepath.Join(shardDir, "b.db"))
+}
+
+func TestIndexShards_DuplicateName(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("go"))
+	root := writeShardRepo(t)
+	err := e.IndexShards(context.Background(), t.TempDir(), []Shard{
+		{Name: "x", Root: filepath.Join(root, "a")},
+		{Name: "x", Root: filepath.Join(root, "b")},
+	})
+	assert.ErrorContains(t, err, `duplicate shard name "x"`)
+}
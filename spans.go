@@ -0,0 +1,85 @@
+package canopy
+
+import (
+	"fmt"
+	"sort"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// lineIndex maps the 0-based line and byte column positions produced by
+// tree-sitter to byte offsets and UTF-16 columns.
+type lineIndex struct {
+	content []byte
+	starts  []int // byte offset of the first byte of each line
+}
+
+func newLineIndex(content []byte) *lineIndex {
+	starts := []int{0}
+	for i, b := range content {
+		if b == '\n' {
+			starts = append(starts, i+1)
+		}
+	}
+	return &lineIndex{content: content, starts: starts}
+}
+
+// offsets returns the byte offset from the start of the file and the UTF-16
+// column of the given line and byte column. Positions past the end of a
+// line or of the file are clamped to its end.
+func (li *lineIndex) offsets(line, col int) (byteOffset, utf16Col int) {
+	if line < 0 {
+		return 0, 0
+	}
+	if line >= len(li.starts) {
+		last := li.starts[len(li.starts)-1]
+		return len(li.content), utf16Len(li.content[last:])
+	}
+	start := li.starts[line]
+	end := len(li.content)
+	if line+1 < len(li.starts) {
+		end = li.starts[line+1] - 1
+	}
+	byteOffset = min(start+max(col, 0), end)
+	return byteOffset, utf16Len(li.content[start:byteOffset])
+}
+
+// position returns the 0-based line and byte column of a byte offset from
+// the start of the file, the inverse of offsets.
+func (li *lineIndex) position(byteOffset int) (line, col int) {
+	line = sort.Search(len(li.starts), func(i int) bool { return li.starts[i] > byteOffset }) - 1
+	line = max(line, 0)
+	return line, byteOffset - li.starts[line]
+}
+
+// recordSpanOffsets fills in byte offsets and UTF-16 columns for the symbols
+// and references extracted from a file, using the file's content.
+func (e *Engine) recordSpanOffsets(fileID int64, content []byte) error {
+	li := newLineIndex(content)
+	span := func(id int64, startLine, startCol, endLine, endCol int) store.SpanOffsets {
+		sp := store.SpanOffsets{ID: id}
+		sp.StartByte, sp.UTF16StartCol = li.offsets(startLine, startCol)
+		sp.EndByte, sp.UTF16EndCol = li.offsets(endLine, endCol)
+		return sp
+	}
+
+	syms, err := e.store.SymbolsByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load symbols: %w", err)
+	}
+	symSpans := make([]store.SpanOffsets, len(syms))
+	for i, s := range syms {
+		symSpans[i] = span(s.ID, s.StartLine, s.StartCol, s.EndLine, s.EndCol)
+	}
+
+	refs, err := e.store.ReferencesByFile(fileID)
+	if err != nil {
+		return fmt.Errorf("load references: %w", err)
+	}
+	refSpans := make([]store.SpanOffsets, len(refs))
+	for i, r := range refs {
+		refSpans[i] = span(r.ID, r.StartLine, r.StartCol, r.EndLine, r.EndCol)
+	}
+
+	return e.store.UpdateSpanOffsets(symSpans, refSpans)
+}
@@ -0,0 +1,74 @@
+package canopy
+
+import (
+	"context"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestLineIndex_Offsets(t *testing.T) {
+	t.Parallel()
+	li := newLineIndex([]byte("ab\n\"é😀\"x\nlast"))
+
+	off, col := li.offsets(0, 1)
+	assert.Equal(t, 1, off)
+	assert.Equal(t, 1, col)
+
+	// "é😀" is 1+2+4+1 = 8 bytes but 1+1+2+1 = 5 UTF-16 units before x.
+	off, col = li.offsets(1, 8)
+	assert.Equal(t, 11, off)
+	assert.Equal(t, 5, col)
+
+	off, col = li.offsets(2, 4)
+	assert.Equal(t, 17, off)
+	assert.Equal(t, 4, col)
+
+	// Out-of-range positions are clamped to the end of the line or file.
+	off, col = li.offsets(1, 99)
+	assert.Equal(t, 12, off)
+	assert.Equal(t, 6, col)
+	off, col = li.offsets(2, 99)
+	assert.Equal(t, 17, off)
+	assert.Equal(t, 4, col)
+	off, col = li.offsets(9, 0)
+	assert.Equal(t, 17, off)
+	assert.Equal(t, 4, col, "the column of the end of the last line")
+}
+
+func TestIndexFiles_RecordsSpanOffsets(t *testing.T) {
+	const src = `package main
+
+func greet() string {
+	return "hi"
+}
+
+func main() {
+	_ = "é😀"; greet()
+}
+`
+	for _, parallel := range []bool{false, true} {
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel))
+		ctx := context.Background()
+		path := writeGoFile(t, t.TempDir(), "main.go", src)
+
+		require.NoError(t, e.IndexFiles(ctx, []string{path}))
+		require.NoError(t, e.Resolve(ctx))
+
+		syms, err := e.store.SymbolsByName("greet")
+		require.NoError(t, err)
+		require.Len(t, syms, 1)
+		assert.Equal(t, strings.Index(src, "func greet"), syms[0].StartByte)
+		assert.Equal(t, strings.Index(src, "}\n\nfunc main")+1, syms[0].EndByte)
+
+		locs, err := e.Query().ReferencesTo(syms[0].ID)
+		require.NoError(t, err)
+		require.Len(t, locs, 1)
+		assert.Equal(t, 7, locs[0].StartLine)
+		assert.Equal(t, 15, locs[0].StartCol, "byte column")
+		assert.Equal(t, 12, locs[0].UTF16StartCol)
+		assert.Equal(t, strings.Index(src, "greet()"), locs[0].StartByte)
+	}
+}
@@ -0,0 +1,524 @@
+package canopy
+
+import (
+	"fmt"
+	"strings"
+	"time"
+
+	"github.com/jward/canopy/internal/store"
+)
+
+// WithSQLReferences enables SQL reference extraction. After resolution, the
+// SQL strings of sqlx query macros (query!, query_as!, query_scalar!, and
+// their _unchecked forms) and diesel::sql_query calls in Rust files are
+// scanned for the tables and columns they name. Each table and column
+// becomes a stub symbol (kinds "table" and "column") and each mention a
+// reference of kind RefKindSQL inside the calling function, so
+// FunctionsTouchingTable can answer "which functions touch table orders".
+// Off by default because it reads the source of every file with SQL again.
+func WithSQLReferences(enabled bool) Option {
+	return func(e *Engine) {
+		e.sqlRefs = enabled
+	}
+}
+
+// sqlStubPath is the stub file holding the tables and columns named by
+// embedded SQL. It uses the rustdoc stub language, so no script runs for it.
+const sqlStubPath = "sql:tables"
+
+// sqlQueryMacros are the sqlx macros whose first string literal argument is
+// a query.
+var sqlQueryMacros = []string{
+	"query", "query_as", "query_scalar",
+	"query_unchecked", "query_as_unchecked", "query_scalar_unchecked",
+}
+
+// sqlQueryCalls are the functions whose first string literal argument is a
+// query.
+var sqlQueryCalls = []string{"sql_query"}
+
+// sqlSite is a macro invocation or call that may carry a query.
+type sqlSite struct {
+	fileID  int64
+	path    string
+	scopeID *int64
+	endByte int // end of the macro or function name
+}
+
+// clearSQLReferences deletes the SQL references and the table and column
+// stubs they resolve to.
+func (e *Engine) clearSQLReferences() error {
+	if err := e.store.DeleteReferencesByContext(RefKindSQL); err != nil {
+		return fmt.Errorf("clear sql references: %w", err)
+	}
+	f, err := e.store.FileByPath(sqlStubPath)
+	if err != nil {
+		return fmt.Errorf("clear sql references: %w", err)
+	}
+	if f == nil {
+		return nil
+	}
+	if err := e.store.DeleteFileData(f.ID); err != nil {
+		return fmt.Errorf("clear sql references: %w", err)
+	}
+	if _, err := e.store.DB().Exec("DELETE FROM files WHERE id = ?", f.ID); err != nil {
+		return fmt.Errorf("clear sql references: delete stub file: %w", err)
+	}
+	return nil
+}
+
+// indexSQLReferences records the tables and columns named by the queries
+// of every Rust file. Tables and qualified columns resolve with probable
+// confidence; an unqualified column is attributed to the only table of its
+// query with heuristic confidence, and skipped when the query names several.
+func (e *Engine) indexSQLReferences() error {
+	sites, err := e.sqlSites()
+	if err != nil {
+		return fmt.Errorf("sql references: %w", err)
+	}
+	if len(sites) == 0 {
+		return nil
+	}
+
+	var stubFileID int64
+	stubs := map[[2]string]int64{} // {table, column} -> stub symbol; column "" for the table
+	var stub func(table, column string) (int64, error)
+	stub = func(table, column string) (int64, error) {
+		if id, ok := stubs[[2]string{table, column}]; ok {
+			return id, nil
+		}
+		if stubFileID == 0 {
+			id, err := e.store.InsertFile(&store.File{
+				Path:        sqlStubPath,
+				Language:    rustdocStubLanguage,
+				LastIndexed: time.Now(),
+			})
+			if err != nil {
+				return 0, fmt.Errorf("stub file: %w", err)
+			}
+			stubFileID = id
+		}
+		sym := &store.Symbol{
+			FileID:     &stubFileID,
+			Name:       table,
+			Kind:       "table",
+			Visibility: "public",
+			Modifiers:  []string{"stub"},
+		}
+		if column != "" {
+			parentID, err := stub(table, "")
+			if err != nil {
+				return 0, err
+			}
+			sym.Name = column
+			sym.Kind = "column"
+			sym.ParentSymbolID = &parentID
+		}
+		id, err := e.store.InsertSymbol(sym)
+		if err != nil {
+			return 0, fmt.Errorf("stub symbol %s: %w", sym.Name, err)
+		}
+		stubs[[2]string{table, column}] = id
+		return id, nil
+	}
+
+	var content []byte
+	var li *lineIndex
+	var loadedPath string
+	for _, site := range sites {
+		if site.path != loadedPath {
+			loadedPath = site.path
+			if content, err = e.readSource(site.path); err != nil {
+				content = nil // deleted since indexing
+			}
+			li = newLineIndex(content)
+		}
+		query, offset, ok := sqlLiteralAfter(content, site.endByte)
+		if !ok {
+			continue
+		}
+		for _, ref := range sqlReferences(query) {
+			target, err := stub(ref.table, ref.column)
+			if err != nil {
+				return fmt.Errorf("sql references: %w", err)
+			}
+			start, end := offset+ref.offset, offset+ref.offset+len(ref.name)
+			r := &store.Reference{
+				FileID:    site.fileID,
+				ScopeID:   site.scopeID,
+				Name:      ref.name,
+				Context:   RefKindSQL,
+				StartByte: start,
+				EndByte:   end,
+			}
+			r.StartLine, r.StartCol = li.position(start)
+			r.EndLine, r.EndCol = li.position(end)
+			_, r.UTF16StartCol = li.offsets(r.StartLine, r.StartCol)
+			_, r.UTF16EndCol = li.offsets(r.EndLine, r.EndCol)
+			refID, err := e.store.InsertReference(r)
+			if err != nil {
+				return fmt.Errorf("sql references: %w", err)
+			}
+			confidence := 0.9
+			if ref.guessed {
+				confidence = 0.8
+			}
+			if _, err := e.store.InsertResolvedReference(&store.ResolvedReference{
+				ReferenceID:    refID,
+				TargetSymbolID: target,
+				Confidence:     confidence,
+				ResolutionKind: "sql",
+			}); err != nil {
+				return fmt.Errorf("sql references: %w", err)
+			}
+		}
+	}
+	return nil
+}
+
+// sqlSites returns the sqlx query macro invocations and diesel sql_query
+// calls of Rust files, ordered by file and position.
+func (e *Engine) sqlSites() ([]sqlSite, error) {
+	args := []any{RefKindMacro}
+	for _, m := range sqlQueryMacros {
+		args = append(args, m)
+	}
+	args = append(args, RefKindCall)
+	for _, c := range sqlQueryCalls {
+		args = append(args, c)
+	}
+	rows, err := e.store.DB().Query(
+		`SELECT r.file_id, f.path, r.scope_id, r.end_byte FROM references_ r
+		 JOIN files f ON f.id = r.file_id
+		 WHERE f.language = 'rust'
+			AND ((r.context = ? AND r.name IN (`+strings.Repeat("?,", len(sqlQueryMacros)-1)+`?))
+			OR (r.context = ? AND r.name IN (`+strings.Repeat("?,", len(sqlQueryCalls)-1)+`?)))
+		 ORDER BY f.path, r.start_byte`, args...)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+	var sites []sqlSite
+	for rows.Next() {
+		var s sqlSite
+		if err := rows.Scan(&s.fileID, &s.path, &s.scopeID, &s.endByte); err != nil {
+			return nil, err
+		}
+		sites = append(sites, s)
+	}
+	return sites, rows.Err()
+}
+
+// sqlLiteralAfter returns the contents of the first string literal among
+// the arguments of the macro invocation or call whose name ends at end, and
+// the offset of the contents in src. Escapes are left as written, so
+// offsets into the contents are offsets into src. ok is false when the
+// arguments hold no string literal, as in sqlx::query!(QUERY).
+func sqlLiteralAfter(src []byte, end int) (contents string, offset int, ok bool) {
+	i := end
+	if i < len(src) && src[i] == '!' {
+		i++
+	}
+	for i < len(src) && isSQLSpace(src[i]) {
+		i++
+	}
+	if i >= len(src) || (src[i] != '(' && src[i] != '[' && src[i] != '{') {
+		return "", 0, false
+	}
+	depth := 0
+	for ; i < len(src); i++ {
+		switch src[i] {
+		case '(', '[', '{':
+			depth++
+		case ')', ']', '}':
+			depth--
+			if depth == 0 {
+				return "", 0, false
+			}
+		case '"':
+			for j := i + 1; j < len(src); j++ {
+				if src[j] == '\\' {
+					j++
+				} else if src[j] == '"' {
+					return string(src[i+1 : j]), i + 1, true
+				}
+			}
+			return "", 0, false
+		case 'r':
+			if i > 0 && isSQLIdentByte(src[i-1]) {
+				continue
+			}
+			// r"..." or r#"..."#: the contents end at a quote followed by
+			// as many hashes as opened the literal.
+			j := i + 1
+			for j < len(src) && src[j] == '#' {
+				j++
+			}
+			if j >= len(src) || src[j] != '"' {
+				continue
+			}
+			closing := `"` + strings.Repeat("#", j-i-1)
+			k := strings.Index(string(src[j+1:]), closing)
+			if k < 0 {
+				return "", 0, false
+			}
+			return string(src[j+1 : j+1+k]), j + 1, true
+		}
+	}
+	return "", 0, false
+}
+
+// sqlToken is an identifier or punctuation of a query. Literals, numbers,
+// parameters, and comments are dropped.
+type sqlToken struct {
+	text   string // quoted identifiers without their quotes
+	offset int    // of text in the query
+	ident  bool
+	quoted bool
+}
+
+// word returns the token as a keyword or unquoted identifier compares: in
+// lower case. Quoted identifiers are case-sensitive.
+func (t sqlToken) word() string {
+	if t.quoted {
+		return t.text
+	}
+	return strings.ToLower(t.text)
+}
+
+// sqlTokens splits a query into tokens. A backslash counts as whitespace,
+// so \" in a Rust string quotes an identifier like " does.
+func sqlTokens(query string) []sqlToken {
+	var tokens []sqlToken
+	for i := 0; i < len(query); {
+		c := query[i]
+		switch {
+		case isSQLSpace(c) || c == '\\':
+			i++
+		case c == '-' && strings.HasPrefix(query[i:], "--"):
+			if n := strings.IndexByte(query[i:], '\n'); n >= 0 {
+				i += n + 1
+			} else {
+				i = len(query)
+			}
+		case c == '/' && strings.HasPrefix(query[i:], "/*"):
+			if n := strings.Index(query[i+2:], "*/"); n >= 0 {
+				i += n + 4
+			} else {
+				i = len(query)
+			}
+		case c == '\'':
+			i++
+			for i < len(query) && query[i] != '\'' {
+				i++
+			}
+			i++
+		case c == '"' || c == '`':
+			n := strings.IndexByte(query[i+1:], c)
+			if n < 0 {
+				n = len(query) - i - 1
+			}
+			text := strings.TrimSuffix(query[i+1:i+1+n], `\`)
+			tokens = append(tokens, sqlToken{text: text, offset: i + 1, ident: true, quoted: true})
+			i += n + 2
+		case c == '$' || c == '?' || (c >= '0' && c <= '9'):
+			i++
+			for i < len(query) && isSQLIdentByte(query[i]) {
+				i++
+			}
+		case c == ':' && i+1 < len(query) && query[i+1] == ':':
+			tokens = append(tokens, sqlToken{text: "::", offset: i})
+			i += 2
+		case c == ':' && i+1 < len(query) && isSQLIdentByte(query[i+1]):
+			// A named parameter, :id.
+			i++
+			for i < len(query) && isSQLIdentByte(query[i]) {
+				i++
+			}
+		case isSQLIdentByte(c):
+			start := i
+			for i < len(query) && isSQLIdentByte(query[i]) {
+				i++
+			}
+			tokens = append(tokens, sqlToken{text: query[start:i], offset: start, ident: true})
+		default:
+			tokens = append(tokens, sqlToken{text: query[i : i+1], offset: i})
+			i++
+		}
+	}
+	return tokens
+}
+
+func isSQLSpace(c byte) bool {
+	return c == ' ' || c == '\t' || c == '\n' || c == '\r'
+}
+
+func isSQLIdentByte(c byte) bool {
+	return c == '_' || (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9')
+}
+
+// sqlKeywords are the words that are never table or column names.
+var sqlKeywords = func() map[string]bool {
+	keywords := map[string]bool{}
+	for _, k := range strings.Fields(`
+		add all alter and any as asc between by cascade case cast check collate column conflict constraint
+		create cross current_date current_time current_timestamp default delete desc distinct do drop else
+		end escape except excluded exists false fetch filter first for foreign from full group having if
+		ilike in index inner insert intersect interval into is join key last lateral left like limit
+		localtime localtimestamp locked natural next not nothing nowait null nulls offset on only or order
+		outer over partition primary recursive references restrict returning right row rows select set
+		share similar skip some table then to true truncate union unique update using values when where
+		window with within`) {
+		keywords[k] = true
+	}
+	return keywords
+}()
+
+// sqlTableIntroducers are the keywords followed by a table name.
+var sqlTableIntroducers = map[string]bool{
+	"from": true, "join": true, "into": true, "update": true, "table": true,
+}
+
+// sqlRef is a table or column named in a query.
+type sqlRef struct {
+	name    string // as written
+	table   string
+	column  string // "" when the table itself is named
+	offset  int    // of name in the query
+	guessed bool   // an unqualified column attributed to the query's only table
+}
+
+// sqlReferences returns the tables and columns a query names, in order.
+// Tables follow FROM, JOIN, INTO, UPDATE, and TABLE; columns are qualified
+// by a table or its alias (o.total), listed after INSERT INTO t, or
+// unqualified in a query of a single table. Names of common table
+// expressions, output aliases (AS name), functions, and types after :: are
+// not references.
+func sqlReferences(query string) []sqlRef {
+	tokens := sqlTokens(query)
+	at := func(i int) sqlToken {
+		if i < 0 || i >= len(tokens) {
+			return sqlToken{}
+		}
+		return tokens[i]
+	}
+	isWord := func(i int, w string) bool {
+		t := at(i)
+		return t.ident && !t.quoted && strings.EqualFold(t.text, w)
+	}
+	isName := func(i int) bool {
+		t := at(i)
+		return t.ident && (t.quoted || !sqlKeywords[t.word()])
+	}
+
+	// Common table expressions (name AS (...)) and output aliases (AS name)
+	// are names the query defines, not tables or columns.
+	ctes, outputs := map[string]bool{}, map[string]bool{}
+	for i := range tokens {
+		if isName(i) && isWord(i+1, "as") && at(i+2).text == "(" {
+			ctes[tokens[i].word()] = true
+		}
+		if isWord(i, "as") && isName(i+1) && at(i+2).text != "(" {
+			outputs[tokens[i+1].word()] = true
+		}
+	}
+
+	type tableName struct {
+		index int
+		table string
+	}
+	var tables []tableName
+	aliases := map[string]string{} // alias or table name -> table
+	columnLists := map[int]string{} // token index of a column listed after INSERT INTO t -> t
+	consumed := map[int]bool{}
+	for i := range tokens {
+		if !tokens[i].ident || tokens[i].quoted || !sqlTableIntroducers[tokens[i].word()] {
+			continue
+		}
+		introducer := tokens[i].word()
+		j := i + 1
+		for {
+			if isWord(j, "only") || isWord(j, "lateral") {
+				j++
+			}
+			if !isName(j) || ctes[tokens[j].word()] {
+				break
+			}
+			// schema.table names the table.
+			for at(j+1).text == "." && isName(j+2) {
+				consumed[j] = true
+				j += 2
+			}
+			if at(j+1).text == "(" && introducer != "into" {
+				break // a table function, as in FROM generate_series(...)
+			}
+			table := tokens[j].word()
+			tables = append(tables, tableName{index: j, table: table})
+			aliases[table] = table
+			consumed[j] = true
+			j++
+			if isWord(j, "as") {
+				j++
+			}
+			if isName(j) {
+				aliases[tokens[j].word()] = table
+				consumed[j] = true
+				j++
+			}
+			if introducer == "into" && at(j).text == "(" {
+				for j++; j < len(tokens) && at(j).text != ")"; j++ {
+					if isName(j) {
+						columnLists[j] = table
+					}
+				}
+			}
+			if introducer != "from" || at(j).text != "," {
+				break
+			}
+			j++
+		}
+	}
+
+	distinct := map[string]bool{}
+	for _, t := range tables {
+		distinct[t.table] = true
+	}
+	var only string
+	if len(distinct) == 1 {
+		only = tables[0].table
+	}
+
+	var refs []sqlRef
+	nextTable := 0
+	for i := 0; i < len(tokens); i++ {
+		if nextTable < len(tables) && tables[nextTable].index == i {
+			t := tokens[i]
+			refs = append(refs, sqlRef{name: t.text, table: tables[nextTable].table, offset: t.offset})
+			nextTable++
+			continue
+		}
+		if consumed[i] || !isName(i) {
+			continue
+		}
+		t := tokens[i]
+		if table, ok := columnLists[i]; ok {
+			refs = append(refs, sqlRef{name: t.text, table: table, column: t.word(), offset: t.offset})
+			continue
+		}
+		if at(i+1).text == "." {
+			// qualifier.column, or qualifier.* which names no column.
+			if table, ok := aliases[t.word()]; ok && isName(i+2) {
+				c := tokens[i+2]
+				refs = append(refs, sqlRef{name: c.text, table: table, column: c.word(), offset: c.offset})
+			}
+			i += 2
+			continue
+		}
+		if at(i+1).text == "(" || at(i-1).text == "::" || isWord(i-1, "as") ||
+			ctes[t.word()] || outputs[t.word()] || only == "" {
+			continue
+		}
+		refs = append(refs, sqlRef{name: t.text, table: only, column: t.word(), offset: t.offset, guessed: true})
+	}
+	return refs
+}
@@ -0,0 +1,156 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestSQLReferences(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		name  string
+		query string
+		want  []string // "table" or "table.column"
+	}{
+		{
+			name:  "single table",
+			query: "SELECT id, total FROM orders WHERE customer_id = $1",
+			want:  []string{"orders.id", "orders.total", "orders", "orders.customer_id"},
+		},
+		{
+			name:  "aliases",
+			query: "SELECT o.id, c.name FROM orders o JOIN customers c ON c.id = o.customer_id",
+			want:  []string{"orders.id", "customers.name", "orders", "customers", "customers.id", "orders.customer_id"},
+		},
+		{
+			name:  "insert column list",
+			query: "INSERT INTO orders (customer_id, total) VALUES ($1, $2) RETURNING id",
+			want:  []string{"orders", "orders.customer_id", "orders.total", "orders.id"},
+		},
+		{
+			name:  "upsert",
+			query: "INSERT INTO stock (sku, qty) VALUES (?, ?) ON CONFLICT (sku) DO UPDATE SET qty = stock.qty + excluded.qty",
+			want:  []string{"stock", "stock.sku", "stock.qty", "stock.sku", "stock.qty", "stock.qty", "stock.qty"},
+		},
+		{
+			name: "cte, functions, output aliases, and casts",
+			query: `WITH recent AS (SELECT customer_id FROM orders WHERE created_at > now() - interval '1 day')
+				SELECT count(*) AS n, customer_id::text FROM recent GROUP BY customer_id -- per customer`,
+			want: []string{"orders.customer_id", "orders", "orders.created_at", "orders.customer_id", "orders.customer_id"},
+		},
+		{
+			name:  "quoted identifiers in a Rust string",
+			query: `SELECT \"Name\" FROM \"Users\"`,
+			want:  []string{"Users.Name", "Users"},
+		},
+		{
+			name:  "unqualified columns of several tables are skipped",
+			query: "SELECT name FROM orders, customers WHERE orders.customer_id = customers.id",
+			want:  []string{"orders", "customers", "orders.customer_id", "customers.id"},
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var got []string
+			for _, r := range sqlReferences(tt.query) {
+				name := r.table
+				if r.column != "" {
+					name += "." + r.column
+				}
+				assert.Equal(t, r.name, tt.query[r.offset:r.offset+len(r.name)], "offset of %s", name)
+				got = append(got, name)
+			}
+			assert.Equal(t, tt.want, got)
+		})
+	}
+}
+
+func TestSQLLiteralAfter(t *testing.T) {
+	t.Parallel()
+
+	tests := []struct {
+		src    string
+		name   string // the macro or function name
+		want   string
+		wantOK bool
+	}{
+		{`sqlx::query_as!(Order, r#"SELECT * FROM orders"#)`, "query_as", "SELECT * FROM orders", true},
+		{`diesel::sql_query("DELETE FROM \"orders\"")`, "sql_query", `DELETE FROM \"orders\"`, true},
+		{`sqlx::query! { "SELECT 1" }`, "query", "SELECT 1", true},
+		{`sqlx::query!(QUERY, id); let s = "x";`, "query", "", false},
+	}
+	for _, tt := range tests {
+		end := strings.Index(tt.src, tt.name) + len(tt.name)
+		got, offset, ok := sqlLiteralAfter([]byte(tt.src), end)
+		assert.Equal(t, tt.wantOK, ok, tt.src)
+		assert.Equal(t, tt.want, got, tt.src)
+		if ok {
+			assert.Equal(t, tt.want, tt.src[offset:offset+len(got)], tt.src)
+		}
+	}
+}
+
+func TestFunctionsTouchingTable(t *testing.T) {
+	e := newIntegrationEngine(t, WithLanguages("rust"), WithSQLReferences(true))
+	ctx := context.Background()
+
+	dir := t.TempDir()
+	path := filepath.Join(dir, "db.rs")
+	src := `pub struct Order;
+
+pub async fn load(pool: &PgPool, id: i64) {
+    sqlx::query!("SELECT id, total FROM orders WHERE id = $1", id);
+}
+
+pub fn purge(conn: &mut PgConnection) {
+    diesel::sql_query("DELETE FROM orders").execute(conn);
+}
+
+pub fn names(pool: &PgPool) {
+    sqlx::query!(r#"SELECT name FROM customers"#);
+}
+`
+	require.NoError(t, os.WriteFile(path, []byte(src), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	uses, err := e.Query().FunctionsTouchingTable("ORDERS")
+	require.NoError(t, err)
+	require.Len(t, uses, 2)
+	assert.Equal(t, "load", uses[0].Function.Name)
+	assert.Equal(t, []string{"id", "total"}, uses[0].Columns)
+	assert.Len(t, uses[0].Sites, 4)
+	assert.Equal(t, "purge", uses[1].Function.Name)
+	assert.Empty(t, uses[1].Columns)
+	require.Len(t, uses[1].Sites, 1)
+	site := uses[1].Sites[0]
+	line := strings.Split(src, "\n")[7]
+	assert.Equal(t, 7, site.StartLine)
+	assert.Equal(t, strings.Index(line, "orders"), site.StartCol)
+	assert.Equal(t, "orders", src[site.StartByte:site.EndByte])
+
+	// SQL references are rebuilt on every resolve, not accumulated.
+	src = strings.Replace(src, `diesel::sql_query("DELETE FROM orders")`, `diesel::sql_query("DELETE FROM customers")`, 1)
+	require.NoError(t, os.WriteFile(path, []byte(src), 0644))
+	require.NoError(t, e.IndexFiles(ctx, []string{path}))
+	require.NoError(t, e.Resolve(ctx))
+
+	uses, err = e.Query().FunctionsTouchingTable("orders")
+	require.NoError(t, err)
+	require.Len(t, uses, 1)
+	assert.Equal(t, "load", uses[0].Function.Name)
+	assert.Len(t, uses[0].Sites, 4)
+
+	uses, err = e.Query().FunctionsTouchingTable("customers")
+	require.NoError(t, err)
+	require.Len(t, uses, 2)
+	assert.Equal(t, []string{"purge", "names"}, []string{uses[0].Function.Name, uses[1].Function.Name})
+	assert.Equal(t, []string{"name"}, uses[1].Columns)
+}
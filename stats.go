@@ -0,0 +1,33 @@
+package canopy
+
+import (
+	"encoding/json"
+	"fmt"
+	"time"
+)
+
+// IndexTiming is the wall time of one phase of an index run.
+type IndexTiming struct {
+	Phase    string // "extract", "resolve", ...
+	Duration time.Duration
+}
+
+// indexRunKey is the metadata key holding the latest recorded index run, as
+// JSON.
+const indexRunKey = "index_run"
+
+// indexRun is the value stored under indexRunKey.
+type indexRun struct {
+	Finished time.Time
+	Timings  []IndexTiming
+}
+
+// RecordIndexTimings stores the phase timings of an index run that just
+// finished, replacing those of the previous run. IndexStats reports them.
+func (e *Engine) RecordIndexTimings(timings []IndexTiming) error {
+	data, err := json.Marshal(indexRun{Finished: time.Now().UTC(), Timings: timings})
+	if err != nil {
+		return fmt.Errorf("record index timings: %w", err)
+	}
+	return e.store.SetMetadata(indexRunKey, string(data))
+}
@@ -14,3 +14,9 @@ fn main() {
     println!("{}", hello());
     let result = add(1, 2);
 }
+
+const fn backoff_base() -> u32 {
+    100
+}
+
+const BACKOFF_MS: u32 = MAX_RETRIES * backoff_base();
@@ -18,3 +18,18 @@ fn main() {
     let d = distance(p1, p2);
     let km = to_km(d);
 }
+
+struct Server;
+
+impl Server {
+    fn start() -> Server {
+        Server
+    }
+}
+
+type Backend = Server;
+type Handle = Backend;
+
+fn launch() -> Handle {
+    Handle::start()
+}
@@ -0,0 +1,27 @@
+pub struct Buf<const N: usize> {
+    data: [u8; N],
+}
+
+impl<const N: usize> Buf<N> {
+    pub fn new() -> Self {
+        Buf { data: [0; N] }
+    }
+
+    pub fn fill(&mut self, bytes: [u8; N]) {
+        self.data = bytes;
+    }
+}
+
+pub struct Grid<T, const W: usize, const H: usize>([[T; W]; H]);
+
+pub type Row<const W: usize> = [u8; W];
+
+fn checksum<const N: usize>(bytes: &[u8; N]) -> [u8; 4] {
+    [0; 4]
+}
+
+fn main() {
+    let mut b: Buf<8> = Buf::new();
+    b.fill([1; 8]);
+    checksum(&[0; 8]);
+}
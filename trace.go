@@ -0,0 +1,270 @@
+package canopy
+
+import (
+	"bufio"
+	"fmt"
+	"io"
+	"regexp"
+	"strconv"
+	"strings"
+)
+
+// TraceSummary describes an ingested execution trace.
+type TraceSummary struct {
+	Stacks       int   // stack lines read
+	Samples      int64 // samples across all stacks
+	Frames       int   // distinct frames
+	MappedFrames int   // distinct frames mapped to an indexed symbol
+	Symbols      int   // symbols with samples
+}
+
+// traceSamplesKey is the metadata key holding the sample count of the
+// ingested trace, the denominator of HotSymbol percentages.
+const traceSamplesKey = "trace_samples"
+
+// IngestTrace reads an execution trace in folded stack format, one stack
+// per line with its frames outermost first and its sample count last:
+//
+//	main;server::run;server::Handler::serve 42
+//
+// This is what inferno-collapse-perf writes for perf profiles, and
+// tracing-flame for the spans of the tracing crate. Each frame is mapped to
+// the indexed function or method it names: paths, generic arguments,
+// closure segments, symbol hashes, and source positions are ignored, a type
+// segment in front of the name must match the method's parent, and a module
+// segment breaks ties between files. Frames that name no indexed symbol, or
+// several, are skipped.
+//
+// A symbol's total counts the samples of every stack it appears on; its
+// self count those where it is the innermost mapped frame, so time spent in
+// unindexed code (the standard library, dependencies) is charged to the
+// indexed caller. The trace replaces any previous one; HotSymbols lists the
+// result. Re-indexing a file drops the samples of its symbols.
+func (e *Engine) IngestTrace(r io.Reader) (*TraceSummary, error) {
+	summary := &TraceSummary{}
+	frames := map[string]int64{} // frame -> symbol ID, 0 if unmapped
+	samples := map[int64]*SymbolSamples{}
+	var order []int64
+
+	sc := bufio.NewScanner(r)
+	sc.Buffer(make([]byte, 64*1024), 16*1024*1024) // deep stacks make long lines
+	for sc.Scan() {
+		line := strings.TrimSpace(sc.Text())
+		i := strings.LastIndexByte(line, ' ')
+		if i < 0 {
+			continue
+		}
+		count, err := strconv.ParseInt(line[i+1:], 10, 64)
+		if err != nil || count <= 0 {
+			continue
+		}
+		summary.Stacks++
+		summary.Samples += count
+
+		var innermost int64
+		seen := map[int64]bool{} // recursion counts once toward the total
+		for _, frame := range strings.Split(line[:i], ";") {
+			id, ok := frames[frame]
+			if !ok {
+				if id, err = e.traceFrameSymbol(frame); err != nil {
+					return nil, err
+				}
+				frames[frame] = id
+			}
+			if id == 0 {
+				continue
+			}
+			innermost = id
+			if seen[id] {
+				continue
+			}
+			seen[id] = true
+			sm, ok := samples[id]
+			if !ok {
+				sm = &SymbolSamples{SymbolID: id}
+				samples[id] = sm
+				order = append(order, id)
+			}
+			sm.Total += count
+		}
+		if innermost != 0 {
+			samples[innermost].Self += count
+		}
+	}
+	if err := sc.Err(); err != nil {
+		return nil, fmt.Errorf("trace: read: %w", err)
+	}
+
+	list := make([]*SymbolSamples, 0, len(order))
+	for _, id := range order {
+		list = append(list, samples[id])
+	}
+	if err := e.store.ReplaceSymbolSamples(list); err != nil {
+		return nil, fmt.Errorf("trace: %w", err)
+	}
+	if err := e.store.SetMetadata(traceSamplesKey, strconv.FormatInt(summary.Samples, 10)); err != nil {
+		return nil, fmt.Errorf("trace: %w", err)
+	}
+
+	summary.Frames = len(frames)
+	for _, id := range frames {
+		if id != 0 {
+			summary.MappedFrames++
+		}
+	}
+	summary.Symbols = len(list)
+	return summary, nil
+}
+
+var (
+	// traceQualifiedSelfRe matches a Rust qualified path prefix
+	// <Type as Trait>::, keeping Type.
+	traceQualifiedSelfRe = regexp.MustCompile(`^<([^<>]+?) as [^>]+>::`)
+	// traceHashRe matches the hash Rust's legacy mangling appends to paths.
+	traceHashRe = regexp.MustCompile(`::h[0-9a-f]{16}$`)
+)
+
+// traceFrameSymbol returns the ID of the indexed function or method a
+// trace frame names, or 0 if it names none or is ambiguous.
+func (e *Engine) traceFrameSymbol(frame string) (int64, error) {
+	owner, name, module := parseTraceFrame(frame)
+	if name == "" {
+		return 0, nil
+	}
+	rows, err := e.store.DB().Query(
+		`SELECT s.id, COALESCE(p.name, ''), f.path FROM symbols s
+		 JOIN files f ON f.id = s.file_id
+		 LEFT JOIN symbols p ON p.id = s.parent_symbol_id
+		 WHERE s.name = ? AND s.kind IN ('function', 'method') AND f.language != ?`,
+		name, rustdocStubLanguage)
+	if err != nil {
+		return 0, fmt.Errorf("trace: symbols named %s: %w", name, err)
+	}
+	type candidate struct {
+		id     int64
+		parent string
+		path   string
+	}
+	var all []candidate
+	for rows.Next() {
+		var c candidate
+		if err := rows.Scan(&c.id, &c.parent, &c.path); err != nil {
+			rows.Close()
+			return 0, fmt.Errorf("trace: scan: %w", err)
+		}
+		all = append(all, c)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return 0, fmt.Errorf("trace: %w", err)
+	}
+
+	// Narrow by the type, then by the module, when they leave a match.
+	narrow := func(keep func(candidate) bool) {
+		var kept []candidate
+		for _, c := range all {
+			if keep(c) {
+				kept = append(kept, c)
+			}
+		}
+		if len(kept) > 0 {
+			all = kept
+		}
+	}
+	if owner != "" {
+		narrow(func(c candidate) bool { return c.parent == owner })
+	} else {
+		narrow(func(c candidate) bool { return c.parent == "" })
+	}
+	if len(all) > 1 && module != "" {
+		narrow(func(c candidate) bool {
+			return strings.Contains(c.path, "/"+module+".") || strings.Contains(c.path, "/"+module+"/")
+		})
+	}
+	if len(all) != 1 {
+		return 0, nil
+	}
+	return all[0].id, nil
+}
+
+// parseTraceFrame splits a trace frame into the function name, the type it
+// is a method of (a capitalized segment in front of the name), and the
+// nearest module segment in front of those. Understands Rust paths
+// (app::server::Handler::serve::{{closure}}::h0123456789abcdef,
+// <app::Handler as app::Service>::call), Go symbols
+// (net/http.(*Server).Serve), dotted names (com.app.Server.serve),
+// tracing targets (app::server:serve), and the file, line, and kernel
+// annotations profilers append.
+func parseTraceFrame(frame string) (owner, name, module string) {
+	frame = strings.TrimSpace(frame)
+	if frame == "" || strings.HasPrefix(frame, "[") {
+		return "", "", "" // [unknown], [kernel.kallsyms]
+	}
+	for _, suffix := range []string{"_[k]", "_[j]", "_[i]", "_[w]"} {
+		frame = strings.TrimSuffix(frame, suffix)
+	}
+	frame = traceQualifiedSelfRe.ReplaceAllString(frame, "$1::")
+	frame = stripAngles(frame)
+	if i := strings.IndexAny(frame, " \t"); i >= 0 {
+		frame = frame[:i] // "serve (src/server.rs:42)", "serve src/server.rs:42"
+	}
+	if i := strings.IndexByte(frame, '('); i > 0 && !strings.HasPrefix(frame[i:], "(*") {
+		frame = frame[:i] // "serve(src/server.rs:42)"
+	}
+	frame = traceHashRe.ReplaceAllString(frame, "")
+
+	var segments []string
+	if strings.Contains(frame, "::") {
+		for _, seg := range strings.Split(frame, "::") {
+			// tracing writes a span as target:name.
+			segments = append(segments, strings.Split(seg, ":")...)
+		}
+	} else {
+		frame = frame[strings.LastIndexByte(frame, '/')+1:]
+		frame = strings.NewReplacer("(*", "", "(", "", ")", "").Replace(frame)
+		segments = strings.FieldsFunc(frame, func(r rune) bool { return r == '.' || r == ':' })
+	}
+	// Closures, async blocks, and shims are not symbols of their own.
+	kept := segments[:0]
+	for _, seg := range segments {
+		if seg != "" && !strings.HasPrefix(seg, "{") {
+			kept = append(kept, seg)
+		}
+	}
+	segments = kept
+	if len(segments) == 0 || !identTailRe.MatchString(segments[len(segments)-1]) {
+		return "", "", ""
+	}
+
+	name = segments[len(segments)-1]
+	rest := segments[:len(segments)-1]
+	if n := len(rest); n > 0 && rest[n-1][0] >= 'A' && rest[n-1][0] <= 'Z' {
+		owner = rest[n-1]
+		rest = rest[:n-1]
+	}
+	if n := len(rest); n > 0 {
+		module = rest[n-1]
+	}
+	return owner, name, module
+}
+
+// stripAngles removes generic arguments, the text between balanced angle
+// brackets: "Vec<T>::push" -> "Vec::push".
+func stripAngles(s string) string {
+	if !strings.Contains(s, "<") {
+		return s
+	}
+	var b strings.Builder
+	depth := 0
+	for _, r := range s {
+		switch {
+		case r == '<':
+			depth++
+		case r == '>' && depth > 0:
+			depth--
+		case depth == 0:
+			b.WriteRune(r)
+		}
+	}
+	return b.String()
+}
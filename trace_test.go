@@ -0,0 +1,92 @@
+package canopy
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestParseTraceFrame(t *testing.T) {
+	t.Parallel()
+	tests := []struct {
+		frame               string
+		owner, name, module string
+	}{
+		{"app::server::run", "", "run", "server"},
+		{"app::server::Handler::serve::h0123456789abcdef", "Handler", "serve", "server"},
+		{"app::server::Handler::serve::{{closure}}", "Handler", "serve", "server"},
+		{"<app::server::Handler as app::Service>::call", "Handler", "call", "server"},
+		{"alloc::vec::Vec<T,A>::push", "Vec", "push", "vec"},
+		{"app::server:serve", "", "serve", "server"},
+		{"serve (src/server.rs:42)", "", "serve", ""},
+		{"net/http.(*Server).Serve", "Server", "Serve", "http"},
+		{"main.main", "", "main", "main"},
+		{"com.app.Server.serve", "Server", "serve", "app"},
+		{"do_syscall_64_[k]", "", "do_syscall_64", ""},
+		{"[unknown]", "", "", ""},
+		{"", "", "", ""},
+	}
+	for _, tt := range tests {
+		owner, name, module := parseTraceFrame(tt.frame)
+		assert.Equal(t, []string{tt.owner, tt.name, tt.module}, []string{owner, name, module}, tt.frame)
+	}
+}
+
+func TestIngestTrace(t *testing.T) {
+	ctx := context.Background()
+	root := t.TempDir()
+	require.NoError(t, os.MkdirAll(filepath.Join(root, "src"), 0o755))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "server.rs"), []byte(`pub struct Handler {}
+
+impl Handler {
+    pub fn serve(&self) {}
+    pub fn parse(&self) {}
+}
+
+pub fn run() {}
+`), 0o644))
+	require.NoError(t, os.WriteFile(filepath.Join(root, "src", "client.rs"), []byte("pub fn run() {}\n"), 0o644))
+
+	e := newIntegrationEngine(t, WithLanguages("rust"))
+	require.NoError(t, e.IndexDirectory(ctx, root))
+	require.NoError(t, e.Resolve(ctx))
+
+	trace := `main;app::server::run;app::server::Handler::serve;std::io::read 30
+main;app::server::run;<app::server::Handler as app::Service>::parse 10
+main;run 5
+`
+	summary, err := e.IngestTrace(strings.NewReader(trace))
+	require.NoError(t, err)
+	assert.Equal(t, &TraceSummary{Stacks: 3, Samples: 45, Frames: 6, MappedFrames: 3, Symbols: 3}, summary)
+
+	result, err := e.Query().HotSymbols(SymbolFilter{}, Pagination{})
+	require.NoError(t, err)
+	require.Equal(t, 3, result.TotalCount)
+	type hot struct {
+		name        string
+		self, total int64
+	}
+	var got []hot
+	for _, h := range result.Items {
+		got = append(got, hot{h.Symbol.Name, h.Samples.Self, h.Samples.Total})
+	}
+	// The ambiguous bare "run" frame is skipped; time in the standard
+	// library is charged to serve.
+	assert.Equal(t, []hot{{"serve", 30, 30}, {"parse", 10, 10}, {"run", 0, 40}}, got)
+	assert.InDelta(t, 100*30/45.0, result.Items[0].SelfPercent, 0.001)
+	assert.Equal(t, filepath.Join(root, "src", "server.rs"), result.Items[2].Symbol.FilePath)
+
+	// A new trace replaces the old one.
+	_, err = e.IngestTrace(strings.NewReader("app::client::run 7\n"))
+	require.NoError(t, err)
+	result, err = e.Query().HotSymbols(SymbolFilter{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, result.Items, 1)
+	assert.Equal(t, filepath.Join(root, "src", "client.rs"), result.Items[0].Symbol.FilePath)
+	assert.InDelta(t, 100.0, result.Items[0].TotalPercent, 0.001)
+}
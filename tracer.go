@@ -0,0 +1,148 @@
+package canopy
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"sync"
+	"time"
+)
+
+// Span names reported to a WithTracer callback, with their fields.
+const (
+	SpanIndexDirectory  = "index_directory"  // root
+	SpanListFiles       = "list_files"       // root, files
+	SpanIndexFiles      = "index_files"      // files
+	SpanExtractFile     = "extract_file"     // path, language, bytes; cached in serial indexing; worker_error if its worker process failed
+	SpanCommitFile      = "commit_file"      // path (parallel indexing only)
+	SpanBlame           = "blame"            // root
+	SpanResolve         = "resolve"          // languages, files (the blast radius; -1 for all)
+	SpanResolveLanguage = "resolve_language" // language
+	SpanCentrality      = "centrality"
+)
+
+// Span is one timed step of the indexing pipeline.
+type Span struct {
+	Name     string // one of the Span* constants
+	Start    time.Time
+	Duration time.Duration
+	// Lane tells apart steps that run at the same time: 0 is the goroutine
+	// that called the Engine, and each parallel extraction worker and
+	// resolution script runs on a lane of its own. Spans on one lane nest.
+	Lane   int
+	Fields map[string]any // structured fields, listed with each Span* constant; "error" if the step failed
+}
+
+// WithTracer registers fn to be called as each step of IndexDirectory,
+// IndexFiles, and Resolve ends, so slow phases and slow files can be found
+// without adding logging. Calls are serialized but may come from any
+// goroutine; fn should return quickly. ChromeTrace.Record is such a
+// function.
+func WithTracer(fn func(Span)) Option {
+	return func(e *Engine) {
+		e.tracer = &spanTracer{fn: fn}
+	}
+}
+
+// spanTracer serializes the calls to a WithTracer callback. Shard engines
+// share their parent's.
+type spanTracer struct {
+	mu sync.Mutex
+	fn func(Span)
+}
+
+// activeSpan is a span that has started. A nil activeSpan, from an Engine
+// without a tracer, ignores end.
+type activeSpan struct {
+	tracer *spanTracer
+	span   Span
+}
+
+// startSpan starts a span on lane with fields given as alternating keys and
+// values, or returns nil when no tracer is registered.
+func (e *Engine) startSpan(name string, lane int, fields ...any) *activeSpan {
+	if e.tracer == nil {
+		return nil
+	}
+	s := &activeSpan{tracer: e.tracer, span: Span{Name: name, Start: time.Now(), Lane: lane, Fields: map[string]any{}}}
+	for i := 0; i+1 < len(fields); i += 2 {
+		s.span.Fields[fmt.Sprint(fields[i])] = fields[i+1]
+	}
+	return s
+}
+
+// set adds a field known only once the step has run.
+func (s *activeSpan) set(key string, value any) {
+	if s != nil {
+		s.span.Fields[key] = value
+	}
+}
+
+// end finishes the span and reports it, recording err if the step failed.
+func (s *activeSpan) end(err error) {
+	if s == nil {
+		return
+	}
+	s.span.Duration = time.Since(s.span.Start)
+	if err != nil {
+		s.span.Fields["error"] = err.Error()
+	}
+	s.tracer.mu.Lock()
+	defer s.tracer.mu.Unlock()
+	s.tracer.fn(s.span)
+}
+
+// ChromeTrace collects spans as Chrome trace events, the JSON format read
+// by chrome://tracing, Perfetto, and speedscope. Each lane is a thread of
+// one process, so parallel workers show as parallel tracks.
+type ChromeTrace struct {
+	mu     sync.Mutex
+	events []chromeTraceEvent
+}
+
+// chromeTraceEvent is a complete ("X") event; times are in microseconds.
+type chromeTraceEvent struct {
+	Name  string         `json:"name"`
+	Cat   string         `json:"cat"`
+	Phase string         `json:"ph"`
+	TS    int64          `json:"ts"`
+	Dur   int64          `json:"dur"`
+	PID   int            `json:"pid"`
+	TID   int            `json:"tid"`
+	Args  map[string]any `json:"args,omitempty"`
+}
+
+// Record adds a span to the trace. Safe for concurrent use.
+func (t *ChromeTrace) Record(s Span) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	t.events = append(t.events, chromeTraceEvent{
+		Name:  s.Name,
+		Cat:   "canopy",
+		Phase: "X",
+		TS:    s.Start.UnixMicro(),
+		Dur:   s.Duration.Microseconds(),
+		PID:   1,
+		TID:   s.Lane,
+		Args:  s.Fields,
+	})
+}
+
+// WriteTo writes the recorded spans as a Chrome trace JSON object.
+func (t *ChromeTrace) WriteTo(w io.Writer) (int64, error) {
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	events := t.events
+	if events == nil {
+		events = []chromeTraceEvent{}
+	}
+	data, err := json.Marshal(struct {
+		TraceEvents     []chromeTraceEvent `json:"traceEvents"`
+		DisplayTimeUnit string             `json:"displayTimeUnit"`
+	}{events, "ms"})
+	if err != nil {
+		return 0, err
+	}
+	n, err := w.Write(append(data, '\n'))
+	return int64(n), err
+}
@@ -0,0 +1,84 @@
+package canopy
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWithTracer(t *testing.T) {
+	for _, parallel := range []bool{false, true} {
+		var spans []Span
+		e := newIntegrationEngine(t, WithLanguages("go"), WithParallel(parallel), WithTracer(func(s Span) {
+			spans = append(spans, s)
+		}))
+		ctx := context.Background()
+		dir := t.TempDir()
+		writeGoFile(t, dir, "main.go", "package main\n\nfunc main() { helper() }\n")
+		writeGoFile(t, dir, "helper.go", "package main\n\nfunc helper() {}\n")
+		require.NoError(t, e.IndexDirectory(ctx, dir))
+		require.NoError(t, e.Resolve(ctx))
+
+		byName := map[string][]Span{}
+		for _, s := range spans {
+			byName[s.Name] = append(byName[s.Name], s)
+		}
+		require.Len(t, byName[SpanIndexDirectory], 1, "parallel: %v", parallel)
+		assert.Equal(t, dir, byName[SpanIndexDirectory][0].Fields["root"])
+		assert.Equal(t, 2, byName[SpanListFiles][0].Fields["files"])
+		assert.Equal(t, 2, byName[SpanIndexFiles][0].Fields["files"])
+
+		extracted := byName[SpanExtractFile]
+		require.Len(t, extracted, 2)
+		for _, s := range extracted {
+			assert.Equal(t, "go", s.Fields["language"])
+			assert.NotContains(t, s.Fields, "error")
+			if parallel {
+				assert.Positive(t, s.Lane, "workers run on their own lanes")
+			} else {
+				assert.Zero(t, s.Lane)
+			}
+		}
+		if parallel {
+			assert.Len(t, byName[SpanCommitFile], 2)
+		}
+
+		require.Len(t, byName[SpanResolve], 1)
+		assert.Equal(t, []string{"go"}, byName[SpanResolve][0].Fields["languages"])
+		require.Len(t, byName[SpanResolveLanguage], 1)
+		assert.Equal(t, 1, byName[SpanResolveLanguage][0].Lane)
+		assert.Len(t, byName[SpanCentrality], 1)
+		assert.Equal(t, SpanResolve, spans[len(spans)-1].Name, "spans are reported as they end")
+	}
+}
+
+func TestChromeTrace(t *testing.T) {
+	t.Parallel()
+	trace := &ChromeTrace{}
+	start := time.UnixMicro(1_000_000)
+	trace.Record(Span{Name: SpanExtractFile, Start: start, Duration: 1500 * time.Microsecond, Lane: 2, Fields: map[string]any{"path": "a.go"}})
+
+	var buf bytes.Buffer
+	_, err := trace.WriteTo(&buf)
+	require.NoError(t, err)
+	var got struct {
+		TraceEvents []map[string]any `json:"traceEvents"`
+	}
+	require.NoError(t, json.Unmarshal(buf.Bytes(), &got))
+	require.Len(t, got.TraceEvents, 1)
+	assert.Equal(t, map[string]any{
+		"name": "extract_file",
+		"cat":  "canopy",
+		"ph":   "X",
+		"ts":   1e6,
+		"dur":  1500.0,
+		"pid":  1.0,
+		"tid":  2.0,
+		"args": map[string]any{"path": "a.go"},
+	}, got.TraceEvents[0])
+}
@@ -14,6 +14,14 @@ type Import = store.Import
 type FunctionParam = store.FunctionParam
 type TypeMember = store.TypeMember
 type TypeParam = store.TypeParam
+type TypeBound = store.TypeBound
 type Annotation = store.Annotation
+type FunctionMetrics = store.FunctionMetrics
 type ExtensionBinding = store.ExtensionBinding
 type Reexport = store.Reexport
+type ImportAlias = store.ImportAlias
+type SymbolHistory = store.SymbolHistory
+type AuthorLines = store.AuthorLines
+type SymbolSamples = store.SymbolSamples
+type SymbolCoverage = store.SymbolCoverage
+type SymbolEmbedding = store.SymbolEmbedding
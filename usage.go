@@ -0,0 +1,93 @@
+package canopy
+
+import (
+	"sync"
+	"time"
+)
+
+// Usage event kinds reported to a WithUsage callback.
+const (
+	UsageIndex   = "index"   // Name is "index_directory" or "index_files"; Results counts files
+	UsageResolve = "resolve" // Name is "resolve"; Results counts languages
+	UsageQuery   = "query"   // Name is the query; Results counts its results
+)
+
+// UsageEvent is an anonymized record of one operation: what ran, how long
+// it took, and how many results it had. It carries no paths, names, or
+// arguments, so it can be collected without exposing the indexed code.
+type UsageEvent struct {
+	Kind     string // one of the Usage* constants
+	Name     string
+	Start    time.Time
+	Duration time.Duration
+	Results  int
+	Failed   bool
+}
+
+// WithUsage registers fn to be called as each IndexDirectory, IndexFiles,
+// and Resolve call ends, so an embedder can feed its own telemetry, and as
+// each navigation and listing query of the Engine's QueryBuilder ends:
+// SymbolAt, DefinitionAt, ReferencesTo, Implementations, Callers, Callees,
+// Dependencies, Dependents, Symbols, Files, and SearchSymbols. canopy sends
+// usage nowhere itself. IndexDirectory also reports the IndexFiles call it
+// makes. Calls are serialized but may come from any goroutine; fn should
+// return quickly.
+func WithUsage(fn func(UsageEvent)) Option {
+	return func(e *Engine) {
+		e.usage = &usageReporter{fn: fn}
+	}
+}
+
+// usageReporter serializes the calls to a WithUsage callback.
+type usageReporter struct {
+	mu sync.Mutex
+	fn func(UsageEvent)
+}
+
+// reportUsage reports an operation that started at start and ends now. A
+// no-op for an Engine without a WithUsage callback.
+func (e *Engine) reportUsage(kind, name string, start time.Time, results int, err error) {
+	e.usage.report(kind, name, start, results, err)
+}
+
+// reportUsage reports a query that started at start and ends now. A no-op
+// for a QueryBuilder without a WithUsage callback, such as one from
+// NewQueryBuilder.
+func (q *QueryBuilder) reportUsage(name string, start time.Time, results int, err error) {
+	q.usage.report(UsageQuery, name, start, results, err)
+}
+
+// usageCount counts the result of a query that finds at most one.
+func usageCount(found bool) int {
+	if found {
+		return 1
+	}
+	return 0
+}
+
+// total is the result count of a paged query, which is nil when it fails.
+func (r *PagedResult[T]) total() int {
+	if r == nil {
+		return 0
+	}
+	return r.TotalCount
+}
+
+// report calls the callback with an event for an operation that started at
+// start and ends now. A no-op on a nil reporter.
+func (u *usageReporter) report(kind, name string, start time.Time, results int, err error) {
+	if u == nil {
+		return
+	}
+	ev := UsageEvent{
+		Kind:     kind,
+		Name:     name,
+		Start:    start,
+		Duration: time.Since(start),
+		Results:  results,
+		Failed:   err != nil,
+	}
+	u.mu.Lock()
+	defer u.mu.Unlock()
+	u.fn(ev)
+}
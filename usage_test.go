@@ -0,0 +1,78 @@
+package canopy
+
+import (
+	"context"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestWithUsage(t *testing.T) {
+	var events []UsageEvent
+	e := newIntegrationEngine(t, WithLanguages("go"), WithUsage(func(ev UsageEvent) {
+		events = append(events, ev)
+	}))
+	ctx := context.Background()
+	dir := t.TempDir()
+	writeGoFile(t, dir, "main.go", "package main\n\nfunc main() { helper() }\n")
+	writeGoFile(t, dir, "helper.go", "package main\n\nfunc helper() {}\n")
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+
+	require.Len(t, events, 3)
+	assert.Equal(t, UsageIndex, events[0].Kind)
+	assert.Equal(t, SpanIndexFiles, events[0].Name, "the nested IndexFiles call ends first")
+	assert.Equal(t, 2, events[0].Results)
+	assert.Equal(t, UsageIndex, events[1].Kind)
+	assert.Equal(t, SpanIndexDirectory, events[1].Name)
+	assert.Equal(t, 2, events[1].Results)
+	assert.GreaterOrEqual(t, events[1].Duration, events[0].Duration)
+	assert.Equal(t, UsageResolve, events[2].Kind)
+	assert.Equal(t, 1, events[2].Results, "one language resolved")
+	for _, ev := range events {
+		assert.False(t, ev.Failed)
+		assert.False(t, ev.Start.IsZero())
+	}
+}
+
+func TestWithUsage_Queries(t *testing.T) {
+	var events []UsageEvent
+	e := newIntegrationEngine(t, WithLanguages("go"), WithUsage(func(ev UsageEvent) {
+		if ev.Kind == UsageQuery {
+			events = append(events, ev)
+		}
+	}))
+	ctx := context.Background()
+	dir := t.TempDir()
+	writeGoFile(t, dir, "main.go", "package main\n\nfunc main() { helper() }\n\nfunc helper() {}\n")
+	require.NoError(t, e.IndexDirectory(ctx, dir))
+	require.NoError(t, e.Resolve(ctx))
+	require.Empty(t, events, "indexing runs no queries")
+
+	q := e.Query()
+	res, err := q.Symbols(SymbolFilter{}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	require.Len(t, events, 1)
+	assert.Equal(t, "symbols", events[0].Name)
+	assert.Equal(t, res.TotalCount, events[0].Results)
+	assert.False(t, events[0].Failed)
+
+	syms, err := e.store.SymbolsByName("helper")
+	require.NoError(t, err)
+	require.Len(t, syms, 1)
+	callers, err := q.Callers(syms[0].ID)
+	require.NoError(t, err)
+	require.Len(t, events, 2)
+	assert.Equal(t, "callers", events[1].Name)
+	assert.Equal(t, len(callers), events[1].Results)
+
+	_, err = q.WithTestCode(false).ReferencesTo(syms[0].ID)
+	require.NoError(t, err)
+	require.Len(t, events, 3, "derived QueryBuilders report too")
+	assert.Equal(t, "references_to", events[2].Name)
+
+	_, err = NewQueryBuilder(e.store).Symbols(SymbolFilter{}, Sort{}, Pagination{})
+	require.NoError(t, err)
+	assert.Len(t, events, 3, "a QueryBuilder of its own has no callback")
+}